@@ -42,6 +42,16 @@ pub(crate) struct Args {
     #[arg(short('P'), long, value_name = "NAME")]
     pub(crate) agent: Option<String>,
 
+    /// Agent definition file (e.g. agent.yaml): model, role, tools, MCP servers, approval
+    /// policy. Takes precedence over --agent.
+    #[arg(long, value_name = "PATH")]
+    pub(crate) agent_file: Option<PathBuf>,
+
+    /// ReAct graph spec (YAML): built-in nodes and the edges between them, replacing the default
+    /// think → act → observe loop (sets LOOM_GRAPH)
+    #[arg(long, value_name = "PATH")]
+    pub(crate) graph: Option<PathBuf>,
+
     /// Session ID for conversation continuity (checkpointer)
     #[arg(long, value_name = "ID")]
     pub(crate) session_id: Option<String>,
@@ -78,6 +88,41 @@ pub(crate) struct Args {
     #[arg(long)]
     pub(crate) dry: bool,
 
+    /// Read-only run: no shell or file-modifying tools, and calls to them are refused
+    #[arg(long)]
+    pub(crate) read_only: bool,
+
+    /// Run on a `loom serve` instance (ws://host:port) instead of in-process; same as LOOM_REMOTE_URL
+    #[arg(long, value_name = "URL")]
+    pub(crate) remote: Option<String>,
+
+    /// Locale of localized prompt variants (e.g. zh-CN); default LOOM_LOCALE or guessed from the message
+    #[arg(long, value_name = "TAG")]
+    pub(crate) locale: Option<String>,
+
+    /// Feature flag for this run, read by nodes, middleware and tools (repeatable; e.g.
+    /// --flag verify_node --flag new_compaction=false). Overrides LOOM_FLAGS
+    #[arg(long = "flag", value_name = "NAME[=BOOL]", value_parser = loom::parse_run_flag)]
+    pub(crate) flags: Vec<(String, bool)>,
+
+    /// Required format of the final reply: markdown, plain or json. JSON replies are validated
+    /// and retried once; the run fails when the retry is still not valid JSON.
+    #[arg(long, value_name = "FORMAT")]
+    pub(crate) reply_format: Option<loom::ReplyFormat>,
+
+    /// JSON Schema file the reply must match (with --reply-format json)
+    #[arg(long, value_name = "PATH")]
+    pub(crate) reply_schema: Option<PathBuf>,
+
+    /// Offline mode: scripted mock LLM instead of a provider, and no network tools (web, Exa,
+    /// Twitter, GitHub, HTTP MCP). Same as LOOM_OFFLINE=1; also applies to `serve`.
+    #[arg(long, global = true)]
+    pub(crate) offline: bool,
+
+    /// YAML or JSON script of mock LLM responses for --offline (sets LOOM_OFFLINE_SCRIPT; implies --offline)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub(crate) offline_script: Option<PathBuf>,
+
     /// Log level (tracing EnvFilter syntax). Overrides RUST_LOG when set; default RUST_LOG or info.
     #[arg(long, global = true, value_name = "LEVEL")]
     pub(crate) log_level: Option<String>,
@@ -89,12 +134,19 @@ pub(crate) struct Args {
     /// Log rotation strategy: none, daily, hourly, minutely (requires --log-file)
     #[arg(long, global = true, default_value = "daily", value_name = "STRATEGY")]
     pub(crate) log_rotate: String,
+
+    /// Directory for per-run log files (<run_id>.log, rotated like --log-file). Overrides LOOM_RUN_LOG_DIR.
+    #[arg(long, global = true, value_name = "DIR")]
+    pub(crate) run_log_dir: Option<PathBuf>,
 }
 
 #[derive(Subcommand, Debug, Clone)]
 pub(crate) enum Command {
     /// Run WebSocket server (ws://127.0.0.1:8080)
     Serve(ServeArgs),
+    /// Worker process of `loom serve` (started by the server when SERVE_WORKERS is set)
+    #[command(hide = true)]
+    Worker,
     /// Run ReAct graph (think → act → observe)
     React,
     /// Run DUP graph (understand → plan → act → observe)
@@ -111,6 +163,72 @@ pub(crate) enum Command {
     Models(ModelsArgs),
     /// Manage MCP servers (list, show, add, edit, delete, enable, disable)
     Mcp(McpArgs),
+    /// Watch files and re-run the ReAct agent with a diff of the changes (Ctrl-C to stop)
+    Watch(WatchArgs),
+    /// Move a thread's checkpoint history between machines (export-archive, import-archive)
+    Thread(ThreadArgs),
+    /// Inspect a thread's checkpoint history (list, show)
+    Checkpoint(CheckpointArgs),
+    /// Check the environment (API key, DB, MCP servers, working folder, model limits) and print fixes
+    Doctor(DoctorArgs),
+    /// Check ~/.loom/config.toml (validate)
+    Config(ConfigArgs),
+    /// Token usage and estimated cost of serve runs, grouped by model and agent type
+    Usage(UsageArgs),
+    /// WebSocket protocol tools (schema)
+    Protocol(ProtocolArgs),
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub(crate) struct ProtocolArgs {
+    #[command(subcommand)]
+    pub(crate) command: ProtocolCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub(crate) enum ProtocolCommand {
+    /// Print the JSON Schema of all requests, responses and stream events (for client codegen)
+    Schema {
+        /// Write the schema to this file instead of stdout
+        #[arg(long, short = 'o', value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub(crate) struct UsageArgs {
+    /// Only runs of this workspace (default: all runs)
+    #[arg(long, value_name = "ID")]
+    pub(crate) workspace: Option<String>,
+    /// Start of the period: YYYY-MM-DD (local midnight) or an RFC 3339 time
+    #[arg(long, value_name = "TIME")]
+    pub(crate) since: Option<String>,
+    /// End of the period (exclusive), same formats as --since
+    #[arg(long, value_name = "TIME")]
+    pub(crate) until: Option<String>,
+    /// Workspace database written by `loom serve` (default: WORKSPACE_DB or ./workspace.db)
+    #[arg(long, value_name = "PATH")]
+    pub(crate) db: Option<PathBuf>,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub(crate) struct DoctorArgs {
+    /// Skip checks that need the network (API ping, HTTP MCP servers, model limits)
+    #[arg(long)]
+    pub(crate) no_network: bool,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub(crate) struct ConfigArgs {
+    #[command(subcommand)]
+    pub(crate) command: ConfigCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub(crate) enum ConfigCommand {
+    /// Check config.toml and its includes: unknown keys, wrong types and conflicting settings,
+    /// each with file, line and column. Exits 1 when there are problems
+    Validate,
 }
 
 #[derive(clap::Args, Debug, Clone)]
@@ -171,7 +289,96 @@ pub(crate) struct GotArgs {
     pub(crate) got_adaptive: bool,
 }
 
+/// Arguments for the `watch` subcommand.
+#[derive(clap::Args, Debug, Clone)]
+pub(crate) struct WatchArgs {
+    /// Glob of files to watch, relative to the working folder (repeatable, e.g. 'src/**/*.rs')
+    #[arg(long = "glob", value_name = "PATTERN", required = true)]
+    pub(crate) globs: Vec<String>,
+
+    /// Instruction sent on every change; the diff of changed files is appended
+    #[arg(short, long, value_name = "TEXT")]
+    pub(crate) message: Option<String>,
+
+    /// Wait until files have been quiet this long before running (milliseconds)
+    #[arg(long, value_name = "MS", default_value = "500")]
+    pub(crate) debounce_ms: u64,
+}
+
 /// Arguments for the `mcp` subcommand.
+#[derive(clap::Args, Debug, Clone)]
+pub(crate) struct ThreadArgs {
+    #[command(subcommand)]
+    pub(crate) command: ThreadCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub(crate) enum ThreadCommand {
+    /// Write every checkpoint of a thread to a portable JSON archive
+    ExportArchive {
+        /// Thread (session) ID to export
+        thread_id: String,
+        /// Archive file to write (default: stdout)
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+        /// Checkpoint database to read (default: ~/.loom/memory.db)
+        #[arg(long, value_name = "PATH")]
+        db: Option<PathBuf>,
+    },
+    /// Import a thread archive written by export-archive
+    ImportArchive {
+        /// Archive file to read
+        file: PathBuf,
+        /// Thread ID to import under (default: the archived thread ID)
+        #[arg(long, value_name = "ID")]
+        thread_id: Option<String>,
+        /// Checkpoint database to write (default: ~/.loom/memory.db)
+        #[arg(long, value_name = "PATH")]
+        db: Option<PathBuf>,
+    },
+}
+
+/// Arguments for the `checkpoint` subcommand.
+#[derive(clap::Args, Debug, Clone)]
+pub(crate) struct CheckpointArgs {
+    #[command(subcommand)]
+    pub(crate) command: CheckpointCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub(crate) enum CheckpointCommand {
+    /// List a thread's checkpoints, oldest first (step, node, time, size, parent)
+    List {
+        /// Thread (session) ID
+        thread_id: String,
+        /// Only checkpoints written after this node ran (e.g. act)
+        #[arg(long, value_name = "NODE")]
+        node: Option<String>,
+        /// Only checkpoints with step >= N
+        #[arg(long, value_name = "N")]
+        min_step: Option<i64>,
+        /// Only checkpoints with step <= N
+        #[arg(long, value_name = "N")]
+        max_step: Option<i64>,
+        /// Show only the newest N matches
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+        /// Checkpoint database to read (default: ~/.loom/memory.db)
+        #[arg(long, value_name = "PATH")]
+        db: Option<PathBuf>,
+    },
+    /// Print one checkpoint's metadata and state as JSON
+    Show {
+        /// Thread (session) ID
+        thread_id: String,
+        /// Checkpoint ID from `checkpoint list` (default: latest)
+        checkpoint_id: Option<String>,
+        /// Checkpoint database to read (default: ~/.loom/memory.db)
+        #[arg(long, value_name = "PATH")]
+        db: Option<PathBuf>,
+    },
+}
+
 #[derive(clap::Args, Debug, Clone)]
 pub(crate) struct McpArgs {
     #[command(subcommand)]
@@ -9,21 +9,58 @@ pub(crate) fn print_config_report() {
     if std::env::var("LOOM_TEST_MODE").is_ok() {
         return; 
     }
-    if let Ok(report) = config::load_and_apply_with_report("loom", None::<&std::path::Path>) {
-        if let Some(p) = &report.dotenv_path {
-            let full = std::fs::canonicalize(p).unwrap_or_else(|_| p.clone());
-            eprintln!("config: .env path={}", full.display());
+    let report = match config::load_and_apply_with_report("loom", None::<&std::path::Path>) {
+        Ok(report) => report,
+        Err(e @ config::LoadError::XdgSchema(_)) => {
+            eprintln!("config: {}", e);
+            eprintln!("config: nothing was applied; check with `loom config validate`");
+            return;
         }
-        if let Some(p) = &report.xdg_path {
-            let full = std::fs::canonicalize(p).unwrap_or_else(|_| p.clone());
-            eprintln!("config: config.toml path={}", full.display());
-        }
-        if let Some(ref provider) = report.active_provider {
-            eprintln!("config: provider={}", provider);
-        }
-        if let Some(keys) = report.keys_summary() {
-            eprintln!("{}", keys);
+        Err(_) => return,
+    };
+    if let Some(p) = &report.dotenv_path {
+        let full = std::fs::canonicalize(p).unwrap_or_else(|_| p.clone());
+        eprintln!("config: .env path={}", full.display());
+    }
+    if let Some(p) = &report.xdg_path {
+        let full = std::fs::canonicalize(p).unwrap_or_else(|_| p.clone());
+        eprintln!("config: config.toml path={}", full.display());
+    }
+    if let Some(ref provider) = report.active_provider {
+        eprintln!("config: provider={}", provider);
+    }
+    if let Some(keys) = report.keys_summary() {
+        eprintln!("{}", keys);
+    }
+}
+
+/// Applies `--offline` / `--offline-script` as `LOOM_OFFLINE` / `LOOM_OFFLINE_SCRIPT`, so run
+/// config built from env (CLI runs, serve, tool listing) picks them up. Runs after config.toml
+/// and .env are applied, so the flags win.
+pub(crate) fn apply_offline_flags(args: &Args) {
+    if let Some(ref path) = args.offline_script {
+        std::env::set_var("LOOM_OFFLINE_SCRIPT", path);
+    }
+    if args.offline || args.offline_script.is_some() {
+        std::env::set_var("LOOM_OFFLINE", "1");
+    }
+}
+
+/// Applies `--remote` as `LOOM_REMOTE_URL` so every turn (single, interactive, watch) runs remotely.
+pub(crate) fn apply_remote_flag(args: &Args) {
+    if let Some(ref url) = args.remote {
+        std::env::set_var(cli::run::ENV_REMOTE_URL, url);
+    }
+}
+
+/// Applies `--graph` as `LOOM_GRAPH` once the spec loads; exits with a message when it does not.
+pub(crate) fn apply_graph_flag(args: &Args) {
+    if let Some(ref path) = args.graph {
+        if let Err(e) = loom::GraphSpec::load(path) {
+            eprintln!("loom: --graph: {}", e);
+            std::process::exit(1);
         }
+        std::env::set_var("LOOM_GRAPH", path);
     }
 }
 
@@ -43,11 +80,18 @@ pub(crate) fn init_logging(args: &Args) -> logging::LogGuard {
             .map(PathBuf::from)
     });
 
+    let run_log_dir = args.run_log_dir.clone().or_else(|| {
+        std::env::var_os("LOOM_RUN_LOG_DIR")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    });
+
     let log_args = logging::LogArgs::new(
         log_level,
         log_file,
         &args.log_rotate,
         args.working_folder.clone(),
-    );
+    )
+    .with_run_log_dir(run_log_dir);
     logging::init(&log_args)
 }
@@ -0,0 +1,499 @@
+//! `loom doctor`: environment diagnostics for first-run problems.
+//!
+//! Resolves config exactly like a run does (`build_helve_config`), then checks the LLM API key
+//! (presence, plus a cheap `/models` request), the checkpoint/memory DB, configured MCP servers,
+//! the working folder, and model-limit resolution. Each failing check prints a fix, so setup
+//! errors show up here instead of as `BuildRunnerError::NoLlm` on the first run.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use config::McpServerDef;
+use loom::model_spec::{ModelLimitResolver, ModelsDevResolver};
+use loom::{HelveConfig, ReactBuildConfig};
+use serde::Serialize;
+
+use crate::args::{Args, DoctorArgs};
+use crate::run_flow::build_run_options;
+
+const DEFAULT_OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(10);
+
+const FIX_NO_API_KEY: &str = "set OPENAI_API_KEY (env, project .env, or [env] in \
+    ~/.loom/config.toml), or add api_key to a [[providers]] entry and select it with --provider \
+    or --model provider/model; use --offline to try loom without a provider";
+const FIX_KEY_REJECTED: &str =
+    "the provider rejected the key: check OPENAI_API_KEY belongs to the OPENAI_BASE_URL provider";
+const FIX_UNREACHABLE: &str =
+    "check OPENAI_BASE_URL (the API root, e.g. https://host/v1) and your network/proxy settings";
+
+/// Outcome of one check.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum CheckStatus {
+    Ok,
+    Warn,
+    Fail,
+    Skip,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct CheckResult {
+    pub(crate) name: String,
+    pub(crate) status: CheckStatus,
+    pub(crate) detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) fix: Option<String>,
+}
+
+impl CheckResult {
+    fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+
+    fn with_fix_if_failed(self, fix: String) -> Self {
+        if self.status == CheckStatus::Fail {
+            self.with_fix(fix)
+        } else {
+            self
+        }
+    }
+}
+
+/// Runs all checks and prints the report. Returns `true` when no check failed.
+pub(crate) async fn handle_doctor_command(
+    args: &Args,
+    doctor_args: &DoctorArgs,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let opts = build_run_options(args, String::new(), false);
+    let (helve, config, _) = loom::build_helve_config(&opts);
+    let network = !doctor_args.no_network && !config.offline;
+
+    let mut results = Vec::new();
+    results.extend(check_llm(&config, network).await);
+    results.push(check_database(&database_path(&config)));
+    results.extend(check_mcp_servers(config.mcp_servers.as_deref(), network).await);
+    results.push(check_working_folder(&working_folder(&helve, &config)));
+    results.push(check_model_limit(config.model.as_deref(), network).await);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    } else {
+        print_report(&results);
+    }
+    Ok(!results.iter().any(|r| r.status == CheckStatus::Fail))
+}
+
+fn database_path(config: &ReactBuildConfig) -> PathBuf {
+    config
+        .db_path
+        .as_ref()
+        .map(PathBuf::from)
+        .unwrap_or_else(loom::memory::default_memory_db_path)
+}
+
+fn working_folder(helve: &HelveConfig, config: &ReactBuildConfig) -> PathBuf {
+    helve
+        .working_folder
+        .clone()
+        .or_else(|| config.working_folder.clone())
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// API key presence (same precedence as the runner: config, then `OPENAI_API_KEY`) and, when
+/// network checks are on, a `/models` request against the configured base URL.
+async fn check_llm(config: &ReactBuildConfig, network: bool) -> Vec<CheckResult> {
+    if config.offline {
+        return vec![CheckResult::new(
+            "api_key",
+            CheckStatus::Skip,
+            "offline mode: the scripted mock LLM is used",
+        )];
+    }
+    let api_key = config
+        .openai_api_key
+        .as_ref()
+        .map(|s| s.expose().to_string())
+        .or_else(|| std::env::var("OPENAI_API_KEY").ok())
+        .filter(|k| !k.trim().is_empty());
+    let Some(api_key) = api_key else {
+        let missing = CheckResult::new("api_key", CheckStatus::Fail, "no API key configured");
+        return vec![missing.with_fix(FIX_NO_API_KEY)];
+    };
+    let mut results = vec![CheckResult::new(
+        "api_key",
+        CheckStatus::Ok,
+        format!("set ({})", config::mask_value(&api_key)),
+    )];
+
+    let base_url = config
+        .openai_base_url
+        .clone()
+        .or_else(|| std::env::var("OPENAI_BASE_URL").ok())
+        .unwrap_or_else(|| DEFAULT_OPENAI_BASE_URL.to_string());
+    if !network {
+        results.push(CheckResult::new(
+            "api_ping",
+            CheckStatus::Skip,
+            "network checks disabled",
+        ));
+        return results;
+    }
+    let probe = tokio::time::timeout(
+        NETWORK_TIMEOUT,
+        loom::llm::probe_models_endpoint(&base_url, Some(&api_key)),
+    )
+    .await;
+    results.push(match probe {
+        Ok(Ok(models)) => CheckResult::new(
+            "api_ping",
+            CheckStatus::Ok,
+            format!("{} accepted the key ({} models)", base_url, models.len()),
+        ),
+        Ok(Err(e)) => {
+            let msg = e.to_string();
+            let fix = if msg.contains("401") || msg.contains("403") {
+                FIX_KEY_REJECTED
+            } else {
+                FIX_UNREACHABLE
+            };
+            CheckResult::new("api_ping", CheckStatus::Fail, msg).with_fix(fix)
+        }
+        Err(_) => CheckResult::new(
+            "api_ping",
+            CheckStatus::Fail,
+            format!("{} did not answer within {:?}", base_url, NETWORK_TIMEOUT),
+        )
+        .with_fix(FIX_UNREACHABLE),
+    });
+    results
+}
+
+/// Opens the DB and takes a write lock (rolled back), creating the parent directory like the
+/// checkpointer would.
+pub(crate) fn check_database(path: &Path) -> CheckResult {
+    let fix = "set LOOM_DB_PATH to a file in a writable directory";
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            return CheckResult::new(
+                "database",
+                CheckStatus::Fail,
+                format!("cannot create {}: {}", parent.display(), e),
+            )
+            .with_fix(fix);
+        }
+    }
+    let writable = rusqlite::Connection::open(path)
+        .and_then(|conn| conn.execute_batch("BEGIN IMMEDIATE; ROLLBACK;"));
+    match writable {
+        Ok(()) => CheckResult::new(
+            "database",
+            CheckStatus::Ok,
+            format!("{} is writable", path.display()),
+        ),
+        Err(e) => CheckResult::new(
+            "database",
+            CheckStatus::Fail,
+            format!("{} is not writable: {}", path.display(), e),
+        )
+        .with_fix(fix),
+    }
+}
+
+/// Stdio servers: the command resolves to a file. HTTP servers: a TCP connection to the host.
+async fn check_mcp_servers(servers: Option<&[McpServerDef]>, network: bool) -> Vec<CheckResult> {
+    let servers = match servers {
+        Some(s) if !s.is_empty() => s,
+        _ => {
+            return vec![CheckResult::new(
+                "mcp",
+                CheckStatus::Skip,
+                "no enabled MCP servers configured",
+            )]
+        }
+    };
+    let mut results = Vec::with_capacity(servers.len());
+    for server in servers {
+        results.push(match server {
+            McpServerDef::Stdio { name, command, .. } => check_mcp_command(name, command),
+            McpServerDef::Http { name, url, .. } => check_mcp_url(name, url, network).await,
+        });
+    }
+    results
+}
+
+fn check_mcp_command(name: &str, command: &str) -> CheckResult {
+    let check = format!("mcp:{}", name);
+    match find_command(command) {
+        Some(path) => CheckResult::new(check, CheckStatus::Ok, path.display().to_string()),
+        None => CheckResult::new(
+            check,
+            CheckStatus::Fail,
+            format!("command not found: {}", command),
+        )
+        .with_fix(format!(
+            "install {} or fix it with `loom mcp edit {} --command ...` (or `loom mcp disable {}`)",
+            command, name, name
+        )),
+    }
+}
+
+async fn check_mcp_url(name: &str, url: &str, network: bool) -> CheckResult {
+    let check = format!("mcp:{}", name);
+    if !network {
+        return CheckResult::new(check, CheckStatus::Skip, "network checks disabled");
+    }
+    let Some((host, port)) = url_host_port(url) else {
+        return CheckResult::new(check, CheckStatus::Fail, format!("invalid url: {}", url))
+            .with_fix(format!(
+                "fix the url with `loom mcp edit {} --url ...`",
+                name
+            ));
+    };
+    let connect = tokio::time::timeout(
+        NETWORK_TIMEOUT,
+        tokio::net::TcpStream::connect((host.as_str(), port)),
+    )
+    .await;
+    match connect {
+        Ok(Ok(_)) => CheckResult::new(check, CheckStatus::Ok, format!("{} is reachable", url)),
+        Ok(Err(e)) => CheckResult::new(
+            check,
+            CheckStatus::Fail,
+            format!("{} is unreachable: {}", url, e),
+        ),
+        Err(_) => CheckResult::new(
+            check,
+            CheckStatus::Fail,
+            format!("{} did not answer within {:?}", url, NETWORK_TIMEOUT),
+        ),
+    }
+    .with_fix_if_failed(format!(
+        "start the server or disable it with `loom mcp disable {}`",
+        name
+    ))
+}
+
+/// Resolves `command` like a shell would: paths are taken as-is, bare names are searched in `PATH`.
+pub(crate) fn find_command(command: &str) -> Option<PathBuf> {
+    let candidate = Path::new(command);
+    if candidate.components().count() > 1 {
+        return candidate.is_file().then(|| candidate.to_path_buf());
+    }
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).find_map(|dir| {
+        let plain = dir.join(command);
+        if plain.is_file() {
+            return Some(plain);
+        }
+        let suffixed = dir.join(format!("{}{}", command, std::env::consts::EXE_SUFFIX));
+        suffixed.is_file().then_some(suffixed)
+    })
+}
+
+/// Host and port of an `http(s)://` url; the port defaults from the scheme.
+pub(crate) fn url_host_port(url: &str) -> Option<(String, u16)> {
+    let (rest, default_port) = if let Some(rest) = url.strip_prefix("https://") {
+        (rest, 443)
+    } else if let Some(rest) = url.strip_prefix("http://") {
+        (rest, 80)
+    } else {
+        return None;
+    };
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let authority = authority.rsplit('@').next()?;
+    let (host, port) = if let Some(v6) = authority.strip_prefix('[') {
+        let (host, after) = v6.split_once(']')?;
+        (host, after.strip_prefix(':'))
+    } else {
+        match authority.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+    if host.is_empty() {
+        return None;
+    }
+    let port = match port {
+        Some(p) => p.parse().ok()?,
+        None => default_port,
+    };
+    Some((host.to_string(), port))
+}
+
+/// The folder exists, can be listed, and accepts a new file.
+pub(crate) fn check_working_folder(path: &Path) -> CheckResult {
+    let fix = "pass -w/--working-folder DIR or set WORKING_FOLDER to a directory you can write";
+    if !path.is_dir() {
+        return CheckResult::new(
+            "working_folder",
+            CheckStatus::Fail,
+            format!("{} is not a directory", path.display()),
+        )
+        .with_fix(fix);
+    }
+    if let Err(e) = std::fs::read_dir(path) {
+        return CheckResult::new(
+            "working_folder",
+            CheckStatus::Fail,
+            format!("cannot list {}: {}", path.display(), e),
+        )
+        .with_fix(fix);
+    }
+    let probe = path.join(format!(".loom-doctor-{}", std::process::id()));
+    match std::fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = std::fs::remove_file(&probe);
+            CheckResult::new(
+                "working_folder",
+                CheckStatus::Ok,
+                format!("{} is readable and writable", path.display()),
+            )
+        }
+        Err(e) => CheckResult::new(
+            "working_folder",
+            CheckStatus::Warn,
+            format!("{} is read-only: {}", path.display(), e),
+        )
+        .with_fix(format!("file-writing tools will fail; {}", fix)),
+    }
+}
+
+/// Context limit lookup on models.dev, as done when the runner sizes compaction.
+async fn check_model_limit(model: Option<&str>, network: bool) -> CheckResult {
+    let Some(model) = model.filter(|m| !m.is_empty()) else {
+        return CheckResult::new(
+            "model_limit",
+            CheckStatus::Warn,
+            "no model configured; the runner falls back to gpt-4o-mini",
+        )
+        .with_fix("set a model with --model provider/model or in your agent profile");
+    };
+    if !network {
+        return CheckResult::new("model_limit", CheckStatus::Skip, "network checks disabled");
+    }
+    let resolver = ModelsDevResolver::new();
+    let lookup = async {
+        if model.contains('/') {
+            resolver.resolve_combined(model).await
+        } else {
+            resolver.resolve_by_bare_model_name(model).await
+        }
+    };
+    match tokio::time::timeout(NETWORK_TIMEOUT, lookup).await {
+        Ok(Some(spec)) => CheckResult::new(
+            "model_limit",
+            CheckStatus::Ok,
+            format!(
+                "{}: {} context, {} output tokens",
+                model, spec.context_limit, spec.output_limit
+            ),
+        ),
+        _ => CheckResult::new(
+            "model_limit",
+            CheckStatus::Warn,
+            format!("could not resolve limits for {}", model),
+        )
+        .with_fix(
+            "use a provider/model id known to models.dev (see `loom models list`); otherwise \
+             compaction assumes the default 128K context",
+        ),
+    }
+}
+
+fn print_report(results: &[CheckResult]) {
+    println!("loom doctor");
+    println!("{}", "─".repeat(80));
+    for r in results {
+        let mark = match r.status {
+            CheckStatus::Ok => "✓",
+            CheckStatus::Warn => "!",
+            CheckStatus::Fail => "✗",
+            CheckStatus::Skip => "-",
+        };
+        println!("{} {}: {}", mark, r.name, r.detail);
+        if let Some(fix) = &r.fix {
+            println!("    fix: {}", fix);
+        }
+    }
+    let failed = results
+        .iter()
+        .filter(|r| r.status == CheckStatus::Fail)
+        .count();
+    let warned = results
+        .iter()
+        .filter(|r| r.status == CheckStatus::Warn)
+        .count();
+    println!();
+    println!(
+        "{} checks: {} failed, {} warnings",
+        results.len(),
+        failed,
+        warned
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn url_host_port_defaults_port_from_scheme() {
+        assert_eq!(
+            url_host_port("https://mcp.example.com/mcp"),
+            Some(("mcp.example.com".to_string(), 443))
+        );
+        assert_eq!(
+            url_host_port("http://localhost:3000/mcp?x=1"),
+            Some(("localhost".to_string(), 3000))
+        );
+        assert_eq!(
+            url_host_port("http://[::1]:8080/"),
+            Some(("::1".to_string(), 8080))
+        );
+        assert_eq!(
+            url_host_port("http://[::1]/mcp"),
+            Some(("::1".to_string(), 80))
+        );
+        assert_eq!(url_host_port("ftp://host"), None);
+        assert_eq!(url_host_port("http:///path"), None);
+    }
+
+    #[test]
+    fn check_database_creates_parent_and_reports_writable() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("memory.db");
+        let result = check_database(&path);
+        assert_eq!(result.status, CheckStatus::Ok, "{:?}", result);
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn check_working_folder_fails_with_fix_for_missing_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let ok = check_working_folder(dir.path());
+        assert_eq!(ok.status, CheckStatus::Ok);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+
+        let missing = check_working_folder(&dir.path().join("nope"));
+        assert_eq!(missing.status, CheckStatus::Fail);
+        assert!(missing.fix.unwrap().contains("--working-folder"));
+    }
+
+    #[test]
+    fn check_mcp_command_reports_missing_binary() {
+        let result = check_mcp_command("fs", "definitely-not-a-real-command-xyz");
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.fix.unwrap().contains("loom mcp disable fs"));
+    }
+}
@@ -14,8 +14,8 @@ pub use loom::Envelope;
 pub use model_cmd::{list_all_models, list_provider_models};
 pub use run::{
     cli_list_models, cli_list_tools, cli_show_tool, print_reply_timestamp,
-    run_agent_wrapper as run_agent, run_cli_turn, RunAgentOutput, RunAgentResult, RunCmd, RunError,
-    RunOptions, RunOutput, RunStopReason, StreamOut,
+    run_agent_wrapper as run_agent, run_cli_turn, RemoteBackend, RunAgentOutput, RunAgentResult,
+    RunCmd, RunError, RunOptions, RunOutput, RunStopReason, StreamOut,
 };
 pub use tool_cmd::{
     format_tool_show_output, format_tools_list, list_tools, show_tool, ToolShowFormat,
@@ -4,6 +4,8 @@
 //! - `--log-level` overrides `RUST_LOG`; otherwise `RUST_LOG`, else `info`
 //! - `--log-file` overrides `LOG_FILE`; when neither is set, logs are dropped (stdout stays clean)
 //! - `--log-rotate`: Rotation strategy when writing to a file (none, daily, hourly, minutely)
+//! - `--run-log-dir` overrides `LOOM_RUN_LOG_DIR`; when set, each run's events are also written
+//!   to their own file in that directory (see [`crate::run_log`])
 
 use std::path::Path;
 use std::str::FromStr;
@@ -12,6 +14,8 @@ use config::tracing_init;
 pub use config::tracing_init::LogRotate;
 use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
+use crate::run_log::RunLogLayer;
+
 /// Log configuration from CLI args.
 #[derive(Debug, Clone)]
 pub struct LogArgs {
@@ -23,6 +27,8 @@ pub struct LogArgs {
     pub rotate: LogRotate,
     /// Working folder for variable substitution in log file path
     pub working_folder: Option<std::path::PathBuf>,
+    /// Optional directory for per-run log files (supports {working_folder} variable)
+    pub run_log_dir: Option<std::path::PathBuf>,
 }
 
 impl LogArgs {
@@ -38,14 +44,35 @@ impl LogArgs {
             file,
             rotate: LogRotate::from_str(rotate).unwrap_or_default(),
             working_folder,
+            run_log_dir: None,
         }
     }
 
+    /// Also writes each run's events to `<dir>/<run_id>.log`, rotated like the log file.
+    pub fn with_run_log_dir(mut self, dir: Option<std::path::PathBuf>) -> Self {
+        self.run_log_dir = dir;
+        self
+    }
+
     fn resolve_log_file(&self) -> Option<std::path::PathBuf> {
         self.file.as_ref().map(|path| {
             tracing_init::resolve_log_path(path.as_path(), self.working_folder.as_deref())
         })
     }
+
+    fn run_log_layer(&self) -> Option<RunLogLayer> {
+        let dir = self.run_log_dir.as_ref().map(|path| {
+            tracing_init::resolve_log_path(path.as_path(), self.working_folder.as_deref())
+        })?;
+        std::fs::create_dir_all(&dir).unwrap_or_else(|e| {
+            panic!(
+                "failed to create run log directory {}: {}",
+                dir.display(),
+                e
+            )
+        });
+        Some(RunLogLayer::new(dir, self.rotate))
+    }
 }
 
 /// Worker guard that keeps the log file writer alive.
@@ -58,6 +85,7 @@ pub struct LogGuard {
 ///
 /// - With a resolved log file path (`--log-file` or `LOG_FILE`): logs go to file (with rotation) only
 /// - Without: logs are dropped (sink)
+/// - With a run log directory: each run's events also go to their own file there
 ///
 /// Returns `LogGuard` that must be kept alive for file logging to work.
 /// Panics if file logging fails to initialize.
@@ -65,15 +93,21 @@ pub fn init(args: &LogArgs) -> LogGuard {
     let filter = tracing_init::build_env_filter(&args.level, &["hyper_util=off"]);
 
     let log_file = args.resolve_log_file();
+    let run_logs = args.run_log_layer();
 
     if let Some(ref path) = log_file {
-        init_file_logging(path, args.rotate, filter)
+        init_file_logging(path, args.rotate, filter, run_logs)
     } else {
-        init_sink_logging(filter)
+        init_sink_logging(filter, run_logs)
     }
 }
 
-fn init_file_logging(path: &Path, rotate: LogRotate, filter: EnvFilter) -> LogGuard {
+fn init_file_logging(
+    path: &Path,
+    rotate: LogRotate,
+    filter: EnvFilter,
+    run_logs: Option<RunLogLayer>,
+) -> LogGuard {
     let (writer, guard) = tracing_init::file_non_blocking_writer(path, rotate, "loom")
         .unwrap_or_else(|e| panic!("failed to open log file {}: {}", path.display(), e));
 
@@ -85,6 +119,7 @@ fn init_file_logging(path: &Path, rotate: LogRotate, filter: EnvFilter) -> LogGu
     tracing_subscriber::registry()
         .with(filter)
         .with(layer)
+        .with(run_logs)
         .init();
 
     LogGuard {
@@ -92,7 +127,7 @@ fn init_file_logging(path: &Path, rotate: LogRotate, filter: EnvFilter) -> LogGu
     }
 }
 
-fn init_sink_logging(filter: EnvFilter) -> LogGuard {
+fn init_sink_logging(filter: EnvFilter, run_logs: Option<RunLogLayer>) -> LogGuard {
     use std::io::{self, Write};
 
     struct Sink;
@@ -116,6 +151,7 @@ fn init_sink_logging(filter: EnvFilter) -> LogGuard {
     tracing_subscriber::registry()
         .with(filter)
         .with(layer)
+        .with(run_logs)
         .init();
 
     LogGuard {
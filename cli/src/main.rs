@@ -1,39 +1,53 @@
 //! Loom CLI binary: run ReAct or DUP agent from the command line.
 //!
-//! Subcommands: `react` (default ReAct), `dup` (DUP), `tot` (ToT), `got` (GoT), `tool` (list/show tools), `models` (list models), `mcp` (manage MCP servers).
+//! Subcommands: `react` (default ReAct), `dup` (DUP), `tot` (ToT), `got` (GoT), `tool` (list/show tools), `models` (list models), `mcp` (manage MCP servers), `watch` (re-run on file changes), `thread` (export/import checkpoint archives), `checkpoint` (list/show checkpoint history), `doctor` (environment diagnostics), `config` (validate config.toml), `usage` (token usage and cost report), `protocol` (protocol JSON Schema).
 //! Dispatch lives here; see `args`, `bootstrap`, `display_limits`, `run_flow`, and `subcommands` for implementation.
 
 mod args;
 mod bootstrap;
 mod display_limits;
+mod doctor;
 mod log_format;
 mod logging;
 mod mcp_manager;
 mod output;
 mod repl;
 mod run_flow;
+mod run_log;
 mod session;
 mod subcommands;
+mod usage_cmd;
+mod watch;
 
 pub(crate) use args::Command;
 
 use clap::Parser;
 
 use args::{Args, Command as Cmd, GotArgs};
-use bootstrap::{init_logging, print_config_report};
+use bootstrap::{
+    apply_graph_flag, apply_offline_flags, apply_remote_flag, init_logging, print_config_report,
+};
 use display_limits::max_reply_len;
+use doctor::handle_doctor_command;
 use run_flow::{
     build_run_options, output_config, resolve_user_message, run_interactive_mode,
-    run_single_turn_mode,
+    run_single_turn_mode, run_watch,
 };
 use subcommands::{
-    handle_mcp_command, handle_models_command, handle_session_command, handle_tool_command,
+    handle_checkpoint_command, handle_config_command, handle_mcp_command, handle_models_command,
+    handle_protocol_command, handle_session_command, handle_thread_command, handle_tool_command,
 };
+use usage_cmd::handle_usage_command;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
-    print_config_report();
+    if !matches!(args.cmd, Some(Cmd::Config(_))) {
+        print_config_report();
+    }
+    apply_offline_flags(&args);
+    apply_remote_flag(&args);
+    apply_graph_flag(&args);
     let _log_guard = init_logging(&args);
 
     if let Some(Cmd::Serve(sa)) = &args.cmd {
@@ -49,11 +63,46 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
         return Ok(());
     }
+    if let Some(Cmd::Worker) = &args.cmd {
+        if let Err(e) = serve::run_worker().await {
+            eprintln!("worker error: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
 
     if let Some(Cmd::Session(sa)) = &args.cmd {
         handle_session_command(sa, args.json).await?;
         return Ok(());
     }
+    if let Some(Cmd::Thread(ta)) = &args.cmd {
+        if let Err(err) = handle_thread_command(ta, args.json).await {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    if let Some(Cmd::Checkpoint(ca)) = &args.cmd {
+        if let Err(err) = handle_checkpoint_command(ca, args.json).await {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    if let Some(Cmd::Usage(ua)) = &args.cmd {
+        if let Err(err) = handle_usage_command(ua, args.json).await {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    if let Some(Cmd::Protocol(pa)) = &args.cmd {
+        if let Err(err) = handle_protocol_command(pa) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
     if let Some(Cmd::Tool(ta)) = &args.cmd {
         if let Err(err) = handle_tool_command(&args, ta).await {
             eprintln!("{}", err);
@@ -68,6 +117,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
         return Ok(());
     }
+    if let Some(Cmd::Doctor(da)) = &args.cmd {
+        match handle_doctor_command(&args, da).await {
+            Ok(true) => return Ok(()),
+            Ok(false) => std::process::exit(1),
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        }
+    }
+    if let Some(Cmd::Config(ca)) = &args.cmd {
+        match handle_config_command(ca, args.json) {
+            Ok(true) => return Ok(()),
+            Ok(false) => std::process::exit(1),
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        }
+    }
     if let Some(Cmd::Mcp(ma)) = &args.cmd {
         if let Err(err) = handle_mcp_command(ma, args.json) {
             eprintln!("{}", err);
@@ -76,6 +145,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
+    if let Some(Cmd::Watch(wa)) = &args.cmd {
+        let Some(message) = wa.message.clone().or_else(|| resolve_user_message(&args)) else {
+            eprintln!("loom watch: provide a message via -m/--message");
+            std::process::exit(1);
+        };
+        let mut opts = build_run_options(&args, message, false);
+        run_watch(&mut opts, wa, max_reply_len(), &output_config(&args)).await?;
+        return Ok(());
+    }
+
     let message = resolve_user_message(&args);
     if !args.interactive && message.is_none() {
         eprintln!("loom: provide a message via -m/--message or positional args");
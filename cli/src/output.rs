@@ -115,10 +115,14 @@ pub(crate) fn emit_run_output(
             reasoning_content,
             reply_envelope,
             stop_reason,
+            transcript,
         } => {
             if config.json {
                 let mut out = reply_value(reply, reasoning_content, reply_envelope);
                 out["stop_reason"] = serde_json::json!(stop_reason_str(stop_reason));
+                if !transcript.is_empty() {
+                    out["transcript"] = serde_json::json!(transcript);
+                }
                 if let Some(session_id) = session_id {
                     out["session_id"] = serde_json::json!(session_id);
                 }
@@ -133,12 +137,16 @@ pub(crate) fn emit_run_output(
             reasoning_content,
             reply_envelope,
             stop_reason,
+            transcript,
         } => {
             let mut out = serde_json::json!({
                 "events": events,
                 "reply": reply_value(reply, reasoning_content, reply_envelope),
                 "stop_reason": stop_reason_str(stop_reason),
             });
+            if !transcript.is_empty() {
+                out["transcript"] = serde_json::json!(transcript);
+            }
             if let Some(session_id) = session_id {
                 out["session_id"] = serde_json::json!(session_id);
             }
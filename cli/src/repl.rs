@@ -6,9 +6,10 @@ use std::io::Write;
 
 use tokio::io::{AsyncBufReadExt, BufReader};
 
-use cli::{run_cli_turn, RunCmd, RunError, RunOptions, RunOutput, StreamOut};
+use cli::run::remote_url;
+use cli::{run_cli_turn, RemoteBackend, RunCmd, RunError, RunOptions, RunOutput, StreamOut};
 use loom::command::{self as loom_command};
-use loom::UserContent;
+use loom::{InputPolicy, UserContent};
 
 use crate::output::{emit_run_output, OutputConfig};
 use crate::Command;
@@ -16,6 +17,7 @@ use crate::Command;
 fn cmd_to_runcmd(cmd: &Command) -> RunCmd {
     match cmd {
         Command::Serve(_) => unreachable!("serve handled in main"),
+        Command::Worker => unreachable!("worker handled in main"),
         Command::React => RunCmd::React,
         Command::Dup => RunCmd::Dup,
         Command::Tot => RunCmd::Tot,
@@ -26,6 +28,13 @@ fn cmd_to_runcmd(cmd: &Command) -> RunCmd {
         Command::Session(_) => unreachable!("session handled in main"),
         Command::Models(_) => unreachable!("models handled in main"),
         Command::Mcp(_) => unreachable!("mcp handled in main"),
+        Command::Watch(_) => unreachable!("watch handled in main"),
+        Command::Thread(_) => unreachable!("thread handled in main"),
+        Command::Checkpoint(_) => unreachable!("checkpoint handled in main"),
+        Command::Doctor(_) => unreachable!("doctor handled in main"),
+        Command::Config(_) => unreachable!("config handled in main"),
+        Command::Usage(_) => unreachable!("usage handled in main"),
+        Command::Protocol(_) => unreachable!("protocol handled in main"),
     }
 }
 
@@ -51,21 +60,27 @@ pub async fn run_repl_loop(
             Some(s) => s,
         };
 
-        if let Some(parsed) = loom_command::parse(&line) {
-            match parsed {
-                loom_command::Command::Models { .. } | loom_command::Command::ModelsUse { .. } => {
-                    println!("/models is not yet supported in CLI mode.");
-                }
-                _ => {
-                    let reply = handle_repl_command(parsed);
-                    println!("{}", reply);
-                }
+        let message = match loom_command::parse(&line) {
+            // `/pin <text>` sends the text as a pinned message.
+            Some(loom_command::Command::Pin { text: Some(text) }) => {
+                UserContent::Text(text).pinned()
             }
-            continue;
-        }
+            Some(
+                loom_command::Command::Models { .. } | loom_command::Command::ModelsUse { .. },
+            ) => {
+                println!("/models is not yet supported in CLI mode.");
+                continue;
+            }
+            Some(parsed) => {
+                let reply = handle_repl_command(parsed);
+                println!("{}", reply);
+                continue;
+            }
+            None => UserContent::Text(line),
+        };
 
         let mut opts = base_opts.clone();
-        opts.message = UserContent::Text(line);
+        opts.message = message;
 
         match run_one_turn(&opts, cmd, stream_out.clone()).await {
             Ok(output_value) => emit_run_output(
@@ -91,9 +106,12 @@ fn handle_repl_command(cmd: loom_command::Command) -> String {
         loom_command::Command::Summarize => {
             "/summarize requires an active session with LLM access.".into()
         }
-        loom_command::Command::Models { .. } | loom_command::Command::ModelsUse { .. } => {
-            unreachable!("handled above")
+        loom_command::Command::Pin { text: None } => {
+            "Usage: /pin <message> sends a message that is never compacted away.".into()
         }
+        loom_command::Command::Pin { text: Some(_) }
+        | loom_command::Command::Models { .. }
+        | loom_command::Command::ModelsUse { .. } => unreachable!("handled above"),
     }
 }
 
@@ -102,13 +120,21 @@ fn is_quit_command(s: &str) -> bool {
     matches!(lower.as_str(), "quit" | "exit" | "/quit")
 }
 
+/// Runs one turn; the user message is normalized first (see [`InputPolicy::from_env`]).
+/// With a remote URL set (`--remote`), the turn runs on that server.
 pub async fn run_one_turn(
     opts: &RunOptions,
     cmd: &Command,
     stream_out: StreamOut,
 ) -> Result<RunOutput, RunError> {
     let run_cmd = cmd_to_runcmd(cmd);
-    run_cli_turn(opts, &run_cmd, stream_out).await
+    let mut opts = opts.clone();
+    InputPolicy::from_env().apply_content(&mut opts.message);
+    if let Some(url) = remote_url() {
+        let mut backend = RemoteBackend::connect(&url).await?;
+        return backend.run_turn(&opts, &run_cmd, stream_out).await;
+    }
+    run_cli_turn(&opts, &run_cmd, stream_out).await
 }
 
 #[cfg(test)]
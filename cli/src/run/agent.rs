@@ -5,7 +5,7 @@ use chrono::Local;
 use loom::{
     build_helve_config, build_react_run_context, list_available_profiles, run_agent_with_options,
     AnyStreamEvent, DupState, Envelope, GotState, MessageChunkKind, ModelLimitResolver,
-    ModelsDevResolver, ReActState, ResolvedAgent, ToolCall, TotState,
+    ModelsDevResolver, ReActState, ResolvedAgent, ToolCall, ToolCallRecord, TotState,
 };
 use serde_json::Value;
 use std::sync::{Arc, Mutex};
@@ -17,6 +17,7 @@ use super::display::{
     format_dup_state_display, format_got_state_display, format_react_state_display,
     format_tot_state_display, truncate_display,
 };
+use super::progress::{progress_enabled, spawn_ticker, Progress};
 use crate::envelope::EnvelopeState;
 use loom::{RunCmd, RunOptions, StreamEvent};
 
@@ -28,14 +29,19 @@ pub enum RunStopReason {
     Cancelled,
 }
 
-fn completion_reply(result: loom::RunCompletion) -> (String, Option<String>, RunStopReason) {
+fn completion_reply(
+    result: loom::RunCompletion,
+) -> (String, Option<String>, RunStopReason, Vec<ToolCallRecord>) {
     match result {
         loom::RunCompletion::Finished(result) => (
             result.reply,
             result.reasoning_content,
             RunStopReason::EndTurn,
+            result.transcript,
         ),
-        loom::RunCompletion::Cancelled => (String::new(), None, RunStopReason::Cancelled),
+        loom::RunCompletion::Cancelled => {
+            (String::new(), None, RunStopReason::Cancelled, Vec::new())
+        }
     }
 }
 
@@ -143,6 +149,8 @@ pub struct RunAgentOutput {
     pub events: Option<Vec<Value>>,
     pub reply_envelope: Option<Envelope>,
     pub stop_reason: RunStopReason,
+    /// Tool calls made during the run (see [`loom::AgentRunResult::transcript`]).
+    pub transcript: Vec<ToolCallRecord>,
 }
 
 /// Result of run_agent_wrapper.
@@ -162,6 +170,12 @@ pub async fn run_agent_wrapper(
         if opts.dry_run {
             eprintln!("dry run: tools will not be executed");
         }
+        if config.offline {
+            eprintln!("offline: scripted mock LLM, network tools disabled");
+        }
+        if config.read_only {
+            eprintln!("read-only: shell and file-modifying tools disabled");
+        }
         print_agent_banner(&resolved_agent);
         print_available_agents();
         if helve.role_setting.is_some() {
@@ -205,13 +219,14 @@ pub async fn run_agent_wrapper(
             });
             let result = run_agent_with_options(opts, cmd, Some(on_event)).await?;
             let reply_env = state.lock().map(|s| s.reply_envelope()).ok();
-            let (reply, reasoning_content, stop_reason) = completion_reply(result);
+            let (reply, reasoning_content, stop_reason, transcript) = completion_reply(result);
             return Ok(RunAgentOutput {
                 reply,
                 reasoning_content,
                 events: None,
                 reply_envelope: reply_env,
                 stop_reason,
+                transcript,
             });
         }
         let events: Arc<Mutex<Vec<Value>>> = Arc::new(Mutex::new(Vec::new()));
@@ -243,13 +258,14 @@ pub async fn run_agent_wrapper(
         let result = run_agent_with_options(opts, cmd, Some(on_event)).await?;
         let events = events.lock().map(|v| v.clone()).unwrap_or_default();
         let reply_env = state.lock().map(|s| s.reply_envelope()).ok();
-        let (reply, reasoning_content, stop_reason) = completion_reply(result);
+        let (reply, reasoning_content, stop_reason, transcript) = completion_reply(result);
         return Ok(RunAgentOutput {
             reply,
             reasoning_content,
             events: Some(events),
             reply_envelope: reply_env,
             stop_reason,
+            transcript,
         });
     }
 
@@ -263,6 +279,7 @@ pub async fn run_agent_wrapper(
         agent_display,
         total_prompt_tokens: 0,
         total_completion_tokens: 0,
+        progress: Progress::new(progress_enabled()),
     }));
 
     let state_clone = state.clone();
@@ -270,25 +287,36 @@ pub async fn run_agent_wrapper(
     let output_timestamp = opts.output_timestamp;
     let on_event = Box::new(move |ev: AnyStreamEvent| {
         let mut s = state_clone.lock().unwrap();
+        s.progress.clear();
         match &ev {
             AnyStreamEvent::React(e) => {
-                on_event_react(e, &mut s, display_max_len, verbose, output_timestamp)
+                on_event_react(e, &mut s, display_max_len, verbose, output_timestamp);
+                s.progress.observe(e);
             }
             AnyStreamEvent::Dup(e) => {
-                on_event_dup(e, &mut s, display_max_len, verbose, output_timestamp)
+                on_event_dup(e, &mut s, display_max_len, verbose, output_timestamp);
+                s.progress.observe(e);
             }
             AnyStreamEvent::Tot(e) => {
-                on_event_tot(e, &mut s, display_max_len, verbose, output_timestamp)
+                on_event_tot(e, &mut s, display_max_len, verbose, output_timestamp);
+                s.progress.observe(e);
             }
             AnyStreamEvent::Got(e) => {
-                on_event_got(e, &mut s, display_max_len, verbose, output_timestamp)
+                on_event_got(e, &mut s, display_max_len, verbose, output_timestamp);
+                s.progress.observe(e);
             }
         }
     });
 
+    let ticker = spawn_ticker(state.clone(), event_progress);
     let start = Instant::now();
-    let result = run_agent_with_options(opts, cmd, Some(on_event)).await?;
+    let result = run_agent_with_options(opts, cmd, Some(on_event)).await;
     let duration = start.elapsed();
+    ticker.abort();
+    if let Ok(mut s) = state.lock() {
+        s.progress.clear();
+    }
+    let result = result?;
 
     if verbose {
         if let Some(ref from) = state.lock().unwrap().last_node {
@@ -308,13 +336,14 @@ pub async fn run_agent_wrapper(
             secs, tokens_per_sec, s.total_prompt_tokens, s.total_completion_tokens
         );
     }
-    let (reply, reasoning_content, stop_reason) = completion_reply(result);
+    let (reply, reasoning_content, stop_reason, transcript) = completion_reply(result);
     Ok(RunAgentOutput {
         reply,
         reasoning_content,
         events: None,
         reply_envelope: None,
         stop_reason,
+        transcript,
     })
 }
 
@@ -415,6 +444,7 @@ fn on_event_react(
                     );
                 }
             }
+            s.progress.line_ended();
 
             tracing::info!(
                 prompt_tokens,
@@ -581,6 +611,10 @@ fn on_event_tot(
     }
 }
 
+fn event_progress(s: &mut EventState) -> &mut Progress {
+    &mut s.progress
+}
+
 struct EventState {
     turn: u32,
     last_node: Option<String>,
@@ -592,6 +626,8 @@ struct EventState {
     total_prompt_tokens: u32,
     /// Accumulated completion tokens from all StreamEvent::Usage in this run.
     total_completion_tokens: u32,
+    /// Spinner line on stderr (see [`super::progress`]).
+    progress: Progress,
 }
 
 async fn print_loaded_tools(config: &loom::ReactBuildConfig) -> Result<(), RunError> {
@@ -659,12 +695,18 @@ fn on_event_got(
             node_id,
             nodes_added,
             edges_added,
+            denied,
         } => {
             if verbose {
-                eprintln!(
-                    "--- AGoT expand: {} → +{} nodes, +{} edges ---",
-                    node_id, nodes_added, edges_added
-                );
+                match denied {
+                    Some(reason) => {
+                        eprintln!("--- AGoT expand denied: {} ({}) ---", node_id, reason)
+                    }
+                    None => eprintln!(
+                        "--- AGoT expand: {} → +{} nodes, +{} edges ---",
+                        node_id, nodes_added, edges_added
+                    ),
+                }
             }
         }
         StreamEvent::Messages { chunk, .. } => {
@@ -746,18 +788,51 @@ mod tests {
             model: None,
             llm_provider: None,
             openai_temperature: None,
+            stop_sequences: Vec::new(),
             embedding_api_key: None,
             embedding_base_url: None,
             embedding_model: None,
             working_folder: None,
             approval_policy: None,
             compaction_config: None,
+            history_window: None,
+            state_limits: None,
+            graph_spec: None,
+            llm_routes: Vec::new(),
+            flags: Default::default(),
             tot_config: TotRunnerConfig::default(),
             got_config: GotRunnerConfig::default(),
             mcp_servers: None,
             skill_registry: None,
+            history_search: None,
             max_sub_agent_depth: None,
             dry_run: false,
+            node_models: Default::default(),
+            auto_continue: false,
+            context_fallback_model: None,
+            enable_reflection: false,
+            confidence_scoring: None,
+            dedup_observations: false,
+            route_rules: Vec::new(),
+            approval_rules: Vec::new(),
+            approval_audit_db: None,
+            tool_result_framing: Default::default(),
+            untrusted_tools: Vec::new(),
+            sanitize_tool_results: false,
+            injection_patterns: Vec::new(),
+            memory_recall: None,
+            env_context: false,
+            observation_summary_tokens: None,
+            tool_prefetch: Vec::new(),
+            tool_arguments: Default::default(),
+            allowed_tools: None,
+            read_only: false,
+            denied_tools: Vec::new(),
+            tool_selection: None,
+            tool_call_mode: None,
+            offline: false,
+            offline_script: None,
+            node_middleware: Default::default(),
         }
     }
 
@@ -787,6 +862,7 @@ mod tests {
             agent_display: None,
             total_prompt_tokens: 0,
             total_completion_tokens: 0,
+            progress: Progress::new(false),
         };
         on_event_react(
             &StreamEvent::TaskStart {
@@ -823,11 +899,13 @@ mod tests {
             agent_display: None,
             total_prompt_tokens: 0,
             total_completion_tokens: 0,
+            progress: Progress::new(false),
         };
 
         let dup_state = DupState {
             core: react_state(),
             understood: None,
+            replan: Default::default(),
         };
         on_event_dup(
             &StreamEvent::TaskStart {
@@ -898,6 +976,7 @@ mod tests {
                         arguments: "{}".to_string(),
                         id: None,
                     }],
+                    allowed_tools: None,
                 }],
                 edges: vec![],
             },
@@ -911,6 +990,8 @@ mod tests {
             )]
             .into_iter()
             .collect(),
+            token_budget: None,
+            tokens_used: 0,
         };
         on_event_got(
             &StreamEvent::TaskStart {
@@ -966,6 +1047,7 @@ mod tests {
             agent_display: None,
             total_prompt_tokens: 0,
             total_completion_tokens: 0,
+            progress: Progress::new(false),
         };
         let react_with_tool = ReActState {
             tool_calls: vec![ToolCall {
@@ -1002,6 +1084,7 @@ mod tests {
                 key_constraints: vec!["c1".to_string()],
                 relevant_context: "ctx".to_string(),
             }),
+            replan: Default::default(),
         };
         on_event_dup(
             &StreamEvent::Updates {
@@ -1026,6 +1109,7 @@ mod tests {
             agent_display: None,
             total_prompt_tokens: 0,
             total_completion_tokens: 0,
+            progress: Progress::new(false),
         };
 
         on_event_tot(
@@ -1096,6 +1180,7 @@ mod tests {
                 node_id: "n1".to_string(),
                 nodes_added: 2,
                 edges_added: 1,
+                denied: None,
             },
             &mut s,
             80,
@@ -1135,6 +1220,7 @@ mod tests {
             cancellation: None,
             thread_id: None,
             agent: None,
+            agent_file: None,
             verbose: false,
             got_adaptive: false,
             display_max_len: 200,
@@ -1143,10 +1229,19 @@ mod tests {
             mcp_config_path: None,
             output_timestamp: false,
             dry_run: false,
+            role_setting: None,
+            allowed_tools: None,
+            read_only: false,
+            reply_format: None,
+            reply_schema: None,
+            messages: None,
+            history_search: None,
+            locale: None,
             provider: None,
             base_url: None,
             api_key: None,
             provider_type: None,
+            flags: Default::default(),
         }
     }
 
@@ -2,9 +2,10 @@
 
 use crate::model_cmd::{list_all_models, list_provider_models};
 use crate::tool_cmd::{list_tools, show_tool, ToolShowFormat};
-use loom::{Envelope, RunCmd, RunError, RunOptions};
+use loom::{Envelope, RunCmd, RunError, RunOptions, ToolCallRecord};
 use serde_json::Value;
 use std::sync::{Arc, Mutex};
+use tracing::Instrument;
 
 use super::run_agent_wrapper as run_agent;
 use super::{RunAgentOutput, RunStopReason};
@@ -21,6 +22,8 @@ pub type StreamOut = Option<Arc<Mutex<dyn FnMut(Value) + Send>>>;
 /// - With `--json`: the reply is accompanied by a list of stream events (or events are
 ///   emitted incrementally via [`StreamOut`]).
 ///
+/// `transcript`: tool calls made during the run; included in `--json` output when non-empty.
+///
 /// `reply_envelope`: when using the protocol envelope (`session_id`/`node_id`/`event_id`),
 /// the reply line also includes an envelope so it can be correlated with the event stream.
 #[derive(Debug)]
@@ -30,6 +33,7 @@ pub enum RunOutput {
         reasoning_content: Option<String>,
         reply_envelope: Option<Envelope>,
         stop_reason: RunStopReason,
+        transcript: Vec<ToolCallRecord>,
     },
     Json {
         events: Vec<Value>,
@@ -37,6 +41,7 @@ pub enum RunOutput {
         reasoning_content: Option<String>,
         reply_envelope: Option<Envelope>,
         stop_reason: RunStopReason,
+        transcript: Vec<ToolCallRecord>,
     },
 }
 
@@ -45,18 +50,31 @@ pub enum RunOutput {
 ///   return `RunOutput::Reply { .. }`.
 /// - `stream_out = None`: may accumulate events. If `opts.output_json` is true, return
 ///   `RunOutput::Json { .. }`; otherwise return `RunOutput::Reply`.
+///
+/// The turn runs in a `cli_run` span with a fresh `run_id`, so `--run-log-dir` gets one log
+/// file per turn.
 pub async fn run_cli_turn(
     opts: &RunOptions,
     cmd: &RunCmd,
     stream_out: StreamOut,
 ) -> Result<RunOutput, RunError> {
-    let output = run_agent(opts, cmd, stream_out).await?;
+    let run_id = format!(
+        "run-{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos()
+    );
+    let output = run_agent(opts, cmd, stream_out)
+        .instrument(tracing::info_span!("cli_run", run_id = %run_id))
+        .await?;
     let RunAgentOutput {
         reply,
         reasoning_content,
         events,
         reply_envelope,
         stop_reason,
+        transcript,
     } = output;
     Ok(match events {
         Some(ev) => RunOutput::Json {
@@ -65,12 +83,14 @@ pub async fn run_cli_turn(
             reasoning_content,
             reply_envelope,
             stop_reason,
+            transcript,
         },
         None => RunOutput::Reply {
             reply,
             reasoning_content,
             reply_envelope,
             stop_reason,
+            transcript,
         },
     })
 }
@@ -257,6 +257,7 @@ mod tests {
         let dup = DupState {
             core,
             understood: None,
+            replan: Default::default(),
         };
 
         let tot_rendered = format_tot_state_display(&tot, 20);
@@ -290,10 +291,13 @@ mod tests {
                     id: "n1".to_string(),
                     description: "desc".to_string(),
                     tool_calls: vec![],
+                    allowed_tools: None,
                 }],
                 edges: vec![],
             },
             node_states,
+            token_budget: None,
+            tokens_used: 0,
         };
 
         let rendered = format_got_state_display(&state, 12);
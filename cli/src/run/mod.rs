@@ -3,6 +3,8 @@
 mod agent;
 mod contract;
 mod display;
+mod progress;
+mod remote;
 
 pub use agent::{
     print_reply_timestamp, run_agent_wrapper, RunAgentOutput, RunAgentResult, RunStopReason,
@@ -11,3 +13,7 @@ pub use contract::{
     cli_list_models, cli_list_tools, cli_show_tool, run_cli_turn, RunOutput, StreamOut,
 };
 pub use loom::{build_helve_config, RunCmd, RunError, RunOptions};
+pub use remote::{
+    remote_url, ReconnectPolicy, RemoteBackend, ENV_REMOTE_QUEUE, ENV_REMOTE_RECONNECT_ATTEMPTS,
+    ENV_REMOTE_URL,
+};
@@ -0,0 +1,205 @@
+//! One-line progress display on stderr for non-JSON runs.
+//!
+//! Shows a spinner with the running node, the tool being executed, elapsed time and tokens used
+//! so far, redrawn in place by a ticker task. Only drawn when stderr is a terminal; set
+//! `LOOM_NO_PROGRESS` to turn it off. The line is erased before any other output is printed
+//! (see [`Progress::clear`]) and hidden while the reply streams.
+
+use std::io::{IsTerminal, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use loom::{MessageChunkKind, StreamEvent};
+use tokio::task::JoinHandle;
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+const TICK: Duration = Duration::from_millis(100);
+/// Erases the current terminal line and returns the cursor to its start.
+const CLEAR_LINE: &str = "\r\x1b[2K";
+
+/// Whether the progress line should be drawn for this process.
+pub(crate) fn progress_enabled() -> bool {
+    std::io::stderr().is_terminal() && std::env::var_os("LOOM_NO_PROGRESS").is_none()
+}
+
+/// Progress line state, updated from stream events.
+pub(crate) struct Progress {
+    enabled: bool,
+    start: Instant,
+    node: Option<String>,
+    tool: Option<String>,
+    tokens: u64,
+    frame: usize,
+    /// The line is on screen and must be erased before other output.
+    drawn: bool,
+    /// Reply text is streaming; drawing would overwrite it.
+    paused: bool,
+    /// Streamed reply text did not end with a newline; start a new line before drawing.
+    pending_newline: bool,
+}
+
+impl Progress {
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            start: Instant::now(),
+            node: None,
+            tool: None,
+            tokens: 0,
+            frame: 0,
+            drawn: false,
+            paused: false,
+            pending_newline: false,
+        }
+    }
+
+    /// Updates node, tool and token count from one event.
+    pub(crate) fn observe<S>(&mut self, ev: &StreamEvent<S>)
+    where
+        S: Clone + Send + Sync + std::fmt::Debug + 'static,
+    {
+        match ev {
+            StreamEvent::TaskStart { node_id, .. } => {
+                self.node = Some(node_id.clone());
+                self.paused = false;
+            }
+            StreamEvent::ToolStart { name, .. } => self.tool = Some(name.clone()),
+            StreamEvent::ToolEnd { .. } => self.tool = None,
+            StreamEvent::Usage { total_tokens, .. } => {
+                self.tokens = self.tokens.saturating_add(u64::from(*total_tokens));
+            }
+            StreamEvent::Messages { chunk, .. } if !chunk.content.is_empty() => {
+                self.paused = true;
+                if chunk.kind == MessageChunkKind::Message {
+                    self.pending_newline = !chunk.content.ends_with('\n');
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Marks the output line as finished (something printed a trailing newline).
+    pub(crate) fn line_ended(&mut self) {
+        self.pending_newline = false;
+    }
+
+    /// Erases the progress line if it is on screen.
+    pub(crate) fn clear(&mut self) {
+        if self.drawn {
+            eprint!("{}", CLEAR_LINE);
+            let _ = std::io::stderr().flush();
+            self.drawn = false;
+        }
+    }
+
+    /// Text of the progress line, e.g. `⠋ act · bash · 4.2s · 1830 tokens`.
+    pub(crate) fn render(&self) -> String {
+        let mut parts = vec![self.node.clone().unwrap_or_else(|| "starting".to_string())];
+        if let Some(tool) = &self.tool {
+            parts.push(tool.clone());
+        }
+        parts.push(format!("{:.1}s", self.start.elapsed().as_secs_f64()));
+        if self.tokens > 0 {
+            parts.push(format!("{} tokens", self.tokens));
+        }
+        format!(
+            "{} {}",
+            SPINNER[self.frame % SPINNER.len()],
+            parts.join(" · ")
+        )
+    }
+
+    /// Advances the spinner and redraws the line.
+    fn tick(&mut self) {
+        if !self.enabled || self.paused {
+            return;
+        }
+        self.frame = self.frame.wrapping_add(1);
+        if self.pending_newline {
+            eprintln!();
+            self.pending_newline = false;
+        }
+        eprint!("{}{}", CLEAR_LINE, self.render());
+        let _ = std::io::stderr().flush();
+        self.drawn = true;
+    }
+}
+
+/// Spawns the task that redraws the progress line of `state` (reached via `progress`) every
+/// tick. Abort the handle when the run ends, then [`Progress::clear`] the line.
+pub(crate) fn spawn_ticker<T: Send + 'static>(
+    state: Arc<Mutex<T>>,
+    progress: fn(&mut T) -> &mut Progress,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TICK);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            if let Ok(mut s) = state.lock() {
+                progress(&mut *s).tick();
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use loom::{MessageChunk, ReActState, StreamMetadata};
+
+    #[test]
+    fn render_shows_node_tool_and_tokens() {
+        let mut p = Progress::new(false);
+        assert!(p.render().contains("starting"));
+        p.observe::<ReActState>(&StreamEvent::TaskStart {
+            node_id: "act".to_string(),
+            namespace: None,
+        });
+        p.observe::<ReActState>(&StreamEvent::ToolStart {
+            call_id: None,
+            name: "bash".to_string(),
+        });
+        p.observe::<ReActState>(&StreamEvent::Usage {
+            prompt_tokens: 1000,
+            completion_tokens: 830,
+            total_tokens: 1830,
+            prefill_duration: None,
+            decode_duration: None,
+        });
+        let line = p.render();
+        assert!(line.contains("act · bash · "), "{}", line);
+        assert!(line.ends_with("1830 tokens"), "{}", line);
+
+        p.observe::<ReActState>(&StreamEvent::ToolEnd {
+            call_id: None,
+            name: "bash".to_string(),
+            result: String::new(),
+            is_error: false,
+            raw_result: None,
+        });
+        assert!(!p.render().contains("bash"));
+    }
+
+    #[test]
+    fn reply_chunks_pause_until_next_node() {
+        let mut p = Progress::new(true);
+        p.observe::<ReActState>(&StreamEvent::Messages {
+            chunk: MessageChunk::message("The answer"),
+            metadata: StreamMetadata {
+                loom_node: "think".to_string(),
+                namespace: None,
+            },
+        });
+        assert!(p.paused && p.pending_newline);
+        p.tick();
+        assert!(!p.drawn);
+
+        p.line_ended();
+        p.observe::<ReActState>(&StreamEvent::TaskStart {
+            node_id: "act".to_string(),
+            namespace: None,
+        });
+        assert!(!p.paused && !p.pending_newline);
+    }
+}
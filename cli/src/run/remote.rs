@@ -0,0 +1,497 @@
+//! Remote mode: runs a turn on a `loom serve` instance over [`WsClient`] instead of in-process.
+//!
+//! Enabled with `--remote <URL>` or `LOOM_REMOTE_URL`. The server builds the agent (its tools,
+//! model and working folder); events and the reply come back in the same shapes as a local run.
+//!
+//! When the connection drops mid-run, [`RemoteBackend`] reconnects with exponential backoff
+//! ([`ReconnectPolicy`]) and resumes the run's event stream from the last event it received;
+//! the server keeps running a run whose client disconnected. With [`ENV_REMOTE_QUEUE`], a turn
+//! submitted while the server is unreachable waits for it instead of failing.
+
+use std::time::Duration;
+
+use loom::client::{ClientError, RunStream, WsClient};
+use loom::protocol::AgentIdentifier;
+use loom::{AgentType, Envelope, RunCmd, RunEndResponse, RunError, RunOptions, RunRequest};
+
+use super::contract::{RunOutput, StreamOut};
+use super::RunStopReason;
+
+/// Env var: serve URL (`ws://host:port`) for remote mode.
+pub const ENV_REMOTE_URL: &str = "LOOM_REMOTE_URL";
+
+/// Env var: reconnect attempts when the connection drops mid-run (default 5; `0` disables).
+pub const ENV_REMOTE_RECONNECT_ATTEMPTS: &str = "LOOM_REMOTE_RECONNECT_ATTEMPTS";
+
+/// Env var: when `1`/`true`/`yes`, a turn submitted while the server is unreachable is held and
+/// sent once a connection succeeds, instead of failing.
+pub const ENV_REMOTE_QUEUE: &str = "LOOM_REMOTE_QUEUE";
+
+/// Remote serve URL from [`ENV_REMOTE_URL`], when set and non-empty.
+pub fn remote_url() -> Option<String> {
+    std::env::var(ENV_REMOTE_URL)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn remote_error(e: ClientError) -> RunError {
+    RunError::Remote(e.to_string())
+}
+
+/// Whether `e` means the connection is gone (as opposed to an error answer from the server).
+fn is_disconnect(e: &ClientError) -> bool {
+    matches!(e, ClientError::Closed | ClientError::WebSocket(_))
+}
+
+/// How [`RemoteBackend`] reconnects.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReconnectPolicy {
+    /// Reconnect attempts per drop; `0` fails the turn on the first drop.
+    pub attempts: u32,
+    /// Delay before the first attempt; doubled after each failed one, up to `max_delay`.
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    /// Hold a turn submitted while disconnected until a connection succeeds, including when
+    /// the connection dropped between turns.
+    pub queue_while_disconnected: bool,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 5,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+            queue_while_disconnected: false,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Defaults overridden by [`ENV_REMOTE_RECONNECT_ATTEMPTS`] and [`ENV_REMOTE_QUEUE`].
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            attempts: std::env::var(ENV_REMOTE_RECONNECT_ATTEMPTS)
+                .ok()
+                .and_then(|s| s.trim().parse().ok())
+                .unwrap_or(defaults.attempts),
+            queue_while_disconnected: std::env::var(ENV_REMOTE_QUEUE)
+                .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false),
+            ..defaults
+        }
+    }
+
+    /// Delay before reconnect attempt `attempt` (0-based).
+    fn delay(&self, attempt: u32) -> Duration {
+        self.initial_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay)
+    }
+}
+
+/// Connects to `url`, retrying with backoff up to `policy.attempts` times, or until it succeeds
+/// when `unbounded`.
+async fn connect_with_backoff(
+    url: &str,
+    policy: &ReconnectPolicy,
+    unbounded: bool,
+) -> Result<WsClient, ClientError> {
+    let mut attempt = 0;
+    loop {
+        match WsClient::connect(url).await {
+            Ok(client) => return Ok(client),
+            Err(e) if unbounded || attempt < policy.attempts => {
+                let delay = policy.delay(attempt);
+                eprintln!(
+                    "remote: cannot reach {} ({}); retrying in {:.1}s",
+                    url,
+                    e,
+                    delay.as_secs_f64()
+                );
+                tokio::time::sleep(delay).await;
+                attempt = attempt.saturating_add(1);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// What the client has seen of the current run, so it can resume after a drop.
+#[derive(Default)]
+struct RunProgress {
+    run_id: Option<String>,
+    last_event_id: Option<u64>,
+    /// Collected events (JSON output without a stream sink).
+    events: Vec<serde_json::Value>,
+}
+
+/// A connection to `loom serve` that runs CLI turns.
+pub struct RemoteBackend {
+    url: String,
+    client: WsClient,
+    policy: ReconnectPolicy,
+}
+
+impl RemoteBackend {
+    /// Connects with [`ReconnectPolicy::from_env`].
+    pub async fn connect(url: &str) -> Result<Self, RunError> {
+        Self::connect_with_policy(url, ReconnectPolicy::from_env()).await
+    }
+
+    /// Connects to `url`. With `policy.queue_while_disconnected`, waits (retrying with backoff)
+    /// until the server is reachable.
+    pub async fn connect_with_policy(url: &str, policy: ReconnectPolicy) -> Result<Self, RunError> {
+        let client = if policy.queue_while_disconnected {
+            connect_with_backoff(url, &policy, true).await
+        } else {
+            WsClient::connect(url).await
+        }
+        .map_err(remote_error)?;
+        Ok(Self {
+            url: url.to_string(),
+            client,
+            policy,
+        })
+    }
+
+    /// Runs one turn; follows the [`run_cli_turn`](super::run_cli_turn) streaming contract.
+    /// A dropped connection is re-established and the run resumed (see [`ReconnectPolicy`]);
+    /// events already received are not repeated. A connection lost between turns is
+    /// re-established before the turn is sent when the send failed, or with
+    /// `queue_while_disconnected`, in which case the turn waits for the server.
+    pub async fn run_turn(
+        &mut self,
+        opts: &RunOptions,
+        cmd: &RunCmd,
+        stream_out: StreamOut,
+    ) -> Result<RunOutput, RunError> {
+        let mut progress = RunProgress::default();
+        let mut request = Some(run_request(opts, cmd));
+        let mut drops = 0;
+        let end = loop {
+            // Whether the run request reached the socket; a failed send was never delivered.
+            let mut sent = true;
+            let result = match request.take() {
+                Some(r) => match self.client.run(r).await {
+                    Ok(run) => follow(run, &mut progress, opts, &stream_out).await,
+                    Err(e) => {
+                        sent = false;
+                        Err(e)
+                    }
+                },
+                None => {
+                    let run_id = progress.run_id.clone().unwrap_or_default();
+                    match self
+                        .client
+                        .resume_run(&run_id, progress.last_event_id)
+                        .await
+                    {
+                        Ok(run) => follow(run, &mut progress, opts, &stream_out).await,
+                        Err(e) => Err(e),
+                    }
+                }
+            };
+            let e = match result {
+                Ok(end) => break end,
+                Err(e) if is_disconnect(&e) => e,
+                Err(e) => return Err(remote_error(e)),
+            };
+            let queue = self.policy.queue_while_disconnected;
+            match progress.run_id.as_deref() {
+                Some(run_id) if drops < self.policy.attempts => {
+                    eprintln!(
+                        "remote: connection lost during run {} ({}); reconnecting",
+                        run_id, e
+                    );
+                }
+                // No run id: the connection dropped before the server started the run (usually
+                // between turns). Resend on a new connection when the request never left, or
+                // when queueing is on; otherwise resending could run the turn twice.
+                None if (!sent && drops < self.policy.attempts) || queue => {
+                    eprintln!(
+                        "remote: connection lost before the run started ({}); reconnecting to send it",
+                        e
+                    );
+                    request = Some(run_request(opts, cmd));
+                }
+                _ => return Err(remote_error(e)),
+            }
+            drops += 1;
+            let unbounded = queue && progress.run_id.is_none();
+            self.client = connect_with_backoff(&self.url, &self.policy, unbounded)
+                .await
+                .map_err(remote_error)?;
+        };
+        let reply_envelope = Some(Envelope {
+            session_id: end.session_id,
+            node_id: end.node_id,
+            event_id: end.event_id,
+            prev_event_id: None,
+        });
+        let transcript = end.transcript.unwrap_or_default();
+        Ok(if opts.output_json && stream_out.is_none() {
+            RunOutput::Json {
+                events: progress.events,
+                reply: end.reply,
+                reasoning_content: end.reasoning_content,
+                reply_envelope,
+                stop_reason: RunStopReason::EndTurn,
+                transcript,
+            }
+        } else {
+            RunOutput::Reply {
+                reply: end.reply,
+                reasoning_content: end.reasoning_content,
+                reply_envelope,
+                stop_reason: RunStopReason::EndTurn,
+                transcript,
+            }
+        })
+    }
+}
+
+/// Reads `run` to its end, delivering events not seen before and recording progress.
+async fn follow(
+    mut run: RunStream<'_>,
+    progress: &mut RunProgress,
+    opts: &RunOptions,
+    stream_out: &StreamOut,
+) -> Result<RunEndResponse, ClientError> {
+    loop {
+        let next = run.next_event().await;
+        if progress.run_id.is_none() {
+            progress.run_id = run.run_id().map(str::to_string);
+        }
+        let Some(event) = next? else {
+            break;
+        };
+        if let (Some(id), Some(last)) = (event.event_id, progress.last_event_id) {
+            if id <= last {
+                continue;
+            }
+        }
+        if event.event_id.is_some() {
+            progress.last_event_id = event.event_id;
+        }
+        if !opts.output_json {
+            continue;
+        }
+        let value = event.to_value()?;
+        match stream_out {
+            Some(out) => {
+                if let Ok(mut f) = out.lock() {
+                    f(value);
+                }
+            }
+            None => progress.events.push(value),
+        }
+    }
+    run.finish().await
+}
+
+/// Run request for `opts`: a named agent (`--agent`) wins over the command's agent type.
+/// Local-only options (agent file, MCP config path, dry run) are not sent.
+fn run_request(opts: &RunOptions, cmd: &RunCmd) -> RunRequest {
+    let agent = match (&opts.agent, cmd) {
+        (Some(name), RunCmd::React) => AgentIdentifier::Name(name.clone()),
+        (_, RunCmd::React) => AgentIdentifier::Type(AgentType::React),
+        (_, RunCmd::Dup) => AgentIdentifier::Type(AgentType::Dup),
+        (_, RunCmd::Tot) => AgentIdentifier::Type(AgentType::Tot),
+        (_, RunCmd::Got { .. }) => AgentIdentifier::Type(AgentType::Got),
+    };
+    RunRequest {
+        id: None,
+        message: opts.message.clone(),
+        agent,
+        thread_id: opts.thread_id.clone(),
+        workspace_id: None,
+        working_folder: opts
+            .working_folder
+            .as_ref()
+            .map(|p| p.display().to_string()),
+        got_adaptive: matches!(cmd, RunCmd::Got { got_adaptive: true }).then_some(true),
+        verbose: opts.verbose.then_some(true),
+        model: opts.model.clone(),
+        reply_format: opts.reply_format,
+        reply_schema: opts.reply_schema.clone(),
+        messages: opts.messages.clone(),
+        read_only: opts.read_only.then_some(true),
+        locale: opts.locale.clone(),
+        pin: None,
+        flags: opts.flags.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opts() -> RunOptions {
+        RunOptions {
+            message: loom::UserContent::Text("hi".to_string()),
+            working_folder: Some(std::path::PathBuf::from("/srv/project")),
+            session_id: None,
+            thread_id: Some("t1".to_string()),
+            agent: Some("dev".to_string()),
+            agent_file: None,
+            verbose: false,
+            got_adaptive: false,
+            display_max_len: 120,
+            output_json: true,
+            model: None,
+            provider: None,
+            base_url: None,
+            api_key: None,
+            provider_type: None,
+            mcp_config_path: None,
+            cancellation: None,
+            output_timestamp: false,
+            dry_run: false,
+            role_setting: None,
+            allowed_tools: None,
+            read_only: true,
+            reply_format: None,
+            reply_schema: None,
+            messages: None,
+            history_search: None,
+            locale: None,
+            flags: Default::default(),
+        }
+    }
+
+    #[test]
+    fn reconnect_delay_doubles_up_to_max() {
+        let policy = ReconnectPolicy::default();
+        assert_eq!(policy.delay(0), Duration::from_millis(500));
+        assert_eq!(policy.delay(1), Duration::from_secs(1));
+        assert_eq!(policy.delay(3), Duration::from_secs(4));
+        assert_eq!(policy.delay(10), policy.max_delay);
+        assert_eq!(policy.delay(u32::MAX), policy.max_delay);
+    }
+
+    #[tokio::test]
+    async fn connect_gives_up_after_attempts_without_queueing() {
+        let policy = ReconnectPolicy {
+            attempts: 1,
+            initial_delay: Duration::from_millis(1),
+            ..ReconnectPolicy::default()
+        };
+        let err = connect_with_backoff("ws://127.0.0.1:1", &policy, false)
+            .await
+            .err()
+            .expect("nothing listens on port 1");
+        assert!(is_disconnect(&err));
+    }
+
+    /// Accepts one connection on `listener` and answers each `run` request with a `run_end`
+    /// carrying `reply`, for `turns` turns; then closes the connection.
+    async fn serve_turns(listener: tokio::net::TcpListener, reply: &'static str, turns: usize) {
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+        for n in 0..turns {
+            let Some(Ok(Message::Text(text))) = ws.next().await else {
+                return;
+            };
+            let req: serde_json::Value = serde_json::from_str(&text).unwrap();
+            assert_eq!(req["type"], "run");
+            let end = serde_json::json!({
+                "type": "run_end",
+                "id": format!("run-{}-{}", reply, n),
+                "reply": reply,
+            });
+            ws.send(Message::Text(end.to_string())).await.unwrap();
+        }
+        let _ = ws.close(None).await;
+    }
+
+    fn reply_of(out: RunOutput) -> String {
+        match out {
+            RunOutput::Json { reply, .. } | RunOutput::Reply { reply, .. } => reply,
+        }
+    }
+
+    #[tokio::test]
+    async fn turn_after_server_restart_is_queued_and_sent() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let first = tokio::spawn(serve_turns(listener, "one", 1));
+        let policy = ReconnectPolicy {
+            attempts: 0,
+            initial_delay: Duration::from_millis(20),
+            max_delay: Duration::from_millis(50),
+            queue_while_disconnected: true,
+        };
+        let mut backend = RemoteBackend::connect_with_policy(&format!("ws://{}", addr), policy)
+            .await
+            .unwrap();
+
+        let out = backend
+            .run_turn(&opts(), &RunCmd::React, None)
+            .await
+            .unwrap();
+        assert_eq!(reply_of(out), "one");
+        // The server goes away between turns and comes back on the same address.
+        first.await.unwrap();
+        let second = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+            serve_turns(listener, "two", 1).await;
+        });
+
+        let out = tokio::time::timeout(
+            Duration::from_secs(10),
+            backend.run_turn(&opts(), &RunCmd::React, None),
+        )
+        .await
+        .expect("turn is sent once the server is back")
+        .unwrap();
+        assert_eq!(reply_of(out), "two");
+        second.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn turn_after_server_drop_fails_without_queueing() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let first = tokio::spawn(serve_turns(listener, "one", 1));
+        let policy = ReconnectPolicy {
+            attempts: 0,
+            ..ReconnectPolicy::default()
+        };
+        let mut backend = RemoteBackend::connect_with_policy(&format!("ws://{}", addr), policy)
+            .await
+            .unwrap();
+        backend
+            .run_turn(&opts(), &RunCmd::React, None)
+            .await
+            .unwrap();
+        first.await.unwrap();
+
+        let err = backend
+            .run_turn(&opts(), &RunCmd::React, None)
+            .await
+            .err()
+            .expect("server is gone");
+        assert!(matches!(err, RunError::Remote(_)));
+    }
+
+    #[test]
+    fn run_request_maps_agent_name_and_flags() {
+        let req = run_request(&opts(), &RunCmd::React);
+        assert_eq!(req.agent, AgentIdentifier::Name("dev".to_string()));
+        assert_eq!(req.thread_id.as_deref(), Some("t1"));
+        assert_eq!(req.working_folder.as_deref(), Some("/srv/project"));
+        assert_eq!(req.read_only, Some(true));
+        assert_eq!(req.verbose, None);
+
+        let req = run_request(&opts(), &RunCmd::Got { got_adaptive: true });
+        assert_eq!(req.agent, AgentIdentifier::Type(AgentType::Got));
+        assert_eq!(req.got_adaptive, Some(true));
+    }
+}
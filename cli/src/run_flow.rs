@@ -2,10 +2,11 @@
 
 use cli::RunOptions;
 
-use crate::args::{Args, Command};
+use crate::args::{Args, Command, WatchArgs};
 use crate::display_limits::{generate_session_id, max_message_len};
 use crate::output::{emit_run_output, make_stream_out, OutputConfig};
 use crate::repl::{run_one_turn, run_repl_loop};
+use crate::watch::run_watch_mode;
 use loom::UserContent;
 
 pub(crate) fn resolve_user_message(args: &Args) -> Option<String> {
@@ -34,6 +35,7 @@ pub(crate) fn build_run_options(args: &Args, message: String, got_adaptive: bool
         cancellation: None,
         thread_id: args.session_id.clone(),
         agent: args.agent.clone(),
+        agent_file: args.agent_file.as_deref().map(check_agent_file),
         verbose: args.verbose,
         got_adaptive,
         display_max_len: max_message_len(),
@@ -42,13 +44,42 @@ pub(crate) fn build_run_options(args: &Args, message: String, got_adaptive: bool
         mcp_config_path: args.mcp_config.clone(),
         output_timestamp: args.timestamp,
         dry_run: args.dry,
+        role_setting: None,
+        allowed_tools: None,
+        read_only: args.read_only,
+        reply_format: args.reply_format,
+        reply_schema: args.reply_schema.as_deref().map(read_reply_schema),
+        messages: None,
+        history_search: None,
+        locale: args.locale.clone(),
         provider: args.provider.clone(),
         base_url: None,
         api_key: None,
         provider_type: None,
+        flags: args.flags.iter().cloned().collect(),
     }
 }
 
+/// Loads the `--agent-file` once up front; exits with a message when it cannot be read or parsed.
+fn check_agent_file(path: &std::path::Path) -> std::path::PathBuf {
+    if let Err(e) = loom::load_agent_profile(path) {
+        eprintln!("loom: --agent-file: {}", e);
+        std::process::exit(1);
+    }
+    path.to_path_buf()
+}
+
+/// Reads the `--reply-schema` file; exits with a message when it is missing or not JSON.
+fn read_reply_schema(path: &std::path::Path) -> serde_json::Value {
+    let parsed = std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|s| serde_json::from_str(&s).map_err(|e| e.to_string()));
+    parsed.unwrap_or_else(|e| {
+        eprintln!("loom: --reply-schema {}: {}", path.display(), e);
+        std::process::exit(1);
+    })
+}
+
 fn print_session_status(session_id: Option<&str>, ended: bool, json: bool) {
     if json {
         return;
@@ -121,3 +152,17 @@ pub(crate) async fn run_interactive_mode(
     println!("Bye.");
     Ok(())
 }
+
+pub(crate) async fn run_watch(
+    opts: &mut RunOptions,
+    watch: &WatchArgs,
+    reply_len: usize,
+    output: &OutputConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    ensure_session_id(opts);
+    print_session_status(opts.thread_id.as_deref(), false, output.json);
+    let base_message = opts.message.as_text().into_owned();
+    run_watch_mode(opts, watch, &base_message, reply_len, output).await?;
+    print_session_status(opts.thread_id.as_deref(), true, output.json);
+    Ok(())
+}
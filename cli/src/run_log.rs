@@ -0,0 +1,255 @@
+//! Per-run log files: a tracing layer that writes the events of each run to its own file.
+//!
+//! A run is identified by a `run_id` field on a span (serve opens `serve_run` spans, `loom run`
+//! a `cli_run` span per turn) or on the event itself. Every event recorded inside such a span,
+//! in nested spans included, is also written to `<dir>/<run_id>.log` (with a date or time part
+//! when rotating), so a single run's log can be handed to support without grepping the
+//! interleaved server log. The file is closed when the span that named the run closes.
+
+use std::collections::HashMap;
+use std::fmt::{self, Write as _};
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use config::tracing_init::LogRotate;
+use tracing_appender::rolling::RollingFileAppender;
+use tracing_core::field::{Field, Visit};
+use tracing_core::span::{Attributes, Id, Record};
+use tracing_core::{Event, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Field naming the run a span or event belongs to.
+const RUN_ID_FIELD: &str = "run_id";
+
+/// Run of a span, stored in its extensions.
+struct RunId {
+    id: String,
+    /// Whether the span named the run itself (else it inherited it from a parent).
+    owner: bool,
+}
+
+/// Layer writing the events of each run to `<dir>/<run_id>.log`.
+pub struct RunLogLayer {
+    dir: PathBuf,
+    rotate: LogRotate,
+    writers: Mutex<HashMap<String, RollingFileAppender>>,
+}
+
+impl RunLogLayer {
+    /// Writes run logs under `dir`, rotating each file with `rotate`.
+    pub fn new(dir: impl Into<PathBuf>, rotate: LogRotate) -> Self {
+        Self {
+            dir: dir.into(),
+            rotate,
+            writers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn write(&self, run_id: &str, line: &str) {
+        let Ok(mut writers) = self.writers.lock() else {
+            return;
+        };
+        if !writers.contains_key(run_id) {
+            let appender = RollingFileAppender::builder()
+                .rotation(self.rotate.rotation())
+                .filename_prefix(file_stem(run_id))
+                .filename_suffix("log")
+                .build(&self.dir);
+            match appender {
+                Ok(appender) => {
+                    writers.insert(run_id.to_string(), appender);
+                }
+                Err(_) => return,
+            }
+        }
+        if let Some(writer) = writers.get_mut(run_id) {
+            let _ = writer.write_all(line.as_bytes());
+        }
+    }
+
+    fn close(&self, run_id: &str) {
+        if let Ok(mut writers) = self.writers.lock() {
+            if let Some(mut writer) = writers.remove(run_id) {
+                let _ = writer.flush();
+            }
+        }
+    }
+}
+
+/// `run_id` as a file name: characters other than ASCII letters, digits, `-`, `_` and `.`
+/// become `_`.
+fn file_stem(run_id: &str) -> String {
+    run_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Collects the `run_id` field of a span or event.
+#[derive(Default)]
+struct RunIdVisitor {
+    run_id: Option<String>,
+}
+
+impl Visit for RunIdVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == RUN_ID_FIELD {
+            self.run_id = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == RUN_ID_FIELD {
+            self.run_id = Some(format!("{:?}", value));
+        }
+    }
+}
+
+/// Formats an event's message and fields, and collects its `run_id`.
+#[derive(Default)]
+struct EventVisitor {
+    message: String,
+    fields: String,
+    run_id: Option<String>,
+}
+
+impl Visit for EventVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == RUN_ID_FIELD {
+            self.run_id = Some(value.to_string());
+        }
+        self.write_field(field, &value);
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == RUN_ID_FIELD {
+            self.run_id = Some(format!("{:?}", value));
+        }
+        self.write_field(field, value);
+    }
+}
+
+impl EventVisitor {
+    fn write_field(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+impl<S> Layer<S> for RunLogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut visitor = RunIdVisitor::default();
+        attrs.record(&mut visitor);
+        let run = match visitor.run_id {
+            Some(id) => Some(RunId { id, owner: true }),
+            None => span.parent().and_then(|parent| {
+                let extensions = parent.extensions();
+                extensions.get::<RunId>().map(|run| RunId {
+                    id: run.id.clone(),
+                    owner: false,
+                })
+            }),
+        };
+        if let Some(run) = run {
+            span.extensions_mut().insert(run);
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let mut visitor = RunIdVisitor::default();
+        values.record(&mut visitor);
+        if let (Some(run_id), Some(span)) = (visitor.run_id, ctx.span(id)) {
+            span.extensions_mut().replace(RunId {
+                id: run_id,
+                owner: true,
+            });
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut visitor = EventVisitor::default();
+        event.record(&mut visitor);
+        let run_id = visitor.run_id.take().or_else(|| {
+            ctx.event_scope(event)?.find_map(|span| {
+                let extensions = span.extensions();
+                extensions.get::<RunId>().map(|run| run.id.clone())
+            })
+        });
+        let Some(run_id) = run_id else {
+            return;
+        };
+        let metadata = event.metadata();
+        let line = format!(
+            "{} {:>5} {}: {}{}\n",
+            chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Micros, true),
+            metadata.level().as_str(),
+            metadata.target(),
+            visitor.message,
+            visitor.fields
+        );
+        self.write(&run_id, &line);
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let extensions = span.extensions();
+        if let Some(run) = extensions.get::<RunId>().filter(|run| run.owner) {
+            self.close(&run.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn writes_events_of_each_run_to_its_own_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let subscriber =
+            tracing_subscriber::registry().with(RunLogLayer::new(dir.path(), LogRotate::None));
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("outside any run");
+            let run_a = tracing::info_span!("serve_run", run_id = "run-a");
+            let run_b = tracing::info_span!("serve_run", run_id = %"run/b");
+            run_a.in_scope(|| {
+                tracing::info_span!("think").in_scope(|| tracing::info!(step = 1, "thinking"));
+            });
+            run_b.in_scope(|| tracing::warn!("tool failed"));
+            tracing::info!(run_id = "run-a", "after the span");
+        });
+
+        let a = std::fs::read_to_string(dir.path().join("run-a.log")).unwrap();
+        assert!(a.contains("INFO"));
+        assert!(a.contains("thinking step=1"));
+        assert!(a.contains("after the span"));
+        assert!(!a.contains("tool failed"));
+        let b = std::fs::read_to_string(dir.path().join("run_b.log")).unwrap();
+        assert!(b.contains(" WARN"));
+        assert!(b.contains("tool failed"));
+        assert!(!b.contains("outside any run"));
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+}
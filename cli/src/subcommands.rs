@@ -1,8 +1,20 @@
-//! Handlers for `tool`, `models`, `session`, and `mcp` CLI subcommands.
+//! Handlers for `tool`, `models`, `session`, `thread`, `checkpoint`, `config`, and `mcp` CLI
+//! subcommands.
+
+use std::path::PathBuf;
+use std::sync::Arc;
 
 use cli::{cli_list_models, cli_list_tools, cli_show_tool, ToolShowFormat};
+use loom::memory::{
+    CheckpointFilter, Checkpointer, JsonSerializer, RunnableConfig, SqliteSaver, ThreadArchive,
+};
+use loom::CheckpointSummary;
 
-use crate::args::{Args, McpArgs, McpCommand, ModelsArgs, ModelsCommand, ToolArgs, ToolCommand};
+use crate::args::{
+    Args, CheckpointArgs, CheckpointCommand, ConfigArgs, ConfigCommand, McpArgs, McpCommand,
+    ModelsArgs, ModelsCommand, ProtocolArgs, ProtocolCommand, ThreadArgs, ThreadCommand, ToolArgs,
+    ToolCommand,
+};
 use crate::mcp_manager::{AddMcpArgs, EditMcpArgs, McpManager, ServerDetail, ServerInfo};
 use crate::run_flow::build_run_options;
 use crate::session::{SessionArgs, SessionCommand, SessionManager};
@@ -72,6 +84,252 @@ pub(crate) async fn handle_session_command(
     Ok(())
 }
 
+/// Opens the checkpoint DB untyped, so threads written by any runner can be moved.
+fn open_thread_saver(
+    db: &Option<PathBuf>,
+) -> Result<SqliteSaver<serde_json::Value>, Box<dyn std::error::Error>> {
+    let path = db
+        .clone()
+        .unwrap_or_else(loom::memory::default_memory_db_path);
+    Ok(SqliteSaver::new(path, Arc::new(JsonSerializer))?)
+}
+
+pub(crate) async fn handle_thread_command(
+    ta: &ThreadArgs,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match &ta.command {
+        ThreadCommand::ExportArchive {
+            thread_id,
+            output,
+            db,
+        } => {
+            let saver = open_thread_saver(db)?;
+            let config = RunnableConfig {
+                thread_id: Some(thread_id.clone()),
+                ..Default::default()
+            };
+            let archive = saver.export_thread(&config).await?;
+            if archive.checkpoints.is_empty() {
+                return Err(format!("thread not found: {}", thread_id).into());
+            }
+            let body = serde_json::to_string_pretty(&archive)?;
+            match output {
+                Some(path) => {
+                    std::fs::write(path, body)?;
+                    if json {
+                        let result = serde_json::json!({
+                            "thread_id": thread_id,
+                            "checkpoints": archive.checkpoints.len(),
+                            "output": path,
+                        });
+                        println!("{}", serde_json::to_string_pretty(&result)?);
+                    } else {
+                        println!(
+                            "Exported thread {} ({} checkpoints) to {}",
+                            thread_id,
+                            archive.checkpoints.len(),
+                            path.display()
+                        );
+                    }
+                }
+                None => println!("{}", body),
+            }
+        }
+        ThreadCommand::ImportArchive {
+            file,
+            thread_id,
+            db,
+        } => {
+            let body = std::fs::read_to_string(file)?;
+            let archive: ThreadArchive<serde_json::Value> = serde_json::from_str(&body)?;
+            let target = thread_id
+                .clone()
+                .unwrap_or_else(|| archive.thread_id.clone());
+            let saver = open_thread_saver(db)?;
+            let config = RunnableConfig {
+                thread_id: Some(target.clone()),
+                checkpoint_ns: archive.checkpoint_ns.clone(),
+                ..Default::default()
+            };
+            let count = saver.import_thread(&config, &archive).await?;
+            if json {
+                let result = serde_json::json!({
+                    "thread_id": target,
+                    "imported_checkpoints": count,
+                });
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            } else {
+                println!("Imported thread {} ({} checkpoints)", target, count);
+            }
+        }
+    }
+    Ok(())
+}
+
+pub(crate) async fn handle_checkpoint_command(
+    ca: &CheckpointArgs,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match &ca.command {
+        CheckpointCommand::List {
+            thread_id,
+            node,
+            min_step,
+            max_step,
+            limit,
+            db,
+        } => {
+            let saver = open_thread_saver(db)?;
+            let config = RunnableConfig {
+                thread_id: Some(thread_id.clone()),
+                ..Default::default()
+            };
+            let filter = CheckpointFilter {
+                node: node.clone(),
+                min_step: *min_step,
+                max_step: *max_step,
+                limit: *limit,
+                ..Default::default()
+            };
+            let items = saver.list_filtered(&config, &filter).await?;
+            let rows: Vec<CheckpointSummary> = items.iter().map(CheckpointSummary::from).collect();
+            if json {
+                println!("{}", serde_json::to_string_pretty(&rows)?);
+            } else {
+                print_checkpoint_list(&rows);
+            }
+        }
+        CheckpointCommand::Show {
+            thread_id,
+            checkpoint_id,
+            db,
+        } => {
+            let path = db
+                .clone()
+                .unwrap_or_else(loom::memory::default_memory_db_path);
+            let checkpoint = loom::runner_common::load_checkpoint_json(
+                path,
+                thread_id,
+                checkpoint_id.as_deref(),
+            )
+            .await?
+            .ok_or_else(|| match checkpoint_id {
+                Some(id) => format!("checkpoint not found: {} in thread {}", id, thread_id),
+                None => format!("thread not found: {}", thread_id),
+            })?;
+            println!("{}", serde_json::to_string_pretty(&checkpoint)?);
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn handle_protocol_command(pa: &ProtocolArgs) -> Result<(), Box<dyn std::error::Error>> {
+    match &pa.command {
+        ProtocolCommand::Schema { output } => {
+            let schema = serde_json::to_string_pretty(&loom::protocol::protocol_schema())?;
+            match output {
+                Some(path) => std::fs::write(path, schema + "\n")?,
+                None => println!("{}", schema),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Runs `config validate`; returns whether the config is valid.
+pub(crate) fn handle_config_command(
+    ca: &ConfigArgs,
+    json: bool,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    match &ca.command {
+        ConfigCommand::Validate => {
+            let path = config::config_file_paths("loom", None).xdg;
+            let issues = config::validate_config("loom")?;
+            let load_error = if issues.is_empty() {
+                config::load_full_config("loom")
+                    .err()
+                    .map(|e| e.to_string())
+            } else {
+                None
+            };
+            let valid = issues.is_empty() && load_error.is_none();
+            if json {
+                let issues: Vec<_> = issues
+                    .iter()
+                    .map(|i| {
+                        serde_json::json!({
+                            "file": i.file,
+                            "line": i.line,
+                            "column": i.column,
+                            "message": i.message,
+                        })
+                    })
+                    .collect();
+                let result = serde_json::json!({
+                    "path": path,
+                    "valid": valid,
+                    "issues": issues,
+                    "error": load_error,
+                });
+                println!("{}", serde_json::to_string_pretty(&result)?);
+                return Ok(valid);
+            }
+            match &path {
+                None => println!("no config.toml in {}", config::home::loom_home().display()),
+                Some(p) if valid => println!("{}: ok", p.display()),
+                Some(_) => {
+                    for issue in &issues {
+                        println!("{}", issue);
+                    }
+                    if let Some(e) = &load_error {
+                        println!("{}", e);
+                    }
+                }
+            }
+            Ok(valid)
+        }
+    }
+}
+
+fn print_checkpoint_list(rows: &[CheckpointSummary]) {
+    if rows.is_empty() {
+        println!("No checkpoints found.");
+        return;
+    }
+    println!(
+        "{:<38} {:>6} {:<8} {:<12} {:<20} {:>10}  {}",
+        "CHECKPOINT ID", "STEP", "SOURCE", "NODE", "CREATED", "SIZE", "PARENT"
+    );
+    println!("{}", "-".repeat(120));
+    for row in rows {
+        let created = row
+            .created_at_ms
+            .and_then(chrono::DateTime::from_timestamp_millis)
+            .map(|t| {
+                t.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_else(|| "N/A".to_string());
+        let size = row
+            .size_bytes
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{:<38} {:>6} {:<8} {:<12} {:<20} {:>10}  {}",
+            row.checkpoint_id,
+            row.step,
+            row.source,
+            row.node.as_deref().unwrap_or("-"),
+            created,
+            size,
+            row.parent_id.as_deref().unwrap_or("-")
+        );
+    }
+    println!("\nTotal checkpoints: {}", rows.len());
+}
+
 pub(crate) fn handle_mcp_command(
     mcp_args: &McpArgs,
     json: bool,
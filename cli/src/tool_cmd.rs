@@ -218,6 +218,7 @@ mod tests {
             description: Some("Read file content".to_string()),
             input_schema: serde_json::json!({"type":"object"}),
             output_hint: None,
+            catalog: Default::default(),
         }];
         format_tools_list(&specs, true).unwrap();
     }
@@ -262,6 +263,7 @@ mod tests {
             cancellation: None,
             thread_id: None,
             agent: None,
+            agent_file: None,
             verbose: false,
             got_adaptive: false,
             display_max_len: 100,
@@ -274,6 +276,15 @@ mod tests {
             mcp_config_path: None,
             output_timestamp: false,
             dry_run: false,
+            role_setting: None,
+            allowed_tools: None,
+            read_only: false,
+            reply_format: None,
+            reply_schema: None,
+            messages: None,
+            history_search: None,
+            locale: None,
+            flags: Default::default(),
         }
     }
 
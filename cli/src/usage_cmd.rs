@@ -0,0 +1,138 @@
+//! `loom usage`: token usage and estimated cost of serve runs, grouped by model and agent type.
+//!
+//! Reads the run usage `loom serve` records in its workspace database (`WORKSPACE_DB`, default
+//! `./workspace.db`) and prices it from models.dev, like the `usage_report` request.
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+use loom::model_spec::{price_usage_rows, ModelsDevResolver};
+use loom::UsageReportRow;
+
+use crate::args::UsageArgs;
+
+/// Parses `--since` / `--until`: a date (`YYYY-MM-DD`, local midnight) or an RFC 3339
+/// timestamp. Returns milliseconds since Unix epoch.
+fn parse_time_ms(s: &str) -> Result<i64, String> {
+    let s = s.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        let midnight = date.and_hms_opt(0, 0, 0).expect("midnight is valid");
+        return Local
+            .from_local_datetime(&midnight)
+            .earliest()
+            .map(|t| t.timestamp_millis())
+            .ok_or_else(|| format!("invalid local date: {}", s));
+    }
+    DateTime::parse_from_rfc3339(s)
+        .map(|t| t.timestamp_millis())
+        .map_err(|_| format!("expected YYYY-MM-DD or an RFC 3339 time, got {:?}", s))
+}
+
+fn workspace_db_path(db: &Option<PathBuf>) -> PathBuf {
+    db.clone().unwrap_or_else(|| {
+        std::env::var("WORKSPACE_DB")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("workspace.db"))
+    })
+}
+
+fn format_cost(cost: Option<f64>) -> String {
+    cost.map_or_else(|| "-".to_string(), |c| format!("${:.4}", c))
+}
+
+fn print_table(rows: &[UsageReportRow]) {
+    println!(
+        "{:<32} {:<6} {:>6} {:>12} {:>12} {:>12} {:>10}",
+        "MODEL", "AGENT", "RUNS", "PROMPT", "COMPLETION", "TOTAL", "COST"
+    );
+    for r in rows {
+        println!(
+            "{:<32} {:<6} {:>6} {:>12} {:>12} {:>12} {:>10}",
+            r.model,
+            r.agent,
+            r.runs,
+            r.prompt_tokens,
+            r.completion_tokens,
+            r.total_tokens,
+            format_cost(r.cost_usd)
+        );
+    }
+    let total_tokens: u64 = rows.iter().map(|r| r.total_tokens).sum();
+    let priced: Vec<f64> = rows.iter().filter_map(|r| r.cost_usd).collect();
+    let unpriced = rows.len() - priced.len();
+    println!(
+        "total: {} tokens, {}{}",
+        total_tokens,
+        format_cost(Some(priced.iter().sum())),
+        if unpriced > 0 {
+            format!(" ({} row(s) without pricing)", unpriced)
+        } else {
+            String::new()
+        }
+    );
+}
+
+pub(crate) async fn handle_usage_command(
+    ua: &UsageArgs,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let since = ua.since.as_deref().map(parse_time_ms).transpose()?;
+    let until = ua.until.as_deref().map(parse_time_ms).transpose()?;
+    let path = workspace_db_path(&ua.db);
+    if !path.exists() {
+        return Err(format!("workspace database not found: {}", path.display()).into());
+    }
+    let store = loom_workspace::Store::new(&path)?;
+    let mut rows: Vec<UsageReportRow> = store
+        .usage_report(ua.workspace.as_deref(), since, until)
+        .await?
+        .into_iter()
+        .map(|s| UsageReportRow {
+            model: s.model,
+            agent: s.agent,
+            runs: s.runs,
+            prompt_tokens: s.prompt_tokens,
+            completion_tokens: s.completion_tokens,
+            total_tokens: s.total_tokens,
+            cost_usd: None,
+        })
+        .collect();
+    price_usage_rows(&ModelsDevResolver::new(), &mut rows).await;
+
+    if json {
+        let result = serde_json::json!({
+            "workspace_id": ua.workspace,
+            "since": since,
+            "until": until,
+            "rows": rows,
+        });
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    } else if rows.is_empty() {
+        println!("No recorded runs.");
+    } else {
+        print_table(&rows);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rfc3339_and_dates() {
+        assert_eq!(
+            parse_time_ms("2024-01-01T00:00:00Z").unwrap(),
+            1_704_067_200_000
+        );
+        let date = parse_time_ms("2024-01-01").unwrap();
+        assert!((date - 1_704_067_200_000).abs() <= 14 * 3600 * 1000);
+        assert!(parse_time_ms("last week").is_err());
+    }
+
+    #[test]
+    fn unpriced_cost_is_a_dash() {
+        assert_eq!(format_cost(None), "-");
+        assert_eq!(format_cost(Some(1.5)), "$1.5000");
+    }
+}
@@ -0,0 +1,348 @@
+//! `loom watch`: re-run the agent whenever watched files change.
+//!
+//! Files matching `--glob` (relative to the working folder) are polled; once changes settle for
+//! `--debounce-ms`, the agent runs with the base message plus a diff of the changed files. All
+//! runs share one session so the agent keeps context between rounds. Ctrl-C stops watching.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use cli::RunOptions;
+use loom::UserContent;
+
+use crate::args::{Command, WatchArgs};
+use crate::output::{emit_run_output, make_stream_out, OutputConfig};
+use crate::repl::run_one_turn;
+
+/// Files larger than this are reported as changed without a line diff.
+const MAX_DIFF_FILE_BYTES: u64 = 256 * 1024;
+/// Max lines per side fed to the line diff; larger files are shown in full instead.
+const MAX_DIFF_LINES: usize = 2000;
+/// Upper bound on the diff text injected into the message.
+const MAX_DIFF_CHARS: usize = 20_000;
+
+/// Content of one watched file at scan time. `None` for large or non-UTF-8 files.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct FileState {
+    len: u64,
+    modified: Option<std::time::SystemTime>,
+    text: Option<String>,
+}
+
+type Snapshot = BTreeMap<PathBuf, FileState>;
+
+#[derive(Debug, PartialEq, Eq)]
+enum FileChange {
+    Added(PathBuf),
+    Modified(PathBuf),
+    Removed(PathBuf),
+}
+
+/// Collects the files matching any of `globs` under `root`.
+fn scan(root: &Path, globs: &[String]) -> Snapshot {
+    let mut snapshot = Snapshot::new();
+    for pattern in globs {
+        let full = root.join(pattern);
+        let Ok(paths) = glob::glob(&full.to_string_lossy()) else {
+            tracing::warn!(pattern = %pattern, "invalid watch glob");
+            continue;
+        };
+        for path in paths.flatten() {
+            let Ok(meta) = std::fs::metadata(&path) else {
+                continue;
+            };
+            if !meta.is_file() {
+                continue;
+            }
+            let text = if meta.len() <= MAX_DIFF_FILE_BYTES {
+                std::fs::read_to_string(&path).ok()
+            } else {
+                None
+            };
+            let rel = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+            snapshot.insert(
+                rel,
+                FileState {
+                    len: meta.len(),
+                    modified: meta.modified().ok(),
+                    text,
+                },
+            );
+        }
+    }
+    snapshot
+}
+
+/// Compares text when both sides have it (so a bare touch is not a change); otherwise
+/// falls back to size and mtime.
+fn content_changed(old: &FileState, new: &FileState) -> bool {
+    match (&old.text, &new.text) {
+        (Some(a), Some(b)) => a != b,
+        _ => old.len != new.len || old.modified != new.modified,
+    }
+}
+
+fn changed_files(old: &Snapshot, new: &Snapshot) -> Vec<FileChange> {
+    let mut changes = Vec::new();
+    for (path, state) in new {
+        match old.get(path) {
+            None => changes.push(FileChange::Added(path.clone())),
+            Some(prev) if content_changed(prev, state) => {
+                changes.push(FileChange::Modified(path.clone()))
+            }
+            Some(_) => {}
+        }
+    }
+    for path in old.keys() {
+        if !new.contains_key(path) {
+            changes.push(FileChange::Removed(path.clone()));
+        }
+    }
+    changes
+}
+
+/// Minimal line diff (LCS) rendered with `-`/`+`/` ` prefixes; unchanged runs longer than
+/// 3 lines are collapsed.
+fn line_diff(old: &str, new: &str) -> String {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    if a.len() > MAX_DIFF_LINES || b.len() > MAX_DIFF_LINES {
+        return b.iter().map(|l| format!("+{}\n", l)).collect();
+    }
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let mut lines: Vec<(char, &str)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            lines.push((' ', a[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            lines.push(('-', a[i]));
+            i += 1;
+        } else {
+            lines.push(('+', b[j]));
+            j += 1;
+        }
+    }
+    lines.extend(a[i..].iter().map(|l| ('-', *l)));
+    lines.extend(b[j..].iter().map(|l| ('+', *l)));
+
+    let mut out = String::new();
+    let mut k = 0;
+    while k < lines.len() {
+        if lines[k].0 != ' ' {
+            out.push_str(&format!("{}{}\n", lines[k].0, lines[k].1));
+            k += 1;
+            continue;
+        }
+        let start = k;
+        while k < lines.len() && lines[k].0 == ' ' {
+            k += 1;
+        }
+        let run = &lines[start..k];
+        if run.len() <= 3 {
+            for (_, l) in run {
+                out.push_str(&format!(" {}\n", l));
+            }
+        } else {
+            if start > 0 {
+                out.push_str(&format!(" {}\n", run[0].1));
+            }
+            out.push_str(&format!("@@ {} unchanged lines @@\n", run.len()));
+            if k < lines.len() {
+                out.push_str(&format!(" {}\n", run[run.len() - 1].1));
+            }
+        }
+    }
+    out
+}
+
+fn render_changes(old: &Snapshot, new: &Snapshot, changes: &[FileChange]) -> String {
+    let mut out = String::new();
+    for change in changes {
+        match change {
+            FileChange::Added(path) => {
+                out.push_str(&format!("--- /dev/null\n+++ {}\n", path.display()));
+                match new.get(path).and_then(|s| s.text.as_deref()) {
+                    Some(text) => out.push_str(&line_diff("", text)),
+                    None => out.push_str("(binary or large file)\n"),
+                }
+            }
+            FileChange::Modified(path) => {
+                out.push_str(&format!("--- {0}\n+++ {0}\n", path.display()));
+                let old_text = old.get(path).and_then(|s| s.text.as_deref());
+                let new_text = new.get(path).and_then(|s| s.text.as_deref());
+                match (old_text, new_text) {
+                    (Some(o), Some(n)) => out.push_str(&line_diff(o, n)),
+                    _ => out.push_str("(binary or large file)\n"),
+                }
+            }
+            FileChange::Removed(path) => {
+                out.push_str(&format!(
+                    "--- {}\n+++ /dev/null\n(file removed)\n",
+                    path.display()
+                ));
+            }
+        }
+    }
+    if out.chars().count() > MAX_DIFF_CHARS {
+        let mut truncated: String = out.chars().take(MAX_DIFF_CHARS).collect();
+        truncated.push_str("\n... (diff truncated)\n");
+        return truncated;
+    }
+    out
+}
+
+fn build_watch_message(base: &str, diff: &str) -> String {
+    format!(
+        "{}\n\nChanged files:\n```diff\n{}```",
+        base.trim_end(),
+        diff
+    )
+}
+
+/// Runs the watch loop until Ctrl-C. `opts.thread_id` must already be set so every round shares
+/// one session.
+pub(crate) async fn run_watch_mode(
+    opts: &mut RunOptions,
+    watch: &WatchArgs,
+    base_message: &str,
+    reply_len: usize,
+    output: &OutputConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let root = match &opts.working_folder {
+        Some(dir) => dir.clone(),
+        None => std::env::current_dir()?,
+    };
+    let debounce = Duration::from_millis(watch.debounce_ms);
+    let poll = debounce
+        .min(Duration::from_millis(250))
+        .max(Duration::from_millis(50));
+    let stream_out = make_stream_out(output);
+
+    let mut baseline = scan(&root, &watch.globs);
+    let mut latest = baseline.clone();
+    let mut last_change: Option<Instant> = None;
+    if !output.json {
+        eprintln!(
+            "Watching {} file(s) matching {:?} in {} (Ctrl-C to stop)",
+            baseline.len(),
+            watch.globs,
+            root.display()
+        );
+    }
+
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            _ = tokio::time::sleep(poll) => {}
+        }
+
+        let current = scan(&root, &watch.globs);
+        if current != latest {
+            latest = current;
+            last_change = Some(Instant::now());
+            continue;
+        }
+        let Some(changed_at) = last_change else {
+            continue;
+        };
+        if changed_at.elapsed() < debounce {
+            continue;
+        }
+        last_change = None;
+
+        let changes = changed_files(&baseline, &latest);
+        if changes.is_empty() {
+            continue;
+        }
+        let diff = render_changes(&baseline, &latest, &changes);
+        baseline = latest.clone();
+        if !output.json {
+            eprintln!("{} file(s) changed, running agent...", changes.len());
+        }
+
+        opts.message = UserContent::Text(build_watch_message(base_message, &diff));
+        match run_one_turn(opts, &Command::React, stream_out.clone()).await {
+            Ok(output_value) => emit_run_output(
+                output_value,
+                output,
+                opts.thread_id.as_deref(),
+                reply_len,
+                opts.output_timestamp,
+            )?,
+            Err(e) => eprintln!("error: {}", e),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_diff_marks_added_and_removed_lines() {
+        let diff = line_diff("a\nb\nc\n", "a\nB\nc\nd\n");
+        assert_eq!(diff, " a\n-b\n+B\n c\n+d\n");
+    }
+
+    #[test]
+    fn line_diff_collapses_long_unchanged_runs() {
+        let old = "1\n2\n3\n4\n5\n6\nx\n";
+        let new = "1\n2\n3\n4\n5\n6\ny\n";
+        let diff = line_diff(old, new);
+        assert!(diff.starts_with("@@ 6 unchanged lines @@\n 6\n"));
+        assert!(diff.ends_with("-x\n+y\n"));
+    }
+
+    #[test]
+    fn scan_and_changed_files_detect_add_modify_remove() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.rs"), "fn a() {}\n").unwrap();
+        std::fs::write(dir.path().join("b.rs"), "fn b() {}\n").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "ignored\n").unwrap();
+        let globs = vec!["*.rs".to_string()];
+        let before = scan(dir.path(), &globs);
+        assert_eq!(before.len(), 2);
+
+        std::fs::write(dir.path().join("a.rs"), "fn a() { todo!() }\n").unwrap();
+        std::fs::remove_file(dir.path().join("b.rs")).unwrap();
+        std::fs::write(dir.path().join("c.rs"), "fn c() {}\n").unwrap();
+        let after = scan(dir.path(), &globs);
+
+        let changes = changed_files(&before, &after);
+        assert_eq!(
+            changes,
+            vec![
+                FileChange::Modified(PathBuf::from("a.rs")),
+                FileChange::Added(PathBuf::from("c.rs")),
+                FileChange::Removed(PathBuf::from("b.rs")),
+            ]
+        );
+        let rendered = render_changes(&before, &after, &changes);
+        assert!(rendered.contains("+fn a() { todo!() }"));
+        assert!(rendered.contains("+++ c.rs"));
+        assert!(rendered.contains("(file removed)"));
+    }
+
+    #[test]
+    fn build_watch_message_appends_diff_block() {
+        let msg = build_watch_message("review the changes\n", "+x\n");
+        assert_eq!(
+            msg,
+            "review the changes\n\nChanged files:\n```diff\n+x\n```"
+        );
+    }
+}
@@ -1,10 +1,15 @@
 //! Load configuration from `~/.loom/config.toml` and project `.env`, then apply to the process
 //! environment with priority: **existing env > .env > providers > config.toml `[env]`**.
+//!
+//! Secrets (API keys, tokens) are wrapped in [`Secret`] and resolved via [`read_secret`]
+//! (env, `NAME_FILE`, or OS keychain).
 
 mod dotenv;
 pub mod home;
 mod lsp_config;
 mod mcp_config;
+mod schema;
+mod secret;
 mod xdg_toml;
 
 #[cfg(feature = "tracing-init")]
@@ -19,16 +24,26 @@ pub use mcp_config::{
     save_mcp_config, upsert_mcp_server, McpConfigError, McpConfigFile, McpServerDef,
     McpServerEntry,
 };
+pub use schema::{validate_config, ConfigIssue, SectionKey, ValueKind, KNOWN_ENV, SECTION_KEYS};
+pub use secret::{
+    read_keychain_secret, read_secret, read_secret_file, redact_text, Secret, KEYCHAIN_SERVICE,
+    REDACTED,
+};
 pub use xdg_toml::{load_full_config, FullConfig, ProviderDef};
 
 use model_spec_core::extract_provider_api_from_models_dev_json;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use thiserror::Error;
 
 const DEFAULT_MODELS_DEV_URL: &str = "https://models.dev/api.json";
 const MODELS_DEV_URL_ENV: &str = "MODELS_DEV_URL";
 const MODELS_DEV_INLINE_JSON_ENV: &str = "LOOM_MODELS_DEV_API_JSON";
 
+/// Keys this process set from `.env` / providers / `config.toml` (not from its own environment).
+/// [`reload_and_apply_with_report`] clears these before re-applying so edited values take effect.
+static APPLIED_KEYS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
 /// Masks a key for logging: keeps first `prefix_len` and last `suffix_len` chars, middle becomes `***`.
 pub fn mask_key(key: &str, prefix_len: usize, suffix_len: usize) -> String {
     let n = key.len();
@@ -177,6 +192,13 @@ pub enum LoadError {
     XdgRead(std::io::Error),
     #[error("parse config toml: {0}")]
     XdgParse(#[from] toml::de::Error),
+    #[error("config include: {0}")]
+    XdgInclude(String),
+    #[error("expand config value: {0}")]
+    XdgExpand(String),
+    /// `config.toml` parsed but does not match the schema (see [`validate_config`]).
+    #[error("invalid config:\n{}", schema::format_issues(.0))]
+    XdgSchema(Vec<ConfigIssue>),
     #[error("read .env: {0}")]
     DotenvRead(std::io::Error),
 }
@@ -223,18 +245,31 @@ pub fn load_and_apply(app_name: &str, override_dir: Option<&Path>) -> Result<(),
     Ok(())
 }
 
+/// Loads `config.toml` and checks it against the schema; schema issues are an error.
+fn load_checked_config(app_name: &str) -> Result<xdg_toml::FullConfig, LoadError> {
+    let full_config = xdg_toml::load_full_config(app_name)?;
+    let issues = schema::validate_config(app_name)?;
+    if !issues.is_empty() {
+        return Err(LoadError::XdgSchema(issues));
+    }
+    Ok(full_config)
+}
+
 /// Like [`load_and_apply`] but returns a report of which keys were applied and from where (keys plain, values masked in report).
 ///
 /// Priority (highest to lowest):
 /// 1. Existing process environment  
 /// 2. Project `.env`
 /// 3. Active `[[providers]]` entry (selected via `[default].provider`)
-/// 4. `[env]` table in `config.toml`
+/// 4. `[env]` table and structured sections (`[history]`, `[state]`) in `config.toml`
+///
+/// A `config.toml` that does not match the schema (unknown keys, wrong types, conflicting
+/// settings) fails with [`LoadError::XdgSchema`] before anything is applied.
 pub fn load_and_apply_with_report(
     app_name: &str,
     override_dir: Option<&Path>,
 ) -> Result<ConfigLoadReport, LoadError> {
-    let full_config = xdg_toml::load_full_config(app_name)?;
+    let full_config = load_checked_config(app_name)?;
     let xdg_map = full_config.env;
     let dotenv_map = dotenv::load_env_map(override_dir).map_err(LoadError::DotenvRead)?;
 
@@ -284,6 +319,10 @@ pub fn load_and_apply_with_report(
         if source != ConfigSource::ExistingEnv {
             if let Some(ref v) = value {
                 std::env::set_var(&key, v);
+                let mut applied = APPLIED_KEYS.lock().unwrap_or_else(|e| e.into_inner());
+                if !applied.contains(&key) {
+                    applied.push(key.clone());
+                }
             }
         }
 
@@ -314,6 +353,25 @@ pub fn load_and_apply_with_report(
     })
 }
 
+/// Re-reads `.env` and `config.toml` for a long-running process (e.g. on SIGHUP).
+///
+/// Keys previously set by [`load_and_apply`] / [`load_and_apply_with_report`] are removed first, so
+/// edited values replace old ones and keys deleted from the files disappear; variables from the
+/// process's own environment keep their priority. Both files are parsed before anything is
+/// removed, so an unreadable or invalid file returns an error and leaves the environment unchanged.
+pub fn reload_and_apply_with_report(
+    app_name: &str,
+    override_dir: Option<&Path>,
+) -> Result<ConfigLoadReport, LoadError> {
+    load_checked_config(app_name)?;
+    dotenv::load_env_map(override_dir).map_err(LoadError::DotenvRead)?;
+    let previous = std::mem::take(&mut *APPLIED_KEYS.lock().unwrap_or_else(|e| e.into_inner()));
+    for key in &previous {
+        std::env::remove_var(key);
+    }
+    load_and_apply_with_report(app_name, override_dir)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -455,6 +513,37 @@ mod tests {
         assert_eq!(val, "from_config");
     }
 
+    #[test]
+    fn reload_replaces_edited_and_removes_deleted_keys() {
+        let _g = CONFIG_ENV_LOCK.lock().unwrap();
+        let loom_home = tempfile::tempdir().unwrap();
+        let config_path = loom_home.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            "[env]\nCONFIG_TEST_RELOAD_A = \"one\"\nCONFIG_TEST_RELOAD_B = \"kept\"\n",
+        )
+        .unwrap();
+        let empty_dir = tempfile::tempdir().unwrap();
+
+        let prev_loom = env::var("LOOM_HOME").ok();
+        env::set_var("LOOM_HOME", loom_home.path());
+        env::remove_var("CONFIG_TEST_RELOAD_A");
+        env::remove_var("CONFIG_TEST_RELOAD_B");
+
+        let _ = load_and_apply("loom", Some(empty_dir.path()));
+        std::fs::write(&config_path, "[env]\nCONFIG_TEST_RELOAD_A = \"two\"\n").unwrap();
+        let reloaded = reload_and_apply_with_report("loom", Some(empty_dir.path()));
+        let a = env::var("CONFIG_TEST_RELOAD_A").ok();
+        let b = env::var("CONFIG_TEST_RELOAD_B").ok();
+        env::remove_var("CONFIG_TEST_RELOAD_A");
+        env::remove_var("CONFIG_TEST_RELOAD_B");
+        restore_var("LOOM_HOME", prev_loom);
+
+        assert!(reloaded.is_ok());
+        assert_eq!(a.as_deref(), Some("two"));
+        assert_eq!(b, None);
+    }
+
     #[test]
     fn dotenv_only_when_no_xdg() {
         let _g = CONFIG_ENV_LOCK.lock().unwrap();
@@ -492,6 +581,35 @@ mod tests {
         assert!(matches!(result, Err(LoadError::XdgParse(_))));
     }
 
+    #[test]
+    fn schema_errors_fail_load_before_applying() {
+        let _g = CONFIG_ENV_LOCK.lock().unwrap();
+        let loom_home = tempfile::tempdir().unwrap();
+        std::fs::write(
+            loom_home.path().join("config.toml"),
+            "[env]\nCONFIG_TEST_SCHEMA = \"set\"\nLOOM_HISTORY_MAX_TURN = \"5\"\n",
+        )
+        .unwrap();
+
+        let prev_loom = env::var("LOOM_HOME").ok();
+        env::set_var("LOOM_HOME", loom_home.path());
+        env::remove_var("CONFIG_TEST_SCHEMA");
+
+        let result = load_and_apply("loom", None::<&std::path::Path>);
+        let applied = env::var("CONFIG_TEST_SCHEMA").ok();
+        restore_var("LOOM_HOME", prev_loom);
+
+        let issues = match result {
+            Err(LoadError::XdgSchema(issues)) => issues,
+            other => panic!("expected schema error, got {:?}", other),
+        };
+        assert_eq!(issues[0].line, 3);
+        assert!(issues[0]
+            .message
+            .contains("did you mean `LOOM_HISTORY_MAX_TURNS`"));
+        assert!(applied.is_none());
+    }
+
     #[test]
     fn config_file_paths_returns_both_paths() {
         let _g = CONFIG_ENV_LOCK.lock().unwrap();
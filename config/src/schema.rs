@@ -0,0 +1,829 @@
+//! Schema of `config.toml`: the sections and keys loom reads, with the type of each value.
+//!
+//! [`validate_config`] checks the file and its includes as written (before layering and
+//! `${VAR}` expansion) and reports each problem with its file, line and column:
+//! - unknown top-level sections, `[[providers]]` fields and `[os.<name>]` platforms;
+//! - `[env]` keys in loom's `LOOM_` namespace that loom does not read (with the closest known
+//!   key), and misspellings of other known keys. Other `[env]` keys are passed through, since
+//!   tools and MCP servers read their own variables;
+//! - values of known keys that do not parse as their type (flag, count, number, one of a set);
+//! - conflicting settings: a structured key and its `[env]` variable set to different values,
+//!   or two `[[providers]]` with the same name in one file.
+//!
+//! Structured sections are typed spellings of `[env]` variables:
+//!
+//! ```toml
+//! [history]
+//! max_turns = 20        # LOOM_HISTORY_MAX_TURNS
+//! max_tokens = 30000    # LOOM_HISTORY_MAX_TOKENS
+//! summary = true        # LOOM_HISTORY_SUMMARY
+//!
+//! [state]
+//! max_messages = 500    # LOOM_STATE_MAX_MESSAGES
+//! max_bytes = 4000000   # LOOM_STATE_MAX_BYTES
+//! limit_action = "fail" # LOOM_STATE_LIMIT_ACTION
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use toml::{Spanned, Table, Value};
+
+use crate::LoadError;
+
+/// Type of a config value. `[env]` values are strings parsed as the kind; structured sections
+/// use the matching TOML type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValueKind {
+    /// `1` / `true` / `yes` to enable, `0` / `false` / `no` to disable.
+    Flag,
+    /// Non-negative integer.
+    Count,
+    /// Decimal number.
+    Number,
+    /// Free text.
+    Text,
+    /// One of the listed words (case-insensitive).
+    OneOf(&'static [&'static str]),
+}
+
+impl ValueKind {
+    fn accepts_str(self, s: &str) -> bool {
+        let s = s.trim();
+        match self {
+            Self::Flag => matches!(
+                s.to_lowercase().as_str(),
+                "1" | "true" | "yes" | "0" | "false" | "no"
+            ),
+            Self::Count => s.parse::<u64>().is_ok(),
+            Self::Number => s.parse::<f64>().is_ok_and(f64::is_finite),
+            Self::Text => true,
+            Self::OneOf(words) => words.iter().any(|w| w.eq_ignore_ascii_case(s)),
+        }
+    }
+
+    fn accepts_value(self, value: &Value) -> bool {
+        match (self, value) {
+            (_, Value::String(s)) if s.contains("${") => true,
+            (Self::Flag, Value::Boolean(_)) => true,
+            (Self::Count, Value::Integer(n)) => *n >= 0,
+            (Self::Number, Value::Integer(_) | Value::Float(_)) => true,
+            (Self::Text | Self::OneOf(_), Value::String(s)) => self.accepts_str(s),
+            _ => false,
+        }
+    }
+
+    fn expected(self) -> String {
+        match self {
+            Self::Flag => "a flag (1/true/yes or 0/false/no)".to_string(),
+            Self::Count => "a non-negative integer".to_string(),
+            Self::Number => "a number".to_string(),
+            Self::Text => "a string".to_string(),
+            Self::OneOf(words) => format!("one of {}", words.join(", ")),
+        }
+    }
+}
+
+/// Environment variables loom reads, with the kind of their value.
+pub const KNOWN_ENV: &[(&str, ValueKind)] = &[
+    ("AUTO_CONTINUE", ValueKind::Flag),
+    ("EMBEDDING_API_KEY", ValueKind::Text),
+    ("EMBEDDING_BASE_URL", ValueKind::Text),
+    ("EMBEDDING_MODEL", ValueKind::Text),
+    ("EXA_API_KEY", ValueKind::Text),
+    ("HELVE_MAX_MESSAGE_LEN", ValueKind::Count),
+    ("HELVE_MAX_REPLY_LEN", ValueKind::Count),
+    ("LLM_PROVIDER", ValueKind::Text),
+    ("LOG_FILE", ValueKind::Text),
+    ("LOOM_APPROVAL_AUDIT_DB", ValueKind::Text),
+    (
+        "LOOM_APPROVAL_POLICY",
+        ValueKind::OneOf(&["always", "destructive_only", "none"]),
+    ),
+    ("LOOM_APPROVAL_RULES", ValueKind::Text),
+    (
+        "LOOM_BASH_OUTPUT",
+        ValueKind::OneOf(&["structured", "legacy"]),
+    ),
+    ("LOOM_BROWSER", ValueKind::Text),
+    ("LOOM_BROWSER_NO_SANDBOX", ValueKind::Flag),
+    ("LOOM_CHAOS", ValueKind::Text),
+    (
+        "LOOM_CONFIDENCE_SCORING",
+        ValueKind::OneOf(&["llm", "heuristic"]),
+    ),
+    ("LOOM_CONTEXT_FALLBACK_MODEL", ValueKind::Text),
+    ("LOOM_DB_KEY", ValueKind::Text),
+    ("LOOM_DB_KEY_FILE", ValueKind::Text),
+    ("LOOM_DB_PATH", ValueKind::Text),
+    ("LOOM_DEDUP_OBSERVATIONS", ValueKind::Flag),
+    ("LOOM_DRY_RUN", ValueKind::Flag),
+    ("LOOM_ENV_CONTEXT", ValueKind::Flag),
+    ("LOOM_EXA_CODESEARCH", ValueKind::Flag),
+    ("LOOM_FLAGS", ValueKind::Text),
+    ("LOOM_GOT_ADAPTIVE", ValueKind::Flag),
+    ("LOOM_GOT_AGOT_LLM_COMPLEXITY", ValueKind::Flag),
+    ("LOOM_GOT_TOKEN_BUDGET", ValueKind::Count),
+    ("LOOM_GRAPH", ValueKind::Text),
+    ("LOOM_HISTORY_MAX_TOKENS", ValueKind::Count),
+    ("LOOM_HISTORY_MAX_TURNS", ValueKind::Count),
+    ("LOOM_HISTORY_SUMMARY", ValueKind::Flag),
+    ("LOOM_HOME", ValueKind::Text),
+    ("LOOM_INJECTION_PATTERNS", ValueKind::Text),
+    ("LOOM_INPUT_MAX_CHARS", ValueKind::Count),
+    ("LOOM_INPUT_NORMALIZE_NEWLINES", ValueKind::Flag),
+    ("LOOM_INPUT_STRIP_CONTROL", ValueKind::Flag),
+    ("LOOM_LLM_ROUTES", ValueKind::Text),
+    ("LOOM_LOCALE", ValueKind::Text),
+    ("LOOM_MCP_CONFIG_PATH", ValueKind::Text),
+    ("LOOM_MEMORY_RECALL", ValueKind::Count),
+    ("LOOM_MODELS_DEV_API_JSON", ValueKind::Text),
+    ("LOOM_NODE_MODELS", ValueKind::Text),
+    ("LOOM_NO_PROGRESS", ValueKind::Text),
+    ("LOOM_OBSERVATION_SUMMARY_TOKENS", ValueKind::Count),
+    ("LOOM_OFFLINE", ValueKind::Flag),
+    ("LOOM_OFFLINE_SCRIPT", ValueKind::Text),
+    ("LOOM_PYTHON_INTERPRETER", ValueKind::Text),
+    ("LOOM_PYTHON_KEEP_DIRS", ValueKind::Count),
+    ("LOOM_PYTHON_MEMORY_MB", ValueKind::Count),
+    ("LOOM_PYTHON_NETWORK", ValueKind::Flag),
+    ("LOOM_READ_ONLY", ValueKind::Flag),
+    ("LOOM_REMOTE_QUEUE", ValueKind::Flag),
+    ("LOOM_REMOTE_RECONNECT_ATTEMPTS", ValueKind::Count),
+    ("LOOM_REMOTE_URL", ValueKind::Text),
+    ("LOOM_ROUTE_RULES", ValueKind::Text),
+    ("LOOM_ROUTING_SEED", ValueKind::Count),
+    ("LOOM_RUN_LOG_DIR", ValueKind::Text),
+    ("LOOM_SANITIZE_TOOL_RESULTS", ValueKind::Flag),
+    ("LOOM_SECRETS_KEYCHAIN", ValueKind::Text),
+    ("LOOM_SSH_HOSTS", ValueKind::Text),
+    (
+        "LOOM_STATE_LIMIT_ACTION",
+        ValueKind::OneOf(&["compact", "fail"]),
+    ),
+    ("LOOM_STATE_MAX_BYTES", ValueKind::Count),
+    ("LOOM_STATE_MAX_MESSAGES", ValueKind::Count),
+    ("LOOM_STOP_SEQUENCES", ValueKind::Text),
+    ("LOOM_TEST_MODE", ValueKind::Text),
+    ("LOOM_THREAD_ID", ValueKind::Text),
+    ("LOOM_TOOL_ARGUMENTS", ValueKind::Text),
+    (
+        "LOOM_TOOL_CALL_MODE",
+        ValueKind::OneOf(&["parallel", "serial", "text"]),
+    ),
+    ("LOOM_TOOL_PREFETCH", ValueKind::Text),
+    (
+        "LOOM_TOOL_RESULT_FRAMING",
+        ValueKind::OneOf(&[
+            "off",
+            "0",
+            "false",
+            "no",
+            "untrusted",
+            "all",
+            "1",
+            "true",
+            "yes",
+        ]),
+    ),
+    ("LOOM_TOOL_SELECTION_THRESHOLD", ValueKind::Number),
+    ("LOOM_TOOL_SELECTION_TOP_K", ValueKind::Count),
+    ("LOOM_TOT_EXPAND_CONCURRENCY", ValueKind::Count),
+    ("LOOM_UNTRUSTED_TOOLS", ValueKind::Text),
+    ("LOOM_USER_ID", ValueKind::Text),
+    ("MAX_SUB_AGENT_DEPTH", ValueKind::Count),
+    ("MCP_VERBOSE", ValueKind::Flag),
+    ("MODEL", ValueKind::Text),
+    ("MODELS_DEV_URL", ValueKind::Text),
+    ("OPENAI_API_BASE", ValueKind::Text),
+    ("OPENAI_API_KEY", ValueKind::Text),
+    ("OPENAI_BASE_URL", ValueKind::Text),
+    ("OPENAI_MODEL", ValueKind::Text),
+    ("OPENAI_TEMPERATURE", ValueKind::Number),
+    ("OPENAI_TOOL_CHOICE", ValueKind::Text),
+    ("PROMPTS_DIR", ValueKind::Text),
+    ("REACT_REFLECTION", ValueKind::Flag),
+    ("REACT_SYSTEM_PROMPT", ValueKind::Text),
+    ("RUST_LOG", ValueKind::Text),
+    ("SERVE_ACCESS_LOG", ValueKind::Text),
+    ("SERVE_ADMIN_TOKEN", ValueKind::Text),
+    ("SERVE_ALLOWED_TOOLS", ValueKind::Text),
+    ("SERVE_APPEND_QUEUE_CAPACITY", ValueKind::Count),
+    ("SERVE_AUTO_SUMMARIZE", ValueKind::Flag),
+    ("SERVE_DEFAULT_MODEL", ValueKind::Text),
+    ("SERVE_DETACHED_RUN_TTL_SECS", ValueKind::Count),
+    ("SERVE_DISPLAY_MAX_LEN", ValueKind::Count),
+    ("SERVE_EVENT_QUEUE_CAPACITY", ValueKind::Count),
+    ("SERVE_GITHUB_API_URL", ValueKind::Text),
+    ("SERVE_GITHUB_WEBHOOK_SECRET", ValueKind::Text),
+    ("SERVE_GITLAB_URL", ValueKind::Text),
+    ("SERVE_GITLAB_WEBHOOK_SECRET", ValueKind::Text),
+    ("SERVE_GRPC_ADDR", ValueKind::Text),
+    ("SERVE_HOOK_AGENT", ValueKind::Text),
+    ("SERVE_HOOK_ALLOWED_USERS", ValueKind::Text),
+    ("SERVE_HOOK_ALLOW_WRITE", ValueKind::Flag),
+    ("SERVE_HOOK_TRIGGER", ValueKind::Text),
+    ("SERVE_INPUT_MAX_CHARS", ValueKind::Count),
+    ("SERVE_MAX_ATTACHMENT_BYTES", ValueKind::Count),
+    ("SERVE_MAX_JSON_DEPTH", ValueKind::Count),
+    ("SERVE_MAX_MESSAGE_BYTES", ValueKind::Count),
+    ("SERVE_MODEL_CATALOG_CACHE", ValueKind::Text),
+    ("SERVE_MODEL_CATALOG_JITTER_SECS", ValueKind::Count),
+    ("SERVE_MODEL_CATALOG_REFRESH_SECS", ValueKind::Count),
+    ("SERVE_READ_ONLY", ValueKind::Flag),
+    ("SERVE_ROLE_FILE", ValueKind::Text),
+    ("SERVE_STORE_DEGRADATION", ValueKind::Text),
+    ("SERVE_STORE_RECONNECT_SECS", ValueKind::Count),
+    ("SERVE_SUMMARY_MODEL", ValueKind::Text),
+    ("SERVE_VALUES_MAX_BYTES", ValueKind::Count),
+    ("SERVE_WORKERS", ValueKind::Count),
+    ("SERVE_WORKER_MAX_RUNS", ValueKind::Count),
+    ("SERVE_WORKER_MEMORY_MB", ValueKind::Count),
+    ("SERVE_WORKER_PROGRAM", ValueKind::Text),
+    ("USER_MESSAGE_DB", ValueKind::Text),
+    ("WORKING_FOLDER", ValueKind::Text),
+    ("WORKSPACE_DB", ValueKind::Text),
+    ("WORKSPACE_DB_KEY", ValueKind::Text),
+];
+
+/// A key of a structured section and the environment variable it sets.
+#[derive(Clone, Copy, Debug)]
+pub struct SectionKey {
+    pub section: &'static str,
+    pub key: &'static str,
+    pub env: &'static str,
+    pub kind: ValueKind,
+}
+
+/// Keys of the structured sections (`[history]`, `[state]`).
+pub const SECTION_KEYS: &[SectionKey] = &[
+    SectionKey {
+        section: "history",
+        key: "max_turns",
+        env: "LOOM_HISTORY_MAX_TURNS",
+        kind: ValueKind::Count,
+    },
+    SectionKey {
+        section: "history",
+        key: "max_tokens",
+        env: "LOOM_HISTORY_MAX_TOKENS",
+        kind: ValueKind::Count,
+    },
+    SectionKey {
+        section: "history",
+        key: "summary",
+        env: "LOOM_HISTORY_SUMMARY",
+        kind: ValueKind::Flag,
+    },
+    SectionKey {
+        section: "state",
+        key: "max_messages",
+        env: "LOOM_STATE_MAX_MESSAGES",
+        kind: ValueKind::Count,
+    },
+    SectionKey {
+        section: "state",
+        key: "max_bytes",
+        env: "LOOM_STATE_MAX_BYTES",
+        kind: ValueKind::Count,
+    },
+    SectionKey {
+        section: "state",
+        key: "limit_action",
+        env: "LOOM_STATE_LIMIT_ACTION",
+        kind: ValueKind::OneOf(&["compact", "fail"]),
+    },
+];
+
+/// Platforms accepted in `[os.<name>]`.
+const OS_NAMES: &[&str] = &[
+    "unix",
+    "linux",
+    "macos",
+    "windows",
+    "android",
+    "ios",
+    "freebsd",
+    "netbsd",
+    "openbsd",
+    "dragonfly",
+    "solaris",
+    "illumos",
+];
+
+/// Prefix of the environment variables loom owns; unknown keys with it are errors.
+const LOOM_NAMESPACE: &str = "LOOM_";
+
+/// One problem found in a config file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigIssue {
+    pub file: PathBuf,
+    /// 1-based line.
+    pub line: usize,
+    /// 1-based column, in characters.
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            self.file.display(),
+            self.line,
+            self.column,
+            self.message
+        )
+    }
+}
+
+/// Joins issues one per line (for [`LoadError::XdgSchema`]).
+pub(crate) fn format_issues(issues: &[ConfigIssue]) -> String {
+    issues
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Env pairs of the structured sections in `table`, removing the sections. Keys and values the
+/// schema does not accept are skipped ([`validate_config`] reports them).
+pub(crate) fn take_section_env(table: &mut Table) -> Vec<(String, String)> {
+    let mut out = vec![];
+    for name in section_names() {
+        let Some(Value::Table(section)) = table.remove(name) else {
+            continue;
+        };
+        for (key, value) in section {
+            let Some(spec) = section_key(name, &key) else {
+                continue;
+            };
+            if !spec.kind.accepts_value(&value) {
+                continue;
+            }
+            let value = match value {
+                Value::String(s) => s,
+                other => other.to_string(),
+            };
+            out.push((spec.env.to_string(), value));
+        }
+    }
+    out
+}
+
+fn section_names() -> Vec<&'static str> {
+    let mut names: Vec<_> = SECTION_KEYS.iter().map(|k| k.section).collect();
+    names.dedup();
+    names
+}
+
+fn section_key(section: &str, key: &str) -> Option<&'static SectionKey> {
+    SECTION_KEYS
+        .iter()
+        .find(|k| k.section == section && k.key == key)
+}
+
+fn known_env(key: &str) -> Option<ValueKind> {
+    KNOWN_ENV
+        .iter()
+        .find(|(name, _)| *name == key)
+        .map(|(_, kind)| *kind)
+}
+
+/// Validates `~/.loom/config.toml` and the files it includes. A missing file has no issues;
+/// only unreadable files are errors.
+pub fn validate_config(app_name: &str) -> Result<Vec<ConfigIssue>, LoadError> {
+    let Some(path) = crate::xdg_toml::config_path(app_name)? else {
+        return Ok(vec![]);
+    };
+    let mut issues = vec![];
+    let mut seen = vec![];
+    validate_file(&path, &mut seen, &mut issues)?;
+    Ok(issues)
+}
+
+fn validate_file(
+    path: &Path,
+    seen: &mut Vec<PathBuf>,
+    issues: &mut Vec<ConfigIssue>,
+) -> Result<(), LoadError> {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if seen.contains(&canonical) {
+        return Ok(());
+    }
+    seen.push(canonical);
+    let content = std::fs::read_to_string(path).map_err(LoadError::XdgRead)?;
+    let includes = check_document(path, &content, issues);
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    for (include, span) in includes {
+        // Paths with `${VAR}` are only known once expanded at load time.
+        if include.contains("${") {
+            continue;
+        }
+        let include_path = dir.join(&include);
+        if include_path.is_file() {
+            validate_file(&include_path, seen, issues)?;
+        } else {
+            issues.push(issue_at(
+                path,
+                &content,
+                span,
+                format!("included file {} does not exist", include_path.display()),
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawFile {
+    #[serde(default)]
+    include: Option<Spanned<Value>>,
+    #[serde(default)]
+    env: BTreeMap<Spanned<String>, Spanned<Value>>,
+    #[serde(default)]
+    default: Option<RawDefault>,
+    #[serde(default)]
+    providers: Vec<RawProvider>,
+    #[serde(default)]
+    os: BTreeMap<Spanned<String>, Spanned<RawFile>>,
+    #[serde(default)]
+    history: BTreeMap<Spanned<String>, Spanned<Value>>,
+    #[serde(default)]
+    state: BTreeMap<Spanned<String>, Spanned<Value>>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+#[allow(dead_code)]
+struct RawDefault {
+    #[serde(default)]
+    provider: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+#[allow(dead_code)]
+struct RawProvider {
+    name: Spanned<String>,
+    #[serde(default)]
+    api_key: Option<String>,
+    #[serde(default)]
+    base_url: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default, rename = "type")]
+    provider_type: Option<String>,
+    #[serde(default)]
+    tool_choice: Option<String>,
+    #[serde(default)]
+    temperature: Option<f64>,
+    #[serde(default)]
+    fetch_models: Option<bool>,
+}
+
+/// Checks one file's text; returns its `include` entries with their spans.
+fn check_document(
+    path: &Path,
+    content: &str,
+    issues: &mut Vec<ConfigIssue>,
+) -> Vec<(String, Range<usize>)> {
+    let file: RawFile = match toml::from_str(content) {
+        Ok(file) => file,
+        Err(e) => {
+            let span = e.span().unwrap_or(0..0);
+            issues.push(issue_at(path, content, span, e.message().to_string()));
+            return vec![];
+        }
+    };
+    let mut push = |span: Range<usize>, message: String| {
+        issues.push(issue_at(path, content, span, message));
+    };
+    check_tables(&file, &mut push);
+    for (name, section) in &file.os {
+        if !OS_NAMES.contains(&name.get_ref().as_str()) {
+            push(
+                name.span(),
+                format!(
+                    "unknown platform `os.{}` (expected one of {})",
+                    name.get_ref(),
+                    OS_NAMES.join(", ")
+                ),
+            );
+        }
+        let section = section.get_ref();
+        if let Some(include) = &section.include {
+            push(
+                include.span(),
+                "`include` is not supported in `[os.*]`".into(),
+            );
+        }
+        if let Some((nested, _)) = section.os.iter().next() {
+            push(nested.span(), "`[os.*]` sections cannot be nested".into());
+        }
+        check_tables(section, &mut push);
+    }
+
+    match &file.include {
+        None => vec![],
+        Some(include) => match include.get_ref() {
+            Value::String(s) => vec![(s.clone(), include.span())],
+            Value::Array(items) if items.iter().all(Value::is_str) => items
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(|s| (s.to_string(), include.span()))
+                .collect(),
+            _ => {
+                push(
+                    include.span(),
+                    "`include` must be a file name or an array of file names".into(),
+                );
+                vec![]
+            }
+        },
+    }
+}
+
+/// Checks `[env]`, the structured sections and `[[providers]]` of one file or `[os.*]` section.
+fn check_tables(file: &RawFile, push: &mut impl FnMut(Range<usize>, String)) {
+    for (key, value) in &file.env {
+        let name = key.get_ref();
+        let kind = known_env(name);
+        if kind.is_none() {
+            match closest_known(name) {
+                Some(known) => push(
+                    key.span(),
+                    format!("unknown variable `{}`; did you mean `{}`?", name, known),
+                ),
+                None if name.starts_with(LOOM_NAMESPACE) => push(
+                    key.span(),
+                    format!("unknown variable `{}`: loom does not read it", name),
+                ),
+                None => {}
+            }
+        }
+        match value.get_ref() {
+            Value::String(s) => {
+                if let Some(kind) = kind.filter(|k| !s.contains("${") && !k.accepts_str(s)) {
+                    push(
+                        value.span(),
+                        format!("`{}` must be {}, got {:?}", name, kind.expected(), s),
+                    );
+                }
+            }
+            other => push(
+                value.span(),
+                format!(
+                    "`[env]` values must be strings, got {} for `{}`",
+                    other.type_str(),
+                    name
+                ),
+            ),
+        }
+    }
+
+    for (section, entries) in [("history", &file.history), ("state", &file.state)] {
+        for (key, value) in entries {
+            let Some(spec) = section_key(section, key.get_ref()) else {
+                let known: Vec<_> = SECTION_KEYS
+                    .iter()
+                    .filter(|k| k.section == section)
+                    .map(|k| k.key)
+                    .collect();
+                push(
+                    key.span(),
+                    format!(
+                        "unknown key `{}` in `[{}]` (expected one of {})",
+                        key.get_ref(),
+                        section,
+                        known.join(", ")
+                    ),
+                );
+                continue;
+            };
+            if !spec.kind.accepts_value(value.get_ref()) {
+                push(
+                    value.span(),
+                    format!(
+                        "`{}.{}` must be {}, got {}",
+                        section,
+                        spec.key,
+                        spec.kind.expected(),
+                        value.get_ref()
+                    ),
+                );
+                continue;
+            }
+            let env_value = file
+                .env
+                .iter()
+                .find(|(k, _)| k.get_ref() == spec.env)
+                .and_then(|(_, v)| v.get_ref().as_str());
+            if let Some(env_value) = env_value {
+                if !same_setting(value.get_ref(), env_value) {
+                    push(
+                        key.span(),
+                        format!(
+                            "`{}.{}` conflicts with `{}` = {:?} in `[env]`",
+                            section, spec.key, spec.env, env_value
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    let mut names: Vec<&Spanned<String>> = vec![];
+    for provider in &file.providers {
+        let name = &provider.name;
+        if names
+            .iter()
+            .any(|n| n.get_ref().eq_ignore_ascii_case(name.get_ref()))
+        {
+            push(
+                name.span(),
+                format!(
+                    "provider `{}` is defined twice in this file",
+                    name.get_ref()
+                ),
+            );
+        } else {
+            names.push(name);
+        }
+    }
+}
+
+/// Whether a structured value and an `[env]` string set the same thing.
+fn same_setting(value: &Value, env_value: &str) -> bool {
+    let env_value = env_value.trim();
+    match value {
+        Value::String(s) => s.trim().eq_ignore_ascii_case(env_value),
+        Value::Boolean(b) => {
+            let on = matches!(env_value.to_lowercase().as_str(), "1" | "true" | "yes");
+            *b == on
+        }
+        other => other.to_string() == env_value,
+    }
+}
+
+/// The known variable `key` is most likely a typo of: within one edit per 8 characters.
+fn closest_known(key: &str) -> Option<&'static str> {
+    KNOWN_ENV
+        .iter()
+        .filter(|(name, _)| name.len() >= 8)
+        .map(|(name, _)| (*name, edit_distance(key, name)))
+        .filter(|(name, d)| *d <= (name.len() / 8).max(1))
+        .min_by_key(|(_, d)| *d)
+        .map(|(name, _)| name)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur.push((prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1));
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+fn issue_at(path: &Path, content: &str, span: Range<usize>, message: String) -> ConfigIssue {
+    let start = span.start.min(content.len());
+    let before = &content[..start];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    ConfigIssue {
+        file: path.to_path_buf(),
+        line,
+        column: before[line_start..].chars().count() + 1,
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(content: &str) -> Vec<String> {
+        let mut issues = vec![];
+        check_document(Path::new("config.toml"), content, &mut issues);
+        issues.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn valid_file_has_no_issues() {
+        let issues = check(
+            r#"
+include = "team.toml"
+
+[env]
+LOOM_HISTORY_SUMMARY = "true"
+GITHUB_TOKEN = "ghp_x"
+LOOM_STATE_MAX_BYTES = "${LOOM_LIMIT:-1000}"
+
+[history]
+max_turns = 20
+
+[state]
+limit_action = "fail"
+
+[[providers]]
+name = "openai"
+tool_choice = "none"
+temperature = 1
+
+[os.linux.env]
+LOOM_DB_PATH = "/var/lib/loom/memory.db"
+"#,
+        );
+        assert!(issues.is_empty(), "{:?}", issues);
+    }
+
+    #[test]
+    fn reports_misspelled_keys_and_bad_values_with_locations() {
+        let issues = check(
+            "[env]\nLOOM_HISTORY_MAX_TURN = \"5\"\nLOOM_DRY_RUN = \"on\"\nLOOM_NOT_A_THING = \"1\"\nOPENAI_API_KY = \"sk\"\n",
+        );
+        assert_eq!(
+            issues,
+            vec![
+                "config.toml:3:16: `LOOM_DRY_RUN` must be a flag (1/true/yes or 0/false/no), got \"on\"",
+                "config.toml:2:1: unknown variable `LOOM_HISTORY_MAX_TURN`; did you mean `LOOM_HISTORY_MAX_TURNS`?",
+                "config.toml:4:1: unknown variable `LOOM_NOT_A_THING`: loom does not read it",
+                "config.toml:5:1: unknown variable `OPENAI_API_KY`; did you mean `OPENAI_API_KEY`?",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_unknown_sections_and_fields() {
+        let issues = check("[histroy]\nmax_turns = 3\n");
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].starts_with("config.toml:1:"), "{}", issues[0]);
+        assert!(
+            issues[0].contains("unknown field `histroy`"),
+            "{}",
+            issues[0]
+        );
+
+        let issues = check("[[providers]]\nname = \"a\"\nmodle = \"x\"\n");
+        assert!(issues[0].starts_with("config.toml:3:1:"), "{}", issues[0]);
+
+        let issues = check("[history]\nmax_turn = 3\nmax_tokens = -1\n");
+        assert_eq!(issues.len(), 2);
+        assert!(issues[0].starts_with("config.toml:3:14:"), "{}", issues[0]);
+        assert!(issues[1].contains("unknown key `max_turn` in `[history]`"));
+    }
+
+    #[test]
+    fn reports_conflicting_settings() {
+        let issues = check(
+            r#"[env]
+LOOM_STATE_LIMIT_ACTION = "compact"
+LOOM_HISTORY_SUMMARY = "1"
+
+[history]
+summary = true
+
+[state]
+limit_action = "fail"
+
+[[providers]]
+name = "openai"
+
+[[providers]]
+name = "OpenAI"
+"#,
+        );
+        assert_eq!(issues.len(), 2, "{:?}", issues);
+        assert!(issues[0].starts_with("config.toml:9:1: `state.limit_action` conflicts"));
+        assert!(issues[1].contains("provider `OpenAI` is defined twice"));
+    }
+
+    #[test]
+    fn section_env_maps_typed_values() {
+        let mut table: Table =
+            toml::from_str("[history]\nmax_turns = 4\nsummary = true\nbogus = 1\n[env]\nA = \"b\"")
+                .unwrap();
+        let mut env = take_section_env(&mut table);
+        env.sort();
+        assert_eq!(
+            env,
+            vec![
+                ("LOOM_HISTORY_MAX_TURNS".to_string(), "4".to_string()),
+                ("LOOM_HISTORY_SUMMARY".to_string(), "true".to_string()),
+            ]
+        );
+        assert!(!table.contains_key("history"));
+        assert!(table.contains_key("env"));
+    }
+}
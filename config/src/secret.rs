@@ -0,0 +1,259 @@
+//! Secret values (API keys, tokens): redacted wrapper, file/keychain sources, and text scrubbing.
+//!
+//! [`Secret`] never prints its value through `Debug`, `Display` or `Serialize`; callers that
+//! need the plain value (e.g. to build an `Authorization` header) call [`Secret::expose`].
+//! [`read_secret`] resolves `NAME` from the environment, then `NAME_FILE`, then (opt-in) the OS
+//! keychain. [`redact_text`] scrubs bearer tokens and well-known key shapes from free text
+//! before it reaches a log or summary.
+
+use std::fmt;
+use std::path::Path;
+
+/// Placeholder printed instead of a secret value.
+pub const REDACTED: &str = "***";
+
+/// Keychain service name used by [`read_secret`] when `LOOM_SECRETS_KEYCHAIN` is enabled.
+pub const KEYCHAIN_SERVICE: &str = "loom";
+
+/// A secret string (API key, token). `Debug`, `Display` and `Serialize` print [`REDACTED`].
+#[derive(Clone, PartialEq, Eq, Default)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    /// Returns the plain value. Use only where the secret is actually sent (headers, clients).
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Masked form for display (first and last 2 chars), see [`crate::mask_value`].
+    pub fn masked(&self) -> String {
+        crate::mask_value(&self.0)
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret({})", REDACTED)
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl serde::Serialize for Secret {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(REDACTED)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Secret {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Secret)
+    }
+}
+
+fn env_flag(name: &str) -> bool {
+    std::env::var(name)
+        .map(|s| matches!(s.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+/// Reads a secret file: contents with surrounding whitespace trimmed; `None` when unreadable or empty.
+pub fn read_secret_file(path: impl AsRef<Path>) -> Option<Secret> {
+    let s = std::fs::read_to_string(path).ok()?;
+    let s = s.trim();
+    (!s.is_empty()).then(|| Secret::from(s))
+}
+
+/// Looks up `account` under [`KEYCHAIN_SERVICE`] in the OS keychain: `security` on macOS,
+/// `secret-tool` (libsecret) elsewhere on Unix. Returns `None` when unavailable or not found.
+pub fn read_keychain_secret(account: &str) -> Option<Secret> {
+    let output = if cfg!(target_os = "macos") {
+        std::process::Command::new("security")
+            .args([
+                "find-generic-password",
+                "-s",
+                KEYCHAIN_SERVICE,
+                "-a",
+                account,
+                "-w",
+            ])
+            .output()
+    } else if cfg!(unix) {
+        std::process::Command::new("secret-tool")
+            .args(["lookup", "service", KEYCHAIN_SERVICE, "account", account])
+            .output()
+    } else {
+        return None;
+    };
+    let output = output.ok().filter(|o| o.status.success())?;
+    let value = String::from_utf8(output.stdout).ok()?;
+    let value = value.trim();
+    (!value.is_empty()).then(|| Secret::from(value))
+}
+
+/// Resolves secret `name` (e.g. `OPENAI_API_KEY`), first match wins:
+/// 1. env `NAME` (non-empty)
+/// 2. env `NAME_FILE`: path to a file holding the value (e.g. Docker/K8s secrets); skipped when unreadable
+/// 3. OS keychain entry (service `loom`, account `NAME`) when `LOOM_SECRETS_KEYCHAIN` is `1`/`true`/`yes`
+pub fn read_secret(name: &str) -> Option<Secret> {
+    if let Some(v) = std::env::var(name).ok().filter(|v| !v.is_empty()) {
+        return Some(Secret::from(v));
+    }
+    if let Some(path) = std::env::var(format!("{}_FILE", name))
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+    {
+        if let Some(s) = read_secret_file(path.trim()) {
+            return Some(s);
+        }
+    }
+    if env_flag("LOOM_SECRETS_KEYCHAIN") {
+        return read_keychain_secret(name);
+    }
+    None
+}
+
+/// Well-known key prefixes that are redacted wherever they appear in free text.
+const KEY_PREFIXES: &[&str] = &[
+    "sk-",
+    "ghp_",
+    "gho_",
+    "ghs_",
+    "github_pat_",
+    "xoxb-",
+    "xoxp-",
+];
+
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '=' | '+' | '/' | '~')
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '_')
+}
+
+fn token_len(s: &str) -> usize {
+    s.chars()
+        .take_while(|c| is_token_char(*c))
+        .map(char::len_utf8)
+        .sum()
+}
+
+/// Replaces bearer/basic credentials and well-known API key shapes in `text` with [`REDACTED`].
+///
+/// Covers `Bearer <token>` / `Basic <token>` (case-insensitive) and tokens starting with common
+/// key prefixes (`sk-`, `ghp_`, `github_pat_`, ...). Used before printing config summaries and
+/// other text that may carry credentials.
+pub fn redact_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    'outer: while !rest.is_empty() {
+        let at_boundary = out.chars().last().map_or(true, |c| !is_word_char(c));
+        if at_boundary {
+            for scheme in ["bearer ", "basic "] {
+                let matches = rest
+                    .get(..scheme.len())
+                    .is_some_and(|p| p.eq_ignore_ascii_case(scheme));
+                if !matches {
+                    continue;
+                }
+                let after = &rest[scheme.len()..];
+                let len = token_len(after);
+                if len > 0 {
+                    out.push_str(&rest[..scheme.len()]);
+                    out.push_str(REDACTED);
+                    rest = &after[len..];
+                    continue 'outer;
+                }
+            }
+            if let Some(prefix) = KEY_PREFIXES.iter().find(|p| rest.starts_with(**p)) {
+                let len = token_len(rest);
+                if len > prefix.len() + 4 {
+                    out.push_str(prefix);
+                    out.push_str(REDACTED);
+                    rest = &rest[len..];
+                    continue;
+                }
+            }
+        }
+        let c = rest.chars().next().expect("non-empty");
+        out.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_never_prints_its_value() {
+        let s = Secret::from("sk-live-1234567890");
+        assert_eq!(format!("{}", s), "***");
+        assert_eq!(format!("{:?}", s), "Secret(***)");
+        assert_eq!(serde_json::to_string(&s).unwrap(), "\"***\"");
+        assert_eq!(s.expose(), "sk-live-1234567890");
+        let parsed: Secret = serde_json::from_str("\"abc\"").unwrap();
+        assert_eq!(parsed.expose(), "abc");
+    }
+
+    #[test]
+    fn read_secret_prefers_env_then_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("key");
+        std::fs::write(&file, "from-file\n").unwrap();
+        let name = "LOOM_TEST_SECRET_READ_SECRET";
+        let file_var = format!("{}_FILE", name);
+
+        std::env::remove_var(name);
+        std::env::set_var(&file_var, &file);
+        assert_eq!(read_secret(name).unwrap().expose(), "from-file");
+
+        std::env::set_var(name, "from-env");
+        assert_eq!(read_secret(name).unwrap().expose(), "from-env");
+
+        std::env::remove_var(name);
+        std::env::remove_var(&file_var);
+        assert!(read_secret(name).is_none());
+    }
+
+    #[test]
+    fn redact_text_masks_bearer_tokens_and_key_prefixes() {
+        assert_eq!(
+            redact_text("Authorization: Bearer abc.def-123"),
+            "Authorization: Bearer ***"
+        );
+        assert_eq!(
+            redact_text("key=sk-proj-abcdef123456 next"),
+            "key=sk-*** next"
+        );
+        assert_eq!(redact_text("token ghp_abcdefghij"), "token ghp_***");
+        assert_eq!(redact_text("task-list and sk-1"), "task-list and sk-1");
+        assert_eq!(redact_text("no secrets here"), "no secrets here");
+    }
+}
@@ -47,6 +47,17 @@ impl LogRotate {
     pub fn from_str_or_daily(s: &str) -> Self {
         Self::parse(s).unwrap_or(Self::Daily)
     }
+
+    /// Rotation for a [`RollingFileAppender`](tracing_appender::rolling::RollingFileAppender);
+    /// [`LogRotate::None`] never rotates.
+    pub fn rotation(self) -> tracing_appender::rolling::Rotation {
+        match self {
+            Self::None => tracing_appender::rolling::Rotation::NEVER,
+            Self::Daily => tracing_appender::rolling::Rotation::DAILY,
+            Self::Hourly => tracing_appender::rolling::Rotation::HOURLY,
+            Self::Minutely => tracing_appender::rolling::Rotation::MINUTELY,
+        }
+    }
 }
 
 /// Resolve a log path template: `{working_folder}` substitution, then relative paths against
@@ -120,14 +131,8 @@ pub fn file_non_blocking_writer(
                 .and_then(|s| s.to_str())
                 .unwrap_or(rolling_default_stem);
             let suffix = path.extension().and_then(|s| s.to_str()).unwrap_or("log");
-            let rotation = match rotate {
-                LogRotate::Daily => tracing_appender::rolling::Rotation::DAILY,
-                LogRotate::Hourly => tracing_appender::rolling::Rotation::HOURLY,
-                LogRotate::Minutely => tracing_appender::rolling::Rotation::MINUTELY,
-                LogRotate::None => unreachable!(),
-            };
             let appender = tracing_appender::rolling::RollingFileAppender::builder()
-                .rotation(rotation)
+                .rotation(rotate.rotation())
                 .filename_prefix(prefix)
                 .filename_suffix(suffix)
                 .build(parent)
@@ -1,7 +1,21 @@
 //! Load `[env]` table and `[[providers]]` from `~/.loom/config.toml`.
+//!
+//! Before the file is deserialized it is layered and expanded:
+//! - `include = ["team.toml"]` loads other files first (paths relative to the including file);
+//!   the including file's values win. Tables merge key by key, `[[providers]]` merge by `name`.
+//! - `[os.linux]`, `[os.macos]`, `[os.windows]` (and `[os.unix]`) are merged over the top level
+//!   on the matching platform, so one file can hold machine-specific paths.
+//! - `${VAR}` and `${VAR:-default}` in string values are replaced from the process environment;
+//!   `$${` is a literal `${`.
+//!
+//! Structured sections (`[history]`, `[state]`) are typed spellings of `[env]` variables and
+//! are merged into the env map; `[env]` wins for a variable set both ways. See [`crate::schema`]
+//! for the keys and the checks `loom config validate` runs.
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use toml::{Table, Value};
 
 use crate::LoadError;
 
@@ -129,8 +143,16 @@ pub fn load_full_config(app_name: &str) -> Result<FullConfig, LoadError> {
             })
         }
     };
-    let content = std::fs::read_to_string(&path).map_err(LoadError::XdgRead)?;
-    let config: ConfigFile = toml::from_str(&content)?;
+    let mut table = read_layered(&path, &mut Vec::new())?;
+    apply_os_sections(&mut table);
+    for (_, value) in table.iter_mut() {
+        expand_value(value)?;
+    }
+    let section_env = crate::schema::take_section_env(&mut table);
+    let mut config: ConfigFile = Value::Table(table).try_into()?;
+    for (key, value) in section_env {
+        config.env.entry(key).or_insert(value);
+    }
     Ok(FullConfig {
         env: config.env,
         default_provider: config.default.provider,
@@ -138,6 +160,175 @@ pub fn load_full_config(app_name: &str) -> Result<FullConfig, LoadError> {
     })
 }
 
+/// Max nesting of `include` files.
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// Reads `path` and its `include` files (depth first), merged so that later layers win.
+/// `stack` holds the files being read, to reject include cycles.
+fn read_layered(path: &Path, stack: &mut Vec<PathBuf>) -> Result<Table, LoadError> {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if stack.contains(&canonical) {
+        return Err(LoadError::XdgInclude(format!(
+            "include cycle at {}",
+            path.display()
+        )));
+    }
+    if stack.len() > MAX_INCLUDE_DEPTH {
+        return Err(LoadError::XdgInclude(format!(
+            "includes nested deeper than {} at {}",
+            MAX_INCLUDE_DEPTH,
+            path.display()
+        )));
+    }
+    let content = std::fs::read_to_string(path).map_err(LoadError::XdgRead)?;
+    let mut table: Table = toml::from_str(&content)?;
+    let includes = match table.remove("include") {
+        None => vec![],
+        Some(Value::String(s)) => vec![s],
+        Some(Value::Array(items)) => items
+            .into_iter()
+            .map(|v| match v {
+                Value::String(s) => Ok(s),
+                other => Err(LoadError::XdgInclude(format!(
+                    "include entries must be strings, got {}",
+                    other.type_str()
+                ))),
+            })
+            .collect::<Result<_, _>>()?,
+        Some(other) => {
+            return Err(LoadError::XdgInclude(format!(
+                "include must be a string or array, got {}",
+                other.type_str()
+            )))
+        }
+    };
+
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut merged = Table::new();
+    stack.push(canonical);
+    for include in includes {
+        let include_path = dir.join(expand_vars(&include)?);
+        let layer = read_layered(&include_path, stack).map_err(|e| match e {
+            LoadError::XdgRead(io) => {
+                LoadError::XdgInclude(format!("{}: {}", include_path.display(), io))
+            }
+            other => other,
+        })?;
+        merge_tables(&mut merged, layer);
+    }
+    stack.pop();
+    merge_tables(&mut merged, table);
+    Ok(merged)
+}
+
+/// Merges `overlay` into `base`: tables recursively, arrays of named tables (e.g. `[[providers]]`)
+/// by `name`, anything else replaced.
+fn merge_tables(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(b)), Value::Table(o)) => merge_tables(b, o),
+            (Some(Value::Array(b)), Value::Array(o)) if is_named_array(b) && is_named_array(&o) => {
+                for item in o {
+                    let name = item.get("name").cloned();
+                    match b
+                        .iter_mut()
+                        .find(|existing| existing.get("name") == name.as_ref())
+                    {
+                        Some(existing) => *existing = item,
+                        None => b.push(item),
+                    }
+                }
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn is_named_array(items: &[Value]) -> bool {
+    items
+        .iter()
+        .all(|v| v.get("name").is_some_and(Value::is_str))
+}
+
+/// Merges `[os.unix]` (on Unix) and `[os.<current OS>]` over the top level and drops `os`.
+fn apply_os_sections(table: &mut Table) {
+    let Some(Value::Table(mut sections)) = table.remove("os") else {
+        return;
+    };
+    let mut names = vec![];
+    if cfg!(unix) {
+        names.push("unix");
+    }
+    names.push(std::env::consts::OS);
+    for name in names {
+        if let Some(Value::Table(section)) = sections.remove(name) {
+            merge_tables(table, section);
+        }
+    }
+}
+
+/// Expands `${VAR}` in every string of `value`.
+fn expand_value(value: &mut Value) -> Result<(), LoadError> {
+    match value {
+        Value::String(s) => *s = expand_vars(s)?,
+        Value::Array(items) => {
+            for item in items {
+                expand_value(item)?;
+            }
+        }
+        Value::Table(table) => {
+            for (_, item) in table.iter_mut() {
+                expand_value(item)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Replaces `${VAR}` / `${VAR:-default}` with the process environment. An unset variable without
+/// a default is an error; `$${` yields a literal `${`.
+fn expand_vars(s: &str) -> Result<String, LoadError> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos..];
+        if let Some(tail) = after.strip_prefix("$${") {
+            out.push_str("${");
+            rest = tail;
+        } else if let Some(tail) = after.strip_prefix("${") {
+            let end = tail
+                .find('}')
+                .ok_or_else(|| LoadError::XdgExpand(format!("unterminated ${{ in {:?}", s)))?;
+            let expr = &tail[..end];
+            let (name, default) = match expr.split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (expr, None),
+            };
+            match (std::env::var(name.trim()).ok(), default) {
+                (Some(v), None) => out.push_str(&v),
+                (Some(v), Some(_)) if !v.is_empty() => out.push_str(&v),
+                (_, Some(default)) => out.push_str(default),
+                (None, None) => {
+                    return Err(LoadError::XdgExpand(format!(
+                        "environment variable {} is not set",
+                        name.trim()
+                    )))
+                }
+            }
+            rest = &tail[end + 1..];
+        } else {
+            out.push('$');
+            rest = &after[1..];
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -296,4 +487,132 @@ temperature = 0.5
         let full = load_full_config("loom").unwrap();
         assert_eq!(full.providers[0].temperature, Some(0.5));
     }
+
+    #[test]
+    fn expand_vars_uses_env_defaults_and_escapes() {
+        env::set_var("LOOM_TEST_EXPAND_DIR", "/opt/team");
+        env::remove_var("LOOM_TEST_EXPAND_UNSET");
+        assert_eq!(
+            expand_vars("${LOOM_TEST_EXPAND_DIR}/models").unwrap(),
+            "/opt/team/models"
+        );
+        assert_eq!(
+            expand_vars("${LOOM_TEST_EXPAND_UNSET:-fallback} costs $5").unwrap(),
+            "fallback costs $5"
+        );
+        assert_eq!(expand_vars("$${NOT_EXPANDED}").unwrap(), "${NOT_EXPANDED}");
+        assert!(matches!(
+            expand_vars("${LOOM_TEST_EXPAND_UNSET}"),
+            Err(crate::LoadError::XdgExpand(_))
+        ));
+        assert!(matches!(
+            expand_vars("${OPEN"),
+            Err(crate::LoadError::XdgExpand(_))
+        ));
+        env::remove_var("LOOM_TEST_EXPAND_DIR");
+    }
+
+    #[test]
+    fn load_full_config_merges_includes_and_os_sections() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("shared")).unwrap();
+        std::fs::write(
+            dir.path().join("shared/team.toml"),
+            r#"
+[env]
+TEAM_ONLY = "team"
+SHARED = "from_team"
+
+[[providers]]
+name = "openai"
+model = "gpt-4o-mini"
+
+[[providers]]
+name = "local"
+base_url = "http://localhost:11434/v1"
+"#,
+        )
+        .unwrap();
+        let os = std::env::consts::OS;
+        std::fs::write(
+            dir.path().join("config.toml"),
+            format!(
+                r#"
+include = ["shared/team.toml"]
+
+[env]
+SHARED = "from_main"
+DATA_DIR = "${{LOOM_TEST_INCLUDE_HOME:-/default}}/data"
+
+[[providers]]
+name = "openai"
+model = "gpt-4o"
+
+[os.{os}.env]
+PLATFORM_DIR = "/{os}/dir"
+
+[os.not-this-os.env]
+PLATFORM_DIR = "wrong"
+"#
+            ),
+        )
+        .unwrap();
+
+        env::remove_var("LOOM_TEST_INCLUDE_HOME");
+        let _guard = LoomHomeGuard::set(dir.path());
+        let full = load_full_config("loom").unwrap();
+
+        assert_eq!(full.env.get("TEAM_ONLY").map(String::as_str), Some("team"));
+        assert_eq!(
+            full.env.get("SHARED").map(String::as_str),
+            Some("from_main")
+        );
+        assert_eq!(
+            full.env.get("DATA_DIR").map(String::as_str),
+            Some("/default/data")
+        );
+        assert_eq!(full.env.get("PLATFORM_DIR"), Some(&format!("/{}/dir", os)));
+        let names: Vec<&str> = full.providers.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["openai", "local"]);
+        assert_eq!(full.providers[0].model.as_deref(), Some("gpt-4o"));
+    }
+
+    #[test]
+    fn structured_sections_map_to_env() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("config.toml"),
+            r#"
+[env]
+LOOM_STATE_MAX_BYTES = "2000"
+
+[history]
+max_turns = 12
+summary = true
+
+[state]
+max_bytes = 1000
+limit_action = "fail"
+"#,
+        )
+        .unwrap();
+        let _guard = LoomHomeGuard::set(dir.path());
+        let env = load_env_map("loom").unwrap();
+        assert_eq!(env["LOOM_HISTORY_MAX_TURNS"], "12");
+        assert_eq!(env["LOOM_HISTORY_SUMMARY"], "true");
+        assert_eq!(env["LOOM_STATE_LIMIT_ACTION"], "fail");
+        assert_eq!(env["LOOM_STATE_MAX_BYTES"], "2000");
+    }
+
+    #[test]
+    fn include_cycle_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("config.toml"), "include = \"a.toml\"\n").unwrap();
+        std::fs::write(dir.path().join("a.toml"), "include = \"config.toml\"\n").unwrap();
+        let _guard = LoomHomeGuard::set(dir.path());
+        assert!(matches!(
+            load_full_config("loom"),
+            Err(crate::LoadError::XdgInclude(ref m)) if m.contains("cycle")
+        ));
+    }
 }
@@ -17,7 +17,7 @@ use agent_client_protocol::{
     SetSessionModeRequest, SetSessionModeResponse, SetSessionModelRequest, SetSessionModelResponse,
     StopReason,
 };
-use loom::memory::{Checkpointer, JsonSerializer, RunnableConfig, SqliteSaver};
+use loom::memory::{Checkpointer, RunnableConfig, SqliteSaver, VersionedJsonSerializer};
 use loom::state::ReActState;
 
 use async_trait::async_trait;
@@ -441,7 +441,7 @@ impl Agent for LoomAcpAgent {
             .begin_prompt(&key)
             .ok_or_else(|| agent_client_protocol::Error::new(-32602, "unknown session"))?;
 
-        let user_content =
+        let mut user_content =
             content_blocks_to_user_content(args.prompt.as_slice()).map_err(|_| {
                 agent_client_protocol::Error::new(-32602, "content_blocks parse failed")
             })?;
@@ -449,6 +449,9 @@ impl Agent for LoomAcpAgent {
         if let loom::message::UserContent::Text(ref text) = user_content {
             if let Some(cmd) = loom::command::parse(text) {
                 match cmd {
+                    loom::command::Command::Pin { text: Some(text) } => {
+                        user_content = loom::message::UserContent::Text(text).pinned();
+                    }
                     loom::command::Command::ResetContext => {
                         self.sessions.cancel_current_generation(&key);
                         tracing::info!(session_id = %args.session_id, "Context cleared via /reset command");
@@ -509,6 +512,7 @@ impl Agent for LoomAcpAgent {
                 self.agent_registry
                     .resolve_agent_name(&entry.session_config.current_agent),
             ),
+            agent_file: None,
             verbose: false,
             got_adaptive: false,
             display_max_len: 4096,
@@ -517,10 +521,19 @@ impl Agent for LoomAcpAgent {
             mcp_config_path: None,
             output_timestamp: false,
             dry_run: false,
+            role_setting: None,
+            allowed_tools: None,
+            read_only: false,
+            reply_format: None,
+            reply_schema: None,
+            messages: None,
+            history_search: None,
+            locale: None,
             provider: resolved.provider,
             base_url: resolved.base_url,
             api_key: resolved.api_key,
             provider_type: resolved.provider_type,
+            flags: Default::default(),
         };
 
         let session_id = args.session_id.clone();
@@ -583,7 +596,7 @@ impl Agent for LoomAcpAgent {
 
         // Build checkpointer to load history
         let db_path = loom::memory::default_memory_db_path();
-        let serializer = Arc::new(JsonSerializer);
+        let serializer = Arc::new(VersionedJsonSerializer);
         let checkpointer: Arc<dyn Checkpointer<ReActState>> = Arc::new(
             SqliteSaver::new(db_path.to_string_lossy().as_ref(), serializer).map_err(|e| {
                 agent_client_protocol::Error::internal_error()
@@ -602,6 +615,8 @@ impl Agent for LoomAcpAgent {
             resume_value: None,
             resume_values_by_namespace: Default::default(),
             resume_values_by_interrupt_id: Default::default(),
+            routing_seed: None,
+            flags: Default::default(),
         };
 
         // Try to load checkpoint
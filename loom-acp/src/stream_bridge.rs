@@ -174,6 +174,9 @@ where
         }
         StreamEvent::ToolOutput {
             call_id, content, ..
+        }
+        | StreamEvent::ToolOutputChunk {
+            call_id, content, ..
         } => {
             // Prefer Loom's call_id so the client can attach streamed tool output to the right tool call.
             // If call_id is missing, we keep an empty id; the notification layer will drop it.
@@ -455,6 +458,16 @@ impl SessionNotifier {
                                 TerminalId::new(terminal_id.clone()),
                             ))
                         }
+                        loom::tool_source::ToolCallContent::Image { media_type, data } => {
+                            agent_client_protocol::ToolCallContent::from(
+                                agent_client_protocol::ContentBlock::Image(
+                                    agent_client_protocol::ImageContent::new(
+                                        data.clone(),
+                                        media_type.clone(),
+                                    ),
+                                ),
+                            )
+                        }
                     };
                     let fields = ToolCallUpdateFields::new()
                         .status(ToolCallStatus::Completed)
@@ -518,6 +531,10 @@ fn tool_call_content_to_raw_output(content: &loom::tool_source::ToolCallContent)
             "type": "terminal",
             "terminalId": terminal_id,
         }),
+        loom::tool_source::ToolCallContent::Image { media_type, .. } => serde_json::json!({
+            "type": "image",
+            "mimeType": media_type,
+        }),
     }
 }
 
@@ -32,6 +32,7 @@ pub(crate) fn create_tool_spec(
         description: Some(description.to_string()),
         input_schema,
         output_hint: None,
+        catalog: Default::default(),
     }
 }
 
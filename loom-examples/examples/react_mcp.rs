@@ -81,11 +81,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         approval_result: None,
         usage: None,
         total_usage: None,
+        usage_by_model: Default::default(),
         message_count_after_last_think: None,
         last_reasoning_content: None,
         think_count: 0,
         summary: None,
         should_continue: true,
+        reflection_count: 0,
+        answer_revision: 0,
+        confidence: None,
+        approval_memory: Default::default(),
+        remembered_approvals: Default::default(),
     };
 
     let result = compiled.invoke(state, None).await?;
@@ -99,6 +99,7 @@ impl LlmClient for MemoryMockLlm {
             reasoning_content: None,
             tool_calls,
             usage: None,
+            finish_reason: None,
         })
     }
 }
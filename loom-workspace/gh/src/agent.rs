@@ -55,9 +55,16 @@ pub fn run_options_from_issues_event(
         mcp_config_path: None,
         output_timestamp: false,
         dry_run: false,
+        role_setting: None,
+        allowed_tools: None,
+        read_only: false,
+        reply_format: None,
+        reply_schema: None,
+        messages: None,
         provider: None,
         base_url: None,
         api_key: None,
         provider_type: None,
+        flags: Default::default(),
     }
 }
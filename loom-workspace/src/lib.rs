@@ -3,8 +3,17 @@
 //! - **Workspace**: container for threads (1 workspace : N threads).
 //! - **Run with workspace_id**: when serve handles a Run request with both `workspace_id` and
 //!   `thread_id`, it registers the thread in that workspace.
+//! - **Thread summaries**: serve stores a generated title + summary per thread after a run
+//!   (`set_thread_summary`); `list_threads` returns them alongside each thread.
+//! - **Defaults**: a workspace can carry default run settings ([`WorkspaceDefaults`]: model,
+//!   working folder, role, tool allowlist) that serve applies to its Run requests.
+//! - **Usage**: serve records each run's token usage ([`RunUsage`]); `usage_report` sums it by
+//!   model and agent type for a workspace and time range.
 //! - **UI**: use `list_threads(workspace_id)` to show "某 workspace 下所有对话列表".
 
 mod store;
 
-pub use store::{Store, StoreError, ThreadInWorkspace, WorkspaceMeta};
+pub use store::{
+    RunUsage, Store, StoreError, ThreadInWorkspace, ThreadSummary, UsageSummary, WorkspaceDefaults,
+    WorkspaceMeta,
+};
@@ -1,5 +1,6 @@
 //! SQLite-backed workspace store: workspaces and thread membership.
 
+use rusqlite::OptionalExtension;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::sync::{Arc, Mutex};
@@ -20,6 +21,33 @@ pub struct WorkspaceMeta {
     pub name: Option<String>,
     /// Milliseconds since Unix epoch.
     pub created_at_ms: i64,
+    /// Default run settings (empty when never set).
+    #[serde(default)]
+    pub defaults: WorkspaceDefaults,
+}
+
+/// Default run settings of a workspace, applied by serve to Run requests with this
+/// `workspace_id` that leave the matching field unset.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceDefaults {
+    /// Model (e.g. "openai/gpt-4o").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Working folder for file tools.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_folder: Option<String>,
+    /// Role instructions prepended to the system prompt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    /// Tool allowlist; `None` allows every tool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<String>>,
+}
+
+impl WorkspaceDefaults {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
 }
 
 /// Thread membership for list_threads (UI: "某 workspace 下所有对话列表").
@@ -28,6 +56,64 @@ pub struct ThreadInWorkspace {
     pub thread_id: String,
     /// Milliseconds since Unix epoch.
     pub created_at_ms: i64,
+    /// Generated title, when the thread has been summarized.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Generated short summary, when the thread has been summarized.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+/// Generated title and summary stored per thread (UI sidebar label and preview).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ThreadSummary {
+    pub thread_id: String,
+    pub title: String,
+    pub summary: String,
+    /// Milliseconds since Unix epoch.
+    pub updated_at_ms: i64,
+}
+
+/// Token usage of one finished run, recorded by serve for usage reports.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunUsage {
+    pub run_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
+    /// Model the run used (e.g. "openai/gpt-4o").
+    pub model: String,
+    /// Agent type (`react`, `dup`, `tot`, `got`).
+    pub agent: String,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+}
+
+/// Usage of all runs with one model and agent type (one row of a usage report).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageSummary {
+    pub model: String,
+    pub agent: String,
+    pub runs: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+}
+
+/// Tool allowlist column: names joined by newlines (tool names never contain one).
+fn tools_to_column(tools: Option<&[String]>) -> Option<String> {
+    tools.map(|t| t.join("\n"))
+}
+
+fn tools_from_column(column: Option<String>) -> Option<Vec<String>> {
+    column.map(|s| {
+        s.split('\n')
+            .filter(|t| !t.is_empty())
+            .map(String::from)
+            .collect()
+    })
 }
 
 fn system_time_to_i64(t: SystemTime) -> i64 {
@@ -36,6 +122,24 @@ fn system_time_to_i64(t: SystemTime) -> i64 {
         .unwrap_or(0)
 }
 
+/// Unlocks `conn` with SQLCipher `key` before any other statement. Plain SQLite ignores the
+/// key, so that case is an error rather than an unencrypted file.
+fn unlock(conn: &rusqlite::Connection, key: &str) -> Result<(), StoreError> {
+    conn.pragma_update(None, "key", key)
+        .map_err(|e| StoreError::Storage(e.to_string()))?;
+    let cipher: Option<String> = conn
+        .query_row("PRAGMA cipher_version", [], |row| row.get(0))
+        .optional()
+        .map_err(|e| StoreError::Storage(e.to_string()))?;
+    if cipher.is_none() {
+        return Err(StoreError::Storage(
+            "key given but SQLite lacks SQLCipher (enable the `sqlcipher` feature)".to_string(),
+        ));
+    }
+    conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |_| Ok(()))
+        .map_err(|e| StoreError::Storage(format!("cannot decrypt workspace database: {}", e)))
+}
+
 /// SQLite-backed workspace store. Own DB, independent of loom checkpoint/store.
 pub struct Store {
     db: Arc<Mutex<rusqlite::Connection>>,
@@ -44,9 +148,21 @@ pub struct Store {
 impl Store {
     /// Opens or creates the database and tables.
     pub fn new(path: impl AsRef<Path>) -> Result<Self, StoreError> {
-        let path = path.as_ref().to_path_buf();
-        let conn =
-            rusqlite::Connection::open(&path).map_err(|e| StoreError::Storage(e.to_string()))?;
+        let conn = rusqlite::Connection::open(path.as_ref())
+            .map_err(|e| StoreError::Storage(e.to_string()))?;
+        Self::init(conn)
+    }
+
+    /// Like [`Store::new`], with the database encrypted by SQLCipher `key`. Needs the
+    /// `sqlcipher` feature; fails when SQLite lacks SQLCipher or `key` does not open the file.
+    pub fn open_encrypted(path: impl AsRef<Path>, key: &str) -> Result<Self, StoreError> {
+        let conn = rusqlite::Connection::open(path.as_ref())
+            .map_err(|e| StoreError::Storage(e.to_string()))?;
+        unlock(&conn, key)?;
+        Self::init(conn)
+    }
+
+    fn init(conn: rusqlite::Connection) -> Result<Self, StoreError> {
         conn.execute_batch(
             r#"
             CREATE TABLE IF NOT EXISTS workspaces (
@@ -62,6 +178,33 @@ impl Store {
                 FOREIGN KEY (workspace_id) REFERENCES workspaces(id)
             );
             CREATE INDEX IF NOT EXISTS idx_workspace_threads_workspace_id ON workspace_threads(workspace_id);
+            CREATE TABLE IF NOT EXISTS thread_summaries (
+                thread_id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                summary TEXT NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS workspace_defaults (
+                workspace_id TEXT PRIMARY KEY,
+                model TEXT,
+                working_folder TEXT,
+                role TEXT,
+                tools TEXT,
+                updated_at INTEGER NOT NULL,
+                FOREIGN KEY (workspace_id) REFERENCES workspaces(id)
+            );
+            CREATE TABLE IF NOT EXISTS run_usage (
+                run_id TEXT PRIMARY KEY,
+                workspace_id TEXT,
+                thread_id TEXT,
+                model TEXT NOT NULL,
+                agent TEXT NOT NULL,
+                prompt_tokens INTEGER NOT NULL,
+                completion_tokens INTEGER NOT NULL,
+                total_tokens INTEGER NOT NULL,
+                created_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_run_usage_workspace_id ON run_usage(workspace_id, created_at);
             "#,
         )
         .map_err(|e| StoreError::Storage(e.to_string()))?;
@@ -70,6 +213,39 @@ impl Store {
         })
     }
 
+    /// In-memory database: same tables, lost when the store is dropped.
+    pub fn in_memory() -> Result<Self, StoreError> {
+        Self::new(":memory:")
+    }
+
+    /// Opens an existing database read-only: reads work, writes fail with
+    /// [`StoreError::Storage`]. Tables are not created.
+    pub fn open_read_only(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        Self::read_only(path.as_ref(), None)
+    }
+
+    /// [`Store::open_read_only`] for a database encrypted with `key` (see
+    /// [`Store::open_encrypted`]).
+    pub fn open_read_only_encrypted(path: impl AsRef<Path>, key: &str) -> Result<Self, StoreError> {
+        Self::read_only(path.as_ref(), Some(key))
+    }
+
+    fn read_only(path: &Path, key: Option<&str>) -> Result<Self, StoreError> {
+        let conn = rusqlite::Connection::open_with_flags(
+            path,
+            rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .map_err(|e| StoreError::Storage(e.to_string()))?;
+        if let Some(key) = key {
+            unlock(&conn, key)?;
+        }
+        conn.query_row("SELECT COUNT(*) FROM workspaces", [], |_| Ok(()))
+            .map_err(|e| StoreError::Storage(e.to_string()))?;
+        Ok(Self {
+            db: Arc::new(Mutex::new(conn)),
+        })
+    }
+
     /// Creates a workspace. Returns the id.
     pub async fn create_workspace(&self, name: Option<String>) -> Result<String, StoreError> {
         let id = uuid::Uuid::new_v4().to_string();
@@ -93,7 +269,9 @@ impl Store {
         tokio::task::block_in_place(|| {
             let conn = db.lock().map_err(|_| StoreError::Storage("lock".into()))?;
             let mut stmt = conn
-                .prepare("SELECT id, name, created_at FROM workspaces ORDER BY created_at ASC")
+                .prepare(
+                    "SELECT w.id, w.name, w.created_at, d.model, d.working_folder, d.role, d.tools FROM workspaces w LEFT JOIN workspace_defaults d ON d.workspace_id = w.id ORDER BY w.created_at ASC",
+                )
                 .map_err(|e| StoreError::Storage(e.to_string()))?;
             let rows = stmt
                 .query_map([], |row| {
@@ -102,6 +280,12 @@ impl Store {
                         id: row.get(0)?,
                         name: row.get(1)?,
                         created_at_ms,
+                        defaults: WorkspaceDefaults {
+                            model: row.get(3)?,
+                            working_folder: row.get(4)?,
+                            role: row.get(5)?,
+                            tools: tools_from_column(row.get(6)?),
+                        },
                     })
                 })
                 .map_err(|e| StoreError::Storage(e.to_string()))?;
@@ -121,7 +305,7 @@ impl Store {
             let conn = db.lock().map_err(|_| StoreError::Storage("lock".into()))?;
             let mut stmt = conn
                 .prepare(
-                    "SELECT thread_id, created_at FROM workspace_threads WHERE workspace_id = ?1 ORDER BY created_at DESC",
+                    "SELECT t.thread_id, t.created_at, s.title, s.summary FROM workspace_threads t LEFT JOIN thread_summaries s ON s.thread_id = t.thread_id WHERE t.workspace_id = ?1 ORDER BY t.created_at DESC",
                 )
                 .map_err(|e| StoreError::Storage(e.to_string()))?;
             let rows = stmt
@@ -130,6 +314,8 @@ impl Store {
                     Ok(ThreadInWorkspace {
                         thread_id: row.get(0)?,
                         created_at_ms,
+                        title: row.get(2)?,
+                        summary: row.get(3)?,
                     })
                 })
                 .map_err(|e| StoreError::Storage(e.to_string()))?;
@@ -178,4 +364,188 @@ impl Store {
             Ok(())
         })
     }
+
+    /// Stores (or replaces) the generated title and summary for a thread.
+    pub async fn set_thread_summary(
+        &self,
+        thread_id: &str,
+        title: &str,
+        summary: &str,
+    ) -> Result<(), StoreError> {
+        let now = system_time_to_i64(SystemTime::now());
+        let db = self.db.clone();
+        let thread_id = thread_id.to_string();
+        let title = title.to_string();
+        let summary = summary.to_string();
+        tokio::task::block_in_place(|| {
+            let conn = db.lock().map_err(|_| StoreError::Storage("lock".into()))?;
+            conn.execute(
+                "INSERT OR REPLACE INTO thread_summaries (thread_id, title, summary, updated_at) VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![thread_id, title, summary, now],
+            )
+            .map_err(|e| StoreError::Storage(e.to_string()))?;
+            Ok(())
+        })
+    }
+
+    /// Returns the stored title and summary for a thread, if any.
+    pub async fn get_thread_summary(
+        &self,
+        thread_id: &str,
+    ) -> Result<Option<ThreadSummary>, StoreError> {
+        let db = self.db.clone();
+        let thread_id = thread_id.to_string();
+        tokio::task::block_in_place(|| {
+            let conn = db.lock().map_err(|_| StoreError::Storage("lock".into()))?;
+            let mut stmt = conn
+                .prepare(
+                    "SELECT thread_id, title, summary, updated_at FROM thread_summaries WHERE thread_id = ?1",
+                )
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            let mut rows = stmt
+                .query_map(rusqlite::params![thread_id.as_str()], |row| {
+                    Ok(ThreadSummary {
+                        thread_id: row.get(0)?,
+                        title: row.get(1)?,
+                        summary: row.get(2)?,
+                        updated_at_ms: row.get(3)?,
+                    })
+                })
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            rows.next()
+                .transpose()
+                .map_err(|e| StoreError::Storage(e.to_string()))
+        })
+    }
+
+    /// Returns the workspace's default run settings (empty when never set).
+    pub async fn get_workspace_defaults(
+        &self,
+        workspace_id: &str,
+    ) -> Result<WorkspaceDefaults, StoreError> {
+        let db = self.db.clone();
+        let workspace_id = workspace_id.to_string();
+        tokio::task::block_in_place(|| {
+            let conn = db.lock().map_err(|_| StoreError::Storage("lock".into()))?;
+            let mut stmt = conn
+                .prepare(
+                    "SELECT model, working_folder, role, tools FROM workspace_defaults WHERE workspace_id = ?1",
+                )
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            let mut rows = stmt
+                .query_map(rusqlite::params![workspace_id.as_str()], |row| {
+                    Ok(WorkspaceDefaults {
+                        model: row.get(0)?,
+                        working_folder: row.get(1)?,
+                        role: row.get(2)?,
+                        tools: tools_from_column(row.get(3)?),
+                    })
+                })
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            rows.next()
+                .transpose()
+                .map(Option::unwrap_or_default)
+                .map_err(|e| StoreError::Storage(e.to_string()))
+        })
+    }
+
+    /// Replaces the workspace's default run settings. Fails with [`StoreError::NotFound`] when
+    /// the workspace does not exist.
+    pub async fn set_workspace_defaults(
+        &self,
+        workspace_id: &str,
+        defaults: &WorkspaceDefaults,
+    ) -> Result<(), StoreError> {
+        let now = system_time_to_i64(SystemTime::now());
+        let db = self.db.clone();
+        let workspace_id = workspace_id.to_string();
+        let defaults = defaults.clone();
+        tokio::task::block_in_place(|| {
+            let conn = db.lock().map_err(|_| StoreError::Storage("lock".into()))?;
+            let exists: bool = conn
+                .query_row(
+                    "SELECT EXISTS(SELECT 1 FROM workspaces WHERE id = ?1)",
+                    rusqlite::params![workspace_id.as_str()],
+                    |row| row.get(0),
+                )
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            if !exists {
+                return Err(StoreError::NotFound(format!("workspace {}", workspace_id)));
+            }
+            conn.execute(
+                "INSERT OR REPLACE INTO workspace_defaults (workspace_id, model, working_folder, role, tools, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                rusqlite::params![
+                    workspace_id,
+                    defaults.model,
+                    defaults.working_folder,
+                    defaults.role,
+                    tools_to_column(defaults.tools.as_deref()),
+                    now
+                ],
+            )
+            .map_err(|e| StoreError::Storage(e.to_string()))?;
+            Ok(())
+        })
+    }
+
+    /// Records the token usage of a finished run. Recording the same `run_id` again replaces it.
+    pub async fn record_run_usage(&self, usage: &RunUsage) -> Result<(), StoreError> {
+        let now = system_time_to_i64(SystemTime::now());
+        let db = self.db.clone();
+        let usage = usage.clone();
+        tokio::task::block_in_place(|| {
+            let conn = db.lock().map_err(|_| StoreError::Storage("lock".into()))?;
+            conn.execute(
+                "INSERT OR REPLACE INTO run_usage (run_id, workspace_id, thread_id, model, agent, prompt_tokens, completion_tokens, total_tokens, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                rusqlite::params![
+                    usage.run_id,
+                    usage.workspace_id,
+                    usage.thread_id,
+                    usage.model,
+                    usage.agent,
+                    usage.prompt_tokens as i64,
+                    usage.completion_tokens as i64,
+                    usage.total_tokens as i64,
+                    now
+                ],
+            )
+            .map_err(|e| StoreError::Storage(e.to_string()))?;
+            Ok(())
+        })
+    }
+
+    /// Sums recorded run usage by model and agent type. `workspace_id` limits the report to
+    /// runs of that workspace (`None`: all runs); `since_ms` / `until_ms` (milliseconds since
+    /// Unix epoch, inclusive / exclusive) limit it to runs recorded in that range.
+    pub async fn usage_report(
+        &self,
+        workspace_id: Option<&str>,
+        since_ms: Option<i64>,
+        until_ms: Option<i64>,
+    ) -> Result<Vec<UsageSummary>, StoreError> {
+        let db = self.db.clone();
+        let workspace_id = workspace_id.map(String::from);
+        tokio::task::block_in_place(|| {
+            let conn = db.lock().map_err(|_| StoreError::Storage("lock".into()))?;
+            let mut stmt = conn
+                .prepare(
+                    "SELECT model, agent, COUNT(*), SUM(prompt_tokens), SUM(completion_tokens), SUM(total_tokens) FROM run_usage WHERE (?1 IS NULL OR workspace_id = ?1) AND (?2 IS NULL OR created_at >= ?2) AND (?3 IS NULL OR created_at < ?3) GROUP BY model, agent ORDER BY SUM(total_tokens) DESC, model ASC, agent ASC",
+                )
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            let rows = stmt
+                .query_map(rusqlite::params![workspace_id, since_ms, until_ms], |row| {
+                    Ok(UsageSummary {
+                        model: row.get(0)?,
+                        agent: row.get(1)?,
+                        runs: row.get::<_, i64>(2)? as u64,
+                        prompt_tokens: row.get::<_, i64>(3)? as u64,
+                        completion_tokens: row.get::<_, i64>(4)? as u64,
+                        total_tokens: row.get::<_, i64>(5)? as u64,
+                    })
+                })
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            rows.collect::<Result<Vec<_>, _>>()
+                .map_err(|e| StoreError::Storage(e.to_string()))
+        })
+    }
 }
@@ -1,7 +1,7 @@
 //! Integration tests for loom_workspace::Store (DB creation, workspaces, thread membership).
 //! Uses multi_thread runtime so Store's block_in_place is allowed.
 
-use loom_workspace::Store;
+use loom_workspace::{RunUsage, Store, StoreError, UsageSummary, WorkspaceDefaults};
 use std::sync::Arc;
 use tempfile::NamedTempFile;
 
@@ -152,3 +152,180 @@ async fn list_threads_isolates_workspaces() {
     assert_eq!(threads_b.len(), 1);
     assert_eq!(threads_b[0].thread_id, "thread-b1");
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn thread_summary_set_get_and_listed_with_threads() {
+    let file = NamedTempFile::new().unwrap();
+    let store = Store::new(file.path()).unwrap();
+    let ws_id = store.create_workspace(None).await.unwrap();
+    store.add_thread_to_workspace(&ws_id, "t1").await.unwrap();
+    store.add_thread_to_workspace(&ws_id, "t2").await.unwrap();
+
+    assert!(store.get_thread_summary("t1").await.unwrap().is_none());
+
+    store
+        .set_thread_summary("t1", "First title", "First summary.")
+        .await
+        .unwrap();
+    store
+        .set_thread_summary("t1", "Trip planning", "The user planned a trip.")
+        .await
+        .unwrap();
+
+    let summary = store.get_thread_summary("t1").await.unwrap().unwrap();
+    assert_eq!(summary.title, "Trip planning");
+    assert_eq!(summary.summary, "The user planned a trip.");
+
+    let threads = store.list_threads(&ws_id).await.unwrap();
+    let t1 = threads.iter().find(|t| t.thread_id == "t1").unwrap();
+    let t2 = threads.iter().find(|t| t.thread_id == "t2").unwrap();
+    assert_eq!(t1.title.as_deref(), Some("Trip planning"));
+    assert_eq!(t1.summary.as_deref(), Some("The user planned a trip."));
+    assert!(t2.title.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn workspace_defaults_set_get_and_listed_with_workspaces() {
+    let file = NamedTempFile::new().unwrap();
+    let store = Store::new(file.path()).unwrap();
+    let ws_id = store.create_workspace(Some("docs".into())).await.unwrap();
+
+    assert!(store
+        .get_workspace_defaults(&ws_id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let defaults = WorkspaceDefaults {
+        model: Some("openai/gpt-4o".to_string()),
+        working_folder: Some("/srv/docs".to_string()),
+        role: Some("You are a technical writer.".to_string()),
+        tools: Some(vec!["read".to_string(), "grep".to_string()]),
+    };
+    store
+        .set_workspace_defaults(&ws_id, &defaults)
+        .await
+        .unwrap();
+    assert_eq!(
+        store.get_workspace_defaults(&ws_id).await.unwrap(),
+        defaults
+    );
+
+    let listed = store.list_workspaces().await.unwrap();
+    assert_eq!(listed[0].defaults, defaults);
+
+    let err = store
+        .set_workspace_defaults("missing", &defaults)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, StoreError::NotFound(_)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn usage_report_groups_by_model_and_agent_per_workspace() {
+    let file = NamedTempFile::new().unwrap();
+    let store = Store::new(file.path()).unwrap();
+    let usage = |run_id: &str, workspace_id: &str, model: &str, tokens: u64| RunUsage {
+        run_id: run_id.to_string(),
+        workspace_id: Some(workspace_id.to_string()),
+        thread_id: None,
+        model: model.to_string(),
+        agent: "react".to_string(),
+        prompt_tokens: tokens,
+        completion_tokens: 1,
+        total_tokens: tokens + 1,
+    };
+    for u in [
+        usage("run-1", "ws-1", "openai/gpt-4o", 10),
+        usage("run-2", "ws-1", "openai/gpt-4o", 20),
+        usage("run-3", "ws-1", "openai/gpt-4o-mini", 5),
+        usage("run-4", "ws-2", "openai/gpt-4o", 100),
+    ] {
+        store.record_run_usage(&u).await.unwrap();
+    }
+
+    let report = store.usage_report(Some("ws-1"), None, None).await.unwrap();
+    assert_eq!(
+        report,
+        vec![
+            UsageSummary {
+                model: "openai/gpt-4o".to_string(),
+                agent: "react".to_string(),
+                runs: 2,
+                prompt_tokens: 30,
+                completion_tokens: 2,
+                total_tokens: 32,
+            },
+            UsageSummary {
+                model: "openai/gpt-4o-mini".to_string(),
+                agent: "react".to_string(),
+                runs: 1,
+                prompt_tokens: 5,
+                completion_tokens: 1,
+                total_tokens: 6,
+            },
+        ]
+    );
+
+    let all = store.usage_report(None, None, None).await.unwrap();
+    assert_eq!(all[0].runs, 3);
+    assert_eq!(all[0].total_tokens, 133);
+
+    let future = store
+        .usage_report(Some("ws-1"), Some(i64::MAX), None)
+        .await
+        .unwrap();
+    assert!(future.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn read_only_store_reads_but_rejects_writes() {
+    let file = NamedTempFile::new().unwrap();
+    let id = Store::new(file.path())
+        .unwrap()
+        .create_workspace(Some("ws1".into()))
+        .await
+        .unwrap();
+
+    let store = Store::open_read_only(file.path()).unwrap();
+    let list = store.list_workspaces().await.unwrap();
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0].id, id);
+    assert!(matches!(
+        store.create_workspace(None).await,
+        Err(StoreError::Storage(_))
+    ));
+    assert!(Store::open_read_only("/nonexistent/dir/workspace.db").is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn in_memory_store_works_without_a_file() {
+    let store = Store::in_memory().unwrap();
+    let id = store.create_workspace(None).await.unwrap();
+    assert_eq!(store.list_workspaces().await.unwrap()[0].id, id);
+}
+
+#[cfg(not(feature = "sqlcipher"))]
+#[test]
+fn encrypted_store_needs_sqlcipher() {
+    let file = NamedTempFile::new().unwrap();
+    match Store::open_encrypted(file.path(), "key") {
+        Err(StoreError::Storage(e)) => assert!(e.contains("SQLCipher"), "{}", e),
+        _ => panic!("expected SQLCipher error"),
+    }
+}
+
+#[cfg(feature = "sqlcipher")]
+#[tokio::test(flavor = "multi_thread")]
+async fn encrypted_store_reopens_only_with_its_key() {
+    let file = NamedTempFile::new().unwrap();
+    let id = Store::open_encrypted(file.path(), "right")
+        .unwrap()
+        .create_workspace(Some("secret-ws".into()))
+        .await
+        .unwrap();
+    assert!(Store::new(file.path()).is_err());
+    assert!(Store::open_encrypted(file.path(), "wrong").is_err());
+    let store = Store::open_read_only_encrypted(file.path(), "right").unwrap();
+    assert_eq!(store.list_workspaces().await.unwrap()[0].id, id);
+}
@@ -10,6 +10,7 @@ use async_trait::async_trait;
 use crate::agent::react::{ActNode, HandleToolErrors, ObserveNode, ThinkNode};
 use crate::error::AgentError;
 use crate::graph::Next;
+use crate::state::ReActState;
 use crate::Node;
 use crate::{helve::ApprovalPolicy, tool_source::ToolSource};
 
@@ -39,7 +40,7 @@ impl Node<DupState> for PlanNode {
         Ok((
             DupState {
                 core: core_out,
-                understood: state.understood,
+                ..state
             },
             next,
         ))
@@ -75,24 +76,46 @@ impl Node<DupState> for DupActNode {
         Ok((
             DupState {
                 core: core_out,
-                understood: state.understood,
+                ..state
             },
             next,
         ))
     }
 }
 
-/// Observe node: adapts ObserveNode for DupState. Loops back to plan.
+/// Consecutive failures of the same step after which observe routes to replan.
+pub const DEFAULT_REPLAN_AFTER_FAILURES: u32 = 2;
+
+/// Maximum re-plans per run; after that, failures loop back to plan as before.
+pub const DEFAULT_MAX_REPLANS: u32 = 2;
+
+/// Longest error text kept as re-plan context.
+const MAX_FAILURE_CONTEXT_CHARS: usize = 500;
+
+/// Observe node: adapts ObserveNode for DupState. Loops back to plan, or to replan when the
+/// same step (tool) has failed `replan_after_failures` act steps in a row and fewer than
+/// `max_replans` re-plans were done.
 pub struct DupObserveNode {
     observe: ObserveNode,
+    replan_after_failures: u32,
+    max_replans: u32,
 }
 
 impl DupObserveNode {
     pub fn new() -> Self {
         Self {
             observe: ObserveNode::with_loop(),
+            replan_after_failures: DEFAULT_REPLAN_AFTER_FAILURES,
+            max_replans: DEFAULT_MAX_REPLANS,
         }
     }
+
+    /// Sets when to re-plan and how often; `max_replans == 0` disables re-planning.
+    pub fn with_replan_limits(mut self, replan_after_failures: u32, max_replans: u32) -> Self {
+        self.replan_after_failures = replan_after_failures.max(1);
+        self.max_replans = max_replans;
+        self
+    }
 }
 
 impl Default for DupObserveNode {
@@ -101,25 +124,97 @@ impl Default for DupObserveNode {
     }
 }
 
+/// First failing tool result of the act step: (tool name, error text).
+fn first_step_failure(core: &ReActState) -> Option<(String, String)> {
+    let failed = core.tool_results.iter().find(|r| r.is_error)?;
+    let name = failed
+        .name
+        .clone()
+        .unwrap_or_else(|| "unknown tool".to_string());
+    let error = failed
+        .content
+        .chars()
+        .take(MAX_FAILURE_CONTEXT_CHARS)
+        .collect();
+    Some((name, error))
+}
+
 #[async_trait]
 impl Node<DupState> for DupObserveNode {
     fn id(&self) -> &str {
         "observe"
     }
 
-    async fn run(&self, state: DupState) -> Result<(DupState, Next), AgentError> {
+    async fn run(&self, mut state: DupState) -> Result<(DupState, Next), AgentError> {
+        state.replan.record_step(first_step_failure(&state.core));
         let (core_out, next) = self.observe.run(state.core).await?;
-        // Map Next::Node("think") to Next::Node("plan") for DUP graph
-        let mapped_next = match &next {
+        // Map Next::Node("think") to Next::Node("plan") for DUP graph; divert the loop back to
+        // plan through replan when a step keeps failing.
+        let replan = state.replan.failure_streak >= self.replan_after_failures
+            && state.replan.replan_count < self.max_replans;
+        let mapped_next = match next {
+            Next::End => Next::End,
+            _ if replan => Next::Node("replan".into()),
             Next::Node(id) if id == "think" => Next::Node("plan".into()),
-            other => other.clone(),
+            other => other,
         };
         Ok((
             DupState {
                 core: core_out,
-                understood: state.understood,
+                ..state
             },
             mapped_next,
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{ToolCall, ToolResult};
+
+    fn failed_step(name: &str) -> DupState {
+        DupState {
+            core: ReActState {
+                tool_calls: vec![ToolCall {
+                    name: name.to_string(),
+                    arguments: "{}".to_string(),
+                    id: Some("c1".to_string()),
+                }],
+                tool_results: vec![ToolResult {
+                    name: Some(name.to_string()),
+                    content: "boom".to_string(),
+                    is_error: true,
+                    ..ToolResult::default()
+                }],
+                ..ReActState::default()
+            },
+            understood: None,
+            replan: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn observe_routes_to_replan_after_repeated_failures_of_a_step() {
+        let node = DupObserveNode::new().with_replan_limits(2, 1);
+        let (state, next) = node.run(failed_step("bash")).await.unwrap();
+        assert!(matches!(next, Next::Continue));
+
+        let retry = DupState {
+            core: failed_step("bash").core,
+            ..state
+        };
+        let (mut state, next) = node.run(retry).await.unwrap();
+        assert!(matches!(next, Next::Node(ref id) if id == "replan"));
+        assert_eq!(state.replan.failed_step.as_deref(), Some("bash"));
+
+        // Re-plan budget used up: keep looping to plan.
+        state.replan.replan_count = 1;
+        let retry = DupState {
+            core: failed_step("bash").core,
+            ..state
+        };
+        let (_, next) = node.run(retry).await.unwrap();
+        assert!(matches!(next, Next::Continue));
+    }
+}
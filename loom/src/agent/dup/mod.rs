@@ -1,14 +1,17 @@
 //! DUP (Deeply Understanding Problems) graph and runner.
 //!
-//! Adds an Understand node before the plan/act/observe loop.
+//! Adds an Understand node before the plan/act/observe loop, and a Replan node that replaces
+//! the plan when the same step keeps failing.
 
 mod adapter_nodes;
 mod prompt;
+mod replan_node;
 mod runner;
 mod state;
 mod understand_node;
 
 pub use prompt::DUP_UNDERSTAND_PROMPT;
+pub use replan_node::{ReplanNode, REPLAN_FEEDBACK_PREFIX};
 pub use runner::{build_dup_initial_state, DupRunError, DupRunner};
-pub use state::{DupState, UnderstandOutput};
+pub use state::{DupState, ReplanState, UnderstandOutput};
 pub use understand_node::UnderstandNode;
@@ -0,0 +1,114 @@
+//! Replan node: turns a repeatedly failing plan step into a request for a new plan.
+//!
+//! Reached from observe when the same step failed several act steps in a row. Appends a user
+//! message with the failure context to `core.messages` (the plan node then plans again),
+//! counts the re-plan and emits a `step_progress` Custom event.
+
+use async_trait::async_trait;
+use serde_json::json;
+use tracing::debug;
+
+use crate::agent::react::STEP_PROGRESS_EVENT_TYPE;
+use crate::error::AgentError;
+use crate::graph::{Next, RunContext};
+use crate::message::Message;
+use crate::Node;
+
+use super::state::DupState;
+
+/// Prefix of the user message the replan node appends to `core.messages`.
+pub const REPLAN_FEEDBACK_PREFIX: &str = "[Re-plan]";
+
+/// Replan node: asks the planner to drop the failing approach and plan again.
+#[derive(Debug, Default)]
+pub struct ReplanNode;
+
+impl ReplanNode {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn feedback(state: &DupState) -> String {
+        let step = state.replan.failed_step.as_deref().unwrap_or("a step");
+        let error = state
+            .replan
+            .last_error
+            .as_deref()
+            .unwrap_or("no error text");
+        format!(
+            "{} The step `{}` has failed {} times in a row. Last error:\n{}\n\nStop retrying it. Review the goal, then make a new plan that takes a different approach.",
+            REPLAN_FEEDBACK_PREFIX, step, state.replan.failure_streak, error
+        )
+    }
+}
+
+#[async_trait]
+impl Node<DupState> for ReplanNode {
+    fn id(&self) -> &str {
+        "replan"
+    }
+
+    async fn run(&self, state: DupState) -> Result<(DupState, Next), AgentError> {
+        let ctx = RunContext::new(crate::memory::RunnableConfig::default());
+        self.run_with_context(state, &ctx).await
+    }
+
+    async fn run_with_context(
+        &self,
+        mut state: DupState,
+        ctx: &RunContext<DupState>,
+    ) -> Result<(DupState, Next), AgentError> {
+        let feedback = Self::feedback(&state);
+        state.replan.replan_count += 1;
+        debug!(
+            step = ?state.replan.failed_step,
+            replan_count = state.replan.replan_count,
+            "Re-planning after repeated step failure"
+        );
+        let _ = ctx
+            .emit_custom(json!({
+                "type": STEP_PROGRESS_EVENT_TYPE,
+                "node_id": "replan",
+                "tool_name": state.replan.failed_step,
+                "summary": format!(
+                    "Re-planning (attempt {}) after repeated failures",
+                    state.replan.replan_count
+                ),
+            }))
+            .await;
+        state.core.messages.push(Message::user(feedback));
+        state.replan.failed_step = None;
+        state.replan.failure_streak = 0;
+        state.replan.last_error = None;
+        Ok((state, Next::Node("plan".into())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::dup::state::ReplanState;
+    use crate::ReActState;
+
+    #[tokio::test]
+    async fn replan_appends_failure_context_and_counts() {
+        let state = DupState {
+            core: ReActState::default(),
+            understood: None,
+            replan: ReplanState {
+                failed_step: Some("bash".to_string()),
+                failure_streak: 2,
+                last_error: Some("command not found".to_string()),
+                replan_count: 0,
+            },
+        };
+        let (out, next) = ReplanNode::new().run(state).await.unwrap();
+        assert!(matches!(next, Next::Node(ref id) if id == "plan"));
+        assert_eq!(out.replan.replan_count, 1);
+        assert_eq!(out.replan.failure_streak, 0);
+        let msg = out.core.messages.last().unwrap().content();
+        assert!(msg.starts_with(REPLAN_FEEDBACK_PREFIX));
+        assert!(msg.contains("`bash`"));
+        assert!(msg.contains("command not found"));
+    }
+}
@@ -1,6 +1,7 @@
 //! DUP graph runner: build, initial state, invoke and stream.
 //!
-//! Graph: START → understand → plan → [tools_condition] → act | end, observe → plan.
+//! Graph: START → understand → plan → [tools_condition] → act | end, observe → plan | replan,
+//! replan → plan. Observe goes to replan when a step keeps failing (see `DupObserveNode`).
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -12,6 +13,7 @@ use crate::graph::{CompilationError, CompiledStateGraph, LoggingNodeMiddleware};
 use crate::helve::ApprovalPolicy;
 use crate::memory::{CheckpointError, Checkpointer, RunnableConfig, Store};
 use crate::message::Message;
+use crate::run_hooks::RunHooks;
 use crate::runner_common::{self, load_from_checkpoint_or_build};
 use crate::stream::StreamEvent;
 use crate::tool_source::ToolSource;
@@ -19,7 +21,8 @@ use crate::LlmClient;
 use crate::{StateGraph, END, START};
 
 use super::adapter_nodes::{DupActNode, DupObserveNode, PlanNode};
-use super::state::DupState;
+use super::replan_node::ReplanNode;
+use super::state::{DupState, ReplanState};
 use super::understand_node::UnderstandNode;
 
 /// Condition for DUP graph: route based on state.core.tool_calls.
@@ -57,12 +60,14 @@ pub async fn build_dup_initial_state(
             Ok(DupState {
                 core,
                 understood: None,
+                replan: ReplanState::default(),
             })
         },
         |mut state, msg| {
             state.core.messages.push(Message::user(msg));
             state.core.tool_calls = vec![];
             state.core.tool_results = vec![];
+            state.replan = ReplanState::default();
             state
         },
     )
@@ -89,6 +94,7 @@ pub struct DupRunner {
     runnable_config: Option<RunnableConfig>,
     system_prompt: Option<String>,
     cancellation: Option<CancellationToken>,
+    hooks: RunHooks<DupState>,
 }
 
 /// Wraps Arc<dyn LlmClient> to share one LLM between UnderstandNode and PlanNode.
@@ -109,6 +115,9 @@ impl LlmClient for SharedLlm {
     ) -> Result<crate::llm::LlmResponse, crate::error::AgentError> {
         self.0.invoke_stream(messages, tx).await
     }
+    fn tool_support(&self) -> crate::model_spec::ToolSupport {
+        self.0.tool_support()
+    }
 }
 
 impl DupRunner {
@@ -117,6 +126,12 @@ impl DupRunner {
         self
     }
 
+    /// Runs `hooks` when a run starts, finishes or fails (see [`RunHooks`]).
+    pub fn with_hooks(mut self, hooks: RunHooks<DupState>) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
     /// Creates a DUP runner with the given LLM, tool source, and optional persistence.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
@@ -134,6 +149,7 @@ impl DupRunner {
         let plan = PlanNode::new(Box::new(SharedLlm(llm)));
         let act = DupActNode::new(tool_source).with_approval_policy(approval_policy);
         let observe = DupObserveNode::new();
+        let replan = ReplanNode::new();
 
         let mut graph = StateGraph::<DupState>::new();
         if let Some(s) = store {
@@ -150,6 +166,7 @@ impl DupRunner {
             .add_node("plan", Arc::new(plan))
             .add_node("act", Arc::new(act))
             .add_node("observe", Arc::new(observe))
+            .add_node("replan", Arc::new(replan))
             .add_edge(START, "understand")
             .add_edge("understand", "plan")
             .add_conditional_edges(
@@ -158,7 +175,8 @@ impl DupRunner {
                 Some(plan_condition_path_map),
             )
             .add_edge("act", "observe")
-            .add_edge("observe", "plan");
+            .add_edge("observe", "plan")
+            .add_edge("replan", "plan");
 
         let graph = if verbose {
             graph.with_middleware(Arc::new(LoggingNodeMiddleware::<DupState>::default()))
@@ -181,9 +199,22 @@ impl DupRunner {
             runnable_config,
             system_prompt,
             cancellation,
+            hooks: RunHooks::default(),
         })
     }
 
+    /// Returns the latest checkpointed state for `thread_id`, or `None` when the runner has
+    /// no checkpointer or the thread has not been checkpointed yet. Does not run the graph,
+    /// so it is safe to call while another task is streaming on the same runner.
+    pub async fn state_snapshot(&self, thread_id: &str) -> Result<Option<DupState>, DupRunError> {
+        Ok(runner_common::load_state_snapshot(
+            self.checkpointer.as_deref(),
+            self.runnable_config.as_ref(),
+            thread_id,
+        )
+        .await?)
+    }
+
     /// Invokes the graph with the given user message.
     pub async fn invoke(&self, user_message: &str) -> Result<DupState, DupRunError> {
         self.invoke_with_config(user_message, None).await
@@ -203,7 +234,9 @@ impl DupRunner {
             self.system_prompt.as_deref(),
         )
         .await?;
-        let final_state = self.compiled.invoke(state, run_config).await?;
+        let final_state =
+            runner_common::invoke_with_hooks(&self.compiled, state, run_config, &self.hooks)
+                .await?;
         Ok(final_state)
     }
 
@@ -244,6 +277,7 @@ impl DupRunner {
             on_event,
             self.cancellation.clone(),
             None,
+            &self.hooks,
         )
         .await
         .map_err(|e| match e {
@@ -266,6 +300,7 @@ mod tests {
         let no_tools = DupState {
             core: crate::ReActState::default(),
             understood: None,
+            replan: ReplanState::default(),
         };
         assert_eq!(dup_tools_condition(&no_tools), END);
 
@@ -279,6 +314,7 @@ mod tests {
                 ..crate::ReActState::default()
             },
             understood: None,
+            replan: ReplanState::default(),
         };
         assert_eq!(dup_tools_condition(&with_tools), "act");
     }
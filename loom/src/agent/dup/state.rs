@@ -4,7 +4,8 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::state::ReActState;
+use crate::memory::{StateMigrations, VersionedState};
+use crate::state::{migrate_core_react_state_v1, ReActState};
 
 /// Structured output from the Understand node (DUP phase 1–2).
 ///
@@ -20,6 +21,44 @@ pub struct UnderstandOutput {
     pub relevant_context: String,
 }
 
+/// Tracks repeated failures of a plan step so a dead plan is replaced instead of retried.
+///
+/// Written by the observe node after each act step; read and updated by the replan node.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReplanState {
+    /// Tool name of the step that failed in the latest act step, if any.
+    pub failed_step: Option<String>,
+    /// Consecutive act steps in which `failed_step` failed.
+    pub failure_streak: u32,
+    /// Error text of the latest failure of `failed_step`.
+    pub last_error: Option<String>,
+    /// Re-plans done in this run; bounded by the observe node.
+    pub replan_count: u32,
+}
+
+impl ReplanState {
+    /// Records the outcome of one act step: `failure` is the first failing step's tool name and
+    /// error, `None` when every tool call succeeded.
+    pub fn record_step(&mut self, failure: Option<(String, String)>) {
+        match failure {
+            Some((step, error)) => {
+                if self.failed_step.as_deref() == Some(step.as_str()) {
+                    self.failure_streak += 1;
+                } else {
+                    self.failed_step = Some(step);
+                    self.failure_streak = 1;
+                }
+                self.last_error = Some(error);
+            }
+            None => {
+                self.failed_step = None;
+                self.failure_streak = 0;
+                self.last_error = None;
+            }
+        }
+    }
+}
+
 /// State for the DUP graph: core execution state plus optional understanding.
 ///
 /// Composes `ReActState` (as `core`) with `UnderstandOutput` (as `understood`).
@@ -34,6 +73,17 @@ pub struct DupState {
     /// Understanding output from the understand node. Set after understand runs.
     #[serde(default)]
     pub understood: Option<UnderstandOutput>,
+    /// Repeated-failure tracking for re-planning.
+    #[serde(default)]
+    pub replan: ReplanState,
+}
+
+impl VersionedState for DupState {
+    const STATE_VERSION: u32 = 2;
+
+    fn migrations() -> StateMigrations {
+        StateMigrations::new().register(1, migrate_core_react_state_v1)
+    }
 }
 
 impl DupState {
@@ -54,11 +104,21 @@ mod tests {
     use crate::state::ReActState;
     use crate::Message;
 
+    #[test]
+    fn legacy_checkpoint_migrates_core() {
+        use crate::memory::{Serializer, VersionedJsonSerializer};
+
+        let legacy = br#"{"core":{"messages":[]},"understood":null}"#;
+        let state: DupState = VersionedJsonSerializer.deserialize(legacy).unwrap();
+        assert!(state.core.should_continue);
+    }
+
     #[test]
     fn last_assistant_reply_delegates_to_core() {
         let mut state = DupState {
             core: ReActState::default(),
             understood: None,
+            replan: ReplanState::default(),
         };
         assert!(state.last_assistant_reply().is_none());
         state
@@ -80,10 +140,28 @@ mod tests {
                 ..Default::default()
             },
             understood: None,
+            replan: ReplanState::default(),
         };
         assert_eq!(state.last_reasoning_content().as_deref(), Some("thinking"));
     }
 
+    #[test]
+    fn record_step_counts_consecutive_failures_of_the_same_step() {
+        let mut replan = ReplanState::default();
+        replan.record_step(Some(("bash".to_string(), "exit 1".to_string())));
+        replan.record_step(Some(("bash".to_string(), "exit 2".to_string())));
+        assert_eq!(replan.failure_streak, 2);
+        assert_eq!(replan.last_error.as_deref(), Some("exit 2"));
+
+        replan.record_step(Some(("read_file".to_string(), "missing".to_string())));
+        assert_eq!(replan.failed_step.as_deref(), Some("read_file"));
+        assert_eq!(replan.failure_streak, 1);
+
+        replan.record_step(None);
+        assert_eq!(replan.failure_streak, 0);
+        assert!(replan.failed_step.is_none());
+    }
+
     #[test]
     fn serialization_round_trip() {
         let state = DupState {
@@ -93,6 +171,7 @@ mod tests {
                 key_constraints: vec!["c1".to_string()],
                 relevant_context: "ctx".to_string(),
             }),
+            replan: ReplanState::default(),
         };
         let json = serde_json::to_string(&state).unwrap();
         let restored: DupState = serde_json::from_str(&json).unwrap();
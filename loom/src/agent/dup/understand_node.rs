@@ -134,6 +134,7 @@ impl Node<DupState> for UnderstandNode {
         let new_state = DupState {
             core,
             understood: Some(understood),
+            replan: state.replan,
         };
 
         Ok((new_state, Next::Continue))
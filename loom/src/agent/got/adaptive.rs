@@ -12,7 +12,7 @@ use crate::LlmClient;
 
 use super::dag::{append_subgraph, AppendSubgraphError};
 use super::prompt::AGOT_EXPAND_SYSTEM;
-use super::state::{GotState, TaskNode, TaskNodeState, TaskStatus};
+use super::state::{response_tokens, GotState, TaskNode, TaskNodeState, TaskStatus};
 
 /// Max total nodes to prevent runaway expansion (AGoT risk mitigation).
 const MAX_TOTAL_NODES: usize = 64;
@@ -37,13 +37,15 @@ pub enum ComplexityLevel {
 ///
 /// Uses the LLM to classify the node as simple or complex (aligns with AGoT paper).
 ///
+/// Also returns the tokens the call used.
+///
 /// **Interaction**: Called by ExecuteGraphNode when `agot_llm_complexity` is true; result is
 /// passed as `complexity_override` to `maybe_expand` so the heuristic is not used.
 pub async fn complexity_score_via_llm(
     llm: Arc<dyn LlmClient>,
     node: &TaskNode,
     _ctx: &ExpandContext<'_>,
-) -> Result<ComplexityLevel, AgentError> {
+) -> Result<(ComplexityLevel, u64), AgentError> {
     let user = format!(
         "{}\nNode id: {}\nDescription: {}",
         AGOT_COMPLEXITY_PROMPT, node.id, node.description
@@ -54,11 +56,12 @@ pub async fn complexity_score_via_llm(
     ];
     let response = llm.invoke(&messages).await?;
     let content = response.content.trim().to_lowercase();
-    if content.contains("complex") {
-        Ok(ComplexityLevel::Complex)
+    let level = if content.contains("complex") {
+        ComplexityLevel::Complex
     } else {
-        Ok(ComplexityLevel::Simple)
-    }
+        ComplexityLevel::Simple
+    };
+    Ok((level, response_tokens(&response)))
 }
 
 /// **Interaction**: Called by `maybe_expand` before deciding to call LLM expand (when no override).
@@ -107,15 +110,17 @@ pub struct ExpandResult {
 ///
 /// Uses [`AGOT_EXPAND_SYSTEM`] prompt. The LLM returns short node ids (e.g. step1,
 /// step2); we prefix them with `parent_id` to avoid collisions. Returns `None` on
-/// parse failure or empty result.
+/// parse failure or empty result. New nodes inherit the parent's `allowed_tools`. Also returns
+/// the tokens the call used.
 ///
 /// **Interaction**: Called by ExecuteGraphNode before `maybe_expand` when adaptive
 /// and complexity is Complex.
+#[allow(clippy::type_complexity)]
 pub async fn expand_node_via_llm(
     llm: Arc<dyn LlmClient>,
     ctx: &ExpandContext<'_>,
     node: &TaskNode,
-) -> Result<Option<(Vec<TaskNode>, Vec<(String, String)>)>, AgentError> {
+) -> Result<(Option<(Vec<TaskNode>, Vec<(String, String)>)>, u64), AgentError> {
     let user_content = format!(
         r#"Parent node id: {}
 Parent description: {}
@@ -134,7 +139,14 @@ Output JSON with "nodes" and "edges". Node ids must be short (e.g. step1, step2)
     let response = llm.invoke(&messages).await?;
     let raw = response.content.trim();
 
-    parse_expand_output(raw, ctx.node_id)
+    let expanded = parse_expand_output(raw, ctx.node_id)?;
+    let expanded = expanded.map(|(mut nodes, edges)| {
+        for n in &mut nodes {
+            n.allowed_tools = node.allowed_tools.clone();
+        }
+        (nodes, edges)
+    });
+    Ok((expanded, response_tokens(&response)))
 }
 
 /// Parses LLM expand output into (nodes, edges) with prefixed node ids.
@@ -178,6 +190,7 @@ fn parse_expand_output(
             id: prefixed_id,
             description: n.description.unwrap_or_default(),
             tool_calls: vec![],
+            allowed_tools: None,
         });
     }
 
@@ -298,6 +311,7 @@ mod tests {
             id: id.to_string(),
             description: desc.to_string(),
             tool_calls: vec![],
+            allowed_tools: None,
         }
     }
 
@@ -355,6 +369,8 @@ mod tests {
             )]
             .into_iter()
             .collect(),
+            token_budget: None,
+            tokens_used: 0,
         };
         let r = maybe_expand(&mut state, "a", "r", false, None, |_| {
             Some((vec![node("a1", "x")], vec![("a".into(), "a1".into())]))
@@ -382,6 +398,8 @@ mod tests {
             )]
             .into_iter()
             .collect(),
+            token_budget: None,
+            tokens_used: 0,
         };
         let r = maybe_expand(&mut state, "a", "r", true, None, |_| {
             Some((vec![node("a1", "x")], vec![("a".into(), "a1".into())]))
@@ -403,7 +421,7 @@ mod tests {
             input_message: "Overall task",
         };
         let node = node("analyze", "Analyze and compare");
-        let out = expand_node_via_llm(llm, &ctx, &node).await.unwrap();
+        let (out, _tokens) = expand_node_via_llm(llm, &ctx, &node).await.unwrap();
         let (nodes, edges) = out.unwrap();
         assert_eq!(nodes.len(), 2);
         assert_eq!(nodes[0].id, "analyze_sub_1");
@@ -432,6 +450,8 @@ mod tests {
             )]
             .into_iter()
             .collect(),
+            token_budget: None,
+            tokens_used: 0,
         };
         let r = maybe_expand(
             &mut state,
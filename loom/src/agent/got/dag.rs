@@ -161,6 +161,7 @@ mod tests {
             id: id.to_string(),
             description: desc.to_string(),
             tool_calls: vec![],
+            allowed_tools: None,
         }
     }
 
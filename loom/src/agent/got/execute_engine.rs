@@ -1,7 +1,9 @@
 //! ExecuteGraph node: run task nodes in DAG order; each sub-task uses ReAct.
 //!
-//! Computes ready nodes, runs one (or more) per step, writes node_states.
-//! Emits GotNodeStart, GotNodeComplete, GotNodeFailed.
+//! Computes ready nodes, runs one (or more) per step, writes node_states and the tokens used.
+//! Emits GotNodeStart, GotNodeComplete, GotNodeFailed. Once the run's token budget is used up,
+//! AGoT expansion stops and each would-be expansion is reported as a denied GotExpand. A node
+//! with `allowed_tools` runs against an [`AllowedToolsSource`] view of the shared tool source.
 
 use std::sync::Arc;
 
@@ -13,7 +15,10 @@ use crate::graph::{Next, RunContext};
 use crate::message::Message;
 use crate::state::ReActState;
 use crate::stream::{StreamEvent, StreamMode};
-use crate::tool_source::ToolSource;
+use crate::tool_source::{
+    AllowedToolsSource, ToolCallContent, ToolCallContext, ToolSource, ToolSourceError,
+    ToolSourceHealth, ToolSpec,
+};
 use crate::LlmClient;
 use crate::Node;
 
@@ -82,6 +87,23 @@ pub(crate) fn build_sub_task_user_message(
     }
 
     parts.push(format!("Sub-task: {}", description));
+
+    let allowed_tools = state
+        .task_graph
+        .nodes
+        .iter()
+        .find(|n| n.id == node_id)
+        .and_then(|n| n.allowed_tools.as_ref());
+    match allowed_tools {
+        Some(tools) if tools.is_empty() => {
+            parts.push("No tools are available for this sub-task.".to_string())
+        }
+        Some(tools) => parts.push(format!(
+            "Only these tools are available for this sub-task: {}",
+            tools.join(", ")
+        )),
+        None => {}
+    }
     parts.join("\n\n")
 }
 
@@ -103,17 +125,61 @@ impl LlmClient for SharedLlm {
     ) -> Result<crate::llm::LlmResponse, AgentError> {
         self.0.invoke_stream(messages, tx).await
     }
+    fn set_tools(&self, tools: Vec<ToolSpec>) {
+        self.0.set_tools(tools);
+    }
+    fn tool_support(&self) -> crate::model_spec::ToolSupport {
+        self.0.tool_support()
+    }
+}
+
+/// Wraps Arc<dyn ToolSource> for use as Box<dyn ToolSource> in ActNode.
+struct SharedToolSource(Arc<dyn ToolSource>);
+
+#[async_trait::async_trait]
+impl ToolSource for SharedToolSource {
+    async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+        self.0.list_tools().await
+    }
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: serde_json::Value,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        self.0.call_tool(name, arguments).await
+    }
+    async fn call_tool_with_context(
+        &self,
+        name: &str,
+        arguments: serde_json::Value,
+        ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        self.0.call_tool_with_context(name, arguments, ctx).await
+    }
+    fn set_call_context(&self, ctx: Option<ToolCallContext>) {
+        self.0.set_call_context(ctx);
+    }
+    async fn refresh_tools(&self) -> Result<Option<Vec<ToolSpec>>, ToolSourceError> {
+        self.0.refresh_tools().await
+    }
+    async fn tools_for_turn(&self, query: &str) -> Result<Option<Vec<ToolSpec>>, ToolSourceError> {
+        self.0.tools_for_turn(query).await
+    }
+    async fn health(&self) -> ToolSourceHealth {
+        self.0.health().await
+    }
 }
 
 /// ExecuteGraph node: runs ready DAG nodes one at a time; each node runs as a ReAct sub-task.
 ///
-/// Holds LLM and ToolSource to run Think → Act → Observe for each task node.
+/// Holds LLM and ToolSource to run Think → Act → Observe for each task node. The Act step of
+/// each node gets only the node's `allowed_tools` when the planner set them.
 /// Emits GotNodeStart / GotNodeComplete / GotNodeFailed when Custom mode is enabled.
 /// When `adaptive` is true (AGoT), may expand complex nodes into subgraphs after completion.
 /// When `agot_llm_complexity` is true, complexity is decided by LLM instead of heuristic.
 pub struct ExecuteGraphNode {
     think: ThinkNode,
-    act: ActNode,
+    tool_source: Arc<dyn ToolSource>,
     observe: ObserveNode,
     adaptive: bool,
     agot_llm_complexity: bool,
@@ -128,16 +194,15 @@ impl ExecuteGraphNode {
     /// classify simple vs complex instead of the heuristic.
     pub fn new(
         llm: Arc<dyn LlmClient>,
-        tool_source: Box<dyn ToolSource>,
+        tool_source: Arc<dyn ToolSource>,
         adaptive: bool,
         agot_llm_complexity: bool,
     ) -> Self {
         let think = ThinkNode::new(Arc::new(SharedLlm(Arc::clone(&llm))));
-        let act = ActNode::new(tool_source).with_handle_tool_errors(HandleToolErrors::Always(None));
         let observe = ObserveNode::with_loop();
         Self {
             think,
-            act,
+            tool_source,
             observe,
             adaptive,
             agot_llm_complexity,
@@ -145,11 +210,30 @@ impl ExecuteGraphNode {
         }
     }
 
+    /// Act node for one task node: restricted to `allowed_tools` when set, else every tool.
+    fn act_node(&self, allowed_tools: Option<&[String]>) -> ActNode {
+        let tools: Box<dyn ToolSource> = match allowed_tools {
+            Some(names) => Box::new(AllowedToolsSource::new(
+                Arc::clone(&self.tool_source),
+                names.iter().cloned(),
+            )),
+            None => Box::new(SharedToolSource(Arc::clone(&self.tool_source))),
+        };
+        ActNode::new(tools).with_handle_tool_errors(HandleToolErrors::Always(None))
+    }
+
     /// Runs one sub-task (ReAct loop until no tool_calls or max turns).
     ///
     /// `user_message` is the full user content for the sub-task (task goal, predecessor
     /// results, and this node's description). Built by [`build_sub_task_user_message`].
-    async fn run_sub_task(&self, user_message: &str) -> Result<String, AgentError> {
+    /// Tool calls outside `allowed_tools` fail and are reported back to the model.
+    /// Returns the reply and the tokens the sub-task's LLM calls used.
+    async fn run_sub_task(
+        &self,
+        user_message: &str,
+        allowed_tools: Option<&[String]>,
+    ) -> Result<(String, u64), AgentError> {
+        let act = self.act_node(allowed_tools);
         let mut state = ReActState {
             messages: vec![
                 Message::system(SUB_TASK_SYSTEM),
@@ -162,26 +246,46 @@ impl ExecuteGraphNode {
             approval_result: None,
             usage: None,
             total_usage: None,
+            usage_by_model: Default::default(),
             message_count_after_last_think: None,
             summary: None,
             think_count: 0,
             should_continue: true,
+            reflection_count: 0,
+            answer_revision: 0,
+            confidence: None,
+            approval_memory: Default::default(),
+            remembered_approvals: Default::default(),
         };
 
         for _ in 0..MAX_SUB_TASK_TURNS {
             let (s1, _) = self.think.run(state).await?;
             if s1.tool_calls.is_empty() {
-                return Ok(s1.last_assistant_reply().unwrap_or_default());
+                return Ok((
+                    s1.last_assistant_reply().unwrap_or_default(),
+                    sub_task_tokens(&s1),
+                ));
             }
-            let (s2, _) = self.act.run(s1).await?;
+            let (s2, _) = act.run(s1).await?;
             let (s3, _) = self.observe.run(s2).await?;
             state = s3;
         }
 
-        Ok(state.last_assistant_reply().unwrap_or_default())
+        Ok((
+            state.last_assistant_reply().unwrap_or_default(),
+            sub_task_tokens(&state),
+        ))
     }
 }
 
+/// Tokens used by a sub-task's think calls.
+fn sub_task_tokens(state: &ReActState) -> u64 {
+    state
+        .total_usage
+        .as_ref()
+        .map_or(0, |u| u64::from(u.total_tokens))
+}
+
 #[async_trait]
 impl Node<GotState> for ExecuteGraphNode {
     fn id(&self) -> &str {
@@ -204,12 +308,12 @@ impl Node<GotState> for ExecuteGraphNode {
         }
 
         let node_id = ready.into_iter().next().unwrap();
-        let description = state
+        let (description, allowed_tools) = state
             .task_graph
             .nodes
             .iter()
             .find(|n| n.id == node_id)
-            .map(|n| n.description.clone())
+            .map(|n| (n.description.clone(), n.allowed_tools.clone()))
             .ok_or_else(|| AgentError::ExecutionFailed("node not found".to_string()))?;
 
         let user_message = build_sub_task_user_message(&state, &node_id, &description);
@@ -237,10 +341,16 @@ impl Node<GotState> for ExecuteGraphNode {
             input_message: state.input_message,
             task_graph: state.task_graph,
             node_states,
+            token_budget: state.token_budget,
+            tokens_used: state.tokens_used,
         };
 
-        match self.run_sub_task(&user_message).await {
-            Ok(result) => {
+        match self
+            .run_sub_task(&user_message, allowed_tools.as_deref())
+            .await
+        {
+            Ok((result, tokens)) => {
+                state.tokens_used += tokens;
                 let summary = if result.len() > 200 {
                     let end = (0..=200)
                         .rev()
@@ -269,7 +379,26 @@ impl Node<GotState> for ExecuteGraphNode {
                     },
                 );
 
-                if self.adaptive {
+                if self.adaptive && state.budget_exhausted() {
+                    let reason = format!(
+                        "token budget exhausted ({} of {} tokens used)",
+                        state.tokens_used,
+                        state.token_budget.unwrap_or_default()
+                    );
+                    tracing::info!(node_id = %node_id, "AGoT expansion denied: {}", reason);
+                    if ctx.stream_mode.contains(&StreamMode::Custom) {
+                        if let Some(tx) = &ctx.stream_tx {
+                            let _ = tx
+                                .send(StreamEvent::GotExpand {
+                                    node_id: node_id.clone(),
+                                    nodes_added: 0,
+                                    edges_added: 0,
+                                    denied: Some(reason),
+                                })
+                                .await;
+                        }
+                    }
+                } else if self.adaptive {
                     let node = state
                         .task_graph
                         .nodes
@@ -282,11 +411,16 @@ impl Node<GotState> for ExecuteGraphNode {
                         node_states: &state.node_states,
                         input_message: &state.input_message,
                     };
+                    let mut llm_tokens = 0;
                     let complexity_override = if let Some(ref n) = node {
                         if self.agot_llm_complexity {
                             complexity_score_via_llm(Arc::clone(&self.llm), n, &expand_ctx)
                                 .await
                                 .ok()
+                                .map(|(level, tokens)| {
+                                    llm_tokens += tokens;
+                                    level
+                                })
                         } else {
                             Some(complexity_score(n, &expand_ctx))
                         }
@@ -297,10 +431,14 @@ impl Node<GotState> for ExecuteGraphNode {
                         expand_node_via_llm(Arc::clone(&self.llm), &expand_ctx, n)
                             .await
                             .ok()
-                            .flatten()
+                            .and_then(|(subgraph, tokens)| {
+                                llm_tokens += tokens;
+                                subgraph
+                            })
                     } else {
                         None
                     };
+                    state.tokens_used += llm_tokens;
                     if let Ok(Some(expand_res)) = maybe_expand(
                         &mut state,
                         &node_id,
@@ -316,6 +454,7 @@ impl Node<GotState> for ExecuteGraphNode {
                                         node_id: node_id.clone(),
                                         nodes_added: expand_res.nodes_added,
                                         edges_added: expand_res.edges_added,
+                                        denied: None,
                                     })
                                     .await;
                             }
@@ -367,6 +506,7 @@ mod tests {
             id: id.to_string(),
             description: desc.to_string(),
             tool_calls: vec![],
+            allowed_tools: None,
         }
     }
 
@@ -389,6 +529,8 @@ mod tests {
             )]
             .into_iter()
             .collect(),
+            token_budget: None,
+            tokens_used: 0,
         };
         let msg = build_sub_task_user_message(&state, "b", "Step B");
         assert!(
@@ -413,6 +555,8 @@ mod tests {
                 edges: vec![],
             },
             node_states: std::collections::HashMap::new(),
+            token_budget: None,
+            tokens_used: 0,
         };
         let msg = build_sub_task_user_message(&state, "a", "Step A");
         assert!(
@@ -420,5 +564,104 @@ mod tests {
             "no predecessor section when no preds"
         );
         assert!(msg.contains("Sub-task: Step A"));
+        assert!(!msg.contains("tools are available"));
+    }
+
+    /// **Scenario**: A node with allowed_tools lists them in its message and its Act step rejects other tools.
+    #[tokio::test]
+    async fn allowed_tools_restrict_sub_task_message_and_act() {
+        let mut research = node("research", "Look it up");
+        research.allowed_tools = Some(vec!["web_fetcher".to_string()]);
+        let state = GotState {
+            input_message: "Task".to_string(),
+            task_graph: TaskGraph {
+                nodes: vec![research],
+                edges: vec![],
+            },
+            node_states: std::collections::HashMap::new(),
+            token_budget: None,
+            tokens_used: 0,
+        };
+        let msg = build_sub_task_user_message(&state, "research", "Look it up");
+        assert!(msg.contains("Only these tools are available for this sub-task: web_fetcher"));
+
+        let engine = ExecuteGraphNode::new(
+            Arc::new(crate::llm::MockLlm::with_no_tool_calls("done")),
+            Arc::new(crate::tool_source::MockToolSource::get_time_example()),
+            false,
+            false,
+        );
+        let react = ReActState {
+            tool_calls: vec![crate::state::ToolCall {
+                name: "get_time".to_string(),
+                arguments: "{}".to_string(),
+                id: Some("c1".to_string()),
+            }],
+            ..ReActState::default()
+        };
+        let restricted = vec!["web_fetcher".to_string()];
+        let (out, _) = engine
+            .act_node(Some(&restricted))
+            .run(react.clone())
+            .await
+            .unwrap();
+        assert!(out.tool_results[0].content.contains("not allowed"));
+
+        let (out, _) = engine.act_node(None).run(react).await.unwrap();
+        assert!(out.tool_results[0].content.contains("2025-01-29"));
+    }
+
+    /// **Scenario**: Once the sub-task uses up the token budget, AGoT does not expand the node and
+    /// reports a denied GotExpand.
+    #[tokio::test]
+    async fn exhausted_budget_denies_expansion() {
+        let llm = crate::llm::MockLlm::with_no_tool_calls("done").with_usage(crate::LlmUsage {
+            prompt_tokens: 40,
+            completion_tokens: 10,
+            total_tokens: 50,
+            ..Default::default()
+        });
+        let engine = ExecuteGraphNode::new(
+            Arc::new(llm),
+            Arc::new(crate::tool_source::MockToolSource::get_time_example()),
+            true,
+            false,
+        );
+        let state = GotState {
+            input_message: "Task".to_string(),
+            task_graph: TaskGraph {
+                nodes: vec![node("a", "Analyze and compare the options")],
+                edges: vec![],
+            },
+            node_states: std::collections::HashMap::new(),
+            token_budget: Some(30),
+            tokens_used: 0,
+        };
+        let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+        let mut ctx = RunContext::<GotState>::new(crate::memory::RunnableConfig::default());
+        ctx.stream_mode.insert(StreamMode::Custom);
+        ctx.stream_tx = Some(tx);
+
+        let (out, next) = engine.run_with_context(state, &ctx).await.unwrap();
+        assert!(matches!(next, Next::End));
+        assert_eq!(out.tokens_used, 50);
+        assert_eq!(out.task_graph.nodes.len(), 1);
+
+        let mut denied = None;
+        while let Ok(event) = rx.try_recv() {
+            if let StreamEvent::GotExpand {
+                nodes_added,
+                denied: reason,
+                ..
+            } = event
+            {
+                assert_eq!(nodes_added, 0);
+                denied = reason;
+            }
+        }
+        assert_eq!(
+            denied.as_deref(),
+            Some("token budget exhausted (50 of 30 tokens used)")
+        );
     }
 }
@@ -1,7 +1,11 @@
 //! PlanGraph node: LLM produces a DAG of sub-tasks from the user message.
 //!
 //! Reads `state.input_message`, calls LLM with GOT prompt, parses JSON into
-//! `state.task_graph`, and emits `StreamEvent::GotPlan`.
+//! `state.task_graph`, and emits `StreamEvent::GotPlan`. When given the tool source, the planner
+//! sees the tool names and may restrict each node to a subset (`TaskNode::allowed_tools`). With
+//! a token budget (`GotState::token_budget`) the planner is told how many nodes fit in it.
+
+use std::sync::Arc;
 
 use async_trait::async_trait;
 
@@ -10,9 +14,11 @@ use crate::graph::{Next, RunContext};
 use crate::llm::LlmClient;
 use crate::message::Message;
 use crate::stream::{StreamEvent, StreamMode};
+use crate::tool_source::ToolSource;
 use crate::Node;
 
-use super::prompt::GOT_PLAN_SYSTEM;
+use super::prompt::{got_plan_budget_hint, got_plan_tools_hint, GOT_PLAN_SYSTEM};
+use super::state::response_tokens;
 use super::state::{GotState, TaskGraph, TaskNode};
 
 /// PlanGraph node: turns user message into a task DAG via LLM.
@@ -21,20 +27,63 @@ use super::state::{GotState, TaskGraph, TaskNode};
 /// `node_states` for each node to Pending. Emits GotPlan when Custom mode is enabled.
 pub struct PlanGraphNode {
     llm: Box<dyn LlmClient>,
+    tool_source: Option<Arc<dyn ToolSource>>,
 }
 
 impl PlanGraphNode {
     pub fn new(llm: Box<dyn LlmClient>) -> Self {
-        Self { llm }
+        Self {
+            llm,
+            tool_source: None,
+        }
+    }
+
+    /// Lists tool names to the planner so it can assign per-node tool subsets.
+    pub fn with_tool_source(mut self, tool_source: Arc<dyn ToolSource>) -> Self {
+        self.tool_source = Some(tool_source);
+        self
+    }
+
+    async fn tool_names(&self) -> Vec<String> {
+        let Some(source) = &self.tool_source else {
+            return vec![];
+        };
+        match source.list_tools().await {
+            Ok(specs) => specs.into_iter().map(|s| s.name).collect(),
+            Err(e) => {
+                tracing::warn!(error = %e, "plan_graph: failed to list tools");
+                vec![]
+            }
+        }
     }
 }
 
+/// Keeps the node's tool list only when it names tools in `known_tools` (all names are kept
+/// when `known_tools` is empty). A list naming only unknown tools is dropped (no restriction)
+/// rather than leaving the node without tools; an explicit empty list means "no tools".
+fn normalize_allowed_tools(
+    tools: Option<Vec<String>>,
+    known_tools: &[String],
+) -> Option<Vec<String>> {
+    let tools = tools?;
+    if tools.is_empty() || known_tools.is_empty() {
+        return Some(tools);
+    }
+    let kept: Vec<String> = tools
+        .into_iter()
+        .filter(|t| known_tools.contains(t))
+        .collect();
+    (!kept.is_empty()).then_some(kept)
+}
+
 /// Parses LLM response into TaskGraph. Fallback: single node with full message.
-fn parse_task_graph(raw: &str, input_message: &str) -> TaskGraph {
+fn parse_task_graph(raw: &str, input_message: &str, known_tools: &[String]) -> TaskGraph {
     #[derive(serde::Deserialize)]
     struct RawNode {
         id: Option<String>,
         description: Option<String>,
+        #[serde(default, alias = "allowed_tools")]
+        tools: Option<Vec<String>>,
     }
     #[derive(serde::Deserialize)]
     struct RawGraph {
@@ -55,6 +104,7 @@ fn parse_task_graph(raw: &str, input_message: &str) -> TaskGraph {
                         id,
                         description,
                         tool_calls: vec![],
+                        allowed_tools: normalize_allowed_tools(n.tools, known_tools),
                     }
                 })
                 .collect();
@@ -81,6 +131,7 @@ fn parse_task_graph(raw: &str, input_message: &str) -> TaskGraph {
             id: "task_1".to_string(),
             description: input_message.to_string(),
             tool_calls: vec![],
+            allowed_tools: None,
         }],
         edges: vec![],
     }
@@ -102,12 +153,21 @@ impl Node<GotState> for PlanGraphNode {
         state: GotState,
         ctx: &RunContext<GotState>,
     ) -> Result<(GotState, Next), AgentError> {
+        let tool_names = self.tool_names().await;
+        let mut user_message = state.input_message.clone();
+        if !tool_names.is_empty() {
+            user_message = format!("{}\n\n{}", user_message, got_plan_tools_hint(&tool_names));
+        }
+        if let Some(budget) = state.token_budget {
+            user_message = format!("{}\n\n{}", user_message, got_plan_budget_hint(budget));
+        }
         let messages = vec![
             Message::system(GOT_PLAN_SYSTEM),
-            Message::user(state.input_message.clone()),
+            Message::user(user_message),
         ];
         let response = self.llm.invoke(&messages).await?;
-        let task_graph = parse_task_graph(response.content.trim(), &state.input_message);
+        let task_graph =
+            parse_task_graph(response.content.trim(), &state.input_message, &tool_names);
 
         let node_ids: Vec<String> = task_graph.nodes.iter().map(|n| n.id.clone()).collect();
         let node_count = task_graph.nodes.len();
@@ -135,6 +195,8 @@ impl Node<GotState> for PlanGraphNode {
             input_message: state.input_message,
             task_graph,
             node_states,
+            token_budget: state.token_budget,
+            tokens_used: state.tokens_used + response_tokens(&response),
         };
 
         Ok((new_state, Next::Continue))
@@ -157,20 +219,51 @@ mod tests {
             ],
             "edges":[["a","b"],["a","missing"],["missing","b"]]
         }"#;
-        let graph = parse_task_graph(raw, "fallback");
+        let graph = parse_task_graph(raw, "fallback", &[]);
         assert_eq!(graph.nodes.len(), 2);
         assert_eq!(graph.edges, vec![("a".to_string(), "b".to_string())]);
     }
 
     #[test]
     fn parse_task_graph_fallbacks_to_single_node() {
-        let graph = parse_task_graph("not json", "hello world");
+        let graph = parse_task_graph("not json", "hello world", &[]);
         assert_eq!(graph.nodes.len(), 1);
         assert_eq!(graph.nodes[0].id, "task_1");
         assert_eq!(graph.nodes[0].description, "hello world");
         assert!(graph.edges.is_empty());
     }
 
+    #[test]
+    fn parse_task_graph_reads_per_node_tools() {
+        let raw = r#"{
+            "nodes":[
+                {"id":"research","description":"search","tools":["web_fetcher","made_up"]},
+                {"id":"write","description":"write file","allowed_tools":["write_file"]},
+                {"id":"guess","description":"x","tools":["made_up"]},
+                {"id":"think","description":"reason","tools":[]},
+                {"id":"any","description":"anything"}
+            ],
+            "edges":[]
+        }"#;
+        let known = vec!["web_fetcher".to_string(), "write_file".to_string()];
+        let graph = parse_task_graph(raw, "fallback", &known);
+        let tools: Vec<Option<Vec<String>>> = graph
+            .nodes
+            .iter()
+            .map(|n| n.allowed_tools.clone())
+            .collect();
+        assert_eq!(
+            tools,
+            vec![
+                Some(vec!["web_fetcher".to_string()]),
+                Some(vec!["write_file".to_string()]),
+                None,
+                Some(vec![]),
+                None,
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn run_with_context_sets_task_graph_and_emits_event() {
         let llm = MockLlm::with_no_tool_calls(
@@ -214,4 +307,31 @@ mod tests {
             other => panic!("expected GotPlan event, got {:?}", other),
         }
     }
+
+    #[test]
+    fn budget_hint_caps_node_count() {
+        assert!(got_plan_budget_hint(10_000).contains("Plan at most 2 nodes."));
+        assert!(got_plan_budget_hint(1_000).contains("Plan at most 1 node."));
+        assert!(got_plan_budget_hint(1_000_000).contains("Plan at most 8 nodes."));
+    }
+
+    #[tokio::test]
+    async fn run_with_context_counts_plan_tokens() {
+        let llm =
+            MockLlm::with_no_tool_calls(r#"{"nodes":[{"id":"a","description":"x"}],"edges":[]}"#)
+                .with_usage(crate::LlmUsage {
+                    total_tokens: 120,
+                    ..Default::default()
+                });
+        let node = PlanGraphNode::new(Box::new(llm));
+        let state = GotState {
+            input_message: "task".to_string(),
+            token_budget: Some(8_000),
+            tokens_used: 5,
+            ..GotState::default()
+        };
+        let (out, _) = node.run(state).await.unwrap();
+        assert_eq!(out.tokens_used, 125);
+        assert_eq!(out.token_budget, Some(8_000));
+    }
 }
@@ -3,7 +3,8 @@
 /// System prompt for the PlanGraph node: decompose the user task into a DAG.
 ///
 /// The LLM must respond with valid JSON: `{"nodes": [{"id": "...", "description": "..."}], "edges": [["from_id", "to_id"]]}`.
-/// The graph must be acyclic. Node ids must be unique.
+/// The graph must be acyclic. Node ids must be unique. Nodes may carry an optional `"tools"` list
+/// restricting which tools the sub-task can call.
 pub const GOT_PLAN_SYSTEM: &str = r#"You are a task planner. Given a user request, you must decompose it into a directed acyclic graph (DAG) of sub-tasks.
 
 Rules:
@@ -13,8 +14,32 @@ Rules:
 - Use short, unique node ids (e.g. read_a, read_b, merge, report).
 - Keep 2-8 nodes. Edges must form a DAG (no cycles).
 - Descriptions should be clear and actionable for an assistant that can use tools.
+- Optionally give a node a "tools" list with the only tool names it may use, e.g. {"id": "research", "description": "...", "tools": ["web_fetcher"]}. Omit "tools" when the node may use any tool.
 "#;
 
+/// Appended to the PlanGraph user message so the planner can pick per-node `"tools"` subsets.
+pub(crate) fn got_plan_tools_hint(tool_names: &[String]) -> String {
+    format!(
+        "Available tools (for optional per-node \"tools\" lists): {}",
+        tool_names.join(", ")
+    )
+}
+
+/// Rough token cost of one sub-task (a few ReAct turns), used to turn a budget into a node count.
+pub(crate) const ESTIMATED_TOKENS_PER_NODE: u64 = 4_000;
+
+/// Appended to the PlanGraph user message when the run has a token budget.
+pub(crate) fn got_plan_budget_hint(token_budget: u64) -> String {
+    let max_nodes = (token_budget / ESTIMATED_TOKENS_PER_NODE).clamp(1, 8);
+    format!(
+        "Token budget for the whole task: {} tokens (about {} tokens per node). Plan at most {} node{}.",
+        token_budget,
+        ESTIMATED_TOKENS_PER_NODE,
+        max_nodes,
+        if max_nodes == 1 { "" } else { "s" }
+    )
+}
+
 /// System prompt for AGoT dynamic expansion: decompose a complex node into sub-tasks.
 ///
 /// The LLM receives the parent node's id, description, result, and task goal.
@@ -1,13 +1,16 @@
 //! GoT graph runner: build, initial state, invoke and stream.
 //!
 //! Graph: START → plan_graph → execute_graph → [has_pending] → execute_graph | END.
+//! An optional token budget ([`GotRunner::with_token_budget`]) is carried in [`GotState`].
 
 use std::sync::Arc;
 use tokio_util::sync::CancellationToken;
 
 use crate::error::AgentError;
 use crate::graph::{CompilationError, CompiledStateGraph, LoggingNodeMiddleware};
+use crate::llm::NodeLlmOverrides;
 use crate::memory::{CheckpointError, Checkpointer, RunnableConfig, Store};
+use crate::run_hooks::RunHooks;
 use crate::runner_common;
 use crate::stream::StreamEvent;
 use crate::tool_source::ToolSource;
@@ -41,6 +44,7 @@ pub async fn build_got_initial_state(
             if let Some((checkpoint, _)) = tuple {
                 let mut state = checkpoint.channel_values.clone();
                 state.input_message = user_message.to_string();
+                state.tokens_used = 0;
                 return Ok(state);
             }
         }
@@ -50,6 +54,8 @@ pub async fn build_got_initial_state(
         input_message: user_message.to_string(),
         task_graph: super::state::TaskGraph::default(),
         node_states: std::collections::HashMap::new(),
+        token_budget: None,
+        tokens_used: 0,
     })
 }
 
@@ -72,6 +78,8 @@ pub struct GotRunner {
     checkpointer: Option<Arc<dyn Checkpointer<GotState>>>,
     runnable_config: Option<RunnableConfig>,
     cancellation: Option<CancellationToken>,
+    hooks: RunHooks<GotState>,
+    token_budget: Option<u64>,
 }
 
 impl GotRunner {
@@ -80,10 +88,24 @@ impl GotRunner {
         self
     }
 
+    /// Runs `hooks` when a run starts, finishes or fails (see [`RunHooks`]).
+    pub fn with_hooks(mut self, hooks: RunHooks<GotState>) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Token budget per run: the planner is asked to fit the DAG in it and AGoT stops expanding
+    /// nodes once it is used up. `None` (default) is unlimited.
+    pub fn with_token_budget(mut self, token_budget: Option<u64>) -> Self {
+        self.token_budget = token_budget;
+        self
+    }
+
     /// Creates a GoT runner with the given LLM, tool source, and optional persistence.
     /// When `adaptive` is true, enables AGoT: complex nodes may be expanded into subgraphs
     /// after completion.
     /// When `agot_llm_complexity` is true, use LLM to decide simple vs complex instead of heuristic.
+    /// `node_llms` may override the LLM for `plan_graph` and `execute_graph`.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         llm: Arc<dyn LlmClient>,
@@ -95,9 +117,19 @@ impl GotRunner {
         verbose: bool,
         adaptive: bool,
         agot_llm_complexity: bool,
+        node_llms: NodeLlmOverrides,
     ) -> Result<Self, CompilationError> {
-        let plan = PlanGraphNode::new(Box::new(super::runner::SharedLlm(Arc::clone(&llm))));
-        let execute = ExecuteGraphNode::new(llm, tool_source, adaptive, agot_llm_complexity);
+        let tool_source: Arc<dyn ToolSource> = Arc::from(tool_source);
+        let plan = PlanGraphNode::new(Box::new(super::runner::SharedLlm(
+            node_llms.llm_for("plan_graph", &llm),
+        )))
+        .with_tool_source(Arc::clone(&tool_source));
+        let execute = ExecuteGraphNode::new(
+            node_llms.llm_for("execute_graph", &llm),
+            tool_source,
+            adaptive,
+            agot_llm_complexity,
+        );
 
         let mut graph = StateGraph::<GotState>::new();
         if let Some(s) = store {
@@ -142,9 +174,23 @@ impl GotRunner {
             checkpointer,
             runnable_config,
             cancellation,
+            token_budget: None,
+            hooks: RunHooks::default(),
         })
     }
 
+    /// Returns the latest checkpointed state for `thread_id`, or `None` when the runner has
+    /// no checkpointer or the thread has not been checkpointed yet. Does not run the graph,
+    /// so it is safe to call while another task is streaming on the same runner.
+    pub async fn state_snapshot(&self, thread_id: &str) -> Result<Option<GotState>, GotRunError> {
+        Ok(runner_common::load_state_snapshot(
+            self.checkpointer.as_deref(),
+            self.runnable_config.as_ref(),
+            thread_id,
+        )
+        .await?)
+    }
+
     /// Invokes the graph with the given user message.
     pub async fn invoke(&self, user_message: &str) -> Result<GotState, GotRunError> {
         self.invoke_with_config(user_message, None).await
@@ -157,13 +203,16 @@ impl GotRunner {
         config: Option<RunnableConfig>,
     ) -> Result<GotState, GotRunError> {
         let run_config = config.or_else(|| self.runnable_config.clone());
-        let state = build_got_initial_state(
+        let mut state = build_got_initial_state(
             user_message,
             self.checkpointer.as_deref(),
             run_config.as_ref(),
         )
         .await?;
-        let final_state = self.compiled.invoke(state, run_config).await?;
+        state.token_budget = self.token_budget;
+        let final_state =
+            runner_common::invoke_with_hooks(&self.compiled, state, run_config, &self.hooks)
+                .await?;
         Ok(final_state)
     }
 
@@ -190,12 +239,13 @@ impl GotRunner {
         F: FnMut(StreamEvent<GotState>),
     {
         let run_config = config.or_else(|| self.runnable_config.clone());
-        let state = build_got_initial_state(
+        let mut state = build_got_initial_state(
             user_message,
             self.checkpointer.as_deref(),
             run_config.as_ref(),
         )
         .await?;
+        state.token_budget = self.token_budget;
         runner_common::run_stream_with_config(
             &self.compiled,
             state,
@@ -203,6 +253,7 @@ impl GotRunner {
             on_event,
             self.cancellation.clone(),
             None,
+            &self.hooks,
         )
         .await
         .map_err(|e| match e {
@@ -232,6 +283,9 @@ impl LlmClient for SharedLlm {
     ) -> Result<crate::llm::LlmResponse, AgentError> {
         self.0.invoke_stream(messages, tx).await
     }
+    fn tool_support(&self) -> crate::model_spec::ToolSupport {
+        self.0.tool_support()
+    }
 }
 
 #[cfg(test)]
@@ -252,10 +306,13 @@ mod tests {
                     id: "n1".to_string(),
                     description: "step".to_string(),
                     tool_calls: vec![],
+                    allowed_tools: None,
                 }],
                 edges: vec![],
             },
             node_states: std::collections::HashMap::new(),
+            token_budget: None,
+            tokens_used: 0,
         };
         assert_eq!(got_execute_condition(&state), "execute_graph");
     }
@@ -285,6 +342,7 @@ mod tests {
             false,
             false,
             false,
+            NodeLlmOverrides::default(),
         )
         .unwrap();
 
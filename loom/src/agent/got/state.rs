@@ -7,6 +7,7 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
+use crate::memory::VersionedState;
 use crate::state::ToolCall;
 
 /// Execution status of a single task node in the DAG.
@@ -38,6 +39,10 @@ pub struct TaskNode {
     /// Optional template or initial tool_calls for this sub-task.
     #[serde(default)]
     pub tool_calls: Vec<ToolCall>,
+    /// Tool names this node may use (e.g. web tools for research, file tools for writing).
+    /// `None` allows every tool; set by PlanGraphNode and enforced by ExecuteGraphNode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_tools: Option<Vec<String>>,
 }
 
 /// DAG definition: nodes and directed edges (from_id, to_id).
@@ -91,9 +96,34 @@ pub struct GotState {
     /// Per-node execution state (key = node id).
     #[serde(default)]
     pub node_states: HashMap<String, TaskNodeState>,
+    /// Token budget for the run (planning, sub-tasks and AGoT expansion); `None` is unlimited.
+    /// Set by [`GotRunner`](super::GotRunner) from its configured budget.
+    #[serde(default)]
+    pub token_budget: Option<u64>,
+    /// Tokens used by the run's LLM calls so far.
+    #[serde(default)]
+    pub tokens_used: u64,
+}
+
+/// Total tokens of one LLM response (0 when the provider reported no usage).
+pub(super) fn response_tokens(response: &crate::llm::LlmResponse) -> u64 {
+    response
+        .usage
+        .as_ref()
+        .map_or(0, |u| u64::from(u.total_tokens))
+}
+
+impl VersionedState for GotState {
+    const STATE_VERSION: u32 = 1;
 }
 
 impl GotState {
+    /// True when a token budget is set and the tokens used reached it.
+    pub fn budget_exhausted(&self) -> bool {
+        self.token_budget
+            .is_some_and(|budget| self.tokens_used >= budget)
+    }
+
     /// Returns a combined result string for display (e.g. last node's result or concatenation).
     ///
     /// Used by CLI/API to show final output when the graph has finished.
@@ -143,6 +173,7 @@ mod tests {
             id: id.to_string(),
             description: format!("desc-{id}"),
             tool_calls: vec![],
+            allowed_tools: None,
         }
     }
 
@@ -163,6 +194,19 @@ mod tests {
         assert!(s.node_states.is_empty());
     }
 
+    #[test]
+    fn budget_exhausted_only_with_a_budget() {
+        let mut s = GotState {
+            tokens_used: 5_000,
+            ..GotState::default()
+        };
+        assert!(!s.budget_exhausted());
+        s.token_budget = Some(10_000);
+        assert!(!s.budget_exhausted());
+        s.tokens_used = 10_000;
+        assert!(s.budget_exhausted());
+    }
+
     #[test]
     fn summary_result_returns_empty_when_no_done_nodes() {
         let s = GotState::default();
@@ -197,6 +241,8 @@ mod tests {
             ]
             .into_iter()
             .collect(),
+            token_budget: None,
+            tokens_used: 0,
         };
         assert_eq!(s.summary_result(), "from b");
     }
@@ -219,6 +265,8 @@ mod tests {
             )]
             .into_iter()
             .collect(),
+            token_budget: None,
+            tokens_used: 0,
         };
         assert_eq!(s.summary_result(), "fallback");
     }
@@ -19,25 +19,37 @@
 //! a `RunContext` that has `StreamMode::Custom` enabled, it creates a `ToolStreamWriter`
 //! and passes it to tools via `ToolCallContext`. Tools can then emit progress updates
 //! or intermediate results during execution.
+//!
+//! Partial results ([`ToolStreamWriter::emit_partial`]) are sent as `ToolOutputChunk` events
+//! when `StreamMode::Tools` is enabled and kept for the model: a tool that returns an empty
+//! result is answered with the stitched chunks, and a failed call's error gets them appended.
+//!
+//! With [`ActNode::with_tool_prefetch`], a call the think node already started while the answer
+//! streamed is not called again: its held result is used.
 
 use async_trait::async_trait;
 use serde_json::Value;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tracing::{debug, trace, warn};
 
+use crate::approval_audit::{
+    args_digest, ApprovalAuditDecision, ApprovalAuditRecord, ApprovalAuditStore,
+};
 use crate::cli_run::ActiveOperationKind;
 use crate::error::AgentError;
 use crate::graph::{run_cancellable, GraphInterrupt, Interrupt, Next, Node, RunContext};
-use crate::helve::{tools_requiring_approval, ApprovalPolicy, APPROVAL_REQUIRED_EVENT_TYPE};
-use crate::memory::uuid6;
+use crate::helve::{ApprovalMemory, ApprovalPolicy, ApprovalRules, APPROVAL_REQUIRED_EVENT_TYPE};
+use crate::memory::{uuid6, RunnableConfig};
 use crate::state::tool_output_normalizer::{
     normalize_tool_output, NormalizationConfig, ToolOutputHint,
 };
 use crate::state::{ReActState, ToolCall, ToolResult};
-use crate::stream::{StreamEvent, StreamMode, ToolStreamWriter};
+use crate::stream::{StreamEvent, StreamMode, TimingKind, ToolStreamWriter};
 use crate::tool_source::{ToolCallContext, ToolSource, ToolSourceError};
 
+use super::prefetch::ToolPrefetch;
+
 /// Event type for Custom stream events emitted after each tool call (step progress).
 /// Server or clients can use this to show progress (e.g. "Calling list_dir", "Done: 12 entries").
 pub const STEP_PROGRESS_EVENT_TYPE: &str = "step_progress";
@@ -64,7 +76,7 @@ fn truncate_for_display(s: &str, max_chars: usize) -> String {
 }
 
 /// Parses ToolCall.arguments string to JSON Value. Logs a warning on parse failure.
-fn parse_tool_arguments(arguments: &str) -> Value {
+pub(crate) fn parse_tool_arguments(arguments: &str) -> Value {
     let raw = if arguments.trim().is_empty() {
         serde_json::json!({})
     } else {
@@ -86,6 +98,48 @@ fn parse_tool_arguments(arguments: &str) -> Value {
     }
 }
 
+/// Why `arguments` cannot be parsed as tool arguments (see [`parse_tool_arguments`]), or `None`
+/// when they can. Empty arguments count as `{}`.
+pub(crate) fn tool_arguments_error(arguments: &str) -> Option<String> {
+    if arguments.trim().is_empty() {
+        return None;
+    }
+    match serde_json::from_str::<Value>(arguments) {
+        Ok(Value::String(s)) => serde_json::from_str::<Value>(&s)
+            .err()
+            .map(|e| e.to_string()),
+        Ok(_) => None,
+        Err(e) => Some(e.to_string()),
+    }
+}
+
+/// Tool result text for a call whose arguments are not valid JSON; the tool is not called.
+fn malformed_arguments_message(tool_name: &str, error: &str) -> String {
+    format!(
+        "Error: the arguments of this '{}' call are not valid JSON ({}). The tool was not called. Re-emit the call with a valid JSON object as arguments.",
+        tool_name, error
+    )
+}
+
+/// Result text for the model: the tool's result, or the partial results it streamed when it
+/// returned nothing.
+fn stitch_partial_output(result: &str, partial: &str) -> String {
+    if result.trim().is_empty() && !partial.is_empty() {
+        partial.to_string()
+    } else {
+        result.to_string()
+    }
+}
+
+/// Error text for the model, followed by the partial results the tool streamed before failing.
+fn error_with_partial_output(error: String, partial: &str) -> String {
+    if partial.trim().is_empty() {
+        error
+    } else {
+        format!("{}\n\nOutput before the error:\n{}", error, partial)
+    }
+}
+
 /// Builds a step_progress Custom event payload for streaming.
 fn step_progress_payload(tool_name: &str, call_id: &str, summary: &str) -> Value {
     serde_json::json!({
@@ -137,11 +191,24 @@ fn approval_required_payload(tc: &ToolCall, args: &Value) -> Value {
     })
 }
 
+fn audit_decision(decision: Option<bool>) -> ApprovalAuditDecision {
+    match decision {
+        None => ApprovalAuditDecision::Requested,
+        Some(true) => ApprovalAuditDecision::Approved,
+        Some(false) => ApprovalAuditDecision::Rejected,
+    }
+}
+
 /// Act node: one ReAct step that executes tool_calls and produces tool_results.
 pub struct ActNode {
     tools: Box<dyn ToolSource>,
     handle_tool_errors: HandleToolErrors,
     approval_policy: Option<ApprovalPolicy>,
+    approval_rules: ApprovalRules,
+    /// Decisions remembered with [`ApprovalMemory::Session`], by [`ApprovalRules::memory_key`].
+    session_approvals: Mutex<HashMap<String, bool>>,
+    approval_audit: Option<Arc<dyn ApprovalAuditStore>>,
+    prefetch: Option<Arc<ToolPrefetch>>,
 }
 
 impl ActNode {
@@ -150,6 +217,10 @@ impl ActNode {
             tools,
             handle_tool_errors: HandleToolErrors::Never,
             approval_policy: None,
+            approval_rules: ApprovalRules::default(),
+            session_approvals: Mutex::new(HashMap::new()),
+            approval_audit: None,
+            prefetch: None,
         }
     }
 
@@ -158,11 +229,90 @@ impl ActNode {
         self
     }
 
-    fn needs_approval(&self, tool_name: &str) -> bool {
-        match &self.approval_policy {
-            None => false,
-            Some(p) => tools_requiring_approval(*p).contains(&tool_name),
+    /// Per-tool / per-argument rules checked before the approval policy.
+    pub fn with_approval_rules(mut self, rules: ApprovalRules) -> Self {
+        self.approval_rules = rules;
+        self
+    }
+
+    /// Records each approval request and decision in `audit` (see [`crate::approval_audit`]).
+    pub fn with_approval_audit(mut self, audit: Option<Arc<dyn ApprovalAuditStore>>) -> Self {
+        self.approval_audit = audit;
+        self
+    }
+
+    /// Uses the results `prefetch` holds for calls the think node started early (see
+    /// [`ThinkNode::with_tool_prefetch`](super::ThinkNode::with_tool_prefetch)); unused ones are
+    /// discarded after the step.
+    pub fn with_tool_prefetch(mut self, prefetch: Option<Arc<ToolPrefetch>>) -> Self {
+        self.prefetch = prefetch;
+        self
+    }
+
+    /// Appends an audit record for `tc`; failures are logged, never fail the call.
+    async fn audit_approval(
+        &self,
+        config: Option<&RunnableConfig>,
+        tc: &ToolCall,
+        args: &Value,
+        decision: ApprovalAuditDecision,
+        remembered: bool,
+    ) {
+        let Some(audit) = &self.approval_audit else {
+            return;
+        };
+        let record = ApprovalAuditRecord {
+            tool_name: tc.name.clone(),
+            call_id: tc.id.clone(),
+            args_digest: args_digest(args),
+            decision,
+            remembered,
+            thread_id: config.and_then(|c| c.thread_id.clone()),
+            user_id: config.and_then(|c| c.user_id.clone()),
+            created_at_ms: chrono::Utc::now().timestamp_millis(),
+        };
+        if let Err(e) = audit.record(&record).await {
+            warn!(tool = %tc.name, error = %e, "failed to record approval audit");
+        }
+    }
+
+    fn needs_approval(&self, tool_name: &str, args: &Value) -> bool {
+        self.approval_rules
+            .requires_approval(self.approval_policy, tool_name, args)
+    }
+
+    /// Decision for a call that needs approval: the pending `approval_result` (remembered per
+    /// `approval_memory`), else a decision remembered for the thread or session.
+    /// Returns the decision and whether the pending result was consumed.
+    fn approval_decision(
+        &self,
+        state: &ReActState,
+        remembered: &mut HashMap<String, bool>,
+        tool_name: &str,
+        args: &Value,
+    ) -> (Option<bool>, bool) {
+        let key = self.approval_rules.memory_key(tool_name, args);
+        if let Some(approved) = state.approval_result {
+            match state.approval_memory {
+                ApprovalMemory::Once => {}
+                ApprovalMemory::Thread => {
+                    remembered.insert(key, approved);
+                }
+                ApprovalMemory::Session => {
+                    if let Ok(mut session) = self.session_approvals.lock() {
+                        session.insert(key, approved);
+                    }
+                }
+            }
+            return (Some(approved), true);
         }
+        let decision = remembered.get(&key).copied().or_else(|| {
+            self.session_approvals
+                .lock()
+                .ok()
+                .and_then(|session| session.get(&key).copied())
+        });
+        (decision, false)
     }
 
     pub fn with_handle_tool_errors(mut self, handle_tool_errors: HandleToolErrors) -> Self {
@@ -253,13 +403,40 @@ impl Node<ReActState> for ActNode {
         let tool_output_hints = self.load_tool_output_hints().await;
         let mut tool_results = Vec::with_capacity(state.tool_calls.len());
         let mut approval_result_consumed = false;
+        let mut remembered_approvals = state.remembered_approvals.clone();
         let mut used_observation_chars = 0usize;
 
         for tc in &state.tool_calls {
+            if let Some(error) = tool_arguments_error(&tc.arguments) {
+                warn!(tool = %tc.name, error = %error, "malformed tool arguments, not calling tool");
+                let normalized = normalize_tool_output(
+                    &tc.name,
+                    &Value::Null,
+                    &malformed_arguments_message(&tc.name, &error),
+                    true,
+                    tool_output_hints.get(&tc.name),
+                    NormalizationConfig::runtime_default()
+                        .with_used_observation_chars(used_observation_chars),
+                );
+                used_observation_chars += normalized.observation_chars;
+                tool_results.push(
+                    ToolResult::from(normalized)
+                        .with_call_id(tc.id.clone())
+                        .with_name(Some(tc.name.clone()))
+                        .with_is_error(true),
+                );
+                continue;
+            }
             let args: Value = parse_tool_arguments(&tc.arguments);
 
-            if self.needs_approval(&tc.name) {
-                match state.approval_result {
+            if self.needs_approval(&tc.name, &args) {
+                let (decision, consumed) =
+                    self.approval_decision(&state, &mut remembered_approvals, &tc.name, &args);
+                approval_result_consumed |= consumed;
+                let remembered = decision.is_some() && !consumed;
+                self.audit_approval(None, tc, &args, audit_decision(decision), remembered)
+                    .await;
+                match decision {
                     None => {
                         let payload = approval_required_payload(tc, &args);
                         self.tools.set_call_context(None);
@@ -284,12 +461,9 @@ impl Node<ReActState> for ActNode {
                                 .with_name(Some(tc.name.clone()))
                                 .with_is_error(true),
                         );
-                        approval_result_consumed = true;
                         continue;
                     }
-                    Some(true) => {
-                        approval_result_consumed = true;
-                    }
+                    Some(true) => {}
                 }
             }
 
@@ -366,6 +540,12 @@ impl Node<ReActState> for ActNode {
             } else {
                 state.approval_result
             },
+            approval_memory: if approval_result_consumed {
+                ApprovalMemory::Once
+            } else {
+                state.approval_memory
+            },
+            remembered_approvals,
             ..state
         };
         Ok((new_state, Next::Continue))
@@ -404,6 +584,7 @@ impl Node<ReActState> for ActNode {
 
         let mut tool_results = Vec::with_capacity(state.tool_calls.len());
         let mut approval_result_consumed = false;
+        let mut remembered_approvals = state.remembered_approvals.clone();
         let mut used_observation_chars = 0usize;
 
         for tc in &state.tool_calls {
@@ -411,10 +592,61 @@ impl Node<ReActState> for ActNode {
                 self.tools.set_call_context(None);
                 return Err(AgentError::Cancelled);
             }
+            if let Some(error) = tool_arguments_error(&tc.arguments) {
+                warn!(tool = %tc.name, error = %error, "malformed tool arguments, not calling tool");
+                let normalized = normalize_tool_output(
+                    &tc.name,
+                    &Value::Null,
+                    &malformed_arguments_message(&tc.name, &error),
+                    true,
+                    tool_output_hints.get(&tc.name),
+                    NormalizationConfig::runtime_default()
+                        .with_used_observation_chars(used_observation_chars),
+                );
+                let summary = truncate_for_log(&normalized.display_text, 200);
+                let display_text = normalized.display_text.clone();
+                used_observation_chars += normalized.observation_chars;
+                tool_results.push(
+                    ToolResult::from(normalized)
+                        .with_call_id(tc.id.clone())
+                        .with_name(Some(tc.name.clone()))
+                        .with_is_error(true),
+                );
+                if tools_mode {
+                    if let Some(tx) = &run_ctx.stream_tx {
+                        let _ = tx
+                            .send(StreamEvent::ToolEnd {
+                                call_id: tc.id.clone(),
+                                name: tc.name.clone(),
+                                result: display_text,
+                                is_error: true,
+                                raw_result: None,
+                            })
+                            .await;
+                    }
+                } else {
+                    let call_id = tc.id.as_deref().unwrap_or("");
+                    let payload = step_progress_payload(&tc.name, call_id, &summary);
+                    let _ = run_ctx.emit_custom(payload).await;
+                }
+                continue;
+            }
             let args: Value = parse_tool_arguments(&tc.arguments);
 
-            if self.needs_approval(&tc.name) {
-                match state.approval_result {
+            if self.needs_approval(&tc.name, &args) {
+                let (decision, consumed) =
+                    self.approval_decision(&state, &mut remembered_approvals, &tc.name, &args);
+                approval_result_consumed |= consumed;
+                let remembered = decision.is_some() && !consumed;
+                self.audit_approval(
+                    Some(&run_ctx.config),
+                    tc,
+                    &args,
+                    audit_decision(decision),
+                    remembered,
+                )
+                .await;
+                match decision {
                     None => {
                         if tools_mode {
                             if let Some(tx) = &run_ctx.stream_tx {
@@ -455,7 +687,6 @@ impl Node<ReActState> for ActNode {
                                 .with_name(Some(tc.name.clone()))
                                 .with_is_error(true),
                         );
-                        approval_result_consumed = true;
                         if tools_mode {
                             if let Some(tx) = &run_ctx.stream_tx {
                                 let _ = tx
@@ -478,9 +709,7 @@ impl Node<ReActState> for ActNode {
                         }
                         continue;
                     }
-                    Some(true) => {
-                        approval_result_consumed = true;
-                    }
+                    Some(true) => {}
                 }
             }
 
@@ -510,6 +739,24 @@ impl Node<ReActState> for ActNode {
             } else {
                 base_custom_writer.clone()
             };
+            let per_tool_writer = match (&run_ctx.stream_tx, tools_mode) {
+                (Some(tx), true) => {
+                    let chunk_tx = tx.clone();
+                    let chunk_call_id = tc.id.clone();
+                    let chunk_name = tc.name.clone();
+                    per_tool_writer.with_partial_output(move |content| {
+                        chunk_tx
+                            .try_send(StreamEvent::ToolOutputChunk {
+                                call_id: chunk_call_id.clone(),
+                                name: chunk_name.clone(),
+                                content: truncate_for_display(&content, display_limit),
+                            })
+                            .is_ok()
+                    })
+                }
+                _ => per_tool_writer.with_partial_output(|_| false),
+            };
+            let partial_writer = per_tool_writer.clone();
 
             let tool_ctx = ToolCallContext {
                 recent_messages: state.messages.clone(),
@@ -518,6 +765,7 @@ impl Node<ReActState> for ActNode {
                 user_id: run_ctx.config.user_id.clone(),
                 depth: run_ctx.config.depth.unwrap_or(0),
                 run_cancellation: run_ctx.run_cancellation.clone(),
+                flags: run_ctx.config.flags.clone(),
             };
             self.tools.set_call_context(Some(tool_ctx.clone()));
 
@@ -534,9 +782,18 @@ impl Node<ReActState> for ActNode {
 
             debug!(tool = %tc.name, args = ?args, "Calling tool");
 
-            let tool_call =
-                self.tools
-                    .call_tool_with_context(&tc.name, args.clone(), Some(&tool_ctx));
+            let tool_start = std::time::Instant::now();
+            let prefetched = self.prefetch.as_ref().and_then(|p| p.take(&tc.name, &args));
+            let tool_call = async {
+                match prefetched {
+                    Some(result) => result.await,
+                    None => {
+                        self.tools
+                            .call_tool_with_context(&tc.name, args.clone(), Some(&tool_ctx))
+                            .await
+                    }
+                }
+            };
             let result = match run_cancellable(
                 tool_call,
                 run_ctx.cancellation.as_ref(),
@@ -556,16 +813,26 @@ impl Node<ReActState> for ActNode {
                 self.tools.set_call_context(None);
                 return Err(AgentError::Cancelled);
             }
+            run_ctx
+                .emit_timing(
+                    self.id(),
+                    TimingKind::Tool,
+                    Some(tc.name.clone()),
+                    tool_start.elapsed(),
+                )
+                .await;
 
             match result {
                 Ok(content) => {
+                    // Non-text results (diffs, images) reach the model as their description.
+                    let text = content.to_display_string();
                     trace!(
                         tool = %tc.name,
-                        result_len = content.as_text().unwrap().len(),
-                        result_preview = %truncate_for_log(content.as_text().unwrap(), 200),
+                        result_len = text.len(),
+                        result_preview = %truncate_for_log(&text, 200),
                         "Tool returned"
                     );
-                    let raw_text = content.as_text().unwrap().to_string();
+                    let raw_text = stitch_partial_output(&text, &partial_writer.partial_output());
                     let normalized = normalize_tool_output(
                         &tc.name,
                         &args,
@@ -612,7 +879,7 @@ impl Node<ReActState> for ActNode {
                     warn!(tool = %tc.name, error = %e, "Tool call failed");
                     let error_text = if let Some(error_msg) = self.handle_error(&e, &tc.name, &args)
                     {
-                        error_msg
+                        error_with_partial_output(error_msg, &partial_writer.partial_output())
                     } else {
                         self.tools.set_call_context(None);
                         return Err(AgentError::ExecutionFailed(e.to_string()));
@@ -661,6 +928,9 @@ impl Node<ReActState> for ActNode {
 
         backfill_tool_result_call_ids(&state.tool_calls, &mut tool_results);
         self.tools.set_call_context(None);
+        if let Some(prefetch) = &self.prefetch {
+            prefetch.discard();
+        }
 
         let new_state = ReActState {
             tool_results,
@@ -669,6 +939,12 @@ impl Node<ReActState> for ActNode {
             } else {
                 state.approval_result
             },
+            approval_memory: if approval_result_consumed {
+                ApprovalMemory::Once
+            } else {
+                state.approval_memory
+            },
+            remembered_approvals,
             ..state
         };
         Ok((new_state, Next::Continue))
@@ -815,6 +1091,42 @@ mod tests {
         assert_eq!(Node::<ReActState>::id(&node), "act");
     }
 
+    #[test]
+    fn tool_arguments_error_flags_only_unparseable_arguments() {
+        assert!(tool_arguments_error("").is_none());
+        assert!(tool_arguments_error(r#"{"path": "/tmp"}"#).is_none());
+        assert!(tool_arguments_error(r#""{\"key\": \"val\"}""#).is_none());
+        assert!(tool_arguments_error(r#"{"path": "/tmp""#).is_some());
+        assert!(tool_arguments_error(r#""{not json""#).is_some());
+    }
+
+    #[tokio::test]
+    async fn malformed_arguments_are_reported_without_calling_the_tool() {
+        use crate::tool_source::MockToolSource;
+        let node = ActNode::new(Box::new(
+            MockToolSource::get_time_example().with_call_result("called".to_string()),
+        ));
+        let state = ReActState {
+            tool_calls: vec![ToolCall {
+                id: Some("c1".into()),
+                name: "get_time".into(),
+                arguments: r#"{"tz": "UTC""#.into(),
+            }],
+            ..Default::default()
+        };
+        let (out, _) = node.run(state).await.unwrap();
+        assert_eq!(out.tool_results.len(), 1);
+        let result = &out.tool_results[0];
+        assert!(result.is_error);
+        assert!(
+            result.content.contains("not valid JSON"),
+            "{}",
+            result.content
+        );
+        assert!(!result.content.contains("called"));
+        assert_eq!(result.call_id.as_deref(), Some("c1"));
+    }
+
     #[test]
     fn backfill_tool_result_call_ids_from_toolcall_id() {
         let tcs = vec![ToolCall {
@@ -1,7 +1,10 @@
 //! Error type when building a ReactRunner from config.
 
+use crate::agent::react::GraphSpecError;
+use crate::approval_audit::ApprovalAuditError;
 use crate::error::AgentError;
-use crate::graph::CompilationError;
+use crate::graph::{CompilationError, RouteRuleError};
+use crate::helve::ApprovalRuleError;
 
 /// Error when building a ReactRunner from config.
 #[derive(Debug, thiserror::Error)]
@@ -10,6 +13,21 @@ pub enum BuildRunnerError {
     Context(#[from] AgentError),
     #[error("compilation failed: {0}")]
     Compilation(#[from] CompilationError),
+    #[error("{0}")]
+    RouteRule(#[from] RouteRuleError),
+    #[error("{0}")]
+    ApprovalRule(#[from] ApprovalRuleError),
+    /// The graph spec (`graph_spec`) could not be read or is invalid.
+    #[error("{0}")]
+    GraphSpec(#[from] GraphSpecError),
+    /// The approval audit store (`approval_audit_db`) could not be opened.
+    #[error("{0}")]
+    ApprovalAudit(#[from] ApprovalAuditError),
+    /// `LOOM_CHAOS` is not a valid fault injection spec (`chaos` feature).
+    #[error("invalid LOOM_CHAOS: {0}")]
+    FaultInjection(String),
+    #[error("invalid injection pattern: {0}")]
+    InjectionPattern(#[from] regex::Error),
     #[error("no LLM provided and config has no openai_api_key/model; pass Some(llm) or set OPENAI_API_KEY and OPENAI_MODEL")]
     NoLlm,
 }
@@ -3,12 +3,14 @@
 //! Only `LLM_PROVIDER=openai` uses the native `async_openai` client; all other providers
 //! (including the default when the model prefix is not `openai/`) use `ChatOpenAICompat`.
 
+use std::sync::Arc;
+
 use crate::error::AgentError;
-use crate::llm::{ChatOpenAI, ChatOpenAICompat, ModelEntry};
+use crate::llm::{ChatOpenAI, ChatOpenAICompat, MockLlm, MockScript, ModelEntry, NodeLlmOverrides};
 use crate::tool_source::ToolSource;
 use crate::LlmClient;
 
-use super::super::config::ReactBuildConfig;
+use super::super::config::{ReactBuildConfig, DEFAULT_MODEL};
 use super::error::BuildRunnerError;
 
 fn parse_provider_model(model: &str) -> Option<(&str, &str)> {
@@ -33,7 +35,8 @@ pub(crate) fn model_entry_from_config(
     // API key: config > env
     let api_key = config
         .openai_api_key
-        .clone()
+        .as_ref()
+        .map(|s| s.expose().to_string())
         .or_else(|| std::env::var("OPENAI_API_KEY").ok())
         .ok_or_else(|| {
             BuildRunnerError::Context(AgentError::ExecutionFailed(
@@ -56,14 +59,14 @@ pub(crate) fn model_entry_from_config(
             model.clone()
         } else {
             tracing::warn!("⚠️ Frontend config model empty, using system default");
-            "gpt-4o-mini".to_string()
+            DEFAULT_MODEL.to_string()
         }
     } else {
         tracing::warn!("⚠️ No frontend config model, using system default");
         tracing::info!(
             "💡 Tip: Specify a model in your config file or via API parameters for better control"
         );
-        "gpt-4o-mini".to_string()
+        DEFAULT_MODEL.to_string()
     };
 
     tracing::info!("✅ Final model to use: {}", raw_model);
@@ -127,10 +130,22 @@ pub(crate) fn model_entry_from_config(
 
 ///
 /// This is the async version that fetches tools from the tool source.
+/// Offline-mode LLM: replays `config.offline_script` when set, otherwise echoes the user.
+fn build_offline_llm(config: &ReactBuildConfig) -> Result<Box<dyn LlmClient>, BuildRunnerError> {
+    let script = match &config.offline_script {
+        Some(path) => MockScript::load(path)?,
+        None => MockScript::default(),
+    };
+    Ok(Box::new(MockLlm::scripted(script)))
+}
+
 pub(crate) async fn build_default_llm_with_tool_source(
     config: &ReactBuildConfig,
     tool_source: &dyn ToolSource,
 ) -> Result<Box<dyn LlmClient>, BuildRunnerError> {
+    if config.offline {
+        return build_offline_llm(config);
+    }
     let entry = model_entry_from_config(config)?;
     let provider_type = entry.provider_type.as_deref().unwrap_or_else(|| {
         if entry.provider.eq_ignore_ascii_case("openai") {
@@ -146,6 +161,10 @@ pub(crate) async fn build_default_llm_with_tool_source(
             e
         )))
     })?;
+    let mut tool_support = crate::model_spec::tool_support_for(&entry.id, None);
+    if let Some(mode) = config.tool_call_mode {
+        tool_support.mode = mode;
+    }
 
     match provider_type {
         "openai" => {
@@ -158,7 +177,9 @@ pub(crate) async fn build_default_llm_with_tool_source(
                 openai_config = openai_config.with_api_base(base_url);
             }
             tracing::debug!("build_default_llm: OpenAI with tools");
-            let mut client = ChatOpenAI::with_config(openai_config, entry.name).with_tools(tools);
+            let mut client = ChatOpenAI::with_config(openai_config, entry.name)
+                .with_tools(tools)
+                .with_tool_support(tool_support);
             
             if let Some(ref thread_id) = config.thread_id {
                 let headers = crate::llm::LlmHeaders::default().with_thread_id(thread_id);
@@ -172,6 +193,9 @@ pub(crate) async fn build_default_llm_with_tool_source(
             if let Some(t) = entry.temperature {
                 client = client.with_temperature(t);
             }
+            if !config.stop_sequences.is_empty() {
+                client = client.with_stop(config.stop_sequences.clone());
+            }
             Ok(Box::new(client) as Box<dyn LlmClient>)
         }
         _ => {
@@ -192,8 +216,9 @@ pub(crate) async fn build_default_llm_with_tool_source(
                 )))
             })?;
             tracing::debug!(provider_type = %provider_type, "build_default_llm: OpenAI-compat with tools");
-            let mut client =
-                ChatOpenAICompat::with_config(base_url, api_key, entry.name).with_tools(tools);
+            let mut client = ChatOpenAICompat::with_config(base_url, api_key, entry.name)
+                .with_tools(tools)
+                .with_tool_support(tool_support);
             
             if let Some(ref thread_id) = config.thread_id {
                 let headers = crate::llm::LlmHeaders::default().with_thread_id(thread_id);
@@ -207,11 +232,39 @@ pub(crate) async fn build_default_llm_with_tool_source(
             if let Some(t) = entry.temperature {
                 client = client.with_temperature(t);
             }
+            if !config.stop_sequences.is_empty() {
+                client = client.with_stop(config.stop_sequences.clone());
+            }
             Ok(Box::new(client) as Box<dyn LlmClient>)
         }
     }
 }
 
+/// Builds per-node LLM overrides from `config.node_models`.
+///
+/// Each override reuses the run's credentials and provider settings with only the model
+/// replaced. `default_model` labels usage of nodes without an override (`None` when the
+/// caller supplied its own default LLM).
+pub(crate) async fn build_node_llms(
+    config: &ReactBuildConfig,
+    tool_source: &dyn ToolSource,
+    default_model: Option<String>,
+) -> Result<NodeLlmOverrides, BuildRunnerError> {
+    let mut overrides = NodeLlmOverrides::new(default_model);
+    if config.offline {
+        return Ok(overrides);
+    }
+    for (node_id, model) in &config.node_models {
+        let mut node_config = config.clone();
+        node_config.model = Some(model.clone());
+        let label = model_entry_from_config(&node_config)?.id;
+        let llm = build_default_llm_with_tool_source(&node_config, tool_source).await?;
+        tracing::debug!(node = %node_id, model = %label, "using per-node LLM override");
+        overrides.insert(node_id.clone(), label, Arc::from(llm));
+    }
+    Ok(overrides)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
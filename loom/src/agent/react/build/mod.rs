@@ -11,21 +11,28 @@ use std::sync::Arc;
 use crate::agent::dup::{DupRunner, DupState};
 use crate::agent::got::{GotRunner, GotState};
 use crate::agent::tot::{TotRunner, TotState};
-use crate::compress::CompactionConfig;
+use crate::approval_audit::{ApprovalAuditError, ApprovalAuditStore, SqliteApprovalAuditStore};
+use crate::compress::{CompactionConfig, ContextGuard};
 use crate::error::AgentError;
-use crate::memory::{Checkpointer, JsonSerializer, RunnableConfig, SqliteSaver};
-use crate::model_spec::{ModelLimitResolver, ModelsDevResolver};
-use crate::state::ReActState;
+use crate::graph::RouteRule;
+use crate::helve::ApprovalRules;
+use crate::llm::{LlmRoute, NodeLlmOverrides, RetryLlmClient, RoutingLlmClient};
+use crate::memory::{
+    Checkpointer, RunnableConfig, SqliteSaver, VersionedJsonSerializer, VersionedState,
+};
+use crate::model_spec::{tokenizer_for, ModelLimitResolver, ModelSpec, ModelsDevResolver};
+use crate::state::{InjectionSanitizer, ReActState, ToolResultFraming};
+use crate::tool_source::ToolSource;
 use crate::LlmClient;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use super::config::ReactBuildConfig;
-use super::runner::ReactRunner;
-use super::REACT_SYSTEM_PROMPT;
-use llm::build_default_llm_with_tool_source;
-use store::build_store;
-use tool_source::build_tool_source;
+use super::config::{ReactBuildConfig, DEFAULT_MODEL};
+use super::runner::{ReactRunner, SummarizeConfig};
+use super::{EnvContext, GraphSpec, MemoryRecall, REACT_SYSTEM_PROMPT};
+use llm::{build_default_llm_with_tool_source, build_node_llms, model_entry_from_config};
+use store::{build_embedder, build_store};
+use tool_source::{build_tool_source, memory_namespace, observation_dir};
 
 pub use context::ReactRunContext;
 pub use error::BuildRunnerError;
@@ -45,17 +52,18 @@ fn resolve_memory_db_path(config: &ReactBuildConfig) -> String {
 
 /// Builds an optional checkpointer for state type `S` when `config.thread_id` is set.
 /// Shared by ReAct, DUP, ToT, and GoT runners to avoid duplicating SqliteSaver construction.
+/// States are versioned, so older checkpoints are migrated on load (see [`VersionedState`]).
 fn build_checkpointer_for_state<S>(
     config: &ReactBuildConfig,
     db_path: &str,
 ) -> Result<Option<Arc<dyn Checkpointer<S>>>, AgentError>
 where
-    S: Clone + Send + Sync + 'static + Serialize + DeserializeOwned,
+    S: Clone + Send + Sync + 'static + Serialize + DeserializeOwned + VersionedState,
 {
     if config.thread_id.is_none() {
         return Ok(None);
     }
-    let serializer = Arc::new(JsonSerializer);
+    let serializer = Arc::new(VersionedJsonSerializer);
     let saver = SqliteSaver::new(db_path, serializer).map_err(to_agent_error)?;
     Ok(Some(Arc::new(saver) as Arc<dyn Checkpointer<S>>))
 }
@@ -68,7 +76,7 @@ fn build_checkpointer(
 }
 
 fn build_runnable_config(config: &ReactBuildConfig) -> Option<RunnableConfig> {
-    if config.thread_id.is_none() && config.user_id.is_none() {
+    if config.thread_id.is_none() && config.user_id.is_none() && config.flags.is_empty() {
         return None;
     }
     Some(RunnableConfig {
@@ -81,9 +89,80 @@ fn build_runnable_config(config: &ReactBuildConfig) -> Option<RunnableConfig> {
         resume_value: None,
         resume_values_by_namespace: Default::default(),
         resume_values_by_interrupt_id: Default::default(),
+        routing_seed: None,
+        flags: config.flags.clone(),
     })
 }
 
+/// Copy of `config` without credentials and HTTP MCP servers, so offline mode registers no
+/// tools that reach the network.
+fn offline_tool_config(config: &ReactBuildConfig) -> ReactBuildConfig {
+    let mut config = config.clone();
+    config.exa_api_key = None;
+    config.twitter_api_key = None;
+    config.github_token = None;
+    if let Some(ref mut servers) = config.mcp_servers {
+        servers.retain(|s| !matches!(s, env_config::McpServerDef::Http { .. }));
+    }
+    config
+}
+
+/// Hides tools that are registered unconditionally but need the network (web fetch).
+async fn without_network_tools(
+    tool_source: Box<dyn ToolSource>,
+) -> Result<Box<dyn ToolSource>, AgentError> {
+    let names: Vec<String> = tool_source
+        .list_tools()
+        .await
+        .map_err(|e| AgentError::ExecutionFailed(e.to_string()))?
+        .into_iter()
+        .map(|t| t.name)
+        .filter(|name| name != crate::tools::TOOL_WEB_FETCHER)
+        .collect();
+    Ok(Box::new(crate::tool_source::AllowedToolsSource::new(
+        Arc::from(tool_source),
+        names,
+    )))
+}
+
+/// Tools that run commands or modify files; never listed or callable in read-only mode.
+const READ_ONLY_DENIED_TOOLS: &[&str] = &[
+    crate::tools::TOOL_BASH,
+    crate::tools::TOOL_POWERSHELL,
+    crate::tools::TOOL_WRITE_FILE,
+    crate::tools::TOOL_EDIT_FILE,
+    crate::tools::TOOL_MULTIEDIT,
+    crate::tools::TOOL_APPLY_PATCH,
+    crate::tools::TOOL_MOVE_FILE,
+    crate::tools::TOOL_DELETE_FILE,
+    crate::tools::TOOL_CREATE_DIR,
+    crate::tools::TOOL_TODO_WRITE,
+    "ssh_exec",
+    "scp_get",
+    "scp_put",
+    "python",
+];
+
+/// Hides and refuses `denied` tools, whichever source registers them (e.g. an MCP server
+/// providing a tool named like a built-in one).
+async fn without_tools(
+    tool_source: Box<dyn ToolSource>,
+    denied: &[&str],
+) -> Result<Box<dyn ToolSource>, AgentError> {
+    let names: Vec<String> = tool_source
+        .list_tools()
+        .await
+        .map_err(|e| AgentError::ExecutionFailed(e.to_string()))?
+        .into_iter()
+        .map(|t| t.name)
+        .filter(|name| !denied.contains(&name.as_str()))
+        .collect();
+    Ok(Box::new(crate::tool_source::AllowedToolsSource::new(
+        Arc::from(tool_source),
+        names,
+    )))
+}
+
 pub async fn build_react_run_context(
     config: &ReactBuildConfig,
 ) -> Result<ReactRunContext, AgentError> {
@@ -95,7 +174,32 @@ pub async fn build_react_run_context(
     let store = build_store(config, db_path)?;
     let runnable_config = build_runnable_config(config);
     tracing::debug!("build_react_run_context: building tool_source");
-    let mut tool_source = build_tool_source(config, &store).await?;
+    let mut tool_source = if config.offline {
+        let tools = build_tool_source(&offline_tool_config(config), &store).await?;
+        without_network_tools(tools).await?
+    } else {
+        build_tool_source(config, &store).await?
+    };
+    if config.read_only {
+        tool_source = without_tools(tool_source, READ_ONLY_DENIED_TOOLS).await?;
+    }
+    if !config.denied_tools.is_empty() {
+        let denied: Vec<&str> = config.denied_tools.iter().map(String::as_str).collect();
+        tool_source = without_tools(tool_source, &denied).await?;
+    }
+    if let Some(ref allowed) = config.allowed_tools {
+        tool_source = Box::new(crate::tool_source::AllowedToolsSource::new(
+            Arc::from(tool_source),
+            allowed.iter().cloned(),
+        ));
+    }
+    if !config.tool_arguments.is_empty() {
+        tool_source = Box::new(crate::tool_source::ToolArgumentsSource::new(
+            tool_source,
+            config.tool_arguments.clone(),
+            config.working_folder.clone(),
+        ));
+    }
     if config.dry_run {
         tool_source = Box::new(crate::tool_source::DryRunToolSource::new(tool_source));
     }
@@ -110,41 +214,249 @@ pub async fn build_react_run_context(
 }
 
 /// Resolves CompactionConfig: uses config.compaction_config if set, otherwise attempts
-/// to infer max_context_tokens from models.dev.
+/// to infer max_context_tokens from models.dev. The tokenizer, when not set, is resolved from
+/// the model (see [`tokenizer_for`]).
 async fn resolve_compaction_config(config: &ReactBuildConfig) -> CompactionConfig {
-    if let Some(ref cfg) = config.compaction_config {
-        return cfg.clone();
-    }
-
-    if let Some(ref model) = config.model {
-        let resolver = ModelsDevResolver::new();
-
-        if model.contains('/') {
-            if let Some(spec) = resolver.resolve_combined(model).await {
-                tracing::info!(
-                    model = %model,
-                    context_limit = spec.context_limit,
-                    output_limit = spec.output_limit,
-                    "resolved model spec from models.dev"
-                );
-                return CompactionConfig::with_max_context_tokens(spec.context_limit);
+    let Some(ref model) = config.model else {
+        return config.compaction_config.clone().unwrap_or_default();
+    };
+    let (mut compaction, spec) = match config.compaction_config {
+        Some(ref cfg) => (cfg.clone(), None),
+        None => match resolve_model_spec(model).await {
+            Some(spec) => (
+                CompactionConfig::with_max_context_tokens(spec.context_limit),
+                Some(spec),
+            ),
+            None => {
+                tracing::debug!(model = %model, "model not found in models.dev, using default config");
+                (CompactionConfig::default(), None)
             }
-        }
+        },
+    };
+    if compaction.tokenizer.is_none() {
+        compaction.tokenizer = tokenizer_for(model, spec.as_ref());
+    }
+    tracing::debug!(
+        model = %model,
+        tokenizer = compaction.tokenizer.as_ref().map_or("heuristic", |t| t.name()),
+        "context token counting"
+    );
+    compaction
+}
 
-        if let Some(spec) = resolver.resolve_by_bare_model_name(model).await {
+/// Context size in tokens of `model` (`provider/model` or a bare model name) from models.dev.
+async fn resolve_context_limit(model: &str) -> Option<u32> {
+    resolve_model_spec(model)
+        .await
+        .map(|spec| spec.context_limit)
+}
+
+/// Spec of `model` (`provider/model` or a bare model name) from models.dev.
+async fn resolve_model_spec(model: &str) -> Option<ModelSpec> {
+    let resolver = ModelsDevResolver::new();
+
+    if model.contains('/') {
+        if let Some(spec) = resolver.resolve_combined(model).await {
             tracing::info!(
                 model = %model,
                 context_limit = spec.context_limit,
                 output_limit = spec.output_limit,
-                "resolved model spec from models.dev by bare model name"
+                "resolved model spec from models.dev"
             );
-            return CompactionConfig::with_max_context_tokens(spec.context_limit);
+            return Some(spec);
+        }
+    }
+
+    let spec = resolver.resolve_by_bare_model_name(model).await?;
+    tracing::info!(
+        model = %model,
+        context_limit = spec.context_limit,
+        output_limit = spec.output_limit,
+        "resolved model spec from models.dev by bare model name"
+    );
+    Some(spec)
+}
+
+/// Context guard for the think node: the think model's context limit (from `compaction`, or
+/// resolved for a per-node `think` model) plus [`ReactBuildConfig::context_fallback_model`]
+/// when set. The fallback reuses the run's credentials and provider settings.
+async fn build_context_guard(
+    config: &ReactBuildConfig,
+    tool_source: &dyn ToolSource,
+    node_llms: &NodeLlmOverrides,
+    compaction: &CompactionConfig,
+) -> Result<ContextGuard, BuildRunnerError> {
+    let mut compaction = compaction.clone();
+    if let Some(think_model) = config.node_models.get("think") {
+        let spec = resolve_model_spec(think_model).await;
+        if let Some(ref spec) = spec {
+            compaction.max_context_tokens = spec.context_limit;
         }
+        compaction.tokenizer = tokenizer_for(think_model, spec.as_ref());
+    }
+    let model = node_llms
+        .model_for("think")
+        .or_else(|| config.model.clone())
+        .unwrap_or_else(|| DEFAULT_MODEL.to_string());
+    let mut guard = ContextGuard::new(model, compaction);
+    let Some(fallback) = config.context_fallback_model.as_ref() else {
+        return Ok(guard);
+    };
+    if config.offline {
+        return Ok(guard);
+    }
+    let mut fallback_config = config.clone();
+    fallback_config.model = Some(fallback.clone());
+    let label = model_entry_from_config(&fallback_config)?.id;
+    let llm = build_default_llm_with_tool_source(&fallback_config, tool_source).await?;
+    let context_limit = resolve_context_limit(fallback).await;
+    if context_limit.is_none() {
+        tracing::warn!(
+            model = %fallback,
+            "context limit of the fallback model is unknown; assuming every prompt fits"
+        );
+    }
+    tracing::debug!(model = %label, ?context_limit, "using context fallback model");
+    guard = guard.with_fallback(
+        label,
+        context_limit,
+        Arc::new(RetryLlmClient::new(Arc::from(llm))),
+    );
+    Ok(guard)
+}
+
+/// Resolves the default LLM (caller-supplied or built from config) plus per-node overrides
+/// from `config.node_models`.
+async fn resolve_llms(
+    config: &ReactBuildConfig,
+    llm: Option<Box<dyn LlmClient>>,
+    tool_source: &dyn ToolSource,
+) -> Result<(Box<dyn LlmClient>, NodeLlmOverrides), BuildRunnerError> {
+    let (llm, default_model) = match llm {
+        Some(l) => (l, None),
+        None => (
+            build_routed_llm(config, tool_source).await?,
+            model_entry_from_config(config).ok().map(|e| e.id),
+        ),
+    };
+    let node_llms = build_node_llms(config, tool_source, default_model).await?;
+    Ok((llm, node_llms))
+}
+
+/// Builds the default LLM, routed across [`ReactBuildConfig::llm_routes`] when set: one client
+/// per `[[providers]]` entry, serving the configured model with that provider's endpoint and
+/// key, behind a [`RoutingLlmClient`].
+async fn build_routed_llm(
+    config: &ReactBuildConfig,
+    tool_source: &dyn ToolSource,
+) -> Result<Box<dyn LlmClient>, BuildRunnerError> {
+    if config.llm_routes.is_empty() || config.offline {
+        return build_default_llm_with_tool_source(config, tool_source).await;
+    }
+    let route_error = |msg: String| {
+        BuildRunnerError::Context(AgentError::ExecutionFailed(format!(
+            "LOOM_LLM_ROUTES: {}",
+            msg
+        )))
+    };
+    let routes = LlmRoute::parse_all(&config.llm_routes).map_err(route_error)?;
+    let providers = env_config::load_full_config("loom")
+        .map_err(|e| route_error(e.to_string()))?
+        .providers;
+    let model = model_entry_from_config(config)?.name;
+    let mut endpoints = Vec::with_capacity(routes.len());
+    for route in routes {
+        let provider = providers
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(&route.name))
+            .ok_or_else(|| {
+                route_error(format!(
+                    "provider `{}` not found in config.toml [[providers]]",
+                    route.name
+                ))
+            })?;
+        let mut route_config = config.clone();
+        route_config.model = Some(model.clone());
+        route_config.llm_provider = Some(
+            provider
+                .provider_type
+                .clone()
+                .unwrap_or_else(|| "openai".to_string()),
+        );
+        if let Some(url) = &provider.base_url {
+            route_config.openai_base_url = Some(url.clone());
+        }
+        if let Some(key) = &provider.api_key {
+            route_config.openai_api_key = Some(key.clone().into());
+        }
+        let llm = build_default_llm_with_tool_source(&route_config, tool_source).await?;
+        endpoints.push((route, Arc::from(llm)));
+    }
+    tracing::debug!(
+        endpoints = endpoints.len(),
+        "routing LLM calls across endpoints"
+    );
+    Ok(Box::new(RoutingLlmClient::new(endpoints)))
+}
+
+/// Builds a tool-less LLM client from `config` for side calls outside the graph (e.g. the
+/// thread title/summary after a run). `model` replaces `config.model` when set, so callers
+/// can pick a cheaper model while reusing the run's provider and credentials.
+pub fn build_auxiliary_llm(
+    config: &ReactBuildConfig,
+    model: Option<&str>,
+) -> Result<Box<dyn LlmClient>, BuildRunnerError> {
+    if config.offline {
+        return Err(BuildRunnerError::Context(AgentError::ExecutionFailed(
+            "offline mode: auxiliary LLM calls are disabled".to_string(),
+        )));
+    }
+    let mut config = config.clone();
+    if let Some(m) = model {
+        config.model = Some(m.to_string());
+    }
+    let entry = model_entry_from_config(&config)?;
+    crate::llm::create_llm_client(&entry).map_err(BuildRunnerError::Context)
+}
 
-        tracing::debug!(model = %model, "model not found in models.dev, using default config");
+/// Wraps `tool_source` in a [`ToolSelectionSource`](crate::tool_source::ToolSelectionSource)
+/// when [`ReactBuildConfig::tool_selection`] is set. Without embedding credentials selection
+/// is skipped with a warning and every tool stays exposed.
+fn with_tool_selection(
+    config: &ReactBuildConfig,
+    tool_source: Box<dyn ToolSource>,
+) -> Box<dyn ToolSource> {
+    let Some(selection) = config.tool_selection.clone() else {
+        return tool_source;
+    };
+    if config.offline {
+        return tool_source;
     }
+    match build_embedder(config) {
+        Ok(embedder) => Box::new(crate::tool_source::ToolSelectionSource::new(
+            tool_source,
+            embedder,
+            selection,
+        )),
+        Err(e) => {
+            tracing::warn!("tool selection disabled: {}", e);
+            tool_source
+        }
+    }
+}
 
-    CompactionConfig::default()
+/// Max follow-up calls per think step when [`ReactBuildConfig::auto_continue`] is on.
+const AUTO_CONTINUE_MAX: u32 = 3;
+
+/// Warns that `auto_continue` has no effect: only the ReAct think node continues truncated
+/// answers.
+fn warn_auto_continue_unsupported(config: &ReactBuildConfig, runner: &str) {
+    if config.auto_continue {
+        tracing::warn!(
+            runner,
+            "auto_continue is only supported by the ReAct runner; ignoring it"
+        );
+    }
 }
 
 pub async fn build_react_runner(
@@ -153,19 +465,32 @@ pub async fn build_react_runner(
     verbose: bool,
 ) -> Result<ReactRunner, BuildRunnerError> {
     let ctx = build_react_run_context(config).await?;
-    let llm = match llm {
-        Some(l) => l,
-        None => build_default_llm_with_tool_source(config, ctx.tool_source.as_ref()).await?,
-    };
+    let tool_source = with_tool_selection(config, ctx.tool_source);
+    let (llm, node_llms) = resolve_llms(config, llm, tool_source.as_ref()).await?;
+    let checkpointer = ctx.checkpointer;
+    #[cfg(feature = "chaos")]
+    let (llm, tool_source, checkpointer) = with_fault_injection(llm, tool_source, checkpointer)?;
     let system_prompt = config
         .system_prompt
         .clone()
         .unwrap_or_else(|| REACT_SYSTEM_PROMPT.to_string());
     let compaction_config = resolve_compaction_config(config).await;
+    let context_guard =
+        build_context_guard(config, tool_source.as_ref(), &node_llms, &compaction_config).await?;
+    let memory_recall = ctx
+        .store
+        .clone()
+        .zip(config.memory_recall)
+        .map(|(store, top_k)| MemoryRecall::new(store, memory_namespace(config), top_k));
+    let graph_spec = config
+        .graph_spec
+        .as_deref()
+        .map(GraphSpec::load)
+        .transpose()?;
     let runner = ReactRunner::new(
         llm,
-        ctx.tool_source,
-        ctx.checkpointer,
+        tool_source,
+        checkpointer,
         ctx.store,
         ctx.runnable_config,
         system_prompt,
@@ -174,11 +499,101 @@ pub async fn build_react_runner(
         None,
         None,
         verbose,
-        None, // session summarize node off unless caller passes Some(SummarizeConfig { enabled: true, .. })
-    )?;
+        // session summarize node off unless caller passes Some(SummarizeConfig { enabled: true, .. })
+        (config.enable_reflection || config.confidence_scoring.is_some()).then(|| {
+            SummarizeConfig::disabled()
+                .with_reflection(config.enable_reflection)
+                .with_confidence_scoring(config.confidence_scoring)
+        }),
+        node_llms,
+        if config.auto_continue {
+            AUTO_CONTINUE_MAX
+        } else {
+            0
+        },
+        config.dedup_observations,
+        Some(context_guard),
+        ApprovalRules::parse_all(&config.approval_rules)?,
+        build_approval_audit(config)?,
+        build_tool_result_framing(config)?,
+        config
+            .observation_summary_tokens
+            .map(|threshold| (threshold, observation_dir(config))),
+        config.tool_prefetch.clone(),
+        graph_spec.as_ref(),
+    )?
+    .with_history_window(config.history_window.clone())
+    .with_state_limits(config.state_limits.clone())
+    .with_middleware_stack(config.node_middleware.clone())
+    .with_memory_recall(memory_recall)
+    .with_env_context(
+        config
+            .env_context
+            .then(|| EnvContext::new(config.working_folder.clone())),
+    )
+    .with_route_rules(RouteRule::parse_all(&config.route_rules)?)?;
     Ok(runner)
 }
 
+/// Wraps the main LLM, the tool source and the checkpointer with the faults of `LOOM_CHAOS`;
+/// unchanged when it is unset.
+#[cfg(feature = "chaos")]
+#[allow(clippy::type_complexity)]
+fn with_fault_injection(
+    llm: Box<dyn LlmClient>,
+    tool_source: Box<dyn ToolSource>,
+    checkpointer: Option<Arc<dyn Checkpointer<ReActState>>>,
+) -> Result<
+    (
+        Box<dyn LlmClient>,
+        Box<dyn ToolSource>,
+        Option<Arc<dyn Checkpointer<ReActState>>>,
+    ),
+    BuildRunnerError,
+> {
+    use crate::chaos::{ChaosCheckpointer, ChaosLlm, ChaosToolSource, FaultInjector};
+
+    let Some(injector) = FaultInjector::from_env().map_err(BuildRunnerError::FaultInjection)?
+    else {
+        return Ok((llm, tool_source, checkpointer));
+    };
+    tracing::warn!("LOOM_CHAOS is set: injecting LLM, tool and checkpoint faults");
+    let checkpointer = checkpointer.map(|checkpointer| {
+        Arc::new(ChaosCheckpointer::new(checkpointer, injector.clone()))
+            as Arc<dyn Checkpointer<ReActState>>
+    });
+    Ok((
+        Box::new(ChaosLlm::new(llm, injector.clone())),
+        Box::new(ChaosToolSource::new(tool_source, injector)),
+        checkpointer,
+    ))
+}
+
+/// Approval audit store from `approval_audit_db`; `None` when unset.
+fn build_approval_audit(
+    config: &ReactBuildConfig,
+) -> Result<Option<Arc<dyn ApprovalAuditStore>>, ApprovalAuditError> {
+    let Some(path) = &config.approval_audit_db else {
+        return Ok(None);
+    };
+    let store = SqliteApprovalAuditStore::new(path)?;
+    Ok(Some(Arc::new(store)))
+}
+
+/// Tool result framing from config; the sanitizer is only set when `sanitize_tool_results` is on.
+fn build_tool_result_framing(config: &ReactBuildConfig) -> Result<ToolResultFraming, regex::Error> {
+    let sanitizer = if config.sanitize_tool_results {
+        Some(InjectionSanitizer::with_extra_patterns(
+            &config.injection_patterns,
+        )?)
+    } else {
+        None
+    };
+    Ok(ToolResultFraming::new(config.tool_result_framing)
+        .with_untrusted_tools(config.untrusted_tools.clone())
+        .with_sanitizer(sanitizer))
+}
+
 struct BoxedLlmClient(Box<dyn LlmClient>);
 
 #[async_trait::async_trait]
@@ -196,6 +611,17 @@ impl LlmClient for BoxedLlmClient {
     ) -> Result<crate::llm::LlmResponse, AgentError> {
         self.0.invoke_stream(messages, tx).await
     }
+    fn set_tools(&self, tools: Vec<crate::tool_source::ToolSpec>) {
+        self.0.set_tools(tools);
+    }
+
+    fn tool_support(&self) -> crate::model_spec::ToolSupport {
+        self.0.tool_support()
+    }
+
+    fn route(&self) -> Option<String> {
+        self.0.route()
+    }
 }
 
 pub async fn build_dup_runner(
@@ -233,11 +659,9 @@ pub async fn build_tot_runner(
     llm: Option<Box<dyn LlmClient>>,
     verbose: bool,
 ) -> Result<TotRunner, BuildRunnerError> {
+    warn_auto_continue_unsupported(config, "tot");
     let ctx = build_react_run_context(config).await?;
-    let llm = match llm {
-        Some(l) => l,
-        None => build_default_llm_with_tool_source(config, ctx.tool_source.as_ref()).await?,
-    };
+    let (llm, node_llms) = resolve_llms(config, llm, ctx.tool_source.as_ref()).await?;
     let llm_arc: Arc<dyn LlmClient> = Arc::new(BoxedLlmClient(llm));
 
     let db_path_owned = resolve_memory_db_path(config);
@@ -258,6 +682,8 @@ pub async fn build_tot_runner(
         tot.max_depth,
         tot.candidates_per_step,
         tot.research_quality_addon,
+        tot.expand_concurrency,
+        node_llms,
     )?;
     Ok(runner)
 }
@@ -267,11 +693,9 @@ pub async fn build_got_runner(
     llm: Option<Box<dyn LlmClient>>,
     verbose: bool,
 ) -> Result<GotRunner, BuildRunnerError> {
+    warn_auto_continue_unsupported(config, "got");
     let ctx = build_react_run_context(config).await?;
-    let llm = match llm {
-        Some(l) => l,
-        None => build_default_llm_with_tool_source(config, ctx.tool_source.as_ref()).await?,
-    };
+    let (llm, node_llms) = resolve_llms(config, llm, ctx.tool_source.as_ref()).await?;
     let llm_arc: Arc<dyn LlmClient> = Arc::new(BoxedLlmClient(llm));
 
     let db_path_owned = resolve_memory_db_path(config);
@@ -289,7 +713,9 @@ pub async fn build_got_runner(
         verbose,
         got.adaptive,
         got.agot_llm_complexity,
-    )?;
+        node_llms,
+    )?
+    .with_token_budget(got.token_budget);
     Ok(runner)
 }
 
@@ -335,18 +761,51 @@ mod tests {
             model: None,
             llm_provider: None,
             openai_temperature: None,
+            stop_sequences: Vec::new(),
             embedding_api_key: None,
             embedding_base_url: None,
             embedding_model: None,
             working_folder: None,
             approval_policy: None,
             compaction_config: None,
+            history_window: None,
+            state_limits: None,
+            graph_spec: None,
+            llm_routes: Vec::new(),
+            flags: Default::default(),
             tot_config: TotRunnerConfig::default(),
             got_config: GotRunnerConfig::default(),
             mcp_servers: None,
             skill_registry: None,
+            history_search: None,
             max_sub_agent_depth: None,
             dry_run: false,
+            node_models: Default::default(),
+            auto_continue: false,
+            context_fallback_model: None,
+            enable_reflection: false,
+            confidence_scoring: None,
+            dedup_observations: false,
+            route_rules: Vec::new(),
+            approval_rules: Vec::new(),
+            approval_audit_db: None,
+            tool_result_framing: Default::default(),
+            untrusted_tools: Vec::new(),
+            sanitize_tool_results: false,
+            injection_patterns: Vec::new(),
+            memory_recall: None,
+            env_context: false,
+            observation_summary_tokens: None,
+            tool_prefetch: Vec::new(),
+            tool_arguments: Default::default(),
+            allowed_tools: None,
+            read_only: false,
+            denied_tools: Vec::new(),
+            tool_selection: None,
+            tool_call_mode: None,
+            offline: false,
+            offline_script: None,
+            node_middleware: Default::default(),
         }
     }
 
@@ -391,10 +850,32 @@ mod tests {
         assert!(!tools.is_empty());
     }
 
+    #[tokio::test]
+    async fn offline_build_drops_network_tools_and_uses_mock_llm() {
+        let mut cfg = base_config();
+        cfg.exa_api_key = Some("k".into());
+        cfg.offline = true;
+        let ctx = build_react_run_context(&cfg).await.unwrap();
+        let tools = ctx.tool_source.list_tools().await.unwrap();
+        let names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
+        assert!(!names.is_empty());
+        assert!(!names.contains(&"websearch"));
+        assert!(!names.contains(&crate::tools::TOOL_WEB_FETCHER));
+
+        let llm = build_default_llm_with_tool_source(&cfg, ctx.tool_source.as_ref())
+            .await
+            .unwrap();
+        let reply = llm
+            .invoke(&[crate::message::Message::user("hello")])
+            .await
+            .unwrap();
+        assert_eq!(reply.content, "[offline] hello");
+    }
+
     #[tokio::test]
     async fn exa_codesearch_off_by_default_when_exa_key_set() {
         let mut cfg = base_config();
-        cfg.exa_api_key = Some("k".to_string());
+        cfg.exa_api_key = Some("k".into());
         cfg.exa_codesearch_enabled = false;
         let ctx = build_react_run_context(&cfg).await.unwrap();
         let tools = ctx.tool_source.list_tools().await.unwrap();
@@ -406,7 +887,7 @@ mod tests {
     #[tokio::test]
     async fn exa_codesearch_registered_when_flag_enabled() {
         let mut cfg = base_config();
-        cfg.exa_api_key = Some("k".to_string());
+        cfg.exa_api_key = Some("k".into());
         cfg.exa_codesearch_enabled = true;
         let ctx = build_react_run_context(&cfg).await.unwrap();
         let tools = ctx.tool_source.list_tools().await.unwrap();
@@ -19,13 +19,22 @@ pub(crate) fn build_store(
 fn build_vector_store(
     config: &ReactBuildConfig,
 ) -> Result<Arc<dyn crate::memory::Store>, AgentError> {
-    use crate::memory::{InMemoryVectorStore, OpenAIEmbedder};
+    let store = crate::memory::InMemoryVectorStore::new(build_embedder(config)?);
+    Ok(Arc::new(store) as Arc<dyn crate::memory::Store>)
+}
+
+/// Builds the OpenAI-compatible embedder from the embedding (or chat) credentials in `config`.
+pub(crate) fn build_embedder(
+    config: &ReactBuildConfig,
+) -> Result<Arc<dyn crate::memory::Embedder>, AgentError> {
+    use crate::memory::OpenAIEmbedder;
     use async_openai::config::OpenAIConfig;
 
     let api_key = config
         .embedding_api_key
-        .as_deref()
-        .or(config.openai_api_key.as_deref())
+        .as_ref()
+        .or(config.openai_api_key.as_ref())
+        .map(|s| s.expose())
         .filter(|s| !s.is_empty())
         .ok_or_else(|| {
             AgentError::ExecutionFailed(
@@ -47,7 +56,5 @@ fn build_vector_store(
         let b = b.trim_end_matches('/');
         openai_config = openai_config.with_api_base(b);
     }
-    let embedder = OpenAIEmbedder::with_config(openai_config, model);
-    let store = InMemoryVectorStore::new(Arc::new(embedder));
-    Ok(Arc::new(store) as Arc<dyn crate::memory::Store>)
+    Ok(Arc::new(OpenAIEmbedder::with_config(openai_config, model)))
 }
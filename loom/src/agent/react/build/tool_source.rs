@@ -1,20 +1,20 @@
 //! Builds tool source from ReactBuildConfig.
 
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::error::AgentError;
 use crate::tool_source::{
-    register_file_tools, McpToolSource, MemoryToolsSource, ToolSource, ToolSourceError,
-    YamlSpecToolSource,
+    register_file_tools, register_read_only_file_tools, McpConnect, McpConnectFuture,
+    McpToolSource, MemoryToolsSource, ToolSource, ToolSourceError, YamlSpecToolSource,
 };
 #[cfg(windows)]
 use crate::tools::powershell::PowerShellTool;
 #[cfg(not(windows))]
 use crate::tools::BashTool;
 use crate::tools::{
-    register_mcp_tools, register_mcp_tools_with_specs, AggregateToolSource, BatchTool,
-    ExaCodesearchTool, ExaWebsearchTool, InvokeAgentTool, LspTool, TwitterSearchTool,
-    WebFetcherTool,
+    AggregateToolSource, BatchTool, ExaCodesearchTool, ExaWebsearchTool, InvokeAgentTool, LspTool,
+    RawObservationTool, SearchHistoryTool, TwitterSearchTool, WebFetcherTool,
 };
 
 use env_config::McpServerDef;
@@ -27,6 +27,171 @@ fn to_agent_error(e: impl std::fmt::Display) -> AgentError {
 
 const DEFAULT_MEMORY_NAMESPACE: &[&str] = &["default", "memories"];
 
+/// Directory where the observation summarizer saves raw tool outputs:
+/// `.loom/observations` in the working folder, else the thread's session directory.
+pub(crate) fn observation_dir(config: &ReactBuildConfig) -> PathBuf {
+    match config.working_folder {
+        Some(ref wf) => wf.join(".loom").join("observations"),
+        None => {
+            env_config::home::thread_session_dir(config.thread_id.as_deref().unwrap_or("default"))
+                .join("observations")
+        }
+    }
+}
+
+/// Store namespace of the memory tools (and memory recall): `[user_id, "memories"]`, or
+/// [`DEFAULT_MEMORY_NAMESPACE`] without a user id.
+pub(crate) fn memory_namespace(config: &ReactBuildConfig) -> Vec<String> {
+    config
+        .user_id
+        .as_ref()
+        .map(|u| vec![u.clone(), "memories".to_string()])
+        .unwrap_or_else(|| {
+            DEFAULT_MEMORY_NAMESPACE
+                .iter()
+                .map(|s| (*s).to_string())
+                .collect()
+        })
+}
+
+/// Registers the platform shell tool (bash, or powershell on Windows) scoped to the working
+/// folder when one is set, plus the python tool and the SSH tools when built with their
+/// features. Not called in read-only mode.
+async fn register_shell_tool(
+    aggregate: &AggregateToolSource,
+    working_folder: &Option<Arc<PathBuf>>,
+) {
+    #[cfg(not(windows))]
+    {
+        let bash_tool = match working_folder {
+            Some(wf) => BashTool::with_working_folder(Arc::clone(wf)),
+            None => BashTool::new(),
+        };
+        aggregate.register_async(Box::new(bash_tool)).await;
+    }
+    #[cfg(windows)]
+    {
+        let ps_tool = match working_folder {
+            Some(wf) => PowerShellTool::with_working_folder(Arc::clone(wf)),
+            None => PowerShellTool::new(),
+        };
+        aggregate.register_async(Box::new(ps_tool)).await;
+    }
+    #[cfg(feature = "python")]
+    aggregate
+        .register_async(Box::new(crate::tools::PythonTool::default()))
+        .await;
+    #[cfg(feature = "ssh")]
+    register_ssh_tools(aggregate, working_folder).await;
+}
+
+/// Registers `ssh_exec`, `scp_get` and `scp_put` for the hosts in the YAML file named by
+/// `LOOM_SSH_HOSTS`, when set. A file that cannot be loaded is logged and skipped.
+#[cfg(feature = "ssh")]
+async fn register_ssh_tools(
+    aggregate: &AggregateToolSource,
+    working_folder: &Option<Arc<PathBuf>>,
+) {
+    let Some(path) = std::env::var("LOOM_SSH_HOSTS")
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+    else {
+        return;
+    };
+    match crate::tools::SshHosts::load(std::path::Path::new(path.trim())) {
+        Ok(hosts) => {
+            crate::tool_source::SshToolsSource::register(aggregate, hosts, working_folder.clone())
+                .await
+        }
+        Err(e) => tracing::warn!("SSH tools not registered: {}", e),
+    }
+}
+
+/// Connector for an MCP server started over stdio; tools are fetched in `spawn_blocking`.
+fn stdio_mcp_connect(
+    command: String,
+    args: Vec<String>,
+    env: Vec<(String, String)>,
+    mcp_verbose: bool,
+) -> McpConnect {
+    Arc::new(move || -> McpConnectFuture {
+        let (command, args, env) = (command.clone(), args.clone(), env.clone());
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || {
+                let mcp = McpToolSource::new_with_env(command, args, env.into_iter(), mcp_verbose)
+                    .map_err(|e| ToolSourceError::Transport(e.to_string()))?;
+                let specs = mcp.list_tools_sync()?;
+                Ok::<_, ToolSourceError>((mcp, Some(specs)))
+            })
+            .await
+            .map_err(|e| ToolSourceError::Transport(format!("spawn_blocking join failed: {}", e)))?
+        })
+    })
+}
+
+/// Connector for an MCP server over Streamable HTTP.
+fn http_mcp_connect(url: String, headers: Vec<(String, String)>) -> McpConnect {
+    Arc::new(move || -> McpConnectFuture {
+        let (url, headers) = (url.clone(), headers.clone());
+        Box::pin(async move {
+            let mcp = McpToolSource::new_http(url, headers).await?;
+            Ok((mcp, None))
+        })
+    })
+}
+
+/// Registers the configured MCP servers, and the GitHub MCP server when a token is set, as lazy
+/// servers (see [`AggregateToolSource::register_lazy_mcp`]): they connect when the tools are
+/// first listed, and one that cannot be reached is skipped instead of failing the build.
+fn register_mcp_servers(aggregate: &AggregateToolSource, config: &ReactBuildConfig) {
+    for def in config.mcp_servers.iter().flatten() {
+        match def {
+            McpServerDef::Stdio {
+                name,
+                command,
+                args,
+                env,
+            } => {
+                let env = env.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+                aggregate.register_lazy_mcp(
+                    name.clone(),
+                    stdio_mcp_connect(command.clone(), args.clone(), env, config.mcp_verbose),
+                );
+            }
+            McpServerDef::Http { name, url, headers } => {
+                let headers = headers
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect();
+                aggregate.register_lazy_mcp(name.clone(), http_mcp_connect(url.clone(), headers));
+            }
+        }
+    }
+    let Some(ref token) = config.github_token else {
+        return;
+    };
+    let http_url = config
+        .mcp_github_url
+        .as_deref()
+        .filter(|u| u.starts_with("http://") || u.starts_with("https://"));
+    let connect = match http_url {
+        Some(url) => http_mcp_connect(
+            url.to_string(),
+            vec![(
+                "Authorization".to_string(),
+                format!("Bearer {}", token.expose()),
+            )],
+        ),
+        None => stdio_mcp_connect(
+            config.mcp_github_cmd.clone(),
+            config.mcp_github_args.clone(),
+            vec![("GITHUB_TOKEN".to_string(), token.expose().to_string())],
+            config.mcp_verbose,
+        ),
+    };
+    aggregate.register_lazy_mcp("github", connect);
+}
+
 pub(crate) async fn build_tool_source(
     config: &ReactBuildConfig,
     store: &Option<Arc<dyn crate::memory::Store>>,
@@ -42,153 +207,18 @@ pub(crate) async fn build_tool_source(
         aggregate
             .register_async(Box::new(WebFetcherTool::new()))
             .await;
-        #[cfg(not(windows))]
-        let bash_tool = match &working_folder_arc {
-            Some(wf) => BashTool::with_working_folder(Arc::clone(wf)),
-            None => BashTool::new(),
-        };
-        #[cfg(not(windows))]
-        aggregate.register_async(Box::new(bash_tool)).await;
-        #[cfg(windows)]
-        {
-            let ps_tool = match &working_folder_arc {
-                Some(wf) => PowerShellTool::with_working_folder(Arc::clone(wf)),
-                None => PowerShellTool::new(),
-            };
-            aggregate.register_async(Box::new(ps_tool)).await;
+        if !config.read_only {
+            register_shell_tool(aggregate.as_ref(), &working_folder_arc).await;
         }
         aggregate.register_sync(Box::new(BatchTool::new(Arc::clone(&aggregate))));
         aggregate.register_sync(Box::new(LspTool::default()));
-        if let Some(ref servers) = config.mcp_servers {
-            for def in servers {
-                match def {
-                    McpServerDef::Stdio {
-                        name,
-                        command,
-                        args,
-                        env,
-                    } => {
-                        tracing::debug!(name = %name, "starting MCP stdio server (spawn_blocking, pre-fetch tools)");
-                        let command = command.clone();
-                        let args = args.clone();
-                        let env_vec: Vec<(String, String)> =
-                            env.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
-                        let mcp_verbose = config.mcp_verbose;
-                        let create_result = tokio::task::spawn_blocking(move || {
-                            let mcp = McpToolSource::new_with_env(
-                                command,
-                                args,
-                                env_vec.into_iter(),
-                                mcp_verbose,
-                            )
-                            .map_err(|e| ToolSourceError::Transport(e.to_string()))?;
-                            let specs = mcp.list_tools_sync()?;
-                            Ok::<_, ToolSourceError>((mcp, specs))
-                        })
-                        .await;
-                        match create_result {
-                            Ok(Ok((mcp, specs))) => {
-                                register_mcp_tools_with_specs(
-                                    aggregate.as_ref(),
-                                    Arc::new(mcp),
-                                    specs,
-                                )
-                                .await;
-                            }
-                            Ok(Err(e)) => {
-                                tracing::warn!(
-                                    name = %name,
-                                    "mcp server failed to start or list tools, skipping: {}",
-                                    e
-                                );
-                            }
-                            Err(join_e) => {
-                                tracing::warn!(
-                                    name = %name,
-                                    "mcp server spawn_blocking join failed: {}",
-                                    join_e
-                                );
-                            }
-                        }
-                    }
-                    McpServerDef::Http { name, url, headers } => {
-                        let headers_iter = headers.iter().map(|(k, v)| (k.as_str(), v.as_str()));
-                        match McpToolSource::new_http(url.clone(), headers_iter).await {
-                            Ok(mcp) => {
-                                if let Err(e) =
-                                    register_mcp_tools(aggregate.as_ref(), Arc::new(mcp)).await
-                                {
-                                    tracing::warn!(
-                                        name = %name,
-                                        "mcp server (HTTP) registered but list/call may fail: {}",
-                                        e
-                                    );
-                                }
-                            }
-                            Err(e) => {
-                                tracing::warn!(
-                                    name = %name,
-                                    "mcp server (HTTP) failed to connect, skipping: {}",
-                                    e
-                                );
-                            }
-                        }
-                    }
-                }
-            }
+        if let Some(ref search) = config.history_search {
+            aggregate.register_sync(Box::new(SearchHistoryTool::new(search.clone())));
         }
-        if let Some(ref token) = config.github_token {
-            let use_http = config
-                .mcp_github_url
-                .as_deref()
-                .map(|u| u.starts_with("http://") || u.starts_with("https://"))
-                .unwrap_or(false);
-            if use_http {
-                let url = config.mcp_github_url.as_deref().unwrap();
-                match McpToolSource::new_http(url, [("Authorization", format!("Bearer {}", token))])
-                    .await
-                {
-                    Ok(mcp) => {
-                        if let Err(e) = register_mcp_tools(aggregate.as_ref(), Arc::new(mcp)).await
-                        {
-                            tracing::warn!(
-                                "GitHub MCP (HTTP) registered but list/call may fail: {}",
-                                e
-                            );
-                        }
-                    }
-                    Err(e) => {
-                        tracing::warn!("GitHub MCP (HTTP) failed to connect, skipping: {}", e);
-                    }
-                }
-            } else {
-                tracing::debug!("starting GitHub MCP (stdio, spawn_blocking, pre-fetch tools)");
-                let cmd = config.mcp_github_cmd.clone();
-                let args = config.mcp_github_args.clone();
-                let env_github = vec![("GITHUB_TOKEN".to_string(), token.clone())];
-                let mcp_verbose = config.mcp_verbose;
-                let create_result = tokio::task::spawn_blocking(move || {
-                    let mcp =
-                        McpToolSource::new_with_env(cmd, args, env_github.into_iter(), mcp_verbose)
-                            .map_err(|e| ToolSourceError::Transport(e.to_string()))?;
-                    let specs = mcp.list_tools_sync()?;
-                    Ok::<_, ToolSourceError>((mcp, specs))
-                })
-                .await;
-                match create_result {
-                    Ok(Ok((mcp, specs))) => {
-                        register_mcp_tools_with_specs(aggregate.as_ref(), Arc::new(mcp), specs)
-                            .await;
-                    }
-                    Ok(Err(e)) => {
-                        tracing::warn!("GitHub MCP failed to start or list tools, skipping: {}", e);
-                    }
-                    Err(join_e) => {
-                        tracing::warn!("GitHub MCP spawn_blocking join failed: {}", join_e);
-                    }
-                }
-            }
+        if config.observation_summary_tokens.is_some() {
+            aggregate.register_sync(Box::new(RawObservationTool::new(observation_dir(config))));
         }
+        register_mcp_servers(aggregate.as_ref(), config);
         aggregate
             .register_async(Box::new(InvokeAgentTool::new(
                 Arc::new(config.clone()),
@@ -204,17 +234,7 @@ pub(crate) async fn build_tool_source(
 
     let base = if has_memory {
         let s = store.as_ref().unwrap();
-        let namespace: Vec<String> = config
-            .user_id
-            .as_ref()
-            .map(|u| vec![u.clone(), "memories".to_string()])
-            .unwrap_or_else(|| {
-                DEFAULT_MEMORY_NAMESPACE
-                    .iter()
-                    .map(|s| (*s).to_string())
-                    .collect()
-            });
-        MemoryToolsSource::new(s.clone(), namespace).await
+        MemoryToolsSource::new(s.clone(), memory_namespace(config)).await
     } else {
         AggregateToolSource::new()
     };
@@ -223,170 +243,50 @@ pub(crate) async fn build_tool_source(
     aggregate
         .register_async(Box::new(WebFetcherTool::new()))
         .await;
-    #[cfg(not(windows))]
-    let bash_tool = match &working_folder_arc {
-        Some(wf) => BashTool::with_working_folder(Arc::clone(wf)),
-        None => BashTool::new(),
-    };
-    #[cfg(not(windows))]
-    aggregate.register_async(Box::new(bash_tool)).await;
-
-    #[cfg(windows)]
-    {
-        let ps_tool = match &working_folder_arc {
-            Some(wf) => PowerShellTool::with_working_folder(Arc::clone(wf)),
-            None => PowerShellTool::new(),
-        };
-        aggregate.register_async(Box::new(ps_tool)).await;
+    if !config.read_only {
+        register_shell_tool(aggregate.as_ref(), &working_folder_arc).await;
     }
 
     if let Some(ref key) = config.twitter_api_key {
         aggregate
-            .register_async(Box::new(TwitterSearchTool::new(key.clone())))
+            .register_async(Box::new(TwitterSearchTool::new(key.expose().to_string())))
             .await;
     }
     if let Some(ref key) = config.exa_api_key {
         aggregate
-            .register_async(Box::new(ExaWebsearchTool::new(key.clone())))
+            .register_async(Box::new(ExaWebsearchTool::new(key.expose().to_string())))
             .await;
         if config.exa_codesearch_enabled {
             aggregate
-                .register_async(Box::new(ExaCodesearchTool::new(key.clone())))
+                .register_async(Box::new(ExaCodesearchTool::new(key.expose().to_string())))
                 .await;
         }
     }
     if let Some(ref wf) = config.working_folder {
-        register_file_tools(aggregate.as_ref(), wf, config.skill_registry.clone())
-            .map_err(to_agent_error)?;
+        if config.read_only {
+            register_read_only_file_tools(aggregate.as_ref(), wf, config.skill_registry.clone())
+        } else {
+            register_file_tools(aggregate.as_ref(), wf, config.skill_registry.clone())
+        }
+        .map_err(to_agent_error)?;
+        #[cfg(feature = "screenshot")]
+        aggregate
+            .register_async(Box::new(crate::tools::ScreenshotTool::new(Arc::new(
+                wf.clone(),
+            ))))
+            .await;
     }
     aggregate.register_sync(Box::new(BatchTool::new(Arc::clone(&aggregate))));
     aggregate.register_sync(Box::new(LspTool::default()));
-
-    if let Some(ref servers) = config.mcp_servers {
-        for def in servers {
-            match def {
-                McpServerDef::Stdio {
-                    name,
-                    command,
-                    args,
-                    env,
-                } => {
-                    tracing::debug!(name = %name, "starting MCP stdio server (spawn_blocking, pre-fetch tools)");
-                    let command = command.clone();
-                    let args = args.clone();
-                    let env_vec: Vec<(String, String)> =
-                        env.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
-                    let mcp_verbose = config.mcp_verbose;
-                    let create_result = tokio::task::spawn_blocking(move || {
-                        let mcp = McpToolSource::new_with_env(
-                            command,
-                            args,
-                            env_vec.into_iter(),
-                            mcp_verbose,
-                        )
-                        .map_err(|e| ToolSourceError::Transport(e.to_string()))?;
-                        let specs = mcp.list_tools_sync()?;
-                        Ok::<_, ToolSourceError>((mcp, specs))
-                    })
-                    .await;
-                    match create_result {
-                        Ok(Ok((mcp, specs))) => {
-                            register_mcp_tools_with_specs(aggregate.as_ref(), Arc::new(mcp), specs)
-                                .await;
-                        }
-                        Ok(Err(e)) => {
-                            tracing::warn!(
-                                name = %name,
-                                "mcp server failed to start or list tools, skipping: {}",
-                                e
-                            );
-                        }
-                        Err(join_e) => {
-                            tracing::warn!(
-                                name = %name,
-                                "mcp server spawn_blocking join failed: {}",
-                                join_e
-                            );
-                        }
-                    }
-                }
-                McpServerDef::Http { name, url, headers } => {
-                    let headers_iter = headers.iter().map(|(k, v)| (k.as_str(), v.as_str()));
-                    match McpToolSource::new_http(url.clone(), headers_iter).await {
-                        Ok(mcp) => {
-                            if let Err(e) =
-                                register_mcp_tools(aggregate.as_ref(), Arc::new(mcp)).await
-                            {
-                                tracing::warn!(
-                                    name = %name,
-                                    "mcp server (HTTP) registered but list/call may fail: {}",
-                                    e
-                                );
-                            }
-                        }
-                        Err(e) => {
-                            tracing::warn!(
-                                name = %name,
-                                "mcp server (HTTP) failed to connect, skipping: {}",
-                                e
-                            );
-                        }
-                    }
-                }
-            }
-        }
+    if let Some(ref search) = config.history_search {
+        aggregate.register_sync(Box::new(SearchHistoryTool::new(search.clone())));
     }
-    if let Some(ref token) = config.github_token {
-        let use_http = config
-            .mcp_github_url
-            .as_deref()
-            .map(|u| u.starts_with("http://") || u.starts_with("https://"))
-            .unwrap_or(false);
-        if use_http {
-            let url = config.mcp_github_url.as_deref().unwrap();
-            match McpToolSource::new_http(url, [("Authorization", format!("Bearer {}", token))])
-                .await
-            {
-                Ok(mcp) => {
-                    if let Err(e) = register_mcp_tools(aggregate.as_ref(), Arc::new(mcp)).await {
-                        tracing::warn!(
-                            "GitHub MCP (HTTP) registered but list/call may fail: {}",
-                            e
-                        );
-                    }
-                }
-                Err(e) => {
-                    tracing::warn!("GitHub MCP (HTTP) failed to connect, skipping: {}", e);
-                }
-            }
-        } else {
-            tracing::debug!("starting GitHub MCP (stdio, spawn_blocking, pre-fetch tools)");
-            let cmd = config.mcp_github_cmd.clone();
-            let args = config.mcp_github_args.clone();
-            let env_github = vec![("GITHUB_TOKEN".to_string(), token.clone())];
-            let mcp_verbose = config.mcp_verbose;
-            let create_result = tokio::task::spawn_blocking(move || {
-                let mcp =
-                    McpToolSource::new_with_env(cmd, args, env_github.into_iter(), mcp_verbose)
-                        .map_err(|e| ToolSourceError::Transport(e.to_string()))?;
-                let specs = mcp.list_tools_sync()?;
-                Ok::<_, ToolSourceError>((mcp, specs))
-            })
-            .await;
-            match create_result {
-                Ok(Ok((mcp, specs))) => {
-                    register_mcp_tools_with_specs(aggregate.as_ref(), Arc::new(mcp), specs).await;
-                }
-                Ok(Err(e)) => {
-                    tracing::warn!("GitHub MCP failed to start or list tools, skipping: {}", e);
-                }
-                Err(join_e) => {
-                    tracing::warn!("GitHub MCP spawn_blocking join failed: {}", join_e);
-                }
-            }
-        }
+    if config.observation_summary_tokens.is_some() {
+        aggregate.register_sync(Box::new(RawObservationTool::new(observation_dir(config))));
     }
 
+    register_mcp_servers(aggregate.as_ref(), config);
+
     aggregate
         .register_async(Box::new(InvokeAgentTool::new(
             Arc::new(config.clone()),
@@ -0,0 +1,291 @@
+//! Confidence node: score the final answer before the turn ends.
+//!
+//! Runs after the turn's last think (and after verify, when reflection is on). An LLM rates the
+//! answer against the user's question and the tool results gathered in this turn, or a
+//! heuristic derives the score from how many tool calls succeeded; the result is stored in
+//! [`ReActState::confidence`] so callers can route low-confidence answers to human review.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use tracing::{debug, warn};
+
+use super::verify_node::{strip_json_fence, VerifyNode, REFLECTION_FEEDBACK_PREFIX};
+use crate::error::AgentError;
+use crate::graph::Next;
+use crate::llm::LlmClient;
+use crate::message::Message;
+use crate::state::{AnswerConfidence, ConfidenceScoring, ReActState};
+use crate::Node;
+
+/// Default system prompt for confidence scoring.
+const DEFAULT_SYSTEM_PROMPT: &str = r#"You rate how confident a reader can be in an assistant's answer.
+
+You get the user's question, the evidence the assistant gathered with tools, and the answer.
+Consider whether every part of the question is answered, whether the answer is supported by the
+evidence, and which claims are guesses or were not checked.
+
+Respond ONLY with JSON:
+{"confidence": <number from 0.0 to 1.0>, "uncertainties": ["<open question or unverified claim>", ...]}
+Use an empty list when nothing is uncertain."#;
+
+/// Heuristic score of an answer given without any tool call.
+const NO_TOOLS_SCORE: f32 = 0.7;
+
+/// Phrases in an answer that admit it is unsure (matched lowercase).
+const HEDGES: &[&str] = &[
+    "i'm not sure",
+    "i am not sure",
+    "not certain",
+    "i couldn't",
+    "i could not",
+    "i was unable",
+    "unable to verify",
+    "i don't know",
+];
+
+#[derive(Debug, Deserialize)]
+struct ScoreResponse {
+    confidence: f32,
+    #[serde(default)]
+    uncertainties: Vec<String>,
+}
+
+/// Node that scores the final answer of the turn and stores it in
+/// [`ReActState::confidence`].
+///
+/// Enabled via [`crate::ReactBuildConfig::confidence_scoring`]. With
+/// [`ConfidenceScoring::Llm`], a failed or unparsable LLM call falls back to the heuristic.
+/// Turns that end without an answer score 0.
+pub struct ConfidenceNode {
+    llm: Arc<dyn LlmClient>,
+    scoring: ConfidenceScoring,
+    system_prompt: String,
+}
+
+impl ConfidenceNode {
+    /// Creates a confidence node scoring with `scoring`; `llm` is only called for
+    /// [`ConfidenceScoring::Llm`].
+    pub fn new(llm: Arc<dyn LlmClient>, scoring: ConfidenceScoring) -> Self {
+        Self {
+            llm,
+            scoring,
+            system_prompt: DEFAULT_SYSTEM_PROMPT.to_string(),
+        }
+    }
+
+    /// Sets a custom system prompt for LLM scoring.
+    pub fn with_system_prompt(mut self, prompt: String) -> Self {
+        self.system_prompt = prompt;
+        self
+    }
+
+    async fn score_with_llm(&self, prompt: String) -> Result<AnswerConfidence, AgentError> {
+        let messages = vec![
+            Message::system(self.system_prompt.clone()),
+            Message::user(prompt),
+        ];
+        let response = self.llm.invoke(&messages).await?;
+        let scored: ScoreResponse = serde_json::from_str(strip_json_fence(&response.content))
+            .map_err(|e| {
+                AgentError::ExecutionFailed(format!("Failed to parse confidence response: {}", e))
+            })?;
+        Ok(AnswerConfidence {
+            score: scored.confidence.clamp(0.0, 1.0),
+            uncertainties: scored
+                .uncertainties
+                .into_iter()
+                .filter(|u| !u.trim().is_empty())
+                .collect(),
+            scoring: ConfidenceScoring::Llm,
+        })
+    }
+}
+
+/// Scores the turn's answer from its tool calls: 0.5 plus half the share of calls that returned
+/// a result without error, or [`NO_TOOLS_SCORE`] without calls; an answer that hedges loses 0.2.
+pub fn heuristic_confidence(state: &ReActState) -> AnswerConfidence {
+    let scored = |score: f32, uncertainties: Vec<String>| AnswerConfidence {
+        score: score.clamp(0.0, 1.0),
+        uncertainties,
+        scoring: ConfidenceScoring::Heuristic,
+    };
+    let answer = match state.messages.last() {
+        Some(Message::Assistant(p)) if !p.content.trim().is_empty() => p.content.to_lowercase(),
+        _ => return scored(0.0, vec!["no final answer was given".to_string()]),
+    };
+    let turn_start = state
+        .messages
+        .iter()
+        .rposition(|m| {
+            matches!(m, Message::User(_)) && !m.content().starts_with(REFLECTION_FEEDBACK_PREFIX)
+        })
+        .map_or(0, |i| i + 1);
+    let turn = &state.messages[turn_start..];
+    let calls: usize = turn
+        .iter()
+        .map(|m| match m {
+            Message::Assistant(p) => p.tool_calls.len(),
+            _ => 0,
+        })
+        .sum();
+    let results: Vec<String> = turn
+        .iter()
+        .filter_map(|m| match m {
+            Message::Tool { content, .. } => Some(content.to_display_string()),
+            _ => None,
+        })
+        .collect();
+    let failed = results.iter().filter(|r| is_error_result(r)).count();
+
+    let mut uncertainties = Vec::new();
+    let mut score = if calls == 0 {
+        uncertainties.push("the answer is not backed by tool results".to_string());
+        NO_TOOLS_SCORE
+    } else {
+        let ok = results.len().saturating_sub(failed).min(calls);
+        if failed > 0 {
+            uncertainties.push(format!("{} of {} tool calls failed", failed, calls));
+        }
+        if results.len() < calls {
+            uncertainties.push(format!(
+                "{} of {} tool calls returned no result",
+                calls - results.len(),
+                calls
+            ));
+        }
+        0.5 + 0.5 * ok as f32 / calls as f32
+    };
+    if HEDGES.iter().any(|h| answer.contains(h)) {
+        uncertainties.push("the answer says it is unsure".to_string());
+        score -= 0.2;
+    }
+    scored(score, uncertainties)
+}
+
+/// Whether an observed tool result is an error (`Tool <name> error:` or a framed result with
+/// `status="error"`; see [`crate::ToolResultFraming::format`]).
+fn is_error_result(text: &str) -> bool {
+    let first = text.lines().next().unwrap_or("");
+    (first.starts_with("Tool ") && first.ends_with(" error:")) || first.contains("status=\"error\"")
+}
+
+#[async_trait]
+impl Node<ReActState> for ConfidenceNode {
+    fn id(&self) -> &str {
+        "confidence"
+    }
+
+    async fn run(&self, mut state: ReActState) -> Result<(ReActState, Next), AgentError> {
+        let confidence = match (self.scoring, VerifyNode::review_prompt(&state)) {
+            (ConfidenceScoring::Llm, Some(prompt)) => match self.score_with_llm(prompt).await {
+                Ok(confidence) => confidence,
+                Err(e) => {
+                    warn!(error = %e, "Confidence scoring failed, using the heuristic");
+                    heuristic_confidence(&state)
+                }
+            },
+            _ => heuristic_confidence(&state),
+        };
+        debug!(
+            score = confidence.score,
+            scoring = confidence.scoring.as_str(),
+            "Scored final answer"
+        );
+        state.confidence = Some(confidence);
+        Ok((state, Next::End))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlm;
+    use crate::message::AssistantToolCall;
+    use crate::tool_source::ToolCallContent;
+
+    fn answered_state(results: &[&str], answer: &str) -> ReActState {
+        let calls = (0..results.len())
+            .map(|i| AssistantToolCall {
+                id: format!("call_{}", i),
+                name: "web_fetcher".to_string(),
+                arguments: "{}".to_string(),
+            })
+            .collect::<Vec<_>>();
+        let mut messages = vec![Message::user("What is the capital of Australia?")];
+        if !calls.is_empty() {
+            messages.push(Message::assistant_with_tool_calls(String::new(), calls));
+        }
+        for (i, result) in results.iter().enumerate() {
+            messages.push(Message::Tool {
+                tool_call_id: format!("call_{}", i),
+                content: ToolCallContent::text(*result),
+            });
+        }
+        messages.push(Message::assistant(answer));
+        ReActState {
+            messages,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn llm_scoring_stores_score_and_uncertainties() {
+        let llm = MockLlm::with_no_tool_calls(
+            "```json\n{\"confidence\": 0.4, \"uncertainties\": [\"the source is from 2019\"]}\n```",
+        );
+        let node = ConfidenceNode::new(Arc::new(llm), ConfidenceScoring::Llm);
+        let state = answered_state(&["Tool web_fetcher result:\nCanberra"], "Canberra.");
+
+        let (state, next) = node.run(state).await.unwrap();
+
+        assert!(matches!(next, Next::End));
+        let confidence = state.confidence.unwrap();
+        assert_eq!(confidence.score, 0.4);
+        assert_eq!(confidence.uncertainties, vec!["the source is from 2019"]);
+        assert_eq!(confidence.scoring, ConfidenceScoring::Llm);
+        assert!(confidence.is_below(0.5));
+    }
+
+    #[tokio::test]
+    async fn unparsable_llm_score_falls_back_to_heuristic() {
+        let llm = MockLlm::with_no_tool_calls("quite confident");
+        let node = ConfidenceNode::new(Arc::new(llm), ConfidenceScoring::Llm);
+
+        let (state, _) = node
+            .run(answered_state(
+                &["Tool web_fetcher result:\nCanberra"],
+                "Canberra.",
+            ))
+            .await
+            .unwrap();
+
+        let confidence = state.confidence.unwrap();
+        assert_eq!(confidence.scoring, ConfidenceScoring::Heuristic);
+        assert_eq!(confidence.score, 1.0);
+    }
+
+    #[test]
+    fn heuristic_counts_failed_calls_and_hedging() {
+        let state = answered_state(
+            &[
+                "Tool web_fetcher result:\nCanberra",
+                "Tool web_fetcher error:\ntimeout",
+            ],
+            "Canberra, I think, but I'm not sure.",
+        );
+        let confidence = heuristic_confidence(&state);
+        assert!((confidence.score - 0.55).abs() < 1e-6);
+        assert_eq!(
+            confidence.uncertainties,
+            vec!["1 of 2 tool calls failed", "the answer says it is unsure"]
+        );
+
+        let no_tools = heuristic_confidence(&answered_state(&[], "Canberra."));
+        assert_eq!(no_tools.score, NO_TOOLS_SCORE);
+
+        let no_answer = heuristic_confidence(&ReActState::default());
+        assert_eq!(no_answer.score, 0.0);
+    }
+}
@@ -1,10 +1,13 @@
 //! Configuration for building a ReAct run context.
 
-use env_config::McpServerDef;
+use env_config::{McpServerDef, Secret};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::graph::{NodeHooks, NodeMiddleware, NodeMiddlewareStack};
 use crate::skill::SkillRegistry;
+use crate::state::ReActState;
 
 /// ToT-specific runner config (max depth, candidates per step, etc.).
 #[derive(Clone, Debug)]
@@ -12,6 +15,10 @@ pub struct TotRunnerConfig {
     pub max_depth: u32,
     pub candidates_per_step: u32,
     pub research_quality_addon: bool,
+    /// Max concurrent candidate-generation calls; each candidate then comes from its own call.
+    /// 0 or 1 (the default) asks for all candidates in one call. Set via
+    /// `LOOM_TOT_EXPAND_CONCURRENCY`.
+    pub expand_concurrency: u32,
 }
 
 impl Default for TotRunnerConfig {
@@ -20,35 +27,43 @@ impl Default for TotRunnerConfig {
             max_depth: 5,
             candidates_per_step: 3,
             research_quality_addon: false,
+            expand_concurrency: 1,
         }
     }
 }
 
-/// GoT-specific runner config (adaptive mode, AGoT LLM complexity).
+/// GoT-specific runner config (adaptive mode, AGoT LLM complexity, token budget).
 #[derive(Clone, Debug, Default)]
 pub struct GotRunnerConfig {
     pub adaptive: bool,
     pub agot_llm_complexity: bool,
+    /// Tokens one run may use; see [`GotRunner::with_token_budget`](crate::GotRunner::with_token_budget).
+    /// Set via `LOOM_GOT_TOKEN_BUDGET`.
+    pub token_budget: Option<u64>,
 }
 
 /// Configuration for building ReAct run context.
+///
+/// API keys and tokens are [`Secret`]s: `Debug` output never contains them. `from_env` resolves
+/// each via [`env_config::read_secret`] (env `NAME`, then `NAME_FILE`, then the OS keychain when
+/// `LOOM_SECRETS_KEYCHAIN` is set).
 #[derive(Clone, Debug)]
 pub struct ReactBuildConfig {
     pub db_path: Option<String>,
     pub thread_id: Option<String>,
     pub user_id: Option<String>,
     pub system_prompt: Option<String>,
-    pub exa_api_key: Option<String>,
+    pub exa_api_key: Option<Secret>,
     /// When `EXA_API_KEY` is set, register the Exa `codesearch` tool only if this is true.
     /// Opt-in via env `LOOM_EXA_CODESEARCH` (`1`, `true`, or `yes`, case-insensitive). Default off.
     pub exa_codesearch_enabled: bool,
-    pub twitter_api_key: Option<String>,
+    pub twitter_api_key: Option<Secret>,
     pub mcp_exa_url: String,
     pub mcp_remote_cmd: String,
     pub mcp_remote_args: String,
     /// When set, loom will spawn the GitHub MCP server (mcp_github_cmd + mcp_github_args) and pass
     /// GITHUB_TOKEN so the agent can operate on issues (comment, close, labels, etc.).
-    pub github_token: Option<String>,
+    pub github_token: Option<Secret>,
     /// Command to run the GitHub MCP server (e.g. "npx"). Override with MCP_GITHUB_CMD.
     pub mcp_github_cmd: String,
     /// Args for the GitHub MCP server (e.g. ["-y", "@modelcontextprotocol/server-github"]). Override with MCP_GITHUB_ARGS (space-separated).
@@ -56,7 +71,7 @@ pub struct ReactBuildConfig {
     /// When set and http(s), use HTTP transport for GitHub MCP (e.g. https://api.githubcopilot.com/mcp/). Override with MCP_GITHUB_URL.
     pub mcp_github_url: Option<String>,
     pub mcp_verbose: bool,
-    pub openai_api_key: Option<String>,
+    pub openai_api_key: Option<Secret>,
     pub openai_base_url: Option<String>,
     pub model: Option<String>,
     /// Explicit provider type override. When `Some("openai_compat")` or `Some("bigmodel")`, build layer uses [`crate::llm::ChatOpenAICompat`]; otherwise default is OpenAI.
@@ -64,43 +79,259 @@ pub struct ReactBuildConfig {
     pub llm_provider: Option<String>,
     /// Sampling temperature for chat completions. Set via `OPENAI_TEMPERATURE`.
     pub openai_temperature: Option<String>,
-    pub embedding_api_key: Option<String>,
+    /// Sequences that end generation (sent as the request's `stop`). Set via
+    /// `LOOM_STOP_SEQUENCES` as a JSON array of strings, or comma-separated. Default none.
+    pub stop_sequences: Vec<String>,
+    pub embedding_api_key: Option<Secret>,
     pub embedding_base_url: Option<String>,
     pub embedding_model: Option<String>,
     pub working_folder: Option<PathBuf>,
     pub approval_policy: Option<crate::helve::ApprovalPolicy>,
     pub compaction_config: Option<crate::compress::CompactionConfig>,
+    /// Bounds how much of a checkpointed thread is replayed into each run (ReAct only). Set via
+    /// `LOOM_HISTORY_MAX_TURNS` / `LOOM_HISTORY_MAX_TOKENS` (+ `LOOM_HISTORY_SUMMARY`).
+    pub history_window: Option<crate::compress::HistoryWindow>,
+    /// Caps on the run state's size, compacting or failing the run past them (ReAct only). Set
+    /// via `LOOM_STATE_MAX_MESSAGES` / `LOOM_STATE_MAX_BYTES` (+ `LOOM_STATE_LIMIT_ACTION`).
+    pub state_limits: Option<crate::graph::StateLimits>,
+    /// YAML graph spec replacing the built-in ReAct topology (see [`crate::GraphSpec`]). Set via
+    /// `LOOM_GRAPH` or CLI `--graph`.
+    pub graph_spec: Option<PathBuf>,
+    /// Endpoints to route LLM calls across by latency and error rate, as `provider[:weight]`
+    /// naming `[[providers]]` in config.toml (see [`crate::llm::RoutingLlmClient`]). Set via
+    /// `LOOM_LLM_ROUTES` (comma-separated). An unknown provider fails the runner build.
+    pub llm_routes: Vec<String>,
+    /// Feature flags copied into the run's [`crate::memory::RunnableConfig::flags`]. Set via
+    /// `LOOM_FLAGS` (comma-separated `name` or `name=false`); a run's own flags override them.
+    pub flags: HashMap<String, bool>,
     pub tot_config: TotRunnerConfig,
     pub got_config: GotRunnerConfig,
     /// MCP servers from mcp.json (discovered by CLI/ACP) or from ACP request.
     pub mcp_servers: Option<Vec<McpServerDef>>,
     /// Skill registry for the skill tool (built during helve config construction).
     pub skill_registry: Option<Arc<SkillRegistry>>,
+    /// When set, the `search_history` tool searches these earlier conversations (e.g. serve
+    /// sets the workspace's threads).
+    pub history_search: Option<crate::tools::HistorySearch>,
     /// Maximum nesting depth for `invoke_agent` tool calls (default 3).
     pub max_sub_agent_depth: Option<u32>,
     /// When true, tools are not executed; call_tool returns a placeholder (CLI --dry).
     pub dry_run: bool,
+    /// Per-node model overrides: graph node id (e.g. `think`, `think_expand`, `plan_graph`) → model
+    /// string in the same format as `model`. Nodes not listed use the default LLM.
+    /// Set via `LOOM_NODE_MODELS` as comma-separated `node=model` pairs.
+    pub node_models: HashMap<String, String>,
+    /// When true, a think answer cut off by the output token limit (`finish_reason: length`) is
+    /// continued automatically with follow-up calls. Set via `AUTO_CONTINUE`. Default off.
+    /// ReAct only: ToT and GoT runners ignore it (a warning is logged when set).
+    pub auto_continue: bool,
+    /// Larger-context model (same format as `model`) that a think call switches to when its
+    /// prompt exceeds the default model's context window; without it, the history is compacted
+    /// instead. Set via `LOOM_CONTEXT_FALLBACK_MODEL`.
+    pub context_fallback_model: Option<String>,
+    /// When true, ReAct reviews its draft final answer with a [`crate::VerifyNode`] and loops
+    /// back to think (at most twice per turn) when the review finds gaps. Set via
+    /// `REACT_REFLECTION`. Default off.
+    pub enable_reflection: bool,
+    /// When set, ReAct scores its final answer with a [`crate::ConfidenceNode`] (an LLM rating,
+    /// or a heuristic over the turn's tool calls) and reports it as [`ReActState::confidence`]
+    /// and `confidence` in the run's end response. Set via `LOOM_CONFIDENCE_SCORING`
+    /// (`llm` | `heuristic`). Default off.
+    pub confidence_scoring: Option<crate::ConfidenceScoring>,
+    /// When true, ReAct's observe step collapses tool results repeated within a thread into a
+    /// pointer to the first occurrence. Set via `LOOM_DEDUP_OBSERVATIONS`. Default off.
+    pub dedup_observations: bool,
+    /// Route rules (`<node>: if <condition> goto <node>`, see [`crate::graph::RouteRule`])
+    /// checked after each ReAct node before its usual edge, e.g.
+    /// `observe: if state.turn_count > 5 goto summarize`. Set via `LOOM_ROUTE_RULES`
+    /// (rules separated by `;`). An invalid rule fails the runner build.
+    pub route_rules: Vec<String>,
+    /// Approval rules (`[!]<tool>[:<regex>]`, see [`crate::helve::ApprovalRules`]) checked before
+    /// `approval_policy`, e.g. `bash:^(rm|sudo)\b` to ask only for matching commands. Set via
+    /// `LOOM_APPROVAL_RULES` (rules separated by `;`). An invalid rule fails the runner build.
+    pub approval_rules: Vec<String>,
+    /// SQLite file that records every approval request and decision (see
+    /// [`crate::approval_audit`]). Set via `LOOM_APPROVAL_AUDIT_DB`. Default off.
+    pub approval_audit_db: Option<PathBuf>,
+    /// Which tool results ObserveNode wraps in `<tool_result>` frames tagged with their origin
+    /// (see [`crate::ToolResultFraming`]). Set via `LOOM_TOOL_RESULT_FRAMING`
+    /// (`off` | `untrusted` | `all`). Default off.
+    pub tool_result_framing: crate::state::ToolResultFramingMode,
+    /// Tools whose results count as untrusted external content in addition to the built-in web
+    /// tools (e.g. MCP tools that browse). Set via `LOOM_UNTRUSTED_TOOLS` (comma-separated).
+    pub untrusted_tools: Vec<String>,
+    /// When true, prompt-injection phrases are removed from untrusted tool results. Set via
+    /// `LOOM_SANITIZE_TOOL_RESULTS`. Default off.
+    pub sanitize_tool_results: bool,
+    /// Extra regexes removed by the sanitizer, on top of the built-in phrases. Set via
+    /// `LOOM_INJECTION_PATTERNS` (patterns separated by `;`). An invalid regex fails the runner
+    /// build.
+    pub injection_patterns: Vec<String>,
+    /// When set, each run searches the memory store for this many memories relevant to the
+    /// user message and adds them to the system prompt (see [`crate::MemoryRecall`]). Needs a
+    /// store (embedding credentials). Set via `LOOM_MEMORY_RECALL` (top-k; 0 = off).
+    pub memory_recall: Option<usize>,
+    /// When true, each run puts the current date and time, OS, working folder and tool names
+    /// into the system prompt (see [`crate::EnvContext`]): in place of an `{env_context}`
+    /// placeholder, else in front of the prompt. Set via `LOOM_ENV_CONTEXT` or an agent
+    /// profile's `behavior.env_context`. Default off.
+    pub env_context: bool,
+    /// When set, a tool result whose raw output is over this many tokens is replaced in the
+    /// messages by an LLM-written summary, and `get_raw_observation` returns the raw output on
+    /// demand (see [`crate::ObservationSummarizer`]). The summary call uses the `observe` node
+    /// model. Set via `LOOM_OBSERVATION_SUMMARY_TOKENS` (0 = off).
+    pub observation_summary_tokens: Option<usize>,
+    /// Tools whose calls start while the model is still streaming them, with results held until
+    /// the turn commits (see [`crate::ToolPrefetch`]). Only list tools without side effects. Set
+    /// via `LOOM_TOOL_PREFETCH`: `1`/`true`/`yes` for [`crate::DEFAULT_PREFETCH_TOOLS`], or a
+    /// comma-separated tool list. Default off.
+    pub tool_prefetch: Vec<String>,
+    /// Arguments set on every call of a tool, by tool name, overriding the model's and hidden
+    /// from the tool's schema; `{working_folder}`, `{thread_id}` and `{user_id}` in string values
+    /// are filled from the run (see [`crate::tool_source::ToolArgumentsSource`]). Set via
+    /// `LOOM_TOOL_ARGUMENTS` (a JSON object) or an agent profile's `tools.arguments`.
+    pub tool_arguments: HashMap<String, serde_json::Map<String, serde_json::Value>>,
+    /// When set, the tool source only lists and calls these tools (e.g. a serve workspace's
+    /// tool allowlist). An allowed `batch` can only run allowed tools.
+    pub allowed_tools: Option<Vec<String>>,
+    /// Read-only mode: bash/powershell and the file tools that modify the working folder are not
+    /// registered, calls to them are refused even when another source provides them, and the
+    /// assembled system prompt gets a read-only section. Set via `LOOM_READ_ONLY`.
+    pub read_only: bool,
+    /// Tools hidden from the model and refused when called, whichever source provides them
+    /// and also as sub-calls of `batch` (e.g. an agent profile's `tools.builtin.disabled`).
+    pub denied_tools: Vec<String>,
+    /// When set, ReAct exposes only the tools most relevant to each turn once the tool source
+    /// lists more than the threshold (see [`crate::tool_source::ToolSelectionSource`]). Needs
+    /// embedding credentials. Set via `LOOM_TOOL_SELECTION_THRESHOLD`.
+    pub tool_selection: Option<crate::tool_source::ToolSelectionConfig>,
+    /// Overrides how the default LLM calls tools (see [`crate::ToolCallMode`]): `serial` for one
+    /// call per turn, `text` for models without function calling. When unset, the mode comes
+    /// from the built-in model matrix ([`crate::tool_support_for`]). Set via
+    /// `LOOM_TOOL_CALL_MODE` (`parallel` | `serial` | `text`).
+    pub tool_call_mode: Option<crate::ToolCallMode>,
+    /// Offline mode: the LLM is a [`crate::MockLlm`] replaying `offline_script` (or echoing the
+    /// user) and tools that reach the network (web fetch, Exa, Twitter, GitHub and HTTP MCP) are
+    /// not registered. Set via `LOOM_OFFLINE` or CLI `--offline`.
+    pub offline: bool,
+    /// YAML or JSON [`crate::MockScript`] for offline mode. Set via `LOOM_OFFLINE_SCRIPT`.
+    pub offline_script: Option<PathBuf>,
+    /// Middleware attached by [`crate::build_react_runner`] to the ReAct graph's nodes, scoped by
+    /// node id pattern. Add entries with [`ReactBuildConfig::with_middleware`] or
+    /// [`ReactBuildConfig::with_hooks`]. Empty by default.
+    pub node_middleware: NodeMiddlewareStack<ReActState>,
+}
+
+/// Model the default LLM uses when the config sets none.
+pub(crate) const DEFAULT_MODEL: &str = "gpt-4o-mini";
+
+/// Splits `LOOM_ROUTE_RULES` on `;`, dropping empty entries. Rules are parsed at build time.
+pub(crate) fn parse_route_rules(s: &str) -> Vec<String> {
+    s.split(';')
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Splits `LOOM_APPROVAL_RULES` on `;`, dropping empty entries. Rules are parsed at build time.
+pub(crate) fn parse_approval_rules(s: &str) -> Vec<String> {
+    parse_route_rules(s)
+}
+
+/// Parses `LOOM_STOP_SEQUENCES`: a JSON array of strings (needed for sequences containing
+/// commas or newlines), otherwise comma-separated values. Empty entries are dropped.
+pub(crate) fn parse_stop_sequences(s: &str) -> Vec<String> {
+    let s = s.trim();
+    let parsed = if s.starts_with('[') {
+        serde_json::from_str::<Vec<String>>(s).unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "ignoring invalid LOOM_STOP_SEQUENCES JSON array");
+            Vec::new()
+        })
+    } else {
+        s.split(',').map(|p| p.trim().to_string()).collect()
+    };
+    parsed.into_iter().filter(|p| !p.is_empty()).collect()
+}
+
+/// Parses `LOOM_NODE_MODELS` (e.g. `think=openai/gpt-4o,think_expand=gpt-4o-mini`).
+/// Entries without `=` or with an empty side are ignored.
+pub(crate) fn parse_node_models(s: &str) -> HashMap<String, String> {
+    s.split(',')
+        .filter_map(|pair| {
+            let (node, model) = pair.split_once('=')?;
+            let (node, model) = (node.trim(), model.trim());
+            if node.is_empty() || model.is_empty() {
+                return None;
+            }
+            Some((node.to_string(), model.to_string()))
+        })
+        .collect()
+}
+
+/// Parses `LOOM_FLAGS` (e.g. `verify_node,new_compaction=false`). Malformed entries are
+/// logged and skipped.
+pub(crate) fn flags_from_env(s: &str) -> HashMap<String, bool> {
+    s.split(',')
+        .filter(|spec| !spec.trim().is_empty())
+        .filter_map(|spec| {
+            crate::memory::parse_run_flag(spec)
+                .map_err(|e| tracing::warn!(error = %e, "invalid LOOM_FLAGS entry"))
+                .ok()
+        })
+        .collect()
 }
 
 impl ReactBuildConfig {
+    /// Model the default LLM is built with: `model` when set and non-empty, otherwise the
+    /// system default (`gpt-4o-mini`).
+    pub fn resolved_model(&self) -> &str {
+        self.model
+            .as_deref()
+            .filter(|m| !m.is_empty())
+            .unwrap_or(DEFAULT_MODEL)
+    }
+
+    /// Wraps ReAct nodes whose id matches `node_id_pattern` (`*` wildcard, e.g. `"think"`,
+    /// `"*"`) with `middleware`. Entries added earlier run outermost.
+    pub fn with_middleware(
+        mut self,
+        node_id_pattern: impl Into<String>,
+        middleware: Arc<dyn NodeMiddleware<ReActState>>,
+    ) -> Self {
+        self.node_middleware.push(node_id_pattern, middleware);
+        self
+    }
+
+    /// Runs `hooks` before and after each ReAct node whose id matches `node_id_pattern`.
+    pub fn with_hooks(
+        mut self,
+        node_id_pattern: impl Into<String>,
+        hooks: Arc<dyn NodeHooks<ReActState>>,
+    ) -> Self {
+        self.node_middleware =
+            std::mem::take(&mut self.node_middleware).with_hooks(node_id_pattern, hooks);
+        self
+    }
+
     pub fn from_env() -> Self {
         Self {
             db_path: std::env::var("LOOM_DB_PATH").ok(),
             thread_id: std::env::var("LOOM_THREAD_ID").ok(),
             user_id: std::env::var("LOOM_USER_ID").ok(),
             system_prompt: std::env::var("SYSTEM_PROMPT").ok(),
-            exa_api_key: std::env::var("EXA_API_KEY").ok(),
+            exa_api_key: env_config::read_secret("EXA_API_KEY"),
             exa_codesearch_enabled: std::env::var("LOOM_EXA_CODESEARCH")
                 .ok()
                 .map(|s| matches!(s.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
                 .unwrap_or(false),
-            twitter_api_key: std::env::var("TWITTER_API_KEY").ok(),
+            twitter_api_key: env_config::read_secret("TWITTER_API_KEY"),
             mcp_exa_url: std::env::var("MCP_EXA_URL")
                 .unwrap_or_else(|_| "https://exa-cp.backend.mcp.dev".to_string()),
             mcp_remote_cmd: std::env::var("MCP_REMOTE_CMD").unwrap_or_else(|_| "npx".to_string()),
             mcp_remote_args: std::env::var("MCP_REMOTE_ARGS")
                 .unwrap_or_else(|_| "mcp-remote".to_string()),
-            github_token: std::env::var("GITHUB_TOKEN").ok(),
+            github_token: env_config::read_secret("GITHUB_TOKEN"),
             mcp_github_cmd: std::env::var("MCP_GITHUB_CMD").unwrap_or_else(|_| "npx".to_string()),
             mcp_github_args: std::env::var("MCP_GITHUB_ARGS")
                 .unwrap_or_else(|_| "-y @modelcontextprotocol/server-github".to_string())
@@ -112,12 +343,15 @@ impl ReactBuildConfig {
                 .ok()
                 .map(|s| matches!(s.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
                 .unwrap_or(false),
-            openai_api_key: std::env::var("OPENAI_API_KEY").ok(),
+            openai_api_key: env_config::read_secret("OPENAI_API_KEY"),
             openai_base_url: std::env::var("OPENAI_BASE_URL").ok(),
             openai_temperature: std::env::var("OPENAI_TEMPERATURE").ok(),
+            stop_sequences: std::env::var("LOOM_STOP_SEQUENCES")
+                .map(|s| parse_stop_sequences(&s))
+                .unwrap_or_default(),
             model: None, // Removed environment variable support, use frontend/API parameters
             llm_provider: None, // Removed environment variable support
-            embedding_api_key: std::env::var("EMBEDDING_API_KEY").ok(),
+            embedding_api_key: env_config::read_secret("EMBEDDING_API_KEY"),
             embedding_base_url: std::env::var("EMBEDDING_BASE_URL").ok(),
             embedding_model: std::env::var("EMBEDDING_MODEL").ok(),
             working_folder: std::env::var("WORKING_FOLDER").ok().map(PathBuf::from),
@@ -130,7 +364,28 @@ impl ReactBuildConfig {
                 }
             }),
             compaction_config: None,
-            tot_config: TotRunnerConfig::default(),
+            history_window: crate::compress::HistoryWindow::from_env(),
+            state_limits: crate::graph::StateLimits::from_env(),
+            graph_spec: std::env::var("LOOM_GRAPH").ok().map(PathBuf::from),
+            llm_routes: std::env::var("LOOM_LLM_ROUTES")
+                .map(|s| {
+                    s.split(',')
+                        .map(str::trim)
+                        .filter(|r| !r.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            flags: std::env::var("LOOM_FLAGS")
+                .map(|s| flags_from_env(&s))
+                .unwrap_or_default(),
+            tot_config: TotRunnerConfig {
+                expand_concurrency: std::env::var("LOOM_TOT_EXPAND_CONCURRENCY")
+                    .ok()
+                    .and_then(|s| s.trim().parse().ok())
+                    .unwrap_or(1),
+                ..TotRunnerConfig::default()
+            },
             got_config: GotRunnerConfig {
                 adaptive: std::env::var("LOOM_GOT_ADAPTIVE")
                     .ok()
@@ -140,9 +395,14 @@ impl ReactBuildConfig {
                     .ok()
                     .map(|s| matches!(s.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
                     .unwrap_or(false),
+                token_budget: std::env::var("LOOM_GOT_TOKEN_BUDGET")
+                    .ok()
+                    .and_then(|s| s.trim().parse().ok())
+                    .filter(|&n: &u64| n > 0),
             },
             mcp_servers: None,
             skill_registry: None,
+            history_search: None,
             max_sub_agent_depth: std::env::var("MAX_SUB_AGENT_DEPTH")
                 .ok()
                 .and_then(|s| s.parse().ok()),
@@ -150,13 +410,107 @@ impl ReactBuildConfig {
                 .ok()
                 .map(|s| matches!(s.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
                 .unwrap_or(false),
+            node_models: std::env::var("LOOM_NODE_MODELS")
+                .ok()
+                .map(|s| parse_node_models(&s))
+                .unwrap_or_default(),
+            auto_continue: std::env::var("AUTO_CONTINUE")
+                .ok()
+                .map(|s| matches!(s.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false),
+            context_fallback_model: std::env::var("LOOM_CONTEXT_FALLBACK_MODEL")
+                .ok()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty()),
+            enable_reflection: std::env::var("REACT_REFLECTION")
+                .ok()
+                .map(|s| matches!(s.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false),
+            confidence_scoring: std::env::var("LOOM_CONFIDENCE_SCORING")
+                .ok()
+                .and_then(|s| s.parse().ok()),
+            dedup_observations: std::env::var("LOOM_DEDUP_OBSERVATIONS")
+                .ok()
+                .map(|s| matches!(s.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false),
+            route_rules: std::env::var("LOOM_ROUTE_RULES")
+                .map(|s| parse_route_rules(&s))
+                .unwrap_or_default(),
+            approval_rules: std::env::var("LOOM_APPROVAL_RULES")
+                .map(|s| parse_approval_rules(&s))
+                .unwrap_or_default(),
+            approval_audit_db: std::env::var(crate::approval_audit::ENV_APPROVAL_AUDIT_DB)
+                .ok()
+                .filter(|s| !s.trim().is_empty())
+                .map(PathBuf::from),
+            tool_result_framing: std::env::var("LOOM_TOOL_RESULT_FRAMING")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or_default(),
+            untrusted_tools: std::env::var("LOOM_UNTRUSTED_TOOLS")
+                .map(|s| {
+                    s.split(',')
+                        .map(str::trim)
+                        .filter(|t| !t.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            sanitize_tool_results: std::env::var("LOOM_SANITIZE_TOOL_RESULTS")
+                .ok()
+                .map(|s| matches!(s.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false),
+            injection_patterns: std::env::var("LOOM_INJECTION_PATTERNS")
+                .map(|s| parse_route_rules(&s))
+                .unwrap_or_default(),
+            memory_recall: std::env::var("LOOM_MEMORY_RECALL")
+                .ok()
+                .and_then(|s| s.trim().parse::<usize>().ok())
+                .filter(|&k| k > 0),
+            env_context: std::env::var("LOOM_ENV_CONTEXT")
+                .ok()
+                .map(|s| matches!(s.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false),
+            observation_summary_tokens: std::env::var("LOOM_OBSERVATION_SUMMARY_TOKENS")
+                .ok()
+                .and_then(|s| s.trim().parse::<usize>().ok())
+                .filter(|&n| n > 0),
+            tool_prefetch: std::env::var("LOOM_TOOL_PREFETCH")
+                .map(|s| super::prefetch::parse_prefetch_tools(&s))
+                .unwrap_or_default(),
+            tool_arguments: std::env::var("LOOM_TOOL_ARGUMENTS")
+                .ok()
+                .filter(|s| !s.trim().is_empty())
+                .and_then(|s| {
+                    serde_json::from_str(&s)
+                        .map_err(|e| tracing::warn!(error = %e, "invalid LOOM_TOOL_ARGUMENTS"))
+                        .ok()
+                })
+                .unwrap_or_default(),
+            allowed_tools: None,
+            read_only: std::env::var("LOOM_READ_ONLY")
+                .ok()
+                .map(|s| matches!(s.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false),
+            denied_tools: Vec::new(),
+            tool_selection: crate::tool_source::ToolSelectionConfig::from_env(),
+            tool_call_mode: std::env::var("LOOM_TOOL_CALL_MODE")
+                .ok()
+                .and_then(|s| s.parse().ok()),
+            offline: std::env::var("LOOM_OFFLINE")
+                .ok()
+                .map(|s| matches!(s.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false),
+            offline_script: std::env::var("LOOM_OFFLINE_SCRIPT").ok().map(PathBuf::from),
+            node_middleware: NodeMiddlewareStack::new(),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::ReactBuildConfig;
+    use super::{flags_from_env, parse_node_models, ReactBuildConfig};
+    use env_config::Secret;
 
     fn with_env(key: &str, value: Option<&str>, f: impl FnOnce()) {
         let prev = std::env::var(key).ok();
@@ -181,7 +535,10 @@ mod tests {
                 with_env("MCP_GITHUB_ARGS", None, || {
                     let config = ReactBuildConfig::from_env();
                     assert!(config.github_token.is_some());
-                    assert_eq!(config.github_token.as_deref(), Some("test-token"));
+                    assert_eq!(
+                        config.github_token.as_ref().map(Secret::expose),
+                        Some("test-token")
+                    );
                     assert_eq!(config.mcp_github_cmd, "npx");
                     assert!(config.mcp_github_args.contains(&"-y".to_string()));
                     assert!(config
@@ -226,4 +583,50 @@ mod tests {
             assert!(config.mcp_github_url.is_none());
         });
     }
+
+    #[test]
+    fn parse_route_rules_splits_on_semicolons() {
+        let rules = parse_route_rules(
+            " observe: if state.turn_count > 5 goto summarize ;; think: if false goto END;",
+        );
+        assert_eq!(
+            rules,
+            vec![
+                "observe: if state.turn_count > 5 goto summarize",
+                "think: if false goto END"
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_node_models_reads_pairs_and_skips_malformed_entries() {
+        let map = parse_node_models("think=openai/gpt-4o, think_expand = gpt-4o-mini,bad,=x,y=");
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get("think").map(String::as_str), Some("openai/gpt-4o"));
+        assert_eq!(
+            map.get("think_expand").map(String::as_str),
+            Some("gpt-4o-mini")
+        );
+    }
+
+    #[test]
+    fn parse_stop_sequences_reads_json_array_or_comma_list() {
+        assert_eq!(
+            parse_stop_sequences(r#"["\nObservation:", "a,b"]"#),
+            vec!["\nObservation:", "a,b"]
+        );
+        assert_eq!(
+            parse_stop_sequences(" END , ,</answer>"),
+            vec!["END", "</answer>"]
+        );
+        assert!(parse_stop_sequences("[not json").is_empty());
+    }
+
+    #[test]
+    fn flags_from_env_reads_specs_and_skips_malformed_entries() {
+        let flags = flags_from_env("verify_node, new_compaction=false,,bad=maybe");
+        assert_eq!(flags.len(), 2);
+        assert_eq!(flags.get("verify_node"), Some(&true));
+        assert_eq!(flags.get("new_compaction"), Some(&false));
+    }
 }
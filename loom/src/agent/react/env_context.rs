@@ -0,0 +1,155 @@
+//! Environment context: a block with the current date and time, the operating system, the
+//! working folder and the available tool names, put into the system prompt at the start of
+//! each run so the model does not have to guess them.
+//!
+//! The block is an `<environment>` section. It replaces the [`ENV_CONTEXT_PLACEHOLDER`] when the
+//! system prompt has one, and is prepended to the prompt otherwise. Each run replaces the block
+//! left by the previous turn of the thread, so the time stays current.
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, FixedOffset};
+
+use crate::message::Message;
+use crate::state::ReActState;
+
+/// Placeholder in a system prompt that the environment block replaces.
+pub const ENV_CONTEXT_PLACEHOLDER: &str = "{env_context}";
+
+const BLOCK_OPEN: &str = "<environment>";
+const BLOCK_CLOSE: &str = "</environment>";
+
+/// Renders the environment block and puts it into the system prompt. Attach with
+/// [`crate::ReactRunner::with_env_context`]; set up by [`crate::build_react_runner`] when
+/// [`crate::ReactBuildConfig::env_context`] is set.
+#[derive(Clone, Debug, Default)]
+pub struct EnvContext {
+    working_folder: Option<PathBuf>,
+}
+
+impl EnvContext {
+    /// Context for runs in `working_folder` (omitted from the block when `None`).
+    pub fn new(working_folder: Option<PathBuf>) -> Self {
+        Self { working_folder }
+    }
+
+    /// The environment block at the current local time, listing `tool_names`.
+    pub fn section(&self, tool_names: &[String]) -> String {
+        self.section_at(chrono::Local::now().fixed_offset(), tool_names)
+    }
+
+    fn section_at(&self, now: DateTime<FixedOffset>, tool_names: &[String]) -> String {
+        let zone = match std::env::var("TZ").ok().filter(|s| !s.trim().is_empty()) {
+            Some(name) => format!("{}, UTC{}", name.trim(), now.format("%:z")),
+            None => format!("UTC{}", now.format("%:z")),
+        };
+        let mut lines = vec![
+            format!(
+                "Current date and time: {} ({})",
+                now.format("%Y-%m-%d %H:%M %A"),
+                zone
+            ),
+            format!(
+                "Operating system: {} ({})",
+                std::env::consts::OS,
+                std::env::consts::ARCH
+            ),
+        ];
+        if let Some(folder) = &self.working_folder {
+            lines.push(format!("Working folder: {}", folder.display()));
+        }
+        if !tool_names.is_empty() {
+            lines.push(format!("Available tools: {}", tool_names.join(", ")));
+        }
+        format!("{BLOCK_OPEN}\n{}\n{BLOCK_CLOSE}", lines.join("\n"))
+    }
+
+    /// Puts a fresh environment block into the state's system message. Does nothing when the
+    /// state has no leading system message.
+    pub fn apply(&self, state: &mut ReActState, tool_names: &[String]) {
+        let section = self.section(tool_names);
+        if let Some(Message::System(prompt)) = state.messages.first_mut() {
+            *prompt = insert_block(prompt, &section);
+        }
+    }
+}
+
+/// `prompt` with `section` in place of an earlier block, else of the placeholder, else in front.
+fn insert_block(prompt: &str, section: &str) -> String {
+    if let Some(start) = prompt.find(BLOCK_OPEN) {
+        if let Some(len) = prompt[start..].find(BLOCK_CLOSE) {
+            let end = start + len + BLOCK_CLOSE.len();
+            return format!("{}{}{}", &prompt[..start], section, &prompt[end..]);
+        }
+    }
+    if prompt.contains(ENV_CONTEXT_PLACEHOLDER) {
+        return prompt.replacen(ENV_CONTEXT_PLACEHOLDER, section, 1);
+    }
+    if prompt.is_empty() {
+        section.to_string()
+    } else {
+        format!("{section}\n\n{prompt}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at() -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339("2026-10-16T14:03:00+02:00").unwrap()
+    }
+
+    #[test]
+    fn section_lists_time_os_folder_and_tools() {
+        let ctx = EnvContext::new(Some(PathBuf::from("/work/repo")));
+        let section = ctx.section_at(at(), &["bash".to_string(), "read".to_string()]);
+        assert!(section.starts_with("<environment>\n"));
+        assert!(section.contains("Current date and time: 2026-10-16 14:03 Friday ("));
+        assert!(section.contains("UTC+02:00)"));
+        assert!(section.contains(&format!("Operating system: {}", std::env::consts::OS)));
+        assert!(section.contains("Working folder: /work/repo"));
+        assert!(section.contains("Available tools: bash, read"));
+
+        let bare = EnvContext::default().section_at(at(), &[]);
+        assert!(!bare.contains("Working folder"));
+        assert!(!bare.contains("Available tools"));
+    }
+
+    #[test]
+    fn block_is_prepended_templated_and_replaced() {
+        let first = insert_block("You are helpful.", "<environment>\nA\n</environment>");
+        assert_eq!(
+            first,
+            "<environment>\nA\n</environment>\n\nYou are helpful."
+        );
+        let second = insert_block(&first, "<environment>\nB\n</environment>");
+        assert_eq!(
+            second,
+            "<environment>\nB\n</environment>\n\nYou are helpful."
+        );
+
+        let templated = insert_block(
+            "Rules.\n\n{env_context}\n\nMore rules.",
+            "<environment>\nA\n</environment>",
+        );
+        assert_eq!(
+            templated,
+            "Rules.\n\n<environment>\nA\n</environment>\n\nMore rules."
+        );
+    }
+
+    #[test]
+    fn apply_updates_the_system_message() {
+        let mut state = ReActState {
+            messages: vec![Message::system("You are helpful."), Message::user("hi")],
+            ..Default::default()
+        };
+        EnvContext::default().apply(&mut state, &["ls".to_string()]);
+        let Message::System(prompt) = &state.messages[0] else {
+            panic!("expected system message");
+        };
+        assert!(prompt.starts_with("<environment>\n"));
+        assert!(prompt.contains("Available tools: ls\n</environment>\n\nYou are helpful."));
+    }
+}
@@ -0,0 +1,275 @@
+//! Graph specs: a ReAct graph topology written as YAML, compiled in place of the built-in
+//! think → act → observe loop (CLI `--graph`, `LOOM_GRAPH`).
+//!
+//! ```yaml
+//! nodes:
+//!   think: { type: think, auto_continue: 2 }
+//!   act: { type: act }
+//!   observe: { type: observe }
+//!   check: { type: verify }
+//! edges:
+//!   - { from: START, to: think }
+//!   - { from: think, when: tools, routes: { tools: act, END: check } }
+//!   - { from: act, to: observe }
+//!   - { from: observe, to: think }
+//!   - { from: check, when: continue, routes: { continue: think, END: END } }
+//! ```
+//!
+//! # Node types
+//!
+//! Built-in ReAct nodes, configured like the default graph (same LLMs, tools and approval
+//! policy): `think` (`auto_continue`), `act`, `observe` (`dedup`), `compress`, `summarize`,
+//! `completion_check` (`max_iterations`, `message_window`) and `verify`. A type may be used
+//! under several ids (e.g. two observe steps). Observe steps always follow their edges; in the
+//! default graph observe ends the run when there were no tool calls.
+//!
+//! # Edges
+//!
+//! `from` → `to`, or a conditional edge: `when` names a condition and `routes` maps each of its
+//! outcomes to a node id or `END`:
+//!
+//! - `tools`: `tools` when the last think produced tool calls, else `END`.
+//! - `continue`: `continue` when the state says to go on (`should_continue`, set by
+//!   completion_check and verify), else `END`.
+//!
+//! Unknown node ids and unreachable nodes are reported when the graph is compiled.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::tools_condition;
+use crate::graph::{StateGraph, END, START};
+use crate::state::ReActState;
+use crate::Node;
+
+/// Error loading a graph spec.
+#[derive(Debug, Error)]
+#[error("invalid graph spec: {0}")]
+pub struct GraphSpecError(pub String);
+
+/// A ReAct graph topology: nodes by id and the edges between them.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GraphSpec {
+    pub nodes: BTreeMap<String, SpecNode>,
+    pub edges: Vec<SpecEdge>,
+}
+
+/// A built-in node type and its parameters.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum SpecNode {
+    Think {
+        /// Max follow-up calls when an answer is cut off by the output token limit.
+        #[serde(default)]
+        auto_continue: Option<u32>,
+    },
+    Act,
+    Observe {
+        /// Collapse repeated tool results (see [`super::ObserveNode::with_observation_dedup`]).
+        #[serde(default)]
+        dedup: Option<bool>,
+    },
+    Compress,
+    Summarize,
+    CompletionCheck {
+        #[serde(default)]
+        max_iterations: Option<usize>,
+        #[serde(default)]
+        message_window: Option<usize>,
+    },
+    Verify,
+}
+
+/// One edge: `from` → `to`, or from `from` along `routes` by the outcome of `when`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpecEdge {
+    pub from: String,
+    #[serde(default)]
+    pub to: Option<String>,
+    #[serde(default)]
+    pub when: Option<SpecCondition>,
+    /// Outcome of `when` → node id or `END`.
+    #[serde(default)]
+    pub routes: BTreeMap<String, String>,
+}
+
+/// Condition of a conditional edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpecCondition {
+    Tools,
+    Continue,
+}
+
+impl SpecCondition {
+    /// Outcomes as written in `routes`.
+    fn outcomes(self) -> [&'static str; 2] {
+        match self {
+            Self::Tools => ["tools", "END"],
+            Self::Continue => ["continue", "END"],
+        }
+    }
+
+    fn route(self, state: &ReActState) -> String {
+        let outcome = match self {
+            Self::Tools => tools_condition(state).as_str(),
+            Self::Continue if state.should_continue => "continue",
+            Self::Continue => END,
+        };
+        outcome.to_string()
+    }
+}
+
+/// `START` / `END` as written in a spec → graph ids.
+fn graph_id(id: &str) -> String {
+    match id {
+        "START" => START.to_string(),
+        "END" => END.to_string(),
+        _ => id.to_string(),
+    }
+}
+
+impl GraphSpec {
+    /// Parses and checks a YAML spec.
+    pub fn from_yaml(yaml: &str) -> Result<Self, GraphSpecError> {
+        let spec: Self = serde_yaml::from_str(yaml).map_err(|e| GraphSpecError(e.to_string()))?;
+        spec.check()?;
+        Ok(spec)
+    }
+
+    /// Reads a YAML spec file.
+    pub fn load(path: &Path) -> Result<Self, GraphSpecError> {
+        let yaml = std::fs::read_to_string(path)
+            .map_err(|e| GraphSpecError(format!("{}: {}", path.display(), e)))?;
+        Self::from_yaml(&yaml)
+    }
+
+    /// Checks what the graph compiler cannot: reserved ids and the shape of each edge.
+    fn check(&self) -> Result<(), GraphSpecError> {
+        let err = |m: String| Err(GraphSpecError(m));
+        if let Some(id) = self
+            .nodes
+            .keys()
+            .find(|id| matches!(id.as_str(), "START" | "END"))
+        {
+            return err(format!("`{}` is reserved and cannot be a node id", id));
+        }
+        for edge in &self.edges {
+            match (&edge.to, edge.when) {
+                (Some(_), None) if edge.routes.is_empty() => {}
+                (None, Some(when)) => {
+                    let outcomes = when.outcomes();
+                    if let Some(key) = edge.routes.keys().find(|k| !outcomes.contains(&k.as_str()))
+                    {
+                        return err(format!(
+                            "edge from `{}`: `{}` is not an outcome of `{:?}` (expected {})",
+                            edge.from,
+                            key,
+                            when,
+                            outcomes.join(", ")
+                        ));
+                    }
+                    if edge.routes.is_empty() {
+                        return err(format!("edge from `{}`: `when` needs `routes`", edge.from));
+                    }
+                }
+                _ => {
+                    return err(format!(
+                        "edge from `{}` needs either `to` or `when` with `routes`",
+                        edge.from
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the spec's nodes (built by `node_for`) and edges to `graph`.
+    pub(crate) fn add_to(
+        &self,
+        graph: &mut StateGraph<ReActState>,
+        node_for: impl Fn(&SpecNode) -> Arc<dyn Node<ReActState>>,
+    ) {
+        for (id, node) in &self.nodes {
+            graph.add_node(id.as_str(), node_for(node));
+        }
+        for edge in &self.edges {
+            let from = graph_id(&edge.from);
+            match (&edge.to, edge.when) {
+                (Some(to), _) => {
+                    graph.add_edge(from.as_str(), graph_id(to).as_str());
+                }
+                (None, Some(when)) => {
+                    let path_map = edge
+                        .routes
+                        .iter()
+                        .map(|(outcome, to)| (graph_id(outcome), graph_id(to)))
+                        .collect();
+                    graph.add_conditional_edges(
+                        from.as_str(),
+                        Arc::new(move |state: &ReActState| when.route(state)),
+                        Some(path_map),
+                    );
+                }
+                (None, None) => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_module_example() {
+        let spec = GraphSpec::from_yaml(
+            r#"
+nodes:
+  think: { type: think, auto_continue: 2 }
+  act: { type: act }
+  observe: { type: observe }
+  check: { type: verify }
+edges:
+  - { from: START, to: think }
+  - { from: think, when: tools, routes: { tools: act, END: check } }
+  - { from: act, to: observe }
+  - { from: observe, to: think }
+  - { from: check, when: continue, routes: { continue: think, END: END } }
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            spec.nodes["think"],
+            SpecNode::Think {
+                auto_continue: Some(2)
+            }
+        );
+        assert_eq!(spec.nodes["check"], SpecNode::Verify);
+        assert_eq!(spec.edges[1].when, Some(SpecCondition::Tools));
+        assert_eq!(graph_id(&spec.edges[4].routes["END"]), END);
+    }
+
+    #[test]
+    fn rejects_unknown_outcomes_types_and_half_edges() {
+        let bad_outcome = "nodes: { think: { type: think } }\nedges:\n  - { from: think, when: tools, routes: { done: think } }";
+        let e = GraphSpec::from_yaml(bad_outcome).unwrap_err();
+        assert!(e.to_string().contains("`done` is not an outcome"), "{}", e);
+
+        let bad_type = "nodes: { plan: { type: planner } }\nedges: []";
+        assert!(GraphSpec::from_yaml(bad_type).is_err());
+
+        let bad_param = "nodes: { think: { type: think, dedup: true } }\nedges: []";
+        assert!(GraphSpec::from_yaml(bad_param).is_err());
+
+        let half_edge = "nodes: { think: { type: think } }\nedges:\n  - { from: think }";
+        let e = GraphSpec::from_yaml(half_edge).unwrap_err();
+        assert!(e.to_string().contains("needs either `to`"), "{}", e);
+    }
+}
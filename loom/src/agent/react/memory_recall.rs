@@ -0,0 +1,159 @@
+//! Memory recall: puts the long-term memories most relevant to the new user message into the
+//! system prompt, so the model sees them without having to call `search_memories` / `recall`.
+//!
+//! The memories go in a `<recalled_memories>` block at the end of the system message, one line
+//! per memory with its key and last update date. Each run replaces the block left by the
+//! previous turn of the thread.
+
+use std::sync::Arc;
+
+use crate::memory::{Namespace, SearchOptions, Store};
+use crate::message::Message;
+use crate::state::ReActState;
+
+const BLOCK_OPEN: &str = "<recalled_memories>";
+const BLOCK_CLOSE: &str = "</recalled_memories>";
+/// Longest memory value shown in the block, in characters.
+const VALUE_CHARS: usize = 400;
+
+/// Searches the store for memories relevant to each new user message and injects them into
+/// the system prompt. Attach with [`crate::ReactRunner::with_memory_recall`]; set up by
+/// [`crate::build_react_runner`] when [`crate::ReactBuildConfig::memory_recall`] is set.
+#[derive(Clone)]
+pub struct MemoryRecall {
+    store: Arc<dyn Store>,
+    namespace: Namespace,
+    top_k: usize,
+}
+
+impl MemoryRecall {
+    /// Recalls at most `top_k` memories from `namespace` (the namespace the memory tools use).
+    pub fn new(store: Arc<dyn Store>, namespace: Namespace, top_k: usize) -> Self {
+        Self {
+            store,
+            namespace,
+            top_k,
+        }
+    }
+
+    /// The memory block for `query`, or `None` when nothing matched (or the search failed,
+    /// which is logged and otherwise ignored).
+    pub async fn section(&self, query: &str) -> Option<String> {
+        if query.trim().is_empty() || self.top_k == 0 {
+            return None;
+        }
+        let options = SearchOptions::new()
+            .with_query(query)
+            .with_limit(self.top_k);
+        let hits = match self.store.search(&self.namespace, options).await {
+            Ok(hits) => hits,
+            Err(e) => {
+                tracing::warn!(error = %e, "memory recall search failed");
+                return None;
+            }
+        };
+        if hits.is_empty() {
+            return None;
+        }
+        let lines: Vec<String> = hits
+            .iter()
+            .map(|h| {
+                let updated = chrono::DateTime::<chrono::Utc>::from(h.item.updated_at);
+                format!(
+                    "- [memory {} | updated {}] {}",
+                    h.item.key,
+                    updated.format("%Y-%m-%d"),
+                    value_text(&h.item.value)
+                )
+            })
+            .collect();
+        Some(format!(
+            "{BLOCK_OPEN}\nLong-term memories recalled for this message (from the memory store, \
+             not said by the user in this conversation; may be outdated):\n{}\n{BLOCK_CLOSE}",
+            lines.join("\n")
+        ))
+    }
+
+    /// Replaces the memory block in the state's system message with one for `query`.
+    /// Does nothing when the state has no leading system message.
+    pub async fn apply(&self, state: &mut ReActState, query: &str) {
+        let section = self.section(query).await;
+        let Some(Message::System(prompt)) = state.messages.first_mut() else {
+            return;
+        };
+        let mut base = strip_block(prompt).to_string();
+        if let Some(section) = section {
+            base = if base.is_empty() {
+                section
+            } else {
+                format!("{base}\n\n{section}")
+            };
+        }
+        *prompt = base;
+    }
+}
+
+impl std::fmt::Debug for MemoryRecall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemoryRecall")
+            .field("namespace", &self.namespace)
+            .field("top_k", &self.top_k)
+            .finish_non_exhaustive()
+    }
+}
+
+/// `prompt` without a memory block added by an earlier run.
+fn strip_block(prompt: &str) -> &str {
+    match prompt.find(BLOCK_OPEN) {
+        Some(i) => prompt[..i].trim_end(),
+        None => prompt,
+    }
+}
+
+fn value_text(value: &serde_json::Value) -> String {
+    let text = match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    let text = text.replace('\n', " ");
+    match text.char_indices().nth(VALUE_CHARS) {
+        Some((i, _)) => format!("{}…", &text[..i]),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::InMemoryStore;
+
+    #[tokio::test]
+    async fn apply_injects_memories_and_replaces_previous_block() {
+        let store = Arc::new(InMemoryStore::new());
+        let ns: Namespace = vec!["u1".into(), "memories".into()];
+        store
+            .put(&ns, "editor", &serde_json::json!("prefers vim"))
+            .await
+            .unwrap();
+        let recall = MemoryRecall::new(store, ns, 3);
+        let mut state = ReActState {
+            messages: vec![Message::system("You are helpful."), Message::user("vim")],
+            ..Default::default()
+        };
+
+        recall.apply(&mut state, "vim").await;
+        let Message::System(prompt) = &state.messages[0] else {
+            panic!("expected system message");
+        };
+        assert!(prompt.starts_with("You are helpful.\n\n<recalled_memories>"));
+        assert!(prompt.contains("- [memory editor | updated "));
+        assert!(prompt.contains("] prefers vim"));
+
+        recall.apply(&mut state, "nothing matches").await;
+        assert_eq!(
+            state.messages[0],
+            Message::system("You are helpful."),
+            "a turn without hits drops the earlier block"
+        );
+    }
+}
@@ -21,18 +21,33 @@
 //! - [`ActNode`]: executes tool calls and records tool results.
 //! - [`ObserveNode`]: appends tool results to the message list and clears the
 //!   tool buffers for the next turn.
+//! - [`VerifyNode`]: optional reflection pass that reviews the draft final answer
+//!   and sends it back to think when it has gaps.
+//! - [`ConfidenceNode`]: optional scoring of the final answer, stored in
+//!   [`ReActState::confidence`] for routing low-confidence answers to review.
 //! - [`ReactRunner`]: owns the compiled graph plus the services needed to run it.
+//! - [`ToolPrefetch`]: optional speculative execution of read-only tool calls
+//!   while the model is still streaming them.
 //! - [`ReactBuildConfig`]: configuration for building runners from env or files.
+//! - [`GraphSpec`]: a graph topology of built-in nodes written as YAML, replacing the
+//!   default loop.
 //! - [`ReactRunContext`]: resolved checkpointer, store, tool source, and run config.
 
 mod act_node;
 mod build;
 mod completion_check_node;
+mod confidence_node;
 mod config;
+mod env_context;
+mod graph_spec;
+mod memory_recall;
+mod observation_summary;
 mod observe_node;
+mod prefetch;
 mod runner;
 mod summarize_node;
 mod think_node;
+mod verify_node;
 mod with_node_logging;
 
 pub use act_node::{
@@ -40,18 +55,27 @@ pub use act_node::{
     DEFAULT_TOOL_ERROR_TEMPLATE, STEP_PROGRESS_EVENT_TYPE,
 };
 pub use build::{
-    build_dup_runner, build_got_runner, build_react_run_context, build_react_runner,
-    build_react_runner_with_openai, build_tot_runner, BuildRunnerError, ReactRunContext,
+    build_auxiliary_llm, build_dup_runner, build_got_runner, build_react_run_context,
+    build_react_runner, build_react_runner_with_openai, build_tot_runner, BuildRunnerError,
+    ReactRunContext,
 };
 pub use completion_check_node::CompletionCheckNode;
+pub use confidence_node::{heuristic_confidence, ConfidenceNode};
 pub use config::{GotRunnerConfig, ReactBuildConfig, TotRunnerConfig};
+pub use env_context::{EnvContext, ENV_CONTEXT_PLACEHOLDER};
+pub use graph_spec::{GraphSpec, GraphSpecError, SpecCondition, SpecEdge, SpecNode};
+pub use memory_recall::MemoryRecall;
+pub use observation_summary::ObservationSummarizer;
 pub use observe_node::ObserveNode;
+pub use prefetch::{parse_prefetch_tools, PrefetchTurn, ToolPrefetch, DEFAULT_PREFETCH_TOOLS};
 pub use runner::{
-    build_react_initial_state, run_agent, run_react_graph_stream, AgentOptions, ReactRunner,
-    RunError,
+    build_react_initial_state, build_react_initial_state_from_history,
+    build_react_initial_state_with_window, run_agent, run_react_graph_stream, AgentOptions,
+    Callbacks, ReactRunner, RunError,
 };
 pub use summarize_node::{is_first_think, SummarizeNode};
-pub use think_node::ThinkNode;
+pub use think_node::{ThinkNode, DEFAULT_TOOL_CALL_REPAIRS};
+pub use verify_node::{VerifyNode, REFLECTION_FEEDBACK_PREFIX};
 pub use with_node_logging::WithNodeLogging;
 
 use crate::state::ReActState;
@@ -0,0 +1,135 @@
+//! Observation summarizer: replaces a tool result that is too large for the context with an
+//! LLM-written summary.
+//!
+//! ObserveNode hands each result to [`ObservationSummarizer::summarize`]. When the raw output
+//! is over the token threshold, it is saved under the call id (see
+//! [`crate::tools::raw_observation_path`]) and the tool message gets a structured summary that
+//! names the call id, so the model can read the raw output with `get_raw_observation`. Errors,
+//! small results and failed summary calls keep the normal observation.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::llm::LlmClient;
+use crate::message::Message;
+use crate::tools::{raw_observation_path, TOOL_GET_RAW_OBSERVATION};
+
+/// Approximate characters per token, as in [`crate::compress::estimate_tokens`].
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Longest part of the raw output sent to the summary call, in characters.
+const MAX_INPUT_CHARS: usize = 60_000;
+
+const SUMMARY_SYSTEM_PROMPT: &str = "You summarize tool output for an agent that cannot see it. \
+Reply with exactly these sections:\n\
+Overview: one or two sentences on what the output is.\n\
+Key facts: bullet list of the facts, numbers, names, paths and errors most likely to matter.\n\
+Omitted: what kinds of detail were left out.\n\
+Do not add anything that is not in the output.";
+
+/// Summarizes tool results whose raw output is over `threshold_tokens`; see the module docs.
+/// Set up by [`crate::build_react_runner`] when
+/// [`crate::ReactBuildConfig::observation_summary_tokens`] is set.
+#[derive(Clone)]
+pub struct ObservationSummarizer {
+    llm: Arc<dyn LlmClient>,
+    threshold_tokens: usize,
+    dir: PathBuf,
+}
+
+impl ObservationSummarizer {
+    /// Summarizes with `llm`; raw outputs are saved in `dir`, where `get_raw_observation`
+    /// reads them.
+    pub fn new(llm: Arc<dyn LlmClient>, threshold_tokens: usize, dir: PathBuf) -> Self {
+        Self {
+            llm,
+            threshold_tokens,
+            dir,
+        }
+    }
+
+    /// Summary to use in place of `raw`, or `None` to keep the normal observation.
+    pub async fn summarize(&self, tool_name: &str, call_id: &str, raw: &str) -> Option<String> {
+        if tool_name == TOOL_GET_RAW_OBSERVATION {
+            return None;
+        }
+        let tokens = raw.chars().count() / CHARS_PER_TOKEN;
+        if tokens <= self.threshold_tokens {
+            return None;
+        }
+        let path = raw_observation_path(&self.dir, call_id);
+        let saved = tokio::fs::create_dir_all(&self.dir).await;
+        if let Err(e) = saved.and(tokio::fs::write(&path, raw).await) {
+            tracing::warn!(error = %e, path = %path.display(), "observation summary: saving raw output failed");
+            return None;
+        }
+        let input = match raw.char_indices().nth(MAX_INPUT_CHARS) {
+            Some((i, _)) => format!("{}\n[... output cut for summarization]", &raw[..i]),
+            None => raw.to_string(),
+        };
+        let messages = vec![
+            Message::system(SUMMARY_SYSTEM_PROMPT),
+            Message::user(format!("Output of tool `{}`:\n\n{}", tool_name, input)),
+        ];
+        match self.llm.invoke(&messages).await {
+            Ok(response) if !response.content.trim().is_empty() => {
+                tracing::info!(tool_name, call_id, tokens, "observation summarized");
+                Some(format!(
+                    "Summary of a large result (about {} tokens). The raw output is available \
+                     with {}(call_id: \"{}\").\n{}",
+                    tokens,
+                    TOOL_GET_RAW_OBSERVATION,
+                    call_id,
+                    response.content.trim()
+                ))
+            }
+            Ok(_) => None,
+            Err(e) => {
+                tracing::warn!(error = %e, tool_name, "observation summary call failed");
+                None
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for ObservationSummarizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ObservationSummarizer")
+            .field("threshold_tokens", &self.threshold_tokens)
+            .field("dir", &self.dir)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlm;
+
+    #[tokio::test]
+    async fn large_results_are_summarized_and_saved() {
+        let dir = tempfile::tempdir().unwrap();
+        let llm = Arc::new(MockLlm::with_no_tool_calls(
+            "Overview: a log.\nKey facts:\n- 3 errors",
+        ));
+        let summarizer = ObservationSummarizer::new(llm, 10, dir.path().to_path_buf());
+
+        assert_eq!(summarizer.summarize("bash", "c1", "short").await, None);
+
+        let raw = "line\n".repeat(100);
+        let summary = summarizer.summarize("bash", "c2", &raw).await.unwrap();
+        assert!(summary.contains("get_raw_observation(call_id: \"c2\")"));
+        assert!(summary.ends_with("- 3 errors"));
+        assert_eq!(
+            std::fs::read_to_string(raw_observation_path(dir.path(), "c2")).unwrap(),
+            raw
+        );
+
+        assert_eq!(
+            summarizer
+                .summarize(TOOL_GET_RAW_OBSERVATION, "c3", &raw)
+                .await,
+            None
+        );
+    }
+}
@@ -1,20 +1,50 @@
 //! Observe node: read tool_results, merge into state (e.g. messages), clear tool_calls and tool_results.
+//!
+//! With observation dedup on, a tool result whose text matches an earlier tool message of the
+//! thread (ignoring whitespace) is written as a short pointer to that message with a repeat
+//! count instead of the full text. Every tool call still gets its own tool message, as providers
+//! require one per call id.
+//!
+//! With [`ToolResultFraming`] set, results are wrapped in delimited `<tool_result>` frames tagged
+//! with their origin, and untrusted output can be sanitized before it enters the messages.
+//!
+//! With an [`ObservationSummarizer`], a result whose raw output is over its token threshold is
+//! replaced by an LLM-written summary that points to the saved raw output.
+
+use std::collections::HashMap;
 
 use async_trait::async_trait;
 use tracing::{info, warn};
 
+use crate::agent::react::ObservationSummarizer;
 use crate::error::AgentError;
 use crate::graph::Next;
 use crate::memory::uuid6;
 use crate::message::Message;
-use crate::state::ReActState;
+use crate::state::{ReActState, ToolResult, ToolResultFraming};
 use crate::tool_source::ToolCallContent;
 use crate::Node;
 
+/// Marker in a deduplicated tool message; followed by the call id holding the full result.
+const DUPLICATE_MARKER: &str = "identical to the earlier result of call ";
+
+/// Results shorter than this (after whitespace normalization) are never deduplicated; a pointer
+/// would save nothing.
+const DEDUP_MIN_CHARS: usize = 200;
+
+/// Separator before the storage hint appended to truncated results; ignored when comparing.
+const STORAGE_HINT_SEPARATOR: &str = "\n\nFull output saved to: ";
+
 pub struct ObserveNode {
     enable_loop: bool,
     /// When `Some(n)`, end loop after n observe rounds. When `None` (default for with_loop), no limit.
     max_turns: Option<u32>,
+    /// When true, repeated tool results are collapsed into pointers to the first occurrence.
+    dedup_observations: bool,
+    /// Framing and sanitizing of tool results (plain text by default).
+    framing: ToolResultFraming,
+    /// Summarizes results that are over its token threshold (off by default).
+    summarizer: Option<ObservationSummarizer>,
 }
 
 impl ObserveNode {
@@ -22,6 +52,9 @@ impl ObserveNode {
         Self {
             enable_loop: false,
             max_turns: None,
+            dedup_observations: false,
+            framing: ToolResultFraming::default(),
+            summarizer: None,
         }
     }
 
@@ -30,6 +63,9 @@ impl ObserveNode {
         Self {
             enable_loop: true,
             max_turns: None,
+            dedup_observations: false,
+            framing: ToolResultFraming::default(),
+            summarizer: None,
         }
     }
 
@@ -38,6 +74,110 @@ impl ObserveNode {
         Self {
             enable_loop: true,
             max_turns: Some(max_turns),
+            dedup_observations: false,
+            framing: ToolResultFraming::default(),
+            summarizer: None,
+        }
+    }
+
+    /// Collapses tool results identical (up to whitespace) to an earlier tool message into a
+    /// pointer with a repeat count. Off by default.
+    pub fn with_observation_dedup(mut self, enabled: bool) -> Self {
+        self.dedup_observations = enabled;
+        self
+    }
+
+    /// Frames (and optionally sanitizes) tool results before they are added to messages.
+    pub fn with_result_framing(mut self, framing: ToolResultFraming) -> Self {
+        self.framing = framing;
+        self
+    }
+
+    /// Replaces results over the summarizer's token threshold with a summary. Off by default.
+    pub fn with_summarizer(mut self, summarizer: Option<ObservationSummarizer>) -> Self {
+        self.summarizer = summarizer;
+        self
+    }
+
+    /// The summary replacing `tr`'s observation, when a summarizer is set and the raw output
+    /// is large enough. Errors are never summarized.
+    async fn summary_for(&self, tr: &ToolResult, name: &str, call_id: &str) -> Option<String> {
+        let summarizer = self.summarizer.as_ref().filter(|_| !tr.is_error)?;
+        let raw = match (tr.raw(), &tr.storage_ref) {
+            (Some(raw), _) => raw.to_string(),
+            (None, Some(storage)) => tokio::fs::read_to_string(&storage.path).await.ok()?,
+            (None, None) => tr.content.clone(),
+        };
+        summarizer.summarize(name, call_id, &raw).await
+    }
+}
+
+/// Comparison key for a tool message body: storage hint dropped, whitespace runs collapsed.
+fn dedup_key(body: &str) -> String {
+    let body = body
+        .split_once(STORAGE_HINT_SEPARATOR)
+        .map_or(body, |(head, _)| head);
+    body.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Call id referenced by a deduplicated tool message, if `body` is one.
+fn duplicate_of(body: &str) -> Option<&str> {
+    let (_, rest) = body.split_once(DUPLICATE_MARKER)?;
+    rest.split_once(' ').map(|(id, _)| id)
+}
+
+/// Full tool results seen so far in the thread: key -> (call id of first occurrence, times seen).
+struct SeenObservations {
+    by_key: HashMap<String, (String, usize)>,
+}
+
+impl SeenObservations {
+    fn from_messages(messages: &[Message]) -> Self {
+        let mut by_key: HashMap<String, (String, usize)> = HashMap::new();
+        let mut key_of_id: HashMap<String, String> = HashMap::new();
+        for m in messages {
+            let Message::Tool {
+                tool_call_id,
+                content,
+            } = m
+            else {
+                continue;
+            };
+            let Some(text) = content.as_text() else {
+                continue;
+            };
+            if let Some(original) = duplicate_of(text) {
+                if let Some(entry) = key_of_id.get(original).and_then(|k| by_key.get_mut(k)) {
+                    entry.1 += 1;
+                }
+                continue;
+            }
+            let key = dedup_key(text);
+            if key.chars().count() < DEDUP_MIN_CHARS {
+                continue;
+            }
+            key_of_id.insert(tool_call_id.clone(), key.clone());
+            by_key.entry(key).or_insert((tool_call_id.clone(), 1));
+        }
+        Self { by_key }
+    }
+
+    /// Records `body` under `call_id`. Returns the first call id and the new repeat count when
+    /// `body` was seen before.
+    fn record(&mut self, call_id: &str, body: &str) -> Option<(String, usize)> {
+        let key = dedup_key(body);
+        if key.chars().count() < DEDUP_MIN_CHARS {
+            return None;
+        }
+        match self.by_key.get_mut(&key) {
+            Some((first, count)) => {
+                *count += 1;
+                Some((first.clone(), *count))
+            }
+            None => {
+                self.by_key.insert(key, (call_id.to_string(), 1));
+                None
+            }
         }
     }
 }
@@ -57,6 +197,9 @@ impl Node<ReActState> for ObserveNode {
     async fn run(&self, state: ReActState) -> Result<(ReActState, Next), AgentError> {
         let had_tool_calls = !state.tool_calls.is_empty();
         let mut messages = state.messages;
+        let mut seen = self
+            .dedup_observations
+            .then(|| SeenObservations::from_messages(&messages));
         for tr in &state.tool_results {
             let name = tr
                 .name
@@ -65,19 +208,6 @@ impl Node<ReActState> for ObserveNode {
                 .unwrap_or("tool");
             let label = if tr.is_error { "error" } else { "result" };
 
-            // Observe only consumes the normalized observation view.
-            let observation = tr.observation();
-
-            let mut body = format!("Tool {} {}:\n{}", name, label, observation);
-
-            // Add storage reference hint if available
-            if let Some(ref storage_ref) = tr.storage_ref {
-                body.push_str(&format!(
-                    "\n\nFull output saved to: {}",
-                    storage_ref.path.display()
-                ));
-            }
-
             let tool_call_id = tr
                 .call_id
                 .clone()
@@ -90,6 +220,31 @@ impl Node<ReActState> for ObserveNode {
                     format!("call_{}", uuid6())
                 });
 
+            // Observe only consumes the normalized observation view (or its summary).
+            let summary = self.summary_for(tr, name, &tool_call_id).await;
+            let observation = summary.as_deref().unwrap_or_else(|| tr.observation());
+
+            let mut body = self.framing.format(name, label, observation);
+
+            // Add storage reference hint if available
+            if let (Some(storage_ref), None) = (&tr.storage_ref, &summary) {
+                body.push_str(&format!(
+                    "\n\nFull output saved to: {}",
+                    storage_ref.path.display()
+                ));
+            }
+
+            if let Some((first, count)) = seen
+                .as_mut()
+                .and_then(|seen| seen.record(&tool_call_id, &body))
+            {
+                info!(tool_name = %name, first_call_id = %first, count, "observe dedup");
+                body = format!(
+                    "Tool {} {}: {}{} (returned {} times); not repeated here.",
+                    name, label, DUPLICATE_MARKER, first, count
+                );
+            }
+
             messages.push(Message::Tool {
                 tool_call_id,
                 content: ToolCallContent::text(body),
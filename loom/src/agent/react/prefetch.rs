@@ -0,0 +1,361 @@
+//! Speculative tool prefetching: read-only tool calls start while the LLM is still streaming the
+//! turn that requests them, so tool latency overlaps LLM latency.
+//!
+//! [`ThinkNode`](super::ThinkNode) feeds the streamed tool-call deltas of each LLM call to a
+//! [`PrefetchTurn`]. As soon as a call is complete (its arguments form a JSON object, or the next
+//! call begins) and its tool is on the prefetch list and needs no approval, it starts in the
+//! background. When the turn commits, results of calls the final answer does not contain (the
+//! model changed its mind, a repair replaced the calls, generation was stopped) are discarded;
+//! [`ActNode`](super::ActNode) takes the held result of each call matching by name and arguments
+//! instead of calling the tool again.
+//!
+//! Prefetched calls run without a stream writer, so their progress and partial output are not
+//! streamed. A call the model ends up not making still ran: list only tools without side effects.
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde_json::Value;
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+use crate::helve::{ApprovalPolicy, ApprovalRules};
+use crate::llm::ToolCallDelta;
+use crate::state::ToolCall;
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSource, ToolSourceError};
+use crate::tools::{
+    TOOL_GLOB, TOOL_GREP, TOOL_LS, TOOL_READ_FILE, TOOL_TODO_READ, TOOL_WEB_FETCHER,
+};
+
+use super::act_node::{parse_tool_arguments, tool_arguments_error};
+
+/// Tools prefetched when `LOOM_TOOL_PREFETCH` is just switched on: built-in tools that only
+/// read.
+pub const DEFAULT_PREFETCH_TOOLS: &[&str] = &[
+    TOOL_READ_FILE,
+    TOOL_GREP,
+    TOOL_GLOB,
+    TOOL_LS,
+    TOOL_TODO_READ,
+    TOOL_WEB_FETCHER,
+];
+
+/// Tools to prefetch for a `LOOM_TOOL_PREFETCH` value: `1`/`true`/`yes` for
+/// [`DEFAULT_PREFETCH_TOOLS`], else a comma-separated list of tool names (`0`/`false`/`no` or
+/// empty = off).
+pub fn parse_prefetch_tools(value: &str) -> Vec<String> {
+    match value.trim().to_lowercase().as_str() {
+        "1" | "true" | "yes" => DEFAULT_PREFETCH_TOOLS
+            .iter()
+            .map(|t| t.to_string())
+            .collect(),
+        "" | "0" | "false" | "no" => Vec::new(),
+        _ => value
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(String::from)
+            .collect(),
+    }
+}
+
+/// A tool call started before its turn committed.
+struct Prefetched {
+    name: String,
+    args: Value,
+    task: JoinHandle<Result<ToolCallContent, ToolSourceError>>,
+}
+
+impl Prefetched {
+    /// Waits for the call's result. Dropping the future aborts the call.
+    async fn result(mut self) -> Result<ToolCallContent, ToolSourceError> {
+        (&mut self.task)
+            .await
+            .unwrap_or_else(|e| Err(ToolSourceError::Transport(e.to_string())))
+    }
+}
+
+impl Drop for Prefetched {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Prefetch state shared by the think node (which starts calls) and the act node (which takes
+/// their results).
+pub struct ToolPrefetch {
+    tools: Arc<dyn ToolSource>,
+    names: HashSet<String>,
+    approval_policy: Option<ApprovalPolicy>,
+    approval_rules: ApprovalRules,
+    held: Mutex<Vec<Prefetched>>,
+}
+
+impl ToolPrefetch {
+    /// Prefetches calls to `names` through `tools` (the source the act node calls).
+    pub fn new(tools: Arc<dyn ToolSource>, names: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            tools,
+            names: names.into_iter().collect(),
+            approval_policy: None,
+            approval_rules: ApprovalRules::default(),
+            held: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Never prefetches a call that needs approval under `policy` and `rules` (pass the act
+    /// node's).
+    pub fn with_approval(mut self, policy: Option<ApprovalPolicy>, rules: ApprovalRules) -> Self {
+        self.approval_policy = policy;
+        self.approval_rules = rules;
+        self
+    }
+
+    fn held(&self) -> MutexGuard<'_, Vec<Prefetched>> {
+        self.held.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn eligible(&self, name: &str, args: &Value) -> bool {
+        self.names.contains(name)
+            && !self
+                .approval_rules
+                .requires_approval(self.approval_policy, name, args)
+    }
+
+    /// Starts assembling the tool calls streamed by one LLM call; they run with `ctx`.
+    pub fn stream(&self, ctx: ToolCallContext) -> PrefetchTurn<'_> {
+        PrefetchTurn {
+            prefetch: self,
+            ctx,
+            current: None,
+        }
+    }
+
+    fn start(&self, name: String, args: Value, ctx: &ToolCallContext) {
+        if !self.eligible(&name, &args) {
+            return;
+        }
+        debug!(tool = %name, "prefetch: starting tool call while the turn streams");
+        let tools = Arc::clone(&self.tools);
+        let ctx = ctx.clone();
+        let (tool, call_args) = (name.clone(), args.clone());
+        let task = tokio::spawn(async move {
+            tools
+                .call_tool_with_context(&tool, call_args, Some(&ctx))
+                .await
+        });
+        self.held().push(Prefetched { name, args, task });
+    }
+
+    /// Keeps the results of calls among `tool_calls` (the committed turn) and discards the rest.
+    pub fn retain(&self, tool_calls: &[ToolCall]) {
+        let mut wanted: Vec<(&str, Value)> = tool_calls
+            .iter()
+            .map(|tc| (tc.name.as_str(), parse_tool_arguments(&tc.arguments)))
+            .collect();
+        self.held().retain(|p| {
+            match wanted
+                .iter()
+                .position(|(name, args)| *name == p.name && *args == p.args)
+            {
+                Some(i) => {
+                    wanted.swap_remove(i);
+                    true
+                }
+                None => {
+                    debug!(tool = %p.name, "prefetch: call not in the committed turn, discarded");
+                    false
+                }
+            }
+        });
+    }
+
+    /// Takes the held call to `name` with `args`: its result, to await in place of calling the
+    /// tool. `None` when no such call was prefetched.
+    pub fn take(
+        &self,
+        name: &str,
+        args: &Value,
+    ) -> Option<impl Future<Output = Result<ToolCallContent, ToolSourceError>> + Send> {
+        let mut held = self.held();
+        let i = held
+            .iter()
+            .position(|p| p.name == name && p.args == *args)?;
+        debug!(tool = %name, "prefetch: using prefetched result");
+        Some(held.remove(i).result())
+    }
+
+    /// Discards (and aborts) every held call, e.g. before a new turn.
+    pub fn discard(&self) {
+        self.held().clear();
+    }
+}
+
+/// Tool call being streamed.
+#[derive(Default)]
+struct StreamingCall {
+    id: Option<String>,
+    name: String,
+    arguments: String,
+    started: bool,
+}
+
+/// Assembles the tool-call deltas of one LLM call and starts each call once complete.
+pub struct PrefetchTurn<'a> {
+    prefetch: &'a ToolPrefetch,
+    ctx: ToolCallContext,
+    current: Option<StreamingCall>,
+}
+
+impl PrefetchTurn<'_> {
+    /// Adds one streamed delta. A delta with a new call id begins the next call.
+    pub fn feed(&mut self, delta: &ToolCallDelta) {
+        let next_call = delta.call_id.as_ref().is_some_and(|id| {
+            self.current
+                .as_ref()
+                .is_none_or(|c| c.id.as_ref() != Some(id))
+        });
+        if next_call {
+            self.flush();
+            self.current = Some(StreamingCall {
+                id: delta.call_id.clone(),
+                ..Default::default()
+            });
+        }
+        let call = self.current.get_or_insert_with(StreamingCall::default);
+        if let Some(name) = &delta.name {
+            call.name.push_str(name);
+        }
+        call.arguments.push_str(&delta.arguments_delta);
+        if call.started || call.name.is_empty() {
+            return;
+        }
+        if let Ok(args @ Value::Object(_)) = serde_json::from_str::<Value>(&call.arguments) {
+            call.started = true;
+            let name = call.name.clone();
+            self.prefetch.start(name, args, &self.ctx);
+        }
+    }
+
+    /// Starts the finished current call when its arguments were not an object yet (e.g. empty
+    /// arguments).
+    fn flush(&mut self) {
+        let Some(call) = self.current.take() else {
+            return;
+        };
+        if call.started || call.name.is_empty() || tool_arguments_error(&call.arguments).is_some() {
+            return;
+        }
+        let args = parse_tool_arguments(&call.arguments);
+        self.prefetch.start(call.name, args, &self.ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::tool_source::ToolSpec;
+
+    /// Echoes the tool name and arguments; counts calls.
+    #[derive(Default)]
+    struct Echo {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ToolSource for Echo {
+        async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+            Ok(Vec::new())
+        }
+
+        async fn call_tool(
+            &self,
+            name: &str,
+            arguments: Value,
+        ) -> Result<ToolCallContent, ToolSourceError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(ToolCallContent::text(format!("{} {}", name, arguments)))
+        }
+    }
+
+    fn delta(id: Option<&str>, name: Option<&str>, args: &str) -> ToolCallDelta {
+        ToolCallDelta {
+            call_id: id.map(String::from),
+            name: name.map(String::from),
+            arguments_delta: args.to_string(),
+        }
+    }
+
+    fn call(name: &str, arguments: &str) -> ToolCall {
+        ToolCall {
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+            id: None,
+        }
+    }
+
+    fn prefetch(tools: &Arc<Echo>) -> ToolPrefetch {
+        let source: Arc<dyn ToolSource> = tools.clone();
+        ToolPrefetch::new(source, parse_prefetch_tools("true"))
+    }
+
+    #[tokio::test]
+    async fn complete_calls_start_while_streaming_and_are_taken_once() {
+        let tools = Arc::new(Echo::default());
+        let prefetch = prefetch(&tools);
+        let mut turn = prefetch.stream(ToolCallContext::default());
+        turn.feed(&delta(Some("c1"), Some(TOOL_READ_FILE), "{\"path\":"));
+        assert!(prefetch.held().is_empty());
+        turn.feed(&delta(None, None, " \"a.rs\"}"));
+        turn.feed(&delta(Some("c2"), Some("bash"), "{\"command\":\"ls\"}"));
+        turn.feed(&delta(Some("c3"), Some(TOOL_LS), ""));
+        drop(turn);
+        assert_eq!(prefetch.held().len(), 1);
+
+        prefetch.retain(&[
+            call(TOOL_READ_FILE, "{\"path\": \"a.rs\"}"),
+            call("bash", "{\"command\":\"ls\"}"),
+        ]);
+        let args = serde_json::json!({"path": "a.rs"});
+        let result = prefetch.take(TOOL_READ_FILE, &args).unwrap().await.unwrap();
+        assert_eq!(result.as_text(), Some("read {\"path\":\"a.rs\"}"));
+        assert!(prefetch.take(TOOL_READ_FILE, &args).is_none());
+        assert_eq!(tools.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn calls_missing_from_the_committed_turn_are_discarded() {
+        let tools = Arc::new(Echo::default());
+        let prefetch = prefetch(&tools);
+        let mut turn = prefetch.stream(ToolCallContext::default());
+        turn.feed(&delta(Some("c1"), Some(TOOL_GREP), "{\"pattern\":\"foo\"}"));
+        turn.feed(&delta(Some("c2"), Some(TOOL_GLOB), ""));
+        turn.feed(&delta(Some("c3"), Some(TOOL_LS), "{}"));
+        drop(turn);
+        assert_eq!(prefetch.held().len(), 3);
+
+        prefetch.retain(&[call(TOOL_GREP, "{\"pattern\":\"bar\"}"), call(TOOL_LS, "")]);
+        assert!(prefetch
+            .take(TOOL_GREP, &serde_json::json!({"pattern": "foo"}))
+            .is_none());
+        assert!(prefetch.take(TOOL_LS, &serde_json::json!({})).is_some());
+        assert!(prefetch.held().is_empty());
+    }
+
+    #[test]
+    fn parses_prefetch_tool_lists() {
+        assert_eq!(
+            parse_prefetch_tools("yes").len(),
+            DEFAULT_PREFETCH_TOOLS.len()
+        );
+        assert!(parse_prefetch_tools(" 0 ").is_empty());
+        assert_eq!(
+            parse_prefetch_tools("read, mcp_search ,"),
+            vec!["read".to_string(), "mcp_search".to_string()]
+        );
+    }
+}
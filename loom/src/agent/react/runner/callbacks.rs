@@ -0,0 +1,132 @@
+//! Per-event callbacks for [`ReactRunner::invoke_with_callbacks`](super::ReactRunner::invoke_with_callbacks).
+//!
+//! [`Callbacks`] covers what most embedders build on top of the stream channel: answer tokens,
+//! tool starts and ends, node starts and token usage. Callbacks that are not set are skipped;
+//! use [`ReactRunner::stream_with_config`](super::ReactRunner::stream_with_config) for the
+//! full [`StreamEvent`] stream.
+
+use std::fmt;
+
+use crate::llm::LlmUsage;
+use crate::state::ReActState;
+use crate::stream::{MessageChunkKind, StreamEvent};
+
+/// Callbacks run while a ReAct run streams. Empty by default.
+///
+/// ```no_run
+/// # async fn demo(runner: loom::ReactRunner) -> Result<(), loom::ReactRunError> {
+/// let state = runner
+///     .invoke_with_callbacks(
+///         "What time is it?",
+///         loom::Callbacks::new()
+///             .on_token(|token| print!("{}", token))
+///             .on_tool_start(|name, _call_id| eprintln!("[{}]", name)),
+///     )
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct Callbacks<'a> {
+    on_token: Option<Box<dyn FnMut(&str) + Send + 'a>>,
+    on_tool_start: Option<Box<dyn FnMut(&str, Option<&str>) + Send + 'a>>,
+    on_tool_end: Option<Box<dyn FnMut(&str, &str, bool) + Send + 'a>>,
+    on_node: Option<Box<dyn FnMut(&str) + Send + 'a>>,
+    on_usage: Option<Box<dyn FnMut(&LlmUsage) + Send + 'a>>,
+}
+
+impl fmt::Debug for Callbacks<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Callbacks")
+            .field("on_token", &self.on_token.is_some())
+            .field("on_tool_start", &self.on_tool_start.is_some())
+            .field("on_tool_end", &self.on_tool_end.is_some())
+            .field("on_node", &self.on_node.is_some())
+            .field("on_usage", &self.on_usage.is_some())
+            .finish()
+    }
+}
+
+impl<'a> Callbacks<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the callback run with each streamed chunk of the answer (not of reasoning).
+    pub fn on_token(mut self, callback: impl FnMut(&str) + Send + 'a) -> Self {
+        self.on_token = Some(Box::new(callback));
+        self
+    }
+
+    /// Sets the callback run with the tool name and call id before a tool runs.
+    pub fn on_tool_start(mut self, callback: impl FnMut(&str, Option<&str>) + Send + 'a) -> Self {
+        self.on_tool_start = Some(Box::new(callback));
+        self
+    }
+
+    /// Sets the callback run with the tool name, its result and whether it failed after a tool
+    /// returned.
+    pub fn on_tool_end(mut self, callback: impl FnMut(&str, &str, bool) + Send + 'a) -> Self {
+        self.on_tool_end = Some(Box::new(callback));
+        self
+    }
+
+    /// Sets the callback run with the node id when a graph node starts (`think`, `act`, ...).
+    pub fn on_node(mut self, callback: impl FnMut(&str) + Send + 'a) -> Self {
+        self.on_node = Some(Box::new(callback));
+        self
+    }
+
+    /// Sets the callback run with the token usage of each LLM completion that reports it.
+    pub fn on_usage(mut self, callback: impl FnMut(&LlmUsage) + Send + 'a) -> Self {
+        self.on_usage = Some(Box::new(callback));
+        self
+    }
+
+    /// Runs the callback matching `event`, if any.
+    pub(crate) fn dispatch(&mut self, event: &StreamEvent<ReActState>) {
+        match event {
+            StreamEvent::Messages { chunk, .. } if chunk.kind == MessageChunkKind::Message => {
+                if let Some(on_token) = &mut self.on_token {
+                    on_token(&chunk.content);
+                }
+            }
+            StreamEvent::ToolStart { call_id, name } => {
+                if let Some(on_tool_start) = &mut self.on_tool_start {
+                    on_tool_start(name, call_id.as_deref());
+                }
+            }
+            StreamEvent::ToolEnd {
+                name,
+                result,
+                is_error,
+                ..
+            } => {
+                if let Some(on_tool_end) = &mut self.on_tool_end {
+                    on_tool_end(name, result, *is_error);
+                }
+            }
+            StreamEvent::TaskStart { node_id, .. } => {
+                if let Some(on_node) = &mut self.on_node {
+                    on_node(node_id);
+                }
+            }
+            StreamEvent::Usage {
+                prompt_tokens,
+                completion_tokens,
+                total_tokens,
+                ..
+            } => {
+                if let Some(on_usage) = &mut self.on_usage {
+                    on_usage(&LlmUsage {
+                        prompt_tokens: *prompt_tokens,
+                        completion_tokens: *completion_tokens,
+                        total_tokens: *total_tokens,
+                        ..LlmUsage::default()
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -1,5 +1,6 @@
 //! Build initial ReAct state from user message, optionally loading from checkpoint.
 
+use crate::compress::HistoryWindow;
 use crate::memory::{CheckpointError, Checkpointer, RunnableConfig};
 use crate::message::Message;
 use crate::runner_common::load_from_checkpoint_or_build;
@@ -11,6 +12,25 @@ pub async fn build_react_initial_state(
     checkpointer: Option<&dyn Checkpointer<ReActState>>,
     runnable_config: Option<&RunnableConfig>,
     system_prompt: &str,
+) -> Result<ReActState, CheckpointError> {
+    build_react_initial_state_with_window(
+        user_message,
+        checkpointer,
+        runnable_config,
+        system_prompt,
+        None,
+    )
+    .await
+}
+
+/// Same as [`build_react_initial_state`], but a thread loaded from checkpoint is first cut down
+/// to `history_window` (when set) before the new user message is appended.
+pub async fn build_react_initial_state_with_window(
+    user_message: &str,
+    checkpointer: Option<&dyn Checkpointer<ReActState>>,
+    runnable_config: Option<&RunnableConfig>,
+    system_prompt: &str,
+    history_window: Option<&HistoryWindow>,
 ) -> Result<ReActState, CheckpointError> {
     let user_message_owned = user_message.to_string();
     load_from_checkpoint_or_build(
@@ -18,30 +38,62 @@ pub async fn build_react_initial_state(
         runnable_config,
         user_message,
         async move {
-            Ok(ReActState {
-                messages: vec![
-                    Message::system(system_prompt),
-                    Message::user(user_message_owned),
-                ],
-                last_reasoning_content: None,
-                tool_calls: vec![],
-                tool_results: vec![],
-                turn_count: 0,
-                approval_result: None,
-                usage: None,
-                total_usage: None,
-                message_count_after_last_think: None,
-                summary: None,
-                think_count: 0,
-                should_continue: true,
-            })
+            Ok(fresh_state(vec![
+                Message::system(system_prompt),
+                Message::user(user_message_owned),
+            ]))
         },
         |mut state, msg| {
+            if let Some(window) = history_window {
+                let before = state.messages.len();
+                state.messages = window.apply(std::mem::take(&mut state.messages));
+                if state.messages.len() != before {
+                    state.message_count_after_last_think = None;
+                }
+            }
             state.messages.push(Message::user(msg));
             state.tool_calls = vec![];
             state.tool_results = vec![];
+            state.reflection_count = 0;
+            state.answer_revision = 0;
+            state.confidence = None;
             state
         },
     )
     .await
 }
+
+/// Builds initial [`ReActState`] from a client-managed history (without checkpoint loading):
+/// the system prompt followed by `messages`, whose last entry is the user message to answer.
+pub fn build_react_initial_state_from_history(
+    messages: Vec<Message>,
+    system_prompt: &str,
+) -> ReActState {
+    let mut all = Vec::with_capacity(messages.len() + 1);
+    all.push(Message::system(system_prompt));
+    all.extend(messages);
+    fresh_state(all)
+}
+
+fn fresh_state(messages: Vec<Message>) -> ReActState {
+    ReActState {
+        messages,
+        last_reasoning_content: None,
+        tool_calls: vec![],
+        tool_results: vec![],
+        turn_count: 0,
+        approval_result: None,
+        usage: None,
+        total_usage: None,
+        usage_by_model: Default::default(),
+        message_count_after_last_think: None,
+        summary: None,
+        think_count: 0,
+        should_continue: true,
+        reflection_count: 0,
+        answer_revision: 0,
+        confidence: None,
+        approval_memory: Default::default(),
+        remembered_approvals: Default::default(),
+    }
+}
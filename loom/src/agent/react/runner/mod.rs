@@ -1,12 +1,18 @@
 //! ReAct graph runner: encapsulates graph build, initial state, invoke and stream.
 
+mod callbacks;
 mod error;
 mod initial_state;
 mod options;
 #[allow(clippy::module_inception)]
 mod runner;
 
+pub use callbacks::Callbacks;
 pub use error::RunError;
-pub use initial_state::build_react_initial_state;
+pub use initial_state::{
+    build_react_initial_state, build_react_initial_state_from_history,
+    build_react_initial_state_with_window,
+};
 pub use options::AgentOptions;
+pub(crate) use options::SummarizeConfig;
 pub use runner::{run_agent, run_react_graph_stream, ReactRunner};
@@ -3,7 +3,7 @@
 use std::sync::Arc;
 
 use crate::memory::{Checkpointer, RunnableConfig, Store};
-use crate::state::ReActState;
+use crate::state::{ConfidenceScoring, ReActState};
 use crate::tool_source::ToolSource;
 use crate::user_message::UserMessageStore;
 use crate::LlmClient;
@@ -22,6 +22,12 @@ pub struct SummarizeConfig {
     /// When disabled (default), no tool calls → end immediately.
     /// When enabled, an extra LLM call determines if task is complete or should continue.
     pub enable_completion_check: bool,
+    /// Whether to review the draft final answer with [`crate::agent::react::VerifyNode`] before
+    /// ending, looping back to think (a bounded number of times) when it has gaps.
+    pub enable_reflection: bool,
+    /// When set, scores the final answer with [`crate::agent::react::ConfidenceNode`] before
+    /// ending and stores it in [`ReActState::confidence`].
+    pub confidence_scoring: Option<ConfidenceScoring>,
 }
 
 impl Default for SummarizeConfig {
@@ -31,6 +37,8 @@ impl Default for SummarizeConfig {
             max_length: 50,
             prompt_template: None,
             enable_completion_check: false,
+            enable_reflection: false,
+            confidence_scoring: None,
         }
     }
 }
@@ -55,6 +63,18 @@ impl SummarizeConfig {
         self
     }
 
+    /// Enable the answer verification (reflection) pass before the turn ends.
+    pub fn with_reflection(mut self, enabled: bool) -> Self {
+        self.enable_reflection = enabled;
+        self
+    }
+
+    /// Score the final answer before the turn ends (`None` = off).
+    pub fn with_confidence_scoring(mut self, scoring: Option<ConfidenceScoring>) -> Self {
+        self.confidence_scoring = scoring;
+        self
+    }
+
     /// Set the maximum length of the summary.
     pub fn with_max_length(mut self, max_length: usize) -> Self {
         self.max_length = max_length;
@@ -137,6 +157,7 @@ mod tests {
         assert_eq!(cfg.max_length, 50);
         assert!(cfg.prompt_template.is_none());
         assert!(!cfg.enable_completion_check);
+        assert!(!cfg.enable_reflection);
     }
 
     #[test]
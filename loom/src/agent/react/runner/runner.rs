@@ -1,34 +1,49 @@
 //! ReactRunner: compiled graph, invoke and stream.
 
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::agent::react::REACT_SYSTEM_PROMPT;
-use crate::compress::{build_graph, CompactionConfig, CompressionGraphNode};
+use crate::approval_audit::ApprovalAuditStore;
+use crate::compress::{
+    build_graph, CompactionConfig, CompressionGraphNode, ContextGuard, HistoryWindow,
+};
 use crate::graph::{
-    CompilationError, CompiledStateGraph, LoggingNodeMiddleware, StateGraph, END, START,
+    CompilationError, CompiledStateGraph, LoggingNodeMiddleware, NodeMiddleware,
+    NodeMiddlewareStack, RouteRule, StateGraph, StateLimits, END, START,
 };
-use crate::helve::ApprovalPolicy;
-use crate::llm::RetryLlmClient;
+use crate::helve::{ApprovalPolicy, ApprovalRules};
+use crate::llm::{NodeLlmOverrides, RetryLlmClient};
 use crate::memory::{Checkpointer, RunnableConfig, Store};
+use crate::message::Message;
+use crate::run_hooks::RunHooks;
 use crate::runner_common;
-use crate::state::ReActState;
+use crate::state::{ReActState, ToolResultFraming};
 use crate::stream::StreamEvent;
-use crate::tool_source::ToolSource;
+use crate::tool_source::{PluggableToolSource, ToolSource, ToolSourceError};
 use crate::user_message::UserMessageStore;
-use crate::{LlmClient, RunCancellation};
+use crate::{LlmClient, Node, RunCancellation};
 
+use super::callbacks::Callbacks;
 use super::error::RunError;
-use super::initial_state::build_react_initial_state;
+use super::initial_state::{
+    build_react_initial_state_from_history, build_react_initial_state_with_window,
+};
 use super::options::SummarizeConfig;
 use super::options::{resolve_run_agent_options, AgentOptions};
 use crate::agent::react::act_node::{ActNode, HandleToolErrors};
 use crate::agent::react::completion_check_node::CompletionCheckNode;
+use crate::agent::react::confidence_node::ConfidenceNode;
+use crate::agent::react::graph_spec::{GraphSpec, SpecNode};
 use crate::agent::react::observe_node::ObserveNode;
+use crate::agent::react::prefetch::ToolPrefetch;
 use crate::agent::react::summarize_node::SummarizeNode;
 use crate::agent::react::think_node::ThinkNode;
 use crate::agent::react::tools_condition;
+use crate::agent::react::verify_node::VerifyNode;
 use crate::agent::react::with_node_logging::WithNodeLogging;
+use crate::agent::react::{EnvContext, MemoryRecall, ObservationSummarizer};
 
 pub struct ReactRunner {
     compiled: CompiledStateGraph<ReActState>,
@@ -36,6 +51,11 @@ pub struct ReactRunner {
     runnable_config: Option<RunnableConfig>,
     system_prompt: String,
     cancellation: Option<RunCancellation>,
+    history_window: Option<HistoryWindow>,
+    memory_recall: Option<MemoryRecall>,
+    env_context: Option<EnvContext>,
+    tools: Arc<PluggableToolSource>,
+    hooks: RunHooks<ReActState>,
 }
 
 impl ReactRunner {
@@ -44,6 +64,86 @@ impl ReactRunner {
         self
     }
 
+    /// Runs `hooks` when a run starts, finishes (with the final state and token usage) or fails
+    /// (see [`RunHooks`]).
+    pub fn with_hooks(mut self, hooks: RunHooks<ReActState>) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Wraps nodes whose id matches `node_id_pattern` (`*` wildcard; ReAct node ids are
+    /// `think`, `act`, `observe`, `compress`, plus `summarize`, `completion_check`, `verify` and
+    /// `confidence` when enabled) with `middleware`. Runs inside the verbose node logging, if any.
+    pub fn with_middleware(
+        self,
+        node_id_pattern: impl Into<String>,
+        middleware: Arc<dyn NodeMiddleware<ReActState>>,
+    ) -> Self {
+        self.with_middleware_stack(NodeMiddlewareStack::new().with(node_id_pattern, middleware))
+    }
+
+    /// Attaches all entries of `stack` (see [`NodeMiddlewareStack`]); a no-op when it is empty.
+    pub fn with_middleware_stack(mut self, stack: NodeMiddlewareStack<ReActState>) -> Self {
+        if !stack.is_empty() {
+            self.compiled = self.compiled.with_added_middleware(Arc::new(stack));
+        }
+        self
+    }
+
+    /// Injects the store memories most relevant to each new user message into the system
+    /// prompt; see [`MemoryRecall`].
+    pub fn with_memory_recall(mut self, memory_recall: Option<MemoryRecall>) -> Self {
+        self.memory_recall = memory_recall;
+        self
+    }
+
+    /// Puts the current date and time, OS, working folder and tool names into the system prompt
+    /// at the start of each run; see [`EnvContext`].
+    pub fn with_env_context(mut self, env_context: Option<EnvContext>) -> Self {
+        self.env_context = env_context;
+        self
+    }
+
+    /// Attaches route rules to the ReAct graph (see [`CompiledStateGraph::with_route_rules`]);
+    /// a no-op when `rules` is empty.
+    pub fn with_route_rules(mut self, rules: Vec<RouteRule>) -> Result<Self, CompilationError> {
+        if !rules.is_empty() {
+            self.compiled = self.compiled.with_route_rules(rules)?;
+        }
+        Ok(self)
+    }
+
+    /// Bounds the history replayed from a checkpointed thread; see [`HistoryWindow`].
+    pub fn with_history_window(mut self, history_window: Option<HistoryWindow>) -> Self {
+        self.history_window = history_window;
+        self
+    }
+
+    /// Caps the run state's size; see [`CompiledStateGraph::with_state_limits`]. `None` = no caps.
+    pub fn with_state_limits(mut self, limits: Option<StateLimits>) -> Self {
+        if let Some(limits) = limits {
+            self.compiled = self.compiled.with_state_limits(limits);
+        }
+        self
+    }
+
+    /// Builds and compiles the ReAct graph.
+    ///
+    /// `node_llms` routes individual LLM-backed nodes (`think`, `compress`, `summarize`,
+    /// `completion_check`, `verify`, `confidence`) to other models; nodes without an override use `llm`.
+    /// `auto_continue` is the max number of follow-up calls when a think answer is truncated by
+    /// the output token limit (0 = off). `dedup_observations` collapses repeated tool results
+    /// (see [`ObserveNode::with_observation_dedup`]). `context_guard` keeps each think prompt
+    /// within the model's context (see [`ThinkNode::with_context_guard`]). `approval_rules` refine
+    /// `approval_policy` per tool and argument pattern (see [`ActNode::with_approval_rules`]);
+    /// `approval_audit` records each approval request and decision.
+    /// `tool_result_framing` frames and sanitizes tool results before they reach the messages
+    /// (see [`ObserveNode::with_result_framing`]). `observation_summary` (token threshold, raw
+    /// output directory) summarizes oversized tool results with the `observe` node model (see
+    /// [`ObservationSummarizer`]). `tool_prefetch` lists the tools whose calls start while think
+    /// is still streaming them (see [`ToolPrefetch`]); empty = off. `graph_spec` replaces the
+    /// built-in topology (and the summarize, completion check and reflection options) with the
+    /// spec's nodes and edges (see [`GraphSpec`]).
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         llm: Box<dyn LlmClient>,
@@ -58,17 +158,66 @@ impl ReactRunner {
         cancellation: Option<RunCancellation>,
         verbose: bool,
         summarize_config: Option<SummarizeConfig>,
+        node_llms: NodeLlmOverrides,
+        auto_continue: u32,
+        dedup_observations: bool,
+        context_guard: Option<ContextGuard>,
+        approval_rules: ApprovalRules,
+        approval_audit: Option<Arc<dyn ApprovalAuditStore>>,
+        tool_result_framing: ToolResultFraming,
+        observation_summary: Option<(usize, PathBuf)>,
+        tool_prefetch: Vec<String>,
+        graph_spec: Option<&GraphSpec>,
     ) -> Result<Self, CompilationError> {
         let llm: Arc<dyn LlmClient> = Arc::from(llm);
         let retry_llm: Arc<dyn LlmClient> = Arc::new(RetryLlmClient::new(llm.clone()));
-        let think = ThinkNode::new(Arc::clone(&retry_llm));
-        let act = ActNode::new(tool_source)
-            .with_handle_tool_errors(HandleToolErrors::Always(None))
-            .with_approval_policy(approval_policy);
-        let observe = ObserveNode::with_loop();
+        let llm_for = |node_id: &str| -> Arc<dyn LlmClient> {
+            match node_llms.get(node_id) {
+                Some(n) => Arc::new(RetryLlmClient::new(Arc::clone(&n.llm))),
+                None => Arc::clone(&retry_llm),
+            }
+        };
+        let tools = Arc::new(PluggableToolSource::new(Arc::from(tool_source)));
+        let tool_source: Arc<dyn ToolSource> = tools.clone();
+        let prefetch = (!tool_prefetch.is_empty()).then(|| {
+            Arc::new(
+                ToolPrefetch::new(Arc::clone(&tool_source), tool_prefetch)
+                    .with_approval(approval_policy, approval_rules.clone()),
+            )
+        });
+        let make_think = |auto_continue: u32| {
+            let think = ThinkNode::new(llm_for("think"))
+                .with_model_label(node_llms.model_for("think"))
+                .with_auto_continue(auto_continue)
+                .with_tool_refresh(Arc::clone(&tool_source))
+                .with_tool_prefetch(prefetch.clone());
+            match context_guard.clone() {
+                Some(guard) => think.with_context_guard(guard),
+                None => think,
+            }
+        };
+        let make_act = || {
+            ActNode::new(Box::new(Arc::clone(&tool_source)))
+                .with_handle_tool_errors(HandleToolErrors::Always(None))
+                .with_approval_policy(approval_policy)
+                .with_approval_rules(approval_rules.clone())
+                .with_approval_audit(approval_audit.clone())
+                .with_tool_prefetch(prefetch.clone())
+        };
+        let make_observe = |observe: ObserveNode, dedup: bool| {
+            observe
+                .with_observation_dedup(dedup)
+                .with_result_framing(tool_result_framing.clone())
+                .with_summarizer(observation_summary.clone().map(|(threshold, dir)| {
+                    ObservationSummarizer::new(llm_for("observe"), threshold, dir)
+                }))
+        };
+        let think = make_think(auto_continue);
+        let act = make_act();
+        let observe = make_observe(ObserveNode::with_loop(), dedup_observations);
 
         let compaction_cfg = compaction_config.unwrap_or_default();
-        let compression_graph = build_graph(compaction_cfg.clone(), Arc::clone(&retry_llm))?;
+        let compression_graph = build_graph(compaction_cfg.clone(), llm_for("compress"))?;
         let compress_node = Arc::new(CompressionGraphNode::new(compression_graph));
 
         let mut graph = StateGraph::<ReActState>::new();
@@ -81,13 +230,56 @@ impl ReactRunner {
         let completion_check_enabled = summarize_config
             .as_ref()
             .is_some_and(|c| c.enable_completion_check);
+        let reflection_enabled = summarize_config
+            .as_ref()
+            .is_some_and(|c| c.enable_reflection);
+        let confidence_scoring = summarize_config
+            .as_ref()
+            .and_then(|c| c.confidence_scoring)
+            .filter(|_| graph_spec.is_none());
+        // Every route that would end the turn goes through verify (with reflection), then
+        // confidence (with scoring) first.
+        let confidence_target = if confidence_scoring.is_some() {
+            "confidence"
+        } else {
+            END
+        };
+        let end_target = if reflection_enabled {
+            "verify"
+        } else {
+            confidence_target
+        };
 
-        if summarize_enabled {
+        if let Some(spec) = graph_spec {
+            spec.add_to(&mut graph, |node| -> Arc<dyn Node<ReActState>> {
+                match *node {
+                    SpecNode::Think { auto_continue: max } => {
+                        Arc::new(make_think(max.unwrap_or(auto_continue)))
+                    }
+                    SpecNode::Act => Arc::new(make_act()),
+                    SpecNode::Observe { dedup } => Arc::new(make_observe(
+                        ObserveNode::new(),
+                        dedup.unwrap_or(dedup_observations),
+                    )),
+                    SpecNode::Compress => compress_node.clone(),
+                    SpecNode::Summarize => Arc::new(SummarizeNode::new(llm_for("summarize"))),
+                    SpecNode::CompletionCheck {
+                        max_iterations,
+                        message_window,
+                    } => Arc::new(
+                        CompletionCheckNode::new(llm_for("completion_check"))
+                            .with_max_iterations(max_iterations.unwrap_or(10))
+                            .with_message_window(message_window.unwrap_or(5)),
+                    ),
+                    SpecNode::Verify => Arc::new(VerifyNode::new(llm_for("verify"))),
+                }
+            });
+        } else if summarize_enabled {
             // Summarize node for generating session summaries after first think
-            let summarize_node = SummarizeNode::new(Arc::clone(&retry_llm));
+            let summarize_node = SummarizeNode::new(llm_for("summarize"));
 
             if completion_check_enabled {
-                let completion_check = CompletionCheckNode::new(Arc::clone(&retry_llm))
+                let completion_check = CompletionCheckNode::new(llm_for("completion_check"))
                     .with_max_iterations(10)
                     .with_message_window(5);
 
@@ -108,7 +300,7 @@ impl ReactRunner {
 
                 let completion_check_path_map: HashMap<String, String> = [
                     ("continue".into(), "think".into()),
-                    (END.into(), END.into()),
+                    (END.into(), end_target.into()),
                 ]
                 .into_iter()
                 .collect();
@@ -172,15 +364,17 @@ impl ReactRunner {
                 let think_condition_path_map: HashMap<String, String> = [
                     ("summarize".into(), "summarize".into()),
                     ("tools".into(), "act".into()),
-                    (END.into(), END.into()),
+                    (END.into(), end_target.into()),
                 ]
                 .into_iter()
                 .collect();
 
-                let summarize_condition_path_map: HashMap<String, String> =
-                    [("tools".into(), "act".into()), (END.into(), END.into())]
-                        .into_iter()
-                        .collect();
+                let summarize_condition_path_map: HashMap<String, String> = [
+                    ("tools".into(), "act".into()),
+                    (END.into(), end_target.into()),
+                ]
+                .into_iter()
+                .collect();
 
                 graph
                     .add_node("think", Arc::new(think))
@@ -219,21 +413,21 @@ impl ReactRunner {
             }
         } else if completion_check_enabled {
             // No summarize, with completion check
-            let completion_check = CompletionCheckNode::new(Arc::clone(&retry_llm))
+            let completion_check = CompletionCheckNode::new(llm_for("completion_check"))
                 .with_max_iterations(10)
                 .with_message_window(5);
 
             let think_condition_path_map: HashMap<String, String> = [
                 ("tools".into(), "act".into()),
                 ("completion_check".into(), "completion_check".into()),
-                (END.into(), END.into()),
+                (END.into(), end_target.into()),
             ]
             .into_iter()
             .collect();
 
             let completion_check_path_map: HashMap<String, String> = [
                 ("continue".into(), "think".into()),
-                (END.into(), END.into()),
+                (END.into(), end_target.into()),
             ]
             .into_iter()
             .collect();
@@ -272,10 +466,12 @@ impl ReactRunner {
                 .add_edge("compress", "think");
         } else {
             // No summarize, no completion check - original graph
-            let think_condition_path_map: HashMap<String, String> =
-                [("tools".into(), "act".into()), (END.into(), END.into())]
-                    .into_iter()
-                    .collect();
+            let think_condition_path_map: HashMap<String, String> = [
+                ("tools".into(), "act".into()),
+                (END.into(), end_target.into()),
+            ]
+            .into_iter()
+            .collect();
 
             graph
                 .add_node("think", Arc::new(think))
@@ -293,6 +489,36 @@ impl ReactRunner {
                 .add_edge("compress", "think");
         }
 
+        if reflection_enabled && graph_spec.is_none() {
+            let verify = VerifyNode::new(llm_for("verify"));
+            let verify_path_map: HashMap<String, String> = [
+                ("continue".into(), "think".into()),
+                (END.into(), confidence_target.into()),
+            ]
+            .into_iter()
+            .collect();
+            graph
+                .add_node("verify", Arc::new(verify))
+                .add_conditional_edges(
+                    "verify",
+                    Arc::new(|state: &ReActState| {
+                        if state.should_continue {
+                            "continue".to_string()
+                        } else {
+                            END.to_string()
+                        }
+                    }),
+                    Some(verify_path_map),
+                );
+        }
+
+        if let Some(scoring) = confidence_scoring {
+            let confidence = ConfidenceNode::new(llm_for("confidence"), scoring);
+            graph
+                .add_node("confidence", Arc::new(confidence))
+                .add_edge("confidence", END);
+        }
+
         let graph = if verbose {
             graph.with_node_logging()
         } else {
@@ -314,9 +540,48 @@ impl ReactRunner {
             runnable_config,
             system_prompt,
             cancellation,
+            history_window: None,
+            memory_recall: None,
+            env_context: None,
+            tools,
+            hooks: RunHooks::default(),
         })
     }
 
+    /// Adds `source` (e.g. an MCP server connected after the thread started) under `name`,
+    /// replacing a source previously added with that name; returns its tool names. The next
+    /// think step exposes the new tools. Call between turns; a turn in progress may not see it.
+    pub async fn add_tool_source(
+        &self,
+        name: impl Into<String>,
+        source: Box<dyn ToolSource>,
+    ) -> Result<Vec<String>, ToolSourceError> {
+        self.tools.add(name, Arc::from(source)).await
+    }
+
+    /// Removes a source added with [`Self::add_tool_source`]; returns whether one was removed.
+    /// Its tools are gone from the next think step on.
+    pub fn remove_tool_source(&self, name: &str) -> bool {
+        self.tools.remove(name)
+    }
+
+    /// Names of the sources added with [`Self::add_tool_source`].
+    pub fn tool_source_names(&self) -> Vec<String> {
+        self.tools.names()
+    }
+
+    /// Returns the latest checkpointed state for `thread_id`, or `None` when the runner has
+    /// no checkpointer or the thread has not been checkpointed yet. Does not run the graph,
+    /// so it is safe to call while another task is streaming on the same runner.
+    pub async fn state_snapshot(&self, thread_id: &str) -> Result<Option<ReActState>, RunError> {
+        Ok(runner_common::load_state_snapshot(
+            self.checkpointer.as_deref(),
+            self.runnable_config.as_ref(),
+            thread_id,
+        )
+        .await?)
+    }
+
     pub async fn invoke(&self, user_message: &str) -> Result<ReActState, RunError> {
         self.invoke_with_config(user_message, None).await
     }
@@ -327,14 +592,21 @@ impl ReactRunner {
         config: Option<RunnableConfig>,
     ) -> Result<ReActState, RunError> {
         let run_config = config.or_else(|| self.runnable_config.clone());
-        let state = build_react_initial_state(
+        let mut state = build_react_initial_state_with_window(
             user_message,
             self.checkpointer.as_deref(),
             run_config.as_ref(),
             &self.system_prompt,
+            self.history_window.as_ref(),
         )
         .await?;
-        let final_state = self.compiled.invoke(state, run_config).await?;
+        self.apply_env_context(&mut state).await;
+        if let Some(recall) = &self.memory_recall {
+            recall.apply(&mut state, user_message).await;
+        }
+        let final_state =
+            runner_common::invoke_with_hooks(&self.compiled, state, run_config, &self.hooks)
+                .await?;
         Ok(final_state)
     }
 
@@ -359,13 +631,93 @@ impl ReactRunner {
         F: FnMut(StreamEvent<ReActState>),
     {
         let run_config = config.or_else(|| self.runnable_config.clone());
-        let state = build_react_initial_state(
+        let mut state = build_react_initial_state_with_window(
             user_message,
             self.checkpointer.as_deref(),
             run_config.as_ref(),
             &self.system_prompt,
+            self.history_window.as_ref(),
         )
         .await?;
+        self.apply_env_context(&mut state).await;
+        if let Some(recall) = &self.memory_recall {
+            recall.apply(&mut state, user_message).await;
+        }
+        self.stream_state(state, run_config, on_event).await
+    }
+
+    /// Like [`Self::invoke`], but streams the run and reports tokens, tool calls, node starts
+    /// and usage to `callbacks` as they happen. A cancelled run fails with
+    /// [`AgentError::Cancelled`](crate::error::AgentError::Cancelled).
+    pub async fn invoke_with_callbacks(
+        &self,
+        user_message: &str,
+        mut callbacks: Callbacks<'_>,
+    ) -> Result<ReActState, RunError> {
+        let outcome = self
+            .stream_with_config(
+                user_message,
+                None,
+                Some(|event: StreamEvent<ReActState>| callbacks.dispatch(&event)),
+            )
+            .await?;
+        match outcome {
+            runner_common::StreamRunOutcome::Finished(state) => Ok(state),
+            runner_common::StreamRunOutcome::Cancelled => {
+                Err(RunError::Execution(crate::error::AgentError::Cancelled))
+            }
+        }
+    }
+
+    /// Like [`Self::stream_with_config`], but starts from a client-managed history instead of
+    /// the checkpointed thread: `messages` follow the system prompt and must end with the user
+    /// message to answer. Checkpoints are still written when a thread id is configured.
+    pub async fn stream_history_with_config<F>(
+        &self,
+        messages: Vec<Message>,
+        config: Option<RunnableConfig>,
+        on_event: Option<F>,
+    ) -> Result<runner_common::StreamRunOutcome<ReActState>, RunError>
+    where
+        F: FnMut(StreamEvent<ReActState>),
+    {
+        let run_config = config.or_else(|| self.runnable_config.clone());
+        let query = messages.iter().rev().find_map(|m| match m {
+            Message::User(c) => Some(c.as_text().to_string()),
+            _ => None,
+        });
+        let mut state = build_react_initial_state_from_history(messages, &self.system_prompt);
+        self.apply_env_context(&mut state).await;
+        if let (Some(recall), Some(query)) = (&self.memory_recall, query) {
+            recall.apply(&mut state, &query).await;
+        }
+        self.stream_state(state, run_config, on_event).await
+    }
+
+    /// Refreshes the environment block in the system prompt, when env context is on.
+    async fn apply_env_context(&self, state: &mut ReActState) {
+        let Some(env_context) = &self.env_context else {
+            return;
+        };
+        let tool_names: Vec<String> = match self.tools.list_tools().await {
+            Ok(specs) => specs.into_iter().map(|t| t.name).collect(),
+            Err(e) => {
+                tracing::warn!(error = %e, "env context: listing tools failed");
+                Vec::new()
+            }
+        };
+        env_context.apply(state, &tool_names);
+    }
+
+    async fn stream_state<F>(
+        &self,
+        state: ReActState,
+        run_config: Option<RunnableConfig>,
+        on_event: Option<F>,
+    ) -> Result<runner_common::StreamRunOutcome<ReActState>, RunError>
+    where
+        F: FnMut(StreamEvent<ReActState>),
+    {
         runner_common::run_stream_with_config(
             &self.compiled,
             state,
@@ -373,6 +725,7 @@ impl ReactRunner {
             on_event,
             self.cancellation.as_ref().map(RunCancellation::token),
             self.cancellation.clone(),
+            &self.hooks,
         )
         .await
         .map_err(|e| match e {
@@ -402,6 +755,16 @@ pub async fn run_agent(
         None,
         opts.verbose,
         Some(opts.summarize_config),
+        NodeLlmOverrides::default(),
+        0,
+        false,
+        None,
+        ApprovalRules::default(),
+        None,
+        ToolResultFraming::default(),
+        None,
+        Vec::new(),
+        None,
     )?;
     runner.invoke(user_message).await
 }
@@ -428,6 +791,16 @@ where
         None,
         opts.verbose,
         Some(opts.summarize_config),
+        NodeLlmOverrides::default(),
+        0,
+        false,
+        None,
+        ApprovalRules::default(),
+        None,
+        ToolResultFraming::default(),
+        None,
+        Vec::new(),
+        None,
     )?;
     runner.stream_with_callback(user_message, on_event).await
 }
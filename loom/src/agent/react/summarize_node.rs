@@ -112,10 +112,16 @@ impl Node<ReActState> for SummarizeNode {
                     approval_result: state.approval_result,
                     usage: state.usage,
                     total_usage: state.total_usage,
+                    usage_by_model: state.usage_by_model,
                     message_count_after_last_think: state.message_count_after_last_think,
                     summary: Some(summary),
                     think_count: state.think_count,
                     should_continue: state.should_continue,
+                    reflection_count: 0,
+                    answer_revision: state.answer_revision,
+                    confidence: state.confidence,
+                    approval_memory: state.approval_memory,
+                    remembered_approvals: state.remembered_approvals,
                 };
 
                 Ok((new_state, Next::Continue))
@@ -1,29 +1,389 @@
 //! Think node: read messages, call LLM, write assistant message and optional tool_calls.
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 
 use async_trait::async_trait;
-use serde_json::Value;
+use serde_json::{json, Value};
 use tokio::sync::mpsc;
 use tracing::{debug, trace};
 
-use crate::cli_run::ActiveOperationKind;
+use super::act_node::{parse_tool_arguments, tool_arguments_error};
+use super::prefetch::{PrefetchTurn, ToolPrefetch};
+use crate::cli_run::{validate_schema, ActiveOperationKind, RunCancellation};
+use crate::compress::{compaction, ContextGuard, GuardDecision};
 use crate::error::AgentError;
 use crate::graph::{run_cancellable, Next, RunContext};
-use crate::llm::{LlmClient, LlmResponse, ToolCallDelta};
+use crate::llm::{FinishReason, LlmClient, LlmResponse, TextToolCallLlm, ToolCallDelta};
 use crate::message::Message;
+use crate::model_spec::ToolCallMode;
 use crate::state::{ReActState, ToolCall};
-use crate::stream::{ChunkToStreamSender, MessageChunk, StreamEvent, StreamMetadata, StreamMode};
+use crate::stream::{
+    ChunkToStreamSender, MessageChunk, StreamEvent, StreamMetadata, StreamMode, TimingKind,
+};
+use crate::tool_source::{
+    ToolCallContext, ToolSource, ToolSpec, TOOL_LIST_ALL_TOOLS, TOOL_SOURCE_DEGRADED_EVENT,
+};
 use crate::Node;
 
 pub struct ThinkNode {
     llm: Arc<dyn LlmClient>,
+    /// Model id used to label usage in [`ReActState::usage_by_model`]; `None` skips per-model accounting.
+    model_label: Option<String>,
+    /// Max follow-up calls when the answer is cut off by the output token limit
+    /// ([`FinishReason::Length`]); 0 disables auto-continue.
+    auto_continue: u32,
+    /// Max extra calls asking the model to re-emit tool calls whose arguments are malformed
+    /// (invalid JSON or not matching the tool's input schema); 0 disables the repair.
+    tool_call_repairs: u32,
+    /// Checked before each LLM call for changed tools (see [`ToolSource::refresh_tools`]) and
+    /// for the tools to expose this turn (see [`ToolSource::tools_for_turn`]).
+    tools: Option<Arc<dyn ToolSource>>,
+    /// True while the LLM has a narrowed tool list, so the full list is restored when
+    /// selection stops applying.
+    tools_narrowed: AtomicBool,
+    /// Set once unavailable tool sources were checked for and reported.
+    health_reported: AtomicBool,
+    /// True when the LLM (or the context guard's fallback) was wrapped in a
+    /// [`TextToolCallLlm`], whose tool list is seeded from `tools` on the first run.
+    text_protocol: bool,
+    /// Set once the text-protocol LLM got its tools.
+    tools_seeded: AtomicBool,
+    /// Checks each prompt against the model's context window; see [`ThinkNode::with_context_guard`].
+    context_guard: Option<ContextGuard>,
+    /// Starts read-only tool calls while the answer streams; see [`ThinkNode::with_tool_prefetch`].
+    prefetch: Option<Arc<ToolPrefetch>>,
 }
 
+/// User turn appended after a truncated answer to ask the model to go on.
+const CONTINUE_PROMPT: &str = "Continue exactly where you stopped, without repeating anything.";
+
+/// Default for [`ThinkNode::with_tool_call_repair`].
+pub const DEFAULT_TOOL_CALL_REPAIRS: u32 = 2;
+
 impl ThinkNode {
+    /// Think node calling `llm`. A model without function calling
+    /// ([`ToolCallMode::Text`] in [`LlmClient::tool_support`]) is wrapped in a
+    /// [`TextToolCallLlm`], so it calls tools through its reply text; a model without parallel
+    /// tool calls gets one tool call per turn.
     pub fn new(llm: Arc<dyn LlmClient>) -> Self {
-        Self { llm }
+        let (llm, text_protocol) = adapt_tool_calls(llm);
+        Self {
+            llm,
+            model_label: None,
+            auto_continue: 0,
+            tool_call_repairs: DEFAULT_TOOL_CALL_REPAIRS,
+            tools: None,
+            tools_narrowed: AtomicBool::new(false),
+            health_reported: AtomicBool::new(false),
+            text_protocol,
+            tools_seeded: AtomicBool::new(false),
+            context_guard: None,
+            prefetch: None,
+        }
+    }
+
+    /// Before each turn's LLM call, estimates the prompt and, when it exceeds the model's
+    /// context, calls the guard's fallback model for that turn (sending a
+    /// [`StreamEvent::ModelSwitched`]) or, without a fitting fallback, compacts the history first.
+    /// A fallback without function calling is adapted like the default LLM.
+    pub fn with_context_guard(mut self, mut guard: ContextGuard) -> Self {
+        if let Some(fallback) = guard.fallback.as_mut() {
+            let (llm, text_protocol) = adapt_tool_calls(fallback.llm.clone());
+            fallback.llm = llm;
+            self.text_protocol |= text_protocol;
+        }
+        self.context_guard = Some(guard);
+        self
+    }
+
+    /// On the first run, gives a text-protocol LLM the source's tools: wrapping it cleared the
+    /// tool definitions set on the model when the agent was built.
+    async fn seed_text_protocol_tools(&self) {
+        if !self.text_protocol {
+            return;
+        }
+        let Some(tools) = self.tools.as_ref() else {
+            return;
+        };
+        if self.tools_seeded.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        match tools.list_tools().await {
+            Ok(specs) => self.set_llm_tools(specs),
+            Err(e) => tracing::warn!("think: listing tools for the text protocol failed: {}", e),
+        }
+    }
+
+    /// Sets the tools on the LLM and on the context guard's fallback, so a switched call sees
+    /// the same tools.
+    fn set_llm_tools(&self, specs: Vec<ToolSpec>) {
+        if let Some(fallback) = self
+            .context_guard
+            .as_ref()
+            .and_then(|g| g.fallback.as_ref())
+        {
+            fallback.llm.set_tools(specs.clone());
+        }
+        self.llm.set_tools(specs);
+    }
+
+    /// Applies the context guard to `state`: returns the (possibly compacted) state, the LLM
+    /// for this turn and its model label, plus the switch to report, if any.
+    async fn guard_context(
+        &self,
+        mut state: ReActState,
+    ) -> Result<(ReActState, ThinkLlm), AgentError> {
+        let default = ThinkLlm {
+            llm: Arc::clone(&self.llm),
+            model_label: self.model_label.clone(),
+            switched: None,
+        };
+        let Some(guard) = self.context_guard.as_ref() else {
+            return Ok((state, default));
+        };
+        let mut prompt_tokens = guard.prompt_tokens(&state);
+        let mut compacted = false;
+        loop {
+            match guard.decide(prompt_tokens, !compacted) {
+                GuardDecision::Fits => {
+                    if compacted
+                        && prompt_tokens.saturating_add(guard.compaction.reserve_tokens)
+                            > guard.compaction.max_context_tokens
+                    {
+                        tracing::warn!(
+                            prompt_tokens,
+                            context_limit = guard.compaction.max_context_tokens,
+                            "think: prompt exceeds the context window even after compaction"
+                        );
+                    }
+                    return Ok((state, default));
+                }
+                GuardDecision::Switch(fallback) => {
+                    debug!(
+                        prompt_tokens,
+                        from = %guard.model,
+                        to = %fallback.model,
+                        "think: prompt exceeds the context window, switching model"
+                    );
+                    let event = StreamEvent::ModelSwitched {
+                        from: guard.model.clone(),
+                        to: fallback.model.clone(),
+                        prompt_tokens,
+                        context_limit: guard.compaction.max_context_tokens,
+                    };
+                    return Ok((
+                        state,
+                        ThinkLlm {
+                            llm: Arc::clone(&fallback.llm),
+                            model_label: Some(fallback.model.clone()),
+                            switched: Some(event),
+                        },
+                    ));
+                }
+                GuardDecision::Compact => {
+                    debug!(
+                        prompt_tokens,
+                        context_limit = guard.compaction.max_context_tokens,
+                        "think: prompt exceeds the context window, compacting history"
+                    );
+                    state.messages =
+                        compaction::compact(&state.messages, self.llm.as_ref(), &guard.compaction)
+                            .await?;
+                    // The last usage no longer describes these messages.
+                    state.message_count_after_last_think = None;
+                    prompt_tokens = guard.prompt_tokens(&state);
+                    compacted = true;
+                }
+            }
+        }
+    }
+
+    /// Compacts the history once the provider rejected the prompt as too long, whatever the
+    /// guard estimated; returns `err` unchanged when it is another error or there is no guard.
+    async fn compact_after_overflow(
+        &self,
+        mut state: ReActState,
+        err: AgentError,
+    ) -> Result<ReActState, AgentError> {
+        let (Some(guard), AgentError::ContextLengthExceeded(message)) =
+            (self.context_guard.as_ref(), &err)
+        else {
+            return Err(err);
+        };
+        if state.messages.len() <= guard.compaction.compact_keep_recent {
+            return Err(err);
+        }
+        tracing::warn!(
+            error = %message,
+            messages = state.messages.len(),
+            "think: provider reported the context length exceeded, compacting history"
+        );
+        state.messages =
+            compaction::compact(&state.messages, self.llm.as_ref(), &guard.compaction).await?;
+        state.message_count_after_last_think = None;
+        Ok(state)
+    }
+
+    /// Before each LLM call, asks `tools` for changed tools; when it reports a new list, the
+    /// LLM gets it via [`LlmClient::set_tools`] and a [`StreamEvent::ToolsRefreshed`] is sent.
+    /// Pass the same source the act node calls.
+    pub fn with_tool_refresh(mut self, tools: Arc<dyn ToolSource>) -> Self {
+        self.tools = Some(tools);
+        self
+    }
+
+    /// Applies changed tools to the LLM; returns the new tool names, if any changed.
+    async fn refresh_tools(&self) -> Option<Vec<String>> {
+        let tools = self.tools.as_ref()?;
+        match tools.refresh_tools().await {
+            Ok(Some(specs)) => {
+                let names: Vec<String> = specs.iter().map(|s| s.name.clone()).collect();
+                debug!(
+                    tools = names.len(),
+                    "think: tool list changed, updating LLM tools"
+                );
+                self.set_llm_tools(specs);
+                Some(names)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("think: refreshing tools failed: {}", e);
+                None
+            }
+        }
+    }
+
+    /// On the node's first run, emits a [`TOOL_SOURCE_DEGRADED_EVENT`] Custom event listing the
+    /// tool sources that are unavailable (see [`ToolSource::health`]); their tools were skipped.
+    async fn report_degraded_tools(&self, ctx: &RunContext<ReActState>) {
+        let Some(tools) = self.tools.as_ref() else {
+            return;
+        };
+        if self.health_reported.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let health = tools.health().await;
+        if health.is_healthy() {
+            return;
+        }
+        tracing::warn!(
+            sources = health.degraded.len(),
+            "think: some tool sources are unavailable; their tools are skipped"
+        );
+        let _ = ctx
+            .emit_custom(json!({
+                "type": TOOL_SOURCE_DEGRADED_EVENT,
+                "sources": health.degraded,
+            }))
+            .await;
+    }
+
+    /// Narrows the LLM's tools to those the source selects for the latest user message. Once
+    /// the model called [`TOOL_LIST_ALL_TOOLS`] in the current turn, every tool is exposed.
+    async fn select_tools(&self, state: &ReActState) {
+        let Some(tools) = self.tools.as_ref() else {
+            return;
+        };
+        let selected = if list_all_tools_requested(&state.messages) {
+            None
+        } else {
+            let query = last_user_text(&state.messages);
+            tools.tools_for_turn(&query).await.unwrap_or_else(|e| {
+                tracing::warn!("think: selecting tools failed, exposing all: {}", e);
+                None
+            })
+        };
+        match selected {
+            Some(specs) => {
+                debug!(tools = specs.len(), "think: exposing selected tools");
+                self.tools_narrowed.store(true, Ordering::SeqCst);
+                self.set_llm_tools(specs);
+            }
+            None if self.tools_narrowed.swap(false, Ordering::SeqCst) => {
+                match tools.list_tools().await {
+                    Ok(specs) => self.set_llm_tools(specs),
+                    Err(e) => tracing::warn!("think: listing tools failed: {}", e),
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// When the LLM stops with [`FinishReason::Length`] and no tool calls, calls it again up to
+    /// `max_continuations` times and appends each continuation to the answer.
+    pub fn with_auto_continue(mut self, max_continuations: u32) -> Self {
+        self.auto_continue = max_continuations;
+        self
+    }
+
+    /// Whether `response` was truncated and another continuation is allowed after `done` ones.
+    fn should_continue(&self, response: &LlmResponse, done: u32) -> bool {
+        done < self.auto_continue
+            && response.tool_calls.is_empty()
+            && response.finish_reason == Some(FinishReason::Length)
+    }
+
+    /// When tool calls come back with malformed arguments, sends the errors to the model and
+    /// asks it to re-emit the calls, up to `max_repairs` times (default
+    /// [`DEFAULT_TOOL_CALL_REPAIRS`]). Calls still malformed afterwards are reported back as
+    /// error results by the act node instead of being run.
+    pub fn with_tool_call_repair(mut self, max_repairs: u32) -> Self {
+        self.tool_call_repairs = max_repairs;
+        self
+    }
+
+    /// Problems with the arguments of `tool_calls`: unparseable JSON, or (when the node has a
+    /// tool source) arguments not matching the tool's input schema. Unknown tools are left to
+    /// the act node.
+    async fn tool_call_problems(&self, tool_calls: &[ToolCall]) -> Vec<String> {
+        if tool_calls.is_empty() {
+            return Vec::new();
+        }
+        let specs = match self.tools.as_ref() {
+            Some(tools) => tools.list_tools().await.unwrap_or_default(),
+            None => Vec::new(),
+        };
+        let mut problems = Vec::new();
+        for tc in tool_calls {
+            if let Some(error) = tool_arguments_error(&tc.arguments) {
+                problems.push(format!(
+                    "{}: arguments are not valid JSON ({})",
+                    tc.name, error
+                ));
+                continue;
+            }
+            let Some(spec) = specs.iter().find(|s| s.name == tc.name) else {
+                continue;
+            };
+            let args = parse_tool_arguments(&tc.arguments);
+            if let Err(e) = validate_schema(&args, &spec.input_schema, "arguments") {
+                problems.push(format!("{}: {}", tc.name, e));
+            }
+        }
+        problems
+    }
+
+    /// Streams every LLM call and hands its tool-call deltas to `prefetch`, which starts eligible
+    /// calls before the answer is complete; once the turn commits, calls it does not contain are
+    /// discarded. Pass the same prefetch to
+    /// [`ActNode::with_tool_prefetch`](super::ActNode::with_tool_prefetch).
+    pub fn with_tool_prefetch(mut self, prefetch: Option<Arc<ToolPrefetch>>) -> Self {
+        self.prefetch = prefetch;
+        self
+    }
+
+    /// Sets the model id under which this node's token usage is recorded.
+    pub fn with_model_label(mut self, model: Option<String>) -> Self {
+        self.model_label = model;
+        self
+    }
+
+    fn record_model_usage(model_label: Option<&str>, state: &mut ReActState) {
+        if let (Some(model), Some(usage)) = (model_label, state.usage.clone()) {
+            state.record_model_usage(model, &usage);
+        }
     }
 
     /// Emits stream events after the LLM returns and before state is committed (messages, tool calls).
@@ -35,6 +395,7 @@ impl ThinkNode {
         content: &str,
         should_stream: bool,
         streamed_chunks: u64,
+        revision: u32,
         tool_calls: &[ToolCall],
         should_stream_tools: bool,
         is_cancelled: impl Fn() -> bool,
@@ -46,7 +407,10 @@ impl ThinkNode {
         if should_stream && !content.is_empty() && streamed_chunks == 0 {
             let _ = stream_tx
                 .send(StreamEvent::Messages {
-                    chunk: MessageChunk::message(content.to_string()),
+                    chunk: MessageChunk {
+                        revision,
+                        ..MessageChunk::message(content.to_string())
+                    },
                     metadata: StreamMetadata {
                         loom_node: self.id().to_string(),
                         namespace: None,
@@ -75,6 +439,83 @@ impl ThinkNode {
         Ok(())
     }
 
+    /// Calls the LLM (streaming when requested), aborting on cancellation. A stop request
+    /// ([`RunCancellation::stop_generation`]) ends the call early with the content produced so far.
+    async fn invoke_cancellable(
+        &self,
+        llm: &Arc<dyn LlmClient>,
+        ctx: &RunContext<ReActState>,
+        messages: &[Message],
+        should_stream: bool,
+        should_stream_tools: bool,
+        revision: u32,
+    ) -> Result<(LlmResponse, u64, Option<Instant>), AgentError> {
+        let stop = ctx.run_cancellation.as_ref();
+        let prefetch = self.prefetch.as_ref().map(|p| {
+            p.stream(ToolCallContext {
+                recent_messages: messages.to_vec(),
+                stream_writer: None,
+                thread_id: ctx.config.thread_id.clone(),
+                user_id: ctx.config.user_id.clone(),
+                depth: ctx.config.depth.unwrap_or(0),
+                run_cancellation: ctx.run_cancellation.clone(),
+                flags: ctx.config.flags.clone(),
+            })
+        });
+        let llm_call = async {
+            if should_stream || should_stream_tools || prefetch.is_some() {
+                invoke_think_llm(
+                    llm,
+                    messages,
+                    should_stream,
+                    should_stream_tools,
+                    ctx.stream_tx.clone(),
+                    self.id(),
+                    revision,
+                    stop,
+                    prefetch,
+                )
+                .await
+            } else {
+                let response = tokio::select! {
+                    biased;
+                    r = llm.invoke(messages) => r?,
+                    _ = stop_signal(stop) => stopped_response(String::new()),
+                };
+                Ok((response, 0u64, None::<Instant>))
+            }
+        };
+        let result = run_cancellable(
+            llm_call,
+            ctx.cancellation.as_ref(),
+            ctx.run_cancellation.as_ref(),
+            ActiveOperationKind::Llm,
+        )
+        .await;
+        if let (false, Some(p)) = (matches!(result, Ok(Ok(_))), self.prefetch.as_ref()) {
+            p.discard();
+        }
+        let result = result?;
+        if let (Ok((response, _, _)), Some(rc)) = (&result, stop) {
+            if response.finish_reason == Some(FinishReason::UserStopped) {
+                debug!(
+                    content_len = response.content.len(),
+                    "think: generation stopped on request"
+                );
+                rc.take_stop_request();
+            }
+        }
+        result
+    }
+
+    async fn emit_finish_reason(&self, ctx: &RunContext<ReActState>, response: &LlmResponse) {
+        if let (Some(stream_tx), Some(reason)) =
+            (ctx.stream_tx.as_ref(), response.finish_reason.clone())
+        {
+            let _ = stream_tx.send(StreamEvent::FinishReason { reason }).await;
+        }
+    }
+
     async fn emit_usage_event(
         &self,
         ctx: &RunContext<ReActState>,
@@ -113,58 +554,221 @@ impl ThinkNode {
     }
 }
 
+/// LLM a think turn calls, chosen by the context guard.
+struct ThinkLlm {
+    llm: Arc<dyn LlmClient>,
+    model_label: Option<String>,
+    /// [`StreamEvent::ModelSwitched`] to send when the guard switched models.
+    switched: Option<StreamEvent<ReActState>>,
+}
+
+/// Wraps `llm` in a [`TextToolCallLlm`] when it has no function calling; the flag tells
+/// whether it did.
+fn adapt_tool_calls(llm: Arc<dyn LlmClient>) -> (Arc<dyn LlmClient>, bool) {
+    if llm.tool_support().mode == ToolCallMode::Text {
+        debug!("think: model has no function calling, using the text tool-call protocol");
+        (Arc::new(TextToolCallLlm::new(llm)), true)
+    } else {
+        (llm, false)
+    }
+}
+
+/// Keeps only the first tool call when `llm` does not support parallel calls; the model sees
+/// the result and calls the next tool in its next turn.
+fn serialize_tool_calls(llm: &dyn LlmClient, response: &mut LlmResponse) {
+    if response.tool_calls.len() > 1 && !llm.tool_support().parallel_tool_calls() {
+        debug!(
+            dropped = response.tool_calls.len() - 1,
+            "think: model calls tools serially, keeping the first call"
+        );
+        response.tool_calls.truncate(1);
+    }
+}
+
+/// Resolves when the run asks to stop generation; never when there is no run handle.
+async fn stop_signal(stop: Option<&RunCancellation>) {
+    match stop {
+        Some(rc) => rc.stop_requested().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Response for a generation stopped on request: the text produced so far and no tool calls
+/// (partially streamed tool-call arguments are unusable).
+fn stopped_response(content: String) -> LlmResponse {
+    LlmResponse {
+        content,
+        reasoning_content: None,
+        tool_calls: Vec::new(),
+        usage: None,
+        finish_reason: Some(FinishReason::UserStopped),
+    }
+}
+
+/// Streams one LLM call. On a stop request the provider stream is dropped and the message text
+/// forwarded so far becomes the response ([`stopped_response`]). Message chunks carry answer
+/// `revision`. Tool-call deltas go to the stream (when `should_stream_tools`) and to `prefetch`.
+#[allow(clippy::too_many_arguments)]
 async fn invoke_think_llm(
     llm: &Arc<dyn LlmClient>,
     messages: &[Message],
     should_stream: bool,
     should_stream_tools: bool,
-    stream_tx: mpsc::Sender<StreamEvent<ReActState>>,
+    stream_tx: Option<mpsc::Sender<StreamEvent<ReActState>>>,
     node_id: &str,
+    revision: u32,
+    stop: Option<&RunCancellation>,
+    mut prefetch: Option<PrefetchTurn<'_>>,
 ) -> Result<(LlmResponse, u64, Option<Instant>), AgentError> {
-    let (chunk_tx, chunk_rx) = if should_stream {
-        let adapter = ChunkToStreamSender::new(stream_tx.clone(), node_id);
-        let (tx, rx) = adapter.channel();
-        (Some(tx), Some((adapter, rx)))
-    } else {
-        (None, None)
+    let (chunk_tx, chunk_rx) = match stream_tx.as_ref().filter(|_| should_stream) {
+        Some(tx) => {
+            let adapter = ChunkToStreamSender::new(tx.clone(), node_id).with_revision(revision);
+            let (tx, rx) = adapter.channel();
+            (Some(tx), Some((adapter, rx)))
+        }
+        None => (None, None),
     };
 
-    let (tool_delta_tx, tool_delta_rx) = if should_stream_tools {
+    let stream_tx_tool = stream_tx.filter(|_| should_stream_tools);
+    let (tool_delta_tx, tool_delta_rx) = if stream_tx_tool.is_some() || prefetch.is_some() {
         let (tx, rx) = mpsc::channel::<ToolCallDelta>(64);
         (Some(tx), Some(rx))
     } else {
         (None, None)
     };
 
-    let stream_tx_tool = stream_tx.clone();
     let tool_forward = async move {
         if let Some(mut rx) = tool_delta_rx {
             while let Some(delta) = rx.recv().await {
-                let _ = stream_tx_tool
-                    .send(StreamEvent::ToolCallChunk {
-                        call_id: delta.call_id,
-                        name: delta.name,
-                        arguments_delta: delta.arguments_delta,
-                    })
-                    .await;
+                if let Some(turn) = prefetch.as_mut() {
+                    turn.feed(&delta);
+                }
+                if let Some(tx) = &stream_tx_tool {
+                    let _ = tx
+                        .send(StreamEvent::ToolCallChunk {
+                            call_id: delta.call_id,
+                            name: delta.name,
+                            arguments_delta: delta.arguments_delta,
+                        })
+                        .await;
+                }
             }
         }
     };
 
     let msg_forward = async move {
         if let Some((adapter, rx)) = chunk_rx {
-            adapter.forward(rx).await
+            adapter.forward_collecting(rx).await
         } else {
-            (0, None)
+            (0, None, String::new())
+        }
+    };
+
+    // Dropping the LLM future on stop also drops its chunk senders, which ends both forwarders.
+    let llm_call = async {
+        tokio::select! {
+            biased;
+            r = llm.invoke_stream_with_tool_delta(messages, chunk_tx, tool_delta_tx) => Some(r),
+            _ = stop_signal(stop) => None,
         }
     };
 
-    let (result, (forwarded_chunks, first_token_at), _) = tokio::join!(
-        llm.invoke_stream_with_tool_delta(messages, chunk_tx, tool_delta_tx),
-        msg_forward,
-        tool_forward,
-    );
-    Ok((result?, forwarded_chunks as u64, first_token_at))
+    let (result, (forwarded_chunks, first_token_at, partial), _) =
+        tokio::join!(llm_call, msg_forward, tool_forward);
+    let response = match result {
+        Some(r) => r?,
+        None => stopped_response(partial),
+    };
+    Ok((response, forwarded_chunks as u64, first_token_at))
+}
+
+/// Text of the latest user message; empty when there is none.
+fn last_user_text(messages: &[Message]) -> String {
+    messages
+        .iter()
+        .rev()
+        .find_map(|m| match m {
+            Message::User(content) => Some(content.as_text().into_owned()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// True when an assistant message after the latest user message called [`TOOL_LIST_ALL_TOOLS`].
+fn list_all_tools_requested(messages: &[Message]) -> bool {
+    messages
+        .iter()
+        .rev()
+        .take_while(|m| !matches!(m, Message::User(_)))
+        .any(|m| match m {
+            Message::Assistant(payload) => payload
+                .tool_calls
+                .iter()
+                .any(|tc| tc.name == TOOL_LIST_ALL_TOOLS),
+            _ => false,
+        })
+}
+
+/// Conversation for a continuation call: the original messages, the truncated answer so far, and
+/// [`CONTINUE_PROMPT`].
+fn continuation_messages(messages: &[Message], partial: &str) -> Vec<Message> {
+    let mut out = messages.to_vec();
+    out.push(Message::assistant(partial));
+    out.push(Message::user(CONTINUE_PROMPT));
+    out
+}
+
+/// Conversation for a repair call: the original messages, the rejected answer with its tool calls
+/// written out as text, and a system message listing the problems.
+fn repair_messages(
+    messages: &[Message],
+    response: &LlmResponse,
+    problems: &[String],
+) -> Vec<Message> {
+    let calls: Vec<String> = response
+        .tool_calls
+        .iter()
+        .map(|tc| format!("{}({})", tc.name, tc.arguments))
+        .collect();
+    let mut rejected = response.content.clone();
+    if !rejected.is_empty() {
+        rejected.push('\n');
+    }
+    rejected.push_str(&calls.join("\n"));
+    let mut out = messages.to_vec();
+    out.push(Message::assistant(rejected));
+    out.push(Message::system(format!(
+        "Your tool calls were not run because their arguments are malformed:\n- {}\nRe-emit the tool calls with arguments that are a valid JSON object matching each tool's schema.",
+        problems.join("\n- ")
+    )));
+    out
+}
+
+/// Replaces a rejected response by its repair; usage of both calls is summed.
+fn merge_repair(acc: &mut LlmResponse, next: LlmResponse) {
+    let usage = match (acc.usage.take(), next.usage.clone()) {
+        (Some(a), Some(b)) => Some(a.accumulate(&b)),
+        (a, b) => a.or(b),
+    };
+    *acc = next;
+    acc.usage = usage;
+}
+
+/// Appends a continuation to the accumulated response; tool calls and finish reason come from
+/// the latest call, usage is summed.
+fn merge_continuation(acc: &mut LlmResponse, next: LlmResponse) {
+    acc.content.push_str(&next.content);
+    if let Some(reasoning) = next.reasoning_content {
+        acc.reasoning_content
+            .get_or_insert_with(String::new)
+            .push_str(&reasoning);
+    }
+    acc.tool_calls = next.tool_calls;
+    acc.finish_reason = next.finish_reason;
+    acc.usage = match (acc.usage.take(), next.usage) {
+        (Some(a), Some(b)) => Some(a.accumulate(&b)),
+        (a, b) => a.or(b),
+    };
 }
 
 #[async_trait]
@@ -174,13 +778,49 @@ impl Node<ReActState> for ThinkNode {
     }
 
     async fn run(&self, state: ReActState) -> Result<(ReActState, Next), AgentError> {
-        let response = self.llm.invoke(&state.messages).await?;
-        let new_state = state.apply_think(
+        self.seed_text_protocol_tools().await;
+        self.refresh_tools().await;
+        self.select_tools(&state).await;
+        let (mut state, turn) = self.guard_context(state).await?;
+        let llm = &turn.llm;
+        let mut response = match llm.invoke(&state.messages).await {
+            Err(e @ AgentError::ContextLengthExceeded(_)) => {
+                state = self.compact_after_overflow(state, e).await?;
+                llm.invoke(&state.messages).await?
+            }
+            result => result?,
+        };
+        let mut continuations = 0;
+        while self.should_continue(&response, continuations) {
+            let messages = continuation_messages(&state.messages, &response.content);
+            let next = llm.invoke(&messages).await?;
+            merge_continuation(&mut response, next);
+            continuations += 1;
+        }
+        let mut repairs = 0;
+        while repairs < self.tool_call_repairs {
+            let problems = self.tool_call_problems(&response.tool_calls).await;
+            if problems.is_empty() {
+                break;
+            }
+            debug!(
+                repairs,
+                ?problems,
+                "think: malformed tool calls, asking for a repair"
+            );
+            let messages = repair_messages(&state.messages, &response, &problems);
+            let next = llm.invoke(&messages).await?;
+            merge_repair(&mut response, next);
+            repairs += 1;
+        }
+        serialize_tool_calls(llm.as_ref(), &mut response);
+        let mut new_state = state.apply_think(
             response.content,
             response.reasoning_content,
             response.tool_calls,
             response.usage,
         );
+        Self::record_model_usage(turn.model_label.as_deref(), &mut new_state);
         Ok((new_state, Next::Continue))
     }
 
@@ -197,50 +837,156 @@ impl Node<ReActState> for ThinkNode {
         if is_cancelled() {
             return Err(AgentError::Cancelled);
         }
+        let node_start = Instant::now();
+        if let Some(prefetch) = &self.prefetch {
+            prefetch.discard();
+        }
         let should_stream =
             ctx.stream_mode.contains(&StreamMode::Messages) && ctx.stream_tx.is_some();
         let should_stream_tools = (ctx.stream_mode.contains(&StreamMode::Tools)
             || ctx.stream_mode.contains(&StreamMode::Debug))
             && ctx.stream_tx.is_some();
 
+        self.seed_text_protocol_tools().await;
+        if let Some(tools) = self.refresh_tools().await {
+            if let Some(stream_tx) = ctx.stream_tx.as_ref() {
+                let _ = stream_tx.send(StreamEvent::ToolsRefreshed { tools }).await;
+            }
+        }
+        self.report_degraded_tools(ctx).await;
+        self.select_tools(&state).await;
+        let (mut state, turn) = self.guard_context(state).await?;
+        if let (Some(stream_tx), Some(event)) = (ctx.stream_tx.as_ref(), turn.switched.clone()) {
+            let _ = stream_tx.send(event).await;
+        }
+        let llm = &turn.llm;
+        let mut revision = state.answer_revision;
+
         debug!(
             messages = state.messages.len(),
             should_stream, should_stream_tools, "think: invoking LLM"
         );
 
         let call_start = Instant::now();
-        let llm_call = async {
-            if should_stream || should_stream_tools {
-                invoke_think_llm(
-                    &self.llm,
+        ctx.emit_timing(
+            self.id(),
+            TimingKind::Prompt,
+            None,
+            call_start.duration_since(node_start),
+        )
+        .await;
+        let first_call = self
+            .invoke_cancellable(
+                llm,
+                ctx,
+                &state.messages,
+                should_stream,
+                should_stream_tools,
+                revision,
+            )
+            .await;
+        let (mut response, mut streamed_chunks, first_token_at) = match first_call {
+            Err(e @ AgentError::ContextLengthExceeded(_)) => {
+                state = self.compact_after_overflow(state, e).await?;
+                self.invoke_cancellable(
+                    llm,
+                    ctx,
                     &state.messages,
                     should_stream,
                     should_stream_tools,
-                    ctx.stream_tx.as_ref().unwrap().clone(),
-                    self.id(),
+                    revision,
                 )
-                .await
-            } else {
-                Ok((
-                    self.llm.invoke(&state.messages).await?,
-                    0u64,
-                    None::<Instant>,
-                ))
+                .await?
             }
+            result => result?,
         };
+        self.emit_finish_reason(ctx, &response).await;
+        if let Some(first_token_at) = first_token_at {
+            ctx.emit_timing(
+                self.id(),
+                TimingKind::FirstToken,
+                None,
+                first_token_at.duration_since(call_start),
+            )
+            .await;
+        }
 
-        let (response, streamed_chunks, first_token_at) = match run_cancellable(
-            llm_call,
-            ctx.cancellation.as_ref(),
-            ctx.run_cancellation.as_ref(),
-            ActiveOperationKind::Llm,
+        let mut continuations = 0;
+        while self.should_continue(&response, continuations) {
+            if is_cancelled() {
+                return Err(AgentError::Cancelled);
+            }
+            debug!(continuations, "think: answer truncated, continuing");
+            let messages = continuation_messages(&state.messages, &response.content);
+            let (next, chunks, _) = self
+                .invoke_cancellable(
+                    llm,
+                    ctx,
+                    &messages,
+                    should_stream,
+                    should_stream_tools,
+                    revision,
+                )
+                .await?;
+            self.emit_finish_reason(ctx, &next).await;
+            streamed_chunks += chunks;
+            merge_continuation(&mut response, next);
+            continuations += 1;
+        }
+
+        let mut repairs = 0;
+        while repairs < self.tool_call_repairs {
+            let problems = self.tool_call_problems(&response.tool_calls).await;
+            if problems.is_empty() {
+                break;
+            }
+            if is_cancelled() {
+                return Err(AgentError::Cancelled);
+            }
+            debug!(
+                repairs,
+                ?problems,
+                "think: malformed tool calls, asking for a repair"
+            );
+            let messages = repair_messages(&state.messages, &response, &problems);
+            // The repair replaces the streamed draft: start a new revision so clients drop it.
+            if streamed_chunks > 0 {
+                revision += 1;
+                if let Some(stream_tx) = ctx.stream_tx.as_ref() {
+                    let _ = stream_tx
+                        .send(StreamEvent::AnswerRevised {
+                            reason: "tool_call_repair".to_string(),
+                            revision,
+                        })
+                        .await;
+                }
+            }
+            let (next, chunks, _) = self
+                .invoke_cancellable(
+                    llm,
+                    ctx,
+                    &messages,
+                    should_stream,
+                    should_stream_tools,
+                    revision,
+                )
+                .await?;
+            self.emit_finish_reason(ctx, &next).await;
+            streamed_chunks = chunks;
+            merge_repair(&mut response, next);
+            repairs += 1;
+        }
+        serialize_tool_calls(llm.as_ref(), &mut response);
+        if let Some(prefetch) = &self.prefetch {
+            prefetch.retain(&response.tool_calls);
+        }
+        ctx.emit_timing(
+            self.id(),
+            TimingKind::Llm,
+            llm.route(),
+            call_start.elapsed(),
         )
-        .await
-        {
-            Ok(Ok(triple)) => triple,
-            Ok(Err(e)) => return Err(e),
-            Err(e) => return Err(e),
-        };
+        .await;
 
         if is_cancelled() {
             return Err(AgentError::Cancelled);
@@ -251,6 +997,7 @@ impl Node<ReActState> for ThinkNode {
             reasoning_content,
             tool_calls,
             usage,
+            finish_reason: _,
         } = response;
 
         let content = if !resp_content.is_empty() {
@@ -272,13 +1019,16 @@ impl Node<ReActState> for ThinkNode {
             &content,
             should_stream,
             streamed_chunks,
+            revision,
             &tool_calls,
             should_stream_tools,
             is_cancelled,
         )
         .await?;
 
-        let new_state = state.apply_think(content, reasoning_content, tool_calls, usage);
+        let mut new_state = state.apply_think(content, reasoning_content, tool_calls, usage);
+        new_state.answer_revision = revision;
+        Self::record_model_usage(turn.model_label.as_deref(), &mut new_state);
 
         if let Some(ref u) = new_state.usage {
             self.emit_usage_event(ctx, call_start, first_token_at, u)
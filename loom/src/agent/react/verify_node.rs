@@ -0,0 +1,271 @@
+//! Verify node: critique the draft final answer before the turn ends (reflection).
+//!
+//! Runs after think routes to END. An LLM checks the draft answer against the user's question
+//! and the tool results gathered in this turn; when it finds gaps, they are appended as
+//! feedback and the graph loops back to think, at most `max_rounds` times per user turn.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use tracing::{debug, warn};
+
+use crate::error::AgentError;
+use crate::graph::{Next, RunContext};
+use crate::llm::LlmClient;
+use crate::message::Message;
+use crate::state::ReActState;
+use crate::stream::StreamEvent;
+use crate::Node;
+
+/// Default system prompt for answer verification.
+const DEFAULT_SYSTEM_PROMPT: &str = r#"You are a strict reviewer of an assistant's draft answer.
+
+You get the user's question, the evidence the assistant gathered with tools, and the draft answer.
+Check that the draft:
+- answers every part of the question (multi-step questions need every step resolved),
+- is supported by the evidence and does not contradict it,
+- does not leave a needed fact unverified when a tool could have checked it.
+
+Respond ONLY with JSON:
+- {"complete": true} when the draft is good enough to send,
+- {"complete": false, "gaps": "..."} listing concretely what is missing or wrong."#;
+
+/// Prefix of the feedback message added when verification finds gaps.
+pub const REFLECTION_FEEDBACK_PREFIX: &str = "Review of your draft answer found gaps:";
+
+/// Max characters of one tool result included as evidence.
+const EVIDENCE_MAX_CHARS: usize = 2000;
+
+#[derive(Debug, Deserialize)]
+struct VerifyResponse {
+    complete: bool,
+    #[serde(default)]
+    gaps: Option<String>,
+}
+
+/// Node that reviews the draft final answer and sends it back to think when it has gaps.
+///
+/// Enabled via [`crate::ReactBuildConfig::enable_reflection`]. Sets `should_continue` and
+/// increments `reflection_count` when looping back, starting a new answer revision
+/// ([`StreamEvent::AnswerRevised`]); a failed or unparsable review keeps the draft.
+pub struct VerifyNode {
+    llm: Arc<dyn LlmClient>,
+    max_rounds: u32,
+    system_prompt: String,
+}
+
+impl VerifyNode {
+    /// Creates a verify node with the given LLM client and at most 2 review rounds per turn.
+    pub fn new(llm: Arc<dyn LlmClient>) -> Self {
+        Self {
+            llm,
+            max_rounds: 2,
+            system_prompt: DEFAULT_SYSTEM_PROMPT.to_string(),
+        }
+    }
+
+    /// Sets how many times per user turn the answer may be sent back to think.
+    pub fn with_max_rounds(mut self, max: u32) -> Self {
+        self.max_rounds = max;
+        self
+    }
+
+    /// Sets a custom system prompt for verification.
+    pub fn with_system_prompt(mut self, prompt: String) -> Self {
+        self.system_prompt = prompt;
+        self
+    }
+
+    /// Builds the review request: question, tool evidence since the question, and the draft.
+    /// Earlier review feedback messages are skipped when looking for the question.
+    /// Returns `None` when there is no draft answer to review.
+    pub(super) fn review_prompt(state: &ReActState) -> Option<String> {
+        let question_idx = state.messages.iter().rposition(|m| {
+            matches!(m, Message::User(_)) && !m.content().starts_with(REFLECTION_FEEDBACK_PREFIX)
+        })?;
+        let draft = match state.messages.last() {
+            Some(Message::Assistant(p)) if !p.content.trim().is_empty() => p.content.clone(),
+            _ => return None,
+        };
+        let question = state.messages[question_idx].content();
+        let evidence: Vec<String> = state.messages[question_idx + 1..]
+            .iter()
+            .filter_map(|m| match m {
+                Message::Tool { content, .. } => {
+                    let text = content.to_display_string();
+                    Some(text.chars().take(EVIDENCE_MAX_CHARS).collect())
+                }
+                _ => None,
+            })
+            .collect();
+        let evidence = if evidence.is_empty() {
+            "(no tool results)".to_string()
+        } else {
+            evidence.join("\n---\n")
+        };
+        Some(format!(
+            "Question:\n{}\n\nEvidence:\n{}\n\nDraft answer:\n{}",
+            question, evidence, draft
+        ))
+    }
+
+    async fn review(&self, prompt: String) -> Result<VerifyResponse, AgentError> {
+        let messages = vec![
+            Message::system(self.system_prompt.clone()),
+            Message::user(prompt),
+        ];
+        let response = self.llm.invoke(&messages).await?;
+        serde_json::from_str(strip_json_fence(&response.content)).map_err(|e| {
+            AgentError::ExecutionFailed(format!("Failed to parse verify response: {}", e))
+        })
+    }
+}
+
+/// `content` without surrounding whitespace and a Markdown code fence (`` ```json ``), if any.
+pub(super) fn strip_json_fence(content: &str) -> &str {
+    let content = content.trim();
+    content
+        .strip_prefix("```json")
+        .or_else(|| content.strip_prefix("```"))
+        .and_then(|s| s.strip_suffix("```"))
+        .unwrap_or(content)
+        .trim()
+}
+
+#[async_trait]
+impl Node<ReActState> for VerifyNode {
+    fn id(&self) -> &str {
+        "verify"
+    }
+
+    async fn run(&self, state: ReActState) -> Result<(ReActState, Next), AgentError> {
+        let ctx = RunContext::new(crate::memory::RunnableConfig::default());
+        self.run_with_context(state, &ctx).await
+    }
+
+    async fn run_with_context(
+        &self,
+        mut state: ReActState,
+        ctx: &RunContext<ReActState>,
+    ) -> Result<(ReActState, Next), AgentError> {
+        state.should_continue = false;
+        if state.reflection_count >= self.max_rounds {
+            debug!(
+                reflection_count = state.reflection_count,
+                "Max reflection rounds reached, ending"
+            );
+            return Ok((state, Next::End));
+        }
+        let Some(prompt) = Self::review_prompt(&state) else {
+            return Ok((state, Next::End));
+        };
+        match self.review(prompt).await {
+            Ok(VerifyResponse { complete: true, .. }) => Ok((state, Next::End)),
+            Ok(VerifyResponse {
+                complete: false,
+                gaps,
+            }) => {
+                let gaps = gaps
+                    .filter(|g| !g.trim().is_empty())
+                    .unwrap_or_else(|| "the answer is incomplete".to_string());
+                debug!(round = state.reflection_count + 1, gaps = %gaps, "Draft answer has gaps");
+                state.messages.push(Message::user(format!(
+                    "{}\n{}\n\nAddress these (use tools if needed), then give the complete final answer.",
+                    REFLECTION_FEEDBACK_PREFIX, gaps
+                )));
+                state.reflection_count += 1;
+                state.answer_revision += 1;
+                state.should_continue = true;
+                if let Some(stream_tx) = ctx.stream_tx.as_ref() {
+                    let _ = stream_tx
+                        .send(StreamEvent::AnswerRevised {
+                            reason: "reflection".to_string(),
+                            revision: state.answer_revision,
+                        })
+                        .await;
+                }
+                Ok((state, Next::Continue))
+            }
+            Err(e) => {
+                warn!(error = %e, "Answer verification failed, keeping draft");
+                Ok((state, Next::End))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::RunContext;
+    use crate::llm::MockLlm;
+    use crate::memory::RunnableConfig;
+    use crate::tool_source::ToolCallContent;
+
+    fn draft_state() -> ReActState {
+        ReActState {
+            messages: vec![
+                Message::user("Who directed the film that won Best Picture in 1998?"),
+                Message::Tool {
+                    tool_call_id: "call_1".into(),
+                    content: ToolCallContent::text("Titanic won Best Picture in 1998."),
+                },
+                Message::assistant("Titanic won."),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn verify_loops_back_with_feedback_when_gaps_found() {
+        let llm = MockLlm::with_no_tool_calls(
+            r#"{"complete": false, "gaps": "The director is not named."}"#,
+        );
+        let node = VerifyNode::new(Arc::new(llm));
+        let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+        let mut ctx = RunContext::<ReActState>::new(RunnableConfig::default());
+        ctx.stream_tx = Some(tx);
+        let (state, next) = node.run_with_context(draft_state(), &ctx).await.unwrap();
+
+        assert!(matches!(next, Next::Continue));
+        assert!(state.should_continue);
+        assert_eq!(state.reflection_count, 1);
+        assert_eq!(state.answer_revision, 1);
+        let feedback = state.messages.last().unwrap().content();
+        assert!(feedback.starts_with(REFLECTION_FEEDBACK_PREFIX));
+        assert!(feedback.contains("director"));
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(StreamEvent::AnswerRevised { ref reason, revision: 1 }) if reason == "reflection"
+        ));
+    }
+
+    #[tokio::test]
+    async fn verify_ends_when_answer_complete() {
+        let llm = MockLlm::with_no_tool_calls("```json\n{\"complete\": true}\n```");
+        let node = VerifyNode::new(Arc::new(llm));
+        let ctx = RunContext::<ReActState>::new(RunnableConfig::default());
+        let (state, next) = node.run_with_context(draft_state(), &ctx).await.unwrap();
+
+        assert!(matches!(next, Next::End));
+        assert!(!state.should_continue);
+        assert_eq!(state.messages.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn verify_ends_after_max_rounds() {
+        let llm = MockLlm::with_no_tool_calls(r#"{"complete": false, "gaps": "still missing"}"#);
+        let node = VerifyNode::new(Arc::new(llm)).with_max_rounds(1);
+        let ctx = RunContext::<ReActState>::new(RunnableConfig::default());
+        let state = ReActState {
+            reflection_count: 1,
+            ..draft_state()
+        };
+        let (state, next) = node.run_with_context(state, &ctx).await.unwrap();
+
+        assert!(matches!(next, Next::End));
+        assert!(!state.should_continue);
+        assert_eq!(state.reflection_count, 1);
+    }
+}
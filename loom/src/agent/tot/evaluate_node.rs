@@ -3,6 +3,12 @@
 //! Reads `state.tot.candidates`, assigns scores (rule-based: thought length,
 //! tool_calls validity), sets `chosen_index` and writes the chosen candidate's
 //! thought and tool_calls into `state.core`. Emits `StreamEvent::TotEvaluate`.
+//!
+//! When an LLM is attached via [`ThinkEvaluateNode::with_llm`] (typically a cheaper
+//! model than the one used for expansion), its 0–10 ratings are added to the rule-based
+//! scores; if the call fails or the reply cannot be parsed, rule-based scores are used alone.
+
+use std::sync::Arc;
 
 use async_trait::async_trait;
 
@@ -10,7 +16,7 @@ use crate::error::AgentError;
 use crate::graph::{Next, RunContext};
 use crate::message::Message;
 use crate::stream::StreamEvent;
-use crate::Node;
+use crate::{LlmClient, LlmUsage, Node};
 
 use super::state::{TotCandidate, TotState};
 
@@ -19,7 +25,12 @@ use super::state::{TotCandidate, TotState};
 /// Rule-based scoring: thought length, tool_calls, B1 (search-keyword penalty,
 /// topic-overlap bonus). Sets `state.tot.chosen_index` and writes
 /// `state.core.messages` and `state.core.tool_calls`. Interacts with `TotState`, `StreamEvent::TotEvaluate`.
-pub struct ThinkEvaluateNode;
+pub struct ThinkEvaluateNode {
+    /// Optional LLM judge whose ratings are blended into the rule-based scores.
+    llm: Option<Arc<dyn LlmClient>>,
+    /// Model id used to label the judge's usage in `core.usage_by_model`.
+    model_label: Option<String>,
+}
 
 /// Keywords suggesting search/research; candidates without tool_calls get a penalty.
 const SEARCH_RESEARCH_KEYWORDS: &[&str] = &[
@@ -41,7 +52,78 @@ const SEARCH_RESEARCH_KEYWORDS: &[&str] = &[
 impl ThinkEvaluateNode {
     /// Creates a ThinkEvaluate node.
     pub fn new() -> Self {
-        Self
+        Self {
+            llm: None,
+            model_label: None,
+        }
+    }
+
+    /// Attaches an LLM judge; `model` labels its usage.
+    pub fn with_llm(mut self, llm: Arc<dyn LlmClient>, model: Option<String>) -> Self {
+        self.llm = Some(llm);
+        self.model_label = model;
+        self
+    }
+
+    /// Asks the LLM judge to rate candidates 0–10. Returns `None` when no judge is set,
+    /// the call fails, or the reply is not a JSON array with one number per candidate.
+    async fn llm_ratings(
+        &self,
+        candidates: &[TotCandidate],
+        last_user: Option<&str>,
+    ) -> Option<(Vec<f32>, Option<LlmUsage>)> {
+        let llm = self.llm.as_ref()?;
+        let mut prompt = String::from(
+            "Rate how well each candidate next step advances the user's task, from 0 (useless) \
+             to 10 (ideal). Reply with only a JSON array of numbers, one per candidate, in order.\n",
+        );
+        if let Some(user) = last_user {
+            prompt.push_str(&format!("\nUser task:\n{}\n", user));
+        }
+        for (i, c) in candidates.iter().enumerate() {
+            let tools: Vec<&str> = c.tool_calls.iter().map(|tc| tc.name.as_str()).collect();
+            prompt.push_str(&format!(
+                "\nCandidate {}:\n{}\nTools: {}\n",
+                i,
+                c.thought,
+                if tools.is_empty() {
+                    "none".to_string()
+                } else {
+                    tools.join(", ")
+                }
+            ));
+        }
+        let response = match llm.invoke(&[Message::user(prompt)]).await {
+            Ok(r) => r,
+            Err(e) => {
+                tracing::warn!(error = %e, "think_evaluate: LLM judge failed, using rule-based scores");
+                return None;
+            }
+        };
+        let ratings = Self::parse_ratings(&response.content, candidates.len());
+        if ratings.is_none() {
+            tracing::warn!("think_evaluate: unparseable LLM judge reply, using rule-based scores");
+        }
+        ratings.map(|r| (r, response.usage))
+    }
+
+    /// Extracts the first JSON array of numbers from `content`; requires exactly `expected` items.
+    fn parse_ratings(content: &str, expected: usize) -> Option<Vec<f32>> {
+        let start = content.find('[')?;
+        let end = content[start..].find(']')? + start;
+        let ratings: Vec<f32> = serde_json::from_str(&content[start..=end]).ok()?;
+        (ratings.len() == expected).then(|| {
+            ratings
+                .into_iter()
+                .map(|r| {
+                    if r.is_finite() {
+                        r.clamp(0.0, 10.0)
+                    } else {
+                        0.0
+                    }
+                })
+                .collect()
+        })
     }
 
     fn last_user_message(messages: &[Message]) -> Option<String> {
@@ -137,14 +219,32 @@ impl Node<TotState> for ThinkEvaluateNode {
             ));
         }
         let last_user = Self::last_user_message(&state.core.messages);
-        let (chosen_index, scores) = Self::choose_best(&tot.candidates, last_user.as_deref());
+        let (mut chosen_index, mut scores) =
+            Self::choose_best(&tot.candidates, last_user.as_deref());
+        let mut core = state.core;
+        if let Some((ratings, usage)) = self
+            .llm_ratings(&tot.candidates, last_user.as_deref())
+            .await
+        {
+            for (s, r) in scores.iter_mut().zip(ratings) {
+                *s += r / 10.0;
+            }
+            chosen_index = scores
+                .iter()
+                .enumerate()
+                .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+                .map(|(i, _)| i)
+                .unwrap_or(0);
+            if let Some(ref u) = usage {
+                core.record_usage(self.model_label.as_deref(), u);
+            }
+        }
         for (c, s) in tot.candidates.iter_mut().zip(scores.iter()) {
             c.score = Some(*s);
         }
         tot.chosen_index = Some(chosen_index);
         tot.tried_indices = vec![chosen_index];
 
-        let mut core = state.core;
         let chosen = tot.candidates.get(chosen_index).unwrap();
         core.messages
             .push(Message::assistant(chosen.thought.clone()));
@@ -171,6 +271,7 @@ impl Node<TotState> for ThinkEvaluateNode {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::llm::MockLlm;
     use crate::memory::RunnableConfig;
     use crate::state::{ReActState, ToolCall};
     use tokio::sync::mpsc;
@@ -253,6 +354,43 @@ mod tests {
         assert_eq!(out.core.tool_calls.len(), 1);
     }
 
+    #[test]
+    fn parse_ratings_requires_one_number_per_candidate() {
+        assert_eq!(
+            ThinkEvaluateNode::parse_ratings("Scores: [3, 12.5]", 2),
+            Some(vec![3.0, 10.0])
+        );
+        assert_eq!(ThinkEvaluateNode::parse_ratings("[3]", 2), None);
+        assert_eq!(ThinkEvaluateNode::parse_ratings("no scores", 2), None);
+    }
+
+    #[tokio::test]
+    async fn run_with_llm_judge_overrides_rule_choice_and_records_usage() {
+        let judge = MockLlm::with_no_tool_calls("[10, 0]").with_usage(LlmUsage {
+            prompt_tokens: 7,
+            completion_tokens: 3,
+            total_tokens: 10,
+            prompt_tokens_details: None,
+            completion_tokens_details: None,
+        });
+        let node =
+            ThinkEvaluateNode::new().with_llm(Arc::new(judge), Some("openai/gpt-4o-mini".into()));
+        let state = base_state(vec![
+            candidate("answer directly from known facts", false),
+            candidate("use search and summarize", true),
+        ]);
+        let (out, _) = node.run(state).await.unwrap();
+        assert_eq!(out.tot.chosen_index, Some(0));
+        assert_eq!(
+            out.core.total_usage.as_ref().map(|u| u.total_tokens),
+            Some(10)
+        );
+        assert_eq!(
+            out.core.usage_by_model["openai/gpt-4o-mini"].total_tokens,
+            10
+        );
+    }
+
     #[tokio::test]
     async fn run_with_context_emits_tot_evaluate_event() {
         let node = ThinkEvaluateNode::new();
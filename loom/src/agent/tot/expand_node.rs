@@ -2,11 +2,15 @@
 //!
 //! Reads `TotState::core.messages`, appends ToT expand prompt, invokes LLM, parses
 //! multiple candidates and writes `state.tot.candidates`. Emits `StreamEvent::TotExpand`.
+//! With [`ThinkExpandNode::with_parallel_expansion`], each candidate comes from its own call
+//! and the calls run concurrently.
 
 use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
 
 use crate::error::AgentError;
 use crate::graph::{Next, RunContext};
+use crate::llm::{LlmResponse, LlmUsage};
 use crate::message::Message;
 use crate::state::ToolCall;
 use crate::stream::StreamEvent;
@@ -28,6 +32,10 @@ pub struct ThinkExpandNode {
     candidates_per_step: usize,
     /// When true, append research-quality addon (multiple tool calls, step-by-step, cite sources).
     research_quality_addon: bool,
+    /// Model id used to label usage in `core.usage_by_model`.
+    model_label: Option<String>,
+    /// Max concurrent candidate calls; at most 1 asks for all candidates in a single call.
+    expand_concurrency: usize,
 }
 
 impl ThinkExpandNode {
@@ -37,9 +45,17 @@ impl ThinkExpandNode {
             llm,
             candidates_per_step: 3,
             research_quality_addon: false,
+            model_label: None,
+            expand_concurrency: 1,
         }
     }
 
+    /// Sets the model id under which this node's token usage is recorded.
+    pub fn with_model_label(mut self, model: Option<String>) -> Self {
+        self.model_label = model;
+        self
+    }
+
     /// Sets the number of candidates to request per step (2 or 3).
     pub fn with_candidates_per_step(mut self, n: usize) -> Self {
         self.candidates_per_step = n.clamp(2, 3);
@@ -52,18 +68,47 @@ impl ThinkExpandNode {
         self
     }
 
+    /// Generates candidates with one LLM call each, running up to `max_concurrent` calls at a
+    /// time, instead of asking for all candidates in one call. Each call is told which branch
+    /// it explores so the candidates differ. Values of 0 or 1 keep the single call.
+    pub fn with_parallel_expansion(mut self, max_concurrent: usize) -> Self {
+        self.expand_concurrency = max_concurrent;
+        self
+    }
+
     /// Builds messages for the expand call: existing messages plus expand instruction.
     fn build_messages(&self, state: &TotState) -> Vec<Message> {
-        let mut messages = state.core.messages.clone();
         let n = self.candidates_per_step;
-        let mut addon =
-            format!(
-            "{}\n\nGenerate exactly {} candidates for the next step. You MUST output {} lines: {}.",
-            TOT_EXPAND_SYSTEM_ADDON.trim(),
+        let addon = format!(
+            "Generate exactly {} candidates for the next step. You MUST output {} lines: {}.",
             n,
             n,
-            (1..=n).map(|i| format!("CANDIDATE {}", i)).collect::<Vec<_>>().join(", ")
+            (1..=n)
+                .map(|i| format!("CANDIDATE {}", i))
+                .collect::<Vec<_>>()
+                .join(", ")
         );
+        self.with_expand_addon(state, &addon)
+    }
+
+    /// Builds messages for one call of a parallel expansion: a single candidate for `branch`
+    /// (0-based) of `candidates_per_step`.
+    fn build_branch_messages(&self, state: &TotState, branch: usize) -> Vec<Message> {
+        let addon = format!(
+            "Generate exactly 1 candidate for the next step. You MUST output 1 line: CANDIDATE 1. \
+             Other candidates are generated separately; yours is alternative {} of {}. \
+             Alternative 1 is the most direct approach, each later one a clearly different \
+             approach than the ones before it.",
+            branch + 1,
+            self.candidates_per_step
+        );
+        self.with_expand_addon(state, &addon)
+    }
+
+    /// Existing messages with the expand prompt and `instruction` appended to the system message.
+    fn with_expand_addon(&self, state: &TotState, instruction: &str) -> Vec<Message> {
+        let mut messages = state.core.messages.clone();
+        let mut addon = format!("{}\n\n{}", TOT_EXPAND_SYSTEM_ADDON.trim(), instruction);
         if self.research_quality_addon {
             addon.push_str("\n\n");
             addon.push_str(TOT_RESEARCH_QUALITY_ADDON.trim());
@@ -199,6 +244,76 @@ impl ThinkExpandNode {
         }
     }
 
+    /// Candidates from one expand response, falling back to the response's native tool calls
+    /// and content when the text has no candidate format.
+    fn candidates_from_response(&self, response: &LlmResponse) -> Vec<TotCandidate> {
+        let mut candidates = self.parse_candidates(&response.content);
+        // Fallback: when we got a single candidate with no tool_calls, use the API's
+        // native tool_calls and content so the user still gets one valid path (e.g. search).
+        if candidates.len() == 1
+            && candidates[0].tool_calls.is_empty()
+            && !response.tool_calls.is_empty()
+        {
+            candidates[0].tool_calls = response.tool_calls.clone();
+        }
+        if candidates.len() == 1 && candidates[0].thought.is_empty() && !response.content.is_empty()
+        {
+            candidates[0].thought = response.content.trim().to_string();
+        }
+        candidates
+    }
+
+    /// Runs one call per candidate, `expand_concurrency` at a time, and keeps the first
+    /// candidate of each reply (in branch order, without duplicates). Failed calls are skipped
+    /// unless all fail.
+    async fn expand_parallel(
+        &self,
+        state: &TotState,
+    ) -> Result<(Vec<TotCandidate>, Option<LlmUsage>), AgentError> {
+        let branches: Vec<Vec<Message>> = (0..self.candidates_per_step)
+            .map(|branch| self.build_branch_messages(state, branch))
+            .collect();
+        let results: Vec<Result<LlmResponse, AgentError>> = stream::iter(branches)
+            .map(|messages| async move { self.llm.invoke(&messages).await })
+            .buffered(self.expand_concurrency)
+            .collect()
+            .await;
+
+        let mut candidates: Vec<TotCandidate> = Vec::new();
+        let mut usage: Option<LlmUsage> = None;
+        let mut first_error = None;
+        for result in results {
+            let response = match result {
+                Ok(r) => r,
+                Err(e) => {
+                    tracing::warn!(error = %e, "think_expand: candidate call failed");
+                    first_error.get_or_insert(e);
+                    continue;
+                }
+            };
+            if let Some(u) = &response.usage {
+                usage = Some(match usage {
+                    Some(acc) => acc.accumulate(u),
+                    None => u.clone(),
+                });
+            }
+            let Some(candidate) = self.candidates_from_response(&response).into_iter().next()
+            else {
+                continue;
+            };
+            let duplicate = candidates
+                .iter()
+                .any(|c| c.thought == candidate.thought && c.tool_calls == candidate.tool_calls);
+            if !duplicate {
+                candidates.push(candidate);
+            }
+        }
+        match first_error {
+            Some(e) if candidates.is_empty() => Err(e),
+            _ => Ok((candidates, usage)),
+        }
+    }
+
     fn parse_tool_calls_json(s: &str) -> Vec<ToolCall> {
         let arr: Vec<serde_json::Value> = match serde_json::from_str(s) {
             Ok(a) => a,
@@ -225,20 +340,16 @@ impl Node<TotState> for ThinkExpandNode {
     }
 
     async fn run(&self, state: TotState) -> Result<(TotState, Next), AgentError> {
-        let messages = self.build_messages(&state);
-        let response = self.llm.invoke(&messages).await?;
-        let mut candidates = self.parse_candidates(&response.content);
-        // Fallback: when we got a single candidate with no tool_calls, use the API's
-        // native tool_calls and content so the user still gets one valid path (e.g. search).
-        if candidates.len() == 1
-            && candidates[0].tool_calls.is_empty()
-            && !response.tool_calls.is_empty()
-        {
-            candidates[0].tool_calls = response.tool_calls;
-        }
-        if candidates.len() == 1 && candidates[0].thought.is_empty() && !response.content.is_empty()
-        {
-            candidates[0].thought = response.content.trim().to_string();
+        let (candidates, usage) = if self.expand_concurrency > 1 {
+            self.expand_parallel(&state).await?
+        } else {
+            let messages = self.build_messages(&state);
+            let response = self.llm.invoke(&messages).await?;
+            (self.candidates_from_response(&response), response.usage)
+        };
+        let mut core = state.core;
+        if let Some(ref usage) = usage {
+            core.record_usage(self.model_label.as_deref(), usage);
         }
         let mut tot = state.tot;
         tot.candidates = candidates;
@@ -246,10 +357,7 @@ impl Node<TotState> for ThinkExpandNode {
         tot.tried_indices.clear();
         tot.suggest_backtrack = false;
         tot.path_failed_reason = None;
-        let out = TotState {
-            core: state.core,
-            tot,
-        };
+        let out = TotState { core, tot };
         Ok((out, Next::Continue))
     }
 
@@ -388,6 +496,48 @@ CANDIDATE 2: THOUGHT: summarize findings | TOOL_CALLS: []"#;
         assert_eq!(out.tot.candidates[0].tool_calls[0].name, "get_time");
     }
 
+    #[tokio::test]
+    async fn parallel_expansion_makes_one_call_per_candidate() {
+        let script = crate::llm::MockScript::from_yaml(
+            r#"
+responses:
+  - content: "CANDIDATE 1: THOUGHT: alpha | TOOL_CALLS: []"
+  - content: "CANDIDATE 1: THOUGHT: beta | TOOL_CALLS: []"
+"#,
+        )
+        .unwrap();
+        let node = ThinkExpandNode::new(Box::new(MockLlm::scripted(script)))
+            .with_candidates_per_step(2)
+            .with_parallel_expansion(2);
+        let (out, _) = node.run(make_state()).await.unwrap();
+        let mut thoughts: Vec<String> = out
+            .tot
+            .candidates
+            .iter()
+            .map(|c| c.thought.clone())
+            .collect();
+        thoughts.sort();
+        assert_eq!(thoughts, vec!["alpha".to_string(), "beta".to_string()]);
+
+        let messages = node.build_branch_messages(&make_state(), 1);
+        let sys = match &messages[0] {
+            Message::System(s) => s,
+            _ => unreachable!(),
+        };
+        assert!(sys.contains("alternative 2 of 2"));
+    }
+
+    #[tokio::test]
+    async fn parallel_expansion_drops_duplicate_candidates() {
+        let node = ThinkExpandNode::new(Box::new(MockLlm::with_no_tool_calls(
+            "CANDIDATE 1: THOUGHT: same idea | TOOL_CALLS: []",
+        )))
+        .with_parallel_expansion(3);
+        let (out, _) = node.run(make_state()).await.unwrap();
+        assert_eq!(out.tot.candidates.len(), 1);
+        assert_eq!(out.tot.candidates[0].thought, "same idea");
+    }
+
     #[tokio::test]
     async fn run_with_context_emits_tot_expand_event() {
         let node = ThinkExpandNode::new(Box::new(MockLlm::with_no_tool_calls(
@@ -11,8 +11,10 @@ use crate::agent::react::{build_react_initial_state, REACT_SYSTEM_PROMPT};
 use crate::error::AgentError;
 use crate::graph::{CompilationError, CompiledStateGraph, LoggingNodeMiddleware};
 use crate::helve::ApprovalPolicy;
+use crate::llm::NodeLlmOverrides;
 use crate::memory::{CheckpointError, Checkpointer, RunnableConfig, Store};
 use crate::message::Message;
+use crate::run_hooks::RunHooks;
 use crate::runner_common::{self, load_from_checkpoint_or_build};
 use crate::stream::StreamEvent;
 use crate::tool_source::ToolSource;
@@ -99,6 +101,7 @@ pub struct TotRunner {
     runnable_config: Option<RunnableConfig>,
     system_prompt: Option<String>,
     cancellation: Option<CancellationToken>,
+    hooks: RunHooks<TotState>,
 }
 
 /// Wraps Arc<dyn LlmClient> to share one LLM between ThinkExpandNode and potential future nodes.
@@ -119,6 +122,9 @@ impl LlmClient for SharedLlm {
     ) -> Result<crate::llm::LlmResponse, AgentError> {
         self.0.invoke_stream(messages, tx).await
     }
+    fn tool_support(&self) -> crate::model_spec::ToolSupport {
+        self.0.tool_support()
+    }
 }
 
 impl TotRunner {
@@ -127,7 +133,16 @@ impl TotRunner {
         self
     }
 
+    /// Runs `hooks` when a run starts, finishes or fails (see [`RunHooks`]).
+    pub fn with_hooks(mut self, hooks: RunHooks<TotState>) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
     /// Creates a ToT runner with the given LLM, tool source, and optional persistence.
+    ///
+    /// `node_llms` may override the LLM for `think_expand` and attach an LLM judge to
+    /// `think_evaluate` (which is rule-based only when no override is given).
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         llm: Arc<dyn LlmClient>,
@@ -142,11 +157,21 @@ impl TotRunner {
         max_depth: u32,
         candidates_per_step: u32,
         research_quality_addon: bool,
+        expand_concurrency: u32,
+        node_llms: NodeLlmOverrides,
     ) -> Result<Self, CompilationError> {
-        let expand = ThinkExpandNode::new(Box::new(SharedLlm(Arc::clone(&llm))))
-            .with_candidates_per_step(candidates_per_step as usize)
-            .with_research_quality_addon(research_quality_addon);
-        let evaluate = ThinkEvaluateNode::new();
+        let expand =
+            ThinkExpandNode::new(Box::new(SharedLlm(node_llms.llm_for("think_expand", &llm))))
+                .with_candidates_per_step(candidates_per_step as usize)
+                .with_research_quality_addon(research_quality_addon)
+                .with_parallel_expansion(expand_concurrency as usize)
+                .with_model_label(node_llms.model_for("think_expand"));
+        let evaluate = match node_llms.get("think_evaluate") {
+            Some(judge) => {
+                ThinkEvaluateNode::new().with_llm(Arc::clone(&judge.llm), Some(judge.model.clone()))
+            }
+            None => ThinkEvaluateNode::new(),
+        };
         let act = TotActNode::new(tool_source).with_approval_policy(approval_policy);
         let observe = TotObserveNode::new();
         let backtrack = BacktrackNode::new();
@@ -212,9 +237,22 @@ impl TotRunner {
             runnable_config,
             system_prompt,
             cancellation,
+            hooks: RunHooks::default(),
         })
     }
 
+    /// Returns the latest checkpointed state for `thread_id`, or `None` when the runner has
+    /// no checkpointer or the thread has not been checkpointed yet. Does not run the graph,
+    /// so it is safe to call while another task is streaming on the same runner.
+    pub async fn state_snapshot(&self, thread_id: &str) -> Result<Option<TotState>, TotRunError> {
+        Ok(runner_common::load_state_snapshot(
+            self.checkpointer.as_deref(),
+            self.runnable_config.as_ref(),
+            thread_id,
+        )
+        .await?)
+    }
+
     /// Invokes the graph with the given user message.
     pub async fn invoke(&self, user_message: &str) -> Result<TotState, TotRunError> {
         self.invoke_with_config(user_message, None).await
@@ -234,7 +272,9 @@ impl TotRunner {
             self.system_prompt.as_deref(),
         )
         .await?;
-        let final_state = self.compiled.invoke(state, run_config).await?;
+        let final_state =
+            runner_common::invoke_with_hooks(&self.compiled, state, run_config, &self.hooks)
+                .await?;
         Ok(final_state)
     }
 
@@ -275,6 +315,7 @@ impl TotRunner {
             on_event,
             self.cancellation.clone(),
             None,
+            &self.hooks,
         )
         .await
         .map_err(|e| match e {
@@ -363,6 +404,8 @@ mod tests {
             3,
             2,
             false,
+            2,
+            NodeLlmOverrides::default(),
         )
         .unwrap();
 
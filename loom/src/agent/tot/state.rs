@@ -5,7 +5,8 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::state::{ReActState, ToolCall};
+use crate::memory::{StateMigrations, VersionedState};
+use crate::state::{migrate_core_react_state_v1, ReActState, ToolCall};
 
 /// One candidate produced by ThinkExpand: a thought and optional tool calls.
 ///
@@ -69,6 +70,14 @@ pub struct TotState {
     pub tot: TotExtension,
 }
 
+impl VersionedState for TotState {
+    const STATE_VERSION: u32 = 2;
+
+    fn migrations() -> StateMigrations {
+        StateMigrations::new().register(1, migrate_core_react_state_v1)
+    }
+}
+
 impl TotState {
     /// Returns the last assistant reply from `core.messages`, if any.
     pub fn last_assistant_reply(&self) -> Option<String> {
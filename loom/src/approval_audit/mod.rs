@@ -0,0 +1,143 @@
+//! Approval audit trail: every approval request and decision, persisted.
+//!
+//! [`ActNode`](crate::agent::react::ActNode) records one [`ApprovalAuditRecord`] when a tool
+//! call waits for approval ([`ApprovalAuditDecision::Requested`]) and one when a decision is
+//! applied to it (approved or rejected, by the user or from a remembered decision). Records
+//! keep a digest of the arguments, not the arguments themselves.
+//!
+//! Enabled by setting [`ENV_APPROVAL_AUDIT_DB`] (a SQLite path); `loom serve` lists records
+//! with the `approvals_list` request.
+
+mod sqlite_store;
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+pub use sqlite_store::SqliteApprovalAuditStore;
+
+/// Env var: SQLite file for the approval audit trail (unset: no audit).
+pub const ENV_APPROVAL_AUDIT_DB: &str = "LOOM_APPROVAL_AUDIT_DB";
+
+/// Error from [`ApprovalAuditStore`] operations.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum ApprovalAuditError {
+    #[error("approval audit store error: {0}")]
+    Other(String),
+}
+
+/// What happened to a tool call that needs approval.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalAuditDecision {
+    /// The run stopped to ask for approval.
+    Requested,
+    Approved,
+    Rejected,
+}
+
+impl ApprovalAuditDecision {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Requested => "requested",
+            Self::Approved => "approved",
+            Self::Rejected => "rejected",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "requested" => Some(Self::Requested),
+            "approved" => Some(Self::Approved),
+            "rejected" => Some(Self::Rejected),
+            _ => None,
+        }
+    }
+}
+
+/// One audited approval event.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ApprovalAuditRecord {
+    pub tool_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub call_id: Option<String>,
+    /// [`args_digest`] of the call arguments.
+    pub args_digest: String,
+    pub decision: ApprovalAuditDecision,
+    /// True when the decision came from one remembered for the thread or session rather than
+    /// from an answer to this call.
+    #[serde(default)]
+    pub remembered: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    /// Milliseconds since Unix epoch.
+    pub created_at_ms: i64,
+}
+
+/// SHA-256 of the compact JSON arguments, as `sha256:<hex>`.
+pub fn args_digest(args: &Value) -> String {
+    let compact = serde_json::to_string(args).unwrap_or_default();
+    let hash = Sha256::digest(compact.as_bytes());
+    let hex: String = hash.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256:{}", hex)
+}
+
+/// Which records [`ApprovalAuditStore::list`] returns. `since` / `until` are milliseconds since
+/// Unix epoch (inclusive / exclusive).
+#[derive(Clone, Debug, Default)]
+pub struct ApprovalAuditFilter {
+    pub thread_id: Option<String>,
+    pub user_id: Option<String>,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    /// Max records; the store applies its own default and cap.
+    pub limit: Option<u32>,
+}
+
+/// Persistent store for approval audit records.
+///
+/// - `record`: append one record; records are never updated or deleted.
+/// - `list`: records matching the filter, newest first.
+#[async_trait]
+pub trait ApprovalAuditStore: Send + Sync {
+    async fn record(&self, record: &ApprovalAuditRecord) -> Result<(), ApprovalAuditError>;
+
+    async fn list(
+        &self,
+        filter: &ApprovalAuditFilter,
+    ) -> Result<Vec<ApprovalAuditRecord>, ApprovalAuditError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn args_digest_is_stable_and_hides_arguments() {
+        let a = args_digest(&json!({"command": "rm -rf /srv/data", "cwd": "/"}));
+        let b = args_digest(&json!({"cwd": "/", "command": "rm -rf /srv/data"}));
+        assert_eq!(a, b);
+        assert!(a.starts_with("sha256:"));
+        assert_eq!(a.len(), "sha256:".len() + 64);
+        assert!(!a.contains("rm -rf"));
+        assert_ne!(a, args_digest(&json!({"command": "ls"})));
+    }
+
+    #[test]
+    fn decision_round_trips_through_text() {
+        for d in [
+            ApprovalAuditDecision::Requested,
+            ApprovalAuditDecision::Approved,
+            ApprovalAuditDecision::Rejected,
+        ] {
+            assert_eq!(ApprovalAuditDecision::parse(d.as_str()), Some(d));
+        }
+        assert_eq!(ApprovalAuditDecision::parse("maybe"), None);
+    }
+}
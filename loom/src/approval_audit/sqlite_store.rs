@@ -0,0 +1,216 @@
+//! SQLite-backed approval audit store.
+
+use std::path::Path;
+
+use async_trait::async_trait;
+use rusqlite::params_from_iter;
+use rusqlite::types::Value as SqlValue;
+
+use super::{
+    ApprovalAuditDecision, ApprovalAuditError, ApprovalAuditFilter, ApprovalAuditRecord,
+    ApprovalAuditStore,
+};
+
+const DEFAULT_LIST_LIMIT: u32 = 100;
+const MAX_LIST_LIMIT: u32 = 1000;
+
+fn sql_err(e: impl std::fmt::Display) -> ApprovalAuditError {
+    ApprovalAuditError::Other(e.to_string())
+}
+
+/// SQLite-backed store: one append-only table `approval_audit (id, tool_name, call_id,
+/// args_digest, decision, remembered, thread_id, user_id, created_at)`.
+pub struct SqliteApprovalAuditStore {
+    db_path: std::path::PathBuf,
+}
+
+impl SqliteApprovalAuditStore {
+    /// Creates the store and ensures the table exists. `path` is the SQLite file path.
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, ApprovalAuditError> {
+        let db_path = path.as_ref().to_path_buf();
+        let conn = crate::memory::sqlite_util::open_sqlite_with_wal(&db_path)
+            .map_err(ApprovalAuditError::Other)?;
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS approval_audit (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                tool_name TEXT NOT NULL,
+                call_id TEXT,
+                args_digest TEXT NOT NULL,
+                decision TEXT NOT NULL,
+                remembered INTEGER NOT NULL DEFAULT 0,
+                thread_id TEXT,
+                user_id TEXT,
+                created_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_approval_audit_thread_id ON approval_audit(thread_id);
+            CREATE INDEX IF NOT EXISTS idx_approval_audit_created_at ON approval_audit(created_at);
+            "#,
+        )
+        .map_err(sql_err)?;
+        Ok(Self { db_path })
+    }
+}
+
+#[async_trait]
+impl ApprovalAuditStore for SqliteApprovalAuditStore {
+    async fn record(&self, record: &ApprovalAuditRecord) -> Result<(), ApprovalAuditError> {
+        let record = record.clone();
+        let db_path = self.db_path.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = crate::memory::sqlite_util::open_sqlite_with_wal(&db_path)
+                .map_err(ApprovalAuditError::Other)?;
+            conn.execute(
+                "INSERT INTO approval_audit (tool_name, call_id, args_digest, decision, remembered, thread_id, user_id, created_at) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                rusqlite::params![
+                    record.tool_name,
+                    record.call_id,
+                    record.args_digest,
+                    record.decision.as_str(),
+                    record.remembered,
+                    record.thread_id,
+                    record.user_id,
+                    record.created_at_ms,
+                ],
+            )
+            .map_err(sql_err)?;
+            Ok(())
+        })
+        .await
+        .map_err(sql_err)?
+    }
+
+    async fn list(
+        &self,
+        filter: &ApprovalAuditFilter,
+    ) -> Result<Vec<ApprovalAuditRecord>, ApprovalAuditError> {
+        let filter = filter.clone();
+        let db_path = self.db_path.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = crate::memory::sqlite_util::open_sqlite_with_wal(&db_path)
+                .map_err(ApprovalAuditError::Other)?;
+            let mut clauses = Vec::new();
+            let mut values: Vec<SqlValue> = Vec::new();
+            if let Some(thread_id) = filter.thread_id {
+                clauses.push("thread_id = ?");
+                values.push(SqlValue::Text(thread_id));
+            }
+            if let Some(user_id) = filter.user_id {
+                clauses.push("user_id = ?");
+                values.push(SqlValue::Text(user_id));
+            }
+            if let Some(since) = filter.since {
+                clauses.push("created_at >= ?");
+                values.push(SqlValue::Integer(since));
+            }
+            if let Some(until) = filter.until {
+                clauses.push("created_at < ?");
+                values.push(SqlValue::Integer(until));
+            }
+            let limit = filter
+                .limit
+                .unwrap_or(DEFAULT_LIST_LIMIT)
+                .min(MAX_LIST_LIMIT);
+            values.push(SqlValue::Integer(i64::from(limit)));
+            let where_sql = if clauses.is_empty() {
+                String::new()
+            } else {
+                format!("WHERE {}", clauses.join(" AND "))
+            };
+            let sql = format!(
+                "SELECT tool_name, call_id, args_digest, decision, remembered, thread_id, user_id, created_at \
+                 FROM approval_audit {} ORDER BY created_at DESC, id DESC LIMIT ?",
+                where_sql
+            );
+            let mut stmt = conn.prepare(&sql).map_err(sql_err)?;
+            let rows = stmt
+                .query_map(params_from_iter(values), |row| {
+                    let decision: String = row.get(3)?;
+                    Ok(ApprovalAuditRecord {
+                        tool_name: row.get(0)?,
+                        call_id: row.get(1)?,
+                        args_digest: row.get(2)?,
+                        decision: ApprovalAuditDecision::parse(&decision)
+                            .unwrap_or(ApprovalAuditDecision::Requested),
+                        remembered: row.get(4)?,
+                        thread_id: row.get(5)?,
+                        user_id: row.get(6)?,
+                        created_at_ms: row.get(7)?,
+                    })
+                })
+                .map_err(sql_err)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(sql_err)
+        })
+        .await
+        .map_err(sql_err)?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(decision: ApprovalAuditDecision, thread: &str, at: i64) -> ApprovalAuditRecord {
+        ApprovalAuditRecord {
+            tool_name: "bash".to_string(),
+            call_id: Some("call-1".to_string()),
+            args_digest: "sha256:00".to_string(),
+            decision,
+            remembered: false,
+            thread_id: Some(thread.to_string()),
+            user_id: Some("alice".to_string()),
+            created_at_ms: at,
+        }
+    }
+
+    #[tokio::test]
+    async fn records_are_listed_newest_first_and_filtered() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let store = SqliteApprovalAuditStore::new(file.path()).unwrap();
+        store
+            .record(&record(ApprovalAuditDecision::Requested, "t1", 1_000))
+            .await
+            .unwrap();
+        store
+            .record(&record(ApprovalAuditDecision::Approved, "t1", 2_000))
+            .await
+            .unwrap();
+        store
+            .record(&record(ApprovalAuditDecision::Rejected, "t2", 3_000))
+            .await
+            .unwrap();
+
+        let all = store.list(&ApprovalAuditFilter::default()).await.unwrap();
+        let decisions: Vec<_> = all.iter().map(|r| r.decision).collect();
+        assert_eq!(
+            decisions,
+            vec![
+                ApprovalAuditDecision::Rejected,
+                ApprovalAuditDecision::Approved,
+                ApprovalAuditDecision::Requested,
+            ]
+        );
+        assert_eq!(all[1], record(ApprovalAuditDecision::Approved, "t1", 2_000));
+
+        let t1 = store
+            .list(&ApprovalAuditFilter {
+                thread_id: Some("t1".to_string()),
+                since: Some(1_500),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(t1.len(), 1);
+        assert_eq!(t1[0].decision, ApprovalAuditDecision::Approved);
+
+        let limited = store
+            .list(&ApprovalAuditFilter {
+                limit: Some(1),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(limited.len(), 1);
+    }
+}
@@ -0,0 +1,295 @@
+//! Fluent entry point for the common ReAct setup: [`Loom::builder`].
+//!
+//! The builder starts from [`ReactBuildConfig::from_env`], so environment variables still apply
+//! and each call overrides one setting:
+//!
+//! ```no_run
+//! # async fn demo() -> Result<(), Box<dyn std::error::Error>> {
+//! let runner = loom::Loom::builder()
+//!     .model("openai/gpt-4o")
+//!     .system_prompt("You are a careful code reviewer.")
+//!     .working_folder("./repo")
+//!     .read_only(true)
+//!     .thread_id("review-1")
+//!     .db_path("loom.db")
+//!     .build()
+//!     .await?;
+//! let state = runner.invoke("Review the last commit.").await?;
+//! println!("{}", state.last_assistant_reply().unwrap_or_default());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! For settings without a builder method, edit [`LoomBuilder::config_mut`] or finish with
+//! [`LoomBuilder::into_config`] and call [`build_react_runner`] yourself. The `serve` crate turns
+//! a builder into a WebSocket router (`serve::router_from_builder`).
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use env_config::McpServerDef;
+
+use crate::agent::react::{build_react_runner, BuildRunnerError, ReactBuildConfig, ReactRunner};
+use crate::compress::{CompactionConfig, HistoryWindow};
+use crate::graph::NodeMiddleware;
+use crate::llm::LlmClient;
+use crate::run_hooks::RunHooks;
+use crate::state::ReActState;
+
+/// Namespace for [`Loom::builder`].
+pub struct Loom;
+
+impl Loom {
+    /// Starts a [`LoomBuilder`] from the environment ([`ReactBuildConfig::from_env`]).
+    pub fn builder() -> LoomBuilder {
+        LoomBuilder::new(ReactBuildConfig::from_env())
+    }
+}
+
+/// Configures LLM, tools, memory, prompt, limits and streaming for a ReAct agent in one
+/// chain; see the [module docs](self).
+pub struct LoomBuilder {
+    config: ReactBuildConfig,
+    llm: Option<Box<dyn LlmClient>>,
+    verbose: bool,
+    run_hooks: RunHooks<ReActState>,
+}
+
+impl LoomBuilder {
+    /// Starts from `config` instead of the environment.
+    pub fn new(config: ReactBuildConfig) -> Self {
+        Self {
+            config,
+            llm: None,
+            verbose: false,
+            run_hooks: RunHooks::default(),
+        }
+    }
+
+    // --- LLM ---
+
+    /// Model in `provider/model` form or a bare model name.
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.config.model = Some(model.into());
+        self
+    }
+
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.config.openai_api_key = Some(api_key.into().into());
+        self
+    }
+
+    /// Base URL of an OpenAI-compatible endpoint.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.config.openai_base_url = Some(base_url.into());
+        self
+    }
+
+    /// Provider type, e.g. `openai` or `openai_compat`.
+    pub fn provider(mut self, provider: impl Into<String>) -> Self {
+        self.config.llm_provider = Some(provider.into());
+        self
+    }
+
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.config.openai_temperature = Some(temperature.to_string());
+        self
+    }
+
+    /// Sequences that end generation, e.g. `</answer>`.
+    pub fn stop_sequences<I, T>(mut self, stop: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.config.stop_sequences = stop.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Uses `llm` instead of building a client from model and credentials (e.g. a
+    /// [`crate::MockLlm`] in tests).
+    pub fn llm(mut self, llm: impl LlmClient + 'static) -> Self {
+        self.llm = Some(Box::new(llm));
+        self
+    }
+
+    // --- Tools ---
+
+    /// Folder the file and shell tools work in.
+    pub fn working_folder(mut self, folder: impl Into<PathBuf>) -> Self {
+        self.config.working_folder = Some(folder.into());
+        self
+    }
+
+    /// Adds an MCP server whose tools the agent can call.
+    pub fn mcp_server(mut self, server: McpServerDef) -> Self {
+        self.config
+            .mcp_servers
+            .get_or_insert_with(Vec::new)
+            .push(server);
+        self
+    }
+
+    /// Only these tools are listed and callable.
+    pub fn allowed_tools<I, T>(mut self, tools: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.config.allowed_tools = Some(tools.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Hides and refuses these tools.
+    pub fn deny_tools<I, T>(mut self, tools: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.config
+            .denied_tools
+            .extend(tools.into_iter().map(Into::into));
+        self
+    }
+
+    /// No shell or file-modifying tools (see [`ReactBuildConfig::read_only`]).
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.config.read_only = read_only;
+        self
+    }
+
+    // --- Memory ---
+
+    /// SQLite file for checkpoints and long-term memory.
+    pub fn db_path(mut self, path: impl Into<String>) -> Self {
+        self.config.db_path = Some(path.into());
+        self
+    }
+
+    /// Conversation thread to checkpoint into and resume.
+    pub fn thread_id(mut self, thread_id: impl Into<String>) -> Self {
+        self.config.thread_id = Some(thread_id.into());
+        self
+    }
+
+    /// User namespace for long-term memory.
+    pub fn user_id(mut self, user_id: impl Into<String>) -> Self {
+        self.config.user_id = Some(user_id.into());
+        self
+    }
+
+    /// Bounds the history replayed from a checkpointed thread.
+    pub fn history_window(mut self, window: HistoryWindow) -> Self {
+        self.config.history_window = Some(window);
+        self
+    }
+
+    // --- Prompt ---
+
+    pub fn system_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.config.system_prompt = Some(prompt.into());
+        self
+    }
+
+    // --- Limits ---
+
+    /// Context size and compaction settings (default: resolved from the model).
+    pub fn compaction(mut self, config: CompactionConfig) -> Self {
+        self.config.compaction_config = Some(config);
+        self
+    }
+
+    /// Larger-context model used when a prompt does not fit the default model.
+    pub fn context_fallback_model(mut self, model: impl Into<String>) -> Self {
+        self.config.context_fallback_model = Some(model.into());
+        self
+    }
+
+    /// Continues answers cut off by the output token limit.
+    pub fn auto_continue(mut self, enabled: bool) -> Self {
+        self.config.auto_continue = enabled;
+        self
+    }
+
+    // --- Streaming and observation ---
+
+    /// Logs each node's input and output.
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Wraps ReAct nodes matching `node_id_pattern` with `middleware` (see
+    /// [`ReactBuildConfig::with_middleware`]).
+    pub fn middleware(
+        mut self,
+        node_id_pattern: impl Into<String>,
+        middleware: Arc<dyn NodeMiddleware<ReActState>>,
+    ) -> Self {
+        self.config = self.config.with_middleware(node_id_pattern, middleware);
+        self
+    }
+
+    /// Calls `hooks` when each run of the built runner starts, finishes or fails (see
+    /// [`ReactRunner::with_hooks`]).
+    pub fn run_hooks(mut self, hooks: RunHooks<ReActState>) -> Self {
+        self.run_hooks = hooks;
+        self
+    }
+
+    // --- Output ---
+
+    pub fn config(&self) -> &ReactBuildConfig {
+        &self.config
+    }
+
+    pub fn config_mut(&mut self) -> &mut ReactBuildConfig {
+        &mut self.config
+    }
+
+    pub fn into_config(self) -> ReactBuildConfig {
+        self.config
+    }
+
+    /// Whether [`Self::llm`] supplied a client.
+    pub fn has_llm(&self) -> bool {
+        self.llm.is_some()
+    }
+
+    /// Builds the ReAct runner. Stream its events with [`ReactRunner::stream_with_callback`].
+    pub async fn build(self) -> Result<ReactRunner, BuildRunnerError> {
+        Ok(build_react_runner(&self.config, self.llm, self.verbose)
+            .await?
+            .with_hooks(self.run_hooks))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_sets_config_fields() {
+        let builder = LoomBuilder::new(ReactBuildConfig::from_env())
+            .model("openai/gpt-4o")
+            .system_prompt("Be brief.")
+            .allowed_tools(["read", "grep"])
+            .deny_tools(["bash"])
+            .read_only(true)
+            .thread_id("t1")
+            .auto_continue(true)
+            .stop_sequences(["</answer>"]);
+        let config = builder.config();
+        assert_eq!(config.model.as_deref(), Some("openai/gpt-4o"));
+        assert_eq!(config.system_prompt.as_deref(), Some("Be brief."));
+        assert_eq!(
+            config.allowed_tools,
+            Some(vec!["read".to_string(), "grep".to_string()])
+        );
+        assert!(config.denied_tools.contains(&"bash".to_string()));
+        assert!(config.read_only);
+        assert_eq!(config.thread_id.as_deref(), Some("t1"));
+        assert!(config.auto_continue);
+        assert_eq!(config.stop_sequences, vec!["</answer>".to_string()]);
+    }
+}
@@ -0,0 +1,485 @@
+//! Fault injection for chaos testing (`chaos` feature).
+//!
+//! [`FaultInjector`] randomly delays or fails LLM calls, tool calls and checkpoint writes with
+//! configured probabilities, so retries, interrupts and resumes can be tested in CI under
+//! realistic failure patterns. [`ChaosLlm`], [`ChaosToolSource`] and [`ChaosCheckpointer`] wrap
+//! the real components; [`build_react_runner`](crate::build_react_runner) wraps the main LLM,
+//! the tool source and the checkpointer when [`ENV_CHAOS`] is set.
+//!
+//! Injected failures look like the real ones the runtime must handle: an LLM call fails as
+//! [`AgentError::RateLimited`] (retried by the runner's retry client), a tool call as
+//! [`ToolSourceError::Transport`] and a checkpoint write as [`CheckpointError::Storage`].
+//!
+//! ```text
+//! LOOM_CHAOS="llm.fail=0.2,llm.delay=0.5,tool.fail=0.1,checkpoint.fail=0.05,delay_ms=2000,seed=7"
+//! ```
+
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use crate::error::AgentError;
+use crate::graph::{routing_seed, RoutingRng};
+use crate::llm::{LlmClient, LlmResponse, MessageChunk, ModelInfo, ToolCallDelta};
+use crate::memory::{
+    Checkpoint, CheckpointError, CheckpointFilter, CheckpointListItem, CheckpointMetadata,
+    Checkpointer, RunnableConfig,
+};
+use crate::message::Message;
+use crate::model_spec::ToolSupport;
+use crate::tool_source::{
+    ToolCallContent, ToolCallContext, ToolSource, ToolSourceError, ToolSourceHealth, ToolSpec,
+};
+
+/// Env var: fault injection spec (see [`FaultConfig`]'s `FromStr`); unset or empty = off.
+pub const ENV_CHAOS: &str = "LOOM_CHAOS";
+
+/// Longest injected delay when the spec sets no `delay_ms`.
+const DEFAULT_MAX_DELAY: Duration = Duration::from_millis(1000);
+
+/// Kind of call a fault is injected into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FaultTarget {
+    Llm,
+    Tool,
+    Checkpoint,
+}
+
+impl FaultTarget {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Llm => "llm",
+            Self::Tool => "tool",
+            Self::Checkpoint => "checkpoint",
+        }
+    }
+}
+
+/// Probabilities (0.0 to 1.0) of delaying and of failing one call.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FaultRates {
+    pub fail: f64,
+    pub delay: f64,
+}
+
+/// What to inject into which calls.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FaultConfig {
+    pub llm: FaultRates,
+    pub tool: FaultRates,
+    pub checkpoint: FaultRates,
+    /// Delays are uniform between zero and this.
+    pub max_delay: Duration,
+    /// Seed of the fault sequence; `None` uses the routing seed (see [`routing_seed`]), so
+    /// `LOOM_ROUTING_SEED` also replays a failure pattern.
+    pub seed: Option<u64>,
+}
+
+impl Default for FaultConfig {
+    fn default() -> Self {
+        Self {
+            llm: FaultRates::default(),
+            tool: FaultRates::default(),
+            checkpoint: FaultRates::default(),
+            max_delay: DEFAULT_MAX_DELAY,
+            seed: None,
+        }
+    }
+}
+
+impl FaultConfig {
+    /// Reads [`ENV_CHAOS`]; `Ok(None)` when unset or empty.
+    pub fn from_env() -> Result<Option<Self>, String> {
+        match std::env::var(ENV_CHAOS) {
+            Ok(spec) if !spec.trim().is_empty() => spec.parse().map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn rates(&self, target: FaultTarget) -> FaultRates {
+        match target {
+            FaultTarget::Llm => self.llm,
+            FaultTarget::Tool => self.tool,
+            FaultTarget::Checkpoint => self.checkpoint,
+        }
+    }
+}
+
+/// Parses comma-separated `key=value` pairs: `<target>.fail` and `<target>.delay` with `llm`,
+/// `tool` or `checkpoint` as target (probabilities), `delay_ms` and `seed`.
+impl FromStr for FaultConfig {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut config = Self::default();
+        for pair in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("expected key=value, got `{}`", pair))?;
+            let (key, value) = (key.trim(), value.trim());
+            match key {
+                "delay_ms" => {
+                    let ms = value
+                        .parse()
+                        .map_err(|_| format!("invalid delay_ms `{}`", value))?;
+                    config.max_delay = Duration::from_millis(ms);
+                }
+                "seed" => {
+                    config.seed = Some(
+                        value
+                            .parse()
+                            .map_err(|_| format!("invalid seed `{}`", value))?,
+                    );
+                }
+                _ => {
+                    let (target, kind) = key
+                        .split_once('.')
+                        .ok_or_else(|| format!("unknown key `{}`", key))?;
+                    let rates = match target {
+                        "llm" => &mut config.llm,
+                        "tool" => &mut config.tool,
+                        "checkpoint" => &mut config.checkpoint,
+                        _ => return Err(format!("unknown fault target `{}`", target)),
+                    };
+                    let p: f64 = value
+                        .parse()
+                        .ok()
+                        .filter(|p| (0.0..=1.0).contains(p))
+                        .ok_or_else(|| format!("`{}` must be between 0 and 1", key))?;
+                    match kind {
+                        "fail" => rates.fail = p,
+                        "delay" => rates.delay = p,
+                        _ => return Err(format!("unknown key `{}`", key)),
+                    }
+                }
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// Draws faults from a [`FaultConfig`]. Shared by the wrappers of one runner, so a seeded
+/// config replays the same sequence as long as the calls happen in the same order.
+#[derive(Debug)]
+pub struct FaultInjector {
+    config: FaultConfig,
+    rng: Mutex<RoutingRng>,
+}
+
+impl FaultInjector {
+    pub fn new(config: FaultConfig) -> Self {
+        let seed = config.seed.unwrap_or_else(|| routing_seed(None));
+        Self {
+            config,
+            rng: Mutex::new(RoutingRng::new(seed)),
+        }
+    }
+
+    /// Injector from [`ENV_CHAOS`]; `Ok(None)` when unset.
+    pub fn from_env() -> Result<Option<Arc<Self>>, String> {
+        Ok(FaultConfig::from_env()?.map(|config| Arc::new(Self::new(config))))
+    }
+
+    /// Whether to fail the next `target` call, and how long to delay it first.
+    pub fn draw(&self, target: FaultTarget) -> (Option<Duration>, bool) {
+        let rates = self.config.rates(target);
+        let mut rng = self.rng.lock().unwrap_or_else(|e| e.into_inner());
+        let delay =
+            (rng.next_f64() < rates.delay).then(|| self.config.max_delay.mul_f64(rng.next_f64()));
+        let fail = rng.next_f64() < rates.fail;
+        (delay, fail)
+    }
+
+    /// Sleeps when a delay is drawn; `Err` with a message when the call is to fail.
+    pub async fn inject(&self, target: FaultTarget) -> Result<(), String> {
+        let (delay, fail) = self.draw(target);
+        if let Some(delay) = delay {
+            warn!(
+                fault = target.as_str(),
+                delay_ms = delay.as_millis() as u64,
+                "chaos: delaying call"
+            );
+            tokio::time::sleep(delay).await;
+        }
+        if fail {
+            warn!(fault = target.as_str(), "chaos: failing call");
+            return Err(format!("injected {} fault", target.as_str()));
+        }
+        Ok(())
+    }
+}
+
+/// [`LlmClient`] whose calls are delayed or fail as [`AgentError::RateLimited`].
+pub struct ChaosLlm {
+    inner: Box<dyn LlmClient>,
+    injector: Arc<FaultInjector>,
+}
+
+impl ChaosLlm {
+    pub fn new(inner: Box<dyn LlmClient>, injector: Arc<FaultInjector>) -> Self {
+        Self { inner, injector }
+    }
+
+    async fn inject(&self) -> Result<(), AgentError> {
+        self.injector
+            .inject(FaultTarget::Llm)
+            .await
+            .map_err(|message| AgentError::RateLimited {
+                retry_after: None,
+                message,
+            })
+    }
+}
+
+#[async_trait]
+impl LlmClient for ChaosLlm {
+    async fn invoke(&self, messages: &[Message]) -> Result<LlmResponse, AgentError> {
+        self.inject().await?;
+        self.inner.invoke(messages).await
+    }
+
+    async fn invoke_stream(
+        &self,
+        messages: &[Message],
+        chunk_tx: Option<mpsc::Sender<MessageChunk>>,
+    ) -> Result<LlmResponse, AgentError> {
+        self.inject().await?;
+        self.inner.invoke_stream(messages, chunk_tx).await
+    }
+
+    async fn invoke_stream_with_tool_delta(
+        &self,
+        messages: &[Message],
+        chunk_tx: Option<mpsc::Sender<MessageChunk>>,
+        tool_delta_tx: Option<mpsc::Sender<ToolCallDelta>>,
+    ) -> Result<LlmResponse, AgentError> {
+        self.inject().await?;
+        self.inner
+            .invoke_stream_with_tool_delta(messages, chunk_tx, tool_delta_tx)
+            .await
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, AgentError> {
+        self.inner.list_models().await
+    }
+
+    fn set_tools(&self, tools: Vec<ToolSpec>) {
+        self.inner.set_tools(tools);
+    }
+
+    fn tool_support(&self) -> ToolSupport {
+        self.inner.tool_support()
+    }
+
+    fn route(&self) -> Option<String> {
+        self.inner.route()
+    }
+}
+
+/// [`ToolSource`] whose tool calls are delayed or fail as [`ToolSourceError::Transport`].
+/// Listing tools is never affected.
+pub struct ChaosToolSource {
+    inner: Box<dyn ToolSource>,
+    injector: Arc<FaultInjector>,
+}
+
+impl ChaosToolSource {
+    pub fn new(inner: Box<dyn ToolSource>, injector: Arc<FaultInjector>) -> Self {
+        Self { inner, injector }
+    }
+}
+
+#[async_trait]
+impl ToolSource for ChaosToolSource {
+    async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+        self.inner.list_tools().await
+    }
+
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Value,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        self.call_tool_with_context(name, arguments, None).await
+    }
+
+    async fn call_tool_with_context(
+        &self,
+        name: &str,
+        arguments: Value,
+        ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        self.injector
+            .inject(FaultTarget::Tool)
+            .await
+            .map_err(ToolSourceError::Transport)?;
+        self.inner
+            .call_tool_with_context(name, arguments, ctx)
+            .await
+    }
+
+    fn set_call_context(&self, ctx: Option<ToolCallContext>) {
+        self.inner.set_call_context(ctx);
+    }
+
+    async fn refresh_tools(&self) -> Result<Option<Vec<ToolSpec>>, ToolSourceError> {
+        self.inner.refresh_tools().await
+    }
+
+    async fn tools_for_turn(&self, query: &str) -> Result<Option<Vec<ToolSpec>>, ToolSourceError> {
+        self.inner.tools_for_turn(query).await
+    }
+
+    async fn health(&self) -> ToolSourceHealth {
+        self.inner.health().await
+    }
+}
+
+/// [`Checkpointer`] whose writes are delayed or fail as [`CheckpointError::Storage`]. Reads are
+/// never affected.
+pub struct ChaosCheckpointer<S> {
+    inner: Arc<dyn Checkpointer<S>>,
+    injector: Arc<FaultInjector>,
+}
+
+impl<S> ChaosCheckpointer<S> {
+    pub fn new(inner: Arc<dyn Checkpointer<S>>, injector: Arc<FaultInjector>) -> Self {
+        Self { inner, injector }
+    }
+}
+
+#[async_trait]
+impl<S> Checkpointer<S> for ChaosCheckpointer<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    async fn put(
+        &self,
+        config: &RunnableConfig,
+        checkpoint: &Checkpoint<S>,
+    ) -> Result<String, CheckpointError> {
+        self.injector
+            .inject(FaultTarget::Checkpoint)
+            .await
+            .map_err(CheckpointError::Storage)?;
+        self.inner.put(config, checkpoint).await
+    }
+
+    async fn get_tuple(
+        &self,
+        config: &RunnableConfig,
+    ) -> Result<Option<(Checkpoint<S>, CheckpointMetadata)>, CheckpointError> {
+        self.inner.get_tuple(config).await
+    }
+
+    async fn list(
+        &self,
+        config: &RunnableConfig,
+        limit: Option<usize>,
+        before: Option<&str>,
+        after: Option<&str>,
+    ) -> Result<Vec<CheckpointListItem>, CheckpointError> {
+        self.inner.list(config, limit, before, after).await
+    }
+
+    async fn list_filtered(
+        &self,
+        config: &RunnableConfig,
+        filter: &CheckpointFilter,
+    ) -> Result<Vec<CheckpointListItem>, CheckpointError> {
+        self.inner.list_filtered(config, filter).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlm;
+    use crate::tool_source::MockToolSource;
+
+    #[test]
+    fn parses_spec() {
+        let config: FaultConfig = "llm.fail=0.2, llm.delay=0.5,tool.fail=1,delay_ms=250,seed=7"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            config.llm,
+            FaultRates {
+                fail: 0.2,
+                delay: 0.5
+            }
+        );
+        assert_eq!(config.tool.fail, 1.0);
+        assert_eq!(config.checkpoint, FaultRates::default());
+        assert_eq!(config.max_delay, Duration::from_millis(250));
+        assert_eq!(config.seed, Some(7));
+
+        assert!("llm.fail=1.5".parse::<FaultConfig>().is_err());
+        assert!("db.fail=0.1".parse::<FaultConfig>().is_err());
+        assert!("llm.crash=0.1".parse::<FaultConfig>().is_err());
+        assert!("llm".parse::<FaultConfig>().is_err());
+    }
+
+    #[test]
+    fn seeded_injector_replays_the_same_faults() {
+        let config = FaultConfig {
+            llm: FaultRates {
+                fail: 0.3,
+                delay: 0.5,
+            },
+            seed: Some(42),
+            ..FaultConfig::default()
+        };
+        let (a, b) = (
+            FaultInjector::new(config.clone()),
+            FaultInjector::new(config),
+        );
+        let draws: Vec<_> = (0..200).map(|_| a.draw(FaultTarget::Llm)).collect();
+        assert_eq!(
+            draws,
+            (0..200)
+                .map(|_| b.draw(FaultTarget::Llm))
+                .collect::<Vec<_>>()
+        );
+        let failures = draws.iter().filter(|(_, fail)| *fail).count();
+        assert!((30..90).contains(&failures), "{} failures", failures);
+        assert!(draws
+            .iter()
+            .filter_map(|(delay, _)| *delay)
+            .all(|d| d <= DEFAULT_MAX_DELAY));
+    }
+
+    #[tokio::test]
+    async fn wrappers_fail_calls_as_the_real_errors() {
+        let injector = Arc::new(FaultInjector::new(FaultConfig {
+            llm: FaultRates {
+                fail: 1.0,
+                delay: 0.0,
+            },
+            tool: FaultRates {
+                fail: 1.0,
+                delay: 0.0,
+            },
+            ..FaultConfig::default()
+        }));
+        let llm = ChaosLlm::new(
+            Box::new(MockLlm::with_no_tool_calls("hi")),
+            injector.clone(),
+        );
+        let err = llm.invoke(&[Message::user("hello")]).await.unwrap_err();
+        assert_eq!(err.code(), Some("rate_limited"));
+
+        let tools = ChaosToolSource::new(Box::new(MockToolSource::get_time_example()), injector);
+        assert_eq!(tools.list_tools().await.unwrap().len(), 1);
+        let err = tools
+            .call_tool("get_time", serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolSourceError::Transport(_)));
+    }
+}
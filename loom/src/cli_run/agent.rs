@@ -1,19 +1,27 @@
 //! Unified agent runner: ReAct, DUP, ToT, GoT.
 
 use crate::cli_run::build_helve_config;
+use crate::cli_run::history::{split_history, HistoryError, HistoryMessage};
+use crate::cli_run::reply_format::ReplyFormat;
+use crate::cli_run::timing::RunTimer;
+use crate::cli_run::transcript::ToolTranscript;
 use crate::export::stream_event_to_format_a;
-use crate::llm::LlmClient;
+use crate::llm::{FinishReason, LlmClient};
+use crate::message::Message;
 use crate::protocol::stream::stream_event_to_protocol_envelope;
 use crate::protocol::EnvelopeState;
-use crate::protocol::ProtocolEventEnvelope;
+use crate::protocol::{ProtocolEventEnvelope, RunTiming, ToolCallRecord};
+use crate::state::AnswerConfidence;
 use crate::{
     build_dup_runner, build_got_runner, build_react_runner, build_tot_runner, DupRunner, DupState,
     GotRunner, GotState, ReActState, ReactBuildConfig, ReactRunner, StreamEvent, TotRunner,
     TotState,
 };
 use serde_json::Value;
+use std::collections::HashMap;
 use std::fmt;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use thiserror::Error;
 use tokio_util::sync::CancellationToken;
@@ -62,6 +70,9 @@ impl fmt::Debug for ActiveOperation {
 #[derive(Debug)]
 struct CancellationState {
     active_operation: Mutex<Option<ActiveOperation>>,
+    /// Set by [`RunCancellation::stop_generation`], cleared when a think step honours it.
+    stop_requested: AtomicBool,
+    stop_notify: tokio::sync::Notify,
 }
 
 #[derive(Debug)]
@@ -90,6 +101,8 @@ impl RunCancellation {
             token: CancellationToken::new(),
             state: Arc::new(CancellationState {
                 active_operation: Mutex::new(None),
+                stop_requested: AtomicBool::new(false),
+                stop_notify: tokio::sync::Notify::new(),
             }),
         }
     }
@@ -107,6 +120,34 @@ impl RunCancellation {
         self.cancel_active_operation();
     }
 
+    /// Asks the LLM call in progress to stop generating, like a chat UI's stop button: the think
+    /// step keeps what was produced so far as its answer and the graph continues from there.
+    /// A request made while no LLM call is running applies to the next one. Unlike
+    /// [`cancel`](Self::cancel), the run is not aborted.
+    pub fn stop_generation(&self) {
+        self.state.stop_requested.store(true, Ordering::SeqCst);
+        self.state.stop_notify.notify_waiters();
+    }
+
+    /// Clears a pending [`stop_generation`](Self::stop_generation) request; returns whether one
+    /// was pending.
+    pub fn take_stop_request(&self) -> bool {
+        self.state.stop_requested.swap(false, Ordering::SeqCst)
+    }
+
+    /// Resolves once [`stop_generation`](Self::stop_generation) is requested (immediately when a
+    /// request is already pending).
+    pub async fn stop_requested(&self) {
+        loop {
+            // Created before the check so a concurrent `notify_waiters` is not missed.
+            let notified = self.state.stop_notify.notified();
+            if self.state.stop_requested.load(Ordering::SeqCst) {
+                return;
+            }
+            notified.await;
+        }
+    }
+
     pub fn set_active_operation(&self, operation: ActiveOperation) {
         if let Ok(mut active_operation) = self.state.active_operation.lock() {
             *active_operation = Some(operation);
@@ -160,6 +201,9 @@ pub struct RunOptions {
     pub session_id: Option<String>,
     /// Named agent profile (e.g. "coding"). Resolved from .loom/agents/<name> or ~/.loom/agents/<name>.
     pub agent: Option<String>,
+    /// Agent definition file (YAML or Markdown with front matter, e.g. `agent.yaml`); takes
+    /// precedence over `agent` (CLI --agent-file).
+    pub agent_file: Option<PathBuf>,
     pub verbose: bool,
     pub got_adaptive: bool,
     pub display_max_len: usize,
@@ -185,6 +229,28 @@ pub struct RunOptions {
     pub output_timestamp: bool,
     /// When true, do not execute tools; LLM runs but tool calls return a placeholder (CLI --dry).
     pub dry_run: bool,
+    /// Role instructions used when the agent profile defines none (e.g. serve workspace defaults).
+    pub role_setting: Option<String>,
+    /// When set, only these tools are listed and callable in the run.
+    pub allowed_tools: Option<Vec<String>>,
+    /// Read-only run: shell and file-modifying tools are not registered, calls to them are
+    /// refused, and the system prompt says so (CLI --read-only, serve `read_only`).
+    pub read_only: bool,
+    /// Requested format of the final reply; JSON replies are validated (see [`ReplyFormat`]).
+    pub reply_format: Option<ReplyFormat>,
+    /// JSON Schema the reply must match when `reply_format` is JSON.
+    pub reply_schema: Option<Value>,
+    /// Client-managed history. When set, the run starts from these messages instead of the
+    /// checkpointed thread and answers the last one (ReAct only; see [`HistoryMessage`]).
+    pub messages: Option<Vec<HistoryMessage>>,
+    /// Earlier conversations the `search_history` tool may search (serve: the workspace's threads).
+    pub history_search: Option<crate::tools::HistorySearch>,
+    /// Locale for localized prompt variants (serve: request `locale`); `None` falls back to
+    /// `LOOM_LOCALE`, then to the script of the message.
+    pub locale: Option<String>,
+    /// Feature flags for this run (CLI --flag, serve request `flags`), layered over
+    /// `LOOM_FLAGS`. Nodes, middleware and tools read them from the run config.
+    pub flags: HashMap<String, bool>,
 }
 
 /// Error type for run operations.
@@ -206,6 +272,26 @@ pub enum RunError {
     Remote(String),
     #[error("config: {0}")]
     ConfigError(String),
+    /// The final reply did not match the requested [`ReplyFormat`], also after one retry.
+    #[error("reply format: {0}")]
+    ReplyFormat(String),
+    #[error("messages: {0}")]
+    History(#[from] HistoryError),
+}
+
+impl RunError {
+    /// Code of the provider error that ended the run (see [`crate::AgentError::code`]), so
+    /// clients can tell a rate limit, an oversized prompt, a content filter or a bad key from
+    /// other failures.
+    pub fn error_code(&self) -> Option<&'static str> {
+        match self {
+            RunError::Run(crate::agent::react::RunError::Execution(e))
+            | RunError::DupRun(crate::DupRunError::Execution(e))
+            | RunError::TotRun(crate::TotRunError::Execution(e))
+            | RunError::GotRun(crate::GotRunError::Execution(e)) => e.code(),
+            _ => None,
+        }
+    }
 }
 
 /// Command mode for running an agent.
@@ -234,19 +320,62 @@ pub enum AnyStreamEvent {
 }
 
 /// Final result of a single agent run.
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct AgentRunResult {
     pub reply: String,
     pub reasoning_content: Option<String>,
+    /// Why the last LLM completion stopped, from the run's last [`StreamEvent::FinishReason`].
+    /// Only tracked when the run streams events (`on_event` is Some).
+    pub finish_reason: Option<FinishReason>,
+    /// Tool calls made during the run, in order. Like `finish_reason`, only tracked when the run
+    /// streams events.
+    pub transcript: Vec<ToolCallRecord>,
+    /// Timing totals from the run's [`StreamEvent::Timing`] events; like `finish_reason`, only
+    /// tracked when the run streams events.
+    pub timing: Option<RunTiming>,
+    /// Feature flags the run was built with (`LOOM_FLAGS` plus [`RunOptions::flags`]).
+    pub flags: HashMap<String, bool>,
+    /// Confidence in the reply ([`ReActState::confidence`]); ReAct runs with confidence scoring
+    /// only.
+    pub confidence: Option<AnswerConfidence>,
 }
 
 /// Final completion state of a run.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum RunCompletion {
     Finished(AgentRunResult),
     Cancelled,
 }
 
+impl RunCompletion {
+    /// Records the run's feature flags on a finished result.
+    fn with_flags(self, flags: &HashMap<String, bool>) -> Self {
+        match self {
+            RunCompletion::Finished(result) => RunCompletion::Finished(AgentRunResult {
+                flags: flags.clone(),
+                ..result
+            }),
+            RunCompletion::Cancelled => RunCompletion::Cancelled,
+        }
+    }
+}
+
+/// Flags as `name` / `name=false`, sorted, for the run span and logs.
+fn flags_summary(flags: &HashMap<String, bool>) -> String {
+    let mut names: Vec<String> = flags
+        .iter()
+        .map(|(name, &on)| {
+            if on {
+                name.clone()
+            } else {
+                format!("{}=false", name)
+            }
+        })
+        .collect();
+    names.sort();
+    names.join(",")
+}
+
 impl AnyStreamEvent {
     /// Converts to format A JSON (EXPORT_SPEC §2).
     pub fn to_format_a(&self) -> Result<Value, serde_json::Error> {
@@ -295,6 +424,11 @@ pub async fn run_agent(
     on_event: Option<Box<dyn FnMut(AnyStreamEvent) + Send>>,
     llm_override: Option<Box<dyn LlmClient>>,
 ) -> Result<RunCompletion, RunError> {
+    let history = match &opts.messages {
+        Some(messages) if matches!(cmd, RunCmd::React) => Some(split_history(messages)?),
+        Some(_) => return Err(HistoryError::UnsupportedAgent.into()),
+        None => None,
+    };
     let (_helve, mut config, _resolved_agent) = build_helve_config(opts);
     let thread_id_log = config.thread_id.as_deref().unwrap_or("").to_string();
     let kind = match cmd {
@@ -303,8 +437,10 @@ pub async fn run_agent(
         RunCmd::Tot => "tot",
         RunCmd::Got { .. } => "got",
     };
-    let span = info_span!("run", kind = kind, thread_id = %thread_id_log);
-    tracing::info!(parent: &span, thread_id = %thread_id_log, "run started");
+    let flags = config.flags.clone();
+    let flags_log = flags_summary(&flags);
+    let span = info_span!("run", kind = kind, thread_id = %thread_id_log, flags = %flags_log);
+    tracing::info!(parent: &span, thread_id = %thread_id_log, flags = %flags_log, "run started");
 
     if let RunCmd::Got { got_adaptive } = cmd {
         config.got_config.adaptive = *got_adaptive;
@@ -314,28 +450,140 @@ pub async fn run_agent(
         .instrument(span.clone())
         .await?;
 
-    let on_event: Option<Arc<Mutex<Box<dyn FnMut(AnyStreamEvent) + Send>>>> =
-        on_event.map(|b| Arc::new(Mutex::new(b)));
+    let on_event: Option<EventSink> = on_event.map(|b| Arc::new(Mutex::new(b)));
+    let tracking = RunTracking::default();
 
-    let result = match &runner {
+    let schema = opts.reply_schema.as_ref();
+    let (mut history, message) = match history {
+        Some((earlier, last)) => (Some(earlier), last.as_text().into_owned()),
+        None => (None, opts.message.as_text().into_owned()),
+    };
+    let message = match opts.reply_format {
+        Some(format) => format.apply_to_message(&message, schema),
+        None => message,
+    };
+    let result = stream_runner(
+        &runner,
+        &message,
+        history.as_deref(),
+        &span,
+        &on_event,
+        &tracking,
+    )
+    .await?
+    .with_flags(&flags);
+    let Some(format) = opts.reply_format else {
+        return Ok(result);
+    };
+    let RunCompletion::Finished(mut finished) = result else {
+        return Ok(result);
+    };
+    let violation = match format.check(&finished.reply, schema) {
+        Ok(reply) => {
+            finished.reply = reply;
+            return Ok(RunCompletion::Finished(finished));
+        }
+        Err(violation) => violation,
+    };
+    tracing::warn!(parent: &span, %violation, "reply format violated; retrying once");
+    let retry = format.retry_message(&finished.reply, &violation, schema);
+    if let Some(earlier) = history.as_mut() {
+        earlier.push(Message::user(message));
+        earlier.push(Message::assistant(finished.reply.clone()));
+    }
+    match stream_runner(
+        &runner,
+        &retry,
+        history.as_deref(),
+        &span,
+        &on_event,
+        &tracking,
+    )
+    .await?
+    .with_flags(&flags)
+    {
+        RunCompletion::Finished(mut finished) => {
+            finished.reply = format
+                .check(&finished.reply, schema)
+                .map_err(RunError::ReplyFormat)?;
+            Ok(RunCompletion::Finished(finished))
+        }
+        RunCompletion::Cancelled => Ok(RunCompletion::Cancelled),
+    }
+}
+
+type EventSink = Arc<Mutex<Box<dyn FnMut(AnyStreamEvent) + Send>>>;
+
+/// Finish reason, tool transcript and timing collected from stream events, across every pass
+/// of a run.
+#[derive(Default)]
+struct RunTracking {
+    finish_reason: Arc<Mutex<Option<FinishReason>>>,
+    transcript: Arc<Mutex<ToolTranscript>>,
+    timer: Arc<Mutex<RunTimer>>,
+}
+
+/// Streams one pass of `runner` on `message`, forwarding events to `on_event`. With `history`
+/// (ReAct only) the pass starts from those earlier turns instead of the checkpointed thread.
+async fn stream_runner(
+    runner: &AnyRunner,
+    message: &str,
+    history: Option<&[Message]>,
+    span: &tracing::Span,
+    on_event: &Option<EventSink>,
+    tracking: &RunTracking,
+) -> Result<RunCompletion, RunError> {
+    let finish_reason = &tracking.finish_reason;
+    let transcript = &tracking.transcript;
+    let last_finish_reason = || finish_reason.lock().ok().and_then(|r| r.clone());
+    let tool_records = || transcript.lock().map(|t| t.records()).unwrap_or_default();
+    let timer = &tracking.timer;
+    let timing_totals = || timer.lock().ok().and_then(|t| t.totals());
+
+    let result = match runner {
         AnyRunner::React(r) => {
             let sink = on_event.clone();
+            let last_finish = Arc::clone(finish_reason);
+            let tools = Arc::clone(transcript);
+            let timing = Arc::clone(timer);
             let on_ev = sink.map(|s| {
                 move |ev: StreamEvent<ReActState>| {
+                    record_finish_reason(&last_finish, &ev);
+                    if let Ok(mut t) = tools.lock() {
+                        t.record(&ev);
+                    }
+                    if let Ok(mut t) = timing.lock() {
+                        t.record(&ev);
+                    }
                     if let Ok(mut f) = s.lock() {
                         f(AnyStreamEvent::React(ev));
                     }
                 }
             });
-            let outcome = r
-                .stream_with_config(opts.message.as_text().as_ref(), None, on_ev)
-                .instrument(span.clone())
-                .await?;
+            let outcome = match history {
+                Some(history) => {
+                    let mut messages = history.to_vec();
+                    messages.push(Message::user(message));
+                    r.stream_history_with_config(messages, None, on_ev)
+                        .instrument(span.clone())
+                        .await?
+                }
+                None => {
+                    r.stream_with_config(message, None, on_ev)
+                        .instrument(span.clone())
+                        .await?
+                }
+            };
             match outcome {
                 crate::runner_common::StreamRunOutcome::Finished(state) => {
                     RunCompletion::Finished(AgentRunResult {
                         reply: state.last_assistant_reply().unwrap_or_default(),
                         reasoning_content: state.last_reasoning_content(),
+                        finish_reason: last_finish_reason(),
+                        transcript: tool_records(),
+                        timing: timing_totals(),
+                        flags: Default::default(),
+                        confidence: state.confidence.clone(),
                     })
                 }
                 crate::runner_common::StreamRunOutcome::Cancelled => RunCompletion::Cancelled,
@@ -343,15 +591,25 @@ pub async fn run_agent(
         }
         AnyRunner::Dup(r) => {
             let sink = on_event.clone();
+            let last_finish = Arc::clone(finish_reason);
+            let tools = Arc::clone(transcript);
+            let timing = Arc::clone(timer);
             let on_ev = sink.map(|s| {
                 move |ev: StreamEvent<DupState>| {
+                    record_finish_reason(&last_finish, &ev);
+                    if let Ok(mut t) = tools.lock() {
+                        t.record(&ev);
+                    }
+                    if let Ok(mut t) = timing.lock() {
+                        t.record(&ev);
+                    }
                     if let Ok(mut f) = s.lock() {
                         f(AnyStreamEvent::Dup(ev));
                     }
                 }
             });
             let outcome = r
-                .stream_with_config(opts.message.as_text().as_ref(), None, on_ev)
+                .stream_with_config(message, None, on_ev)
                 .instrument(span.clone())
                 .await?;
             match outcome {
@@ -359,6 +617,11 @@ pub async fn run_agent(
                     RunCompletion::Finished(AgentRunResult {
                         reply: state.last_assistant_reply().unwrap_or_default(),
                         reasoning_content: state.last_reasoning_content(),
+                        finish_reason: last_finish_reason(),
+                        transcript: tool_records(),
+                        timing: timing_totals(),
+                        flags: Default::default(),
+                        confidence: None,
                     })
                 }
                 crate::runner_common::StreamRunOutcome::Cancelled => RunCompletion::Cancelled,
@@ -366,15 +629,25 @@ pub async fn run_agent(
         }
         AnyRunner::Tot(r) => {
             let sink = on_event.clone();
+            let last_finish = Arc::clone(finish_reason);
+            let tools = Arc::clone(transcript);
+            let timing = Arc::clone(timer);
             let on_ev = sink.map(|s| {
                 move |ev: StreamEvent<TotState>| {
+                    record_finish_reason(&last_finish, &ev);
+                    if let Ok(mut t) = tools.lock() {
+                        t.record(&ev);
+                    }
+                    if let Ok(mut t) = timing.lock() {
+                        t.record(&ev);
+                    }
                     if let Ok(mut f) = s.lock() {
                         f(AnyStreamEvent::Tot(ev));
                     }
                 }
             });
             let outcome = r
-                .stream_with_config(opts.message.as_text().as_ref(), None, on_ev)
+                .stream_with_config(message, None, on_ev)
                 .instrument(span.clone())
                 .await?;
             match outcome {
@@ -382,6 +655,11 @@ pub async fn run_agent(
                     RunCompletion::Finished(AgentRunResult {
                         reply: state.last_assistant_reply().unwrap_or_default(),
                         reasoning_content: state.last_reasoning_content(),
+                        finish_reason: last_finish_reason(),
+                        transcript: tool_records(),
+                        timing: timing_totals(),
+                        flags: Default::default(),
+                        confidence: None,
                     })
                 }
                 crate::runner_common::StreamRunOutcome::Cancelled => RunCompletion::Cancelled,
@@ -389,15 +667,25 @@ pub async fn run_agent(
         }
         AnyRunner::Got(r) => {
             let sink = on_event.clone();
+            let last_finish = Arc::clone(finish_reason);
+            let tools = Arc::clone(transcript);
+            let timing = Arc::clone(timer);
             let on_ev = sink.map(|s| {
                 move |ev: StreamEvent<GotState>| {
+                    record_finish_reason(&last_finish, &ev);
+                    if let Ok(mut t) = tools.lock() {
+                        t.record(&ev);
+                    }
+                    if let Ok(mut t) = timing.lock() {
+                        t.record(&ev);
+                    }
                     if let Ok(mut f) = s.lock() {
                         f(AnyStreamEvent::Got(ev));
                     }
                 }
             });
             let outcome = r
-                .stream_with_config(opts.message.as_text().as_ref(), None, on_ev)
+                .stream_with_config(message, None, on_ev)
                 .instrument(span.clone())
                 .await?;
             match outcome {
@@ -405,6 +693,11 @@ pub async fn run_agent(
                     RunCompletion::Finished(AgentRunResult {
                         reply: state.summary_result(),
                         reasoning_content: None,
+                        finish_reason: last_finish_reason(),
+                        transcript: tool_records(),
+                        timing: timing_totals(),
+                        flags: Default::default(),
+                        confidence: None,
                     })
                 }
                 crate::runner_common::StreamRunOutcome::Cancelled => RunCompletion::Cancelled,
@@ -415,6 +708,14 @@ pub async fn run_agent(
     Ok(result)
 }
 
+fn record_finish_reason<S>(slot: &Mutex<Option<FinishReason>>, ev: &StreamEvent<S>) {
+    if let StreamEvent::FinishReason { reason } = ev {
+        if let Ok(mut last) = slot.lock() {
+            *last = Some(reason.clone());
+        }
+    }
+}
+
 /// Convenience wrapper that runs the agent with no LLM override (default LLM from config).
 /// Used by CLI, serve, and ACP. For tests with a mock LLM, use [`run_agent_with_llm_override`].
 pub async fn run_agent_with_options(
@@ -527,6 +828,7 @@ pub async fn run_agent_with_provider(
         cancellation: None,
         thread_id: None,
         agent: None,
+        agent_file: None,
         verbose: false,
         got_adaptive: false,
         display_max_len: 120,
@@ -535,10 +837,19 @@ pub async fn run_agent_with_provider(
         model: Some(model.to_string()),
         mcp_config_path: None,
         dry_run: false,
+        role_setting: None,
+        allowed_tools: None,
+        read_only: false,
+        reply_format: None,
+        reply_schema: None,
+        messages: None,
+        history_search: None,
+        locale: None,
         provider: Some(provider.name),
         base_url: provider.base_url,
         api_key: provider.api_key,
         provider_type: provider.provider_type,
+        flags: Default::default(),
     };
 
     // Run with LLM override
@@ -561,6 +872,7 @@ mod tests {
             cancellation: None,
             thread_id: None,
             agent: None,
+            agent_file: None,
             verbose: false,
             got_adaptive,
             display_max_len: 120,
@@ -569,10 +881,19 @@ mod tests {
             mcp_config_path: None,
             output_timestamp: false,
             dry_run: false,
+            role_setting: None,
+            allowed_tools: None,
+            read_only: false,
+            reply_format: None,
+            reply_schema: None,
+            messages: None,
+            history_search: None,
+            locale: None,
             provider: None,
             base_url: None,
             api_key: None,
             provider_type: None,
+            flags: Default::default(),
         }
     }
 
@@ -601,6 +922,7 @@ mod tests {
             model: None,
             llm_provider: None,
             openai_temperature: None,
+            stop_sequences: Vec::new(),
             embedding_api_key: None,
             embedding_base_url: None,
             embedding_model: None,
@@ -609,12 +931,44 @@ mod tests {
             )),
             approval_policy: None,
             compaction_config: None,
+            history_window: None,
+            state_limits: None,
+            graph_spec: None,
+            llm_routes: Vec::new(),
+            flags: Default::default(),
             tot_config: crate::TotRunnerConfig::default(),
             got_config: crate::GotRunnerConfig::default(),
             mcp_servers: None,
             skill_registry: None,
+            history_search: None,
             max_sub_agent_depth: None,
             dry_run: false,
+            node_models: Default::default(),
+            auto_continue: false,
+            context_fallback_model: None,
+            enable_reflection: false,
+            confidence_scoring: None,
+            dedup_observations: false,
+            route_rules: Vec::new(),
+            approval_rules: Vec::new(),
+            approval_audit_db: None,
+            tool_result_framing: Default::default(),
+            untrusted_tools: Vec::new(),
+            sanitize_tool_results: false,
+            injection_patterns: Vec::new(),
+            memory_recall: None,
+            env_context: false,
+            observation_summary_tokens: None,
+            tool_prefetch: Vec::new(),
+            tool_arguments: Default::default(),
+            allowed_tools: None,
+            read_only: false,
+            denied_tools: Vec::new(),
+            tool_selection: None,
+            tool_call_mode: None,
+            offline: false,
+            offline_script: None,
+            node_middleware: Default::default(),
         }
     }
 
@@ -656,6 +1010,7 @@ mod tests {
             cancellation: None,
             thread_id: None,
             agent: None,
+            agent_file: None,
             verbose: false,
             got_adaptive: false,
             display_max_len: 200,
@@ -664,10 +1019,19 @@ mod tests {
             mcp_config_path: None,
             output_timestamp: false,
             dry_run: false,
+            role_setting: None,
+            allowed_tools: None,
+            read_only: false,
+            reply_format: None,
+            reply_schema: None,
+            messages: None,
+            history_search: None,
+            locale: None,
             provider: None,
             base_url: None,
             api_key: None,
             provider_type: None,
+            flags: Default::default(),
         };
         assert!(build_runner(&cfg, &opts, &RunCmd::React, None)
             .await
@@ -702,6 +1066,34 @@ mod tests {
         assert!(e2.to_string().contains("tool not found"));
     }
 
+    #[test]
+    fn flags_are_summarized_sorted_and_recorded_on_finished_runs() {
+        let flags = HashMap::from([
+            ("verify_node".to_string(), true),
+            ("new_compaction".to_string(), false),
+        ]);
+        assert_eq!(flags_summary(&flags), "new_compaction=false,verify_node");
+
+        let finished = RunCompletion::Finished(AgentRunResult {
+            reply: "ok".to_string(),
+            reasoning_content: None,
+            finish_reason: None,
+            transcript: Vec::new(),
+            timing: None,
+            flags: HashMap::new(),
+            confidence: None,
+        })
+        .with_flags(&flags);
+        match finished {
+            RunCompletion::Finished(result) => assert_eq!(result.flags, flags),
+            RunCompletion::Cancelled => panic!("expected a finished run"),
+        }
+        assert_eq!(
+            RunCompletion::Cancelled.with_flags(&flags),
+            RunCompletion::Cancelled
+        );
+    }
+
     #[test]
     fn got_state_summary_result_path_is_usable() {
         let s = GotState {
@@ -711,6 +1103,7 @@ mod tests {
                     id: "n1".to_string(),
                     description: "d".to_string(),
                     tool_calls: vec![],
+                    allowed_tools: None,
                 }],
                 edges: vec![],
             },
@@ -724,6 +1117,8 @@ mod tests {
             )]
             .into_iter()
             .collect(),
+            token_budget: None,
+            tokens_used: 0,
         };
         assert_eq!(s.summary_result(), "ok");
 
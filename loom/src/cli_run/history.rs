@@ -0,0 +1,190 @@
+//! Client-managed message history (`messages` on RunOptions / RunRequest).
+//!
+//! Clients that keep their own conversation (e.g. an OpenAI-compatible chat endpoint) send the
+//! full `system` / `user` / `assistant` history with each run. The run then starts from that
+//! history instead of the checkpointed thread: the agent's system prompt comes first, followed
+//! by the client messages, and the last entry (which must be a user message) is the turn the
+//! run answers. Histories are checked against [`MAX_HISTORY_MESSAGES`] and
+//! [`MAX_HISTORY_TEXT_BYTES`] before the run starts.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::message::{Message, UserContent};
+
+/// Maximum number of entries accepted in a client-managed history.
+pub const MAX_HISTORY_MESSAGES: usize = 512;
+
+/// Maximum total text size (bytes) of a client-managed history.
+pub const MAX_HISTORY_TEXT_BYTES: usize = 1024 * 1024;
+
+/// Role of one [`HistoryMessage`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryRole {
+    System,
+    User,
+    Assistant,
+}
+
+/// One entry of a client-managed history. `content` may be multimodal for user messages only.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct HistoryMessage {
+    pub role: HistoryRole,
+    pub content: UserContent,
+}
+
+impl HistoryMessage {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self {
+            role: HistoryRole::System,
+            content: UserContent::Text(content.into()),
+        }
+    }
+
+    pub fn user(content: impl Into<UserContent>) -> Self {
+        Self {
+            role: HistoryRole::User,
+            content: content.into(),
+        }
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self {
+            role: HistoryRole::Assistant,
+            content: UserContent::Text(content.into()),
+        }
+    }
+
+    fn to_message(&self) -> Message {
+        match self.role {
+            HistoryRole::System => Message::system(self.content.as_text()),
+            HistoryRole::User => Message::User(self.content.clone()),
+            HistoryRole::Assistant => Message::assistant(self.content.as_text()),
+        }
+    }
+}
+
+/// Why a client-managed history was rejected.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum HistoryError {
+    #[error("messages must not be empty")]
+    Empty,
+    #[error("too many messages: {0} (max {MAX_HISTORY_MESSAGES})")]
+    TooManyMessages(usize),
+    #[error("messages too large: {0} bytes of text (max {MAX_HISTORY_TEXT_BYTES})")]
+    TooLarge(usize),
+    #[error("last message must have role user")]
+    LastNotUser,
+    #[error("message {0}: only user messages may have multimodal content")]
+    MultimodalNotUser(usize),
+    #[error("message history is only supported by the react agent")]
+    UnsupportedAgent,
+}
+
+/// Checks `messages` against the history rules and size limits.
+pub fn validate_history(messages: &[HistoryMessage]) -> Result<(), HistoryError> {
+    let Some(last) = messages.last() else {
+        return Err(HistoryError::Empty);
+    };
+    if messages.len() > MAX_HISTORY_MESSAGES {
+        return Err(HistoryError::TooManyMessages(messages.len()));
+    }
+    if last.role != HistoryRole::User {
+        return Err(HistoryError::LastNotUser);
+    }
+    let mut bytes = 0;
+    for (i, m) in messages.iter().enumerate() {
+        if m.role != HistoryRole::User && matches!(m.content, UserContent::Multimodal(_)) {
+            return Err(HistoryError::MultimodalNotUser(i));
+        }
+        bytes += m.content.as_text().len();
+    }
+    if bytes > MAX_HISTORY_TEXT_BYTES {
+        return Err(HistoryError::TooLarge(bytes));
+    }
+    Ok(())
+}
+
+/// Validates `messages` and splits them into the earlier turns (as state messages) and the
+/// final user message the run answers.
+pub(crate) fn split_history(
+    messages: &[HistoryMessage],
+) -> Result<(Vec<Message>, UserContent), HistoryError> {
+    validate_history(messages)?;
+    let (last, earlier) = messages.split_last().ok_or(HistoryError::Empty)?;
+    Ok((
+        earlier.iter().map(HistoryMessage::to_message).collect(),
+        last.content.clone(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_openai_style_messages() {
+        let messages: Vec<HistoryMessage> = serde_json::from_str(
+            r#"[{"role":"system","content":"be brief"},
+                {"role":"user","content":"hi"},
+                {"role":"assistant","content":"hello"},
+                {"role":"user","content":"how are you?"}]"#,
+        )
+        .unwrap();
+        assert_eq!(messages[0], HistoryMessage::system("be brief"));
+        assert_eq!(messages[2], HistoryMessage::assistant("hello"));
+        assert!(validate_history(&messages).is_ok());
+    }
+
+    #[test]
+    fn split_history_returns_earlier_turns_and_last_user_message() {
+        let (earlier, last) = split_history(&[
+            HistoryMessage::user("hi"),
+            HistoryMessage::assistant("hello"),
+            HistoryMessage::user("again"),
+        ])
+        .unwrap();
+        assert_eq!(
+            earlier,
+            vec![Message::user("hi"), Message::assistant("hello")]
+        );
+        assert_eq!(last, "again");
+    }
+
+    #[test]
+    fn rejects_empty_and_non_user_last() {
+        assert_eq!(validate_history(&[]), Err(HistoryError::Empty));
+        assert_eq!(
+            validate_history(&[HistoryMessage::user("q"), HistoryMessage::assistant("a")]),
+            Err(HistoryError::LastNotUser)
+        );
+    }
+
+    #[test]
+    fn rejects_histories_over_the_limits() {
+        let many = vec![HistoryMessage::user("x"); MAX_HISTORY_MESSAGES + 1];
+        assert_eq!(
+            validate_history(&many),
+            Err(HistoryError::TooManyMessages(MAX_HISTORY_MESSAGES + 1))
+        );
+        let big = vec![HistoryMessage::user("x".repeat(MAX_HISTORY_TEXT_BYTES + 1))];
+        assert_eq!(
+            validate_history(&big),
+            Err(HistoryError::TooLarge(MAX_HISTORY_TEXT_BYTES + 1))
+        );
+    }
+
+    #[test]
+    fn rejects_multimodal_assistant_content() {
+        let parts = vec![crate::message::ContentPart::Text { text: "x".into() }];
+        let assistant = HistoryMessage {
+            role: HistoryRole::Assistant,
+            content: UserContent::Multimodal(parts),
+        };
+        assert_eq!(
+            validate_history(&[assistant, HistoryMessage::user("q")]),
+            Err(HistoryError::MultimodalNotUser(0))
+        );
+    }
+}
@@ -4,7 +4,11 @@
 //! Used by both cli (local) and loom serve (remote).
 
 mod agent;
+mod history;
 mod profile;
+mod reply_format;
+mod timing;
+mod transcript;
 
 pub use agent::{
     run_agent, run_agent_with_llm_override, run_agent_with_options, run_agent_with_provider,
@@ -20,9 +24,15 @@ use crate::{
 use std::path::PathBuf;
 use std::sync::Arc;
 
+pub use history::{
+    validate_history, HistoryError, HistoryMessage, HistoryRole, MAX_HISTORY_MESSAGES,
+    MAX_HISTORY_TEXT_BYTES,
+};
+pub use reply_format::{extract_json, validate_schema, ReplyFormat};
+
 pub use profile::{
-    list_available_profiles, load_profile_from_options, resolve_profile, AgentProfile,
-    ProfileError, ProfileSource, ProfileSummary,
+    list_available_profiles, load_agent_profile, load_profile_from_options, resolve_profile,
+    AgentProfile, ProfileError, ProfileSource, ProfileSummary,
 };
 
 /// Metadata about the agent profile that was resolved for a run.
@@ -84,13 +94,16 @@ pub fn build_helve_config(
     });
     let profile = loaded.map(|(p, _)| p);
     let mut effective_opts = opts.clone();
-    apply_model_provider_resolution(&mut effective_opts);
     if let Some(ref p) = profile {
         apply_profile_to_run_options(p, &mut effective_opts);
     }
+    apply_model_provider_resolution(&mut effective_opts);
 
     let mut base = ReactBuildConfig::from_env();
     base.dry_run = effective_opts.dry_run;
+    base.allowed_tools = effective_opts.allowed_tools.clone();
+    base.read_only = base.read_only || effective_opts.read_only;
+    base.history_search = effective_opts.history_search.clone();
     if let Some(ref m) = effective_opts.model {
         base.model = Some(m.clone());
     }
@@ -100,7 +113,7 @@ pub fn build_helve_config(
         base.openai_base_url = Some(url.clone());
     }
     if let Some(ref key) = effective_opts.api_key {
-        base.openai_api_key = Some(key.clone());
+        base.openai_api_key = Some(key.clone().into());
     }
     if let Some(ref t) = effective_opts.provider_type {
         base.llm_provider = Some(t.clone());
@@ -135,6 +148,9 @@ pub fn build_helve_config(
             Err(e) => tracing::warn!(path = %path.display(), "failed to load mcp config: {}", e),
         }
     }
+    if let Some(ref p) = profile {
+        apply_profile_to_build_config(p, &mut base);
+    }
 
     let skill_registry = {
         let extra_dirs: Vec<PathBuf> = profile
@@ -183,7 +199,8 @@ pub fn build_helve_config(
         Some(prompt)
     };
 
-    let agent_instructions = role_content_from_profile(profile_role);
+    let agent_instructions = role_content_from_profile(profile_role)
+        .or_else(|| role_content_from_profile(effective_opts.role_setting.clone()));
 
     tracing::trace!(
         agent_instructions_len = agent_instructions.as_ref().map(|s| s.len()),
@@ -196,13 +213,21 @@ pub fn build_helve_config(
         thread_id: effective_opts.thread_id.clone(),
         user_id: base.user_id.clone(),
         approval_policy: None,
+        approval_rules: Vec::new(),
         role_setting: agent_instructions,
         agents_md: load_agents_md(Some(&working_folder)),
         system_prompt_override: None,
         skills_prompt,
+        skill_packs: Vec::new(),
+        skills: Vec::new(),
+        locale: crate::prompts::resolve_locale(
+            effective_opts.locale.as_deref(),
+            &effective_opts.message.as_text(),
+        ),
     };
     let mut config = to_react_build_config(&helve, base);
     config.skill_registry = Some(skill_registry.0);
+    config.flags.extend(effective_opts.flags.clone());
     config.max_sub_agent_depth = profile
         .as_ref()
         .and_then(|p| p.behavior.as_ref())
@@ -264,6 +289,7 @@ pub fn build_config_from_profile(
             }
         }
     }
+    apply_profile_to_build_config(profile, &mut config);
 
     // System prompt from profile role / AGENTS.md uses the same assembler as top-level runs.
     let role_setting =
@@ -556,6 +582,18 @@ fn apply_profile_to_run_options(profile: &AgentProfile, opts: &mut RunOptions) {
                 opts.model = Some(name.clone());
             }
         }
+        if opts.provider.is_none() {
+            opts.provider = model.provider.clone();
+        }
+        if opts.base_url.is_none() {
+            opts.base_url = model.base_url.clone();
+        }
+        if opts.api_key.is_none() {
+            opts.api_key = model.api_key.clone();
+        }
+        if opts.provider_type.is_none() {
+            opts.provider_type = model.provider_type.clone();
+        }
     }
     if let Some(ref env) = profile.environment {
         if opts.working_folder.is_none() {
@@ -567,6 +605,67 @@ fn apply_profile_to_run_options(profile: &AgentProfile, opts: &mut RunOptions) {
     }
 }
 
+/// Applies a profile's approval policy, tool allow/deny lists (`tools.builtin.enabled` only when
+/// no allowlist is set yet), fixed tool arguments (`tools.arguments`, per tool over
+/// `LOOM_TOOL_ARGUMENTS`), and inline MCP servers (which replace same-named servers from the MCP
+/// config file).
+fn apply_profile_to_build_config(profile: &AgentProfile, config: &mut ReactBuildConfig) {
+    if let Some(policy) = profile
+        .behavior
+        .as_ref()
+        .and_then(|b| b.approval_policy.as_deref())
+    {
+        match parse_profile_approval_policy(policy) {
+            Some(p) => config.approval_policy = Some(p),
+            None => tracing::warn!(policy, "agent profile: unknown approval_policy"),
+        }
+    }
+    if let Some(env_context) = profile.behavior.as_ref().and_then(|b| b.env_context) {
+        config.env_context = env_context;
+    }
+    let Some(ref tools) = profile.tools else {
+        return;
+    };
+    if let Some(enabled) = tools.builtin.as_ref().and_then(|b| b.enabled.as_ref()) {
+        if config.allowed_tools.is_none() {
+            config.allowed_tools = Some(enabled.clone());
+        }
+    }
+    if let Some(disabled) = tools.builtin.as_ref().and_then(|b| b.disabled.as_ref()) {
+        config.denied_tools.extend(disabled.iter().cloned());
+    }
+    if let Some(arguments) = tools.arguments.as_ref() {
+        config
+            .tool_arguments
+            .extend(arguments.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    if let Some(servers) = tools.mcp.as_ref().and_then(|m| m.servers.as_ref()) {
+        let defs = config.mcp_servers.get_or_insert_with(Vec::new);
+        for server in servers.iter().filter(|s| s.enabled) {
+            defs.retain(|d| mcp_server_name(d) != server.name);
+            defs.push(server.to_server_def());
+        }
+    }
+}
+
+/// Parses a profile's `behavior.approval_policy`: `none`, `destructive_only` (or `destructive`),
+/// `always`.
+fn parse_profile_approval_policy(s: &str) -> Option<crate::helve::ApprovalPolicy> {
+    match s.trim().to_lowercase().replace('-', "_").as_str() {
+        "none" => Some(crate::helve::ApprovalPolicy::None),
+        "destructive_only" | "destructive" => Some(crate::helve::ApprovalPolicy::DestructiveOnly),
+        "always" => Some(crate::helve::ApprovalPolicy::Always),
+        _ => None,
+    }
+}
+
+fn mcp_server_name(def: &env_config::McpServerDef) -> &str {
+    match def {
+        env_config::McpServerDef::Stdio { name, .. }
+        | env_config::McpServerDef::Http { name, .. } => name,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -584,6 +683,7 @@ mod tests {
             cancellation: None,
             thread_id: None,
             agent: None,
+            agent_file: None,
             verbose: false,
             got_adaptive: false,
             display_max_len: 120,
@@ -592,10 +692,19 @@ mod tests {
             mcp_config_path: None,
             output_timestamp: false,
             dry_run: false,
+            role_setting: None,
+            allowed_tools: None,
+            read_only: false,
+            reply_format: None,
+            reply_schema: None,
+            messages: None,
+            history_search: None,
+            locale: None,
             provider: None,
             base_url: None,
             api_key: None,
             provider_type: None,
+            flags: Default::default(),
         }
     }
 
@@ -697,6 +806,7 @@ mod tests {
                     servers: None,
                 }),
                 builtin: None,
+                arguments: None,
             }),
             ..Default::default()
         };
@@ -721,6 +831,74 @@ mod tests {
         assert_eq!(opts.thread_id.as_deref(), Some("t-123"));
     }
 
+    #[test]
+    fn agent_file_profile_sets_policy_tools_and_mcp_servers() {
+        let _lock = crate::env_test_lock().lock().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("agent.yaml");
+        std::fs::write(
+            &path,
+            r#"
+name: triage
+model:
+  name: gpt-4o
+tools:
+  builtin:
+    enabled: [read, ls]
+    disabled: [bash]
+  arguments:
+    jira_search:
+      project: OPS
+  mcp:
+    servers:
+      - name: fs
+        command: npx
+        args: ["-y", "server-filesystem"]
+      - name: off
+        command: nope
+        enabled: false
+behavior:
+  approval_policy: always
+  env_context: true
+"#,
+        )
+        .unwrap();
+        let mut opts = default_opts();
+        opts.agent = Some("dev".to_string());
+        opts.agent_file = Some(path);
+        let (profile, _) = load_profile_from_options(&opts).expect("agent file profile");
+        assert_eq!(profile.name, "triage");
+
+        let mut config = parent_config();
+        config.mcp_servers = Some(vec![env_config::McpServerDef::Stdio {
+            name: "fs".to_string(),
+            command: "old".to_string(),
+            args: vec![],
+            env: Default::default(),
+        }]);
+        apply_profile_to_build_config(&profile, &mut config);
+        assert_eq!(
+            config.approval_policy,
+            Some(crate::helve::ApprovalPolicy::Always)
+        );
+        assert!(config.env_context);
+        assert_eq!(
+            config.allowed_tools,
+            Some(vec!["read".to_string(), "ls".to_string()])
+        );
+        assert_eq!(config.denied_tools, vec!["bash".to_string()]);
+        assert_eq!(
+            config.tool_arguments["jira_search"]["project"],
+            serde_json::json!("OPS")
+        );
+        let servers = config.mcp_servers.unwrap();
+        assert_eq!(servers.len(), 1);
+        assert!(matches!(
+            &servers[0],
+            env_config::McpServerDef::Stdio { command, .. } if command == "npx"
+        ));
+    }
+
     fn parent_config() -> ReactBuildConfig {
         let mut c = ReactBuildConfig::from_env();
         c.model = Some("parent-model".to_string());
@@ -845,6 +1023,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn build_helve_config_layers_run_flags_over_env_flags() {
+        let _lock = crate::env_test_lock().lock().unwrap();
+        let _g = ENV_LOCK.lock().unwrap();
+        let prev = std::env::var("LOOM_FLAGS").ok();
+        std::env::set_var("LOOM_FLAGS", "verify_node,new_compaction");
+
+        let mut opts = default_opts();
+        opts.flags.insert("new_compaction".to_string(), false);
+        let (_, config, _) = build_helve_config(&opts);
+        assert_eq!(config.flags.get("verify_node"), Some(&true));
+        assert_eq!(config.flags.get("new_compaction"), Some(&false));
+
+        match prev {
+            Some(v) => std::env::set_var("LOOM_FLAGS", v),
+            None => std::env::remove_var("LOOM_FLAGS"),
+        }
+    }
+
     #[test]
     fn constants_match() {
         assert_eq!(DEFAULT_WORKING_FOLDER, ".");
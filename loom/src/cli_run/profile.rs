@@ -100,6 +100,11 @@ pub struct ToolsConfig {
     pub builtin: Option<BuiltinToolsConfig>,
     #[serde(default)]
     pub mcp: Option<McpConfig>,
+    /// Arguments set on every call of a tool, by tool name (e.g. `jira_search: { project: OPS }`).
+    /// String values may use `{working_folder}`, `{thread_id}` and `{user_id}`.
+    #[serde(default)]
+    pub arguments:
+        Option<std::collections::HashMap<String, serde_json::Map<String, serde_json::Value>>>,
 }
 
 #[derive(Debug, Clone, Default, Deserialize)]
@@ -134,6 +139,18 @@ fn default_true() -> bool {
     true
 }
 
+impl McpServerConfig {
+    /// Stdio MCP server definition for this entry.
+    pub fn to_server_def(&self) -> env_config::McpServerDef {
+        env_config::McpServerDef::Stdio {
+            name: self.name.clone(),
+            command: self.command.clone(),
+            args: self.args.clone().unwrap_or_default(),
+            env: self.env.clone().unwrap_or_default(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, Deserialize)]
 pub struct ModelConfig {
     #[serde(default)]
@@ -167,6 +184,10 @@ pub struct BehaviorConfig {
     /// Maximum nesting depth for `invoke_agent` calls (default 3).
     #[serde(default)]
     pub max_sub_agent_depth: Option<u32>,
+    /// Put the current date and time, OS, working folder and tool names into the system prompt
+    /// (default: `LOOM_ENV_CONTEXT`).
+    #[serde(default)]
+    pub env_context: Option<bool>,
 }
 
 #[derive(Debug, Clone, Default, Deserialize)]
@@ -203,17 +224,30 @@ fn parse_front_matter(content: &str) -> (&str, Option<String>) {
     (yaml_str, Some(body.to_string()))
 }
 
-/// Resolves base profile path for `extends: name`. Same directory as current path: name.yaml, name.yml, name/config.yaml, name/config.yml, name.md.
+/// Profile file names looked up inside an agent directory, in order.
+const PROFILE_DIR_FILES: &[&str] = &[
+    "config.yaml",
+    "config.yml",
+    "config.md",
+    "agent.yaml",
+    "agent.yml",
+];
+
+/// Resolves base profile path for `extends: name`. Same directory as current path: name.yaml, name.yml, name.md, or a `name/` directory holding one of [`PROFILE_DIR_FILES`].
 fn resolve_extends_path(parent: &Path, extends: &str) -> Option<PathBuf> {
     let with_ext = parent.join(extends);
-    let candidates = [
+    let candidates: Vec<PathBuf> = [
         with_ext.with_extension("yaml"),
         with_ext.with_extension("yml"),
         with_ext.with_extension("md"),
-        parent.join(extends).join("config.yaml"),
-        parent.join(extends).join("config.yml"),
-        parent.join(extends).join("config.md"),
-    ];
+    ]
+    .into_iter()
+    .chain(
+        PROFILE_DIR_FILES
+            .iter()
+            .map(|f| parent.join(extends).join(f)),
+    )
+    .collect();
     for p in &candidates {
         if p.exists() {
             return Some(p.clone());
@@ -226,7 +260,8 @@ fn resolve_extends_path(parent: &Path, extends: &str) -> Option<PathBuf> {
 }
 
 /// Merges base and override. Override wins for simple values and arrays; objects merged recursively.
-/// Special: `tools.builtin.disabled` is combined (base + override, deduped).
+/// Special: `tools.builtin.disabled` is combined (base + override, deduped); `tools.arguments`
+/// is merged per tool.
 fn merge_profiles(mut base: AgentProfile, over: AgentProfile) -> AgentProfile {
     if !over.name.is_empty() {
         base.name = over.name;
@@ -280,7 +315,18 @@ fn merge_tools_config(base: ToolsConfig, over: ToolsConfig) -> ToolsConfig {
         }
     };
     let mcp = over.mcp.or(base.mcp);
-    ToolsConfig { builtin, mcp }
+    let arguments = match (base.arguments, over.arguments) {
+        (Some(mut b), Some(o)) => {
+            b.extend(o);
+            Some(b)
+        }
+        (b, o) => o.or(b),
+    };
+    ToolsConfig {
+        builtin,
+        mcp,
+        arguments,
+    }
 }
 
 fn merge_disabled_lists(a: Option<Vec<String>>, b: Option<Vec<String>>) -> Option<Vec<String>> {
@@ -332,43 +378,48 @@ pub fn load_agent_profile(path: &Path) -> Result<AgentProfile, ProfileError> {
     Ok(profile)
 }
 
-/// Resolve named profile path: project .loom/agents first, then ~/.loom/agents. Supports .yaml, .yml, .md.
+/// Resolve named profile path: project .loom/agents first, then ~/.loom/agents. Supports a
+/// `<name>/` directory holding one of [`PROFILE_DIR_FILES`], or `<name>.yaml`, `.yml`, `.md`.
 pub fn resolve_named_profile(name: &str) -> Option<PathBuf> {
-    let project_agents = PathBuf::from(".loom/agents");
-    let tries = [
-        project_agents.join(name).join("config.yaml"),
-        project_agents.join(name).join("config.yml"),
-        project_agents.join(name).join("config.md"),
-        project_agents.join(format!("{}.yaml", name)),
-        project_agents.join(format!("{}.yml", name)),
-        project_agents.join(format!("{}.md", name)),
+    let roots = [
+        PathBuf::from(".loom/agents"),
+        env_config::home::loom_home().join("agents"),
     ];
-    for p in &tries {
-        if p.exists() {
-            return Some(p.clone());
-        }
-    }
-    let user_agents = env_config::home::loom_home().join("agents");
-    let user_tries = [
-        user_agents.join(name).join("config.yaml"),
-        user_agents.join(name).join("config.yml"),
-        user_agents.join(name).join("config.md"),
-        user_agents.join(format!("{}.yaml", name)),
-        user_agents.join(format!("{}.yml", name)),
-        user_agents.join(format!("{}.md", name)),
-    ];
-    for p in &user_tries {
-        if p.exists() {
-            return Some(p.clone());
-        }
-    }
-    None
+    roots.iter().find_map(|agents| {
+        PROFILE_DIR_FILES
+            .iter()
+            .map(|f| agents.join(name).join(f))
+            .chain(
+                ["yaml", "yml", "md"]
+                    .iter()
+                    .map(|ext| agents.join(format!("{}.{}", name, ext))),
+            )
+            .find(|p| p.exists())
+    })
+}
+
+/// Profile file inside an agent directory (first of [`PROFILE_DIR_FILES`] that exists).
+fn profile_file_in_dir(dir: &Path) -> Option<PathBuf> {
+    PROFILE_DIR_FILES
+        .iter()
+        .map(|f| dir.join(f))
+        .find(|p| p.exists())
 }
 
-/// Load profile from RunOptions when `--agent` / `-P` is set: built-in agents (compile-time) or
-/// resolve_named_profile. When `agent` is unset, returns [`None`] (no implicit default profile).
-/// Returns the loaded profile together with its source (BuiltIn / Project / User).
+/// Load profile from RunOptions: `agent_file` (`--agent-file`) when set, otherwise `--agent` /
+/// `-P` via built-in agents (compile-time) or resolve_named_profile. When neither is set,
+/// returns [`None`] (no implicit default profile). Returns the loaded profile together with its
+/// source (BuiltIn / Project / User).
 pub fn load_profile_from_options(opts: &RunOptions) -> Option<(AgentProfile, ProfileSource)> {
+    if let Some(ref path) = opts.agent_file {
+        return match load_agent_profile(path) {
+            Ok(p) => Some((p, classify_profile_path(path))),
+            Err(e) => {
+                tracing::warn!("agent file: {}", e);
+                None
+            }
+        };
+    }
     let name = opts.agent.as_ref()?;
     if let Some(mut profile) = load_builtin_profile(name) {
         let project_dir = PathBuf::from(".loom/agents").join(name);
@@ -475,17 +526,7 @@ pub fn list_available_profiles() -> Vec<ProfileSummary> {
                     continue;
                 }
                 let profile = if path.is_dir() {
-                    let config = path.join("config.yaml");
-                    if config.exists() {
-                        load_agent_profile(&config).ok()
-                    } else {
-                        let config_yml = path.join("config.yml");
-                        if config_yml.exists() {
-                            load_agent_profile(&config_yml).ok()
-                        } else {
-                            None
-                        }
-                    }
+                    profile_file_in_dir(&path).and_then(|f| load_agent_profile(&f).ok())
                 } else if path.extension().and_then(|e| e.to_str()) == Some("yaml")
                     || path.extension().and_then(|e| e.to_str()) == Some("yml")
                     || path.extension().and_then(|e| e.to_str()) == Some("md")
@@ -544,6 +585,7 @@ mod tests {
             cancellation: None,
             thread_id: None,
             agent: Some("dev".to_string()),
+            agent_file: None,
             verbose: false,
             got_adaptive: false,
             display_max_len: 200,
@@ -552,10 +594,19 @@ mod tests {
             mcp_config_path: None,
             output_timestamp: false,
             dry_run: false,
+            role_setting: None,
+            allowed_tools: None,
+            read_only: false,
+            reply_format: None,
+            reply_schema: None,
+            messages: None,
+            history_search: None,
+            locale: None,
             provider: None,
             base_url: None,
             api_key: None,
             provider_type: None,
+            flags: Default::default(),
         };
         let (profile, source) = load_profile_from_options(&opts).expect("built-in dev profile");
         assert_eq!(profile.name, "dev");
@@ -578,6 +629,7 @@ mod tests {
             cancellation: None,
             thread_id: None,
             agent: Some("agent-builder".to_string()),
+            agent_file: None,
             verbose: false,
             got_adaptive: false,
             display_max_len: 200,
@@ -586,10 +638,19 @@ mod tests {
             mcp_config_path: None,
             output_timestamp: false,
             dry_run: false,
+            role_setting: None,
+            allowed_tools: None,
+            read_only: false,
+            reply_format: None,
+            reply_schema: None,
+            messages: None,
+            history_search: None,
+            locale: None,
             provider: None,
             base_url: None,
             api_key: None,
             provider_type: None,
+            flags: Default::default(),
         };
         let (profile, source) =
             load_profile_from_options(&opts).expect("built-in agent-builder profile");
@@ -700,6 +761,7 @@ You are a helpful assistant.
                     config: Some(PathBuf::from("./mcp.json")),
                     servers: None,
                 }),
+                arguments: None,
             }),
             ..Default::default()
         };
@@ -712,6 +774,7 @@ You are a helpful assistant.
                     disabled: Some(vec!["websearch".to_string()]),
                 }),
                 mcp: None,
+                arguments: None,
             }),
             ..Default::default()
         };
@@ -887,6 +950,7 @@ tools:
             cancellation: None,
             thread_id: None,
             agent: None,
+            agent_file: None,
             verbose: false,
             got_adaptive: false,
             display_max_len: 200,
@@ -895,10 +959,19 @@ tools:
             mcp_config_path: None,
             output_timestamp: false,
             dry_run: false,
+            role_setting: None,
+            allowed_tools: None,
+            read_only: false,
+            reply_format: None,
+            reply_schema: None,
+            messages: None,
+            history_search: None,
+            locale: None,
             provider: None,
             base_url: None,
             api_key: None,
             provider_type: None,
+            flags: Default::default(),
         };
         let result = load_profile_from_options(&opts);
 
@@ -927,6 +1000,7 @@ tools:
             cancellation: None,
             thread_id: None,
             agent: Some("nonexistent-agent-xyz".to_string()),
+            agent_file: None,
             verbose: false,
             got_adaptive: false,
             display_max_len: 200,
@@ -935,10 +1009,19 @@ tools:
             mcp_config_path: None,
             output_timestamp: false,
             dry_run: false,
+            role_setting: None,
+            allowed_tools: None,
+            read_only: false,
+            reply_format: None,
+            reply_schema: None,
+            messages: None,
+            history_search: None,
+            locale: None,
             provider: None,
             base_url: None,
             api_key: None,
             provider_type: None,
+            flags: Default::default(),
         };
         let result = load_profile_from_options(&opts);
         match prev_loom {
@@ -986,10 +1069,12 @@ tools:
                 disabled: None,
             }),
             mcp: None,
+            arguments: None,
         };
         let over = ToolsConfig {
             builtin: None,
             mcp: None,
+            arguments: None,
         };
         let merged = merge_tools_config(base, over);
         assert_eq!(
@@ -1003,6 +1088,7 @@ tools:
         let base = ToolsConfig {
             builtin: None,
             mcp: None,
+            arguments: None,
         };
         let over = ToolsConfig {
             builtin: Some(BuiltinToolsConfig {
@@ -1010,6 +1096,7 @@ tools:
                 disabled: Some(vec!["bash".to_string()]),
             }),
             mcp: None,
+            arguments: None,
         };
         let merged = merge_tools_config(base, over);
         let b = merged.builtin.unwrap();
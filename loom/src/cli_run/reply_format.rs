@@ -0,0 +1,241 @@
+//! Final reply format control (`reply_format` on RunOptions / RunRequest).
+//!
+//! The requested format is appended to the user message as an instruction. For
+//! [`ReplyFormat::Json`] the final reply is also checked: the JSON value is extracted (code
+//! fences and surrounding prose are dropped) and validated against the optional schema. The
+//! run retries once with a correction message before giving up with a format violation.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Format the final reply must follow.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplyFormat {
+    /// Markdown (headings, lists, code fences allowed).
+    Markdown,
+    /// Plain text without markdown syntax.
+    Plain,
+    /// A single JSON value, optionally matching a JSON Schema.
+    Json,
+}
+
+impl std::str::FromStr for ReplyFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Ok(ReplyFormat::Markdown),
+            "plain" | "text" => Ok(ReplyFormat::Plain),
+            "json" => Ok(ReplyFormat::Json),
+            other => Err(format!(
+                "unknown reply format '{}' (expected markdown, plain or json)",
+                other
+            )),
+        }
+    }
+}
+
+impl ReplyFormat {
+    /// Instruction appended to the user message for this format.
+    pub fn instructions(self, schema: Option<&Value>) -> String {
+        match self {
+            ReplyFormat::Markdown => "Format your final answer as Markdown.".to_string(),
+            ReplyFormat::Plain => "Format your final answer as plain text: no Markdown \
+                 headings, emphasis, lists markup or code fences."
+                .to_string(),
+            ReplyFormat::Json => {
+                let mut s = "Your final answer must be a single valid JSON value and nothing \
+                     else: no prose before or after it and no code fences."
+                    .to_string();
+                if let Some(schema) = schema {
+                    s.push_str(" It must conform to this JSON Schema:\n");
+                    s.push_str(&schema.to_string());
+                }
+                s
+            }
+        }
+    }
+
+    /// Appends [`instructions`](Self::instructions) to `message`.
+    pub fn apply_to_message(self, message: &str, schema: Option<&Value>) -> String {
+        format!("{}\n\n{}", message, self.instructions(schema))
+    }
+
+    /// Checks `reply` against this format and returns the normalized reply. For JSON this is
+    /// the compact JSON text of the extracted value; other formats are returned unchanged.
+    pub fn check(self, reply: &str, schema: Option<&Value>) -> Result<String, String> {
+        match self {
+            ReplyFormat::Markdown | ReplyFormat::Plain => Ok(reply.to_string()),
+            ReplyFormat::Json => {
+                let value = extract_json(reply)
+                    .ok_or_else(|| "reply does not contain a valid JSON value".to_string())?;
+                if let Some(schema) = schema {
+                    validate_schema(&value, schema, "$")?;
+                }
+                Ok(value.to_string())
+            }
+        }
+    }
+
+    /// Message for the single retry after [`check`](Self::check) failed.
+    pub fn retry_message(self, reply: &str, violation: &str, schema: Option<&Value>) -> String {
+        format!(
+            "Your previous answer did not follow the required format ({}). \
+             Previous answer:\n{}\n\n{}",
+            violation,
+            reply,
+            self.instructions(schema)
+        )
+    }
+}
+
+/// Parses `reply` as JSON, falling back to the content of a ```json fence or the span from the
+/// first `{`/`[` to the last matching `}`/`]`.
+pub fn extract_json(reply: &str) -> Option<Value> {
+    let trimmed = reply.trim();
+    if let Ok(v) = serde_json::from_str(trimmed) {
+        return Some(v);
+    }
+    if let Some(start) = trimmed.find("```") {
+        let body = &trimmed[start + 3..];
+        let body = body.split_once('\n').map(|(_, rest)| rest).unwrap_or(body);
+        if let Some(end) = body.find("```") {
+            if let Ok(v) = serde_json::from_str(body[..end].trim()) {
+                return Some(v);
+            }
+        }
+    }
+    for (open, close) in [('{', '}'), ('[', ']')] {
+        if let (Some(start), Some(end)) = (trimmed.find(open), trimmed.rfind(close)) {
+            if start < end {
+                if let Ok(v) = serde_json::from_str(&trimmed[start..=end]) {
+                    return Some(v);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Validates `value` against the common subset of JSON Schema: `type`, `enum`, `required`,
+/// `properties`, `additionalProperties: false` and `items`. Other keywords are ignored.
+pub fn validate_schema(value: &Value, schema: &Value, path: &str) -> Result<(), String> {
+    if let Some(ty) = schema.get("type") {
+        let types: Vec<&str> = match ty {
+            Value::String(s) => vec![s.as_str()],
+            Value::Array(a) => a.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| json_type_matches(value, t)) {
+            return Err(format!("{}: expected {}", path, types.join(" or ")));
+        }
+    }
+    if let Some(Value::Array(options)) = schema.get("enum") {
+        if !options.contains(value) {
+            return Err(format!("{}: value is not one of the allowed values", path));
+        }
+    }
+    if let Value::Object(map) = value {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !map.contains_key(key) {
+                    return Err(format!("{}: missing required property '{}'", path, key));
+                }
+            }
+        }
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for (key, child) in map {
+            match properties.and_then(|p| p.get(key)) {
+                Some(child_schema) => {
+                    validate_schema(child, child_schema, &format!("{}.{}", path, key))?
+                }
+                None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                    return Err(format!("{}: unexpected property '{}'", path, key));
+                }
+                None => {}
+            }
+        }
+    }
+    if let (Value::Array(items), Some(item_schema)) = (value, schema.get("items")) {
+        for (i, item) in items.iter().enumerate() {
+            validate_schema(item, item_schema, &format!("{}[{}]", path, i))?;
+        }
+    }
+    Ok(())
+}
+
+fn json_type_matches(value: &Value, ty: &str) -> bool {
+    match ty {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn extract_json_repairs_fences_and_prose() {
+        assert_eq!(extract_json(r#"{"a":1}"#), Some(json!({"a": 1})));
+        assert_eq!(
+            extract_json("Here you go:\n```json\n{\"a\": 1}\n```\nDone."),
+            Some(json!({"a": 1}))
+        );
+        assert_eq!(
+            extract_json("The answer is [1, 2] as requested."),
+            Some(json!([1, 2]))
+        );
+        assert_eq!(extract_json("no json here"), None);
+    }
+
+    #[test]
+    fn validate_schema_reports_path_of_violation() {
+        let schema = json!({
+            "type": "object",
+            "required": ["name", "tags"],
+            "properties": {
+                "name": {"type": "string"},
+                "tags": {"type": "array", "items": {"type": "string"}}
+            },
+            "additionalProperties": false
+        });
+        assert!(validate_schema(&json!({"name": "x", "tags": ["a"]}), &schema, "$").is_ok());
+        assert_eq!(
+            validate_schema(&json!({"name": "x"}), &schema, "$").unwrap_err(),
+            "$: missing required property 'tags'"
+        );
+        assert_eq!(
+            validate_schema(&json!({"name": "x", "tags": [1]}), &schema, "$").unwrap_err(),
+            "$.tags[0]: expected string"
+        );
+        assert!(
+            validate_schema(&json!({"name": "x", "tags": [], "extra": 1}), &schema, "$")
+                .unwrap_err()
+                .contains("unexpected property 'extra'")
+        );
+    }
+
+    #[test]
+    fn json_check_normalizes_reply() {
+        let out = ReplyFormat::Json
+            .check("```json\n{ \"ok\": true }\n```", None)
+            .unwrap();
+        assert_eq!(out, r#"{"ok":true}"#);
+        assert_eq!(
+            ReplyFormat::Plain.check("**bold**", None).unwrap(),
+            "**bold**"
+        );
+        assert_eq!("JSON".parse::<ReplyFormat>(), Ok(ReplyFormat::Json));
+        assert!("yaml".parse::<ReplyFormat>().is_err());
+    }
+}
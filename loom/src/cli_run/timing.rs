@@ -0,0 +1,79 @@
+//! Run timing totals built from a run's [`StreamEvent::Timing`] events.
+
+use crate::protocol::RunTiming;
+use crate::stream::{StreamEvent, TimingKind};
+
+/// Sums timing events into a [`RunTiming`].
+#[derive(Default)]
+pub(crate) struct RunTimer {
+    timing: RunTiming,
+    seen: bool,
+}
+
+impl RunTimer {
+    /// Updates the totals from one stream event; other events are ignored.
+    pub(crate) fn record<S>(&mut self, ev: &StreamEvent<S>) {
+        let StreamEvent::Timing {
+            node_id,
+            kind,
+            duration_ms,
+            ..
+        } = ev
+        else {
+            return;
+        };
+        self.seen = true;
+        let t = &mut self.timing;
+        match kind {
+            TimingKind::Node => *t.nodes.entry(node_id.clone()).or_default() += duration_ms,
+            TimingKind::Prompt => t.prompt_ms += duration_ms,
+            TimingKind::FirstToken => {
+                t.first_token_ms.get_or_insert(*duration_ms);
+            }
+            TimingKind::Llm => t.llm_ms += duration_ms,
+            TimingKind::Tool => t.tool_ms += duration_ms,
+        }
+    }
+
+    /// Totals so far; `None` when no timing event was seen.
+    pub(crate) fn totals(&self) -> Option<RunTiming> {
+        self.seen.then(|| self.timing.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(node_id: &str, kind: TimingKind, duration_ms: u64) -> StreamEvent<()> {
+        StreamEvent::Timing {
+            node_id: node_id.to_string(),
+            kind,
+            name: None,
+            duration_ms,
+            state: None,
+        }
+    }
+
+    #[test]
+    fn sums_timing_events_per_kind_and_node() {
+        let mut timer = RunTimer::default();
+        assert!(timer.totals().is_none());
+        timer.record(&timing("think", TimingKind::Prompt, 3));
+        timer.record(&timing("think", TimingKind::FirstToken, 120));
+        timer.record(&timing("think", TimingKind::Llm, 400));
+        timer.record(&timing("think", TimingKind::Node, 410));
+        timer.record(&timing("act", TimingKind::Tool, 50));
+        timer.record(&timing("act", TimingKind::Node, 52));
+        timer.record(&timing("think", TimingKind::FirstToken, 90));
+        timer.record(&timing("think", TimingKind::Node, 200));
+
+        let totals = timer.totals().unwrap();
+        assert_eq!(totals.first_token_ms, Some(120));
+        assert_eq!(totals.prompt_ms, 3);
+        assert_eq!(totals.llm_ms, 400);
+        assert_eq!(totals.tool_ms, 50);
+        assert_eq!(totals.nodes["think"], 610);
+        assert_eq!(totals.nodes["act"], 52);
+    }
+}
@@ -0,0 +1,170 @@
+//! Compact tool-call transcript built from a run's stream events.
+//!
+//! [`ToolTranscript`] pairs `ToolCall` / `ToolStart` / `ToolEnd` events by call id (or by tool
+//! name when the id is missing) into [`ToolCallRecord`]s, so non-streaming clients can see what
+//! the agent did from the final result alone.
+
+use std::time::Instant;
+
+use serde_json::Value;
+
+use crate::protocol::{ToolCallRecord, ToolCallStatus};
+use crate::stream::StreamEvent;
+
+/// Max characters of the compact argument JSON in [`ToolCallRecord::args_digest`].
+const ARGS_DIGEST_MAX_CHARS: usize = 120;
+/// Max characters of [`ToolCallRecord::result_preview`].
+const RESULT_PREVIEW_MAX_CHARS: usize = 200;
+
+struct Entry {
+    call_id: Option<String>,
+    record: ToolCallRecord,
+    started: Option<Instant>,
+}
+
+/// Accumulates tool calls in the order they were first seen.
+#[derive(Default)]
+pub(crate) struct ToolTranscript {
+    entries: Vec<Entry>,
+}
+
+impl ToolTranscript {
+    /// Updates the transcript from one stream event; other events are ignored.
+    pub(crate) fn record<S>(&mut self, ev: &StreamEvent<S>) {
+        match ev {
+            StreamEvent::ToolCall {
+                call_id,
+                name,
+                arguments,
+            } => {
+                let idx = self.entry(call_id, name);
+                self.entries[idx].record.args_digest = args_digest(arguments);
+            }
+            StreamEvent::ToolStart { call_id, name } => {
+                let idx = self.entry(call_id, name);
+                self.entries[idx].started = Some(Instant::now());
+            }
+            StreamEvent::ToolEnd {
+                call_id,
+                name,
+                result,
+                is_error,
+                ..
+            } => {
+                let idx = self.entry(call_id, name);
+                let entry = &mut self.entries[idx];
+                entry.record.status = if *is_error {
+                    ToolCallStatus::Error
+                } else {
+                    ToolCallStatus::Ok
+                };
+                entry.record.duration_ms = entry
+                    .started
+                    .map(|t| t.elapsed().as_millis() as u64)
+                    .unwrap_or(0);
+                entry.record.result_preview = truncate(result, RESULT_PREVIEW_MAX_CHARS);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the records collected so far.
+    pub(crate) fn records(&self) -> Vec<ToolCallRecord> {
+        self.entries.iter().map(|e| e.record.clone()).collect()
+    }
+
+    /// Index of the still-pending entry for this call, creating one when none matches.
+    fn entry(&mut self, call_id: &Option<String>, name: &str) -> usize {
+        let found = self.entries.iter().rposition(|e| {
+            e.record.status == ToolCallStatus::Pending
+                && match (call_id, &e.call_id) {
+                    (Some(id), Some(other)) => id == other,
+                    _ => e.record.tool == name,
+                }
+        });
+        found.unwrap_or_else(|| {
+            self.entries.push(Entry {
+                call_id: call_id.clone(),
+                record: ToolCallRecord {
+                    tool: name.to_string(),
+                    args_digest: String::new(),
+                    status: ToolCallStatus::Pending,
+                    duration_ms: 0,
+                    result_preview: String::new(),
+                },
+                started: None,
+            });
+            self.entries.len() - 1
+        })
+    }
+}
+
+fn args_digest(arguments: &Value) -> String {
+    let compact = serde_json::to_string(arguments).unwrap_or_default();
+    truncate(&compact, ARGS_DIGEST_MAX_CHARS)
+}
+
+fn truncate(s: &str, max_chars: usize) -> String {
+    let line = s.trim().replace('\n', " ");
+    if line.chars().count() <= max_chars {
+        return line;
+    }
+    let mut out: String = line.chars().take(max_chars).collect();
+    out.push('…');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn pairs_call_start_end_by_call_id() {
+        let mut t = ToolTranscript::default();
+        t.record::<()>(&StreamEvent::ToolCall {
+            call_id: Some("c1".into()),
+            name: "read".into(),
+            arguments: json!({"path": "a.txt"}),
+        });
+        t.record::<()>(&StreamEvent::ToolCall {
+            call_id: Some("c2".into()),
+            name: "read".into(),
+            arguments: json!({"path": "b.txt"}),
+        });
+        t.record::<()>(&StreamEvent::ToolStart {
+            call_id: Some("c2".into()),
+            name: "read".into(),
+        });
+        t.record::<()>(&StreamEvent::ToolEnd {
+            call_id: Some("c2".into()),
+            name: "read".into(),
+            result: "line one\nline two".into(),
+            is_error: false,
+            raw_result: None,
+        });
+        t.record::<()>(&StreamEvent::ToolEnd {
+            call_id: None,
+            name: "bash".into(),
+            result: "denied".into(),
+            is_error: true,
+            raw_result: None,
+        });
+
+        let records = t.records();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].args_digest, r#"{"path":"a.txt"}"#);
+        assert_eq!(records[0].status, ToolCallStatus::Pending);
+        assert_eq!(records[1].status, ToolCallStatus::Ok);
+        assert_eq!(records[1].result_preview, "line one line two");
+        assert_eq!(records[2].tool, "bash");
+        assert_eq!(records[2].status, ToolCallStatus::Error);
+    }
+
+    #[test]
+    fn long_arguments_are_truncated() {
+        let digest = args_digest(&json!({"content": "x".repeat(500)}));
+        assert_eq!(digest.chars().count(), ARGS_DIGEST_MAX_CHARS + 1);
+        assert!(digest.ends_with('…'));
+    }
+}
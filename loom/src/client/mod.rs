@@ -0,0 +1,278 @@
+//! Typed WebSocket client for `loom serve` (feature `client`).
+//!
+//! [`WsClient`] sends [`ClientRequest`]s as JSON text frames and decodes [`ServerResponse`]s in
+//! the encoding the server accepted ([`WireEncoding`]). [`WsClient::run`] starts a run and
+//! returns a [`RunStream`] that yields the run's [`ProtocolEventEnvelope`]s and then its final
+//! [`RunEndResponse`]; an `error` response for the run surfaces as [`ClientError::Server`].
+//! When the connection drops mid-run, [`WsClient::resume_run`] on a new connection follows the
+//! same run from the last event received.
+//!
+//! ```ignore
+//! use loom::client::WsClient;
+//!
+//! let mut client = WsClient::connect("ws://127.0.0.1:8080").await?;
+//! let mut run = client.run(request).await?;
+//! while let Some(event) = run.next_event().await? {
+//!     println!("{}", event.to_value()?);
+//! }
+//! let end = run.finish().await?;
+//! println!("{}", end.reply);
+//! ```
+//!
+//! The server handles one request at a time per connection, so a client runs one request at a
+//! time too; open more connections for concurrent runs.
+
+use futures::{SinkExt, Stream, StreamExt};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+use crate::memory::uuid6;
+use crate::protocol::encoding::decode_binary;
+use crate::protocol::{
+    ClientRequest, EncodingError, ErrorResponse, PingRequest, ProtocolEventEnvelope,
+    ResumeRunRequest, RunEndResponse, RunRequest, ServerResponse, WireEncoding,
+};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Error from a [`WsClient`] call.
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("websocket: {0}")]
+    WebSocket(Box<tokio_tungstenite::tungstenite::Error>),
+    #[error("encoding: {0}")]
+    Encoding(#[from] EncodingError),
+    /// The server answered with an `error` response.
+    #[error("server: {}", .0.error)]
+    Server(ErrorResponse),
+    #[error("connection closed")]
+    Closed,
+    #[error("invalid request: {0}")]
+    InvalidRequest(&'static str),
+}
+
+impl From<tokio_tungstenite::tungstenite::Error> for ClientError {
+    fn from(e: tokio_tungstenite::tungstenite::Error) -> Self {
+        ClientError::WebSocket(Box::new(e))
+    }
+}
+
+impl From<serde_json::Error> for ClientError {
+    fn from(e: serde_json::Error) -> Self {
+        ClientError::Encoding(EncodingError::Json(e))
+    }
+}
+
+/// WebSocket connection to a `loom serve` instance.
+pub struct WsClient {
+    socket: Socket,
+    encoding: WireEncoding,
+}
+
+impl WsClient {
+    /// Connects to `url` (`ws://` or `wss://`) with JSON server messages.
+    pub async fn connect(url: &str) -> Result<Self, ClientError> {
+        Self::connect_with_encoding(url, WireEncoding::Json).await
+    }
+
+    /// Connects to `url` offering `encoding` for server messages. A server that does not
+    /// accept the subprotocol answers in JSON; see [`Self::encoding`].
+    pub async fn connect_with_encoding(
+        url: &str,
+        encoding: WireEncoding,
+    ) -> Result<Self, ClientError> {
+        let mut request = url.into_client_request()?;
+        if encoding != WireEncoding::Json {
+            request.headers_mut().insert(
+                "sec-websocket-protocol",
+                HeaderValue::from_static(encoding.subprotocol()),
+            );
+        }
+        let (socket, response) = tokio_tungstenite::connect_async(request).await?;
+        let accepted = response
+            .headers()
+            .get("sec-websocket-protocol")
+            .and_then(|v| v.to_str().ok());
+        Ok(Self {
+            socket,
+            encoding: WireEncoding::from_subprotocol(accepted),
+        })
+    }
+
+    /// Encoding of the server messages on this connection.
+    pub fn encoding(&self) -> WireEncoding {
+        self.encoding
+    }
+
+    /// Sends one request without waiting for a response.
+    pub async fn send(&mut self, request: &ClientRequest) -> Result<(), ClientError> {
+        let json = serde_json::to_string(request)?;
+        self.socket.send(Message::Text(json)).await?;
+        Ok(())
+    }
+
+    /// Reads the next server message; control frames are skipped.
+    pub async fn recv(&mut self) -> Result<ServerResponse, ClientError> {
+        loop {
+            let message = self.socket.next().await.ok_or(ClientError::Closed)??;
+            match message {
+                Message::Text(text) => return Ok(serde_json::from_str(&text)?),
+                Message::Binary(bytes) => return Ok(decode_binary(&bytes, self.encoding)?),
+                Message::Close(_) => return Err(ClientError::Closed),
+                Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => continue,
+            }
+        }
+    }
+
+    /// Sends a request other than `run` and returns its response. Leftover stream events of an
+    /// earlier run are skipped; an `error` response becomes [`ClientError::Server`].
+    pub async fn request(
+        &mut self,
+        request: &ClientRequest,
+    ) -> Result<ServerResponse, ClientError> {
+        if matches!(request, ClientRequest::Run(_)) {
+            return Err(ClientError::InvalidRequest(
+                "use WsClient::run for run requests",
+            ));
+        }
+        self.send(request).await?;
+        loop {
+            match self.recv().await? {
+                ServerResponse::RunStreamEvent(_) | ServerResponse::RunEnd(_) => continue,
+                ServerResponse::Error(e) => return Err(ClientError::Server(e)),
+                response => return Ok(response),
+            }
+        }
+    }
+
+    /// Sends a `ping` and waits for the `pong`.
+    pub async fn ping(&mut self) -> Result<(), ClientError> {
+        let id = format!("ping-{}", uuid6());
+        self.request(&ClientRequest::Ping(PingRequest { id }))
+            .await?;
+        Ok(())
+    }
+
+    /// Starts a run. Read its events from the returned [`RunStream`] before sending anything
+    /// else on this connection.
+    pub async fn run(&mut self, request: RunRequest) -> Result<RunStream<'_>, ClientError> {
+        let request_id = request.id.clone();
+        self.send(&ClientRequest::Run(request)).await?;
+        Ok(RunStream {
+            client: self,
+            request_id,
+            run_id: None,
+            end: None,
+        })
+    }
+
+    /// Follows run `run_id`, started on a connection that dropped: the server replays its
+    /// events after `after_event_id` (the last one received), then the rest of the run, as for
+    /// [`Self::run`]. The server keeps a disconnected run only for a while after it ended; an
+    /// unknown run is a [`ClientError::Server`].
+    pub async fn resume_run(
+        &mut self,
+        run_id: &str,
+        after_event_id: Option<u64>,
+    ) -> Result<RunStream<'_>, ClientError> {
+        let request_id = format!("resume-{}", uuid6());
+        self.send(&ClientRequest::ResumeRun(ResumeRunRequest {
+            id: request_id.clone(),
+            run_id: run_id.to_string(),
+            after_event_id,
+        }))
+        .await?;
+        Ok(RunStream {
+            client: self,
+            request_id: Some(request_id),
+            run_id: Some(run_id.to_string()),
+            end: None,
+        })
+    }
+
+    /// Closes the connection.
+    pub async fn close(mut self) -> Result<(), ClientError> {
+        self.socket.close(None).await?;
+        Ok(())
+    }
+}
+
+/// Events of one run started by [`WsClient::run`] (or followed by [`WsClient::resume_run`]).
+pub struct RunStream<'a> {
+    client: &'a mut WsClient,
+    /// Id of the `run` / `resume_run` request, which an early `error` may carry instead.
+    request_id: Option<String>,
+    run_id: Option<String>,
+    end: Option<RunEndResponse>,
+}
+
+impl RunStream<'_> {
+    /// Server-assigned run id, known once the first event (or the end) arrived.
+    pub fn run_id(&self) -> Option<&str> {
+        self.run_id.as_deref()
+    }
+
+    /// Next event of the run; `Ok(None)` once the run ended (see [`Self::end`]).
+    pub async fn next_event(&mut self) -> Result<Option<ProtocolEventEnvelope>, ClientError> {
+        if self.end.is_some() {
+            return Ok(None);
+        }
+        loop {
+            match self.client.recv().await? {
+                ServerResponse::RunStreamEvent(r) => {
+                    let run_id = self.run_id.get_or_insert_with(|| r.id.clone());
+                    if *run_id == r.id {
+                        return Ok(Some(r.event));
+                    }
+                }
+                ServerResponse::RunEnd(r) => {
+                    if self.run_id.as_deref().is_none_or(|id| id == r.id) {
+                        self.run_id = Some(r.id.clone());
+                        self.end = Some(r);
+                        return Ok(None);
+                    }
+                }
+                ServerResponse::Error(e) => {
+                    if self.run_id.is_none()
+                        || e.id.is_none()
+                        || e.id == self.run_id
+                        || e.id == self.request_id
+                    {
+                        return Err(ClientError::Server(e));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// The run's events as a [`Stream`]; it ends with the run (or after the first error).
+    pub fn events(
+        &mut self,
+    ) -> impl Stream<Item = Result<ProtocolEventEnvelope, ClientError>> + '_ {
+        futures::stream::unfold((self, false), |(run, failed)| async move {
+            if failed {
+                return None;
+            }
+            match run.next_event().await {
+                Ok(Some(event)) => Some((Ok(event), (run, false))),
+                Ok(None) => None,
+                Err(e) => Some((Err(e), (run, true))),
+            }
+        })
+    }
+
+    /// The final response once the run ended.
+    pub fn end(&self) -> Option<&RunEndResponse> {
+        self.end.as_ref()
+    }
+
+    /// Skips the remaining events and returns the final response.
+    pub async fn finish(mut self) -> Result<RunEndResponse, ClientError> {
+        while self.next_event().await?.is_some() {}
+        self.end.take().ok_or(ClientError::Closed)
+    }
+}
@@ -33,6 +33,7 @@ pub fn execute(cmd: Command, state: &mut dyn ResetState) -> CommandResult {
         }
         Command::Compact { .. }
         | Command::Summarize
+        | Command::Pin { .. }
         | Command::Models { .. }
         | Command::ModelsUse { .. } => CommandResult::PassThrough,
     }
@@ -91,6 +92,24 @@ where
 
             Ok(CommandResult::Reply(content))
         }
+        Command::Pin { text } => {
+            let mut messages = CompactState::messages(state).to_vec();
+            match text {
+                Some(text) => messages.push(Message::pinned_user(text)),
+                None => {
+                    let Some(Message::User(content)) = messages
+                        .iter_mut()
+                        .rev()
+                        .find(|m| matches!(m, Message::User(_)))
+                    else {
+                        return Ok(CommandResult::Reply("Nothing to pin.".into()));
+                    };
+                    *content = std::mem::take(content).pinned();
+                }
+            }
+            state.set_messages(messages);
+            Ok(CommandResult::Reply("Pinned.".into()))
+        }
         Command::Models { .. } | Command::ModelsUse { .. } => Ok(CommandResult::PassThrough),
     }
 }
@@ -5,6 +5,7 @@ pub enum Command {
     ResetContext,
     Compact { instructions: Option<String> },
     Summarize,
+    Pin { text: Option<String> },
     Models { query: Option<String> },
     ModelsUse { model_id: String },
 }
@@ -15,6 +15,13 @@ pub fn parse(text: &str) -> Option<Command> {
             Some(Command::Compact { instructions })
         }
         "/summarize" => Some(Command::Summarize),
+        "/pin" => {
+            let text = trimmed
+                .strip_prefix("/pin")
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty());
+            Some(Command::Pin { text })
+        }
         "/models" => {
             let rest = trimmed
                 .strip_prefix("/models")
@@ -60,6 +67,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_pin_with_and_without_text() {
+        assert_eq!(parse("/pin"), Some(Command::Pin { text: None }));
+        assert_eq!(
+            parse("/pin  only touch src/api "),
+            Some(Command::Pin {
+                text: Some("only touch src/api".into())
+            })
+        );
+        assert_eq!(parse("/pinned"), None);
+    }
+
     #[test]
     fn parse_summarize() {
         assert_eq!(parse("/summarize"), Some(Command::Summarize));
@@ -45,6 +45,7 @@ impl Node<ReActState> for CompactNode {
             message_count_after_last_think: state.message_count_after_last_think,
             max_context_tokens: self.config.max_context_tokens,
             reserve_tokens: self.config.reserve_tokens,
+            tokenizer: self.config.tokenizer.as_deref(),
         };
         let current_tokens = context_window::current_tokens(&overflow_input);
         let overflow = context_window::is_overflow(&overflow_input);
@@ -108,10 +109,16 @@ mod tests {
             approval_result: None,
             usage: None,
             total_usage: None,
+            usage_by_model: Default::default(),
             message_count_after_last_think: None,
             think_count: 0,
             summary: None,
             should_continue: true,
+            reflection_count: 0,
+            answer_revision: 0,
+            confidence: None,
+            approval_memory: Default::default(),
+            remembered_approvals: Default::default(),
         };
         let (out, next) = node.run(state).await.unwrap();
         assert_eq!(out.messages.len(), 1);
@@ -138,10 +145,16 @@ mod tests {
             approval_result: None,
             usage: None,
             total_usage: None,
+            usage_by_model: Default::default(),
             message_count_after_last_think: None,
             think_count: 0,
             summary: None,
             should_continue: true,
+            reflection_count: 0,
+            answer_revision: 0,
+            confidence: None,
+            approval_memory: Default::default(),
+            remembered_approvals: Default::default(),
         };
         let (out, next) = node.run(state).await.unwrap();
         assert_eq!(out.messages.len(), 1);
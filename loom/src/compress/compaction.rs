@@ -12,7 +12,7 @@ use crate::message::Message;
 use crate::tool_source::ToolCallContent;
 
 use super::config::CompactionConfig;
-use super::context_window::estimate_tokens;
+use super::context_window::estimate_tokens_with;
 
 /// Placeholder text used to replace pruned tool results in messages.
 pub const PRUNE_PLACEHOLDER: &str = "[Old tool result cleared]";
@@ -55,7 +55,7 @@ pub fn prune(messages: Vec<Message>, config: &CompactionConfig) -> Vec<Message>
     // Walk from newest to oldest; once total exceeds keep, mark older tool results for pruning
     for (i, m) in messages.iter().enumerate().rev() {
         if is_tool_result_message(m) {
-            let tok = estimate_tokens(std::slice::from_ref(m));
+            let tok = estimate_tokens_with(std::slice::from_ref(m), config.tokenizer.as_deref());
             total += tok;
             if total > config.prune_keep_tokens {
                 pruned += tok;
@@ -109,7 +109,9 @@ pub fn prune(messages: Vec<Message>, config: &CompactionConfig) -> Vec<Message>
 
 /// Summarize earlier messages into one System message via LLM and keep the most recent N as-is.
 ///
-/// Output is `[one summary System message] + [last compact_keep_recent original messages]`.
+/// Output is `[one summary System message] + [pinned earlier messages] + [last
+/// compact_keep_recent original messages]`; pinned messages ([`Message::is_pinned`]) are never
+/// summarized away.
 pub async fn compact(
     messages: &[Message],
     llm: &dyn LlmClient,
@@ -127,7 +129,8 @@ pub async fn compact(
     // Split: older messages to summarize, last `keep` messages to keep verbatim
     let split = messages.len().saturating_sub(keep);
     let (to_summarize, recent) = messages.split_at(split);
-    let estimated_tokens_to_summarize = estimate_tokens(to_summarize);
+    let estimated_tokens_to_summarize =
+        estimate_tokens_with(to_summarize, config.tokenizer.as_deref());
 
     info!(
         to_summarize_count = split,
@@ -145,6 +148,7 @@ pub async fn compact(
     // Prepend one System message with the summary, then the recent messages
     let summary = Message::System(format!("[Summary of earlier conversation]: {}", content));
     let mut out = vec![summary];
+    out.extend(to_summarize.iter().filter(|m| m.is_pinned()).cloned());
     out.extend(recent.iter().cloned());
 
     let input_messages = message_count;
@@ -349,4 +353,24 @@ mod tests {
             matches!(&out[1], Message::User(UserContent::Text(s)) if s.contains("Tool a returned:"))
         );
     }
+
+    #[tokio::test]
+    async fn compact_keeps_pinned_messages_after_the_summary() {
+        let config = CompactionConfig {
+            compact_keep_recent: 1,
+            ..Default::default()
+        };
+        let msgs = vec![
+            Message::pinned_user("never use unsafe"),
+            Message::user("old question"),
+            Message::assistant("old answer"),
+            Message::user("latest"),
+        ];
+        let llm = crate::llm::MockLlm::with_no_tool_calls("the gist");
+        let out = compact(&msgs, &llm, &config).await.unwrap();
+        assert_eq!(out.len(), 3);
+        assert!(matches!(&out[0], Message::System(s) if s.contains("the gist")));
+        assert!(out[1].is_pinned());
+        assert_eq!(out[2].content(), "latest");
+    }
 }
@@ -2,6 +2,10 @@
 //!
 //! Controls when and how to prune tool results and compact conversation history.
 
+use std::sync::Arc;
+
+use crate::model_spec::Tokenizer;
+
 /// Configuration for context compression: pruning and compaction.
 #[derive(Debug, Clone)]
 pub struct CompactionConfig {
@@ -19,6 +23,9 @@ pub struct CompactionConfig {
     pub prune_minimum: Option<u32>,
     /// When compacting, keep this many most recent messages; older ones are summarized.
     pub compact_keep_recent: usize,
+    /// Tokenizer of the model, used for all token counts here; `None` uses the ~4 chars per
+    /// token heuristic. Resolved with [`crate::model_spec::tokenizer_for`] by the runner build.
+    pub tokenizer: Option<Arc<dyn Tokenizer>>,
 }
 
 impl Default for CompactionConfig {
@@ -31,6 +38,7 @@ impl Default for CompactionConfig {
             prune_keep_tokens: 40_000,
             prune_minimum: Some(20_000),
             compact_keep_recent: 20,
+            tokenizer: None,
         }
     }
 }
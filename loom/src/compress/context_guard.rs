@@ -0,0 +1,142 @@
+//! Context-window guard for the think step.
+//!
+//! Before each LLM call the think node estimates the prompt (see [`context_window`]) and checks
+//! it against the model's context limit. When the prompt does not fit, the guard picks a
+//! configured larger-context fallback model for that call, or, when no fallback fits, asks for
+//! the history to be compacted first. This avoids the provider rejecting the request.
+
+use std::sync::Arc;
+
+use crate::llm::LlmClient;
+use crate::state::ReActState;
+
+use super::config::CompactionConfig;
+use super::context_window::{self, ContextWindowCheck};
+
+/// Larger-context model used for a think call whose prompt does not fit the default model.
+#[derive(Clone)]
+pub struct ContextFallback {
+    /// Model id, reported in [`crate::stream::StreamEvent::ModelSwitched`] and usage accounting.
+    pub model: String,
+    /// Context size of the fallback in tokens; `None` when unknown (assumed to fit).
+    pub context_limit: Option<u32>,
+    pub llm: Arc<dyn LlmClient>,
+}
+
+/// What the think node should do before calling the LLM.
+pub enum GuardDecision<'a> {
+    /// The prompt fits the default model.
+    Fits,
+    /// Call the fallback model instead of the default one for this call.
+    Switch(&'a ContextFallback),
+    /// Compact the history, then check again.
+    Compact,
+}
+
+/// Context limits the think node checks each prompt against; see the [module docs](self).
+#[derive(Clone)]
+pub struct ContextGuard {
+    /// Model id of the think node's default LLM.
+    pub model: String,
+    /// Compaction settings used when no fallback fits; `max_context_tokens` is the default
+    /// model's context size and `reserve_tokens` is kept free for the answer.
+    pub compaction: CompactionConfig,
+    pub fallback: Option<ContextFallback>,
+}
+
+impl ContextGuard {
+    /// Guard for `model` with the limits in `compaction` and no fallback.
+    pub fn new(model: impl Into<String>, compaction: CompactionConfig) -> Self {
+        Self {
+            model: model.into(),
+            compaction,
+            fallback: None,
+        }
+    }
+
+    /// Sets the larger-context model to switch to when a prompt does not fit.
+    pub fn with_fallback(
+        mut self,
+        model: impl Into<String>,
+        context_limit: Option<u32>,
+        llm: Arc<dyn LlmClient>,
+    ) -> Self {
+        self.fallback = Some(ContextFallback {
+            model: model.into(),
+            context_limit,
+            llm,
+        });
+        self
+    }
+
+    /// Estimated prompt tokens for the next call (hybrid estimate, see
+    /// [`context_window::current_tokens`]).
+    pub fn prompt_tokens(&self, state: &ReActState) -> u32 {
+        context_window::current_tokens(&ContextWindowCheck {
+            messages: &state.messages,
+            usage: state
+                .usage
+                .as_ref()
+                .map(|u| (u.prompt_tokens, u.completion_tokens)),
+            message_count_after_last_think: state.message_count_after_last_think,
+            max_context_tokens: self.compaction.max_context_tokens,
+            reserve_tokens: self.compaction.reserve_tokens,
+            tokenizer: self.compaction.tokenizer.as_deref(),
+        })
+    }
+
+    /// Decides for a prompt of `prompt_tokens`. With `allow_compaction` false (history already
+    /// compacted) or auto-compaction disabled in the config, a prompt that fits nowhere yields [`GuardDecision::Fits`] and the call is
+    /// left to the provider.
+    pub fn decide(&self, prompt_tokens: u32, allow_compaction: bool) -> GuardDecision<'_> {
+        let needed = prompt_tokens.saturating_add(self.compaction.reserve_tokens);
+        if needed <= self.compaction.max_context_tokens {
+            return GuardDecision::Fits;
+        }
+        match &self.fallback {
+            Some(f) if f.context_limit.is_none_or(|limit| needed <= limit) => {
+                GuardDecision::Switch(f)
+            }
+            _ if allow_compaction && self.compaction.auto => GuardDecision::Compact,
+            _ => GuardDecision::Fits,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockLlm;
+
+    fn guard(max_context_tokens: u32) -> ContextGuard {
+        ContextGuard::new(
+            "small",
+            CompactionConfig {
+                max_context_tokens,
+                reserve_tokens: 100,
+                ..CompactionConfig::default()
+            },
+        )
+    }
+
+    #[test]
+    fn decide_fits_switches_or_compacts() {
+        let llm: Arc<dyn LlmClient> = Arc::new(MockLlm::with_no_tool_calls(""));
+        assert!(matches!(
+            guard(1_000).decide(900, true),
+            GuardDecision::Fits
+        ));
+        assert!(matches!(
+            guard(1_000).decide(950, true),
+            GuardDecision::Compact
+        ));
+        assert!(matches!(
+            guard(1_000).decide(950, false),
+            GuardDecision::Fits
+        ));
+
+        let g = guard(1_000).with_fallback("large", Some(10_000), llm);
+        assert!(matches!(g.decide(950, true), GuardDecision::Switch(f) if f.model == "large"));
+        assert!(matches!(g.decide(20_000, true), GuardDecision::Compact));
+    }
+}
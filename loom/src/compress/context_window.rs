@@ -1,13 +1,32 @@
 //! Token estimation and overflow detection for context window.
 //!
-//! Uses a heuristic (~4 chars per token) and, when available, hybrid strategy
-//! with last LLM usage + delta for messages after last think.
+//! Uses the model's [`Tokenizer`] when one is known, else a heuristic (~4 chars per token),
+//! and, when available, hybrid strategy with last LLM usage + delta for messages after last
+//! think.
 
 use crate::message::{ContentPart, Message, UserContent};
+use crate::model_spec::Tokenizer;
 
 /// Heuristic: approximate characters per token for English/mixed text (used by `estimate_tokens`).
 const CHARS_PER_TOKEN: u32 = 4;
 
+/// Tokens a chat message adds beyond its text (role and separators), counted with a real
+/// tokenizer.
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+
+/// Characters a content part counts as in the heuristic; images, audio, video and files are
+/// not tokenized and count a fixed amount.
+fn media_chars(part: &ContentPart) -> usize {
+    match part {
+        ContentPart::Text { text } => text.len(),
+        ContentPart::ImageUrl { .. } | ContentPart::ImageBase64 { .. } => 4000,
+        ContentPart::AudioBase64 { .. } => 2000,
+        ContentPart::VideoUrl { .. } | ContentPart::VideoBase64 { .. } => 8000,
+        ContentPart::PdfUrl { .. } | ContentPart::PdfBase64 { .. } => 4000,
+        ContentPart::File { .. } => 2000,
+    }
+}
+
 /// Heuristic token estimate: ~4 characters per token.
 pub fn estimate_tokens(messages: &[Message]) -> u32 {
     let total: usize = messages
@@ -16,17 +35,7 @@ pub fn estimate_tokens(messages: &[Message]) -> u32 {
             Message::System(s) => s.len(),
             Message::User(c) => match c {
                 UserContent::Text(s) => s.len(),
-                UserContent::Multimodal(parts) => parts
-                    .iter()
-                    .map(|p| match p {
-                        ContentPart::Text { text } => text.len(),
-                        ContentPart::ImageUrl { .. } | ContentPart::ImageBase64 { .. } => 4000,
-                        ContentPart::AudioBase64 { .. } => 2000,
-                        ContentPart::VideoUrl { .. } | ContentPart::VideoBase64 { .. } => 8000,
-                        ContentPart::PdfUrl { .. } | ContentPart::PdfBase64 { .. } => 4000,
-                        ContentPart::File { .. } => 2000,
-                    })
-                    .sum::<usize>(),
+                UserContent::Multimodal(parts) => parts.iter().map(media_chars).sum::<usize>(),
             },
             Message::Assistant(p) => {
                 p.content.len()
@@ -41,6 +50,40 @@ pub fn estimate_tokens(messages: &[Message]) -> u32 {
     (total / CHARS_PER_TOKEN as usize) as u32
 }
 
+/// Token count of `messages` with `tokenizer` (plus a small per-message overhead), or
+/// [`estimate_tokens`] when `None`. Non-text parts count as in the heuristic.
+pub fn estimate_tokens_with(messages: &[Message], tokenizer: Option<&dyn Tokenizer>) -> u32 {
+    let Some(tokenizer) = tokenizer else {
+        return estimate_tokens(messages);
+    };
+    let total: usize = messages
+        .iter()
+        .map(|m| {
+            let text = match m {
+                Message::System(s) => tokenizer.count(s),
+                Message::User(UserContent::Text(s)) => tokenizer.count(s),
+                Message::User(UserContent::Multimodal(parts)) => parts
+                    .iter()
+                    .map(|p| match p {
+                        ContentPart::Text { text } => tokenizer.count(text),
+                        other => media_chars(other) / CHARS_PER_TOKEN as usize,
+                    })
+                    .sum(),
+                Message::Assistant(p) => {
+                    tokenizer.count(&p.content)
+                        + p.tool_calls
+                            .iter()
+                            .map(|tc| tokenizer.count(&tc.name) + tokenizer.count(&tc.arguments))
+                            .sum::<usize>()
+                }
+                Message::Tool { content, .. } => tokenizer.count(&content.to_display_string()),
+            };
+            text + MESSAGE_OVERHEAD_TOKENS
+        })
+        .sum();
+    total as u32
+}
+
 /// Input for overflow check: only the fields needed to decide if context overflows.
 ///
 /// Constructed by the caller (e.g. from `ReActState` + `CompactionConfig`); this module
@@ -57,6 +100,8 @@ pub struct ContextWindowCheck<'a> {
     pub max_context_tokens: u32,
     /// Tokens to reserve for generation.
     pub reserve_tokens: u32,
+    /// Tokenizer of the model; `None` uses the heuristic (see [`estimate_tokens_with`]).
+    pub tokenizer: Option<&'a dyn Tokenizer>,
 }
 
 /// Current token count for the given context (hybrid when usage + message_count_after_last_think available, else full estimate).
@@ -65,10 +110,10 @@ pub fn current_tokens(input: &ContextWindowCheck<'_>) -> u32 {
     match (input.usage, input.message_count_after_last_think) {
         (Some((prompt, completion)), Some(count)) if count <= input.messages.len() => {
             let base = prompt + completion;
-            let delta = estimate_tokens(&input.messages[count..]);
+            let delta = estimate_tokens_with(&input.messages[count..], input.tokenizer);
             base + delta
         }
-        _ => estimate_tokens(input.messages),
+        _ => estimate_tokens_with(input.messages, input.tokenizer),
     }
 }
 
@@ -141,6 +186,7 @@ mod tests {
             message_count_after_last_think: None,
             max_context_tokens: 100,
             reserve_tokens: 10,
+            tokenizer: None,
         };
         assert!(is_overflow(&input));
     }
@@ -155,6 +201,7 @@ mod tests {
             message_count_after_last_think: None,
             max_context_tokens: 1000,
             reserve_tokens: 10,
+            tokenizer: None,
         };
         assert!(!is_overflow(&input));
     }
@@ -171,7 +218,29 @@ mod tests {
             message_count_after_last_think: Some(1),
             max_context_tokens: 100,
             reserve_tokens: 10,
+            tokenizer: None,
         };
         assert!(!is_overflow(&input));
     }
+
+    #[test]
+    fn estimate_tokens_with_tokenizer_counts_per_message() {
+        // Word tokenizer: 2 + 3 words, plus the per-message overhead for each message.
+        #[derive(Debug)]
+        struct Words;
+        impl Tokenizer for Words {
+            fn name(&self) -> &str {
+                "words"
+            }
+            fn count(&self, text: &str) -> usize {
+                text.split_whitespace().count()
+            }
+        }
+        let msgs = vec![Message::user("hello world"), Message::assistant("a b c")];
+        assert_eq!(
+            estimate_tokens_with(&msgs, Some(&Words)),
+            5 + 2 * MESSAGE_OVERHEAD_TOKENS as u32
+        );
+        assert_eq!(estimate_tokens_with(&msgs, None), estimate_tokens(&msgs));
+    }
 }
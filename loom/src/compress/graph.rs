@@ -97,10 +97,16 @@ mod tests {
             approval_result: None,
             usage: None,
             total_usage: None,
+            usage_by_model: Default::default(),
             message_count_after_last_think: None,
             summary: None,
             think_count: 0,
             should_continue: true,
+            reflection_count: 0,
+            answer_revision: 0,
+            confidence: None,
+            approval_memory: Default::default(),
+            remembered_approvals: Default::default(),
         };
         let out = compiled.invoke(state, None).await.unwrap();
         assert_eq!(out.messages.len(), 1);
@@ -129,10 +135,16 @@ mod tests {
             approval_result: None,
             usage: None,
             total_usage: None,
+            usage_by_model: Default::default(),
             message_count_after_last_think: None,
             summary: None,
             think_count: 1,
             should_continue: true,
+            reflection_count: 0,
+            answer_revision: 0,
+            confidence: None,
+            approval_memory: Default::default(),
+            remembered_approvals: Default::default(),
         };
         let (out, next) = node.run(state).await.unwrap();
         assert_eq!(out.messages.len(), 1);
@@ -0,0 +1,230 @@
+//! History window: bound how much of a checkpointed thread is replayed into the next run.
+//!
+//! Applied when a runner loads a thread from its checkpoint, before the new user message is
+//! appended. Leading system messages (system prompt, earlier compaction summaries) are always
+//! kept; the rest is cut at user-turn boundaries so tool calls and their results stay paired.
+//! The elided prefix can be replaced by a short extractive summary (no LLM call). Pinned
+//! messages ([`Message::is_pinned`]) in the elided prefix are kept, after the summary.
+
+use crate::message::Message;
+
+use super::context_window::estimate_tokens;
+
+/// Prefix of the system message that stands in for elided history.
+pub const ELIDED_HISTORY_PREFIX: &str = "[Earlier conversation omitted]";
+
+/// Max characters quoted from each elided user message in the summary.
+const SUMMARY_QUOTE_MAX_CHARS: usize = 120;
+/// Max elided user messages quoted in the summary (most recent ones).
+const SUMMARY_MAX_QUOTES: usize = 10;
+
+/// Limits on the history a thread carries into a new run. `None` limits are not applied.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HistoryWindow {
+    /// Keep at most this many most recent user turns.
+    pub max_turns: Option<usize>,
+    /// Drop the oldest turns while the kept history exceeds this many (estimated) tokens.
+    /// The most recent turn is always kept.
+    pub max_tokens: Option<u32>,
+    /// When true, elided turns are replaced by a system message listing what the user asked.
+    pub summarize_elided: bool,
+}
+
+impl HistoryWindow {
+    /// Reads `LOOM_HISTORY_MAX_TURNS`, `LOOM_HISTORY_MAX_TOKENS` and `LOOM_HISTORY_SUMMARY`.
+    /// Returns `None` when neither limit is set.
+    pub fn from_env() -> Option<Self> {
+        let max_turns = std::env::var("LOOM_HISTORY_MAX_TURNS")
+            .ok()
+            .and_then(|s| s.trim().parse().ok());
+        let max_tokens = std::env::var("LOOM_HISTORY_MAX_TOKENS")
+            .ok()
+            .and_then(|s| s.trim().parse().ok());
+        if max_turns.is_none() && max_tokens.is_none() {
+            return None;
+        }
+        Some(Self {
+            max_turns,
+            max_tokens,
+            summarize_elided: std::env::var("LOOM_HISTORY_SUMMARY")
+                .ok()
+                .map(|s| matches!(s.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false),
+        })
+    }
+
+    /// Returns the windowed messages. Messages are returned unchanged when within limits.
+    pub fn apply(&self, messages: Vec<Message>) -> Vec<Message> {
+        let head_len = messages
+            .iter()
+            .take_while(|m| matches!(m, Message::System(_)))
+            .count();
+        let turn_starts: Vec<usize> = messages
+            .iter()
+            .enumerate()
+            .skip(head_len)
+            .filter(|(_, m)| is_turn_start(m))
+            .map(|(i, _)| i)
+            .collect();
+        if turn_starts.is_empty() {
+            return messages;
+        }
+
+        let mut first_kept = 0;
+        if let Some(max) = self.max_turns {
+            first_kept = turn_starts.len().saturating_sub(max.max(1));
+        }
+        if let Some(max_tokens) = self.max_tokens {
+            while first_kept + 1 < turn_starts.len()
+                && estimate_tokens(&messages[turn_starts[first_kept]..]) > max_tokens
+            {
+                first_kept += 1;
+            }
+        }
+        let cut = turn_starts[first_kept];
+        if cut == head_len {
+            return messages;
+        }
+
+        let mut messages = messages;
+        let tail = messages.split_off(cut);
+        let (pinned, elided): (Vec<_>, Vec<_>) = messages
+            .split_off(head_len)
+            .into_iter()
+            .partition(Message::is_pinned);
+        tracing::debug!(
+            elided = elided.len(),
+            pinned = pinned.len(),
+            kept = tail.len(),
+            "history window applied"
+        );
+        if self.summarize_elided && !elided.is_empty() {
+            messages.push(Message::system(elided_summary(&elided)));
+        }
+        messages.extend(pinned);
+        messages.extend(tail);
+        messages
+    }
+}
+
+/// A user message that starts a turn (tool results sent as user text do not).
+fn is_turn_start(m: &Message) -> bool {
+    match m {
+        Message::User(c) => {
+            let s = c.as_text();
+            !(s.starts_with("Tool ") && s.contains(" returned: "))
+        }
+        _ => false,
+    }
+}
+
+fn elided_summary(elided: &[Message]) -> String {
+    let asks: Vec<String> = elided
+        .iter()
+        .filter(|m| is_turn_start(m))
+        .map(|m| {
+            let text = m.content().trim().replace('\n', " ");
+            if text.chars().count() > SUMMARY_QUOTE_MAX_CHARS {
+                let cut: String = text.chars().take(SUMMARY_QUOTE_MAX_CHARS).collect();
+                format!("{}…", cut)
+            } else {
+                text
+            }
+        })
+        .collect();
+    let skipped = asks.len().saturating_sub(SUMMARY_MAX_QUOTES);
+    let mut out = format!(
+        "{} {} earlier messages ({} user turns) were dropped from this thread's context.",
+        ELIDED_HISTORY_PREFIX,
+        elided.len(),
+        asks.len()
+    );
+    if !asks.is_empty() {
+        out.push_str(" The user had asked:");
+        if skipped > 0 {
+            out.push_str(&format!("\n- ({} older requests)", skipped));
+        }
+        for ask in &asks[skipped..] {
+            out.push_str("\n- ");
+            out.push_str(ask);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thread(turns: usize) -> Vec<Message> {
+        let mut messages = vec![Message::system("sys")];
+        for i in 0..turns {
+            messages.push(Message::user(format!("question {}", i)));
+            messages.push(Message::assistant(format!("answer {}", i)));
+        }
+        messages
+    }
+
+    #[test]
+    fn max_turns_keeps_system_prompt_and_recent_turns() {
+        let window = HistoryWindow {
+            max_turns: Some(2),
+            ..Default::default()
+        };
+        let out = window.apply(thread(5));
+        assert_eq!(out.len(), 5);
+        assert_eq!(out[0].content(), "sys");
+        assert_eq!(out[1].content(), "question 3");
+        assert_eq!(out[4].content(), "answer 4");
+
+        let unchanged = window.apply(thread(2));
+        assert_eq!(unchanged.len(), 5);
+    }
+
+    #[test]
+    fn max_tokens_drops_oldest_turns_and_summarizes() {
+        let window = HistoryWindow {
+            max_tokens: Some(10),
+            summarize_elided: true,
+            ..Default::default()
+        };
+        let out = window.apply(thread(4));
+        assert_eq!(out[0].content(), "sys");
+        let summary = out[1].content();
+        assert!(summary.starts_with(ELIDED_HISTORY_PREFIX));
+        assert!(summary.contains("question 0"));
+        assert!(!summary.contains("question 3"));
+        assert_eq!(out.last().unwrap().content(), "answer 3");
+    }
+
+    #[test]
+    fn pinned_messages_survive_the_window() {
+        let mut messages = vec![Message::system("sys"), Message::pinned_user("the spec")];
+        messages.extend(thread(3).into_iter().skip(1));
+        let window = HistoryWindow {
+            max_turns: Some(1),
+            summarize_elided: true,
+            ..Default::default()
+        };
+        let out = window.apply(messages);
+        assert!(out[1].content().starts_with(ELIDED_HISTORY_PREFIX));
+        assert!(!out[1].content().contains("the spec"));
+        assert_eq!(out[2].content(), "[Pinned] the spec");
+        assert_eq!(out[3].content(), "question 2");
+    }
+
+    #[test]
+    fn tool_result_messages_do_not_split_a_turn() {
+        let mut messages = thread(1);
+        messages.push(Message::user("do it"));
+        messages.push(Message::user("Tool bash returned: ok"));
+        messages.push(Message::assistant("done"));
+        let window = HistoryWindow {
+            max_turns: Some(1),
+            ..Default::default()
+        };
+        let out = window.apply(messages);
+        assert_eq!(out.len(), 4);
+        assert_eq!(out[1].content(), "do it");
+    }
+}
@@ -1,13 +1,19 @@
 //! Context compression: prune tool results and compact conversation history.
 //!
-//! Used by the ReAct graph to stay within context limits via pruning and LLM summarization.
+//! Used by the ReAct graph to stay within context limits via pruning and LLM summarization,
+//! by the think step to keep each prompt within the model's context ([`ContextGuard`]),
+//! and by the runner to window long checkpointed threads ([`HistoryWindow`]).
 
 pub mod compact_node;
 pub mod compaction;
 pub mod config;
+pub mod context_guard;
 pub mod context_window;
 pub mod graph;
+pub mod history_window;
 pub mod prune_node;
 
 pub use config::CompactionConfig;
+pub use context_guard::{ContextFallback, ContextGuard, GuardDecision};
 pub use graph::{build_graph, CompressionGraphNode};
+pub use history_window::{HistoryWindow, ELIDED_HISTORY_PREFIX};
@@ -71,10 +71,16 @@ mod tests {
             approval_result: None,
             usage: None,
             total_usage: None,
+            usage_by_model: Default::default(),
             message_count_after_last_think: None,
             think_count: 0,
             summary: None,
             should_continue: true,
+            reflection_count: 0,
+            answer_revision: 0,
+            confidence: None,
+            approval_memory: Default::default(),
+            remembered_approvals: Default::default(),
         };
         let (out, next) = node.run(state).await.unwrap();
         assert_eq!(out.messages.len(), 1);
@@ -106,10 +112,16 @@ mod tests {
             approval_result: None,
             usage: None,
             total_usage: None,
+            usage_by_model: Default::default(),
             message_count_after_last_think: None,
             think_count: 0,
             summary: None,
             should_continue: true,
+            reflection_count: 0,
+            answer_revision: 0,
+            confidence: None,
+            approval_memory: Default::default(),
+            remembered_approvals: Default::default(),
         };
         let (out, next) = node.run(state).await.unwrap();
         assert_eq!(out.messages.len(), 2);
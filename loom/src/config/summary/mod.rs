@@ -26,19 +26,28 @@ pub trait ConfigSection: Send + Sync {
     fn section_name(&self) -> &str;
     /// Key-value pairs (no secrets). Keys are `&'static str` for use in display and tests.
     fn entries(&self) -> Vec<(&'static str, String)>;
-    /// Print one line to stderr in the form `[section_name] k1=v1 k2=v2 ...`. Best-effort.
-    fn print_to_stderr(&self) {
+    /// One line in the form `[section_name] k1=v1 k2=v2 ...`, with secrets redacted: values of
+    /// secret-looking keys (see [`env_config::is_secret_key`]) are masked and every other value
+    /// goes through [`env_config::redact_text`] (e.g. a token embedded in a URL).
+    fn summary_line(&self) -> String {
         let entries: Vec<String> = self
             .entries()
             .into_iter()
-            .map(|(k, v)| format!("{}={}", k, v))
+            .map(|(k, v)| {
+                let v = if env_config::is_secret_key(k) {
+                    env_config::mask_value(&v)
+                } else {
+                    env_config::redact_text(&v)
+                };
+                format!("{}={}", k, v)
+            })
             .collect();
-        let _ = writeln!(
-            std::io::stderr(),
-            "[{}] {}",
-            self.section_name(),
-            entries.join(" ")
-        );
+        format!("[{}] {}", self.section_name(), entries.join(" "))
+    }
+
+    /// Print [`summary_line`](ConfigSection::summary_line) to stderr. Best-effort.
+    fn print_to_stderr(&self) {
+        let _ = writeln!(std::io::stderr(), "{}", self.summary_line());
         let _ = std::io::stderr().flush();
     }
 }
@@ -93,7 +102,7 @@ pub trait RunConfigSummarySource: Send + Sync {
     fn llm_section(&self) -> LlmConfigSummary;
     /// Memory section (mode, short_term, long_term, store).
     fn memory_section(&self) -> MemoryConfigSummary;
-    /// Tools section (sources, exa_url).
+    /// Tools section (sources, exa_url, MCP breaker states).
     fn tools_section(&self) -> ToolConfigSummary;
     /// Embedding section (model, api_base).
     fn embedding_section(&self) -> EmbeddingConfigSummary;
@@ -158,6 +167,7 @@ mod tests {
             ToolConfigSummary {
                 sources: vec!["memory".to_string(), "exa".to_string()],
                 exa_url: Some("https://example.com/mcp".to_string()),
+                breakers: vec![],
             }
         }
 
@@ -221,4 +231,25 @@ mod tests {
         section.print_to_stderr();
         assert_eq!(section.entries().len(), 2);
     }
+
+    #[test]
+    fn summary_line_redacts_secrets() {
+        let section = DummySection {
+            name: "dummy",
+            entries: vec![
+                ("model", "glm-5".to_string()),
+                ("api_key", "sk-abcdef123456".to_string()),
+                (
+                    "api_base",
+                    "https://api.example.com/v1?key=sk-abcdef123456".to_string(),
+                ),
+            ],
+        };
+        let line = section.summary_line();
+        assert_eq!(
+            line,
+            "[dummy] model=glm-5 api_key=sk***56 api_base=https://api.example.com/v1?key=sk-***"
+        );
+        assert!(!line.contains("abcdef123456"));
+    }
 }
@@ -5,7 +5,7 @@
 
 use super::ConfigSection;
 
-/// Tool sources summary: list of sources and optional Exa URL.
+/// Tool sources summary: list of sources, optional Exa URL and MCP breaker states.
 ///
 /// Built from `RunConfig` tool_source and mcp_exa_url. Implements [`ConfigSection`].
 pub struct ToolConfigSummary {
@@ -13,6 +13,8 @@ pub struct ToolConfigSummary {
     pub sources: Vec<String>,
     /// Exa MCP URL when Exa is enabled (optional display).
     pub exa_url: Option<String>,
+    /// Circuit breaker state per HTTP MCP server, e.g. `("exa", "open")`. Empty when none.
+    pub breakers: Vec<(String, String)>,
 }
 
 impl ConfigSection for ToolConfigSummary {
@@ -26,6 +28,15 @@ impl ConfigSection for ToolConfigSummary {
         if let Some(ref u) = self.exa_url {
             out.push(("exa_url", u.clone()));
         }
+        if !self.breakers.is_empty() {
+            let breakers = self
+                .breakers
+                .iter()
+                .map(|(name, state)| format!("{}:{}", name, state))
+                .collect::<Vec<_>>()
+                .join(",");
+            out.push(("mcp_breakers", breakers));
+        }
         out
     }
 }
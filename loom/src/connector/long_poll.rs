@@ -0,0 +1,398 @@
+//! Reference long-poll loop: poll the connector, run each inbound message, stream the reply.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use super::{split_message, Connector, ConnectorError, InboundMessage, ReplyBuffer, ReplyUpdate};
+use crate::cli_run::{
+    resolve_model_config, run_agent_with_options, AnyStreamEvent, RunCmd, RunCompletion, RunOptions,
+};
+use crate::protocol::{AgentIdentifier, AgentType, RunRequest};
+
+/// Executes a [`RunRequest`] and returns the final reply text.
+#[async_trait]
+pub trait RequestRunner: Send + Sync {
+    async fn run(
+        &self,
+        request: RunRequest,
+        on_event: Box<dyn FnMut(AnyStreamEvent) + Send>,
+    ) -> Result<String, ConnectorError>;
+}
+
+/// [`RequestRunner`] that runs the agent in-process via [`run_agent_with_options`].
+#[derive(Clone, Debug, Default)]
+pub struct AgentRequestRunner {
+    /// Working folder for requests that do not set one.
+    pub working_folder: Option<PathBuf>,
+}
+
+#[async_trait]
+impl RequestRunner for AgentRequestRunner {
+    async fn run(
+        &self,
+        request: RunRequest,
+        on_event: Box<dyn FnMut(AnyStreamEvent) + Send>,
+    ) -> Result<String, ConnectorError> {
+        let resolved = resolve_model_config(request.model.as_deref()).await;
+        let got_adaptive = request.got_adaptive.unwrap_or(false);
+        let cmd = match request.agent {
+            AgentIdentifier::Type(AgentType::Dup) => RunCmd::Dup,
+            AgentIdentifier::Type(AgentType::Tot) => RunCmd::Tot,
+            AgentIdentifier::Type(AgentType::Got) => RunCmd::Got { got_adaptive },
+            AgentIdentifier::Type(AgentType::React) | AgentIdentifier::Name(_) => RunCmd::React,
+        };
+        let agent = match &request.agent {
+            AgentIdentifier::Name(name) => Some(name.clone()),
+            AgentIdentifier::Type(_) => None,
+        };
+        let opts = RunOptions {
+            message: request.message,
+            working_folder: request
+                .working_folder
+                .map(PathBuf::from)
+                .or_else(|| self.working_folder.clone()),
+            session_id: None,
+            agent,
+            verbose: request.verbose.unwrap_or(false),
+            agent_file: None,
+            got_adaptive,
+            display_max_len: usize::MAX,
+            output_json: false,
+            model: resolved.model,
+            provider: resolved.provider,
+            base_url: resolved.base_url,
+            api_key: resolved.api_key,
+            provider_type: resolved.provider_type,
+            mcp_config_path: None,
+            cancellation: None,
+            thread_id: request.thread_id,
+            output_timestamp: false,
+            dry_run: false,
+            role_setting: None,
+            allowed_tools: None,
+            read_only: false,
+            reply_format: None,
+            reply_schema: None,
+            messages: None,
+            history_search: None,
+            locale: None,
+            flags: Default::default(),
+        };
+        match run_agent_with_options(&opts, &cmd, Some(on_event)).await {
+            Ok(RunCompletion::Finished(result)) => Ok(result.reply),
+            Ok(RunCompletion::Cancelled) => Err(ConnectorError::Run("run cancelled".to_string())),
+            Err(e) => Err(ConnectorError::Run(e.to_string())),
+        }
+    }
+}
+
+/// Timing of [`run_long_poll`] and [`handle_inbound`].
+#[derive(Clone, Debug)]
+pub struct LongPollConfig {
+    /// Minimum time between edits of the in-progress reply (platform rate limits).
+    pub edit_interval: Duration,
+    /// Wait after a failed [`Connector::poll`] before polling again.
+    pub error_backoff: Duration,
+}
+
+impl Default for LongPollConfig {
+    fn default() -> Self {
+        Self {
+            edit_interval: Duration::from_secs(1),
+            error_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Runs one inbound message and streams the reply back through the connector.
+///
+/// The reply is posted on the first visible update, edited at most once per
+/// `config.edit_interval` while the run streams, then replaced with the final reply (split into
+/// extra messages when longer than [`Connector::max_message_len`]). A failed run is reported in
+/// the chat and also returned.
+pub async fn handle_inbound<C>(
+    connector: Arc<C>,
+    runner: &dyn RequestRunner,
+    inbound: InboundMessage,
+    config: &LongPollConfig,
+) -> Result<(), ConnectorError>
+where
+    C: Connector + ?Sized + 'static,
+{
+    let request = connector.run_request(&inbound);
+    let conversation_id = inbound.conversation_id;
+    let (tx, rx) = mpsc::unbounded_channel();
+    let on_event: Box<dyn FnMut(AnyStreamEvent) + Send> = Box::new(move |ev| {
+        if let Some(update) = ReplyUpdate::from_event(&ev) {
+            let _ = tx.send(update);
+        }
+    });
+    let render = tokio::spawn(stream_reply(
+        Arc::clone(&connector),
+        conversation_id.clone(),
+        rx,
+        config.edit_interval,
+    ));
+
+    let result = runner.run(request, on_event).await;
+    let (message_id, buffer) = render
+        .await
+        .map_err(|e| ConnectorError::Run(format!("reply task failed: {}", e)))?;
+
+    let final_text = match &result {
+        Ok(reply) if !reply.trim().is_empty() => reply.clone(),
+        Ok(_) => buffer.text().to_string(),
+        Err(e) => format!("⚠️ {}", e),
+    };
+    finish_reply(
+        connector.as_ref(),
+        &conversation_id,
+        message_id,
+        &final_text,
+    )
+    .await?;
+    result.map(|_| ())
+}
+
+/// Applies reply updates until the run drops its event callback; returns the posted message id.
+async fn stream_reply<C>(
+    connector: Arc<C>,
+    conversation_id: String,
+    mut rx: mpsc::UnboundedReceiver<ReplyUpdate>,
+    edit_interval: Duration,
+) -> (Option<String>, ReplyBuffer)
+where
+    C: Connector + ?Sized,
+{
+    let max_len = connector.max_message_len();
+    let mut buffer = ReplyBuffer::default();
+    let mut message_id: Option<String> = None;
+    let mut shown = String::new();
+    let mut last_edit: Option<Instant> = None;
+    while let Some(update) = rx.recv().await {
+        buffer.apply(update);
+        if last_edit.is_some_and(|t| t.elapsed() < edit_interval) {
+            continue;
+        }
+        let Some(preview) = split_message(&buffer.render(), max_len).into_iter().next() else {
+            continue;
+        };
+        if preview == shown {
+            continue;
+        }
+        let outcome = match &message_id {
+            Some(id) => connector.edit(&conversation_id, id, &preview).await,
+            None => connector.send(&conversation_id, &preview).await.map(|id| {
+                message_id = Some(id);
+            }),
+        };
+        if let Err(e) = outcome {
+            tracing::warn!(conversation_id = %conversation_id, "connector reply update: {}", e);
+        }
+        shown = preview;
+        last_edit = Some(Instant::now());
+    }
+    (message_id, buffer)
+}
+
+/// Writes the final reply: edits the streamed message with the first part, sends the rest.
+async fn finish_reply<C>(
+    connector: &C,
+    conversation_id: &str,
+    message_id: Option<String>,
+    text: &str,
+) -> Result<(), ConnectorError>
+where
+    C: Connector + ?Sized,
+{
+    let mut parts = split_message(text, connector.max_message_len()).into_iter();
+    let Some(first) = parts.next() else {
+        return Ok(());
+    };
+    match message_id {
+        Some(id) => connector.edit(conversation_id, &id, &first).await?,
+        None => {
+            connector.send(conversation_id, &first).await?;
+        }
+    }
+    for part in parts {
+        connector.send(conversation_id, &part).await?;
+    }
+    Ok(())
+}
+
+/// Polls `connector` until `cancel` fires and runs every inbound message.
+///
+/// Messages run concurrently across conversations and in arrival order within one
+/// conversation (one worker per conversation), so a thread never has two runs at once.
+pub async fn run_long_poll<C>(
+    connector: Arc<C>,
+    runner: Arc<dyn RequestRunner>,
+    config: LongPollConfig,
+    cancel: CancellationToken,
+) where
+    C: Connector + ?Sized + 'static,
+{
+    let config = Arc::new(config);
+    let mut lanes: HashMap<String, mpsc::UnboundedSender<InboundMessage>> = HashMap::new();
+    loop {
+        let batch = tokio::select! {
+            _ = cancel.cancelled() => break,
+            batch = connector.poll() => batch,
+        };
+        let messages = match batch {
+            Ok(messages) => messages,
+            Err(e) => {
+                tracing::warn!(connector = connector.name(), "connector poll: {}", e);
+                tokio::select! {
+                    _ = cancel.cancelled() => break,
+                    _ = tokio::time::sleep(config.error_backoff) => continue,
+                }
+            }
+        };
+        for inbound in messages {
+            let inbound = match lanes.get(&inbound.conversation_id) {
+                Some(lane) => match lane.send(inbound) {
+                    Ok(()) => continue,
+                    Err(mpsc::error::SendError(inbound)) => inbound,
+                },
+                None => inbound,
+            };
+            let (tx, rx) = mpsc::unbounded_channel();
+            lanes.insert(inbound.conversation_id.clone(), tx.clone());
+            let _ = tx.send(inbound);
+            tokio::spawn(run_conversation(
+                Arc::clone(&connector),
+                Arc::clone(&runner),
+                Arc::clone(&config),
+                rx,
+            ));
+        }
+    }
+}
+
+/// Runs one conversation's messages in order until its lane is dropped.
+async fn run_conversation<C>(
+    connector: Arc<C>,
+    runner: Arc<dyn RequestRunner>,
+    config: Arc<LongPollConfig>,
+    mut rx: mpsc::UnboundedReceiver<InboundMessage>,
+) where
+    C: Connector + ?Sized + 'static,
+{
+    while let Some(inbound) = rx.recv().await {
+        let conversation_id = inbound.conversation_id.clone();
+        if let Err(e) =
+            handle_inbound(Arc::clone(&connector), runner.as_ref(), inbound, &config).await
+        {
+            tracing::warn!(conversation_id = %conversation_id, "connector run: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::{MessageChunk, StreamEvent, StreamMetadata};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingConnector {
+        log: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Connector for RecordingConnector {
+        fn name(&self) -> &str {
+            "test"
+        }
+
+        async fn poll(&self) -> Result<Vec<InboundMessage>, ConnectorError> {
+            Ok(vec![])
+        }
+
+        async fn send(&self, conversation_id: &str, text: &str) -> Result<String, ConnectorError> {
+            let mut log = self.log.lock().unwrap();
+            log.push(format!("send {} {}", conversation_id, text));
+            Ok(format!("m{}", log.len()))
+        }
+
+        async fn edit(
+            &self,
+            _conversation_id: &str,
+            message_id: &str,
+            text: &str,
+        ) -> Result<(), ConnectorError> {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("edit {} {}", message_id, text));
+            Ok(())
+        }
+
+        fn max_message_len(&self) -> usize {
+            16
+        }
+    }
+
+    struct ScriptedRunner;
+
+    #[async_trait]
+    impl RequestRunner for ScriptedRunner {
+        async fn run(
+            &self,
+            request: RunRequest,
+            mut on_event: Box<dyn FnMut(AnyStreamEvent) + Send>,
+        ) -> Result<String, ConnectorError> {
+            assert_eq!(request.thread_id.as_deref(), Some("test_chat1"));
+            on_event(AnyStreamEvent::React(StreamEvent::Messages {
+                chunk: MessageChunk::message("Hello"),
+                metadata: StreamMetadata {
+                    loom_node: "think".to_string(),
+                    namespace: None,
+                },
+            }));
+            tokio::task::yield_now().await;
+            on_event(AnyStreamEvent::React(StreamEvent::ToolStart {
+                call_id: None,
+                name: "bash".to_string(),
+            }));
+            tokio::task::yield_now().await;
+            Ok("Hello world, this is long".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_inbound_streams_then_finalizes_split_reply() {
+        let connector = Arc::new(RecordingConnector::default());
+        let config = LongPollConfig {
+            edit_interval: Duration::ZERO,
+            ..LongPollConfig::default()
+        };
+        handle_inbound(
+            Arc::clone(&connector),
+            &ScriptedRunner,
+            InboundMessage::text("chat1", "hi"),
+            &config,
+        )
+        .await
+        .unwrap();
+
+        let log = connector.log.lock().unwrap().clone();
+        assert_eq!(log.first().map(String::as_str), Some("send chat1 Hello"));
+        assert!(log.contains(&"edit m1 Hello\n\n🔧 bash…".to_string()));
+        assert_eq!(
+            &log[log.len() - 2..],
+            &[
+                "edit m1 Hello world,".to_string(),
+                "send chat1 this is long".to_string()
+            ]
+        );
+    }
+}
@@ -0,0 +1,264 @@
+//! Chat connectors: bridge a chat platform (Telegram, Slack, ...) to agent runs.
+//!
+//! A [`Connector`] turns inbound platform messages into [`RunRequest`]s and shows the run's
+//! stream events as one reply message that is edited in place while the agent works.
+//! [`run_long_poll`] is the reference loop for bots that fetch updates by long polling; a bot
+//! only implements [`Connector::poll`], [`Connector::send`] and [`Connector::edit`].
+
+mod long_poll;
+
+pub use long_poll::{
+    handle_inbound, run_long_poll, AgentRequestRunner, LongPollConfig, RequestRunner,
+};
+
+use async_trait::async_trait;
+
+use crate::cli_run::AnyStreamEvent;
+use crate::message::UserContent;
+use crate::protocol::{AgentIdentifier, AgentType, RunRequest};
+use crate::stream::{MessageChunkKind, StreamEvent};
+
+/// Default [`Connector::max_message_len`] (Telegram's text message limit).
+pub const DEFAULT_MAX_MESSAGE_LEN: usize = 4096;
+
+/// One message received from the chat platform.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InboundMessage {
+    /// Platform conversation (chat / channel) id; the reply is posted here.
+    pub conversation_id: String,
+    /// Platform id of the inbound message, when the platform has one.
+    pub message_id: Option<String>,
+    /// Platform id of the sender, when the platform has one.
+    pub sender_id: Option<String>,
+    /// Message text and attachments.
+    pub content: UserContent,
+}
+
+impl InboundMessage {
+    /// Text-only inbound message.
+    pub fn text(conversation_id: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            conversation_id: conversation_id.into(),
+            message_id: None,
+            sender_id: None,
+            content: UserContent::Text(text.into()),
+        }
+    }
+}
+
+/// Error from a connector's transport or from the run it drives.
+#[derive(Debug, thiserror::Error)]
+pub enum ConnectorError {
+    #[error("transport: {0}")]
+    Transport(String),
+    #[error("run: {0}")]
+    Run(String),
+}
+
+/// Chat platform adapter.
+///
+/// Inbound: [`Self::poll`] returns new messages, [`Self::run_request`] maps each one to a
+/// [`RunRequest`]. Outbound: the reply is posted with [`Self::send`] and then updated with
+/// [`Self::edit`] as stream events arrive (see [`ReplyBuffer`]).
+#[async_trait]
+pub trait Connector: Send + Sync {
+    /// Short platform name (e.g. "telegram"); prefixes thread ids.
+    fn name(&self) -> &str;
+
+    /// Waits for the next batch of inbound messages (long poll). An empty batch is fine.
+    async fn poll(&self) -> Result<Vec<InboundMessage>, ConnectorError>;
+
+    /// Posts a new message and returns its platform id for later [`Self::edit`] calls.
+    async fn send(&self, conversation_id: &str, text: &str) -> Result<String, ConnectorError>;
+
+    /// Replaces the text of a message previously posted with [`Self::send`].
+    async fn edit(
+        &self,
+        conversation_id: &str,
+        message_id: &str,
+        text: &str,
+    ) -> Result<(), ConnectorError>;
+
+    /// Max characters of one outbound message; longer replies are split.
+    fn max_message_len(&self) -> usize {
+        DEFAULT_MAX_MESSAGE_LEN
+    }
+
+    /// Thread id for a conversation: `{name}_{conversation_id}`, so each chat keeps its history.
+    fn thread_id(&self, inbound: &InboundMessage) -> String {
+        format!("{}_{}", self.name(), inbound.conversation_id)
+    }
+
+    /// Maps an inbound message to the run request executed for it (ReAct on [`Self::thread_id`]).
+    fn run_request(&self, inbound: &InboundMessage) -> RunRequest {
+        RunRequest {
+            id: inbound.message_id.clone(),
+            message: inbound.content.clone(),
+            agent: AgentIdentifier::Type(AgentType::React),
+            thread_id: Some(self.thread_id(inbound)),
+            workspace_id: None,
+            working_folder: None,
+            got_adaptive: None,
+            verbose: None,
+            model: None,
+            reply_format: None,
+            reply_schema: None,
+            messages: None,
+            read_only: None,
+            locale: None,
+            pin: None,
+            flags: Default::default(),
+        }
+    }
+}
+
+/// Stream event reduced to what a chat reply shows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReplyUpdate {
+    /// Assistant reply text chunk.
+    Text(String),
+    /// A tool started running.
+    ToolStart { name: String },
+    /// A tool finished.
+    ToolEnd { name: String, is_error: bool },
+}
+
+impl ReplyUpdate {
+    /// Extracts the reply-relevant part of an agent stream event; other events return `None`.
+    pub fn from_event(ev: &AnyStreamEvent) -> Option<Self> {
+        match ev {
+            AnyStreamEvent::React(ev) => Self::from_stream_event(ev),
+            AnyStreamEvent::Dup(ev) => Self::from_stream_event(ev),
+            AnyStreamEvent::Tot(ev) => Self::from_stream_event(ev),
+            AnyStreamEvent::Got(ev) => Self::from_stream_event(ev),
+        }
+    }
+
+    fn from_stream_event<S>(ev: &StreamEvent<S>) -> Option<Self> {
+        match ev {
+            StreamEvent::Messages { chunk, .. }
+                if chunk.kind == MessageChunkKind::Message && !chunk.content.is_empty() =>
+            {
+                Some(Self::Text(chunk.content.clone()))
+            }
+            StreamEvent::ToolStart { name, .. } => Some(Self::ToolStart { name: name.clone() }),
+            StreamEvent::ToolEnd { name, is_error, .. } => Some(Self::ToolEnd {
+                name: name.clone(),
+                is_error: *is_error,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Accumulates [`ReplyUpdate`]s into the text of the in-progress reply message.
+#[derive(Clone, Debug, Default)]
+pub struct ReplyBuffer {
+    text: String,
+    running_tool: Option<String>,
+    failed_tools: Vec<String>,
+}
+
+impl ReplyBuffer {
+    pub fn apply(&mut self, update: ReplyUpdate) {
+        match update {
+            ReplyUpdate::Text(chunk) => self.text.push_str(&chunk),
+            ReplyUpdate::ToolStart { name } => {
+                if !self.text.is_empty() && !self.text.ends_with('\n') {
+                    self.text.push_str("\n\n");
+                }
+                self.running_tool = Some(name);
+            }
+            ReplyUpdate::ToolEnd { name, is_error } => {
+                if self.running_tool.as_deref() == Some(name.as_str()) {
+                    self.running_tool = None;
+                }
+                if is_error {
+                    self.failed_tools.push(name);
+                }
+            }
+        }
+    }
+
+    /// Reply text streamed so far, without status lines.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Text to show while the run is in progress: reply so far plus a tool status line.
+    pub fn render(&self) -> String {
+        let mut out = self.text.trim_end().to_string();
+        let status = match &self.running_tool {
+            Some(name) => Some(format!("🔧 {}…", name)),
+            None if !self.failed_tools.is_empty() => {
+                Some(format!("⚠️ failed: {}", self.failed_tools.join(", ")))
+            }
+            None => None,
+        };
+        if let Some(status) = status {
+            if !out.is_empty() {
+                out.push_str("\n\n");
+            }
+            out.push_str(&status);
+        }
+        out
+    }
+}
+
+/// Splits `text` into parts of at most `max_chars` characters, preferring line breaks, then spaces.
+pub fn split_message(text: &str, max_chars: usize) -> Vec<String> {
+    let max_chars = max_chars.max(1);
+    let mut parts = Vec::new();
+    let mut rest = text;
+    while rest.chars().count() > max_chars {
+        let limit = rest
+            .char_indices()
+            .nth(max_chars)
+            .map(|(i, _)| i)
+            .unwrap_or(rest.len());
+        let head = &rest[..limit];
+        let (cut, skip) = match head.rfind('\n').or_else(|| head.rfind(' ')) {
+            Some(i) if i > 0 => (i, 1),
+            _ => (limit, 0),
+        };
+        parts.push(rest[..cut].to_string());
+        rest = &rest[cut + skip..];
+    }
+    if !rest.is_empty() {
+        parts.push(rest.to_string());
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_message_prefers_line_breaks_and_respects_char_limit() {
+        assert_eq!(split_message("", 10), Vec::<String>::new());
+        assert_eq!(split_message("short", 10), vec!["short"]);
+        assert_eq!(
+            split_message("line one\nline two", 12),
+            vec!["line one", "line two"]
+        );
+        let parts = split_message("ééééé", 2);
+        assert_eq!(parts, vec!["éé", "éé", "é"]);
+    }
+
+    #[test]
+    fn reply_buffer_shows_running_tool_then_clears_it() {
+        let mut buffer = ReplyBuffer::default();
+        buffer.apply(ReplyUpdate::Text("Let me check.".into()));
+        buffer.apply(ReplyUpdate::ToolStart {
+            name: "web_fetcher".into(),
+        });
+        assert_eq!(buffer.render(), "Let me check.\n\n🔧 web_fetcher…");
+        buffer.apply(ReplyUpdate::ToolEnd {
+            name: "web_fetcher".into(),
+            is_error: false,
+        });
+        buffer.apply(ReplyUpdate::Text("It is sunny.".into()));
+        assert_eq!(buffer.render(), "Let me check.\n\nIt is sunny.");
+    }
+}
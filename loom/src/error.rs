@@ -2,6 +2,8 @@
 //!
 //! Used by `Agent::run` and all agents that implement the minimal Agent trait.
 
+use std::time::Duration;
+
 use thiserror::Error;
 
 use crate::graph::GraphInterrupt;
@@ -31,6 +33,46 @@ pub enum AgentError {
     /// LLM returned empty response after all retries exhausted.
     #[error("LLM returned empty response after {retries} retries")]
     EmptyLlmResponse { retries: u32 },
+
+    /// Provider rejected the request with a rate limit; `retry_after` is the wait it asked for.
+    #[error("rate limited by provider: {message}")]
+    RateLimited {
+        retry_after: Option<Duration>,
+        message: String,
+    },
+
+    /// Prompt is longer than the model's context window.
+    #[error("context length exceeded: {0}")]
+    ContextLengthExceeded(String),
+
+    /// Provider's content filter blocked the request or the response.
+    #[error("blocked by content filter: {0}")]
+    ContentFiltered(String),
+
+    /// Provider rejected the API key or credentials.
+    #[error("provider authentication failed: {0}")]
+    AuthFailed(String),
+
+    /// The run's state exceeded its size limits (see `graph::StateLimits`) and could not be
+    /// compacted to fit.
+    #[error("state too large: {0}")]
+    StateTooLarge(String),
+}
+
+impl AgentError {
+    /// Stable code for provider errors and oversized state, sent to clients with the error
+    /// message (`rate_limited`, `context_length_exceeded`, `content_filtered`, `auth_failed`,
+    /// `state_too_large`).
+    pub fn code(&self) -> Option<&'static str> {
+        match self {
+            AgentError::RateLimited { .. } => Some("rate_limited"),
+            AgentError::ContextLengthExceeded(_) => Some("context_length_exceeded"),
+            AgentError::ContentFiltered(_) => Some("content_filtered"),
+            AgentError::AuthFailed(_) => Some("auth_failed"),
+            AgentError::StateTooLarge(_) => Some("state_too_large"),
+            _ => None,
+        }
+    }
 }
 
 impl From<GraphInterrupt> for AgentError {
@@ -68,4 +110,28 @@ mod tests {
         );
         assert!(s.contains("test"), "Debug should contain message: {}", s);
     }
+
+    /// **Scenario**: Provider error variants carry a code; other variants do not.
+    #[test]
+    fn agent_error_codes_for_provider_errors() {
+        let rate = AgentError::RateLimited {
+            retry_after: Some(Duration::from_secs(2)),
+            message: "slow down".to_string(),
+        };
+        assert_eq!(rate.code(), Some("rate_limited"));
+        assert_eq!(rate.to_string(), "rate limited by provider: slow down");
+        assert_eq!(
+            AgentError::ContextLengthExceeded("x".into()).code(),
+            Some("context_length_exceeded")
+        );
+        assert_eq!(
+            AgentError::ContentFiltered("x".into()).code(),
+            Some("content_filtered")
+        );
+        assert_eq!(
+            AgentError::AuthFailed("x".into()).code(),
+            Some("auth_failed")
+        );
+        assert_eq!(AgentError::ExecutionFailed("x".into()).code(), None);
+    }
 }
@@ -37,7 +37,11 @@ where
                 },
         } => json!({
             "Messages": {
-                "chunk": { "content": chunk.content, "kind": format!("{:?}", chunk.kind) },
+                "chunk": {
+                    "content": chunk.content,
+                    "kind": format!("{:?}", chunk.kind),
+                    "revision": chunk.revision
+                },
                 "metadata": { "loom_node": loom_node, "namespace": namespace }
             }
         }),
@@ -99,8 +103,14 @@ where
             node_id,
             nodes_added,
             edges_added,
+            denied,
         } => json!({
-            "GotExpand": { "node_id": node_id, "nodes_added": nodes_added, "edges_added": edges_added }
+            "GotExpand": {
+                "node_id": node_id,
+                "nodes_added": nodes_added,
+                "edges_added": edges_added,
+                "denied": denied
+            }
         }),
         StreamEvent::Usage {
             prompt_tokens,
@@ -114,6 +124,10 @@ where
                 "total_tokens": total_tokens
             }
         }),
+        StreamEvent::FinishReason { reason } => json!({
+            "FinishReason": { "reason": reason.as_str() }
+        }),
+        StreamEvent::GraphProgress(progress) => json!({ "GraphProgress": progress }),
         StreamEvent::ToolCallChunk {
             call_id,
             name,
@@ -138,6 +152,13 @@ where
         } => json!({
             "ToolOutput": { "call_id": call_id, "name": name, "content": content }
         }),
+        StreamEvent::ToolOutputChunk {
+            call_id,
+            name,
+            content,
+        } => json!({
+            "ToolOutputChunk": { "call_id": call_id, "name": name, "content": content }
+        }),
         StreamEvent::ToolEnd {
             call_id,
             name,
@@ -160,6 +181,44 @@ where
         } => json!({
             "ToolApproval": { "call_id": call_id, "name": name, "arguments": arguments }
         }),
+        StreamEvent::ToolsRefreshed { tools } => json!({
+            "ToolsRefreshed": { "tools": tools }
+        }),
+        StreamEvent::ModelSwitched {
+            from,
+            to,
+            prompt_tokens,
+            context_limit,
+        } => json!({
+            "ModelSwitched": {
+                "from": from,
+                "to": to,
+                "prompt_tokens": prompt_tokens,
+                "context_limit": context_limit
+            }
+        }),
+        StreamEvent::AnswerRevised { reason, revision } => json!({
+            "AnswerRevised": { "reason": reason, "revision": revision }
+        }),
+        StreamEvent::Timing {
+            node_id,
+            kind,
+            name,
+            duration_ms,
+            state,
+        } => json!({
+            "Timing": {
+                "node_id": node_id,
+                "kind": kind.as_str(),
+                "name": name,
+                "duration_ms": duration_ms,
+                "state_bytes": state.map(|s| s.bytes),
+                "message_count": state.map(|s| s.messages)
+            }
+        }),
+        StreamEvent::ThreadSummary { title, summary } => json!({
+            "ThreadSummary": { "title": title, "summary": summary }
+        }),
     };
     Ok(obj)
 }
@@ -353,6 +412,7 @@ mod tests {
             node_id: "n1".to_string(),
             nodes_added: 2,
             edges_added: 3,
+            denied: None,
         };
         let v = stream_event_to_format_a(&ev).unwrap();
         assert_eq!(v["GotExpand"]["nodes_added"], 2);
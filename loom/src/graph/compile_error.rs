@@ -1,7 +1,9 @@
 //! Graph compilation error.
 //!
 //! Returned by `StateGraph::compile` when edges reference unknown nodes or
-//! do not form a single linear chain from START to END.
+//! do not form a single linear chain from START to END. When a graph has several problems,
+//! they are returned together as [`CompilationError::Diagnostics`] (see
+//! [`GraphDiagnostics`](super::GraphDiagnostics)).
 
 use thiserror::Error;
 
@@ -35,6 +37,44 @@ pub enum CompilationError {
     /// A value in a conditional path_map is not a valid node id or END.
     #[error("conditional path_map invalid target: {0}")]
     InvalidConditionalPathMap(String),
+
+    /// A route rule names a source or target that is not a node id (or END for targets).
+    #[error("route rule references unknown node: {0}")]
+    InvalidRouteRule(String),
+
+    /// Weights of a weighted edge are negative, not finite, or all zero.
+    #[error("invalid edge weights from {0}")]
+    InvalidEdgeWeights(String),
+
+    /// No route from START reaches the node. Reported as a warning by `StateGraph::validate`.
+    #[error("node not reachable from START: {0}")]
+    UnreachableNode(String),
+
+    /// Nodes (sorted) that route among themselves with no route leading to END.
+    #[error("cycle with no route to END: {}", .0.join(", "))]
+    CycleWithoutExit(Vec<String>),
+
+    /// Several problems found at once, in the order checked.
+    #[error("{} graph errors: {}", .0.len(), join_errors(.0))]
+    Diagnostics(Vec<CompilationError>),
+}
+
+impl CompilationError {
+    /// The individual problems: the list of [`Self::Diagnostics`], or this error alone.
+    pub fn problems(&self) -> &[CompilationError] {
+        match self {
+            Self::Diagnostics(errors) => errors,
+            other => std::slice::from_ref(other),
+        }
+    }
+}
+
+fn join_errors(errors: &[CompilationError]) -> String {
+    errors
+        .iter()
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
 }
 
 #[cfg(test)]
@@ -90,4 +130,18 @@ mod tests {
         );
         assert!(s.contains("reason"), "Display should contain reason: {}", s);
     }
+
+    /// **Scenario**: Diagnostics lists every problem; problems() flattens single errors too.
+    #[test]
+    fn compilation_error_diagnostics_lists_all_problems() {
+        let err = CompilationError::Diagnostics(vec![
+            CompilationError::MissingStart,
+            CompilationError::CycleWithoutExit(vec!["a".to_string(), "b".to_string()]),
+        ]);
+        let s = err.to_string();
+        assert!(s.starts_with("2 graph errors"), "{}", s);
+        assert!(s.contains("START") && s.contains("a, b"), "{}", s);
+        assert_eq!(err.problems().len(), 2);
+        assert_eq!(CompilationError::MissingEnd.problems().len(), 1);
+    }
 }
@@ -19,14 +19,21 @@ use crate::error::AgentError;
 use crate::memory::{Checkpoint, CheckpointSource, Checkpointer, RunnableConfig, Store};
 use crate::stream::{StreamEvent, StreamMode};
 
+use super::compile_error::CompilationError;
 use super::interrupt::InterruptHandler;
 use super::logging::{
     log_graph_complete, log_graph_error, log_graph_start, log_node_complete, log_node_start,
     log_node_state, log_state_update,
 };
+use super::middleware_stack::NodeMiddlewareStack;
 use super::node_middleware::NodeMiddleware;
 use super::retry::RetryPolicy;
+use super::route_rule::{RouteRule, RouteRules};
 use super::state_graph::END;
+use super::state_limits::{SizedState, StateLimitGuard, StateLimits};
+use super::state_validator::StateValidator;
+use super::visualization::GraphProgress;
+use super::weighted::{routing_seed, ROUTE_SPLIT_EVENT_TYPE};
 use super::{Next, NextEntry, Node, RunContext};
 
 /// Compiled graph: immutable structure, supports invoke only.
@@ -56,6 +63,13 @@ pub struct CompiledStateGraph<S> {
     pub(super) retry_policy: RetryPolicy,
     /// Optional interrupt handler for human-in-the-loop scenarios.
     pub(super) interrupt_handler: Option<Arc<dyn InterruptHandler>>,
+    /// Checks run on each node's output before it is merged; see [`StateValidator`].
+    pub(super) state_validators: Vec<Arc<dyn StateValidator<S>>>,
+    /// Text route rules checked before the compiled edges; see `with_route_rules`.
+    pub(super) route_rules: Option<RouteRules<S>>,
+    /// Caps on state size checked before the first node and after each node; see
+    /// `with_state_limits`.
+    pub(super) state_limits: Option<StateLimitGuard<S>>,
 }
 
 /// Streaming graph execution: event stream plus final completion result.
@@ -77,10 +91,33 @@ where
             .is_some_and(CancellationToken::is_cancelled)
     }
 
+    /// Sends a `GraphProgress` snapshot to the run stream (callers check the stream mode).
+    async fn emit_graph_progress(run_ctx: Option<&RunContext<S>>, progress: &GraphProgress) {
+        if let Some(tx) = run_ctx.and_then(|ctx| ctx.stream_tx.as_ref()) {
+            let _ = tx.send(StreamEvent::GraphProgress(progress.clone())).await;
+        }
+    }
+
+    /// Runs the state validators on a node's output; the first rejection becomes the node's error.
+    fn validate_output(&self, node_id: &str, before: &S, after: &mut S) -> Result<(), AgentError> {
+        for validator in &self.state_validators {
+            validator
+                .validate(node_id, before, after)
+                .map_err(|reason| {
+                    AgentError::ExecutionFailed(format!(
+                        "state validation failed after node '{}': {}",
+                        node_id, reason
+                    ))
+                })?;
+        }
+        Ok(())
+    }
+
     /// Execute a node with retry logic.
     ///
     /// Attempts to run the node, retrying according to the configured retry policy
-    /// if the execution fails.
+    /// if the execution fails. State validators count as part of the node: a rejected
+    /// output is retried like a node error.
     async fn execute_node_with_retry(
         &self,
         node: Arc<dyn Node<S>>,
@@ -90,14 +127,17 @@ where
         let mut attempt = 0;
         loop {
             let current_state = state.clone();
+            let node_id = node.id().to_string();
             let result = if let Some(middleware) = &self.middleware {
-                let node_id = node.id().to_string();
                 let run_ctx_owned = run_ctx.cloned();
                 let node_clone = node.clone();
+                let no_flags = HashMap::new();
+                let flags = run_ctx.map_or(&no_flags, |ctx| &ctx.config.flags);
                 middleware
-                    .around_run(
+                    .around_run_with_flags(
                         &node_id,
                         current_state,
+                        flags,
                         Box::new(move |s| {
                             let node = node_clone.clone();
                             let run_ctx_inner = run_ctx_owned.clone();
@@ -117,6 +157,10 @@ where
                 node.run(current_state).await
             };
 
+            let result = result.and_then(|(mut out, next)| {
+                self.validate_output(&node_id, &state, &mut out)?;
+                Ok((out, next))
+            });
             match result {
                 Ok(output) => return Ok(output),
                 Err(e) => {
@@ -150,6 +194,23 @@ where
     ) -> Result<(), AgentError> {
         log_graph_start();
 
+        let mut progress = run_ctx
+            .filter(|ctx| {
+                ctx.stream_tx.is_some()
+                    && (ctx.stream_mode.contains(&StreamMode::Graph)
+                        || ctx.stream_mode.contains(&StreamMode::Debug))
+            })
+            .map(|_| GraphProgress::new(self));
+        // One seed per run: a thread takes the same branch of each weighted split every turn.
+        let routing_seed = routing_seed(config.as_ref());
+
+        if let Some(limits) = &self.state_limits {
+            if let Err(e) = limits.enforce("the run input", state) {
+                log_graph_error(&e);
+                return Err(e);
+            }
+        }
+
         loop {
             if Self::is_cancelled(run_ctx) {
                 log_graph_error(&AgentError::Cancelled);
@@ -166,6 +227,11 @@ where
             log_node_start(current_id);
             log_node_state(current_id, &current_state);
 
+            if let Some(progress) = progress.as_mut() {
+                progress.enter(current_id);
+                Self::emit_graph_progress(run_ctx, progress).await;
+            }
+
             // Emit TaskStart event if Tasks or Debug mode is enabled
             if let Some(ctx) = run_ctx {
                 if let Some(tx) = &ctx.stream_tx {
@@ -183,6 +249,7 @@ where
             }
 
             // Execute node with retry logic
+            let node_start = std::time::Instant::now();
             let result = self
                 .execute_node_with_retry(node, current_state, run_ctx)
                 .await;
@@ -195,8 +262,9 @@ where
                     if let (Some(cp), Some(cfg)) = (&self.checkpointer, config) {
                         if cfg.thread_id.is_some() {
                             // Save checkpoint before interrupt so we can resume later
-                            let checkpoint =
+                            let mut checkpoint =
                                 Checkpoint::from_state(state.clone(), CheckpointSource::Update, 0);
+                            checkpoint.metadata.node = Some(current_id.clone());
                             let _ = cp.put(cfg, &checkpoint).await;
 
                             // Emit checkpoint event if enabled
@@ -305,6 +373,21 @@ where
             // Log state update
             log_state_update(current_id);
 
+            let state_size = match &self.state_limits {
+                Some(limits) => match limits.enforce(&format!("node '{}'", current_id), state) {
+                    Ok(size) => Some(size),
+                    Err(e) => {
+                        log_graph_error(&e);
+                        return Err(e);
+                    }
+                },
+                None => None,
+            };
+            if let Some(ctx) = run_ctx {
+                ctx.emit_node_timing(current_id.clone(), node_start.elapsed(), state_size)
+                    .await;
+            }
+
             if Self::is_cancelled(run_ctx) {
                 log_graph_error(&AgentError::Cancelled);
                 return Err(AgentError::Cancelled);
@@ -327,42 +410,73 @@ where
                 }
             }
 
-            let next_id: Option<String> =
-                if let Some(NextEntry::Conditional(router)) = self.next_map.get(current_id) {
-                    let target = router.resolve_next(state);
-                    tracing::debug!(
+            let routed = self
+                .route_rules
+                .as_ref()
+                .and_then(|r| r.resolve(current_id, state));
+            let next_id: Option<String> = if let Some(rule) = routed {
+                tracing::debug!(
+                    from = %current_id,
+                    to = %rule.target,
+                    rule = %rule.as_str(),
+                    "route rule matched"
+                );
+                Some(rule.target.clone())
+            } else if let Some(NextEntry::Conditional(router)) = self.next_map.get(current_id) {
+                let choice = router.resolve(state, routing_seed, current_id);
+                tracing::debug!(
+                    from = %current_id,
+                    to = %choice.target,
+                    "conditional routing"
+                );
+                if let Some((key, share)) = &choice.split {
+                    tracing::info!(
                         from = %current_id,
-                        to = %target,
-                        "conditional routing"
+                        to = %choice.target,
+                        key = %key,
+                        share = share,
+                        "weighted route split"
                     );
-                    Some(target)
-                } else {
-                    match next {
-                        Next::End => None,
-                        Next::Node(id) => Some(id),
-                        Next::Continue => self
-                            .next_map
-                            .get(current_id)
-                            .and_then(|e| {
-                                if let NextEntry::Unconditional(id) = e {
-                                    Some(id.clone())
-                                } else {
-                                    None
-                                }
-                            })
-                            .or_else(|| {
-                                let pos = self.edge_order.iter().position(|x| x == current_id)?;
-                                self.edge_order.get(pos + 1).cloned()
-                            }),
+                    if let Some(ctx) = run_ctx {
+                        ctx.emit_custom(serde_json::json!({
+                            "type": ROUTE_SPLIT_EVENT_TYPE,
+                            "from": current_id.as_str(),
+                            "to": choice.target,
+                            "key": key,
+                            "share": share,
+                        }))
+                        .await;
                     }
-                };
+                }
+                Some(choice.target)
+            } else {
+                match next {
+                    Next::End => None,
+                    Next::Node(id) => Some(id),
+                    Next::Continue => self
+                        .next_map
+                        .get(current_id)
+                        .and_then(|e| {
+                            if let NextEntry::Unconditional(id) = e {
+                                Some(id.clone())
+                            } else {
+                                None
+                            }
+                        })
+                        .or_else(|| {
+                            let pos = self.edge_order.iter().position(|x| x == current_id)?;
+                            self.edge_order.get(pos + 1).cloned()
+                        }),
+                }
+            };
 
             let should_end = next_id.is_none() || next_id.as_deref() == Some(END);
             if should_end {
                 if let (Some(cp), Some(cfg)) = (&self.checkpointer, config) {
                     if cfg.thread_id.is_some() {
-                        let checkpoint =
+                        let mut checkpoint =
                             Checkpoint::from_state(state.clone(), CheckpointSource::Update, 0);
+                        checkpoint.metadata.node = Some(current_id.clone());
                         let _ = cp.put(cfg, &checkpoint).await;
                         if let Some(ctx) = run_ctx {
                             if let Some(tx) = &ctx.stream_tx {
@@ -391,6 +505,10 @@ where
                         }
                     }
                 }
+                if let Some(progress) = progress.as_mut() {
+                    progress.current = None;
+                    Self::emit_graph_progress(run_ctx, progress).await;
+                }
                 log_graph_complete();
                 return Ok(());
             }
@@ -516,6 +634,57 @@ where
         }
     }
 
+    /// Adds node middleware to an already compiled graph. It runs inside any middleware the
+    /// graph was compiled with (e.g. node logging), so that middleware still wraps the whole run.
+    pub fn with_added_middleware(mut self, middleware: Arc<dyn NodeMiddleware<S>>) -> Self {
+        self.middleware = Some(match self.middleware.take() {
+            Some(existing) => Arc::new(
+                NodeMiddlewareStack::new()
+                    .with("*", existing)
+                    .with("*", middleware),
+            ),
+            None => middleware,
+        });
+        self
+    }
+
+    /// Attaches route rules (see [`RouteRule`]) checked after each node before its edge or
+    /// conditional router: the first rule for the node whose condition holds for
+    /// `serde_json::to_value(state)` picks the next node, even when the node returned
+    /// `Next::End`. Replaces rules attached earlier.
+    ///
+    /// Returns `CompilationError::InvalidRouteRule` when a rule's source is not a node or its
+    /// target is neither a node nor END.
+    pub fn with_route_rules(mut self, rules: Vec<RouteRule>) -> Result<Self, CompilationError>
+    where
+        S: serde::Serialize,
+    {
+        for rule in &rules {
+            if !self.nodes.contains_key(&rule.source) {
+                return Err(CompilationError::InvalidRouteRule(rule.source.clone()));
+            }
+            if rule.target != END && !self.nodes.contains_key(&rule.target) {
+                return Err(CompilationError::InvalidRouteRule(rule.target.clone()));
+            }
+        }
+        self.route_rules = (!rules.is_empty()).then(|| RouteRules {
+            rules,
+            view: Arc::new(|s: &S| serde_json::to_value(s).unwrap_or_default()),
+        });
+        Ok(self)
+    }
+
+    /// Caps the state's size (see [`StateLimits`]): the input state is checked before the first
+    /// node and the merged state after each node; over a limit it is compacted or the run fails
+    /// with [`AgentError::StateTooLarge`]. Node timing events then carry the state size.
+    pub fn with_state_limits(mut self, limits: StateLimits) -> Self
+    where
+        S: SizedState,
+    {
+        self.state_limits = Some(StateLimitGuard::new(limits));
+        self
+    }
+
     /// Returns the long-term store if the graph was compiled with `with_store(store)`.
     ///
     /// Nodes can use it for cross-thread memory (e.g. namespace from `config.user_id`).
@@ -551,6 +720,9 @@ mod tests {
             state_updater: Arc::new(crate::channels::ReplaceUpdater),
             retry_policy: RetryPolicy::None,
             interrupt_handler: None,
+            state_validators: Vec::new(),
+            route_rules: None,
+            state_limits: None,
         };
         let state = crate::state::ReActState::default();
         let result = graph.invoke(state, None).await;
@@ -850,6 +1022,37 @@ mod tests {
         );
     }
 
+    /// **Scenario**: Weighted edges pick the same branch for the same seed and thread, never a
+    /// zero-weight branch, and both branches across threads.
+    #[tokio::test]
+    async fn invoke_weighted_edges_split_by_seed_and_thread() {
+        let mut graph = StateGraph::<i32>::new();
+        for (id, delta) in [("decide", 0), ("arm_a", 1), ("arm_b", 10), ("off", 100)] {
+            graph.add_node(id, Arc::new(AddNode { id, delta }));
+        }
+        graph.add_edge(START, "decide");
+        graph.add_edge("arm_a", END);
+        graph.add_edge("arm_b", END);
+        graph.add_edge("off", END);
+        graph.add_weighted_edges("decide", [("arm_a", 1.0), ("arm_b", 1.0), ("off", 0.0)]);
+        let compiled = graph.compile().expect("graph compiles");
+        let config = |thread: String| RunnableConfig {
+            thread_id: Some(thread),
+            routing_seed: Some(7),
+            ..Default::default()
+        };
+
+        let mut outcomes = HashSet::new();
+        for i in 0..32 {
+            let first = compiled.invoke(0, Some(config(format!("t{}", i))));
+            let again = compiled.invoke(0, Some(config(format!("t{}", i))));
+            let first = first.await.unwrap();
+            assert_eq!(first, again.await.unwrap());
+            outcomes.insert(first);
+        }
+        assert_eq!(outcomes, HashSet::from([1, 10]));
+    }
+
     /// **Scenario**: invoke with checkpointer and config.thread_id saves checkpoint at end of run.
     #[tokio::test]
     async fn invoke_with_checkpointer_and_thread_id_saves_checkpoint() {
@@ -886,7 +1089,8 @@ mod tests {
             ..Default::default()
         };
         let tuple = cp.get_tuple(&cfg).await.unwrap();
-        assert!(tuple.is_some(), "checkpoint should be saved");
+        let (_, metadata) = tuple.expect("checkpoint should be saved");
+        assert_eq!(metadata.node.as_deref(), Some("second"));
     }
 
     /// **Scenario**: Node returning Next::End triggers checkpoint save when checkpointer and thread_id set.
@@ -1019,6 +1223,9 @@ mod tests {
             state_updater: Arc::new(crate::channels::ReplaceUpdater),
             retry_policy: RetryPolicy::None,
             interrupt_handler: None,
+            state_validators: Vec::new(),
+            route_rules: None,
+            state_limits: None,
         };
         let stream = graph.stream(
             0,
@@ -1166,8 +1373,16 @@ mod tests {
                 | StreamEvent::ToolCall { .. }
                 | StreamEvent::ToolStart { .. }
                 | StreamEvent::ToolOutput { .. }
+                | StreamEvent::ToolOutputChunk { .. }
                 | StreamEvent::ToolEnd { .. }
-                | StreamEvent::ToolApproval { .. } => {
+                | StreamEvent::ToolApproval { .. }
+                | StreamEvent::ThreadSummary { .. }
+                | StreamEvent::ToolsRefreshed { .. }
+                | StreamEvent::ModelSwitched { .. }
+                | StreamEvent::AnswerRevised { .. }
+                | StreamEvent::Timing { .. }
+                | StreamEvent::FinishReason { .. }
+                | StreamEvent::GraphProgress(_) => {
                     panic!(
                         "run_loop does not emit Messages/Custom/Checkpoint/Task/Usage/Tool events in this test, got {:?}",
                         e
@@ -1273,6 +1488,93 @@ mod tests {
         }
     }
 
+    /// **Scenario**: Route rules override the compiled edge when their condition holds; rules
+    /// naming unknown nodes are rejected.
+    #[tokio::test]
+    async fn route_rules_override_edges() {
+        use crate::graph::RouteRule;
+
+        let mut graph = StateGraph::<i32>::new();
+        graph.add_node(
+            "inc",
+            Arc::new(AddNode {
+                id: "inc",
+                delta: 1,
+            }),
+        );
+        graph.add_node(
+            "big",
+            Arc::new(AddNode {
+                id: "big",
+                delta: 100,
+            }),
+        );
+        graph.add_edge(START, "inc");
+        graph.add_edge("inc", END);
+        graph.add_edge("big", END);
+        let compiled = graph.compile().expect("graph compiles");
+
+        let rules =
+            RouteRule::parse_all(["inc: if state >= 3 goto big", "inc: if state < 3 goto inc"])
+                .unwrap();
+        let routed = compiled.clone().with_route_rules(rules).unwrap();
+        assert_eq!(routed.invoke(0, None).await.unwrap(), 103);
+        assert_eq!(compiled.invoke(0, None).await.unwrap(), 1);
+
+        let bad = RouteRule::parse_all(["inc: if true goto missing"]).unwrap();
+        assert!(matches!(
+            compiled.with_route_rules(bad),
+            Err(CompilationError::InvalidRouteRule(id)) if id == "missing"
+        ));
+    }
+
+    /// **Scenario**: A validator rejects a node output that drops messages; another amends it.
+    #[tokio::test]
+    async fn state_validators_reject_or_amend_node_output() {
+        use crate::graph::{FnStateValidator, NonDecreasing};
+
+        let build = |validator: Arc<dyn crate::graph::StateValidator<MessageState>>| {
+            let mut graph = StateGraph::<MessageState>::new().with_state_validator(validator);
+            graph.add_node(
+                "first",
+                Arc::new(AddMessageNode {
+                    id: "first",
+                    message: "Hello",
+                }),
+            );
+            graph.add_edge(START, "first");
+            graph.add_edge("first", END);
+            graph.compile().expect("graph compiles")
+        };
+        let initial = MessageState {
+            messages: vec!["a".to_string(), "b".to_string()],
+            count: 0,
+        };
+
+        let grow = build(Arc::new(NonDecreasing::new(
+            "messages",
+            |s: &MessageState| s.messages.len(),
+        )));
+        match grow.invoke(initial.clone(), None).await {
+            Err(AgentError::ExecutionFailed(msg)) => {
+                assert!(msg.contains("after node 'first'"), "{}", msg);
+                assert!(msg.contains("messages shrank from 2 to 1"), "{}", msg);
+            }
+            other => panic!("expected validation failure, got {:?}", other),
+        }
+
+        let append = build(Arc::new(FnStateValidator::new(
+            |_: &str, before: &MessageState, after: &mut MessageState| {
+                let mut messages = before.messages.clone();
+                messages.append(&mut after.messages);
+                after.messages = messages;
+                Ok(())
+            },
+        )));
+        let out = append.invoke(initial, None).await.unwrap();
+        assert_eq!(out.messages, vec!["a", "b", "Hello"]);
+    }
+
     /// **Scenario**: Custom StateUpdater appends messages instead of replacing.
     #[tokio::test]
     async fn invoke_with_custom_state_updater_appends_messages() {
@@ -1733,6 +2035,33 @@ mod tests {
         }
     }
 
+    /// **Scenario**: with Tasks mode, each node run is followed by a Node timing event.
+    #[tokio::test]
+    async fn stream_tasks_emits_node_timing() {
+        let mut graph = StateGraph::<i32>::new();
+        graph.add_node(
+            "add_one",
+            Arc::new(AddNode {
+                id: "add_one",
+                delta: 1,
+            }),
+        );
+        graph.add_edge(START, "add_one");
+        graph.add_edge("add_one", END);
+        let compiled = graph.compile().expect("graph compiles");
+
+        let stream = compiled.stream(0, None, HashSet::from_iter([StreamMode::Tasks]), None, None);
+        let events: Vec<_> = stream.events.collect().await;
+        let timings: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                StreamEvent::Timing { node_id, kind, .. } => Some((node_id.as_str(), *kind)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(timings, vec![("add_one", crate::stream::TimingKind::Node)]);
+    }
+
     /// **Scenario**: stream() does not emit task events when Tasks mode is disabled.
     #[tokio::test]
     async fn stream_no_task_events_without_tasks_mode() {
@@ -2,7 +2,8 @@
 //!
 //! Conditional edges: a source node has a
 //! routing function that takes the current state and returns a key; the key is
-//! either used as the next node id or looked up in an optional path map.
+//! either used as the next node id or looked up in an optional path map. A key may
+//! instead name a weighted split, sampled per run (see [`super::weighted`]).
 //!
 //! **Interaction**: Used by `StateGraph::add_conditional_edges` and
 //! `CompiledStateGraph` run loop to resolve the next node after a node with
@@ -12,6 +13,8 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::Arc;
 
+use super::weighted::{pick, split_sample, WeightedBranch};
+
 /// Router function: takes a reference to state and returns a routing key.
 ///
 /// The key is used as the next node id when no path map is provided, or
@@ -33,6 +36,16 @@ pub struct ConditionalRouter<S> {
     pub(super) path: ConditionalRouterFn<S>,
     /// Optional map from routing key to node id (or END). If None, key is used as node id.
     pub(super) path_map: Option<HashMap<String, String>>,
+    /// Routing keys whose next node is sampled from weighted branches; checked before `path_map`.
+    pub(super) splits: HashMap<String, Vec<WeightedBranch>>,
+}
+
+/// Next node picked by a [`ConditionalRouter`]; `split` is set when it came from a weighted split.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RouteChoice {
+    pub target: String,
+    /// Routing key and the picked branch's share of the split's total weight.
+    pub split: Option<(String, f64)>,
 }
 
 impl<S> ConditionalRouter<S>
@@ -44,19 +57,50 @@ where
     /// - `path`: function `(state) -> key`. When `path_map` is None, `key` is the next node id.
     /// - `path_map`: if provided, `next_id = path_map.get(&key).unwrap_or(&key)`.
     pub fn new(path: ConditionalRouterFn<S>, path_map: Option<HashMap<String, String>>) -> Self {
-        Self { path, path_map }
+        Self {
+            path,
+            path_map,
+            splits: HashMap::new(),
+        }
+    }
+
+    /// Routes `key` to weighted branches: when `path` returns it, the next node is sampled.
+    pub fn with_split(mut self, key: impl Into<String>, branches: Vec<WeightedBranch>) -> Self {
+        self.splits.insert(key.into(), branches);
+        self
     }
 
     /// Resolves the next node id from the current state.
     ///
-    /// Returns the node id (or END) to run next. Used by the compiled graph run loop.
+    /// Returns the node id (or END) to run next. A weighted split is sampled with a random
+    /// seed; the run loop uses [`Self::resolve`] with the run's seed instead.
     pub fn resolve_next(&self, state: &S) -> String {
+        self.resolve(state, super::weighted::routing_seed(None), "")
+            .target
+    }
+
+    /// Resolves the next node after `source`; a weighted split is sampled from `seed`, so the
+    /// same seed and source always pick the same branch.
+    pub fn resolve(&self, state: &S, seed: u64, source: &str) -> RouteChoice {
         let key = (self.path)(state);
-        self.path_map
+        if let Some(branches) = self.splits.get(&key) {
+            let branch = pick(branches, split_sample(seed, source));
+            let total: f64 = branches.iter().map(|b| b.weight).sum();
+            return RouteChoice {
+                target: branch.target.clone(),
+                split: Some((key, branch.weight / total)),
+            };
+        }
+        let target = self
+            .path_map
             .as_ref()
             .and_then(|m| m.get(&key))
             .cloned()
-            .unwrap_or(key)
+            .unwrap_or(key);
+        RouteChoice {
+            target,
+            split: None,
+        }
     }
 }
 
@@ -0,0 +1,166 @@
+//! Graph validation report.
+//!
+//! [`StateGraph::validate`](super::StateGraph::validate) checks the whole graph and collects
+//! every problem instead of stopping at the first: edges and path_map targets naming unknown
+//! nodes, missing or branching START, no route to END, cycles no path leads out of, and nodes
+//! START never reaches. `compile` fails with all errors at once (see
+//! [`CompilationError::Diagnostics`]).
+//!
+//! Connectivity is judged on declared routes: edges, conditional path_map values and weighted
+//! branch targets. A router without a path_map may route anywhere, and a node without outgoing
+//! routes ends the run.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use super::compile_error::CompilationError;
+use super::state_graph::END;
+
+/// Every problem found in a graph, split into errors (the graph does not compile) and warnings
+/// (it runs, but part of it never will).
+#[derive(Debug, Default)]
+pub struct GraphDiagnostics {
+    pub errors: Vec<CompilationError>,
+    /// Suspicious structure such as [`CompilationError::UnreachableNode`].
+    pub warnings: Vec<CompilationError>,
+}
+
+impl GraphDiagnostics {
+    /// True when there are no errors (warnings allowed).
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// `Ok` without errors; a single error as itself; several as
+    /// [`CompilationError::Diagnostics`].
+    pub fn into_result(mut self) -> Result<(), CompilationError> {
+        match self.errors.len() {
+            0 => Ok(()),
+            1 => Err(self.errors.remove(0)),
+            _ => Err(CompilationError::Diagnostics(self.errors)),
+        }
+    }
+
+    /// Adds an error unless the same one was already reported (e.g. an unknown node named by
+    /// two edges).
+    pub(super) fn error(&mut self, error: CompilationError) {
+        let text = error.to_string();
+        if !self.errors.iter().any(|e| e.to_string() == text) {
+            self.errors.push(error);
+        }
+    }
+}
+
+impl fmt::Display for GraphDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for e in &self.errors {
+            writeln!(f, "error: {}", e)?;
+        }
+        for w in &self.warnings {
+            writeln!(f, "warning: {}", w)?;
+        }
+        Ok(())
+    }
+}
+
+/// Declared successors of each node; `None` for a node whose router may route anywhere.
+pub(super) type Successors<'a> = HashMap<&'a str, Option<Vec<&'a str>>>;
+
+/// Reports cycles with no route to END (errors) and nodes `entry` never reaches (warnings).
+/// `nodes` are the registered node ids, sorted.
+pub(super) fn check_connectivity<'a>(
+    entry: &[&'a str],
+    nodes: &[&'a str],
+    successors: &Successors<'a>,
+    report: &mut GraphDiagnostics,
+) {
+    let next = |id: &str| declared(successors, id);
+
+    let reachable = reach(entry.iter().copied(), next);
+    let dynamic = reachable
+        .iter()
+        .any(|id| matches!(successors.get(id), Some(None)));
+    if !dynamic {
+        for id in nodes.iter().filter(|id| !reachable.contains(*id)) {
+            report
+                .warnings
+                .push(CompilationError::UnreachableNode(id.to_string()));
+        }
+    }
+
+    // Nodes from which END (or an open end) can be reached, to a fixpoint.
+    let mut exits: HashSet<&str> = HashSet::new();
+    loop {
+        let before = exits.len();
+        for &id in nodes {
+            if exits.contains(id) {
+                continue;
+            }
+            let leaves = match successors.get(id) {
+                None | Some(None) => true,
+                Some(Some(targets)) => {
+                    targets.is_empty()
+                        || targets
+                            .iter()
+                            .any(|t| *t == END || exits.contains(t) || !nodes.contains(t))
+                }
+            };
+            if leaves {
+                exits.insert(id);
+            }
+        }
+        if exits.len() == before {
+            break;
+        }
+    }
+
+    let stuck: Vec<&str> = nodes
+        .iter()
+        .copied()
+        .filter(|id| reachable.contains(id) && !exits.contains(id))
+        .collect();
+    let mut grouped: HashSet<&str> = HashSet::new();
+    for &id in &stuck {
+        if grouped.contains(id) {
+            continue;
+        }
+        let from_id = reach(next(id), next);
+        if !from_id.contains(id) {
+            continue;
+        }
+        let mut cycle: Vec<&str> = stuck
+            .iter()
+            .copied()
+            .filter(|&m| from_id.contains(m) && reach(next(m), next).contains(id))
+            .collect();
+        cycle.sort_unstable();
+        grouped.extend(cycle.iter().copied());
+        report.error(CompilationError::CycleWithoutExit(
+            cycle.into_iter().map(str::to_string).collect(),
+        ));
+    }
+}
+
+/// Declared successors of `id` (none for a router that may route anywhere).
+fn declared<'a>(successors: &Successors<'a>, id: &str) -> Vec<&'a str> {
+    successors
+        .get(id)
+        .and_then(|s| s.as_ref())
+        .map(|s| s.to_vec())
+        .unwrap_or_default()
+}
+
+/// Ids reachable from `from` (included) by following `next`.
+fn reach<'a>(
+    from: impl IntoIterator<Item = &'a str>,
+    next: impl Fn(&str) -> Vec<&'a str>,
+) -> HashSet<&'a str> {
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut queue: Vec<&str> = from.into_iter().collect();
+    while let Some(id) = queue.pop() {
+        if seen.insert(id) {
+            queue.extend(next(id));
+        }
+    }
+    seen
+}
@@ -0,0 +1,324 @@
+//! Middleware stack: several [`NodeMiddleware`]s, each scoped to node ids by pattern.
+//!
+//! A graph holds a single middleware; [`NodeMiddlewareStack`] is itself a middleware that runs
+//! every entry whose pattern matches the current node, first entry outermost. [`NodeHooks`]
+//! covers the common case of plain before/after hooks without writing an `around_run`.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::error::AgentError;
+
+use super::{Next, NodeMiddleware};
+
+type NodeRunFn<S> = Box<
+    dyn FnOnce(
+            S,
+        )
+            -> Pin<Box<dyn std::future::Future<Output = Result<(S, Next), AgentError>> + Send>>
+        + Send,
+>;
+
+/// Async hooks around one node run, with full access to the state.
+///
+/// `before` may rewrite the state the node receives; `after` may rewrite the node's output
+/// and its routing decision. Returning an error fails the node like a node error would
+/// (subject to the graph's retry policy). Attach with [`NodeMiddlewareStack::with_hooks`].
+#[async_trait]
+pub trait NodeHooks<S>: Send + Sync
+where
+    S: Clone + Send + Sync + Debug + 'static,
+{
+    /// Called with the node's input state; the returned state is what the node runs on.
+    async fn before(&self, _node_id: &str, state: S) -> Result<S, AgentError> {
+        Ok(state)
+    }
+
+    /// Called with the node's output state and next step; the returned pair replaces them.
+    async fn after(&self, _node_id: &str, state: S, next: Next) -> Result<(S, Next), AgentError> {
+        Ok((state, next))
+    }
+}
+
+/// Adapts [`NodeHooks`] to [`NodeMiddleware`].
+struct HooksMiddleware<S> {
+    hooks: Arc<dyn NodeHooks<S>>,
+}
+
+#[async_trait]
+impl<S> NodeMiddleware<S> for HooksMiddleware<S>
+where
+    S: Clone + Send + Sync + Debug + 'static,
+{
+    async fn around_run(
+        &self,
+        node_id: &str,
+        state: S,
+        inner: NodeRunFn<S>,
+    ) -> Result<(S, Next), AgentError> {
+        let state = self.hooks.before(node_id, state).await?;
+        let (state, next) = inner(state).await?;
+        self.hooks.after(node_id, state, next).await
+    }
+}
+
+/// Returns whether `node_id` matches `pattern`, where `*` matches any run of characters
+/// (`"*"` matches every node, `"think*"` matches `think` and `think_expand`).
+pub fn node_pattern_matches(pattern: &str, node_id: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = node_id.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Ordered list of `(node id pattern, middleware)` entries, usable as one [`NodeMiddleware`].
+///
+/// For each node run, the matching entries wrap the node in insertion order: the first entry
+/// is outermost and sees the final result last. Nodes that match nothing run unwrapped.
+pub struct NodeMiddlewareStack<S> {
+    entries: Vec<(String, Arc<dyn NodeMiddleware<S>>)>,
+}
+
+impl<S> Clone for NodeMiddlewareStack<S> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.clone(),
+        }
+    }
+}
+
+impl<S> Default for NodeMiddlewareStack<S> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<S> Debug for NodeMiddlewareStack<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NodeMiddlewareStack")
+            .field(
+                "patterns",
+                &self.entries.iter().map(|(p, _)| p).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl<S> NodeMiddlewareStack<S>
+where
+    S: Clone + Send + Sync + Debug + 'static,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `middleware` for nodes whose id matches `pattern` (see [`node_pattern_matches`]).
+    pub fn with(
+        mut self,
+        pattern: impl Into<String>,
+        middleware: Arc<dyn NodeMiddleware<S>>,
+    ) -> Self {
+        self.push(pattern, middleware);
+        self
+    }
+
+    /// Adds before/after `hooks` for nodes whose id matches `pattern`.
+    pub fn with_hooks(self, pattern: impl Into<String>, hooks: Arc<dyn NodeHooks<S>>) -> Self {
+        self.with(pattern, Arc::new(HooksMiddleware { hooks }))
+    }
+
+    /// In-place form of [`with`](Self::with).
+    pub fn push(&mut self, pattern: impl Into<String>, middleware: Arc<dyn NodeMiddleware<S>>) {
+        self.entries.push((pattern.into(), middleware));
+    }
+
+    /// Appends all entries of `other` after the entries of `self`.
+    pub fn extend(&mut self, other: NodeMiddlewareStack<S>) {
+        self.entries.extend(other.entries);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[async_trait]
+impl<S> NodeMiddleware<S> for NodeMiddlewareStack<S>
+where
+    S: Clone + Send + Sync + Debug + 'static,
+{
+    async fn around_run(
+        &self,
+        node_id: &str,
+        state: S,
+        inner: NodeRunFn<S>,
+    ) -> Result<(S, Next), AgentError> {
+        self.around_run_with_flags(node_id, state, &HashMap::new(), inner)
+            .await
+    }
+
+    async fn around_run_with_flags(
+        &self,
+        node_id: &str,
+        state: S,
+        flags: &HashMap<String, bool>,
+        inner: NodeRunFn<S>,
+    ) -> Result<(S, Next), AgentError> {
+        let mut run = inner;
+        for (_, middleware) in self
+            .entries
+            .iter()
+            .rev()
+            .filter(|(pattern, _)| node_pattern_matches(pattern, node_id))
+        {
+            let middleware = Arc::clone(middleware);
+            let id = node_id.to_string();
+            let flags = flags.clone();
+            let next = run;
+            run = Box::new(move |s| {
+                Box::pin(
+                    async move { middleware.around_run_with_flags(&id, s, &flags, next).await },
+                )
+            });
+        }
+        run(state).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn node_pattern_matches_wildcards() {
+        assert!(node_pattern_matches("*", "think"));
+        assert!(node_pattern_matches("think", "think"));
+        assert!(!node_pattern_matches("think", "think_expand"));
+        assert!(node_pattern_matches("think*", "think_expand"));
+        assert!(node_pattern_matches("*_check", "completion_check"));
+        assert!(node_pattern_matches("a*c*e", "abcde"));
+        assert!(!node_pattern_matches("a*c*e", "abcd"));
+        assert!(!node_pattern_matches("act", "observe"));
+    }
+
+    struct Tag(&'static str, Arc<std::sync::Mutex<Vec<String>>>);
+
+    #[async_trait]
+    impl NodeMiddleware<i32> for Tag {
+        async fn around_run(
+            &self,
+            node_id: &str,
+            state: i32,
+            inner: NodeRunFn<i32>,
+        ) -> Result<(i32, Next), AgentError> {
+            self.1
+                .lock()
+                .unwrap()
+                .push(format!("{}>{}", self.0, node_id));
+            let out = inner(state).await;
+            self.1
+                .lock()
+                .unwrap()
+                .push(format!("{}<{}", self.0, node_id));
+            out
+        }
+    }
+
+    #[tokio::test]
+    async fn stack_runs_matching_entries_first_outermost() {
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let stack = NodeMiddlewareStack::<i32>::new()
+            .with("*", Arc::new(Tag("all", log.clone())))
+            .with("act", Arc::new(Tag("act", log.clone())))
+            .with("think", Arc::new(Tag("think", log.clone())));
+
+        let (out, _) = stack
+            .around_run(
+                "think",
+                1,
+                Box::new(|s| Box::pin(async move { Ok((s + 1, Next::Continue)) })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(out, 2);
+        assert_eq!(
+            log.lock().unwrap().as_slice(),
+            &["all>think", "think>think", "think<think", "all<think"]
+        );
+    }
+
+    struct FlagGate(&'static str);
+
+    #[async_trait]
+    impl NodeMiddleware<i32> for FlagGate {
+        async fn around_run(
+            &self,
+            _node_id: &str,
+            state: i32,
+            inner: NodeRunFn<i32>,
+        ) -> Result<(i32, Next), AgentError> {
+            inner(state).await
+        }
+
+        async fn around_run_with_flags(
+            &self,
+            _node_id: &str,
+            state: i32,
+            flags: &HashMap<String, bool>,
+            inner: NodeRunFn<i32>,
+        ) -> Result<(i32, Next), AgentError> {
+            let (out, next) = inner(state).await?;
+            if flags.get(self.0).copied().unwrap_or(false) {
+                return Ok((out * 10, next));
+            }
+            Ok((out, next))
+        }
+    }
+
+    #[tokio::test]
+    async fn stack_forwards_run_flags_to_entries() {
+        let stack = NodeMiddlewareStack::<i32>::new().with("*", Arc::new(FlagGate("scale")));
+        let flags = HashMap::from([("scale".to_string(), true)]);
+        let (out, _) = stack
+            .around_run_with_flags(
+                "think",
+                1,
+                &flags,
+                Box::new(|s| Box::pin(async move { Ok((s + 1, Next::Continue)) })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(out, 20);
+
+        let (out, _) = stack
+            .around_run(
+                "think",
+                1,
+                Box::new(|s| Box::pin(async move { Ok((s + 1, Next::Continue)) })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(out, 2);
+    }
+}
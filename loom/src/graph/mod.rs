@@ -7,35 +7,52 @@ mod cancellable;
 mod compile_error;
 mod compiled;
 mod conditional;
+mod diagnostics;
 mod interrupt;
 mod logging;
 mod logging_middleware;
+mod middleware_stack;
 mod name_node;
 mod next;
 mod node;
 mod node_middleware;
 mod retry;
+mod route_rule;
 mod run_context;
 mod runtime;
 mod state_graph;
+mod state_limits;
+mod state_validator;
 mod visualization;
+mod weighted;
 
 pub use cancellable::run_cancellable;
 pub use compile_error::CompilationError;
 pub use compiled::CompiledStateGraph;
-pub use conditional::{ConditionalRouter, ConditionalRouterFn, NextEntry};
+pub use conditional::{ConditionalRouter, ConditionalRouterFn, NextEntry, RouteChoice};
+pub use diagnostics::GraphDiagnostics;
 pub use interrupt::{DefaultInterruptHandler, GraphInterrupt, Interrupt, InterruptHandler};
 pub use logging::{
     log_graph_complete, log_graph_error, log_graph_start, log_node_complete, log_node_start,
     log_state_update,
 };
 pub use logging_middleware::LoggingNodeMiddleware;
+pub use middleware_stack::{node_pattern_matches, NodeHooks, NodeMiddlewareStack};
 pub use name_node::NameNode;
 pub use next::Next;
 pub use node::Node;
 pub use node_middleware::NodeMiddleware;
 pub use retry::RetryPolicy;
+pub use route_rule::{RouteRule, RouteRuleError};
 pub use run_context::RunContext;
 pub use runtime::Runtime;
 pub use state_graph::{StateGraph, END, START};
-pub use visualization::{generate_dot, generate_text};
+pub use state_limits::{OnStateLimit, SizedState, StateLimits, StateSize};
+pub use state_validator::{FnStateValidator, Invariant, NonDecreasing, StateValidator};
+pub use visualization::{
+    annotate_dot, generate_dot, generate_dot_with_progress, generate_text, graph_edges,
+    graph_node_ids, GraphEdge, GraphProgress, NodeStatus,
+};
+pub use weighted::{
+    routing_seed, RoutingRng, WeightedBranch, ENV_ROUTING_SEED, ROUTE_SPLIT_EVENT_TYPE,
+};
@@ -4,6 +4,7 @@
 //! `compile_with_middleware` / `compile_with_checkpointer_and_middleware`.
 
 use async_trait::async_trait;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::pin::Pin;
 
@@ -36,4 +37,25 @@ where
                 > + Send,
         >,
     ) -> Result<(S, Next), AgentError>;
+
+    /// Same as [`around_run`](Self::around_run), with the run's feature flags
+    /// ([`RunnableConfig::flags`](crate::memory::RunnableConfig::flags)).
+    ///
+    /// The graph calls this one; the default ignores `flags`. Override it to branch on
+    /// experiment flags.
+    async fn around_run_with_flags(
+        &self,
+        node_id: &str,
+        state: S,
+        _flags: &HashMap<String, bool>,
+        inner: Box<
+            dyn FnOnce(
+                    S,
+                ) -> Pin<
+                    Box<dyn std::future::Future<Output = Result<(S, Next), AgentError>> + Send>,
+                > + Send,
+        >,
+    ) -> Result<(S, Next), AgentError> {
+        self.around_run(node_id, state, inner).await
+    }
 }
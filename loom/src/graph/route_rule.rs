@@ -0,0 +1,508 @@
+//! Route rules: conditional routing written as text (e.g. from config).
+//!
+//! A rule has the form `<source>: if <condition> goto <target>`, e.g.
+//! `observe: if state.turn_count > 5 goto summarize`. After `source` runs, the condition
+//! is evaluated against a `serde_json` view of the state; when it holds, the graph goes to
+//! `target` (a node id or `END`) instead of its compiled edge. Rules for the same source
+//! are tried in order; the first match wins.
+//!
+//! # Conditions
+//!
+//! - Paths: `state`, `state.field`, `state.list.0` (missing fields are `null`)
+//! - Literals: numbers, `"strings"` / `'strings'`, `true`, `false`, `null`
+//! - `len(x)`: length of a string, array or object (0 otherwise)
+//! - Comparisons: `==`, `!=`, `>`, `>=`, `<`, `<=` (ordering only between numbers or strings)
+//! - Logic: `&&` / `and`, `||` / `or`, `!` / `not`, parentheses
+//!
+//! A bare value is true unless it is `null`, `false`, `0`, `""`, `[]` or `{}`.
+//!
+//! **Interaction**: Attached with `CompiledStateGraph::with_route_rules`; checked by the run
+//! loop before the node's edge or conditional router.
+
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use serde_json::Value;
+use thiserror::Error;
+
+use super::state_graph::END;
+
+/// Error parsing a route rule.
+#[derive(Debug, Error)]
+#[error("invalid route rule `{rule}`: {message}")]
+pub struct RouteRuleError {
+    pub rule: String,
+    pub message: String,
+}
+
+/// One parsed route rule: after `source` runs, go to `target` when the condition holds.
+#[derive(Debug, Clone)]
+pub struct RouteRule {
+    pub source: String,
+    /// Node id, or [`END`] (written `END` in the rule).
+    pub target: String,
+    condition: Expr,
+    text: String,
+}
+
+impl RouteRule {
+    /// Parses `<source>: if <condition> goto <target>`.
+    pub fn parse(rule: &str) -> Result<Self, RouteRuleError> {
+        let text = rule.trim().to_string();
+        let err = |message: &str| RouteRuleError {
+            rule: text.clone(),
+            message: message.to_string(),
+        };
+        let (source, rest) = text
+            .split_once(':')
+            .ok_or_else(|| err("expected `<source>: if <condition> goto <target>`"))?;
+        let source = source.trim();
+        if !is_ident(source) {
+            return Err(err("source must be a node id"));
+        }
+        let rest = rest
+            .trim_start()
+            .strip_prefix("if")
+            .filter(|r| r.starts_with(char::is_whitespace))
+            .ok_or_else(|| err("expected `if` after the source"))?;
+        let (condition, target) = rest
+            .rsplit_once(" goto ")
+            .ok_or_else(|| err("expected `goto <target>`"))?;
+        let target = target.trim();
+        if !is_ident(target) {
+            return Err(err("target must be a node id or END"));
+        }
+        let tokens = tokenize(condition).map_err(|m| err(&m))?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let condition = parser.parse_or().map_err(|m| err(&m))?;
+        if parser.pos != parser.tokens.len() {
+            return Err(err("unexpected trailing tokens in condition"));
+        }
+        Ok(Self {
+            source: source.to_string(),
+            target: if target == "END" {
+                END.to_string()
+            } else {
+                target.to_string()
+            },
+            condition,
+            text: text.clone(),
+        })
+    }
+
+    /// Parses each non-empty rule; fails on the first invalid one.
+    pub fn parse_all<I, T>(rules: I) -> Result<Vec<Self>, RouteRuleError>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        rules
+            .into_iter()
+            .filter(|r| !r.as_ref().trim().is_empty())
+            .map(|r| Self::parse(r.as_ref()))
+            .collect()
+    }
+
+    /// Whether the condition holds for `state` (the JSON view of the graph state).
+    pub fn matches(&self, state: &Value) -> bool {
+        truthy(&self.condition.eval(state))
+    }
+
+    /// The rule as written.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+}
+
+/// State view used to evaluate route rules.
+pub(crate) type StateViewFn<S> = Arc<dyn Fn(&S) -> Value + Send + Sync>;
+
+/// Route rules attached to a compiled graph, with the state-to-JSON view they read.
+#[derive(Clone)]
+pub(crate) struct RouteRules<S> {
+    pub(crate) rules: Vec<RouteRule>,
+    pub(crate) view: StateViewFn<S>,
+}
+
+impl<S> RouteRules<S> {
+    /// Target of the first rule for `source` that matches `state`, if any.
+    pub(crate) fn resolve(&self, source: &str, state: &S) -> Option<&RouteRule> {
+        let mut rules = self.rules.iter().filter(|r| r.source == source).peekable();
+        rules.peek()?;
+        let view = (self.view)(state);
+        rules.find(|r| r.matches(&view))
+    }
+}
+
+fn is_ident(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn truthy(v: &Value) -> bool {
+    match v {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|f| f != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Literal(Value),
+    Path(Vec<String>),
+    Len(Box<Expr>),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Cmp(CmpOp, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn eval(&self, state: &Value) -> Value {
+        match self {
+            Expr::Literal(v) => v.clone(),
+            Expr::Path(segments) => segments
+                .iter()
+                .try_fold(state, |v, seg| match v {
+                    Value::Object(o) => o.get(seg),
+                    Value::Array(a) => seg.parse::<usize>().ok().and_then(|i| a.get(i)),
+                    _ => None,
+                })
+                .cloned()
+                .unwrap_or(Value::Null),
+            Expr::Len(e) => {
+                let n = match e.eval(state) {
+                    Value::String(s) => s.chars().count(),
+                    Value::Array(a) => a.len(),
+                    Value::Object(o) => o.len(),
+                    _ => 0,
+                };
+                Value::from(n)
+            }
+            Expr::Not(e) => Value::Bool(!truthy(&e.eval(state))),
+            Expr::And(a, b) => Value::Bool(truthy(&a.eval(state)) && truthy(&b.eval(state))),
+            Expr::Or(a, b) => Value::Bool(truthy(&a.eval(state)) || truthy(&b.eval(state))),
+            Expr::Cmp(op, a, b) => Value::Bool(compare(*op, &a.eval(state), &b.eval(state))),
+        }
+    }
+}
+
+fn compare(op: CmpOp, a: &Value, b: &Value) -> bool {
+    let ord = match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x
+            .as_f64()
+            .zip(y.as_f64())
+            .and_then(|(x, y)| x.partial_cmp(&y)),
+        (Value::String(x), Value::String(y)) => Some(x.cmp(y)),
+        _ => None,
+    };
+    match op {
+        CmpOp::Eq => ord.map_or(a == b, |o| o == Ordering::Equal),
+        CmpOp::Ne => ord.map_or(a != b, |o| o != Ordering::Equal),
+        CmpOp::Gt => ord == Some(Ordering::Greater),
+        CmpOp::Ge => matches!(ord, Some(Ordering::Greater | Ordering::Equal)),
+        CmpOp::Lt => ord == Some(Ordering::Less),
+        CmpOp::Le => matches!(ord, Some(Ordering::Less | Ordering::Equal)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Num(f64),
+    Str(String),
+    Dot,
+    LParen,
+    RParen,
+    Cmp(CmpOp),
+    And,
+    Or,
+    Not,
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '.' => {
+                tokens.push(Token::Dot);
+                i += 1;
+            }
+            '&' if next == Some('&') => {
+                tokens.push(Token::And);
+                i += 2;
+            }
+            '|' if next == Some('|') => {
+                tokens.push(Token::Or);
+                i += 2;
+            }
+            '=' if next == Some('=') => {
+                tokens.push(Token::Cmp(CmpOp::Eq));
+                i += 2;
+            }
+            '!' if next == Some('=') => {
+                tokens.push(Token::Cmp(CmpOp::Ne));
+                i += 2;
+            }
+            '!' => {
+                tokens.push(Token::Not);
+                i += 1;
+            }
+            '>' | '<' => {
+                let eq = next == Some('=');
+                tokens.push(Token::Cmp(match (c, eq) {
+                    ('>', true) => CmpOp::Ge,
+                    ('>', false) => CmpOp::Gt,
+                    ('<', true) => CmpOp::Le,
+                    _ => CmpOp::Lt,
+                }));
+                i += if eq { 2 } else { 1 };
+            }
+            '"' | '\'' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&ch| ch == c)
+                    .ok_or("unterminated string")?;
+                tokens.push(Token::Str(chars[i + 1..i + 1 + end].iter().collect()));
+                i += end + 2;
+            }
+            c if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) => {
+                // After a `.` this is a path index (`state.list.0.name`): integers only.
+                let index = tokens.last() == Some(&Token::Dot);
+                let start = i;
+                i += 1;
+                while i < chars.len()
+                    && (chars[i].is_ascii_digit()
+                        || (!index
+                            && chars[i] == '.'
+                            && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit())))
+                {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let n = text
+                    .parse::<f64>()
+                    .map_err(|_| format!("invalid number `{text}`"))?;
+                tokens.push(Token::Num(n));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(match word.as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    _ => Token::Ident(word),
+                });
+            }
+            _ => return Err(format!("unexpected character `{c}`")),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive-descent parser: or → and → not → comparison → primary.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        t
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), String> {
+        match self.advance() {
+            Some(t) if t == token => Ok(()),
+            _ => Err(format!("expected {token:?}")),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            lhs = Expr::Or(Box::new(lhs), Box::new(self.parse_and()?));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_not()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            lhs = Expr::And(Box::new(lhs), Box::new(self.parse_not()?));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, String> {
+        if self.peek() == Some(&Token::Not) {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_cmp()
+    }
+
+    fn parse_cmp(&mut self) -> Result<Expr, String> {
+        let lhs = self.parse_primary()?;
+        if let Some(Token::Cmp(op)) = self.peek().cloned() {
+            self.pos += 1;
+            let rhs = self.parse_primary()?;
+            return Ok(Expr::Cmp(op, Box::new(lhs), Box::new(rhs)));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, String> {
+        match self.advance() {
+            Some(Token::Num(n)) => Ok(Expr::Literal(
+                serde_json::Number::from_f64(n).map_or(Value::Null, Value::Number),
+            )),
+            Some(Token::Str(s)) => Ok(Expr::Literal(Value::String(s))),
+            Some(Token::LParen) => {
+                let e = self.parse_or()?;
+                self.expect(Token::RParen)?;
+                Ok(e)
+            }
+            Some(Token::Ident(word)) => match word.as_str() {
+                "true" => Ok(Expr::Literal(Value::Bool(true))),
+                "false" => Ok(Expr::Literal(Value::Bool(false))),
+                "null" => Ok(Expr::Literal(Value::Null)),
+                "len" => {
+                    self.expect(Token::LParen)?;
+                    let e = self.parse_or()?;
+                    self.expect(Token::RParen)?;
+                    Ok(Expr::Len(Box::new(e)))
+                }
+                "state" => self.parse_path(),
+                other => Err(format!("unknown name `{other}` (paths start with `state`)")),
+            },
+            Some(t) => Err(format!("unexpected {t:?}")),
+            None => Err("unexpected end of condition".to_string()),
+        }
+    }
+
+    fn parse_path(&mut self) -> Result<Expr, String> {
+        let mut segments = Vec::new();
+        while self.peek() == Some(&Token::Dot) {
+            self.pos += 1;
+            match self.advance() {
+                Some(Token::Ident(s)) => segments.push(s),
+                Some(Token::Num(n)) if n >= 0.0 && n.fract() == 0.0 => {
+                    segments.push((n as u64).to_string())
+                }
+                _ => return Err("expected a field name or index after `.`".to_string()),
+            }
+        }
+        Ok(Expr::Path(segments))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn holds(rule: &str, state: Value) -> bool {
+        RouteRule::parse(rule).unwrap().matches(&state)
+    }
+
+    #[test]
+    fn parses_source_target_and_end() {
+        let r = RouteRule::parse("observe: if state.turn_count > 5 goto summarize").unwrap();
+        assert_eq!(r.source, "observe");
+        assert_eq!(r.target, "summarize");
+        assert_eq!(
+            r.as_str(),
+            "observe: if state.turn_count > 5 goto summarize"
+        );
+        let r = RouteRule::parse("think: if true goto END").unwrap();
+        assert_eq!(r.target, END);
+    }
+
+    #[test]
+    fn evaluates_comparisons_logic_and_len() {
+        let state = json!({
+            "turn_count": 6,
+            "summary": null,
+            "messages": [{"role": "user"}, {"role": "assistant"}],
+            "mode": "draft"
+        });
+        assert!(holds("a: if state.turn_count > 5 goto b", state.clone()));
+        assert!(!holds("a: if state.turn_count >= 7 goto b", state.clone()));
+        assert!(holds(
+            "a: if len(state.messages) == 2 && state.mode == 'draft' goto b",
+            state.clone()
+        ));
+        assert!(holds(
+            "a: if not state.summary or state.turn_count < 0 goto b",
+            state.clone()
+        ));
+        assert!(holds(
+            "a: if state.messages.1.role == \"assistant\" goto b",
+            state.clone()
+        ));
+        assert!(!holds("a: if state.missing.field goto b", state.clone()));
+        assert!(!holds("a: if state.mode > 3 goto b", state));
+    }
+
+    #[test]
+    fn rejects_malformed_rules() {
+        for rule in [
+            "if state.x goto b",
+            "a: state.x goto b",
+            "a: if state.x",
+            "a: if x > 1 goto b",
+            "a: if state.x > goto b",
+            "a: if (state.x goto b",
+            "a: if state.x > 1 goto b c",
+        ] {
+            assert!(RouteRule::parse(rule).is_err(), "{rule}");
+        }
+    }
+
+    #[test]
+    fn parse_all_skips_blank_entries() {
+        let rules = RouteRule::parse_all(["a: if true goto b", " ", ""]).unwrap();
+        assert_eq!(rules.len(), 1);
+    }
+}
@@ -33,7 +33,9 @@ use tokio_util::sync::CancellationToken;
 use crate::cli_run::RunCancellation;
 use crate::managed::ManagedValue;
 use crate::memory::{RunnableConfig, Store};
-use crate::stream::{StreamEvent, StreamMode, StreamWriter};
+use crate::stream::{StreamEvent, StreamMode, StreamWriter, TimingKind};
+
+use super::StateSize;
 
 /// Run context passed into nodes for streaming-aware execution.
 ///
@@ -191,6 +193,11 @@ where
         self.runtime_context.as_ref()
     }
 
+    /// Whether feature flag `name` is on for this run (see [`RunnableConfig::flags`]).
+    pub fn flag(&self, name: &str) -> bool {
+        self.config.flag(name)
+    }
+
     // === StreamWriter Integration ===
 
     /// Creates a StreamWriter from this context.
@@ -244,6 +251,36 @@ where
         self.stream_writer().emit_message(content, node_id).await
     }
 
+    /// Emits a [`StreamEvent::Timing`] directly from the context.
+    ///
+    /// Only sends if `StreamMode::Tasks` or `StreamMode::Debug` is enabled.
+    ///
+    /// Returns `true` if the event was sent, `false` otherwise.
+    pub async fn emit_timing(
+        &self,
+        node_id: impl Into<String>,
+        kind: TimingKind,
+        name: Option<String>,
+        duration: std::time::Duration,
+    ) -> bool {
+        self.stream_writer()
+            .emit_timing(node_id, kind, name, duration)
+            .await
+    }
+
+    /// Emits a node [`StreamEvent::Timing`] carrying the state size after the node; see
+    /// [`StreamWriter::emit_node_timing`].
+    pub async fn emit_node_timing(
+        &self,
+        node_id: impl Into<String>,
+        duration: std::time::Duration,
+        state: Option<StateSize>,
+    ) -> bool {
+        self.stream_writer()
+            .emit_node_timing(node_id, duration, state)
+            .await
+    }
+
     /// Checks if a specific stream mode is enabled.
     ///
     /// Useful for nodes that want to conditionally perform expensive operations
@@ -288,6 +325,15 @@ mod tests {
         assert_eq!(ctx.get_managed_value("missing"), None);
     }
 
+    #[test]
+    fn flag_reads_run_config_flags() {
+        let mut config = RunnableConfig::default();
+        config.flags.insert("verify_node".into(), true);
+        let ctx = RunContext::<String>::new(config);
+        assert!(ctx.flag("verify_node"));
+        assert!(!ctx.flag("new_compaction"));
+    }
+
     #[tokio::test]
     async fn emit_helpers_send_events_when_modes_are_enabled() {
         let (tx, mut rx) = mpsc::channel(8);
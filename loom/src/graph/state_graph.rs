@@ -11,6 +11,18 @@
 //! used as the next node id, or looked up in an optional path map. A node must have
 //! either one outgoing `add_edge` or `add_conditional_edges`, not both.
 //!
+//! # Weighted edges
+//!
+//! `add_weighted_edges` splits traffic after a node between targets by weight (e.g. an A/B
+//! experiment between two implementations of a node); `add_weighted_conditional_edges` does
+//! the same for some keys of a routing function. The branch is sampled from a per-run seed,
+//! so a thread keeps its branch; see [`RunnableConfig::routing_seed`](crate::memory::RunnableConfig).
+//!
+//! # Validation
+//!
+//! `compile` checks the whole graph and fails with every problem at once; `validate` returns
+//! the same report (plus warnings such as unreachable nodes) without compiling.
+//!
 //! # State Updates
 //!
 //! By default, nodes return a new state that completely replaces the previous state.
@@ -25,10 +37,13 @@ use crate::channels::{BoxedStateUpdater, ReplaceUpdater};
 use crate::graph::compile_error::CompilationError;
 use crate::graph::compiled::CompiledStateGraph;
 use crate::graph::conditional::{ConditionalRouter, ConditionalRouterFn, NextEntry};
+use crate::graph::diagnostics::{check_connectivity, GraphDiagnostics, Successors};
 use crate::graph::interrupt::InterruptHandler;
 use crate::graph::node::Node;
 use crate::graph::node_middleware::NodeMiddleware;
 use crate::graph::retry::RetryPolicy;
+use crate::graph::state_validator::StateValidator;
+use crate::graph::weighted::{validate_weights, WeightedBranch};
 use crate::memory::{Checkpointer, Store};
 
 /// Sentinel for graph entry: use as `from_id` in `add_edge(START, first_node_id)`.
@@ -67,6 +82,8 @@ pub struct StateGraph<S> {
     retry_policy: RetryPolicy,
     /// Optional interrupt handler for human-in-the-loop scenarios.
     interrupt_handler: Option<Arc<dyn InterruptHandler>>,
+    /// Checks run on each node's output, in insertion order. See `with_state_validator`.
+    state_validators: Vec<Arc<dyn StateValidator<S>>>,
 }
 
 impl<S> Default for StateGraph<S>
@@ -93,6 +110,7 @@ where
             state_updater: None,
             retry_policy: RetryPolicy::None,
             interrupt_handler: None,
+            state_validators: Vec::new(),
         }
     }
 
@@ -194,6 +212,25 @@ where
         }
     }
 
+    /// Adds a validator run after each node with the node's input and output state; it may
+    /// amend the output or reject it, which fails the node. Validators run in the order added.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use loom::graph::{NonDecreasing, StateGraph};
+    /// use loom::state::ReActState;
+    /// use std::sync::Arc;
+    ///
+    /// let graph = StateGraph::<ReActState>::new().with_state_validator(Arc::new(
+    ///     NonDecreasing::new("messages", |s: &ReActState| s.messages.len()).except(["compress"]),
+    /// ));
+    /// ```
+    pub fn with_state_validator(mut self, validator: Arc<dyn StateValidator<S>>) -> Self {
+        self.state_validators.push(validator);
+        self
+    }
+
     /// Adds a node; id must be unique. Replaces if same id.
     ///
     /// Returns `&mut Self` for method chaining. The node is stored as
@@ -257,6 +294,47 @@ where
         self
     }
 
+    /// Adds weighted edges from `source`: after it runs, the next node is one of `branches`,
+    /// picked with probability proportional to its weight.
+    ///
+    /// Targets must be node ids or `END`; weights must be finite, non-negative and not all
+    /// zero. Like conditional edges, the source must not also have an `add_edge`.
+    ///
+    /// ```rust,ignore
+    /// graph.add_weighted_edges("think", [("act_v1", 0.9), ("act_v2", 0.1)]);
+    /// ```
+    pub fn add_weighted_edges<T: Into<String>>(
+        &mut self,
+        source: impl Into<String>,
+        branches: impl IntoIterator<Item = (T, f64)>,
+    ) -> &mut Self {
+        let branches = branches
+            .into_iter()
+            .map(|(target, weight)| WeightedBranch::new(target, weight))
+            .collect();
+        let router = ConditionalRouter::new(Arc::new(|_: &S| String::new()), Some(HashMap::new()))
+            .with_split(String::new(), branches);
+        self.conditional_edges.insert(source.into(), router);
+        self
+    }
+
+    /// Like [`Self::add_conditional_edges`], but keys in `splits` route to weighted branches
+    /// instead of a single node (e.g. only the `"tools"` route is split between two act nodes).
+    pub fn add_weighted_conditional_edges(
+        &mut self,
+        source: impl Into<String>,
+        path: ConditionalRouterFn<S>,
+        path_map: Option<HashMap<String, String>>,
+        splits: HashMap<String, Vec<WeightedBranch>>,
+    ) -> &mut Self {
+        let router = splits.into_iter().fold(
+            ConditionalRouter::new(path, path_map),
+            |r, (key, branches)| r.with_split(key, branches),
+        );
+        self.conditional_edges.insert(source.into(), router);
+        self
+    }
+
     /// Builds the executable graph: validates that all edge node ids exist and
     /// edges form a single linear chain from START to END.
     /// If middleware was set via `with_middleware`, it is used; otherwise no middleware.
@@ -296,83 +374,137 @@ where
         self.compile_internal(Some(checkpointer), Some(middleware))
     }
 
-    fn compile_internal(
-        self,
-        checkpointer: Option<Arc<dyn Checkpointer<S>>>,
-        middleware: Option<Arc<dyn NodeMiddleware<S>>>,
-    ) -> Result<CompiledStateGraph<S>, CompilationError> {
+    /// Checks the whole graph and returns every problem found, without compiling: unknown
+    /// nodes in edges and path maps, invalid weights, missing or branching START, no route to
+    /// END, nodes with both an edge and conditional edges, cycles with no route to END
+    /// (errors), and nodes START never reaches (warnings). `compile` fails when there are
+    /// errors; see [`GraphDiagnostics`] for how routes are judged.
+    pub fn validate(&self) -> GraphDiagnostics {
+        let mut report = GraphDiagnostics::default();
+        let mut sources: Vec<&String> = self.conditional_edges.keys().collect();
+        sources.sort();
+
         for (from, to) in &self.edges {
             if from != START && !self.nodes.contains_key(from) {
-                return Err(CompilationError::NodeNotFound(from.clone()));
+                report.error(CompilationError::NodeNotFound(from.clone()));
             }
             if to != END && !self.nodes.contains_key(to) {
-                return Err(CompilationError::NodeNotFound(to.clone()));
+                report.error(CompilationError::NodeNotFound(to.clone()));
             }
         }
-        for (source, router) in &self.conditional_edges {
-            if !self.nodes.contains_key(source) {
-                return Err(CompilationError::NodeNotFound(source.clone()));
+        for source in &sources {
+            let router = &self.conditional_edges[*source];
+            if !self.nodes.contains_key(*source) {
+                report.error(CompilationError::NodeNotFound((*source).clone()));
             }
             if let Some(ref path_map) = router.path_map {
-                for target in path_map.values() {
+                let mut targets: Vec<&String> = path_map.values().collect();
+                targets.sort();
+                for target in targets {
                     if target != END && !self.nodes.contains_key(target) {
-                        return Err(CompilationError::InvalidConditionalPathMap(target.clone()));
+                        report.error(CompilationError::InvalidConditionalPathMap(target.clone()));
+                    }
+                }
+            }
+            for branches in router.splits.values() {
+                for b in branches {
+                    if b.target != END && !self.nodes.contains_key(&b.target) {
+                        report.error(CompilationError::InvalidConditionalPathMap(
+                            b.target.clone(),
+                        ));
                     }
                 }
+                if let Err(e) = validate_weights(branches) {
+                    report.error(CompilationError::InvalidEdgeWeights(format!(
+                        "{}: {}",
+                        source, e
+                    )));
+                }
             }
         }
 
-        let start_edges: Vec<_> = self
+        let start_edges: Vec<&str> = self
             .edges
             .iter()
             .filter(|(f, _)| f == START)
-            .map(|(_, t)| t.clone())
+            .map(|(_, t)| t.as_str())
             .collect();
-        let first = match start_edges.len() {
-            0 => return Err(CompilationError::MissingStart),
-            1 => start_edges.into_iter().next().unwrap(),
-            _ => {
-                return Err(CompilationError::InvalidChain(
-                    "multiple edges from START (branch)".into(),
-                ))
-            }
-        };
+        match start_edges.len() {
+            0 => report.error(CompilationError::MissingStart),
+            1 => {}
+            _ => report.error(CompilationError::InvalidChain(
+                "multiple edges from START (branch)".into(),
+            )),
+        }
 
         let has_end = self.edges.iter().any(|(_, t)| t == END)
             || self.conditional_edges.values().any(|r| {
                 r.path_map
                     .as_ref()
                     .is_none_or(|m| m.values().any(|v| v == END))
+                    || r.splits.values().flatten().any(|b| b.target == END)
             });
         if !has_end {
-            return Err(CompilationError::MissingEnd);
+            report.error(CompilationError::MissingEnd);
         }
 
-        let edge_froms: HashSet<_> = self
-            .edges
-            .iter()
-            .filter(|(f, _)| f.as_str() != START)
-            .map(|(f, _)| f.clone())
-            .collect();
-        if edge_froms.len()
-            != self
-                .edges
-                .iter()
-                .filter(|(f, _)| f.as_str() != START)
-                .count()
-        {
-            return Err(CompilationError::InvalidChain(
+        let non_start = self.edges.iter().filter(|(f, _)| f.as_str() != START);
+        let edge_froms: HashSet<&str> = non_start.clone().map(|(f, _)| f.as_str()).collect();
+        if edge_froms.len() != non_start.count() {
+            report.error(CompilationError::InvalidChain(
                 "duplicate from (branch)".into(),
             ));
         }
-        for source in self.conditional_edges.keys() {
-            if edge_froms.contains(source) {
-                return Err(CompilationError::NodeHasBothEdgeAndConditional(
-                    source.clone(),
+        for source in &sources {
+            if edge_froms.contains(source.as_str()) {
+                report.error(CompilationError::NodeHasBothEdgeAndConditional(
+                    (*source).clone(),
                 ));
             }
         }
 
+        // Route analysis needs an entry and an exit; without them every node would be flagged.
+        if !start_edges.is_empty() && has_end {
+            let mut successors: Successors<'_> = HashMap::new();
+            for (from, to) in self.edges.iter().filter(|(f, _)| f.as_str() != START) {
+                if let Some(targets) = successors.entry(from.as_str()).or_insert(Some(Vec::new())) {
+                    targets.push(to.as_str());
+                }
+            }
+            for (source, router) in &self.conditional_edges {
+                let targets = router.path_map.as_ref().map(|m| {
+                    m.values()
+                        .map(String::as_str)
+                        .chain(router.splits.values().flatten().map(|b| b.target.as_str()))
+                        .collect()
+                });
+                successors.insert(source.as_str(), targets);
+            }
+            let mut nodes: Vec<&str> = self.nodes.keys().map(String::as_str).collect();
+            nodes.sort_unstable();
+            check_connectivity(&start_edges, &nodes, &successors, &mut report);
+        }
+        report
+    }
+
+    fn compile_internal(
+        self,
+        checkpointer: Option<Arc<dyn Checkpointer<S>>>,
+        middleware: Option<Arc<dyn NodeMiddleware<S>>>,
+    ) -> Result<CompiledStateGraph<S>, CompilationError> {
+        let report = self.validate();
+        for warning in &report.warnings {
+            tracing::debug!("graph: {}", warning);
+        }
+        report.into_result()?;
+
+        let first = self
+            .edges
+            .iter()
+            .find(|(f, _)| f == START)
+            .map(|(_, t)| t.clone())
+            .ok_or(CompilationError::MissingStart)?;
+
         let mut next_map: HashMap<String, NextEntry<S>> = self
             .edges
             .iter()
@@ -422,6 +554,9 @@ where
             state_updater,
             retry_policy: self.retry_policy,
             interrupt_handler: self.interrupt_handler,
+            state_validators: self.state_validators,
+            route_rules: None,
+            state_limits: None,
         })
     }
 }
@@ -492,10 +627,13 @@ mod tests {
                     .collect(),
             ),
         );
+        // The path_map has no END either; both problems are reported.
         let result = graph.compile();
         match result {
-            Err(CompilationError::InvalidConditionalPathMap(id)) => assert_eq!(id, "nonexistent"),
-            Err(e) => panic!(
+            Err(e) => assert!(
+                e.problems().iter().any(
+                    |p| matches!(p, CompilationError::InvalidConditionalPathMap(id) if id == "nonexistent")
+                ),
                 "expected InvalidConditionalPathMap(nonexistent), got {:?}",
                 e
             ),
@@ -644,12 +782,103 @@ mod tests {
         let graph = StateGraph::<DummyState>::default();
         let result = graph.compile();
         match result {
-            Err(CompilationError::MissingStart) => {}
-            Err(e) => panic!("expected MissingStart for empty graph, got {:?}", e),
+            Err(e) => assert!(
+                matches!(
+                    e.problems(),
+                    [CompilationError::MissingStart, CompilationError::MissingEnd]
+                ),
+                "expected MissingStart and MissingEnd for empty graph, got {:?}",
+                e
+            ),
             Ok(_) => panic!("empty graph should not compile"),
         }
     }
 
+    /// **Scenario**: Compile reports every problem at once instead of the first one.
+    #[test]
+    fn compile_reports_all_problems_at_once() {
+        let mut graph = StateGraph::<DummyState>::new();
+        graph.add_node("a", Arc::new(DummyNode("a")));
+        graph.add_edge(START, "a");
+        graph.add_edge("a", "ghost");
+        graph.add_edge("ghost", END);
+        graph.add_conditional_edges(
+            "b",
+            Arc::new(|_| "x".to_string()),
+            Some(
+                [("x".to_string(), "nowhere".to_string())]
+                    .into_iter()
+                    .collect(),
+            ),
+        );
+        let err = graph.compile().err().expect("expected compile error");
+        let problems: Vec<String> = err.problems().iter().map(|e| e.to_string()).collect();
+        assert!(matches!(err, CompilationError::Diagnostics(_)));
+        assert_eq!(
+            problems,
+            vec![
+                "node not found: ghost",
+                "node not found: b",
+                "conditional path_map invalid target: nowhere",
+            ]
+        );
+    }
+
+    /// **Scenario**: A loop whose routes never lead to END is an error; a node START never
+    /// reaches is a warning.
+    #[test]
+    fn validate_finds_cycle_without_exit_and_unreachable_node() {
+        let mut graph = StateGraph::<DummyState>::new();
+        for id in ["a", "b", "c", "orphan"] {
+            graph.add_node(id, Arc::new(DummyNode(id)));
+        }
+        graph.add_edge(START, "a");
+        graph.add_conditional_edges(
+            "a",
+            Arc::new(|_| "loop".to_string()),
+            Some(
+                [
+                    ("loop".to_string(), "b".to_string()),
+                    ("done".to_string(), END.to_string()),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+        );
+        graph.add_edge("b", "c");
+        graph.add_edge("c", "b");
+        graph.add_edge("orphan", END);
+
+        let report = graph.validate();
+        assert!(!report.is_ok());
+        assert!(matches!(
+            report.errors.as_slice(),
+            [CompilationError::CycleWithoutExit(ids)] if ids == &["b", "c"]
+        ));
+        assert!(matches!(
+            report.warnings.as_slice(),
+            [CompilationError::UnreachableNode(id)] if id == "orphan"
+        ));
+    }
+
+    /// **Scenario**: A router without path_map may route anywhere, so nothing is flagged.
+    #[test]
+    fn validate_trusts_routers_without_path_map() {
+        let mut graph = StateGraph::<DummyState>::new();
+        for id in ["a", "b", "c"] {
+            graph.add_node(id, Arc::new(DummyNode(id)));
+        }
+        graph.add_edge(START, "a");
+        graph.add_conditional_edges("a", Arc::new(|_| END.to_string()), None);
+        graph.add_edge("b", "c");
+        graph.add_edge("c", "a");
+
+        let report = graph.validate();
+        assert!(report.is_ok(), "{}", report);
+        assert!(report.warnings.is_empty(), "{}", report);
+        assert!(graph.compile().is_ok());
+    }
+
     /// **Scenario**: Compile succeeds with valid linear chain.
     #[test]
     fn compile_succeeds_linear_chain() {
@@ -682,6 +911,35 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    /// **Scenario**: Weighted edges compile; unknown targets and bad weights are rejected.
+    #[test]
+    fn compile_validates_weighted_edges() {
+        let build = |branches: Vec<(&str, f64)>| {
+            let mut graph = StateGraph::<DummyState>::new();
+            graph.add_node("a", Arc::new(DummyNode("a")));
+            graph.add_node("b", Arc::new(DummyNode("b")));
+            graph.add_node("c", Arc::new(DummyNode("c")));
+            graph.add_edge(START, "a");
+            graph.add_edge("b", END);
+            graph.add_edge("c", END);
+            graph.add_weighted_edges("a", branches);
+            graph.compile()
+        };
+        assert!(build(vec![("b", 0.5), ("c", 0.5)]).is_ok());
+        assert!(matches!(
+            build(vec![("b", 0.5), ("x", 0.5)]),
+            Err(CompilationError::InvalidConditionalPathMap(id)) if id == "x"
+        ));
+        assert!(matches!(
+            build(vec![("b", -1.0), ("c", 2.0)]),
+            Err(CompilationError::InvalidEdgeWeights(_))
+        ));
+        assert!(matches!(
+            build(vec![("b", 0.0), ("c", 0.0)]),
+            Err(CompilationError::InvalidEdgeWeights(_))
+        ));
+    }
+
     /// **Scenario**: with_store, with_middleware, with_retry_policy are applied.
     #[test]
     fn builder_methods_apply() {
@@ -0,0 +1,195 @@
+//! State size limits: caps on how many messages and bytes a run's state may carry, so one
+//! oversized input (e.g. a pasted multi-megabyte log) cannot exhaust memory as the state is
+//! cloned, streamed and checkpointed.
+//!
+//! Attach with [`CompiledStateGraph::with_state_limits`](super::CompiledStateGraph::with_state_limits)
+//! for states implementing [`SizedState`]. The executor checks the input state before the first
+//! node and the merged state after each node. Over a limit, the state is shrunk with
+//! [`SizedState::shrink`] ([`OnStateLimit::Compact`], the default); when that is not enough, or
+//! with [`OnStateLimit::Fail`], the run fails with [`AgentError::StateTooLarge`] and advice on
+//! what to do instead. Node `Timing` events report the state size after each node.
+
+use crate::error::AgentError;
+
+/// What to do with a state over its limits.
+const ADVICE: &str = "put large content (logs, dumps) in a file in the working folder and refer to it instead of pasting it, start a new thread, or raise LOOM_STATE_MAX_BYTES / LOOM_STATE_MAX_MESSAGES";
+
+/// Size of a state as measured for [`StateLimits`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StateSize {
+    pub messages: usize,
+    pub bytes: usize,
+}
+
+/// State that can be measured and shrunk to fit [`StateLimits`].
+pub trait SizedState {
+    /// Current size. `bytes` may be an estimate (e.g. text content only) as long as it grows
+    /// with what the state carries.
+    fn state_size(&self) -> StateSize;
+
+    /// Shrinks the state towards `limits` (e.g. truncating large messages, dropping old ones).
+    /// Need not succeed; the executor measures again afterwards.
+    fn shrink(&mut self, limits: &StateLimits);
+}
+
+/// Action when the state exceeds a limit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OnStateLimit {
+    /// Shrink the state; fail only when it still does not fit.
+    #[default]
+    Compact,
+    /// Fail the run.
+    Fail,
+}
+
+/// Caps on state size. `None` limits are not applied.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StateLimits {
+    pub max_messages: Option<usize>,
+    pub max_bytes: Option<usize>,
+    pub on_exceed: OnStateLimit,
+}
+
+impl StateLimits {
+    /// Reads `LOOM_STATE_MAX_MESSAGES`, `LOOM_STATE_MAX_BYTES` and `LOOM_STATE_LIMIT_ACTION`
+    /// (`compact`, default, or `fail`). Returns `None` when neither limit is set.
+    pub fn from_env() -> Option<Self> {
+        let max_messages = std::env::var("LOOM_STATE_MAX_MESSAGES")
+            .ok()
+            .and_then(|s| s.trim().parse().ok());
+        let max_bytes = std::env::var("LOOM_STATE_MAX_BYTES")
+            .ok()
+            .and_then(|s| s.trim().parse().ok());
+        if max_messages.is_none() && max_bytes.is_none() {
+            return None;
+        }
+        let on_exceed = match std::env::var("LOOM_STATE_LIMIT_ACTION")
+            .map(|s| s.trim().to_lowercase())
+            .as_deref()
+        {
+            Ok("fail") => OnStateLimit::Fail,
+            _ => OnStateLimit::Compact,
+        };
+        Some(Self {
+            max_messages,
+            max_bytes,
+            on_exceed,
+        })
+    }
+
+    /// Describes the limits `size` exceeds; `None` when it fits.
+    pub fn exceeded(&self, size: StateSize) -> Option<String> {
+        let mut over = Vec::new();
+        if let Some(max) = self.max_messages.filter(|max| size.messages > *max) {
+            over.push(format!("{} messages (limit {})", size.messages, max));
+        }
+        if let Some(max) = self.max_bytes.filter(|max| size.bytes > *max) {
+            over.push(format!("{} bytes (limit {})", size.bytes, max));
+        }
+        (!over.is_empty()).then(|| over.join(", "))
+    }
+}
+
+/// [`StateLimits`] bound to a state type, as held by the compiled graph.
+#[derive(Clone)]
+pub(super) struct StateLimitGuard<S> {
+    limits: StateLimits,
+    measure: fn(&S) -> StateSize,
+    shrink: fn(&mut S, &StateLimits),
+}
+
+impl<S> StateLimitGuard<S> {
+    pub(super) fn new(limits: StateLimits) -> Self
+    where
+        S: SizedState,
+    {
+        Self {
+            limits,
+            measure: S::state_size,
+            shrink: S::shrink,
+        }
+    }
+
+    /// Applies the limits to `state`, the state after `at` (e.g. "node 'think'"). Returns the
+    /// size of the state as it continues.
+    pub(super) fn enforce(&self, at: &str, state: &mut S) -> Result<StateSize, AgentError> {
+        let size = (self.measure)(state);
+        let Some(over) = self.limits.exceeded(size) else {
+            return Ok(size);
+        };
+        if self.limits.on_exceed == OnStateLimit::Compact {
+            (self.shrink)(state, &self.limits);
+            let shrunk = (self.measure)(state);
+            if self.limits.exceeded(shrunk).is_none() {
+                tracing::warn!(
+                    at,
+                    messages = shrunk.messages,
+                    bytes = shrunk.bytes,
+                    "state over size limit ({}); compacted",
+                    over
+                );
+                return Ok(shrunk);
+            }
+        }
+        Err(AgentError::StateTooLarge(format!(
+            "state after {} has {}; {}",
+            at, over, ADVICE
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Text chunks; shrinking drops the oldest.
+    #[derive(Clone, Debug)]
+    struct Chunks(Vec<String>);
+
+    impl SizedState for Chunks {
+        fn state_size(&self) -> StateSize {
+            StateSize {
+                messages: self.0.len(),
+                bytes: self.0.iter().map(String::len).sum(),
+            }
+        }
+
+        fn shrink(&mut self, limits: &StateLimits) {
+            while limits.exceeded(self.state_size()).is_some() && self.0.len() > 1 {
+                self.0.remove(0);
+            }
+        }
+    }
+
+    #[test]
+    fn compacts_when_it_can_and_fails_with_advice_otherwise() {
+        let limits = StateLimits {
+            max_messages: Some(2),
+            max_bytes: Some(10),
+            on_exceed: OnStateLimit::Compact,
+        };
+        let guard = StateLimitGuard::<Chunks>::new(limits.clone());
+        let mut state = Chunks(vec!["aaaa".into(), "bbbb".into(), "cccc".into()]);
+        let size = guard.enforce("node 'a'", &mut state).unwrap();
+        assert_eq!(
+            size,
+            StateSize {
+                messages: 2,
+                bytes: 8
+            }
+        );
+
+        let mut huge = Chunks(vec!["x".repeat(64)]);
+        let err = guard.enforce("the run input", &mut huge).unwrap_err();
+        assert_eq!(err.code(), Some("state_too_large"));
+        assert!(err.to_string().contains("64 bytes (limit 10)"), "{}", err);
+
+        let fail = StateLimitGuard::<Chunks>::new(StateLimits {
+            on_exceed: OnStateLimit::Fail,
+            ..limits
+        });
+        let mut state = Chunks(vec!["aaaa".into(), "bbbb".into(), "cccc".into()]);
+        assert!(fail.enforce("node 'a'", &mut state).is_err());
+        assert_eq!(state.0.len(), 3, "fail leaves the state alone");
+    }
+}
@@ -0,0 +1,149 @@
+//! State validators: checks run after each node with the node's input and output state.
+//!
+//! Attach with [`StateGraph::with_state_validator`](super::StateGraph::with_state_validator).
+//! A validator sees the state the node received (`before`) and the state it returned
+//! (`after`, before it is merged by the graph's state updater). It may amend `after` in place
+//! or reject it; a rejection fails the node like a node error would (subject to the graph's
+//! retry policy). Meant for catching buggy custom nodes early, e.g. with [`NonDecreasing`]
+//! ("messages only ever grow") or [`Invariant`].
+
+use std::collections::HashSet;
+use std::fmt::Debug;
+
+/// Check run after each node; see the [module docs](self).
+pub trait StateValidator<S>: Send + Sync
+where
+    S: Clone + Send + Sync + Debug + 'static,
+{
+    /// Validates the output of node `node_id`. Return `Err(reason)` to fail the node; mutate
+    /// `after` to amend the update.
+    fn validate(&self, node_id: &str, before: &S, after: &mut S) -> Result<(), String>;
+}
+
+/// Validator from a closure with the same signature as [`StateValidator::validate`].
+pub struct FnStateValidator<F> {
+    f: F,
+}
+
+impl<F> FnStateValidator<F> {
+    pub fn new(f: F) -> Self {
+        Self { f }
+    }
+}
+
+impl<S, F> StateValidator<S> for FnStateValidator<F>
+where
+    S: Clone + Send + Sync + Debug + 'static,
+    F: Fn(&str, &S, &mut S) -> Result<(), String> + Send + Sync,
+{
+    fn validate(&self, node_id: &str, before: &S, after: &mut S) -> Result<(), String> {
+        (self.f)(node_id, before, after)
+    }
+}
+
+/// Rejects a node output when a measured quantity shrank, e.g. "messages only ever grow":
+/// `NonDecreasing::new("messages", |s: &ReActState| s.messages.len())`. Nodes that legitimately
+/// shrink it (such as a compaction node) are exempted with [`NonDecreasing::except`].
+pub struct NonDecreasing<F> {
+    name: String,
+    measure: F,
+    exempt: HashSet<String>,
+}
+
+impl<F> NonDecreasing<F> {
+    /// `name` labels the quantity in rejection messages.
+    pub fn new(name: impl Into<String>, measure: F) -> Self {
+        Self {
+            name: name.into(),
+            measure,
+            exempt: HashSet::new(),
+        }
+    }
+
+    /// Skips the check for these node ids.
+    pub fn except<I, T>(mut self, node_ids: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.exempt.extend(node_ids.into_iter().map(Into::into));
+        self
+    }
+}
+
+impl<S, F> StateValidator<S> for NonDecreasing<F>
+where
+    S: Clone + Send + Sync + Debug + 'static,
+    F: Fn(&S) -> usize + Send + Sync,
+{
+    fn validate(&self, node_id: &str, before: &S, after: &mut S) -> Result<(), String> {
+        if self.exempt.contains(node_id) {
+            return Ok(());
+        }
+        let (was, now) = ((self.measure)(before), (self.measure)(after));
+        if now < was {
+            return Err(format!("{} shrank from {} to {}", self.name, was, now));
+        }
+        Ok(())
+    }
+}
+
+/// Rejects a node output that does not satisfy `predicate`.
+pub struct Invariant<F> {
+    name: String,
+    predicate: F,
+}
+
+impl<F> Invariant<F> {
+    /// `name` describes the invariant in rejection messages.
+    pub fn new(name: impl Into<String>, predicate: F) -> Self {
+        Self {
+            name: name.into(),
+            predicate,
+        }
+    }
+}
+
+impl<S, F> StateValidator<S> for Invariant<F>
+where
+    S: Clone + Send + Sync + Debug + 'static,
+    F: Fn(&S) -> bool + Send + Sync,
+{
+    fn validate(&self, _node_id: &str, _before: &S, after: &mut S) -> Result<(), String> {
+        if (self.predicate)(after) {
+            Ok(())
+        } else {
+            Err(format!("invariant violated: {}", self.name))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_decreasing_rejects_shrinking_and_honours_exemptions() {
+        let v = NonDecreasing::new("items", |s: &Vec<i32>| s.len()).except(["compact"]);
+        let before = vec![1, 2];
+        assert!(v.validate("add", &before, &mut vec![1, 2, 3]).is_ok());
+        let err = v.validate("drop", &before, &mut vec![1]).unwrap_err();
+        assert_eq!(err, "items shrank from 2 to 1");
+        assert!(v.validate("compact", &before, &mut vec![]).is_ok());
+    }
+
+    #[test]
+    fn invariant_and_fn_validator() {
+        let positive = Invariant::new("all positive", |s: &Vec<i32>| s.iter().all(|x| *x > 0));
+        assert!(positive.validate("n", &vec![], &mut vec![1]).is_ok());
+        assert!(positive.validate("n", &vec![], &mut vec![-1]).is_err());
+
+        let dedup = FnStateValidator::new(|_: &str, _: &Vec<i32>, after: &mut Vec<i32>| {
+            after.dedup();
+            Ok(())
+        });
+        let mut after = vec![1, 1, 2];
+        dedup.validate("n", &vec![], &mut after).unwrap();
+        assert_eq!(after, vec![1, 2]);
+    }
+}
@@ -1,13 +1,162 @@
 //! Graph visualization utilities.
 //!
 //! Provides functionality to export graph structure to Graphviz DOT format
-//! for visualization and debugging.
+//! for visualization and debugging, and [`GraphProgress`] snapshots (emitted as
+//! `StreamEvent::GraphProgress` during a run) that [`annotate_dot`] paints onto the DOT output.
 
+use std::collections::BTreeMap;
 use std::fmt::Write;
 
+use serde::{Deserialize, Serialize};
+
 use super::CompiledStateGraph;
+use super::NextEntry;
 use super::{END, START};
 
+/// One edge of a compiled graph. Conditional edges carry the routing key(s) as `label`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// Live run progress over the graph structure: which node runs now and how often each ran.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphProgress {
+    /// Node ids of the graph (without START/END), sorted.
+    pub nodes: Vec<String>,
+    /// Graph edges, including START and END edges.
+    pub edges: Vec<GraphEdge>,
+    /// Node being executed; `None` once the run has finished.
+    pub current: Option<String>,
+    /// Number of times each node has started in this run.
+    pub visits: BTreeMap<String, u32>,
+}
+
+/// Display status of one node in a [`GraphProgress`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeStatus {
+    /// Not run yet.
+    Pending,
+    /// Currently executing.
+    Running,
+    /// Ran at least once and is not running now.
+    Visited,
+}
+
+impl GraphProgress {
+    /// Progress for the start of a run: structure of `graph`, nothing visited.
+    pub fn new<S>(graph: &CompiledStateGraph<S>) -> Self {
+        Self {
+            nodes: graph_node_ids(graph),
+            edges: graph_edges(graph),
+            current: None,
+            visits: BTreeMap::new(),
+        }
+    }
+
+    /// Marks `node_id` as running and counts the visit.
+    pub fn enter(&mut self, node_id: &str) {
+        *self.visits.entry(node_id.to_string()).or_insert(0) += 1;
+        self.current = Some(node_id.to_string());
+    }
+
+    /// Number of times `node_id` has started.
+    pub fn visit_count(&self, node_id: &str) -> u32 {
+        self.visits.get(node_id).copied().unwrap_or(0)
+    }
+
+    pub fn status(&self, node_id: &str) -> NodeStatus {
+        if self.current.as_deref() == Some(node_id) {
+            NodeStatus::Running
+        } else if self.visit_count(node_id) > 0 {
+            NodeStatus::Visited
+        } else {
+            NodeStatus::Pending
+        }
+    }
+}
+
+/// Node ids of the graph (without START/END), sorted.
+pub fn graph_node_ids<S>(graph: &CompiledStateGraph<S>) -> Vec<String> {
+    let mut ids: Vec<String> = graph.nodes.keys().cloned().collect();
+    ids.sort();
+    ids
+}
+
+/// Edges of the graph: START to the first node, fixed edges, every target of a conditional
+/// path map (routers without a path map have no static targets) and every branch of a weighted
+/// split, labelled with its share. Sorted after the START edge.
+pub fn graph_edges<S>(graph: &CompiledStateGraph<S>) -> Vec<GraphEdge> {
+    let mut edges = Vec::new();
+    for (from, entry) in &graph.next_map {
+        match entry {
+            NextEntry::Unconditional(to) => edges.push(GraphEdge {
+                from: from.clone(),
+                to: to.clone(),
+                label: None,
+            }),
+            NextEntry::Conditional(router) => {
+                let mut splits: Vec<_> = router.splits.iter().collect();
+                splits.sort_by(|a, b| a.0.cmp(b.0));
+                for (key, branches) in splits {
+                    let total: f64 = branches.iter().map(|b| b.weight).sum();
+                    for b in branches {
+                        let share = format!("{:.0}%", b.weight / total * 100.0);
+                        edges.push(GraphEdge {
+                            from: from.clone(),
+                            to: b.target.clone(),
+                            label: Some(if key.is_empty() {
+                                share
+                            } else {
+                                format!("{} {}", key, share)
+                            }),
+                        });
+                    }
+                }
+                let Some(path_map) = &router.path_map else {
+                    continue;
+                };
+                let mut by_target: BTreeMap<&String, Vec<&str>> = BTreeMap::new();
+                for (key, to) in path_map {
+                    by_target.entry(to).or_default().push(key);
+                }
+                for (to, mut keys) in by_target {
+                    keys.sort();
+                    let label = (keys != [to.as_str()]).then(|| keys.join(","));
+                    edges.push(GraphEdge {
+                        from: from.clone(),
+                        to: to.clone(),
+                        label,
+                    });
+                }
+            }
+        }
+    }
+    // Nodes that rely on `Next::Continue` without an explicit edge follow edge order.
+    for pair in graph.edge_order.windows(2) {
+        if !graph.next_map.contains_key(&pair[0]) {
+            edges.push(GraphEdge {
+                from: pair[0].clone(),
+                to: pair[1].clone(),
+                label: None,
+            });
+        }
+    }
+    edges.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+    edges.insert(
+        0,
+        GraphEdge {
+            from: START.to_string(),
+            to: graph.first_node_id.clone(),
+            label: None,
+        },
+    );
+    edges
+}
+
 /// Generate Graphviz DOT format representation of the graph.
 ///
 /// Returns a string in DOT format that can be rendered using Graphviz tools.
@@ -30,32 +179,19 @@ where
     ));
 
     // Add regular nodes
-    for node_id in graph.nodes.keys() {
+    for node_id in graph_node_ids(graph) {
         dot.push_str(&format!("  \"{}\";\n", node_id));
     }
 
     dot.push('\n');
 
-    // Add edges based on edge_order
-    if !graph.edge_order.is_empty() {
-        // Edge from START to first node
-        dot.push_str(&format!(
-            "  \"{}\" -> \"{}\";\n",
-            START, graph.edge_order[0]
-        ));
-
-        // Edges between nodes
-        for i in 1..graph.edge_order.len() {
-            dot.push_str(&format!(
-                "  \"{}\" -> \"{}\";\n",
-                graph.edge_order[i - 1],
-                graph.edge_order[i]
-            ));
-        }
-
-        // Edge from last node to END
-        if let Some(last_node) = graph.edge_order.last() {
-            dot.push_str(&format!("  \"{}\" -> \"{}\";\n", last_node, END));
+    for edge in graph_edges(graph) {
+        match edge.label {
+            Some(label) => dot.push_str(&format!(
+                "  \"{}\" -> \"{}\" [label=\"{}\"];\n",
+                edge.from, edge.to, label
+            )),
+            None => dot.push_str(&format!("  \"{}\" -> \"{}\";\n", edge.from, edge.to)),
         }
     }
 
@@ -63,6 +199,52 @@ where
     dot
 }
 
+/// Paints per-node status from `progress` onto DOT produced by [`generate_dot`]: the running node
+/// is filled gold, visited nodes light blue with their visit count in the label. Other lines are
+/// kept as they are.
+pub fn annotate_dot(dot: &str, progress: &GraphProgress) -> String {
+    let mut out = String::with_capacity(dot.len());
+    for line in dot.lines() {
+        let node_id = line
+            .trim()
+            .strip_suffix(';')
+            .and_then(|l| l.strip_prefix('"'))
+            .and_then(|l| l.strip_suffix('"'))
+            .filter(|id| !id.contains('"') && progress.nodes.iter().any(|n| n == id));
+        match node_id.map(|id| (id, progress.status(id))) {
+            Some((id, NodeStatus::Running)) => writeln!(
+                out,
+                "  \"{}\" [style=filled, fillcolor=gold, label=\"{} ({})\"];",
+                id,
+                id,
+                progress.visit_count(id)
+            )
+            .unwrap(),
+            Some((id, NodeStatus::Visited)) => writeln!(
+                out,
+                "  \"{}\" [style=filled, fillcolor=lightblue, label=\"{} ({})\"];",
+                id,
+                id,
+                progress.visit_count(id)
+            )
+            .unwrap(),
+            _ => writeln!(out, "{}", line).unwrap(),
+        }
+    }
+    out
+}
+
+/// [`generate_dot`] with live status from `progress` (see [`annotate_dot`]).
+pub fn generate_dot_with_progress<S>(
+    graph: &CompiledStateGraph<S>,
+    progress: &GraphProgress,
+) -> String
+where
+    S: std::fmt::Debug,
+{
+    annotate_dot(&generate_dot(graph), progress)
+}
+
 /// Generate a simple text representation of the graph structure.
 pub fn generate_text<S>(graph: &CompiledStateGraph<S>) -> String
 where
@@ -124,4 +306,51 @@ mod tests {
         assert!(text.contains(END)); // Use the constant directly
         assert!(text.contains("node1"));
     }
+
+    #[test]
+    fn conditional_edges_appear_in_dot_and_progress_paints_nodes() {
+        let mut graph = StateGraph::<String>::new();
+        graph.add_node("think", std::sync::Arc::new(NameNode::new("think")));
+        graph.add_node("act", std::sync::Arc::new(NameNode::new("act")));
+        graph.add_edge(START, "think");
+        graph.add_conditional_edges(
+            "think",
+            std::sync::Arc::new(|_: &String| END.to_string()),
+            Some(
+                [
+                    ("tools".to_string(), "act".to_string()),
+                    (END.to_string(), END.to_string()),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+        );
+        graph.add_edge("act", "think");
+        let compiled = graph.compile().unwrap();
+
+        let edges = graph_edges(&compiled);
+        assert_eq!(edges[0].from, START);
+        assert!(edges.contains(&GraphEdge {
+            from: "think".into(),
+            to: "act".into(),
+            label: Some("tools".into()),
+        }));
+        assert!(edges.contains(&GraphEdge {
+            from: "think".into(),
+            to: END.into(),
+            label: None,
+        }));
+
+        let mut progress = GraphProgress::new(&compiled);
+        progress.enter("think");
+        progress.enter("act");
+        progress.enter("think");
+        assert_eq!(progress.status("think"), NodeStatus::Running);
+        assert_eq!(progress.status("act"), NodeStatus::Visited);
+
+        let dot = generate_dot_with_progress(&compiled, &progress);
+        assert!(dot.contains("\"think\" -> \"act\" [label=\"tools\"];"));
+        assert!(dot.contains("\"think\" [style=filled, fillcolor=gold, label=\"think (2)\"];"));
+        assert!(dot.contains("\"act\" [style=filled, fillcolor=lightblue, label=\"act (1)\"];"));
+    }
 }
@@ -0,0 +1,182 @@
+//! Weighted edges: split traffic from a node between targets by weight (e.g. A/B experiments
+//! between two node implementations).
+//!
+//! A split is sampled from a [`RoutingRng`] seeded per run (see [`routing_seed`]): with a
+//! `thread_id` the same thread always takes the same branch, so each conversation stays in one
+//! arm of the experiment; [`RunnableConfig::routing_seed`] or [`ENV_ROUTING_SEED`] make the
+//! choice reproducible. Each sampled choice is logged and emitted as a [`ROUTE_SPLIT_EVENT_TYPE`]
+//! custom event so runs can be grouped by branch afterwards.
+
+use std::hash::{BuildHasher, Hasher};
+
+use crate::memory::RunnableConfig;
+
+/// Custom event type emitted when a weighted split picks a branch.
+pub const ROUTE_SPLIT_EVENT_TYPE: &str = "route_split";
+
+/// Env var: routing seed for runs whose config sets none (`u64`).
+pub const ENV_ROUTING_SEED: &str = "LOOM_ROUTING_SEED";
+
+/// One target of a weighted split. Weights are relative; they need not sum to 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeightedBranch {
+    pub target: String,
+    pub weight: f64,
+}
+
+impl WeightedBranch {
+    pub fn new(target: impl Into<String>, weight: f64) -> Self {
+        Self {
+            target: target.into(),
+            weight,
+        }
+    }
+}
+
+/// Checks that weights are finite, non-negative and not all zero.
+pub(super) fn validate_weights(branches: &[WeightedBranch]) -> Result<(), String> {
+    if let Some(b) = branches
+        .iter()
+        .find(|b| !b.weight.is_finite() || b.weight < 0.0)
+    {
+        return Err(format!("weight of `{}` is {}", b.target, b.weight));
+    }
+    if branches.iter().map(|b| b.weight).sum::<f64>() <= 0.0 {
+        return Err("weights sum to zero".to_string());
+    }
+    Ok(())
+}
+
+/// Picks the branch whose cumulative weight covers `sample` (in `[0, 1)`).
+pub(super) fn pick(branches: &[WeightedBranch], sample: f64) -> &WeightedBranch {
+    let total: f64 = branches.iter().map(|b| b.weight).sum();
+    let mut point = sample * total;
+    for b in branches {
+        if point < b.weight {
+            return b;
+        }
+        point -= b.weight;
+    }
+    branches
+        .iter()
+        .rev()
+        .find(|b| b.weight > 0.0)
+        .unwrap_or(&branches[0])
+}
+
+/// Small seedable RNG (SplitMix64); good enough for traffic splitting, not for cryptography.
+#[derive(Clone, Debug)]
+pub struct RoutingRng {
+    state: u64,
+}
+
+impl RoutingRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform sample in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Stable 64-bit FNV-1a hash (the same across processes, unlike `DefaultHasher` seeds).
+pub(super) fn stable_hash(s: &str) -> u64 {
+    s.bytes().fold(0xcbf2_9ce4_8422_2325, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Seed of a run's weighted splits: the explicit seed (config, else [`ENV_ROUTING_SEED`])
+/// mixed with the thread id. Without either, a random seed.
+pub fn routing_seed(config: Option<&RunnableConfig>) -> u64 {
+    let explicit = config.and_then(|c| c.routing_seed).or_else(|| {
+        std::env::var(ENV_ROUTING_SEED)
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+    });
+    let thread = config.and_then(|c| c.thread_id.as_deref()).map(stable_hash);
+    match (explicit, thread) {
+        (Some(seed), Some(thread)) => RoutingRng::new(seed ^ thread).next_u64(),
+        (Some(seed), None) => seed,
+        (None, Some(thread)) => thread,
+        (None, None) => std::collections::hash_map::RandomState::new()
+            .build_hasher()
+            .finish(),
+    }
+}
+
+/// Sample for the split after `source`: fixed for a given run seed and source node.
+pub(super) fn split_sample(seed: u64, source: &str) -> f64 {
+    RoutingRng::new(seed ^ stable_hash(source)).next_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rng_is_deterministic_and_in_range() {
+        let mut a = RoutingRng::new(42);
+        let mut b = RoutingRng::new(42);
+        for _ in 0..100 {
+            let x = a.next_f64();
+            assert_eq!(x, b.next_f64());
+            assert!((0.0..1.0).contains(&x));
+        }
+    }
+
+    #[test]
+    fn pick_follows_weights() {
+        let branches = vec![WeightedBranch::new("a", 3.0), WeightedBranch::new("b", 1.0)];
+        assert_eq!(pick(&branches, 0.0).target, "a");
+        assert_eq!(pick(&branches, 0.74).target, "a");
+        assert_eq!(pick(&branches, 0.76).target, "b");
+
+        let mut rng = RoutingRng::new(7);
+        let a = (0..10_000)
+            .filter(|_| pick(&branches, rng.next_f64()).target == "a")
+            .count();
+        assert!((7_000..8_000).contains(&a), "a picked {} times", a);
+    }
+
+    #[test]
+    fn zero_weight_branch_is_never_picked() {
+        let branches = vec![WeightedBranch::new("a", 1.0), WeightedBranch::new("b", 0.0)];
+        assert_eq!(pick(&branches, 0.999).target, "a");
+    }
+
+    #[test]
+    fn invalid_weights_are_rejected() {
+        assert!(validate_weights(&[WeightedBranch::new("a", -1.0)]).is_err());
+        assert!(validate_weights(&[WeightedBranch::new("a", f64::NAN)]).is_err());
+        assert!(validate_weights(&[WeightedBranch::new("a", 0.0)]).is_err());
+        assert!(validate_weights(&[WeightedBranch::new("a", 0.5)]).is_ok());
+    }
+
+    #[test]
+    fn seed_is_sticky_per_thread() {
+        let config = |thread: &str| RunnableConfig {
+            thread_id: Some(thread.to_string()),
+            routing_seed: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            routing_seed(Some(&config("t1"))),
+            routing_seed(Some(&config("t1")))
+        );
+        assert_ne!(
+            routing_seed(Some(&config("t1"))),
+            routing_seed(Some(&config("t2")))
+        );
+    }
+}
@@ -0,0 +1,277 @@
+//! Per-tool approval rules and remembered approval decisions.
+//!
+//! [`ApprovalPolicy`] picks a fixed set of tools that need approval. [`ApprovalRules`] refine
+//! that per tool and per argument pattern. A rule is written as text (e.g. from config):
+//!
+//! - `bash` — every `bash` call needs approval
+//! - `bash:^(rm|sudo)\b` — `bash` needs approval when a string argument matches the regex
+//! - `!write_file` / `!bash:^ls\b` — never ask for approval for those calls
+//!
+//! Rules are tried in order and the first match wins; when none matches, the policy decides.
+//!
+//! When the user answers an `approval_required` interrupt, the resume payload may ask to
+//! remember the decision: `{ "approved": true, "remember": "thread" }` (see
+//! [`ApprovalDecision`]). Remembered decisions apply to later calls of the same tool without
+//! asking again, except when a pattern rule asked for the approval: then the decision covers
+//! only calls with the same arguments (see [`ApprovalRules::memory_key`]), so approving one
+//! `rm` command does not approve a later `sudo` one.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use super::prompt::{tools_requiring_approval, ApprovalPolicy};
+use crate::approval_audit::args_digest;
+use crate::tools::{batch_sub_calls, TOOL_BATCH};
+
+/// Error parsing an approval rule.
+#[derive(Debug, Error)]
+#[error("invalid approval rule `{rule}`: {message}")]
+pub struct ApprovalRuleError {
+    pub rule: String,
+    pub message: String,
+}
+
+/// One parsed approval rule: for calls of `tool` whose arguments match `pattern` (any call when
+/// unset), approval is required (`require`) or skipped.
+#[derive(Debug, Clone)]
+pub struct ApprovalRule {
+    pub tool: String,
+    pub pattern: Option<Regex>,
+    pub require: bool,
+}
+
+impl ApprovalRule {
+    /// Parses `[!]<tool>[:<regex>]`.
+    pub fn parse(rule: &str) -> Result<Self, ApprovalRuleError> {
+        let text = rule.trim();
+        let err = |message: String| ApprovalRuleError {
+            rule: text.to_string(),
+            message,
+        };
+        let (require, rest) = match text.strip_prefix('!') {
+            Some(rest) => (false, rest),
+            None => (true, text),
+        };
+        let (tool, pattern) = match rest.split_once(':') {
+            Some((tool, pattern)) => (tool.trim(), Some(pattern.trim())),
+            None => (rest.trim(), None),
+        };
+        if tool.is_empty() {
+            return Err(err("expected `[!]<tool>[:<regex>]`".to_string()));
+        }
+        let pattern = pattern
+            .map(|p| Regex::new(p).map_err(|e| err(e.to_string())))
+            .transpose()?;
+        Ok(Self {
+            tool: tool.to_string(),
+            pattern,
+            require,
+        })
+    }
+
+    /// True when the rule applies to a call of `tool_name` with `args`. The pattern is matched
+    /// against every string in the arguments (e.g. the `command` of a `bash` call).
+    pub fn matches(&self, tool_name: &str, args: &Value) -> bool {
+        if self.tool != tool_name {
+            return false;
+        }
+        match &self.pattern {
+            None => true,
+            Some(re) => any_string(args, &|s| re.is_match(s)),
+        }
+    }
+}
+
+fn any_string(value: &Value, f: &dyn Fn(&str) -> bool) -> bool {
+    match value {
+        Value::String(s) => f(s),
+        Value::Array(items) => items.iter().any(|v| any_string(v, f)),
+        Value::Object(map) => map.values().any(|v| any_string(v, f)),
+        _ => false,
+    }
+}
+
+/// Ordered approval rules; the first matching rule wins.
+#[derive(Debug, Clone, Default)]
+pub struct ApprovalRules {
+    rules: Vec<ApprovalRule>,
+}
+
+impl ApprovalRules {
+    /// Parses each non-empty rule (see [`ApprovalRule::parse`]).
+    pub fn parse_all<I, T>(rules: I) -> Result<Self, ApprovalRuleError>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        let rules = rules
+            .into_iter()
+            .filter(|r| !r.as_ref().trim().is_empty())
+            .map(|r| ApprovalRule::parse(r.as_ref()))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Whether a call needs approval: the first matching rule decides, else `policy`
+    /// (no approval when unset). A `batch` call needs approval when any of its sub-calls does.
+    pub fn requires_approval(
+        &self,
+        policy: Option<ApprovalPolicy>,
+        tool_name: &str,
+        args: &Value,
+    ) -> bool {
+        if tool_name == TOOL_BATCH
+            && batch_sub_calls(args)
+                .any(|(tool, params)| self.requires_approval(policy, tool, params))
+        {
+            return true;
+        }
+        match self.rules.iter().find(|r| r.matches(tool_name, args)) {
+            Some(rule) => rule.require,
+            None => policy.is_some_and(|p| tools_requiring_approval(p).contains(&tool_name)),
+        }
+    }
+
+    /// Key a remembered decision on this call is stored under: the tool name, or, when a
+    /// pattern rule matched, the tool, the rule's pattern and the arguments digest. A `batch`
+    /// decision covers only the same batch.
+    pub fn memory_key(&self, tool_name: &str, args: &Value) -> String {
+        if tool_name == TOOL_BATCH {
+            return format!("{}:{}", TOOL_BATCH, args_digest(args));
+        }
+        match self.rules.iter().find(|r| r.matches(tool_name, args)) {
+            Some(ApprovalRule {
+                pattern: Some(re), ..
+            }) => format!("{}:{}:{}", tool_name, re.as_str(), args_digest(args)),
+            _ => tool_name.to_string(),
+        }
+    }
+}
+
+/// How long a user's approval decision is remembered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalMemory {
+    /// Applies to the pending call only.
+    #[default]
+    Once,
+    /// Applies to later calls of the tool (same arguments, for pattern rules) for as long as
+    /// the runner lives.
+    Session,
+    /// Applies to later calls of the tool (same arguments, for pattern rules) in the thread;
+    /// saved with the thread's checkpoint.
+    Thread,
+}
+
+/// A user's answer to an `approval_required` interrupt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalDecision {
+    pub approved: bool,
+    #[serde(default)]
+    pub remember: ApprovalMemory,
+}
+
+impl ApprovalDecision {
+    /// Reads a resume payload: `{ "approved": bool, "remember"?: "once" | "session" | "thread" }`,
+    /// or a bare boolean.
+    pub fn from_resume_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Bool(approved) => Some(Self {
+                approved: *approved,
+                remember: ApprovalMemory::Once,
+            }),
+            _ => serde_json::from_value(value.clone()).ok(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn pattern_rule_requires_approval_only_for_matching_commands() {
+        let rules = ApprovalRules::parse_all([r"bash:^(rm|sudo)\b"]).unwrap();
+        assert!(rules.requires_approval(None, "bash", &json!({"command": "rm -rf target"})));
+        assert!(!rules.requires_approval(None, "bash", &json!({"command": "ls -la"})));
+    }
+
+    #[test]
+    fn pattern_rule_decisions_are_remembered_per_arguments() {
+        let rules = ApprovalRules::parse_all([r"bash:^(rm|sudo)\b", "write_file"]).unwrap();
+        let rm = rules.memory_key("bash", &json!({"command": "rm -rf target"}));
+        let sudo = rules.memory_key("bash", &json!({"command": "sudo reboot"}));
+        assert_ne!(rm, sudo);
+        assert!(rm.starts_with(r"bash:^(rm|sudo)\b:sha256:"), "{rm}");
+        assert_eq!(
+            rm,
+            rules.memory_key("bash", &json!({"command": "rm -rf target"}))
+        );
+        assert_eq!(
+            rules.memory_key("write_file", &json!({"path": "a.rs"})),
+            "write_file"
+        );
+    }
+
+    #[test]
+    fn batch_needs_approval_when_a_sub_call_does() {
+        let rules = ApprovalRules::parse_all(["!batch", r"bash:^rm\b"]).unwrap();
+        let batch = |command: &str| {
+            json!({"calls": [
+                {"tool": "read", "parameters": {"path": "a.rs"}},
+                {"tool": "bash", "parameters": {"command": command}}
+            ]})
+        };
+        assert!(rules.requires_approval(None, "batch", &batch("rm -rf /")));
+        assert!(!rules.requires_approval(None, "batch", &batch("ls")));
+        let policy = Some(ApprovalPolicy::DestructiveOnly);
+        let delete = json!({"calls": [{"tool": "delete_file", "parameters": {"path": "a"}}]});
+        assert!(ApprovalRules::default().requires_approval(policy, "batch", &delete));
+        assert_ne!(
+            rules.memory_key("batch", &batch("rm a")),
+            rules.memory_key("batch", &batch("rm b"))
+        );
+    }
+
+    #[test]
+    fn first_matching_rule_wins_over_policy() {
+        let rules = ApprovalRules::parse_all(["!delete_file:\\.tmp$", "write_file"]).unwrap();
+        let policy = Some(ApprovalPolicy::DestructiveOnly);
+        assert!(!rules.requires_approval(policy, "delete_file", &json!({"path": "a.tmp"})));
+        assert!(rules.requires_approval(policy, "delete_file", &json!({"path": "a.rs"})));
+        assert!(rules.requires_approval(policy, "write_file", &json!({"path": "a.rs"})));
+        assert!(!rules.requires_approval(policy, "read_file", &json!({"path": "a.rs"})));
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        assert!(ApprovalRule::parse(":x").is_err());
+        assert!(ApprovalRule::parse("bash:(").is_err());
+    }
+
+    #[test]
+    fn decision_reads_resume_payloads() {
+        assert_eq!(
+            ApprovalDecision::from_resume_value(&json!({"approved": true, "remember": "thread"})),
+            Some(ApprovalDecision {
+                approved: true,
+                remember: ApprovalMemory::Thread,
+            })
+        );
+        assert_eq!(
+            ApprovalDecision::from_resume_value(&json!(false)),
+            Some(ApprovalDecision {
+                approved: false,
+                remember: ApprovalMemory::Once,
+            })
+        );
+        assert!(ApprovalDecision::from_resume_value(&json!("yes")).is_none());
+    }
+}
@@ -7,6 +7,10 @@
 use std::path::PathBuf;
 
 use super::prompt::{assemble_react_system_prompt, ApprovalPolicy, ReactPromptInputs};
+use super::skill_pack::{
+    enabled_skill_packs, skill_pack_allowed_tools, skill_pack_mcp_servers, skill_pack_prompts,
+    SkillPack,
+};
 use crate::agent::react::ReactBuildConfig;
 
 /// Product-semantic configuration for a Helve-style run.
@@ -28,6 +32,9 @@ pub struct HelveConfig {
     pub user_id: Option<String>,
     /// When set, tools that require approval (e.g. delete_file) will interrupt before execution.
     pub approval_policy: Option<ApprovalPolicy>,
+    /// Per-tool / per-argument approval rules (`[!]<tool>[:<regex>]`) checked before
+    /// `approval_policy`; see [`ApprovalRules`](super::ApprovalRules).
+    pub approval_rules: Vec<String>,
     /// Role/persona setting (e.g. from instructions.md): prepended to the assembled system prompt.
     /// E.g. "You are a code review expert." Does not apply when `system_prompt_override` is set.
     pub role_setting: Option<String>,
@@ -38,15 +45,31 @@ pub struct HelveConfig {
     pub system_prompt_override: Option<String>,
     /// Skills prompt: available_skills summary (and optionally preloaded content). Injected between agents_md and base_content.
     pub skills_prompt: Option<String>,
+    /// Skill packs this run may enable by name; see [`SkillPack`].
+    pub skill_packs: Vec<SkillPack>,
+    /// Names of the skill packs enabled for this run (e.g. `["code-review", "jira"]`). Their
+    /// prompts follow `skills_prompt`, their tools limit `allowed_tools` and their MCP servers
+    /// are added to `mcp_servers`.
+    pub skills: Vec<String>,
+    /// Locale tag (e.g. `zh-CN`). When the prompts directory has a variant for it
+    /// (`prompts/<locale>/*.yaml`), its base prompt, workdir and approval text are used.
+    pub locale: Option<String>,
 }
 
 /// Converts a HelveConfig and a base ReactBuildConfig into a single ReactBuildConfig.
 ///
-/// Product fields (working_folder, thread_id, user_id, approval_policy) are taken from
-/// `helve` when set; otherwise from `base`. The final system prompt is assembled
+/// Product fields (working_folder, thread_id, user_id, approval_policy, approval_rules) are
+/// taken from `helve` when set (non-empty for rules); otherwise from `base`. The final system prompt is assembled
 /// through [`assemble_react_system_prompt`].
 ///
-/// Other fields (db_path, mcp_*, openai_*, etc.) are always taken from `base`.
+/// Skill packs named in `helve.skills` add their prompt fragments after `skills_prompt`, limit
+/// `allowed_tools` to the tools they list (within `base.allowed_tools` when set) and append
+/// their MCP servers to `base.mcp_servers`.
+///
+/// With `helve.locale` set, the localized prompt variant is loaded via
+/// [`load_or_default`](crate::prompts::load_or_default) and passed to the assembler.
+///
+/// Other fields (db_path, openai_*, etc.) are always taken from `base`.
 ///
 /// # Example
 ///
@@ -62,14 +85,22 @@ pub struct HelveConfig {
 /// let runner = build_react_runner(&config, None, false).await?;
 /// ```
 pub fn to_react_build_config(helve: &HelveConfig, base: ReactBuildConfig) -> ReactBuildConfig {
+    let packs = enabled_skill_packs(&helve.skill_packs, &helve.skills);
     let prompt_inputs = ReactPromptInputs {
         full_override: helve.system_prompt_override.clone(),
         base_prompt_override: base.system_prompt.clone(),
         role_setting: helve.role_setting.clone(),
         agents_md: helve.agents_md.clone(),
         skills_prompt: helve.skills_prompt.clone(),
+        skill_pack_prompts: skill_pack_prompts(&packs),
         working_folder: helve.working_folder.clone(),
         approval_policy: helve.approval_policy,
+        read_only: base.read_only,
+        localized: helve.locale.as_deref().and_then(|locale| {
+            crate::prompts::load_or_default(None)
+                .for_locale(locale)
+                .cloned()
+        }),
     };
     let system_prompt = Some(assemble_react_system_prompt(&prompt_inputs));
 
@@ -79,6 +110,13 @@ pub fn to_react_build_config(helve: &HelveConfig, base: ReactBuildConfig) -> Rea
         thread_id: helve.thread_id.clone().or(base.thread_id),
         user_id: helve.user_id.clone().or(base.user_id),
         approval_policy: helve.approval_policy.or(base.approval_policy),
+        approval_rules: if helve.approval_rules.is_empty() {
+            base.approval_rules
+        } else {
+            helve.approval_rules.clone()
+        },
+        allowed_tools: skill_pack_allowed_tools(&packs, base.allowed_tools),
+        mcp_servers: skill_pack_mcp_servers(&packs, base.mcp_servers),
         ..base
     }
 }
@@ -108,6 +146,21 @@ mod tests {
         assert_eq!(out.thread_id.as_deref(), Some("t1"));
     }
 
+    /// **Scenario**: approval_rules from helve replace the base rules; empty keeps the base.
+    #[test]
+    fn to_react_build_config_approval_rules_override_base() {
+        let mut base = ReactBuildConfig::from_env();
+        base.approval_rules = vec!["write_file".to_string()];
+        let out = to_react_build_config(&HelveConfig::default(), base.clone());
+        assert_eq!(out.approval_rules, vec!["write_file"]);
+        let helve = HelveConfig {
+            approval_rules: vec![r"bash:^rm\b".to_string()],
+            ..Default::default()
+        };
+        let out = to_react_build_config(&helve, base);
+        assert_eq!(out.approval_rules, vec![r"bash:^rm\b"]);
+    }
+
     /// **Scenario**: system_prompt_override takes precedence over assembled prompt.
     #[test]
     fn to_react_build_config_override_precedence() {
@@ -129,10 +182,53 @@ mod tests {
         assert!(c.thread_id.is_none());
         assert!(c.user_id.is_none());
         assert!(c.approval_policy.is_none());
+        assert!(c.approval_rules.is_empty());
         assert!(c.role_setting.is_none());
         assert!(c.agents_md.is_none());
         assert!(c.system_prompt_override.is_none());
         assert!(c.skills_prompt.is_none());
+        assert!(c.skill_packs.is_empty());
+        assert!(c.skills.is_empty());
+    }
+
+    /// **Scenario**: enabled skill packs add their prompt before the base prompt, limit the
+    /// tools and add their MCP servers; packs not named in `skills` have no effect.
+    #[test]
+    fn to_react_build_config_folds_in_enabled_skill_packs() {
+        let mut base = ReactBuildConfig::from_env();
+        base.system_prompt = Some("BASE".to_string());
+        base.allowed_tools = None;
+        base.mcp_servers = None;
+        let jira = SkillPack {
+            name: "jira".to_string(),
+            prompt: Some("JIRA".to_string()),
+            tools: vec!["jira_search".to_string()],
+            mcp_servers: vec![env_config::McpServerDef::Http {
+                name: "jira".to_string(),
+                url: "https://jira.example.com/mcp".to_string(),
+                headers: Default::default(),
+            }],
+        };
+        let unused = SkillPack {
+            name: "unused".to_string(),
+            prompt: Some("UNUSED".to_string()),
+            ..Default::default()
+        };
+        let helve = HelveConfig {
+            skills_prompt: Some("SKILLS".to_string()),
+            skill_packs: vec![jira, unused],
+            skills: vec!["jira".to_string()],
+            ..Default::default()
+        };
+        let out = to_react_build_config(&helve, base);
+        let prompt = out.system_prompt.as_deref().unwrap();
+        let skills_pos = prompt.find("SKILLS").unwrap();
+        let jira_pos = prompt.find("JIRA").unwrap();
+        let base_pos = prompt.find("BASE").unwrap();
+        assert!(skills_pos < jira_pos && jira_pos < base_pos);
+        assert!(!prompt.contains("UNUSED"));
+        assert_eq!(out.allowed_tools, Some(vec!["jira_search".to_string()]));
+        assert_eq!(out.mcp_servers.map(|s| s.len()), Some(1));
     }
 
     /// **Scenario**: role_setting is prepended to assembled prompt when no system_prompt_override.
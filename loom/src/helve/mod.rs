@@ -19,11 +19,14 @@
 //!
 //! | Item | Role |
 //! |------|-----|
-//! | [`HelveConfig`] | Product config: `working_folder`, `thread_id`, `user_id`, `approval_policy`, `role_setting`, `system_prompt_override`. Built from request/CLI. |
+//! | [`HelveConfig`] | Product config: `working_folder`, `thread_id`, `user_id`, `approval_policy`, `role_setting`, `system_prompt_override`, `skills`. Built from request/CLI. |
 //! | [`to_react_build_config`] | Merges `HelveConfig` with a base `ReactBuildConfig`; assembles the final `system_prompt` from loaded materials. |
 //! | [`assemble_react_system_prompt`] | Single main assembly path for the final ReAct system prompt. |
 //! | [`assemble_system_prompt`] | Convenience wrapper for base ReAct prompt + workdir path + optional approval text. |
+//! | [`SkillPack`] | Named bundle of a prompt fragment, tool allowlist and MCP servers; enabled per run via `HelveConfig.skills`. |
 //! | [`ApprovalPolicy`] | `None` / `DestructiveOnly` / `Always`; controls which tools require user confirmation. |
+//! | [`ApprovalRules`] | Per-tool / per-argument-pattern rules (e.g. `bash:^rm\b`) checked before the policy. |
+//! | [`ApprovalDecision`] | Resume payload for an approval interrupt; may remember the decision for the session or thread ([`ApprovalMemory`]). |
 //! | [`tools_requiring_approval`] | Returns tool names that need approval for a given policy; used by [`ActNode`](crate::agent::react::ActNode) to trigger interrupts. |
 //! | [`APPROVAL_REQUIRED_EVENT_TYPE`] | Stream/interrupt event type string; clients use it to show approval UI and resume with `approved` payload. |
 //!
@@ -35,14 +38,22 @@
 //!
 //! ## Internal structure
 //!
+//! - **approval**: [`ApprovalRules`], [`ApprovalDecision`], [`ApprovalMemory`].
 //! - **config**: [`HelveConfig`], [`to_react_build_config`].
+//! - **skill_pack**: [`SkillPack`], [`enabled_skill_packs`].
 //! - **prompt**: [`assemble_react_system_prompt`], [`assemble_system_prompt`], [`ApprovalPolicy`], [`tools_requiring_approval`], [`APPROVAL_REQUIRED_EVENT_TYPE`].
 
+mod approval;
 mod config;
 mod prompt;
+mod skill_pack;
 
+pub use approval::{
+    ApprovalDecision, ApprovalMemory, ApprovalRule, ApprovalRuleError, ApprovalRules,
+};
 pub use config::{to_react_build_config, HelveConfig};
 pub use prompt::{
     assemble_react_system_prompt, assemble_system_prompt, tools_requiring_approval, ApprovalPolicy,
     ReactPromptInputs, APPROVAL_REQUIRED_EVENT_TYPE,
 };
+pub use skill_pack::{enabled_skill_packs, SkillPack};
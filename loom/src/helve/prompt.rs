@@ -6,7 +6,9 @@
 
 use std::path::{Path, PathBuf};
 
+use super::skill_pack::{skill_pack_prompts, SkillPack};
 use crate::agent::react::REACT_SYSTEM_PROMPT;
+use crate::prompts::AgentPrompts;
 
 /// Approval policy for destructive or high-risk file operations.
 ///
@@ -44,10 +46,18 @@ pub struct ReactPromptInputs {
     pub agents_md: Option<String>,
     /// Optional skills section prepended after `agents_md`.
     pub skills_prompt: Option<String>,
+    /// Prompt fragments of the enabled skill packs (see [`SkillPack`]), each its own section
+    /// after `skills_prompt`.
+    pub skill_pack_prompts: Vec<String>,
     /// Working folder displayed in the workdir section when present.
     pub working_folder: Option<PathBuf>,
     /// Approval policy appended after the workdir section when present.
     pub approval_policy: Option<ApprovalPolicy>,
+    /// When true, a read-only section is appended after the approval section.
+    pub read_only: bool,
+    /// Localized prompt variant (see [`AgentPrompts::for_locale`]). Its `react.system_prompt`
+    /// replaces [`REACT_SYSTEM_PROMPT`] and its `helve` keys replace the workdir and approval text.
+    pub localized: Option<AgentPrompts>,
 }
 
 /// Returns the list of tool names that require user approval for the given policy.
@@ -72,10 +82,16 @@ fn canonical_display(path: &Path) -> String {
         .to_string()
 }
 
-fn build_workdir_section(working_folder: Option<&Path>) -> String {
+fn build_workdir_section(
+    working_folder: Option<&Path>,
+    localized: Option<&AgentPrompts>,
+) -> String {
     let Some(path) = working_folder else {
         return String::new();
     };
+    if let Some(template) = localized.and_then(|p| p.helve.workdir_section_template.as_ref()) {
+        return template.replace("{workdir}", &canonical_display(path));
+    }
     format!(
         r#"
 WORKING FOLDER & FILE RULES:
@@ -85,7 +101,18 @@ WORKING FOLDER & FILE RULES:
     )
 }
 
-fn build_approval_section(approval_policy: Option<ApprovalPolicy>) -> String {
+fn build_approval_section(
+    approval_policy: Option<ApprovalPolicy>,
+    localized: Option<&AgentPrompts>,
+) -> String {
+    let localized_text = localized.and_then(|p| match approval_policy {
+        Some(ApprovalPolicy::DestructiveOnly) => p.helve.approval_destructive.clone(),
+        Some(ApprovalPolicy::Always) => p.helve.approval_always.clone(),
+        Some(ApprovalPolicy::None) | None => None,
+    });
+    if let Some(text) = localized_text {
+        return text;
+    }
     match approval_policy {
         Some(ApprovalPolicy::None) | None => String::new(),
         Some(ApprovalPolicy::DestructiveOnly) => "\n\nAPPROVAL: Before executing delete_file or remove_dir, output your plan and wait for the user to confirm (e.g. \"Proceed?\" or \"Continue?\"). Do not perform the deletion until the user approves.".to_string(),
@@ -93,6 +120,14 @@ fn build_approval_section(approval_policy: Option<ApprovalPolicy>) -> String {
     }
 }
 
+fn build_read_only_section(read_only: bool) -> &'static str {
+    if read_only {
+        "\n\nREAD-ONLY MODE: This run cannot modify anything. Shell, write, edit, move and delete tools are unavailable; only read, list and search tools can be used. Answer from what you can read and do not attempt changes."
+    } else {
+        ""
+    }
+}
+
 fn collect_prefix_sections(inputs: &ReactPromptInputs) -> Vec<&str> {
     [
         inputs.role_setting.as_deref(),
@@ -101,6 +136,7 @@ fn collect_prefix_sections(inputs: &ReactPromptInputs) -> Vec<&str> {
     ]
     .into_iter()
     .flatten()
+    .chain(inputs.skill_pack_prompts.iter().map(String::as_str))
     .map(str::trim)
     .filter(|s| !s.is_empty())
     .collect()
@@ -115,15 +151,18 @@ pub fn assemble_react_system_prompt(inputs: &ReactPromptInputs) -> String {
         return full.clone();
     }
 
+    let localized = inputs.localized.as_ref();
     let base_prompt = inputs
         .base_prompt_override
         .clone()
+        .or_else(|| localized.and_then(|p| p.react.system_prompt.clone()))
         .unwrap_or_else(|| REACT_SYSTEM_PROMPT.to_string());
     let base_content = format!(
-        "{}{}{}",
+        "{}{}{}{}",
         base_prompt,
-        build_workdir_section(inputs.working_folder.as_deref()),
-        build_approval_section(inputs.approval_policy)
+        build_workdir_section(inputs.working_folder.as_deref(), localized),
+        build_approval_section(inputs.approval_policy, localized),
+        build_read_only_section(inputs.read_only)
     );
 
     let prefix_sections = collect_prefix_sections(inputs);
@@ -137,7 +176,8 @@ pub fn assemble_react_system_prompt(inputs: &ReactPromptInputs) -> String {
 }
 
 /// Assembles the full system prompt for a Helve-style run: base ReAct prompt plus
-/// working folder path, permission rules, and optional approval instructions.
+/// working folder path, permission rules, and optional approval instructions, preceded by the
+/// prompt fragments of `skill_packs`.
 ///
 /// Callers (e.g. Server) pass the result to `ReactBuildConfig.system_prompt`.
 /// Does not perform I/O; `working_folder` is used only as display path in the prompt.
@@ -146,6 +186,7 @@ pub fn assemble_react_system_prompt(inputs: &ReactPromptInputs) -> String {
 ///
 /// * `working_folder` - Path to the working directory (shown in the prompt; need not exist yet).
 /// * `approval_policy` - When `Some(p)` with `p != ApprovalPolicy::None`, appends approval instructions.
+/// * `skill_packs` - Enabled skill packs (see [`enabled_skill_packs`](super::enabled_skill_packs)).
 ///
 /// # Example
 ///
@@ -153,14 +194,16 @@ pub fn assemble_react_system_prompt(inputs: &ReactPromptInputs) -> String {
 /// use loom::helve::{assemble_system_prompt, ApprovalPolicy};
 /// use std::path::Path;
 ///
-/// let prompt = assemble_system_prompt(Path::new("/tmp/workspace"), Some(ApprovalPolicy::DestructiveOnly));
+/// let prompt = assemble_system_prompt(Path::new("/tmp/workspace"), Some(ApprovalPolicy::DestructiveOnly), &[]);
 /// config.system_prompt = Some(prompt);
 /// ```
 pub fn assemble_system_prompt(
     working_folder: &Path,
     approval_policy: Option<ApprovalPolicy>,
+    skill_packs: &[&SkillPack],
 ) -> String {
     assemble_react_system_prompt(&ReactPromptInputs {
+        skill_pack_prompts: skill_pack_prompts(skill_packs),
         working_folder: Some(working_folder.to_path_buf()),
         approval_policy,
         ..Default::default()
@@ -173,7 +216,7 @@ mod tests {
 
     #[test]
     fn assemble_system_prompt_includes_workdir_and_base() {
-        let p = assemble_system_prompt(Path::new("/tmp/ws"), None);
+        let p = assemble_system_prompt(Path::new("/tmp/ws"), None, &[]);
         assert!(p.contains(REACT_SYSTEM_PROMPT));
         assert!(p.contains("/tmp/ws"));
         assert!(p.contains("Working folder path"));
@@ -181,7 +224,7 @@ mod tests {
 
     #[test]
     fn assemble_system_prompt_with_approval_destructive_adds_approval_text() {
-        let p = assemble_system_prompt(Path::new("/x"), Some(ApprovalPolicy::DestructiveOnly));
+        let p = assemble_system_prompt(Path::new("/x"), Some(ApprovalPolicy::DestructiveOnly), &[]);
         assert!(p.contains("APPROVAL"));
         assert!(p.contains("delete_file"));
         assert!(p.contains("wait for the user"));
@@ -189,7 +232,7 @@ mod tests {
 
     #[test]
     fn assemble_system_prompt_with_approval_none_no_approval_section() {
-        let p = assemble_system_prompt(Path::new("/x"), Some(ApprovalPolicy::None));
+        let p = assemble_system_prompt(Path::new("/x"), Some(ApprovalPolicy::None), &[]);
         assert!(!p.contains("APPROVAL:"));
     }
 
@@ -210,4 +253,55 @@ mod tests {
         assert!(p.contains("/tmp/ws"));
         assert!(p.contains("APPROVAL"));
     }
+
+    #[test]
+    fn assemble_system_prompt_puts_skill_packs_before_base() {
+        let review = SkillPack {
+            name: "code-review".to_string(),
+            prompt: Some("Review diffs line by line.".to_string()),
+            ..Default::default()
+        };
+        let p = assemble_system_prompt(Path::new("/tmp/ws"), None, &[&review]);
+        assert!(p.starts_with("Review diffs line by line."));
+        assert!(p.contains(REACT_SYSTEM_PROMPT));
+    }
+
+    #[test]
+    fn assemble_react_system_prompt_uses_localized_variant() {
+        let mut zh = AgentPrompts::default();
+        zh.react.system_prompt = Some("请始终使用中文回答。".to_string());
+        zh.helve.workdir_section_template = Some("\n工作目录：{workdir}\n".to_string());
+        zh.helve.approval_destructive = Some("\n\n删除前请先征得用户同意。".to_string());
+        let p = assemble_react_system_prompt(&ReactPromptInputs {
+            working_folder: Some(PathBuf::from("/tmp/ws")),
+            approval_policy: Some(ApprovalPolicy::DestructiveOnly),
+            localized: Some(zh.clone()),
+            ..Default::default()
+        });
+        assert!(p.starts_with("请始终使用中文回答。"));
+        assert!(p.contains("工作目录：/tmp/ws"));
+        assert!(p.contains("删除前请先征得用户同意。"));
+        assert!(!p.contains("WORKING FOLDER"));
+        assert!(!p.contains("APPROVAL"));
+
+        let p = assemble_react_system_prompt(&ReactPromptInputs {
+            base_prompt_override: Some("Explicit base.".to_string()),
+            approval_policy: Some(ApprovalPolicy::Always),
+            localized: Some(zh),
+            ..Default::default()
+        });
+        assert!(p.starts_with("Explicit base."));
+        assert!(p.contains("APPROVAL"));
+    }
+
+    #[test]
+    fn assemble_react_system_prompt_read_only_adds_section() {
+        let p = assemble_react_system_prompt(&ReactPromptInputs {
+            read_only: true,
+            ..Default::default()
+        });
+        assert!(p.contains("READ-ONLY MODE"));
+        let p = assemble_react_system_prompt(&ReactPromptInputs::default());
+        assert!(!p.contains("READ-ONLY MODE"));
+    }
 }
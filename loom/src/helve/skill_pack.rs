@@ -0,0 +1,157 @@
+//! Skill packs: named bundles of a prompt fragment, a tool allowlist and MCP servers that a run
+//! switches on by name (`skills: ["code-review", "jira"]`).
+//!
+//! Packs are plain data; callers load them (e.g. from a profile or request) and put them in
+//! [`HelveConfig::skill_packs`](super::HelveConfig::skill_packs).
+//! [`to_react_build_config`](super::to_react_build_config) folds the enabled ones into the
+//! prompt, `allowed_tools` and `mcp_servers`.
+
+use env_config::McpServerDef;
+
+/// A named bundle of prompt text, tools and MCP servers enabled together.
+#[derive(Clone, Debug, Default)]
+pub struct SkillPack {
+    /// Name used to enable the pack (e.g. `code-review`).
+    pub name: String,
+    /// Prompt fragment added after the skills section, before the base prompt.
+    pub prompt: Option<String>,
+    /// Tools the pack needs. When any enabled pack lists tools, the run is limited to the union
+    /// of the listed tools (MCP tools of the pack included only when listed). Empty = no limit.
+    pub tools: Vec<String>,
+    /// MCP servers registered for the run while the pack is enabled.
+    pub mcp_servers: Vec<McpServerDef>,
+}
+
+/// Packs from `packs` named in `enabled`, in `enabled` order. Unknown and repeated names are
+/// skipped (unknown ones with a warning).
+pub fn enabled_skill_packs<'a>(packs: &'a [SkillPack], enabled: &[String]) -> Vec<&'a SkillPack> {
+    let mut out: Vec<&SkillPack> = Vec::new();
+    for name in enabled {
+        if out.iter().any(|p| &p.name == name) {
+            continue;
+        }
+        match packs.iter().find(|p| &p.name == name) {
+            Some(pack) => out.push(pack),
+            None => tracing::warn!(skill = %name, "unknown skill pack, skipped"),
+        }
+    }
+    out
+}
+
+/// Prompt fragments of `packs`, trimmed, empty ones dropped.
+pub(crate) fn skill_pack_prompts(packs: &[&SkillPack]) -> Vec<String> {
+    packs
+        .iter()
+        .filter_map(|p| p.prompt.as_deref())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Tool allowlist for `packs` combined with `base`: `None` when no pack lists tools; otherwise
+/// the union of listed tools, kept to `base` when that is set too.
+pub(crate) fn skill_pack_allowed_tools(
+    packs: &[&SkillPack],
+    base: Option<Vec<String>>,
+) -> Option<Vec<String>> {
+    let mut tools: Vec<String> = Vec::new();
+    for tool in packs.iter().flat_map(|p| &p.tools) {
+        if !tools.contains(tool) {
+            tools.push(tool.clone());
+        }
+    }
+    if tools.is_empty() {
+        return base;
+    }
+    match base {
+        Some(base) => Some(tools.into_iter().filter(|t| base.contains(t)).collect()),
+        None => Some(tools),
+    }
+}
+
+/// `base` MCP servers plus those of `packs`; a pack server whose name is already present is
+/// skipped.
+pub(crate) fn skill_pack_mcp_servers(
+    packs: &[&SkillPack],
+    base: Option<Vec<McpServerDef>>,
+) -> Option<Vec<McpServerDef>> {
+    let extra: Vec<&McpServerDef> = packs.iter().flat_map(|p| &p.mcp_servers).collect();
+    if extra.is_empty() {
+        return base;
+    }
+    let mut servers = base.unwrap_or_default();
+    for def in extra {
+        if !servers.iter().any(|s| server_name(s) == server_name(def)) {
+            servers.push(def.clone());
+        }
+    }
+    Some(servers)
+}
+
+fn server_name(def: &McpServerDef) -> &str {
+    match def {
+        McpServerDef::Stdio { name, .. } | McpServerDef::Http { name, .. } => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn pack(name: &str, tools: &[&str], server: Option<&str>) -> SkillPack {
+        SkillPack {
+            name: name.to_string(),
+            prompt: Some(format!("{} rules.", name)),
+            tools: tools.iter().map(|t| t.to_string()).collect(),
+            mcp_servers: server
+                .map(|s| McpServerDef::Http {
+                    name: s.to_string(),
+                    url: "https://example.com/mcp".to_string(),
+                    headers: HashMap::new(),
+                })
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn enabled_packs_follow_request_order_and_skip_unknown() {
+        let packs = vec![pack("jira", &[], None), pack("code-review", &[], None)];
+        let names = ["code-review", "missing", "jira", "code-review"].map(String::from);
+        let enabled = enabled_skill_packs(&packs, &names);
+        let got: Vec<&str> = enabled.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(got, vec!["code-review", "jira"]);
+        assert_eq!(
+            skill_pack_prompts(&enabled),
+            vec!["code-review rules.", "jira rules."]
+        );
+    }
+
+    #[test]
+    fn allowed_tools_union_is_limited_by_base() {
+        let a = pack("a", &["read", "grep"], None);
+        let b = pack("b", &["grep", "jira_search"], None);
+        let none = pack("none", &[], None);
+        assert_eq!(skill_pack_allowed_tools(&[&none], None), None);
+        assert_eq!(
+            skill_pack_allowed_tools(&[&a, &b], None),
+            Some(vec!["read".into(), "grep".into(), "jira_search".into()])
+        );
+        assert_eq!(
+            skill_pack_allowed_tools(&[&a, &b], Some(vec!["grep".into(), "bash".into()])),
+            Some(vec!["grep".into()])
+        );
+    }
+
+    #[test]
+    fn mcp_servers_are_appended_once_by_name() {
+        let a = pack("a", &[], Some("jira"));
+        let b = pack("b", &[], Some("jira"));
+        let servers = skill_pack_mcp_servers(&[&a, &b], None).unwrap();
+        assert_eq!(servers.len(), 1);
+        assert_eq!(server_name(&servers[0]), "jira");
+        assert!(skill_pack_mcp_servers(&[], None).is_none());
+    }
+}
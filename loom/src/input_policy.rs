@@ -0,0 +1,194 @@
+//! Normalization of user input before it reaches an agent.
+//!
+//! Front ends (the CLI, the WebSocket server) apply one [`InputPolicy`] to each incoming user
+//! message: control characters are stripped, `\r\n` / `\r` become `\n`, and with a max length
+//! set the text is cut and [`truncation_marker`] is appended so the model knows the message
+//! is incomplete.
+
+use crate::message::{ContentPart, UserContent};
+
+/// Env var: max characters of a user message (unset or `0`: no limit).
+pub const ENV_INPUT_MAX_CHARS: &str = "LOOM_INPUT_MAX_CHARS";
+/// Legacy name of [`ENV_INPUT_MAX_CHARS`], read when that is unset.
+pub const ENV_MAX_MESSAGE_LEN: &str = "HELVE_MAX_MESSAGE_LEN";
+/// Env var: `0`/`false`/`no` keeps control characters.
+pub const ENV_INPUT_STRIP_CONTROL: &str = "LOOM_INPUT_STRIP_CONTROL";
+/// Env var: `0`/`false`/`no` keeps `\r` line endings.
+pub const ENV_INPUT_NORMALIZE_NEWLINES: &str = "LOOM_INPUT_NORMALIZE_NEWLINES";
+
+/// Text appended to a message cut from `total` to `kept` characters.
+pub fn truncation_marker(kept: usize, total: usize) -> String {
+    format!(
+        "\n[truncated: message was {} characters, kept the first {}]",
+        total, kept
+    )
+}
+
+/// How user input is cleaned up before a run.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct InputPolicy {
+    /// Remove control characters other than line breaks and `\t`.
+    pub strip_control: bool,
+    /// Turn `\r\n` and lone `\r` into `\n`.
+    pub normalize_newlines: bool,
+    /// Max characters kept; longer input is cut and marked. `None`: no limit.
+    pub max_chars: Option<usize>,
+}
+
+impl Default for InputPolicy {
+    fn default() -> Self {
+        Self {
+            strip_control: true,
+            normalize_newlines: true,
+            max_chars: None,
+        }
+    }
+}
+
+fn env_flag(name: &str, default: bool) -> bool {
+    match std::env::var(name) {
+        Ok(s) => !matches!(s.trim().to_lowercase().as_str(), "0" | "false" | "no"),
+        Err(_) => default,
+    }
+}
+
+/// Parses a max length; `0` and invalid values mean no limit.
+pub(crate) fn parse_max_chars(value: &str) -> Option<usize> {
+    value.trim().parse::<usize>().ok().filter(|n| *n > 0)
+}
+
+impl InputPolicy {
+    /// Reads [`ENV_INPUT_MAX_CHARS`] (else [`ENV_MAX_MESSAGE_LEN`]), [`ENV_INPUT_STRIP_CONTROL`]
+    /// and [`ENV_INPUT_NORMALIZE_NEWLINES`]; unset values keep the defaults.
+    pub fn from_env() -> Self {
+        let max_chars = std::env::var(ENV_INPUT_MAX_CHARS)
+            .or_else(|_| std::env::var(ENV_MAX_MESSAGE_LEN))
+            .ok()
+            .and_then(|s| parse_max_chars(&s));
+        Self {
+            strip_control: env_flag(ENV_INPUT_STRIP_CONTROL, true),
+            normalize_newlines: env_flag(ENV_INPUT_NORMALIZE_NEWLINES, true),
+            max_chars,
+        }
+    }
+
+    /// Normalizes `text`; see the module docs.
+    pub fn apply(&self, text: &str) -> String {
+        self.apply_with_budget(text, self.max_chars)
+    }
+
+    /// Normalizes a user message. For multimodal content each text part is normalized and the
+    /// max length applies to the text parts together; other parts are kept as they are.
+    pub fn apply_content(&self, content: &mut UserContent) {
+        match content {
+            UserContent::Text(text) => *text = self.apply(text),
+            UserContent::Multimodal(parts) => {
+                let mut budget = self.max_chars;
+                for part in parts.iter_mut() {
+                    if let ContentPart::Text { text } = part {
+                        *text = self.apply_with_budget(text, budget);
+                        budget = budget.map(|b| b.saturating_sub(text.chars().count()));
+                    }
+                }
+            }
+        }
+    }
+
+    fn apply_with_budget(&self, text: &str, max_chars: Option<usize>) -> String {
+        let mut out = if self.normalize_newlines {
+            text.replace("\r\n", "\n").replace('\r', "\n")
+        } else {
+            text.to_string()
+        };
+        if self.strip_control {
+            out.retain(|c| !c.is_control() || matches!(c, '\n' | '\t' | '\r'));
+        }
+        let Some(max) = max_chars else {
+            return out;
+        };
+        let total = out.chars().count();
+        if total <= max {
+            return out;
+        }
+        tracing::debug!(total, max, "user input truncated");
+        let mut cut: String = out.chars().take(max).collect();
+        cut.push_str(&truncation_marker(max, total));
+        cut
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_policy_strips_control_chars_and_normalizes_newlines() {
+        let policy = InputPolicy::default();
+        assert_eq!(
+            policy.apply("a\r\nb\rc\u{0}\u{1b}[31md\te"),
+            "a\nb\nc[31md\te"
+        );
+        let keep = InputPolicy {
+            strip_control: false,
+            normalize_newlines: false,
+            max_chars: None,
+        };
+        assert_eq!(keep.apply("a\r\n\u{7}"), "a\r\n\u{7}");
+    }
+
+    #[test]
+    fn long_input_is_cut_on_char_boundary_with_marker() {
+        let policy = InputPolicy {
+            max_chars: Some(3),
+            ..InputPolicy::default()
+        };
+        assert_eq!(
+            policy.apply("héllo"),
+            format!("hél{}", truncation_marker(3, 5))
+        );
+        assert_eq!(policy.apply("hé"), "hé");
+    }
+
+    #[test]
+    fn multimodal_text_parts_share_the_budget() {
+        let policy = InputPolicy {
+            max_chars: Some(4),
+            ..InputPolicy::default()
+        };
+        let mut content = UserContent::Multimodal(vec![
+            ContentPart::Text {
+                text: "ab\r\n".to_string(),
+            },
+            ContentPart::ImageUrl {
+                url: "https://example.com/a.png".to_string(),
+                detail: None,
+            },
+            ContentPart::Text {
+                text: "cdef".to_string(),
+            },
+        ]);
+        policy.apply_content(&mut content);
+        let UserContent::Multimodal(parts) = content else {
+            panic!("multimodal content expected");
+        };
+        assert_eq!(
+            parts[0],
+            ContentPart::Text {
+                text: "ab\n".to_string()
+            }
+        );
+        assert_eq!(
+            parts[2],
+            ContentPart::Text {
+                text: format!("c{}", truncation_marker(1, 4))
+            }
+        );
+    }
+
+    #[test]
+    fn zero_or_invalid_max_means_no_limit() {
+        assert_eq!(parse_max_chars("0"), None);
+        assert_eq!(parse_max_chars("abc"), None);
+        assert_eq!(parse_max_chars(" 120 "), Some(120));
+    }
+}
@@ -15,6 +15,8 @@
 //!
 //! ## Features
 //!
+//! - **Quick start**: [`Loom::builder`] configures LLM, tools, memory, prompt and limits in one
+//!   chain and builds a [`ReactRunner`].
 //! - **State Graphs**: Build and run stateful agent graphs with conditional routing.
 //! - **ReAct Pattern**: Built-in reasoning + acting loops (Think → Act → Observe); [`ReactRunner`]
 //!   and [`build_react_runner`] for config-driven ReAct (optional persistence, MCP, memory tools).
@@ -29,12 +31,21 @@
 //! - **Runtime Context**: Custom runtime context, store access, and managed values ([`RunContext`], [`ManagedValue`]).
 //! - **Cache, Retry, Interrupts**: In-memory caching ([`InMemoryCache`]), retry policies ([`RetryPolicy`]),
 //!   human-in-the-loop ([`InterruptHandler`]).
-//! - **Graph Visualization**: [`generate_dot`], [`generate_text`].
+//! - **Graph Visualization**: [`generate_dot`], [`generate_text`]; live progress via [`GraphProgress`]
+//!   and [`generate_dot_with_progress`].
 //! - **Helve**: Product-semantic config ([`HelveConfig`]), system prompt assembly ([`assemble_system_prompt`]),
 //!   conversion to ReAct config ([`to_react_build_config`]), approval policy ([`ApprovalPolicy`],
 //!   [`tools_requiring_approval`], [`APPROVAL_REQUIRED_EVENT_TYPE`]).
 //!
-//! Feature flag: `lance` — LanceDB vector store for long-term memory (optional; heavy dependency).
+//! Feature flags: `lance` — LanceDB vector store for long-term memory (optional; heavy dependency);
+//! `ssh` — [`tool_source::SshToolsSource`] for allowlisted remote commands and scp, registered by
+//! [`build_react_runner`] outside read-only mode for the hosts in the `LOOM_SSH_HOSTS` file;
+//! `python` — [`tools::PythonTool`], registered by [`build_react_runner`] outside read-only mode;
+//! `screenshot` — [`tools::ScreenshotTool`], registered by [`build_react_runner`] when a working
+//! folder is set;
+//! `client` — [`client::WsClient`], a typed WebSocket client for `loom serve`;
+//! `chaos` — [`chaos::FaultInjector`], random LLM, tool and checkpoint faults for chaos testing
+//! (`LOOM_CHAOS`).
 //!
 //! ## Main modules
 //!
@@ -47,19 +58,23 @@
 //! - [`tool_source`]: [`ToolSource`], [`ToolSpec`]; MCP ([`McpToolSource`]); [`WebToolsSource`], [`BashToolsSource`].
 //! - [`traits`]: Core [`Agent`] trait — implement for custom agents.
 //! - [`message`]: [`Message`] (System / User / Assistant / Tool).
+//! - [`input_policy`]: [`InputPolicy`] — control-char stripping, newline normalization and truncation of user input.
 //! - [`stream`]: [`StreamWriter`], [`StreamEvent`], [`StreamMode`] for graph runs.
 //! - [`config`]: Config summaries ([`RunConfigSummary`], [`build_config_summary`]).
 //! - [`cache`]: [`Cache`], [`InMemoryCache`].
+//! - [`connector`]: [`Connector`] trait for chat bots (inbound message → [`RunRequest`], stream events → reply edits), [`run_long_poll`].
 //! - [`channels`]: [`Channel`], [`LastValue`], [`Topic`], etc.; [`StateUpdater`], [`FieldBasedUpdater`].
 //! - [`managed`]: [`ManagedValue`], [`IsLastStep`].
 //! - [`tools`]: [`register_mcp_tools`], [`McpToolAdapter`].
 //! - [`openai_sse`]: OpenAI-compatible SSE ([`StreamToSse`], [`ChatCompletionChunk`], [`parse_chat_request`]).
 //! - [`helve`]: Product config ([`HelveConfig`]), [`to_react_build_config`], [`assemble_system_prompt`],
 //!   [`ApprovalPolicy`], [`tools_requiring_approval`], [`APPROVAL_REQUIRED_EVENT_TYPE`].
+//! - [`approval_audit`]: [`ApprovalAuditStore`] trail of approval requests and decisions ([`SqliteApprovalAuditStore`]).
 //! - [`protocol`]: WebSocket message types for CLI remote mode ([`ClientRequest`], [`ServerResponse`]);
 //!   streaming output protocol in [`protocol::stream`] ([`stream_event_to_protocol_format`], [`Envelope`]).
-//! - [`user_message`]: [`UserMessageStore`] trait for per-thread message append/list ([`NoOpUserMessageStore`]).
+//! - [`user_message`]: [`UserMessageStore`] trait for per-thread message history with run, usage and tool meta ([`StoredMessage`]; [`NoOpUserMessageStore`], [`InMemoryUserMessageStore`]).
 //! - [`pregel`]: Low-level Pregel graph runtime with channels, checkpointing, task cache, and subgraph support.
+//! - [`run_hooks`]: [`RunHooks`] — async callbacks when a run starts, finishes ([`RunEnd`]: final state and usage) or fails ([`RunFailure`]).
 //! - [`runner_common`]: Shared helpers for stream-based graph runs ([`StreamRunOutcome`], [`run_stream_with_config`]).
 //!
 //! Key types are re-exported at crate root: `use loom::{Agent, StateGraph, Message, ReActState};`.
@@ -119,17 +134,25 @@
 //! `memory_checkpoint`, `memory_persistence`, `openai_embedding`, `state_graph_echo`.
 
 pub mod agent;
+pub mod approval_audit;
+pub mod builder;
 pub mod cache;
 pub mod channels;
+#[cfg(feature = "chaos")]
+pub mod chaos;
 pub mod cli_run;
+#[cfg(feature = "client")]
+pub mod client;
 pub mod command;
 pub mod compress;
 pub mod config;
+pub mod connector;
 pub mod error;
 pub mod export;
 pub mod graph;
 pub mod helve;
 mod http_retry;
+pub mod input_policy;
 pub mod llm;
 pub mod lsp;
 pub mod managed;
@@ -140,6 +163,7 @@ pub mod openai_sse;
 pub mod pregel;
 pub mod prompts;
 pub mod protocol;
+pub mod run_hooks;
 pub mod runner_common;
 pub mod skill;
 pub mod state;
@@ -152,49 +176,70 @@ pub mod traits;
 pub mod user_message;
 
 pub use agent::react::{
-    build_dup_runner, build_got_runner, build_react_initial_state, build_react_run_context,
-    build_react_runner, build_react_runner_with_openai, build_tot_runner, run_agent,
-    run_react_graph_stream, tools_condition, ActNode, AgentOptions, BuildRunnerError,
-    ErrorHandlerFn, GotRunnerConfig, HandleToolErrors, ObserveNode, ReactBuildConfig,
-    ReactRunContext, ReactRunner, RunError as ReactRunError, ThinkNode, ToolsConditionResult,
-    TotRunnerConfig, WithNodeLogging, DEFAULT_EXECUTION_ERROR_TEMPLATE,
-    DEFAULT_TOOL_ERROR_TEMPLATE, REACT_SYSTEM_PROMPT, STEP_PROGRESS_EVENT_TYPE,
+    build_auxiliary_llm, build_dup_runner, build_got_runner, build_react_initial_state,
+    build_react_initial_state_from_history, build_react_initial_state_with_window,
+    build_react_run_context, build_react_runner, build_react_runner_with_openai, build_tot_runner,
+    heuristic_confidence, run_agent, run_react_graph_stream, tools_condition, ActNode,
+    AgentOptions, BuildRunnerError, Callbacks, ConfidenceNode, EnvContext, ErrorHandlerFn, GotRunnerConfig,
+    GraphSpec, GraphSpecError, HandleToolErrors, MemoryRecall, ObservationSummarizer, ObserveNode,
+    ReactBuildConfig, ReactRunContext, ReactRunner, RunError as ReactRunError, ThinkNode,
+    ToolPrefetch, ToolsConditionResult, TotRunnerConfig, VerifyNode, WithNodeLogging,
+    DEFAULT_EXECUTION_ERROR_TEMPLATE, DEFAULT_PREFETCH_TOOLS, DEFAULT_TOOL_CALL_REPAIRS,
+    DEFAULT_TOOL_ERROR_TEMPLATE, ENV_CONTEXT_PLACEHOLDER, REACT_SYSTEM_PROMPT,
+    REFLECTION_FEEDBACK_PREFIX, STEP_PROGRESS_EVENT_TYPE,
 };
+pub use approval_audit::{
+    ApprovalAuditDecision, ApprovalAuditError, ApprovalAuditFilter, ApprovalAuditRecord,
+    ApprovalAuditStore, SqliteApprovalAuditStore,
+};
+pub use builder::{Loom, LoomBuilder};
 pub use cache::{Cache, CacheError, InMemoryCache};
 pub use channels::{
     BinaryOperatorAggregate, Channel, ChannelError, EphemeralValue, FieldBasedUpdater, LastValue,
     NamedBarrierValue, StateUpdater, Topic,
 };
 pub use cli_run::{
-    build_config_from_profile, build_helve_config, list_available_profiles, load_agents_md,
-    resolve_model_config, resolve_profile, run_agent_with_llm_override, run_agent_with_options,
-    ActiveOperation, ActiveOperationCanceller, ActiveOperationKind, AgentProfile, AgentRunResult,
-    AnyRunner, AnyStreamEvent, ProfileError, ProfileSource, ProfileSummary, ResolvedAgent,
+    build_config_from_profile, build_helve_config, list_available_profiles, load_agent_profile,
+    load_agents_md, resolve_model_config, resolve_profile, run_agent_with_llm_override,
+    run_agent_with_options, ActiveOperation, ActiveOperationCanceller, ActiveOperationKind,
+    AgentProfile, AgentRunResult, AnyRunner, AnyStreamEvent, HistoryError, HistoryMessage,
+    HistoryRole, ProfileError, ProfileSource, ProfileSummary, ReplyFormat, ResolvedAgent,
     ResolvedModelConfig, RunCancellation, RunCmd, RunCompletion, RunError, RunOptions,
     DEFAULT_WORKING_FOLDER,
 };
-pub use compress::CompactionConfig;
+pub use compress::{CompactionConfig, ContextGuard, HistoryWindow};
 pub use config::{
     build_config_summary, ConfigSection, EmbeddingConfigSummary, LlmConfigSummary,
     MemoryConfigSummary, RunConfigSummary, RunConfigSummarySource, ToolConfigSummary,
 };
+pub use connector::{
+    run_long_poll, AgentRequestRunner, Connector, ConnectorError, InboundMessage, LongPollConfig,
+    ReplyBuffer, ReplyUpdate, RequestRunner,
+};
+pub use env_config::{McpServerDef, Secret};
 pub use error::AgentError;
 pub use export::stream_event_to_format_a;
 pub use graph::{
-    generate_dot, generate_text, log_graph_complete, log_graph_error, log_graph_start,
-    log_node_complete, log_node_start, log_state_update, CompilationError, CompiledStateGraph,
-    DefaultInterruptHandler, GraphInterrupt, Interrupt, InterruptHandler, LoggingNodeMiddleware,
-    NameNode, Next, Node, NodeMiddleware, RetryPolicy, RunContext, Runtime, StateGraph, END, START,
+    annotate_dot, generate_dot, generate_dot_with_progress, generate_text, graph_edges,
+    graph_node_ids, log_graph_complete, log_graph_error, log_graph_start, log_node_complete,
+    log_node_start, log_state_update, CompilationError, CompiledStateGraph,
+    DefaultInterruptHandler, GraphDiagnostics, GraphEdge, GraphInterrupt, GraphProgress, Interrupt,
+    InterruptHandler, LoggingNodeMiddleware, NameNode, Next, Node, NodeHooks, NodeMiddleware,
+    NodeMiddlewareStack, OnStateLimit, RetryPolicy, RunContext, Runtime, SizedState, StateGraph,
+    StateLimits, StateSize, StateValidator, END, START,
 };
 pub use helve::{
-    assemble_react_system_prompt, assemble_system_prompt, to_react_build_config,
-    tools_requiring_approval, ApprovalPolicy, HelveConfig, ReactPromptInputs,
+    assemble_react_system_prompt, assemble_system_prompt, enabled_skill_packs,
+    to_react_build_config, tools_requiring_approval, ApprovalDecision, ApprovalMemory,
+    ApprovalPolicy, ApprovalRules, HelveConfig, ReactPromptInputs, SkillPack,
     APPROVAL_REQUIRED_EVENT_TYPE,
 };
+pub use input_policy::InputPolicy;
 pub use llm::{ChatOpenAI, ChatOpenAICompat};
 pub use llm::{
-    CompletionTokensDetails, LlmClient, LlmResponse, LlmUsage, MockLlm, PromptTokensDetails,
-    ToolCallDelta, ToolChoiceMode,
+    CompletionTokensDetails, FinishReason, LlmClient, LlmResponse, LlmUsage, MockLlm, MockScript,
+    NodeLlm, NodeLlmOverrides, PromptTokensDetails, TextToolCallLlm, ThreadSummary, ToolCallDelta,
+    ToolChoiceMode,
 };
 pub use managed::{IsLastStep, ManagedValue};
 pub use memory::Embedder;
@@ -202,17 +247,21 @@ pub use memory::Embedder;
 pub use memory::LanceStore;
 pub use memory::OpenAIEmbedder;
 pub use memory::{
-    Checkpoint, CheckpointError, CheckpointListItem, CheckpointMetadata, CheckpointSource,
-    Checkpointer, InMemoryStore, JsonSerializer, MemorySaver, Namespace, RunnableConfig, Store,
-    StoreError, StoreSearchHit,
+    Checkpoint, CheckpointError, CheckpointFilter, CheckpointListItem, CheckpointMetadata,
+    CheckpointSource, Checkpointer, InMemoryStore, JsonSerializer, MemorySaver, Namespace,
+    RunnableConfig, StateMigrations, Store, StoreError, StoreSearchHit, ThreadArchive,
+    VersionedJsonSerializer, VersionedState,
 };
-pub use memory::{SqliteSaver, SqliteStore};
+pub use memory::parse_run_flag;
+pub use memory::{SqliteSaver, SqliteStore, DB_KEY_ENV};
 pub use message::{
     AssistantPayload, AssistantToolCall, ContentError, ContentPart, Message, UserContent,
+    PINNED_PREFIX,
 };
 pub use model_spec::{
-    CachedResolver, CompositeResolver, ConfigOverride, LocalFileResolver, ModelLimitResolver,
-    ModelSpec, ModelsDevResolver, ResolverRefresher,
+    tokenizer_for, tool_support_for, CachedResolver, CompositeResolver, ConfigOverride,
+    LocalFileResolver, ModelLimitResolver, ModelSpec, ModelsDevResolver, ResolverRefresher,
+    Tokenizer, ToolCallMode, ToolSupport,
 };
 pub use openai_sse::{
     parse_chat_request, write_sse_line, ChatCompletionChunk, ChatCompletionRequest, ChatMessage,
@@ -228,36 +277,51 @@ pub use protocol::stream::{
     stream_event_to_protocol_value, Envelope,
 };
 pub use protocol::{
+    ActiveRunInfo, ActiveRunsRequest, ActiveRunsResponse, AdminReloadRequest, AdminReloadResponse,
     AgentListRequest, AgentListResponse, AgentSource, AgentSourceFilter, AgentSummary, AgentType,
-    ClientRequest, EnvelopeState, ErrorResponse, ListModelsRequest, ListModelsResponse,
-    PingRequest, PongResponse, ProtocolEvent, ProtocolEventEnvelope, RunEndResponse, RunRequest,
-    RunStreamEventResponse, ServerResponse, SetModelRequest, SetModelResponse, ThreadInWorkspace,
-    ToolShowOutput, ToolShowRequest, ToolShowResponse, ToolsListRequest, ToolsListResponse,
-    UserMessageItem, UserMessagesRequest, UserMessagesResponse, WorkspaceCreateRequest,
-    WorkspaceCreateResponse, WorkspaceListRequest, WorkspaceListResponse, WorkspaceMeta,
-    WorkspaceThreadAddRequest, WorkspaceThreadAddResponse, WorkspaceThreadListRequest,
-    WorkspaceThreadListResponse, WorkspaceThreadRemoveRequest, WorkspaceThreadRemoveResponse,
+    ApprovalsListRequest, ApprovalsListResponse, CheckpointListRequest, CheckpointListResponse,
+    CheckpointSummary, ClientRequest, EnvelopeState, ErrorResponse, EventSchemaListRequest,
+    EventSchemaListResponse, ListModelsRequest, ListModelsResponse, PingRequest, PongResponse,
+    ProtocolEvent, ProtocolEventEnvelope, ResumeRunRequest, RunEndResponse, RunInspectRequest,
+    RunInspectResponse, RunKillRequest, RunKillResponse, RunRequest, RunStreamEventResponse,
+    RunTiming, ServerResponse, SessionEndRequest, SessionEndResponse, SessionMessageRequest,
+    SessionStartRequest, SessionStartResponse, SetModelRequest, SetModelResponse, StateShowRequest,
+    StateShowResponse, StopGenerationRequest, StopGenerationResponse, ThreadInWorkspace,
+    ToolCallRecord, ToolCallStatus, ToolShowOutput, ToolShowRequest, ToolShowResponse,
+    ToolsListRequest, ToolsListResponse, UsageReportRequest, UsageReportResponse, UsageReportRow,
+    UserMessageItem, UserMessagesDeleteRequest, UserMessagesDeleteResponse, UserMessagesRequest,
+    UserMessagesResponse, WorkspaceCreateRequest, WorkspaceCreateResponse, WorkspaceDefaults,
+    WorkspaceListRequest, WorkspaceListResponse, WorkspaceMeta, WorkspaceThreadAddRequest,
+    WorkspaceThreadAddResponse, WorkspaceThreadListRequest, WorkspaceThreadListResponse,
+    WorkspaceThreadRemoveRequest, WorkspaceThreadRemoveResponse, WorkspaceUpdateRequest,
+    WorkspaceUpdateResponse, ERROR_CODE_PAYLOAD_TOO_LARGE, ERROR_CODE_UNAUTHORIZED,
 };
+pub use run_hooks::{HookFuture, RunEnd, RunFailure, RunHooks, RunStart, RunStateUsage};
 pub use state::{
     normalize_tool_output, NormalizationConfig, NormalizedToolOutput, ToolOutputHint,
     ToolOutputStrategy, ToolStorageRef,
 };
-pub use state::{ReActState, ToolCall, ToolResult};
+pub use state::{
+    AnswerConfidence, ConfidenceScoring, InjectionSanitizer, ReActState, ToolCall, ToolResult,
+    ToolResultFraming, ToolResultFramingMode, ToolResultOrigin,
+};
 pub use stream::{
-    CheckpointEvent, MessageChunk, MessageChunkKind, StreamEvent, StreamMetadata, StreamMode,
-    StreamWriter, ToolStreamWriter,
+    register_custom_event, CheckpointEvent, CustomEventSchema, MessageChunk, MessageChunkKind,
+    StreamEvent, StreamMetadata, StreamMode, StreamWriter, ToolStreamWriter,
 };
 pub use tool_source::McpToolSource;
 pub use tool_source::{
-    BashToolsSource, MemoryToolsSource, MockToolSource, ShortTermMemoryToolSource, StoreToolSource,
-    ToolCallContent, ToolCallContext, ToolSource, ToolSourceError, ToolSpec, WebToolsSource,
-    TOOL_BASH, TOOL_GET_RECENT_MESSAGES, TOOL_LIST_MEMORIES, TOOL_RECALL, TOOL_REMEMBER,
+    BashToolsSource, MemoryToolsSource, MockToolSource, PluggableToolSource,
+    ShortTermMemoryToolSource, StoreToolSource, ToolCallContent, ToolCallContext, ToolCatalogInfo,
+    ToolDanger, ToolSource, ToolSourceError, ToolSourceHealth, ToolSpec, WebToolsSource, TOOL_BASH,
+    TOOL_FORGET, TOOL_GET_RECENT_MESSAGES, TOOL_LIST_MEMORIES, TOOL_RECALL, TOOL_REMEMBER,
     TOOL_SEARCH_MEMORIES, TOOL_WEB_FETCHER,
 };
 pub use tools::{register_mcp_tools, BashTool, McpToolAdapter};
 pub use traits::Agent;
 pub use user_message::{
-    NoOpUserMessageStore, SqliteUserMessageStore, UserMessageStore, UserMessageStoreError,
+    InMemoryUserMessageStore, MessageMeta, MessageSearchHit, NoOpUserMessageStore,
+    SqliteUserMessageStore, StoredMessage, UserMessageStore, UserMessageStoreError,
 };
 
 // Re-export DUP, GoT, ToT from agent for backward compatibility.
@@ -2,6 +2,8 @@
 //!
 //! Returns fixed assistant message and optional fixed ToolCall (e.g. get_time);
 //! configurable "no tool_calls" to test END path. Optional stateful mode for multi-round.
+//! [`MockLlm::scripted`] replays a [`MockScript`] (YAML or JSON) for offline mode
+//! (`LOOM_OFFLINE`) and for tests that drive full ReAct loops without a network.
 //!
 //! # Streaming Support
 //!
@@ -9,18 +11,204 @@
 //! - Default: sends content as a single chunk (efficient for most tests)
 //! - Character-by-character: splits content into individual character chunks (for stream testing)
 
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
 use std::time::Duration;
 
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 
 use crate::error::AgentError;
-use crate::llm::{LlmClient, LlmResponse, LlmUsage};
+use crate::llm::provider_error::provider_error;
+use crate::llm::{FinishReason, LlmClient, LlmResponse, LlmUsage};
 use crate::message::Message;
+use crate::model_spec::ToolSupport;
 use crate::state::ToolCall;
 use crate::stream::MessageChunk;
 
+/// Scripted replies for an offline [`MockLlm`], loaded from YAML or JSON:
+///
+/// ```yaml
+/// responses:
+///   - content: "Let me look at the files."
+///     tool_calls:
+///       - name: ls
+///         arguments: { path: "." }
+///   - when: { last_message_contains: "README" }
+///     content: "The project has a README and a src folder."
+///   - error: "rate limited"
+/// ```
+///
+/// Each LLM call consumes the first unused response whose `when` matches the incoming
+/// messages (a response without `when` always matches), so a plain list replays in order.
+/// A response with `error` fails the call instead of replying. Once no response matches (or
+/// when the script is empty) the mock echoes the last user message, so runs always end.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MockScript {
+    #[serde(default)]
+    pub responses: Vec<ScriptedResponse>,
+}
+
+/// One scripted LLM reply.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ScriptedResponse {
+    /// Only use this response when the incoming messages match.
+    #[serde(default)]
+    pub when: Option<ScriptedMatch>,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub tool_calls: Vec<ScriptedToolCall>,
+    /// Finish reason to report; defaults to `tool_calls` when there are tool calls, else `stop`.
+    #[serde(default)]
+    pub finish_reason: Option<FinishReason>,
+    /// When set, the call fails with this message (failure injection). The message is classified
+    /// like a provider error body, so `"rate limit exceeded"` or `"context_length_exceeded"`
+    /// fail with the matching typed [`AgentError`].
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Condition on the incoming messages of a [`ScriptedResponse`]; every field that is set must
+/// match (substring, case-sensitive).
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ScriptedMatch {
+    /// Text of the last user message contains this.
+    #[serde(default)]
+    pub last_user_contains: Option<String>,
+    /// Text of the last message of any role (e.g. the latest tool result) contains this.
+    #[serde(default)]
+    pub last_message_contains: Option<String>,
+}
+
+impl ScriptedMatch {
+    fn matches(&self, messages: &[Message]) -> bool {
+        let last_user = messages
+            .iter()
+            .rev()
+            .find(|m| matches!(m, Message::User(_)))
+            .map(|m| m.content());
+        let last = messages.last().map(|m| m.content());
+        let contains = |text: Option<&std::borrow::Cow<'_, str>>, needle: &Option<String>| {
+            needle
+                .as_deref()
+                .is_none_or(|n| text.is_some_and(|t| t.contains(n)))
+        };
+        contains(last_user.as_ref(), &self.last_user_contains)
+            && contains(last.as_ref(), &self.last_message_contains)
+    }
+}
+
+/// Tool call of a [`ScriptedResponse`]; `arguments` is any YAML/JSON value.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScriptedToolCall {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: serde_json::Value,
+}
+
+impl MockScript {
+    pub fn from_yaml(yaml: &str) -> Result<Self, AgentError> {
+        serde_yaml::from_str(yaml)
+            .map_err(|e| AgentError::ExecutionFailed(format!("invalid mock script: {}", e)))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, AgentError> {
+        serde_json::from_str(json)
+            .map_err(|e| AgentError::ExecutionFailed(format!("invalid mock script: {}", e)))
+    }
+
+    /// Appends a reply with `content` and no tool calls (builder).
+    pub fn with_reply(mut self, content: impl Into<String>) -> Self {
+        self.responses.push(ScriptedResponse {
+            content: content.into(),
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Appends a reply that calls tool `name` with `arguments` (builder).
+    pub fn with_tool_call(mut self, name: impl Into<String>, arguments: serde_json::Value) -> Self {
+        self.responses.push(ScriptedResponse {
+            tool_calls: vec![ScriptedToolCall {
+                name: name.into(),
+                arguments,
+            }],
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Loads a script file; `.json` files are parsed as JSON, anything else as YAML.
+    pub fn load(path: &Path) -> Result<Self, AgentError> {
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            AgentError::ExecutionFailed(format!("read mock script {}: {}", path.display(), e))
+        })?;
+        if path.extension().is_some_and(|ext| ext == "json") {
+            Self::from_json(&text)
+        } else {
+            Self::from_yaml(&text)
+        }
+    }
+
+    /// Index of the first unused response matching `messages`; marks it used.
+    fn take(&self, messages: &[Message], used: &mut [bool]) -> Option<usize> {
+        let index = self
+            .responses
+            .iter()
+            .zip(used.iter())
+            .position(|(r, used)| !used && r.when.as_ref().is_none_or(|w| w.matches(messages)))?;
+        used[index] = true;
+        Some(index)
+    }
+
+    fn reply(&self, index: usize) -> Result<LlmResponse, AgentError> {
+        let r = &self.responses[index];
+        if let Some(error) = &r.error {
+            return Err(provider_error(None, error.clone()));
+        }
+        let tool_calls: Vec<ToolCall> = r
+            .tool_calls
+            .iter()
+            .enumerate()
+            .map(|(i, tc)| ToolCall {
+                name: tc.name.clone(),
+                arguments: match &tc.arguments {
+                    serde_json::Value::Null => "{}".to_string(),
+                    v => v.to_string(),
+                },
+                id: Some(format!("call-{}-{}", index + 1, i + 1)),
+            })
+            .collect();
+        let finish_reason = r.finish_reason.clone().unwrap_or(if tool_calls.is_empty() {
+            FinishReason::Stop
+        } else {
+            FinishReason::ToolCalls
+        });
+        Ok(LlmResponse {
+            content: r.content.clone(),
+            reasoning_content: None,
+            tool_calls,
+            usage: None,
+            finish_reason: Some(finish_reason),
+        })
+    }
+}
+
+/// Offline reply once a script is exhausted: echoes the last user message.
+fn echo_reply(messages: &[Message]) -> String {
+    let last_user = messages.iter().rev().find_map(|m| match m {
+        Message::User(c) => Some(c.as_text().into_owned()),
+        _ => None,
+    });
+    match last_user {
+        Some(text) => format!("[offline] {}", text),
+        None => "[offline]".to_string(),
+    }
+}
+
 /// Mock LLM: fixed assistant text and optional tool_calls.
 ///
 /// Configurable to return one fixed ToolCall (e.g. get_time) or no tool_calls,
@@ -49,6 +237,14 @@ pub struct MockLlm {
     stream_delay_ms: Option<u64>,
     /// Token usage to return when set (for testing usage merge in ThinkNode).
     usage: Option<LlmUsage>,
+    /// Finish reason of every response, or of the first response in stateful mode.
+    finish_reason: Option<FinishReason>,
+    /// When Some, replies come from the script (see [`MockLlm::scripted`]).
+    script: Option<MockScript>,
+    /// Which script responses have been used.
+    script_used: Mutex<Vec<bool>>,
+    /// Reported by [`LlmClient::tool_support`]; see [`MockLlm::with_tool_support`].
+    tool_support: ToolSupport,
 }
 
 impl MockLlm {
@@ -68,6 +264,10 @@ impl MockLlm {
             stream_by_char: AtomicBool::new(false),
             stream_delay_ms: None,
             usage: None,
+            finish_reason: None,
+            script: None,
+            script_used: Mutex::new(Vec::new()),
+            tool_support: ToolSupport::default(),
         }
     }
 
@@ -81,6 +281,10 @@ impl MockLlm {
             stream_by_char: AtomicBool::new(false),
             stream_delay_ms: None,
             usage: None,
+            finish_reason: None,
+            script: None,
+            script_used: Mutex::new(Vec::new()),
+            tool_support: ToolSupport::default(),
         }
     }
 
@@ -94,6 +298,10 @@ impl MockLlm {
             stream_by_char: AtomicBool::new(false),
             stream_delay_ms: None,
             usage: None,
+            finish_reason: None,
+            script: None,
+            script_used: Mutex::new(Vec::new()),
+            tool_support: ToolSupport::default(),
         }
     }
 
@@ -112,6 +320,20 @@ impl MockLlm {
             stream_by_char: AtomicBool::new(false),
             stream_delay_ms: None,
             usage: None,
+            finish_reason: None,
+            script: None,
+            script_used: Mutex::new(Vec::new()),
+            tool_support: ToolSupport::default(),
+        }
+    }
+
+    /// Creates a mock that answers each call from `script` (see [`MockScript`]), then echoes
+    /// the last user message. Used by offline mode and fixture-driven tests.
+    pub fn scripted(script: MockScript) -> Self {
+        Self {
+            script_used: Mutex::new(vec![false; script.responses.len()]),
+            script: Some(script),
+            ..Self::with_no_tool_calls("")
         }
     }
 
@@ -147,16 +369,58 @@ impl MockLlm {
         self.usage = Some(usage);
         self
     }
+
+    /// Set the finish reason to return (first response only in stateful mode).
+    pub fn with_finish_reason(mut self, reason: FinishReason) -> Self {
+        self.finish_reason = Some(reason);
+        self
+    }
+
+    /// Set the tool-calling capabilities the mock reports, e.g. to test serial or text tool calls.
+    pub fn with_tool_support(mut self, support: ToolSupport) -> Self {
+        self.tool_support = support;
+        self
+    }
+
+    /// Switch to stateful mode: the first invoke() returns the configured response, later
+    /// ones return `content` with no tool_calls and no finish reason.
+    pub fn with_second_content(mut self, content: impl Into<String>) -> Self {
+        self.call_count = Some(AtomicUsize::new(0));
+        self.second_content = Some(content.into());
+        self
+    }
 }
 
 #[async_trait]
 impl LlmClient for MockLlm {
-    async fn invoke(&self, _messages: &[Message]) -> Result<LlmResponse, AgentError> {
-        let (content, tool_calls) = match &self.call_count {
+    async fn invoke(&self, messages: &[Message]) -> Result<LlmResponse, AgentError> {
+        if let Some(script) = &self.script {
+            let index = {
+                let mut used = self.script_used.lock().unwrap_or_else(|e| e.into_inner());
+                script.take(messages, &mut used)
+            };
+            let mut response = match index {
+                Some(index) => script.reply(index)?,
+                None => LlmResponse {
+                    content: echo_reply(messages),
+                    reasoning_content: None,
+                    tool_calls: vec![],
+                    usage: None,
+                    finish_reason: Some(FinishReason::Stop),
+                },
+            };
+            response.usage = self.usage.clone();
+            return Ok(response);
+        }
+        let (content, tool_calls, finish_reason) = match &self.call_count {
             Some(c) => {
                 let n = c.fetch_add(1, Ordering::SeqCst);
                 if n == 0 {
-                    (self.content.clone(), self.tool_calls.clone())
+                    (
+                        self.content.clone(),
+                        self.tool_calls.clone(),
+                        self.finish_reason.clone(),
+                    )
                 } else {
                     (
                         self.second_content
@@ -164,16 +428,22 @@ impl LlmClient for MockLlm {
                             .unwrap_or(&self.content)
                             .to_string(),
                         vec![],
+                        None,
                     )
                 }
             }
-            None => (self.content.clone(), self.tool_calls.clone()),
+            None => (
+                self.content.clone(),
+                self.tool_calls.clone(),
+                self.finish_reason.clone(),
+            ),
         };
         Ok(LlmResponse {
             content,
             reasoning_content: None,
             tool_calls,
             usage: self.usage.clone(),
+            finish_reason,
         })
     }
 
@@ -228,4 +498,8 @@ impl LlmClient for MockLlm {
             owned_by: Some("mock".to_string()),
         }])
     }
+
+    fn tool_support(&self) -> ToolSupport {
+        self.tool_support
+    }
 }
@@ -20,7 +20,13 @@
 mod mock;
 mod model_cache;
 mod model_registry;
+mod node_llm;
+mod provider_error;
 mod retry;
+mod routing;
+mod text_tools;
+mod thread_summary;
+mod usage_estimate;
 
 use tokio::sync::mpsc;
 
@@ -109,11 +115,17 @@ pub use openai_compat::ChatOpenAICompat;
 #[deprecated(note = "renamed to ChatOpenAICompat")]
 pub type ChatBigModel = ChatOpenAICompat;
 
-pub use mock::MockLlm;
+pub use mock::{MockLlm, MockScript, ScriptedMatch, ScriptedResponse, ScriptedToolCall};
 pub use model_cache::{fetch_provider_models, ModelCache, ProviderModels};
-pub use model_registry::{create_llm_client, ModelEntry, ModelRegistry, ProviderConfig};
+pub use model_registry::{
+    create_llm_client, probe_models_endpoint, ModelEntry, ModelRegistry, ProviderConfig,
+};
+pub use node_llm::{NodeLlm, NodeLlmOverrides};
 pub use openai::ChatOpenAI;
 pub use retry::RetryLlmClient;
+pub use routing::{LlmRoute, RouteHealth, RoutingLlmClient};
+pub use text_tools::{parse_text_tool_call, text_protocol_messages, TextToolCallLlm};
+pub use thread_summary::{generate_thread_summary, parse_thread_summary, ThreadSummary};
 
 use async_trait::async_trait;
 
@@ -158,7 +170,9 @@ pub struct ToolCallDelta {
 }
 
 /// Breakdown of prompt tokens (OpenAI `prompt_tokens_details`).
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+)]
 pub struct PromptTokensDetails {
     /// Cached tokens present in the prompt.
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -169,7 +183,9 @@ pub struct PromptTokensDetails {
 }
 
 /// Breakdown of completion tokens (OpenAI `completion_tokens_details`).
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+)]
 pub struct CompletionTokensDetails {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub reasoning_tokens: Option<u32>,
@@ -188,7 +204,9 @@ pub struct CompletionTokensDetails {
 ///
 /// **Interaction**: Optional part of `LlmResponse`; emitted as `StreamEvent::Usage`
 /// when streaming so CLI can print usage when `--verbose`.
-#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+)]
 pub struct LlmUsage {
     /// Tokens in the prompt (input).
     pub prompt_tokens: u32,
@@ -216,6 +234,83 @@ impl LlmUsage {
     }
 }
 
+/// Why the model stopped generating, as reported by the provider.
+///
+/// Serialized as the OpenAI wire string (`stop`, `length`, `tool_calls`, `content_filter`);
+/// unknown provider values are kept verbatim in [`FinishReason::Other`].
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum FinishReason {
+    /// Natural end of the answer or a stop sequence was hit.
+    Stop,
+    /// Output token limit reached; the answer is truncated.
+    Length,
+    /// The model stopped to call tools.
+    ToolCalls,
+    /// Output was cut by the provider's content filter.
+    ContentFilter,
+    /// Generation was stopped on request ([`RunCancellation::stop_generation`](crate::cli_run::RunCancellation::stop_generation));
+    /// the content is what had been produced so far.
+    UserStopped,
+    /// Any other provider-specific reason.
+    Other(String),
+}
+
+impl FinishReason {
+    /// Maps a provider finish reason string, including Anthropic-style aliases
+    /// (`end_turn`, `stop_sequence`, `max_tokens`, `tool_use`).
+    pub fn from_provider(reason: &str) -> Self {
+        match reason.trim().to_ascii_lowercase().as_str() {
+            "stop" | "end_turn" | "stop_sequence" | "eos" => Self::Stop,
+            "length" | "max_tokens" => Self::Length,
+            "tool_calls" | "function_call" | "tool_use" => Self::ToolCalls,
+            "content_filter" | "safety" => Self::ContentFilter,
+            "user_stopped" => Self::UserStopped,
+            _ => Self::Other(reason.to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Stop => "stop",
+            Self::Length => "length",
+            Self::ToolCalls => "tool_calls",
+            Self::ContentFilter => "content_filter",
+            Self::UserStopped => "user_stopped",
+            Self::Other(s) => s,
+        }
+    }
+}
+
+impl std::fmt::Display for FinishReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<String> for FinishReason {
+    fn from(s: String) -> Self {
+        Self::from_provider(&s)
+    }
+}
+
+impl From<FinishReason> for String {
+    fn from(r: FinishReason) -> Self {
+        r.as_str().to_string()
+    }
+}
+
+/// On the wire a finish reason is its string (see [`FinishReason::as_str`]).
+impl schemars::JsonSchema for FinishReason {
+    fn schema_name() -> String {
+        "FinishReason".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(gen)
+    }
+}
+
 /// Response from an LLM completion: assistant message text and optional tool calls.
 ///
 /// **Interaction**: Returned by `LlmClient::invoke()`; ThinkNode writes
@@ -230,6 +325,8 @@ pub struct LlmResponse {
     pub tool_calls: Vec<ToolCall>,
     /// Token usage for this call, when available (e.g. OpenAI returns this).
     pub usage: Option<LlmUsage>,
+    /// Why generation stopped, when the provider reports it.
+    pub finish_reason: Option<FinishReason>,
 }
 
 /// LLM client: given messages, returns assistant text and optional tool_calls.
@@ -315,12 +412,56 @@ pub trait LlmClient: Send + Sync {
     ) -> Result<LlmResponse, AgentError> {
         self.invoke_stream(messages, chunk_tx).await
     }
+
+    /// Replaces the tool definitions sent with each request, e.g. after the tool source
+    /// reported changed tools. The default implementation ignores the call (clients that do
+    /// not send tool definitions).
+    fn set_tools(&self, _tools: Vec<crate::tool_source::ToolSpec>) {}
+
+    /// Tool-calling capabilities of the model behind this client (see
+    /// [`crate::model_spec::tool_support_for`]); the think node adapts to them. The default
+    /// reports full support.
+    fn tool_support(&self) -> crate::model_spec::ToolSupport {
+        crate::model_spec::ToolSupport::default()
+    }
+
+    /// Endpoint that served the last call, for clients that route between several
+    /// (see [`RoutingLlmClient`]). `None` for single-endpoint clients.
+    fn route(&self) -> Option<String> {
+        None
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn finish_reason_maps_provider_values_and_roundtrips_as_string() {
+        assert_eq!(FinishReason::from_provider("stop"), FinishReason::Stop);
+        assert_eq!(FinishReason::from_provider("end_turn"), FinishReason::Stop);
+        assert_eq!(
+            FinishReason::from_provider("max_tokens"),
+            FinishReason::Length
+        );
+        assert_eq!(
+            FinishReason::from_provider("tool_use"),
+            FinishReason::ToolCalls
+        );
+        assert_eq!(
+            FinishReason::from_provider("content_filter"),
+            FinishReason::ContentFilter
+        );
+        assert_eq!(
+            FinishReason::from_provider("recitation"),
+            FinishReason::Other("recitation".to_string())
+        );
+        let json = serde_json::to_string(&FinishReason::Length).unwrap();
+        assert_eq!(json, "\"length\"");
+        let parsed: FinishReason = serde_json::from_str("\"tool_calls\"").unwrap();
+        assert_eq!(parsed, FinishReason::ToolCalls);
+    }
+
     struct StubLlm {
         content: String,
     }
@@ -333,6 +474,7 @@ mod tests {
                 reasoning_content: None,
                 tool_calls: vec![],
                 usage: None,
+                finish_reason: None,
             })
         }
     }
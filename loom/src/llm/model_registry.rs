@@ -452,6 +452,18 @@ struct OpenAiModelItem {
     id: String,
 }
 
+/// Lists model ids from an OpenAI-compatible `{base_url}/models` endpoint.
+///
+/// A cheap authenticated request that costs no tokens, so it doubles as a check that the base
+/// URL is reachable and the API key is accepted (used by `loom doctor`).
+pub async fn probe_models_endpoint(
+    base_url: &str,
+    api_key: Option<&str>,
+) -> Result<Vec<String>, AgentError> {
+    let url = format!("{}/models", base_url.trim_end_matches('/'));
+    fetch_models_from_api(&url, api_key).await
+}
+
 async fn fetch_models_from_api(
     url: &str,
     api_key: Option<&str>,
@@ -469,6 +481,10 @@ async fn fetch_models_from_api(
         .map_err(|e| {
             AgentError::ExecutionFailed(format!("failed to fetch models from {url}: {e}"))
         })?
+        .error_for_status()
+        .map_err(|e| {
+            AgentError::ExecutionFailed(format!("failed to fetch models from {url}: {e}"))
+        })?
         .json()
         .await
         .map_err(|e| {
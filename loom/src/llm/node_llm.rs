@@ -0,0 +1,137 @@
+//! Per-node LLM overrides: route individual graph nodes to different models.
+//!
+//! Runners (ReAct, ToT, GoT) look up their LLM-backed nodes here by node id
+//! (e.g. `think`, `think_expand`, `plan_graph`) and fall back to the runner's default
+//! LLM when no override is registered. Each override carries its model id so
+//! usage can be accounted per model.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::LlmClient;
+
+/// An LLM client bound to a graph node, labelled with the model id it talks to.
+#[derive(Clone)]
+pub struct NodeLlm {
+    /// Model id used to label usage (e.g. `openai/gpt-4o`).
+    pub model: String,
+    /// Client used by the node instead of the runner's default LLM.
+    pub llm: Arc<dyn LlmClient>,
+}
+
+impl std::fmt::Debug for NodeLlm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NodeLlm")
+            .field("model", &self.model)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Node id → LLM overrides for one runner, plus the default model's label.
+#[derive(Clone, Debug, Default)]
+pub struct NodeLlmOverrides {
+    /// Model id of the runner's default LLM; used to label usage of nodes without an override.
+    /// `None` when the default LLM was supplied by the caller and its model is unknown.
+    pub default_model: Option<String>,
+    nodes: HashMap<String, NodeLlm>,
+}
+
+impl NodeLlmOverrides {
+    /// Creates an empty set of overrides labelled with the default model id.
+    pub fn new(default_model: Option<String>) -> Self {
+        Self {
+            default_model,
+            nodes: HashMap::new(),
+        }
+    }
+
+    /// Registers `llm` (talking to `model`) for the node with id `node_id`.
+    pub fn with_node(
+        mut self,
+        node_id: impl Into<String>,
+        model: impl Into<String>,
+        llm: Arc<dyn LlmClient>,
+    ) -> Self {
+        self.insert(node_id, model, llm);
+        self
+    }
+
+    /// Registers `llm` (talking to `model`) for the node with id `node_id`.
+    pub fn insert(
+        &mut self,
+        node_id: impl Into<String>,
+        model: impl Into<String>,
+        llm: Arc<dyn LlmClient>,
+    ) {
+        self.nodes.insert(
+            node_id.into(),
+            NodeLlm {
+                model: model.into(),
+                llm,
+            },
+        );
+    }
+
+    /// Returns the override for `node_id`, if any.
+    pub fn get(&self, node_id: &str) -> Option<&NodeLlm> {
+        self.nodes.get(node_id)
+    }
+
+    /// Returns the override LLM for `node_id`, or `default` when none is registered.
+    pub fn llm_for(&self, node_id: &str, default: &Arc<dyn LlmClient>) -> Arc<dyn LlmClient> {
+        self.nodes
+            .get(node_id)
+            .map(|n| Arc::clone(&n.llm))
+            .unwrap_or_else(|| Arc::clone(default))
+    }
+
+    /// Returns the model id used by `node_id`: its override's model, else the default model.
+    pub fn model_for(&self, node_id: &str) -> Option<String> {
+        self.nodes
+            .get(node_id)
+            .map(|n| n.model.clone())
+            .or_else(|| self.default_model.clone())
+    }
+
+    /// Node ids that have an override.
+    pub fn node_ids(&self) -> impl Iterator<Item = &str> {
+        self.nodes.keys().map(String::as_str)
+    }
+
+    /// True when no node has an override.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlm;
+
+    #[test]
+    fn llm_for_and_model_for_fall_back_to_default() {
+        let default: Arc<dyn LlmClient> = Arc::new(MockLlm::with_no_tool_calls("default"));
+        let cheap: Arc<dyn LlmClient> = Arc::new(MockLlm::with_no_tool_calls("cheap"));
+        let overrides = NodeLlmOverrides::new(Some("openai/gpt-4o".into())).with_node(
+            "think_expand",
+            "openai/gpt-4o-mini",
+            Arc::clone(&cheap),
+        );
+
+        assert!(Arc::ptr_eq(
+            &overrides.llm_for("think_expand", &default),
+            &cheap
+        ));
+        assert!(Arc::ptr_eq(&overrides.llm_for("think", &default), &default));
+        assert_eq!(
+            overrides.model_for("think_expand").as_deref(),
+            Some("openai/gpt-4o-mini")
+        );
+        assert_eq!(
+            overrides.model_for("think").as_deref(),
+            Some("openai/gpt-4o")
+        );
+        assert!(!overrides.is_empty());
+    }
+}
@@ -23,16 +23,25 @@ use crate::http_retry::{
     classify_openai_error_message, retry_backoff_for_attempt, RetryDecision,
     TRANSIENT_HTTP_MAX_RETRIES,
 };
+use crate::llm::provider_error::provider_error;
 use crate::llm::thinking::collect_thinking_tags;
-use crate::llm::{LlmClient, LlmResponse, LlmUsage, ToolCallDelta};
+use crate::llm::usage_estimate::estimate_usage;
+use crate::llm::{FinishReason, LlmClient, LlmResponse, LlmUsage, ToolCallDelta};
 use crate::memory::uuid6;
 use crate::message::Message;
+use crate::model_spec::ToolSupport;
 use crate::state::ToolCall;
 use crate::stream::MessageChunk;
 use crate::tool_source::{ToolSource, ToolSourceError, ToolSpec};
 
 use super::ToolChoiceMode;
 
+/// Maps async-openai's finish reason via its wire string, so new upstream variants still map.
+fn finish_reason_from_openai<T: serde::Serialize>(reason: Option<&T>) -> Option<FinishReason> {
+    let value = serde_json::to_value(reason?).ok()?;
+    value.as_str().map(FinishReason::from_provider)
+}
+
 pub(super) fn completion_usage_to_llm(u: &CompletionUsage) -> LlmUsage {
     use crate::llm::{CompletionTokensDetails, PromptTokensDetails};
 
@@ -67,12 +76,17 @@ pub(super) fn completion_usage_to_llm(u: &CompletionUsage) -> LlmUsage {
 pub struct ChatOpenAI {
     client: Client<OpenAIConfig>,
     model: String,
-    tools: Option<Vec<ToolSpec>>,
+    /// Behind a lock so [`LlmClient::set_tools`] can replace them between calls.
+    tools: std::sync::RwLock<Option<Vec<ToolSpec>>>,
     temperature: Option<f32>,
     tool_choice: Option<ToolChoiceMode>,
+    /// Sequences that end generation; empty omits `stop` from requests.
+    stop: Vec<String>,
     /// When true, parse content for thinking tags and emit as MessageChunk::thinking / message.
     parse_thinking_tags: bool,
     headers: Option<crate::llm::LlmHeaders>,
+    /// Reported by [`LlmClient::tool_support`]; set with [`Self::with_tool_support`].
+    tool_support: ToolSupport,
 }
 
 impl ChatOpenAI {
@@ -84,11 +98,13 @@ impl ChatOpenAI {
         Self {
             client: Client::new(),
             model: model.into(),
-            tools: None,
+            tools: std::sync::RwLock::new(None),
             temperature: None,
             tool_choice: None,
+            stop: Vec::new(),
             parse_thinking_tags: false,
             headers: None,
+            tool_support: ToolSupport::default(),
         }
     }
 
@@ -100,11 +116,13 @@ impl ChatOpenAI {
         Self {
             client: Client::with_config(config),
             model: model.into(),
-            tools: None,
+            tools: std::sync::RwLock::new(None),
             temperature: None,
             tool_choice: None,
+            stop: Vec::new(),
             parse_thinking_tags: false,
             headers: None,
+            tool_support: ToolSupport::default(),
         }
     }
 
@@ -128,7 +146,7 @@ impl ChatOpenAI {
     ///
     /// Passing a non-empty tool list allows the provider to return tool calls.
     pub fn with_tools(mut self, tools: Vec<ToolSpec>) -> Self {
-        self.tools = Some(tools);
+        self.tools = std::sync::RwLock::new(Some(tools));
         self
     }
 
@@ -138,6 +156,13 @@ impl ChatOpenAI {
         self
     }
 
+    /// Sets stop sequences: generation ends before the model emits any of them, and the
+    /// response reports [`crate::llm::FinishReason::Stop`].
+    pub fn with_stop(mut self, stop: Vec<String>) -> Self {
+        self.stop = stop;
+        self
+    }
+
     /// Sets the tool choice mode used when tools are present.
     ///
     /// If unset, the request omits `tool_choice` and the API default applies
@@ -149,6 +174,13 @@ impl ChatOpenAI {
         self
     }
 
+    /// Sets the tool-calling capabilities of the model (see [`crate::model_spec::tool_support_for`]),
+    /// which the think node adapts to. Default: full support.
+    pub fn with_tool_support(mut self, support: ToolSupport) -> Self {
+        self.tool_support = support;
+        self
+    }
+
     /// Enables parsing of thinking-tag segments in streamed output.
     ///
     /// Content inside thinking tags is emitted as
@@ -204,6 +236,16 @@ impl ChatOpenAI {
         }
     }
 
+    fn tools_snapshot(&self) -> Option<Vec<ToolSpec>> {
+        self.tools.read().ok().and_then(|tools| tools.clone())
+    }
+
+    fn tools_count(&self) -> usize {
+        self.tools
+            .read()
+            .map_or(0, |tools| tools.as_ref().map_or(0, Vec::len))
+    }
+
     fn build_request(
         &self,
         messages: &[Message],
@@ -212,9 +254,10 @@ impl ChatOpenAI {
         request::build_chat_request(
             &self.model,
             messages,
-            self.tools.as_deref(),
+            self.tools_snapshot().as_deref(),
             self.temperature,
             self.tool_choice,
+            &self.stop,
             stream,
         )
     }
@@ -222,10 +265,20 @@ impl ChatOpenAI {
 
 #[async_trait]
 impl LlmClient for ChatOpenAI {
+    fn set_tools(&self, tools: Vec<ToolSpec>) {
+        if let Ok(mut guard) = self.tools.write() {
+            *guard = Some(tools);
+        }
+    }
+
+    fn tool_support(&self) -> ToolSupport {
+        self.tool_support
+    }
+
     async fn invoke(&self, messages: &[Message]) -> Result<LlmResponse, AgentError> {
         let trace_id = uuid6().to_string();
         let request_id = uuid6().to_string();
-        let tools_count = self.tools.as_ref().map(|t| t.len()).unwrap_or(0);
+        let tools_count = self.tools_count();
         let url = Self::chat_completions_url();
         debug!(
             trace_id = %trace_id,
@@ -272,10 +325,10 @@ impl LlmClient for ChatOpenAI {
                         error = %error_message,
                         "OpenAI API request failed without retry"
                     );
-                    return Err(AgentError::ExecutionFailed(format!(
-                        "OpenAI API error: {}",
-                        error_message
-                    )));
+                    return Err(provider_error(
+                        None,
+                        format!("OpenAI API error: {}", error_message),
+                    ));
                 }
             }
         };
@@ -285,6 +338,7 @@ impl LlmClient for ChatOpenAI {
                 AgentError::ExecutionFailed("OpenAI returned no choices".to_string())
             })?;
 
+        let finish_reason = finish_reason_from_openai(choice.finish_reason.as_ref());
         let msg = choice.message;
         let content = msg.content.unwrap_or_default();
         let reasoning_content = collect_thinking_tags(&content);
@@ -311,6 +365,7 @@ impl LlmClient for ChatOpenAI {
             reasoning_content,
             tool_calls,
             usage,
+            finish_reason,
         })
     }
 
@@ -336,7 +391,7 @@ impl LlmClient for ChatOpenAI {
         let trace_id = uuid6().to_string();
         let request_id = uuid6().to_string();
         let chunk_tx = chunk_tx.expect("chunk_tx must be Some when streaming");
-        let tools_count = self.tools.as_ref().map(|t| t.len()).unwrap_or(0);
+        let tools_count = self.tools_count();
         let url = Self::chat_completions_url();
         debug!(
             trace_id = %trace_id,
@@ -384,18 +439,18 @@ impl LlmClient for ChatOpenAI {
                         error = %error_message,
                         "OpenAI stream request failed without retry"
                     );
-                    return Err(AgentError::ExecutionFailed(format!(
-                        "OpenAI stream error: {}",
-                        error_message
-                    )));
+                    return Err(provider_error(
+                        None,
+                        format!("OpenAI stream error: {}", error_message),
+                    ));
                 }
             }
         };
 
         let mut acc = stream::StreamAccumulator::new(self.parse_thinking_tags);
         while let Some(result) = stream.next().await {
-            let response = result
-                .map_err(|e| AgentError::ExecutionFailed(format!("OpenAI stream error: {}", e)))?;
+            let response =
+                result.map_err(|e| provider_error(None, format!("OpenAI stream error: {}", e)))?;
             acc.process_chunk(response, &chunk_tx, tool_delta_tx.as_ref())
                 .await;
         }
@@ -414,12 +469,17 @@ impl LlmClient for ChatOpenAI {
             "OpenAI stream response"
         );
 
-        Ok(LlmResponse {
+        let mut response = LlmResponse {
             content: result.content,
             reasoning_content: result.reasoning_content,
             tool_calls: result.tool_calls,
             usage: result.usage,
-        })
+            finish_reason: result.finish_reason,
+        };
+        if response.usage.is_none() {
+            response.usage = Some(estimate_usage(&self.model, messages, &response));
+        }
+        Ok(response)
     }
 
     async fn list_models(&self) -> Result<Vec<crate::llm::ModelInfo>, AgentError> {
@@ -2,7 +2,7 @@
 //!
 //! Centralizes `Message → ChatCompletionRequestMessage` conversion and
 //! the `CreateChatCompletionRequest` assembly (tools, temperature,
-//! tool_choice, stream flag) so `invoke()` and `invoke_stream()` share
+//! tool_choice, stop sequences, stream flag) so `invoke()` and `invoke_stream()` share
 //! one code path.
 
 use async_openai::types::chat::{
@@ -14,7 +14,7 @@ use async_openai::types::chat::{
     ChatCompletionRequestUserMessage, ChatCompletionRequestUserMessageContent,
     ChatCompletionRequestUserMessageContentPart, ChatCompletionTool,
     ChatCompletionToolChoiceOption, ChatCompletionTools, CreateChatCompletionRequestArgs,
-    FunctionCall, FunctionObject, ImageDetail, ImageUrl, ToolChoiceOptions,
+    FunctionCall, FunctionObject, ImageDetail, ImageUrl, StopConfiguration, ToolChoiceOptions,
 };
 
 use crate::error::AgentError;
@@ -160,13 +160,14 @@ pub(super) fn messages_to_openai(messages: &[Message]) -> Vec<ChatCompletionRequ
 /// Build a complete `CreateChatCompletionRequest`.
 ///
 /// When `stream` is true, sets `args.stream(true)` so the same function
-/// serves both invoke and invoke_stream.
+/// serves both invoke and invoke_stream. An empty `stop` omits the field.
 pub(super) fn build_chat_request(
     model: &str,
     messages: &[Message],
     tools: Option<&[ToolSpec]>,
     temperature: Option<f32>,
     tool_choice: Option<ToolChoiceMode>,
+    stop: &[String],
     stream: bool,
 ) -> Result<async_openai::types::chat::CreateChatCompletionRequest, AgentError> {
     debug!(
@@ -208,7 +209,7 @@ pub(super) fn build_chat_request(
         args.stream(true);
     }
 
-    if let Some(tools) = tools {
+    if let Some(tools) = tools.filter(|t| !t.is_empty()) {
         let chat_tools: Vec<ChatCompletionTools> = tools
             .iter()
             .map(|t| {
@@ -229,6 +230,10 @@ pub(super) fn build_chat_request(
         args.temperature(t);
     }
 
+    if !stop.is_empty() {
+        args.stop(StopConfiguration::StringArray(stop.to_vec()));
+    }
+
     let tools_nonempty = tools.is_some_and(|t| !t.is_empty());
     if let Some(mode) = tool_choice {
         if tools_nonempty {
@@ -261,6 +266,7 @@ pub(super) fn build_chat_request(
         tools_count = req.tools.as_ref().map_or(0, |t| t.len()),
         temperature = ?req.temperature,
         tool_choice = ?req.tool_choice,
+        stop = ?req.stop,
         stream = ?req.stream,
         "build_chat_request complete"
     );
@@ -319,6 +325,7 @@ mod tests {
             None,
             None,
             None,
+            &[],
             true,
         )
         .unwrap();
@@ -329,6 +336,7 @@ mod tests {
             None,
             None,
             None,
+            &[],
             false,
         )
         .unwrap();
@@ -343,6 +351,7 @@ mod tests {
             description: None,
             input_schema: serde_json::json!({}),
             output_hint: None,
+            catalog: Default::default(),
         }];
         let r = build_chat_request(
             "gpt-4o-mini",
@@ -350,6 +359,7 @@ mod tests {
             Some(&tools),
             None,
             Some(ToolChoiceMode::Required),
+            &[],
             false,
         )
         .unwrap();
@@ -365,9 +375,41 @@ mod tests {
             None,
             None,
             Some(ToolChoiceMode::Required),
+            &[],
             false,
         )
         .unwrap();
         assert!(r.tool_choice.is_none());
     }
+
+    #[test]
+    fn build_chat_request_sets_stop_sequences() {
+        let stop = vec!["</answer>".to_string(), "\nObservation:".to_string()];
+        let r = build_chat_request(
+            "gpt-4o-mini",
+            &[Message::user("hi")],
+            None,
+            None,
+            None,
+            &stop,
+            false,
+        )
+        .unwrap();
+        let v = serde_json::to_value(&r).expect("json");
+        assert_eq!(
+            v["stop"],
+            serde_json::json!(["</answer>", "\nObservation:"])
+        );
+        let r2 = build_chat_request(
+            "gpt-4o-mini",
+            &[Message::user("hi")],
+            None,
+            None,
+            None,
+            &[],
+            false,
+        )
+        .unwrap();
+        assert!(r2.stop.is_none());
+    }
 }
@@ -13,7 +13,7 @@ use crate::llm::thinking::{
     collect_thinking_tags, strip_thinking_tags, ThinkingSegment, ThinkingTagParser,
 };
 use crate::llm::tool_call_accumulator::{RawToolCallDelta, ToolCallAccumulator};
-use crate::llm::{LlmUsage, ToolCallDelta};
+use crate::llm::{FinishReason, LlmUsage, ToolCallDelta};
 use crate::stream::MessageChunk;
 
 /// Accumulates streaming SSE chunks into a complete response.
@@ -21,6 +21,7 @@ pub(super) struct StreamAccumulator {
     full_content: String,
     tool_calls: ToolCallAccumulator,
     usage: Option<LlmUsage>,
+    finish_reason: Option<FinishReason>,
     sent_any_content: bool,
     thinking_parser: Option<ThinkingTagParser>,
     parse_thinking_tags: bool,
@@ -31,6 +32,7 @@ pub(super) struct StreamResult {
     pub reasoning_content: Option<String>,
     pub tool_calls: Vec<crate::state::ToolCall>,
     pub usage: Option<LlmUsage>,
+    pub finish_reason: Option<FinishReason>,
 }
 
 impl StreamAccumulator {
@@ -43,6 +45,7 @@ impl StreamAccumulator {
             full_content: String::new(),
             tool_calls: ToolCallAccumulator::new(),
             usage: None,
+            finish_reason: None,
             sent_any_content: false,
             thinking_parser: parse_thinking.then(ThinkingTagParser::new),
             parse_thinking_tags: parse_thinking,
@@ -64,6 +67,9 @@ impl StreamAccumulator {
         }
 
         for choice in response.choices {
+            if let Some(reason) = super::finish_reason_from_openai(choice.finish_reason.as_ref()) {
+                self.finish_reason = Some(reason);
+            }
             let delta = &choice.delta;
 
             if let Some(ref content) = delta.content {
@@ -183,6 +189,7 @@ impl StreamAccumulator {
             reasoning_content,
             tool_calls: self.tool_calls.finish(),
             usage: self.usage,
+            finish_reason: self.finish_reason,
         }
     }
 }
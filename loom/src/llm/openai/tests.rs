@@ -86,6 +86,7 @@ fn chat_openai_with_tools_and_temperature_builder() {
         description: None,
         input_schema: serde_json::json!({}),
         output_hint: None,
+        catalog: Default::default(),
     }];
     let _ = ChatOpenAI::new("gpt-4")
         .with_tools(tools)
@@ -275,6 +276,7 @@ async fn invoke_and_invoke_stream_none_channel_succeed_with_mock_server() {
         description: Some("time".into()),
         input_schema: serde_json::json!({"type":"object"}),
         output_hint: None,
+        catalog: Default::default(),
     }];
     let client = ChatOpenAI::with_config(config, "gpt-4o-mini")
         .with_tools(tools)
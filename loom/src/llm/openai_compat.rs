@@ -28,17 +28,20 @@ use crate::error::AgentError;
 use crate::http_retry::{
     is_retryable_reqwest_error, retry_backoff_for_attempt, TRANSIENT_HTTP_MAX_RETRIES,
 };
-use crate::llm::{LlmClient, LlmResponse, LlmUsage, ToolCallDelta};
+use crate::llm::{FinishReason, LlmClient, LlmResponse, LlmUsage, ToolCallDelta};
 use crate::memory::uuid6;
 use crate::message::{assistant_content_for_chat_api, ContentPart, Message, UserContent};
+use crate::model_spec::ToolSupport;
 use crate::state::ToolCall;
 use crate::stream::MessageChunk;
 use crate::tool_source::{ToolSource, ToolSourceError, ToolSpec};
 
+use super::provider_error::provider_error;
 use super::thinking::{
     collect_thinking_tags, strip_thinking_tags, ThinkingSegment, ThinkingTagParser,
 };
 use super::tool_call_accumulator::{RawToolCallDelta, ToolCallAccumulator};
+use super::usage_estimate::estimate_usage;
 use super::ToolChoiceMode;
 
 /// Example default base URL (Zhipu BigModel OpenAI-compatible API).
@@ -118,6 +121,8 @@ struct ChatCompletionRequest {
     tools: Option<Vec<ToolSpecRequest>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_choice: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop: Option<Vec<String>>,
 }
 
 // ----- Non-stream response DTOs -----
@@ -148,6 +153,8 @@ struct ResponseMessage {
 #[derive(serde::Deserialize)]
 struct ResponseChoice {
     message: ResponseMessage,
+    #[serde(default)]
+    finish_reason: Option<String>,
 }
 
 #[derive(serde::Deserialize)]
@@ -191,11 +198,9 @@ struct StreamDelta {
 }
 
 #[derive(serde::Deserialize)]
-#[allow(dead_code)]
 struct StreamChoice {
     delta: StreamDelta,
     /// OpenAI-compatible; optional so we don't fail if the API omits it.
-    #[allow(dead_code)]
     #[serde(default)]
     finish_reason: Option<String>,
 }
@@ -217,11 +222,16 @@ pub struct ChatOpenAICompat {
     base_url: String,
     api_key: String,
     model: String,
-    tools: Option<Vec<ToolSpec>>,
+    /// Behind a lock so [`LlmClient::set_tools`] can replace them between calls.
+    tools: std::sync::RwLock<Option<Vec<ToolSpec>>>,
     temperature: Option<f32>,
     tool_choice: Option<ToolChoiceMode>,
+    /// Sequences that end generation; empty omits `stop` from requests.
+    stop: Vec<String>,
     parse_thinking_tags: bool,
     headers: Option<crate::llm::LlmHeaders>,
+    /// Reported by [`LlmClient::tool_support`]; set with [`Self::with_tool_support`].
+    tool_support: ToolSupport,
 }
 
 impl ChatOpenAICompat {
@@ -249,11 +259,13 @@ impl ChatOpenAICompat {
             base_url: base_url.into(),
             api_key: api_key.into(),
             model: model.into(),
-            tools: None,
+            tools: std::sync::RwLock::new(None),
             temperature: None,
             tool_choice: None,
+            stop: Vec::new(),
             parse_thinking_tags: false,
             headers: None,
+            tool_support: ToolSupport::default(),
         }
     }
 
@@ -276,7 +288,7 @@ impl ChatOpenAICompat {
     /// Passing tools allows the provider to return function calls in the
     /// response payload.
     pub fn with_tools(mut self, tools: Vec<ToolSpec>) -> Self {
-        self.tools = Some(tools);
+        self.tools = std::sync::RwLock::new(Some(tools));
         self
     }
 
@@ -288,6 +300,12 @@ impl ChatOpenAICompat {
         self
     }
 
+    /// Sets stop sequences: generation ends before the model emits any of them.
+    pub fn with_stop(mut self, stop: Vec<String>) -> Self {
+        self.stop = stop;
+        self
+    }
+
     /// Sets the tool choice mode used when tools are present.
     ///
     /// If unset, `tool_choice` is omitted from the request (provider default,
@@ -297,6 +315,13 @@ impl ChatOpenAICompat {
         self
     }
 
+    /// Sets the tool-calling capabilities of the model (see [`crate::model_spec::tool_support_for`]),
+    /// which the think node adapts to. Default: full support.
+    pub fn with_tool_support(mut self, support: ToolSupport) -> Self {
+        self.tool_support = support;
+        self
+    }
+
     /// Enables parsing of `<think>...</think>` segments in streamed output.
     ///
     /// Content inside thinking tags is emitted separately from normal assistant
@@ -469,6 +494,16 @@ impl ChatOpenAICompat {
             .collect()
     }
 
+    fn tools_snapshot(&self) -> Option<Vec<ToolSpec>> {
+        self.tools.read().ok().and_then(|tools| tools.clone())
+    }
+
+    fn tools_count(&self) -> usize {
+        self.tools
+            .read()
+            .map_or(0, |tools| tools.as_ref().map_or(0, Vec::len))
+    }
+
     fn build_request(&self, messages: &[Message], stream: bool) -> ChatCompletionRequest {
         let messages = Self::messages_to_request(messages, &self.model);
         let mut req = ChatCompletionRequest {
@@ -478,8 +513,9 @@ impl ChatOpenAICompat {
             temperature: self.temperature,
             tools: None,
             tool_choice: None,
+            stop: (!self.stop.is_empty()).then(|| self.stop.clone()),
         };
-        if let Some(ref tools) = self.tools {
+        if let Some(tools) = self.tools_snapshot().filter(|t| !t.is_empty()) {
             req.tools = Some(
                 tools
                     .iter()
@@ -510,12 +546,22 @@ impl ChatOpenAICompat {
 
 #[async_trait]
 impl LlmClient for ChatOpenAICompat {
+    fn set_tools(&self, tools: Vec<ToolSpec>) {
+        if let Ok(mut guard) = self.tools.write() {
+            *guard = Some(tools);
+        }
+    }
+
+    fn tool_support(&self) -> ToolSupport {
+        self.tool_support
+    }
+
     async fn invoke(&self, messages: &[Message]) -> Result<LlmResponse, AgentError> {
         let trace_id = uuid6().to_string();
         let request_id = uuid6().to_string();
         let url = self.chat_completions_url();
         let body = self.build_request(messages, false);
-        let tools_count = self.tools.as_ref().map(|t| t.len()).unwrap_or(0);
+        let tools_count = self.tools_count();
         debug!(
             trace_id = %trace_id,
             request_id = %request_id,
@@ -602,10 +648,10 @@ impl LlmClient for ChatOpenAICompat {
             }
             if !is_retryable_status(status) {
                 let msg = String::from_utf8_lossy(&body_bytes);
-                return Err(AgentError::ExecutionFailed(format!(
-                    "OpenAI-compat API error {}: {}",
-                    status, msg
-                )));
+                return Err(provider_error(
+                    Some(status.as_u16()),
+                    format!("OpenAI-compat API error {}: {}", status, msg),
+                ));
             }
             for attempt in 0..COMPAT_RETRY_MAX_RETRIES {
                 let delay = backoff_for_attempt(attempt);
@@ -639,24 +685,27 @@ impl LlmClient for ChatOpenAICompat {
                 }
                 if !is_retryable_status(retry_status) {
                     let msg = String::from_utf8_lossy(&retry_bytes);
-                    return Err(AgentError::ExecutionFailed(format!(
-                        "OpenAI-compat API error {}: {}",
-                        retry_status, msg
-                    )));
+                    return Err(provider_error(
+                        Some(retry_status.as_u16()),
+                        format!("OpenAI-compat API error {}: {}", retry_status, msg),
+                    ));
                 }
                 if attempt == COMPAT_RETRY_MAX_RETRIES - 1 {
                     let msg = String::from_utf8_lossy(&retry_bytes);
-                    return Err(AgentError::ExecutionFailed(format!(
-                        "OpenAI-compat API error {}: {} (after {} retries)",
-                        retry_status, msg, COMPAT_RETRY_MAX_RETRIES
-                    )));
+                    return Err(provider_error(
+                        Some(retry_status.as_u16()),
+                        format!(
+                            "OpenAI-compat API error {}: {} (after {} retries)",
+                            retry_status, msg, COMPAT_RETRY_MAX_RETRIES
+                        ),
+                    ));
                 }
             }
             let msg = String::from_utf8_lossy(&body_bytes);
-            return Err(AgentError::ExecutionFailed(format!(
-                "OpenAI-compat API error {}: {}",
-                status, msg
-            )));
+            return Err(provider_error(
+                Some(status.as_u16()),
+                format!("OpenAI-compat API error {}: {}", status, msg),
+            ));
         };
 
         let response: ChatCompletionResponse =
@@ -668,6 +717,10 @@ impl LlmClient for ChatOpenAICompat {
             AgentError::ExecutionFailed("OpenAI-compat returned no choices".to_string())
         })?;
 
+        let finish_reason = choice
+            .finish_reason
+            .as_deref()
+            .map(FinishReason::from_provider);
         let msg = choice.message;
         let content = msg.content.unwrap_or_default();
         let reasoning_content = msg
@@ -699,6 +752,7 @@ impl LlmClient for ChatOpenAICompat {
             reasoning_content,
             tool_calls,
             usage,
+            finish_reason,
         })
     }
 
@@ -726,7 +780,7 @@ impl LlmClient for ChatOpenAICompat {
         let chunk_tx = chunk_tx.expect("chunk_tx must be Some when streaming");
         let url = self.chat_completions_url();
         let body = self.build_request(messages, true);
-        let tools_count = self.tools.as_ref().map(|t| t.len()).unwrap_or(0);
+        let tools_count = self.tools_count();
         debug!(
             trace_id = %trace_id,
             request_id = %request_id,
@@ -785,10 +839,10 @@ impl LlmClient for ChatOpenAICompat {
         } else if !is_retryable_status(status) {
             let body_bytes = response.bytes().await.unwrap_or_default();
             let msg = String::from_utf8_lossy(&body_bytes);
-            return Err(AgentError::ExecutionFailed(format!(
-                "OpenAI-compat stream error {}: {}",
-                status, msg
-            )));
+            return Err(provider_error(
+                Some(status.as_u16()),
+                format!("OpenAI-compat stream error {}: {}", status, msg),
+            ));
         } else {
             let mut final_response = None;
             for attempt in 0..COMPAT_RETRY_MAX_RETRIES {
@@ -822,18 +876,21 @@ impl LlmClient for ChatOpenAICompat {
                 if !is_retryable_status(retry_status) {
                     let body_bytes = retry_res.bytes().await.unwrap_or_default();
                     let msg = String::from_utf8_lossy(&body_bytes);
-                    return Err(AgentError::ExecutionFailed(format!(
-                        "OpenAI-compat stream error {}: {}",
-                        retry_status, msg
-                    )));
+                    return Err(provider_error(
+                        Some(retry_status.as_u16()),
+                        format!("OpenAI-compat stream error {}: {}", retry_status, msg),
+                    ));
                 }
                 if attempt == COMPAT_RETRY_MAX_RETRIES - 1 {
                     let body_bytes = retry_res.bytes().await.unwrap_or_default();
                     let msg = String::from_utf8_lossy(&body_bytes);
-                    return Err(AgentError::ExecutionFailed(format!(
-                        "OpenAI-compat stream error {}: {} (after {} retries)",
-                        retry_status, msg, COMPAT_RETRY_MAX_RETRIES
-                    )));
+                    return Err(provider_error(
+                        Some(retry_status.as_u16()),
+                        format!(
+                            "OpenAI-compat stream error {}: {} (after {} retries)",
+                            retry_status, msg, COMPAT_RETRY_MAX_RETRIES
+                        ),
+                    ));
                 }
             }
 
@@ -842,10 +899,10 @@ impl LlmClient for ChatOpenAICompat {
                 None => {
                     let body_bytes = response.bytes().await.unwrap_or_default();
                     let msg = String::from_utf8_lossy(&body_bytes);
-                    return Err(AgentError::ExecutionFailed(format!(
-                        "OpenAI-compat stream error {}: {}",
-                        status, msg
-                    )));
+                    return Err(provider_error(
+                        Some(status.as_u16()),
+                        format!("OpenAI-compat stream error {}: {}", status, msg),
+                    ));
                 }
             }
         };
@@ -856,6 +913,7 @@ impl LlmClient for ChatOpenAICompat {
         let mut sent_any_content = false;
         let mut tool_calls_acc = ToolCallAccumulator::new();
         let mut stream_usage: Option<LlmUsage> = None;
+        let mut finish_reason: Option<FinishReason> = None;
         let mut thinking_parser = self.parse_thinking_tags.then(ThinkingTagParser::new);
         let mut done = false;
         let mut stream_read_attempt = 0;
@@ -932,6 +990,9 @@ impl LlmClient for ChatOpenAICompat {
                 };
 
                 for choice in choices {
+                    if let Some(reason) = choice.finish_reason.as_deref() {
+                        finish_reason = Some(FinishReason::from_provider(reason));
+                    }
                     let delta = choice.delta;
 
                     if let Some(ref reasoning_content) = delta.reasoning_content {
@@ -1040,6 +1101,7 @@ impl LlmClient for ChatOpenAICompat {
                     if stream_usage.is_none() {
                         stream_usage = fallback_resp.usage;
                     }
+                    finish_reason = fallback_resp.finish_reason.or(finish_reason);
                     tool_calls_acc.replace_from_vec(fallback_resp.tool_calls);
                 }
             }
@@ -1070,7 +1132,7 @@ impl LlmClient for ChatOpenAICompat {
             Some(full_reasoning_content)
         };
 
-        Ok(LlmResponse {
+        let mut response = LlmResponse {
             content: if self.parse_thinking_tags {
                 strip_thinking_tags(&full_content)
             } else {
@@ -1079,7 +1141,12 @@ impl LlmClient for ChatOpenAICompat {
             reasoning_content,
             tool_calls,
             usage: stream_usage,
-        })
+            finish_reason,
+        };
+        if response.usage.is_none() {
+            response.usage = Some(estimate_usage(&self.model, messages, &response));
+        }
+        Ok(response)
     }
 
     async fn list_models(&self) -> Result<Vec<crate::llm::ModelInfo>, AgentError> {
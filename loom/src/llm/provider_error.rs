@@ -0,0 +1,141 @@
+//! Typed provider errors: maps a failed chat request (HTTP status and error body) to the
+//! [`AgentError`] variant the runner handles specially, so a rate limit is backed off, an
+//! oversized prompt is compacted and an auth or content-filter failure is reported as such.
+
+use std::time::Duration;
+
+use crate::error::AgentError;
+
+/// Error for a failed provider request: a typed variant when `status` or the error text
+/// identifies the cause, else [`AgentError::ExecutionFailed`]. `message` is kept as the error
+/// text either way.
+pub(crate) fn provider_error(status: Option<u16>, message: String) -> AgentError {
+    let lower = message.to_ascii_lowercase();
+    let has = |needles: &[&str]| needles.iter().any(|n| lower.contains(n));
+    if has(&[
+        "content_filter",
+        "content management policy",
+        "content_policy_violation",
+        "responsibleaipolicyviolation",
+        "safety system",
+    ]) {
+        return AgentError::ContentFiltered(message);
+    }
+    if matches!(status, Some(401 | 403))
+        || has(&[
+            "status code 401",
+            "status code 403",
+            "invalid_api_key",
+            "incorrect api key",
+            "invalid api key",
+            "authentication",
+        ])
+    {
+        return AgentError::AuthFailed(message);
+    }
+    if has(&[
+        "context_length_exceeded",
+        "maximum context length",
+        "context window",
+        "prompt is too long",
+        "input is too long",
+    ]) {
+        return AgentError::ContextLengthExceeded(message);
+    }
+    if status == Some(429)
+        || has(&[
+            "status code 429",
+            "rate_limit",
+            "rate limit",
+            "too many requests",
+        ])
+    {
+        return AgentError::RateLimited {
+            retry_after: retry_after(&lower),
+            message,
+        };
+    }
+    AgentError::ExecutionFailed(message)
+}
+
+/// Wait the provider asked for, from texts like "try again in 20s", "try again in 250ms" or
+/// "retry after 3 seconds".
+fn retry_after(lower: &str) -> Option<Duration> {
+    let rest = ["try again in ", "retry after ", "retry-after: "]
+        .iter()
+        .find_map(|marker| lower.find(marker).map(|i| &lower[i + marker.len()..]))?;
+    let number_len = rest
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(rest.len());
+    let value: f64 = rest[..number_len].parse().ok()?;
+    let unit = rest[number_len..].trim_start();
+    let secs = if unit.starts_with("ms") {
+        value / 1000.0
+    } else if unit.starts_with('m') && !unit.starts_with("mi") {
+        value * 60.0
+    } else {
+        value
+    };
+    Duration::try_from_secs_f64(secs).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_provider_error_bodies() {
+        let rate = provider_error(
+            None,
+            "Rate limit reached for gpt-4o. Please try again in 1.5s.".to_string(),
+        );
+        assert!(matches!(
+            rate,
+            AgentError::RateLimited { retry_after: Some(d), .. } if d == Duration::from_millis(1500)
+        ));
+        assert!(matches!(
+            provider_error(Some(429), "{}".to_string()),
+            AgentError::RateLimited {
+                retry_after: None,
+                ..
+            }
+        ));
+        assert!(matches!(
+            provider_error(
+                Some(400),
+                r#"{"error":{"code":"context_length_exceeded"}}"#.to_string()
+            ),
+            AgentError::ContextLengthExceeded(_)
+        ));
+        assert!(matches!(
+            provider_error(
+                Some(400),
+                r#"{"error":{"code":"content_filter"}}"#.to_string()
+            ),
+            AgentError::ContentFiltered(_)
+        ));
+        assert!(matches!(
+            provider_error(Some(401), "Incorrect API key provided".to_string()),
+            AgentError::AuthFailed(_)
+        ));
+        let generic = provider_error(Some(400), "bad request".to_string());
+        assert!(matches!(&generic, AgentError::ExecutionFailed(m) if m == "bad request"));
+    }
+
+    #[test]
+    fn parses_retry_after_units() {
+        assert_eq!(
+            retry_after("please try again in 250ms"),
+            Some(Duration::from_millis(250))
+        );
+        assert_eq!(
+            retry_after("retry after 3 seconds"),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(
+            retry_after("try again in 2m"),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(retry_after("slow down"), None);
+    }
+}
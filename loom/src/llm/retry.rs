@@ -11,6 +11,8 @@ use crate::llm::{LlmClient, LlmResponse, MessageChunk, ModelInfo, ToolCallDelta}
 
 const DEFAULT_MAX_RETRIES: u32 = 3;
 const BASE_DELAY: Duration = Duration::from_millis(500);
+/// Longest wait before retrying a rate-limited call, whatever the provider asked for.
+const MAX_RATE_LIMIT_DELAY: Duration = Duration::from_secs(60);
 
 fn is_empty_response(resp: &LlmResponse) -> bool {
     let content_empty = resp.content.trim().is_empty();
@@ -47,6 +49,25 @@ impl RetryLlmClient {
         self
     }
 
+    /// Wait before retrying after `err`, or `None` to return it: only rate limits are retried,
+    /// after the provider's `retry_after` when given, else the exponential delay.
+    fn rate_limit_delay(&self, err: &AgentError, attempt: u32) -> Option<Duration> {
+        let AgentError::RateLimited { retry_after, .. } = err else {
+            return None;
+        };
+        if attempt >= self.max_retries {
+            return None;
+        }
+        let delay = retry_after.unwrap_or(self.base_delay * 2_u32.pow(attempt));
+        warn!(
+            max_retries = self.max_retries,
+            attempt = attempt + 1,
+            delay_secs = delay.as_secs_f64(),
+            "LLM provider rate limited, retrying"
+        );
+        Some(delay.min(MAX_RATE_LIMIT_DELAY))
+    }
+
     async fn retry_with_delay<F, Fut, T, E>(&self, mut f: F) -> Result<T, AgentError>
     where
         F: FnMut() -> Fut,
@@ -55,7 +76,16 @@ impl RetryLlmClient {
         T: IsEmptyResponse,
     {
         for attempt in 0..=self.max_retries {
-            let result = f().await.map_err(Into::into)?;
+            let result = match f().await.map_err(Into::into) {
+                Ok(result) => result,
+                Err(e) => match self.rate_limit_delay(&e, attempt) {
+                    Some(delay) => {
+                        sleep(delay).await;
+                        continue;
+                    }
+                    None => return Err(e),
+                },
+            };
 
             if !result.is_empty() {
                 return Ok(result);
@@ -120,10 +150,16 @@ impl LlmClient for RetryLlmClient {
         let messages = messages.to_vec();
 
         for attempt in 0..=self.max_retries {
-            let resp = inner
-                .invoke_stream(&messages, None)
-                .await
-                .map_err(|e| AgentError::ExecutionFailed(e.to_string()))?;
+            let resp = match inner.invoke_stream(&messages, None).await {
+                Ok(resp) => resp,
+                Err(e) => match self.rate_limit_delay(&e, attempt) {
+                    Some(delay) => {
+                        sleep(delay).await;
+                        continue;
+                    }
+                    None => return Err(e),
+                },
+            };
 
             if !resp.is_empty() {
                 Self::send_chunks_to(&chunk_tx, &resp).await;
@@ -149,6 +185,18 @@ impl LlmClient for RetryLlmClient {
         self.inner.list_models().await
     }
 
+    fn set_tools(&self, tools: Vec<crate::tool_source::ToolSpec>) {
+        self.inner.set_tools(tools);
+    }
+
+    fn tool_support(&self) -> crate::model_spec::ToolSupport {
+        self.inner.tool_support()
+    }
+
+    fn route(&self) -> Option<String> {
+        self.inner.route()
+    }
+
     async fn invoke_stream_with_tool_delta(
         &self,
         messages: &[crate::llm::Message],
@@ -159,10 +207,19 @@ impl LlmClient for RetryLlmClient {
         let messages = messages.to_vec();
 
         for attempt in 0..=self.max_retries {
-            let resp = inner
+            let resp = match inner
                 .invoke_stream_with_tool_delta(&messages, None, None)
                 .await
-                .map_err(|e| AgentError::ExecutionFailed(e.to_string()))?;
+            {
+                Ok(resp) => resp,
+                Err(e) => match self.rate_limit_delay(&e, attempt) {
+                    Some(delay) => {
+                        sleep(delay).await;
+                        continue;
+                    }
+                    None => return Err(e),
+                },
+            };
 
             if !resp.is_empty() {
                 Self::send_chunks_to(&chunk_tx, &resp).await;
@@ -201,7 +258,7 @@ impl LlmClient for RetryLlmClient {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::llm::mock::MockLlm;
+    use crate::llm::mock::{MockLlm, MockScript};
 
     #[tokio::test]
     async fn test_is_empty_response_all_empty() {
@@ -210,6 +267,7 @@ mod tests {
             reasoning_content: None,
             tool_calls: vec![],
             usage: None,
+            finish_reason: None,
         };
         assert!(is_empty_response(&resp));
     }
@@ -221,6 +279,7 @@ mod tests {
             reasoning_content: None,
             tool_calls: vec![],
             usage: None,
+            finish_reason: None,
         };
         assert!(!is_empty_response(&resp));
     }
@@ -232,6 +291,7 @@ mod tests {
             reasoning_content: Some("thinking".to_string()),
             tool_calls: vec![],
             usage: None,
+            finish_reason: None,
         };
         assert!(!is_empty_response(&resp));
     }
@@ -247,6 +307,7 @@ mod tests {
                 arguments: "{}".to_string(),
             }],
             usage: None,
+            finish_reason: None,
         };
         assert!(!is_empty_response(&resp));
     }
@@ -258,6 +319,7 @@ mod tests {
             reasoning_content: Some("   ".to_string()),
             tool_calls: vec![],
             usage: None,
+            finish_reason: None,
         };
         assert!(is_empty_response(&resp));
     }
@@ -311,4 +373,40 @@ mod tests {
             AgentError::EmptyLlmResponse { retries: 1 }
         ));
     }
+
+    #[tokio::test]
+    async fn test_retry_llm_client_waits_out_rate_limits() {
+        let script = MockScript::from_yaml(
+            r#"
+responses:
+  - error: "Rate limit reached. Please try again in 1ms."
+  - error: "429 Too Many Requests"
+  - content: "done"
+"#,
+        )
+        .unwrap();
+        let retry = RetryLlmClient::new(Arc::new(MockLlm::scripted(script)))
+            .with_base_delay(Duration::from_millis(1));
+
+        let result = retry.invoke(&[]).await.unwrap();
+        assert_eq!(result.content, "done");
+    }
+
+    #[tokio::test]
+    async fn test_retry_llm_client_keeps_provider_error_type() {
+        let script = MockScript::from_yaml(
+            r#"
+responses:
+  - error: "context_length_exceeded"
+"#,
+        )
+        .unwrap();
+        let retry = RetryLlmClient::new(Arc::new(MockLlm::scripted(script)));
+
+        let result = retry.invoke_stream(&[], None).await;
+        assert!(matches!(
+            result.unwrap_err(),
+            AgentError::ContextLengthExceeded(_)
+        ));
+    }
 }
@@ -0,0 +1,364 @@
+//! Latency-aware routing across endpoints that serve the same model (e.g. two regions).
+//!
+//! [`RoutingLlmClient`] sends each call to the healthiest endpoint: the lowest rolling latency,
+//! raised by the endpoint's rolling error rate and divided by its weight. Endpoints without a
+//! measurement yet are tried first. Health ([`RouteHealth`]) is shared by all routers in the
+//! process, keyed by endpoint name, so failures seen by one run steer the next away; errors fade
+//! with a half-life of [`ERROR_HALF_LIFE`].
+//!
+//! Within a run the router sticks to its endpoint until a call fails. A failed call is retried on
+//! the next healthiest endpoint (failover), except when the request itself is at fault (context
+//! length, content filter), the run was cancelled, or chunks of a streamed call may already have
+//! been sent. [`LlmClient::route`] reports the endpoint of the last call; the think node puts it
+//! in its `llm` timing events.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+
+use crate::error::AgentError;
+use crate::llm::{LlmClient, LlmResponse, MessageChunk, ModelInfo, ToolCallDelta};
+use crate::message::Message;
+
+/// Weight of the newest sample in the rolling latency and error rate.
+const SMOOTHING: f64 = 0.3;
+/// How much a 100% error rate multiplies an endpoint's latency when ranking.
+const ERROR_PENALTY: f64 = 20.0;
+/// Time for an endpoint's error rate to halve without new calls.
+pub const ERROR_HALF_LIFE: Duration = Duration::from_secs(60);
+
+/// One endpoint in `LOOM_LLM_ROUTES`: a `[[providers]]` name and its weight.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LlmRoute {
+    pub name: String,
+    /// Relative share; an endpoint with weight 2 is preferred until it is twice as slow.
+    pub weight: f64,
+}
+
+impl LlmRoute {
+    /// Parses `name` or `name:weight` entries (weight defaults to 1).
+    pub fn parse_all(entries: &[String]) -> Result<Vec<Self>, String> {
+        entries
+            .iter()
+            .map(|entry| {
+                let (name, weight) = match entry.split_once(':') {
+                    Some((name, weight)) => {
+                        let weight = weight
+                            .trim()
+                            .parse::<f64>()
+                            .ok()
+                            .filter(|w| w.is_finite() && *w > 0.0)
+                            .ok_or_else(|| format!("invalid weight in route `{}`", entry))?;
+                        (name, weight)
+                    }
+                    None => (entry.as_str(), 1.0),
+                };
+                let name = name.trim();
+                if name.is_empty() {
+                    return Err(format!("missing endpoint name in route `{}`", entry));
+                }
+                Ok(Self {
+                    name: name.to_string(),
+                    weight,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct EndpointStats {
+    latency_ms: Option<f64>,
+    error_rate: f64,
+    updated: Instant,
+}
+
+impl EndpointStats {
+    fn decayed_error_rate(&self, now: Instant) -> f64 {
+        let halvings =
+            now.duration_since(self.updated).as_secs_f64() / ERROR_HALF_LIFE.as_secs_f64();
+        self.error_rate * 0.5_f64.powf(halvings)
+    }
+}
+
+/// Rolling latency and error rate of each endpoint, by name.
+#[derive(Default)]
+pub struct RouteHealth {
+    stats: Mutex<HashMap<String, EndpointStats>>,
+}
+
+impl RouteHealth {
+    /// Health shared by every router in the process.
+    pub fn global() -> Arc<Self> {
+        static GLOBAL: OnceLock<Arc<RouteHealth>> = OnceLock::new();
+        Arc::clone(GLOBAL.get_or_init(Default::default))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, EndpointStats>> {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn record(&self, name: &str, latency: Option<Duration>) {
+        let now = Instant::now();
+        let mut stats = self.lock();
+        let entry = stats.entry(name.to_string()).or_insert(EndpointStats {
+            latency_ms: None,
+            error_rate: 0.0,
+            updated: now,
+        });
+        let error_rate = entry.decayed_error_rate(now);
+        match latency {
+            Some(latency) => {
+                let ms = latency.as_secs_f64() * 1000.0;
+                entry.latency_ms = Some(match entry.latency_ms {
+                    Some(prev) => SMOOTHING * ms + (1.0 - SMOOTHING) * prev,
+                    None => ms,
+                });
+                entry.error_rate = (1.0 - SMOOTHING) * error_rate;
+            }
+            None => entry.error_rate = SMOOTHING + (1.0 - SMOOTHING) * error_rate,
+        }
+        entry.updated = now;
+    }
+
+    /// Ranking cost of `route`; lower is healthier.
+    fn cost(&self, route: &LlmRoute) -> f64 {
+        let Some(stats) = self.lock().get(&route.name).copied() else {
+            return 0.0;
+        };
+        let latency = stats.latency_ms.unwrap_or(0.0) + 1.0;
+        latency * (1.0 + ERROR_PENALTY * stats.decayed_error_rate(Instant::now())) / route.weight
+    }
+}
+
+/// Whether `err` says the endpoint is unhealthy (rather than the request or the run).
+fn is_endpoint_failure(err: &AgentError) -> bool {
+    matches!(
+        err,
+        AgentError::ExecutionFailed(_)
+            | AgentError::RateLimited { .. }
+            | AgentError::AuthFailed(_)
+            | AgentError::EmptyLlmResponse { .. }
+    )
+}
+
+struct Endpoint {
+    route: LlmRoute,
+    llm: Arc<dyn LlmClient>,
+}
+
+/// LLM client routing calls across endpoints; see the [module docs](self).
+pub struct RoutingLlmClient {
+    endpoints: Vec<Endpoint>,
+    health: Arc<RouteHealth>,
+    /// Endpoint this router sticks to; `None` until the first call and after a failure.
+    current: Mutex<Option<usize>>,
+}
+
+impl RoutingLlmClient {
+    /// Router over `endpoints` using the process-wide [`RouteHealth::global`].
+    pub fn new(endpoints: Vec<(LlmRoute, Arc<dyn LlmClient>)>) -> Self {
+        Self {
+            endpoints: endpoints
+                .into_iter()
+                .map(|(route, llm)| Endpoint { route, llm })
+                .collect(),
+            health: RouteHealth::global(),
+            current: Mutex::new(None),
+        }
+    }
+
+    /// Uses `health` instead of the process-wide health (e.g. in tests).
+    pub fn with_health(mut self, health: Arc<RouteHealth>) -> Self {
+        self.health = health;
+        self
+    }
+
+    fn current(&self) -> MutexGuard<'_, Option<usize>> {
+        self.current.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The sticky endpoint unless it already failed this call, else the healthiest untried one.
+    fn pick(&self, tried: &[usize]) -> usize {
+        let mut current = self.current();
+        if let Some(i) = current.filter(|i| !tried.contains(i)) {
+            return i;
+        }
+        let best = (0..self.endpoints.len())
+            .filter(|i| !tried.contains(i))
+            .map(|i| (i, self.health.cost(&self.endpoints[i].route)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or(0, |(i, _)| i);
+        if *current != Some(best) {
+            tracing::info!(
+                endpoint = %self.endpoints[best].route.name,
+                "routing LLM calls to endpoint"
+            );
+        }
+        *current = Some(best);
+        best
+    }
+
+    /// Runs `call` on the picked endpoint, failing over to the others when `failover` is set.
+    async fn call<'a, F, Fut>(&'a self, failover: bool, call: F) -> Result<LlmResponse, AgentError>
+    where
+        F: Fn(&'a dyn LlmClient) -> Fut,
+        Fut: Future<Output = Result<LlmResponse, AgentError>>,
+    {
+        let mut tried = Vec::new();
+        loop {
+            let i = self.pick(&tried);
+            let endpoint = &self.endpoints[i];
+            let start = Instant::now();
+            let result = call(endpoint.llm.as_ref()).await;
+            let err = match result {
+                Ok(response) => {
+                    self.health
+                        .record(&endpoint.route.name, Some(start.elapsed()));
+                    return Ok(response);
+                }
+                Err(e) if is_endpoint_failure(&e) => e,
+                Err(e) => return Err(e),
+            };
+            self.health.record(&endpoint.route.name, None);
+            tried.push(i);
+            if !failover || tried.len() == self.endpoints.len() {
+                *self.current() = None;
+                return Err(err);
+            }
+            tracing::warn!(
+                endpoint = %endpoint.route.name,
+                error = %err,
+                "LLM endpoint failed, failing over"
+            );
+        }
+    }
+}
+
+#[async_trait]
+impl LlmClient for RoutingLlmClient {
+    async fn invoke(&self, messages: &[Message]) -> Result<LlmResponse, AgentError> {
+        self.call(true, |llm| llm.invoke(messages)).await
+    }
+
+    async fn invoke_stream(
+        &self,
+        messages: &[Message],
+        chunk_tx: Option<mpsc::Sender<MessageChunk>>,
+    ) -> Result<LlmResponse, AgentError> {
+        let failover = chunk_tx.is_none();
+        self.call(failover, |llm| {
+            llm.invoke_stream(messages, chunk_tx.clone())
+        })
+        .await
+    }
+
+    async fn invoke_stream_with_tool_delta(
+        &self,
+        messages: &[Message],
+        chunk_tx: Option<mpsc::Sender<MessageChunk>>,
+        tool_delta_tx: Option<mpsc::Sender<ToolCallDelta>>,
+    ) -> Result<LlmResponse, AgentError> {
+        let failover = chunk_tx.is_none() && tool_delta_tx.is_none();
+        self.call(failover, |llm| {
+            llm.invoke_stream_with_tool_delta(messages, chunk_tx.clone(), tool_delta_tx.clone())
+        })
+        .await
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, AgentError> {
+        let i = self.pick(&[]);
+        self.endpoints[i].llm.list_models().await
+    }
+
+    fn set_tools(&self, tools: Vec<crate::tool_source::ToolSpec>) {
+        for endpoint in &self.endpoints {
+            endpoint.llm.set_tools(tools.clone());
+        }
+    }
+
+    /// Every endpoint serves the same model, so the first one's support applies.
+    fn tool_support(&self) -> crate::model_spec::ToolSupport {
+        self.endpoints
+            .first()
+            .map(|endpoint| endpoint.llm.tool_support())
+            .unwrap_or_default()
+    }
+
+    fn route(&self) -> Option<String> {
+        self.current().map(|i| self.endpoints[i].route.name.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlm;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Fails every call with a provider error.
+    struct Down(AtomicUsize);
+
+    #[async_trait]
+    impl LlmClient for Down {
+        async fn invoke(&self, _messages: &[Message]) -> Result<LlmResponse, AgentError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Err(AgentError::ExecutionFailed(
+                "503 Service Unavailable".to_string(),
+            ))
+        }
+    }
+
+    fn route(name: &str, weight: f64) -> LlmRoute {
+        LlmRoute {
+            name: name.to_string(),
+            weight,
+        }
+    }
+
+    #[test]
+    fn parses_names_and_weights() {
+        let routes =
+            LlmRoute::parse_all(&["us-east:2".to_string(), " eu-west ".to_string()]).unwrap();
+        assert_eq!(routes, vec![route("us-east", 2.0), route("eu-west", 1.0)]);
+        assert!(LlmRoute::parse_all(&["us-east:0".to_string()]).is_err());
+        assert!(LlmRoute::parse_all(&[":2".to_string()]).is_err());
+    }
+
+    #[tokio::test]
+    async fn fails_over_and_sticks_to_the_healthy_endpoint() {
+        let health = Arc::new(RouteHealth::default());
+        let down = Arc::new(Down(AtomicUsize::new(0)));
+        let router = RoutingLlmClient::new(vec![
+            (route("us-east", 2.0), down.clone() as Arc<dyn LlmClient>),
+            (
+                route("eu-west", 1.0),
+                Arc::new(MockLlm::with_no_tool_calls("from eu")),
+            ),
+        ])
+        .with_health(Arc::clone(&health));
+
+        let reply = router.invoke(&[Message::user("hi")]).await.unwrap();
+        assert_eq!(reply.content, "from eu");
+        assert_eq!(router.route().as_deref(), Some("eu-west"));
+
+        router.invoke(&[Message::user("again")]).await.unwrap();
+        assert_eq!(down.0.load(Ordering::SeqCst), 1, "sticks to eu-west");
+
+        // A new run ranks the failed endpoint below the healthy one despite its weight.
+        let next_run = RoutingLlmClient::new(vec![
+            (route("us-east", 2.0), down.clone() as Arc<dyn LlmClient>),
+            (
+                route("eu-west", 1.0),
+                Arc::new(MockLlm::with_no_tool_calls("from eu")),
+            ),
+        ])
+        .with_health(health);
+        next_run.invoke(&[Message::user("hi")]).await.unwrap();
+        assert_eq!(down.0.load(Ordering::SeqCst), 1);
+    }
+}
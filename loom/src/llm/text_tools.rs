@@ -0,0 +1,265 @@
+//! Text tool-call protocol for models without function calling ([`ToolCallMode::Text`]).
+//!
+//! [`TextToolCallLlm`] keeps the tool definitions out of the request: it describes the tools in
+//! the system prompt, asks for a `<tool_call>{"name": ..., "arguments": {...}}</tool_call>`
+//! block per call, and turns the first such block of each reply into a regular [`ToolCall`].
+//! Earlier tool calls and results in the conversation are rewritten the same way, so the model
+//! only ever sees system, user and assistant text.
+//!
+//! [`ToolCallMode::Text`]: crate::model_spec::ToolCallMode::Text
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use crate::error::AgentError;
+use crate::llm::{FinishReason, LlmClient, LlmResponse, ModelInfo};
+use crate::message::Message;
+use crate::model_spec::{ToolCallMode, ToolSupport};
+use crate::state::ToolCall;
+use crate::tool_source::ToolSpec;
+
+const CALL_OPEN: &str = "<tool_call>";
+const CALL_CLOSE: &str = "</tool_call>";
+
+/// [`LlmClient`] adapter that lets a model without function calling call tools through its
+/// reply text. Replies are not streamed token by token: each is sent as one chunk once its tool
+/// call was parsed out.
+pub struct TextToolCallLlm {
+    inner: Arc<dyn LlmClient>,
+    tools: RwLock<Vec<ToolSpec>>,
+}
+
+impl TextToolCallLlm {
+    /// Wraps `inner`, clearing the tool definitions it would send with each request.
+    pub fn new(inner: Arc<dyn LlmClient>) -> Self {
+        inner.set_tools(Vec::new());
+        Self {
+            inner,
+            tools: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Sets the tools described to the model.
+    pub fn with_tools(self, tools: Vec<ToolSpec>) -> Self {
+        self.set_tools(tools);
+        self
+    }
+
+    fn tools(&self) -> Vec<ToolSpec> {
+        self.tools.read().map(|t| t.clone()).unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl LlmClient for TextToolCallLlm {
+    async fn invoke(&self, messages: &[Message]) -> Result<LlmResponse, AgentError> {
+        let prompt = text_protocol_messages(messages, &self.tools());
+        let response = self.inner.invoke(&prompt).await?;
+        Ok(parse_text_tool_call(response, messages.len()))
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, AgentError> {
+        self.inner.list_models().await
+    }
+
+    fn set_tools(&self, tools: Vec<ToolSpec>) {
+        if let Ok(mut guard) = self.tools.write() {
+            *guard = tools;
+        }
+    }
+
+    /// One tool call per reply, as native calls.
+    fn tool_support(&self) -> ToolSupport {
+        ToolSupport::new(ToolCallMode::Serial, false)
+    }
+
+    fn route(&self) -> Option<String> {
+        self.inner.route()
+    }
+}
+
+/// Instructions and tool list appended to the system prompt.
+fn tool_instructions(tools: &[ToolSpec]) -> String {
+    let mut text = format!(
+        "You can call tools. To call one, reply with a single block of this form and nothing after it, then wait for the result:\n{}\n{{\"name\": \"<tool name>\", \"arguments\": {{<arguments as JSON>}}}}\n{}\nCall one tool at a time. When you need no tool, answer normally.\n\nTools:",
+        CALL_OPEN, CALL_CLOSE
+    );
+    for tool in tools {
+        text.push_str(&format!(
+            "\n- {}: {}\n  arguments schema: {}",
+            tool.name,
+            tool.description.as_deref().unwrap_or(""),
+            tool.input_schema
+        ));
+    }
+    text
+}
+
+fn call_block(name: &str, arguments: &str) -> String {
+    let arguments: Value = serde_json::from_str(arguments).unwrap_or_else(|_| json!(arguments));
+    format!(
+        "{}\n{}\n{}",
+        CALL_OPEN,
+        json!({ "name": name, "arguments": arguments }),
+        CALL_CLOSE
+    )
+}
+
+/// `messages` as the model sees them under the text protocol: the tool instructions appended to
+/// the system prompt (or as a first system message), assistant tool calls as call blocks and
+/// tool results as user messages naming the tool.
+pub fn text_protocol_messages(messages: &[Message], tools: &[ToolSpec]) -> Vec<Message> {
+    let mut names: HashMap<&str, &str> = HashMap::new();
+    let mut out = Vec::with_capacity(messages.len() + 1);
+    for message in messages {
+        match message {
+            Message::Assistant(payload) if !payload.tool_calls.is_empty() => {
+                let mut text = payload.content.clone();
+                for call in &payload.tool_calls {
+                    names.insert(&call.id, &call.name);
+                    if !text.is_empty() {
+                        text.push('\n');
+                    }
+                    text.push_str(&call_block(&call.name, &call.arguments));
+                }
+                out.push(Message::assistant(text));
+            }
+            Message::Tool {
+                tool_call_id,
+                content,
+            } => {
+                let name = names.get(tool_call_id.as_str()).copied().unwrap_or("tool");
+                out.push(Message::user(format!(
+                    "Result of {}:\n{}",
+                    name,
+                    content.clone().into_text()
+                )));
+            }
+            other => out.push(other.clone()),
+        }
+    }
+    if tools.is_empty() {
+        return out;
+    }
+    let instructions = tool_instructions(tools);
+    match out.iter_mut().find(|m| matches!(m, Message::System(_))) {
+        Some(Message::System(prompt)) => {
+            prompt.push_str("\n\n");
+            prompt.push_str(&instructions);
+        }
+        _ => out.insert(0, Message::system(instructions)),
+    }
+    out
+}
+
+/// Moves the first call block of `response.content` into `response.tool_calls`; the text before
+/// it stays the content. Replies whose block is not a JSON object with a `name` are returned
+/// unchanged. `turn` makes the call id unique within the conversation.
+pub fn parse_text_tool_call(mut response: LlmResponse, turn: usize) -> LlmResponse {
+    let Some(start) = response.content.find(CALL_OPEN) else {
+        return response;
+    };
+    let body_start = start + CALL_OPEN.len();
+    let body_end = response.content[body_start..]
+        .find(CALL_CLOSE)
+        .map_or(response.content.len(), |i| body_start + i);
+    let Ok(call) = serde_json::from_str::<Value>(response.content[body_start..body_end].trim())
+    else {
+        return response;
+    };
+    let Some(name) = call.get("name").and_then(Value::as_str) else {
+        return response;
+    };
+    let arguments = match call.get("arguments") {
+        Some(Value::String(s)) => s.clone(),
+        Some(args) => args.to_string(),
+        None => "{}".to_string(),
+    };
+    response.tool_calls.push(ToolCall {
+        name: name.to_string(),
+        arguments,
+        id: Some(format!("text_call_{}", turn)),
+    });
+    response.content = response.content[..start].trim_end().to_string();
+    if response.finish_reason == Some(FinishReason::Stop) {
+        response.finish_reason = Some(FinishReason::ToolCalls);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::AssistantToolCall;
+    use crate::tool_source::ToolCallContent;
+
+    fn reply(content: &str) -> LlmResponse {
+        LlmResponse {
+            content: content.to_string(),
+            reasoning_content: None,
+            tool_calls: Vec::new(),
+            usage: None,
+            finish_reason: None,
+        }
+    }
+
+    #[test]
+    fn parses_first_call_block_into_a_tool_call() {
+        let response = parse_text_tool_call(
+            reply("Let me look.\n<tool_call>\n{\"name\": \"ls\", \"arguments\": {\"path\": \".\"}}\n</tool_call>"),
+            3,
+        );
+        assert_eq!(response.content, "Let me look.");
+        assert_eq!(response.tool_calls.len(), 1);
+        assert_eq!(response.tool_calls[0].name, "ls");
+        assert_eq!(response.tool_calls[0].arguments, r#"{"path":"."}"#);
+        assert_eq!(response.tool_calls[0].id.as_deref(), Some("text_call_3"));
+
+        let plain = parse_text_tool_call(reply("<tool_call>not json</tool_call>"), 0);
+        assert!(plain.tool_calls.is_empty());
+        assert_eq!(plain.content, "<tool_call>not json</tool_call>");
+    }
+
+    #[test]
+    fn rewrites_tool_history_as_text() {
+        let tools = vec![ToolSpec {
+            name: "ls".to_string(),
+            description: Some("List files".to_string()),
+            input_schema: json!({ "type": "object" }),
+            output_hint: None,
+            catalog: Default::default(),
+        }];
+        let messages = vec![
+            Message::system("Be brief."),
+            Message::user("what is here?"),
+            Message::assistant_with_tool_calls(
+                String::new(),
+                vec![AssistantToolCall {
+                    id: "c1".to_string(),
+                    name: "ls".to_string(),
+                    arguments: r#"{"path":"."}"#.to_string(),
+                }],
+            ),
+            Message::Tool {
+                tool_call_id: "c1".to_string(),
+                content: ToolCallContent::text("README.md"),
+            },
+        ];
+        let prompt = text_protocol_messages(&messages, &tools);
+        assert_eq!(prompt.len(), 4);
+        let Message::System(system) = &prompt[0] else {
+            panic!("expected system prompt, got {:?}", prompt[0]);
+        };
+        assert!(system.starts_with("Be brief."));
+        assert!(system.contains("- ls: List files"));
+        let Message::Assistant(call) = &prompt[2] else {
+            panic!("expected assistant, got {:?}", prompt[2]);
+        };
+        assert!(call.tool_calls.is_empty());
+        assert!(call.content.contains(r#""name":"ls""#));
+        assert_eq!(prompt[3], Message::user("Result of ls:\nREADME.md"));
+    }
+}
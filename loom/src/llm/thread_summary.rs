@@ -0,0 +1,132 @@
+//! Thread title + summary generation: one cheap LLM call after a run finishes.
+//!
+//! Chat UIs show the title in sidebars and the summary as a preview. The model is asked for
+//! a JSON object `{"title": ..., "summary": ...}`; plain-text replies fall back to
+//! "first line is the title, rest is the summary".
+
+use serde::{Deserialize, Serialize};
+
+use super::LlmClient;
+use crate::error::AgentError;
+use crate::message::Message;
+
+/// Max characters kept for a generated title.
+const MAX_TITLE_CHARS: usize = 80;
+/// Max characters kept for a generated summary.
+const MAX_SUMMARY_CHARS: usize = 400;
+/// Max characters of the user message / reply included in the prompt.
+const MAX_INPUT_CHARS: usize = 4000;
+
+const SYSTEM_PROMPT: &str = "You title and summarize conversations for a chat sidebar. \
+Reply with a JSON object only: {\"title\": \"<one line, at most 8 words>\", \
+\"summary\": \"<at most 2 sentences>\"}. Use the language of the conversation.";
+
+/// One-line title and short summary of a thread.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThreadSummary {
+    pub title: String,
+    pub summary: String,
+}
+
+fn clamp_chars(s: &str, max: usize) -> String {
+    let s = s.trim();
+    if s.chars().count() <= max {
+        return s.to_string();
+    }
+    let mut out: String = s.chars().take(max.saturating_sub(1)).collect();
+    out.push('…');
+    out
+}
+
+/// Parses the model output into a [`ThreadSummary`]. Accepts a JSON object (optionally inside
+/// a ```json fence) or plain text. Returns `None` when no title can be extracted.
+pub fn parse_thread_summary(content: &str) -> Option<ThreadSummary> {
+    let trimmed = content.trim();
+    let json_part = match (trimmed.find('{'), trimmed.rfind('}')) {
+        (Some(start), Some(end)) if start < end => Some(&trimmed[start..=end]),
+        _ => None,
+    };
+    if let Some(parsed) = json_part.and_then(|j| serde_json::from_str::<ThreadSummary>(j).ok()) {
+        let title = clamp_chars(parsed.title.lines().next().unwrap_or(""), MAX_TITLE_CHARS);
+        if title.is_empty() {
+            return None;
+        }
+        return Some(ThreadSummary {
+            title,
+            summary: clamp_chars(&parsed.summary, MAX_SUMMARY_CHARS),
+        });
+    }
+
+    let mut lines = trimmed.lines().map(str::trim).filter(|l| !l.is_empty());
+    let title = lines
+        .next()?
+        .trim_start_matches('#')
+        .trim_matches('"')
+        .trim();
+    if title.is_empty() {
+        return None;
+    }
+    let summary = lines.collect::<Vec<_>>().join(" ");
+    Some(ThreadSummary {
+        title: clamp_chars(title, MAX_TITLE_CHARS),
+        summary: clamp_chars(&summary, MAX_SUMMARY_CHARS),
+    })
+}
+
+/// Asks `llm` for a title and 2-sentence summary of one exchange (`user_message` → `reply`).
+///
+/// Returns [`AgentError::ExecutionFailed`] when the model output has no usable title.
+pub async fn generate_thread_summary(
+    llm: &dyn LlmClient,
+    user_message: &str,
+    reply: &str,
+) -> Result<ThreadSummary, AgentError> {
+    let prompt = format!(
+        "User:\n{}\n\nAssistant:\n{}",
+        clamp_chars(user_message, MAX_INPUT_CHARS),
+        clamp_chars(reply, MAX_INPUT_CHARS)
+    );
+    let messages = vec![Message::system(SYSTEM_PROMPT), Message::user(prompt)];
+    let response = llm.invoke(&messages).await?;
+    parse_thread_summary(&response.content).ok_or_else(|| {
+        AgentError::ExecutionFailed("thread summary: empty model output".to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlm;
+
+    #[test]
+    fn parse_thread_summary_accepts_fenced_json_and_plain_text() {
+        let fenced =
+            "```json\n{\"title\": \"Rust lifetimes\", \"summary\": \"Explained borrows.\"}\n```";
+        assert_eq!(
+            parse_thread_summary(fenced),
+            Some(ThreadSummary {
+                title: "Rust lifetimes".to_string(),
+                summary: "Explained borrows.".to_string(),
+            })
+        );
+
+        let plain = "# Weather in Paris\nThe user asked for the forecast.\nIt will rain.";
+        let parsed = parse_thread_summary(plain).unwrap();
+        assert_eq!(parsed.title, "Weather in Paris");
+        assert_eq!(
+            parsed.summary,
+            "The user asked for the forecast. It will rain."
+        );
+
+        assert_eq!(parse_thread_summary("   "), None);
+    }
+
+    #[tokio::test]
+    async fn generate_thread_summary_uses_llm_reply() {
+        let llm =
+            MockLlm::with_no_tool_calls(r#"{"title":"Greeting","summary":"The user said hi."}"#);
+        let summary = generate_thread_summary(&llm, "hi", "hello!").await.unwrap();
+        assert_eq!(summary.title, "Greeting");
+        assert_eq!(summary.summary, "The user said hi.");
+    }
+}
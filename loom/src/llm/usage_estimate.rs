@@ -0,0 +1,72 @@
+//! Usage estimate for a streamed response whose provider reported no usage (e.g. an
+//! OpenAI-compatible endpoint that ignores `stream_options.include_usage`), so usage totals and
+//! the context guard still account for the call. Counts with the model's tokenizer when one is
+//! known (see [`tokenizer_for`]), else with the heuristic.
+
+use crate::compress::context_window::estimate_tokens_with;
+use crate::message::Message;
+use crate::model_spec::{tokenizer_for, HeuristicTokenizer, Tokenizer};
+
+use super::{LlmResponse, LlmUsage};
+
+/// Estimated usage of a call to `model` with `messages` that produced `response`.
+pub(crate) fn estimate_usage(
+    model: &str,
+    messages: &[Message],
+    response: &LlmResponse,
+) -> LlmUsage {
+    let tokenizer = tokenizer_for(model, None);
+    let counter: &dyn Tokenizer = tokenizer.as_deref().unwrap_or(&HeuristicTokenizer);
+    let prompt_tokens = estimate_tokens_with(messages, tokenizer.as_deref());
+    let completion_tokens = counter.count(&response.content)
+        + response
+            .reasoning_content
+            .as_deref()
+            .map_or(0, |r| counter.count(r))
+        + response
+            .tool_calls
+            .iter()
+            .map(|tc| counter.count(&tc.name) + counter.count(&tc.arguments))
+            .sum::<usize>();
+    let completion_tokens = completion_tokens as u32;
+    tracing::debug!(
+        model,
+        tokenizer = counter.name(),
+        prompt_tokens,
+        completion_tokens,
+        "provider reported no usage; estimated"
+    );
+    LlmUsage {
+        prompt_tokens,
+        completion_tokens,
+        total_tokens: prompt_tokens + completion_tokens,
+        prompt_tokens_details: None,
+        completion_tokens_details: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::ToolCall;
+
+    #[test]
+    fn estimates_prompt_and_completion_tokens() {
+        let messages = vec![Message::user("x".repeat(400))];
+        let response = LlmResponse {
+            content: "y".repeat(40),
+            reasoning_content: None,
+            tool_calls: vec![ToolCall {
+                name: "read".to_string(),
+                arguments: "z".repeat(36),
+                id: None,
+            }],
+            usage: None,
+            finish_reason: None,
+        };
+        let usage = estimate_usage("acme/local-model", &messages, &response);
+        assert_eq!(usage.prompt_tokens, 100);
+        assert_eq!(usage.completion_tokens, 10 + 1 + 9);
+        assert_eq!(usage.total_tokens, 120);
+    }
+}
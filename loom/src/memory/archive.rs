@@ -0,0 +1,75 @@
+//! Portable thread archives: every checkpoint of one thread, for moving a conversation between
+//! checkpointer backends or machines.
+//!
+//! Produced by [`Checkpointer::export_thread`](crate::memory::Checkpointer::export_thread) and
+//! consumed by [`Checkpointer::import_thread`](crate::memory::Checkpointer::import_thread). The
+//! archive is plain serde data, so it round-trips through JSON regardless of the backend that
+//! wrote it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::memory::checkpoint::Checkpoint;
+use crate::memory::checkpointer::CheckpointError;
+
+/// Value of [`ThreadArchive::format`]; used to reject unrelated JSON files on import.
+pub const THREAD_ARCHIVE_FORMAT: &str = "loom.thread_archive";
+
+/// Current [`ThreadArchive::version`]. Bumped when the archive layout changes incompatibly.
+pub const THREAD_ARCHIVE_VERSION: u32 = 1;
+
+/// All checkpoints of one thread (and namespace), oldest first, with their metadata.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadArchive<S> {
+    /// Always [`THREAD_ARCHIVE_FORMAT`].
+    pub format: String,
+    /// Archive layout version ([`THREAD_ARCHIVE_VERSION`] when written by this build).
+    pub version: u32,
+    /// Thread the checkpoints were exported from.
+    pub thread_id: String,
+    /// Checkpoint namespace the checkpoints were exported from (empty for the root graph).
+    #[serde(default)]
+    pub checkpoint_ns: String,
+    /// Export time in milliseconds since the Unix epoch.
+    pub exported_at_ms: u64,
+    /// Checkpoints in history order, oldest first. Each carries its own metadata.
+    pub checkpoints: Vec<Checkpoint<S>>,
+}
+
+impl<S> ThreadArchive<S> {
+    /// Creates an archive stamped with the current format, version and time.
+    pub fn new(
+        thread_id: impl Into<String>,
+        checkpoint_ns: impl Into<String>,
+        checkpoints: Vec<Checkpoint<S>>,
+    ) -> Self {
+        let exported_at_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        Self {
+            format: THREAD_ARCHIVE_FORMAT.to_string(),
+            version: THREAD_ARCHIVE_VERSION,
+            thread_id: thread_id.into(),
+            checkpoint_ns: checkpoint_ns.into(),
+            exported_at_ms,
+            checkpoints,
+        }
+    }
+
+    /// Checks that the archive was written in a format this build can import.
+    pub fn validate(&self) -> Result<(), CheckpointError> {
+        if self.format != THREAD_ARCHIVE_FORMAT {
+            return Err(CheckpointError::Serialization(format!(
+                "not a thread archive (format {:?})",
+                self.format
+            )));
+        }
+        if self.version > THREAD_ARCHIVE_VERSION {
+            return Err(CheckpointError::Serialization(format!(
+                "thread archive version {} is newer than supported version {}",
+                self.version, THREAD_ARCHIVE_VERSION
+            )));
+        }
+        Ok(())
+    }
+}
@@ -66,6 +66,10 @@ pub struct CheckpointMetadata {
     /// Generated after the first think in ReAct loop.
     #[serde(default)]
     pub summary: Option<String>,
+    /// Graph node that ran last before this checkpoint was written (e.g. the node that
+    /// interrupted), when the runtime records it.
+    #[serde(default)]
+    pub node: Option<String>,
 }
 
 /// Why a checkpoint was created.
@@ -103,6 +107,7 @@ mod tests {
             parents: HashMap::new(),
             children: HashMap::new(),
             summary: None,
+            node: None,
         };
     }
 
@@ -242,6 +247,50 @@ mod tests {
         assert!(metadata.parents.is_empty());
         assert!(metadata.children.is_empty());
     }
+
+    /// **Scenario**: CheckpointFilter matches on node, source, step range and creation time.
+    #[test]
+    fn checkpoint_filter_matches_set_conditions_only() {
+        let t0 = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000);
+        let item = CheckpointListItem {
+            checkpoint_id: "c2".to_string(),
+            metadata: CheckpointMetadata {
+                source: CheckpointSource::Update,
+                step: 3,
+                created_at: Some(t0),
+                node: Some("act".to_string()),
+                ..Default::default()
+            },
+            parent_id: Some("c1".to_string()),
+            size_bytes: Some(42),
+        };
+        assert!(CheckpointFilter::default().matches(&item));
+        let filter = CheckpointFilter {
+            node: Some("act".to_string()),
+            source: Some(CheckpointSource::Update),
+            min_step: Some(3),
+            max_step: Some(3),
+            since: Some(t0),
+            ..Default::default()
+        };
+        assert!(filter.matches(&item));
+        let by_node = CheckpointFilter {
+            node: Some("think".to_string()),
+            ..Default::default()
+        };
+        assert!(!by_node.matches(&item));
+        let by_step = CheckpointFilter {
+            min_step: Some(4),
+            ..Default::default()
+        };
+        assert!(!by_step.matches(&item));
+        let by_time = CheckpointFilter {
+            until: Some(t0),
+            ..Default::default()
+        };
+        assert!(!by_time.matches(&item));
+        assert_eq!(item.created_at_ms(), Some(1_000_000));
+    }
 }
 
 fn default_checkpoint_version() -> u32 {
@@ -299,6 +348,80 @@ pub struct CheckpointListItem {
     pub checkpoint_id: String,
     /// Metadata associated with the checkpoint.
     pub metadata: CheckpointMetadata,
+    /// Id of the checkpoint written before this one in the same lineage (`None` for the first).
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    /// Size of the serialized state in bytes, when the backend stores it serialized.
+    #[serde(default)]
+    pub size_bytes: Option<u64>,
+}
+
+impl CheckpointListItem {
+    /// Creation time in milliseconds since the Unix epoch, when recorded.
+    pub fn created_at_ms(&self) -> Option<i64> {
+        self.metadata
+            .created_at
+            .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .map(|d| d.as_millis() as i64)
+    }
+}
+
+/// Sets each item's `parent_id` to the id of the item before it (items oldest first).
+pub(crate) fn link_parents(items: &mut [CheckpointListItem]) {
+    let mut previous: Option<String> = None;
+    for item in items.iter_mut() {
+        item.parent_id = previous.replace(item.checkpoint_id.clone());
+    }
+}
+
+/// Filter for [`crate::memory::Checkpointer::list_filtered`]. Unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct CheckpointFilter {
+    /// Only checkpoints written after this node ran.
+    pub node: Option<String>,
+    /// Only checkpoints with this source.
+    pub source: Option<CheckpointSource>,
+    /// Only checkpoints with `step >= min_step`.
+    pub min_step: Option<i64>,
+    /// Only checkpoints with `step <= max_step`.
+    pub max_step: Option<i64>,
+    /// Only checkpoints created at or after this time.
+    pub since: Option<SystemTime>,
+    /// Only checkpoints created before this time.
+    pub until: Option<SystemTime>,
+    /// Keep only the newest `limit` matches.
+    pub limit: Option<usize>,
+}
+
+impl CheckpointFilter {
+    /// Whether `item` passes every set condition (`limit` is applied by the caller).
+    pub fn matches(&self, item: &CheckpointListItem) -> bool {
+        let meta = &item.metadata;
+        if let Some(node) = &self.node {
+            if meta.node.as_deref() != Some(node.as_str()) {
+                return false;
+            }
+        }
+        if self.source.as_ref().is_some_and(|s| *s != meta.source) {
+            return false;
+        }
+        if self.min_step.is_some_and(|min| meta.step < min)
+            || self.max_step.is_some_and(|max| meta.step > max)
+        {
+            return false;
+        }
+        if self.since.is_some() || self.until.is_some() {
+            let Some(created_at) = meta.created_at else {
+                return false;
+            };
+            if self.since.is_some_and(|since| created_at < since)
+                || self.until.is_some_and(|until| created_at >= until)
+            {
+                return false;
+            }
+        }
+        true
+    }
 }
 
 /// Expanded checkpoint record returned by [`crate::memory::Checkpointer::get_tuple`].
@@ -348,6 +471,7 @@ impl<S> Checkpoint<S> {
                 parents: HashMap::new(),
                 children: HashMap::new(),
                 summary: None,
+                node: None,
             },
         }
     }
@@ -381,6 +505,7 @@ impl<S> Checkpoint<S> {
                 parents: HashMap::new(),
                 children: HashMap::new(),
                 summary: None,
+                node: None,
             },
         }
     }
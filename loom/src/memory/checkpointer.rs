@@ -6,7 +6,10 @@
 
 use async_trait::async_trait;
 
-use crate::memory::checkpoint::{Checkpoint, CheckpointListItem, CheckpointMetadata};
+use crate::memory::archive::ThreadArchive;
+use crate::memory::checkpoint::{
+    Checkpoint, CheckpointFilter, CheckpointListItem, CheckpointMetadata,
+};
 use crate::memory::config::RunnableConfig;
 
 /// Error type for checkpoint operations.
@@ -20,6 +23,8 @@ pub enum CheckpointError {
     Storage(String),
     #[error("not found: {0}")]
     NotFound(String),
+    #[error("state migration: {0}")]
+    Migration(String),
 }
 
 /// Persists and retrieves checkpoints for one state type.
@@ -63,11 +68,86 @@ where
         before: Option<&str>,
         after: Option<&str>,
     ) -> Result<Vec<CheckpointListItem>, CheckpointError>;
+
+    /// Lists the checkpoints of the selected lineage that match `filter`, oldest first; with
+    /// `filter.limit`, only the newest matches are kept.
+    ///
+    /// The default implementation filters the full [`Self::list`] in memory, which is enough
+    /// for debugging a thread's history.
+    async fn list_filtered(
+        &self,
+        config: &RunnableConfig,
+        filter: &CheckpointFilter,
+    ) -> Result<Vec<CheckpointListItem>, CheckpointError> {
+        let mut items: Vec<CheckpointListItem> = self
+            .list(config, None, None, None)
+            .await?
+            .into_iter()
+            .filter(|item| filter.matches(item))
+            .collect();
+        if let Some(n) = filter.limit {
+            let skip = items.len().saturating_sub(n);
+            items.drain(..skip);
+        }
+        Ok(items)
+    }
+
+    /// Exports every checkpoint of the selected thread and namespace into a portable archive.
+    ///
+    /// `config.checkpoint_id` is ignored. Checkpoints are loaded one by one via
+    /// [`Self::list`] and [`Self::get_tuple`], so any backend gets this for free.
+    async fn export_thread(
+        &self,
+        config: &RunnableConfig,
+    ) -> Result<ThreadArchive<S>, CheckpointError> {
+        let thread_id = config
+            .thread_id
+            .clone()
+            .ok_or(CheckpointError::ThreadIdRequired)?;
+        let items = self.list(config, None, None, None).await?;
+        let mut checkpoints = Vec::with_capacity(items.len());
+        for item in items {
+            let mut cp_config = config.clone();
+            cp_config.checkpoint_id = Some(item.checkpoint_id.clone());
+            let (mut checkpoint, metadata) = self
+                .get_tuple(&cp_config)
+                .await?
+                .ok_or_else(|| CheckpointError::NotFound(item.checkpoint_id.clone()))?;
+            checkpoint.metadata = metadata;
+            checkpoints.push(checkpoint);
+        }
+        Ok(ThreadArchive::new(
+            thread_id,
+            config.checkpoint_ns.clone(),
+            checkpoints,
+        ))
+    }
+
+    /// Writes every checkpoint of `archive` into the thread selected by `config`, oldest first,
+    /// keeping checkpoint ids and metadata. `config.thread_id` may differ from the archive's
+    /// thread to import under a new id. Returns the number of checkpoints written.
+    async fn import_thread(
+        &self,
+        config: &RunnableConfig,
+        archive: &ThreadArchive<S>,
+    ) -> Result<usize, CheckpointError> {
+        archive.validate()?;
+        if config.thread_id.is_none() {
+            return Err(CheckpointError::ThreadIdRequired);
+        }
+        let mut cp_config = config.clone();
+        cp_config.checkpoint_id = None;
+        for checkpoint in &archive.checkpoints {
+            self.put(&cp_config, checkpoint).await?;
+        }
+        Ok(archive.checkpoints.len())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::memory::{CheckpointSource, MemorySaver};
 
     /// **Scenario**: Display of each CheckpointError variant contains expected keywords.
     #[test]
@@ -89,4 +169,119 @@ mod tests {
             .to_lowercase()
             .contains("not found"));
     }
+
+    /// **Scenario**: A thread exported from one saver and imported into another under a new id
+    /// keeps its checkpoint ids, order, state and metadata.
+    #[tokio::test]
+    async fn export_then_import_thread_round_trips_history() {
+        let source = MemorySaver::<i32>::new();
+        let config = RunnableConfig {
+            thread_id: Some("laptop".into()),
+            ..Default::default()
+        };
+        for (step, value) in [(-1, 1), (0, 2), (1, 3)] {
+            let cp = Checkpoint::from_state(value, CheckpointSource::Loop, step);
+            source.put(&config, &cp).await.unwrap();
+        }
+
+        let archive = source.export_thread(&config).await.unwrap();
+        assert_eq!(archive.thread_id, "laptop");
+        assert_eq!(archive.checkpoints.len(), 3);
+        let json = serde_json::to_string(&archive).unwrap();
+        let archive: ThreadArchive<i32> = serde_json::from_str(&json).unwrap();
+
+        let target = MemorySaver::<i32>::new();
+        let target_config = RunnableConfig {
+            thread_id: Some("server".into()),
+            ..Default::default()
+        };
+        let written = target
+            .import_thread(&target_config, &archive)
+            .await
+            .unwrap();
+        assert_eq!(written, 3);
+
+        let source_ids: Vec<String> = source
+            .list(&config, None, None, None)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|i| i.checkpoint_id)
+            .collect();
+        let target_ids: Vec<String> = target
+            .list(&target_config, None, None, None)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|i| i.checkpoint_id)
+            .collect();
+        assert_eq!(source_ids, target_ids);
+
+        let (latest, metadata) = target.get_tuple(&target_config).await.unwrap().unwrap();
+        assert_eq!(latest.channel_values, 3);
+        assert_eq!(metadata.step, 1);
+    }
+
+    /// **Scenario**: list_filtered keeps matching checkpoints, links parents and applies the
+    /// limit to the newest matches.
+    #[tokio::test]
+    async fn list_filtered_by_node_and_step() {
+        let saver = MemorySaver::<i32>::new();
+        let config = RunnableConfig {
+            thread_id: Some("t".into()),
+            ..Default::default()
+        };
+        for (step, node) in [(0, "think"), (1, "act"), (2, "think"), (3, "act")] {
+            let mut cp = Checkpoint::from_state(step as i32, CheckpointSource::Loop, step);
+            cp.metadata.node = Some(node.to_string());
+            saver.put(&config, &cp).await.unwrap();
+        }
+        let all = saver.list(&config, None, None, None).await.unwrap();
+        assert_eq!(all[0].parent_id, None);
+        assert_eq!(
+            all[1].parent_id.as_deref(),
+            Some(all[0].checkpoint_id.as_str())
+        );
+
+        let acts = saver
+            .list_filtered(
+                &config,
+                &CheckpointFilter {
+                    node: Some("act".into()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let steps: Vec<i64> = acts.iter().map(|i| i.metadata.step).collect();
+        assert_eq!(steps, vec![1, 3]);
+
+        let newest = saver
+            .list_filtered(
+                &config,
+                &CheckpointFilter {
+                    max_step: Some(2),
+                    limit: Some(2),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let steps: Vec<i64> = newest.iter().map(|i| i.metadata.step).collect();
+        assert_eq!(steps, vec![1, 2]);
+    }
+
+    /// **Scenario**: Importing a JSON file that is not a thread archive fails before writing.
+    #[tokio::test]
+    async fn import_thread_rejects_unknown_format() {
+        let mut archive = ThreadArchive::<i32>::new("t", "", vec![]);
+        archive.format = "something_else".into();
+        let saver = MemorySaver::<i32>::new();
+        let config = RunnableConfig {
+            thread_id: Some("t".into()),
+            ..Default::default()
+        };
+        let err = saver.import_thread(&config, &archive).await.unwrap_err();
+        assert!(matches!(err, CheckpointError::Serialization(_)));
+    }
 }
@@ -34,6 +34,44 @@ pub struct RunnableConfig {
     /// Resume values keyed by interrupt id.
     #[serde(default)]
     pub resume_values_by_interrupt_id: std::collections::HashMap<String, serde_json::Value>,
+    /// Seed for weighted edge splits; mixed with `thread_id` so a thread keeps its branch.
+    /// `None` falls back to `LOOM_ROUTING_SEED`, then to the thread id alone.
+    #[serde(default)]
+    pub routing_seed: Option<u64>,
+    /// Per-run feature flags (e.g. `"verify_node"`), readable by nodes, middleware and tools.
+    /// A flag that is not set reads as off.
+    #[serde(default)]
+    pub flags: std::collections::HashMap<String, bool>,
+}
+
+impl RunnableConfig {
+    /// Returns whether feature flag `name` is on for this run; unset flags are off.
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.get(name).copied().unwrap_or(false)
+    }
+}
+
+/// Parses one feature flag spec: `name` (on) or `name=<bool>`, where the value is
+/// `1`/`true`/`yes`/`on` or `0`/`false`/`no`/`off`. Used by `LOOM_FLAGS` and CLI `--flag`.
+pub fn parse_run_flag(spec: &str) -> Result<(String, bool), String> {
+    let (name, value) = match spec.split_once('=') {
+        Some((name, value)) => (name.trim(), Some(value.trim())),
+        None => (spec.trim(), None),
+    };
+    if name.is_empty() {
+        return Err(format!("empty flag name in '{}'", spec));
+    }
+    let on = match value.map(str::to_lowercase).as_deref() {
+        None | Some("1" | "true" | "yes" | "on") => true,
+        Some("0" | "false" | "no" | "off") => false,
+        Some(other) => {
+            return Err(format!(
+                "flag '{}': expected true or false, got '{}'",
+                name, other
+            ))
+        }
+    };
+    Ok((name.to_string(), on))
 }
 
 #[cfg(test)]
@@ -64,6 +102,8 @@ mod tests {
             resume_value: None,
             resume_values_by_namespace: Default::default(),
             resume_values_by_interrupt_id: Default::default(),
+            routing_seed: None,
+            flags: Default::default(),
         };
         let c2 = c.clone();
         assert_eq!(c.thread_id, c2.thread_id);
@@ -73,4 +113,27 @@ mod tests {
         assert_eq!(c.resume_from_node_id, c2.resume_from_node_id);
         assert_eq!(c.resume_value, c2.resume_value);
     }
+
+    /// **Scenario**: flag() reads set flags and treats unset ones as off.
+    #[test]
+    fn runnable_config_flag_defaults_to_off() {
+        let mut c = RunnableConfig::default();
+        c.flags.insert("verify_node".into(), true);
+        c.flags.insert("new_compaction".into(), false);
+        assert!(c.flag("verify_node"));
+        assert!(!c.flag("new_compaction"));
+        assert!(!c.flag("missing"));
+    }
+
+    #[test]
+    fn parse_run_flag_accepts_bare_names_and_bools() {
+        assert_eq!(
+            parse_run_flag("verify_node"),
+            Ok(("verify_node".into(), true))
+        );
+        assert_eq!(parse_run_flag(" a = off "), Ok(("a".into(), false)));
+        assert_eq!(parse_run_flag("a=YES"), Ok(("a".into(), true)));
+        assert!(parse_run_flag("a=maybe").is_err());
+        assert!(parse_run_flag("=true").is_err());
+    }
 }
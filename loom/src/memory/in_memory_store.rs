@@ -2,10 +2,11 @@
 //!
 //! Semantic search uses in-memory vector store (see 16-memory-design §5.2.1).
 //! This implementation does key/list and optional query filter only.
+//! Items stored with a TTL are hidden once expired and dropped by `purge_expired`.
 
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 use async_trait::async_trait;
 use tokio::sync::RwLock;
@@ -23,10 +24,16 @@ struct StoredItem {
     key: String,
     created_at: SystemTime,
     updated_at: SystemTime,
+    expires_at: Option<SystemTime>,
 }
 
 impl StoredItem {
-    fn new(namespace: Namespace, key: String, value: serde_json::Value) -> Self {
+    fn new(
+        namespace: Namespace,
+        key: String,
+        value: serde_json::Value,
+        expires_at: Option<SystemTime>,
+    ) -> Self {
         let now = SystemTime::now();
         Self {
             value,
@@ -34,12 +41,18 @@ impl StoredItem {
             key,
             created_at: now,
             updated_at: now,
+            expires_at,
         }
     }
 
-    fn update(&mut self, value: serde_json::Value) {
+    fn update(&mut self, value: serde_json::Value, expires_at: Option<SystemTime>) {
         self.value = value;
         self.updated_at = SystemTime::now();
+        self.expires_at = expires_at;
+    }
+
+    fn is_live(&self, now: SystemTime) -> bool {
+        self.expires_at.is_none_or(|t| t > now)
     }
 
     fn to_item(&self) -> Item {
@@ -50,6 +63,7 @@ impl StoredItem {
             self.created_at,
             self.updated_at,
         )
+        .with_expires_at(self.expires_at)
     }
 }
 
@@ -84,6 +98,28 @@ impl InMemoryStore {
         }
     }
 
+    /// Inserts or updates one entry in an already locked map.
+    fn upsert(
+        map: &mut HashMap<String, StoredItem>,
+        namespace: &Namespace,
+        key: &str,
+        value: &serde_json::Value,
+        expires_at: Option<SystemTime>,
+    ) {
+        let k = map_key(namespace, key);
+        if let Some(existing) = map.get_mut(&k) {
+            existing.update(value.clone(), expires_at);
+        } else {
+            let item = StoredItem::new(
+                namespace.clone(),
+                key.to_string(),
+                value.clone(),
+                expires_at,
+            );
+            map.insert(k, item);
+        }
+    }
+
     fn namespace_prefix(namespace: &Namespace) -> String {
         if namespace.is_empty() {
             String::new()
@@ -138,15 +174,7 @@ impl Store for InMemoryStore {
         key: &str,
         value: &serde_json::Value,
     ) -> Result<(), StoreError> {
-        let k = map_key(namespace, key);
-        let mut guard = self.inner.write().await;
-        if let Some(existing) = guard.get_mut(&k) {
-            existing.update(value.clone());
-        } else {
-            let item = StoredItem::new(namespace.clone(), key.to_string(), value.clone());
-            guard.insert(k, item);
-        }
-        Ok(())
+        self.put_with_ttl(namespace, key, value, None).await
     }
 
     async fn get(
@@ -155,12 +183,26 @@ impl Store for InMemoryStore {
         key: &str,
     ) -> Result<Option<serde_json::Value>, StoreError> {
         let k = map_key(namespace, key);
-        Ok(self.inner.read().await.get(&k).map(|s| s.value.clone()))
+        let now = SystemTime::now();
+        Ok(self
+            .inner
+            .read()
+            .await
+            .get(&k)
+            .filter(|s| s.is_live(now))
+            .map(|s| s.value.clone()))
     }
 
     async fn get_item(&self, namespace: &Namespace, key: &str) -> Result<Option<Item>, StoreError> {
         let k = map_key(namespace, key);
-        Ok(self.inner.read().await.get(&k).map(|s| s.to_item()))
+        let now = SystemTime::now();
+        Ok(self
+            .inner
+            .read()
+            .await
+            .get(&k)
+            .filter(|s| s.is_live(now))
+            .map(|s| s.to_item()))
     }
 
     async fn delete(&self, namespace: &Namespace, key: &str) -> Result<(), StoreError> {
@@ -171,10 +213,11 @@ impl Store for InMemoryStore {
 
     async fn list(&self, namespace: &Namespace) -> Result<Vec<String>, StoreError> {
         let prefix = Self::namespace_prefix(namespace);
+        let now = SystemTime::now();
         let guard = self.inner.read().await;
         let mut keys: Vec<String> = guard
             .iter()
-            .filter(|(k, _)| k.starts_with(&prefix))
+            .filter(|(k, item)| k.starts_with(&prefix) && item.is_live(now))
             .map(|(_, item)| item.key.clone())
             .collect();
         keys.sort();
@@ -188,11 +231,12 @@ impl Store for InMemoryStore {
         options: SearchOptions,
     ) -> Result<Vec<SearchItem>, StoreError> {
         let prefix = Self::namespace_prefix(namespace_prefix);
+        let now = SystemTime::now();
         let guard = self.inner.read().await;
 
         let mut hits: Vec<SearchItem> = guard
             .iter()
-            .filter(|(k, _)| k.starts_with(&prefix))
+            .filter(|(k, stored)| k.starts_with(&prefix) && stored.is_live(now))
             .map(|(_, stored)| SearchItem::from_item(stored.to_item()))
             .collect();
 
@@ -261,11 +305,15 @@ impl Store for InMemoryStore {
         &self,
         options: ListNamespacesOptions,
     ) -> Result<Vec<Namespace>, StoreError> {
+        let now = SystemTime::now();
         let guard = self.inner.read().await;
 
         // Collect unique namespaces
-        let mut namespaces: HashSet<Namespace> =
-            guard.values().map(|item| item.namespace.clone()).collect();
+        let mut namespaces: HashSet<Namespace> = guard
+            .values()
+            .filter(|item| item.is_live(now))
+            .map(|item| item.namespace.clone())
+            .collect();
 
         // Apply match conditions
         if !options.match_conditions.is_empty() {
@@ -350,6 +398,47 @@ impl Store for InMemoryStore {
         Ok(results)
     }
 
+    async fn put_with_ttl(
+        &self,
+        namespace: &Namespace,
+        key: &str,
+        value: &serde_json::Value,
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        let expires_at = ttl.map(|d| SystemTime::now() + d);
+        let mut guard = self.inner.write().await;
+        Self::upsert(&mut guard, namespace, key, value, expires_at);
+        Ok(())
+    }
+
+    async fn purge_expired(&self) -> Result<usize, StoreError> {
+        let now = SystemTime::now();
+        let mut guard = self.inner.write().await;
+        let before = guard.len();
+        guard.retain(|_, item| item.is_live(now));
+        Ok(before - guard.len())
+    }
+
+    async fn put_many(
+        &self,
+        namespace: &Namespace,
+        items: Vec<(String, serde_json::Value)>,
+    ) -> Result<(), StoreError> {
+        let mut guard = self.inner.write().await;
+        for (key, value) in &items {
+            Self::upsert(&mut guard, namespace, key, value, None);
+        }
+        Ok(())
+    }
+
+    async fn delete_many(&self, namespace: &Namespace, keys: &[String]) -> Result<(), StoreError> {
+        let mut guard = self.inner.write().await;
+        for key in keys {
+            guard.remove(&map_key(namespace, key));
+        }
+        Ok(())
+    }
+
     async fn search_simple(
         &self,
         namespace: &Namespace,
@@ -1069,4 +1158,97 @@ mod tests {
         let item = store.get_item(&ns, "nonexistent").await.unwrap();
         assert!(item.is_none());
     }
+
+    /// **Scenario**: An expired item is hidden from reads and listings, then purged.
+    #[tokio::test]
+    async fn expired_item_is_hidden_and_purged() {
+        let store = InMemoryStore::new();
+        let ns: Namespace = vec!["u1".into(), "memories".into()];
+
+        store
+            .put_with_ttl(&ns, "short", &json!("gone soon"), Some(Duration::ZERO))
+            .await
+            .unwrap();
+        store
+            .put_with_ttl(
+                &ns,
+                "long",
+                &json!("stays"),
+                Some(Duration::from_secs(3600)),
+            )
+            .await
+            .unwrap();
+
+        assert!(store.get(&ns, "short").await.unwrap().is_none());
+        assert!(store.get_item(&ns, "short").await.unwrap().is_none());
+        assert_eq!(store.list(&ns).await.unwrap(), vec!["long".to_string()]);
+        let item = store.get_item(&ns, "long").await.unwrap().unwrap();
+        assert!(item.expires_at.is_some());
+
+        assert_eq!(store.purge_expired().await.unwrap(), 1);
+        assert_eq!(store.purge_expired().await.unwrap(), 0);
+    }
+
+    /// **Scenario**: A plain put over a TTL item clears its expiry.
+    #[tokio::test]
+    async fn put_clears_previous_ttl() {
+        let store = InMemoryStore::new();
+        let ns: Namespace = vec!["test".into()];
+
+        store
+            .put_with_ttl(&ns, "k", &json!(1), Some(Duration::from_secs(60)))
+            .await
+            .unwrap();
+        store.put(&ns, "k", &json!(2)).await.unwrap();
+
+        let item = store.get_item(&ns, "k").await.unwrap().unwrap();
+        assert_eq!(item.value, json!(2));
+        assert!(item.expires_at.is_none());
+    }
+
+    /// **Scenario**: Namespaces whose items have all expired are not listed.
+    #[tokio::test]
+    async fn list_namespaces_skips_expired_only_namespaces() {
+        let store = InMemoryStore::new();
+        store
+            .put_with_ttl(&vec!["a".into()], "k", &json!(1), Some(Duration::ZERO))
+            .await
+            .unwrap();
+        store.put(&vec!["b".into()], "k", &json!(1)).await.unwrap();
+
+        let namespaces = store
+            .list_namespaces(ListNamespacesOptions::new())
+            .await
+            .unwrap();
+        assert_eq!(namespaces, vec![vec!["b".to_string()]]);
+    }
+
+    /// **Scenario**: put_many and delete_many write and remove several keys at once.
+    #[tokio::test]
+    async fn put_many_and_delete_many() {
+        let store = InMemoryStore::new();
+        let ns: Namespace = vec!["test".into()];
+
+        store
+            .put_many(
+                &ns,
+                vec![
+                    ("a".to_string(), json!(1)),
+                    ("b".to_string(), json!(2)),
+                    ("c".to_string(), json!(3)),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(store.list(&ns).await.unwrap().len(), 3);
+
+        store
+            .delete_many(
+                &ns,
+                &["a".to_string(), "c".to_string(), "missing".to_string()],
+            )
+            .await
+            .unwrap();
+        assert_eq!(store.list(&ns).await.unwrap(), vec!["b".to_string()]);
+    }
 }
@@ -8,7 +8,7 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use tokio::sync::RwLock;
 
-use crate::memory::checkpoint::{Checkpoint, CheckpointListItem, CheckpointMetadata};
+use crate::memory::checkpoint::{link_parents, Checkpoint, CheckpointListItem, CheckpointMetadata};
 use crate::memory::checkpointer::{CheckpointError, Checkpointer};
 use crate::memory::config::RunnableConfig;
 
@@ -125,8 +125,11 @@ where
             .map(|(id, cp)| CheckpointListItem {
                 checkpoint_id: id.clone(),
                 metadata: cp.metadata.clone(),
+                parent_id: None,
+                size_bytes: None,
             })
             .collect();
+        link_parents(&mut items);
         if let Some(a) = after {
             if let Some(pos) = items.iter().position(|i| i.checkpoint_id.as_str() == a) {
                 items = items[pos + 1..].to_vec();
@@ -181,6 +184,7 @@ mod tests {
                 parents: HashMap::new(),
                 children: HashMap::new(),
                 summary: None,
+                node: None,
             },
         };
         saver.put(&config, &checkpoint).await.unwrap();
@@ -0,0 +1,196 @@
+//! Versioned checkpoint state: schema version tag plus registered migrations.
+//!
+//! [`VersionedJsonSerializer`] writes the state as JSON with its schema version stored under
+//! [`STATE_VERSION_KEY`]. On load it runs the migrations registered for the state type (see
+//! [`VersionedState::migrations`]) from the stored version up to the current one before
+//! decoding, so a schema change does not make existing threads fail to deserialize. Checkpoints
+//! written without a version tag (before versioning) count as version 1.
+
+use std::collections::BTreeMap;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::memory::checkpointer::CheckpointError;
+use crate::memory::serializer::Serializer;
+
+/// JSON key holding the schema version of a serialized state object.
+pub const STATE_VERSION_KEY: &str = "__state_version";
+
+/// Upgrades a serialized state from version `n` to `n + 1`.
+pub type MigrationFn = fn(Value) -> Result<Value, String>;
+
+/// Migrations for one state type, keyed by the version they upgrade from.
+#[derive(Clone, Default)]
+pub struct StateMigrations {
+    steps: BTreeMap<u32, MigrationFn>,
+}
+
+impl StateMigrations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the migration from `from_version` to `from_version + 1`.
+    pub fn register(mut self, from_version: u32, migration: MigrationFn) -> Self {
+        self.steps.insert(from_version, migration);
+        self
+    }
+
+    /// Runs every migration from `from` up to `to`. Fails when a step is missing.
+    pub fn migrate(&self, mut value: Value, from: u32, to: u32) -> Result<Value, CheckpointError> {
+        for version in from..to {
+            let step = self.steps.get(&version).ok_or_else(|| {
+                CheckpointError::Migration(format!("no migration from state version {}", version))
+            })?;
+            value = step(value).map_err(|e| {
+                CheckpointError::Migration(format!("state version {}: {}", version, e))
+            })?;
+        }
+        Ok(value)
+    }
+}
+
+/// A checkpoint state type with a schema version.
+///
+/// Bump [`Self::STATE_VERSION`] and register a migration from the previous version whenever a
+/// change to the type would not decode older checkpoints correctly (renamed or retyped fields,
+/// fields whose serde default differs from the intended default).
+pub trait VersionedState {
+    /// Schema version written with new checkpoints.
+    const STATE_VERSION: u32;
+
+    /// Migrations from older schema versions.
+    fn migrations() -> StateMigrations {
+        StateMigrations::new()
+    }
+}
+
+/// JSON serializer that tags states with their schema version and migrates older ones on load.
+pub struct VersionedJsonSerializer;
+
+impl<S> Serializer<S> for VersionedJsonSerializer
+where
+    S: Clone + Send + Sync + 'static + Serialize + DeserializeOwned + VersionedState,
+{
+    fn serialize(&self, state: &S) -> Result<Vec<u8>, CheckpointError> {
+        let mut value = serde_json::to_value(state)
+            .map_err(|e| CheckpointError::Serialization(e.to_string()))?;
+        if let Value::Object(map) = &mut value {
+            map.insert(STATE_VERSION_KEY.to_string(), S::STATE_VERSION.into());
+        }
+        serde_json::to_vec(&value).map_err(|e| CheckpointError::Serialization(e.to_string()))
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<S, CheckpointError> {
+        let mut value: Value = serde_json::from_slice(bytes)
+            .map_err(|e| CheckpointError::Serialization(e.to_string()))?;
+        let version = take_state_version(&mut value)?;
+        if version > S::STATE_VERSION {
+            return Err(CheckpointError::Migration(format!(
+                "state version {} is newer than supported version {}",
+                version,
+                S::STATE_VERSION
+            )));
+        }
+        let value = S::migrations().migrate(value, version, S::STATE_VERSION)?;
+        serde_json::from_value(value).map_err(|e| CheckpointError::Serialization(e.to_string()))
+    }
+}
+
+/// Removes and returns the version tag of a serialized state; untagged states are version 1.
+fn take_state_version(value: &mut Value) -> Result<u32, CheckpointError> {
+    let Value::Object(map) = value else {
+        return Ok(1);
+    };
+    match map.remove(STATE_VERSION_KEY) {
+        None => Ok(1),
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| CheckpointError::Migration(format!("invalid state version: {}", v))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, serde::Deserialize)]
+    struct Note {
+        title: String,
+        #[serde(default)]
+        pinned: bool,
+    }
+
+    impl VersionedState for Note {
+        const STATE_VERSION: u32 = 3;
+
+        fn migrations() -> StateMigrations {
+            StateMigrations::new()
+                .register(1, |mut v| {
+                    let name = v["name"].take();
+                    v["title"] = name;
+                    Ok(v)
+                })
+                .register(2, |mut v| {
+                    v["pinned"] = json!(true);
+                    Ok(v)
+                })
+        }
+    }
+
+    fn tagged(mut value: Value, version: u32) -> Vec<u8> {
+        value[STATE_VERSION_KEY] = json!(version);
+        serde_json::to_vec(&value).unwrap()
+    }
+
+    fn note() -> Note {
+        Note {
+            title: "todo".into(),
+            pinned: false,
+        }
+    }
+
+    #[test]
+    fn roundtrip_tags_current_version() {
+        let bytes = Serializer::<Note>::serialize(&VersionedJsonSerializer, &note()).unwrap();
+        let raw: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(raw[STATE_VERSION_KEY], json!(3));
+        let back: Note = VersionedJsonSerializer.deserialize(&bytes).unwrap();
+        assert_eq!(back, note());
+    }
+
+    #[test]
+    fn untagged_state_runs_all_migrations() {
+        let bytes = serde_json::to_vec(&json!({"name": "todo"})).unwrap();
+        let note: Note = VersionedJsonSerializer.deserialize(&bytes).unwrap();
+        assert_eq!(note.title, "todo");
+        assert!(note.pinned);
+    }
+
+    #[test]
+    fn migrations_start_at_the_stored_version() {
+        let bytes = tagged(json!({"title": "todo"}), 2);
+        let note: Note = VersionedJsonSerializer.deserialize(&bytes).unwrap();
+        assert!(note.pinned);
+    }
+
+    #[test]
+    fn newer_state_version_is_rejected() {
+        let bytes = tagged(json!({"title": "todo"}), 4);
+        let err = Serializer::<Note>::deserialize(&VersionedJsonSerializer, &bytes).unwrap_err();
+        assert!(matches!(err, CheckpointError::Migration(_)));
+    }
+
+    #[test]
+    fn missing_migration_step_is_an_error() {
+        let migrations = StateMigrations::new().register(1, Ok);
+        let err = migrations.migrate(json!({}), 1, 3).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("no migration from state version 2"));
+    }
+}
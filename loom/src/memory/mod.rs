@@ -32,8 +32,13 @@
 //! | [`MemorySaver`]  | In-memory   | Dev, tests                  | —        |
 //! | [`SqliteSaver`]  | SQLite file | Single-node, production     | — |
 //!
+//! [`Checkpointer::export_thread`] / [`Checkpointer::import_thread`] move a thread's full history
+//! between backends as a [`ThreadArchive`] (plain serde data, e.g. a JSON file).
+//!
 //! Use with [`StateGraph::compile_with_checkpointer`](crate::graph::StateGraph::compile_with_checkpointer).
 //! [`JsonSerializer`] is required for `SqliteSaver` (state must be `Serialize + DeserializeOwned`).
+//! [`VersionedJsonSerializer`] additionally tags states with their schema version and runs the
+//! [`StateMigrations`] of a [`VersionedState`] when loading older checkpoints.
 //!
 //! ## Store Implementations
 //!
@@ -48,6 +53,7 @@
 //! `SqliteVecStore`, `LanceStore`, and `InMemoryVectorStore` require an
 //! [`Embedder`] for vector indexing; search with `query` uses semantic similarity.
 
+mod archive;
 mod checkpoint;
 mod checkpointer;
 mod config;
@@ -55,6 +61,7 @@ mod embedder;
 mod in_memory_store;
 mod in_memory_vector_store;
 mod memory_saver;
+mod migration;
 mod openai_embedder;
 mod serializer;
 mod store;
@@ -67,15 +74,19 @@ mod sqlite_store;
 pub(crate) mod sqlite_util;
 mod sqlite_vec_store;
 
+pub use archive::{ThreadArchive, THREAD_ARCHIVE_FORMAT, THREAD_ARCHIVE_VERSION};
 pub use checkpoint::{
-    writes_idx_map, ChannelVersions, Checkpoint, CheckpointListItem, CheckpointMetadata,
-    CheckpointSource, CheckpointTuple, PendingWrite, CHECKPOINT_VERSION, ERROR, INTERRUPT, RESUME,
-    SCHEDULED,
+    writes_idx_map, ChannelVersions, Checkpoint, CheckpointFilter, CheckpointListItem,
+    CheckpointMetadata, CheckpointSource, CheckpointTuple, PendingWrite, CHECKPOINT_VERSION, ERROR,
+    INTERRUPT, RESUME, SCHEDULED,
 };
 pub use checkpointer::{CheckpointError, Checkpointer};
-pub use config::RunnableConfig;
+pub use config::{parse_run_flag, RunnableConfig};
 pub use in_memory_store::InMemoryStore;
 pub use memory_saver::MemorySaver;
+pub use migration::{
+    MigrationFn, StateMigrations, VersionedJsonSerializer, VersionedState, STATE_VERSION_KEY,
+};
 pub use serializer::{
     JsonSerializer, Serializer, TypedData, TypedSerializer, TYPE_BYTES, TYPE_JSON, TYPE_NULL,
 };
@@ -92,6 +103,7 @@ pub use lance_store::LanceStore;
 pub use openai_embedder::OpenAIEmbedder;
 pub use sqlite_saver::SqliteSaver;
 pub use sqlite_store::SqliteStore;
+pub use sqlite_util::DB_KEY_ENV;
 pub use sqlite_vec_store::SqliteVecStore;
 
 /// Returns the default SQLite memory database path.
@@ -9,8 +9,8 @@ use async_trait::async_trait;
 use rusqlite::params;
 
 use crate::memory::checkpoint::{
-    ChannelVersions, Checkpoint, CheckpointListItem, CheckpointMetadata, CheckpointSource,
-    CHECKPOINT_VERSION,
+    link_parents, ChannelVersions, Checkpoint, CheckpointListItem, CheckpointMetadata,
+    CheckpointSource, CHECKPOINT_VERSION,
 };
 use crate::memory::checkpointer::{CheckpointError, Checkpointer};
 use crate::memory::config::RunnableConfig;
@@ -151,6 +151,11 @@ fn ensure_checkpoint_runtime_columns(conn: &rusqlite::Connection) -> Result<(),
         .map_err(|e| CheckpointError::Storage(e.to_string()))?;
     }
 
+    if !columns.iter().any(|column| column == "metadata_node") {
+        conn.execute("ALTER TABLE checkpoints ADD COLUMN metadata_node TEXT", [])
+            .map_err(|e| CheckpointError::Storage(e.to_string()))?;
+    }
+
     Ok(())
 }
 
@@ -192,6 +197,7 @@ where
                 metadata_parents TEXT NOT NULL DEFAULT '{}',
                 metadata_children TEXT NOT NULL DEFAULT '{}',
                 metadata_summary TEXT,
+                metadata_node TEXT,
                 updated_channels TEXT NOT NULL DEFAULT '[]',
                 pending_sends TEXT NOT NULL DEFAULT '[]',
                 pending_writes TEXT NOT NULL DEFAULT '[]',
@@ -240,6 +246,7 @@ where
         let metadata_parents = serialize_parents(&checkpoint.metadata.parents)?;
         let metadata_children = serialize_children(&checkpoint.metadata.children)?;
         let metadata_summary = checkpoint.metadata.summary.clone();
+        let metadata_node = checkpoint.metadata.node.clone();
         let updated_channels = serialize_json_field(&checkpoint.updated_channels)?;
         let pending_sends = serialize_json_field(&checkpoint.pending_sends)?;
         let pending_writes = serialize_json_field(&checkpoint.pending_writes)?;
@@ -256,8 +263,9 @@ where
                 INSERT OR REPLACE INTO checkpoints
                 (thread_id, checkpoint_ns, checkpoint_id, ts, payload, channel_versions, versions_seen,
                  metadata_source, metadata_step, metadata_created_at, metadata_parents, metadata_children,
-                 metadata_summary, updated_channels, pending_sends, pending_writes, pending_interrupts)
-                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
+                 metadata_summary, metadata_node, updated_channels, pending_sends, pending_writes,
+                 pending_interrupts)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)
                 "#,
                 params![
                     thread_id,
@@ -273,6 +281,7 @@ where
                     metadata_parents,
                     metadata_children,
                     metadata_summary,
+                    metadata_node,
                     updated_channels,
                     pending_sends,
                     pending_writes,
@@ -307,6 +316,7 @@ where
             String,
             String,
             Option<String>, // metadata_summary
+            Option<String>, // metadata_node
             String,
             String,
             String,
@@ -317,11 +327,11 @@ where
                 .map_err(CheckpointError::Storage)?;
             let sql = if want_id.is_some() {
                 "SELECT checkpoint_id, ts, payload, channel_versions, versions_seen, metadata_source, metadata_step, metadata_created_at, metadata_parents, metadata_children, metadata_summary,
-                        updated_channels, pending_sends, pending_writes, pending_interrupts
+                        metadata_node, updated_channels, pending_sends, pending_writes, pending_interrupts
                  FROM checkpoints WHERE thread_id = ?1 AND checkpoint_ns = ?2 AND checkpoint_id = ?3"
             } else {
                 "SELECT checkpoint_id, ts, payload, channel_versions, versions_seen, metadata_source, metadata_step, metadata_created_at, metadata_parents, metadata_children, metadata_summary,
-                        updated_channels, pending_sends, pending_writes, pending_interrupts
+                        metadata_node, updated_channels, pending_sends, pending_writes, pending_interrupts
                  FROM checkpoints WHERE thread_id = ?1 AND checkpoint_ns = ?2
                  ORDER BY metadata_created_at DESC LIMIT 1"
             };
@@ -347,10 +357,11 @@ where
             let metadata_parents: String = row.get(8).map_err(|e| CheckpointError::Storage(e.to_string()))?;
             let metadata_children: String = row.get(9).map_err(|e| CheckpointError::Storage(e.to_string()))?;
             let metadata_summary: Option<String> = row.get(10).map_err(|e| CheckpointError::Storage(e.to_string()))?;
-            let updated_channels: String = row.get(11).map_err(|e| CheckpointError::Storage(e.to_string()))?;
-            let pending_sends: String = row.get(12).map_err(|e| CheckpointError::Storage(e.to_string()))?;
-            let pending_writes: String = row.get(13).map_err(|e| CheckpointError::Storage(e.to_string()))?;
-            let pending_interrupts: String = row.get(14).map_err(|e| CheckpointError::Storage(e.to_string()))?;
+            let metadata_node: Option<String> = row.get(11).map_err(|e| CheckpointError::Storage(e.to_string()))?;
+            let updated_channels: String = row.get(12).map_err(|e| CheckpointError::Storage(e.to_string()))?;
+            let pending_sends: String = row.get(13).map_err(|e| CheckpointError::Storage(e.to_string()))?;
+            let pending_writes: String = row.get(14).map_err(|e| CheckpointError::Storage(e.to_string()))?;
+            let pending_interrupts: String = row.get(15).map_err(|e| CheckpointError::Storage(e.to_string()))?;
             Ok(Some((
                 checkpoint_id,
                 ts,
@@ -363,6 +374,7 @@ where
                 metadata_parents,
                 metadata_children,
                 metadata_summary,
+                metadata_node,
                 updated_channels,
                 pending_sends,
                 pending_writes,
@@ -384,6 +396,7 @@ where
             metadata_parents,
             metadata_children,
             metadata_summary,
+            metadata_node,
             updated_channels_json,
             pending_sends_json,
             pending_writes_json,
@@ -405,6 +418,7 @@ where
             parents: deserialize_parents(&metadata_parents)?,
             children: deserialize_children(&metadata_children)?,
             summary: metadata_summary,
+            node: metadata_node,
         };
         let checkpoint = Checkpoint {
             v: CHECKPOINT_VERSION,
@@ -440,7 +454,8 @@ where
                 .map_err(CheckpointError::Storage)?;
             let mut stmt = conn
                 .prepare(
-                    "SELECT checkpoint_id, metadata_source, metadata_step, metadata_created_at, metadata_parents, metadata_children, metadata_summary
+                    "SELECT checkpoint_id, metadata_source, metadata_step, metadata_created_at, metadata_parents, metadata_children, metadata_summary,
+                            metadata_node, length(payload)
                      FROM checkpoints WHERE thread_id = ?1 AND checkpoint_ns = ?2
                      ORDER BY metadata_created_at ASC",
                 )
@@ -458,13 +473,17 @@ where
                             children: serde_json::from_str::<HashMap<String, Vec<String>>>(&row.get::<_, String>(5)?)
                                 .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?,
                             summary: row.get(6)?,
+                            node: row.get(7)?,
                         },
+                        parent_id: None,
+                        size_bytes: row.get::<_, Option<i64>>(8)?.map(|n| n as u64),
                     })
                 })
                 .map_err(|e| CheckpointError::Storage(e.to_string()))?;
             let mut list: Vec<CheckpointListItem> = rows
                 .collect::<Result<Vec<_>, _>>()
                 .map_err(|e| CheckpointError::Storage(e.to_string()))?;
+            link_parents(&mut list);
             if let Some(a) = &after {
                 if let Some(pos) = list.iter().position(|i| i.checkpoint_id.as_str() == a) {
                     list = list[pos + 1..].to_vec();
@@ -493,6 +512,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::memory::checkpoint::CheckpointFilter;
     use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
     #[test]
@@ -611,6 +631,7 @@ mod tests {
                 .into_iter()
                 .collect(),
                 summary: None,
+                node: Some("act".to_string()),
             },
         };
 
@@ -638,6 +659,7 @@ mod tests {
         assert_eq!(ck.pending_interrupts.len(), 1);
         assert!(matches!(meta.source, CheckpointSource::Input));
         assert_eq!(meta.step, 1);
+        assert_eq!(meta.node.as_deref(), Some("act"));
         assert_eq!(meta.parents.get("parent").map(String::as_str), Some("cp-0"));
         assert_eq!(
             meta.children
@@ -679,6 +701,7 @@ mod tests {
                     parents: HashMap::new(),
                     children: HashMap::new(),
                     summary: None,
+                    node: None,
                 },
             };
             saver.put(&config, &checkpoint).await.unwrap();
@@ -687,6 +710,23 @@ mod tests {
         let items = saver.list(&config, None, None, None).await.unwrap();
         assert_eq!(items.len(), 3);
         assert!(items.iter().all(|item| item.metadata.children.is_empty()));
+        assert_eq!(items[0].parent_id, None);
+        assert_eq!(items[2].parent_id.as_deref(), Some("ck-1"));
+        assert!(items.iter().all(|item| item.size_bytes.unwrap_or(0) > 0));
+
+        let filtered = saver
+            .list_filtered(
+                &config,
+                &CheckpointFilter {
+                    min_step: Some(1),
+                    limit: Some(1),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].checkpoint_id, "ck-2");
 
         let limited = saver.list(&config, Some(2), None, None).await.unwrap();
         assert_eq!(limited.len(), 2);
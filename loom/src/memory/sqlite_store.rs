@@ -1,10 +1,12 @@
 //! SQLite-backed Store (SqliteStore). Persistent across process restarts.
 //!
 //! Aligns with 16-memory-design §5.2.2. put/get/list; search is key/value filter (no semantic index).
+//! Rows may carry an `expires_at` (millis); expired rows are filtered out of every read and
+//! deleted by `purge_expired`.
 
 use std::collections::HashSet;
 use std::path::Path;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use async_trait::async_trait;
 use rusqlite::params;
@@ -32,6 +34,57 @@ fn system_time_to_millis(time: SystemTime) -> i64 {
         .unwrap_or(0)
 }
 
+/// SQL predicate keeping rows that have not expired. Positional parameter `now_param` must be
+/// bound to the current time in millis.
+fn live_clause(now_param: usize) -> String {
+    format!("(expires_at IS NULL OR expires_at > ?{})", now_param)
+}
+
+/// Adds the `expires_at` column to tables created before TTL support.
+fn ensure_expires_at_column(conn: &rusqlite::Connection) -> Result<(), StoreError> {
+    let mut stmt = conn
+        .prepare("PRAGMA table_info(store_kv)")
+        .map_err(|e| StoreError::Storage(e.to_string()))?;
+    let rows = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| StoreError::Storage(e.to_string()))?;
+    let columns = rows
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| StoreError::Storage(e.to_string()))?;
+
+    if !columns.iter().any(|column| column == "expires_at") {
+        conn.execute("ALTER TABLE store_kv ADD COLUMN expires_at INTEGER", [])
+            .map_err(|e| StoreError::Storage(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Upserts one row on an open connection, preserving `created_at` of an existing row.
+fn upsert_row(
+    conn: &rusqlite::Connection,
+    ns: &str,
+    key: &str,
+    value_str: &str,
+    now: i64,
+    expires_at: Option<i64>,
+) -> Result<(), StoreError> {
+    let existing_created: Option<i64> = conn
+        .query_row(
+            "SELECT created_at FROM store_kv WHERE ns = ?1 AND key = ?2",
+            params![ns, key],
+            |row| row.get(0),
+        )
+        .ok();
+    let created_at = existing_created.unwrap_or(now);
+
+    conn.execute(
+        "INSERT OR REPLACE INTO store_kv (ns, key, value, created_at, updated_at, expires_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![ns, key, value_str, created_at, now, expires_at],
+    )
+    .map_err(|e| StoreError::Storage(e.to_string()))?;
+    Ok(())
+}
+
 /// SQLite-backed Store. Key: (namespace, key). Value stored as JSON text.
 ///
 /// Persistent; for single-node and dev. Uses spawn_blocking for async.
@@ -55,12 +108,14 @@ impl SqliteStore {
                 value TEXT NOT NULL,
                 created_at INTEGER NOT NULL DEFAULT 0,
                 updated_at INTEGER NOT NULL DEFAULT 0,
+                expires_at INTEGER,
                 PRIMARY KEY (ns, key)
             )
             "#,
             [],
         )
         .map_err(|e| StoreError::Storage(e.to_string()))?;
+        ensure_expires_at_column(&conn)?;
         Ok(Self { db_path })
     }
 
@@ -104,34 +159,7 @@ impl Store for SqliteStore {
         key: &str,
         value: &serde_json::Value,
     ) -> Result<(), StoreError> {
-        let ns = ns_to_key(namespace);
-        let key = key.to_string();
-        let value_str = serde_json::to_string(value)?;
-        let db_path = self.db_path.clone();
-        let now = system_time_to_millis(SystemTime::now());
-
-        tokio::task::spawn_blocking(move || {
-            let conn = crate::memory::sqlite_util::open_sqlite_with_wal(&db_path)
-                .map_err(|e| StoreError::Storage(e.to_string()))?;
-
-            // Check if exists to preserve created_at
-            let mut stmt = conn
-                .prepare("SELECT created_at FROM store_kv WHERE ns = ?1 AND key = ?2")
-                .map_err(|e| StoreError::Storage(e.to_string()))?;
-            let existing_created: Option<i64> = stmt
-                .query_row(params![ns, key], |row| row.get(0))
-                .ok();
-            let created_at = existing_created.unwrap_or(now);
-
-            conn.execute(
-                "INSERT OR REPLACE INTO store_kv (ns, key, value, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)",
-                params![ns, key, value_str, created_at, now],
-            )
-            .map_err(|e| StoreError::Storage(e.to_string()))?;
-            Ok::<(), StoreError>(())
-        })
-        .await
-        .map_err(|e| StoreError::Storage(e.to_string()))?
+        self.put_with_ttl(namespace, key, value, None).await
     }
 
     async fn get(
@@ -142,15 +170,19 @@ impl Store for SqliteStore {
         let ns = ns_to_key(namespace);
         let key = key.to_string();
         let db_path = self.db_path.clone();
+        let now = system_time_to_millis(SystemTime::now());
 
         let value_str_opt = tokio::task::spawn_blocking(move || {
             let conn = crate::memory::sqlite_util::open_sqlite_with_wal(&db_path)
                 .map_err(|e| StoreError::Storage(e.to_string()))?;
             let mut stmt = conn
-                .prepare("SELECT value FROM store_kv WHERE ns = ?1 AND key = ?2")
+                .prepare(&format!(
+                    "SELECT value FROM store_kv WHERE ns = ?1 AND key = ?2 AND {}",
+                    live_clause(3)
+                ))
                 .map_err(|e| StoreError::Storage(e.to_string()))?;
             let mut rows = stmt
-                .query(params![ns, key])
+                .query(params![ns, key, now])
                 .map_err(|e| StoreError::Storage(e.to_string()))?;
             let row = match rows
                 .next()
@@ -178,17 +210,19 @@ impl Store for SqliteStore {
         let ns_clone = namespace.clone();
         let key = key.to_string();
         let db_path = self.db_path.clone();
+        let now = system_time_to_millis(SystemTime::now());
 
         let result = tokio::task::spawn_blocking(move || {
             let conn = crate::memory::sqlite_util::open_sqlite_with_wal(&db_path)
                 .map_err(|e| StoreError::Storage(e.to_string()))?;
             let mut stmt = conn
-                .prepare(
-                    "SELECT value, created_at, updated_at FROM store_kv WHERE ns = ?1 AND key = ?2",
-                )
+                .prepare(&format!(
+                    "SELECT value, created_at, updated_at, expires_at FROM store_kv WHERE ns = ?1 AND key = ?2 AND {}",
+                    live_clause(3)
+                ))
                 .map_err(|e| StoreError::Storage(e.to_string()))?;
             let mut rows = stmt
-                .query(params![ns_str, key])
+                .query(params![ns_str, key, now])
                 .map_err(|e| StoreError::Storage(e.to_string()))?;
             let row = match rows
                 .next()
@@ -200,15 +234,20 @@ impl Store for SqliteStore {
             let value_str: String = row.get(0).map_err(|e| StoreError::Storage(e.to_string()))?;
             let created_at: i64 = row.get(1).map_err(|e| StoreError::Storage(e.to_string()))?;
             let updated_at: i64 = row.get(2).map_err(|e| StoreError::Storage(e.to_string()))?;
+            let expires_at: Option<i64> =
+                row.get(3).map_err(|e| StoreError::Storage(e.to_string()))?;
             let value: serde_json::Value = serde_json::from_str(&value_str)?;
 
-            Ok(Some(Item::with_timestamps(
-                ns_clone,
-                key,
-                value,
-                millis_to_system_time(created_at),
-                millis_to_system_time(updated_at),
-            )))
+            Ok(Some(
+                Item::with_timestamps(
+                    ns_clone,
+                    key,
+                    value,
+                    millis_to_system_time(created_at),
+                    millis_to_system_time(updated_at),
+                )
+                .with_expires_at(expires_at.map(millis_to_system_time)),
+            ))
         })
         .await
         .map_err(|e| StoreError::Storage(e.to_string()))??;
@@ -238,15 +277,19 @@ impl Store for SqliteStore {
     async fn list(&self, namespace: &Namespace) -> Result<Vec<String>, StoreError> {
         let ns = ns_to_key(namespace);
         let db_path = self.db_path.clone();
+        let now = system_time_to_millis(SystemTime::now());
 
         let keys = tokio::task::spawn_blocking(move || {
             let conn = crate::memory::sqlite_util::open_sqlite_with_wal(&db_path)
                 .map_err(|e| StoreError::Storage(e.to_string()))?;
             let mut stmt = conn
-                .prepare("SELECT key FROM store_kv WHERE ns = ?1 ORDER BY key")
+                .prepare(&format!(
+                    "SELECT key FROM store_kv WHERE ns = ?1 AND {} ORDER BY key",
+                    live_clause(2)
+                ))
                 .map_err(|e| StoreError::Storage(e.to_string()))?;
             let rows = stmt
-                .query_map(params![ns], |row| row.get(0))
+                .query_map(params![ns, now], |row| row.get(0))
                 .map_err(|e| StoreError::Storage(e.to_string()))?;
             let keys: Vec<String> = rows
                 .collect::<Result<Vec<_>, _>>()
@@ -267,6 +310,7 @@ impl Store for SqliteStore {
         let ns_prefix = ns_to_key(namespace_prefix);
         let query = options.query.clone();
         let db_path = self.db_path.clone();
+        let now = system_time_to_millis(SystemTime::now());
 
         let mut hits = tokio::task::spawn_blocking(move || {
             let conn = crate::memory::sqlite_util::open_sqlite_with_wal(&db_path)
@@ -274,25 +318,27 @@ impl Store for SqliteStore {
             // For prefix matching, we use LIKE with the JSON-serialized namespace prefix
             // This is a simplified approach; in production you might use a more sophisticated method
             let mut stmt = conn
-                .prepare(
-                    "SELECT ns, key, value, created_at, updated_at FROM store_kv WHERE ns LIKE ?1",
-                )
+                .prepare(&format!(
+                    "SELECT ns, key, value, created_at, updated_at, expires_at FROM store_kv WHERE ns LIKE ?1 AND {}",
+                    live_clause(2)
+                ))
                 .map_err(|e| StoreError::Storage(e.to_string()))?;
             let like_pattern = format!("{}%", ns_prefix.trim_end_matches(']'));
             let rows = stmt
-                .query_map(params![like_pattern], |row| {
+                .query_map(params![like_pattern, now], |row| {
                     Ok((
                         row.get::<_, String>(0)?,
                         row.get::<_, String>(1)?,
                         row.get::<_, String>(2)?,
                         row.get::<_, i64>(3)?,
                         row.get::<_, i64>(4)?,
+                        row.get::<_, Option<i64>>(5)?,
                     ))
                 })
                 .map_err(|e| StoreError::Storage(e.to_string()))?;
             let mut hits: Vec<SearchItem> = Vec::new();
             for row in rows {
-                let (ns_str, key, value_str, created_at, updated_at) =
+                let (ns_str, key, value_str, created_at, updated_at, expires_at) =
                     row.map_err(|e| StoreError::Storage(e.to_string()))?;
                 let value: serde_json::Value = serde_json::from_str(&value_str)?;
                 let namespace = key_to_ns(&ns_str);
@@ -302,7 +348,8 @@ impl Store for SqliteStore {
                     value,
                     millis_to_system_time(created_at),
                     millis_to_system_time(updated_at),
-                );
+                )
+                .with_expires_at(expires_at.map(millis_to_system_time));
                 hits.push(SearchItem::from_item(item));
             }
             Ok::<Vec<SearchItem>, StoreError>(hits)
@@ -339,15 +386,19 @@ impl Store for SqliteStore {
         options: ListNamespacesOptions,
     ) -> Result<Vec<Namespace>, StoreError> {
         let db_path = self.db_path.clone();
+        let now = system_time_to_millis(SystemTime::now());
 
         let all_ns = tokio::task::spawn_blocking(move || {
             let conn = crate::memory::sqlite_util::open_sqlite_with_wal(&db_path)
                 .map_err(|e| StoreError::Storage(e.to_string()))?;
             let mut stmt = conn
-                .prepare("SELECT DISTINCT ns FROM store_kv")
+                .prepare(&format!(
+                    "SELECT DISTINCT ns FROM store_kv WHERE {}",
+                    live_clause(1)
+                ))
                 .map_err(|e| StoreError::Storage(e.to_string()))?;
             let rows = stmt
-                .query_map([], |row| row.get::<_, String>(0))
+                .query_map(params![now], |row| row.get::<_, String>(0))
                 .map_err(|e| StoreError::Storage(e.to_string()))?;
             let namespaces: Vec<Namespace> = rows
                 .filter_map(|r| r.ok())
@@ -442,6 +493,99 @@ impl Store for SqliteStore {
         Ok(results)
     }
 
+    async fn put_with_ttl(
+        &self,
+        namespace: &Namespace,
+        key: &str,
+        value: &serde_json::Value,
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        let ns = ns_to_key(namespace);
+        let key = key.to_string();
+        let value_str = serde_json::to_string(value)?;
+        let db_path = self.db_path.clone();
+        let now_time = SystemTime::now();
+        let now = system_time_to_millis(now_time);
+        let expires_at = ttl.map(|d| system_time_to_millis(now_time + d));
+
+        tokio::task::spawn_blocking(move || {
+            let conn = crate::memory::sqlite_util::open_sqlite_with_wal(&db_path)
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            upsert_row(&conn, &ns, &key, &value_str, now, expires_at)
+        })
+        .await
+        .map_err(|e| StoreError::Storage(e.to_string()))?
+    }
+
+    async fn purge_expired(&self) -> Result<usize, StoreError> {
+        let db_path = self.db_path.clone();
+        let now = system_time_to_millis(SystemTime::now());
+
+        tokio::task::spawn_blocking(move || {
+            let conn = crate::memory::sqlite_util::open_sqlite_with_wal(&db_path)
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            conn.execute(
+                "DELETE FROM store_kv WHERE expires_at IS NOT NULL AND expires_at <= ?1",
+                params![now],
+            )
+            .map_err(|e| StoreError::Storage(e.to_string()))
+        })
+        .await
+        .map_err(|e| StoreError::Storage(e.to_string()))?
+    }
+
+    async fn put_many(
+        &self,
+        namespace: &Namespace,
+        items: Vec<(String, serde_json::Value)>,
+    ) -> Result<(), StoreError> {
+        let ns = ns_to_key(namespace);
+        let rows = items
+            .into_iter()
+            .map(|(key, value)| Ok((key, serde_json::to_string(&value)?)))
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        let db_path = self.db_path.clone();
+        let now = system_time_to_millis(SystemTime::now());
+
+        tokio::task::spawn_blocking(move || {
+            let mut conn = crate::memory::sqlite_util::open_sqlite_with_wal(&db_path)
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            let tx = conn
+                .transaction()
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            for (key, value_str) in &rows {
+                upsert_row(&tx, &ns, key, value_str, now, None)?;
+            }
+            tx.commit().map_err(|e| StoreError::Storage(e.to_string()))
+        })
+        .await
+        .map_err(|e| StoreError::Storage(e.to_string()))?
+    }
+
+    async fn delete_many(&self, namespace: &Namespace, keys: &[String]) -> Result<(), StoreError> {
+        let ns = ns_to_key(namespace);
+        let keys = keys.to_vec();
+        let db_path = self.db_path.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let mut conn = crate::memory::sqlite_util::open_sqlite_with_wal(&db_path)
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            let tx = conn
+                .transaction()
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            for key in &keys {
+                tx.execute(
+                    "DELETE FROM store_kv WHERE ns = ?1 AND key = ?2",
+                    params![ns, key],
+                )
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            }
+            tx.commit().map_err(|e| StoreError::Storage(e.to_string()))
+        })
+        .await
+        .map_err(|e| StoreError::Storage(e.to_string()))?
+    }
+
     async fn search_simple(
         &self,
         namespace: &Namespace,
@@ -672,4 +816,81 @@ mod tests {
         assert!(second.updated_at >= first.updated_at);
         assert_eq!(second.value, json!({"v":2}));
     }
+
+    #[tokio::test]
+    async fn ttl_rows_are_hidden_once_expired_and_purged() {
+        let (store, _dir) = temp_store();
+        let ns = vec!["u".to_string(), "mem".to_string()];
+        store
+            .put_with_ttl(&ns, "short", &json!(1), Some(Duration::ZERO))
+            .await
+            .unwrap();
+        store
+            .put_with_ttl(&ns, "long", &json!(2), Some(Duration::from_secs(3600)))
+            .await
+            .unwrap();
+        store.put(&ns, "forever", &json!(3)).await.unwrap();
+
+        assert!(store.get(&ns, "short").await.unwrap().is_none());
+        assert_eq!(
+            store.list(&ns).await.unwrap(),
+            vec!["forever".to_string(), "long".to_string()]
+        );
+        let hits = store
+            .search(&vec!["u".to_string()], SearchOptions::new())
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 2);
+        let long = store.get_item(&ns, "long").await.unwrap().unwrap();
+        assert!(long.expires_at.is_some());
+        let forever = store.get_item(&ns, "forever").await.unwrap().unwrap();
+        assert!(forever.expires_at.is_none());
+
+        assert_eq!(store.purge_expired().await.unwrap(), 1);
+        assert_eq!(store.purge_expired().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn new_adds_expires_at_to_existing_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("store.db");
+        {
+            let conn = rusqlite::Connection::open(&db).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE store_kv (ns TEXT NOT NULL, key TEXT NOT NULL, value TEXT NOT NULL, \
+                 created_at INTEGER NOT NULL DEFAULT 0, updated_at INTEGER NOT NULL DEFAULT 0, \
+                 PRIMARY KEY (ns, key));
+                 INSERT INTO store_kv (ns, key, value) VALUES ('[\"u\"]', 'old', '1');",
+            )
+            .unwrap();
+        }
+        let store = SqliteStore::new(&db).unwrap();
+        let ns = vec!["u".to_string()];
+        assert_eq!(store.get(&ns, "old").await.unwrap(), Some(json!(1)));
+        store
+            .put_with_ttl(&ns, "new", &json!(2), Some(Duration::from_secs(60)))
+            .await
+            .unwrap();
+        assert_eq!(store.list(&ns).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn put_many_and_delete_many_apply_all_keys() {
+        let (store, _dir) = temp_store();
+        let ns = vec!["u".to_string()];
+        store
+            .put_many(
+                &ns,
+                vec![("a".to_string(), json!(1)), ("b".to_string(), json!(2))],
+            )
+            .await
+            .unwrap();
+        assert_eq!(store.list(&ns).await.unwrap().len(), 2);
+
+        store
+            .delete_many(&ns, &["a".to_string(), "b".to_string()])
+            .await
+            .unwrap();
+        assert!(store.list(&ns).await.unwrap().is_empty());
+    }
 }
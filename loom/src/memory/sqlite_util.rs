@@ -1,9 +1,29 @@
 //! Shared SQLite helpers (e.g. open with WAL for concurrent read/write).
+//!
+//! # Encryption at rest
+//!
+//! Checkpoints and stores hold full conversations, including secrets pasted into them. When the
+//! secret [`DB_KEY_ENV`] is set, every database opened here (checkpoints, memory store, vector
+//! store, user messages, approval audit) is unlocked with it as a SQLCipher key before anything
+//! else runs, so the files are encrypted. This needs loom built with the `sqlcipher` feature;
+//! without it, opening fails instead of silently writing plain text. An existing plaintext
+//! database is not converted: export it with SQLCipher's `sqlcipher_export()` first.
 
 use std::path::{Path, PathBuf};
 
+use env_config::Secret;
+use once_cell::sync::Lazy;
+use rusqlite::OptionalExtension;
+
 const MEMORY_DB_FILENAME: &str = "memory.db";
 
+/// Secret holding the SQLCipher key of loom's SQLite databases. Resolved like API keys: env
+/// `LOOM_DB_KEY`, then `LOOM_DB_KEY_FILE`, then the OS keychain (see [`env_config::read_secret`]).
+pub const DB_KEY_ENV: &str = "LOOM_DB_KEY";
+
+/// [`DB_KEY_ENV`], read once per process.
+static DB_KEY: Lazy<Option<Secret>> = Lazy::new(|| env_config::read_secret(DB_KEY_ENV));
+
 /// Returns the default memory DB path (`~/.loom/memory.db`).
 /// Creates the parent directory if missing. Falls back to `memory.db` (cwd-relative) if home is unavailable.
 pub(crate) fn default_memory_db_path() -> PathBuf {
@@ -30,16 +50,61 @@ fn open_error_message(path: &Path, e: &impl std::fmt::Display) -> String {
     format!("path='{}' resolved='{}': {}", path_display, resolved, e)
 }
 
+/// Unlocks `conn` with SQLCipher `key`; must run before any other statement. Fails when SQLite
+/// was built without SQLCipher (the key would be ignored) or when the key does not open the
+/// database.
+pub(crate) fn apply_db_key(conn: &rusqlite::Connection, key: &str) -> Result<(), String> {
+    conn.pragma_update(None, "key", key)
+        .map_err(|e| e.to_string())?;
+    let cipher: Option<String> = conn
+        .query_row("PRAGMA cipher_version", [], |row| row.get(0))
+        .optional()
+        .map_err(|e| e.to_string())?;
+    if cipher.is_none() {
+        return Err(format!(
+            "{} is set but SQLite was built without SQLCipher (enable the `sqlcipher` feature)",
+            DB_KEY_ENV
+        ));
+    }
+    conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |_| Ok(()))
+        .map_err(|e| {
+            format!(
+                "cannot decrypt (wrong {} or unencrypted database): {}",
+                DB_KEY_ENV, e
+            )
+        })
+}
+
+/// Applies [`DB_KEY_ENV`] to a freshly opened connection, when set.
+fn unlock(conn: &rusqlite::Connection, path: &Path) -> Result<(), String> {
+    match DB_KEY.as_ref() {
+        Some(key) => apply_db_key(conn, key.expose()).map_err(|e| open_error_message(path, &e)),
+        None => Ok(()),
+    }
+}
+
 /// Opens a SQLite database and enables WAL mode for better concurrent read/write.
 /// On failure, the error message includes the path, its resolution (cwd-relative), and the underlying cause.
 pub(crate) fn open_sqlite_with_wal(path: &Path) -> Result<rusqlite::Connection, String> {
     let conn = rusqlite::Connection::open(path).map_err(|e| open_error_message(path, &e))?;
+    unlock(&conn, path)?;
     // PRAGMA journal_mode returns a row; use execute_batch to avoid "Execute returned results".
     conn.execute_batch("PRAGMA journal_mode=WAL;")
         .map_err(|e| open_error_message(path, &e))?;
     Ok(conn)
 }
 
+/// Opens an existing SQLite database read-only (no WAL switch, nothing created).
+pub(crate) fn open_sqlite_read_only(path: &Path) -> Result<rusqlite::Connection, String> {
+    let conn = rusqlite::Connection::open_with_flags(
+        path,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| open_error_message(path, &e))?;
+    unlock(&conn, path)?;
+    Ok(conn)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -104,6 +169,21 @@ mod tests {
         assert!(db_path.exists());
     }
 
+    #[test]
+    fn open_sqlite_read_only_rejects_writes_and_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        assert!(open_sqlite_read_only(&db_path).is_err());
+        open_sqlite_with_wal(&db_path)
+            .unwrap()
+            .execute_batch("CREATE TABLE t (id INTEGER PRIMARY KEY);")
+            .unwrap();
+        let conn = open_sqlite_read_only(&db_path).unwrap();
+        assert!(conn
+            .execute_batch("INSERT INTO t (id) VALUES (1);")
+            .is_err());
+    }
+
     #[test]
     fn open_sqlite_with_wal_invalid_path_returns_error() {
         let result = open_sqlite_with_wal(Path::new("/nonexistent/dir/db.sqlite"));
@@ -112,6 +192,37 @@ mod tests {
         assert!(err.contains("/nonexistent/dir/db.sqlite"));
     }
 
+    #[cfg(not(feature = "sqlcipher"))]
+    #[test]
+    fn db_key_without_sqlcipher_is_refused() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        let err = apply_db_key(&conn, "secret").unwrap_err();
+        assert!(err.contains("without SQLCipher"), "{}", err);
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    #[test]
+    fn db_key_encrypts_and_reopens_only_with_the_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("enc.db");
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        apply_db_key(&conn, "right").unwrap();
+        conn.execute_batch("CREATE TABLE t (v TEXT); INSERT INTO t VALUES ('pasted secret');")
+            .unwrap();
+        drop(conn);
+
+        let raw = std::fs::read(&db_path).unwrap();
+        assert!(!raw.windows(13).any(|w| w == b"pasted secret"));
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        assert!(apply_db_key(&conn, "wrong").is_err());
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        apply_db_key(&conn, "right").unwrap();
+        let v: String = conn
+            .query_row("SELECT v FROM t", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(v, "pasted secret");
+    }
+
     #[test]
     fn memory_db_filename_constant() {
         assert_eq!(MEMORY_DB_FILENAME, "memory.db");
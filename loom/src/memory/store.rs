@@ -7,7 +7,7 @@
 //! ## Core Types
 //!
 //! - [`Store`]: The main trait for persistent key-value stores.
-//! - [`Item`]: Stored key-value pairs with metadata (namespace, key, value, timestamps).
+//! - [`Item`]: Stored key-value pairs with metadata (namespace, key, value, timestamps, expiry).
 //! - [`SearchItem`]: Search result with optional relevance score.
 //! - [`StoreOp`]: Operations for batch execution (Get, Put, Search, Delete, ListNamespaces).
 //!
@@ -28,7 +28,7 @@
 //! ```
 
 use async_trait::async_trait;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 /// Hierarchical namespace for store items.
 ///
@@ -83,6 +83,8 @@ pub struct Item {
     pub created_at: SystemTime,
     /// Timestamp of last update.
     pub updated_at: SystemTime,
+    /// When the item expires; `None` means it never does. Expired items are not returned.
+    pub expires_at: Option<SystemTime>,
 }
 
 impl Item {
@@ -95,6 +97,7 @@ impl Item {
             namespace,
             created_at: now,
             updated_at: now,
+            expires_at: None,
         }
     }
 
@@ -115,8 +118,20 @@ impl Item {
             namespace,
             created_at,
             updated_at,
+            expires_at: None,
         }
     }
+
+    /// Sets the expiry time.
+    pub fn with_expires_at(mut self, expires_at: Option<SystemTime>) -> Self {
+        self.expires_at = expires_at;
+        self
+    }
+
+    /// Returns true when the item has an expiry at or before `now`.
+    pub fn is_expired_at(&self, now: SystemTime) -> bool {
+        self.expires_at.is_some_and(|t| t <= now)
+    }
 }
 
 /// Search result item with an optional ranking score.
@@ -411,6 +426,21 @@ mod tests {
         assert_eq!(item.key, "doc1");
         assert_eq!(item.created_at, created);
         assert_eq!(item.updated_at, updated);
+        assert!(item.expires_at.is_none());
+    }
+
+    /// **Scenario**: Item expiry is inclusive of the expiry instant; no expiry never expires.
+    #[test]
+    fn item_is_expired_at_respects_expiry() {
+        use std::time::Duration;
+
+        let at = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+        let item = Item::new(vec!["ns".into()], "k".into(), serde_json::json!(1));
+        assert!(!item.is_expired_at(at));
+
+        let item = item.with_expires_at(Some(at));
+        assert!(!item.is_expired_at(at - Duration::from_secs(1)));
+        assert!(item.is_expired_at(at));
     }
 
     /// **Scenario**: SearchItem can be created from Item without score.
@@ -569,6 +599,8 @@ pub struct StoreSearchHit {
 /// - **search**: Search for items within a namespace prefix with optional query and filters.
 /// - **list_namespaces**: List namespaces matching given conditions.
 /// - **batch**: Execute multiple operations efficiently in a single call.
+/// - **put_with_ttl** / **purge_expired**: Store items that expire, and drop them for good.
+/// - **put_many** / **delete_many**: Write or remove several keys of one namespace at once.
 ///
 /// ## Example
 ///
@@ -646,6 +678,67 @@ pub trait Store: Send + Sync {
     /// The order of results must match the order of input operations.
     async fn batch(&self, ops: Vec<StoreOp>) -> Result<Vec<StoreOpResult>, StoreError>;
 
+    /// Stores `value` like [`Self::put`], expiring it `ttl` from now.
+    ///
+    /// `None` stores without expiry (and clears an earlier one). Expired items are hidden
+    /// from reads and listings until [`Self::purge_expired`] removes them. Stores without
+    /// TTL support return [`StoreError::Storage`] for `Some(ttl)`.
+    async fn put_with_ttl(
+        &self,
+        namespace: &Namespace,
+        key: &str,
+        value: &serde_json::Value,
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        match ttl {
+            None => self.put(namespace, key, value).await,
+            Some(_) => Err(StoreError::Storage(
+                "ttl is not supported by this store".to_string(),
+            )),
+        }
+    }
+
+    /// Removes expired items and returns how many were removed.
+    ///
+    /// Stores without TTL support have nothing to purge and return `Ok(0)`.
+    async fn purge_expired(&self) -> Result<usize, StoreError> {
+        Ok(0)
+    }
+
+    /// Stores several `(key, value)` pairs under `namespace`.
+    ///
+    /// The default runs them through [`Self::batch`]; backends may apply them atomically.
+    async fn put_many(
+        &self,
+        namespace: &Namespace,
+        items: Vec<(String, serde_json::Value)>,
+    ) -> Result<(), StoreError> {
+        let ops = items
+            .into_iter()
+            .map(|(key, value)| StoreOp::Put {
+                namespace: namespace.clone(),
+                key,
+                value: Some(value),
+            })
+            .collect();
+        self.batch(ops).await.map(|_| ())
+    }
+
+    /// Deletes several keys under `namespace`. Missing keys are ignored.
+    ///
+    /// The default runs them through [`Self::batch`]; backends may apply them atomically.
+    async fn delete_many(&self, namespace: &Namespace, keys: &[String]) -> Result<(), StoreError> {
+        let ops = keys
+            .iter()
+            .map(|key| StoreOp::Put {
+                namespace: namespace.clone(),
+                key: key.clone(),
+                value: None,
+            })
+            .collect();
+        self.batch(ops).await.map(|_| ())
+    }
+
     // --- Legacy API for backward compatibility ---
 
     /// Searches within the namespace using the legacy simplified API.
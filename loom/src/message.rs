@@ -5,14 +5,19 @@
 
 use std::borrow::Cow;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::warn;
 
 use crate::memory::uuid6;
 use crate::tool_source::ToolCallContent;
 
+/// Prefix of a pinned message's text: compaction, history windows and state limits never drop
+/// or shorten pinned messages (e.g. the task spec or key constraints). See [`Message::pinned_user`].
+pub const PINNED_PREFIX: &str = "[Pinned]";
+
 /// User message content: plain text or multimodal part array.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum UserContent {
     Text(String),
@@ -20,7 +25,7 @@ pub enum UserContent {
 }
 
 /// One content part in a multimodal user message.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ContentPart {
     Text {
@@ -101,6 +106,36 @@ impl UserContent {
         Ok(Self::Multimodal(parts))
     }
 
+    /// This content marked as pinned (its text starts with [`PINNED_PREFIX`]). Already pinned
+    /// content is returned unchanged.
+    pub fn pinned(self) -> Self {
+        if self.is_pinned() {
+            return self;
+        }
+        match self {
+            UserContent::Text(s) => UserContent::Text(format!("{} {}", PINNED_PREFIX, s)),
+            UserContent::Multimodal(mut parts) => {
+                match parts.first_mut() {
+                    Some(ContentPart::Text { text }) => {
+                        *text = format!("{} {}", PINNED_PREFIX, text);
+                    }
+                    _ => parts.insert(
+                        0,
+                        ContentPart::Text {
+                            text: PINNED_PREFIX.to_string(),
+                        },
+                    ),
+                }
+                UserContent::Multimodal(parts)
+            }
+        }
+    }
+
+    /// Whether this content is pinned (see [`Self::pinned`]).
+    pub fn is_pinned(&self) -> bool {
+        self.starts_with(PINNED_PREFIX)
+    }
+
     /// Returns the list of modalities used in this content.
     pub fn modalities(&self) -> Vec<model_spec_core::spec::ModalityType> {
         match self {
@@ -134,6 +169,12 @@ impl UserContent {
     }
 }
 
+impl Default for UserContent {
+    fn default() -> Self {
+        Self::Text(String::new())
+    }
+}
+
 impl From<String> for UserContent {
     fn from(s: String) -> Self {
         Self::Text(s)
@@ -306,6 +347,20 @@ impl Message {
         Self::User(content.into())
     }
 
+    /// Creates a pinned user message: kept verbatim through compaction and history trimming.
+    pub fn pinned_user(content: impl Into<UserContent>) -> Self {
+        Self::User(content.into().pinned())
+    }
+
+    /// Whether this is a pinned user or system message (its text starts with [`PINNED_PREFIX`]).
+    pub fn is_pinned(&self) -> bool {
+        match self {
+            Message::System(s) => s.starts_with(PINNED_PREFIX),
+            Message::User(c) => c.is_pinned(),
+            Message::Assistant(_) | Message::Tool { .. } => false,
+        }
+    }
+
     /// Creates a user message with multimodal content.
     pub fn user_multimodal(parts: Vec<ContentPart>) -> Result<Self, ContentError> {
         Ok(Self::User(UserContent::multimodal(parts)?))
@@ -506,6 +561,20 @@ mod tests {
         assert_eq!(v, serde_json::json!({"Assistant": "hi"}));
     }
 
+    /// **Scenario**: Pinning marks user text once and leaves other messages unpinned.
+    #[test]
+    fn pinned_user_is_marked_once() {
+        let pinned = Message::pinned_user("keep the API stable");
+        assert!(pinned.is_pinned());
+        assert_eq!(pinned.content(), "[Pinned] keep the API stable");
+        let Message::User(content) = pinned else {
+            unreachable!()
+        };
+        assert_eq!(content.clone().pinned(), content);
+        assert!(!Message::user("hi").is_pinned());
+        assert!(!Message::assistant("[Pinned] echo").is_pinned());
+    }
+
     /// **Scenario**: role() returns correct string for each variant.
     #[test]
     fn message_role() {
@@ -35,6 +35,25 @@ where
         }
     }
 
+    /// Copy of every cached spec, keyed `provider/model`.
+    pub async fn snapshot(&self) -> HashMap<String, ModelSpec> {
+        self.cache.read().await.clone()
+    }
+
+    /// Cached spec for `provider_id/model_id` without asking the inner resolver. Falls back to
+    /// any provider's entry for the bare `model_id` (e.g. a gateway serving `gpt-4o`).
+    pub async fn get_cached(&self, provider_id: &str, model_id: &str) -> Option<ModelSpec> {
+        let cache = self.cache.read().await;
+        if let Some(spec) = cache.get(&format!("{}/{}", provider_id, model_id)) {
+            return Some(spec.clone());
+        }
+        let suffix = format!("/{}", model_id);
+        cache
+            .iter()
+            .find(|(key, _)| key.ends_with(&suffix))
+            .map(|(_, spec)| spec.clone())
+    }
+
     /// Clear the cache.
     pub async fn clear(&self) {
         self.cache.write().await.clear();
@@ -132,4 +151,26 @@ mod tests {
         assert_eq!(spec2.context_limit, 128_000);
         assert_eq!(client.call_count.load(Ordering::SeqCst), 1);
     }
+
+    #[tokio::test]
+    async fn get_cached_never_calls_inner_and_falls_back_to_bare_name() {
+        let client = Arc::new(CountingMockClient {
+            body: "{}".to_string(),
+            call_count: AtomicUsize::new(0),
+        });
+        let cached = CachedResolver::new(ModelsDevResolver::with_client(
+            "https://x.com/api.json".to_string(),
+            client.clone(),
+        ));
+        let mut specs = HashMap::new();
+        specs.insert("openai/gpt-4o".to_string(), ModelSpec::new(128_000, 16_384));
+        cached.refresh(specs).await;
+
+        assert!(cached.get_cached("openai", "gpt-4o").await.is_some());
+        let via_gateway = cached.get_cached("my-gateway", "gpt-4o").await.unwrap();
+        assert_eq!(via_gateway.context_limit, 128_000);
+        assert!(cached.get_cached("openai", "unknown").await.is_none());
+        assert_eq!(client.call_count.load(Ordering::SeqCst), 0);
+        assert_eq!(cached.snapshot().await.len(), 1);
+    }
 }
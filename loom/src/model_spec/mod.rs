@@ -27,12 +27,20 @@ mod models_dev;
 mod refresher;
 mod resolver;
 mod spec;
+mod tokenizer;
+mod tool_support;
+mod usage_cost;
 
 pub use cached::CachedResolver;
 pub use composite::CompositeResolver;
 pub use config_override::ConfigOverride;
 pub use local_file::LocalFileResolver;
 pub use models_dev::{HttpClient, ModelsDevResolver, ReqwestHttpClient, DEFAULT_MODELS_DEV_URL};
-pub use refresher::ResolverRefresher;
+pub use refresher::{default_catalog_cache_path, ResolverRefresher, MODEL_CATALOG_CACHE_FILENAME};
 pub use resolver::ModelLimitResolver;
 pub use spec::{Cost, Modalities, ModalityType, Model, ModelLimit, ModelSpec, Provider};
+#[cfg(feature = "tiktoken")]
+pub use tokenizer::TiktokenTokenizer;
+pub use tokenizer::{tokenizer_for, HeuristicTokenizer, Tokenizer};
+pub use tool_support::{tool_support_for, ToolCallMode, ToolSupport};
+pub use usage_cost::{estimate_usage_cost, price_usage_rows};
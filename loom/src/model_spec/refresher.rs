@@ -1,5 +1,14 @@
 //! Background refresher: periodically fetches models.dev and updates cache.
+//!
+//! Refreshes run every `interval` plus a random delay of up to `jitter`, so many processes
+//! started together do not hit models.dev at the same moment. With a cache file set, the
+//! resolved catalog is loaded from it before the first fetch (a restart resolves limits without
+//! the network) and written back after every successful refresh.
 
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -7,33 +16,133 @@ use tokio::task::JoinHandle;
 
 use super::cached::CachedResolver;
 use super::models_dev::ModelsDevResolver;
+use super::spec::ModelSpec;
+
+/// File name of the persisted catalog under `~/.loom/cache`.
+pub const MODEL_CATALOG_CACHE_FILENAME: &str = "models.json";
+
+/// Default path of the persisted catalog: `~/.loom/cache/models.json`.
+pub fn default_catalog_cache_path() -> PathBuf {
+    env_config::home::loom_home()
+        .join("cache")
+        .join(MODEL_CATALOG_CACHE_FILENAME)
+}
 
 /// Spawns a background task that periodically refreshes the cache from models.dev.
 pub struct ResolverRefresher {
     cached: Arc<CachedResolver<ModelsDevResolver>>,
     interval: Duration,
+    jitter: Duration,
+    cache_file: Option<PathBuf>,
 }
 
 impl ResolverRefresher {
     /// Create a refresher that will run every `interval`.
     pub fn new(cached: Arc<CachedResolver<ModelsDevResolver>>, interval: Duration) -> Self {
-        Self { cached, interval }
+        Self {
+            cached,
+            interval,
+            jitter: Duration::ZERO,
+            cache_file: None,
+        }
+    }
+
+    /// Adds a random delay of up to `jitter` to every interval.
+    pub fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Loads the catalog from `path` on start and persists it there after every refresh.
+    pub fn with_cache_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cache_file = Some(path.into());
+        self
+    }
+
+    /// Loads the persisted catalog into the cache. Returns the number of specs loaded; a missing
+    /// or unreadable file loads nothing.
+    pub async fn load_cache_file(&self) -> usize {
+        let Some(path) = &self.cache_file else {
+            return 0;
+        };
+        match read_catalog(path) {
+            Ok(specs) => {
+                let n = specs.len();
+                self.cached.refresh(specs).await;
+                tracing::debug!(path = %path.display(), n, "model_spec cache loaded from file");
+                n
+            }
+            Err(e) => {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    tracing::warn!(path = %path.display(), "model catalog cache unreadable: {}", e);
+                }
+                0
+            }
+        }
+    }
+
+    /// Fetches models.dev once, updates the cache and persists it. Returns false when the fetch
+    /// failed (the cache is left as is).
+    pub async fn refresh_once(&self) -> bool {
+        match self.cached.inner().fetch_all().await {
+            Ok(specs) => {
+                self.cached.refresh(specs).await;
+                tracing::debug!("model_spec cache refreshed from models.dev");
+                if let Some(path) = &self.cache_file {
+                    let snapshot = self.cached.snapshot().await;
+                    if let Err(e) = write_catalog(path, &snapshot) {
+                        tracing::warn!(
+                            path = %path.display(),
+                            "failed to persist model catalog: {}",
+                            e
+                        );
+                    }
+                }
+                true
+            }
+            Err(e) => {
+                tracing::warn!("model_spec refresh from models.dev failed: {}", e);
+                false
+            }
+        }
     }
 
     /// Spawn the background refresh loop. Returns a handle that can be used to abort.
     pub fn spawn(self) -> JoinHandle<()> {
         tokio::spawn(async move {
-            let mut interval = tokio::time::interval(self.interval);
-            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            self.load_cache_file().await;
             loop {
-                interval.tick().await;
-                if let Ok(specs) = self.cached.inner().fetch_all().await {
-                    self.cached.refresh(specs).await;
-                    tracing::debug!("model_spec cache refreshed from models.dev");
-                }
+                self.refresh_once().await;
+                tokio::time::sleep(self.next_delay()).await;
             }
         })
     }
+
+    fn next_delay(&self) -> Duration {
+        if self.jitter.is_zero() {
+            return self.interval;
+        }
+        // RandomState is seeded randomly per instance; good enough to spread refreshes.
+        let random = RandomState::new().hash_one(std::time::Instant::now());
+        let jitter_ms = self.jitter.as_millis().max(1) as u64;
+        self.interval + Duration::from_millis(random % jitter_ms)
+    }
+}
+
+fn read_catalog(path: &Path) -> std::io::Result<HashMap<String, ModelSpec>> {
+    let text = std::fs::read_to_string(path)?;
+    serde_json::from_str(&text).map_err(std::io::Error::other)
+}
+
+/// Writes via a temporary file and rename so readers never see a partial catalog.
+fn write_catalog(path: &Path, specs: &HashMap<String, ModelSpec>) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string(specs).map_err(std::io::Error::other)?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)?;
+    std::fs::rename(&tmp, path)
 }
 
 #[cfg(test)]
@@ -85,4 +194,59 @@ mod tests {
         let spec = cached.resolve("zai", "glm-5").await.unwrap();
         assert_eq!(spec.context_limit, 204_800);
     }
+
+    #[tokio::test]
+    async fn refresh_persists_catalog_and_restart_loads_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache").join("models.json");
+        let client = Arc::new(CountingHttpClient {
+            body: fixture_json(),
+            calls: AtomicUsize::new(0),
+        });
+        let resolver =
+            ModelsDevResolver::with_client("https://example.com/models.json".to_string(), client);
+        let refresher = ResolverRefresher::new(
+            Arc::new(CachedResolver::new(resolver)),
+            Duration::from_secs(3600),
+        )
+        .with_cache_file(&path);
+        assert!(refresher.refresh_once().await);
+        assert!(path.exists());
+
+        // A fresh process whose fetches fail still resolves from the persisted file.
+        let offline = Arc::new(CountingHttpClient {
+            body: "not json".to_string(),
+            calls: AtomicUsize::new(0),
+        });
+        let cached = Arc::new(CachedResolver::new(ModelsDevResolver::with_client(
+            "https://example.com/models.json".to_string(),
+            offline,
+        )));
+        let restarted = ResolverRefresher::new(cached.clone(), Duration::from_secs(3600))
+            .with_cache_file(&path);
+        assert_eq!(restarted.load_cache_file().await, 1);
+        assert!(!restarted.refresh_once().await);
+        let spec = cached.resolve("zai", "glm-5").await.unwrap();
+        assert_eq!(spec.output_limit, 131_072);
+    }
+
+    #[test]
+    fn jitter_stays_within_bound() {
+        let resolver = ModelsDevResolver::with_client(
+            "https://example.com/models.json".to_string(),
+            Arc::new(CountingHttpClient {
+                body: String::new(),
+                calls: AtomicUsize::new(0),
+            }),
+        );
+        let refresher = ResolverRefresher::new(
+            Arc::new(CachedResolver::new(resolver)),
+            Duration::from_secs(60),
+        )
+        .with_jitter(Duration::from_secs(10));
+        for _ in 0..20 {
+            let delay = refresher.next_delay();
+            assert!(delay >= Duration::from_secs(60) && delay < Duration::from_secs(70));
+        }
+    }
 }
@@ -3,6 +3,8 @@
 pub use model_spec_core::spec::{Cost, Modalities, ModalityType, Model, ModelLimit, Provider};
 use serde::{Deserialize, Serialize};
 
+use super::tool_support::{ToolCallMode, ToolSupport};
+
 /// Legacy ModelSpec for backward compatibility
 /// Wraps ModelLimit to maintain existing API
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -21,6 +23,10 @@ pub struct ModelSpec {
     #[serde(default)]
     pub cache_write: Option<u32>,
 
+    /// Tool-calling capabilities (parallel calls, strict schemas, or none at all)
+    #[serde(default)]
+    pub tool_support: ToolSupport,
+
     /// Full model metadata (optional, for extended information)
     #[serde(skip)]
     pub full_model: Option<Model>,
@@ -34,6 +40,7 @@ impl ModelSpec {
             output_limit,
             cache_read: None,
             cache_write: None,
+            tool_support: ToolSupport::default(),
             full_model: None,
         }
     }
@@ -45,6 +52,7 @@ impl ModelSpec {
             output_limit: limit.output,
             cache_read: limit.cache_read,
             cache_write: limit.cache_write,
+            tool_support: ToolSupport::default(),
             full_model: None,
         }
     }
@@ -57,6 +65,11 @@ impl ModelSpec {
             output_limit: limit.output,
             cache_read: limit.cache_read,
             cache_write: limit.cache_write,
+            tool_support: if model.tool_call {
+                ToolSupport::default()
+            } else {
+                ToolSupport::new(ToolCallMode::Text, false)
+            },
             full_model: Some(model.clone()),
         })
     }
@@ -73,6 +86,12 @@ impl ModelSpec {
         self
     }
 
+    /// Set tool-calling capabilities
+    pub fn with_tool_support(mut self, support: ToolSupport) -> Self {
+        self.tool_support = support;
+        self
+    }
+
     /// Get modalities if available
     pub fn modalities(&self) -> Option<&Modalities> {
         self.full_model.as_ref().map(|m| &m.modalities)
@@ -117,10 +136,7 @@ impl ModelSpec {
 
     /// Check if supports tool calling
     pub fn supports_tool_call(&self) -> bool {
-        self.full_model
-            .as_ref()
-            .map(|m| m.tool_call)
-            .unwrap_or(true) // Default to true for backward compatibility
+        self.tool_support.native_tool_calls()
     }
 
     /// Estimate cost for given token counts
@@ -219,6 +235,7 @@ mod tests {
         assert!(spec.supports_vision());
         assert!(!spec.supports_audio());
         assert!(spec.supports_tool_call());
+        assert_eq!(spec.tool_support, ToolSupport::default());
 
         let estimated_cost = spec.estimate_cost(100_000, 10_000).unwrap();
         assert!((estimated_cost - 0.35).abs() < 0.01); // 0.25 + 0.10
@@ -0,0 +1,149 @@
+//! Token counting for context accounting.
+//!
+//! [`Tokenizer`] counts the tokens of a text. [`tokenizer_for`] resolves the real tokenizer of a
+//! model from its id (or the id in its [`ModelSpec`]); with the `tiktoken` feature, OpenAI
+//! models get their BPE encoding ([`TiktokenTokenizer`]). When no tokenizer is known, callers
+//! fall back to the ~4 characters per token heuristic ([`HeuristicTokenizer`]).
+//!
+//! Used by compaction and the context guard (via
+//! [`CompactionConfig::tokenizer`](crate::compress::CompactionConfig::tokenizer)) and by the
+//! LLM clients to estimate usage when a streaming response reports none.
+
+use std::sync::Arc;
+
+use super::spec::ModelSpec;
+
+/// Counts tokens in text the way a model's provider does.
+pub trait Tokenizer: Send + Sync + std::fmt::Debug {
+    /// Name for logs (e.g. `heuristic`, `O200kBase`).
+    fn name(&self) -> &str;
+
+    /// Number of tokens in `text`.
+    fn count(&self, text: &str) -> usize;
+}
+
+/// ~4 characters per token, as in [`crate::compress::context_window::estimate_tokens`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicTokenizer;
+
+impl Tokenizer for HeuristicTokenizer {
+    fn name(&self) -> &str {
+        "heuristic"
+    }
+
+    fn count(&self, text: &str) -> usize {
+        text.len() / 4
+    }
+}
+
+#[cfg(feature = "tiktoken")]
+pub use bpe::TiktokenTokenizer;
+
+#[cfg(feature = "tiktoken")]
+mod bpe {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use once_cell::sync::Lazy;
+    use tiktoken_rs::CoreBPE;
+
+    /// Encodings already loaded, by name; building one takes a noticeable moment.
+    static ENCODINGS: Lazy<Mutex<HashMap<String, Arc<CoreBPE>>>> =
+        Lazy::new(|| Mutex::new(HashMap::new()));
+
+    /// OpenAI BPE tokenizer (`o200k_base`, `cl100k_base`, ...) from `tiktoken-rs`, named after
+    /// its encoding (e.g. `O200kBase`).
+    #[derive(Clone)]
+    pub struct TiktokenTokenizer {
+        name: String,
+        bpe: Arc<CoreBPE>,
+    }
+
+    impl TiktokenTokenizer {
+        /// Tokenizer of OpenAI model `model` (bare id, e.g. `gpt-4o`); `None` for other models.
+        pub fn for_model(model: &str) -> Option<Self> {
+            let encoding = tiktoken_rs::tokenizer::get_tokenizer(model)?;
+            let name = format!("{:?}", encoding);
+            let mut loaded = ENCODINGS.lock().unwrap_or_else(|e| e.into_inner());
+            let bpe = match loaded.get(&name) {
+                Some(bpe) => bpe.clone(),
+                None => {
+                    let bpe = Arc::new(tiktoken_rs::get_bpe_from_tokenizer(encoding).ok()?);
+                    loaded.insert(name.clone(), bpe.clone());
+                    bpe
+                }
+            };
+            Some(Self { name, bpe })
+        }
+    }
+
+    impl super::Tokenizer for TiktokenTokenizer {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn count(&self, text: &str) -> usize {
+            self.bpe.encode_with_special_tokens(text).len()
+        }
+    }
+
+    impl std::fmt::Debug for TiktokenTokenizer {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("TiktokenTokenizer")
+                .field("name", &self.name)
+                .finish_non_exhaustive()
+        }
+    }
+}
+
+/// Tokenizer of `model` (`provider/model` or a bare model name), trying the model id in `spec`
+/// first. `None` when no exact tokenizer is known (or the `tiktoken` feature is off); callers
+/// then use the heuristic.
+pub fn tokenizer_for(model: &str, spec: Option<&ModelSpec>) -> Option<Arc<dyn Tokenizer>> {
+    #[cfg(feature = "tiktoken")]
+    {
+        let ids = spec
+            .and_then(|s| s.full_model.as_ref())
+            .map(|m| m.id.as_str())
+            .into_iter()
+            .chain(std::iter::once(model));
+        for id in ids {
+            let bare = id.rsplit('/').next().unwrap_or(id);
+            if let Some(tokenizer) = TiktokenTokenizer::for_model(bare) {
+                return Some(Arc::new(tokenizer));
+            }
+        }
+        None
+    }
+    #[cfg(not(feature = "tiktoken"))]
+    {
+        let _ = (model, spec);
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heuristic_counts_four_bytes_per_token() {
+        assert_eq!(HeuristicTokenizer.count("12345678"), 2);
+        assert_eq!(HeuristicTokenizer.count("abc"), 0);
+    }
+
+    #[test]
+    fn unknown_models_have_no_tokenizer() {
+        assert!(tokenizer_for("acme/llama-local", None).is_none());
+    }
+
+    #[cfg(feature = "tiktoken")]
+    #[test]
+    fn openai_models_resolve_to_bpe_tokenizers() {
+        let tokenizer = tokenizer_for("openai/gpt-4o", None).unwrap();
+        assert_eq!(tokenizer.name(), "O200kBase");
+        assert_eq!(tokenizer.count("hello world"), 2);
+        let spec = ModelSpec::new(8192, 4096);
+        assert!(tokenizer_for("gpt-4", Some(&spec)).is_some());
+    }
+}
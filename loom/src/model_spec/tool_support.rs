@@ -0,0 +1,160 @@
+//! Tool-calling capabilities per model: whether a model takes native tool definitions, several
+//! tool calls per answer, or strict (exactly followed) argument schemas.
+//!
+//! [`tool_support_for`] looks the model up in a built-in matrix of models known to deviate from
+//! full support, then falls back to the [`ModelSpec`] (models.dev `tool_call`). The ReAct think
+//! node adapts to the result (see [`crate::agent::react::ThinkNode`]): one tool call per turn
+//! for [`ToolCallMode::Serial`], the text protocol of [`crate::llm::TextToolCallLlm`] for
+//! [`ToolCallMode::Text`].
+
+use serde::{Deserialize, Serialize};
+
+use super::spec::ModelSpec;
+
+/// How a model calls tools.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCallMode {
+    /// Native tool calls, several per answer.
+    #[default]
+    Parallel,
+    /// Native tool calls, one per answer.
+    Serial,
+    /// No function calling: tools are described in the prompt and called in the reply text.
+    Text,
+}
+
+impl ToolCallMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Parallel => "parallel",
+            Self::Serial => "serial",
+            Self::Text => "text",
+        }
+    }
+}
+
+impl std::str::FromStr for ToolCallMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "parallel" => Ok(Self::Parallel),
+            "serial" => Ok(Self::Serial),
+            "text" => Ok(Self::Text),
+            _ => Err(format!(
+                "unknown tool call mode: {} (use parallel, serial, or text)",
+                s
+            )),
+        }
+    }
+}
+
+/// Tool-calling capabilities of a model.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolSupport {
+    pub mode: ToolCallMode,
+    /// Whether the model follows tool input schemas exactly (OpenAI structured outputs).
+    #[serde(default)]
+    pub strict_schemas: bool,
+}
+
+impl ToolSupport {
+    pub const fn new(mode: ToolCallMode, strict_schemas: bool) -> Self {
+        Self {
+            mode,
+            strict_schemas,
+        }
+    }
+
+    /// Whether the model takes native tool definitions (not [`ToolCallMode::Text`]).
+    pub fn native_tool_calls(&self) -> bool {
+        self.mode != ToolCallMode::Text
+    }
+
+    /// Whether the model may return several tool calls in one answer.
+    pub fn parallel_tool_calls(&self) -> bool {
+        self.mode == ToolCallMode::Parallel
+    }
+}
+
+const TEXT: ToolSupport = ToolSupport::new(ToolCallMode::Text, false);
+const SERIAL: ToolSupport = ToolSupport::new(ToolCallMode::Serial, false);
+const SERIAL_STRICT: ToolSupport = ToolSupport::new(ToolCallMode::Serial, true);
+const PARALLEL_STRICT: ToolSupport = ToolSupport::new(ToolCallMode::Parallel, true);
+
+/// Models known to deviate from full tool support, by bare model name prefix; the first match
+/// wins, so more specific prefixes come first.
+const TOOL_SUPPORT_MATRIX: &[(&str, ToolSupport)] = &[
+    ("o1-mini", TEXT),
+    ("o1-preview", TEXT),
+    ("gpt-3.5-turbo-instruct", TEXT),
+    ("deepseek-r1", TEXT),
+    ("gemma", TEXT),
+    ("phi-3", TEXT),
+    ("phi3", TEXT),
+    ("llama2", TEXT),
+    ("llama-2", TEXT),
+    ("o1", SERIAL_STRICT),
+    ("o3", SERIAL_STRICT),
+    ("o4-mini", SERIAL_STRICT),
+    ("deepseek-reasoner", SERIAL),
+    ("qwq", SERIAL),
+    ("gpt-4o", PARALLEL_STRICT),
+    ("gpt-4.1", PARALLEL_STRICT),
+    ("gpt-5", PARALLEL_STRICT),
+];
+
+/// Tool support of `model` (`provider/model` or a bare model name): the built-in matrix entry
+/// for the model id in `spec` or `model`, else what `spec` reports, else full support.
+pub fn tool_support_for(model: &str, spec: Option<&ModelSpec>) -> ToolSupport {
+    let ids = spec
+        .and_then(|s| s.full_model.as_ref())
+        .map(|m| m.id.as_str())
+        .into_iter()
+        .chain(std::iter::once(model));
+    for id in ids {
+        let bare = id.rsplit('/').next().unwrap_or(id).to_lowercase();
+        if let Some((_, support)) = TOOL_SUPPORT_MATRIX
+            .iter()
+            .find(|(prefix, _)| bare.starts_with(prefix))
+        {
+            return *support;
+        }
+    }
+    spec.map(|s| s.tool_support).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matrix_matches_most_specific_prefix_first() {
+        assert_eq!(
+            tool_support_for("openai/o1-mini", None).mode,
+            ToolCallMode::Text
+        );
+        assert_eq!(
+            tool_support_for("o1-2024-12-17", None).mode,
+            ToolCallMode::Serial
+        );
+        assert!(tool_support_for("openai/gpt-4o-mini", None).strict_schemas);
+        assert_eq!(
+            tool_support_for("acme/llama-local", None),
+            ToolSupport::default()
+        );
+    }
+
+    #[test]
+    fn spec_without_tool_calls_falls_back_to_text() {
+        let spec = ModelSpec::new(8192, 1024)
+            .with_tool_support(ToolSupport::new(ToolCallMode::Text, false));
+        assert_eq!(
+            tool_support_for("acme/local", Some(&spec)).mode,
+            ToolCallMode::Text
+        );
+        assert_eq!("Serial".parse::<ToolCallMode>(), Ok(ToolCallMode::Serial));
+        assert!("none".parse::<ToolCallMode>().is_err());
+    }
+}
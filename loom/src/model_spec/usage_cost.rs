@@ -0,0 +1,112 @@
+//! Estimated cost of recorded token usage, from models.dev pricing.
+
+use std::collections::HashMap;
+
+use super::models_dev::ModelsDevResolver;
+use super::spec::ModelSpec;
+use crate::protocol::UsageReportRow;
+
+/// Estimates the USD cost of `prompt_tokens` + `completion_tokens` for `model`. The model is
+/// looked up as `provider/model` first, then as a bare model name under any provider (first
+/// provider in name order). `None` when the model or its pricing is unknown.
+pub fn estimate_usage_cost(
+    specs: &HashMap<String, ModelSpec>,
+    model: &str,
+    prompt_tokens: u64,
+    completion_tokens: u64,
+) -> Option<f64> {
+    let suffix = format!("/{}", model);
+    let spec = specs.get(model).or_else(|| {
+        specs
+            .iter()
+            .filter(|(key, _)| key.ends_with(&suffix))
+            .min_by(|a, b| a.0.cmp(b.0))
+            .map(|(_, spec)| spec)
+    })?;
+    let cost = spec.cost()?;
+    Some(
+        cost.input_cost_usd() * prompt_tokens as f64 / 1_000_000.0
+            + cost.output_cost_usd() * completion_tokens as f64 / 1_000_000.0,
+    )
+}
+
+/// Fills `cost_usd` of each row with one models.dev fetch. When the fetch fails (e.g. offline)
+/// the rows are left unpriced and a warning is logged.
+pub async fn price_usage_rows(resolver: &ModelsDevResolver, rows: &mut [UsageReportRow]) {
+    if rows.is_empty() {
+        return;
+    }
+    let specs = match resolver.fetch_all().await {
+        Ok(specs) => specs,
+        Err(e) => {
+            tracing::warn!("usage report: models.dev pricing unavailable: {}", e);
+            return;
+        }
+    };
+    for row in rows {
+        row.cost_usd =
+            estimate_usage_cost(&specs, &row.model, row.prompt_tokens, row.completion_tokens);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model_spec::HttpClient;
+    use async_trait::async_trait;
+    use std::sync::Arc;
+
+    struct FixtureClient;
+
+    #[async_trait]
+    impl HttpClient for FixtureClient {
+        async fn get(&self, _url: &str) -> Result<String, String> {
+            Ok(r#"{
+                "openai": {
+                    "id": "openai",
+                    "name": "OpenAI",
+                    "models": {
+                        "gpt-4o": {
+                            "id": "gpt-4o",
+                            "name": "GPT-4o",
+                            "attachment": true,
+                            "reasoning": false,
+                            "tool_call": true,
+                            "temperature": true,
+                            "modalities": { "input": ["text"], "output": ["text"] },
+                            "open_weights": false,
+                            "cost": { "input": 2.5, "output": 10 },
+                            "limit": { "context": 128000, "output": 16384 }
+                        }
+                    }
+                }
+            }"#
+            .to_string())
+        }
+    }
+
+    fn row(model: &str) -> UsageReportRow {
+        UsageReportRow {
+            model: model.to_string(),
+            agent: "react".to_string(),
+            runs: 1,
+            prompt_tokens: 1_000_000,
+            completion_tokens: 100_000,
+            total_tokens: 1_100_000,
+            cost_usd: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn prices_known_models_by_full_or_bare_name() {
+        let resolver = ModelsDevResolver::with_client(
+            "https://example.com".to_string(),
+            Arc::new(FixtureClient),
+        );
+        let mut rows = vec![row("openai/gpt-4o"), row("gpt-4o"), row("acme/unknown")];
+        price_usage_rows(&resolver, &mut rows).await;
+        assert_eq!(rows[0].cost_usd, Some(3.5));
+        assert_eq!(rows[1].cost_usd, Some(3.5));
+        assert_eq!(rows[2].cost_usd, None);
+    }
+}
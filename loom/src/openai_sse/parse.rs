@@ -92,6 +92,8 @@ pub fn parse_chat_request(req: &ChatCompletionRequest) -> Result<ParsedChatReque
         resume_value: None,
         resume_values_by_namespace: Default::default(),
         resume_values_by_interrupt_id: Default::default(),
+        routing_seed: None,
+        flags: Default::default(),
     };
 
     let include_usage = req
@@ -131,10 +133,14 @@ pub fn parse_chat_request(req: &ChatCompletionRequest) -> Result<ParsedChatReque
             thread_id: req.thread_id.clone(),
             user_id: None,
             approval_policy,
+            approval_rules: Vec::new(),
             role_setting: None,
             agents_md: None,
             system_prompt_override: None,
             skills_prompt: None,
+            skill_packs: Vec::new(),
+            skills: Vec::new(),
+            locale: None,
         })
     } else {
         None
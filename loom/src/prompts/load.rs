@@ -2,12 +2,15 @@
 //!
 //! **Canonical source**: Default prompt text lives in `loom/prompts/*.yaml`; they are
 //! embedded at compile time and used when no `PROMPTS_DIR` or directory is present.
+//! Subdirectories named by a locale tag (`zh/`, `pt-BR/`) hold localized variants of the same files.
 //! See [`load`], [`load_or_default`], [`default_from_embedded`], and [`LoadError`].
 
+use std::collections::HashMap;
 use std::path::Path;
 
 use serde::Deserialize;
 
+use super::locale::normalize_locale;
 use super::{DupPromptsFile, GotPromptsFile, HelvePromptsFile, ReactPromptsFile, TotPromptsFile};
 
 /// Embedded default YAML (canonical source: `loom/prompts/*.yaml`).
@@ -78,8 +81,45 @@ where
     Ok(Some(value))
 }
 
+/// Reads the per-pattern files of one directory; missing files keep their defaults.
+fn read_prompt_files(dir: &Path) -> Result<super::resolve::AgentPrompts, LoadError> {
+    Ok(super::resolve::AgentPrompts {
+        react: read_yaml_file::<ReactPromptsFile>(dir, REACT_FILE)?.unwrap_or_default(),
+        tot: read_yaml_file::<TotPromptsFile>(dir, TOT_FILE)?.unwrap_or_default(),
+        got: read_yaml_file::<GotPromptsFile>(dir, GOT_FILE)?.unwrap_or_default(),
+        dup: read_yaml_file::<DupPromptsFile>(dir, DUP_FILE)?.unwrap_or_default(),
+        helve: read_yaml_file::<HelvePromptsFile>(dir, HELVE_FILE)?.unwrap_or_default(),
+        locales: HashMap::new(),
+    })
+}
+
+/// Reads localized variants from subdirectories whose name is a locale tag (others, such as
+/// `experimental/`, are skipped).
+fn read_locales(base: &Path) -> Result<HashMap<String, super::resolve::AgentPrompts>, LoadError> {
+    let mut locales = HashMap::new();
+    let Ok(entries) = std::fs::read_dir(base) else {
+        return Ok(locales);
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let Some(tag) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(normalize_locale)
+        else {
+            continue;
+        };
+        locales.insert(tag, read_prompt_files(&path)?);
+    }
+    Ok(locales)
+}
+
 /// Loads prompts from a directory: reads `react.yaml`, `tot.yaml`, `got.yaml`, `dup.yaml`, `helve.yaml`,
-/// and returns an [`AgentPrompts`](super::resolve::AgentPrompts).
+/// and returns an [`AgentPrompts`](super::resolve::AgentPrompts). The same files under
+/// `<dir>/<locale>/` become [`AgentPrompts::locales`](super::resolve::AgentPrompts::locales).
 ///
 /// If `dir` is `None`, uses `PROMPTS_DIR` env or default `./prompts`. Missing files are ignored
 /// (that pattern keeps code defaults). Only returns error when the directory is required but missing,
@@ -90,19 +130,9 @@ pub fn load(dir: Option<&Path>) -> Result<super::resolve::AgentPrompts, LoadErro
         return Err(LoadError::DirNotFound(base.display().to_string()));
     }
 
-    let react = read_yaml_file::<ReactPromptsFile>(&base, REACT_FILE)?.unwrap_or_default();
-    let tot = read_yaml_file::<TotPromptsFile>(&base, TOT_FILE)?.unwrap_or_default();
-    let got = read_yaml_file::<GotPromptsFile>(&base, GOT_FILE)?.unwrap_or_default();
-    let dup = read_yaml_file::<DupPromptsFile>(&base, DUP_FILE)?.unwrap_or_default();
-    let helve = read_yaml_file::<HelvePromptsFile>(&base, HELVE_FILE)?.unwrap_or_default();
-
-    Ok(super::resolve::AgentPrompts {
-        react,
-        tot,
-        got,
-        dup,
-        helve,
-    })
+    let mut prompts = read_prompt_files(&base)?;
+    prompts.locales = read_locales(&base)?;
+    Ok(prompts)
 }
 
 /// Returns default prompts by parsing the embedded YAML in `loom/prompts/*.yaml`.
@@ -121,6 +151,7 @@ pub fn default_from_embedded() -> super::resolve::AgentPrompts {
         got,
         dup,
         helve,
+        locales: HashMap::new(),
     }
 }
 
@@ -186,6 +217,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn load_reads_locale_subdirectories() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dir = temp.path();
+        std::fs::create_dir(dir.join("zh_CN")).unwrap();
+        std::fs::create_dir(dir.join("experimental")).unwrap();
+        std::fs::write(
+            dir.join("zh_CN").join("react.yaml"),
+            "system_prompt: \"中文\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("experimental").join("react.yaml"),
+            "system_prompt: x\n",
+        )
+        .unwrap();
+        let p = load(Some(dir)).unwrap();
+        assert_eq!(p.locales.len(), 1);
+        let zh = p.for_locale("zh-CN").unwrap();
+        assert_eq!(zh.react.system_prompt.as_deref(), Some("中文"));
+        assert!(p.react.system_prompt.is_none());
+    }
+
     #[test]
     fn load_missing_files_are_ignored() {
         let temp = tempfile::TempDir::new().unwrap();
@@ -0,0 +1,98 @@
+//! Locale tags for localized prompt variants.
+//!
+//! A run's locale comes from the request (`RunRequest.locale`, [`RunOptions::locale`](crate::RunOptions)),
+//! else [`ENV_LOCALE`], else [`detect_locale`] on the user message. It selects a variant via
+//! [`AgentPrompts::for_locale`](super::AgentPrompts::for_locale).
+
+/// Env var: default locale tag for runs that do not set one (e.g. `zh-CN`).
+pub const ENV_LOCALE: &str = "LOOM_LOCALE";
+
+/// Normalizes a locale tag to lowercase with `-` separators, dropping any encoding suffix:
+/// `zh_CN.UTF-8` → `zh-cn`. Returns `None` unless the language subtag is 2–3 ASCII letters
+/// (so `C`, `POSIX` and plain directory names are not locales).
+pub fn normalize_locale(tag: &str) -> Option<String> {
+    let tag = tag.trim().split(['.', '@']).next()?;
+    let tag = tag.replace('_', "-").to_ascii_lowercase();
+    let language = tag.split('-').next()?;
+    let is_language =
+        (2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_alphabetic());
+    is_language.then_some(tag)
+}
+
+/// Guesses a locale from the script of `text`. Returns `None` for Latin-script text, which
+/// does not identify a language; any kana makes CJK text Japanese.
+pub fn detect_locale(text: &str) -> Option<&'static str> {
+    const SCRIPTS: &[(&str, &[(char, char)])] = &[
+        ("ja", &[('\u{3040}', '\u{30ff}')]),
+        ("ko", &[('\u{1100}', '\u{11ff}'), ('\u{ac00}', '\u{d7af}')]),
+        ("zh", &[('\u{3400}', '\u{4dbf}'), ('\u{4e00}', '\u{9fff}')]),
+        ("ru", &[('\u{0400}', '\u{04ff}')]),
+        ("el", &[('\u{0370}', '\u{03ff}')]),
+        ("ar", &[('\u{0600}', '\u{06ff}')]),
+        ("he", &[('\u{0590}', '\u{05ff}')]),
+        ("th", &[('\u{0e00}', '\u{0e7f}')]),
+    ];
+    let mut counts = [0usize; SCRIPTS.len()];
+    let mut letters = 0usize;
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        letters += 1;
+        if let Some(i) = SCRIPTS
+            .iter()
+            .position(|(_, ranges)| ranges.iter().any(|(lo, hi)| (*lo..=*hi).contains(&c)))
+        {
+            counts[i] += 1;
+        }
+    }
+    if counts[0] > 0 && counts[2] > 0 {
+        return Some("ja");
+    }
+    let (i, &count) = counts.iter().enumerate().max_by_key(|(_, n)| **n)?;
+    // Code, paths and identifiers are Latin; a quarter of the letters is enough.
+    (count > 0 && count * 4 >= letters).then_some(SCRIPTS[i].0)
+}
+
+/// Locale for a run: `explicit`, else [`ENV_LOCALE`], else [`detect_locale`] on `text`.
+pub fn resolve_locale(explicit: Option<&str>, text: &str) -> Option<String> {
+    explicit
+        .and_then(normalize_locale)
+        .or_else(|| {
+            std::env::var(ENV_LOCALE)
+                .ok()
+                .and_then(|s| normalize_locale(&s))
+        })
+        .or_else(|| detect_locale(text).map(str::to_string))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_locale_accepts_language_tags_only() {
+        assert_eq!(normalize_locale("zh_CN.UTF-8").as_deref(), Some("zh-cn"));
+        assert_eq!(normalize_locale(" pt-BR ").as_deref(), Some("pt-br"));
+        assert_eq!(normalize_locale("ja").as_deref(), Some("ja"));
+        assert_eq!(normalize_locale("C"), None);
+        assert_eq!(normalize_locale("POSIX"), None);
+        assert_eq!(normalize_locale("experimental"), None);
+    }
+
+    #[test]
+    fn detect_locale_uses_dominant_non_latin_script() {
+        assert_eq!(detect_locale("帮我修复 main.rs 里的 bug"), Some("zh"));
+        assert_eq!(detect_locale("このファイルを読んで"), Some("ja"));
+        assert_eq!(detect_locale("파일을 읽어줘"), Some("ko"));
+        assert_eq!(detect_locale("Прочитай файл"), Some("ru"));
+        assert_eq!(detect_locale("Read the README please"), None);
+        assert_eq!(detect_locale("rename get_user_by_id to 用户"), None);
+        assert_eq!(detect_locale("123 !?"), None);
+    }
+
+    #[test]
+    fn explicit_locale_wins_over_detection() {
+        assert_eq!(
+            resolve_locale(Some("en_US"), "帮我看看").as_deref(),
+            Some("en-us")
+        );
+    }
+}
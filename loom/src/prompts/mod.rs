@@ -1,15 +1,18 @@
 //! Agent prompts loaded from YAML files by directory (optional override for in-code defaults).
 //!
-//! See [`AgentPrompts`] and [`load`].
+//! See [`AgentPrompts`] and [`load`]. Localized variants live in `<dir>/<locale>/` and are
+//! selected per run with [`resolve_locale`] and [`AgentPrompts::for_locale`].
 //! Interacts with [`ReactBuildConfig`](crate::agent::react::ReactBuildConfig), [`assemble_system_prompt`](crate::helve::assemble_system_prompt),
 //! and runners that use system/prompt strings (ReAct, ToT, GoT, DUP, Helve).
 
 mod load;
+mod locale;
 mod resolve;
 
 use serde::Deserialize;
 
 pub use load::{default_from_embedded, load, load_or_default, LoadError};
+pub use locale::{detect_locale, normalize_locale, resolve_locale, ENV_LOCALE};
 pub use resolve::AgentPrompts;
 
 /// Per-file YAML shape for `prompts/react.yaml`. All keys optional.
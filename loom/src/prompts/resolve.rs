@@ -4,10 +4,13 @@
 //! getters resolve from loaded values to code defaults. ReAct prompt assembly now lives
 //! in the single main assembler path under [`crate::helve`].
 
+use std::collections::HashMap;
+
 use crate::agent::dup::DUP_UNDERSTAND_PROMPT;
 use crate::agent::got::{AGOT_EXPAND_SYSTEM, GOT_PLAN_SYSTEM};
 use crate::agent::tot::{TOT_EXPAND_SYSTEM_ADDON, TOT_RESEARCH_QUALITY_ADDON};
 
+use super::locale::normalize_locale;
 use super::{DupPromptsFile, GotPromptsFile, HelvePromptsFile, ReactPromptsFile, TotPromptsFile};
 
 /// Loaded YAML prompt materials for all agent patterns.
@@ -21,9 +24,21 @@ pub struct AgentPrompts {
     pub got: GotPromptsFile,
     pub dup: DupPromptsFile,
     pub helve: HelvePromptsFile,
+    /// Localized variants keyed by normalized locale tag (`zh`, `pt-br`), loaded from
+    /// `<dir>/<locale>/*.yaml`. Keys a variant leaves unset keep the built-in text.
+    pub locales: HashMap<String, AgentPrompts>,
 }
 
 impl AgentPrompts {
+    /// Localized variant for `locale`: the exact tag first, then its language (`zh-tw` → `zh`).
+    pub fn for_locale(&self, locale: &str) -> Option<&AgentPrompts> {
+        let tag = normalize_locale(locale)?;
+        self.locales.get(&tag).or_else(|| {
+            let language = tag.split('-').next()?;
+            self.locales.get(language)
+        })
+    }
+
     /// ToT expand node system addon.
     pub fn tot_expand_system_addon(&self) -> String {
         self.tot
@@ -146,6 +161,22 @@ mod tests {
         assert_eq!(p.helve_approval_always(), "ask always");
     }
 
+    #[test]
+    fn for_locale_falls_back_to_language() {
+        let mut zh = AgentPrompts::default();
+        zh.react.system_prompt = Some("请用中文回答。".to_string());
+        let mut p = AgentPrompts::default();
+        p.locales.insert("zh".to_string(), zh);
+
+        let variant = p.for_locale("zh_TW").expect("zh variant");
+        assert_eq!(
+            variant.react.system_prompt.as_deref(),
+            Some("请用中文回答。")
+        );
+        assert!(p.for_locale("ja").is_none());
+        assert!(p.for_locale("C").is_none());
+    }
+
     #[test]
     fn helve_defaults_include_expected_placeholders_and_guidance() {
         let p = AgentPrompts::default();
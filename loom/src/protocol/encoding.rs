@@ -0,0 +1,215 @@
+//! Wire encodings for protocol messages, negotiated per WebSocket connection.
+//!
+//! JSON text frames are the default. A client that offers the [`SUBPROTOCOL_MSGPACK`]
+//! subprotocol (`Sec-WebSocket-Protocol`) receives every [`ServerResponse`](super::ServerResponse)
+//! as a binary MessagePack frame instead: the same serde shape (maps with field names, `type`
+//! tags), so a client decodes it into the same structure it would get from JSON. A client that
+//! offers [`SUBPROTOCOL_JSON_DEFLATE`] receives binary frames of JSON compressed with raw DEFLATE
+//! (RFC 1951, as WebSocket `permessage-deflate` does), which shrinks event streams several times
+//! on slow links. Requests from the client stay JSON in every mode.
+
+use std::io::Read;
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Subprotocol for the default JSON encoding.
+pub const SUBPROTOCOL_JSON: &str = "loom.json";
+/// Subprotocol for MessagePack-encoded server messages.
+pub const SUBPROTOCOL_MSGPACK: &str = "loom.msgpack";
+/// Subprotocol for DEFLATE-compressed JSON server messages.
+pub const SUBPROTOCOL_JSON_DEFLATE: &str = "loom.json.deflate";
+
+/// Encoding of server → client messages on one connection.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WireEncoding {
+    /// JSON text frames.
+    #[default]
+    Json,
+    /// MessagePack binary frames (named fields).
+    MessagePack,
+    /// Binary frames of raw-DEFLATE-compressed JSON.
+    DeflateJson,
+}
+
+impl WireEncoding {
+    /// Encoding for the subprotocol the server accepted; JSON when none (or an unknown one) was.
+    pub fn from_subprotocol(protocol: Option<&str>) -> Self {
+        match protocol {
+            Some(SUBPROTOCOL_MSGPACK) => WireEncoding::MessagePack,
+            Some(SUBPROTOCOL_JSON_DEFLATE) => WireEncoding::DeflateJson,
+            _ => WireEncoding::Json,
+        }
+    }
+
+    /// Subprotocol name that selects this encoding.
+    pub fn subprotocol(self) -> &'static str {
+        match self {
+            WireEncoding::Json => SUBPROTOCOL_JSON,
+            WireEncoding::MessagePack => SUBPROTOCOL_MSGPACK,
+            WireEncoding::DeflateJson => SUBPROTOCOL_JSON_DEFLATE,
+        }
+    }
+}
+
+/// One encoded message, ready to be sent as a text or binary frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EncodedFrame {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+/// Error from encoding or decoding a protocol message.
+#[derive(Debug, thiserror::Error)]
+pub enum EncodingError {
+    #[error("json: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("msgpack encode: {0}")]
+    MessagePackEncode(#[from] rmp_serde::encode::Error),
+    #[error("msgpack decode: {0}")]
+    MessagePackDecode(#[from] rmp_serde::decode::Error),
+    #[error("deflate: {0}")]
+    Deflate(#[from] std::io::Error),
+}
+
+/// Encodes `value` for the wire.
+pub fn encode<T: Serialize>(
+    value: &T,
+    encoding: WireEncoding,
+) -> Result<EncodedFrame, EncodingError> {
+    match encoding {
+        WireEncoding::Json => Ok(EncodedFrame::Text(serde_json::to_string(value)?)),
+        WireEncoding::MessagePack => Ok(EncodedFrame::Binary(rmp_serde::to_vec_named(value)?)),
+        WireEncoding::DeflateJson => {
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::fast());
+            serde_json::to_writer(&mut encoder, value)?;
+            Ok(EncodedFrame::Binary(encoder.finish()?))
+        }
+    }
+}
+
+/// Decodes a MessagePack frame produced by [`encode`] with [`WireEncoding::MessagePack`].
+pub fn decode_msgpack<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, EncodingError> {
+    Ok(rmp_serde::from_slice(bytes)?)
+}
+
+/// Decodes a compressed frame produced by [`encode`] with [`WireEncoding::DeflateJson`].
+pub fn decode_deflate_json<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, EncodingError> {
+    let mut json = Vec::new();
+    DeflateDecoder::new(bytes).read_to_end(&mut json)?;
+    Ok(serde_json::from_slice(&json)?)
+}
+
+/// Decodes a binary frame received on a connection with `encoding`.
+pub fn decode_binary<T: DeserializeOwned>(
+    bytes: &[u8],
+    encoding: WireEncoding,
+) -> Result<T, EncodingError> {
+    match encoding {
+        WireEncoding::DeflateJson => decode_deflate_json(bytes),
+        WireEncoding::Json | WireEncoding::MessagePack => decode_msgpack(bytes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{ProtocolEventEnvelope, RunStreamEventResponse, ServerResponse};
+    use stream_event::ProtocolEvent;
+
+    fn stream_event_response() -> ServerResponse {
+        let event = ProtocolEventEnvelope {
+            session_id: Some("s1".to_string()),
+            node_id: None,
+            event_id: Some(7),
+            prev_event_id: None,
+            event: ProtocolEvent::MessageChunk {
+                content: "hello".to_string(),
+                id: "think".to_string(),
+                revision: 0,
+            },
+        };
+        ServerResponse::RunStreamEvent(RunStreamEventResponse {
+            id: "run-1".to_string(),
+            event,
+        })
+    }
+
+    #[test]
+    fn encoding_from_subprotocol_defaults_to_json() {
+        assert_eq!(WireEncoding::from_subprotocol(None), WireEncoding::Json);
+        assert_eq!(
+            WireEncoding::from_subprotocol(Some("unknown")),
+            WireEncoding::Json
+        );
+        assert_eq!(
+            WireEncoding::from_subprotocol(Some(SUBPROTOCOL_MSGPACK)),
+            WireEncoding::MessagePack
+        );
+        assert_eq!(WireEncoding::MessagePack.subprotocol(), SUBPROTOCOL_MSGPACK);
+        assert_eq!(
+            WireEncoding::from_subprotocol(Some(SUBPROTOCOL_JSON_DEFLATE)),
+            WireEncoding::DeflateJson
+        );
+    }
+
+    #[test]
+    fn deflate_frame_decodes_to_the_json_value() {
+        let mut response = stream_event_response();
+        if let ServerResponse::RunStreamEvent(r) = &mut response {
+            r.event.event = ProtocolEvent::MessageChunk {
+                content: "the same sentence again. ".repeat(40),
+                id: "think".to_string(),
+                revision: 0,
+            };
+        }
+        let json = match encode(&response, WireEncoding::Json).unwrap() {
+            EncodedFrame::Text(t) => t,
+            other => panic!("expected text frame, got {:?}", other),
+        };
+        let bytes = match encode(&response, WireEncoding::DeflateJson).unwrap() {
+            EncodedFrame::Binary(b) => b,
+            other => panic!("expected binary frame, got {:?}", other),
+        };
+        assert!(
+            bytes.len() * 4 < json.len(),
+            "{} vs {}",
+            bytes.len(),
+            json.len()
+        );
+
+        let inflated: serde_json::Value = decode_binary(&bytes, WireEncoding::DeflateJson).unwrap();
+        let from_json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(inflated, from_json);
+        assert!(decode_deflate_json::<ServerResponse>(b"not deflate").is_err());
+    }
+
+    #[test]
+    fn msgpack_frame_decodes_to_the_same_shape_as_json() {
+        let response = stream_event_response();
+        let json = match encode(&response, WireEncoding::Json).unwrap() {
+            EncodedFrame::Text(t) => t,
+            other => panic!("expected text frame, got {:?}", other),
+        };
+        let bytes = match encode(&response, WireEncoding::MessagePack).unwrap() {
+            EncodedFrame::Binary(b) => b,
+            other => panic!("expected binary frame, got {:?}", other),
+        };
+        assert!(bytes.len() < json.len());
+
+        let from_msgpack: serde_json::Value = decode_msgpack(&bytes).unwrap();
+        let from_json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(from_msgpack, from_json);
+
+        match decode_msgpack::<ServerResponse>(&bytes).unwrap() {
+            ServerResponse::RunStreamEvent(r) => {
+                assert_eq!(r.id, "run-1");
+                assert_eq!(r.event.event_id, Some(7));
+            }
+            other => panic!("expected run_stream_event, got {:?}", other),
+        }
+    }
+}
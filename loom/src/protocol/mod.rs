@@ -3,6 +3,10 @@
 //! - **WebSocket**: CLI remote mode request/response types. Aligned with [DESIGN_CLI_REMOTE_MODE]
 //!   §2.3 (requests) and §2.4 (responses), and with [EXPORT_SPEC] / [USER_GUIDELINE].
 //! - **Stream**: Streaming output protocol (type + payload, envelope) per [protocol_spec].
+//! - **Encoding**: JSON by default; MessagePack or DEFLATE-compressed JSON for server messages
+//!   when negotiated via the `loom.msgpack` / `loom.json.deflate` WebSocket subprotocols
+//!   ([`encoding`]).
+//! - **Schema**: JSON Schema of all of the above for client codegen ([`schema::protocol_schema`]).
 //!
 //! ## Architecture
 //!
@@ -20,6 +24,9 @@
 //! │     UserMessages(UserMessagesRequest)        UserMessages(UserMessagesResponse)  │
 //! │     AgentList(AgentListRequest)              AgentList(AgentListResponse)     │
 //! │     Ping(PingRequest)                        ToolShow(ToolShowResponse)       │
+//! │     StateShow(StateShowRequest)              StateShow(StateShowResponse)     │
+//! │     CheckpointList(CheckpointListRequest)    CheckpointList(CheckpointListResponse) │
+//! │     ApprovalsList(ApprovalsListRequest)      ApprovalsList(ApprovalsListResponse) │
 //! │                                              Pong(PongResponse)              │
 //! │                                              Error(ErrorResponse)             │
 //! │                                                                              │
@@ -43,28 +50,45 @@
 //! [USER_GUIDELINE]: https://github.com/loom/loom/blob/main/docs/USER_GUIDELINE.md
 //! [protocol_spec]: https://github.com/loom/loom/blob/main/docs/protocol_spec.md
 
+pub mod encoding;
 pub mod envelope_state;
 pub mod requests;
 pub mod responses;
+pub mod schema;
 pub mod stream;
 pub mod types;
 
 // Re-export sub-module types for convenience
+pub use encoding::{
+    EncodedFrame, EncodingError, WireEncoding, SUBPROTOCOL_JSON, SUBPROTOCOL_JSON_DEFLATE,
+    SUBPROTOCOL_MSGPACK,
+};
 pub use envelope_state::EnvelopeState;
+pub use schema::protocol_schema;
 pub use stream_event::ProtocolEvent;
 
 // Re-export types from sub-modules
 pub use requests::{
-    AgentIdentifier, AgentListRequest, AgentSourceFilter, AgentType, ClientRequest,
-    ListModelsRequest, PingRequest, RunRequest, SetModelRequest, ToolShowOutput, ToolShowRequest,
-    ToolsListRequest, UserMessagesRequest, WorkspaceCreateRequest, WorkspaceListRequest,
-    WorkspaceThreadAddRequest, WorkspaceThreadListRequest, WorkspaceThreadRemoveRequest,
+    ActiveRunsRequest, AdminReloadRequest, AgentIdentifier, AgentListRequest, AgentSourceFilter,
+    AgentType, ApprovalsListRequest, CheckpointListRequest, ClientRequest, EventSchemaListRequest,
+    ListModelsRequest, PingRequest, ResumeRunRequest, RunInspectRequest, RunKillRequest,
+    RunRequest, SessionEndRequest, SessionMessageRequest, SessionStartRequest, SetModelRequest,
+    StateShowRequest, StopGenerationRequest, ToolShowOutput, ToolShowRequest, ToolsListRequest,
+    UsageReportRequest, UserMessagesDeleteRequest, UserMessagesRequest, WorkspaceCreateRequest,
+    WorkspaceDefaults, WorkspaceListRequest, WorkspaceThreadAddRequest, WorkspaceThreadListRequest,
+    WorkspaceThreadRemoveRequest, WorkspaceUpdateRequest,
 };
 pub use responses::{
-    AgentListResponse, AgentSource, AgentSummary, ErrorResponse, ListModelsResponse, PongResponse,
-    ProtocolEventEnvelope, RunEndResponse, RunStreamEventResponse, ServerResponse,
-    SetModelResponse, ThreadInWorkspace, ToolShowResponse, ToolsListResponse, UserMessageItem,
-    UserMessagesResponse, WorkspaceCreateResponse, WorkspaceListResponse, WorkspaceMeta,
-    WorkspaceThreadAddResponse, WorkspaceThreadListResponse, WorkspaceThreadRemoveResponse,
+    ActiveRunInfo, ActiveRunsResponse, AdminReloadResponse, AgentListResponse, AgentSource,
+    AgentSummary, ApprovalsListResponse, CheckpointListResponse, CheckpointSummary, ErrorResponse,
+    EventSchemaListResponse, ListModelsResponse, PongResponse, ProtocolEventEnvelope,
+    RunEndResponse, RunInspectResponse, RunKillResponse, RunStreamEventResponse, RunTiming,
+    ServerResponse, SessionEndResponse, SessionStartResponse, SetModelResponse, StateShowResponse,
+    StopGenerationResponse, ThreadInWorkspace, ToolCallRecord, ToolCallStatus, ToolShowResponse,
+    ToolsListResponse, UsageReportResponse, UsageReportRow, UserMessageItem,
+    UserMessagesDeleteResponse, UserMessagesResponse, WorkspaceCreateResponse,
+    WorkspaceListResponse, WorkspaceMeta, WorkspaceThreadAddResponse, WorkspaceThreadListResponse,
+    WorkspaceThreadRemoveResponse, WorkspaceUpdateResponse, ERROR_CODE_PAYLOAD_TOO_LARGE,
+    ERROR_CODE_UNAUTHORIZED,
 };
 pub use types::{AgentSource as AgentSourceExport, AgentSourceFilter as AgentSourceFilterExport};
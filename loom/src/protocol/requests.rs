@@ -1,10 +1,13 @@
 //! WebSocket request types (client → server).
 
+use std::collections::HashMap;
+
 use crate::message::UserContent;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Agent type for run requests.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum AgentType {
     React,
@@ -13,7 +16,7 @@ pub enum AgentType {
     Got,
 }
 /// Agent identifier - can be a builtin AgentType or a custom agent name
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum AgentIdentifier {
     /// Builtin agent type
@@ -32,10 +35,12 @@ impl std::fmt::Display for AgentIdentifier {
 }
 
 /// Run request: execute one Agent run (streaming events + final RunEnd).
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct RunRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
+    /// The user message to answer. May be omitted when `messages` is set.
+    #[serde(default)]
     pub message: UserContent,
     pub agent: AgentIdentifier,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -52,9 +57,54 @@ pub struct RunRequest {
     /// Model to use for this run (e.g. "openai/gpt-4o", "gpt-4o").
     #[serde(skip_serializing_if = "Option::is_none")]
     pub model: Option<String>,
+    /// Required format of the final reply: `markdown`, `plain` or `json`. JSON replies are
+    /// validated (and retried once) before `run_end`; see [`crate::ReplyFormat`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_format: Option<crate::ReplyFormat>,
+    /// JSON Schema for the reply when `reply_format` is `json`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_schema: Option<serde_json::Value>,
+    /// Full `system` / `user` / `assistant` history managed by the client. When set, the run
+    /// starts from it instead of the checkpointed thread and answers its last (user) message;
+    /// see [`crate::HistoryMessage`] for the rules and size limits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Vec<crate::HistoryMessage>>,
+    /// When true, the run may only read: shell and file-modifying tools are not available and
+    /// calls to them are refused. A server running in read-only mode ignores `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only: Option<bool>,
+    /// Locale tag (e.g. `zh-CN`) selecting localized prompt variants; when omitted it is
+    /// guessed from the message (see [`crate::prompts::detect_locale`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// When true, the user message is pinned: compaction and history trimming never drop it
+    /// (e.g. the task spec or key constraints). See [`crate::Message::is_pinned`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pin: Option<bool>,
+    /// Feature flags for this run (e.g. `{"verify_node": true}`), layered over the server's
+    /// `LOOM_FLAGS`. Nodes, middleware and tools branch on them; `run_end` echoes them back.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub flags: HashMap<String, bool>,
 }
 
 impl RunRequest {
+    /// The user message this run answers: the last `messages` entry when a history is
+    /// supplied, otherwise `message`.
+    pub fn user_message(&self) -> &UserContent {
+        self.messages
+            .as_ref()
+            .and_then(|m| m.last())
+            .map_or(&self.message, |m| &m.content)
+    }
+
+    /// Mutable [`Self::user_message`], e.g. to normalize it before the run.
+    pub fn user_message_mut(&mut self) -> &mut UserContent {
+        match self.messages.as_mut().and_then(|m| m.last_mut()) {
+            Some(last) => &mut last.content,
+            None => &mut self.message,
+        }
+    }
+
     /// Validates that the message modalities are supported by the given model.
     pub fn validate_modalities(
         &self,
@@ -87,7 +137,7 @@ impl RunRequest {
 }
 
 /// Tool list request: list all available tools.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ToolsListRequest {
     pub id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -97,7 +147,7 @@ pub struct ToolsListRequest {
 }
 
 /// Output format for tool_show.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ToolShowOutput {
     Json,
@@ -105,7 +155,7 @@ pub enum ToolShowOutput {
 }
 
 /// Tool show request: get a single tool definition.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ToolShowRequest {
     pub id: String,
     pub name: String,
@@ -118,19 +168,26 @@ pub struct ToolShowRequest {
 }
 
 /// Ping request: health / keepalive.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct PingRequest {
     pub id: String,
 }
 
+/// Event schema list request: list the registered custom event types and their JSON schemas
+/// (see [`crate::stream::register_custom_event`]).
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct EventSchemaListRequest {
+    pub id: String,
+}
+
 /// List models request: list available models.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ListModelsRequest {
     pub id: String,
 }
 
 /// Set model request: set model for a session.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct SetModelRequest {
     pub id: String,
     pub model_id: String,
@@ -139,7 +196,7 @@ pub struct SetModelRequest {
 }
 
 /// User messages list request: list stored messages for a thread (pagination).
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct UserMessagesRequest {
     pub id: String,
     pub thread_id: String,
@@ -147,10 +204,21 @@ pub struct UserMessagesRequest {
     pub before: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u32>,
+    /// When set, full-text search of the thread's user and assistant messages instead of a
+    /// listing: best match first, `before` ignored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+}
+
+/// User messages delete request: delete all stored messages of a thread.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct UserMessagesDeleteRequest {
+    pub id: String,
+    pub thread_id: String,
 }
 
 /// Filter for agent list by source type.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum AgentSourceFilter {
     BuiltIn,
@@ -159,7 +227,7 @@ pub enum AgentSourceFilter {
 }
 
 /// Agent list request: list available agent profiles.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct AgentListRequest {
     pub id: String,
     /// Optional filter by agent source (built-in, project, user).
@@ -171,46 +239,307 @@ pub struct AgentListRequest {
     pub thread_id: Option<String>,
 }
 
+/// State show request: return the latest checkpointed state of a thread, or the checkpoint
+/// `checkpoint_id` (ids come from `checkpoint_list`).
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct StateShowRequest {
+    pub id: String,
+    pub thread_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint_id: Option<String>,
+}
+
+/// Checkpoint list request: checkpoint metadata of a thread, oldest first. The optional filters
+/// narrow the list; `limit` keeps only the newest matches.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct CheckpointListRequest {
+    pub id: String,
+    pub thread_id: String,
+    /// Only checkpoints written after this node ran.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_step: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_step: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
 /// Cancel run request: cancel a running agent.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct CancelRunRequest {
     pub id: String,
     pub run_id: String,
 }
 
+/// Stop generation request: end the running LLM call of a run early, keeping what was generated
+/// so far as the answer (chat UI "stop" button). Unlike `cancel_run`, the run itself finishes
+/// normally.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct StopGenerationRequest {
+    pub id: String,
+    pub run_id: String,
+}
+
+/// Resume run request: follow a run whose connection dropped. The server replays the run's
+/// events after `after_event_id` (all it kept when omitted) as `run_stream_event`s with the run
+/// id, then streams the rest of the run and its `run_end` or `error`, as for `run`.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ResumeRunRequest {
+    pub id: String,
+    pub run_id: String,
+    /// Last `event_id` the client received.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after_event_id: Option<u64>,
+}
+
+/// Admin reload request: re-read server configuration (config files, run settings, role file,
+/// tool allowlist) without dropping connections. `token` must match the server's admin token.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AdminReloadRequest {
+    pub id: String,
+    pub token: String,
+}
+
+/// Redacts `token` so logging a request never prints the admin token.
+impl std::fmt::Debug for AdminReloadRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdminReloadRequest")
+            .field("id", &self.id)
+            .field("token", &"***")
+            .finish()
+    }
+}
+
+/// Active runs request (admin): list the runs in progress on the server, whichever connection
+/// started them. `token` must match the server's admin token.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ActiveRunsRequest {
+    pub id: String,
+    pub token: String,
+}
+
+/// Run inspect request (admin): current node, elapsed time and usage so far of run `run_id`.
+/// `token` must match the server's admin token.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RunInspectRequest {
+    pub id: String,
+    pub token: String,
+    pub run_id: String,
+}
+
+/// Run kill request (admin): cancel run `run_id`, whichever connection started it. The run's
+/// client gets a `cancel_run` acknowledgment and the run ends as if it had cancelled it.
+/// `token` must match the server's admin token.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RunKillRequest {
+    pub id: String,
+    pub token: String,
+    pub run_id: String,
+}
+
+/// Redacts `token` so logging a request never prints the admin token.
+impl std::fmt::Debug for ActiveRunsRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ActiveRunsRequest")
+            .field("id", &self.id)
+            .field("token", &"***")
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for RunInspectRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RunInspectRequest")
+            .field("id", &self.id)
+            .field("token", &"***")
+            .field("run_id", &self.run_id)
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for RunKillRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RunKillRequest")
+            .field("id", &self.id)
+            .field("token", &"***")
+            .field("run_id", &self.run_id)
+            .finish()
+    }
+}
+
+fn default_session_agent() -> AgentIdentifier {
+    AgentIdentifier::Type(AgentType::React)
+}
+
+/// Session start request: opens a server-managed conversation on a new thread. Later
+/// `session_message` requests run on that thread with these settings, so the client never
+/// tracks thread ids. Omitted settings fall back to the workspace and server defaults, as for
+/// `run`.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SessionStartRequest {
+    pub id: String,
+    /// Agent of every run in the session; default `react`.
+    #[serde(default = "default_session_agent")]
+    pub agent: AgentIdentifier,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_folder: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+}
+
+/// Session message request: runs `message` on the session's thread. The reply streams as for
+/// `run` (`run_stream_event`s, then `run_end`).
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SessionMessageRequest {
+    pub id: String,
+    pub session_id: String,
+    pub message: UserContent,
+}
+
+/// Session end request: closes the session. The thread and its history stay; `compact`
+/// replaces older messages of the thread with a summary, `title` generates the thread's title
+/// and summary (stored with the workspace thread).
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SessionEndRequest {
+    pub id: String,
+    pub session_id: String,
+    #[serde(default)]
+    pub compact: bool,
+    #[serde(default)]
+    pub title: bool,
+}
+
 /// Client-to-server request envelope.
 ///
 /// Each variant maps to a JSON object with `"type": "<variant_name>"`.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ClientRequest {
     Run(RunRequest),
     ToolsList(ToolsListRequest),
     ToolShow(ToolShowRequest),
     UserMessages(UserMessagesRequest),
+    UserMessagesDelete(UserMessagesDeleteRequest),
     AgentList(AgentListRequest),
     WorkspaceList(WorkspaceListRequest),
     WorkspaceCreate(WorkspaceCreateRequest),
     WorkspaceThreadList(WorkspaceThreadListRequest),
     WorkspaceThreadAdd(WorkspaceThreadAddRequest),
     WorkspaceThreadRemove(WorkspaceThreadRemoveRequest),
+    WorkspaceUpdate(WorkspaceUpdateRequest),
+    UsageReport(UsageReportRequest),
+    ApprovalsList(ApprovalsListRequest),
     Ping(PingRequest),
     ListModels(ListModelsRequest),
     SetModel(SetModelRequest),
     CancelRun(CancelRunRequest),
+    StateShow(StateShowRequest),
+    CheckpointList(CheckpointListRequest),
+    AdminReload(AdminReloadRequest),
+    StopGeneration(StopGenerationRequest),
+    EventSchemaList(EventSchemaListRequest),
+    ResumeRun(ResumeRunRequest),
+    ActiveRuns(ActiveRunsRequest),
+    RunInspect(RunInspectRequest),
+    RunKill(RunKillRequest),
+    SessionStart(SessionStartRequest),
+    SessionMessage(SessionMessageRequest),
+    SessionEnd(SessionEndRequest),
+}
+
+impl ClientRequest {
+    /// Wire name of the request (its `"type"`), e.g. `run` or `tools_list`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Run(_) => "run",
+            Self::ToolsList(_) => "tools_list",
+            Self::ToolShow(_) => "tool_show",
+            Self::UserMessages(_) => "user_messages",
+            Self::UserMessagesDelete(_) => "user_messages_delete",
+            Self::AgentList(_) => "agent_list",
+            Self::WorkspaceList(_) => "workspace_list",
+            Self::WorkspaceCreate(_) => "workspace_create",
+            Self::WorkspaceThreadList(_) => "workspace_thread_list",
+            Self::WorkspaceThreadAdd(_) => "workspace_thread_add",
+            Self::WorkspaceThreadRemove(_) => "workspace_thread_remove",
+            Self::WorkspaceUpdate(_) => "workspace_update",
+            Self::UsageReport(_) => "usage_report",
+            Self::ApprovalsList(_) => "approvals_list",
+            Self::Ping(_) => "ping",
+            Self::ListModels(_) => "list_models",
+            Self::SetModel(_) => "set_model",
+            Self::CancelRun(_) => "cancel_run",
+            Self::StateShow(_) => "state_show",
+            Self::CheckpointList(_) => "checkpoint_list",
+            Self::AdminReload(_) => "admin_reload",
+            Self::StopGeneration(_) => "stop_generation",
+            Self::EventSchemaList(_) => "event_schema_list",
+            Self::ResumeRun(_) => "resume_run",
+            Self::ActiveRuns(_) => "active_runs",
+            Self::RunInspect(_) => "run_inspect",
+            Self::RunKill(_) => "run_kill",
+            Self::SessionStart(_) => "session_start",
+            Self::SessionMessage(_) => "session_message",
+            Self::SessionEnd(_) => "session_end",
+        }
+    }
+
+    /// Client-chosen request id (optional only for `run`).
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            Self::Run(r) => r.id.as_deref(),
+            Self::ToolsList(r) => Some(&r.id),
+            Self::ToolShow(r) => Some(&r.id),
+            Self::UserMessages(r) => Some(&r.id),
+            Self::UserMessagesDelete(r) => Some(&r.id),
+            Self::AgentList(r) => Some(&r.id),
+            Self::WorkspaceList(r) => Some(&r.id),
+            Self::WorkspaceCreate(r) => Some(&r.id),
+            Self::WorkspaceThreadList(r) => Some(&r.id),
+            Self::WorkspaceThreadAdd(r) => Some(&r.id),
+            Self::WorkspaceThreadRemove(r) => Some(&r.id),
+            Self::WorkspaceUpdate(r) => Some(&r.id),
+            Self::UsageReport(r) => Some(&r.id),
+            Self::ApprovalsList(r) => Some(&r.id),
+            Self::Ping(r) => Some(&r.id),
+            Self::ListModels(r) => Some(&r.id),
+            Self::SetModel(r) => Some(&r.id),
+            Self::CancelRun(r) => Some(&r.id),
+            Self::StateShow(r) => Some(&r.id),
+            Self::CheckpointList(r) => Some(&r.id),
+            Self::AdminReload(r) => Some(&r.id),
+            Self::StopGeneration(r) => Some(&r.id),
+            Self::EventSchemaList(r) => Some(&r.id),
+            Self::ResumeRun(r) => Some(&r.id),
+            Self::ActiveRuns(r) => Some(&r.id),
+            Self::RunInspect(r) => Some(&r.id),
+            Self::RunKill(r) => Some(&r.id),
+            Self::SessionStart(r) => Some(&r.id),
+            Self::SessionMessage(r) => Some(&r.id),
+            Self::SessionEnd(r) => Some(&r.id),
+        }
+    }
 }
 // -----------------------------------------------------------------------------
 // Workspace requests
 // -----------------------------------------------------------------------------
 
 /// Workspace list request: list all workspaces.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct WorkspaceListRequest {
     pub id: String,
 }
 
 /// Workspace create request: create a new workspace.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct WorkspaceCreateRequest {
     pub id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -218,14 +547,14 @@ pub struct WorkspaceCreateRequest {
 }
 
 /// Workspace thread list request: list threads in a workspace.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct WorkspaceThreadListRequest {
     pub id: String,
     pub workspace_id: String,
 }
 
 /// Workspace thread add request: associate a thread with a workspace.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct WorkspaceThreadAddRequest {
     pub id: String,
     pub workspace_id: String,
@@ -233,12 +562,76 @@ pub struct WorkspaceThreadAddRequest {
 }
 
 /// Workspace thread remove request: disassociate a thread from a workspace.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct WorkspaceThreadRemoveRequest {
     pub id: String,
     pub workspace_id: String,
     pub thread_id: String,
 }
+
+/// Default run settings of a workspace. Serve applies each set field to Run requests with this
+/// `workspace_id` that leave it unset (`model`, `working_folder`) or have no own equivalent
+/// (`role`, `tools`).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct WorkspaceDefaults {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_folder: Option<String>,
+    /// Role instructions prepended to the system prompt when the agent profile has none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    /// Tool allowlist; other tools are hidden from the run. `None` allows every tool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<String>>,
+}
+
+impl WorkspaceDefaults {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Workspace update request: replace the workspace's default run settings.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct WorkspaceUpdateRequest {
+    pub id: String,
+    pub workspace_id: String,
+    #[serde(default)]
+    pub defaults: WorkspaceDefaults,
+}
+
+/// Usage report request: token usage and estimated cost of recorded runs, grouped by model and
+/// agent type. `since` / `until` are milliseconds since Unix epoch (inclusive / exclusive).
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct UsageReportRequest {
+    pub id: String,
+    /// Only runs of this workspace; all runs when omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<i64>,
+}
+
+/// Approvals list request: the approval audit trail (requests and decisions), newest first.
+/// `since` / `until` are milliseconds since Unix epoch (inclusive / exclusive).
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ApprovalsListRequest {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<i64>,
+    /// Max records (default 100, at most 1000).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+}
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -255,6 +648,13 @@ mod tests {
             got_adaptive: None,
             verbose: Some(true),
             model: None,
+            reply_format: None,
+            reply_schema: None,
+            messages: None,
+            read_only: None,
+            locale: None,
+            pin: None,
+            flags: Default::default(),
         });
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains("\"type\":\"run\""));
@@ -265,6 +665,30 @@ mod tests {
         assert!(matches!(parsed, ClientRequest::Run(_)));
     }
 
+    #[test]
+    fn request_run_with_messages_omits_message() {
+        let json = r#"{"type":"run","agent":"react","messages":[
+            {"role":"system","content":"be brief"},
+            {"role":"user","content":"hi"}]}"#;
+        let ClientRequest::Run(run) = serde_json::from_str(json).unwrap() else {
+            panic!("expected run request");
+        };
+        assert_eq!(run.message, "");
+        assert_eq!(run.messages.as_ref().map(Vec::len), Some(2));
+        assert_eq!(run.user_message(), &"hi");
+    }
+
+    #[test]
+    fn request_run_parses_flags() {
+        let json = r#"{"type":"run","agent":"react","message":"hi",
+            "flags":{"verify_node":true,"new_compaction":false}}"#;
+        let ClientRequest::Run(run) = serde_json::from_str(json).unwrap() else {
+            panic!("expected run request");
+        };
+        assert_eq!(run.flags.get("verify_node"), Some(&true));
+        assert_eq!(run.flags.get("new_compaction"), Some(&false));
+    }
+
     #[test]
     fn request_tools_list_roundtrip() {
         let req = ClientRequest::ToolsList(ToolsListRequest {
@@ -276,6 +700,8 @@ mod tests {
         assert_eq!(json, r#"{"type":"tools_list","id":"req-1"}"#);
         let parsed: ClientRequest = serde_json::from_str(&json).unwrap();
         assert!(matches!(parsed, ClientRequest::ToolsList(_)));
+        assert_eq!(parsed.kind(), "tools_list");
+        assert_eq!(parsed.id(), Some("req-1"));
     }
 
     #[test]
@@ -419,6 +845,19 @@ mod tests {
         assert!(matches!(parsed, ClientRequest::WorkspaceThreadAdd(_)));
     }
 
+    #[test]
+    fn request_user_messages_delete_roundtrip() {
+        let req = ClientRequest::UserMessagesDelete(UserMessagesDeleteRequest {
+            id: "req-umd".to_string(),
+            thread_id: "t-1".to_string(),
+        });
+        let json = serde_json::to_string(&req).unwrap();
+        assert!(json.contains("\"type\":\"user_messages_delete\""));
+        let parsed: ClientRequest = serde_json::from_str(&json).unwrap();
+        assert!(matches!(parsed, ClientRequest::UserMessagesDelete(_)));
+        assert_eq!(parsed.kind(), "user_messages_delete");
+    }
+
     #[test]
     fn request_workspace_thread_remove_roundtrip() {
         let req = ClientRequest::WorkspaceThreadRemove(WorkspaceThreadRemoveRequest {
@@ -432,6 +871,61 @@ mod tests {
         assert!(matches!(parsed, ClientRequest::WorkspaceThreadRemove(_)));
     }
 
+    #[test]
+    fn request_workspace_update_roundtrip() {
+        let req = ClientRequest::WorkspaceUpdate(WorkspaceUpdateRequest {
+            id: "req-wu".to_string(),
+            workspace_id: "ws-1".to_string(),
+            defaults: WorkspaceDefaults {
+                model: Some("gpt-4o".to_string()),
+                tools: Some(vec!["read".to_string()]),
+                ..Default::default()
+            },
+        });
+        let json = serde_json::to_string(&req).unwrap();
+        assert!(json.contains("\"type\":\"workspace_update\""));
+        assert!(!json.contains("working_folder"));
+        let parsed: ClientRequest = serde_json::from_str(&json).unwrap();
+        match parsed {
+            ClientRequest::WorkspaceUpdate(r) => {
+                assert_eq!(r.defaults.model.as_deref(), Some("gpt-4o"));
+                assert_eq!(r.defaults.tools, Some(vec!["read".to_string()]));
+            }
+            other => panic!("expected WorkspaceUpdate, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn request_usage_report_roundtrip() {
+        let json =
+            r#"{"type":"usage_report","id":"u1","workspace_id":"ws-1","since":1700000000000}"#;
+        let parsed: ClientRequest = serde_json::from_str(json).unwrap();
+        match parsed {
+            ClientRequest::UsageReport(r) => {
+                assert_eq!(r.workspace_id.as_deref(), Some("ws-1"));
+                assert_eq!(r.since, Some(1_700_000_000_000));
+                assert_eq!(r.until, None);
+            }
+            other => panic!("expected UsageReport, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn request_approvals_list_parses_optional_filters() {
+        let json = r#"{"type":"approvals_list","id":"a1","thread_id":"t-1","limit":20}"#;
+        let parsed: ClientRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.kind(), "approvals_list");
+        assert_eq!(parsed.id(), Some("a1"));
+        match parsed {
+            ClientRequest::ApprovalsList(r) => {
+                assert_eq!(r.thread_id.as_deref(), Some("t-1"));
+                assert_eq!(r.limit, Some(20));
+                assert_eq!(r.user_id, None);
+            }
+            other => panic!("expected ApprovalsList, got {:?}", other),
+        }
+    }
+
     #[test]
     fn request_list_models_roundtrip() {
         let req = ClientRequest::ListModels(ListModelsRequest {
@@ -456,4 +950,105 @@ mod tests {
         let parsed: ClientRequest = serde_json::from_str(&json).unwrap();
         assert!(matches!(parsed, ClientRequest::SetModel(_)));
     }
+
+    #[test]
+    fn request_state_show_roundtrip() {
+        let req = ClientRequest::StateShow(StateShowRequest {
+            id: "req-ss".to_string(),
+            thread_id: "t-1".to_string(),
+            checkpoint_id: None,
+        });
+        let json = serde_json::to_string(&req).unwrap();
+        assert!(json.contains("\"type\":\"state_show\""));
+        assert!(!json.contains("checkpoint_id"));
+        let parsed: ClientRequest = serde_json::from_str(&json).unwrap();
+        assert!(matches!(parsed, ClientRequest::StateShow(r) if r.thread_id == "t-1"));
+    }
+
+    #[test]
+    fn request_checkpoint_list_parses_optional_filters() {
+        let json =
+            r#"{"type":"checkpoint_list","id":"cl","thread_id":"t-1","node":"act","limit":5}"#;
+        let parsed: ClientRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.kind(), "checkpoint_list");
+        match parsed {
+            ClientRequest::CheckpointList(r) => {
+                assert_eq!(r.node.as_deref(), Some("act"));
+                assert_eq!(r.limit, Some(5));
+                assert_eq!(r.min_step, None);
+            }
+            other => panic!("expected CheckpointList, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn request_admin_reload_roundtrip() {
+        let json = r#"{"type":"admin_reload","id":"r1","token":"secret"}"#;
+        let parsed: ClientRequest = serde_json::from_str(json).unwrap();
+        assert!(matches!(parsed, ClientRequest::AdminReload(ref r) if r.token == "secret"));
+        let out = serde_json::to_string(&parsed).unwrap();
+        assert!(out.contains("\"type\":\"admin_reload\""));
+        assert!(!format!("{:?}", parsed).contains("secret"));
+    }
+
+    #[test]
+    fn request_stop_generation_roundtrip() {
+        let json = r#"{"type":"stop_generation","id":"s1","run_id":"run-1"}"#;
+        let parsed: ClientRequest = serde_json::from_str(json).unwrap();
+        assert!(matches!(parsed, ClientRequest::StopGeneration(ref r) if r.run_id == "run-1"));
+    }
+
+    #[test]
+    fn request_resume_run_roundtrip() {
+        let json = r#"{"type":"resume_run","id":"r1","run_id":"run-1","after_event_id":7}"#;
+        let parsed: ClientRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.kind(), "resume_run");
+        assert!(matches!(parsed, ClientRequest::ResumeRun(ref r)
+            if r.run_id == "run-1" && r.after_event_id == Some(7)));
+    }
+
+    #[test]
+    fn request_event_schema_list_roundtrip() {
+        let json = r#"{"type":"event_schema_list","id":"e1"}"#;
+        let parsed: ClientRequest = serde_json::from_str(json).unwrap();
+        assert!(matches!(parsed, ClientRequest::EventSchemaList(ref r) if r.id == "e1"));
+    }
+
+    #[test]
+    fn request_admin_run_requests_roundtrip() {
+        let parsed: ClientRequest =
+            serde_json::from_str(r#"{"type":"active_runs","id":"a1","token":"secret"}"#).unwrap();
+        assert_eq!(parsed.kind(), "active_runs");
+        assert!(!format!("{:?}", parsed).contains("secret"));
+        let json = r#"{"type":"run_inspect","id":"i1","token":"secret","run_id":"run-1"}"#;
+        let parsed: ClientRequest = serde_json::from_str(json).unwrap();
+        assert!(matches!(parsed, ClientRequest::RunInspect(ref r) if r.run_id == "run-1"));
+        assert!(!format!("{:?}", parsed).contains("secret"));
+        let json = r#"{"type":"run_kill","id":"k1","token":"secret","run_id":"run-1"}"#;
+        let parsed: ClientRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.id(), Some("k1"));
+        assert!(serde_json::to_string(&parsed)
+            .unwrap()
+            .contains("\"type\":\"run_kill\""));
+    }
+
+    #[test]
+    fn request_session_requests_roundtrip() {
+        let parsed: ClientRequest =
+            serde_json::from_str(r#"{"type":"session_start","id":"s1"}"#).unwrap();
+        match parsed {
+            ClientRequest::SessionStart(ref r) => {
+                assert_eq!(r.agent, AgentIdentifier::Type(AgentType::React));
+                assert!(r.model.is_none());
+            }
+            ref other => panic!("expected session_start, got {:?}", other),
+        }
+        let json = r#"{"type":"session_message","id":"m1","session_id":"sess-1","message":"hi"}"#;
+        let parsed: ClientRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.kind(), "session_message");
+        assert!(matches!(parsed, ClientRequest::SessionMessage(ref r) if r.session_id == "sess-1"));
+        let json = r#"{"type":"session_end","id":"e1","session_id":"sess-1","title":true}"#;
+        let parsed: ClientRequest = serde_json::from_str(json).unwrap();
+        assert!(matches!(parsed, ClientRequest::SessionEnd(ref r) if r.title && !r.compact));
+    }
 }
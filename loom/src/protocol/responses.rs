@@ -1,13 +1,19 @@
 //! WebSocket response types (server → client).
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::llm::LlmUsage;
+use crate::approval_audit::ApprovalAuditRecord;
+use crate::llm::{FinishReason, LlmUsage};
+use crate::memory::{CheckpointListItem, CheckpointSource};
+use crate::protocol::requests::WorkspaceDefaults;
+use crate::state::AnswerConfidence;
+use crate::stream::CustomEventSchema;
 use crate::tool_source::ToolSpec;
 use stream_event::ProtocolEvent;
 
 /// Typed protocol stream event payload with optional envelope fields.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ProtocolEventEnvelope {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub session_id: Option<String>,
@@ -15,6 +21,10 @@ pub struct ProtocolEventEnvelope {
     pub node_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub event_id: Option<u64>,
+    /// Id of the previous event of the session; a mismatch with the last id seen means events
+    /// were dropped in between.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_event_id: Option<u64>,
     #[serde(flatten)]
     pub event: ProtocolEvent,
 }
@@ -32,14 +42,14 @@ impl ProtocolEventEnvelope {
 }
 
 /// Protocol event stream response.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct RunStreamEventResponse {
     pub id: String,
     pub event: ProtocolEventEnvelope,
 }
 
 /// Run end response: final event after a successful run.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct RunEndResponse {
     pub id: String,
     pub reply: String,
@@ -49,23 +59,77 @@ pub struct RunEndResponse {
     pub usage: Option<LlmUsage>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub total_usage: Option<LlmUsage>,
+    /// Why the last LLM completion stopped (`stop`, `length`, `tool_calls`, `content_filter`, ...).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finish_reason: Option<FinishReason>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub session_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub node_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub event_id: Option<u64>,
+    /// Tool calls made during the run, in order; absent when the run called no tools.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcript: Option<Vec<ToolCallRecord>>,
+    /// Timing totals of the run; absent when no timing events were recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timing: Option<RunTiming>,
+    /// Feature flags the run was built with (request `flags` over `LOOM_FLAGS`), so traces
+    /// can be split by experiment arm; absent when no flags were set.
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub flags: std::collections::BTreeMap<String, bool>,
+    /// Confidence in the reply, when the server scores answers (`LOOM_CONFIDENCE_SCORING`);
+    /// low scores can be routed to human review.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<AnswerConfidence>,
+}
+
+/// Timing totals of a run, summed from its `timing` stream events. All values in milliseconds.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct RunTiming {
+    /// Time to the first LLM token of the run's first streamed think call.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_token_ms: Option<u64>,
+    /// Think step work before LLM calls (tool selection, context check, compaction).
+    pub prompt_ms: u64,
+    pub llm_ms: u64,
+    pub tool_ms: u64,
+    /// Total run time per node id.
+    pub nodes: std::collections::BTreeMap<String, u64>,
+}
+
+/// Outcome of one tool call in a [`RunEndResponse::transcript`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCallStatus {
+    Ok,
+    Error,
+    /// Started (or requested) but no result was seen, e.g. the run was cancelled.
+    Pending,
+}
+
+/// One entry of the compact tool-call transcript.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ToolCallRecord {
+    pub tool: String,
+    /// Compact single-line JSON of the arguments, truncated.
+    pub args_digest: String,
+    pub status: ToolCallStatus,
+    /// Wall time from tool start to result, in milliseconds.
+    pub duration_ms: u64,
+    /// Start of the (display) result, truncated.
+    pub result_preview: String,
 }
 
 /// Tool list response: all available tools.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ToolsListResponse {
     pub id: String,
     pub tools: Vec<ToolSpec>,
 }
 
 /// Tool show response: either `tool` (JSON) or `tool_yaml` (YAML string).
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ToolShowResponse {
     pub id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -75,28 +139,64 @@ pub struct ToolShowResponse {
 }
 
 /// Pong: response to ping.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct PongResponse {
     pub id: String,
 }
 
+/// Event schema list response: registered custom event types, sorted by name.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct EventSchemaListResponse {
+    pub id: String,
+    pub events: Vec<CustomEventSchema>,
+}
+
+/// [`ErrorResponse::code`] when a request frame, attachment or JSON nesting exceeds server limits.
+pub const ERROR_CODE_PAYLOAD_TOO_LARGE: &str = "payload_too_large";
+
+/// [`ErrorResponse::code`] when a privileged request (`admin_reload`, `active_runs`,
+/// `run_inspect`, `run_kill`) has a missing or wrong token.
+pub const ERROR_CODE_UNAUTHORIZED: &str = "unauthorized";
+
 /// Error response for any failed request.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ErrorResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
     pub error: String,
+    /// Machine-readable error code (e.g. [`ERROR_CODE_PAYLOAD_TOO_LARGE`], or a provider error
+    /// code from [`crate::AgentError::code`]); absent for generic errors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
 }
 
-/// One message in user messages list (role + content).
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// One message in user messages list: role and content, with what the store knows about it.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
 pub struct UserMessageItem {
     pub role: String,
     pub content: String,
+    /// Store id; pass as `before` to page further back.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    /// Run that produced the message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    /// Token usage of the LLM call that produced an assistant message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<LlmUsage>,
+    /// Tool whose result a tool message holds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    /// Call id a tool message answers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+    /// When the message was stored, in milliseconds since the Unix epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at_ms: Option<i64>,
 }
 
 /// User messages list response.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct UserMessagesResponse {
     pub id: String,
     pub thread_id: String,
@@ -105,8 +205,16 @@ pub struct UserMessagesResponse {
     pub has_more: Option<bool>,
 }
 
+/// User messages delete response: how many messages were deleted.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct UserMessagesDeleteResponse {
+    pub id: String,
+    pub thread_id: String,
+    pub deleted: u64,
+}
+
 /// Agent summary information.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct AgentSummary {
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -115,7 +223,7 @@ pub struct AgentSummary {
 }
 
 /// Agent source type.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum AgentSource {
     BuiltIn,
@@ -124,23 +232,171 @@ pub enum AgentSource {
 }
 
 /// Agent list response.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct AgentListResponse {
     pub id: String,
     pub agents: Vec<AgentSummary>,
 }
 
+/// Admin reload response: what the server reloaded. Settings apply to runs started afterwards;
+/// runs already in progress keep the settings they started with.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AdminReloadResponse {
+    pub id: String,
+    /// Reloaded parts, e.g. `config_files`, `run_config`.
+    pub reloaded: Vec<String>,
+    /// Non-fatal problems (e.g. an unreadable config file); the previous values were kept for those parts.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// One run in progress, as reported by `active_runs` and `run_inspect`.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ActiveRunInfo {
+    pub run_id: String,
+    /// Agent of the run (type or profile name).
+    pub agent: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<String>,
+    pub started_at_ms: i64,
+    pub elapsed_ms: u64,
+    /// Node the run entered last (e.g. `think`, `act`); absent before the first node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_node: Option<String>,
+    /// Token usage reported by the run so far.
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+}
+
+/// Active runs response: runs in progress on the server, oldest first.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ActiveRunsResponse {
+    pub id: String,
+    pub runs: Vec<ActiveRunInfo>,
+}
+
+/// Run inspect response: the run's current state.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RunInspectResponse {
+    pub id: String,
+    pub run: ActiveRunInfo,
+}
+
+/// Run kill response: acknowledgment that the run was asked to cancel.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RunKillResponse {
+    pub id: String,
+    pub run_id: String,
+}
+
+/// Session start response: the session and the thread its messages run on.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SessionStartResponse {
+    pub id: String,
+    pub session_id: String,
+    pub thread_id: String,
+}
+
+/// Session end response. `title` / `summary` are set when requested and generated;
+/// `compacted` tells whether the thread's history was compacted.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SessionEndResponse {
+    pub id: String,
+    pub session_id: String,
+    pub thread_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub compacted: bool,
+}
+
 /// Cancel run response: acknowledgment that a run has been cancelled.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct CancelRunResponse {
     pub id: String,
     pub run_id: String,
 }
 
+/// Stop generation response: acknowledgment that the run was asked to stop generating. The run
+/// still ends with its usual `run_end`, whose `finish_reason` is `user_stopped`.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct StopGenerationResponse {
+    pub id: String,
+    pub run_id: String,
+}
+
+/// State show response: latest checkpointed state of a thread as JSON.
+///
+/// `state` is the serialized agent state (e.g. `ReActState`); `None` when the thread has no
+/// checkpoint yet.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct StateShowResponse {
+    pub id: String,
+    pub thread_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checkpoint_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<serde_json::Value>,
+}
+
+/// One checkpoint in a [`CheckpointListResponse`].
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct CheckpointSummary {
+    pub checkpoint_id: String,
+    pub step: i64,
+    /// Why the checkpoint was written: `input`, `loop`, `update` or `fork`.
+    pub source: String,
+    /// Node that ran last before the checkpoint, when recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at_ms: Option<i64>,
+    /// Previous checkpoint of the thread.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    /// Size of the serialized state in bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+}
+
+impl From<&CheckpointListItem> for CheckpointSummary {
+    fn from(item: &CheckpointListItem) -> Self {
+        let source = match item.metadata.source {
+            CheckpointSource::Input => "input",
+            CheckpointSource::Loop => "loop",
+            CheckpointSource::Update => "update",
+            CheckpointSource::Fork => "fork",
+        };
+        Self {
+            checkpoint_id: item.checkpoint_id.clone(),
+            step: item.metadata.step,
+            source: source.to_string(),
+            node: item.metadata.node.clone(),
+            created_at_ms: item.created_at_ms(),
+            parent_id: item.parent_id.clone(),
+            size_bytes: item.size_bytes,
+        }
+    }
+}
+
+/// Checkpoint list response: checkpoints of a thread matching the request's filters, oldest
+/// first. Empty for an unknown thread.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct CheckpointListResponse {
+    pub id: String,
+    pub thread_id: String,
+    pub checkpoints: Vec<CheckpointSummary>,
+}
+
 /// Server-to-client response envelope.
 ///
 /// Each variant maps to a JSON object with `"type": "<variant_name>"`.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ServerResponse {
     RunStreamEvent(RunStreamEventResponse),
@@ -148,76 +404,136 @@ pub enum ServerResponse {
     ToolsList(ToolsListResponse),
     ToolShow(ToolShowResponse),
     UserMessages(UserMessagesResponse),
+    UserMessagesDelete(UserMessagesDeleteResponse),
     AgentList(AgentListResponse),
     WorkspaceList(WorkspaceListResponse),
     WorkspaceCreate(WorkspaceCreateResponse),
     WorkspaceThreadList(WorkspaceThreadListResponse),
     WorkspaceThreadAdd(WorkspaceThreadAddResponse),
     WorkspaceThreadRemove(WorkspaceThreadRemoveResponse),
+    WorkspaceUpdate(WorkspaceUpdateResponse),
+    UsageReport(UsageReportResponse),
+    ApprovalsList(ApprovalsListResponse),
     Pong(PongResponse),
     Error(ErrorResponse),
     ListModels(ListModelsResponse),
     SetModel(SetModelResponse),
     CancelRun(CancelRunResponse),
+    StateShow(StateShowResponse),
+    CheckpointList(CheckpointListResponse),
+    AdminReload(AdminReloadResponse),
+    StopGeneration(StopGenerationResponse),
+    EventSchemaList(EventSchemaListResponse),
+    ActiveRuns(ActiveRunsResponse),
+    RunInspect(RunInspectResponse),
+    RunKill(RunKillResponse),
+    SessionStart(SessionStartResponse),
+    SessionEnd(SessionEndResponse),
 }
 // -----------------------------------------------------------------------------
 // Workspace responses
 // -----------------------------------------------------------------------------
 // -----------------------------------------------------------------------------
 /// Workspace metadata.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct WorkspaceMeta {
     pub id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
     pub created_at_ms: i64,
+    /// Default run settings; omitted when none are set.
+    #[serde(default, skip_serializing_if = "WorkspaceDefaults::is_empty")]
+    pub defaults: WorkspaceDefaults,
 }
 /// Workspace list response.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct WorkspaceListResponse {
     pub id: String,
     pub workspaces: Vec<WorkspaceMeta>,
 }
 /// Workspace create response.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct WorkspaceCreateResponse {
     pub id: String,
     pub workspace_id: String,
 }
 /// Thread in workspace.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ThreadInWorkspace {
     pub thread_id: String,
     pub created_at_ms: i64,
+    /// Generated thread title (auto-summarize), when available.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Generated thread summary (auto-summarize), when available.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
 }
 /// Workspace thread list response.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct WorkspaceThreadListResponse {
     pub id: String,
     pub workspace_id: String,
     pub threads: Vec<ThreadInWorkspace>,
 }
 /// Workspace thread add response.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct WorkspaceThreadAddResponse {
     pub id: String,
     pub workspace_id: String,
     pub thread_id: String,
 }
 /// Workspace thread remove response.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct WorkspaceThreadRemoveResponse {
     pub id: String,
     pub workspace_id: String,
     pub thread_id: String,
 }
+/// Workspace update response: the stored default run settings.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct WorkspaceUpdateResponse {
+    pub id: String,
+    pub workspace_id: String,
+    pub defaults: WorkspaceDefaults,
+}
+
+/// Usage of all runs with one model and agent type.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct UsageReportRow {
+    pub model: String,
+    pub agent: String,
+    pub runs: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+    /// Estimated cost from models.dev pricing; omitted when the model's pricing is unknown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+}
+
+/// Usage report response: rows ordered by total tokens, largest first.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct UsageReportResponse {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<String>,
+    pub rows: Vec<UsageReportRow>,
+}
+
+/// Approvals list response: audit records matching the request's filters, newest first.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ApprovalsListResponse {
+    pub id: String,
+    pub approvals: Vec<ApprovalAuditRecord>,
+}
 
 // -----------------------------------------------------------------------------
 // Model responses
 // -----------------------------------------------------------------------------
 
 /// Model information
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ModelInfo {
     pub id: String,
     pub name: String,
@@ -226,17 +542,23 @@ pub struct ModelInfo {
     pub family: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub capabilities: Option<Vec<String>>,
+    /// Context (input) token limit from the model catalog, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_limit: Option<u32>,
+    /// Output token limit from the model catalog, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_limit: Option<u32>,
 }
 
 /// List models response
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ListModelsResponse {
     pub id: String,
     pub models: Vec<ModelInfo>,
 }
 
 /// Set model response
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct SetModelResponse {
     pub id: String,
     pub success: bool,
@@ -263,16 +585,65 @@ mod tests {
                 completion_tokens_details: None,
             }),
             total_usage: None,
+            finish_reason: Some(FinishReason::Length),
             session_id: None,
             node_id: None,
             event_id: None,
+            transcript: None,
+            timing: None,
+            flags: Default::default(),
+            confidence: None,
         });
         let json = serde_json::to_string(&resp).unwrap();
         assert!(json.contains("\"type\":\"run_end\""));
+        assert!(!json.contains("transcript"));
+        assert!(!json.contains("timing"));
+        assert!(!json.contains("flags"));
         assert!(json.contains("\"id\":\"req-1\""));
         assert!(json.contains("\"reply\":\"hello\""));
+        assert!(json.contains("\"finish_reason\":\"length\""));
         let parsed: ServerResponse = serde_json::from_str(&json).unwrap();
-        assert!(matches!(parsed, ServerResponse::RunEnd(_)));
+        assert!(matches!(
+            parsed,
+            ServerResponse::RunEnd(RunEndResponse {
+                finish_reason: Some(FinishReason::Length),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn response_run_end_transcript_roundtrip() {
+        let resp = ServerResponse::RunEnd(RunEndResponse {
+            id: "req-2".to_string(),
+            reply: "done".to_string(),
+            reasoning_content: None,
+            usage: None,
+            total_usage: None,
+            finish_reason: None,
+            session_id: None,
+            node_id: None,
+            event_id: None,
+            transcript: Some(vec![ToolCallRecord {
+                tool: "read".to_string(),
+                args_digest: r#"{"path":"a.txt"}"#.to_string(),
+                status: ToolCallStatus::Error,
+                duration_ms: 12,
+                result_preview: "not found".to_string(),
+            }]),
+            timing: None,
+            flags: Default::default(),
+            confidence: None,
+        });
+        let value = serde_json::to_value(&resp).unwrap();
+        assert_eq!(value["transcript"][0]["tool"], "read");
+        assert_eq!(value["transcript"][0]["status"], "error");
+        assert_eq!(value["transcript"][0]["duration_ms"], 12);
+        let parsed: ServerResponse = serde_json::from_value(value).unwrap();
+        match parsed {
+            ServerResponse::RunEnd(r) => assert_eq!(r.transcript.unwrap().len(), 1),
+            other => panic!("expected RunEnd, got {:?}", other),
+        }
     }
 
     #[test]
@@ -284,6 +655,7 @@ mod tests {
                 description: Some("A test tool".to_string()),
                 input_schema: serde_json::json!({}),
                 output_hint: None,
+                catalog: Default::default(),
             }],
         });
         let json = serde_json::to_string(&resp).unwrap();
@@ -345,12 +717,26 @@ mod tests {
         let resp = ServerResponse::Error(ErrorResponse {
             id: Some("req-x".to_string()),
             error: "something failed".to_string(),
+            code: None,
         });
         let json = serde_json::to_string(&resp).unwrap();
         assert!(json.contains("\"type\":\"error\""));
         assert!(json.contains("\"error\":\"something failed\""));
+        assert!(!json.contains("\"code\""));
         let parsed: ServerResponse = serde_json::from_str(&json).unwrap();
         assert!(matches!(parsed, ServerResponse::Error(_)));
+
+        let resp = ServerResponse::Error(ErrorResponse {
+            id: None,
+            error: "message too large".to_string(),
+            code: Some(ERROR_CODE_PAYLOAD_TOO_LARGE.to_string()),
+        });
+        let json = serde_json::to_string(&resp).unwrap();
+        assert!(json.contains("\"code\":\"payload_too_large\""));
+        match serde_json::from_str::<ServerResponse>(&json).unwrap() {
+            ServerResponse::Error(e) => assert_eq!(e.code.as_deref(), Some("payload_too_large")),
+            other => panic!("expected Error, got {:?}", other),
+        }
     }
 
     #[test]
@@ -387,11 +773,13 @@ mod tests {
                 id: "ws-1".to_string(),
                 name: Some("project-alpha".to_string()),
                 created_at_ms: 1712649600000,
+                defaults: WorkspaceDefaults::default(),
             }],
         });
         let json = serde_json::to_string(&resp).unwrap();
         assert!(json.contains("\"type\":\"workspace_list\""));
         assert!(json.contains("\"name\":\"project-alpha\""));
+        assert!(!json.contains("defaults"));
         let parsed: ServerResponse = serde_json::from_str(&json).unwrap();
         assert!(matches!(parsed, ServerResponse::WorkspaceList(_)));
     }
@@ -417,11 +805,15 @@ mod tests {
             threads: vec![ThreadInWorkspace {
                 thread_id: "t-1".to_string(),
                 created_at_ms: 1712649600000,
+                title: Some("Trip planning".to_string()),
+                summary: None,
             }],
         });
         let json = serde_json::to_string(&resp).unwrap();
         assert!(json.contains("\"type\":\"workspace_thread_list\""));
         assert!(json.contains("\"thread_id\":\"t-1\""));
+        assert!(json.contains("\"title\":\"Trip planning\""));
+        assert!(!json.contains("\"summary\""));
         let parsed: ServerResponse = serde_json::from_str(&json).unwrap();
         assert!(matches!(parsed, ServerResponse::WorkspaceThreadList(_)));
     }
@@ -439,6 +831,44 @@ mod tests {
         assert!(matches!(parsed, ServerResponse::WorkspaceThreadAdd(_)));
     }
 
+    #[test]
+    fn response_user_messages_roundtrip() {
+        let resp = ServerResponse::UserMessages(UserMessagesResponse {
+            id: "req-um".to_string(),
+            thread_id: "t-1".to_string(),
+            messages: vec![UserMessageItem {
+                role: "tool".to_string(),
+                content: "sunny".to_string(),
+                id: Some(7),
+                run_id: Some("run-1".to_string()),
+                tool_name: Some("get_weather".to_string()),
+                ..Default::default()
+            }],
+            has_more: Some(false),
+        });
+        let json = serde_json::to_string(&resp).unwrap();
+        assert!(json.contains("\"tool_name\":\"get_weather\""));
+        assert!(!json.contains("usage"));
+        let parsed: ServerResponse = serde_json::from_str(&json).unwrap();
+        let ServerResponse::UserMessages(parsed) = parsed else {
+            panic!("expected user_messages");
+        };
+        assert_eq!(parsed.messages[0].id, Some(7));
+
+        let resp = ServerResponse::UserMessagesDelete(UserMessagesDeleteResponse {
+            id: "req-umd".to_string(),
+            thread_id: "t-1".to_string(),
+            deleted: 3,
+        });
+        let json = serde_json::to_string(&resp).unwrap();
+        assert!(json.contains("\"type\":\"user_messages_delete\""));
+        let parsed: ServerResponse = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            parsed,
+            ServerResponse::UserMessagesDelete(UserMessagesDeleteResponse { deleted: 3, .. })
+        ));
+    }
+
     #[test]
     fn response_workspace_thread_remove_roundtrip() {
         let resp = ServerResponse::WorkspaceThreadRemove(WorkspaceThreadRemoveResponse {
@@ -451,4 +881,93 @@ mod tests {
         let parsed: ServerResponse = serde_json::from_str(&json).unwrap();
         assert!(matches!(parsed, ServerResponse::WorkspaceThreadRemove(_)));
     }
+
+    #[test]
+    fn response_state_show_roundtrip() {
+        let resp = ServerResponse::StateShow(StateShowResponse {
+            id: "req-ss".to_string(),
+            thread_id: "t-1".to_string(),
+            checkpoint_id: Some("cp-1".to_string()),
+            state: Some(serde_json::json!({ "turn_count": 2 })),
+        });
+        let json = serde_json::to_string(&resp).unwrap();
+        assert!(json.contains("\"type\":\"state_show\""));
+        let parsed: ServerResponse = serde_json::from_str(&json).unwrap();
+        match parsed {
+            ServerResponse::StateShow(r) => {
+                assert_eq!(r.checkpoint_id.as_deref(), Some("cp-1"));
+                assert_eq!(r.state.unwrap()["turn_count"], 2);
+            }
+            other => panic!("expected StateShow, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn response_state_show_omits_missing_state() {
+        let resp = ServerResponse::StateShow(StateShowResponse {
+            id: "req-ss".to_string(),
+            thread_id: "t-1".to_string(),
+            checkpoint_id: None,
+            state: None,
+        });
+        let json = serde_json::to_string(&resp).unwrap();
+        assert!(!json.contains("\"state\""));
+        assert!(!json.contains("checkpoint_id"));
+    }
+
+    #[test]
+    fn response_checkpoint_list_from_list_items() {
+        let item = CheckpointListItem {
+            checkpoint_id: "cp-2".to_string(),
+            metadata: crate::memory::CheckpointMetadata {
+                source: CheckpointSource::Update,
+                step: 4,
+                node: Some("act".to_string()),
+                ..Default::default()
+            },
+            parent_id: Some("cp-1".to_string()),
+            size_bytes: Some(512),
+        };
+        let resp = ServerResponse::CheckpointList(CheckpointListResponse {
+            id: "req-cl".to_string(),
+            thread_id: "t-1".to_string(),
+            checkpoints: vec![CheckpointSummary::from(&item)],
+        });
+        let json = serde_json::to_string(&resp).unwrap();
+        assert!(json.contains("\"type\":\"checkpoint_list\""));
+        assert!(json.contains("\"source\":\"update\""));
+        assert!(!json.contains("created_at_ms"));
+        let parsed: ServerResponse = serde_json::from_str(&json).unwrap();
+        match parsed {
+            ServerResponse::CheckpointList(r) => {
+                assert_eq!(r.checkpoints[0].node.as_deref(), Some("act"));
+                assert_eq!(r.checkpoints[0].parent_id.as_deref(), Some("cp-1"));
+                assert_eq!(r.checkpoints[0].size_bytes, Some(512));
+            }
+            other => panic!("expected CheckpointList, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn response_event_schema_list_roundtrip() {
+        let resp = ServerResponse::EventSchemaList(EventSchemaListResponse {
+            id: "req-es".to_string(),
+            events: vec![CustomEventSchema {
+                name: "progress".to_string(),
+                description: None,
+                schema: serde_json::json!({ "type": "object" }),
+            }],
+        });
+        let json = serde_json::to_string(&resp).unwrap();
+        assert!(json.contains("\"type\":\"event_schema_list\""));
+        assert!(!json.contains("description"));
+        let parsed: ServerResponse = serde_json::from_str(&json).unwrap();
+        match parsed {
+            ServerResponse::EventSchemaList(r) => {
+                assert_eq!(r.events.len(), 1);
+                assert_eq!(r.events[0].name, "progress");
+            }
+            other => panic!("expected EventSchemaList, got {:?}", other),
+        }
+    }
 }
@@ -0,0 +1,61 @@
+//! JSON Schema of the WebSocket protocol, generated from the Rust types with schemars.
+//!
+//! [`protocol_schema`] returns one draft-07 document: `definitions` holds every request,
+//! response and stream event type, and `properties` points at the three entry types
+//! (`client_request`, `server_response`, `event`). Clients generate their types from it
+//! (e.g. `json-schema-to-typescript`) instead of keeping hand-written copies in sync.
+//! `loom serve` serves it at `GET /schema`; `loom protocol schema` prints it.
+
+use schemars::gen::SchemaSettings;
+use serde_json::{json, Value};
+
+use super::{ClientRequest, ProtocolEventEnvelope, ServerResponse};
+
+/// JSON Schema document of the protocol (see the module docs for its layout).
+pub fn protocol_schema() -> Value {
+    let mut gen = SchemaSettings::draft07().into_generator();
+    let client_request = gen.subschema_for::<ClientRequest>();
+    let server_response = gen.subschema_for::<ServerResponse>();
+    let event = gen.subschema_for::<ProtocolEventEnvelope>();
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "loom protocol",
+        "type": "object",
+        "properties": {
+            "client_request": client_request,
+            "server_response": server_response,
+            "event": event,
+        },
+        "definitions": gen.take_definitions(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_defines_entry_types_and_variants() {
+        let schema = protocol_schema();
+        let defs = schema["definitions"].as_object().unwrap();
+        for name in [
+            "ClientRequest",
+            "ServerResponse",
+            "ProtocolEventEnvelope",
+            "ProtocolEvent",
+            "RunRequest",
+            "RunEndResponse",
+            "UserContent",
+            "FinishReason",
+        ] {
+            assert!(defs.contains_key(name), "missing definition {}", name);
+        }
+        assert_eq!(
+            schema["properties"]["client_request"]["$ref"],
+            "#/definitions/ClientRequest"
+        );
+        let text = defs["ClientRequest"].to_string();
+        assert!(text.contains("\"approvals_list\""), "{}", text);
+        assert_eq!(defs["FinishReason"]["type"], "string");
+    }
+}
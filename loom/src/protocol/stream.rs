@@ -51,6 +51,7 @@ where
                 ProtocolEvent::MessageChunk {
                     content: chunk.content.clone(),
                     id: loom_node.clone(),
+                    revision: chunk.revision,
                 }
             }
         }
@@ -64,6 +65,19 @@ where
             completion_tokens: *completion_tokens,
             total_tokens: *total_tokens,
         },
+        StreamEvent::FinishReason { reason } => ProtocolEvent::FinishReason {
+            reason: reason.to_string(),
+        },
+        StreamEvent::GraphProgress(progress) => ProtocolEvent::GraphProgress {
+            nodes: progress.nodes.clone(),
+            edges: progress
+                .edges
+                .iter()
+                .map(serde_json::to_value)
+                .collect::<Result<_, _>>()?,
+            current: progress.current.clone(),
+            visits: progress.visits.clone(),
+        },
         StreamEvent::Values(state) => ProtocolEvent::Values {
             state: serde_json::to_value(state)?,
         },
@@ -118,10 +132,12 @@ where
             node_id,
             nodes_added,
             edges_added,
+            denied,
         } => ProtocolEvent::GotExpand {
             node_id: node_id.clone(),
             nodes_added: *nodes_added,
             edges_added: *edges_added,
+            denied: denied.clone(),
         },
         StreamEvent::ToolCallChunk {
             call_id,
@@ -154,6 +170,15 @@ where
             name: name.clone(),
             content: content.clone(),
         },
+        StreamEvent::ToolOutputChunk {
+            call_id,
+            name,
+            content,
+        } => ProtocolEvent::ToolOutputChunk {
+            call_id: call_id.clone(),
+            name: name.clone(),
+            content: content.clone(),
+        },
         StreamEvent::ToolEnd {
             call_id,
             name,
@@ -176,6 +201,42 @@ where
             name: name.clone(),
             arguments: arguments.clone(),
         },
+        StreamEvent::ThreadSummary { title, summary } => ProtocolEvent::ThreadSummary {
+            title: title.clone(),
+            summary: summary.clone(),
+        },
+        StreamEvent::ToolsRefreshed { tools } => ProtocolEvent::ToolsRefreshed {
+            tools: tools.clone(),
+        },
+        StreamEvent::ModelSwitched {
+            from,
+            to,
+            prompt_tokens,
+            context_limit,
+        } => ProtocolEvent::ModelSwitched {
+            from: from.clone(),
+            to: to.clone(),
+            prompt_tokens: *prompt_tokens,
+            context_limit: *context_limit,
+        },
+        StreamEvent::AnswerRevised { reason, revision } => ProtocolEvent::AnswerRevised {
+            reason: reason.clone(),
+            revision: *revision,
+        },
+        StreamEvent::Timing {
+            node_id,
+            kind,
+            name,
+            duration_ms,
+            state,
+        } => ProtocolEvent::Timing {
+            node_id: node_id.clone(),
+            kind: kind.as_str().to_string(),
+            name: name.clone(),
+            duration_ms: *duration_ms,
+            state_bytes: state.map(|s| s.bytes as u64),
+            message_count: state.map(|s| s.messages as u64),
+        },
     };
     Ok(pe)
 }
@@ -498,6 +559,22 @@ mod tests {
         assert_eq!(v["content"], "hello\n");
     }
 
+    #[test]
+    fn tool_output_chunk_format() {
+        let ev: StreamEvent<DummyState> = StreamEvent::ToolOutputChunk {
+            call_id: Some("c1".into()),
+            name: "bash".into(),
+            content: "line 1\n".into(),
+        };
+        let v = stream_event_to_protocol_event(&ev)
+            .unwrap()
+            .to_value()
+            .unwrap();
+        assert_eq!(v["type"], "tool_output_chunk");
+        assert_eq!(v["call_id"], "c1");
+        assert_eq!(v["content"], "line 1\n");
+    }
+
     #[test]
     fn tool_end_success_format() {
         let ev: StreamEvent<DummyState> = StreamEvent::ToolEnd {
@@ -549,6 +626,43 @@ mod tests {
         assert_eq!(v["arguments"]["path"], "x.txt");
     }
 
+    #[test]
+    fn thread_summary_format() {
+        let ev: StreamEvent<DummyState> = StreamEvent::ThreadSummary {
+            title: "Greeting".into(),
+            summary: "The user said hello.".into(),
+        };
+        let v = stream_event_to_protocol_event(&ev)
+            .unwrap()
+            .to_value()
+            .unwrap();
+        assert_eq!(v["type"], "thread_summary");
+        assert_eq!(v["title"], "Greeting");
+    }
+
+    #[test]
+    fn graph_progress_format() {
+        let mut progress = crate::graph::GraphProgress {
+            nodes: vec!["act".into(), "think".into()],
+            edges: vec![crate::graph::GraphEdge {
+                from: "think".into(),
+                to: "act".into(),
+                label: Some("tools".into()),
+            }],
+            ..Default::default()
+        };
+        progress.enter("think");
+        let ev: StreamEvent<DummyState> = StreamEvent::GraphProgress(progress);
+        let v = stream_event_to_protocol_event(&ev)
+            .unwrap()
+            .to_value()
+            .unwrap();
+        assert_eq!(v["type"], "graph_progress");
+        assert_eq!(v["current"], "think");
+        assert_eq!(v["visits"]["think"], 1);
+        assert_eq!(v["edges"][0]["label"], "tools");
+    }
+
     #[test]
     fn custom_format() {
         let ev: StreamEvent<DummyState> = StreamEvent::Custom(json!({"key": "val"}));
@@ -666,11 +780,24 @@ mod tests {
             node_id: "gn1".to_string(),
             nodes_added: 2,
             edges_added: 1,
+            denied: None,
         };
         let pe = stream_event_to_protocol_event(&ev).unwrap();
         let v = pe.to_value().unwrap();
         assert_eq!(v["type"], "got_expand");
         assert_eq!(v["nodes_added"], 2);
         assert_eq!(v["edges_added"], 1);
+
+        let ev: StreamEvent<DummyState> = StreamEvent::GotExpand {
+            node_id: "gn2".to_string(),
+            nodes_added: 0,
+            edges_added: 0,
+            denied: Some("token budget exhausted".to_string()),
+        };
+        let v = stream_event_to_protocol_event(&ev)
+            .unwrap()
+            .to_value()
+            .unwrap();
+        assert_eq!(v["denied"], "token budget exhausted");
     }
 }
@@ -0,0 +1,298 @@
+//! Run lifecycle hooks: async callbacks when a run starts, finishes or fails.
+//!
+//! [`RunHooks`] holds up to three callbacks — `on_start`, `on_end` (final state and token usage)
+//! and `on_error` (failure or cancellation) — so applications can send notifications, update
+//! billing or invalidate caches without polling run history. Register them on a runner
+//! ([`crate::ReactRunner::with_hooks`], [`crate::LoomBuilder::run_hooks`] and the DUP, ToT and
+//! GoT runners' `with_hooks`) or on a `serve` router, where the final state is the run's
+//! [`crate::RunEndResponse`].
+//!
+//! Hooks are awaited in the run's task: `on_start` before the graph runs, `on_end` / `on_error`
+//! before the runner returns. Spawn slow work from the hook instead of awaiting it there.
+//!
+//! ```no_run
+//! # async fn demo(runner: loom::ReactRunner) -> Result<(), Box<dyn std::error::Error>> {
+//! let runner = runner.with_hooks(
+//!     loom::RunHooks::new()
+//!         .on_end(|end: loom::RunEnd<loom::ReActState>| async move {
+//!             let tokens = end.usage.map_or(0, |u| u.total_tokens);
+//!             println!("thread {:?} used {} tokens", end.thread_id, tokens);
+//!         })
+//!         .on_error(|failure: loom::RunFailure| async move {
+//!             eprintln!("run failed: {}", failure.error);
+//!         }),
+//! );
+//! runner.invoke("hello").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::agent::{DupState, GotState, TotState};
+use crate::error::AgentError;
+use crate::llm::LlmUsage;
+use crate::state::ReActState;
+
+/// Future returned by a hook callback.
+pub type HookFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+type Hook<T> = Arc<dyn Fn(T) -> HookFuture + Send + Sync>;
+
+/// Passed to `on_start` before the run's graph starts.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RunStart {
+    /// Run id where the caller assigns one (serve); `None` for runner calls.
+    pub run_id: Option<String>,
+    pub thread_id: Option<String>,
+}
+
+/// Passed to `on_end` when a run finished.
+#[derive(Clone, Debug)]
+pub struct RunEnd<S> {
+    pub run_id: Option<String>,
+    pub thread_id: Option<String>,
+    /// Final state of the run.
+    pub state: S,
+    /// Token usage of the whole run, when the provider reported it.
+    pub usage: Option<LlmUsage>,
+}
+
+/// Passed to `on_error` when a run failed or was cancelled.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RunFailure {
+    pub run_id: Option<String>,
+    pub thread_id: Option<String>,
+    pub error: String,
+    /// Provider error code (`rate_limited`, `auth_failed`, ...; see [`AgentError::code`]).
+    pub code: Option<String>,
+    /// True when the run was cancelled rather than failed.
+    pub cancelled: bool,
+}
+
+impl RunFailure {
+    /// Failure of the run on `thread_id` with `error`.
+    pub fn from_agent_error(thread_id: Option<String>, error: &AgentError) -> Self {
+        Self {
+            run_id: None,
+            thread_id,
+            error: error.to_string(),
+            code: error.code().map(String::from),
+            cancelled: matches!(error, AgentError::Cancelled),
+        }
+    }
+}
+
+/// Final states that know the run's total token usage, reported as [`RunEnd::usage`].
+pub trait RunStateUsage {
+    fn run_usage(&self) -> Option<LlmUsage>;
+}
+
+impl RunStateUsage for ReActState {
+    fn run_usage(&self) -> Option<LlmUsage> {
+        self.total_usage.clone()
+    }
+}
+
+impl RunStateUsage for DupState {
+    fn run_usage(&self) -> Option<LlmUsage> {
+        self.core.run_usage()
+    }
+}
+
+impl RunStateUsage for TotState {
+    fn run_usage(&self) -> Option<LlmUsage> {
+        self.core.run_usage()
+    }
+}
+
+impl RunStateUsage for GotState {
+    /// GoT only counts total tokens (see [`GotState::tokens_used`]).
+    fn run_usage(&self) -> Option<LlmUsage> {
+        (self.tokens_used > 0).then(|| LlmUsage {
+            total_tokens: u32::try_from(self.tokens_used).unwrap_or(u32::MAX),
+            ..LlmUsage::default()
+        })
+    }
+}
+
+/// Start, end and failure callbacks of a run with final state `S`. Empty by default; cheap to
+/// clone.
+pub struct RunHooks<S> {
+    on_start: Option<Hook<RunStart>>,
+    on_end: Option<Hook<RunEnd<S>>>,
+    on_error: Option<Hook<RunFailure>>,
+}
+
+impl<S> Default for RunHooks<S> {
+    fn default() -> Self {
+        Self {
+            on_start: None,
+            on_end: None,
+            on_error: None,
+        }
+    }
+}
+
+impl<S> Clone for RunHooks<S> {
+    fn clone(&self) -> Self {
+        Self {
+            on_start: self.on_start.clone(),
+            on_end: self.on_end.clone(),
+            on_error: self.on_error.clone(),
+        }
+    }
+}
+
+impl<S> fmt::Debug for RunHooks<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RunHooks")
+            .field("on_start", &self.on_start.is_some())
+            .field("on_end", &self.on_end.is_some())
+            .field("on_error", &self.on_error.is_some())
+            .finish()
+    }
+}
+
+impl<S: Send + 'static> RunHooks<S> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the callback run before the graph starts.
+    pub fn on_start<F, Fut>(mut self, hook: F) -> Self
+    where
+        F: Fn(RunStart) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_start = Some(Arc::new(move |info| Box::pin(hook(info))));
+        self
+    }
+
+    /// Sets the callback run with the final state when the run finished.
+    pub fn on_end<F, Fut>(mut self, hook: F) -> Self
+    where
+        F: Fn(RunEnd<S>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_end = Some(Arc::new(move |info| Box::pin(hook(info))));
+        self
+    }
+
+    /// Sets the callback run when the run failed or was cancelled.
+    pub fn on_error<F, Fut>(mut self, hook: F) -> Self
+    where
+        F: Fn(RunFailure) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_error = Some(Arc::new(move |info| Box::pin(hook(info))));
+        self
+    }
+}
+
+impl<S> RunHooks<S> {
+    /// True when no callback is set.
+    pub fn is_empty(&self) -> bool {
+        self.on_start.is_none() && self.on_end.is_none() && self.on_error.is_none()
+    }
+
+    /// True when an `on_end` callback is set, so callers can skip building its [`RunEnd`].
+    pub fn has_on_end(&self) -> bool {
+        self.on_end.is_some()
+    }
+
+    /// Runs `on_start`, if set.
+    pub async fn run_started(&self, info: RunStart) {
+        if let Some(hook) = &self.on_start {
+            hook(info).await;
+        }
+    }
+
+    /// Runs `on_end`, if set.
+    pub async fn run_ended(&self, info: RunEnd<S>) {
+        if let Some(hook) = &self.on_end {
+            hook(info).await;
+        }
+    }
+
+    /// Runs `on_error`, if set.
+    pub async fn run_failed(&self, info: RunFailure) {
+        if let Some(hook) = &self.on_error {
+            hook(info).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn hooks_receive_start_end_and_failure() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let (s1, s2, s3) = (seen.clone(), seen.clone(), seen.clone());
+        let hooks = RunHooks::<ReActState>::new()
+            .on_start(move |start: RunStart| {
+                let seen = s1.clone();
+                async move {
+                    seen.lock()
+                        .unwrap()
+                        .push(format!("start {:?}", start.thread_id));
+                }
+            })
+            .on_end(move |end: RunEnd<ReActState>| {
+                let seen = s2.clone();
+                async move {
+                    let tokens = end.usage.map_or(0, |u| u.total_tokens);
+                    seen.lock().unwrap().push(format!("end {}", tokens));
+                }
+            })
+            .on_error(move |failure: RunFailure| {
+                let seen = s3.clone();
+                async move {
+                    seen.lock()
+                        .unwrap()
+                        .push(format!("error {} {}", failure.error, failure.cancelled));
+                }
+            });
+        assert!(!hooks.is_empty());
+
+        let state = ReActState {
+            total_usage: Some(LlmUsage {
+                total_tokens: 42,
+                ..LlmUsage::default()
+            }),
+            ..ReActState::default()
+        };
+        hooks
+            .run_started(RunStart {
+                run_id: None,
+                thread_id: Some("t1".to_string()),
+            })
+            .await;
+        hooks
+            .run_ended(RunEnd {
+                run_id: None,
+                thread_id: None,
+                usage: state.run_usage(),
+                state,
+            })
+            .await;
+        hooks
+            .run_failed(RunFailure::from_agent_error(None, &AgentError::Cancelled))
+            .await;
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                "start Some(\"t1\")".to_string(),
+                "end 42".to_string(),
+                format!("error {} true", AgentError::Cancelled),
+            ]
+        );
+        assert!(RunHooks::<ReActState>::default().is_empty());
+    }
+}
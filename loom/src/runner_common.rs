@@ -1,10 +1,17 @@
 //! Common stream execution logic and checkpoint loading shared by ReAct, DUP, ToT, and GoT runners.
 //!
 //! - [`run_stream_with_config`]: build initial state → compiled.stream → consume events → return final state.
+//! - [`invoke_with_hooks`]: compiled.invoke between the runner's [`RunHooks`] callbacks.
 //! - [`load_from_checkpoint_or_build`]: try load from checkpointer, else run `build_fresh` future; merge user message when loaded.
+//! - [`load_state_snapshot`]: read the latest checkpointed state for a thread without running the graph.
+//! - [`load_thread_state_json`]: same, straight from the SQLite memory DB as untyped JSON (serve `state_show`).
+//! - [`load_checkpoint_json`]: one checkpoint of a thread (latest or by id) from the memory DB as JSON.
+//! - [`list_thread_checkpoints`]: checkpoint metadata of a thread from the memory DB (serve `checkpoint_list`).
+//! - [`put_thread_state_json`]: write a manually updated state as the thread's latest checkpoint (serve `session_end`).
 
 use std::collections::HashSet;
 use std::future::Future;
+use std::sync::Arc;
 
 use tokio_stream::StreamExt;
 use tokio_util::sync::CancellationToken;
@@ -12,7 +19,11 @@ use tokio_util::sync::CancellationToken;
 use crate::cli_run::RunCancellation;
 use crate::error::AgentError;
 use crate::graph::CompiledStateGraph;
-use crate::memory::{CheckpointError, Checkpointer, RunnableConfig};
+use crate::memory::{
+    Checkpoint, CheckpointError, CheckpointFilter, CheckpointListItem, CheckpointSource,
+    Checkpointer, JsonSerializer, RunnableConfig, SqliteSaver,
+};
+use crate::run_hooks::{RunEnd, RunFailure, RunHooks, RunStart, RunStateUsage};
 use crate::stream::{StreamEvent, StreamMode};
 
 /// Tries to load state from checkpointer; if found, merges `user_message` via `merge` and returns.
@@ -53,6 +64,114 @@ where
     build_fresh.await
 }
 
+/// Loads the latest checkpointed state for `thread_id`, or `None` when there is no checkpointer
+/// or the thread has no checkpoint yet. Shared by the runners' `state_snapshot` methods.
+///
+/// `base_config` supplies the checkpoint namespace and user id; its `thread_id` and
+/// `checkpoint_id` are replaced so the latest checkpoint of the requested thread is read.
+pub async fn load_state_snapshot<S>(
+    checkpointer: Option<&dyn Checkpointer<S>>,
+    base_config: Option<&RunnableConfig>,
+    thread_id: &str,
+) -> Result<Option<S>, CheckpointError>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let Some(cp) = checkpointer else {
+        return Ok(None);
+    };
+    if thread_id.is_empty() {
+        return Err(CheckpointError::ThreadIdRequired);
+    }
+    let mut config = base_config.cloned().unwrap_or_default();
+    config.thread_id = Some(thread_id.to_string());
+    config.checkpoint_id = None;
+    let tuple = cp.get_tuple(&config).await?;
+    Ok(tuple.map(|(checkpoint, _)| checkpoint.channel_values))
+}
+
+/// Loads the latest checkpoint of `thread_id` from the SQLite memory DB at `db_path` as JSON,
+/// returning `(checkpoint_id, state)`. The state is not decoded into a concrete type, so this
+/// works for threads written by any runner (ReAct, DUP, ToT, GoT).
+pub async fn load_thread_state_json(
+    db_path: impl AsRef<std::path::Path>,
+    thread_id: &str,
+) -> Result<Option<(String, serde_json::Value)>, CheckpointError> {
+    let checkpoint = load_checkpoint_json(db_path, thread_id, None).await?;
+    Ok(checkpoint.map(|checkpoint| (checkpoint.id, checkpoint.channel_values)))
+}
+
+/// Loads one checkpoint of `thread_id` from the SQLite memory DB at `db_path` with its state as
+/// untyped JSON: `checkpoint_id` when set, else the latest. The returned checkpoint carries its
+/// metadata.
+pub async fn load_checkpoint_json(
+    db_path: impl AsRef<std::path::Path>,
+    thread_id: &str,
+    checkpoint_id: Option<&str>,
+) -> Result<Option<Checkpoint<serde_json::Value>>, CheckpointError> {
+    let saver = open_thread_saver(db_path.as_ref(), thread_id)?;
+    let config = RunnableConfig {
+        thread_id: Some(thread_id.to_string()),
+        checkpoint_id: checkpoint_id.map(String::from),
+        ..Default::default()
+    };
+    let tuple = saver.get_tuple(&config).await?;
+    Ok(tuple.map(|(mut checkpoint, metadata)| {
+        checkpoint.metadata = metadata;
+        checkpoint
+    }))
+}
+
+/// Lists the checkpoints of `thread_id` in the SQLite memory DB at `db_path` that match
+/// `filter`, oldest first.
+pub async fn list_thread_checkpoints(
+    db_path: impl AsRef<std::path::Path>,
+    thread_id: &str,
+    filter: &CheckpointFilter,
+) -> Result<Vec<CheckpointListItem>, CheckpointError> {
+    let saver = open_thread_saver(db_path.as_ref(), thread_id)?;
+    let config = RunnableConfig {
+        thread_id: Some(thread_id.to_string()),
+        ..Default::default()
+    };
+    saver.list_filtered(&config, filter).await
+}
+
+/// Writes `state` to the SQLite memory DB at `db_path` as a new checkpoint of `thread_id` that
+/// follows `parent` (a manual update, e.g. a compacted history), so the thread's next run starts
+/// from it. Returns the new checkpoint id.
+pub async fn put_thread_state_json(
+    db_path: impl AsRef<std::path::Path>,
+    thread_id: &str,
+    parent: &Checkpoint<serde_json::Value>,
+    state: serde_json::Value,
+) -> Result<String, CheckpointError> {
+    let saver = open_thread_saver(db_path.as_ref(), thread_id)?;
+    let mut checkpoint =
+        Checkpoint::from_state(state, CheckpointSource::Update, parent.metadata.step + 1);
+    checkpoint.channel_versions = parent.channel_versions.clone();
+    checkpoint.versions_seen = parent.versions_seen.clone();
+    checkpoint
+        .metadata
+        .parents
+        .insert(String::new(), parent.id.clone());
+    let config = RunnableConfig {
+        thread_id: Some(thread_id.to_string()),
+        ..Default::default()
+    };
+    saver.put(&config, &checkpoint).await
+}
+
+fn open_thread_saver(
+    db_path: &std::path::Path,
+    thread_id: &str,
+) -> Result<SqliteSaver<serde_json::Value>, CheckpointError> {
+    if thread_id.is_empty() {
+        return Err(CheckpointError::ThreadIdRequired);
+    }
+    SqliteSaver::new(db_path, Arc::new(JsonSerializer))
+}
+
 /// Error when the stream ends without producing a final `Values` state.
 #[derive(Debug, thiserror::Error)]
 #[error("stream ended without final state")]
@@ -78,7 +197,8 @@ pub enum StreamRunError {
 ///
 /// Uses fixed stream modes (Messages, Tasks, Updates, Values, Custom). When `on_event`
 /// is provided, invokes it for each `StreamEvent`. Returns the state from the last
-/// `StreamEvent::Values` in the stream.
+/// `StreamEvent::Values` in the stream. `hooks` run before the graph starts and once the
+/// outcome is known.
 pub async fn run_stream_with_config<S, F>(
     compiled: &CompiledStateGraph<S>,
     initial_state: S,
@@ -86,11 +206,19 @@ pub async fn run_stream_with_config<S, F>(
     mut on_event: Option<F>,
     cancellation: Option<CancellationToken>,
     run_cancellation: Option<RunCancellation>,
+    hooks: &RunHooks<S>,
 ) -> Result<StreamRunOutcome<S>, StreamRunError>
 where
-    S: Clone + Send + Sync + std::fmt::Debug + 'static,
+    S: Clone + Send + Sync + std::fmt::Debug + RunStateUsage + 'static,
     F: FnMut(StreamEvent<S>),
 {
+    let thread_id = run_config.as_ref().and_then(|c| c.thread_id.clone());
+    hooks
+        .run_started(RunStart {
+            run_id: None,
+            thread_id: thread_id.clone(),
+        })
+        .await;
     let modes = HashSet::from([
         StreamMode::Messages,
         StreamMode::Tasks,
@@ -99,6 +227,7 @@ where
         StreamMode::Values,
         StreamMode::Custom,
         StreamMode::Checkpoints,
+        StreamMode::Graph,
     ]);
     let graph_stream = compiled.stream(
         initial_state,
@@ -122,12 +251,81 @@ where
             "graph stream task failed: {}",
             e
         )))
-    })?;
-    match completion {
-        Ok(()) => final_state
+    });
+    let outcome: Result<StreamRunOutcome<S>, StreamRunError> = match completion {
+        Ok(Ok(())) => final_state
             .map(StreamRunOutcome::Finished)
             .ok_or(StreamEndedWithoutState.into()),
-        Err(AgentError::Cancelled) => Ok(StreamRunOutcome::Cancelled),
-        Err(e) => Err(StreamRunError::Execution(e)),
+        Ok(Err(AgentError::Cancelled)) => Ok(StreamRunOutcome::Cancelled),
+        Ok(Err(e)) => Err(StreamRunError::Execution(e)),
+        Err(e) => Err(e),
+    };
+    let reported = match &outcome {
+        Ok(StreamRunOutcome::Finished(state)) => Ok(state),
+        Ok(StreamRunOutcome::Cancelled) => Err(RunFailure::from_agent_error(
+            thread_id.clone(),
+            &AgentError::Cancelled,
+        )),
+        Err(StreamRunError::Execution(e)) => {
+            Err(RunFailure::from_agent_error(thread_id.clone(), e))
+        }
+        Err(e) => Err(RunFailure {
+            thread_id: thread_id.clone(),
+            error: e.to_string(),
+            ..RunFailure::default()
+        }),
+    };
+    report_outcome(hooks, thread_id, reported).await;
+    outcome
+}
+
+/// Runs `compiled.invoke` between the start and end/failure callbacks of `hooks`. Shared by the
+/// runners' `invoke_with_config` methods.
+pub async fn invoke_with_hooks<S>(
+    compiled: &CompiledStateGraph<S>,
+    state: S,
+    run_config: Option<RunnableConfig>,
+    hooks: &RunHooks<S>,
+) -> Result<S, AgentError>
+where
+    S: Clone + Send + Sync + std::fmt::Debug + RunStateUsage + 'static,
+{
+    let thread_id = run_config.as_ref().and_then(|c| c.thread_id.clone());
+    hooks
+        .run_started(RunStart {
+            run_id: None,
+            thread_id: thread_id.clone(),
+        })
+        .await;
+    let result = compiled.invoke(state, run_config).await;
+    let reported = match &result {
+        Ok(state) => Ok(state),
+        Err(e) => Err(RunFailure::from_agent_error(thread_id.clone(), e)),
+    };
+    report_outcome(hooks, thread_id, reported).await;
+    result
+}
+
+/// Runs `on_end` with the final state or `on_error` with the failure.
+async fn report_outcome<S>(
+    hooks: &RunHooks<S>,
+    thread_id: Option<String>,
+    outcome: Result<&S, RunFailure>,
+) where
+    S: Clone + RunStateUsage,
+{
+    match outcome {
+        Ok(state) if hooks.has_on_end() => {
+            hooks
+                .run_ended(RunEnd {
+                    run_id: None,
+                    thread_id,
+                    state: state.clone(),
+                    usage: state.run_usage(),
+                })
+                .await
+        }
+        Ok(_) => {}
+        Err(failure) => hooks.run_failed(failure).await,
     }
 }
@@ -93,6 +93,8 @@ impl ModelService {
                     provider,
                     family: model.family.clone(),
                     capabilities: Self::extract_capabilities(model),
+                    context_limit: model.limit.as_ref().map(|l| l.context),
+                    output_limit: model.limit.as_ref().map(|l| l.output),
                 }
             })
             .collect()
@@ -0,0 +1,66 @@
+//! Confidence in a run's final answer, for routing low-confidence answers to human review.
+//!
+//! Set on [`ReActState::confidence`](crate::ReActState::confidence) by the ReAct
+//! [`ConfidenceNode`](crate::agent::react::ConfidenceNode) when scoring is enabled, and reported
+//! as `confidence` in [`RunEndResponse`](crate::RunEndResponse).
+
+use std::str::FromStr;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// How an answer's confidence is scored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfidenceScoring {
+    /// An extra LLM call rates the answer against the question and the tool evidence; falls
+    /// back to the heuristic when the call fails.
+    #[default]
+    Llm,
+    /// No LLM call: derived from how many of the turn's tool calls succeeded and whether the
+    /// answer hedges.
+    Heuristic,
+}
+
+impl ConfidenceScoring {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Llm => "llm",
+            Self::Heuristic => "heuristic",
+        }
+    }
+}
+
+impl FromStr for ConfidenceScoring {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "llm" => Ok(Self::Llm),
+            "heuristic" => Ok(Self::Heuristic),
+            other => Err(format!(
+                "unknown confidence scoring `{}` (expected llm or heuristic)",
+                other
+            )),
+        }
+    }
+}
+
+/// Confidence score of the final answer of a turn.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AnswerConfidence {
+    /// From 0.0 (no confidence) to 1.0 (fully confident).
+    pub score: f32,
+    /// Open questions or unverified claims in the answer; empty when none were found.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub uncertainties: Vec<String>,
+    /// How the score was obtained (the heuristic when the LLM call failed).
+    pub scoring: ConfidenceScoring,
+}
+
+impl AnswerConfidence {
+    /// Whether the score is below `threshold`, e.g. to send the answer to human review.
+    pub fn is_below(&self, threshold: f32) -> bool {
+        self.score < threshold
+    }
+}
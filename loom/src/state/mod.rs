@@ -25,11 +25,18 @@
 //! // ... pass state to run_agent or StateGraph::invoke
 //! ```
 
+pub mod confidence;
 pub mod react_state;
 pub mod tool_output_normalizer;
+pub mod tool_result_framing;
 
+pub use confidence::{AnswerConfidence, ConfidenceScoring};
+pub(crate) use react_state::migrate_core_react_state_v1;
 pub use react_state::{ReActState, ToolCall, ToolResult};
 pub use tool_output_normalizer::{
     normalize_tool_output, NormalizationConfig, NormalizedToolOutput, ToolOutputHint,
     ToolOutputStrategy, ToolStorageRef,
 };
+pub use tool_result_framing::{
+    InjectionSanitizer, ToolResultFraming, ToolResultFramingMode, ToolResultOrigin,
+};
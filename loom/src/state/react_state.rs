@@ -4,12 +4,19 @@
 //! nodes read and write these fields. ToolCall and ToolResult align with MCP `tools/call`
 //! and result content.
 
-use crate::memory::uuid6;
-use crate::message::{AssistantToolCall, Message};
+use crate::compress::HistoryWindow;
+use crate::graph::{SizedState, StateLimits, StateSize};
+use crate::helve::{ApprovalDecision, ApprovalMemory};
+use crate::memory::{uuid6, StateMigrations, VersionedState};
+use crate::message::{AssistantToolCall, Message, UserContent};
+use crate::tool_source::ToolCallContent;
 use crate::LlmUsage;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
 use tracing::debug;
 
+use crate::state::confidence::AnswerConfidence;
 use crate::state::tool_output_normalizer::{ToolOutputStrategy, ToolStorageRef};
 
 /// A single tool invocation produced by the LLM (Think node) and consumed by Act.
@@ -199,12 +206,25 @@ pub struct ReActState {
     /// Consumed by ActNode: `Some(true)` → execute the tool; `Some(false)` → add "User rejected" result.
     #[serde(default)]
     pub approval_result: Option<bool>,
+    /// How long ActNode remembers `approval_result` once it consumes it; set by the caller
+    /// together with `approval_result` (see [`ReActState::apply_approval_decision`]).
+    #[serde(default)]
+    pub approval_memory: ApprovalMemory,
+    /// Approval decisions remembered for this thread, by tool name (or tool, pattern rule and
+    /// arguments digest; see [`crate::ApprovalRules::memory_key`]). Written by ActNode; kept
+    /// with the thread's checkpoint so later turns do not ask again.
+    #[serde(default)]
+    pub remembered_approvals: HashMap<String, bool>,
     /// Token usage for the last LLM call (Think node). Set by ThinkNode when the provider returns usage.
     #[serde(default)]
     pub usage: Option<LlmUsage>,
     /// Accumulated token usage over the whole run (sum of all Think turns). Set by ThinkNode.
     #[serde(default)]
     pub total_usage: Option<LlmUsage>,
+    /// Accumulated token usage split by model id, for runs where nodes use different models
+    /// (see [`crate::llm::NodeLlmOverrides`]). Sums to `total_usage` when every call is labelled.
+    #[serde(default)]
+    pub usage_by_model: HashMap<String, LlmUsage>,
     /// Number of messages at the time of the last Think; used for hybrid token estimate in compression.
     #[serde(default)]
     pub message_count_after_last_think: Option<usize>,
@@ -218,6 +238,19 @@ pub struct ReActState {
     /// Used by conditional routing after completion_check node.
     #[serde(default)]
     pub should_continue: bool,
+    /// Number of times VerifyNode sent a draft answer back to think in the current turn.
+    /// Bounds the reflection loop; reset when a new user message starts a turn.
+    #[serde(default)]
+    pub reflection_count: u32,
+    /// Revision of the answer being streamed in the current turn. Bumped (with a
+    /// [`StreamEvent::AnswerRevised`](crate::stream::StreamEvent::AnswerRevised)) whenever a
+    /// streamed draft is discarded; reset when a new user message starts a turn.
+    #[serde(default)]
+    pub answer_revision: u32,
+    /// Confidence in the final answer of the current turn, when scoring is enabled (see
+    /// [`crate::agent::react::ConfidenceNode`]); reset when a new user message starts a turn.
+    #[serde(default)]
+    pub confidence: Option<AnswerConfidence>,
 }
 
 impl Default for ReActState {
@@ -229,16 +262,50 @@ impl Default for ReActState {
             tool_results: vec![],
             turn_count: 0,
             approval_result: None,
+            approval_memory: ApprovalMemory::Once,
+            remembered_approvals: HashMap::new(),
             usage: None,
             total_usage: None,
+            usage_by_model: HashMap::new(),
             message_count_after_last_think: None,
             think_count: 0,
             summary: None,
             should_continue: true,
+            reflection_count: 0,
+            answer_revision: 0,
+            confidence: None,
         }
     }
 }
 
+impl VersionedState for ReActState {
+    /// Version 2 tags checkpoints with their schema version; see [`migrate_react_state_v1`].
+    const STATE_VERSION: u32 = 2;
+
+    fn migrations() -> StateMigrations {
+        StateMigrations::new().register(1, migrate_react_state_v1)
+    }
+}
+
+/// Upgrades an untagged (version 1) ReAct state: `should_continue` was added with a field-level
+/// serde default of `false`, so threads saved before it would stop routing; restore `true`.
+pub(crate) fn migrate_react_state_v1(mut value: Value) -> Result<Value, String> {
+    let map = value
+        .as_object_mut()
+        .ok_or_else(|| "react state is not a JSON object".to_string())?;
+    map.entry("should_continue").or_insert(Value::Bool(true));
+    Ok(value)
+}
+
+/// Applies [`migrate_react_state_v1`] to the ReAct state nested under `core` (DUP, ToT).
+pub(crate) fn migrate_core_react_state_v1(mut value: Value) -> Result<Value, String> {
+    let core = value
+        .get_mut("core")
+        .ok_or_else(|| "state has no core".to_string())?;
+    *core = migrate_react_state_v1(core.take())?;
+    Ok(value)
+}
+
 fn normalize_tool_call_ids(mut calls: Vec<ToolCall>) -> Vec<ToolCall> {
     for tc in &mut calls {
         if tc.id.as_deref().is_none_or(|s| s.is_empty()) {
@@ -261,6 +328,12 @@ fn compute_think_usage(
 }
 
 impl ReActState {
+    /// Sets the user's answer to a pending `approval_required` interrupt before resuming.
+    pub fn apply_approval_decision(&mut self, decision: ApprovalDecision) {
+        self.approval_result = Some(decision.approved);
+        self.approval_memory = decision.remember;
+    }
+
     /// Applies a Think step: append assistant message, set `tool_calls`, update usage counters.
     pub fn apply_think(
         mut self,
@@ -322,6 +395,24 @@ impl ReActState {
         self
     }
 
+    /// Adds `usage` to the running total for `model` in `usage_by_model`.
+    pub fn record_model_usage(&mut self, model: &str, usage: &LlmUsage) {
+        let entry = self.usage_by_model.entry(model.to_string()).or_default();
+        *entry = entry.accumulate(usage);
+    }
+
+    /// Adds usage from an LLM call made outside Think (e.g. ToT expand/evaluate) to
+    /// `total_usage`, and to `usage_by_model` when `model` is known. Leaves `usage` untouched.
+    pub fn record_usage(&mut self, model: Option<&str>, usage: &LlmUsage) {
+        self.total_usage = Some(match self.total_usage.as_ref() {
+            Some(t) => t.accumulate(usage),
+            None => usage.clone(),
+        });
+        if let Some(model) = model {
+            self.record_model_usage(model, usage);
+        }
+    }
+
     /// Returns the content of the chronologically last Assistant message, if any.
     ///
     /// Used by callers (e.g. bot, CLI) to get the final reply without scanning `messages`.
@@ -386,6 +477,105 @@ impl crate::command::builtins::SummarizeState for ReActState {
     }
 }
 
+/// Share of `max_bytes` one message may take before shrinking truncates it (1/4).
+const SHRINK_MESSAGE_SHARE: usize = 4;
+
+impl SizedState for ReActState {
+    /// `bytes` counts the text of messages and tool results.
+    fn state_size(&self) -> StateSize {
+        let messages: usize = self.messages.iter().map(|m| m.content().len()).sum();
+        let results: usize = self.tool_results.iter().map(|r| r.content.len()).sum();
+        StateSize {
+            messages: self.messages.len(),
+            bytes: messages + results,
+        }
+    }
+
+    /// Cuts the middle out of messages and tool results larger than a quarter of `max_bytes`,
+    /// then drops the oldest user turns (keeping the system prompt, pinned messages and an
+    /// elided-history note, see [`HistoryWindow`]) until the state fits.
+    fn shrink(&mut self, limits: &StateLimits) {
+        if let Some(max_bytes) = limits.max_bytes {
+            let cap = (max_bytes / SHRINK_MESSAGE_SHARE).max(1);
+            for message in self.messages.iter_mut().filter(|m| !m.is_pinned()) {
+                truncate_message(message, cap);
+            }
+            for result in &mut self.tool_results {
+                if let Some(cut) = cut_middle(&result.content, cap) {
+                    result.content = cut;
+                }
+            }
+        }
+        let full = self.messages.clone();
+        let users = full
+            .iter()
+            .filter(|m| matches!(m, Message::User(_)))
+            .count();
+        for max_turns in (1..users).rev() {
+            if limits.exceeded(self.state_size()).is_none() {
+                break;
+            }
+            let window = HistoryWindow {
+                max_turns: Some(max_turns),
+                max_tokens: None,
+                summarize_elided: true,
+            };
+            self.messages = window.apply(full.clone());
+        }
+        let removed = full.len().saturating_sub(self.messages.len());
+        if removed > 0 {
+            self.message_count_after_last_think = self
+                .message_count_after_last_think
+                .map(|n| n.saturating_sub(removed));
+        }
+    }
+}
+
+/// Truncates the text of a user, assistant or tool message longer than `cap` bytes. System
+/// messages and multimodal user messages are left alone.
+fn truncate_message(message: &mut Message, cap: usize) {
+    match message {
+        Message::User(UserContent::Text(text)) => {
+            if let Some(cut) = cut_middle(text, cap) {
+                *text = cut;
+            }
+        }
+        Message::Assistant(payload) => {
+            if let Some(cut) = cut_middle(&payload.content, cap) {
+                payload.content = cut;
+            }
+        }
+        Message::Tool { content, .. } => {
+            if let Some(cut) = cut_middle(&content.to_display_string(), cap) {
+                *content = ToolCallContent::text(cut);
+            }
+        }
+        Message::System(_) | Message::User(_) => {}
+    }
+}
+
+/// `text` with its middle replaced by a note when longer than `cap` bytes; keeps `cap / 2`
+/// bytes from each end.
+fn cut_middle(text: &str, cap: usize) -> Option<String> {
+    if text.len() <= cap {
+        return None;
+    }
+    let mut head = cap / 2;
+    while !text.is_char_boundary(head) {
+        head -= 1;
+    }
+    let mut tail = text.len() - cap / 2;
+    while !text.is_char_boundary(tail) {
+        tail += 1;
+    }
+    Some(format!(
+        "{}\n[... {} bytes cut to keep the run state under its size limit ...]\n{}",
+        &text[..head],
+        tail - head,
+        &text[tail..]
+    ))
+}
+
 // ReActState, ToolCall, ToolResult: fields are standard types (String, Vec<Message>, Option<String>, etc.),
 // so they satisfy Clone + Send + Sync + 'static required by Node<S> and StateGraph<S>.
 
@@ -406,6 +596,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn legacy_checkpoint_migrates_should_continue() {
+        use crate::memory::{Serializer, VersionedJsonSerializer};
+
+        let legacy = br#"{"messages":[{"User":"hi"}],"turn_count":2}"#;
+        let state: ReActState = VersionedJsonSerializer.deserialize(legacy).unwrap();
+        assert!(state.should_continue);
+        assert_eq!(state.turn_count, 2);
+
+        let bytes = VersionedJsonSerializer.serialize(&state).unwrap();
+        let tagged: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(tagged[crate::memory::STATE_VERSION_KEY], 2);
+        let mut stopped = state.clone();
+        stopped.should_continue = false;
+        let bytes = VersionedJsonSerializer.serialize(&stopped).unwrap();
+        let back: ReActState = VersionedJsonSerializer.deserialize(&bytes).unwrap();
+        assert!(!back.should_continue);
+    }
+
     #[test]
     fn apply_think_appends_message_and_increments_think_count() {
         let state = ReActState::default();
@@ -443,4 +652,47 @@ mod tests {
         assert_eq!(total.completion_tokens, 7);
         assert_eq!(total.total_tokens, 20);
     }
+
+    #[test]
+    fn record_model_usage_accumulates_per_model() {
+        let usage = |p: u32, c: u32| LlmUsage {
+            prompt_tokens: p,
+            completion_tokens: c,
+            total_tokens: p + c,
+            prompt_tokens_details: None,
+            completion_tokens_details: None,
+        };
+        let mut state = ReActState::default();
+        state.record_model_usage("openai/gpt-4o", &usage(10, 5));
+        state.record_model_usage("openai/gpt-4o-mini", &usage(3, 1));
+        state.record_model_usage("openai/gpt-4o", &usage(2, 2));
+        assert_eq!(state.usage_by_model.len(), 2);
+        assert_eq!(state.usage_by_model["openai/gpt-4o"].total_tokens, 19);
+        assert_eq!(state.usage_by_model["openai/gpt-4o-mini"].total_tokens, 4);
+    }
+
+    #[test]
+    fn shrink_cuts_large_messages_then_drops_old_turns() {
+        let mut state = ReActState {
+            messages: vec![
+                Message::system("sys"),
+                Message::user("first"),
+                Message::assistant("x".repeat(1000)),
+                Message::user("second"),
+                Message::assistant("done"),
+            ],
+            ..Default::default()
+        };
+        let limits = StateLimits {
+            max_messages: Some(4),
+            max_bytes: Some(400),
+            ..Default::default()
+        };
+        state.shrink(&limits);
+        let size = state.state_size();
+        assert!(limits.exceeded(size).is_none(), "{:?}", size);
+        assert!(matches!(&state.messages[0], Message::System(s) if s == "sys"));
+        assert_eq!(state.messages.last().unwrap().content(), "done");
+        assert!(!state.messages.iter().any(|m| m.content() == "first"));
+    }
 }
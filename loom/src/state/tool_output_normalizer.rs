@@ -5,12 +5,13 @@
 //! tool outputs from exploding the LLM context in subsequent turns.
 
 use chrono::Utc;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Strategy for normalizing tool output.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 #[derive(Default)]
 pub enum ToolOutputStrategy {
@@ -28,7 +29,7 @@ pub enum ToolOutputStrategy {
 }
 
 /// Optional metadata supplied by a tool to influence output normalization.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct ToolOutputHint {
     /// Strong preference for a specific normalization strategy.
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -0,0 +1,181 @@
+//! Named custom event types: JSON schemas for `Custom` stream payloads.
+//!
+//! A custom payload names its type in a `"type"` field (e.g. `approval_required`). Nodes and
+//! tools register the types they emit with [`register_custom_event`]; frontends fetch the
+//! registry with the `event_schema_list` request and generate types from it. In debug builds
+//! [`StreamWriter::emit_custom`](super::StreamWriter::emit_custom) and
+//! [`ToolStreamWriter::emit_custom`](super::ToolStreamWriter::emit_custom) check payloads of a
+//! registered type against its schema and log mismatches; payloads without a registered type
+//! are not checked.
+
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::cli_run::validate_schema;
+use crate::graph::ROUTE_SPLIT_EVENT_TYPE;
+use crate::helve::APPROVAL_REQUIRED_EVENT_TYPE;
+use crate::tool_source::TOOL_SOURCE_UNHEALTHY_EVENT;
+
+/// A registered custom event type.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CustomEventSchema {
+    /// Value of the payload's `type` field.
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// JSON Schema of the whole payload (including `type`).
+    pub schema: Value,
+}
+
+static REGISTRY: Lazy<RwLock<BTreeMap<String, CustomEventSchema>>> = Lazy::new(|| {
+    RwLock::new(
+        builtin_schemas()
+            .into_iter()
+            .map(|s| (s.name.clone(), s))
+            .collect(),
+    )
+});
+
+/// Custom events emitted by loom itself.
+fn builtin_schemas() -> Vec<CustomEventSchema> {
+    vec![
+        CustomEventSchema {
+            name: APPROVAL_REQUIRED_EVENT_TYPE.to_string(),
+            description: Some("A tool call waits for user approval.".to_string()),
+            schema: json!({
+                "type": "object",
+                "required": ["type", "tool_name", "arguments"],
+                "properties": {
+                    "type": { "type": "string", "enum": [APPROVAL_REQUIRED_EVENT_TYPE] },
+                    "node_id": { "type": "string" },
+                    "tool_name": { "type": "string" },
+                    "call_id": { "type": ["string", "null"] },
+                    "arguments": {}
+                }
+            }),
+        },
+        CustomEventSchema {
+            name: TOOL_SOURCE_UNHEALTHY_EVENT.to_string(),
+            description: Some("A tool source's circuit breaker opened.".to_string()),
+            schema: json!({
+                "type": "object",
+                "required": ["type", "source", "breaker"],
+                "properties": {
+                    "type": { "type": "string", "enum": [TOOL_SOURCE_UNHEALTHY_EVENT] },
+                    "source": { "type": "string" },
+                    "url": { "type": "string" },
+                    "tool": { "type": "string" },
+                    "breaker": { "type": "string" },
+                    "error": { "type": "string" }
+                }
+            }),
+        },
+        CustomEventSchema {
+            name: ROUTE_SPLIT_EVENT_TYPE.to_string(),
+            description: Some("A weighted edge picked a branch.".to_string()),
+            schema: json!({
+                "type": "object",
+                "required": ["type", "from", "to", "key", "share"],
+                "properties": {
+                    "type": { "type": "string", "enum": [ROUTE_SPLIT_EVENT_TYPE] },
+                    "from": { "type": "string" },
+                    "to": { "type": "string" },
+                    "key": { "type": "string" },
+                    "share": { "type": "number" }
+                }
+            }),
+        },
+    ]
+}
+
+/// Registers (or replaces) the schema of custom event type `name`.
+pub fn register_custom_event(name: impl Into<String>, description: Option<String>, schema: Value) {
+    let name = name.into();
+    let entry = CustomEventSchema {
+        name: name.clone(),
+        description,
+        schema,
+    };
+    REGISTRY
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(name, entry);
+}
+
+/// All registered custom event types, sorted by name.
+pub fn custom_event_schemas() -> Vec<CustomEventSchema> {
+    REGISTRY
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .values()
+        .cloned()
+        .collect()
+}
+
+/// Schema of custom event type `name`, when registered.
+pub fn custom_event_schema(name: &str) -> Option<CustomEventSchema> {
+    REGISTRY
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(name)
+        .cloned()
+}
+
+/// Validates a custom payload against the schema registered for its `type`. Payloads without a
+/// `type` or with an unregistered one are accepted.
+pub fn validate_custom_event(value: &Value) -> Result<(), String> {
+    let Some(name) = value.get("type").and_then(Value::as_str) else {
+        return Ok(());
+    };
+    match custom_event_schema(name) {
+        Some(entry) => validate_schema(value, &entry.schema, name),
+        None => Ok(()),
+    }
+}
+
+/// Debug-build check used by the stream writers before emitting a custom payload.
+pub(crate) fn debug_check_custom_event(value: &Value) {
+    if cfg!(debug_assertions) {
+        if let Err(e) = validate_custom_event(value) {
+            tracing::error!("custom event does not match its registered schema: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_types_are_registered() {
+        let names: Vec<String> = custom_event_schemas().into_iter().map(|s| s.name).collect();
+        assert!(names.contains(&APPROVAL_REQUIRED_EVENT_TYPE.to_string()));
+        assert!(names.contains(&TOOL_SOURCE_UNHEALTHY_EVENT.to_string()));
+        assert!(names.contains(&ROUTE_SPLIT_EVENT_TYPE.to_string()));
+    }
+
+    #[test]
+    fn registered_type_is_validated_and_unknown_types_pass() {
+        register_custom_event(
+            "test_progress",
+            Some("Progress of a test node.".to_string()),
+            json!({
+                "type": "object",
+                "required": ["type", "pct"],
+                "properties": { "pct": { "type": "integer" } }
+            }),
+        );
+        assert!(validate_custom_event(&json!({"type": "test_progress", "pct": 50})).is_ok());
+        let err =
+            validate_custom_event(&json!({"type": "test_progress", "pct": "half"})).unwrap_err();
+        assert!(err.contains("pct"), "{}", err);
+        assert!(validate_custom_event(&json!({"type": "test_progress"})).is_err());
+        assert!(validate_custom_event(&json!({"type": "unregistered", "x": 1})).is_ok());
+        assert!(validate_custom_event(&json!({"progress": 50})).is_ok());
+    }
+}
@@ -20,6 +20,10 @@ pub struct MessageChunk {
     /// When `Thinking`, ACP bridge emits `agent_thought_chunk`; otherwise `agent_message_chunk`.
     #[allow(clippy::struct_field_names)]
     pub kind: MessageChunkKind,
+    /// Answer revision the chunk belongs to (0 for the first draft). Set by
+    /// [`ChunkToStreamSender`](super::ChunkToStreamSender); a new revision follows a
+    /// [`StreamEvent::AnswerRevised`](super::StreamEvent::AnswerRevised).
+    pub revision: u32,
 }
 
 impl MessageChunk {
@@ -28,6 +32,7 @@ impl MessageChunk {
         Self {
             content: content.into(),
             kind: MessageChunkKind::Message,
+            revision: 0,
         }
     }
 
@@ -36,6 +41,7 @@ impl MessageChunk {
         Self {
             content: content.into(),
             kind: MessageChunkKind::Thinking,
+            revision: 0,
         }
     }
 }
@@ -45,6 +51,7 @@ impl Default for MessageChunk {
         Self {
             content: String::new(),
             kind: MessageChunkKind::Message,
+            revision: 0,
         }
     }
 }
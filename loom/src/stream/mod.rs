@@ -25,7 +25,11 @@
 //!     Ok((state, Next::Continue))
 //! }
 //! ```
+//!
+//! Custom payloads that carry a `"type"` can have a JSON schema registered with
+//! [`register_custom_event`]; see [`event_schema`].
 
+pub mod event_schema;
 pub mod message;
 pub mod metadata;
 pub mod sender;
@@ -33,10 +37,14 @@ pub mod stream_event;
 pub mod stream_mode;
 pub mod writers;
 
+pub use event_schema::{
+    custom_event_schema, custom_event_schemas, register_custom_event, validate_custom_event,
+    CustomEventSchema,
+};
 pub use message::{MessageChunk, MessageChunkKind};
 pub use metadata::{CheckpointEvent, StreamMetadata};
 pub use sender::ChunkToStreamSender;
-pub use stream_event::StreamEvent;
+pub use stream_event::{StreamEvent, TimingKind};
 pub use stream_mode::StreamMode;
 pub use writers::{StreamWriter, ToolStreamWriter};
 
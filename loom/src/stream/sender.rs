@@ -1,4 +1,4 @@
-use super::super::{MessageChunk, StreamEvent, StreamMetadata};
+use super::super::{MessageChunk, MessageChunkKind, StreamEvent, StreamMetadata};
 use std::fmt::Debug;
 use tokio::sync::mpsc;
 
@@ -14,6 +14,7 @@ where
     stream_tx: mpsc::Sender<StreamEvent<S>>,
     node_id: String,
     namespace: Option<String>,
+    revision: u32,
 }
 
 impl<S> ChunkToStreamSender<S>
@@ -25,6 +26,7 @@ where
             stream_tx,
             node_id: node_id.into(),
             namespace: None,
+            revision: 0,
         }
     }
 
@@ -37,9 +39,16 @@ where
             stream_tx,
             node_id: node_id.into(),
             namespace,
+            revision: 0,
         }
     }
 
+    /// Stamps forwarded chunks with answer `revision` (see [`MessageChunk::revision`]).
+    pub fn with_revision(mut self, revision: u32) -> Self {
+        self.revision = revision;
+        self
+    }
+
     /// Returns (chunk_tx, chunk_rx). Pass chunk_tx to `invoke_stream`, then await
     /// `forward(chunk_rx)` together with invoke_stream via `tokio::join!` so forwarding
     /// completes before the caller returns.
@@ -54,18 +63,34 @@ where
     /// the very first chunk was received (used by callers to compute prefill/decode durations).
     pub async fn forward(
         &self,
-        mut chunk_rx: mpsc::Receiver<MessageChunk>,
+        chunk_rx: mpsc::Receiver<MessageChunk>,
     ) -> (usize, Option<std::time::Instant>) {
+        let (forwarded, first_token_at, _) = self.forward_collecting(chunk_rx).await;
+        (forwarded, first_token_at)
+    }
+
+    /// Like [`forward`](Self::forward), and also returns the concatenated message text (thinking
+    /// chunks excluded), so a caller that stops the LLM mid-stream still has the partial answer.
+    pub async fn forward_collecting(
+        &self,
+        mut chunk_rx: mpsc::Receiver<MessageChunk>,
+    ) -> (usize, Option<std::time::Instant>, String) {
         let stream_tx = self.stream_tx.clone();
         let node_id = self.node_id.clone();
         let namespace = self.namespace.clone();
+        let revision = self.revision;
         let mut forwarded = 0usize;
         let mut first_token_at: Option<std::time::Instant> = None;
-        while let Some(chunk) = chunk_rx.recv().await {
+        let mut text = String::new();
+        while let Some(mut chunk) = chunk_rx.recv().await {
             if first_token_at.is_none() {
                 first_token_at = Some(std::time::Instant::now());
             }
             forwarded += 1;
+            chunk.revision = revision;
+            if chunk.kind == MessageChunkKind::Message {
+                text.push_str(&chunk.content);
+            }
             let event = StreamEvent::Messages {
                 chunk,
                 metadata: StreamMetadata {
@@ -75,6 +100,6 @@ where
             };
             let _ = stream_tx.send(event).await;
         }
-        (forwarded, first_token_at)
+        (forwarded, first_token_at, text)
     }
 }
@@ -1,4 +1,5 @@
 use super::super::{CheckpointEvent, MessageChunk, StreamMetadata};
+use crate::graph::StateSize;
 use serde_json::Value;
 use std::fmt::Debug;
 
@@ -96,6 +97,8 @@ where
         nodes_added: usize,
         /// Number of new edges added.
         edges_added: usize,
+        /// Why the expansion was not made (e.g. token budget exhausted); counts are 0 then.
+        denied: Option<String>,
     },
     /// LLM token usage for the last completion (e.g. after think node).
     /// Emitted when the provider returns usage (e.g. OpenAI); consumers can print when verbose.
@@ -113,6 +116,13 @@ where
         /// `None` in non-streaming mode.
         decode_duration: Option<std::time::Duration>,
     },
+    /// Graph structure with the running node and per-node visit counts. Emitted by the graph
+    /// executor when each node starts and once more (with no current node) when the run ends.
+    /// Enabled by `StreamMode::Graph` or `StreamMode::Debug`.
+    GraphProgress(crate::graph::GraphProgress),
+    /// Why the last LLM completion stopped (Think node), when the provider reports it.
+    /// `Length` means the output token limit truncated the answer.
+    FinishReason { reason: crate::llm::FinishReason },
     /// LLM streaming tool call argument delta (Think node, per chunk).
     ToolCallChunk {
         call_id: Option<String>,
@@ -136,6 +146,13 @@ where
         name: String,
         content: String,
     },
+    /// Partial result of a running tool, e.g. a line of a command's stdout (Act node, from
+    /// [`ToolStreamWriter::emit_partial`](crate::stream::ToolStreamWriter::emit_partial)).
+    ToolOutputChunk {
+        call_id: Option<String>,
+        name: String,
+        content: String,
+    },
     /// Tool execution finished (Act node, after tool returns).
     ToolEnd {
         call_id: Option<String>,
@@ -152,4 +169,83 @@ where
         name: String,
         arguments: Value,
     },
+    /// Generated thread title and short summary, emitted after the run finishes
+    /// when auto-summarize is enabled (for chat UI sidebars).
+    ThreadSummary {
+        /// One-line title.
+        title: String,
+        /// Summary of at most two sentences.
+        summary: String,
+    },
+    /// The tool list changed during the run (e.g. an MCP server sent
+    /// `notifications/tools/list_changed`); the new definitions are sent to the LLM from this
+    /// think turn on (Think node, before the LLM call).
+    ToolsRefreshed {
+        /// Names of all tools now available.
+        tools: Vec<String>,
+    },
+    /// The prompt did not fit the model's context window, so this think call goes to a
+    /// configured larger-context model instead (Think node, before the LLM call).
+    ModelSwitched {
+        /// Model the node normally calls.
+        from: String,
+        /// Fallback model used for this call.
+        to: String,
+        /// Estimated prompt size in tokens.
+        prompt_tokens: u32,
+        /// Context size of `from` in tokens.
+        context_limit: u32,
+    },
+    /// The answer streamed so far was discarded and a new draft follows (e.g. reflection found
+    /// gaps, or malformed tool calls were repaired). Message chunks of the new draft carry
+    /// `revision`; clients should clear what they displayed for earlier revisions.
+    AnswerRevised {
+        /// Why the draft was discarded (`reflection`, `tool_call_repair`).
+        reason: String,
+        /// Revision of the chunks that follow.
+        revision: u32,
+    },
+    /// How long one part of the run took. Enabled by `StreamMode::Tasks` or `StreamMode::Debug`.
+    Timing {
+        /// Node the measurement belongs to.
+        node_id: String,
+        kind: TimingKind,
+        /// Tool name for [`TimingKind::Tool`]; for [`TimingKind::Llm`], the endpoint that served
+        /// the call when the LLM routes between several (see [`crate::llm::RoutingLlmClient`]).
+        name: Option<String>,
+        duration_ms: u64,
+        /// State size after the node, for [`TimingKind::Node`] when the graph has state limits
+        /// (see [`crate::graph::StateLimits`]).
+        state: Option<StateSize>,
+    },
+}
+
+/// What a [`StreamEvent::Timing`] measured.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TimingKind {
+    /// One node run, from start to output (graph executor).
+    Node,
+    /// Think step work before the LLM call: tool refresh and selection, context check and
+    /// compaction.
+    Prompt,
+    /// From the start of the first LLM call of a think step to its first streamed token. Only
+    /// measured when the response streams.
+    FirstToken,
+    /// All LLM calls of a think step, including continuations and tool-call repairs.
+    Llm,
+    /// One tool call (Act node).
+    Tool,
+}
+
+impl TimingKind {
+    /// Wire name (`node`, `prompt`, `first_token`, `llm`, `tool`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Node => "node",
+            Self::Prompt => "prompt",
+            Self::FirstToken => "first_token",
+            Self::Llm => "llm",
+            Self::Tool => "tool",
+        }
+    }
 }
@@ -18,6 +18,8 @@ pub enum StreamMode {
     Tasks,
     /// Emit tool lifecycle events (tool_call, tool_start, tool_output, tool_end, tool_approval).
     Tools,
-    /// Emit both checkpoints and tasks events (debug mode).
+    /// Emit graph progress (node set, edges, current node, visit counts) as each node starts.
+    Graph,
+    /// Emit checkpoints, tasks and graph progress events (debug mode).
     Debug,
 }
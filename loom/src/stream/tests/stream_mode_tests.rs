@@ -16,12 +16,13 @@ mod tests {
             StreamMode::Checkpoints,
             StreamMode::Tasks,
             StreamMode::Tools,
+            StreamMode::Graph,
             StreamMode::Debug,
         ];
 
         // Ensure all modes are unique
         let modes_set: HashSet<StreamMode> = HashSet::from_iter(modes.iter().copied());
-        assert_eq!(modes_set.len(), 8, "All stream modes should be unique");
+        assert_eq!(modes_set.len(), 9, "All stream modes should be unique");
 
         // Test Debug mode contains other modes' functionality
         assert!(StreamMode::Debug != StreamMode::Tasks);
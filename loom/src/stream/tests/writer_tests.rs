@@ -1,8 +1,9 @@
 #[cfg(test)]
 mod tests {
-    use crate::stream::{StreamEvent, StreamMode, StreamWriter};
+    use crate::stream::{StreamEvent, StreamMode, StreamWriter, ToolStreamWriter};
     use serde_json;
     use std::collections::HashSet;
+    use std::sync::Arc;
     use tokio::sync::mpsc;
 
     #[derive(Clone, Debug, PartialEq)]
@@ -459,4 +460,26 @@ mod tests {
             _ => panic!("expected ToolApproval event"),
         }
     }
+
+    /// **Scenario**: ToolStreamWriter keeps partial results in order and forwards each chunk.
+    #[test]
+    fn tool_stream_writer_partial_output_is_kept_and_forwarded() {
+        let forwarded = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = forwarded.clone();
+        let writer = ToolStreamWriter::noop().with_partial_output(move |chunk| {
+            sink.lock().unwrap().push(chunk);
+            true
+        });
+        let clone = writer.clone();
+
+        assert!(writer.emit_partial("line 1\n"));
+        assert!(clone.emit_partial("line 2\n"));
+
+        assert_eq!(writer.partial_output(), "line 1\nline 2\n");
+        assert_eq!(*forwarded.lock().unwrap(), vec!["line 1\n", "line 2\n"]);
+
+        let silent = ToolStreamWriter::noop();
+        assert!(!silent.emit_partial("kept"));
+        assert_eq!(silent.partial_output(), "kept");
+    }
 }
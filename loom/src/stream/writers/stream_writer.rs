@@ -1,10 +1,15 @@
-use super::super::{CheckpointEvent, MessageChunk, StreamEvent, StreamMetadata, StreamMode};
+use super::super::event_schema::debug_check_custom_event;
+use super::super::{
+    CheckpointEvent, MessageChunk, StreamEvent, StreamMetadata, StreamMode, TimingKind,
+};
 use serde_json::Value;
 use std::collections::HashSet;
 use std::fmt::Debug;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
+use crate::graph::StateSize;
+
 /// A writer for emitting streaming events from nodes and tools.
 ///
 /// `StreamWriter` encapsulates the stream sender and mode checking, providing
@@ -82,7 +87,9 @@ where
     /// Emits a custom JSON payload.
     ///
     /// Only sends if `StreamMode::Custom` is enabled and a sender is available.
-    /// Returns `true` if the event was sent, `false` otherwise.
+    /// Returns `true` if the event was sent, `false` otherwise. In debug builds a payload whose
+    /// `type` is registered (see [`crate::stream::register_custom_event`]) is checked against
+    /// its schema and mismatches are logged.
     ///
     /// # Arguments
     ///
@@ -91,6 +98,7 @@ where
         if !self.modes.contains(&StreamMode::Custom) {
             return false;
         }
+        debug_check_custom_event(&value);
         if let Some(tx) = &self.tx {
             tx.send(StreamEvent::Custom(value)).await.is_ok()
         } else {
@@ -108,6 +116,7 @@ where
         if !self.modes.contains(&StreamMode::Custom) {
             return false;
         }
+        debug_check_custom_event(&value);
         if let Some(tx) = &self.tx {
             tx.try_send(StreamEvent::Custom(value)).is_ok()
         } else {
@@ -316,6 +325,59 @@ where
         }
     }
 
+    /// Emits a [`StreamEvent::Timing`] for `node_id`.
+    ///
+    /// Only sends if `StreamMode::Tasks` or `StreamMode::Debug` is enabled and a sender is available.
+    /// Returns `true` if the event was sent, `false` otherwise.
+    pub async fn emit_timing(
+        &self,
+        node_id: impl Into<String>,
+        kind: TimingKind,
+        name: Option<String>,
+        duration: std::time::Duration,
+    ) -> bool {
+        self.send_timing(node_id.into(), kind, name, duration, None)
+            .await
+    }
+
+    /// Emits a [`TimingKind::Node`] timing for `node_id` with the state size after the node.
+    ///
+    /// Same conditions as [`emit_timing`](Self::emit_timing).
+    pub async fn emit_node_timing(
+        &self,
+        node_id: impl Into<String>,
+        duration: std::time::Duration,
+        state: Option<StateSize>,
+    ) -> bool {
+        self.send_timing(node_id.into(), TimingKind::Node, None, duration, state)
+            .await
+    }
+
+    async fn send_timing(
+        &self,
+        node_id: String,
+        kind: TimingKind,
+        name: Option<String>,
+        duration: std::time::Duration,
+        state: Option<StateSize>,
+    ) -> bool {
+        if !self.modes.contains(&StreamMode::Tasks) && !self.modes.contains(&StreamMode::Debug) {
+            return false;
+        }
+        if let Some(tx) = &self.tx {
+            let event = StreamEvent::Timing {
+                node_id,
+                kind,
+                name,
+                duration_ms: duration.as_millis() as u64,
+                state,
+            };
+            tx.send(event).await.is_ok()
+        } else {
+            false
+        }
+    }
+
     fn is_tools_enabled(&self) -> bool {
         self.modes.contains(&StreamMode::Tools) || self.modes.contains(&StreamMode::Debug)
     }
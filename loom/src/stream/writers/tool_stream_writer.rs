@@ -1,13 +1,15 @@
+use super::super::event_schema::debug_check_custom_event;
 use serde_json::Value;
 use std::fmt::Debug;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 /// A writer for emitting custom streaming events from tools.
 ///
 /// This is a type-erased wrapper that doesn't require the state type `S`,
 /// making it suitable for use in tools which are state-agnostic. Tools can
 /// use this to emit progress updates, intermediate results, or any custom
-/// JSON data during execution.
+/// JSON data during execution. Long-running tools stream partial results with
+/// [`emit_partial`](Self::emit_partial) (e.g. each line of a command's stdout).
 ///
 /// # Example
 ///
@@ -36,6 +38,10 @@ pub struct ToolStreamWriter {
     emit_fn: Arc<dyn Fn(Value) -> bool + Send + Sync>,
     /// Function that emits a tool output chunk. Returns true if sent successfully.
     output_fn: Option<Arc<dyn Fn(String) -> bool + Send + Sync>>,
+    /// Function that forwards a partial result. Returns true if sent successfully.
+    partial_fn: Option<Arc<dyn Fn(String) -> bool + Send + Sync>>,
+    /// Partial results emitted so far; shared by clones of this writer.
+    partial: Arc<Mutex<String>>,
 }
 
 impl ToolStreamWriter {
@@ -51,6 +57,8 @@ impl ToolStreamWriter {
         Self {
             emit_fn: Arc::new(emit_fn),
             output_fn: None,
+            partial_fn: None,
+            partial: Arc::default(),
         }
     }
 
@@ -62,9 +70,22 @@ impl ToolStreamWriter {
         Self {
             emit_fn: Arc::new(emit_fn),
             output_fn: Some(Arc::new(output_fn)),
+            partial_fn: None,
+            partial: Arc::default(),
         }
     }
 
+    /// Returns this writer with `partial_fn` forwarding [`emit_partial`](Self::emit_partial)
+    /// chunks and an empty partial-result buffer (not shared with `self`).
+    pub fn with_partial_output(
+        mut self,
+        partial_fn: impl Fn(String) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.partial_fn = Some(Arc::new(partial_fn));
+        self.partial = Arc::default();
+        self
+    }
+
     /// Creates a no-op ToolStreamWriter that does nothing.
     ///
     /// Useful when streaming is not enabled but code still needs a writer.
@@ -72,13 +93,16 @@ impl ToolStreamWriter {
         Self {
             emit_fn: Arc::new(|_| false),
             output_fn: None,
+            partial_fn: None,
+            partial: Arc::default(),
         }
     }
 
     /// Emits a custom JSON payload.
     ///
     /// Returns `true` if the event was sent successfully, `false` otherwise.
-    /// This is a non-blocking operation that uses `try_send` internally.
+    /// This is a non-blocking operation that uses `try_send` internally. In debug builds a
+    /// payload of a registered custom event type is checked against its schema.
     ///
     /// # Arguments
     ///
@@ -95,6 +119,7 @@ impl ToolStreamWriter {
     /// }
     /// ```
     pub fn emit_custom(&self, value: Value) -> bool {
+        debug_check_custom_event(&value);
         (self.emit_fn)(value)
     }
 
@@ -109,6 +134,30 @@ impl ToolStreamWriter {
             .unwrap_or(false)
     }
 
+    /// Emits a partial result of the running tool (e.g. a line of stdout); chunks are kept in
+    /// order and [`partial_output`](Self::partial_output) returns them joined.
+    ///
+    /// Returns `true` if the chunk was forwarded to the stream, `false` if no partial callback
+    /// is configured or sending failed. The chunk is kept either way.
+    pub fn emit_partial(&self, chunk: &str) -> bool {
+        self.partial
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push_str(chunk);
+        self.partial_fn
+            .as_ref()
+            .map(|f| f(chunk.to_string()))
+            .unwrap_or(false)
+    }
+
+    /// All partial results emitted so far, concatenated.
+    pub fn partial_output(&self) -> String {
+        self.partial
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
     /// Checks if this writer is a no-op (always returns false).
     ///
     /// This can be used to skip expensive computations when streaming
@@ -0,0 +1,169 @@
+//! Allowlist tool source: exposes only a named subset of a shared inner source.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use super::{
+    ToolCallContent, ToolCallContext, ToolSource, ToolSourceError, ToolSourceHealth, ToolSpec,
+};
+use crate::tools::{batch_sub_calls, TOOL_BATCH};
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Wraps a shared `ToolSource` and only lists / calls tools whose name is in `allowed`.
+/// Calls to other tools fail with [`ToolSourceError::NotFound`] without reaching the inner source;
+/// so does a `batch` call when any of its sub-calls names such a tool.
+/// Used by GoT's ExecuteGraphNode to give each task node its planner-chosen tool subset.
+pub struct AllowedToolsSource {
+    inner: Arc<dyn ToolSource>,
+    allowed: HashSet<String>,
+}
+
+impl AllowedToolsSource {
+    pub fn new(inner: Arc<dyn ToolSource>, allowed: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            inner,
+            allowed: allowed.into_iter().collect(),
+        }
+    }
+
+    fn check(&self, name: &str, arguments: &Value) -> Result<(), ToolSourceError> {
+        if !self.allowed.contains(name) {
+            return Err(ToolSourceError::NotFound(format!(
+                "{} (not allowed for this task)",
+                name
+            )));
+        }
+        if name == TOOL_BATCH {
+            for (tool, params) in batch_sub_calls(arguments) {
+                self.check(tool, params)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ToolSource for AllowedToolsSource {
+    async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+        let tools = self.inner.list_tools().await?;
+        Ok(tools
+            .into_iter()
+            .filter(|t| self.allowed.contains(&t.name))
+            .collect())
+    }
+
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Value,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        self.check(name, &arguments)?;
+        self.inner.call_tool(name, arguments).await
+    }
+
+    async fn call_tool_with_context(
+        &self,
+        name: &str,
+        arguments: Value,
+        ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        self.check(name, &arguments)?;
+        self.inner
+            .call_tool_with_context(name, arguments, ctx)
+            .await
+    }
+
+    fn set_call_context(&self, ctx: Option<ToolCallContext>) {
+        self.inner.set_call_context(ctx);
+    }
+
+    async fn refresh_tools(&self) -> Result<Option<Vec<ToolSpec>>, ToolSourceError> {
+        Ok(self.inner.refresh_tools().await?.map(|tools| {
+            tools
+                .into_iter()
+                .filter(|t| self.allowed.contains(&t.name))
+                .collect()
+        }))
+    }
+
+    async fn tools_for_turn(&self, query: &str) -> Result<Option<Vec<ToolSpec>>, ToolSourceError> {
+        Ok(self.inner.tools_for_turn(query).await?.map(|tools| {
+            tools
+                .into_iter()
+                .filter(|t| self.allowed.contains(&t.name))
+                .collect()
+        }))
+    }
+
+    async fn health(&self) -> ToolSourceHealth {
+        self.inner.health().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AllowedToolsSource, ToolSource, ToolSourceError};
+    use crate::tool_source::{MockToolSource, ToolSpec};
+    use std::sync::Arc;
+
+    fn spec(name: &str) -> ToolSpec {
+        ToolSpec {
+            name: name.to_string(),
+            description: None,
+            input_schema: serde_json::json!({ "type": "object" }),
+            output_hint: None,
+            catalog: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn allowed_tools_source_filters_list_and_rejects_other_calls() {
+        let inner = Arc::new(MockToolSource::new(
+            vec![spec("web_fetcher"), spec("bash")],
+            "ok".to_string(),
+        ));
+        let source = AllowedToolsSource::new(inner, ["web_fetcher".to_string()]);
+
+        let names: Vec<String> = source
+            .list_tools()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(names, vec!["web_fetcher".to_string()]);
+
+        let out = source
+            .call_tool("web_fetcher", serde_json::json!({}))
+            .await
+            .unwrap();
+        assert_eq!(out.as_text(), Some("ok"));
+
+        let err = source
+            .call_tool("bash", serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolSourceError::NotFound(ref m) if m.contains("not allowed")));
+    }
+
+    #[tokio::test]
+    async fn allowed_tools_source_rejects_batch_calling_other_tools() {
+        let inner = Arc::new(MockToolSource::new(
+            vec![spec("batch"), spec("read"), spec("bash")],
+            "ok".to_string(),
+        ));
+        let source = AllowedToolsSource::new(inner, ["batch".to_string(), "read".to_string()]);
+        let batch = |tool: &str| {
+            serde_json::json!({"calls": [
+                {"tool": "read", "parameters": {}},
+                {"tool": tool, "parameters": {"command": "id"}}
+            ]})
+        };
+
+        let out = source.call_tool("batch", batch("read")).await.unwrap();
+        assert_eq!(out.as_text(), Some("ok"));
+        let err = source.call_tool("batch", batch("bash")).await.unwrap_err();
+        assert!(matches!(err, ToolSourceError::NotFound(ref m) if m.starts_with("bash")));
+    }
+}
@@ -25,6 +25,8 @@
 //! }
 //! ```
 
+use std::collections::HashMap;
+
 use crate::message::Message;
 use crate::stream::ToolStreamWriter;
 use crate::RunCancellation;
@@ -48,6 +50,7 @@ use crate::RunCancellation;
 /// - `stream_writer`: Optional writer for emitting custom streaming events
 /// - `thread_id`: Optional thread/session id from [`RunnableConfig`](crate::memory::RunnableConfig); set by ActNode when running with RunContext. Use for session-scoped storage (e.g. todo per thread).
 /// - `user_id`: Optional user id from RunnableConfig; use for multi-tenant or store namespace.
+/// - `flags`: Per-run feature flags from RunnableConfig; read with [`ToolCallContext::flag`].
 ///
 /// # Streaming
 ///
@@ -98,6 +101,9 @@ pub struct ToolCallContext {
 
     /// Shared cancellation handle for the current run, including active-operation tracking.
     pub run_cancellation: Option<RunCancellation>,
+
+    /// Feature flags for the current run, copied from `RunnableConfig::flags` by ActNode.
+    pub flags: HashMap<String, bool>,
 }
 
 impl ToolCallContext {
@@ -112,6 +118,7 @@ impl ToolCallContext {
             user_id: None,
             depth: 0,
             run_cancellation: None,
+            flags: HashMap::new(),
         }
     }
 
@@ -130,6 +137,7 @@ impl ToolCallContext {
             user_id: None,
             depth: 0,
             run_cancellation: None,
+            flags: HashMap::new(),
         }
     }
 
@@ -150,4 +158,9 @@ impl ToolCallContext {
             .map(|w| w.emit_custom(value))
             .unwrap_or(false)
     }
+
+    /// Whether feature flag `name` is on for this run; unset flags are off.
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.get(name).copied().unwrap_or(false)
+    }
 }
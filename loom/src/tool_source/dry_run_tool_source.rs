@@ -1,6 +1,8 @@
 //! Dry-run tool source: delegates list_tools, returns a placeholder for calls so tools are not executed.
 
-use super::{ToolCallContent, ToolCallContext, ToolSource, ToolSourceError, ToolSpec};
+use super::{
+    ToolCallContent, ToolCallContext, ToolSource, ToolSourceError, ToolSourceHealth, ToolSpec,
+};
 use async_trait::async_trait;
 use serde_json::Value;
 
@@ -48,6 +50,18 @@ impl ToolSource for DryRunToolSource {
     fn set_call_context(&self, ctx: Option<ToolCallContext>) {
         self.inner.set_call_context(ctx);
     }
+
+    async fn refresh_tools(&self) -> Result<Option<Vec<ToolSpec>>, ToolSourceError> {
+        self.inner.refresh_tools().await
+    }
+
+    async fn tools_for_turn(&self, query: &str) -> Result<Option<Vec<ToolSpec>>, ToolSourceError> {
+        self.inner.tools_for_turn(query).await
+    }
+
+    async fn health(&self) -> ToolSourceHealth {
+        self.inner.health().await
+    }
 }
 
 #[cfg(test)]
@@ -41,19 +41,7 @@ pub fn register_file_tools(
     working_folder: impl AsRef<Path>,
     skill_registry: Option<Arc<SkillRegistry>>,
 ) -> Result<(), ToolSourceError> {
-    let path = working_folder.as_ref();
-    let canonical = path.canonicalize().map_err(|e| {
-        ToolSourceError::InvalidInput(format!(
-            "working folder not found or not a directory: {}",
-            e
-        ))
-    })?;
-    if !canonical.is_dir() {
-        return Err(ToolSourceError::InvalidInput(
-            "working folder is not a directory".to_string(),
-        ));
-    }
-    let working_folder = Arc::new(canonical);
+    let working_folder = canonical_working_folder(working_folder.as_ref())?;
     aggregate.register_sync(Box::new(LsTool::new(working_folder.clone())));
     aggregate.register_sync(Box::new(ReadFileTool::new(working_folder.clone())));
     aggregate.register_sync(Box::new(WriteFileTool::new(working_folder.clone())));
@@ -75,6 +63,48 @@ pub fn register_file_tools(
     Ok(())
 }
 
+/// Registers the read-only file tools (ls, read, glob, grep, todo_read, skill) on an existing
+/// [`AggregateToolSource`]: the variant of [`register_file_tools`] used in read-only mode, so
+/// nothing that writes, edits, moves or deletes under the working folder is reachable (also not
+/// through the batch tool, which calls the same aggregate).
+///
+/// # Errors
+///
+/// Same as [`register_file_tools`].
+pub fn register_read_only_file_tools(
+    aggregate: &AggregateToolSource,
+    working_folder: impl AsRef<Path>,
+    skill_registry: Option<Arc<SkillRegistry>>,
+) -> Result<(), ToolSourceError> {
+    let working_folder = canonical_working_folder(working_folder.as_ref())?;
+    aggregate.register_sync(Box::new(LsTool::new(working_folder.clone())));
+    aggregate.register_sync(Box::new(ReadFileTool::new(working_folder.clone())));
+    aggregate.register_sync(Box::new(GlobTool::new(working_folder.clone())));
+    aggregate.register_sync(Box::new(GrepTool::new(working_folder.clone())));
+    aggregate.register_sync(Box::new(TodoReadTool::new(working_folder.clone())));
+    if let Some(registry) = skill_registry {
+        aggregate.register_sync(Box::new(SkillTool::new_with_registry(registry)));
+    } else {
+        aggregate.register_sync(Box::new(SkillTool::new(working_folder)));
+    }
+    Ok(())
+}
+
+fn canonical_working_folder(path: &Path) -> Result<Arc<std::path::PathBuf>, ToolSourceError> {
+    let canonical = path.canonicalize().map_err(|e| {
+        ToolSourceError::InvalidInput(format!(
+            "working folder not found or not a directory: {}",
+            e
+        ))
+    })?;
+    if !canonical.is_dir() {
+        return Err(ToolSourceError::InvalidInput(
+            "working folder is not a directory".to_string(),
+        ));
+    }
+    Ok(Arc::new(canonical))
+}
+
 /// Tool source that exposes file operations under a fixed working folder.
 ///
 /// Paths are validated to be under the working folder (canonical subpath).
@@ -106,19 +136,7 @@ impl FileToolSource {
     /// # }
     /// ```
     pub fn new(working_folder: impl AsRef<Path>) -> Result<Self, ToolSourceError> {
-        let path = working_folder.as_ref();
-        let canonical = path.canonicalize().map_err(|e| {
-            ToolSourceError::InvalidInput(format!(
-                "working folder not found or not a directory: {}",
-                e
-            ))
-        })?;
-        if !canonical.is_dir() {
-            return Err(ToolSourceError::InvalidInput(
-                "working folder is not a directory".to_string(),
-            ));
-        }
-        let working_folder = Arc::new(canonical);
+        let working_folder = canonical_working_folder(working_folder.as_ref())?;
         let source = AggregateToolSource::new();
         source.register_sync(Box::new(LsTool::new(working_folder.clone())));
         source.register_sync(Box::new(ReadFileTool::new(working_folder.clone())));
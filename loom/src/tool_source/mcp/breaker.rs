@@ -0,0 +1,217 @@
+//! Retry policy and circuit breaker for MCP HTTP sessions.
+//!
+//! [`McpHttpSession::request`](super::McpHttpSession::request) retries transient failures
+//! (transport errors, HTTP 429 and 5xx) with exponential backoff, and counts consecutive failed
+//! requests in a [`CircuitBreaker`]. After `failure_threshold` failures the breaker opens and
+//! requests fail fast; once `cooldown` has passed, one half-open probe is let through and its
+//! outcome closes or re-opens the breaker.
+
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Breaker state, as shown in the tools config summary and in `tool_source_unhealthy` events.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests go through; failures are being counted.
+    Closed,
+    /// Requests fail fast until the cooldown expires.
+    Open,
+    /// Cooldown expired; one probe request is allowed.
+    HalfOpen,
+}
+
+impl CircuitState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CircuitState::Closed => "closed",
+            CircuitState::Open => "open",
+            CircuitState::HalfOpen => "half_open",
+        }
+    }
+}
+
+impl fmt::Display for CircuitState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How often and how long to retry one MCP HTTP request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts per request, including the first (1 disables retries).
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled for each further retry.
+    pub base_delay: Duration,
+    /// Upper bound for one backoff delay.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(2),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Backoff before retry number `retry` (1-based).
+    pub fn delay(&self, retry: u32) -> Duration {
+        let factor = 1u32 << retry.saturating_sub(1).min(16);
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+#[derive(Debug)]
+enum Inner {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    HalfOpen { probing: bool },
+}
+
+/// Consecutive-failure circuit breaker. Thread-safe; shared by all calls on one session.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    inner: Mutex<Inner>,
+}
+
+/// Default consecutive failures before the breaker opens.
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+/// Default time the breaker stays open before allowing a half-open probe.
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new(DEFAULT_FAILURE_THRESHOLD, DEFAULT_COOLDOWN)
+    }
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            inner: Mutex::new(Inner::Closed { failures: 0 }),
+        }
+    }
+
+    /// Current state. An open breaker whose cooldown has expired reports `HalfOpen`.
+    pub fn state(&self) -> CircuitState {
+        self.state_at(Instant::now())
+    }
+
+    fn state_at(&self, now: Instant) -> CircuitState {
+        match *self.lock() {
+            Inner::Closed { .. } => CircuitState::Closed,
+            Inner::Open { until } if now < until => CircuitState::Open,
+            Inner::Open { .. } | Inner::HalfOpen { .. } => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Returns true when a request may be sent now. In half-open state only one probe is
+    /// admitted until its outcome is recorded.
+    pub fn try_acquire(&self) -> bool {
+        self.try_acquire_at(Instant::now())
+    }
+
+    fn try_acquire_at(&self, now: Instant) -> bool {
+        let mut inner = self.lock();
+        match *inner {
+            Inner::Closed { .. } => true,
+            Inner::Open { until } if now < until => false,
+            Inner::Open { .. } => {
+                *inner = Inner::HalfOpen { probing: true };
+                true
+            }
+            Inner::HalfOpen { probing: true } => false,
+            Inner::HalfOpen { probing: false } => {
+                *inner = Inner::HalfOpen { probing: true };
+                true
+            }
+        }
+    }
+
+    /// Records a successful request; closes the breaker.
+    pub fn record_success(&self) {
+        *self.lock() = Inner::Closed { failures: 0 };
+    }
+
+    /// Records a failed request (after retries). Returns true when this failure opened the breaker.
+    pub fn record_failure(&self) -> bool {
+        self.record_failure_at(Instant::now())
+    }
+
+    fn record_failure_at(&self, now: Instant) -> bool {
+        let mut inner = self.lock();
+        let open = match *inner {
+            Inner::Closed { failures } if failures + 1 < self.failure_threshold => {
+                *inner = Inner::Closed {
+                    failures: failures + 1,
+                };
+                false
+            }
+            Inner::Open { .. } => false,
+            _ => true,
+        };
+        if open {
+            *inner = Inner::Open {
+                until: now + self.cooldown,
+            };
+        }
+        open
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn breaker_opens_after_threshold_and_probes_after_cooldown() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(10));
+        let t0 = Instant::now();
+        assert!(breaker.try_acquire_at(t0));
+        assert!(!breaker.record_failure_at(t0));
+        assert_eq!(breaker.state_at(t0), CircuitState::Closed);
+        assert!(breaker.record_failure_at(t0));
+        assert_eq!(breaker.state_at(t0), CircuitState::Open);
+        assert!(!breaker.try_acquire_at(t0 + Duration::from_secs(5)));
+
+        let later = t0 + Duration::from_secs(11);
+        assert_eq!(breaker.state_at(later), CircuitState::HalfOpen);
+        assert!(breaker.try_acquire_at(later));
+        assert!(
+            !breaker.try_acquire_at(later),
+            "only one half-open probe at a time"
+        );
+        assert!(breaker.record_failure_at(later), "failed probe re-opens");
+        assert_eq!(breaker.state_at(later), CircuitState::Open);
+
+        let much_later = later + Duration::from_secs(11);
+        assert!(breaker.try_acquire_at(much_later));
+        breaker.record_success();
+        assert_eq!(breaker.state_at(much_later), CircuitState::Closed);
+    }
+
+    #[test]
+    fn retry_delay_doubles_up_to_max() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(350),
+        };
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(350));
+    }
+}
@@ -3,10 +3,21 @@
 //! Uses `McpSession` (stdio) or `McpHttpSession` (HTTP); maps MCP tools/list and
 //! tools/call to `ToolSpec` and `ToolCallContent`. For Exa, HTTP is preferred when
 //! the server URL is http(s).
+//!
+//! `notifications/tools/list_changed` from the server sets a flag read by
+//! [`McpToolSource::take_tools_changed`]; `AggregateToolSource::refresh_mcp_tools` uses it to
+//! re-list and replace the server's tools.
+//!
+//! An [`McpConnect`] defers connecting until the aggregate first lists its tools (see
+//! `AggregateToolSource::register_lazy_mcp`), so an unreachable server is skipped instead of
+//! failing the build.
 
+mod breaker;
 mod session;
 mod session_http;
 
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
@@ -17,12 +28,30 @@ use tokio::task;
 use mcp_core::ResultMessage;
 
 use crate::cli_run::ActiveOperationKind;
-use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSource, ToolSourceError, ToolSpec};
+use crate::tool_source::{
+    ToolCallContent, ToolCallContext, ToolCatalogInfo, ToolDanger, ToolSource, ToolSourceError,
+    ToolSourceHealth, ToolSpec,
+};
 use crate::{ToolOutputHint, ToolOutputStrategy};
 
+pub use breaker::{CircuitBreaker, CircuitState, RetryPolicy};
 pub use session::{McpSession, McpSessionError};
 pub use session_http::McpHttpSession;
 
+/// `type` of the custom stream event emitted when an MCP tool source is marked unhealthy mid-run.
+pub const TOOL_SOURCE_UNHEALTHY_EVENT: &str = "tool_source_unhealthy";
+
+/// Future returned by an [`McpConnect`]: the connected source and, when already fetched
+/// (e.g. by a stdio server started in `spawn_blocking`), its tools.
+pub type McpConnectFuture = Pin<
+    Box<
+        dyn Future<Output = Result<(McpToolSource, Option<Vec<ToolSpec>>), ToolSourceError>> + Send,
+    >,
+>;
+
+/// Connects to an MCP server; called again to retry after a failure.
+pub type McpConnect = Arc<dyn Fn() -> McpConnectFuture + Send + Sync>;
+
 /// Transport kind: stdio (spawn process) or HTTP (POST to URL).
 /// HTTP variant uses `Arc` so we can release the mutex before awaiting.
 #[allow(clippy::large_enum_variant)]
@@ -42,6 +71,16 @@ enum McpSessionKind {
 /// that pass tools to ChatOpenAI. Holds session behind Mutex for interior mutability.
 pub struct McpToolSource {
     session: Mutex<McpSessionKind>,
+    /// Task started by [`Self::watch_notifications`]; aborted on drop.
+    listener: Mutex<Option<task::AbortHandle>>,
+}
+
+impl Drop for McpToolSource {
+    fn drop(&mut self) {
+        if let Some(handle) = self.listener.get_mut().ok().and_then(Option::take) {
+            handle.abort();
+        }
+    }
 }
 
 impl McpToolSource {
@@ -61,6 +100,7 @@ impl McpToolSource {
             McpSession::new(command, args, None::<Vec<(String, String)>>, stderr_verbose)?;
         Ok(Self {
             session: Mutex::new(McpSessionKind::Stdio(session)),
+            listener: Mutex::new(None),
         })
     }
 
@@ -79,6 +119,7 @@ impl McpToolSource {
         let session = McpSession::new(command, args, Some(env), stderr_verbose)?;
         Ok(Self {
             session: Mutex::new(McpSessionKind::Stdio(session)),
+            listener: Mutex::new(None),
         })
     }
 
@@ -95,9 +136,55 @@ impl McpToolSource {
         let session = McpHttpSession::new(url, headers).await?;
         Ok(Self {
             session: Mutex::new(McpSessionKind::Http(Arc::new(session))),
+            listener: Mutex::new(None),
         })
     }
 
+    /// Circuit breaker state of the HTTP session; `None` for stdio servers.
+    pub fn breaker_state(&self) -> Option<CircuitState> {
+        let guard = self.session.lock().ok()?;
+        match &*guard {
+            McpSessionKind::Stdio(_) => None,
+            McpSessionKind::Http(h) => Some(h.breaker_state()),
+        }
+    }
+
+    /// Returns whether the server sent `notifications/tools/list_changed` since the last call,
+    /// and clears the flag. Stdio servers are always watched; HTTP servers report notifications
+    /// carried on response streams, plus the standalone stream once
+    /// [`watch_notifications`](Self::watch_notifications) is started.
+    pub fn take_tools_changed(&self) -> bool {
+        let Ok(guard) = self.session.lock() else {
+            return false;
+        };
+        match &*guard {
+            McpSessionKind::Stdio(s) => s.take_tools_changed(),
+            McpSessionKind::Http(h) => h.take_tools_changed(),
+        }
+    }
+
+    /// For HTTP servers, spawns a task that listens on the server's notification stream (see
+    /// [`McpHttpSession::listen_notifications`]) for tool list changes. No-op for stdio servers
+    /// and when already watching. Must be called within a tokio runtime.
+    pub fn watch_notifications(&self) {
+        let session = match self.session.lock().as_deref() {
+            Ok(McpSessionKind::Http(h)) => Arc::clone(h),
+            _ => return,
+        };
+        let Ok(mut listener) = self.listener.lock() else {
+            return;
+        };
+        if listener.is_some() {
+            return;
+        }
+        let handle = tokio::spawn(async move {
+            if let Err(e) = session.listen_notifications().await {
+                tracing::debug!(url = %session.url(), error = %e, "MCP notification stream ended");
+            }
+        });
+        *listener = Some(handle.abort_handle());
+    }
+
     /// Sends one JSON-RPC request and returns the result (stdio only; HTTP path uses async in `list_tools`/`call_tool`).
     fn request(
         &self,
@@ -182,11 +269,40 @@ fn parse_list_tools_result(result: ResultMessage) -> Result<Vec<ToolSpec>, ToolS
             output_hint: Some(ToolOutputHint::preferred(
                 ToolOutputStrategy::FileRefWithExcerpt,
             )),
+            catalog: catalog_from_mcp(obj),
         });
     }
     Ok(specs)
 }
 
+/// Catalog metadata from an MCP `tools/list` item: the danger level from `annotations`
+/// (`readOnlyHint`, else `destructiveHint`, which MCP defaults to true) and the icon from the
+/// first of `icons`. Tools without annotations have no danger level.
+fn catalog_from_mcp(tool: &serde_json::Map<String, Value>) -> ToolCatalogInfo {
+    let danger = tool.get("annotations").and_then(Value::as_object).map(|a| {
+        let hint = |key: &str| a.get(key).and_then(Value::as_bool);
+        if hint("readOnlyHint") == Some(true) {
+            ToolDanger::Safe
+        } else if hint("destructiveHint") == Some(false) {
+            ToolDanger::Caution
+        } else {
+            ToolDanger::Destructive
+        }
+    });
+    let icon = tool
+        .get("icons")
+        .and_then(Value::as_array)
+        .and_then(|icons| icons.first())
+        .and_then(|icon| icon.get("src"))
+        .and_then(Value::as_str)
+        .map(String::from);
+    ToolCatalogInfo {
+        danger,
+        icon,
+        ..Default::default()
+    }
+}
+
 /// Parses a `tools/call` JSON-RPC result into `ToolCallContent`.
 fn parse_call_tool_result(result: ResultMessage) -> Result<ToolCallContent, ToolSourceError> {
     if let Some(err) = result.error {
@@ -311,11 +427,40 @@ impl ToolSource for McpToolSource {
         if let Some(run_cancellation) = ctx.and_then(|ctx| ctx.run_cancellation.clone()) {
             run_cancellation.set_abortable_operation(ActiveOperationKind::McpRequest, abort_handle);
         }
+        let was_open = arc.breaker_state() == CircuitState::Open;
         match request.await {
-            Ok(result) => parse_call_tool_result(result?),
+            Ok(Ok(result)) => parse_call_tool_result(result),
+            Ok(Err(e)) => {
+                let state = arc.breaker_state();
+                if state == CircuitState::Open && !was_open {
+                    if let Some(ctx) = ctx {
+                        ctx.emit_custom(serde_json::json!({
+                            "type": TOOL_SOURCE_UNHEALTHY_EVENT,
+                            "source": "mcp",
+                            "url": arc.url(),
+                            "tool": name,
+                            "breaker": state.as_str(),
+                            "error": e.to_string(),
+                        }));
+                    }
+                }
+                Err(e)
+            }
             Err(Aborted) => Err(ToolSourceError::Transport("MCP request cancelled".into())),
         }
     }
+
+    /// Degraded while the HTTP session's circuit breaker is open.
+    async fn health(&self) -> ToolSourceHealth {
+        let url = match self.session.lock().as_deref() {
+            Ok(McpSessionKind::Http(h)) => h.url().to_string(),
+            _ => return ToolSourceHealth::default(),
+        };
+        match self.breaker_state() {
+            Some(CircuitState::Open) => ToolSourceHealth::degraded(url, "circuit breaker open"),
+            _ => ToolSourceHealth::default(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -413,6 +558,42 @@ mod tests {
         assert_eq!(tools[0].input_schema["type"], "object");
     }
 
+    #[test]
+    fn parse_list_tools_result_maps_annotations_to_catalog() {
+        let result = ResultMessage::success(
+            "1",
+            serde_json::json!({
+                "tools": [
+                    {"name": "read", "inputSchema": {}, "annotations": {"readOnlyHint": true}},
+                    {"name": "rename", "inputSchema": {}, "annotations": {"destructiveHint": false}},
+                    {
+                        "name": "drop_table",
+                        "inputSchema": {},
+                        "annotations": {"title": "Drop table"},
+                        "icons": [{"src": "https://example.com/db.svg"}]
+                    },
+                    {"name": "plain", "inputSchema": {}}
+                ]
+            }),
+        );
+        let tools = parse_list_tools_result(result).unwrap();
+        let dangers: Vec<_> = tools.iter().map(|t| t.catalog.danger).collect();
+        assert_eq!(
+            dangers,
+            vec![
+                Some(ToolDanger::Safe),
+                Some(ToolDanger::Caution),
+                Some(ToolDanger::Destructive),
+                None
+            ]
+        );
+        assert_eq!(
+            tools[2].catalog.icon.as_deref(),
+            Some("https://example.com/db.svg")
+        );
+        assert_eq!(tools[3].catalog, ToolCatalogInfo::default());
+    }
+
     #[test]
     fn parse_list_tools_result_errors_for_missing_or_invalid_tools() {
         let missing_tools = ResultMessage::success("1", serde_json::json!({}));
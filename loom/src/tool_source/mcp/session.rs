@@ -3,6 +3,8 @@
 //! Wraps `StdioClientTransport` from mcp_client; used by `McpToolSource` for
 //! `tools/list` and `tools/call`. Does not handle resources or prompts.
 
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use crossbeam_channel::{unbounded, RecvTimeoutError};
@@ -18,6 +20,8 @@ use serde_json::{json, Value};
 const PROTOCOL_VERSION: &str = "2025-11-25";
 /// Request id for initialize.
 const INITIALIZE_REQUEST_ID: &str = "loom-mcp-initialize";
+/// Notification a server sends when its tool list changed.
+pub(crate) const TOOLS_LIST_CHANGED: &str = "notifications/tools/list_changed";
 
 /// MCP session over stdio: spawns server process, performs initialize handshake,
 /// provides `send_request` and `wait_for_result` for JSON-RPC calls.
@@ -29,6 +33,8 @@ const INITIALIZE_REQUEST_ID: &str = "loom-mcp-initialize";
 pub struct McpSession {
     transport: StdioClientTransport,
     receiver: crossbeam_channel::Receiver<JsonRpcMessage>,
+    /// Set by the transport reader when the server sends `notifications/tools/list_changed`.
+    tools_changed: Arc<AtomicBool>,
 }
 
 #[cfg(target_os = "windows")]
@@ -86,8 +92,15 @@ impl McpSession {
             params = params.env(env_iter);
         }
 
+        let tools_changed = Arc::new(AtomicBool::new(false));
+        let changed_flag = Arc::clone(&tools_changed);
         let mut transport = StdioClientTransport::new(params);
         transport.on_message(move |msg| {
+            if let JsonRpcMessage::Notification(ref n) = msg {
+                if n.method == TOOLS_LIST_CHANGED {
+                    changed_flag.store(true, Ordering::SeqCst);
+                }
+            }
             let _ = tx.send(msg);
         });
         transport.on_error(|e| {
@@ -99,6 +112,7 @@ impl McpSession {
         let mut session = Self {
             transport,
             receiver: rx,
+            tools_changed,
         };
         session.initialize()?;
         Ok(session)
@@ -145,6 +159,12 @@ impl McpSession {
         Ok(())
     }
 
+    /// Returns whether the server announced a tool list change since the last call, and clears
+    /// the flag. The flag is set as soon as the notification arrives, even between requests.
+    pub fn take_tools_changed(&self) -> bool {
+        self.tools_changed.swap(false, Ordering::SeqCst)
+    }
+
     /// Sends a JSON-RPC request. Does not wait for the response.
     pub fn send_request(
         &mut self,
@@ -308,6 +328,60 @@ for raw in sys.stdin:
         assert!(call_result.error.is_none());
     }
 
+    #[test]
+    fn tools_list_changed_notification_sets_flag_once() {
+        let script_path = write_python_server(
+            r#"
+import json, sys
+
+for raw in sys.stdin:
+    raw = raw.strip()
+    if not raw:
+        continue
+    msg = json.loads(raw)
+    method = msg.get("method")
+    if method == "initialize":
+        print(json.dumps({
+            "jsonrpc":"2.0",
+            "id":msg["id"],
+            "result":{"protocolVersion":"2025-11-25","capabilities":{"tools":{"listChanged":True}}}
+        }), flush=True)
+    elif method == "tools/call":
+        print(json.dumps({
+            "jsonrpc":"2.0",
+            "method":"notifications/tools/list_changed"
+        }), flush=True)
+        print(json.dumps({
+            "jsonrpc":"2.0",
+            "id":msg["id"],
+            "result":{"content":[{"type":"text","text":"installed"}]}
+        }), flush=True)
+"#,
+        );
+
+        let mut session = McpSession::new(
+            "python3",
+            vec![script_path],
+            None::<Vec<(String, String)>>,
+            false,
+        )
+        .unwrap();
+        assert!(!session.take_tools_changed());
+        session
+            .send_request(
+                "call-1",
+                "tools/call",
+                json!({"name":"install","arguments":{}}),
+            )
+            .unwrap();
+        session
+            .wait_for_result("call-1", Duration::from_secs(2))
+            .unwrap()
+            .expect("tools/call response");
+        assert!(session.take_tools_changed());
+        assert!(!session.take_tools_changed());
+    }
+
     #[test]
     fn wait_for_result_times_out_when_no_matching_response() {
         let script_path = write_python_server(
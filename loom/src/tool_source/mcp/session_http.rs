@@ -8,7 +8,9 @@
 //! **Interaction**: Created by `McpToolSource::new_http`; used for `initialize`,
 //! `tools/list`, and `tools/call` when the server URL is http(s).
 //! Uses async reqwest; safe to create and use from async/tokio context.
+//! Requests are retried with backoff and guarded by a [`CircuitBreaker`].
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
 
 use mcp_core::{ErrorObject, MessageId, NotificationMessage, RequestMessage, ResultMessage};
@@ -16,6 +18,8 @@ use reqwest::Client;
 use serde::Deserialize;
 use serde_json::{json, Value};
 
+use super::breaker::{CircuitBreaker, CircuitState, RetryPolicy};
+use super::session::TOOLS_LIST_CHANGED;
 use crate::tool_source::ToolSourceError;
 
 /// MCP protocol version for HTTP header.
@@ -45,12 +49,7 @@ fn parse_json_rpc_from_body(
     body: &str,
     content_type: Option<&reqwest::header::HeaderValue>,
 ) -> Result<JsonRpcResponse, ToolSourceError> {
-    let is_sse = content_type
-        .and_then(|v| v.to_str().ok())
-        .map(|s| s.contains("text/event-stream"))
-        .unwrap_or(false);
-
-    if is_sse {
+    if is_sse(content_type) {
         let mut data_buffer = String::new();
         for line in body.lines() {
             if let Some(data) = line.strip_prefix("data: ") {
@@ -101,6 +100,20 @@ fn parse_json_rpc_from_body(
     }
 }
 
+/// Returns whether any SSE `data:` line of `body` is a `notifications/tools/list_changed`.
+fn sse_has_tools_list_changed(body: &str) -> bool {
+    body.lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .filter_map(|data| serde_json::from_str::<Value>(data.trim()).ok())
+        .any(|msg| msg.get("method").and_then(Value::as_str) == Some(TOOLS_LIST_CHANGED))
+}
+
+fn is_sse(content_type: Option<&reqwest::header::HeaderValue>) -> bool {
+    content_type
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|s| s.contains("text/event-stream"))
+}
+
 /// MCP session over Streamable HTTP.
 ///
 /// Performs initialize handshake via POST, then supports request/response
@@ -113,8 +126,17 @@ pub struct McpHttpSession {
     headers: Vec<(String, String)>,
     /// Session id from server MCP-Session-Id header; sent on subsequent requests.
     session_id: Mutex<Option<String>>,
+    /// Retry policy for transient failures of one request.
+    retry: RetryPolicy,
+    /// Opens after repeated failed requests so a dead server fails fast.
+    breaker: CircuitBreaker,
+    /// Set when the server sends `notifications/tools/list_changed` on an SSE stream.
+    tools_changed: AtomicBool,
 }
 
+/// Prefix of the error returned while the breaker is open.
+const CIRCUIT_OPEN_ERROR: &str = "circuit open";
+
 impl McpHttpSession {
     /// Creates a new HTTP MCP session and completes the initialize handshake.
     ///
@@ -139,6 +161,9 @@ impl McpHttpSession {
             url: url.clone(),
             headers,
             session_id,
+            retry: RetryPolicy::default(),
+            breaker: CircuitBreaker::default(),
+            tools_changed: AtomicBool::new(false),
         };
         s.initialize().await?;
         Ok(s)
@@ -238,19 +263,120 @@ impl McpHttpSession {
         Ok(())
     }
 
-    /// Sends a JSON-RPC request and returns the parsed result (one POST, one response).
+    /// URL of the MCP endpoint.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Current circuit breaker state.
+    pub fn breaker_state(&self) -> CircuitState {
+        self.breaker.state()
+    }
+
+    /// Returns whether the server announced a tool list change since the last call, and clears
+    /// the flag.
+    pub fn take_tools_changed(&self) -> bool {
+        self.tools_changed.swap(false, Ordering::SeqCst)
+    }
+
+    /// Opens the server-to-client SSE stream (HTTP GET on the endpoint) and watches it for
+    /// `notifications/tools/list_changed` until the server closes it. Returns `Ok` right away
+    /// when the server does not offer the stream (HTTP 405).
+    pub async fn listen_notifications(&self) -> Result<(), ToolSourceError> {
+        let mut req = self
+            .client
+            .get(&self.url)
+            .header("Accept", "text/event-stream")
+            .header("MCP-Protocol-Version", MCP_PROTOCOL_VERSION)
+            .timeout(std::time::Duration::from_secs(60 * 60 * 24));
+        for (k, v) in &self.headers {
+            req = req.header(k.as_str(), v.as_str());
+        }
+        if let Ok(guard) = self.session_id.lock() {
+            if let Some(ref sid) = *guard {
+                req = req.header("MCP-Session-Id", sid.as_str());
+            }
+        }
+        let mut resp = req
+            .send()
+            .await
+            .map_err(|e| ToolSourceError::Transport(e.to_string()))?;
+        if resp.status() == reqwest::StatusCode::METHOD_NOT_ALLOWED {
+            return Ok(());
+        }
+        if !resp.status().is_success() {
+            return Err(ToolSourceError::Transport(format!(
+                "notification stream HTTP {}",
+                resp.status()
+            )));
+        }
+        let mut pending = String::new();
+        while let Some(chunk) = resp
+            .chunk()
+            .await
+            .map_err(|e| ToolSourceError::Transport(e.to_string()))?
+        {
+            pending.push_str(&String::from_utf8_lossy(&chunk));
+            if let Some(end) = pending.rfind('\n') {
+                if sse_has_tools_list_changed(&pending[..end]) {
+                    self.tools_changed.store(true, Ordering::SeqCst);
+                }
+                pending.drain(..=end);
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends a JSON-RPC request and returns the parsed result.
     ///
-    /// Used by McpToolSource for tools/list and tools/call. Response must be
-    /// Content-Type: application/json with a single JSON-RPC response.
+    /// Used by McpToolSource for tools/list and tools/call. Transport errors, HTTP 429 and 5xx
+    /// are retried per [`RetryPolicy`]; a request that still fails counts towards the circuit
+    /// breaker. While the breaker is open, returns a `circuit open` transport error without
+    /// contacting the server. JSON-RPC errors are a successful response, not a failure.
     pub async fn request(
         &self,
         id: &str,
         method: &str,
         params: Value,
     ) -> Result<ResultMessage, ToolSourceError> {
+        if !self.breaker.try_acquire() {
+            return Err(ToolSourceError::Transport(format!(
+                "{}: MCP server {} is unhealthy, {} not sent",
+                CIRCUIT_OPEN_ERROR, self.url, method
+            )));
+        }
+        let mut attempt = 1;
+        loop {
+            match self.send_once(id, method, params.clone()).await {
+                Ok(result) => {
+                    self.breaker.record_success();
+                    return Ok(result);
+                }
+                Err((e, retriable)) if retriable && attempt < self.retry.max_attempts => {
+                    tracing::debug!(url = %self.url, method, attempt, error = %e, "retrying MCP request");
+                    tokio::time::sleep(self.retry.delay(attempt)).await;
+                    attempt += 1;
+                }
+                Err((e, _)) => {
+                    if self.breaker.record_failure() {
+                        tracing::warn!(url = %self.url, error = %e, "MCP circuit breaker opened");
+                    }
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    /// One POST for [`Self::request`]. The error flag tells whether the failure is worth retrying.
+    async fn send_once(
+        &self,
+        id: &str,
+        method: &str,
+        params: Value,
+    ) -> Result<ResultMessage, (ToolSourceError, bool)> {
         let request = RequestMessage::new(id, method, params);
-        let body =
-            serde_json::to_vec(&request).map_err(|e| ToolSourceError::Transport(e.to_string()))?;
+        let body = serde_json::to_vec(&request)
+            .map_err(|e| (ToolSourceError::Transport(e.to_string()), false))?;
         let mut req = self
             .client
             .post(&self.url)
@@ -269,24 +395,32 @@ impl McpHttpSession {
         let resp = req
             .send()
             .await
-            .map_err(|e| ToolSourceError::Transport(e.to_string()))?;
+            .map_err(|e| (ToolSourceError::Transport(e.to_string()), true))?;
         let status = resp.status();
         if !status.is_success() {
+            let retriable =
+                status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS;
             let text = resp.text().await.unwrap_or_default();
-            return Err(ToolSourceError::Transport(format!(
-                "{} HTTP {}: {}",
-                method,
-                status,
-                if text.is_empty() { "no body" } else { &text }
-            )));
+            return Err((
+                ToolSourceError::Transport(format!(
+                    "{} HTTP {}: {}",
+                    method,
+                    status,
+                    if text.is_empty() { "no body" } else { &text }
+                )),
+                retriable,
+            ));
         }
         let content_type = resp.headers().get("content-type").cloned();
         let text = resp
             .text()
             .await
-            .map_err(|e| ToolSourceError::Transport(e.to_string()))?;
+            .map_err(|e| (ToolSourceError::Transport(e.to_string()), true))?;
+        if is_sse(content_type.as_ref()) && sse_has_tools_list_changed(&text) {
+            self.tools_changed.store(true, Ordering::SeqCst);
+        }
         let json: JsonRpcResponse = parse_json_rpc_from_body(&text, content_type.as_ref())
-            .map_err(|e| ToolSourceError::Transport(e.to_string()))?;
+            .map_err(|e| (ToolSourceError::Transport(e.to_string()), false))?;
         let msg_id = json.id.unwrap_or_else(|| MessageId::from(id));
         if let Some(err) = json.error {
             let err_obj = ErrorObject::new(err.code as i32, err.message, None);
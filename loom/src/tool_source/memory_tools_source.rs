@@ -10,18 +10,18 @@ use async_trait::async_trait;
 use crate::memory::{Namespace, Store};
 use crate::tool_source::{ToolSource, ToolSourceError};
 use crate::tools::{
-    AggregateToolSource, GetRecentMessagesTool, ListMemoriesTool, RecallTool, RememberTool,
-    SearchMemoriesTool,
+    AggregateToolSource, ForgetTool, GetRecentMessagesTool, ListMemoriesTool, RecallTool,
+    RememberTool, SearchMemoriesTool,
 };
 
 /// Composite tool source that exposes both long-term (Store) and short-term (recent messages) memory tools.
 ///
 /// Uses AggregateToolSource internally to register all memory tools and the conversation tool.
-/// `list_tools` returns all 5 tools; `call_tool` delegates to the registry;
+/// `list_tools` returns all 6 tools; `call_tool` delegates to the registry;
 /// `set_call_context` stores context for get_recent_messages to use.
 ///
 /// **Interaction**: Use with `ActNode::new(Box::new(MemoryToolsSource::new(store, namespace)))`
-/// when you want both remember/recall/search_memories/list_memories and get_recent_messages.
+/// when you want both remember/recall/search_memories/list_memories/forget and get_recent_messages.
 pub struct MemoryToolsSource {
     _source: AggregateToolSource,
 }
@@ -57,13 +57,15 @@ impl MemoryToolsSource {
         let remember = RememberTool::new(store.clone(), namespace.clone());
         let recall = RecallTool::new(store.clone(), namespace.clone());
         let search = SearchMemoriesTool::new(store.clone(), namespace.clone());
-        let list = ListMemoriesTool::new(store, namespace);
+        let list = ListMemoriesTool::new(store.clone(), namespace.clone());
+        let forget = ForgetTool::new(store, namespace);
         let get_recent = GetRecentMessagesTool::new();
 
         source.register_async(Box::new(remember)).await;
         source.register_async(Box::new(recall)).await;
         source.register_async(Box::new(search)).await;
         source.register_async(Box::new(list)).await;
+        source.register_async(Box::new(forget)).await;
         source.register_async(Box::new(get_recent)).await;
 
         source
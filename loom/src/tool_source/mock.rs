@@ -32,6 +32,7 @@ impl MockToolSource {
                 description: Some("Get current time. Use ONLY when the user explicitly asks for current date, time, or 'what time is it'. Do NOT use for math, general knowledge, or other questions.".to_string()),
                 input_schema: json!({ "type": "object", "properties": {} }),
                 output_hint: None,
+                catalog: Default::default(),
             }],
             call_result: "2025-01-29 12:00:00".to_string(),
         }
@@ -20,41 +20,70 @@
 //!   Use `WebToolsSource::new()` to enable HTTP GET/POST capabilities; pass to `ActNode::new(Box::new(web_tools))`.
 //! - **BashToolsSource**: shell command execution as tool (`bash`).
 //!   Use `BashToolsSource::new()` to enable running shell commands; pass to `ActNode::new(Box::new(bash_tools))`.
+//! - **SshToolsSource** (feature `ssh`): allowlisted commands and scp on configured hosts
+//!   (`ssh_exec`, `scp_get`, `scp_put`). Use `SshToolsSource::new(hosts, working_folder)`;
+//!   built runners get them from the hosts file named by `LOOM_SSH_HOSTS`.
+//!
+//! ## Runtime changes
+//!
+//! - **PluggableToolSource**: base source plus named sources added or removed between turns
+//!   (e.g. project MCP servers). `ReactRunner` wraps its tool source in one; see
+//!   `ReactRunner::add_tool_source`.
+//! - **ToolArgumentsSource**: arguments configured per tool (templated from the working folder,
+//!   thread id and user id) set on every call and hidden from the model.
 
+mod allowed_tools_source;
 mod bash_tools_source;
 mod context;
 mod dry_run_tool_source;
 mod file_tool_source;
 mod memory_tools_source;
 mod mock;
+mod pluggable_tool_source;
 mod read_only_dir_tool_source;
 mod short_term_memory_tool_source;
+#[cfg(feature = "ssh")]
+mod ssh_tools_source;
 mod store_tool_source;
 mod telegram_tools_source;
+mod tool_arguments_source;
+mod tool_selection_source;
 mod web_tools_source;
 mod yaml_specs;
 
 mod mcp;
 
+pub use allowed_tools_source::AllowedToolsSource;
 pub use bash_tools_source::{BashToolsSource, TOOL_BASH};
 pub use context::ToolCallContext;
 pub use dry_run_tool_source::DryRunToolSource;
-pub use file_tool_source::{register_file_tools, FileToolSource};
+pub use file_tool_source::{register_file_tools, register_read_only_file_tools, FileToolSource};
 pub use memory_tools_source::MemoryToolsSource;
 pub use mock::MockToolSource;
+pub use pluggable_tool_source::PluggableToolSource;
 pub use read_only_dir_tool_source::{
     register_read_only_dir_tools, ReadOnlyDirToolSource, TOOL_READ_ONLY_LIST_DIR,
     TOOL_READ_ONLY_READ_FILE,
 };
 pub use short_term_memory_tool_source::{ShortTermMemoryToolSource, TOOL_GET_RECENT_MESSAGES};
+#[cfg(feature = "ssh")]
+pub use ssh_tools_source::SshToolsSource;
 pub use store_tool_source::{
-    StoreToolSource, TOOL_LIST_MEMORIES, TOOL_RECALL, TOOL_REMEMBER, TOOL_SEARCH_MEMORIES,
+    StoreToolSource, TOOL_FORGET, TOOL_LIST_MEMORIES, TOOL_RECALL, TOOL_REMEMBER,
+    TOOL_SEARCH_MEMORIES,
 };
 pub use telegram_tools_source::TelegramToolsSource;
+pub use tool_arguments_source::ToolArgumentsSource;
+pub use tool_selection_source::{
+    ToolSelectionConfig, ToolSelectionSource, DEFAULT_TOOL_SELECTION_TOP_K, TOOL_LIST_ALL_TOOLS,
+};
 pub use web_tools_source::{WebToolsSource, TOOL_WEB_FETCHER};
 pub use yaml_specs::{load_tool_specs, YamlSpecError, YamlSpecToolSource};
 
-pub use mcp::{McpSession, McpSessionError, McpToolSource};
+pub use mcp::{
+    CircuitState, McpConnect, McpConnectFuture, McpSession, McpSessionError, McpToolSource,
+    TOOL_SOURCE_UNHEALTHY_EVENT,
+};
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -67,7 +96,7 @@ use crate::state::tool_output_normalizer::{ToolOutputHint, ToolOutputStrategy};
 ///
 /// This is the schema-facing description shown to the model during tool-aware
 /// thinking. It can also be deserialized from YAML-backed tool definitions.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct ToolSpec {
     /// Tool name (e.g. used in MCP tools/call).
     pub name: String,
@@ -78,6 +107,9 @@ pub struct ToolSpec {
     /// Optional output normalization hint used by the unified tool output controller.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub output_hint: Option<ToolOutputHint>,
+    /// Category, icon, danger level and examples for client UIs (flattened into the spec).
+    #[serde(flatten)]
+    pub catalog: ToolCatalogInfo,
 }
 
 impl ToolSpec {
@@ -86,6 +118,73 @@ impl ToolSpec {
         self.output_hint = Some(output_hint);
         self
     }
+
+    /// Sets the catalog metadata shown by client UIs.
+    pub fn with_catalog(mut self, catalog: ToolCatalogInfo) -> Self {
+        self.catalog = catalog;
+        self
+    }
+}
+
+/// How much harm a call to a tool can do, so clients can mark risky tools or confirm calls.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolDanger {
+    /// Only reads (files, the web, memory).
+    Safe,
+    /// Changes local state that can be reviewed or undone (file edits, memory, todos).
+    Caution,
+    /// Deletes data, runs arbitrary commands or acts outside the machine (sending messages).
+    Destructive,
+}
+
+/// Catalog metadata of a tool for client UIs: how to group, show and warn about it.
+///
+/// Not sent to the model. Built-in tools set it in their YAML spec (`loom/tools/*.yaml`), MCP
+/// tools get the danger level from their `annotations` and the icon from `icons`; a
+/// [`ToolsListResponse`](crate::ToolsListResponse) carries it as top-level keys of each tool.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ToolCatalogInfo {
+    /// Group to list the tool under (e.g. `files`, `shell`, `web`, `memory`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Icon name (e.g. `terminal`, `file-text`) or URL; clients map names to their icon set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// Unknown when not set (e.g. MCP tools without annotations).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub danger: Option<ToolDanger>,
+    /// Example argument objects, e.g. to prefill a form for trying the tool.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<Value>,
+}
+
+impl ToolCatalogInfo {
+    /// Sets the category.
+    pub fn with_category(mut self, category: impl Into<String>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// Sets the icon hint.
+    pub fn with_icon(mut self, icon: impl Into<String>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Sets the danger level.
+    pub fn with_danger(mut self, danger: ToolDanger) -> Self {
+        self.danger = Some(danger);
+        self
+    }
+
+    /// Adds an example argument object.
+    pub fn with_example(mut self, arguments: Value) -> Self {
+        self.examples.push(arguments);
+        self
+    }
 }
 
 impl ToolOutputHint {
@@ -114,7 +213,7 @@ impl ToolOutputHint {
 /// Result of a single tool call.
 ///
 /// This represents the structured output returned to the ReAct runtime after a
-/// tool invocation. Tools can return text or structured content like file diffs.
+/// tool invocation. Tools can return text or structured content like file diffs and images.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ToolCallContent {
     /// Plain text result (most common case).
@@ -130,6 +229,14 @@ pub enum ToolCallContent {
     },
     /// Terminal command output with a terminal ID.
     Terminal { terminal_id: String },
+    /// Base64-encoded image (e.g. a screenshot). Until tool results carry multimodal content
+    /// the model sees [`Self::to_display_string`], a short description.
+    Image {
+        /// MIME type, e.g. `image/png`.
+        media_type: String,
+        /// Base64 (standard alphabet, padded) image bytes.
+        data: String,
+    },
 }
 
 impl Serialize for ToolCallContent {
@@ -159,6 +266,14 @@ impl Serialize for ToolCallContent {
                 s.serialize_field("terminal_id", terminal_id)?;
                 s.end()
             }
+            ToolCallContent::Image { media_type, data } => {
+                use serde::ser::SerializeStruct;
+                let mut s = serializer.serialize_struct("Image", 3)?;
+                s.serialize_field("type", "image")?;
+                s.serialize_field("media_type", media_type)?;
+                s.serialize_field("data", data)?;
+                s.end()
+            }
         }
     }
 }
@@ -176,7 +291,7 @@ impl<'de> Deserialize<'de> for ToolCallContent {
             type Value = ToolCallContent;
 
             fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                formatter.write_str("a string or a diff object")
+                formatter.write_str("a string or a diff, terminal or image object")
             }
 
             fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
@@ -201,6 +316,8 @@ impl<'de> Deserialize<'de> for ToolCallContent {
                 let mut old_text = None;
                 let mut new_text = None;
                 let mut terminal_id = None;
+                let mut media_type = None;
+                let mut data = None;
                 let mut content_type = None;
 
                 while let Some(key) = map.next_key::<String>()? {
@@ -210,6 +327,8 @@ impl<'de> Deserialize<'de> for ToolCallContent {
                         "old_text" => old_text = map.next_value()?,
                         "new_text" => new_text = Some(map.next_value()?),
                         "terminal_id" => terminal_id = Some(map.next_value()?),
+                        "media_type" => media_type = Some(map.next_value()?),
+                        "data" => data = Some(map.next_value()?),
                         _ => {
                             let _ = map.next_value::<serde::de::IgnoredAny>()?;
                         }
@@ -228,8 +347,13 @@ impl<'de> Deserialize<'de> for ToolCallContent {
                         old_text,
                         new_text: new_text.ok_or_else(|| de::Error::missing_field("new_text"))?,
                     }),
+                    "image" => Ok(ToolCallContent::Image {
+                        media_type: media_type
+                            .ok_or_else(|| de::Error::missing_field("media_type"))?,
+                        data: data.ok_or_else(|| de::Error::missing_field("data"))?,
+                    }),
                     other => Err(de::Error::custom(format!(
-                        "expected type 'diff' or 'terminal', got '{}'",
+                        "expected type 'diff', 'terminal' or 'image', got '{}'",
                         other
                     ))),
                 }
@@ -263,10 +387,19 @@ impl ToolCallContent {
         }
     }
 
+    pub fn image(media_type: impl Into<String>, data: impl Into<String>) -> Self {
+        ToolCallContent::Image {
+            media_type: media_type.into(),
+            data: data.into(),
+        }
+    }
+
     pub fn as_text(&self) -> Option<&str> {
         match self {
             ToolCallContent::Text(t) => Some(t),
-            ToolCallContent::Diff { .. } | ToolCallContent::Terminal { .. } => None,
+            ToolCallContent::Diff { .. }
+            | ToolCallContent::Terminal { .. }
+            | ToolCallContent::Image { .. } => None,
         }
     }
 
@@ -279,6 +412,7 @@ impl ToolCallContent {
             ToolCallContent::Terminal { terminal_id } => {
                 format!("Terminal: {}", terminal_id)
             }
+            image @ ToolCallContent::Image { .. } => image.to_display_string(),
         }
     }
 
@@ -287,6 +421,7 @@ impl ToolCallContent {
             ToolCallContent::Text(t) => t.len(),
             ToolCallContent::Diff { new_text, .. } => new_text.len(),
             ToolCallContent::Terminal { terminal_id } => terminal_id.len(),
+            ToolCallContent::Image { data, .. } => data.len(),
         }
     }
 
@@ -303,6 +438,11 @@ impl ToolCallContent {
             ToolCallContent::Terminal { terminal_id } => {
                 format!("Terminal: {}", terminal_id)
             }
+            ToolCallContent::Image { media_type, data } => {
+                let padding = data.bytes().rev().take_while(|b| *b == b'=').count();
+                let bytes = (data.len() / 4 * 3).saturating_sub(padding);
+                format!("Image ({}, {} bytes)", media_type, bytes)
+            }
         }
     }
 }
@@ -313,6 +453,7 @@ impl std::fmt::Display for ToolCallContent {
             ToolCallContent::Text(t) => write!(f, "{}", t),
             ToolCallContent::Diff { path, .. } => write!(f, "Diff({})", path),
             ToolCallContent::Terminal { terminal_id } => write!(f, "Terminal({})", terminal_id),
+            ToolCallContent::Image { media_type, .. } => write!(f, "Image({})", media_type),
         }
     }
 }
@@ -344,6 +485,43 @@ pub enum ToolSourceError {
     ToolError(String),
 }
 
+/// `type` of the custom stream event the think step emits when some tool sources were skipped
+/// because they are unavailable (see [`ToolSource::health`]).
+pub const TOOL_SOURCE_DEGRADED_EVENT: &str = "tool_source_degraded";
+
+/// A tool source (or one part of an aggregate) that is unavailable, with the reason.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DegradedSource {
+    pub name: String,
+    pub reason: String,
+}
+
+/// Result of [`ToolSource::health`]: healthy when no source is degraded.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolSourceHealth {
+    pub degraded: Vec<DegradedSource>,
+}
+
+impl ToolSourceHealth {
+    pub fn degraded(name: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            degraded: vec![DegradedSource {
+                name: name.into(),
+                reason: reason.into(),
+            }],
+        }
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.degraded.is_empty()
+    }
+
+    /// Adds the degraded sources of `other`.
+    pub fn merge(&mut self, other: ToolSourceHealth) {
+        self.degraded.extend(other.degraded);
+    }
+}
+
 /// Tool source contract used by ReAct runners.
 ///
 /// [`crate::agent::react::ThinkNode`] consumes [`Self::list_tools`] to advertise
@@ -382,6 +560,75 @@ pub trait ToolSource: Send + Sync {
     /// This hook exists for implementations that prefer explicit stateful setup
     /// before one round of tool calls. The default implementation is a no-op.
     fn set_call_context(&self, _ctx: Option<ToolCallContext>) {}
+
+    /// Picks up tools that changed upstream since the last call (e.g. an MCP server sent
+    /// `notifications/tools/list_changed`).
+    ///
+    /// Returns the full new tool list when something changed, `None` otherwise. Called by
+    /// the think step before each LLM call, so implementations should be cheap when nothing
+    /// changed. The default implementation never reports a change.
+    async fn refresh_tools(&self) -> Result<Option<Vec<ToolSpec>>, ToolSourceError> {
+        Ok(None)
+    }
+
+    /// Tools to expose to the model for a turn about `query` (the latest user message), for
+    /// sources that narrow large tool sets (see [`ToolSelectionSource`]).
+    ///
+    /// Returns `None` to expose every listed tool; this is what the default implementation does.
+    async fn tools_for_turn(&self, query: &str) -> Result<Option<Vec<ToolSpec>>, ToolSourceError> {
+        let _ = query;
+        Ok(None)
+    }
+
+    /// Sources that are currently unavailable, e.g. an MCP server that failed to connect and
+    /// whose tools are therefore not listed. Wrappers report the health of what they wrap.
+    ///
+    /// The default implementation reports a healthy source.
+    async fn health(&self) -> ToolSourceHealth {
+        ToolSourceHealth::default()
+    }
+}
+
+#[async_trait]
+impl ToolSource for std::sync::Arc<dyn ToolSource> {
+    async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+        self.as_ref().list_tools().await
+    }
+
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Value,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        self.as_ref().call_tool(name, arguments).await
+    }
+
+    async fn call_tool_with_context(
+        &self,
+        name: &str,
+        arguments: Value,
+        ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        self.as_ref()
+            .call_tool_with_context(name, arguments, ctx)
+            .await
+    }
+
+    fn set_call_context(&self, ctx: Option<ToolCallContext>) {
+        self.as_ref().set_call_context(ctx);
+    }
+
+    async fn refresh_tools(&self) -> Result<Option<Vec<ToolSpec>>, ToolSourceError> {
+        self.as_ref().refresh_tools().await
+    }
+
+    async fn tools_for_turn(&self, query: &str) -> Result<Option<Vec<ToolSpec>>, ToolSourceError> {
+        self.as_ref().tools_for_turn(query).await
+    }
+
+    async fn health(&self) -> ToolSourceHealth {
+        self.as_ref().health().await
+    }
 }
 
 #[cfg(test)]
@@ -417,6 +664,7 @@ mod tests {
             description: Some("Get time".into()),
             input_schema: serde_json::json!({}),
             output_hint: None,
+            catalog: Default::default(),
         };
         assert_eq!(spec.name, "get_time");
         let _ = spec.clone();
@@ -431,4 +679,16 @@ mod tests {
         assert!(diff.as_text().is_none());
         let _ = diff.clone();
     }
+
+    #[test]
+    fn image_content_round_trips_and_describes_itself() {
+        let image = ToolCallContent::image("image/png", "iVBORw0KGgo=");
+        assert!(image.as_text().is_none());
+        assert_eq!(image.to_display_string(), "Image (image/png, 8 bytes)");
+        let json = serde_json::to_value(&image).unwrap();
+        assert_eq!(json["type"], "image");
+        assert_eq!(json["media_type"], "image/png");
+        let back: ToolCallContent = serde_json::from_value(json).unwrap();
+        assert_eq!(back, image);
+    }
 }
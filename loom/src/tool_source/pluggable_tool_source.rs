@@ -0,0 +1,314 @@
+//! Tool source whose set of inner sources can change while a thread is active.
+//!
+//! [`PluggableToolSource`] wraps the runner's base source and lets callers add or remove named
+//! sources (e.g. a project's MCP servers) between turns. A change is reported through
+//! [`ToolSource::refresh_tools`], so the next think step sends the updated tool list to the
+//! model without rebuilding the runner.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::{
+    ToolCallContent, ToolCallContext, ToolSource, ToolSourceError, ToolSourceHealth, ToolSpec,
+};
+
+/// A source added with [`PluggableToolSource::add`] and the tool names it lists.
+struct Plugged {
+    name: String,
+    source: Arc<dyn ToolSource>,
+    tools: Vec<String>,
+}
+
+/// Base tool source plus named sources that can be added and removed at runtime.
+///
+/// Tools of added sources are listed after the base tools. Tool names must stay unique:
+/// [`add`](Self::add) rejects a source that lists a name already provided by another source.
+pub struct PluggableToolSource {
+    base: Arc<dyn ToolSource>,
+    plugged: RwLock<Vec<Plugged>>,
+    /// Set by add/remove; taken by the next `refresh_tools`.
+    changed: AtomicBool,
+}
+
+impl PluggableToolSource {
+    pub fn new(base: Arc<dyn ToolSource>) -> Self {
+        Self {
+            base,
+            plugged: RwLock::new(Vec::new()),
+            changed: AtomicBool::new(false),
+        }
+    }
+
+    /// Adds `source` under `name`, replacing a source previously added with that name.
+    /// Returns the tool names it provides.
+    ///
+    /// Fails when listing the source fails or one of its tools clashes with a tool of the base
+    /// source or another added source.
+    pub async fn add(
+        &self,
+        name: impl Into<String>,
+        source: Arc<dyn ToolSource>,
+    ) -> Result<Vec<String>, ToolSourceError> {
+        let name = name.into();
+        let tools: Vec<String> = source
+            .list_tools()
+            .await?
+            .into_iter()
+            .map(|spec| spec.name)
+            .collect();
+        let base_tools = self.base.list_tools().await?;
+        let mut plugged = self
+            .plugged
+            .write()
+            .map_err(|_| ToolSourceError::ToolError("tool source lock poisoned".to_string()))?;
+        let clash = tools.iter().find(|tool| {
+            base_tools.iter().any(|spec| &spec.name == *tool)
+                || plugged
+                    .iter()
+                    .any(|p| p.name != name && p.tools.contains(tool))
+        });
+        if let Some(tool) = clash {
+            return Err(ToolSourceError::InvalidInput(format!(
+                "tool `{}` of source `{}` is already provided by another source",
+                tool, name
+            )));
+        }
+        plugged.retain(|p| p.name != name);
+        plugged.push(Plugged {
+            name: name.clone(),
+            source,
+            tools: tools.clone(),
+        });
+        self.changed.store(true, Ordering::SeqCst);
+        tracing::debug!(source = %name, tools = tools.len(), "tool source added");
+        Ok(tools)
+    }
+
+    /// Removes the source added under `name`; returns whether one was removed.
+    pub fn remove(&self, name: &str) -> bool {
+        let Ok(mut plugged) = self.plugged.write() else {
+            return false;
+        };
+        let before = plugged.len();
+        plugged.retain(|p| p.name != name);
+        let removed = plugged.len() != before;
+        if removed {
+            self.changed.store(true, Ordering::SeqCst);
+            tracing::debug!(source = %name, "tool source removed");
+        }
+        removed
+    }
+
+    /// Names of the added sources, in the order they were added.
+    pub fn names(&self) -> Vec<String> {
+        self.plugged
+            .read()
+            .map(|plugged| plugged.iter().map(|p| p.name.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    fn plugged_sources(&self) -> Vec<(String, Arc<dyn ToolSource>)> {
+        self.plugged
+            .read()
+            .map(|plugged| {
+                plugged
+                    .iter()
+                    .map(|p| (p.name.clone(), Arc::clone(&p.source)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The source that provides `tool`: an added source listing it, else the base source.
+    fn source_for(&self, tool: &str) -> Arc<dyn ToolSource> {
+        self.plugged
+            .read()
+            .ok()
+            .and_then(|plugged| {
+                plugged
+                    .iter()
+                    .find(|p| p.tools.iter().any(|t| t == tool))
+                    .map(|p| Arc::clone(&p.source))
+            })
+            .unwrap_or_else(|| Arc::clone(&self.base))
+    }
+
+    /// Tools of all added sources. A source whose listing fails is skipped with a warning.
+    async fn plugged_tools(&self) -> Vec<ToolSpec> {
+        let mut specs = Vec::new();
+        for (name, source) in self.plugged_sources() {
+            match source.list_tools().await {
+                Ok(tools) => specs.extend(tools),
+                Err(e) => tracing::warn!(source = %name, "listing tools failed: {}", e),
+            }
+        }
+        specs
+    }
+}
+
+#[async_trait]
+impl ToolSource for PluggableToolSource {
+    async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+        let mut specs = self.base.list_tools().await?;
+        specs.extend(self.plugged_tools().await);
+        Ok(specs)
+    }
+
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Value,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        self.source_for(name).call_tool(name, arguments).await
+    }
+
+    async fn call_tool_with_context(
+        &self,
+        name: &str,
+        arguments: Value,
+        ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        self.source_for(name)
+            .call_tool_with_context(name, arguments, ctx)
+            .await
+    }
+
+    fn set_call_context(&self, ctx: Option<ToolCallContext>) {
+        for (_, source) in self.plugged_sources() {
+            source.set_call_context(ctx.clone());
+        }
+        self.base.set_call_context(ctx);
+    }
+
+    /// Reports the full tool list when a source was added or removed since the last call, or
+    /// when the base or an added source reports changed tools.
+    async fn refresh_tools(&self) -> Result<Option<Vec<ToolSpec>>, ToolSourceError> {
+        let mut changed = self.changed.swap(false, Ordering::SeqCst);
+        if self.base.refresh_tools().await?.is_some() {
+            changed = true;
+        }
+        for (name, source) in self.plugged_sources() {
+            match source.refresh_tools().await {
+                Ok(Some(specs)) => {
+                    if let Ok(mut plugged) = self.plugged.write() {
+                        if let Some(p) = plugged.iter_mut().find(|p| p.name == name) {
+                            p.tools = specs.into_iter().map(|spec| spec.name).collect();
+                        }
+                    }
+                    changed = true;
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!(source = %name, "refreshing tools failed: {}", e),
+            }
+        }
+        if changed {
+            Ok(Some(self.list_tools().await?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// The base source's selection for the turn plus every tool of the added sources.
+    async fn tools_for_turn(&self, query: &str) -> Result<Option<Vec<ToolSpec>>, ToolSourceError> {
+        match self.base.tools_for_turn(query).await? {
+            Some(mut specs) => {
+                specs.extend(self.plugged_tools().await);
+                Ok(Some(specs))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Health of the base source and every added source.
+    async fn health(&self) -> ToolSourceHealth {
+        let mut health = self.base.health().await;
+        for (_, source) in self.plugged_sources() {
+            health.merge(source.health().await);
+        }
+        health
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool_source::MockToolSource;
+
+    fn mock(names: &[&str], result: &str) -> Arc<dyn ToolSource> {
+        let tools = names
+            .iter()
+            .map(|name| ToolSpec {
+                name: name.to_string(),
+                description: None,
+                input_schema: serde_json::json!({ "type": "object" }),
+                output_hint: None,
+                catalog: Default::default(),
+            })
+            .collect();
+        Arc::new(MockToolSource::new(tools, result.to_string()))
+    }
+
+    async fn names(source: &PluggableToolSource) -> Vec<String> {
+        source
+            .list_tools()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.name)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn added_source_is_listed_routed_and_reported_once() {
+        let source = PluggableToolSource::new(mock(&["bash"], "base"));
+        assert!(source.refresh_tools().await.unwrap().is_none());
+
+        let added = source
+            .add("project", mock(&["query_db"], "project"))
+            .await
+            .unwrap();
+        assert_eq!(added, vec!["query_db".to_string()]);
+        assert_eq!(names(&source).await, vec!["bash", "query_db"]);
+        let out = source
+            .call_tool("query_db", serde_json::json!({}))
+            .await
+            .unwrap();
+        assert_eq!(out.as_text(), Some("project"));
+        let out = source
+            .call_tool("bash", serde_json::json!({}))
+            .await
+            .unwrap();
+        assert_eq!(out.as_text(), Some("base"));
+
+        let refreshed = source.refresh_tools().await.unwrap().unwrap();
+        assert_eq!(refreshed.len(), 2);
+        assert!(source.refresh_tools().await.unwrap().is_none());
+
+        assert!(source.remove("project"));
+        assert!(!source.remove("project"));
+        assert_eq!(names(&source).await, vec!["bash"]);
+        assert_eq!(source.refresh_tools().await.unwrap().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn clashing_tool_names_are_rejected_but_replacing_a_source_is_not() {
+        let source = PluggableToolSource::new(mock(&["bash"], "base"));
+        let err = source
+            .add("shell", mock(&["bash"], "other"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolSourceError::InvalidInput(_)));
+
+        source.add("db", mock(&["query_db"], "v1")).await.unwrap();
+        source.add("db", mock(&["query_db"], "v2")).await.unwrap();
+        assert_eq!(source.names(), vec!["db".to_string()]);
+        let out = source
+            .call_tool("query_db", serde_json::json!({}))
+            .await
+            .unwrap();
+        assert_eq!(out.as_text(), Some("v2"));
+    }
+}
@@ -11,7 +11,9 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use serde_json::json;
 
-use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError};
+use crate::tool_source::{
+    ToolCallContent, ToolCallContext, ToolCatalogInfo, ToolDanger, ToolSourceError,
+};
 use crate::tools::file::resolve_path_under;
 use crate::tools::{AggregateToolSource, Tool};
 
@@ -80,6 +82,10 @@ impl Tool for ReadOnlyListDirTool {
                 "required": ["path"]
             }),
             output_hint: None,
+            catalog: ToolCatalogInfo::default()
+                .with_category("files")
+                .with_icon("folder")
+                .with_danger(ToolDanger::Safe),
         }
     }
 
@@ -137,6 +143,10 @@ impl Tool for ReadOnlyReadFileTool {
                 "required": ["path"]
             }),
             output_hint: None,
+            catalog: ToolCatalogInfo::default()
+                .with_category("files")
+                .with_icon("file-text")
+                .with_danger(ToolDanger::Safe),
         }
     }
 
@@ -0,0 +1,103 @@
+//! SSH tools source: remote command execution and file copy (`ssh_exec`, `scp_get`, `scp_put`).
+//!
+//! Uses `AggregateToolSource` internally to register the [`crate::tools::ssh`] tools for a set
+//! of configured hosts. Requires feature `ssh`.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::tool_source::{ToolSource, ToolSourceError};
+use crate::tools::{AggregateToolSource, ScpGetTool, ScpPutTool, SshExecTool, SshHosts};
+
+/// Tool source that exposes SSH tools against configured hosts: ssh_exec, scp_get, scp_put.
+///
+/// Hosts authenticate with their key files; the model only sees host names. See
+/// [`crate::tools::SshHost`] for the per-host command and path allowlists.
+pub struct SshToolsSource {
+    _source: AggregateToolSource,
+}
+
+impl SshToolsSource {
+    /// Creates an SSH tools source for `hosts`. Local scp paths resolve against `working_folder`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use loom::tool_source::SshToolsSource;
+    /// use loom::tools::SshHosts;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let hosts = SshHosts::load(std::path::Path::new("ssh_hosts.yaml")).unwrap();
+    /// let source = SshToolsSource::new(hosts, None).await;
+    /// # }
+    /// ```
+    #[allow(clippy::new_ret_no_self)]
+    pub async fn new(hosts: SshHosts, working_folder: Option<PathBuf>) -> AggregateToolSource {
+        let source = AggregateToolSource::new();
+        Self::register(&source, hosts, working_folder.map(Arc::new)).await;
+        source
+    }
+
+    /// Registers the SSH tools for `hosts` on an existing `aggregate`.
+    pub async fn register(
+        aggregate: &AggregateToolSource,
+        hosts: SshHosts,
+        working_folder: Option<Arc<PathBuf>>,
+    ) {
+        aggregate
+            .register_async(Box::new(SshExecTool::new(hosts.clone())))
+            .await;
+        aggregate
+            .register_async(Box::new(ScpGetTool::new(
+                hosts.clone(),
+                working_folder.clone(),
+            )))
+            .await;
+        aggregate
+            .register_async(Box::new(ScpPutTool::new(hosts, working_folder)))
+            .await;
+    }
+}
+
+#[async_trait]
+impl ToolSource for SshToolsSource {
+    /// Lists all registered tools.
+    ///
+    /// Delegates to [`AggregateToolSource::list_tools`].
+    async fn list_tools(&self) -> Result<Vec<crate::tool_source::ToolSpec>, ToolSourceError> {
+        self._source.list_tools().await
+    }
+
+    /// Calls a tool by name with given arguments.
+    ///
+    /// Delegates to [`AggregateToolSource::call_tool`].
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: serde_json::Value,
+    ) -> Result<crate::tool_source::ToolCallContent, ToolSourceError> {
+        self._source.call_tool(name, arguments).await
+    }
+
+    /// Calls a tool by name with given arguments and optional context.
+    ///
+    /// Delegates to [`AggregateToolSource::call_tool_with_context`]; the context's run
+    /// cancellation kills a running `ssh` / `scp` process.
+    async fn call_tool_with_context(
+        &self,
+        name: &str,
+        arguments: serde_json::Value,
+        ctx: Option<&crate::tool_source::ToolCallContext>,
+    ) -> Result<crate::tool_source::ToolCallContent, ToolSourceError> {
+        self._source
+            .call_tool_with_context(name, arguments, ctx)
+            .await
+    }
+
+    /// Sets the call context for this source.
+    fn set_call_context(&self, ctx: Option<crate::tool_source::ToolCallContext>) {
+        self._source.set_call_context(ctx)
+    }
+}
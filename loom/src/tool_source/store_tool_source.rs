@@ -1,4 +1,4 @@
-//! Store-backed tool source: long-term memory as tools (remember, recall, search_memories, list_memories).
+//! Store-backed tool source: long-term memory as tools (remember, recall, search_memories, list_memories, forget).
 //!
 //! Wraps `Store` with a fixed namespace and exposes put/get/list/search as tools for the LLM.
 //! Uses AggregateToolSource internally to register memory tools.
@@ -10,7 +10,7 @@ use async_trait::async_trait;
 use crate::memory::{Namespace, Store};
 use crate::tool_source::{ToolSource, ToolSourceError};
 use crate::tools::{
-    AggregateToolSource, ListMemoriesTool, RecallTool, RememberTool, SearchMemoriesTool,
+    AggregateToolSource, ForgetTool, ListMemoriesTool, RecallTool, RememberTool, SearchMemoriesTool,
 };
 
 /// Tool name: write a key-value pair to long-term memory.
@@ -21,8 +21,10 @@ pub const TOOL_RECALL: &str = "recall";
 pub const TOOL_SEARCH_MEMORIES: &str = "search_memories";
 /// Tool name: list all keys in the current namespace.
 pub const TOOL_LIST_MEMORIES: &str = "list_memories";
+/// Tool name: delete one or more keys from long-term memory.
+pub const TOOL_FORGET: &str = "forget";
 
-/// Tool source that exposes Store operations as tools (remember, recall, search_memories, list_memories).
+/// Tool source that exposes Store operations as tools (remember, recall, search_memories, list_memories, forget).
 ///
 /// Holds `Arc<dyn Store>` and a fixed namespace (e.g. `[user_id, "memories"]`). Uses AggregateToolSource
 /// internally to register memory tools. Use with ActNode or composite ToolSource for long-term memory.
@@ -61,12 +63,14 @@ impl StoreToolSource {
         let remember = RememberTool::new(store.clone(), namespace.clone());
         let recall = RecallTool::new(store.clone(), namespace.clone());
         let search = SearchMemoriesTool::new(store.clone(), namespace.clone());
-        let list = ListMemoriesTool::new(store, namespace);
+        let list = ListMemoriesTool::new(store.clone(), namespace.clone());
+        let forget = ForgetTool::new(store, namespace);
 
         source.register_sync(Box::new(remember));
         source.register_sync(Box::new(recall));
         source.register_sync(Box::new(search));
         source.register_sync(Box::new(list));
+        source.register_sync(Box::new(forget));
 
         source
     }
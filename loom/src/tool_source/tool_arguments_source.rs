@@ -0,0 +1,262 @@
+//! Fixed tool arguments: values configured per tool and filled in before every call, so the
+//! model does not have to pass constants such as a project key or the working folder.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::{
+    ToolCallContent, ToolCallContext, ToolSource, ToolSourceError, ToolSourceHealth, ToolSpec,
+};
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+
+/// Wraps a `ToolSource` and sets configured arguments on calls to the named tools, overriding
+/// whatever the model passed. The arguments are removed from the tools' input schemas, so the
+/// model is not asked for them.
+///
+/// String values (also nested in arrays and objects) are templates: `{working_folder}`,
+/// `{thread_id}` and `{user_id}` are replaced with the run's values. An argument whose template
+/// names a value the run does not have (e.g. no user id) is left to the model.
+pub struct ToolArgumentsSource {
+    inner: Box<dyn ToolSource>,
+    arguments: HashMap<String, Map<String, Value>>,
+    working_folder: Option<PathBuf>,
+}
+
+impl ToolArgumentsSource {
+    /// `arguments` maps tool name to the arguments set on each call of that tool.
+    pub fn new(
+        inner: Box<dyn ToolSource>,
+        arguments: HashMap<String, Map<String, Value>>,
+        working_folder: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            inner,
+            arguments,
+            working_folder,
+        }
+    }
+
+    /// `arguments` with the configured ones for `name` filled in from `ctx`.
+    fn apply(&self, name: &str, mut arguments: Value, ctx: Option<&ToolCallContext>) -> Value {
+        let Some(fixed) = self.arguments.get(name) else {
+            return arguments;
+        };
+        if !arguments.is_object() {
+            arguments = Value::Object(Map::new());
+        }
+        let working_folder = self
+            .working_folder
+            .as_ref()
+            .map(|p| p.to_string_lossy().into_owned());
+        let vars = TemplateVars {
+            working_folder: working_folder.as_deref(),
+            thread_id: ctx.and_then(|c| c.thread_id.as_deref()),
+            user_id: ctx.and_then(|c| c.user_id.as_deref()),
+        };
+        let object = arguments.as_object_mut().expect("object");
+        for (key, value) in fixed {
+            match vars.render(value) {
+                Some(value) => {
+                    object.insert(key.clone(), value);
+                }
+                None => tracing::debug!(
+                    tool = name,
+                    argument = %key,
+                    "tool argument template names a value the run does not have; left to the model"
+                ),
+            }
+        }
+        arguments
+    }
+
+    /// `tools` with the configured arguments removed from their input schemas.
+    fn hide(&self, tools: Vec<ToolSpec>) -> Vec<ToolSpec> {
+        tools
+            .into_iter()
+            .map(|mut tool| {
+                if let Some(fixed) = self.arguments.get(&tool.name) {
+                    hide_properties(&mut tool.input_schema, fixed);
+                }
+                tool
+            })
+            .collect()
+    }
+}
+
+/// Values a template can refer to.
+struct TemplateVars<'a> {
+    working_folder: Option<&'a str>,
+    thread_id: Option<&'a str>,
+    user_id: Option<&'a str>,
+}
+
+impl TemplateVars<'_> {
+    /// `value` with placeholders replaced; `None` when one names a missing value.
+    fn render(&self, value: &Value) -> Option<Value> {
+        Some(match value {
+            Value::String(s) => Value::String(self.render_str(s)?),
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|v| self.render(v))
+                    .collect::<Option<_>>()?,
+            ),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| Some((k.clone(), self.render(v)?)))
+                    .collect::<Option<_>>()?,
+            ),
+            other => other.clone(),
+        })
+    }
+
+    fn render_str(&self, s: &str) -> Option<String> {
+        let mut out = s.to_string();
+        for (placeholder, value) in [
+            ("{working_folder}", self.working_folder),
+            ("{thread_id}", self.thread_id),
+            ("{user_id}", self.user_id),
+        ] {
+            if out.contains(placeholder) {
+                out = out.replace(placeholder, value?);
+            }
+        }
+        Some(out)
+    }
+}
+
+/// Removes `fixed` keys from the schema's `properties` and `required`.
+fn hide_properties(schema: &mut Value, fixed: &Map<String, Value>) {
+    if let Some(properties) = schema.get_mut("properties").and_then(Value::as_object_mut) {
+        properties.retain(|k, _| !fixed.contains_key(k));
+    }
+    if let Some(required) = schema.get_mut("required").and_then(Value::as_array_mut) {
+        required.retain(|k| k.as_str().is_none_or(|k| !fixed.contains_key(k)));
+    }
+}
+
+#[async_trait]
+impl ToolSource for ToolArgumentsSource {
+    async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+        Ok(self.hide(self.inner.list_tools().await?))
+    }
+
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Value,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let arguments = self.apply(name, arguments, None);
+        self.inner.call_tool(name, arguments).await
+    }
+
+    async fn call_tool_with_context(
+        &self,
+        name: &str,
+        arguments: Value,
+        ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let arguments = self.apply(name, arguments, ctx);
+        self.inner
+            .call_tool_with_context(name, arguments, ctx)
+            .await
+    }
+
+    fn set_call_context(&self, ctx: Option<ToolCallContext>) {
+        self.inner.set_call_context(ctx);
+    }
+
+    async fn refresh_tools(&self) -> Result<Option<Vec<ToolSpec>>, ToolSourceError> {
+        Ok(self
+            .inner
+            .refresh_tools()
+            .await?
+            .map(|tools| self.hide(tools)))
+    }
+
+    async fn tools_for_turn(&self, query: &str) -> Result<Option<Vec<ToolSpec>>, ToolSourceError> {
+        Ok(self
+            .inner
+            .tools_for_turn(query)
+            .await?
+            .map(|tools| self.hide(tools)))
+    }
+
+    async fn health(&self) -> ToolSourceHealth {
+        self.inner.health().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Returns the arguments it was called with.
+    struct Echo;
+
+    #[async_trait]
+    impl ToolSource for Echo {
+        async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+            Ok(vec![ToolSpec {
+                name: "jira_search".to_string(),
+                description: None,
+                input_schema: json!({
+                    "type": "object",
+                    "properties": { "project": {}, "query": {}, "cwd": {} },
+                    "required": ["project", "query"],
+                }),
+                output_hint: None,
+                catalog: Default::default(),
+            }])
+        }
+
+        async fn call_tool(
+            &self,
+            _name: &str,
+            arguments: Value,
+        ) -> Result<ToolCallContent, ToolSourceError> {
+            Ok(ToolCallContent::text(arguments.to_string()))
+        }
+    }
+
+    fn source() -> ToolArgumentsSource {
+        let fixed =
+            json!({ "project": "OPS", "cwd": "{working_folder}/{thread_id}", "user": "{user_id}" });
+        ToolArgumentsSource::new(
+            Box::new(Echo),
+            HashMap::from([(
+                "jira_search".to_string(),
+                fixed.as_object().unwrap().clone(),
+            )]),
+            Some(PathBuf::from("/ws")),
+        )
+    }
+
+    #[tokio::test]
+    async fn fills_and_hides_fixed_arguments() {
+        let source = source();
+        let tools = source.list_tools().await.unwrap();
+        assert_eq!(tools[0].input_schema["properties"], json!({ "query": {} }));
+        assert_eq!(tools[0].input_schema["required"], json!(["query"]));
+
+        let ctx = ToolCallContext {
+            thread_id: Some("t1".to_string()),
+            ..Default::default()
+        };
+        let out = source
+            .call_tool_with_context(
+                "jira_search",
+                json!({ "project": "WRONG", "query": "open bugs" }),
+                Some(&ctx),
+            )
+            .await
+            .unwrap();
+        let args: Value = serde_json::from_str(out.as_text().unwrap()).unwrap();
+        assert_eq!(
+            args,
+            json!({ "project": "OPS", "query": "open bugs", "cwd": "/ws/t1" })
+        );
+    }
+}
@@ -0,0 +1,336 @@
+//! Tool selection for large tool sets: exposes only the tools relevant to the current turn.
+//!
+//! [`ToolSelectionSource`] wraps the aggregated tool source. When it lists more than
+//! [`ToolSelectionConfig::threshold`] tools, [`ToolSource::tools_for_turn`] embeds the tool
+//! descriptions (once per tool list) and the latest user message, and returns the
+//! [`ToolSelectionConfig::top_k`] closest tools plus the escape hatch [`TOOL_LIST_ALL_TOOLS`].
+//! Calling that tool lists every tool, and the think step exposes all of them for the rest of
+//! the turn. Calls to tools outside the selection still go through to the inner source.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+
+use super::{
+    ToolCallContent, ToolCallContext, ToolCatalogInfo, ToolDanger, ToolSource, ToolSourceError,
+    ToolSourceHealth, ToolSpec,
+};
+use crate::memory::Embedder;
+
+/// Escape hatch tool: lists every tool and makes all of them available for the rest of the turn.
+pub const TOOL_LIST_ALL_TOOLS: &str = "list_all_tools";
+
+/// Default number of tools exposed per turn when selection is active.
+pub const DEFAULT_TOOL_SELECTION_TOP_K: usize = 10;
+
+/// When and how much to narrow the tool list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolSelectionConfig {
+    /// Selection applies only when the source lists more than this many tools.
+    pub threshold: usize,
+    /// Number of most relevant tools exposed per turn.
+    pub top_k: usize,
+}
+
+impl ToolSelectionConfig {
+    /// Reads `LOOM_TOOL_SELECTION_THRESHOLD` and `LOOM_TOOL_SELECTION_TOP_K` (default
+    /// [`DEFAULT_TOOL_SELECTION_TOP_K`]). Returns `None` when no threshold is set.
+    pub fn from_env() -> Option<Self> {
+        let threshold = std::env::var("LOOM_TOOL_SELECTION_THRESHOLD")
+            .ok()
+            .and_then(|s| s.trim().parse().ok())?;
+        let top_k = std::env::var("LOOM_TOOL_SELECTION_TOP_K")
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+            .filter(|k| *k > 0)
+            .unwrap_or(DEFAULT_TOOL_SELECTION_TOP_K);
+        Some(Self { threshold, top_k })
+    }
+}
+
+/// Tool description vectors for one tool list, reused until the list changes.
+struct ToolIndex {
+    names: Vec<String>,
+    vectors: Vec<Vec<f32>>,
+}
+
+/// Wraps a `ToolSource` and narrows large tool lists per turn by embedding similarity.
+pub struct ToolSelectionSource {
+    inner: Box<dyn ToolSource>,
+    embedder: Arc<dyn Embedder>,
+    config: ToolSelectionConfig,
+    index: Mutex<Option<ToolIndex>>,
+}
+
+impl ToolSelectionSource {
+    pub fn new(
+        inner: Box<dyn ToolSource>,
+        embedder: Arc<dyn Embedder>,
+        config: ToolSelectionConfig,
+    ) -> Self {
+        Self {
+            inner,
+            embedder,
+            config,
+            index: Mutex::new(None),
+        }
+    }
+
+    fn list_all_tools_spec() -> ToolSpec {
+        ToolSpec {
+            name: TOOL_LIST_ALL_TOOLS.to_string(),
+            description: Some(
+                "Only the tools most relevant to the request are shown. Call this to list every \
+                 available tool; all of them can be used afterwards."
+                    .to_string(),
+            ),
+            input_schema: json!({"type": "object", "properties": {}}),
+            output_hint: None,
+            catalog: ToolCatalogInfo::default()
+                .with_category("meta")
+                .with_icon("list")
+                .with_danger(ToolDanger::Safe),
+        }
+    }
+
+    fn with_list_all_tools(mut tools: Vec<ToolSpec>) -> Vec<ToolSpec> {
+        tools.push(Self::list_all_tools_spec());
+        tools
+    }
+
+    async fn list_all_tools(&self) -> Result<ToolCallContent, ToolSourceError> {
+        let tools = self.inner.list_tools().await?;
+        let lines: Vec<String> = tools
+            .iter()
+            .map(|t| match &t.description {
+                Some(d) => format!("- {}: {}", t.name, d),
+                None => format!("- {}", t.name),
+            })
+            .collect();
+        Ok(ToolCallContent::text(format!(
+            "{} tools are available:\n{}",
+            tools.len(),
+            lines.join("\n")
+        )))
+    }
+
+    /// Returns one description vector per tool, embedding only when the tool list changed.
+    async fn tool_vectors(&self, tools: &[ToolSpec]) -> Result<Vec<Vec<f32>>, ToolSourceError> {
+        let names: Vec<String> = tools.iter().map(|t| t.name.clone()).collect();
+        let mut index = self.index.lock().await;
+        if let Some(index) = index.as_ref().filter(|i| i.names == names) {
+            return Ok(index.vectors.clone());
+        }
+        let texts: Vec<String> = tools.iter().map(tool_text).collect();
+        let refs: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let vectors = self
+            .embedder
+            .embed(&refs)
+            .await
+            .map_err(|e| ToolSourceError::Transport(format!("embedding tools: {}", e)))?;
+        *index = Some(ToolIndex {
+            names,
+            vectors: vectors.clone(),
+        });
+        Ok(vectors)
+    }
+}
+
+fn tool_text(tool: &ToolSpec) -> String {
+    match &tool.description {
+        Some(d) => format!("{}: {}", tool.name, d),
+        None => tool.name.clone(),
+    }
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+#[async_trait]
+impl ToolSource for ToolSelectionSource {
+    async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+        Ok(Self::with_list_all_tools(self.inner.list_tools().await?))
+    }
+
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Value,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        if name == TOOL_LIST_ALL_TOOLS {
+            return self.list_all_tools().await;
+        }
+        self.inner.call_tool(name, arguments).await
+    }
+
+    async fn call_tool_with_context(
+        &self,
+        name: &str,
+        arguments: Value,
+        ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        if name == TOOL_LIST_ALL_TOOLS {
+            return self.list_all_tools().await;
+        }
+        self.inner
+            .call_tool_with_context(name, arguments, ctx)
+            .await
+    }
+
+    fn set_call_context(&self, ctx: Option<ToolCallContext>) {
+        self.inner.set_call_context(ctx);
+    }
+
+    async fn refresh_tools(&self) -> Result<Option<Vec<ToolSpec>>, ToolSourceError> {
+        Ok(self
+            .inner
+            .refresh_tools()
+            .await?
+            .map(Self::with_list_all_tools))
+    }
+
+    async fn health(&self) -> ToolSourceHealth {
+        self.inner.health().await
+    }
+
+    async fn tools_for_turn(&self, query: &str) -> Result<Option<Vec<ToolSpec>>, ToolSourceError> {
+        let tools = self.inner.list_tools().await?;
+        if tools.len() <= self.config.threshold || query.trim().is_empty() {
+            return Ok(None);
+        }
+        let vectors = self.tool_vectors(&tools).await?;
+        let query_vector = self
+            .embedder
+            .embed(&[query])
+            .await
+            .map_err(|e| ToolSourceError::Transport(format!("embedding query: {}", e)))?
+            .pop()
+            .unwrap_or_default();
+        let mut scored: Vec<(f32, ToolSpec)> = vectors
+            .iter()
+            .map(|v| cosine_similarity(v, &query_vector))
+            .zip(tools)
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let selected = scored
+            .into_iter()
+            .take(self.config.top_k)
+            .map(|(_, tool)| tool)
+            .collect();
+        Ok(Some(Self::with_list_all_tools(selected)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::StoreError;
+
+    /// Embeds each text as a keyword histogram over a tiny fixed vocabulary.
+    struct KeywordEmbedder;
+
+    const VOCAB: [&str; 4] = ["weather", "file", "git", "time"];
+
+    #[async_trait]
+    impl Embedder for KeywordEmbedder {
+        async fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, StoreError> {
+            Ok(texts
+                .iter()
+                .map(|t| {
+                    let t = t.to_lowercase();
+                    VOCAB.iter().map(|w| t.matches(w).count() as f32).collect()
+                })
+                .collect())
+        }
+
+        fn dimension(&self) -> usize {
+            VOCAB.len()
+        }
+    }
+
+    struct FixedTools(Vec<ToolSpec>);
+
+    #[async_trait]
+    impl ToolSource for FixedTools {
+        async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+            Ok(self.0.clone())
+        }
+
+        async fn call_tool(
+            &self,
+            name: &str,
+            _arguments: Value,
+        ) -> Result<ToolCallContent, ToolSourceError> {
+            Ok(ToolCallContent::text(format!("called {}", name)))
+        }
+    }
+
+    fn spec(name: &str, description: &str) -> ToolSpec {
+        ToolSpec {
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            input_schema: json!({}),
+            output_hint: None,
+            catalog: Default::default(),
+        }
+    }
+
+    fn source(threshold: usize, top_k: usize) -> ToolSelectionSource {
+        let tools = vec![
+            spec("forecast", "Get the weather forecast"),
+            spec("read", "Read a file"),
+            spec("commit", "Create a git commit"),
+            spec("clock", "Current time"),
+        ];
+        ToolSelectionSource::new(
+            Box::new(FixedTools(tools)),
+            Arc::new(KeywordEmbedder),
+            ToolSelectionConfig { threshold, top_k },
+        )
+    }
+
+    fn names(tools: &[ToolSpec]) -> Vec<&str> {
+        tools.iter().map(|t| t.name.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn selects_top_k_relevant_tools_plus_escape_hatch() {
+        let source = source(2, 1);
+        let tools = source
+            .tools_for_turn("what's the weather tomorrow?")
+            .await
+            .unwrap()
+            .expect("selection active");
+        assert_eq!(names(&tools), ["forecast", TOOL_LIST_ALL_TOOLS]);
+    }
+
+    #[tokio::test]
+    async fn small_tool_sets_are_not_narrowed() {
+        let source = source(4, 1);
+        assert!(source.tools_for_turn("weather").await.unwrap().is_none());
+        assert_eq!(source.list_tools().await.unwrap().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn list_all_tools_lists_every_tool_and_other_calls_pass_through() {
+        let source = source(2, 1);
+        let listing = source
+            .call_tool(TOOL_LIST_ALL_TOOLS, json!({}))
+            .await
+            .unwrap()
+            .into_text();
+        assert!(listing.starts_with("4 tools are available"));
+        assert!(listing.contains("- commit: Create a git commit"));
+        let out = source.call_tool("clock", json!({})).await.unwrap();
+        assert_eq!(out.into_text(), "called clock");
+    }
+}
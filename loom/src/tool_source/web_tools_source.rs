@@ -200,6 +200,7 @@ mod tests {
                     "properties": { "x": { "type": "number" } }
                 }),
                 output_hint: None,
+                catalog: Default::default(),
             }
         }
 
@@ -4,13 +4,20 @@
 //! `include_str!` and parsed when building the tool source. Specs from YAML override the Rust
 //! tool specs for `list_tools()`; execution still dispatches to the registered Rust `Tool`
 //! implementations. Add a new line to `TOOL_YAML_FILES` when adding a tool YAML.
+//!
+//! Besides `name`, `description` and `input_schema`, a YAML spec sets the tool's catalog
+//! metadata for client UIs: `category`, `icon`, `danger` (`safe`, `caution`, `destructive`)
+//! and `examples` (argument objects); see [`ToolCatalogInfo`](crate::tool_source::ToolCatalogInfo).
 
 use std::collections::HashMap;
+use std::sync::RwLock;
 
 use async_trait::async_trait;
 use thiserror::Error;
 
-use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSource, ToolSourceError, ToolSpec};
+use crate::tool_source::{
+    ToolCallContent, ToolCallContext, ToolSource, ToolSourceError, ToolSourceHealth, ToolSpec,
+};
 
 /// Builds a static list of embedded YAML file contents. One entry per tool; paths relative to
 /// this source file (loom/src/tool_source/). Add a new line when you add a tool under
@@ -40,7 +47,9 @@ const TOOL_YAML_FILES: &[&str] = embed_tool_yaml!(
     "../../tools/recall.yaml",
     "../../tools/search_memories.yaml",
     "../../tools/list_memories.yaml",
+    "../../tools/forget.yaml",
     "../../tools/get_recent_messages.yaml",
+    "../../tools/search_history.yaml",
     "../../tools/todo_write.yaml",
     "../../tools/todo_read.yaml",
     "../../tools/twitter_search.yaml",
@@ -81,10 +90,12 @@ pub fn load_tool_specs() -> Result<Vec<ToolSpec>, YamlSpecError> {
 ///
 /// For each tool name returned by the inner source, if a spec exists in the loaded YAML it is
 /// used; otherwise the inner spec is kept. `call_tool`, `call_tool_with_context`, and
-/// `set_call_context` are delegated to the inner source.
+/// `set_call_context` are delegated to the inner source. The merged list is rebuilt when the
+/// inner source reports changed tools from `refresh_tools()`.
 pub struct YamlSpecToolSource {
     inner: Box<dyn ToolSource>,
-    specs: Vec<ToolSpec>,
+    yaml_map: HashMap<String, ToolSpec>,
+    specs: RwLock<Vec<ToolSpec>>,
 }
 
 impl YamlSpecToolSource {
@@ -103,18 +114,29 @@ impl YamlSpecToolSource {
             .into_iter()
             .map(|s| (s.name.clone(), s))
             .collect();
-        let specs: Vec<ToolSpec> = registered
-            .into_iter()
-            .map(|r| yaml_map.get(&r.name).cloned().unwrap_or(r))
-            .collect();
-        Ok(Self { inner, specs })
+        let specs = merge_specs(&yaml_map, registered);
+        Ok(Self {
+            inner,
+            yaml_map,
+            specs: RwLock::new(specs),
+        })
     }
 }
 
+fn merge_specs(yaml_map: &HashMap<String, ToolSpec>, registered: Vec<ToolSpec>) -> Vec<ToolSpec> {
+    registered
+        .into_iter()
+        .map(|r| yaml_map.get(&r.name).cloned().unwrap_or(r))
+        .collect()
+}
+
 #[async_trait]
 impl ToolSource for YamlSpecToolSource {
     async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
-        Ok(self.specs.clone())
+        self.specs
+            .read()
+            .map(|specs| specs.clone())
+            .map_err(|e| ToolSourceError::Transport(e.to_string()))
     }
 
     async fn call_tool(
@@ -139,6 +161,21 @@ impl ToolSource for YamlSpecToolSource {
     fn set_call_context(&self, ctx: Option<ToolCallContext>) {
         self.inner.set_call_context(ctx);
     }
+
+    async fn refresh_tools(&self) -> Result<Option<Vec<ToolSpec>>, ToolSourceError> {
+        let Some(registered) = self.inner.refresh_tools().await? else {
+            return Ok(None);
+        };
+        let specs = merge_specs(&self.yaml_map, registered);
+        if let Ok(mut guard) = self.specs.write() {
+            *guard = specs.clone();
+        }
+        Ok(Some(specs))
+    }
+
+    async fn health(&self) -> ToolSourceHealth {
+        self.inner.health().await
+    }
 }
 
 #[cfg(test)]
@@ -165,4 +202,28 @@ mod tests {
         );
         assert!(names.contains(&"read"), "expected read in {:?}", names);
     }
+
+    /// **Scenario**: Every embedded YAML spec has a category and danger level, and catalog
+    /// fields serialize as top-level keys of the spec (as in ToolsListResponse).
+    #[test]
+    fn load_tool_specs_include_catalog_metadata() {
+        let specs = load_tool_specs().expect("tools/*.yaml must parse");
+        for spec in &specs {
+            assert!(
+                spec.catalog.category.is_some(),
+                "{} has no category",
+                spec.name
+            );
+            assert!(spec.catalog.danger.is_some(), "{} has no danger", spec.name);
+        }
+        let bash = specs.iter().find(|s| s.name == "bash").unwrap();
+        assert_eq!(
+            bash.catalog.danger,
+            Some(crate::tool_source::ToolDanger::Destructive)
+        );
+        let json = serde_json::to_value(bash).unwrap();
+        assert_eq!(json["category"], "shell");
+        assert_eq!(json["danger"], "destructive");
+        assert_eq!(json["examples"][0]["command"], "cargo test");
+    }
 }
@@ -1,7 +1,31 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use async_trait::async_trait;
 
-use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSource, ToolSourceError};
-use crate::tools::{Tool, ToolRegistryLocked};
+use crate::tool_source::{
+    CircuitState, McpConnect, McpToolSource, ToolCallContent, ToolCallContext, ToolSource,
+    ToolSourceError, ToolSourceHealth, ToolSpec,
+};
+use crate::tools::{McpToolAdapter, Tool, ToolRegistryLocked};
+
+/// Max time one lazily registered MCP server may take to connect and list its tools.
+pub const MCP_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// MCP server whose tools are registered in an [`AggregateToolSource`], with the tool names
+/// it contributed.
+struct McpRegistration {
+    /// Name given to [`AggregateToolSource::register_lazy_mcp`]; `None` for eager registrations.
+    name: Option<String>,
+    source: Arc<McpToolSource>,
+    names: Vec<String>,
+}
+
+/// MCP server registered with [`AggregateToolSource::register_lazy_mcp`] and not connected yet.
+struct LazyMcp {
+    name: String,
+    connect: McpConnect,
+}
 
 /// Aggregates multiple tools and implements ToolSource trait via ToolRegistry.
 ///
@@ -44,6 +68,13 @@ use crate::tools::{Tool, ToolRegistryLocked};
 pub struct AggregateToolSource {
     registry: ToolRegistryLocked,
     context: std::sync::Arc<std::sync::RwLock<Option<crate::tool_source::ToolCallContext>>>,
+    mcp_sources: std::sync::Mutex<Vec<McpRegistration>>,
+    /// Lazy MCP servers to connect on the next listing or call.
+    pending_mcp: std::sync::Mutex<Vec<LazyMcp>>,
+    /// Lazy MCP servers whose connection failed, with the error; see [`Self::retry_degraded`].
+    degraded_mcp: std::sync::Mutex<Vec<(LazyMcp, String)>>,
+    /// Held while pending servers connect, so concurrent callers connect them once.
+    connecting: tokio::sync::Mutex<()>,
 }
 
 impl AggregateToolSource {
@@ -66,6 +97,10 @@ impl AggregateToolSource {
         Self {
             registry: ToolRegistryLocked::new(),
             context: std::sync::Arc::new(std::sync::RwLock::new(None)),
+            mcp_sources: std::sync::Mutex::new(Vec::new()),
+            pending_mcp: std::sync::Mutex::new(Vec::new()),
+            degraded_mcp: std::sync::Mutex::new(Vec::new()),
+            connecting: tokio::sync::Mutex::new(()),
         }
     }
 
@@ -112,6 +147,168 @@ impl AggregateToolSource {
     pub fn register_sync(&self, tool: Box<dyn Tool>) {
         self.registry.register_sync(tool);
     }
+
+    /// Registers one [`McpToolAdapter`] per spec and remembers that these tools come from
+    /// `mcp`, so [`refresh_mcp_tools`](Self::refresh_mcp_tools) can replace them later.
+    pub async fn register_mcp(&self, mcp: Arc<McpToolSource>, specs: Vec<ToolSpec>) {
+        self.register_named_mcp(None, mcp, specs).await;
+    }
+
+    async fn register_named_mcp(
+        &self,
+        name: Option<String>,
+        mcp: Arc<McpToolSource>,
+        specs: Vec<ToolSpec>,
+    ) {
+        let names = self.register_mcp_specs(&mcp, specs).await;
+        if let Ok(mut sources) = self.mcp_sources.lock() {
+            sources.push(McpRegistration {
+                name,
+                source: mcp,
+                names,
+            });
+        }
+    }
+
+    /// Registers an MCP server that is connected on the first listing or call instead of now.
+    ///
+    /// A server that fails to connect within [`MCP_CONNECT_TIMEOUT`] is skipped with a warning
+    /// and reported by [`ToolSource::health`]; the other tools stay usable. Tool sources are
+    /// built per run, so the next run tries again; a long-lived aggregate can call
+    /// [`Self::retry_degraded`].
+    pub fn register_lazy_mcp(&self, name: impl Into<String>, connect: McpConnect) {
+        if let Ok(mut pending) = self.pending_mcp.lock() {
+            pending.push(LazyMcp {
+                name: name.into(),
+                connect,
+            });
+        }
+    }
+
+    /// Queues MCP servers that failed to connect for another attempt on the next listing.
+    pub fn retry_degraded(&self) {
+        let failed = match self.degraded_mcp.lock() {
+            Ok(mut degraded) => std::mem::take(&mut *degraded),
+            Err(_) => return,
+        };
+        if let Ok(mut pending) = self.pending_mcp.lock() {
+            pending.extend(failed.into_iter().map(|(lazy, _)| lazy));
+        }
+    }
+
+    /// Connects pending lazy MCP servers concurrently and registers their tools. Returns whether
+    /// any server was registered.
+    async fn connect_pending_mcp(&self) -> bool {
+        let has_pending = self.pending_mcp.lock().is_ok_and(|p| !p.is_empty());
+        if !has_pending {
+            return false;
+        }
+        let _connecting = self.connecting.lock().await;
+        let pending = match self.pending_mcp.lock() {
+            Ok(mut pending) => std::mem::take(&mut *pending),
+            Err(_) => return false,
+        };
+        let attempts = pending.iter().map(|lazy| async move {
+            let connected = tokio::time::timeout(MCP_CONNECT_TIMEOUT, (lazy.connect)())
+                .await
+                .map_err(|_| {
+                    ToolSourceError::Transport(format!(
+                        "timed out after {}s",
+                        MCP_CONNECT_TIMEOUT.as_secs()
+                    ))
+                })??;
+            let (mcp, specs) = connected;
+            mcp.watch_notifications();
+            let specs = match specs {
+                Some(specs) => specs,
+                None => mcp.list_tools().await?,
+            };
+            Ok::<_, ToolSourceError>((mcp, specs))
+        });
+        let results = futures::future::join_all(attempts).await;
+        let mut registered = false;
+        for (lazy, result) in pending.into_iter().zip(results) {
+            match result {
+                Ok((mcp, specs)) => {
+                    tracing::debug!(name = %lazy.name, tools = specs.len(), "MCP server connected");
+                    self.register_named_mcp(Some(lazy.name), Arc::new(mcp), specs)
+                        .await;
+                    registered = true;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        name = %lazy.name,
+                        "MCP server unavailable, skipping its tools: {}",
+                        e
+                    );
+                    if let Ok(mut degraded) = self.degraded_mcp.lock() {
+                        degraded.push((lazy, e.to_string()));
+                    }
+                }
+            }
+        }
+        registered
+    }
+
+    async fn register_mcp_specs(
+        &self,
+        mcp: &Arc<McpToolSource>,
+        specs: Vec<ToolSpec>,
+    ) -> Vec<String> {
+        let mut names = Vec::with_capacity(specs.len());
+        for spec in specs {
+            let name = spec.name.clone();
+            names.push(name.clone());
+            let adapter = McpToolAdapter::new(name, spec, Arc::clone(mcp));
+            self.registry.register_async(Box::new(adapter)).await;
+        }
+        names
+    }
+
+    /// Re-lists the tools of every registered MCP server that sent
+    /// `notifications/tools/list_changed` and replaces its tools in the registry.
+    ///
+    /// Returns whether any server's tools were replaced. A server whose `tools/list` fails
+    /// keeps its previous tools.
+    pub async fn refresh_mcp_tools(&self) -> bool {
+        let changed: Vec<(usize, Arc<McpToolSource>)> = match self.mcp_sources.lock() {
+            Ok(sources) => sources
+                .iter()
+                .enumerate()
+                .filter(|(_, r)| r.source.take_tools_changed())
+                .map(|(i, r)| (i, Arc::clone(&r.source)))
+                .collect(),
+            Err(_) => return false,
+        };
+        let mut refreshed = false;
+        for (idx, source) in changed {
+            let specs = match source.list_tools().await {
+                Ok(specs) => specs,
+                Err(e) => {
+                    tracing::warn!("MCP tool list changed but tools/list failed: {}", e);
+                    continue;
+                }
+            };
+            let old_names = match self.mcp_sources.lock() {
+                Ok(mut sources) => std::mem::take(&mut sources[idx].names),
+                Err(_) => continue,
+            };
+            for name in &old_names {
+                self.registry.unregister(name).await;
+            }
+            let names = self.register_mcp_specs(&source, specs).await;
+            tracing::debug!(
+                before = old_names.len(),
+                after = names.len(),
+                "MCP tools refreshed"
+            );
+            if let Ok(mut sources) = self.mcp_sources.lock() {
+                sources[idx].names = names;
+            }
+            refreshed = true;
+        }
+        refreshed
+    }
 }
 
 impl Default for AggregateToolSource {
@@ -139,6 +336,7 @@ impl ToolSource for AggregateToolSource {
     /// - Called by ThinkNode to build tool descriptions for LLM prompts
     /// - Delegates to ToolRegistryLocked::list()
     async fn list_tools(&self) -> Result<Vec<crate::tool_source::ToolSpec>, ToolSourceError> {
+        self.connect_pending_mcp().await;
         Ok(self.registry.list().await)
     }
 
@@ -169,6 +367,7 @@ impl ToolSource for AggregateToolSource {
         name: &str,
         arguments: serde_json::Value,
     ) -> Result<ToolCallContent, ToolSourceError> {
+        self.connect_pending_mcp().await;
         let effective_ctx: Option<ToolCallContext> =
             self.context.read().ok().and_then(|g| g.as_ref().cloned());
         let effective_ctx_ref: Option<&ToolCallContext> = effective_ctx.as_ref();
@@ -206,6 +405,7 @@ impl ToolSource for AggregateToolSource {
         arguments: serde_json::Value,
         ctx: Option<&ToolCallContext>,
     ) -> Result<ToolCallContent, ToolSourceError> {
+        self.connect_pending_mcp().await;
         if let Some(c) = ctx {
             if let Ok(mut g) = self.context.write() {
                 *g = Some(c.clone());
@@ -224,6 +424,39 @@ impl ToolSource for AggregateToolSource {
             *g = ctx;
         }
     }
+
+    /// Connects pending lazy MCP servers and replaces the tools of MCP servers that announced a
+    /// tool list change; see [`AggregateToolSource::refresh_mcp_tools`].
+    async fn refresh_tools(&self) -> Result<Option<Vec<ToolSpec>>, ToolSourceError> {
+        let connected = self.connect_pending_mcp().await;
+        if self.refresh_mcp_tools().await || connected {
+            Ok(Some(self.list_tools().await?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Lazy MCP servers that failed to connect, plus MCP servers whose circuit breaker is open.
+    async fn health(&self) -> ToolSourceHealth {
+        let mut health = ToolSourceHealth::default();
+        if let Ok(degraded) = self.degraded_mcp.lock() {
+            for (lazy, reason) in degraded.iter() {
+                health.merge(ToolSourceHealth::degraded(
+                    lazy.name.clone(),
+                    reason.clone(),
+                ));
+            }
+        }
+        if let Ok(sources) = self.mcp_sources.lock() {
+            for r in sources.iter() {
+                if r.source.breaker_state() == Some(CircuitState::Open) {
+                    let name = r.name.clone().unwrap_or_else(|| "mcp".to_string());
+                    health.merge(ToolSourceHealth::degraded(name, "circuit breaker open"));
+                }
+            }
+        }
+        health
+    }
 }
 
 #[async_trait]
@@ -254,4 +487,49 @@ impl ToolSource for std::sync::Arc<AggregateToolSource> {
     fn set_call_context(&self, ctx: Option<ToolCallContext>) {
         self.as_ref().set_call_context(ctx);
     }
+
+    async fn refresh_tools(&self) -> Result<Option<Vec<ToolSpec>>, ToolSourceError> {
+        self.as_ref().refresh_tools().await
+    }
+
+    async fn health(&self) -> ToolSourceHealth {
+        self.as_ref().health().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::tool_source::McpConnectFuture;
+    use crate::tools::WebFetcherTool;
+
+    #[tokio::test]
+    async fn unreachable_lazy_mcp_is_skipped_reported_and_retried() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&attempts);
+        let connect: McpConnect = Arc::new(move || -> McpConnectFuture {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async { Err(ToolSourceError::Transport("connection refused".to_string())) })
+        });
+        let source = AggregateToolSource::new();
+        source.register_sync(Box::new(WebFetcherTool::new()));
+        source.register_lazy_mcp("exa", connect);
+        assert!(source.health().await.is_healthy());
+
+        let tools = source.list_tools().await.unwrap();
+        assert_eq!(tools.len(), 1);
+        source.list_tools().await.unwrap();
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+        let health = source.health().await;
+        assert_eq!(health.degraded.len(), 1);
+        assert_eq!(health.degraded[0].name, "exa");
+        assert!(health.degraded[0].reason.contains("connection refused"));
+
+        source.retry_degraded();
+        assert!(source.refresh_tools().await.unwrap().is_none());
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert!(!source.health().await.is_healthy());
+    }
 }
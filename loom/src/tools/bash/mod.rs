@@ -1,7 +1,10 @@
 //! Bash (shell) tool: run shell commands as an agent tool.
 //!
-//! Provides [`BashTool`] which executes a single shell command and returns
-//! stdout and stderr. Uses `sh -c` on Unix and `cmd /C` on Windows.
+//! Provides [`BashTool`] which executes a single shell command and returns a JSON object with
+//! `exit_code`, `stdout`, `stderr`, `duration_ms` and `timed_out` ([`BashOutputFormat::Structured`]),
+//! or the older concatenated text ([`BashOutputFormat::Legacy`]). Uses `sh -c` on Unix and
+//! `cmd /C` on Windows. Output is streamed line by line as partial results
+//! ([`ToolStreamWriter::emit_partial`]) while the command runs.
 //! Interacts with [`Tool`], [`ToolRegistry`](crate::tools::ToolRegistryLocked),
 //! and [`AggregateToolSource`].
 
@@ -13,6 +16,7 @@ use serde_json::json;
 use tokio::io::AsyncReadExt;
 use tokio::sync::watch;
 
+use crate::stream::ToolStreamWriter;
 use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError};
 use crate::tools::Tool;
 use crate::{ActiveOperation, ActiveOperationCanceller, ActiveOperationKind};
@@ -56,6 +60,29 @@ pub const TOOL_BASH: &str = "bash";
 ///   child-process cancellation so user cancel kills the shell (`sh`/`cmd`) subprocess.
 pub struct BashTool {
     working_folder: Option<Arc<std::path::PathBuf>>,
+    output_format: BashOutputFormat,
+}
+
+/// Shape of the text [`BashTool`] returns.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BashOutputFormat {
+    /// JSON object: `{"exit_code", "stdout", "stderr", "duration_ms", "timed_out"}`. A timeout is
+    /// reported with `timed_out: true` and the output captured so far.
+    #[default]
+    Structured,
+    /// Stdout and stderr concatenated as `stdout:\n...\nstderr:\n...`; exit code is dropped and
+    /// a timeout is an error. For callers that parse the old format.
+    Legacy,
+}
+
+impl BashOutputFormat {
+    /// `LOOM_BASH_OUTPUT=legacy` selects [`BashOutputFormat::Legacy`]; anything else is structured.
+    pub fn from_env() -> Self {
+        match std::env::var("LOOM_BASH_OUTPUT") {
+            Ok(v) if v.trim().eq_ignore_ascii_case("legacy") => Self::Legacy,
+            _ => Self::Structured,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -79,9 +106,11 @@ impl BashTool {
     /// Creates a new BashTool without a default working folder.
     ///
     /// Commands will run in the process's current directory unless `workdir` is specified.
+    /// The output format comes from [`BashOutputFormat::from_env`].
     pub fn new() -> Self {
         Self {
             working_folder: None,
+            output_format: BashOutputFormat::from_env(),
         }
     }
 
@@ -91,8 +120,15 @@ impl BashTool {
     pub fn with_working_folder(working_folder: Arc<std::path::PathBuf>) -> Self {
         Self {
             working_folder: Some(working_folder),
+            output_format: BashOutputFormat::from_env(),
         }
     }
+
+    /// Sets the result format (default: structured JSON).
+    pub fn with_output_format(mut self, output_format: BashOutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
 }
 
 #[async_trait]
@@ -118,8 +154,15 @@ impl Tool for BashTool {
             description: Some(
                 "Executes a shell command in a subprocess with optional workdir and timeout. \
                  Use for git, npm, cargo, docker, etc. Do NOT use for file read/write/search — use read, grep, glob, edit instead. \
-                 On Unix uses sh -c; on Windows uses cmd /C. Returns combined stdout and stderr."
-                    .to_string(),
+                 On Unix uses sh -c; on Windows uses cmd /C. "
+                    .to_string()
+                    + match self.output_format {
+                        BashOutputFormat::Structured => {
+                            "Returns JSON with exit_code (null if killed), stdout, stderr, \
+                             duration_ms and timed_out; check exit_code to detect failure."
+                        }
+                        BashOutputFormat::Legacy => "Returns combined stdout and stderr.",
+                    },
             ),
             input_schema: json!({
                 "type": "object",
@@ -147,6 +190,7 @@ impl Tool for BashTool {
             output_hint: Some(
                 ToolOutputHint::preferred(ToolOutputStrategy::HeadTail).prefer_head_tail(),
             ),
+            catalog: Default::default(),
         }
     }
 
@@ -159,13 +203,16 @@ impl Tool for BashTool {
     ///
     /// # Returns
     ///
-    /// Combined stdout and stderr as text. If both are non-empty, format is
-    /// "stdout:\n{stdout}\nstderr:\n{stderr}".
+    /// With [`BashOutputFormat::Structured`], a JSON object as text:
+    /// `{"exit_code": 0, "stdout": "...", "stderr": "...", "duration_ms": 12, "timed_out": false}`.
+    /// With [`BashOutputFormat::Legacy`], combined stdout and stderr; if both are non-empty,
+    /// format is "stdout:\n{stdout}\nstderr:\n{stderr}".
     ///
     /// # Errors
     ///
     /// - [`ToolSourceError::InvalidInput`] if `command` is missing or not a string.
-    /// - [`ToolSourceError::Transport`] if the process fails to start or times out.
+    /// - [`ToolSourceError::Transport`] if the process fails to start or is cancelled, and in
+    ///   legacy format also when it times out.
     ///
     /// # Interaction
     ///
@@ -197,24 +244,55 @@ impl Tool for BashTool {
 
         let output = run_shell_command(command, workdir_str, timeout_ms, ctx).await?;
 
-        let text = if output.stderr.is_empty() {
-            output.stdout
-        } else if output.stdout.is_empty() {
-            format!("stderr:\n{}", output.stderr)
-        } else {
-            format!("stdout:\n{}\nstderr:\n{}", output.stdout, output.stderr)
+        let text = match self.output_format {
+            BashOutputFormat::Structured => output.to_json().to_string(),
+            BashOutputFormat::Legacy => output.into_legacy_text()?,
         };
-
         Ok(ToolCallContent::text(text))
     }
 }
 
-/// Result of running a shell command (stdout and stderr).
-struct ShellOutput {
-    stdout: String,
-    stderr: String,
+/// Result of running a shell command.
+pub(crate) struct ShellOutput {
+    pub(crate) stdout: String,
+    pub(crate) stderr: String,
+    /// Exit code; `None` when the process was killed (timeout) or ended by a signal.
+    pub(crate) exit_code: Option<i32>,
+    pub(crate) timed_out: bool,
+    pub(crate) duration: std::time::Duration,
 }
 
+impl ShellOutput {
+    /// Structured result object returned by [`BashOutputFormat::Structured`].
+    pub(crate) fn to_json(&self) -> serde_json::Value {
+        json!({
+            "exit_code": self.exit_code,
+            "stdout": self.stdout,
+            "stderr": self.stderr,
+            "duration_ms": self.duration.as_millis() as u64,
+            "timed_out": self.timed_out,
+        })
+    }
+
+    /// Concatenated stdout/stderr text; a timeout is an error, as before structured output.
+    pub(crate) fn into_legacy_text(self) -> Result<String, ToolSourceError> {
+        if self.timed_out {
+            return Err(ToolSourceError::Transport("command timed out".to_string()));
+        }
+        Ok(if self.stderr.is_empty() {
+            self.stdout
+        } else if self.stdout.is_empty() {
+            format!("stderr:\n{}", self.stderr)
+        } else {
+            format!("stdout:\n{}\nstderr:\n{}", self.stdout, self.stderr)
+        })
+    }
+}
+
+/// How long to keep reading pipes after a timed-out process was killed. Background children
+/// that inherited the pipes would otherwise keep the readers open indefinitely.
+const PIPE_DRAIN_GRACE: std::time::Duration = std::time::Duration::from_millis(500);
+
 #[cfg(unix)]
 async fn run_shell_command(
     command: &str,
@@ -250,18 +328,27 @@ async fn run_shell_command(
     run_spawned_shell_command(cmd, timeout_ms, ctx).await
 }
 
-async fn run_spawned_shell_command(
+/// Spawns `cmd` (stdout/stderr piped) and collects its output. On timeout (`timeout_ms` 0 =
+/// none) the child is killed and the output so far is returned with `timed_out` set; when the
+/// run in `ctx` is cancelled the child is killed and an error is returned. Complete lines of
+/// stdout and stderr are emitted as partial results on the context's stream writer as they
+/// arrive. Also used by the SSH and python tools.
+pub(crate) async fn run_spawned_shell_command(
     mut cmd: tokio::process::Command,
     timeout_ms: u64,
     ctx: Option<&ToolCallContext>,
 ) -> Result<ShellOutput, ToolSourceError> {
+    let started = std::time::Instant::now();
     let mut child = cmd
         .spawn()
         .map_err(|e| ToolSourceError::Transport(format!("failed to run command: {}", e)))?;
     let stdout = child.stdout.take();
     let stderr = child.stderr.take();
-    let stdout_reader = tokio::spawn(async move { read_pipe(stdout).await });
-    let stderr_reader = tokio::spawn(async move { read_pipe(stderr).await });
+    let stdout_buf = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let stderr_buf = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let writer = ctx.and_then(|ctx| ctx.stream_writer.clone());
+    let stdout_reader = tokio::spawn(read_pipe(stdout, Arc::clone(&stdout_buf), writer.clone()));
+    let stderr_reader = tokio::spawn(read_pipe(stderr, Arc::clone(&stderr_buf), writer));
 
     let (kill_tx, mut kill_rx) = watch::channel(false);
     if let Some(run_cancellation) = ctx.and_then(|ctx| ctx.run_cancellation.clone()) {
@@ -271,48 +358,87 @@ async fn run_spawned_shell_command(
         ));
     }
 
-    let status = if timeout_ms == 0 {
-        tokio::select! {
-            _ = kill_rx.changed() => {
-                let _ = child.kill().await;
-                return Err(ToolSourceError::Transport("command cancelled".to_string()));
-            }
-            status = child.wait() => status,
+    let deadline = async {
+        if timeout_ms == 0 {
+            std::future::pending::<()>().await
+        } else {
+            tokio::time::sleep(std::time::Duration::from_millis(timeout_ms)).await
         }
-    } else {
-        tokio::select! {
-            _ = kill_rx.changed() => {
-                let _ = child.kill().await;
-                return Err(ToolSourceError::Transport("command cancelled".to_string()));
-            }
-            _ = tokio::time::sleep(std::time::Duration::from_millis(timeout_ms)) => {
-                let _ = child.kill().await;
-                return Err(ToolSourceError::Transport("command timed out".to_string()));
-            }
-            status = child.wait() => status,
+    };
+    let status = tokio::select! {
+        _ = kill_rx.changed() => {
+            let _ = child.kill().await;
+            return Err(ToolSourceError::Transport("command cancelled".to_string()));
         }
-    }
-    .map_err(|e| ToolSourceError::Transport(format!("failed to run command: {}", e)))?;
+        _ = deadline => {
+            let _ = child.kill().await;
+            None
+        }
+        status = child.wait() => Some(
+            status.map_err(|e| ToolSourceError::Transport(format!("failed to run command: {}", e)))?,
+        ),
+    };
+    let timed_out = status.is_none();
 
-    let stdout = stdout_reader
-        .await
-        .map_err(|e| ToolSourceError::Transport(format!("failed to read stdout: {}", e)))?;
-    let stderr = stderr_reader
-        .await
-        .map_err(|e| ToolSourceError::Transport(format!("failed to read stderr: {}", e)))?;
-    let _ = status;
-    Ok(ShellOutput { stdout, stderr })
+    if timed_out {
+        let _ = tokio::time::timeout(PIPE_DRAIN_GRACE, async {
+            let _ = stdout_reader.await;
+            let _ = stderr_reader.await;
+        })
+        .await;
+    } else {
+        stdout_reader
+            .await
+            .map_err(|e| ToolSourceError::Transport(format!("failed to read stdout: {}", e)))?;
+        stderr_reader
+            .await
+            .map_err(|e| ToolSourceError::Transport(format!("failed to read stderr: {}", e)))?;
+    }
+    let take = |buf: &std::sync::Mutex<Vec<u8>>| {
+        let bytes = std::mem::take(&mut *buf.lock().unwrap_or_else(|e| e.into_inner()));
+        String::from_utf8_lossy(&bytes).into_owned()
+    };
+    let (stdout, stderr) = (take(&stdout_buf), take(&stderr_buf));
+    Ok(ShellOutput {
+        stdout,
+        stderr,
+        exit_code: status.and_then(|s| s.code()),
+        timed_out,
+        duration: started.elapsed(),
+    })
 }
 
-async fn read_pipe<R>(pipe: Option<R>) -> String
-where
+/// Reads `pipe` to the end, appending to `buf` as data arrives so output is available even
+/// when the reader is abandoned after a timeout. Complete lines go to `writer` as partial
+/// results; a last line without a newline is emitted at the end.
+async fn read_pipe<R>(
+    pipe: Option<R>,
+    buf: Arc<std::sync::Mutex<Vec<u8>>>,
+    writer: Option<ToolStreamWriter>,
+) where
     R: tokio::io::AsyncRead + Unpin,
 {
-    if let Some(mut pipe) = pipe {
-        let mut buf = Vec::new();
-        let _ = pipe.read_to_end(&mut buf).await;
-        String::from_utf8_lossy(&buf).into_owned()
-    } else {
-        String::new()
+    let Some(mut pipe) = pipe else {
+        return;
+    };
+    let mut chunk = [0u8; 8192];
+    let mut pending = Vec::new();
+    while let Ok(n) = pipe.read(&mut chunk).await {
+        if n == 0 {
+            break;
+        }
+        buf.lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend_from_slice(&chunk[..n]);
+        if let Some(writer) = &writer {
+            pending.extend_from_slice(&chunk[..n]);
+            if let Some(end) = pending.iter().rposition(|b| *b == b'\n') {
+                let lines: Vec<u8> = pending.drain(..=end).collect();
+                writer.emit_partial(&String::from_utf8_lossy(&lines));
+            }
+        }
+    }
+    if let (Some(writer), false) = (&writer, pending.is_empty()) {
+        writer.emit_partial(&String::from_utf8_lossy(&pending));
     }
 }
@@ -13,6 +13,24 @@ pub const TOOL_BATCH: &str = "batch";
 
 const MAX_CALLS: usize = 25;
 
+/// Sub-calls of `batch` arguments as `(tool, parameters)`; entries without a tool name are
+/// skipped. Lets tool filters and approval rules see the tools a batch would run.
+pub(crate) fn batch_sub_calls(
+    args: &serde_json::Value,
+) -> impl Iterator<Item = (&str, &serde_json::Value)> {
+    args.get("calls")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|call| {
+            let tool = call.get("tool")?.as_str()?;
+            Some((
+                tool,
+                call.get("parameters").unwrap_or(&serde_json::Value::Null),
+            ))
+        })
+}
+
 /// Tool that executes multiple tool calls in parallel.
 pub struct BatchTool {
     source: Arc<AggregateToolSource>,
@@ -59,6 +77,7 @@ impl Tool for BatchTool {
                 "required": ["calls"]
             }),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
@@ -116,6 +116,7 @@ impl Tool for GetRecentMessagesTool {
             output_hint: Some(
                 ToolOutputHint::preferred(ToolOutputStrategy::SummaryOnly).safe_inline_chars(1_000),
             ),
+            catalog: Default::default(),
         }
     }
 
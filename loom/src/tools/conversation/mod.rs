@@ -1,3 +1,5 @@
 mod get_recent_messages;
+mod search_history;
 
 pub use get_recent_messages::{GetRecentMessagesTool, TOOL_GET_RECENT_MESSAGES};
+pub use search_history::{HistorySearch, SearchHistoryTool, TOOL_SEARCH_HISTORY};
@@ -0,0 +1,203 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use crate::message::Message;
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError};
+use crate::tools::Tool;
+use crate::user_message::UserMessageStore;
+use crate::{ToolOutputHint, ToolOutputStrategy};
+
+/// Tool name for the search_history operation.
+pub const TOOL_SEARCH_HISTORY: &str = "search_history";
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 50;
+/// Longest message excerpt returned per hit, in characters.
+const EXCERPT_CHARS: usize = 500;
+
+/// Message store and thread scope the `search_history` tool searches.
+///
+/// Set on [`crate::ReactBuildConfig::history_search`] (serve sets it for runs in a workspace,
+/// scoped to the workspace's threads).
+#[derive(Clone)]
+pub struct HistorySearch {
+    pub store: Arc<dyn UserMessageStore>,
+    /// Threads that may be searched; `None` searches every thread in the store.
+    pub thread_ids: Option<Vec<String>>,
+}
+
+impl std::fmt::Debug for HistorySearch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HistorySearch")
+            .field("thread_ids", &self.thread_ids)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Tool for searching messages of earlier conversations.
+///
+/// Full-text search via [`UserMessageStore::search`] over the threads in [`HistorySearch`],
+/// excluding the current thread (from ToolCallContext). Each hit cites its thread and the
+/// time the message was stored.
+///
+/// # Interaction
+///
+/// - **UserMessageStore**: Performs the search
+/// - **ToolCallContext**: Provides the current thread id to exclude
+/// - **build_tool_source**: Registers this tool when `ReactBuildConfig::history_search` is set
+pub struct SearchHistoryTool {
+    search: HistorySearch,
+}
+
+impl SearchHistoryTool {
+    pub fn new(search: HistorySearch) -> Self {
+        Self { search }
+    }
+
+    fn excerpt(text: &str) -> String {
+        match text.char_indices().nth(EXCERPT_CHARS) {
+            Some((i, _)) => format!("{}…", &text[..i]),
+            None => text.to_string(),
+        }
+    }
+
+    fn role_and_text(m: &Message) -> (&'static str, String) {
+        match m {
+            Message::System(s) => ("system", s.clone()),
+            Message::User(c) => ("user", c.as_text().to_string()),
+            Message::Assistant(p) => ("assistant", p.content.clone()),
+            Message::Tool { content, .. } => {
+                ("tool", content.as_text().unwrap_or_default().to_string())
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for SearchHistoryTool {
+    fn name(&self) -> &str {
+        TOOL_SEARCH_HISTORY
+    }
+
+    fn spec(&self) -> crate::tool_source::ToolSpec {
+        crate::tool_source::ToolSpec {
+            name: TOOL_SEARCH_HISTORY.to_string(),
+            description: Some(
+                "Search messages from earlier conversations with this user (not the current one). \
+                 Call when the user refers to something discussed before. Each result cites the \
+                 source thread_id and the message timestamp."
+                    .to_string(),
+            ),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Words to search for; all must appear in a message" },
+                    "limit": { "type": "integer", "description": "Max results (optional, default 10)" }
+                },
+                "required": ["query"]
+            }),
+            output_hint: Some(
+                ToolOutputHint::preferred(ToolOutputStrategy::SummaryOnly).safe_inline_chars(4_000),
+            ),
+            catalog: Default::default(),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: Value,
+        ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .filter(|q| !q.trim().is_empty())
+            .ok_or_else(|| ToolSourceError::InvalidInput("missing query".to_string()))?;
+        let limit = args
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map_or(DEFAULT_LIMIT, |n| n.min(MAX_LIMIT as u64) as u32);
+
+        let current_thread = ctx.and_then(|c| c.thread_id.as_deref());
+        let thread_ids: Option<Vec<String>> = self.search.thread_ids.as_ref().map(|ids| {
+            ids.iter()
+                .filter(|id| Some(id.as_str()) != current_thread)
+                .cloned()
+                .collect()
+        });
+        // An unscoped search can't exclude the current thread in the query; fetch extra so
+        // `limit` hits usually remain after dropping its messages.
+        let fetch = if thread_ids.is_none() && current_thread.is_some() {
+            limit.saturating_mul(2)
+        } else {
+            limit
+        };
+        let hits = self
+            .search
+            .store
+            .search(query, thread_ids.as_deref(), fetch)
+            .await
+            .map_err(|e| ToolSourceError::Transport(e.to_string()))?;
+
+        let arr: Vec<Value> = hits
+            .into_iter()
+            .filter(|h| Some(h.thread_id.as_str()) != current_thread)
+            .take(limit as usize)
+            .map(|h| {
+                let (role, text) = Self::role_and_text(&h.message);
+                json!({
+                    "thread_id": h.thread_id,
+                    "timestamp": h
+                        .created_at_ms
+                        .and_then(chrono::DateTime::from_timestamp_millis)
+                        .map(|t| t.to_rfc3339()),
+                    "role": role,
+                    "content": Self::excerpt(&text),
+                })
+            })
+            .collect();
+        let text = serde_json::to_string(&arr)
+            .map_err(|e| ToolSourceError::InvalidInput(e.to_string()))?;
+        Ok(ToolCallContent::text(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::user_message::SqliteUserMessageStore;
+
+    #[tokio::test]
+    async fn search_history_cites_other_threads_only() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let store = Arc::new(SqliteUserMessageStore::new(file.path()).unwrap());
+        store
+            .append("old", &Message::user("we picked the blue logo"))
+            .await
+            .unwrap();
+        store
+            .append("current", &Message::user("which logo did we pick?"))
+            .await
+            .unwrap();
+        let tool = SearchHistoryTool::new(HistorySearch {
+            store,
+            thread_ids: None,
+        });
+        let mut ctx = ToolCallContext::new(vec![]);
+        ctx.thread_id = Some("current".to_string());
+
+        let out = tool
+            .call(json!({"query": "logo"}), Some(&ctx))
+            .await
+            .unwrap();
+        let hits: Vec<Value> = serde_json::from_str(out.as_text().unwrap()).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0]["thread_id"], "old");
+        assert_eq!(hits[0]["role"], "user");
+        assert!(hits[0]["timestamp"].is_string());
+
+        assert!(tool.call(json!({}), Some(&ctx)).await.is_err());
+    }
+}
@@ -263,6 +263,7 @@ impl Tool for ExaWebsearchTool {
             output_hint: Some(ToolOutputHint::preferred(
                 ToolOutputStrategy::FileRefWithExcerpt,
             )),
+            catalog: Default::default(),
         }
     }
 
@@ -355,6 +356,7 @@ impl Tool for ExaCodesearchTool {
             output_hint: Some(ToolOutputHint::preferred(
                 ToolOutputStrategy::FileRefWithExcerpt,
             )),
+            catalog: Default::default(),
         }
     }
 
@@ -174,6 +174,7 @@ impl Tool for ApplyPatchTool {
                 "required": ["patchText"]
             }),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
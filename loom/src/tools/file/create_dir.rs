@@ -64,6 +64,7 @@ impl Tool for CreateDirTool {
                 "required": ["path"]
             }),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
@@ -63,6 +63,7 @@ impl Tool for DeleteFileTool {
                 "required": ["path"]
             }),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
@@ -103,6 +103,7 @@ impl Tool for EditFileTool {
                 "required": ["path", "oldString", "newString"]
             }),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
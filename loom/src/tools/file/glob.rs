@@ -82,6 +82,7 @@ impl Tool for GlobTool {
                 "required": ["pattern"]
             }),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
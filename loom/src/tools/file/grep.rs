@@ -139,6 +139,7 @@ impl Tool for GrepTool {
                 "required": ["pattern"]
             }),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
@@ -106,6 +106,7 @@ impl Tool for LsTool {
                 }
             }),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
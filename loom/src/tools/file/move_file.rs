@@ -62,6 +62,7 @@ impl Tool for MoveFileTool {
                 "required": ["source", "target"]
             }),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
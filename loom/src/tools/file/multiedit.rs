@@ -66,6 +66,7 @@ impl Tool for MultieditTool {
                 "required": ["path", "edits"]
             }),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
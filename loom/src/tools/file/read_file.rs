@@ -77,6 +77,7 @@ impl Tool for ReadFileTool {
                 "required": ["path"]
             }),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
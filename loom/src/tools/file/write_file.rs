@@ -67,6 +67,7 @@ impl Tool for WriteFileTool {
                 "required": ["path", "content"]
             }),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
@@ -180,6 +180,7 @@ impl Tool for InvokeAgentTool {
                 "required": ["agents"]
             }),
             output_hint: Some(ToolOutputHint::preferred(ToolOutputStrategy::SummaryOnly)),
+            catalog: Default::default(),
         }
     }
 
@@ -120,6 +120,7 @@ impl Tool for LspTool {
                 "required": ["action", "file_path"]
             }),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
@@ -75,16 +75,15 @@ pub async fn register_mcp_tools(
 
 /// Registers pre-fetched MCP tool specs into the aggregate. Use when tools were
 /// already obtained (e.g. inside spawn_blocking for stdio MCP) to avoid block_in_place on the async worker.
+///
+/// The aggregate remembers the server, so its tools are replaced when it later sends
+/// `notifications/tools/list_changed` (see [`super::AggregateToolSource::refresh_mcp_tools`]).
 pub async fn register_mcp_tools_with_specs(
     aggregate: &super::AggregateToolSource,
     mcp: Arc<McpToolSource>,
     specs: Vec<ToolSpec>,
 ) {
-    for spec in specs {
-        let name = spec.name.clone();
-        let adapter = McpToolAdapter::new(name, spec, Arc::clone(&mcp));
-        aggregate.register_async(Box::new(adapter)).await;
-    }
+    aggregate.register_mcp(mcp, specs).await;
 }
 
 #[cfg(test)]
@@ -224,4 +223,72 @@ mod tests {
 
         server.await.unwrap();
     }
+
+    #[tokio::test]
+    async fn refresh_mcp_tools_replaces_tools_after_list_changed_notification() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let mut lists = 0;
+            for _ in 0..5 {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let body = read_http_request(&mut stream).await;
+                let json: serde_json::Value =
+                    serde_json::from_str(&body).unwrap_or(serde_json::Value::Null);
+                let method = json.get("method").and_then(|m| m.as_str()).unwrap_or("");
+                match method {
+                    "initialize" | "notifications/initialized" => {
+                        write_http_response(&mut stream, "202 Accepted", None, "").await;
+                    }
+                    "tools/list" => {
+                        lists += 1;
+                        let name = if lists == 1 { "old_tool" } else { "new_tool" };
+                        let body = serde_json::json!({
+                            "jsonrpc":"2.0",
+                            "id":"loom-tools-list",
+                            "result":{"tools":[{"name":name,"inputSchema":{"type":"object"}}]}
+                        })
+                        .to_string();
+                        write_http_response(&mut stream, "200 OK", Some("application/json"), &body)
+                            .await;
+                    }
+                    "tools/call" => {
+                        let sse = concat!(
+                            "data: {\"jsonrpc\":\"2.0\",\"method\":\"notifications/tools/list_changed\"}\n\n",
+                            "data: {\"jsonrpc\":\"2.0\",\"id\":\"loom-call-old_tool\",",
+                            "\"result\":{\"content\":[{\"type\":\"text\",\"text\":\"ok\"}]}}\n\n"
+                        );
+                        write_http_response(&mut stream, "200 OK", Some("text/event-stream"), sse)
+                            .await;
+                    }
+                    _ => panic!("unexpected method: {}", method),
+                }
+            }
+        });
+
+        let mcp = McpToolSource::new_http(
+            format!("http://{}", addr),
+            std::iter::empty::<(String, String)>(),
+        )
+        .await
+        .unwrap();
+        let aggregate = AggregateToolSource::new();
+        register_mcp_tools(&aggregate, Arc::new(mcp)).await.unwrap();
+        assert!(aggregate.refresh_tools().await.unwrap().is_none());
+
+        aggregate
+            .call_tool("old_tool", serde_json::json!({}))
+            .await
+            .unwrap();
+        let tools = aggregate
+            .refresh_tools()
+            .await
+            .unwrap()
+            .expect("tool list changed");
+        let names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["new_tool"]);
+        assert!(aggregate.refresh_tools().await.unwrap().is_none());
+
+        server.await.unwrap();
+    }
 }
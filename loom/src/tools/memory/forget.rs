@@ -0,0 +1,144 @@
+use async_trait::async_trait;
+
+use serde_json::json;
+
+use crate::memory::{Namespace, Store};
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError};
+use crate::tools::Tool;
+
+/// Tool name for the forget operation.
+pub const TOOL_FORGET: &str = "forget";
+
+/// Tool for deleting memories by key from long-term memory.
+///
+/// Wraps Store::delete_many() and exposes it as a tool for LLM.
+/// Accepts a single `key`, a `keys` array, or both; unknown keys are ignored.
+///
+/// # Examples
+///
+/// ```no_run
+/// use loom::tools::{ForgetTool, RecallTool, RememberTool, Tool};
+/// use loom::memory::{InMemoryStore, Namespace};
+/// use std::sync::Arc;
+/// use serde_json::json;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let store = Arc::new(InMemoryStore::new());
+/// let namespace = vec!["user-123".to_string()];
+///
+/// let remember = RememberTool::new(store.clone(), namespace.clone());
+/// remember.call(json!({"key": "coffee", "value": "likes coffee"}), None).await.unwrap();
+///
+/// let forget = ForgetTool::new(store.clone(), namespace.clone());
+/// forget.call(json!({"key": "coffee"}), None).await.unwrap();
+///
+/// let recall = RecallTool::new(store, namespace);
+/// assert!(recall.call(json!({"key": "coffee"}), None).await.is_err());
+/// # }
+/// ```
+///
+/// # Interaction
+///
+/// - **Store**: Deletes keys via Store::delete_many()
+/// - **Namespace**: Isolates storage per user/context
+/// - **ToolRegistry**: Registers this tool by name "forget"
+/// - **StoreToolSource**: Uses this tool via AggregateToolSource
+pub struct ForgetTool {
+    store: std::sync::Arc<dyn Store>,
+    namespace: Namespace,
+}
+
+impl ForgetTool {
+    /// Creates a new ForgetTool with the given store and namespace.
+    ///
+    /// # Parameters
+    ///
+    /// - `store`: Arc<dyn Store> for deleting key-value pairs
+    /// - `namespace`: Namespace to isolate storage (e.g., [user_id])
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use loom::tools::memory::ForgetTool;
+    /// use loom::memory::{InMemoryStore, Namespace};
+    /// use std::sync::Arc;
+    ///
+    /// let store = Arc::new(InMemoryStore::new());
+    /// let namespace = vec!["user-123".to_string()];
+    /// let tool = ForgetTool::new(store, namespace);
+    /// ```
+    pub fn new(store: std::sync::Arc<dyn Store>, namespace: Namespace) -> Self {
+        Self { store, namespace }
+    }
+}
+
+#[async_trait]
+impl Tool for ForgetTool {
+    fn name(&self) -> &str {
+        TOOL_FORGET
+    }
+
+    fn spec(&self) -> crate::tool_source::ToolSpec {
+        crate::tool_source::ToolSpec {
+            name: TOOL_FORGET.to_string(),
+            description: Some(
+                "Delete memories by key from long-term memory. Call when: the user asks to forget \
+                 something, or a stored memory is outdated or wrong. Use list_memories first if \
+                 unsure of the exact keys."
+                    .to_string(),
+            ),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "key": { "type": "string", "description": "Memory key to delete" },
+                    "keys": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Several memory keys to delete at once"
+                    }
+                }
+            }),
+            output_hint: None,
+            catalog: Default::default(),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: serde_json::Value,
+        _ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let mut keys: Vec<String> = args
+            .get("keys")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if let Some(key) = args.get("key").and_then(|v| v.as_str()) {
+            keys.push(key.to_string());
+        }
+        if keys.is_empty() {
+            return Err(ToolSourceError::InvalidInput(
+                "missing key or keys".to_string(),
+            ));
+        }
+
+        self.store
+            .delete_many(&self.namespace, &keys)
+            .await
+            .map_err(|e| match e {
+                crate::memory::StoreError::NotFound => {
+                    ToolSourceError::NotFound("key not found".to_string())
+                }
+                crate::memory::StoreError::Serialization(s) => ToolSourceError::InvalidInput(s),
+                crate::memory::StoreError::Storage(s) => ToolSourceError::Transport(s),
+                crate::memory::StoreError::EmbeddingError(s) => ToolSourceError::Transport(s),
+            })?;
+
+        Ok(ToolCallContent::text("ok".to_string()))
+    }
+}
@@ -9,7 +9,7 @@ use crate::tools::Tool;
 /// Tool name for the list_memories operation.
 pub const TOOL_LIST_MEMORIES: &str = "list_memories";
 
-/// Tool for listing all memory keys in the current namespace.
+/// Tool for listing memory keys in the current namespace, optionally only those with a given prefix.
 ///
 /// Wraps Store::list() and exposes it as a tool for LLM.
 /// Interacts with Store and Namespace to enumerate stored keys in a fixed namespace.
@@ -83,24 +83,29 @@ impl Tool for ListMemoriesTool {
         crate::tool_source::ToolSpec {
             name: TOOL_LIST_MEMORIES.to_string(),
             description: Some(
-                "List all memory keys in the current namespace. Call when you need to see what \
-                 has been stored before recalling or searching."
+                "List memory keys in the current namespace. Call when you need to see what \
+                 has been stored before recalling, searching or forgetting. Pass prefix to list \
+                 only keys that start with it (e.g. \"project:\")."
                     .to_string(),
             ),
             input_schema: json!({
                 "type": "object",
-                "properties": {}
+                "properties": {
+                    "prefix": { "type": "string", "description": "Only list keys starting with this prefix" }
+                }
             }),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
     async fn call(
         &self,
-        _args: serde_json::Value,
+        args: serde_json::Value,
         _ctx: Option<&ToolCallContext>,
     ) -> Result<ToolCallContent, ToolSourceError> {
-        let keys = self
+        let prefix = args.get("prefix").and_then(|v| v.as_str()).unwrap_or("");
+        let mut keys = self
             .store
             .list(&self.namespace)
             .await
@@ -112,6 +117,7 @@ impl Tool for ListMemoriesTool {
                 crate::memory::StoreError::Storage(s) => ToolSourceError::Transport(s),
                 crate::memory::StoreError::EmbeddingError(s) => ToolSourceError::Transport(s),
             })?;
+        keys.retain(|k| k.starts_with(prefix));
 
         Ok(ToolCallContent::text(
             serde_json::to_string(&keys)
@@ -1,8 +1,10 @@
+mod forget;
 mod list_memories;
 mod recall;
 mod remember;
 mod search_memories;
 
+pub use forget::{ForgetTool, TOOL_FORGET};
 pub use list_memories::{ListMemoriesTool, TOOL_LIST_MEMORIES};
 pub use recall::{RecallTool, TOOL_RECALL};
 pub use remember::{RememberTool, TOOL_REMEMBER};
@@ -92,6 +92,7 @@ impl Tool for RecallTool {
                 "required": ["key"]
             }),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
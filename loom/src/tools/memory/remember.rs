@@ -11,7 +11,8 @@ pub const TOOL_REMEMBER: &str = "remember";
 
 /// Tool for writing key-value pairs to long-term memory.
 ///
-/// Wraps Store::put() and exposes it as a tool for the LLM.
+/// Wraps Store::put_with_ttl() and exposes it as a tool for the LLM. An optional
+/// `ttl_seconds` makes the memory expire; without it the memory is kept until forgotten.
 /// Interacts with Store and Namespace to persist data in a fixed namespace.
 ///
 /// # Examples
@@ -39,7 +40,7 @@ pub const TOOL_REMEMBER: &str = "remember";
 ///
 /// # Interaction
 ///
-/// - **Store**: Stores key-value pairs via Store::put()
+/// - **Store**: Stores key-value pairs via Store::put_with_ttl()
 /// - **Namespace**: Isolates storage per user/context
 /// - **ToolRegistry**: Registers this tool by name "remember"
 /// - **StoreToolSource**: Uses this tool via AggregateToolSource
@@ -89,11 +90,17 @@ impl Tool for RememberTool {
                 "type": "object",
                 "properties": {
                     "key": { "type": "string", "description": "Memory key" },
-                    "value": { "description": "Value (any JSON)" }
+                    "value": { "description": "Value (any JSON)" },
+                    "ttl_seconds": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Optional lifetime in seconds; omit to keep the memory until forgotten"
+                    }
                 },
                 "required": ["key", "value"]
             }),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
@@ -110,9 +117,20 @@ impl Tool for RememberTool {
             .get("value")
             .cloned()
             .unwrap_or(serde_json::Value::Null);
+        let ttl = match args.get("ttl_seconds") {
+            None | Some(serde_json::Value::Null) => None,
+            Some(v) => match v.as_u64() {
+                Some(secs) if secs > 0 => Some(std::time::Duration::from_secs(secs)),
+                _ => {
+                    return Err(ToolSourceError::InvalidInput(
+                        "ttl_seconds must be a positive integer".to_string(),
+                    ))
+                }
+            },
+        };
 
         self.store
-            .put(&self.namespace, key, &value)
+            .put_with_ttl(&self.namespace, key, &value, ttl)
             .await
             .map_err(|e| match e {
                 crate::memory::StoreError::NotFound => {
@@ -93,6 +93,7 @@ impl Tool for SearchMemoriesTool {
                 }
             }),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
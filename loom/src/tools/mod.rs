@@ -8,19 +8,30 @@ pub mod invoke_agent;
 mod lsp;
 mod mcp_adapter;
 pub mod memory;
+mod observation;
 pub mod powershell;
+#[cfg(feature = "python")]
+pub mod python;
 mod registry;
+#[cfg(feature = "screenshot")]
+pub mod screenshot;
 pub mod skill;
+#[cfg(feature = "ssh")]
+pub mod ssh;
 pub mod telegram;
 pub mod todo;
 mod r#trait;
 pub mod twitter;
 pub mod web;
 
-pub use aggregate_source::AggregateToolSource;
-pub use bash::{BashTool, TOOL_BASH};
+pub use aggregate_source::{AggregateToolSource, MCP_CONNECT_TIMEOUT};
+pub use bash::{BashOutputFormat, BashTool, TOOL_BASH};
+pub(crate) use batch::batch_sub_calls;
 pub use batch::{BatchTool, TOOL_BATCH};
-pub use conversation::{GetRecentMessagesTool, TOOL_GET_RECENT_MESSAGES};
+pub use conversation::{
+    GetRecentMessagesTool, HistorySearch, SearchHistoryTool, TOOL_GET_RECENT_MESSAGES,
+    TOOL_SEARCH_HISTORY,
+};
 pub use exa::{ExaCodesearchTool, ExaWebsearchTool};
 pub use file::{
     ApplyPatchTool, CreateDirTool, DeleteFileTool, EditFileTool, GlobTool, GrepTool, LsTool,
@@ -30,12 +41,22 @@ pub use file::{
 };
 pub use lsp::{LspTool, TOOL_LSP};
 pub use memory::{
-    ListMemoriesTool, RecallTool, RememberTool, SearchMemoriesTool, TOOL_LIST_MEMORIES,
-    TOOL_RECALL, TOOL_REMEMBER, TOOL_SEARCH_MEMORIES,
+    ForgetTool, ListMemoriesTool, RecallTool, RememberTool, SearchMemoriesTool, TOOL_FORGET,
+    TOOL_LIST_MEMORIES, TOOL_RECALL, TOOL_REMEMBER, TOOL_SEARCH_MEMORIES,
 };
+pub use observation::{raw_observation_path, RawObservationTool, TOOL_GET_RAW_OBSERVATION};
+#[cfg(feature = "python")]
+pub use python::{PythonSandbox, PythonTool, TOOL_PYTHON};
 pub use r#trait::Tool;
 pub use registry::{ToolRegistry, ToolRegistryLocked};
+#[cfg(feature = "screenshot")]
+pub use screenshot::{BrowserConfig, ScreenshotTool, TOOL_SCREENSHOT};
 pub use skill::{SkillTool, TOOL_SKILL};
+#[cfg(feature = "ssh")]
+pub use ssh::{
+    ScpGetTool, ScpPutTool, SshExecTool, SshHost, SshHosts, TOOL_SCP_GET, TOOL_SCP_PUT,
+    TOOL_SSH_EXEC,
+};
 pub use telegram::{
     set_current_chat_id, set_telegram_api, TelegramApi, TelegramSendDocumentTool,
     TelegramSendMessageTool, TelegramSendPollTool, TOOL_TELEGRAM_SEND_DOCUMENT,
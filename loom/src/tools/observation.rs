@@ -0,0 +1,149 @@
+//! `get_raw_observation` tool: reads the raw output of a tool call whose result was replaced by
+//! a summary (see [`crate::ObservationSummarizer`]), a page at a time.
+//!
+//! Raw outputs are files named after the call id in the summarizer's directory.
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use crate::tool_source::{
+    ToolCallContent, ToolCallContext, ToolCatalogInfo, ToolDanger, ToolSourceError,
+};
+use crate::tools::Tool;
+use crate::{ToolOutputHint, ToolOutputStrategy};
+
+/// Tool name for the get_raw_observation operation.
+pub const TOOL_GET_RAW_OBSERVATION: &str = "get_raw_observation";
+
+const DEFAULT_LIMIT: usize = 4_000;
+const MAX_LIMIT: usize = 8_000;
+
+/// File holding the raw output of `call_id` in `dir`.
+pub fn raw_observation_path(dir: &Path, call_id: &str) -> PathBuf {
+    let name: String = call_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    dir.join(format!("{}.txt", name))
+}
+
+/// Tool that pages through raw tool outputs saved by the observation summarizer.
+///
+/// # Interaction
+///
+/// - **ObservationSummarizer**: Writes the files this tool reads
+/// - **build_tool_source**: Registers this tool when
+///   `ReactBuildConfig::observation_summary_tokens` is set
+pub struct RawObservationTool {
+    dir: PathBuf,
+}
+
+impl RawObservationTool {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+}
+
+#[async_trait]
+impl Tool for RawObservationTool {
+    fn name(&self) -> &str {
+        TOOL_GET_RAW_OBSERVATION
+    }
+
+    fn spec(&self) -> crate::tool_source::ToolSpec {
+        crate::tool_source::ToolSpec {
+            name: TOOL_GET_RAW_OBSERVATION.to_string(),
+            description: Some(
+                "Read the raw output of an earlier tool call whose result was summarized. Call \
+                 when the summary lacks a detail you need; page with offset until `remaining` \
+                 is 0."
+                    .to_string(),
+            ),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "call_id": { "type": "string", "description": "Call id named in the summary" },
+                    "offset": { "type": "integer", "description": "First character to return (optional, default 0)" },
+                    "limit": { "type": "integer", "description": "Max characters (optional, default 4000, max 8000)" }
+                },
+                "required": ["call_id"]
+            }),
+            output_hint: Some(
+                ToolOutputHint::preferred(ToolOutputStrategy::Inline).safe_inline_chars(MAX_LIMIT),
+            ),
+            catalog: ToolCatalogInfo::default()
+                .with_category("meta")
+                .with_icon("file-search")
+                .with_danger(ToolDanger::Safe),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: Value,
+        _ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let call_id = args
+            .get("call_id")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.trim().is_empty())
+            .ok_or_else(|| ToolSourceError::InvalidInput("missing call_id".to_string()))?;
+        let offset = args.get("offset").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+        let limit = args
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map_or(DEFAULT_LIMIT, |n| (n as usize).clamp(1, MAX_LIMIT));
+
+        let path = raw_observation_path(&self.dir, call_id.trim());
+        let raw = tokio::fs::read_to_string(&path).await.map_err(|_| {
+            ToolSourceError::InvalidInput(format!("no raw output saved for call {}", call_id))
+        })?;
+        let total = raw.chars().count();
+        let content: String = raw.chars().skip(offset).take(limit).collect();
+        let end = (offset + content.chars().count()).min(total);
+        let text = format!(
+            "[characters {}..{} of {}, remaining {}]\n{}",
+            offset.min(total),
+            end,
+            total,
+            total - end,
+            content
+        );
+        Ok(ToolCallContent::text(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn pages_through_saved_output() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(raw_observation_path(dir.path(), "call/1"), "abcdefghij").unwrap();
+        let tool = RawObservationTool::new(dir.path().to_path_buf());
+
+        let out = tool
+            .call(json!({"call_id": "call/1", "offset": 2, "limit": 5}), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            out.as_text().unwrap(),
+            "[characters 2..7 of 10, remaining 3]\ncdefg"
+        );
+
+        let out = tool.call(json!({"call_id": "call/1"}), None).await.unwrap();
+        assert!(out.as_text().unwrap().ends_with("remaining 0]\nabcdefghij"));
+
+        assert!(tool.call(json!({"call_id": "other"}), None).await.is_err());
+        assert!(tool.call(json!({}), None).await.is_err());
+    }
+}
@@ -132,6 +132,7 @@ impl Tool for PowerShellTool {
             output_hint: Some(
                 ToolOutputHint::preferred(ToolOutputStrategy::HeadTail).prefer_head_tail(),
             ),
+            catalog: Default::default(),
         }
     }
 
@@ -0,0 +1,400 @@
+//! Python tool: run a snippet in a separate, resource-limited interpreter (feature `python`).
+//!
+//! Each call writes the snippet to `main.py` in a fresh temp directory and runs it there with
+//! `python3 -I` (isolated mode: no user site-packages, `PYTHON*` variables ignored) and a
+//! cleared environment. A small runner sets memory, CPU-time and file-size limits (Unix
+//! `setrlimit`) and, unless [`PythonSandbox::allow_network`] is set, makes IP sockets fail.
+//! This keeps well-meaning analysis code contained; it is not a security boundary against
+//! hostile code (use a container for that).
+//!
+//! The result lists files the snippet created in its directory, so the model can read or
+//! hand on plots and exports. The directories of the last [`PythonSandbox::keep_dirs`] calls
+//! are kept; older ones are removed when a call starts.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use async_trait::async_trait;
+use serde_json::json;
+
+use super::bash::run_spawned_shell_command;
+use crate::tool_source::{
+    ToolCallContent, ToolCallContext, ToolCatalogInfo, ToolDanger, ToolSourceError, ToolSpec,
+};
+use crate::tools::Tool;
+use crate::{ToolOutputHint, ToolOutputStrategy};
+
+/// Tool name for running Python code.
+pub const TOOL_PYTHON: &str = "python";
+
+/// Runs `main.py` under the limits given as arguments; see the script header.
+const SANDBOX_RUNNER: &str = include_str!("sandbox.py");
+const MAIN_FILE: &str = "main.py";
+/// Name prefix of the per-call directories.
+const WORKDIR_PREFIX: &str = "loom-python-";
+/// Most generated files listed in one result.
+const MAX_LISTED_FILES: usize = 100;
+
+/// Interpreter and limits for [`PythonTool`].
+#[derive(Clone, Debug)]
+pub struct PythonSandbox {
+    /// Interpreter to run (default `python3`; `python` on Windows).
+    pub interpreter: PathBuf,
+    /// Wall-clock timeout per call in milliseconds; a call's `timeout` may only lower it.
+    pub timeout_ms: u64,
+    /// Address space limit in MiB (0 = unlimited).
+    pub memory_mb: u64,
+    /// CPU time limit in seconds (0 = unlimited).
+    pub cpu_seconds: u64,
+    /// Largest file the snippet may write, in MiB (0 = unlimited).
+    pub max_file_mb: u64,
+    /// When false (default), IP sockets fail with `PermissionError`.
+    pub allow_network: bool,
+    /// Parent of the per-call directories (default: the system temp directory).
+    pub temp_root: PathBuf,
+    /// Per-call directories kept in `temp_root` (default 20); `0` removes each one when its
+    /// call ends, and the result then lists no files.
+    pub keep_dirs: usize,
+}
+
+impl Default for PythonSandbox {
+    fn default() -> Self {
+        Self {
+            interpreter: PathBuf::from(if cfg!(windows) { "python" } else { "python3" }),
+            timeout_ms: 60_000,
+            memory_mb: 2048,
+            cpu_seconds: 60,
+            max_file_mb: 100,
+            allow_network: false,
+            temp_root: std::env::temp_dir(),
+            keep_dirs: 20,
+        }
+    }
+}
+
+impl PythonSandbox {
+    /// Defaults, overridden by `LOOM_PYTHON_INTERPRETER`, `LOOM_PYTHON_MEMORY_MB`,
+    /// `LOOM_PYTHON_KEEP_DIRS` and `LOOM_PYTHON_NETWORK` (`1`/`true` allows network access).
+    pub fn from_env() -> Self {
+        let mut sandbox = Self::default();
+        if let Ok(path) = std::env::var("LOOM_PYTHON_INTERPRETER") {
+            if !path.trim().is_empty() {
+                sandbox.interpreter = PathBuf::from(path.trim());
+            }
+        }
+        if let Some(mb) = std::env::var("LOOM_PYTHON_MEMORY_MB")
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+        {
+            sandbox.memory_mb = mb;
+        }
+        if let Some(keep) = std::env::var("LOOM_PYTHON_KEEP_DIRS")
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+        {
+            sandbox.keep_dirs = keep;
+        }
+        sandbox.allow_network = std::env::var("LOOM_PYTHON_NETWORK")
+            .ok()
+            .map(|s| matches!(s.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        sandbox
+    }
+
+    fn runner_args(&self) -> [String; 4] {
+        const MIB: u64 = 1024 * 1024;
+        [
+            self.memory_mb.saturating_mul(MIB).to_string(),
+            self.cpu_seconds.to_string(),
+            self.max_file_mb.saturating_mul(MIB).to_string(),
+            u8::from(self.allow_network).to_string(),
+        ]
+    }
+
+    /// Removes the oldest per-call directories in `temp_root` so that, with the one about to be
+    /// created, at most `keep_dirs` remain. Directories that cannot be read or removed are left.
+    fn prune_workdirs(&self) {
+        let Ok(entries) = std::fs::read_dir(&self.temp_root) else {
+            return;
+        };
+        let mut dirs: Vec<(std::time::SystemTime, PathBuf)> = entries
+            .filter_map(Result::ok)
+            .filter(|e| e.file_name().to_string_lossy().starts_with(WORKDIR_PREFIX))
+            .filter_map(|e| {
+                let meta = e.metadata().ok().filter(|m| m.is_dir())?;
+                Some((meta.modified().ok()?, e.path()))
+            })
+            .collect();
+        let keep = self.keep_dirs.saturating_sub(1);
+        if dirs.len() <= keep {
+            return;
+        }
+        dirs.sort();
+        let excess = dirs.len() - keep;
+        for (_, dir) in dirs.into_iter().take(excess) {
+            if let Err(e) = std::fs::remove_dir_all(&dir) {
+                tracing::debug!("python: cannot remove {}: {}", dir.display(), e);
+            }
+        }
+    }
+
+    /// Creates a new, empty directory for one call.
+    fn create_workdir(&self) -> Result<PathBuf, ToolSourceError> {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or(0);
+        let dir = self.temp_root.join(format!(
+            "{}{}-{}-{}",
+            WORKDIR_PREFIX,
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed),
+            nanos
+        ));
+        std::fs::create_dir_all(&dir).map_err(|e| {
+            ToolSourceError::Transport(format!("failed to create {}: {}", dir.display(), e))
+        })?;
+        Ok(dir)
+    }
+}
+
+/// Tool that runs a Python snippet in a [`PythonSandbox`] and returns its output.
+///
+/// The result is a JSON object: `exit_code`, `stdout`, `stderr`, `duration_ms`, `timed_out`,
+/// plus `workdir` and `files` (absolute paths of files the snippet created).
+pub struct PythonTool {
+    sandbox: PythonSandbox,
+}
+
+impl Default for PythonTool {
+    fn default() -> Self {
+        Self::new(PythonSandbox::from_env())
+    }
+}
+
+impl PythonTool {
+    pub fn new(sandbox: PythonSandbox) -> Self {
+        Self { sandbox }
+    }
+}
+
+/// Files under `dir` other than the snippet itself, sorted, at most [`MAX_LISTED_FILES`].
+fn generated_files(dir: &Path) -> Vec<String> {
+    let mut files: Vec<String> = walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file() && e.path() != dir.join(MAIN_FILE))
+        .map(|e| e.path().to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    files.truncate(MAX_LISTED_FILES);
+    files
+}
+
+#[async_trait]
+impl Tool for PythonTool {
+    fn name(&self) -> &str {
+        TOOL_PYTHON
+    }
+
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: TOOL_PYTHON.to_string(),
+            description: Some(format!(
+                "Runs a Python 3 snippet in a fresh sandboxed interpreter and returns JSON with \
+                 exit_code, stdout, stderr, timed_out, the run's working directory (workdir) and \
+                 the files the snippet wrote there (files). Use for data analysis, calculations \
+                 and plots instead of python one-liners in bash; print results you need. Each \
+                 call starts with empty state. Limits: {} MiB memory, {} s CPU{}. Write output \
+                 files to the current directory.",
+                self.sandbox.memory_mb,
+                self.sandbox.cpu_seconds,
+                if self.sandbox.allow_network {
+                    ""
+                } else {
+                    ", no network access"
+                }
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "code": {
+                        "type": "string",
+                        "description": "Python source to run as a script."
+                    },
+                    "timeout": {
+                        "type": "integer",
+                        "description": format!(
+                            "Timeout in milliseconds (default and maximum {}).",
+                            self.sandbox.timeout_ms
+                        )
+                    }
+                },
+                "required": ["code"]
+            }),
+            output_hint: Some(
+                ToolOutputHint::preferred(ToolOutputStrategy::HeadTail).prefer_head_tail(),
+            ),
+            catalog: ToolCatalogInfo::default()
+                .with_category("shell")
+                .with_icon("code")
+                .with_danger(ToolDanger::Destructive),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: serde_json::Value,
+        ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let code = args
+            .get("code")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolSourceError::InvalidInput("missing code".to_string()))?;
+        // A call may shorten the sandbox timeout but not extend or disable it (`0` is no limit).
+        let max = self.sandbox.timeout_ms;
+        let timeout_ms = match args.get("timeout").and_then(|v| v.as_u64()) {
+            Some(t) if t > 0 && (max == 0 || t < max) => t,
+            _ => max,
+        };
+
+        self.sandbox.prune_workdirs();
+        let workdir = self.sandbox.create_workdir()?;
+        std::fs::write(workdir.join(MAIN_FILE), code).map_err(|e| {
+            ToolSourceError::Transport(format!("failed to write {}: {}", MAIN_FILE, e))
+        })?;
+
+        let mut cmd = tokio::process::Command::new(&self.sandbox.interpreter);
+        cmd.args(["-I", "-X", "utf8", "-c", SANDBOX_RUNNER])
+            .args(self.sandbox.runner_args())
+            .current_dir(&workdir)
+            .env_clear()
+            .env("HOME", &workdir)
+            .env("TMPDIR", &workdir)
+            .env("MPLBACKEND", "Agg")
+            // Thread pools reserve address space per thread, which the memory limit counts.
+            .env("OPENBLAS_NUM_THREADS", "1")
+            .env("OMP_NUM_THREADS", "1")
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped());
+        // PATH to find the interpreter; Windows needs SYSTEMROOT for it to start.
+        for key in ["PATH", "SYSTEMROOT"] {
+            if let Some(value) = std::env::var_os(key) {
+                cmd.env(key, value);
+            }
+        }
+        let output = run_spawned_shell_command(cmd, timeout_ms, ctx).await;
+        if self.sandbox.keep_dirs == 0 {
+            let _ = std::fs::remove_dir_all(&workdir);
+        }
+        let mut result = output?.to_json();
+        if self.sandbox.keep_dirs > 0 {
+            result["workdir"] = json!(workdir.to_string_lossy());
+            result["files"] = json!(generated_files(&workdir));
+        }
+        Ok(ToolCallContent::text(result.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(temp_root: &Path) -> Option<PythonTool> {
+        let interpreter = which::which("python3").ok()?;
+        Some(PythonTool::new(PythonSandbox {
+            interpreter,
+            temp_root: temp_root.to_path_buf(),
+            ..PythonSandbox::default()
+        }))
+    }
+
+    async fn run(tool: &PythonTool, code: &str) -> serde_json::Value {
+        let out = tool.call(json!({ "code": code }), None).await.unwrap();
+        serde_json::from_str(out.as_text().unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn runs_code_and_lists_generated_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let Some(tool) = tool(temp.path()) else {
+            return;
+        };
+        let out = run(
+            &tool,
+            "import os\nprint(sum(range(10)))\nopen('out.csv', 'w').write('a,b\\n')\nprint(os.environ.get('OPENAI_API_KEY'))",
+        )
+        .await;
+        assert_eq!(out["exit_code"], 0, "{out}");
+        assert_eq!(out["stdout"], "45\nNone\n");
+        let files = out["files"].as_array().unwrap();
+        assert_eq!(files.len(), 1);
+        assert!(files[0].as_str().unwrap().ends_with("out.csv"));
+        assert!(Path::new(files[0].as_str().unwrap()).exists());
+    }
+
+    #[tokio::test]
+    async fn blocks_network_and_reports_errors() {
+        let temp = tempfile::tempdir().unwrap();
+        let Some(tool) = tool(temp.path()) else {
+            return;
+        };
+        let out = run(
+            &tool,
+            "import socket\nsocket.create_connection(('example.com', 80))",
+        )
+        .await;
+        assert_ne!(out["exit_code"], 0);
+        assert!(out["stderr"]
+            .as_str()
+            .unwrap()
+            .contains("network access is disabled"));
+
+        assert!(tool.call(json!({}), None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn keeps_only_the_latest_workdirs() {
+        let temp = tempfile::tempdir().unwrap();
+        let Some(mut tool) = tool(temp.path()) else {
+            return;
+        };
+        tool.sandbox.keep_dirs = 2;
+        let mut workdirs = Vec::new();
+        for _ in 0..3 {
+            let out = run(&tool, "print(1)").await;
+            workdirs.push(PathBuf::from(out["workdir"].as_str().unwrap()));
+            // Distinct modification times, so the oldest directory is well defined.
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert!(!workdirs[0].exists());
+        assert!(workdirs[1].exists() && workdirs[2].exists());
+
+        tool.sandbox.keep_dirs = 0;
+        let out = run(&tool, "open('x.txt', 'w').write('x')").await;
+        assert_eq!(out["exit_code"], 0, "{out}");
+        assert!(out.get("workdir").is_none());
+        let left = std::fs::read_dir(temp.path()).unwrap().count();
+        assert_eq!(left, 0);
+    }
+
+    #[tokio::test]
+    async fn call_timeout_cannot_exceed_the_sandbox_timeout() {
+        let temp = tempfile::tempdir().unwrap();
+        let Some(mut tool) = tool(temp.path()) else {
+            return;
+        };
+        tool.sandbox.timeout_ms = 300;
+        let out = tool
+            .call(
+                json!({ "code": "import time\ntime.sleep(5)", "timeout": 600_000 }),
+                None,
+            )
+            .await
+            .unwrap();
+        let out: serde_json::Value = serde_json::from_str(out.as_text().unwrap()).unwrap();
+        assert_eq!(out["timed_out"], true, "{out}");
+    }
+}
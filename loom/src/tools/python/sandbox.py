@@ -0,0 +1,44 @@
+# Runs main.py from the current directory under the limits passed on the command line:
+#   sandbox.py <memory_bytes> <cpu_seconds> <file_size_bytes> <allow_network 0|1>
+# A limit of 0 leaves that resource unlimited. Limits need the `resource` module (Unix).
+import sys
+
+memory, cpu, fsize, network = (int(a) for a in sys.argv[1:5])
+
+try:
+    import resource
+
+    for res, value in (
+        (resource.RLIMIT_AS, memory),
+        (resource.RLIMIT_CPU, cpu),
+        (resource.RLIMIT_FSIZE, fsize),
+    ):
+        if value:
+            try:
+                resource.setrlimit(res, (value, value))
+            except (ValueError, OSError):
+                pass
+except ImportError:
+    pass
+
+if not network:
+    import socket
+
+    def _denied(*args, **kwargs):
+        raise PermissionError("network access is disabled in the python sandbox")
+
+    class _LocalOnlySocket(socket.socket):
+        def __init__(self, family=socket.AF_INET, *args, **kwargs):
+            if family in (socket.AF_INET, socket.AF_INET6):
+                _denied()
+            super().__init__(family, *args, **kwargs)
+
+    socket.socket = _LocalOnlySocket
+    socket.create_connection = _denied
+    socket.getaddrinfo = _denied
+
+del sys.argv[1:]
+sys.argv[0] = "main.py"
+with open("main.py", encoding="utf-8") as f:
+    source = f.read()
+exec(compile(source, "main.py", "exec"), {"__name__": "__main__", "__file__": "main.py"})
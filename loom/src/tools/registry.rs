@@ -86,6 +86,11 @@ impl ToolRegistry {
         self.tools.insert(name, tool);
     }
 
+    /// Removes the tool with the given name; returns it when it was registered.
+    pub fn unregister(&mut self, name: &str) -> Option<Box<dyn Tool>> {
+        self.tools.remove(name)
+    }
+
     /// Lists all registered tools as ToolSpec objects.
     ///
     /// Returns a vector of tool specifications that can be sent to the LLM.
@@ -291,6 +296,12 @@ impl ToolRegistryLocked {
         .expect("Failed to join registration thread");
     }
 
+    /// Removes the tool with the given name; returns whether it was registered.
+    pub async fn unregister(&self, name: &str) -> bool {
+        let mut inner = self.inner.write().await;
+        inner.unregister(name).is_some()
+    }
+
     /// Lists all registered tools as ToolSpec objects.
     ///
     /// This method acquires a read lock on the inner registry.
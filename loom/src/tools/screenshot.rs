@@ -0,0 +1,365 @@
+//! Screenshot tool: render a web page or an HTML file of the working folder to a PNG
+//! (feature `screenshot`).
+//!
+//! Each call runs a headless Chrome/Chromium (`--headless --screenshot`) with a fresh,
+//! temporary profile and returns the image as [`ToolCallContent::Image`]. The browser is
+//! `LOOM_BROWSER` when set, else the first Chrome/Chromium found on `PATH` (or in the usual
+//! macOS location). Local paths are resolved under the working folder like the file tools', so
+//! the model can check how a page it just edited renders.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use base64::Engine;
+use serde_json::json;
+
+use super::bash::run_spawned_shell_command;
+use super::file::resolve_path_under;
+use crate::tool_source::{
+    ToolCallContent, ToolCallContext, ToolCatalogInfo, ToolDanger, ToolSourceError, ToolSpec,
+};
+use crate::tools::Tool;
+
+/// Tool name for taking a screenshot of a page.
+pub const TOOL_SCREENSHOT: &str = "screenshot";
+
+const DEFAULT_WIDTH: u64 = 1280;
+const DEFAULT_HEIGHT: u64 = 800;
+/// Largest viewport side accepted, in pixels.
+const MAX_DIMENSION: u64 = 4096;
+/// Default time the page may run scripts before the capture, in milliseconds.
+const DEFAULT_WAIT_MS: u64 = 1000;
+const SCREENSHOT_FILE: &str = "screenshot.png";
+
+/// Browser binaries tried in order when `LOOM_BROWSER` is not set.
+const BROWSER_CANDIDATES: &[&str] = &[
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "google-chrome-stable",
+    "chrome",
+    "msedge",
+];
+const MACOS_CHROME: &str = "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome";
+
+/// Headless browser and limits for [`ScreenshotTool`].
+#[derive(Clone, Debug)]
+pub struct BrowserConfig {
+    /// Browser binary; `None` looks one up on each call.
+    pub browser: Option<PathBuf>,
+    /// Wall-clock timeout per call in milliseconds.
+    pub timeout_ms: u64,
+    /// Pass `--no-sandbox` (needed when running as root, e.g. in containers).
+    pub no_sandbox: bool,
+    /// Parent of the per-call profile directories (default: the system temp directory).
+    pub temp_root: PathBuf,
+}
+
+impl Default for BrowserConfig {
+    fn default() -> Self {
+        Self {
+            browser: None,
+            timeout_ms: 30_000,
+            no_sandbox: false,
+            temp_root: std::env::temp_dir(),
+        }
+    }
+}
+
+impl BrowserConfig {
+    /// Defaults, overridden by `LOOM_BROWSER` (browser binary) and `LOOM_BROWSER_NO_SANDBOX`
+    /// (`1`/`true` passes `--no-sandbox`).
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Ok(path) = std::env::var("LOOM_BROWSER") {
+            if !path.trim().is_empty() {
+                config.browser = Some(PathBuf::from(path.trim()));
+            }
+        }
+        config.no_sandbox = std::env::var("LOOM_BROWSER_NO_SANDBOX")
+            .ok()
+            .map(|s| matches!(s.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        config
+    }
+
+    /// The configured browser, else the first candidate found.
+    fn find_browser(&self) -> Option<PathBuf> {
+        if let Some(ref browser) = self.browser {
+            return Some(browser.clone());
+        }
+        BROWSER_CANDIDATES
+            .iter()
+            .find_map(|name| which::which(name).ok())
+            .or_else(|| {
+                let chrome = Path::new(MACOS_CHROME);
+                chrome.exists().then(|| chrome.to_path_buf())
+            })
+    }
+
+    /// Creates a new, empty directory for one call.
+    fn create_workdir(&self) -> Result<PathBuf, ToolSourceError> {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let dir = self.temp_root.join(format!(
+            "loom-screenshot-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&dir).map_err(|e| {
+            ToolSourceError::Transport(format!("failed to create {}: {}", dir.display(), e))
+        })?;
+        Ok(dir)
+    }
+}
+
+/// Tool that renders a URL or an HTML file of the working folder in a headless browser and
+/// returns a PNG screenshot as [`ToolCallContent::Image`].
+pub struct ScreenshotTool {
+    working_folder: Arc<PathBuf>,
+    config: BrowserConfig,
+}
+
+impl ScreenshotTool {
+    /// Tool for files under `working_folder`, with the browser from [`BrowserConfig::from_env`].
+    pub fn new(working_folder: Arc<PathBuf>) -> Self {
+        Self::with_config(working_folder, BrowserConfig::from_env())
+    }
+
+    pub fn with_config(working_folder: Arc<PathBuf>, config: BrowserConfig) -> Self {
+        Self {
+            working_folder,
+            config,
+        }
+    }
+
+    /// URL to load for `target`: http(s) URLs as given, anything else a file under the working
+    /// folder.
+    fn target_url(&self, target: &str) -> Result<String, ToolSourceError> {
+        let target = target.trim();
+        if target.starts_with("http://") || target.starts_with("https://") {
+            return Ok(target.to_string());
+        }
+        if target.contains("://") {
+            return Err(ToolSourceError::InvalidInput(format!(
+                "target must be an http(s) URL or a file in the working folder: {}",
+                target
+            )));
+        }
+        let path = resolve_path_under(&self.working_folder, target)?;
+        if !path.is_file() {
+            return Err(ToolSourceError::InvalidInput(format!(
+                "file not found: {}",
+                target
+            )));
+        }
+        url::Url::from_file_path(&path)
+            .map(String::from)
+            .map_err(|()| ToolSourceError::InvalidInput(format!("invalid path: {}", target)))
+    }
+}
+
+/// `args[key]` as a viewport side, clamped to `1..=MAX_DIMENSION`.
+fn dimension(args: &serde_json::Value, key: &str, default: u64) -> u64 {
+    args.get(key)
+        .and_then(|v| v.as_u64())
+        .unwrap_or(default)
+        .clamp(1, MAX_DIMENSION)
+}
+
+#[async_trait]
+impl Tool for ScreenshotTool {
+    fn name(&self) -> &str {
+        TOOL_SCREENSHOT
+    }
+
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: TOOL_SCREENSHOT.to_string(),
+            description: Some(
+                "Renders a web page (http/https URL) or an HTML file in the working folder in a \
+                 headless browser and returns a PNG screenshot of the viewport. Use to check how \
+                 a page looks, e.g. after editing its HTML or CSS."
+                    .to_string(),
+            ),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "target": {
+                        "type": "string",
+                        "description": "http(s) URL, or path of an HTML file relative to the working folder."
+                    },
+                    "width": {
+                        "type": "integer",
+                        "description": format!("Viewport width in pixels (default {}).", DEFAULT_WIDTH)
+                    },
+                    "height": {
+                        "type": "integer",
+                        "description": format!("Viewport height in pixels (default {}).", DEFAULT_HEIGHT)
+                    },
+                    "wait_ms": {
+                        "type": "integer",
+                        "description": format!(
+                            "Time the page may run scripts before the capture, in milliseconds (default {}).",
+                            DEFAULT_WAIT_MS
+                        )
+                    }
+                },
+                "required": ["target"]
+            }),
+            output_hint: None,
+            catalog: ToolCatalogInfo::default()
+                .with_category("system")
+                .with_icon("camera")
+                .with_danger(ToolDanger::Safe),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: serde_json::Value,
+        ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let target = args
+            .get("target")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolSourceError::InvalidInput("missing target".to_string()))?;
+        let url = self.target_url(target)?;
+        let width = dimension(&args, "width", DEFAULT_WIDTH);
+        let height = dimension(&args, "height", DEFAULT_HEIGHT);
+        let wait_ms = args
+            .get("wait_ms")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_WAIT_MS)
+            .min(self.config.timeout_ms);
+        let browser = self.config.find_browser().ok_or_else(|| {
+            ToolSourceError::ToolError(
+                "no headless browser found; install Chrome or Chromium, or set LOOM_BROWSER"
+                    .to_string(),
+            )
+        })?;
+
+        let workdir = self.config.create_workdir()?;
+        let screenshot = workdir.join(SCREENSHOT_FILE);
+        let mut cmd = tokio::process::Command::new(&browser);
+        cmd.args([
+            "--headless",
+            "--disable-gpu",
+            "--hide-scrollbars",
+            "--no-first-run",
+            "--no-default-browser-check",
+            "--mute-audio",
+        ])
+        .arg(format!(
+            "--user-data-dir={}",
+            workdir.join("profile").display()
+        ))
+        .arg(format!("--window-size={},{}", width, height))
+        .arg(format!("--virtual-time-budget={}", wait_ms))
+        .arg(format!("--screenshot={}", screenshot.display()));
+        if self.config.no_sandbox {
+            cmd.arg("--no-sandbox");
+        }
+        cmd.arg(&url)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped());
+        // Browser logs are noise for the model; keep cancellation but drop partial output.
+        let ctx = ctx.map(|ctx| ToolCallContext {
+            stream_writer: None,
+            ..ctx.clone()
+        });
+        let output = run_spawned_shell_command(cmd, self.config.timeout_ms, ctx.as_ref()).await;
+        let image = std::fs::read(&screenshot);
+        let _ = std::fs::remove_dir_all(&workdir);
+        let output = output?;
+        if output.timed_out {
+            return Err(ToolSourceError::ToolError(format!(
+                "browser timed out after {} ms loading {}",
+                self.config.timeout_ms, url
+            )));
+        }
+        let image = image.map_err(|_| {
+            let stderr = output.stderr.trim();
+            let tail: String = stderr
+                .chars()
+                .skip(stderr.chars().count().saturating_sub(500))
+                .collect();
+            ToolSourceError::ToolError(format!(
+                "browser wrote no screenshot (exit code {:?}): {}",
+                output.exit_code, tail
+            ))
+        })?;
+        Ok(ToolCallContent::image(
+            "image/png",
+            base64::engine::general_purpose::STANDARD.encode(image),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(dir: &Path) -> ScreenshotTool {
+        ScreenshotTool::with_config(
+            Arc::new(dir.to_path_buf()),
+            BrowserConfig {
+                temp_root: dir.to_path_buf(),
+                ..BrowserConfig::default()
+            },
+        )
+    }
+
+    #[test]
+    fn target_url_accepts_web_urls_and_working_folder_files() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(temp.path().join("index.html"), "<h1>hi</h1>").unwrap();
+        let tool = tool(temp.path());
+
+        assert_eq!(
+            tool.target_url("https://example.com/a").unwrap(),
+            "https://example.com/a"
+        );
+        let local = tool.target_url("index.html").unwrap();
+        assert!(local.starts_with("file://"), "{local}");
+        assert!(local.ends_with("/index.html"), "{local}");
+
+        assert!(tool.target_url("missing.html").is_err());
+        assert!(tool.target_url("../outside.html").is_err());
+        assert!(tool.target_url("ftp://example.com/x").is_err());
+    }
+
+    #[tokio::test]
+    async fn captures_a_local_page_as_png() {
+        let temp = tempfile::tempdir().unwrap();
+        let tool = tool(temp.path());
+        if tool.config.find_browser().is_none() {
+            return;
+        }
+        std::fs::write(
+            temp.path().join("page.html"),
+            "<body style=\"background:red\">hello</body>",
+        )
+        .unwrap();
+        let out = tool
+            .call(
+                json!({ "target": "page.html", "width": 200, "height": 100 }),
+                None,
+            )
+            .await;
+        // A browser that cannot start here (e.g. sandboxing as root) is not a tool failure.
+        let Ok(out) = out else {
+            return;
+        };
+        match out {
+            ToolCallContent::Image { media_type, data } => {
+                assert_eq!(media_type, "image/png");
+                assert!(data.starts_with("iVBORw0KGgo"), "not a PNG");
+            }
+            other => panic!("expected an image, got {other}"),
+        }
+        assert!(tool.call(json!({}), None).await.is_err());
+    }
+}
@@ -70,6 +70,7 @@ impl Tool for SkillTool {
                 "required": ["name"]
             }),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
@@ -0,0 +1,82 @@
+//! `ssh_exec`: run an allowlisted command on a configured host.
+
+use async_trait::async_trait;
+use serde_json::json;
+
+use super::{run_ssh_program, timeout_arg, SshHosts};
+use crate::tool_source::{
+    ToolCallContent, ToolCallContext, ToolCatalogInfo, ToolDanger, ToolSourceError, ToolSpec,
+};
+use crate::tools::Tool;
+use crate::{ToolOutputHint, ToolOutputStrategy};
+
+/// Tool name for running a command over SSH.
+pub const TOOL_SSH_EXEC: &str = "ssh_exec";
+
+/// Runs one command on a configured [`super::SshHost`] when its allowlist permits it.
+pub struct SshExecTool {
+    hosts: SshHosts,
+}
+
+impl SshExecTool {
+    pub fn new(hosts: SshHosts) -> Self {
+        Self { hosts }
+    }
+}
+
+#[async_trait]
+impl Tool for SshExecTool {
+    fn name(&self) -> &str {
+        TOOL_SSH_EXEC
+    }
+
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: TOOL_SSH_EXEC.to_string(),
+            description: Some(
+                "Runs a read-only inspection command on a configured remote host over SSH and \
+                 returns stdout and stderr. Only commands on the host's allowlist are accepted; \
+                 pipes, redirects and command substitution are rejected."
+                    .to_string(),
+            ),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "host": self.hosts.host_schema(),
+                    "command": {
+                        "type": "string",
+                        "description": "Command to run, e.g. `df -h` or `systemctl status nginx`."
+                    },
+                    "timeout": {
+                        "type": "integer",
+                        "description": "Timeout in milliseconds (default 60000)."
+                    }
+                },
+                "required": ["host", "command"]
+            }),
+            output_hint: Some(
+                ToolOutputHint::preferred(ToolOutputStrategy::HeadTail).prefer_head_tail(),
+            ),
+            catalog: ToolCatalogInfo::default()
+                .with_category("remote")
+                .with_icon("server")
+                .with_danger(ToolDanger::Destructive),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: serde_json::Value,
+        ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let host = self.hosts.get(&args)?;
+        let command = args
+            .get("command")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolSourceError::InvalidInput("missing command".to_string()))?;
+        host.check_command(command)?;
+        let output =
+            run_ssh_program("ssh", &host.ssh_args(command), timeout_arg(&args), ctx).await?;
+        Ok(ToolCallContent::text(output))
+    }
+}
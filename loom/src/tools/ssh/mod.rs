@@ -0,0 +1,294 @@
+//! SSH tools: run allowlisted commands on and copy files from/to configured remote hosts.
+//!
+//! Hosts are declared up front ([`SshHost`]); the model only picks a host by name, so
+//! credentials never appear in tool arguments. Commands run through the system `ssh` / `scp`
+//! binaries with `BatchMode=yes` (key-based auth only, no password prompts) and strict host key
+//! checking. Each host has its own command allowlist and remote path allowlist; both are empty
+//! (deny all) by default.
+
+mod exec;
+mod scp;
+
+pub use exec::{SshExecTool, TOOL_SSH_EXEC};
+pub use scp::{ScpGetTool, ScpPutTool, TOOL_SCP_GET, TOOL_SCP_PUT};
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::tool_source::ToolSourceError;
+
+/// Default timeout for one ssh/scp invocation.
+const DEFAULT_TIMEOUT_MS: u64 = 60_000;
+
+/// Characters that would let a command escape its allowlist on the remote shell.
+const SHELL_METACHARACTERS: &[char] = &[
+    ';', '|', '&', '`', '$', '>', '<', '(', ')', '\n', '\r', '\\',
+];
+
+/// One remote host the SSH tools may connect to.
+#[derive(Clone, Debug, Deserialize)]
+pub struct SshHost {
+    /// Name the model uses to select the host.
+    pub name: String,
+    /// Hostname or IP address.
+    pub host: String,
+    pub user: String,
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// Private key used for authentication (`ssh -i`).
+    pub identity_file: PathBuf,
+    /// Known hosts file; when unset, the user's default known hosts are used.
+    #[serde(default)]
+    pub known_hosts_file: Option<PathBuf>,
+    /// Allowed commands for `ssh_exec`. An entry allows any command that starts with its words,
+    /// e.g. `"systemctl status"` allows `systemctl status nginx`. Empty = `ssh_exec` disabled.
+    #[serde(default)]
+    pub allowed_commands: Vec<String>,
+    /// Remote path prefixes `scp_get` / `scp_put` may touch. Empty = scp disabled.
+    #[serde(default)]
+    pub allowed_paths: Vec<String>,
+}
+
+impl SshHost {
+    /// Returns an error unless `command` is covered by [`SshHost::allowed_commands`].
+    pub fn check_command(&self, command: &str) -> Result<(), ToolSourceError> {
+        if command.contains(SHELL_METACHARACTERS) {
+            return Err(ToolSourceError::InvalidInput(
+                "command must not contain shell metacharacters".to_string(),
+            ));
+        }
+        let words: Vec<&str> = command.split_whitespace().collect();
+        let allowed = !words.is_empty()
+            && self.allowed_commands.iter().any(|entry| {
+                let prefix: Vec<&str> = entry.split_whitespace().collect();
+                !prefix.is_empty() && words.starts_with(&prefix)
+            });
+        if allowed {
+            Ok(())
+        } else {
+            Err(ToolSourceError::InvalidInput(format!(
+                "command not allowed on host '{}'",
+                self.name
+            )))
+        }
+    }
+
+    /// Returns an error unless `path` is absolute, free of `..`, and under an allowed prefix.
+    pub fn check_remote_path(&self, path: &str) -> Result<(), ToolSourceError> {
+        let p = Path::new(path);
+        let normal = p.is_absolute()
+            && !p
+                .components()
+                .any(|c| matches!(c, std::path::Component::ParentDir))
+            && !path.contains(SHELL_METACHARACTERS)
+            && !path.contains(char::is_whitespace);
+        let allowed = normal
+            && self
+                .allowed_paths
+                .iter()
+                .any(|prefix| p.starts_with(Path::new(prefix)));
+        if allowed {
+            Ok(())
+        } else {
+            Err(ToolSourceError::InvalidInput(format!(
+                "remote path not allowed on host '{}': {}",
+                self.name, path
+            )))
+        }
+    }
+
+    fn destination(&self) -> String {
+        format!("{}@{}", self.user, self.host)
+    }
+
+    /// Options shared by `ssh` and `scp`: key-only auth, strict host keys, no prompts.
+    fn common_options(&self) -> Vec<String> {
+        let mut args = vec![
+            "-i".to_string(),
+            self.identity_file.to_string_lossy().into_owned(),
+            "-o".to_string(),
+            "BatchMode=yes".to_string(),
+            "-o".to_string(),
+            "IdentitiesOnly=yes".to_string(),
+            "-o".to_string(),
+            "StrictHostKeyChecking=yes".to_string(),
+        ];
+        if let Some(ref known_hosts) = self.known_hosts_file {
+            args.push("-o".to_string());
+            args.push(format!(
+                "UserKnownHostsFile={}",
+                known_hosts.to_string_lossy()
+            ));
+        }
+        args
+    }
+
+    /// Arguments for `ssh` running `command` on this host.
+    pub(crate) fn ssh_args(&self, command: &str) -> Vec<String> {
+        let mut args = self.common_options();
+        if let Some(port) = self.port {
+            args.push("-p".to_string());
+            args.push(port.to_string());
+        }
+        args.push(self.destination());
+        args.push("--".to_string());
+        args.push(command.to_string());
+        args
+    }
+
+    /// Arguments for `scp` copying `from` to `to` (either side may be remote).
+    pub(crate) fn scp_args(&self, from: ScpEndpoint<'_>, to: ScpEndpoint<'_>) -> Vec<String> {
+        let mut args = self.common_options();
+        if let Some(port) = self.port {
+            args.push("-P".to_string());
+            args.push(port.to_string());
+        }
+        args.push("--".to_string());
+        for end in [from, to] {
+            args.push(match end {
+                ScpEndpoint::Local(p) => p.to_string_lossy().into_owned(),
+                ScpEndpoint::Remote(p) => format!("{}:{}", self.destination(), p),
+            });
+        }
+        args
+    }
+}
+
+/// One side of an scp copy.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum ScpEndpoint<'a> {
+    Local(&'a Path),
+    Remote(&'a str),
+}
+
+/// Configured hosts by name, shared by the SSH tools.
+#[derive(Clone, Debug, Default)]
+pub struct SshHosts {
+    hosts: Arc<HashMap<String, SshHost>>,
+}
+
+impl SshHosts {
+    pub fn new(hosts: impl IntoIterator<Item = SshHost>) -> Self {
+        Self {
+            hosts: Arc::new(hosts.into_iter().map(|h| (h.name.clone(), h)).collect()),
+        }
+    }
+
+    /// Loads hosts from a YAML file holding a list of [`SshHost`] entries.
+    pub fn load(path: &Path) -> Result<Self, ToolSourceError> {
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            ToolSourceError::Transport(format!("failed to read {}: {}", path.display(), e))
+        })?;
+        let hosts: Vec<SshHost> = serde_yaml::from_str(&text).map_err(|e| {
+            ToolSourceError::InvalidInput(format!("invalid ssh hosts {}: {}", path.display(), e))
+        })?;
+        Ok(Self::new(hosts))
+    }
+
+    /// Host names, sorted (for tool descriptions).
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.hosts.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    pub(crate) fn get(&self, args: &serde_json::Value) -> Result<&SshHost, ToolSourceError> {
+        let name = args
+            .get("host")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolSourceError::InvalidInput("missing host".to_string()))?;
+        self.hosts
+            .get(name)
+            .ok_or_else(|| ToolSourceError::InvalidInput(format!("unknown host '{}'", name)))
+    }
+
+    pub(crate) fn host_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "string",
+            "enum": self.names(),
+            "description": "Configured host name."
+        })
+    }
+}
+
+/// Runs `program args...` with the bash tool's timeout and cancellation handling.
+/// A non-zero exit is reported as output (stderr), not as an error; a timeout is an error.
+pub(crate) async fn run_ssh_program(
+    program: &str,
+    args: &[String],
+    timeout_ms: u64,
+    ctx: Option<&crate::tool_source::ToolCallContext>,
+) -> Result<String, ToolSourceError> {
+    let mut cmd = tokio::process::Command::new(program);
+    cmd.args(args);
+    cmd.stdin(std::process::Stdio::null());
+    cmd.stdout(std::process::Stdio::piped());
+    cmd.stderr(std::process::Stdio::piped());
+    super::bash::run_spawned_shell_command(cmd, timeout_ms, ctx)
+        .await?
+        .into_legacy_text()
+}
+
+fn timeout_arg(args: &serde_json::Value) -> u64 {
+    args.get("timeout")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_TIMEOUT_MS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host() -> SshHost {
+        SshHost {
+            name: "web1".into(),
+            host: "10.0.0.5".into(),
+            user: "ops".into(),
+            port: Some(2222),
+            identity_file: PathBuf::from("/keys/ops"),
+            known_hosts_file: None,
+            allowed_commands: vec!["uptime".into(), "systemctl status".into()],
+            allowed_paths: vec!["/var/log".into()],
+        }
+    }
+
+    #[test]
+    fn command_allowlist_matches_leading_words_and_rejects_metacharacters() {
+        let h = host();
+        assert!(h.check_command("uptime").is_ok());
+        assert!(h.check_command("systemctl status nginx").is_ok());
+        assert!(h.check_command("systemctl restart nginx").is_err());
+        assert!(h.check_command("uptimeX").is_err());
+        assert!(h.check_command("uptime; rm -rf /").is_err());
+        assert!(h.check_command("uptime $(id)").is_err());
+        assert!(h.check_command("").is_err());
+    }
+
+    #[test]
+    fn remote_paths_must_stay_under_allowed_prefixes() {
+        let h = host();
+        assert!(h.check_remote_path("/var/log/syslog").is_ok());
+        assert!(h.check_remote_path("/var/log/../../etc/shadow").is_err());
+        assert!(h.check_remote_path("/var/logs/x").is_err());
+        assert!(h.check_remote_path("var/log/syslog").is_err());
+    }
+
+    #[test]
+    fn ssh_args_use_key_only_batch_mode() {
+        let args = host().ssh_args("uptime");
+        let joined = args.join(" ");
+        assert!(joined.starts_with("-i /keys/ops -o BatchMode=yes"));
+        assert!(joined.ends_with("-p 2222 ops@10.0.0.5 -- uptime"));
+
+        let scp = host().scp_args(
+            ScpEndpoint::Remote("/var/log/syslog"),
+            ScpEndpoint::Local(Path::new("/tmp/syslog")),
+        );
+        assert!(scp
+            .join(" ")
+            .ends_with("-P 2222 -- ops@10.0.0.5:/var/log/syslog /tmp/syslog"));
+    }
+}
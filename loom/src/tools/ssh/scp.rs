@@ -0,0 +1,202 @@
+//! `scp_get` / `scp_put`: copy files between the working folder and allowed remote paths.
+
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::json;
+
+use super::{run_ssh_program, timeout_arg, ScpEndpoint, SshHosts};
+use crate::tool_source::{
+    ToolCallContent, ToolCallContext, ToolCatalogInfo, ToolDanger, ToolSourceError, ToolSpec,
+};
+use crate::tools::Tool;
+
+/// Tool name for copying a remote file to the local machine.
+pub const TOOL_SCP_GET: &str = "scp_get";
+/// Tool name for copying a local file to a remote host.
+pub const TOOL_SCP_PUT: &str = "scp_put";
+
+/// Resolves `path` against the working folder. `..` components are rejected so the local side
+/// stays inside the working folder when one is set.
+fn local_path(
+    working_folder: &Option<Arc<PathBuf>>,
+    args: &serde_json::Value,
+) -> Result<PathBuf, ToolSourceError> {
+    let path = args
+        .get("local_path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ToolSourceError::InvalidInput("missing local_path".to_string()))?;
+    let p = Path::new(path);
+    if p.components().any(|c| matches!(c, Component::ParentDir)) {
+        return Err(ToolSourceError::InvalidInput(
+            "local_path must not contain '..'".to_string(),
+        ));
+    }
+    match working_folder {
+        Some(wf) if p.is_absolute() && !p.starts_with(wf.as_path()) => Err(
+            ToolSourceError::InvalidInput("local_path must be inside the working folder".into()),
+        ),
+        Some(wf) => Ok(wf.join(p)),
+        None => Ok(p.to_path_buf()),
+    }
+}
+
+fn remote_path(args: &serde_json::Value) -> Result<&str, ToolSourceError> {
+    args.get("remote_path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ToolSourceError::InvalidInput("missing remote_path".to_string()))
+}
+
+fn copy_schema(hosts: &SshHosts) -> serde_json::Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "host": hosts.host_schema(),
+            "remote_path": {
+                "type": "string",
+                "description": "Absolute path on the remote host (must be under an allowed prefix)."
+            },
+            "local_path": {
+                "type": "string",
+                "description": "Local path, relative to the working folder."
+            },
+            "timeout": {
+                "type": "integer",
+                "description": "Timeout in milliseconds (default 60000)."
+            }
+        },
+        "required": ["host", "remote_path", "local_path"]
+    })
+}
+
+/// Copies a file from an allowed remote path into the working folder.
+pub struct ScpGetTool {
+    hosts: SshHosts,
+    working_folder: Option<Arc<PathBuf>>,
+}
+
+impl ScpGetTool {
+    pub fn new(hosts: SshHosts, working_folder: Option<Arc<PathBuf>>) -> Self {
+        Self {
+            hosts,
+            working_folder,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ScpGetTool {
+    fn name(&self) -> &str {
+        TOOL_SCP_GET
+    }
+
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: TOOL_SCP_GET.to_string(),
+            description: Some(
+                "Copies a file from a configured remote host to a local path (scp).".to_string(),
+            ),
+            input_schema: copy_schema(&self.hosts),
+            output_hint: None,
+            catalog: ToolCatalogInfo::default()
+                .with_category("remote")
+                .with_icon("download")
+                .with_danger(ToolDanger::Caution),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: serde_json::Value,
+        ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let host = self.hosts.get(&args)?;
+        let remote = remote_path(&args)?;
+        host.check_remote_path(remote)?;
+        let local = local_path(&self.working_folder, &args)?;
+        let scp_args = host.scp_args(ScpEndpoint::Remote(remote), ScpEndpoint::Local(&local));
+        let output = run_ssh_program("scp", &scp_args, timeout_arg(&args), ctx).await?;
+        Ok(ToolCallContent::text(if output.trim().is_empty() {
+            format!("copied {}:{} to {}", host.name, remote, local.display())
+        } else {
+            output
+        }))
+    }
+}
+
+/// Copies a file from the working folder to an allowed remote path.
+pub struct ScpPutTool {
+    hosts: SshHosts,
+    working_folder: Option<Arc<PathBuf>>,
+}
+
+impl ScpPutTool {
+    pub fn new(hosts: SshHosts, working_folder: Option<Arc<PathBuf>>) -> Self {
+        Self {
+            hosts,
+            working_folder,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ScpPutTool {
+    fn name(&self) -> &str {
+        TOOL_SCP_PUT
+    }
+
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: TOOL_SCP_PUT.to_string(),
+            description: Some(
+                "Copies a local file to a configured remote host (scp). Overwrites the remote file."
+                    .to_string(),
+            ),
+            input_schema: copy_schema(&self.hosts),
+            output_hint: None,
+            catalog: ToolCatalogInfo::default()
+                .with_category("remote")
+                .with_icon("upload")
+                .with_danger(ToolDanger::Destructive),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: serde_json::Value,
+        ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let host = self.hosts.get(&args)?;
+        let remote = remote_path(&args)?;
+        host.check_remote_path(remote)?;
+        let local = local_path(&self.working_folder, &args)?;
+        if !local.is_file() {
+            return Err(ToolSourceError::InvalidInput(format!(
+                "local file not found: {}",
+                local.display()
+            )));
+        }
+        let scp_args = host.scp_args(ScpEndpoint::Local(&local), ScpEndpoint::Remote(remote));
+        let output = run_ssh_program("scp", &scp_args, timeout_arg(&args), ctx).await?;
+        Ok(ToolCallContent::text(if output.trim().is_empty() {
+            format!("copied {} to {}:{}", local.display(), host.name, remote)
+        } else {
+            output
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_path_stays_in_working_folder() {
+        let wf = Some(Arc::new(PathBuf::from("/work")));
+        let ok = local_path(&wf, &json!({"local_path": "logs/syslog"})).unwrap();
+        assert_eq!(ok, PathBuf::from("/work/logs/syslog"));
+        assert!(local_path(&wf, &json!({"local_path": "../etc/passwd"})).is_err());
+        assert!(local_path(&wf, &json!({"local_path": "/etc/passwd"})).is_err());
+    }
+}
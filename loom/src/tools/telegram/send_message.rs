@@ -4,7 +4,9 @@ use async_trait::async_trait;
 use serde::Deserialize;
 use serde_json::{json, Value};
 
-use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError, ToolSpec};
+use crate::tool_source::{
+    ToolCallContent, ToolCallContext, ToolCatalogInfo, ToolDanger, ToolSourceError, ToolSpec,
+};
 use crate::tools::Tool;
 
 use super::{get_current_chat_id, get_telegram_api};
@@ -56,6 +58,10 @@ impl Tool for TelegramSendMessageTool {
                 "required": ["text"]
             }),
             output_hint: None,
+            catalog: ToolCatalogInfo::default()
+                .with_category("messaging")
+                .with_icon("send")
+                .with_danger(ToolDanger::Destructive),
         }
     }
 
@@ -50,6 +50,7 @@ impl Tool for TodoReadTool {
             description: Some("Read the current todo list.".to_string()),
             input_schema: json!({ "type": "object", "properties": {}, "required": [] }),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
@@ -97,6 +97,7 @@ impl Tool for TodoWriteTool {
                 "required": ["todos"]
             }),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
@@ -32,6 +32,7 @@ use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError, Tool
 ///             description: Some("A sample tool".to_string()),
 ///             input_schema: serde_json::json!({}),
 ///             output_hint: None,
+///             catalog: Default::default(),
 ///         }
 ///     }
 ///
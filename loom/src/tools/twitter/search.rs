@@ -101,6 +101,7 @@ impl Tool for TwitterSearchTool {
             output_hint: Some(ToolOutputHint::preferred(
                 ToolOutputStrategy::FileRefWithExcerpt,
             )),
+            catalog: Default::default(),
         }
     }
 
@@ -151,6 +151,7 @@ impl Tool for WebFetcherTool {
             output_hint: Some(ToolOutputHint::preferred(
                 ToolOutputStrategy::FileRefWithExcerpt,
             )),
+            catalog: Default::default(),
         }
     }
 
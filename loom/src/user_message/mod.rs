@@ -1,14 +1,21 @@
-//! User message store: append and list messages per thread.
+//! User message store: append, list, search and delete messages per thread.
 //!
 //! Used so that clients can read full, ordered message history by `thread_id`
-//! from a dedicated store instead of short-term memory or checkpoint.
+//! from a dedicated store instead of short-term memory or checkpoint. Each message is kept
+//! with its [`MessageMeta`] (run, token usage, tool) so history views need not decode
+//! checkpoints.
 
 mod sqlite_store;
 
+use std::collections::HashMap;
+use std::sync::Mutex;
+
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 
 pub use sqlite_store::SqliteUserMessageStore;
 
+use crate::llm::LlmUsage;
 use crate::message::Message;
 
 /// Error from [`UserMessageStore`] operations.
@@ -19,26 +26,121 @@ pub enum UserMessageStoreError {
     Other(String),
 }
 
+/// What is known about a message besides its role and content. All fields are optional;
+/// messages appended with [`UserMessageStore::append`] have none.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageMeta {
+    /// Run that produced the message (or, for user messages, that it started).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    /// Token usage of the LLM call that produced an assistant message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<LlmUsage>,
+    /// Tool whose result a tool message holds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    /// Call id a tool message answers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+impl MessageMeta {
+    /// Meta naming the run only.
+    pub fn for_run(run_id: impl Into<String>) -> Self {
+        Self {
+            run_id: Some(run_id.into()),
+            ..Self::default()
+        }
+    }
+}
+
+/// A message as stored: the message with its id, thread and [`MessageMeta`].
+#[derive(Debug, Clone)]
+pub struct StoredMessage {
+    /// Store-wide id, increasing in append order; the `before` cursor of
+    /// [`UserMessageStore::list_records`].
+    pub id: u64,
+    pub thread_id: String,
+    pub message: Message,
+    pub meta: MessageMeta,
+    /// When the message was appended, in milliseconds since the Unix epoch; `None` for
+    /// messages stored before timestamps were recorded.
+    pub created_at_ms: Option<i64>,
+}
+
+/// A stored message matching a [`UserMessageStore::search`] query.
+pub type MessageSearchHit = StoredMessage;
+
 /// Store for user-facing messages per thread.
 ///
-/// - `append`: add one message; caller ensures order and thread consistency.
-/// - `list`: return messages for the thread in order; `before` is a pagination cursor (e.g. seq or id), `limit` caps the count.
+/// - `append_with_meta`: add one message with its meta; caller ensures order and thread consistency.
+/// - `list_records`: return stored messages for the thread in order; `before` is the id cursor, `limit` caps the count.
+/// - `search`: full-text search over user and assistant messages, best match first.
+/// - `delete`: remove all messages of a thread.
 #[async_trait]
 pub trait UserMessageStore: Send + Sync {
-    /// Appends one message for the given thread.
-    async fn append(&self, thread_id: &str, message: &Message)
-        -> Result<(), UserMessageStoreError>;
+    /// Appends one message for the given thread, without meta.
+    async fn append(
+        &self,
+        thread_id: &str,
+        message: &Message,
+    ) -> Result<(), UserMessageStoreError> {
+        self.append_with_meta(thread_id, message, &MessageMeta::default())
+            .await
+    }
 
-    /// Lists messages for the thread in order.
+    /// Appends one message for the given thread with its meta.
+    async fn append_with_meta(
+        &self,
+        thread_id: &str,
+        message: &Message,
+        meta: &MessageMeta,
+    ) -> Result<(), UserMessageStoreError>;
+
+    /// Lists stored messages for the thread in order.
     ///
-    /// - `before`: if set, return only messages with seq (or id) less than this (cursor-based pagination).
-    /// - `limit`: max number of messages to return.
+    /// - `before`: if set, return only messages with an id less than this (cursor-based pagination).
+    /// - `limit`: max number of messages to return (default 100, at most 1000).
+    async fn list_records(
+        &self,
+        thread_id: &str,
+        before: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<StoredMessage>, UserMessageStoreError>;
+
+    /// Lists messages for the thread in order; [`list_records`](Self::list_records) without
+    /// ids and meta.
     async fn list(
         &self,
         thread_id: &str,
         before: Option<u64>,
         limit: Option<u32>,
-    ) -> Result<Vec<Message>, UserMessageStoreError>;
+    ) -> Result<Vec<Message>, UserMessageStoreError> {
+        Ok(self
+            .list_records(thread_id, before, limit)
+            .await?
+            .into_iter()
+            .map(|r| r.message)
+            .collect())
+    }
+
+    /// Searches user and assistant messages for `query` (words matched anywhere in the text).
+    ///
+    /// - `thread_ids`: if set, only these threads are searched; otherwise all threads.
+    /// - `limit`: max number of hits to return.
+    ///
+    /// The default implementation finds nothing.
+    async fn search(
+        &self,
+        _query: &str,
+        _thread_ids: Option<&[String]>,
+        _limit: u32,
+    ) -> Result<Vec<StoredMessage>, UserMessageStoreError> {
+        Ok(vec![])
+    }
+
+    /// Deletes all messages of the thread. Returns how many were deleted.
+    async fn delete(&self, thread_id: &str) -> Result<u64, UserMessageStoreError>;
 }
 
 /// No-op implementation: append does nothing, list always returns an empty vec.
@@ -47,22 +149,127 @@ pub struct NoOpUserMessageStore;
 
 #[async_trait]
 impl UserMessageStore for NoOpUserMessageStore {
-    async fn append(
+    async fn append_with_meta(
         &self,
         _thread_id: &str,
         _message: &Message,
+        _meta: &MessageMeta,
     ) -> Result<(), UserMessageStoreError> {
         Ok(())
     }
 
-    async fn list(
+    async fn list_records(
         &self,
         _thread_id: &str,
         _before: Option<u64>,
         _limit: Option<u32>,
-    ) -> Result<Vec<Message>, UserMessageStoreError> {
+    ) -> Result<Vec<StoredMessage>, UserMessageStoreError> {
         Ok(vec![])
     }
+
+    async fn delete(&self, _thread_id: &str) -> Result<u64, UserMessageStoreError> {
+        Ok(0)
+    }
+}
+
+/// In-process store: messages live as long as the value. Ids are 1-based positions across the
+/// store (like the SQLite row id); `search` matches words case-insensitively, newest first.
+#[derive(Debug, Default)]
+pub struct InMemoryUserMessageStore {
+    threads: Mutex<HashMap<String, Vec<StoredMessage>>>,
+    next_id: Mutex<u64>,
+}
+
+impl InMemoryUserMessageStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl UserMessageStore for InMemoryUserMessageStore {
+    async fn append_with_meta(
+        &self,
+        thread_id: &str,
+        message: &Message,
+        meta: &MessageMeta,
+    ) -> Result<(), UserMessageStoreError> {
+        let id = {
+            let mut next = self.next_id.lock().unwrap_or_else(|e| e.into_inner());
+            *next += 1;
+            *next
+        };
+        self.threads
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(thread_id.to_string())
+            .or_default()
+            .push(StoredMessage {
+                id,
+                thread_id: thread_id.to_string(),
+                message: message.clone(),
+                meta: meta.clone(),
+                created_at_ms: Some(chrono::Utc::now().timestamp_millis()),
+            });
+        Ok(())
+    }
+
+    async fn list_records(
+        &self,
+        thread_id: &str,
+        before: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<StoredMessage>, UserMessageStoreError> {
+        let limit = limit.unwrap_or(100).min(1000) as usize;
+        let threads = self.threads.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(threads
+            .get(thread_id)
+            .map(|messages| {
+                messages
+                    .iter()
+                    .filter(|m| before.is_none_or(|b| m.id < b))
+                    .take(limit)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        thread_ids: Option<&[String]>,
+        limit: u32,
+    ) -> Result<Vec<StoredMessage>, UserMessageStoreError> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        if words.is_empty() {
+            return Ok(vec![]);
+        }
+        let threads = self.threads.lock().unwrap_or_else(|e| e.into_inner());
+        let mut hits: Vec<StoredMessage> = threads
+            .iter()
+            .filter(|(id, _)| thread_ids.is_none_or(|ids| ids.contains(*id)))
+            .flat_map(|(_, messages)| messages.iter())
+            .filter(|m| matches!(m.message, Message::User(_) | Message::Assistant(_)))
+            .filter(|m| {
+                let text = m.message.content().to_lowercase();
+                words.iter().all(|w| text.contains(w.as_str()))
+            })
+            .cloned()
+            .collect();
+        hits.sort_by(|a, b| b.id.cmp(&a.id));
+        hits.truncate(limit.min(1000) as usize);
+        Ok(hits)
+    }
+
+    async fn delete(&self, thread_id: &str) -> Result<u64, UserMessageStoreError> {
+        let removed = self
+            .threads
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(thread_id);
+        Ok(removed.map_or(0, |messages| messages.len() as u64))
+    }
 }
 
 #[cfg(test)]
@@ -87,4 +294,70 @@ mod tests {
             .expect("list should succeed");
         assert!(msgs.is_empty());
     }
+
+    #[tokio::test]
+    async fn in_memory_store_keeps_threads_apart_and_pages() {
+        let store = InMemoryUserMessageStore::new();
+        store.append("t1", &Message::user("a")).await.unwrap();
+        store.append("t2", &Message::user("other")).await.unwrap();
+        store.append("t1", &Message::assistant("b")).await.unwrap();
+        assert_eq!(store.list("t1", None, None).await.unwrap().len(), 2);
+        assert_eq!(store.list("t1", Some(3), None).await.unwrap().len(), 1);
+        assert_eq!(store.list("t1", None, Some(1)).await.unwrap().len(), 1);
+        assert!(store.list("t3", None, None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn in_memory_store_keeps_meta_searches_and_deletes() {
+        let store = InMemoryUserMessageStore::new();
+        store
+            .append_with_meta(
+                "t1",
+                &Message::user("Deploy on Friday?"),
+                &MessageMeta::for_run("r1"),
+            )
+            .await
+            .unwrap();
+        let usage = LlmUsage {
+            prompt_tokens: 10,
+            completion_tokens: 5,
+            total_tokens: 15,
+            ..LlmUsage::default()
+        };
+        store
+            .append_with_meta(
+                "t1",
+                &Message::assistant("Not on friday."),
+                &MessageMeta {
+                    usage: Some(usage.clone()),
+                    ..MessageMeta::for_run("r1")
+                },
+            )
+            .await
+            .unwrap();
+        store
+            .append("t2", &Message::user("friday lunch"))
+            .await
+            .unwrap();
+
+        let records = store.list_records("t1", None, None).await.unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].meta.run_id.as_deref(), Some("r1"));
+        assert_eq!(records[1].meta.usage, Some(usage));
+        assert!(records[0].id < records[1].id);
+
+        let hits = store.search("FRIDAY", None, 10).await.unwrap();
+        assert_eq!(hits.len(), 3);
+        assert_eq!(hits[0].thread_id, "t2", "newest first");
+        let hits = store
+            .search("friday", Some(&["t1".to_string()]), 1)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+
+        assert_eq!(store.delete("t1").await.unwrap(), 2);
+        assert_eq!(store.delete("t1").await.unwrap(), 0);
+        assert!(store.list("t1", None, None).await.unwrap().is_empty());
+        assert_eq!(store.list("t2", None, None).await.unwrap().len(), 1);
+    }
 }
@@ -9,12 +9,33 @@ use tracing::{debug, warn};
 use crate::memory::uuid6;
 use crate::message::{AssistantPayload, Message, UserContent};
 use crate::tool_source::ToolCallContent;
-use crate::user_message::{UserMessageStore, UserMessageStoreError};
+use crate::user_message::{MessageMeta, StoredMessage, UserMessageStore, UserMessageStoreError};
 
-/// SQLite-backed store: one table `user_messages (id, thread_id, role, content)`.
-/// `id` is auto-increment and used as the pagination cursor (`before`).
+/// Columns read into a [`StoredMessage`] by [`row_to_record`], in order.
+const RECORD_COLUMNS: &str =
+    "id, thread_id, role, content, created_at, run_id, usage, tool_name, tool_call_id";
+
+/// SQLite-backed store: one table `user_messages (id, thread_id, role, content, created_at,
+/// run_id, usage, tool_name, tool_call_id)`, with `usage` as JSON. `id` is auto-increment and
+/// used as the pagination cursor (`before`). An FTS5 index (`user_messages_fts`) over `content`,
+/// kept in sync by insert and delete triggers, serves [`UserMessageStore::search`].
 pub struct SqliteUserMessageStore {
     db_path: std::path::PathBuf,
+    /// Opened with [`SqliteUserMessageStore::open_read_only`]: appends and deletes fail.
+    read_only: bool,
+}
+
+/// Connection for one operation: read-only stores never open the file for writing.
+fn open_conn(
+    db_path: &Path,
+    read_only: bool,
+) -> Result<rusqlite::Connection, UserMessageStoreError> {
+    if read_only {
+        crate::memory::sqlite_util::open_sqlite_read_only(db_path)
+    } else {
+        crate::memory::sqlite_util::open_sqlite_with_wal(db_path)
+    }
+    .map_err(UserMessageStoreError::Other)
 }
 
 fn row_to_message(role: &str, content: &str) -> Message {
@@ -71,6 +92,25 @@ fn row_to_message(role: &str, content: &str) -> Message {
     }
 }
 
+/// Reads a row selected with [`RECORD_COLUMNS`]. Unreadable `usage` JSON is dropped.
+fn row_to_record(row: &rusqlite::Row<'_>) -> rusqlite::Result<StoredMessage> {
+    let role: String = row.get(2)?;
+    let content: String = row.get(3)?;
+    let usage: Option<String> = row.get(6)?;
+    Ok(StoredMessage {
+        id: row.get::<_, i64>(0)? as u64,
+        thread_id: row.get(1)?,
+        message: row_to_message(&role, &content),
+        created_at_ms: row.get(4)?,
+        meta: MessageMeta {
+            run_id: row.get(5)?,
+            usage: usage.and_then(|u| serde_json::from_str(&u).ok()),
+            tool_name: row.get(7)?,
+            tool_call_id: row.get(8)?,
+        },
+    })
+}
+
 impl SqliteUserMessageStore {
     /// Creates the store and ensures the table exists. `path` is the SQLite file path.
     pub fn new(path: impl AsRef<Path>) -> Result<Self, UserMessageStoreError> {
@@ -83,7 +123,8 @@ impl SqliteUserMessageStore {
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
                 thread_id TEXT NOT NULL,
                 role TEXT NOT NULL,
-                content TEXT NOT NULL
+                content TEXT NOT NULL,
+                created_at INTEGER
             )
             "#,
             [],
@@ -94,26 +135,136 @@ impl SqliteUserMessageStore {
             [],
         )
         .map_err(|e| UserMessageStoreError::Other(e.to_string()))?;
-        Ok(Self { db_path })
+        ensure_schema(&conn).map_err(|e| UserMessageStoreError::Other(e.to_string()))?;
+        Ok(Self {
+            db_path,
+            read_only: false,
+        })
+    }
+
+    /// Opens an existing store read-only: `list` and `search` work, `append` and `delete` fail.
+    /// For a database that cannot be written (e.g. read-only volume, full disk).
+    pub fn open_read_only(path: impl AsRef<Path>) -> Result<Self, UserMessageStoreError> {
+        let db_path = path.as_ref().to_path_buf();
+        let conn = open_conn(&db_path, true)?;
+        conn.query_row("SELECT COUNT(*) FROM user_messages", [], |_| Ok(()))
+            .map_err(|e| UserMessageStoreError::Other(e.to_string()))?;
+        Ok(Self {
+            db_path,
+            read_only: true,
+        })
     }
 }
 
+/// Adds the `created_at` and meta columns to tables created before they existed, and creates
+/// the FTS5 index with its insert and delete triggers, indexing existing rows when the index is
+/// new.
+fn ensure_schema(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    for (column, sql_type) in [
+        ("created_at", "INTEGER"),
+        ("run_id", "TEXT"),
+        ("usage", "TEXT"),
+        ("tool_name", "TEXT"),
+        ("tool_call_id", "TEXT"),
+    ] {
+        let exists = conn
+            .prepare("SELECT 1 FROM pragma_table_info('user_messages') WHERE name = ?1")?
+            .exists([column])?;
+        if !exists {
+            conn.execute(
+                &format!(
+                    "ALTER TABLE user_messages ADD COLUMN {} {}",
+                    column, sql_type
+                ),
+                [],
+            )?;
+        }
+    }
+    let has_fts = conn
+        .prepare("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'user_messages_fts'")?
+        .exists([])?;
+    if !has_fts {
+        conn.execute_batch(
+            r#"
+            CREATE VIRTUAL TABLE user_messages_fts USING fts5(
+                content, content='user_messages', content_rowid='id'
+            );
+            INSERT INTO user_messages_fts(user_messages_fts) VALUES ('rebuild');
+            "#,
+        )?;
+    }
+    conn.execute(
+        r#"
+        CREATE TRIGGER IF NOT EXISTS user_messages_fts_insert AFTER INSERT ON user_messages
+        BEGIN
+            INSERT INTO user_messages_fts(rowid, content) VALUES (new.id, new.content);
+        END
+        "#,
+        [],
+    )?;
+    conn.execute(
+        r#"
+        CREATE TRIGGER IF NOT EXISTS user_messages_fts_delete AFTER DELETE ON user_messages
+        BEGIN
+            INSERT INTO user_messages_fts(user_messages_fts, rowid, content)
+                VALUES ('delete', old.id, old.content);
+        END
+        "#,
+        [],
+    )?;
+    Ok(())
+}
+
+/// FTS5 query matching all words of `query`, each quoted so FTS syntax in user input is taken
+/// literally. `None` when the query has no words.
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|w| format!("\"{}\"", w.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
 #[async_trait]
 impl UserMessageStore for SqliteUserMessageStore {
-    async fn append(
+    async fn append_with_meta(
         &self,
         thread_id: &str,
         message: &Message,
+        meta: &MessageMeta,
     ) -> Result<(), UserMessageStoreError> {
+        if self.read_only {
+            return Err(read_only_error());
+        }
         let (role, content) = message.to_role_content_pair_for_store();
         let thread_id = thread_id.to_string();
+        let run_id = meta.run_id.clone();
+        let usage = meta
+            .usage
+            .as_ref()
+            .and_then(|u| serde_json::to_string(u).ok());
+        let tool_name = meta.tool_name.clone();
+        let tool_call_id = meta.tool_call_id.clone().or_else(|| match message {
+            Message::Tool { tool_call_id, .. } if !tool_call_id.is_empty() => {
+                Some(tool_call_id.clone())
+            }
+            _ => None,
+        });
         let db_path = self.db_path.clone();
         tokio::task::spawn_blocking(move || {
-            let conn = crate::memory::sqlite_util::open_sqlite_with_wal(&db_path)
-                .map_err(UserMessageStoreError::Other)?;
+            let conn = open_conn(&db_path, false)?;
             conn.execute(
-                "INSERT INTO user_messages (thread_id, role, content) VALUES (?1, ?2, ?3)",
-                params![thread_id, role, content],
+                "INSERT INTO user_messages (thread_id, role, content, created_at, run_id, usage, tool_name, tool_call_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    thread_id,
+                    role,
+                    content,
+                    chrono::Utc::now().timestamp_millis(),
+                    run_id,
+                    usage,
+                    tool_name,
+                    tool_call_id
+                ],
             )
             .map_err(|e| UserMessageStoreError::Other(e.to_string()))?;
             Ok::<(), UserMessageStoreError>(())
@@ -122,55 +273,43 @@ impl UserMessageStore for SqliteUserMessageStore {
         .map_err(|e| UserMessageStoreError::Other(e.to_string()))?
     }
 
-    async fn list(
+    async fn list_records(
         &self,
         thread_id: &str,
         before: Option<u64>,
         limit: Option<u32>,
-    ) -> Result<Vec<Message>, UserMessageStoreError> {
+    ) -> Result<Vec<StoredMessage>, UserMessageStoreError> {
         let thread_id = thread_id.to_string();
         let limit = limit.unwrap_or(100).min(1000);
         let db_path = self.db_path.clone();
+        let read_only = self.read_only;
         let thread_id_for_query = thread_id.clone();
-        let rows: Vec<(String, String)> = tokio::task::spawn_blocking(move || {
-
-            let conn = crate::memory::sqlite_util::open_sqlite_with_wal(&db_path)
-                .map_err(UserMessageStoreError::Other)?;
+        let records: Vec<StoredMessage> = tokio::task::spawn_blocking(move || {
+            let conn = open_conn(&db_path, read_only)?;
             let sql = match before {
-                Some(_) => "SELECT role, content FROM user_messages WHERE thread_id = ?1 AND id < ?2 ORDER BY id ASC LIMIT ?3",
-                None => "SELECT role, content FROM user_messages WHERE thread_id = ?1 ORDER BY id ASC LIMIT ?2",
+                Some(_) => format!("SELECT {} FROM user_messages WHERE thread_id = ?1 AND id < ?2 ORDER BY id ASC LIMIT ?3", RECORD_COLUMNS),
+                None => format!("SELECT {} FROM user_messages WHERE thread_id = ?1 ORDER BY id ASC LIMIT ?2", RECORD_COLUMNS),
             };
-            let mut stmt = conn.prepare(sql).map_err(|e| UserMessageStoreError::Other(e.to_string()))?;
+            let mut stmt = conn.prepare(&sql).map_err(|e| UserMessageStoreError::Other(e.to_string()))?;
             let rows = match before {
-                Some(b) => stmt.query(params![thread_id_for_query, b as i64, limit as i64]),
-                None => stmt.query(params![thread_id_for_query, limit as i64]),
-
+                Some(b) => stmt.query_map(params![thread_id_for_query, b as i64, limit as i64], row_to_record),
+                None => stmt.query_map(params![thread_id_for_query, limit as i64], row_to_record),
             }
             .map_err(|e| UserMessageStoreError::Other(e.to_string()))?;
-            let mut out = Vec::new();
-            let mut rows = rows;
-            while let Some(row) = rows.next().map_err(|e| UserMessageStoreError::Other(e.to_string()))? {
-                let role: String = row.get(0).map_err(|e| UserMessageStoreError::Other(e.to_string()))?;
-                let content: String = row.get(1).map_err(|e| UserMessageStoreError::Other(e.to_string()))?;
-                out.push((role, content));
-            }
-            Ok::<Vec<(String, String)>, UserMessageStoreError>(out)
+            rows.collect::<Result<Vec<_>, _>>()
+                .map_err(|e| UserMessageStoreError::Other(e.to_string()))
         })
         .await
         .map_err(|e| UserMessageStoreError::Other(e.to_string()))??;
-        let messages: Vec<Message> = rows
-            .into_iter()
-            .map(|(role, content)| row_to_message(&role, &content))
-            .collect();
         debug!(
             thread_id,
             before = ?before,
             limit,
-            loaded_count = messages.len(),
-            message_summary = ?messages
+            loaded_count = records.len(),
+            message_summary = ?records
                 .iter()
                 .enumerate()
-                .map(|(idx, msg)| match msg {
+                .map(|(idx, record)| match &record.message {
                     Message::Assistant(payload) => format!(
                         "idx={idx} role=assistant tool_calls={} content_len={}",
                         payload.tool_calls.len(),
@@ -191,15 +330,106 @@ impl UserMessageStore for SqliteUserMessageStore {
                 .collect::<Vec<_>>(),
             "loaded messages from sqlite store"
         );
-        Ok(messages)
+        Ok(records)
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        thread_ids: Option<&[String]>,
+        limit: u32,
+    ) -> Result<Vec<StoredMessage>, UserMessageStoreError> {
+        let Some(fts) = fts_query(query) else {
+            return Ok(vec![]);
+        };
+        if thread_ids.is_some_and(|ids| ids.is_empty()) {
+            return Ok(vec![]);
+        }
+        let thread_ids: Option<Vec<String>> = thread_ids.map(<[String]>::to_vec);
+        let limit = limit.min(1000);
+        let db_path = self.db_path.clone();
+        let read_only = self.read_only;
+        tokio::task::spawn_blocking(move || {
+            let conn = open_conn(&db_path, read_only)?;
+            let mut values: Vec<rusqlite::types::Value> = vec![fts.into(), (limit as i64).into()];
+            let columns: Vec<String> = RECORD_COLUMNS
+                .split(", ")
+                .map(|c| format!("m.{}", c))
+                .collect();
+            let mut sql = format!(
+                "SELECT {} \
+                 FROM user_messages_fts JOIN user_messages m ON m.id = user_messages_fts.rowid \
+                 WHERE user_messages_fts MATCH ?1 AND m.role IN ('user', 'assistant')",
+                columns.join(", ")
+            );
+            if let Some(ids) = thread_ids {
+                let placeholders: Vec<String> =
+                    (0..ids.len()).map(|i| format!("?{}", i + 3)).collect();
+                sql.push_str(&format!(
+                    " AND m.thread_id IN ({})",
+                    placeholders.join(", ")
+                ));
+                values.extend(ids.into_iter().map(rusqlite::types::Value::from));
+            }
+            sql.push_str(" ORDER BY bm25(user_messages_fts) LIMIT ?2");
+            let mut stmt = conn
+                .prepare(&sql)
+                .map_err(|e| UserMessageStoreError::Other(e.to_string()))?;
+            let rows = stmt
+                .query_map(rusqlite::params_from_iter(values), row_to_record)
+                .map_err(|e| UserMessageStoreError::Other(e.to_string()))?;
+            rows.collect::<Result<Vec<_>, _>>()
+                .map_err(|e| UserMessageStoreError::Other(e.to_string()))
+        })
+        .await
+        .map_err(|e| UserMessageStoreError::Other(e.to_string()))?
+    }
+
+    async fn delete(&self, thread_id: &str) -> Result<u64, UserMessageStoreError> {
+        if self.read_only {
+            return Err(read_only_error());
+        }
+        let thread_id = thread_id.to_string();
+        let db_path = self.db_path.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = open_conn(&db_path, false)?;
+            let deleted = conn
+                .execute(
+                    "DELETE FROM user_messages WHERE thread_id = ?1",
+                    params![thread_id],
+                )
+                .map_err(|e| UserMessageStoreError::Other(e.to_string()))?;
+            Ok(deleted as u64)
+        })
+        .await
+        .map_err(|e| UserMessageStoreError::Other(e.to_string()))?
     }
 }
 
+fn read_only_error() -> UserMessageStoreError {
+    UserMessageStoreError::Other("user message store is read-only".to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::NamedTempFile;
 
+    #[tokio::test]
+    async fn read_only_store_lists_but_rejects_appends() {
+        let file = NamedTempFile::new().unwrap();
+        SqliteUserMessageStore::new(file.path())
+            .unwrap()
+            .append("t1", &Message::user("hi"))
+            .await
+            .unwrap();
+        let store = SqliteUserMessageStore::open_read_only(file.path()).unwrap();
+        assert_eq!(store.list("t1", None, None).await.unwrap().len(), 1);
+        assert!(store.append("t1", &Message::user("bye")).await.is_err());
+        assert!(store.delete("t1").await.is_err());
+        assert!(SqliteUserMessageStore::open_read_only("/nonexistent/dir/db.sqlite").is_err());
+    }
+
     #[tokio::test]
     async fn sqlite_append_and_list_order() {
         let file = NamedTempFile::new().unwrap();
@@ -243,6 +473,141 @@ mod tests {
         assert_eq!(page2.len(), 2);
     }
 
+    #[tokio::test]
+    async fn sqlite_search_matches_words_scoped_to_threads() {
+        let file = NamedTempFile::new().unwrap();
+        let store = SqliteUserMessageStore::new(file.path()).unwrap();
+        store
+            .append(
+                "t1",
+                &Message::user("Let's use Postgres for the billing service"),
+            )
+            .await
+            .unwrap();
+        store
+            .append("t1", &Message::assistant("Postgres it is."))
+            .await
+            .unwrap();
+        store
+            .append("t2", &Message::user("What about postgres replicas?"))
+            .await
+            .unwrap();
+        store
+            .append(
+                "t2",
+                &Message::Tool {
+                    tool_call_id: "c1".to_string(),
+                    content: "postgres tool output".into(),
+                },
+            )
+            .await
+            .unwrap();
+
+        let hits = store.search("postgres", None, 10).await.unwrap();
+        assert_eq!(hits.len(), 3, "tool messages are not searched");
+        assert!(hits.iter().all(|h| h.created_at_ms.is_some()));
+
+        let hits = store
+            .search("billing postgres", Some(&["t1".to_string()]), 10)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].thread_id, "t1");
+
+        assert!(store.search("\"unbalanced", None, 10).await.is_ok());
+        assert!(store.search("   ", None, 10).await.unwrap().is_empty());
+        assert!(store
+            .search("postgres", Some(&[]), 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn sqlite_keeps_meta_and_delete_clears_thread_and_index() {
+        let file = NamedTempFile::new().unwrap();
+        let store = SqliteUserMessageStore::new(file.path()).unwrap();
+        let usage = crate::llm::LlmUsage {
+            prompt_tokens: 12,
+            completion_tokens: 3,
+            total_tokens: 15,
+            ..Default::default()
+        };
+        store
+            .append_with_meta(
+                "t1",
+                &Message::assistant("checking the weather"),
+                &MessageMeta {
+                    usage: Some(usage.clone()),
+                    ..MessageMeta::for_run("run-1")
+                },
+            )
+            .await
+            .unwrap();
+        store
+            .append_with_meta(
+                "t1",
+                &Message::Tool {
+                    tool_call_id: "c1".to_string(),
+                    content: "sunny".into(),
+                },
+                &MessageMeta {
+                    tool_name: Some("get_weather".to_string()),
+                    ..MessageMeta::for_run("run-1")
+                },
+            )
+            .await
+            .unwrap();
+        store
+            .append("t2", &Message::user("weather tomorrow?"))
+            .await
+            .unwrap();
+
+        let records = store.list_records("t1", None, None).await.unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].thread_id, "t1");
+        assert_eq!(records[0].meta.run_id.as_deref(), Some("run-1"));
+        assert_eq!(records[0].meta.usage, Some(usage));
+        assert_eq!(records[1].meta.tool_name.as_deref(), Some("get_weather"));
+        assert_eq!(
+            records[1].meta.tool_call_id.as_deref(),
+            Some("c1"),
+            "taken from the tool message"
+        );
+        let hits = store.search("weather", None, 10).await.unwrap();
+        assert_eq!(hits.len(), 2);
+        assert!(hits
+            .iter()
+            .any(|h| h.meta.run_id.as_deref() == Some("run-1")));
+
+        assert_eq!(store.delete("t1").await.unwrap(), 2);
+        assert!(store.list("t1", None, None).await.unwrap().is_empty());
+        let hits = store.search("weather", None, 10).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].thread_id, "t2");
+    }
+
+    #[tokio::test]
+    async fn sqlite_adds_meta_columns_to_old_tables() {
+        let file = NamedTempFile::new().unwrap();
+        {
+            let conn = rusqlite::Connection::open(file.path()).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE user_messages (id INTEGER PRIMARY KEY AUTOINCREMENT, thread_id TEXT NOT NULL, role TEXT NOT NULL, content TEXT NOT NULL);
+                 INSERT INTO user_messages (thread_id, role, content) VALUES ('t1', 'user', 'old message');",
+            )
+            .unwrap();
+        }
+        let store = SqliteUserMessageStore::new(file.path()).unwrap();
+        let records = store.list_records("t1", None, None).await.unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].meta, MessageMeta::default());
+        assert_eq!(records[0].created_at_ms, None);
+        assert_eq!(store.search("old", None, 10).await.unwrap().len(), 1);
+        assert_eq!(store.delete("t1").await.unwrap(), 1);
+        assert!(store.search("old", None, 10).await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn sqlite_append_tool_with_empty_call_id_gets_generated_id_on_read() {
         let file = NamedTempFile::new().unwrap();
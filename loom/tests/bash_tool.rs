@@ -2,7 +2,7 @@
 
 mod init_logging;
 
-use loom::tools::{BashTool, Tool, TOOL_BASH};
+use loom::tools::{BashOutputFormat, BashTool, Tool, TOOL_BASH};
 use serde_json::json;
 
 #[tokio::test]
@@ -53,3 +53,65 @@ async fn bash_tool_default_construction() {
     let tool = BashTool::default();
     assert_eq!(tool.name(), TOOL_BASH);
 }
+
+#[cfg(unix)]
+#[tokio::test]
+async fn bash_tool_structured_output_separates_streams_and_exit_code() {
+    let tool = BashTool::new().with_output_format(BashOutputFormat::Structured);
+    let args = json!({ "command": "echo out; echo err >&2; exit 3" });
+    let result = tool.call(args, None).await.unwrap();
+    let v: serde_json::Value = serde_json::from_str(result.as_text().unwrap()).unwrap();
+    assert_eq!(v["exit_code"], 3);
+    assert_eq!(v["stdout"], "out\n");
+    assert_eq!(v["stderr"], "err\n");
+    assert_eq!(v["timed_out"], false);
+    assert!(v["duration_ms"].is_u64());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn bash_tool_structured_output_reports_timeout() {
+    let tool = BashTool::new().with_output_format(BashOutputFormat::Structured);
+    let args = json!({ "command": "echo started; sleep 5", "timeout": 200 });
+    let result = tool.call(args, None).await.unwrap();
+    let v: serde_json::Value = serde_json::from_str(result.as_text().unwrap()).unwrap();
+    assert_eq!(v["timed_out"], true);
+    assert!(v["exit_code"].is_null());
+    assert_eq!(v["stdout"], "started\n");
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn bash_tool_legacy_output_keeps_concatenated_text() {
+    let tool = BashTool::new().with_output_format(BashOutputFormat::Legacy);
+    let args = json!({ "command": "echo out; echo err >&2" });
+    let result = tool.call(args, None).await.unwrap();
+    assert_eq!(result.as_text().unwrap(), "stdout:\nout\n\nstderr:\nerr\n");
+
+    let args = json!({ "command": "sleep 5", "timeout": 100 });
+    assert!(tool.call(args, None).await.is_err());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn bash_tool_streams_output_lines_as_partial_results() {
+    let chunks = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sink = chunks.clone();
+    let writer = loom::ToolStreamWriter::noop().with_partial_output(move |chunk| {
+        sink.lock().unwrap().push(chunk);
+        true
+    });
+    let ctx = loom::ToolCallContext::with_stream_writer(vec![], writer.clone());
+    let tool = BashTool::new();
+    let args = json!({ "command": "echo one; sleep 0.1; echo two; printf three" });
+    tool.call(args, Some(&ctx)).await.unwrap();
+
+    assert_eq!(writer.partial_output(), "one\ntwo\nthree");
+    let chunks = chunks.lock().unwrap();
+    assert!(
+        chunks.len() >= 3,
+        "expected one chunk per line: {:?}",
+        chunks
+    );
+    assert_eq!(chunks.first().map(String::as_str), Some("one\n"));
+}
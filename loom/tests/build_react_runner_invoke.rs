@@ -1,12 +1,17 @@
-﻿//! Integration test: build_react_runner then invoke (config → runner → one invoke).
+//! Integration test: build_react_runner then invoke (config → runner → one invoke).
 //!
 //! Phase 0 refactoring: ensures the full pipeline from ReactBuildConfig through
 //! build_react_runner and ReactRunner::invoke is covered by a test.
 
 mod init_logging;
 
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
 use loom::{
-    build_react_runner, GotRunnerConfig, MockLlm, ReactBuildConfig, ReactRunner, TotRunnerConfig,
+    build_react_runner, AgentError, ConfidenceScoring, GotRunnerConfig, LoomBuilder, Message,
+    MockLlm, MockScript, Next, NodeHooks, ReActState, ReactBuildConfig, ReactRunner,
+    TotRunnerConfig, REFLECTION_FEEDBACK_PREFIX,
 };
 
 fn minimal_config() -> ReactBuildConfig {
@@ -34,18 +39,51 @@ fn minimal_config() -> ReactBuildConfig {
         model: None,
         llm_provider: None,
         openai_temperature: None,
+        stop_sequences: Vec::new(),
         embedding_api_key: None,
         embedding_base_url: None,
         embedding_model: None,
         working_folder: None,
         approval_policy: None,
         compaction_config: None,
+        history_window: None,
+        state_limits: None,
+        graph_spec: None,
+        llm_routes: Vec::new(),
+        flags: Default::default(),
         tot_config: TotRunnerConfig::default(),
         got_config: GotRunnerConfig::default(),
         mcp_servers: None,
         skill_registry: None,
+        history_search: None,
         max_sub_agent_depth: None,
         dry_run: false,
+        node_models: Default::default(),
+        auto_continue: false,
+        context_fallback_model: None,
+        enable_reflection: false,
+        confidence_scoring: None,
+        dedup_observations: false,
+        route_rules: Vec::new(),
+        approval_rules: Vec::new(),
+        approval_audit_db: None,
+        tool_result_framing: Default::default(),
+        untrusted_tools: Vec::new(),
+        sanitize_tool_results: false,
+        injection_patterns: Vec::new(),
+        memory_recall: None,
+        env_context: false,
+        observation_summary_tokens: None,
+        tool_prefetch: Vec::new(),
+        tool_arguments: Default::default(),
+        allowed_tools: None,
+        read_only: false,
+        denied_tools: Vec::new(),
+        tool_selection: None,
+        tool_call_mode: None,
+        offline: false,
+        offline_script: None,
+        node_middleware: Default::default(),
     }
 }
 
@@ -70,3 +108,161 @@ async fn build_react_runner_then_invoke_one_turn() {
         last
     );
 }
+
+/// Scenario: the fluent builder produces the same runner as a hand-written config.
+#[tokio::test]
+async fn loom_builder_builds_runner() {
+    let runner = LoomBuilder::new(minimal_config())
+        .llm(MockLlm::with_no_tool_calls("Built fluently."))
+        .system_prompt("You are a test agent.")
+        .read_only(true)
+        .build()
+        .await
+        .expect("build");
+    let state = runner.invoke("Hi").await.expect("invoke");
+    assert_eq!(
+        state.last_assistant_reply().as_deref(),
+        Some("Built fluently.")
+    );
+}
+
+/// Scenario: with enable_reflection, the verify pass finds a gap in the first draft, think
+/// answers again, and the second review accepts it (LLM calls: think, verify, think, verify).
+#[tokio::test]
+async fn build_react_runner_with_reflection_revises_draft() {
+    let config = ReactBuildConfig {
+        enable_reflection: true,
+        ..minimal_config()
+    };
+    let script = MockScript::from_yaml(
+        r#"
+responses:
+  - content: "Titanic won."
+  - content: '{"complete": false, "gaps": "The director is not named."}'
+  - content: "Titanic, directed by James Cameron, won."
+  - content: '{"complete": true}'
+"#,
+    )
+    .unwrap();
+    let llm = Box::new(MockLlm::scripted(script));
+    let runner = build_react_runner(&config, Some(llm), false)
+        .await
+        .expect("build_react_runner");
+    let state = runner
+        .invoke("Who directed the 1998 Best Picture winner?")
+        .await
+        .expect("invoke");
+    assert_eq!(state.reflection_count, 1);
+    assert!(state
+        .messages
+        .iter()
+        .any(|m| m.content().starts_with(REFLECTION_FEEDBACK_PREFIX)));
+    assert_eq!(
+        state.last_assistant_reply().as_deref(),
+        Some("Titanic, directed by James Cameron, won.")
+    );
+}
+
+/// Scenario: with reflection and LLM confidence scoring, the accepted answer is scored after
+/// the verify pass (LLM calls: think, verify, confidence).
+#[tokio::test]
+async fn build_react_runner_scores_answer_confidence() {
+    let config = ReactBuildConfig {
+        enable_reflection: true,
+        confidence_scoring: Some(ConfidenceScoring::Llm),
+        ..minimal_config()
+    };
+    let script = MockScript::from_yaml(
+        r#"
+responses:
+  - content: "Canberra."
+  - content: '{"complete": true}'
+  - content: '{"confidence": 0.35, "uncertainties": ["no source was checked"]}'
+"#,
+    )
+    .unwrap();
+    let llm = Box::new(MockLlm::scripted(script));
+    let runner = build_react_runner(&config, Some(llm), false)
+        .await
+        .expect("build_react_runner");
+    let state = runner
+        .invoke("What is the capital of Australia?")
+        .await
+        .expect("invoke");
+    let confidence = state.confidence.expect("confidence");
+    assert_eq!(confidence.score, 0.35);
+    assert_eq!(confidence.uncertainties, vec!["no source was checked"]);
+    assert_eq!(state.last_assistant_reply().as_deref(), Some("Canberra."));
+}
+
+/// Scenario: with env_context, the system prompt's `{env_context}` placeholder is replaced by
+/// the environment block, which lists the working folder.
+#[tokio::test]
+async fn build_react_runner_with_env_context_fills_placeholder() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = ReactBuildConfig {
+        env_context: true,
+        system_prompt: Some("You are a test agent.\n\n{env_context}".to_string()),
+        working_folder: Some(dir.path().to_path_buf()),
+        ..minimal_config()
+    };
+    let llm = Box::new(MockLlm::with_no_tool_calls("ok"));
+    let runner = build_react_runner(&config, Some(llm), false)
+        .await
+        .expect("build_react_runner");
+    let state = runner.invoke("What day is it?").await.expect("invoke");
+    let Message::System(prompt) = &state.messages[0] else {
+        panic!("expected system message");
+    };
+    assert!(prompt.starts_with("You are a test agent.\n\n<environment>\n"));
+    assert!(prompt.contains("Current date and time: "));
+    assert!(prompt.contains(&format!("Working folder: {}", dir.path().display())));
+    assert!(!prompt.contains("{env_context}"));
+}
+
+/// Records every hooked node and tags the reply produced by `think`.
+struct RecordingHooks {
+    seen: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl NodeHooks<ReActState> for RecordingHooks {
+    async fn before(&self, node_id: &str, state: ReActState) -> Result<ReActState, AgentError> {
+        self.seen.lock().unwrap().push(node_id.to_string());
+        Ok(state)
+    }
+
+    async fn after(
+        &self,
+        node_id: &str,
+        mut state: ReActState,
+        next: Next,
+    ) -> Result<(ReActState, Next), AgentError> {
+        if node_id == "think" {
+            if let Some(Message::Assistant(p)) = state.messages.last_mut() {
+                p.content.push_str(" [reviewed]");
+            }
+        }
+        Ok((state, next))
+    }
+}
+
+/// Scenario: hooks registered on ReactBuildConfig for `think` only run around think (not act,
+/// observe or compress) and can rewrite the node's output state.
+#[tokio::test]
+async fn build_react_runner_applies_config_node_hooks() {
+    let hooks = Arc::new(RecordingHooks {
+        seen: Mutex::new(Vec::new()),
+    });
+    let config = minimal_config().with_hooks("think", hooks.clone());
+    let llm = Box::new(MockLlm::first_tools_then_end());
+    let runner = build_react_runner(&config, Some(llm), false)
+        .await
+        .expect("build_react_runner");
+    let state = runner.invoke("What time is it?").await.expect("invoke");
+
+    assert_eq!(hooks.seen.lock().unwrap().as_slice(), &["think", "think"]);
+    assert!(state
+        .last_assistant_reply()
+        .is_some_and(|r| r.ends_with(" [reviewed]")));
+}
@@ -32,18 +32,51 @@ fn base_config(working_folder: std::path::PathBuf) -> ReactBuildConfig {
         model: None,
         llm_provider: None,
         openai_temperature: None,
+        stop_sequences: Vec::new(),
         embedding_api_key: None,
         embedding_base_url: None,
         embedding_model: None,
         working_folder: Some(working_folder),
         approval_policy: None,
         compaction_config: None,
+        history_window: None,
+        state_limits: None,
+        graph_spec: None,
+        llm_routes: Vec::new(),
+        flags: Default::default(),
         tot_config: loom::TotRunnerConfig::default(),
         got_config: loom::GotRunnerConfig::default(),
         mcp_servers: None,
         skill_registry: None,
+        history_search: None,
         max_sub_agent_depth: None,
         dry_run: false,
+        node_models: Default::default(),
+        auto_continue: false,
+        context_fallback_model: None,
+        enable_reflection: false,
+        confidence_scoring: None,
+        dedup_observations: false,
+        route_rules: Vec::new(),
+        approval_rules: Vec::new(),
+        approval_audit_db: None,
+        tool_result_framing: Default::default(),
+        untrusted_tools: Vec::new(),
+        sanitize_tool_results: false,
+        injection_patterns: Vec::new(),
+        memory_recall: None,
+        env_context: false,
+        observation_summary_tokens: None,
+        tool_prefetch: Vec::new(),
+        tool_arguments: Default::default(),
+        allowed_tools: None,
+        read_only: false,
+        denied_tools: Vec::new(),
+        tool_selection: None,
+        tool_call_mode: None,
+        offline: false,
+        offline_script: None,
+        node_middleware: Default::default(),
     }
 }
 
@@ -65,7 +98,7 @@ async fn github_token_none_build_succeeds() {
 async fn github_mcp_invalid_command_build_succeeds_github_skipped() {
     let dir = tempfile::tempdir().unwrap();
     let mut config = base_config(dir.path().to_path_buf());
-    config.github_token = Some("x".to_string());
+    config.github_token = Some("x".into());
     config.mcp_github_cmd = "_nonexistent_command_".to_string();
     config.mcp_github_args = vec!["-y".to_string(), "nonexistent".to_string()];
     let ctx = build_react_run_context(&config)
@@ -6,15 +6,13 @@
 mod init_logging;
 
 use loom::tools::{
-    TOOL_CREATE_DIR, TOOL_DELETE_FILE, TOOL_LS, TOOL_MOVE_FILE, TOOL_READ_FILE, TOOL_WRITE_FILE,
+    TOOL_BASH, TOOL_BATCH, TOOL_CREATE_DIR, TOOL_DELETE_FILE, TOOL_EDIT_FILE, TOOL_GREP, TOOL_LS,
+    TOOL_MOVE_FILE, TOOL_READ_FILE, TOOL_WRITE_FILE,
 };
 use loom::{build_react_run_context, ReactBuildConfig};
 
-/// Scenario: building run context with working_folder set yields tool source that includes file tools.
-#[tokio::test]
-async fn build_tool_source_with_working_folder_includes_file_tools() {
-    let dir = tempfile::tempdir().unwrap();
-    let config = ReactBuildConfig {
+fn config_with_working_folder(dir: &std::path::Path) -> ReactBuildConfig {
+    ReactBuildConfig {
         db_path: None,
         thread_id: None,
         user_id: None,
@@ -38,19 +36,59 @@ async fn build_tool_source_with_working_folder_includes_file_tools() {
         model: None,
         llm_provider: None,
         openai_temperature: None,
+        stop_sequences: Vec::new(),
         embedding_api_key: None,
         embedding_base_url: None,
         embedding_model: None,
-        working_folder: Some(dir.path().to_path_buf()),
+        working_folder: Some(dir.to_path_buf()),
         approval_policy: None,
         compaction_config: None,
+        history_window: None,
+        state_limits: None,
+        graph_spec: None,
+        llm_routes: Vec::new(),
+        flags: Default::default(),
         tot_config: loom::TotRunnerConfig::default(),
         got_config: loom::GotRunnerConfig::default(),
         mcp_servers: None,
         skill_registry: None,
+        history_search: None,
         max_sub_agent_depth: None,
         dry_run: false,
-    };
+        node_models: Default::default(),
+        auto_continue: false,
+        context_fallback_model: None,
+        enable_reflection: false,
+        confidence_scoring: None,
+        dedup_observations: false,
+        route_rules: Vec::new(),
+        approval_rules: Vec::new(),
+        approval_audit_db: None,
+        tool_result_framing: Default::default(),
+        untrusted_tools: Vec::new(),
+        sanitize_tool_results: false,
+        injection_patterns: Vec::new(),
+        memory_recall: None,
+        env_context: false,
+        observation_summary_tokens: None,
+        tool_prefetch: Vec::new(),
+        tool_arguments: Default::default(),
+        allowed_tools: None,
+        read_only: false,
+        denied_tools: Vec::new(),
+        tool_selection: None,
+        tool_call_mode: None,
+        offline: false,
+        offline_script: None,
+        node_middleware: Default::default(),
+    }
+}
+
+/// Scenario: building run context with working_folder set yields tool source that includes file tools.
+#[tokio::test]
+async fn build_tool_source_with_working_folder_includes_file_tools() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config_with_working_folder(dir.path());
     let ctx = build_react_run_context(&config).await.unwrap();
     let tools = ctx.tool_source.list_tools().await.unwrap();
     let names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
@@ -61,3 +99,112 @@ async fn build_tool_source_with_working_folder_includes_file_tools() {
     assert!(names.contains(&TOOL_DELETE_FILE));
     assert!(names.contains(&TOOL_CREATE_DIR));
 }
+
+/// Scenario: in read-only mode only the reading file tools are listed, and calls to write or
+/// shell tools are refused without touching the working folder, also through batch.
+#[tokio::test]
+async fn read_only_mode_drops_and_refuses_write_tools() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut config = config_with_working_folder(dir.path());
+    config.read_only = true;
+    let ctx = build_react_run_context(&config).await.unwrap();
+    let tools = ctx.tool_source.list_tools().await.unwrap();
+    let names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
+    assert!(names.contains(&TOOL_LS), "expected ls in {:?}", names);
+    assert!(names.contains(&TOOL_READ_FILE));
+    assert!(names.contains(&TOOL_GREP));
+    for denied in [
+        TOOL_BASH,
+        TOOL_WRITE_FILE,
+        TOOL_EDIT_FILE,
+        TOOL_MOVE_FILE,
+        TOOL_DELETE_FILE,
+        TOOL_CREATE_DIR,
+    ] {
+        assert!(!names.contains(&denied), "{} listed in {:?}", denied, names);
+    }
+
+    let args = serde_json::json!({"path": "x.txt", "content": "x"});
+    assert!(ctx
+        .tool_source
+        .call_tool(TOOL_WRITE_FILE, args.clone())
+        .await
+        .is_err());
+    let _ = ctx
+        .tool_source
+        .call_tool(
+            TOOL_BATCH,
+            serde_json::json!({"calls": [{"tool": TOOL_WRITE_FILE, "parameters": args}]}),
+        )
+        .await;
+    assert!(!dir.path().join("x.txt").exists());
+}
+
+/// Scenario: a denied tool is refused when called directly and when called inside `batch`.
+#[tokio::test]
+async fn denied_tool_is_refused_inside_batch() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut config = config_with_working_folder(dir.path());
+    config.denied_tools = vec![TOOL_WRITE_FILE.to_string()];
+    let ctx = build_react_run_context(&config).await.unwrap();
+    let names: Vec<String> = ctx
+        .tool_source
+        .list_tools()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|t| t.name)
+        .collect();
+    assert!(names.iter().any(|n| n == TOOL_BATCH), "{:?}", names);
+    assert!(!names.iter().any(|n| n == TOOL_WRITE_FILE), "{:?}", names);
+
+    let args = serde_json::json!({"path": "x.txt", "content": "x"});
+    let err = ctx
+        .tool_source
+        .call_tool(
+            TOOL_BATCH,
+            serde_json::json!({"calls": [
+                {"tool": TOOL_LS, "parameters": {}},
+                {"tool": TOOL_WRITE_FILE, "parameters": args}
+            ]}),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains(TOOL_WRITE_FILE), "{}", err);
+    assert!(!dir.path().join("x.txt").exists());
+}
+
+/// Scenario: with a tool allowlist that includes `batch`, batch sub-calls are held to the same
+/// allowlist, so `batch` cannot reach a tool the run was not given.
+#[tokio::test]
+async fn allowlisted_batch_only_runs_allowed_tools() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+    let mut config = config_with_working_folder(dir.path());
+    config.allowed_tools = Some(vec![TOOL_BATCH.to_string(), TOOL_LS.to_string()]);
+    let ctx = build_react_run_context(&config).await.unwrap();
+
+    let out = ctx
+        .tool_source
+        .call_tool(
+            TOOL_BATCH,
+            serde_json::json!({"calls": [{"tool": TOOL_LS, "parameters": {}}]}),
+        )
+        .await
+        .unwrap();
+    assert!(out.as_text().unwrap().contains("a.txt"), "{:?}", out);
+
+    for tool in [TOOL_BASH, TOOL_DELETE_FILE] {
+        let result = ctx
+            .tool_source
+            .call_tool(
+                TOOL_BATCH,
+                serde_json::json!({"calls": [
+                    {"tool": tool, "parameters": {"command": "rm a.txt", "path": "a.txt"}}
+                ]}),
+            )
+            .await;
+        assert!(result.is_err(), "{} ran through batch: {:?}", tool, result);
+    }
+    assert!(dir.path().join("a.txt").exists());
+}
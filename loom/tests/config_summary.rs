@@ -106,6 +106,7 @@ fn tool_config_summary_section_name_and_entries_with_exa() {
     let s = ToolConfigSummary {
         sources: vec!["memory".into(), "exa".into()],
         exa_url: Some("https://mcp.exa.ai/mcp".into()),
+        breakers: vec![],
     };
     assert_eq!(s.section_name(), "Tools");
     let entries = s.entries();
@@ -117,6 +118,24 @@ fn tool_config_summary_section_name_and_entries_with_exa() {
     );
 }
 
+/// Given breaker states, entries include them as name:state pairs.
+#[test]
+fn tool_config_summary_lists_mcp_breakers() {
+    let s = ToolConfigSummary {
+        sources: vec!["exa".into()],
+        exa_url: None,
+        breakers: vec![
+            ("exa".into(), "open".into()),
+            ("github".into(), "closed".into()),
+        ],
+    };
+    let map: std::collections::HashMap<_, _> = s.entries().into_iter().collect();
+    assert_eq!(
+        map.get("mcp_breakers").map(|v| v.as_str()),
+        Some("exa:open,github:closed")
+    );
+}
+
 // --- EmbeddingConfigSummary ---
 
 /// Given model and api_base, section_name is "Embedding" and entries contain model and api_base.
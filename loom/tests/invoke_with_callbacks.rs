@@ -0,0 +1,87 @@
+//! Integration tests: ReactRunner::invoke_with_callbacks reports answer tokens, tool calls,
+//! node starts and usage to the callbacks and returns the final state.
+
+use loom::helve::ApprovalRules;
+use loom::{
+    Callbacks, LlmUsage, MockLlm, MockToolSource, NodeLlmOverrides, ReactRunner, ToolResultFraming,
+};
+
+fn runner(llm: MockLlm) -> ReactRunner {
+    ReactRunner::new(
+        Box::new(llm),
+        Box::new(MockToolSource::get_time_example()),
+        None,
+        None,
+        None,
+        "You are a test agent.".to_string(),
+        None,
+        None,
+        None,
+        None,
+        false,
+        None,
+        NodeLlmOverrides::default(),
+        0,
+        false,
+        None,
+        ApprovalRules::default(),
+        None,
+        ToolResultFraming::default(),
+        None,
+        Vec::new(),
+        None,
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn callbacks_receive_tokens_tools_nodes_and_usage() {
+    let llm = MockLlm::first_tools_then_end()
+        .with_stream_by_char()
+        .with_usage(LlmUsage {
+            prompt_tokens: 10,
+            completion_tokens: 5,
+            total_tokens: 15,
+            ..LlmUsage::default()
+        });
+    let (mut tokens, mut tool_starts, mut tool_ends, mut nodes, mut total_tokens) =
+        (String::new(), Vec::new(), Vec::new(), Vec::new(), 0);
+
+    let state = runner(llm)
+        .invoke_with_callbacks(
+            "What time is it?",
+            Callbacks::new()
+                .on_token(|token| tokens.push_str(token))
+                .on_tool_start(|name, call_id| {
+                    tool_starts.push(format!("{} {}", name, call_id.unwrap_or_default()))
+                })
+                .on_tool_end(|name, _result, is_error| {
+                    tool_ends.push(format!("{} {}", name, is_error))
+                })
+                .on_node(|node_id| nodes.push(node_id.to_string()))
+                .on_usage(|usage| total_tokens += usage.total_tokens),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        state.last_assistant_reply().as_deref(),
+        Some("The time is as above.")
+    );
+    assert!(tokens.ends_with("The time is as above."), "{tokens:?}");
+    assert_eq!(tool_starts, vec!["get_time call-1"]);
+    assert_eq!(tool_ends, vec!["get_time false"]);
+    assert_eq!(nodes.first().map(String::as_str), Some("think"));
+    assert!(nodes.iter().any(|n| n == "act"), "{nodes:?}");
+    assert!(total_tokens >= 15);
+}
+
+#[tokio::test]
+async fn empty_callbacks_behave_like_invoke() {
+    let state = runner(MockLlm::with_no_tool_calls("plain reply"))
+        .invoke_with_callbacks("hello", Callbacks::default())
+        .await
+        .unwrap();
+
+    assert_eq!(state.last_assistant_reply().as_deref(), Some("plain reply"));
+}
@@ -15,6 +15,7 @@ fn opts(working_folder: PathBuf) -> RunOptions {
         session_id: None,
         thread_id: None,
         agent: None,
+        agent_file: None,
         verbose: false,
         got_adaptive: false,
         display_max_len: 120,
@@ -24,10 +25,19 @@ fn opts(working_folder: PathBuf) -> RunOptions {
         cancellation: None,
         output_timestamp: false,
         dry_run: false,
+        role_setting: None,
+        allowed_tools: None,
+        read_only: false,
+        reply_format: None,
+        reply_schema: None,
+        messages: None,
+        history_search: None,
+        locale: None,
         provider: None,
         base_url: None,
         api_key: None,
         provider_type: None,
+        flags: Default::default(),
     }
 }
 
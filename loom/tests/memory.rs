@@ -42,6 +42,7 @@ async fn memory_saver_put_and_get_tuple() {
             parents: HashMap::new(),
             children: HashMap::new(),
             summary: None,
+            node: None,
         },
     };
     let id = saver.put(&config, &checkpoint).await.unwrap();
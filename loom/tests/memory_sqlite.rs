@@ -45,6 +45,7 @@ async fn sqlite_saver_put_and_get_tuple() {
             parents: HashMap::new(),
             children: HashMap::new(),
             summary: None,
+            node: None,
         },
     };
     let id = saver.put(&config, &checkpoint).await.unwrap();
@@ -104,6 +105,7 @@ async fn sqlite_saver_list() {
             parents: HashMap::new(),
             children: HashMap::new(),
             summary: None,
+            node: None,
         },
     };
     saver.put(&config, &checkpoint).await.unwrap();
@@ -1,6 +1,6 @@
 //! Unit tests for MemoryToolsSource (composite long-term + short-term).
 //!
-//! Verifies list_tools returns 5 tools; call_tool dispatches to store/short-term;
+//! Verifies list_tools returns 6 tools; call_tool dispatches to store/short-term;
 //! set_call_context is forwarded so get_recent_messages sees context.
 
 mod init_logging;
@@ -9,8 +9,8 @@ use async_trait::async_trait;
 use loom::memory::{Embedder, InMemoryStore, InMemoryVectorStore, Store, StoreError};
 use loom::message::Message;
 use loom::tool_source::{
-    MemoryToolsSource, ToolCallContext, ToolSource, TOOL_GET_RECENT_MESSAGES, TOOL_LIST_MEMORIES,
-    TOOL_RECALL, TOOL_REMEMBER, TOOL_SEARCH_MEMORIES,
+    MemoryToolsSource, ToolCallContext, ToolSource, TOOL_FORGET, TOOL_GET_RECENT_MESSAGES,
+    TOOL_LIST_MEMORIES, TOOL_RECALL, TOOL_REMEMBER, TOOL_SEARCH_MEMORIES,
 };
 use serde_json::json;
 use std::sync::Arc;
@@ -41,16 +41,17 @@ impl Embedder for MockEmbedder {
 }
 
 #[tokio::test]
-async fn memory_tools_source_list_tools_returns_five_tools() {
+async fn memory_tools_source_list_tools_returns_six_tools() {
     let store: Arc<dyn Store> = Arc::new(InMemoryStore::new());
     let ns = vec!["memories".to_string()];
     let source = MemoryToolsSource::new(store, ns).await;
     let tools = source.list_tools().await.unwrap();
-    assert_eq!(tools.len(), 5);
+    assert_eq!(tools.len(), 6);
     let names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
     assert!(names.contains(&TOOL_REMEMBER));
     assert!(names.contains(&TOOL_RECALL));
     assert!(names.contains(&TOOL_LIST_MEMORIES));
+    assert!(names.contains(&TOOL_FORGET));
     assert!(names.contains(&TOOL_GET_RECENT_MESSAGES));
 }
 
@@ -5,7 +5,7 @@
 
 mod init_logging;
 
-use loom::{LlmClient, Message, MockLlm};
+use loom::{FinishReason, LlmClient, Message, MockLlm, MockScript};
 
 #[tokio::test]
 async fn mock_llm_with_get_time_returns_content_and_one_tool_call() {
@@ -54,3 +54,86 @@ async fn mock_llm_ignores_input_messages_returns_fixed_response() {
     assert_eq!(out.content, "I'll check the time.");
     assert_eq!(out.tool_calls[0].name, "get_time");
 }
+
+#[tokio::test]
+async fn mock_llm_scripted_replays_responses_then_echoes_user() {
+    let script = MockScript::from_yaml(
+        r#"
+responses:
+  - content: "Checking."
+    tool_calls:
+      - name: ls
+        arguments: { path: "." }
+  - content: "Done."
+"#,
+    )
+    .unwrap();
+    let llm = MockLlm::scripted(script);
+    let messages = vec![Message::user("List files")];
+
+    let first = llm.invoke(&messages).await.unwrap();
+    assert_eq!(first.content, "Checking.");
+    assert_eq!(first.tool_calls.len(), 1);
+    assert_eq!(first.tool_calls[0].name, "ls");
+    assert_eq!(first.tool_calls[0].arguments, r#"{"path":"."}"#);
+
+    let second = llm.invoke(&messages).await.unwrap();
+    assert_eq!(second.content, "Done.");
+    assert!(second.tool_calls.is_empty());
+
+    let third = llm.invoke(&messages).await.unwrap();
+    assert_eq!(third.content, "[offline] List files");
+    assert!(third.tool_calls.is_empty());
+}
+
+#[tokio::test]
+async fn mock_script_builder_appends_replies_in_order() {
+    let script = MockScript::default()
+        .with_tool_call("read", serde_json::json!({ "path": "README.md" }))
+        .with_reply("Read it.");
+    let llm = MockLlm::scripted(script);
+    let messages = vec![Message::user("Summarize the README")];
+
+    let first = llm.invoke(&messages).await.unwrap();
+    assert_eq!(first.tool_calls[0].name, "read");
+    assert_eq!(first.tool_calls[0].arguments, r#"{"path":"README.md"}"#);
+    assert_eq!(first.finish_reason, Some(FinishReason::ToolCalls));
+
+    let second = llm.invoke(&messages).await.unwrap();
+    assert_eq!(second.content, "Read it.");
+    assert!(second.tool_calls.is_empty());
+}
+
+#[tokio::test]
+async fn mock_llm_scripted_matches_messages_and_injects_failures() {
+    let script = MockScript::from_json(
+        r#"{
+  "responses": [
+    { "when": { "last_message_contains": "timeout" }, "content": "Retrying later." },
+    { "error": "rate limited" },
+    { "content": "Recovered.", "finish_reason": "length" }
+  ]
+}"#,
+    )
+    .unwrap();
+    let llm = MockLlm::scripted(script);
+    let messages = vec![Message::user("Fetch the page")];
+
+    let err = llm.invoke(&messages).await.unwrap_err();
+    assert!(err.to_string().contains("rate limited"));
+    assert_eq!(err.code(), Some("rate_limited"));
+
+    let next = llm.invoke(&messages).await.unwrap();
+    assert_eq!(next.content, "Recovered.");
+    assert_eq!(next.finish_reason, Some(FinishReason::Length));
+
+    let failed_tool = vec![
+        Message::user("Fetch the page"),
+        Message::assistant("error: timeout"),
+    ];
+    let matched = llm.invoke(&failed_tool).await.unwrap();
+    assert_eq!(matched.content, "Retrying later.");
+
+    let exhausted = llm.invoke(&failed_tool).await.unwrap();
+    assert_eq!(exhausted.content, "[offline] Fetch the page");
+}
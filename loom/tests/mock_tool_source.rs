@@ -55,6 +55,7 @@ async fn mock_tool_source_new_custom_tools_and_result() {
             description: Some("Search.".to_string()),
             input_schema: json!({ "type": "object", "properties": { "q": {} } }),
             output_hint: None,
+            catalog: Default::default(),
         }],
         "[]".to_string(),
     );
@@ -0,0 +1,82 @@
+//! Integration tests: ReactRunner built from a YAML graph spec instead of the default topology.
+
+use loom::helve::ApprovalRules;
+use loom::{
+    CompilationError, GraphSpec, MockLlm, MockToolSource, NodeLlmOverrides, ReactRunner,
+    ToolResultFraming,
+};
+
+fn runner(llm: MockLlm, spec: &GraphSpec) -> Result<ReactRunner, CompilationError> {
+    ReactRunner::new(
+        Box::new(llm),
+        Box::new(MockToolSource::get_time_example()),
+        None,
+        None,
+        None,
+        "You are a test agent.".to_string(),
+        None,
+        None,
+        None,
+        None,
+        false,
+        None,
+        NodeLlmOverrides::default(),
+        0,
+        false,
+        None,
+        ApprovalRules::default(),
+        None,
+        ToolResultFraming::default(),
+        None,
+        Vec::new(),
+        Some(spec),
+    )
+}
+
+#[tokio::test]
+async fn double_observe_spec_runs_both_observe_steps() {
+    let spec = GraphSpec::from_yaml(
+        r#"
+nodes:
+  think: { type: think }
+  act: { type: act }
+  observe: { type: observe }
+  observe_again: { type: observe, dedup: true }
+edges:
+  - { from: START, to: think }
+  - { from: think, when: tools, routes: { tools: act, END: END } }
+  - { from: act, to: observe }
+  - { from: observe, to: observe_again }
+  - { from: observe_again, to: think }
+"#,
+    )
+    .unwrap();
+    let state = runner(MockLlm::first_tools_then_end(), &spec)
+        .unwrap()
+        .invoke("what time is it?")
+        .await
+        .unwrap();
+    assert_eq!(
+        state.last_assistant_reply().as_deref(),
+        Some("The time is as above.")
+    );
+    assert_eq!(state.turn_count, 2, "each observe step counts a turn");
+}
+
+#[test]
+fn spec_with_unknown_edge_target_fails_to_compile() {
+    let spec = GraphSpec::from_yaml(
+        r#"
+nodes:
+  think: { type: think }
+edges:
+  - { from: START, to: think }
+  - { from: think, to: missing }
+"#,
+    )
+    .unwrap();
+    let err = runner(MockLlm::with_no_tool_calls("unused"), &spec)
+        .err()
+        .expect("compile error");
+    assert!(err.to_string().contains("missing"), "{}", err);
+}
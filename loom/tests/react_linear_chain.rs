@@ -10,8 +10,8 @@ use std::collections::HashMap;
 
 use loom::{
     compress::{build_graph, CompactionConfig, CompressionGraphNode},
-    tools_condition, ActNode, CompiledStateGraph, LlmClient, Message, MockLlm, MockToolSource,
-    ObserveNode, ReActState, StateGraph, ThinkNode, END, START,
+    tools_condition, ActNode, CompiledStateGraph, LlmClient, Message, MockLlm, MockScript,
+    MockToolSource, ObserveNode, ReActState, StateGraph, ThinkNode, END, START,
 };
 
 #[tokio::test]
@@ -42,11 +42,17 @@ async fn react_linear_chain_user_to_tool_result_in_messages() {
         approval_result: None,
         usage: None,
         total_usage: None,
+        usage_by_model: Default::default(),
         message_count_after_last_think: None,
         last_reasoning_content: None,
         think_count: 0,
         summary: None,
         should_continue: true,
+        reflection_count: 0,
+        answer_revision: 0,
+        confidence: None,
+        approval_memory: Default::default(),
+        remembered_approvals: Default::default(),
     };
 
     let out = compiled.invoke(state, None).await.unwrap();
@@ -106,11 +112,17 @@ async fn react_multi_round_loop_then_end() {
         approval_result: None,
         usage: None,
         total_usage: None,
+        usage_by_model: Default::default(),
         message_count_after_last_think: None,
         last_reasoning_content: None,
         think_count: 0,
         summary: None,
         should_continue: true,
+        reflection_count: 0,
+        answer_revision: 0,
+        confidence: None,
+        approval_memory: Default::default(),
+        remembered_approvals: Default::default(),
     };
 
     let out = compiled.invoke(state, None).await.unwrap();
@@ -121,3 +133,49 @@ async fn react_multi_round_loop_then_end() {
     assert!(out.tool_calls.is_empty());
     assert!(out.tool_results.is_empty());
 }
+
+/// Full ReAct loop driven by a JSON MockScript fixture: the tool call is picked by matching the
+/// user message, the final answer by matching the tool result.
+#[tokio::test]
+async fn react_loop_replays_fixture_script() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/mock_script_get_time.json");
+    let script = MockScript::load(&path).expect("fixture");
+    let llm: Arc<dyn LlmClient> = Arc::new(MockLlm::scripted(script));
+    let think_path_map: HashMap<String, String> =
+        [("tools".into(), "act".into()), (END.into(), END.into())]
+            .into_iter()
+            .collect();
+
+    let mut graph = StateGraph::<ReActState>::new();
+    graph
+        .add_node("think", Arc::new(ThinkNode::new(llm)))
+        .add_node(
+            "act",
+            Arc::new(ActNode::new(Box::new(MockToolSource::get_time_example()))),
+        )
+        .add_node("observe", Arc::new(ObserveNode::with_loop()))
+        .add_edge(START, "think")
+        .add_conditional_edges(
+            "think",
+            Arc::new(|s: &ReActState| tools_condition(s).as_str().to_string()),
+            Some(think_path_map),
+        )
+        .add_edge("act", "observe")
+        .add_edge("observe", "think");
+    let compiled: CompiledStateGraph<ReActState> = graph.compile().expect("valid graph");
+
+    let state = ReActState {
+        messages: vec![Message::user("What time is it?")],
+        ..Default::default()
+    };
+    let out = compiled.invoke(state, None).await.unwrap();
+
+    assert!(matches!(&out.messages[1], Message::Assistant(p)
+            if p.tool_calls.len() == 1 && p.tool_calls[0].name == "get_time"));
+    assert!(matches!(&out.messages[2], Message::Tool { .. }));
+    assert_eq!(
+        out.last_assistant_reply().as_deref(),
+        Some("It is 12:00 on 2025-01-29.")
+    );
+}
@@ -10,8 +10,9 @@ mod init_logging;
 #[cfg(unix)]
 use loom::ActiveOperationKind;
 use loom::{
-    run_agent_with_llm_override, run_agent_with_options, AnyStreamEvent, Checkpointer, MockLlm,
-    RunCancellation, RunCmd, RunCompletion, RunOptions, StreamEvent, UserContent,
+    run_agent_with_llm_override, run_agent_with_options, AnyStreamEvent, Checkpointer,
+    HistoryError, HistoryMessage, Message, MockLlm, MockScript, ReplyFormat, RunCancellation,
+    RunCmd, RunCompletion, RunError, RunOptions, StreamEvent, UserContent,
 };
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -32,6 +33,7 @@ fn opts(working_folder: PathBuf) -> RunOptions {
         cancellation: None,
         thread_id: None,
         agent: None,
+        agent_file: None,
         verbose: false,
         got_adaptive: false,
         display_max_len: 120,
@@ -40,10 +42,19 @@ fn opts(working_folder: PathBuf) -> RunOptions {
         mcp_config_path: None,
         output_timestamp: false,
         dry_run: false,
+        role_setting: None,
+        allowed_tools: None,
+        read_only: false,
+        reply_format: None,
+        reply_schema: None,
+        messages: None,
+        history_search: None,
+        locale: None,
         provider: None,
         base_url: None,
         api_key: None,
         provider_type: None,
+        flags: Default::default(),
     }
 }
 
@@ -189,6 +200,7 @@ async fn session_id_restores_context_from_checkpoint() {
         cancellation: None,
         thread_id: Some(session_id.to_string()),
         agent: None,
+        agent_file: None,
         verbose: false,
         got_adaptive: false,
         display_max_len: 120,
@@ -197,10 +209,19 @@ async fn session_id_restores_context_from_checkpoint() {
         mcp_config_path: None,
         output_timestamp: false,
         dry_run: false,
+        role_setting: None,
+        allowed_tools: None,
+        read_only: false,
+        reply_format: None,
+        reply_schema: None,
+        messages: None,
+        history_search: None,
+        locale: None,
         provider: None,
         base_url: None,
         api_key: None,
         provider_type: None,
+        flags: Default::default(),
     };
     let opts2 = RunOptions {
         message: UserContent::Text("Second message".to_string()),
@@ -209,6 +230,7 @@ async fn session_id_restores_context_from_checkpoint() {
         cancellation: None,
         thread_id: Some(session_id.to_string()),
         agent: None,
+        agent_file: None,
         verbose: false,
         got_adaptive: false,
         display_max_len: 120,
@@ -217,10 +239,19 @@ async fn session_id_restores_context_from_checkpoint() {
         mcp_config_path: None,
         output_timestamp: false,
         dry_run: false,
+        role_setting: None,
+        allowed_tools: None,
+        read_only: false,
+        reply_format: None,
+        reply_schema: None,
+        messages: None,
+        history_search: None,
+        locale: None,
         provider: None,
         base_url: None,
         api_key: None,
         provider_type: None,
+        flags: Default::default(),
     };
 
     let result1 = run_agent_with_llm_override(
@@ -388,7 +419,10 @@ async fn run_agent_with_llm_override_returns_cancelled_during_streaming() {
     // For now, just verify that streaming MockLlm works
     match result {
         RunCompletion::Finished(result) => {
-            assert_eq!(result.reply.trim(), "This is a streamed response that should be cancelled.");
+            assert_eq!(
+                result.reply.trim(),
+                "This is a streamed response that should be cancelled."
+            );
         }
         RunCompletion::Cancelled => {
             // This is also acceptable if the run was cancelled somehow
@@ -441,3 +475,160 @@ async fn cancelled_bash_tool_kills_active_child_process() {
     assert_eq!(cancellation.active_operation_kind(), None);
     assert!(matches!(result, RunCompletion::Cancelled));
 }
+
+fn reply_format_opts(dir: &tempfile::TempDir) -> RunOptions {
+    let loom_dir = dir.path().join(".loom");
+    std::fs::create_dir_all(&loom_dir).expect("create .loom");
+    std::fs::write(loom_dir.join("mcp.json"), r#"{"mcpServers":{}}"#).expect("write mcp.json");
+    let mut run_opts = opts(dir.path().to_path_buf());
+    run_opts.reply_format = Some(ReplyFormat::Json);
+    run_opts.reply_schema = Some(serde_json::json!({
+        "type": "object",
+        "required": ["city"],
+        "properties": {"city": {"type": "string"}}
+    }));
+    run_opts
+}
+
+/// Integration test: a JSON reply_format reply that is prose first is retried once; the valid
+/// retry is returned as normalized JSON.
+#[tokio::test]
+async fn json_reply_format_retries_once_and_normalizes_reply() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let run_opts = reply_format_opts(&dir);
+    let script = MockScript::from_yaml(
+        r#"
+responses:
+  - content: "The capital is Paris."
+  - content: "```json\n{\"city\": \"Paris\"}\n```"
+"#,
+    )
+    .unwrap();
+
+    let result = run_agent_with_llm_override(
+        &run_opts,
+        &RunCmd::React,
+        None,
+        Some(Box::new(MockLlm::scripted(script))),
+    )
+    .await
+    .expect("run_agent");
+
+    match result {
+        RunCompletion::Finished(result) => assert_eq!(result.reply, r#"{"city":"Paris"}"#),
+        RunCompletion::Cancelled => panic!("expected finished run"),
+    }
+}
+
+/// Integration test: when the retry still violates the schema, the run fails with a
+/// reply-format error instead of returning the reply.
+#[tokio::test]
+async fn json_reply_format_fails_after_second_violation() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let run_opts = reply_format_opts(&dir);
+    let script = MockScript::from_yaml(
+        r#"
+responses:
+  - content: "Paris."
+  - content: '{"country": "France"}'
+"#,
+    )
+    .unwrap();
+
+    let err = run_agent_with_llm_override(
+        &run_opts,
+        &RunCmd::React,
+        None,
+        Some(Box::new(MockLlm::scripted(script))),
+    )
+    .await
+    .expect_err("format violation");
+    match err {
+        RunError::ReplyFormat(msg) => assert!(msg.contains("missing required property 'city'")),
+        other => panic!("expected reply format error, got {other}"),
+    }
+}
+
+/// Integration test: a client-managed history seeds the run after the system prompt and its
+/// last user message is answered; the thread's checkpoint is not loaded.
+#[tokio::test]
+async fn client_history_seeds_react_state() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let mut run_opts = reply_format_opts(&dir);
+    run_opts.reply_format = None;
+    run_opts.reply_schema = None;
+    run_opts.message = UserContent::default();
+    run_opts.messages = Some(vec![
+        HistoryMessage::system("Answer in French."),
+        HistoryMessage::user("Hello"),
+        HistoryMessage::assistant("Bonjour"),
+        HistoryMessage::user("Thanks"),
+    ]);
+    let last_state = std::sync::Arc::new(Mutex::new(None));
+    let sink = std::sync::Arc::clone(&last_state);
+    let on_event = Box::new(move |ev: AnyStreamEvent| {
+        if let AnyStreamEvent::React(StreamEvent::Values(state)) = ev {
+            *sink.lock().unwrap() = Some(state);
+        }
+    });
+
+    let result = run_agent_with_llm_override(
+        &run_opts,
+        &RunCmd::React,
+        Some(on_event),
+        Some(Box::new(MockLlm::with_no_tool_calls("Merci"))),
+    )
+    .await
+    .expect("run_agent");
+
+    match result {
+        RunCompletion::Finished(result) => assert_eq!(result.reply.trim(), "Merci"),
+        RunCompletion::Cancelled => panic!("expected finished run"),
+    }
+    let state = last_state.lock().unwrap().take().expect("values event");
+    assert!(matches!(state.messages[0], Message::System(_)));
+    assert_eq!(
+        state.messages[1..5],
+        [
+            Message::system("Answer in French."),
+            Message::user("Hello"),
+            Message::assistant("Bonjour"),
+            Message::user("Thanks"),
+        ]
+    );
+}
+
+/// Integration test: an invalid history is rejected before the run, and histories are only
+/// accepted by ReAct.
+#[tokio::test]
+async fn client_history_is_validated() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let mut run_opts = reply_format_opts(&dir);
+    run_opts.messages = Some(vec![
+        HistoryMessage::user("Hello"),
+        HistoryMessage::assistant("Hi"),
+    ]);
+    let err = run_agent_with_llm_override(
+        &run_opts,
+        &RunCmd::React,
+        None,
+        Some(Box::new(MockLlm::with_no_tool_calls("unused"))),
+    )
+    .await
+    .expect_err("last message is not user");
+    assert!(matches!(err, RunError::History(HistoryError::LastNotUser)));
+
+    run_opts.messages = Some(vec![HistoryMessage::user("Hello")]);
+    let err = run_agent_with_llm_override(
+        &run_opts,
+        &RunCmd::Tot,
+        None,
+        Some(Box::new(MockLlm::with_no_tool_calls("unused"))),
+    )
+    .await
+    .expect_err("tot does not take a history");
+    assert!(matches!(
+        err,
+        RunError::History(HistoryError::UnsupportedAgent)
+    ));
+}
@@ -0,0 +1,105 @@
+//! Integration tests: RunHooks registered on a ReactRunner fire on start and end of invoke and
+//! streaming runs, with the final state and the run's thread.
+
+use std::sync::{Arc, Mutex};
+
+use loom::helve::ApprovalRules;
+use loom::memory::RunnableConfig;
+use loom::{
+    MockLlm, MockToolSource, NodeLlmOverrides, ReActState, ReactRunner, RunEnd, RunFailure,
+    RunHooks, RunStart, StreamEvent, ToolResultFraming,
+};
+
+fn runner() -> ReactRunner {
+    ReactRunner::new(
+        Box::new(MockLlm::with_no_tool_calls("hooked reply")),
+        Box::new(MockToolSource::get_time_example()),
+        None,
+        None,
+        Some(RunnableConfig {
+            thread_id: Some("hook-thread".to_string()),
+            ..Default::default()
+        }),
+        "You are a test agent.".to_string(),
+        None,
+        None,
+        None,
+        None,
+        false,
+        None,
+        NodeLlmOverrides::default(),
+        0,
+        false,
+        None,
+        ApprovalRules::default(),
+        None,
+        ToolResultFraming::default(),
+        None,
+        Vec::new(),
+        None,
+    )
+    .unwrap()
+}
+
+/// Hooks that record each call as a line in `log`.
+fn recording_hooks(log: &Arc<Mutex<Vec<String>>>) -> RunHooks<ReActState> {
+    let (on_start, on_end, on_error) = (log.clone(), log.clone(), log.clone());
+    RunHooks::new()
+        .on_start(move |start: RunStart| {
+            let log = on_start.clone();
+            async move {
+                log.lock()
+                    .unwrap()
+                    .push(format!("start {}", start.thread_id.unwrap_or_default()));
+            }
+        })
+        .on_end(move |end: RunEnd<ReActState>| {
+            let log = on_end.clone();
+            async move {
+                let reply = end.state.last_assistant_reply().unwrap_or_default();
+                log.lock().unwrap().push(format!(
+                    "end {} {}",
+                    end.thread_id.unwrap_or_default(),
+                    reply
+                ));
+            }
+        })
+        .on_error(move |failure: RunFailure| {
+            let log = on_error.clone();
+            async move {
+                log.lock().unwrap().push(format!("error {}", failure.error));
+            }
+        })
+}
+
+#[tokio::test]
+async fn hooks_fire_around_invoke() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let runner = runner().with_hooks(recording_hooks(&log));
+
+    runner.invoke("hello").await.unwrap();
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec!["start hook-thread", "end hook-thread hooked reply"]
+    );
+}
+
+#[tokio::test]
+async fn hooks_fire_around_streaming_runs() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let runner = runner().with_hooks(recording_hooks(&log));
+
+    for _ in 0..2 {
+        runner
+            .stream_with_callback("hello", None::<fn(StreamEvent<ReActState>)>)
+            .await
+            .unwrap();
+    }
+
+    let log = log.lock().unwrap();
+    assert_eq!(log.len(), 4, "{log:?}");
+    assert_eq!(log[0], "start hook-thread");
+    assert_eq!(log[1], "end hook-thread hooked reply");
+    assert_eq!(log[2..], log[..2]);
+}
@@ -0,0 +1,113 @@
+//! Integration tests: ReactRunner::state_snapshot reads the latest checkpoint without running the
+//! graph; tool sources can be added and removed between turns.
+
+use std::sync::Arc;
+
+use loom::helve::ApprovalRules;
+use loom::memory::{Checkpointer, MemorySaver, RunnableConfig};
+use loom::{
+    MockLlm, MockToolSource, NodeLlmOverrides, ReActState, ReactRunner, ToolResultFraming, ToolSpec,
+};
+
+fn runner(checkpointer: Option<Arc<dyn Checkpointer<ReActState>>>) -> ReactRunner {
+    ReactRunner::new(
+        Box::new(MockLlm::with_no_tool_calls("snapshot reply")),
+        Box::new(MockToolSource::get_time_example()),
+        checkpointer,
+        None,
+        Some(RunnableConfig {
+            thread_id: Some("snap-thread".to_string()),
+            ..Default::default()
+        }),
+        "You are a test agent.".to_string(),
+        None,
+        None,
+        None,
+        None,
+        false,
+        None,
+        NodeLlmOverrides::default(),
+        0,
+        false,
+        None,
+        ApprovalRules::default(),
+        None,
+        ToolResultFraming::default(),
+        None,
+        Vec::new(),
+        None,
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn state_snapshot_returns_latest_checkpoint_after_invoke() {
+    let saver: Arc<dyn Checkpointer<ReActState>> = Arc::new(MemorySaver::new());
+    let runner = runner(Some(saver));
+
+    assert!(runner
+        .state_snapshot("snap-thread")
+        .await
+        .unwrap()
+        .is_none());
+
+    runner.invoke("hello").await.unwrap();
+
+    let snapshot = runner
+        .state_snapshot("snap-thread")
+        .await
+        .unwrap()
+        .expect("checkpointed state");
+    assert_eq!(
+        snapshot.last_assistant_reply().as_deref(),
+        Some("snapshot reply")
+    );
+    assert!(runner
+        .state_snapshot("other-thread")
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn state_snapshot_without_checkpointer_is_none() {
+    let runner = runner(None);
+    assert!(runner
+        .state_snapshot("snap-thread")
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn tool_sources_can_be_added_and_removed_between_turns() {
+    let runner = runner(None);
+    runner.invoke("hello").await.unwrap();
+
+    let tools = runner
+        .add_tool_source(
+            "project",
+            Box::new(MockToolSource::new(
+                vec![ToolSpec {
+                    name: "query_db".to_string(),
+                    description: None,
+                    input_schema: serde_json::json!({ "type": "object" }),
+                    output_hint: None,
+                    catalog: Default::default(),
+                }],
+                "rows".to_string(),
+            )),
+        )
+        .await
+        .unwrap();
+    assert_eq!(tools, vec!["query_db".to_string()]);
+    assert_eq!(runner.tool_source_names(), vec!["project".to_string()]);
+    assert!(runner
+        .add_tool_source("clash", Box::new(MockToolSource::get_time_example()))
+        .await
+        .is_err());
+
+    runner.invoke("again").await.unwrap();
+    assert!(runner.remove_tool_source("project"));
+    assert!(runner.tool_source_names().is_empty());
+}
@@ -9,6 +9,7 @@ fn opts(working_folder: std::path::PathBuf) -> RunOptions {
         session_id: None,
         thread_id: None,
         agent: None,
+        agent_file: None,
         verbose: false,
         got_adaptive: false,
         display_max_len: 2000,
@@ -22,6 +23,15 @@ fn opts(working_folder: std::path::PathBuf) -> RunOptions {
         cancellation: None,
         output_timestamp: false,
         dry_run: false,
+        role_setting: None,
+        allowed_tools: None,
+        read_only: false,
+        reply_format: None,
+        reply_schema: None,
+        messages: None,
+        history_search: None,
+        locale: None,
+        flags: Default::default(),
     }
 }
 
@@ -1,14 +1,14 @@
 //! Unit tests for StoreToolSource.
 //!
-//! Verifies list_tools returns 4 tools; remember → recall consistent; recall missing key
-//! returns not found; list_memories / search_memories behavior.
+//! Verifies list_tools returns 5 tools; remember → recall consistent; recall missing key
+//! returns not found; list_memories / search_memories / forget behavior; remember with a TTL.
 
 mod init_logging;
 
 use async_trait::async_trait;
 use loom::memory::{Embedder, InMemoryStore, InMemoryVectorStore, Store, StoreError};
 use loom::tool_source::{
-    StoreToolSource, ToolSource, TOOL_LIST_MEMORIES, TOOL_RECALL, TOOL_REMEMBER,
+    StoreToolSource, ToolSource, TOOL_FORGET, TOOL_LIST_MEMORIES, TOOL_RECALL, TOOL_REMEMBER,
     TOOL_SEARCH_MEMORIES,
 };
 use serde_json::json;
@@ -40,17 +40,18 @@ impl Embedder for MockEmbedder {
 }
 
 #[tokio::test]
-async fn store_tool_source_list_tools_returns_four_tools() {
+async fn store_tool_source_list_tools_returns_five_tools() {
     let store: Arc<dyn Store> = Arc::new(InMemoryStore::new());
     let ns = vec!["memories".to_string()];
     let source = StoreToolSource::new(store, ns).await;
     let tools = source.list_tools().await.unwrap();
-    assert_eq!(tools.len(), 4);
+    assert_eq!(tools.len(), 5);
     let names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
     assert!(names.contains(&TOOL_REMEMBER));
     assert!(names.contains(&TOOL_RECALL));
     assert!(names.contains(&TOOL_SEARCH_MEMORIES));
     assert!(names.contains(&TOOL_LIST_MEMORIES));
+    assert!(names.contains(&TOOL_FORGET));
 }
 
 #[tokio::test]
@@ -177,3 +178,79 @@ async fn store_tool_source_remember_search_with_vector_store() {
         .iter()
         .any(|h| h.get("key").and_then(|v| v.as_str()) == Some("rust")));
 }
+
+#[tokio::test]
+async fn store_tool_source_list_memories_filters_by_prefix() {
+    let store: Arc<dyn Store> = Arc::new(InMemoryStore::new());
+    let ns = vec!["memories".to_string()];
+    let source = StoreToolSource::new(store, ns).await;
+
+    for key in ["project:a", "project:b", "pref:theme"] {
+        source
+            .call_tool(TOOL_REMEMBER, json!({ "key": key, "value": 1 }))
+            .await
+            .unwrap();
+    }
+
+    let r = source
+        .call_tool(TOOL_LIST_MEMORIES, json!({ "prefix": "project:" }))
+        .await
+        .unwrap();
+    let keys: Vec<String> = serde_json::from_str(r.as_text().unwrap()).unwrap();
+    assert_eq!(keys, vec!["project:a".to_string(), "project:b".to_string()]);
+}
+
+#[tokio::test]
+async fn store_tool_source_forget_removes_keys() {
+    let store: Arc<dyn Store> = Arc::new(InMemoryStore::new());
+    let ns = vec!["memories".to_string()];
+    let source = StoreToolSource::new(store, ns).await;
+
+    for key in ["a", "b", "c"] {
+        source
+            .call_tool(TOOL_REMEMBER, json!({ "key": key, "value": 1 }))
+            .await
+            .unwrap();
+    }
+
+    let r = source
+        .call_tool(TOOL_FORGET, json!({ "key": "a", "keys": ["c", "missing"] }))
+        .await
+        .unwrap();
+    assert_eq!(r.as_text().unwrap(), "ok");
+
+    let r = source
+        .call_tool(TOOL_LIST_MEMORIES, json!({}))
+        .await
+        .unwrap();
+    let keys: Vec<String> = serde_json::from_str(r.as_text().unwrap()).unwrap();
+    assert_eq!(keys, vec!["b".to_string()]);
+
+    assert!(source.call_tool(TOOL_FORGET, json!({})).await.is_err());
+}
+
+#[tokio::test]
+async fn store_tool_source_remember_with_ttl() {
+    let store: Arc<dyn Store> = Arc::new(InMemoryStore::new());
+    let ns = vec!["memories".to_string()];
+    let source = StoreToolSource::new(store.clone(), ns.clone()).await;
+
+    source
+        .call_tool(
+            TOOL_REMEMBER,
+            json!({ "key": "temp", "value": "x", "ttl_seconds": 60 }),
+        )
+        .await
+        .unwrap();
+    let item = store.get_item(&ns, "temp").await.unwrap().unwrap();
+    assert!(item.expires_at.is_some());
+
+    let err = source
+        .call_tool(
+            TOOL_REMEMBER,
+            json!({ "key": "temp", "value": "x", "ttl_seconds": 0 }),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("ttl_seconds"));
+}
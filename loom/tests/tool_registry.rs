@@ -28,6 +28,7 @@ impl Tool for MockTool {
             description: None,
             input_schema: serde_json::json!({}),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
@@ -46,6 +46,7 @@ impl Tool for StreamingTool {
                 "required": []
             }),
             output_hint: None,
+            catalog: Default::default(),
         }
     }
 
@@ -0,0 +1,244 @@
+//! Access log: one structured record per request handled on a WebSocket connection.
+//!
+//! Each record carries the connection id, the request's sequence number on that connection,
+//! request type and id, the run id (for `run`), duration, bytes sent and the result status.
+//! Records go to `tracing` (target `serve::access`) and, when `SERVE_ACCESS_LOG` names a file,
+//! are appended to it as NDJSON.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use loom::ServerResponse;
+use serde_json::json;
+
+static NEXT_CONNECTION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Process-unique id for a new connection (starting at 1).
+pub(crate) fn next_connection_id() -> u64 {
+    NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// How a request ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum AccessStatus {
+    Ok,
+    /// An `error` response was sent (rejected, parse error, run failed, ...).
+    Error,
+    /// The run was cancelled.
+    Cancelled,
+    /// The connection failed while handling the request.
+    Failed,
+}
+
+impl AccessStatus {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Error => "error",
+            Self::Cancelled => "cancelled",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// Record for one request; filled in while the request is handled.
+#[derive(Debug)]
+pub(crate) struct AccessRecord {
+    pub(crate) connection_id: u64,
+    /// 1-based position of the request on its connection.
+    pub(crate) seq: u64,
+    /// Wire name of the request (`run`, `tools_list`, ...); `invalid` when it did not parse.
+    pub(crate) request_type: &'static str,
+    pub(crate) request_id: Option<String>,
+    pub(crate) run_id: Option<String>,
+    pub(crate) status: AccessStatus,
+    pub(crate) error: Option<String>,
+    /// Encoded size of all frames sent for this request.
+    pub(crate) bytes_sent: u64,
+    pub(crate) duration: Duration,
+}
+
+impl AccessRecord {
+    pub(crate) fn new(connection_id: u64, seq: u64) -> Self {
+        Self {
+            connection_id,
+            seq,
+            request_type: "invalid",
+            request_id: None,
+            run_id: None,
+            status: AccessStatus::Ok,
+            error: None,
+            bytes_sent: 0,
+            duration: Duration::ZERO,
+        }
+    }
+
+    /// Accounts for a response of `bytes` sent for this request: run ids from run events, and
+    /// the status from `error` responses.
+    pub(crate) fn observe(&mut self, response: &ServerResponse, bytes: usize) {
+        self.bytes_sent += bytes as u64;
+        match response {
+            ServerResponse::RunStreamEvent(r) if self.run_id.is_none() => {
+                self.run_id = Some(r.id.clone());
+            }
+            ServerResponse::RunEnd(r) => {
+                self.run_id.get_or_insert_with(|| r.id.clone());
+            }
+            ServerResponse::Error(e) => {
+                self.status = if e.error == "run cancelled" {
+                    AccessStatus::Cancelled
+                } else {
+                    AccessStatus::Error
+                };
+                self.error = Some(e.error.clone());
+            }
+            _ => {}
+        }
+    }
+
+    /// Marks the request as failed by a connection error.
+    pub(crate) fn fail(&mut self, error: impl ToString) {
+        self.status = AccessStatus::Failed;
+        self.error = Some(error.to_string());
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "connection_id": self.connection_id,
+            "seq": self.seq,
+            "request_type": self.request_type,
+            "request_id": self.request_id,
+            "run_id": self.run_id,
+            "status": self.status.as_str(),
+            "error": self.error,
+            "bytes_sent": self.bytes_sent,
+            "duration_ms": self.duration.as_millis() as u64,
+        })
+    }
+}
+
+/// Sink for [`AccessRecord`]s: always `tracing`, plus an NDJSON file when configured.
+#[derive(Default)]
+pub(crate) struct AccessLog {
+    file: Option<Mutex<File>>,
+}
+
+impl AccessLog {
+    /// Appends to the file named by `SERVE_ACCESS_LOG`, if set; an unopenable file is logged
+    /// and skipped.
+    pub(crate) fn from_env() -> Self {
+        match std::env::var("SERVE_ACCESS_LOG") {
+            Ok(path) if !path.trim().is_empty() => Self::open(Path::new(path.trim()))
+                .unwrap_or_else(|e| {
+                    tracing::warn!("SERVE_ACCESS_LOG {}: {}", path, e);
+                    Self::default()
+                }),
+            _ => Self::default(),
+        }
+    }
+
+    pub(crate) fn open(path: &Path) -> std::io::Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        Ok(Self {
+            file: Some(Mutex::new(file)),
+        })
+    }
+
+    pub(crate) fn record(&self, record: &AccessRecord) {
+        tracing::info!(
+            target: "serve::access",
+            connection_id = record.connection_id,
+            seq = record.seq,
+            request_type = record.request_type,
+            request_id = record.request_id.as_deref(),
+            run_id = record.run_id.as_deref(),
+            status = record.status.as_str(),
+            error = record.error.as_deref(),
+            bytes_sent = record.bytes_sent,
+            duration_ms = record.duration.as_millis() as u64,
+            "request handled"
+        );
+        if let Some(file) = &self.file {
+            let mut file = file.lock().unwrap_or_else(|e| e.into_inner());
+            if let Err(e) = writeln!(file, "{}", record.to_json()) {
+                tracing::warn!("failed to write access log: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use loom::{ErrorResponse, ProtocolEvent, ProtocolEventEnvelope, RunStreamEventResponse};
+
+    fn run_event(run_id: &str) -> ServerResponse {
+        ServerResponse::RunStreamEvent(RunStreamEventResponse {
+            id: run_id.to_string(),
+            event: ProtocolEventEnvelope {
+                session_id: None,
+                node_id: None,
+                event_id: None,
+                prev_event_id: None,
+                event: ProtocolEvent::NodeEnter {
+                    id: "think".to_string(),
+                },
+            },
+        })
+    }
+
+    #[test]
+    fn observe_tracks_bytes_run_id_and_status() {
+        let mut record = AccessRecord::new(3, 1);
+        record.observe(&run_event("run-1"), 40);
+        record.observe(&run_event("run-1"), 60);
+        assert_eq!(record.bytes_sent, 100);
+        assert_eq!(record.run_id.as_deref(), Some("run-1"));
+        assert_eq!(record.status, AccessStatus::Ok);
+
+        record.observe(
+            &ServerResponse::Error(ErrorResponse {
+                id: Some("run-1".to_string()),
+                error: "run cancelled".to_string(),
+                code: None,
+            }),
+            20,
+        );
+        assert_eq!(record.status, AccessStatus::Cancelled);
+        assert_eq!(record.bytes_sent, 120);
+    }
+
+    #[test]
+    fn record_appends_ndjson_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("access.ndjson");
+        let log = AccessLog::open(&path).unwrap();
+        let mut record = AccessRecord::new(7, 2);
+        record.request_type = "tools_list";
+        record.request_id = Some("req-1".to_string());
+        log.record(&record);
+        record.seq = 3;
+        record.fail("connection reset");
+        log.record(&record);
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = text
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["connection_id"], 7);
+        assert_eq!(lines[0]["request_type"], "tools_list");
+        assert_eq!(lines[0]["status"], "ok");
+        assert_eq!(lines[1]["seq"], 3);
+        assert_eq!(lines[1]["status"], "failed");
+        assert_eq!(lines[1]["error"], "connection reset");
+    }
+}
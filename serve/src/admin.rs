@@ -0,0 +1,50 @@
+//! Token check for privileged requests (`admin_reload`, `active_runs`, `run_inspect`,
+//! `run_kill`): the request's token must match `SERVE_ADMIN_TOKEN`. When the variable is unset
+//! or empty, these requests are disabled.
+
+use loom::{ErrorResponse, ServerResponse, ERROR_CODE_UNAUTHORIZED};
+
+/// Env var holding the token admin requests must present. Unset = admin requests disabled.
+pub(crate) const ADMIN_TOKEN_ENV: &str = "SERVE_ADMIN_TOKEN";
+
+/// Checks `token` of the `kind` request `id` against `SERVE_ADMIN_TOKEN`. `Err` holds the
+/// `unauthorized` error to send back.
+pub(crate) fn authorize(kind: &str, id: &str, token: &str) -> Result<(), ServerResponse> {
+    let expected = std::env::var(ADMIN_TOKEN_ENV)
+        .ok()
+        .filter(|t| !t.is_empty());
+    let authorized = expected
+        .as_deref()
+        .is_some_and(|t| token_eq(t.as_bytes(), token.as_bytes()));
+    if authorized {
+        return Ok(());
+    }
+    tracing::warn!("⚠️  Rejected {}: bad or unconfigured token", kind);
+    Err(ServerResponse::Error(ErrorResponse {
+        id: Some(id.to_string()),
+        error: if expected.is_some() {
+            "invalid admin token".to_string()
+        } else {
+            format!("{} disabled: {} not set", kind, ADMIN_TOKEN_ENV)
+        },
+        code: Some(ERROR_CODE_UNAUTHORIZED.to_string()),
+    }))
+}
+
+/// Compares tokens without returning early on the first differing byte.
+pub(crate) fn token_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_eq_requires_identical_bytes() {
+        assert!(token_eq(b"secret", b"secret"));
+        assert!(!token_eq(b"secret", b"secreT"));
+        assert!(!token_eq(b"secret", b"secret2"));
+        assert!(!token_eq(b"", b"x"));
+    }
+}
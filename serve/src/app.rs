@@ -1,29 +1,80 @@
 //! Axum app: state, router, and WebSocket upgrade handler.
 //!
-//! Single route: `GET /` upgrades to WebSocket; each connection is handled by [`handle_socket`]
-//! with shared state (workspace store, user message store, run config, optional shutdown).
+//! Routes: `GET /` upgrades to WebSocket, and each connection is handled by [`handle_socket`]
+//! with shared state (workspace and user message stores, run config, optional shutdown).
+//! The run config is a [`SharedRunConfig`]: connections read a snapshot per request, so a reload
+//! (see [`crate::reload`]) applies to subsequent requests without dropping connections.
+//! `GET /schema` returns the protocol's JSON Schema ([`loom::protocol::protocol_schema`]);
+//! `GET /healthz` reports store health (see [`crate::stores`]). `POST /hooks/github` and
+//! `POST /hooks/gitlab` start runs from issue and pull/merge request comments (see
+//! [`crate::hooks`]).
 
 use axum::{
     extract::{ws::WebSocketUpgrade, State},
-    response::Response,
-    routing::get,
+    response::{Json, Response},
+    routing::{get, post},
     Router,
 };
-use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
 use tokio::sync::oneshot;
 
+use super::access_log::AccessLog;
 use super::connection::handle_socket;
+use super::hooks::{github_hook_handler, gitlab_hook_handler, HookConfig};
+use super::limits::{request_limits_from_env, RequestLimits};
+use super::models::ModelCatalog;
+use super::run::{ActiveRuns, DetachedRuns, WorkerPool};
+use super::session::Sessions;
+use super::stores::Stores;
 use loom::llm::ProviderConfig;
+use loom::protocol::encoding::{SUBPROTOCOL_JSON, SUBPROTOCOL_JSON_DEFLATE, SUBPROTOCOL_MSGPACK};
 
-/// Run-related server configuration (queue capacities and display limits).
-#[derive(Clone)]
+/// Run-related server configuration (queue capacities, display limits, request limits,
+/// auto-summarize, server-wide role, tool allowlist, read-only mode, builder defaults and run
+/// hooks). Serializable so each run sent to a worker process carries the server's settings
+/// (see [`crate::run::WorkerPool`]); run hooks stay with the server, which fires them.
+#[derive(Clone, Serialize, Deserialize)]
 pub(crate) struct RunConfig {
-    /// Max protocol events buffered between run task and WebSocket sender.
+    /// Max data events (chunks, values) buffered between run task and WebSocket sender; control
+    /// events such as tool approvals are never dropped.
     pub(crate) event_queue_capacity: usize,
     /// Max (thread_id, message) pairs buffered for the append-to-store task.
     pub(crate) append_queue_capacity: usize,
     /// Max length for truncated display strings in run/tools.
     pub(crate) display_max_len: usize,
+    /// When true, a finished run with a thread_id gets a generated title + summary
+    /// (stored in the workspace store and sent as a `thread_summary` event).
+    pub(crate) auto_summarize: bool,
+    /// Model used for the title/summary call; `None` uses the run's model.
+    pub(crate) summary_model: Option<String>,
+    /// Max incoming message/attachment size and JSON depth.
+    pub(crate) limits: RequestLimits,
+    /// Role setting for runs whose workspace sets none (contents of `SERVE_ROLE_FILE`).
+    pub(crate) role_setting: Option<String>,
+    /// Server-wide tool allowlist; `None` allows every tool.
+    pub(crate) allowed_tools: Option<Vec<String>>,
+    /// When true, every run is read-only (no shell or file-modifying tools), whatever the
+    /// request's `read_only` says.
+    pub(crate) read_only: bool,
+    /// Model for runs whose request and workspace name none; `None` uses the configured default.
+    pub(crate) default_model: Option<String>,
+    /// Normalization applied to each run's user message (control chars, newlines, max length).
+    pub(crate) input_policy: loom::InputPolicy,
+    /// `values` events whose state serializes to more bytes than this are sent with a state
+    /// digest instead; `None` sends full state.
+    pub(crate) values_max_bytes: Option<usize>,
+    /// When set, every run's LLM is a [`loom::MockLlm`] replaying this script from the start
+    /// (the `test-util` harness sets it); `None` builds the LLM from config.
+    pub(crate) llm_script: Option<loom::MockScript>,
+    /// Start/end/error callbacks fired for every run (WebSocket, gRPC and webhook); registered
+    /// with [`crate::router_with_hooks`], kept across reloads.
+    #[serde(skip)]
+    pub(crate) run_hooks: loom::RunHooks<loom::RunEndResponse>,
+    /// Settings of the [`loom::LoomBuilder`] the server was built from; kept across reloads and
+    /// re-applied over the reloaded environment (see [`with_builder_defaults`]).
+    pub(crate) builder: Option<Arc<BuilderDefaults>>,
 }
 
 impl Default for RunConfig {
@@ -32,15 +83,39 @@ impl Default for RunConfig {
             event_queue_capacity: 128,
             append_queue_capacity: 64,
             display_max_len: 2000,
+            auto_summarize: false,
+            summary_model: None,
+            limits: RequestLimits::default(),
+            role_setting: None,
+            allowed_tools: None,
+            read_only: false,
+            default_model: None,
+            input_policy: loom::InputPolicy::default(),
+            values_max_bytes: None,
+            llm_script: None,
+            run_hooks: loom::RunHooks::default(),
+            builder: None,
         }
     }
 }
 
 /// Builds RunConfig from environment variables, falling back to [`Default`] for unset or invalid values.
 ///
-/// - `SERVE_EVENT_QUEUE_CAPACITY` (default 128)
+/// - `SERVE_EVENT_QUEUE_CAPACITY` (data events buffered per run; default 128)
 /// - `SERVE_APPEND_QUEUE_CAPACITY` (default 64)
 /// - `SERVE_DISPLAY_MAX_LEN` (default 2000)
+/// - `SERVE_AUTO_SUMMARIZE` (`1`/`true`/`yes` to enable; default off)
+/// - `SERVE_SUMMARY_MODEL` (model for the title/summary call; default: the run's model)
+/// - `SERVE_MAX_MESSAGE_BYTES`, `SERVE_MAX_ATTACHMENT_BYTES`, `SERVE_MAX_JSON_DEPTH` (see [`request_limits_from_env`])
+/// - `SERVE_ROLE_FILE` (file whose contents are the default role setting; read now, not per run)
+/// - `SERVE_ALLOWED_TOOLS` (comma-separated tool names; default: all tools)
+/// - `SERVE_READ_ONLY` (`1`/`true`/`yes` to make every run read-only; default off)
+/// - `SERVE_DEFAULT_MODEL` (model for runs that name none; default: the configured default)
+/// - `SERVE_INPUT_MAX_CHARS` (max characters of a user message; default: `LOOM_INPUT_MAX_CHARS`,
+///   else no limit), plus `LOOM_INPUT_STRIP_CONTROL` / `LOOM_INPUT_NORMALIZE_NEWLINES`
+///   (see [`loom::InputPolicy::from_env`])
+/// - `SERVE_VALUES_MAX_BYTES` (`values` events with larger state carry a digest instead; 0 or
+///   unset sends full state)
 pub(crate) fn run_config_from_env() -> RunConfig {
     let default = RunConfig::default();
     RunConfig {
@@ -56,6 +131,172 @@ pub(crate) fn run_config_from_env() -> RunConfig {
             .ok()
             .and_then(|s| s.parse().ok())
             .unwrap_or(default.display_max_len),
+        auto_summarize: std::env::var("SERVE_AUTO_SUMMARIZE")
+            .map(|s| matches!(s.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(default.auto_summarize),
+        summary_model: std::env::var("SERVE_SUMMARY_MODEL")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .or(default.summary_model),
+        limits: request_limits_from_env(),
+        role_setting: std::env::var("SERVE_ROLE_FILE")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .and_then(|path| match std::fs::read_to_string(&path) {
+                Ok(text) => Some(text),
+                Err(e) => {
+                    tracing::warn!("SERVE_ROLE_FILE {}: {}", path, e);
+                    None
+                }
+            })
+            .or(default.role_setting),
+        allowed_tools: std::env::var("SERVE_ALLOWED_TOOLS")
+            .ok()
+            .map(|s| {
+                s.split(',')
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty())
+                    .collect::<Vec<_>>()
+            })
+            .filter(|tools| !tools.is_empty())
+            .or(default.allowed_tools),
+        read_only: std::env::var("SERVE_READ_ONLY")
+            .map(|s| matches!(s.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(default.read_only),
+        default_model: std::env::var("SERVE_DEFAULT_MODEL")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .or(default.default_model),
+        input_policy: input_policy_from_env(),
+        values_max_bytes: std::env::var("SERVE_VALUES_MAX_BYTES")
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+            .filter(|&n: &usize| n > 0)
+            .or(default.values_max_bytes),
+        llm_script: default.llm_script,
+        run_hooks: default.run_hooks,
+        builder: default.builder,
+    }
+}
+
+fn input_policy_from_env() -> loom::InputPolicy {
+    let mut policy = loom::InputPolicy::from_env();
+    if let Ok(s) = std::env::var("SERVE_INPUT_MAX_CHARS") {
+        policy.max_chars = s.trim().parse().ok().filter(|n: &usize| *n > 0);
+    }
+    policy
+}
+
+/// Settings of a [`loom::LoomBuilder`] that serve runs honor.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct BuilderDefaults {
+    /// Model for runs whose request and workspace name none.
+    pub(crate) model: Option<String>,
+    /// Default role (system prompt) for runs whose workspace sets none.
+    pub(crate) system_prompt: Option<String>,
+    /// Tool allowlist, narrowing the workspace allowlist.
+    pub(crate) allowed_tools: Option<Vec<String>>,
+    /// Makes every run read-only.
+    pub(crate) read_only: bool,
+    /// Working folder for runs whose request and workspace set none.
+    pub(crate) working_folder: Option<PathBuf>,
+    /// Provider, base URL and API key, used where the run's model resolution leaves them unset.
+    pub(crate) provider: Option<String>,
+    pub(crate) base_url: Option<String>,
+    /// Sent in clear to the server's own worker processes only.
+    #[serde(serialize_with = "serialize_exposed")]
+    pub(crate) api_key: Option<config::Secret>,
+}
+
+fn serialize_exposed<S: serde::Serializer>(
+    key: &Option<config::Secret>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    key.as_ref()
+        .map(config::Secret::expose)
+        .serialize(serializer)
+}
+
+impl BuilderDefaults {
+    /// Takes the honored settings from `builder`, refusing those serve runs cannot apply: a
+    /// custom LLM client (runs build their own per request), MCP servers and denied tools
+    /// (use `allowed_tools` or the MCP config file instead).
+    pub(crate) fn from_builder(
+        builder: &loom::LoomBuilder,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let config = builder.config();
+        let unsupported = [
+            (builder.has_llm(), "llm()"),
+            (config.mcp_servers.is_some(), "mcp_server()"),
+            (!config.denied_tools.is_empty(), "deny_tools()"),
+        ];
+        if let Some((_, name)) = unsupported.iter().find(|(set, _)| *set) {
+            return Err(format!("serve runs cannot apply the builder's {}", name).into());
+        }
+        Ok(Self {
+            model: config.model.clone(),
+            system_prompt: config.system_prompt.clone(),
+            allowed_tools: config.allowed_tools.clone(),
+            read_only: config.read_only,
+            working_folder: config.working_folder.clone(),
+            provider: config.llm_provider.clone(),
+            base_url: config.openai_base_url.clone(),
+            api_key: config.openai_api_key.clone(),
+        })
+    }
+}
+
+/// `run_config` with `builder` applied on top: its model (for runs that name none), system
+/// prompt (as the default role), tool allowlist and read-only mode win over the environment;
+/// working folder and provider connection are applied per run (see
+/// [`crate::run::stream_run`]).
+pub(crate) fn with_builder_defaults(
+    run_config: RunConfig,
+    builder: Option<Arc<BuilderDefaults>>,
+) -> RunConfig {
+    let Some(b) = builder else {
+        return run_config;
+    };
+    RunConfig {
+        default_model: b.model.clone().or(run_config.default_model),
+        role_setting: b.system_prompt.clone().or(run_config.role_setting),
+        allowed_tools: b.allowed_tools.clone().or(run_config.allowed_tools),
+        read_only: b.read_only || run_config.read_only,
+        builder: Some(b),
+        ..run_config
+    }
+}
+
+/// [`RunConfig`] from the environment as in [`run_config_from_env`], with the settings of
+/// `builder` applied (see [`BuilderDefaults`]). Fails when the builder sets something serve
+/// runs cannot apply.
+pub(crate) fn run_config_from_builder(
+    builder: &loom::LoomBuilder,
+) -> Result<RunConfig, Box<dyn std::error::Error + Send + Sync>> {
+    let defaults = BuilderDefaults::from_builder(builder)?;
+    Ok(with_builder_defaults(
+        run_config_from_env(),
+        Some(Arc::new(defaults)),
+    ))
+}
+
+/// Reloadable [`RunConfig`] shared by all connections. Readers take a cheap snapshot with
+/// [`SharedRunConfig::current`]; [`SharedRunConfig::replace`] swaps in a new config for later reads.
+#[derive(Clone)]
+pub(crate) struct SharedRunConfig(Arc<RwLock<Arc<RunConfig>>>);
+
+impl SharedRunConfig {
+    pub(crate) fn new(config: RunConfig) -> Self {
+        Self(Arc::new(RwLock::new(Arc::new(config))))
+    }
+
+    /// Snapshot of the current config; unaffected by later reloads.
+    pub(crate) fn current(&self) -> Arc<RunConfig> {
+        self.0.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub(crate) fn replace(&self, config: RunConfig) {
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(config);
     }
 }
 
@@ -67,41 +308,93 @@ pub(crate) fn run_config_from_env() -> RunConfig {
 pub(crate) struct AppState {
     /// When set, the first WebSocket connection to close will send on this to signal server exit (once mode).
     pub(crate) shutdown_tx: Arc<Mutex<Option<oneshot::Sender<()>>>>,
-    /// When set, Run requests with workspace_id + thread_id register the thread in this workspace.
-    pub(crate) workspace_store: Option<Arc<loom_workspace::Store>>,
-    /// When set, user and assistant messages are appended per thread (Phase 2: stream-event driven).
-    pub(crate) user_message_store: Option<std::sync::Arc<dyn loom::UserMessageStore>>,
-    /// Run and tools configuration (queue capacities, display_max_len).
-    pub(crate) run_config: RunConfig,
+    /// Workspace and user-message stores; each may be degraded (read-only, in memory, or
+    /// unavailable) and is read per request.
+    pub(crate) stores: Stores,
+    /// Run and tools configuration (queue capacities, display_max_len); reloadable.
+    pub(crate) run_config: SharedRunConfig,
     /// Provider configurations for model access.
     pub(crate) providers: Arc<Vec<ProviderConfig>>,
+    /// Model limits catalog refreshed in the background; `None` when disabled.
+    pub(crate) model_catalog: Option<ModelCatalog>,
+    /// Per-request access records of all connections.
+    pub(crate) access_log: Arc<AccessLog>,
+    /// Runs whose client disconnected, kept for `resume_run`.
+    pub(crate) detached_runs: DetachedRuns,
+    /// Worker processes runs are dispatched to; disabled unless `SERVE_WORKERS` is set.
+    pub(crate) worker_pool: WorkerPool,
+    /// Runs in progress on any connection, for the admin requests `active_runs`, `run_inspect`
+    /// and `run_kill`.
+    pub(crate) active_runs: ActiveRuns,
+    /// Open `session_*` conversations of all connections.
+    pub(crate) sessions: Sessions,
+    /// GitHub/GitLab webhook configuration for `/hooks/*`.
+    pub(crate) hooks: Arc<HookConfig>,
 }
 
-/// Builds the Axum router with a single WebSocket route at `/`.
+/// Builds the Axum router: the WebSocket route at `/`, the protocol schema at `/schema`, store
+/// health at `/healthz` and the webhook endpoints under `/hooks` (see [`crate::hooks`]).
 pub(crate) fn router(state: Arc<AppState>) -> Router {
-    Router::new().route("/", get(ws_handler)).with_state(state)
+    Router::new()
+        .route("/", get(ws_handler))
+        .route("/schema", get(schema_handler))
+        .route("/healthz", get(healthz_handler))
+        .route("/hooks/github", post(github_hook_handler))
+        .route("/hooks/gitlab", post(gitlab_hook_handler))
+        .with_state(state)
+}
+
+/// Handles `GET /healthz`: `200` with `status` `ok` or `degraded` and each store's state, so a
+/// degraded server keeps serving while monitoring sees it.
+async fn healthz_handler(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    Json(state.stores.health())
+}
+
+/// Handles `GET /schema`: the protocol's JSON Schema, for client codegen.
+async fn schema_handler() -> Json<serde_json::Value> {
+    Json(loom::protocol::protocol_schema())
 }
 
 /// Handles `GET /`: upgrades to WebSocket and delegates to [`handle_socket`] with state clones.
+/// Accepts the `loom.msgpack` / `loom.json.deflate` / `loom.json` subprotocols, which select the
+/// server message encoding.
 async fn ws_handler(ws: WebSocketUpgrade, State(state): State<Arc<AppState>>) -> Response {
     tracing::info!("🔌 WebSocket upgrade request received");
 
     let shutdown_tx = state.shutdown_tx.lock().ok().and_then(|mut g| g.take());
-    let workspace_store = state.workspace_store.clone();
-    let user_message_store = state.user_message_store.clone();
+    let stores = state.stores.clone();
     let run_config = state.run_config.clone();
     let providers = state.providers.clone();
+    let model_catalog = state.model_catalog.clone();
+    let access_log = state.access_log.clone();
+    let detached_runs = state.detached_runs.clone();
+    let worker_pool = state.worker_pool.clone();
+    let active_runs = state.active_runs.clone();
+    let sessions = state.sessions.clone();
+    let transport_max = run_config.current().limits.transport_max_message_bytes();
 
     tracing::debug!("📤 Upgrading HTTP connection to WebSocket");
 
-    ws.on_upgrade(move |socket| {
+    ws.protocols([
+        SUBPROTOCOL_MSGPACK,
+        SUBPROTOCOL_JSON_DEFLATE,
+        SUBPROTOCOL_JSON,
+    ])
+    .max_message_size(transport_max)
+    .max_frame_size(transport_max)
+    .on_upgrade(move |socket| {
         handle_socket(
             socket,
             shutdown_tx,
-            workspace_store,
-            user_message_store,
+            stores,
             run_config,
             providers,
+            model_catalog,
+            access_log,
+            detached_runs,
+            worker_pool,
+            active_runs,
+            sessions,
         )
     })
 }
@@ -0,0 +1,49 @@
+//! Handle `ApprovalsList` request: the approval audit trail recorded by runs.
+
+use loom::approval_audit::ENV_APPROVAL_AUDIT_DB;
+use loom::{
+    ApprovalAuditFilter, ApprovalAuditStore, ApprovalsListRequest, ApprovalsListResponse,
+    ErrorResponse, ServerResponse, SqliteApprovalAuditStore,
+};
+
+/// Handles approvals_list request: audit records from `LOOM_APPROVAL_AUDIT_DB` (the store runs
+/// write to), newest first. Errors when the audit trail is not configured.
+pub(crate) async fn handle_approvals_list(r: ApprovalsListRequest) -> ServerResponse {
+    let error = |id: String, error: String| {
+        ServerResponse::Error(ErrorResponse {
+            id: Some(id),
+            error,
+            code: None,
+        })
+    };
+    let Some(path) = std::env::var(ENV_APPROVAL_AUDIT_DB)
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+    else {
+        return error(
+            r.id,
+            format!(
+                "approval audit not configured (set {})",
+                ENV_APPROVAL_AUDIT_DB
+            ),
+        );
+    };
+    let store = match SqliteApprovalAuditStore::new(&path) {
+        Ok(store) => store,
+        Err(e) => return error(r.id, e.to_string()),
+    };
+    let filter = ApprovalAuditFilter {
+        thread_id: r.thread_id,
+        user_id: r.user_id,
+        since: r.since,
+        until: r.until,
+        limit: r.limit,
+    };
+    match store.list(&filter).await {
+        Ok(approvals) => ServerResponse::ApprovalsList(ApprovalsListResponse {
+            id: r.id,
+            approvals,
+        }),
+        Err(e) => error(r.id, e.to_string()),
+    }
+}
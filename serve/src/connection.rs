@@ -1,19 +1,29 @@
 //! WebSocket connection lifecycle: recv loop and request dispatch.
+//!
+//! Each connection gets a process-unique id; every request handled on it produces one
+//! [`AccessRecord`] (see [`crate::access_log`]).
 
 use axum::extract::ws::{Message, WebSocket};
 use loom::cli_run::RunCancellation;
 use loom::llm::ProviderConfig;
 use loom::protocol::responses::CancelRunResponse;
-use loom::{ClientRequest, ErrorResponse, ServerResponse};
-use std::collections::HashMap;
+use loom::{ClientRequest, ErrorResponse, ServerResponse, StopGenerationResponse};
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use tokio::sync::oneshot;
 
+use super::access_log::{next_connection_id, AccessLog, AccessRecord};
 use super::agents::handle_agent_list;
-use super::app::RunConfig;
-use super::models::{handle_list_models, handle_set_model};
-use super::response::send_response;
-use super::run::handle_run;
+use super::app::{RunConfig, SharedRunConfig};
+use super::limits::payload_too_large;
+use super::models::{handle_list_models, handle_set_model, ModelCatalog};
+use super::response::{send_response, socket_encoding};
+use super::run::{
+    handle_active_runs, handle_resume_run, handle_run, handle_run_inspect, handle_run_kill,
+    ActiveRuns, DetachedRuns, WorkerPool,
+};
+use super::session::{handle_session_end, Sessions};
+use super::stores::Stores;
 use super::tools::{handle_tool_show, handle_tools_list};
 
 /// Registry for tracking active runs and their cancellation handles.
@@ -50,73 +60,103 @@ impl ActiveRunRegistry {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn handle_socket(
     mut socket: WebSocket,
     shutdown_tx: Option<oneshot::Sender<()>>,
-    workspace_store: Option<Arc<loom_workspace::Store>>,
-    user_message_store: Option<std::sync::Arc<dyn loom::UserMessageStore>>,
-    run_config: RunConfig,
+    stores: Stores,
+    run_config: SharedRunConfig,
     providers: Arc<Vec<ProviderConfig>>,
+    model_catalog: Option<ModelCatalog>,
+    access_log: Arc<AccessLog>,
+    detached_runs: DetachedRuns,
+    worker_pool: WorkerPool,
+    active_runs: ActiveRuns,
+    sessions: Sessions,
 ) {
-    tracing::info!("🔗 New WebSocket connection established");
+    let connection_id = next_connection_id();
+    tracing::info!(
+        connection_id,
+        "🔗 New WebSocket connection established (encoding: {:?})",
+        socket_encoding(&socket)
+    );
 
     let mut request_count = 0;
     let connection_start = std::time::Instant::now();
     let mut active_run_registry = ActiveRunRegistry::new();
+    // Requests that arrived while a run was streaming; handled before reading the socket again.
+    let mut deferred: VecDeque<String> = VecDeque::new();
 
-    while let Some(res) = socket.recv().await {
-        let msg = match res {
-            Ok(m) => m,
-            Err(e) => {
-                tracing::warn!("❌ WebSocket read error (client closed?): {}", e);
-                let _ = socket.close().await;
+    loop {
+        let text = if let Some(text) = deferred.pop_front() {
+            text
+        } else {
+            let Some(res) = socket.recv().await else {
                 break;
-            }
-        };
-        let text = match &msg {
-            Message::Text(t) => t.clone(),
-            Message::Binary(b) => String::from_utf8_lossy(b).into_owned(),
-            _ => {
-                tracing::debug!("Received non-text message, skipping");
-                continue;
+            };
+            let msg = match res {
+                Ok(m) => m,
+                Err(e) => {
+                    tracing::warn!(
+                        connection_id,
+                        "❌ WebSocket read error (client closed?): {}",
+                        e
+                    );
+                    let _ = socket.close().await;
+                    break;
+                }
+            };
+            match &msg {
+                Message::Text(t) => t.clone(),
+                Message::Binary(b) => String::from_utf8_lossy(b).into_owned(),
+                _ => {
+                    tracing::debug!("Received non-text message, skipping");
+                    continue;
+                }
             }
         };
 
         request_count += 1;
         tracing::debug!(
+            connection_id,
             "📨 Request #{}: {}",
             request_count,
             text.chars().take(100).collect::<String>()
         );
 
         let request_start = std::time::Instant::now();
+        let mut record = AccessRecord::new(connection_id, request_count);
 
-        if let Err(e) = handle_request_and_send(
+        let result = handle_request_and_send(
             &text,
             &mut socket,
-            workspace_store.clone(),
-            user_message_store.clone(),
+            &mut deferred,
+            &mut record,
+            &stores,
             &run_config,
             providers.clone(),
+            model_catalog.as_ref(),
             &mut active_run_registry,
+            &detached_runs,
+            &worker_pool,
+            &active_runs,
+            &sessions,
         )
-        .await
-        {
-            tracing::error!("❌ Request #{} failed: {}", request_count, e);
+        .await;
+        record.duration = request_start.elapsed();
+        if let Err(e) = &result {
+            record.fail(e);
+        }
+        access_log.record(&record);
+        if result.is_err() {
             let _ = socket.close().await;
             break;
         }
-
-        let duration = request_start.elapsed();
-        tracing::debug!(
-            "✅ Request #{} completed in {}ms",
-            request_count,
-            duration.as_millis()
-        );
     }
 
     let connection_duration = connection_start.elapsed();
     tracing::info!(
+        connection_id,
         "🔌 WebSocket connection closed (handled {} requests in {}ms)",
         request_count,
         connection_duration.as_millis()
@@ -127,15 +167,44 @@ pub(crate) async fn handle_socket(
     }
 }
 
+/// Sends `response` and accounts for it in `access`.
+async fn send_recorded(
+    socket: &mut WebSocket,
+    response: &ServerResponse,
+    access: &mut AccessRecord,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let bytes = send_response(socket, response).await?;
+    access.observe(response, bytes);
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn handle_request_and_send(
     text: &str,
     socket: &mut WebSocket,
-    workspace_store: Option<Arc<loom_workspace::Store>>,
-    user_message_store: Option<std::sync::Arc<dyn loom::UserMessageStore>>,
-    run_config: &RunConfig,
+    deferred: &mut VecDeque<String>,
+    access: &mut AccessRecord,
+    stores: &Stores,
+    shared_run_config: &SharedRunConfig,
     providers: Arc<Vec<ProviderConfig>>,
+    model_catalog: Option<&ModelCatalog>,
     active_run_registry: &mut ActiveRunRegistry,
+    detached_runs: &DetachedRuns,
+    worker_pool: &WorkerPool,
+    active_runs: &ActiveRuns,
+    sessions: &Sessions,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Snapshot per request: a reload applies to the next request, never to one in progress.
+    let run_config: Arc<RunConfig> = shared_run_config.current();
+    let run_config = run_config.as_ref();
+    // Stores too: a reopened store is used from the next request on.
+    let workspace_store = stores.workspace.current();
+    let user_message_store = stores.user_messages.current();
+    if let Err(e) = run_config.limits.check_frame(text) {
+        tracing::warn!("⚠️  Rejected request: {}", e);
+        send_recorded(socket, &payload_too_large(None, e), access).await?;
+        return Ok(());
+    }
     let req: ClientRequest = match serde_json::from_str(text) {
         Ok(r) => r,
         Err(e) => {
@@ -143,37 +212,68 @@ async fn handle_request_and_send(
             let resp = ServerResponse::Error(ErrorResponse {
                 id: None,
                 error: format!("parse error: {}", e),
+                code: None,
             });
-            send_response(socket, &resp).await?;
+            send_recorded(socket, &resp, access).await?;
             return Ok(());
         }
     };
 
-    let request_type = format!("{:?}", req);
-    tracing::info!(
-        "Handling request: {} (id: {:?})",
-        request_type,
-        match &req {
-            ClientRequest::Run(r) => r.id.clone(),
-            ClientRequest::ListModels(r) => Some(r.id.clone()),
-            ClientRequest::SetModel(r) => Some(r.id.clone()),
-            ClientRequest::CancelRun(r) => Some(r.id.clone()),
-            _ => None,
-        }
+    let request_type = req.kind();
+    access.request_type = request_type;
+    access.request_id = req.id().map(str::to_string);
+    tracing::debug!(
+        connection_id = access.connection_id,
+        seq = access.seq,
+        "Handling request: {}",
+        request_type
     );
 
+    // A session message is a run on the session's thread.
+    let req = match req {
+        ClientRequest::SessionMessage(r) => match sessions.run_request(r) {
+            Ok(run) => ClientRequest::Run(run),
+            Err(resp) => {
+                send_recorded(socket, &resp, access).await?;
+                return Ok(());
+            }
+        },
+        other => other,
+    };
+
+    if let ClientRequest::Run(r) = &req {
+        if let Err(e) = run_config.limits.check_message(&r.message) {
+            tracing::warn!("⚠️  Rejected run: {}", e);
+            send_recorded(socket, &payload_too_large(r.id.clone(), e), access).await?;
+            return Ok(());
+        }
+    }
+
     let resp = match req {
         ClientRequest::Run(r) => {
-            tracing::info!("🚀 Starting agent run with profile: {}", r.agent);
-            match handle_run(r, socket, workspace_store, user_message_store, run_config).await {
+            tracing::debug!("🚀 Starting agent run with profile: {}", r.agent);
+            match handle_run(
+                r,
+                socket,
+                deferred,
+                access,
+                workspace_store,
+                user_message_store,
+                run_config,
+                detached_runs,
+                worker_pool,
+                active_runs,
+            )
+            .await
+            {
                 Ok((run_id, cancellation, Some(resp))) => {
+                    access.run_id = Some(run_id.clone());
                     active_run_registry.insert(run_id, cancellation);
-                    tracing::info!("✅ Run completed with response");
                     resp
                 }
                 Ok((run_id, cancellation, None)) => {
+                    access.run_id = Some(run_id.clone());
                     active_run_registry.insert(run_id, cancellation);
-                    tracing::info!("✅ Run streamed to client");
                     return Ok(());
                 }
                 Err(e) => {
@@ -181,6 +281,7 @@ async fn handle_request_and_send(
                     ServerResponse::Error(ErrorResponse {
                         id: None,
                         error: e.to_string(),
+                        code: None,
                     })
                 }
             }
@@ -201,43 +302,56 @@ async fn handle_request_and_send(
             tracing::debug!("💬 Handling user messages for thread: {}", r.thread_id);
             super::user_messages::handle_user_messages(r, user_message_store).await
         }
+        ClientRequest::UserMessagesDelete(r) => {
+            tracing::debug!("🗑️ Deleting user messages for thread: {}", r.thread_id);
+            super::user_messages::handle_user_messages_delete(r, user_message_store).await
+        }
+        ClientRequest::StateShow(r) => {
+            tracing::debug!("🔍 Showing state for thread: {}", r.thread_id);
+            super::state_show::handle_state_show(r).await
+        }
+        ClientRequest::CheckpointList(r) => {
+            tracing::debug!("🔍 Listing checkpoints for thread: {}", r.thread_id);
+            super::state_show::handle_checkpoint_list(r).await
+        }
         ClientRequest::Ping(r) => {
             tracing::debug!("🏓 Ping received");
-            send_response(
+            send_recorded(
                 socket,
                 &ServerResponse::Pong(loom::PongResponse { id: r.id }),
+                access,
             )
             .await?;
             return Ok(());
         }
         ClientRequest::ListModels(r) => {
             tracing::debug!("📋 Listing available models");
-            let resp = handle_list_models(r, &providers).await;
+            let resp = handle_list_models(r, &providers, model_catalog).await;
             match &resp {
                 ServerResponse::ListModels(m) => {
-                    tracing::info!("📋 Listed {} models", m.models.len());
+                    tracing::debug!("📋 Listed {} models", m.models.len());
                 }
                 ServerResponse::Error(e) => {
                     tracing::error!("❌ Failed to list models: {}", e.error);
                 }
                 _ => {}
             }
-            send_response(socket, &resp).await?;
+            send_recorded(socket, &resp, access).await?;
             return Ok(());
         }
         ClientRequest::SetModel(r) => {
-            tracing::info!(
+            tracing::debug!(
                 "🔄 Setting model: {} for session: {}",
                 r.model_id,
                 r.session_id.as_deref().unwrap_or("default")
             );
             let resp = handle_set_model(r, &providers).await;
             match &resp {
-                ServerResponse::SetModel(_) => tracing::info!("✅ Model set successfully"),
+                ServerResponse::SetModel(_) => tracing::debug!("✅ Model set successfully"),
                 ServerResponse::Error(e) => tracing::error!("❌ Failed to set model: {}", e.error),
                 _ => {}
             }
-            send_response(socket, &resp).await?;
+            send_recorded(socket, &resp, access).await?;
             return Ok(());
         }
         ClientRequest::WorkspaceList(r) => {
@@ -260,6 +374,60 @@ async fn handle_request_and_send(
             tracing::debug!("➖ Removing thread from workspace");
             super::workspace::handle_workspace_thread_remove(r, workspace_store.clone()).await
         }
+        ClientRequest::WorkspaceUpdate(r) => {
+            tracing::debug!("⚙️ Updating workspace defaults");
+            super::workspace::handle_workspace_update(r, workspace_store.clone()).await
+        }
+        ClientRequest::UsageReport(r) => {
+            tracing::debug!("📊 Building usage report");
+            super::workspace::handle_usage_report(r, workspace_store.clone()).await
+        }
+        ClientRequest::ApprovalsList(r) => {
+            tracing::debug!("🛂 Listing approval audit records");
+            super::approvals::handle_approvals_list(r).await
+        }
+        ClientRequest::EventSchemaList(r) => {
+            tracing::debug!("🧾 Listing custom event schemas");
+            ServerResponse::EventSchemaList(loom::EventSchemaListResponse {
+                id: r.id,
+                events: loom::stream::custom_event_schemas(),
+            })
+        }
+        ClientRequest::AdminReload(r) => {
+            tracing::info!("🔄 Admin reload requested");
+            super::reload::handle_admin_reload(r, shared_run_config, worker_pool)
+        }
+        ClientRequest::ActiveRuns(r) => handle_active_runs(r, active_runs),
+        ClientRequest::RunInspect(r) => handle_run_inspect(r, active_runs),
+        ClientRequest::RunKill(r) => {
+            tracing::info!("🛑 Admin kill requested for run: {}", r.run_id);
+            handle_run_kill(r, active_runs)
+        }
+        ClientRequest::StopGeneration(r) => {
+            tracing::info!("✋ Stopping generation for run: {}", r.run_id);
+            match active_run_registry.get(&r.run_id) {
+                Some(cancellation) => {
+                    cancellation.stop_generation();
+                    ServerResponse::StopGeneration(StopGenerationResponse {
+                        id: r.id,
+                        run_id: r.run_id,
+                    })
+                }
+                None => ServerResponse::Error(ErrorResponse {
+                    id: Some(r.id),
+                    error: format!("Run {} not found or already completed", r.run_id),
+                    code: None,
+                }),
+            }
+        }
+        ClientRequest::ResumeRun(r) => {
+            tracing::info!("🔁 Resuming run: {}", r.run_id);
+            access.run_id = Some(r.run_id.clone());
+            match handle_resume_run(r, socket, deferred, access, detached_runs).await? {
+                Some(resp) => resp,
+                None => return Ok(()),
+            }
+        }
         ClientRequest::CancelRun(r) => {
             tracing::info!("🛑 Cancelling run: {}", r.run_id);
             if active_run_registry.cancel(&r.run_id) {
@@ -271,12 +439,18 @@ async fn handle_request_and_send(
                 ServerResponse::Error(ErrorResponse {
                     id: Some(r.id),
                     error: format!("Run {} not found or already completed", r.run_id),
+                    code: None,
                 })
             }
         }
+        ClientRequest::SessionStart(r) => sessions.start(r),
+        ClientRequest::SessionEnd(r) => {
+            handle_session_end(r, sessions, run_config, workspace_store, user_message_store).await
+        }
+        ClientRequest::SessionMessage(_) => unreachable!("session messages are converted to runs"),
     };
 
     tracing::debug!("📤 Sending response for: {}", request_type);
-    send_response(socket, &resp).await?;
+    send_recorded(socket, &resp, access).await?;
     Ok(())
 }
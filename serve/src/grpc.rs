@@ -0,0 +1,301 @@
+//! gRPC server (tonic, `grpc` feature): `Run`, `ToolsList` and `Ping` from `proto/loom.proto`.
+//!
+//! Runs on its own listener next to the WebSocket server when `SERVE_GRPC_ADDR` is set, sharing
+//! the same [`AppState`]. `Run` goes through the same preparation and delivery as the WebSocket
+//! `run` request ([`crate::run::dispatch_run`]); each `RunStreamEvent` / `RunEnd` / `Error` becomes
+//! one [`proto::RunEvent`] on the server stream. Dropping the stream aborts the run.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use loom::protocol::AgentIdentifier;
+use loom::{
+    AgentType, ProtocolEventEnvelope, RunRequest, ServerResponse, ToolsListRequest, UserContent,
+};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::{ReceiverStream, TcpListenerStream};
+use tonic::{Request, Response, Status};
+
+use crate::app::AppState;
+use crate::run::{dispatch_run, RunStreamSender};
+use crate::tools::handle_tools_list;
+
+/// Generated messages, client and server for package `loom.v1`.
+#[allow(clippy::all)]
+pub mod proto {
+    tonic::include_proto!("loom.v1");
+}
+
+use proto::loom_server::{Loom, LoomServer};
+
+/// Address for the gRPC server, from `SERVE_GRPC_ADDR` (e.g. `127.0.0.1:50051`); unset disables it.
+pub(crate) fn grpc_addr_from_env() -> Option<String> {
+    std::env::var("SERVE_GRPC_ADDR")
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Serves the gRPC API on `listener` until the process exits.
+pub(crate) async fn serve_grpc(
+    listener: TcpListener,
+    state: Arc<AppState>,
+) -> Result<(), tonic::transport::Error> {
+    tonic::transport::Server::builder()
+        .add_service(LoomServer::new(LoomService { state }))
+        .serve_with_incoming(TcpListenerStream::new(listener))
+        .await
+}
+
+struct LoomService {
+    state: Arc<AppState>,
+}
+
+#[tonic::async_trait]
+impl Loom for LoomService {
+    type RunStream = ReceiverStream<Result<proto::RunEvent, Status>>;
+
+    async fn run(
+        &self,
+        request: Request<proto::RunRequest>,
+    ) -> Result<Response<Self::RunStream>, Status> {
+        let r = run_request_from_proto(request.into_inner())?;
+        let run_config = self.state.run_config.current();
+        run_config
+            .limits
+            .check_message(&r.message)
+            .map_err(Status::invalid_argument)?;
+        tracing::info!("🚀 gRPC run with profile: {}", r.agent);
+
+        let (tx, rx) = mpsc::channel(run_config.event_queue_capacity);
+        let state = self.state.clone();
+        tokio::spawn(async move {
+            let mut sender = GrpcRunSender { tx };
+            if let Err(e) = dispatch_run(
+                r,
+                &mut sender,
+                state.stores.workspace.current(),
+                state.stores.user_messages.current(),
+                &run_config,
+                &state.worker_pool,
+                &state.active_runs,
+            )
+            .await
+            {
+                tracing::warn!("⚠️  gRPC run stream closed early: {}", e);
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn tools_list(
+        &self,
+        request: Request<proto::ToolsListRequest>,
+    ) -> Result<Response<proto::ToolsListResponse>, Status> {
+        let request = request.into_inner();
+        let r = ToolsListRequest {
+            id: "grpc".to_string(),
+            working_folder: request.working_folder,
+            thread_id: request.thread_id,
+        };
+        match handle_tools_list(r, &self.state.run_config.current()).await {
+            ServerResponse::ToolsList(resp) => Ok(Response::new(proto::ToolsListResponse {
+                tools: resp
+                    .tools
+                    .into_iter()
+                    .map(|t| proto::Tool {
+                        name: t.name,
+                        description: t.description,
+                        input_schema_json: t.input_schema.to_string(),
+                    })
+                    .collect(),
+            })),
+            ServerResponse::Error(e) => Err(Status::internal(e.error)),
+            _ => Err(Status::internal("unexpected tools_list response")),
+        }
+    }
+
+    async fn ping(
+        &self,
+        _request: Request<proto::PingRequest>,
+    ) -> Result<Response<proto::PingResponse>, Status> {
+        Ok(Response::new(proto::PingResponse {}))
+    }
+}
+
+/// Converts a gRPC run request into the protocol [`RunRequest`].
+fn run_request_from_proto(r: proto::RunRequest) -> Result<RunRequest, Status> {
+    let agent = if r.agent.trim().is_empty() {
+        AgentIdentifier::Type(AgentType::React)
+    } else {
+        serde_json::from_value(serde_json::Value::String(r.agent))
+            .map_err(|e| Status::invalid_argument(format!("agent: {}", e)))?
+    };
+    Ok(RunRequest {
+        id: None,
+        message: UserContent::Text(r.message),
+        agent,
+        thread_id: r.thread_id,
+        workspace_id: r.workspace_id,
+        working_folder: r.working_folder,
+        got_adaptive: r.got_adaptive,
+        verbose: r.verbose,
+        model: r.model,
+        reply_format: None,
+        reply_schema: None,
+        messages: None,
+        read_only: None,
+        locale: None,
+        pin: None,
+        flags: Default::default(),
+    })
+}
+
+fn stream_event_to_proto(envelope: &ProtocolEventEnvelope) -> proto::StreamEvent {
+    let value = envelope.to_value().unwrap_or_default();
+    proto::StreamEvent {
+        session_id: envelope.session_id.clone(),
+        node_id: envelope.node_id.clone(),
+        event_id: envelope.event_id,
+        event_type: value["type"].as_str().unwrap_or_default().to_string(),
+        event_json: value.to_string(),
+    }
+}
+
+/// Maps a run response to a [`proto::RunEvent`]; `None` for responses with no gRPC counterpart
+/// (run controls are not exposed over gRPC).
+fn run_event_from_response(response: &ServerResponse) -> Option<proto::RunEvent> {
+    use proto::run_event::Kind;
+    let (run_id, kind) = match response {
+        ServerResponse::RunStreamEvent(e) => {
+            (e.id.clone(), Kind::Event(stream_event_to_proto(&e.event)))
+        }
+        ServerResponse::RunEnd(e) => (
+            e.id.clone(),
+            Kind::End(proto::RunEnd {
+                reply: e.reply.clone(),
+                reasoning_content: e.reasoning_content.clone(),
+                finish_reason: e.finish_reason.clone().map(String::from),
+                session_id: e.session_id.clone(),
+                node_id: e.node_id.clone(),
+                event_id: e.event_id,
+            }),
+        ),
+        ServerResponse::Error(e) => (
+            e.id.clone().unwrap_or_default(),
+            Kind::Error(proto::RunError {
+                error: e.error.clone(),
+                code: e.code.clone(),
+            }),
+        ),
+        _ => return None,
+    };
+    Some(proto::RunEvent {
+        run_id,
+        kind: Some(kind),
+    })
+}
+
+/// Delivers run responses to the gRPC response stream. Sending fails once the client is gone.
+struct GrpcRunSender {
+    tx: mpsc::Sender<Result<proto::RunEvent, Status>>,
+}
+
+#[async_trait]
+impl RunStreamSender for GrpcRunSender {
+    async fn send_response(
+        &mut self,
+        response: &ServerResponse,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let Some(event) = run_event_from_response(response) else {
+            return Ok(());
+        };
+        self.tx
+            .send(Ok(event))
+            .await
+            .map_err(|_| "gRPC client disconnected".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use loom::{ErrorResponse, ProtocolEvent, RunEndResponse, RunStreamEventResponse};
+
+    fn request(agent: &str) -> proto::RunRequest {
+        proto::RunRequest {
+            message: "hi".to_string(),
+            agent: agent.to_string(),
+            thread_id: Some("t1".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn run_request_maps_agent_names() {
+        let r = run_request_from_proto(request("")).unwrap();
+        assert_eq!(r.agent, AgentIdentifier::Type(AgentType::React));
+        assert_eq!(r.thread_id.as_deref(), Some("t1"));
+        assert_eq!(r.message, UserContent::Text("hi".to_string()));
+        let r = run_request_from_proto(request("dev")).unwrap();
+        assert_eq!(r.agent, AgentIdentifier::Name("dev".to_string()));
+    }
+
+    #[test]
+    fn stream_event_keeps_envelope_fields_and_json() {
+        let event =
+            run_event_from_response(&ServerResponse::RunStreamEvent(RunStreamEventResponse {
+                id: "run-1".to_string(),
+                event: ProtocolEventEnvelope {
+                    session_id: Some("run-1".to_string()),
+                    node_id: Some("think".to_string()),
+                    event_id: Some(3),
+                    prev_event_id: None,
+                    event: ProtocolEvent::NodeEnter {
+                        id: "think".to_string(),
+                    },
+                },
+            }))
+            .unwrap();
+        assert_eq!(event.run_id, "run-1");
+        let Some(proto::run_event::Kind::Event(e)) = event.kind else {
+            panic!("expected stream event");
+        };
+        assert_eq!(e.event_type, "node_enter");
+        assert_eq!(e.event_id, Some(3));
+        let json: serde_json::Value = serde_json::from_str(&e.event_json).unwrap();
+        assert_eq!(json["node_id"], "think");
+    }
+
+    #[test]
+    fn run_end_and_error_map_to_terminal_events() {
+        let end = run_event_from_response(&ServerResponse::RunEnd(RunEndResponse {
+            id: "run-1".to_string(),
+            reply: "done".to_string(),
+            reasoning_content: None,
+            usage: None,
+            total_usage: None,
+            finish_reason: None,
+            session_id: None,
+            node_id: None,
+            event_id: None,
+            transcript: None,
+            timing: None,
+            flags: Default::default(),
+            confidence: None,
+        }))
+        .unwrap();
+        assert!(matches!(end.kind, Some(proto::run_event::Kind::End(e)) if e.reply == "done"));
+        let err = run_event_from_response(&ServerResponse::Error(ErrorResponse {
+            id: Some("run-1".to_string()),
+            error: "run cancelled".to_string(),
+            code: None,
+        }))
+        .unwrap();
+        assert_eq!(err.run_id, "run-1");
+        assert!(
+            matches!(err.kind, Some(proto::run_event::Kind::Error(e)) if e.error == "run cancelled")
+        );
+    }
+}
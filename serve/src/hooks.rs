@@ -0,0 +1,789 @@
+//! Webhook triggers: `POST /hooks/github` and `POST /hooks/gitlab` turn issue and pull/merge
+//! request comments into runs and post each run's reply back as a comment.
+//!
+//! An endpoint is enabled by its secret: `SERVE_GITHUB_WEBHOOK_SECRET` (checked as the HMAC-SHA256
+//! `X-Hub-Signature-256` of the body) and `SERVE_GITLAB_WEBHOOK_SECRET` (compared with the
+//! `X-Gitlab-Token` header; GitLab sends the secret itself, not a signature). Replies are posted
+//! with `GITHUB_TOKEN` / `GITLAB_TOKEN`. Only comments mentioning `SERVE_HOOK_TRIGGER` (default
+//! `@loom`) start a run; the run uses the agent `SERVE_HOOK_AGENT` (default `react`) on thread
+//! `github-<owner>-<repo>-<number>` / `gitlab-<project id>-<issues|merge_requests>-<iid>`, so later comments continue the
+//! conversation. The endpoint answers `202` before the run starts; bot comments and the
+//! server's own replies (marked with [`REPLY_MARKER`]) are ignored so replies cannot loop.
+//!
+//! Only trusted authors start runs: GitHub commenters whose `author_association` is `OWNER`,
+//! `MEMBER` or `COLLABORATOR`, and the users listed in `SERVE_HOOK_ALLOWED_USERS` (the only way
+//! to allow GitLab users, whose note payloads carry no role). Other triggered comments are
+//! answered `403`. Runs are read-only unless `SERVE_HOOK_ALLOW_WRITE` is set.
+
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use config::Secret;
+use hmac::Mac;
+use loom::protocol::AgentIdentifier;
+use loom::{AgentType, RunRequest, ServerResponse, UserContent};
+use serde::Deserialize;
+
+use crate::admin::token_eq;
+use crate::app::AppState;
+use crate::run::{dispatch_run, RunStreamSender};
+
+/// Hidden marker appended to every posted reply; comments containing it never start a run.
+const REPLY_MARKER: &str = "<!-- loom-serve -->";
+const DEFAULT_TRIGGER: &str = "@loom";
+const DEFAULT_GITHUB_API_URL: &str = "https://api.github.com";
+const DEFAULT_GITLAB_URL: &str = "https://gitlab.com";
+/// GitHub `author_association` values trusted to start runs.
+const TRUSTED_ASSOCIATIONS: &[&str] = &["OWNER", "MEMBER", "COLLABORATOR"];
+
+/// Secret, reply token and API base URL of one forge.
+#[derive(Clone, Debug)]
+struct ForgeConfig {
+    secret: Secret,
+    token: Option<Secret>,
+    api_url: String,
+}
+
+impl ForgeConfig {
+    /// `None` when `secret_env` is unset, which disables the endpoint.
+    fn from_env(
+        secret_env: &str,
+        token_env: &str,
+        url_env: &str,
+        default_url: &str,
+    ) -> Option<Self> {
+        let secret = config::read_secret(secret_env)?;
+        let token = config::read_secret(token_env);
+        if token.is_none() {
+            tracing::warn!(
+                "⚠️  {} set but {} is not: webhook runs will not post replies",
+                secret_env,
+                token_env
+            );
+        }
+        let api_url = std::env::var(url_env)
+            .ok()
+            .map(|s| s.trim().trim_end_matches('/').to_string())
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| default_url.to_string());
+        Some(Self {
+            secret,
+            token,
+            api_url,
+        })
+    }
+}
+
+/// Webhook configuration, read once at startup.
+#[derive(Clone, Debug)]
+pub(crate) struct HookConfig {
+    github: Option<ForgeConfig>,
+    gitlab: Option<ForgeConfig>,
+    agent: AgentIdentifier,
+    /// Text a comment must contain to start a run; empty = every comment.
+    trigger: String,
+    /// Usernames allowed to start runs on either forge, besides trusted GitHub associations.
+    allowed_users: Vec<String>,
+    /// When false (default), webhook runs are read-only: no bash or write tools.
+    allow_write: bool,
+    http: reqwest::Client,
+}
+
+impl Default for HookConfig {
+    /// Both endpoints disabled.
+    fn default() -> Self {
+        Self {
+            github: None,
+            gitlab: None,
+            agent: AgentIdentifier::Type(AgentType::React),
+            trigger: DEFAULT_TRIGGER.to_string(),
+            allowed_users: Vec::new(),
+            allow_write: false,
+            http: reqwest::Client::new(),
+        }
+    }
+}
+
+impl HookConfig {
+    /// Reads the `SERVE_GITHUB_*`, `SERVE_GITLAB_*` and `SERVE_HOOK_*` variables.
+    pub(crate) fn from_env() -> Self {
+        let agent = match std::env::var("SERVE_HOOK_AGENT") {
+            Ok(name) if !name.trim().is_empty() => {
+                serde_json::from_value(serde_json::Value::String(name.trim().to_string()))
+                    .unwrap_or_else(|e| {
+                        tracing::warn!("⚠️  Invalid SERVE_HOOK_AGENT ({}), using react", e);
+                        AgentIdentifier::Type(AgentType::React)
+                    })
+            }
+            _ => AgentIdentifier::Type(AgentType::React),
+        };
+        Self {
+            github: ForgeConfig::from_env(
+                "SERVE_GITHUB_WEBHOOK_SECRET",
+                "GITHUB_TOKEN",
+                "SERVE_GITHUB_API_URL",
+                DEFAULT_GITHUB_API_URL,
+            ),
+            gitlab: ForgeConfig::from_env(
+                "SERVE_GITLAB_WEBHOOK_SECRET",
+                "GITLAB_TOKEN",
+                "SERVE_GITLAB_URL",
+                DEFAULT_GITLAB_URL,
+            ),
+            agent,
+            trigger: std::env::var("SERVE_HOOK_TRIGGER")
+                .map(|s| s.trim().to_string())
+                .unwrap_or_else(|_| DEFAULT_TRIGGER.to_string()),
+            allowed_users: std::env::var("SERVE_HOOK_ALLOWED_USERS")
+                .map(|s| {
+                    s.split(',')
+                        .map(|u| u.trim().trim_start_matches('@').to_string())
+                        .filter(|u| !u.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            allow_write: std::env::var("SERVE_HOOK_ALLOW_WRITE")
+                .map(|s| matches!(s.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false),
+            ..Self::default()
+        }
+    }
+
+    /// Whether `comment` asks for a run: not from a bot, not one of our replies, and
+    /// mentioning the trigger.
+    fn triggered_by(&self, comment: &HookComment) -> bool {
+        !comment.from_bot
+            && !comment.body.contains(REPLY_MARKER)
+            && (self.trigger.is_empty() || comment.body.contains(&self.trigger))
+    }
+
+    /// Whether the author of `comment` may start runs: a trusted GitHub association or a
+    /// listed user.
+    fn trusts(&self, comment: &HookComment) -> bool {
+        comment
+            .association
+            .as_deref()
+            .is_some_and(|a| TRUSTED_ASSOCIATIONS.contains(&a))
+            || self
+                .allowed_users
+                .iter()
+                .any(|u| u.eq_ignore_ascii_case(&comment.author))
+    }
+
+    /// Whether `comment` should start a run: triggered by a trusted author.
+    fn accepts(&self, comment: &HookComment) -> bool {
+        self.triggered_by(comment) && self.trusts(comment)
+    }
+
+    /// Run request for `comment` on its issue's thread.
+    fn run_request(&self, comment: &HookComment) -> RunRequest {
+        RunRequest {
+            id: None,
+            message: UserContent::text(comment.prompt()),
+            agent: self.agent.clone(),
+            thread_id: Some(comment.target.thread_id()),
+            workspace_id: None,
+            working_folder: None,
+            got_adaptive: None,
+            verbose: None,
+            model: None,
+            reply_format: None,
+            reply_schema: None,
+            messages: None,
+            read_only: Some(!self.allow_write),
+            locale: None,
+            pin: None,
+            flags: Default::default(),
+        }
+    }
+
+    /// Posts `reply` as a comment on `target`, with [`REPLY_MARKER`] appended.
+    async fn post_reply(&self, target: &ReplyTarget, reply: &str) -> Result<(), String> {
+        let body = serde_json::json!({ "body": format!("{}\n\n{}", reply, REPLY_MARKER) });
+        let request = match target {
+            ReplyTarget::GitHub { repo, number } => {
+                let forge = self.github.as_ref().ok_or("GitHub hooks disabled")?;
+                let token = forge.token.as_ref().ok_or("GITHUB_TOKEN not set")?;
+                self.http
+                    .post(format!(
+                        "{}/repos/{}/issues/{}/comments",
+                        forge.api_url, repo, number
+                    ))
+                    .bearer_auth(token.expose())
+                    .header("Accept", "application/vnd.github+json")
+                    .header("User-Agent", "loom-serve")
+            }
+            ReplyTarget::GitLab {
+                project_id,
+                kind,
+                iid,
+                ..
+            } => {
+                let forge = self.gitlab.as_ref().ok_or("GitLab hooks disabled")?;
+                let token = forge.token.as_ref().ok_or("GITLAB_TOKEN not set")?;
+                self.http
+                    .post(format!(
+                        "{}/api/v4/projects/{}/{}/{}/notes",
+                        forge.api_url,
+                        project_id,
+                        kind.path(),
+                        iid
+                    ))
+                    .header("PRIVATE-TOKEN", token.expose())
+            }
+        };
+        let response = request
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("{} answered {}", target, response.status()));
+        }
+        Ok(())
+    }
+}
+
+/// GitLab item a note belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum GitLabNoteable {
+    Issue,
+    MergeRequest,
+}
+
+impl GitLabNoteable {
+    fn path(self) -> &'static str {
+        match self {
+            Self::Issue => "issues",
+            Self::MergeRequest => "merge_requests",
+        }
+    }
+}
+
+/// Where the reply to a comment goes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ReplyTarget {
+    /// Issue or pull request `number` of `repo` (`owner/name`); both take issue comments.
+    GitHub { repo: String, number: u64 },
+    /// Issue or merge request `iid` of project `project_id` (`project` is its path, for display).
+    GitLab {
+        project_id: u64,
+        project: String,
+        kind: GitLabNoteable,
+        iid: u64,
+    },
+}
+
+impl ReplyTarget {
+    /// Thread shared by all runs on the same issue or pull/merge request.
+    fn thread_id(&self) -> String {
+        match self {
+            Self::GitHub { repo, number } => {
+                format!("github-{}-{}", repo.replace('/', "-"), number)
+            }
+            Self::GitLab {
+                project_id,
+                kind,
+                iid,
+                ..
+            } => format!("gitlab-{}-{}-{}", project_id, kind.path(), iid),
+        }
+    }
+}
+
+impl std::fmt::Display for ReplyTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::GitHub { repo, number } => write!(f, "GitHub {}#{}", repo, number),
+            Self::GitLab {
+                project, kind, iid, ..
+            } => {
+                let sigil = match kind {
+                    GitLabNoteable::Issue => '#',
+                    GitLabNoteable::MergeRequest => '!',
+                };
+                write!(f, "GitLab {}{}{}", project, sigil, iid)
+            }
+        }
+    }
+}
+
+/// A new comment from either forge.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct HookComment {
+    target: ReplyTarget,
+    title: String,
+    author: String,
+    /// GitHub `author_association` (`OWNER`, `MEMBER`, `CONTRIBUTOR`, `NONE`, ...); `None` on
+    /// GitLab.
+    association: Option<String>,
+    body: String,
+    from_bot: bool,
+}
+
+impl HookComment {
+    /// User message for the run: where the comment is, then the comment.
+    fn prompt(&self) -> String {
+        format!(
+            "Comment by @{} on {} \"{}\":\n\n{}",
+            self.author, self.target, self.title, self.body
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct GitHubUser {
+    login: String,
+    #[serde(rename = "type", default)]
+    user_type: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GitHubComment {
+    body: String,
+    user: GitHubUser,
+    #[serde(default)]
+    author_association: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GitHubIssue {
+    number: u64,
+    title: String,
+}
+
+#[derive(Deserialize)]
+struct GitHubRepository {
+    full_name: String,
+}
+
+/// `issue_comment` (issue or pull request conversation) and `pull_request_review_comment`
+/// payloads; the item is in `issue` or `pull_request` respectively.
+#[derive(Deserialize)]
+struct GitHubCommentEvent {
+    action: String,
+    comment: GitHubComment,
+    #[serde(default)]
+    issue: Option<GitHubIssue>,
+    #[serde(default)]
+    pull_request: Option<GitHubIssue>,
+    repository: GitHubRepository,
+}
+
+/// Parses a GitHub delivery of type `event`. `Ok(None)` for events and actions that do not
+/// start a run (anything but a newly created comment).
+fn parse_github_event(event: &str, body: &[u8]) -> Result<Option<HookComment>, String> {
+    if !matches!(event, "issue_comment" | "pull_request_review_comment") {
+        return Ok(None);
+    }
+    let e: GitHubCommentEvent = serde_json::from_slice(body).map_err(|e| e.to_string())?;
+    if e.action != "created" {
+        return Ok(None);
+    }
+    let item = e
+        .issue
+        .or(e.pull_request)
+        .ok_or("payload has neither issue nor pull_request")?;
+    Ok(Some(HookComment {
+        target: ReplyTarget::GitHub {
+            repo: e.repository.full_name,
+            number: item.number,
+        },
+        title: item.title,
+        from_bot: e.comment.user.user_type.as_deref() == Some("Bot"),
+        author: e.comment.user.login,
+        association: e.comment.author_association,
+        body: e.comment.body,
+    }))
+}
+
+#[derive(Deserialize)]
+struct GitLabUser {
+    username: String,
+    #[serde(default)]
+    bot: bool,
+}
+
+#[derive(Deserialize)]
+struct GitLabProject {
+    id: u64,
+    path_with_namespace: String,
+}
+
+#[derive(Deserialize)]
+struct GitLabNote {
+    note: String,
+    noteable_type: String,
+}
+
+#[derive(Deserialize)]
+struct GitLabItem {
+    iid: u64,
+    title: String,
+}
+
+/// Note Hook payload (`object_kind` `note`); the item is in `issue` or `merge_request` per `noteable_type`.
+#[derive(Deserialize)]
+struct GitLabNoteEvent {
+    user: GitLabUser,
+    project: GitLabProject,
+    object_attributes: GitLabNote,
+    #[serde(default)]
+    issue: Option<GitLabItem>,
+    #[serde(default)]
+    merge_request: Option<GitLabItem>,
+}
+
+/// Parses a GitLab delivery. `Ok(None)` for anything but a note on an issue or merge request.
+fn parse_gitlab_event(body: &[u8]) -> Result<Option<HookComment>, String> {
+    let value: serde_json::Value = serde_json::from_slice(body).map_err(|e| e.to_string())?;
+    if value.get("object_kind").and_then(|k| k.as_str()) != Some("note") {
+        return Ok(None);
+    }
+    let e: GitLabNoteEvent = serde_json::from_value(value).map_err(|e| e.to_string())?;
+    let (kind, item) = match e.object_attributes.noteable_type.as_str() {
+        "Issue" => (GitLabNoteable::Issue, e.issue),
+        "MergeRequest" => (GitLabNoteable::MergeRequest, e.merge_request),
+        _ => return Ok(None),
+    };
+    let item = item.ok_or("note payload is missing its issue or merge_request")?;
+    Ok(Some(HookComment {
+        target: ReplyTarget::GitLab {
+            project_id: e.project.id,
+            project: e.project.path_with_namespace,
+            kind,
+            iid: item.iid,
+        },
+        title: item.title,
+        author: e.user.username,
+        association: None,
+        body: e.object_attributes.note,
+        from_bot: e.user.bot,
+    }))
+}
+
+/// Checks a `sha256=<hex>` signature header against the HMAC-SHA256 of `body`.
+fn verify_github_signature(secret: &[u8], body: &[u8], header: &str) -> bool {
+    let Some(expected) = header
+        .strip_prefix("sha256=")
+        .and_then(|sig| hex::decode(sig).ok())
+    else {
+        return false;
+    };
+    let mut mac =
+        hmac::Hmac::<sha2::Sha256>::new_from_slice(secret).expect("HMAC accepts any key size");
+    mac.update(body);
+    token_eq(&expected, &mac.finalize().into_bytes())
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> &'a str {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+}
+
+/// Handles `POST /hooks/github`.
+pub(crate) async fn github_hook_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, String) {
+    let Some(forge) = state.hooks.github.as_ref() else {
+        return (
+            StatusCode::NOT_FOUND,
+            "GitHub hooks disabled: SERVE_GITHUB_WEBHOOK_SECRET not set".to_string(),
+        );
+    };
+    let signature = header(&headers, "X-Hub-Signature-256");
+    if !verify_github_signature(forge.secret.expose().as_bytes(), &body, signature) {
+        tracing::warn!("⚠️  Rejected GitHub webhook: bad signature");
+        return (StatusCode::UNAUTHORIZED, "invalid signature".to_string());
+    }
+    let parsed = parse_github_event(header(&headers, "X-GitHub-Event"), &body);
+    accept(state, parsed)
+}
+
+/// Handles `POST /hooks/gitlab`.
+pub(crate) async fn gitlab_hook_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, String) {
+    let Some(forge) = state.hooks.gitlab.as_ref() else {
+        return (
+            StatusCode::NOT_FOUND,
+            "GitLab hooks disabled: SERVE_GITLAB_WEBHOOK_SECRET not set".to_string(),
+        );
+    };
+    let token = header(&headers, "X-Gitlab-Token");
+    if !token_eq(forge.secret.expose().as_bytes(), token.as_bytes()) {
+        tracing::warn!("⚠️  Rejected GitLab webhook: bad token");
+        return (StatusCode::UNAUTHORIZED, "invalid token".to_string());
+    }
+    accept(state, parse_gitlab_event(&body))
+}
+
+/// Starts the run for an accepted comment in the background and answers the delivery.
+fn accept(
+    state: Arc<AppState>,
+    parsed: Result<Option<HookComment>, String>,
+) -> (StatusCode, String) {
+    let comment = match parsed {
+        Ok(Some(comment)) if state.hooks.accepts(&comment) => comment,
+        Ok(Some(comment)) if state.hooks.triggered_by(&comment) => {
+            tracing::warn!(
+                "⚠️  Refused webhook run for {} by @{}: author not allowed",
+                comment.target,
+                comment.author
+            );
+            return (
+                StatusCode::FORBIDDEN,
+                "comment author is not allowed to start runs".to_string(),
+            );
+        }
+        Ok(_) => return (StatusCode::OK, "ignored".to_string()),
+        Err(e) => return (StatusCode::BAD_REQUEST, format!("invalid payload: {}", e)),
+    };
+    tracing::info!(
+        "🪝 Webhook run for {} by @{}",
+        comment.target,
+        comment.author
+    );
+    tokio::spawn(run_and_reply(state, comment));
+    (StatusCode::ACCEPTED, "accepted".to_string())
+}
+
+/// Runs the agent on `comment` and posts the reply (or the run's error) back.
+async fn run_and_reply(state: Arc<AppState>, comment: HookComment) {
+    let request = state.hooks.run_request(&comment);
+    let run_config = state.run_config.current();
+    let mut sender = ReplyCollector::default();
+    let reply = match dispatch_run(
+        request,
+        &mut sender,
+        state.stores.workspace.current(),
+        state.stores.user_messages.current(),
+        &run_config,
+        &state.worker_pool,
+        &state.active_runs,
+    )
+    .await
+    {
+        Ok((_, _, final_response)) => {
+            if let Some(response) = final_response {
+                sender.observe(&response);
+            }
+            sender.reply
+        }
+        Err(e) => Some(format!("Run failed: {}", e)),
+    };
+    let reply = reply.unwrap_or_else(|| "Run ended without a reply.".to_string());
+    if let Err(e) = state.hooks.post_reply(&comment.target, &reply).await {
+        tracing::warn!(
+            "⚠️  Could not post webhook reply to {}: {}",
+            comment.target,
+            e
+        );
+    }
+}
+
+/// Keeps the final reply (or error) of a run; stream events are dropped.
+#[derive(Default)]
+struct ReplyCollector {
+    reply: Option<String>,
+}
+
+impl ReplyCollector {
+    fn observe(&mut self, response: &ServerResponse) {
+        match response {
+            ServerResponse::RunEnd(end) => self.reply = Some(end.reply.clone()),
+            ServerResponse::Error(e) => self.reply = Some(format!("Run failed: {}", e.error)),
+            _ => {}
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl RunStreamSender for ReplyCollector {
+    async fn send_response(
+        &mut self,
+        response: &ServerResponse,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.observe(response);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn github_comment(body: &str, user_type: &str) -> Vec<u8> {
+        github_comment_by(body, user_type, "alice", "MEMBER")
+    }
+
+    fn github_comment_by(body: &str, user_type: &str, login: &str, association: &str) -> Vec<u8> {
+        json!({
+            "action": "created",
+            "issue": { "number": 7, "title": "Crash on start" },
+            "comment": {
+                "body": body,
+                "user": { "login": login, "type": user_type },
+                "author_association": association
+            },
+            "repository": { "full_name": "acme/app" }
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    #[test]
+    fn github_signature_is_hmac_sha256_of_body() {
+        let mut mac = hmac::Hmac::<sha2::Sha256>::new_from_slice(b"s3cret").unwrap();
+        mac.update(b"{}");
+        let header = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+        assert!(verify_github_signature(b"s3cret", b"{}", &header));
+        assert!(!verify_github_signature(b"other", b"{}", &header));
+        assert!(!verify_github_signature(b"s3cret", b"{ }", &header));
+        assert!(!verify_github_signature(b"s3cret", b"{}", "sha256=zz"));
+        assert!(!verify_github_signature(b"s3cret", b"{}", ""));
+    }
+
+    #[test]
+    fn github_issue_comment_becomes_a_run_on_the_issue_thread() {
+        let comment = parse_github_event("issue_comment", &github_comment("@loom why?", "User"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            comment.target,
+            ReplyTarget::GitHub {
+                repo: "acme/app".to_string(),
+                number: 7
+            }
+        );
+        assert!(!comment.from_bot);
+        let config = HookConfig::default();
+        assert!(config.accepts(&comment));
+        let request = config.run_request(&comment);
+        assert_eq!(request.thread_id.as_deref(), Some("github-acme-app-7"));
+        assert_eq!(request.agent, AgentIdentifier::Type(AgentType::React));
+        assert_eq!(request.read_only, Some(true));
+        let UserContent::Text(message) = request.message else {
+            panic!("expected text message");
+        };
+        assert!(
+            message.contains("GitHub acme/app#7 \"Crash on start\""),
+            "{message}"
+        );
+        assert!(message.ends_with("@loom why?"), "{message}");
+    }
+
+    #[test]
+    fn github_ignores_other_events_and_actions() {
+        assert_eq!(parse_github_event("push", b"{}"), Ok(None));
+        let edited = json!({
+            "action": "edited",
+            "issue": { "number": 1, "title": "t" },
+            "comment": { "body": "@loom", "user": { "login": "a" } },
+            "repository": { "full_name": "a/b" }
+        })
+        .to_string();
+        assert_eq!(
+            parse_github_event("issue_comment", edited.as_bytes()),
+            Ok(None)
+        );
+        assert!(parse_github_event("issue_comment", b"not json").is_err());
+    }
+
+    #[test]
+    fn bots_own_replies_and_untriggered_comments_are_not_accepted() {
+        let config = HookConfig::default();
+        let parse = |body: &str, user_type: &str| {
+            parse_github_event("issue_comment", &github_comment(body, user_type))
+                .unwrap()
+                .unwrap()
+        };
+        assert!(!config.accepts(&parse("@loom hi", "Bot")));
+        assert!(!config.accepts(&parse(&format!("@loom hi\n\n{}", REPLY_MARKER), "User")));
+        assert!(!config.accepts(&parse("just a comment", "User")));
+        let everything = HookConfig {
+            trigger: String::new(),
+            ..HookConfig::default()
+        };
+        assert!(everything.accepts(&parse("just a comment", "User")));
+    }
+
+    #[test]
+    fn gitlab_merge_request_note_targets_the_merge_request() {
+        let body = json!({
+            "object_kind": "note",
+            "user": { "username": "bob" },
+            "project": { "id": 42, "path_with_namespace": "group/app" },
+            "object_attributes": { "note": "@loom review", "noteable_type": "MergeRequest" },
+            "merge_request": { "iid": 3, "title": "Add cache" }
+        })
+        .to_string();
+        let comment = parse_gitlab_event(body.as_bytes()).unwrap().unwrap();
+        assert_eq!(comment.target.thread_id(), "gitlab-42-merge_requests-3");
+        assert_eq!(comment.target.to_string(), "GitLab group/app!3");
+        assert_eq!(comment.author, "bob");
+        assert!(!HookConfig::default().accepts(&comment));
+        let bob_allowed = HookConfig {
+            allowed_users: vec!["Bob".to_string()],
+            ..HookConfig::default()
+        };
+        assert!(bob_allowed.accepts(&comment));
+
+        let push = json!({ "object_kind": "push" }).to_string();
+        assert_eq!(parse_gitlab_event(push.as_bytes()), Ok(None));
+        let commit_note = json!({
+            "object_kind": "note",
+            "user": { "username": "bob" },
+            "project": { "id": 42, "path_with_namespace": "group/app" },
+            "object_attributes": { "note": "@loom", "noteable_type": "Commit" }
+        })
+        .to_string();
+        assert_eq!(parse_gitlab_event(commit_note.as_bytes()), Ok(None));
+    }
+
+    #[test]
+    fn outside_authors_are_refused_unless_listed() {
+        let parse = |login: &str, association: &str| {
+            let body = github_comment_by("@loom run rm -rf /", "User", login, association);
+            parse_github_event("issue_comment", &body).unwrap().unwrap()
+        };
+        let config = HookConfig::default();
+        let outsider = parse("mallory", "NONE");
+        assert!(config.triggered_by(&outsider));
+        assert!(!config.accepts(&outsider));
+        assert!(!config.accepts(&parse("carol", "CONTRIBUTOR")));
+        assert!(config.accepts(&parse("owner", "OWNER")));
+        assert!(config.accepts(&parse("dave", "COLLABORATOR")));
+
+        let listed = HookConfig {
+            allowed_users: vec!["mallory".to_string()],
+            ..HookConfig::default()
+        };
+        assert!(listed.accepts(&outsider));
+    }
+
+    #[test]
+    fn write_tools_need_explicit_opt_in() {
+        let comment = parse_github_event("issue_comment", &github_comment("@loom fix it", "User"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            HookConfig::default().run_request(&comment).read_only,
+            Some(true)
+        );
+        let writable = HookConfig {
+            allow_write: true,
+            ..HookConfig::default()
+        };
+        assert_eq!(writable.run_request(&comment).read_only, Some(false));
+    }
+}
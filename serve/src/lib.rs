@@ -1,15 +1,47 @@
 //! WebSocket server for Loom (axum + ws).
 //!
-//! Listens on ws://127.0.0.1:8080, handles run, tools_list, tool_show, agent_list, workspace_*, ping.
+//! Listens on ws://127.0.0.1:8080, handles run, tools_list, tool_show, agent_list, workspace_*,
+//! usage_report, event_schema_list, ping. Each run's token usage is recorded in the workspace store.
+//! Every request gets one access-log record (`serve::access` tracing target, plus NDJSON lines
+//! in the file named by `SERVE_ACCESS_LOG`).
+//! `GET /healthz` reports whether the workspace and user-message stores are degraded (see
+//! `stores`).
+//! Configuration is reloaded on SIGHUP or an `admin_reload` request (see `reload`).
+//! Operators list, inspect and kill the runs of all connections with the `active_runs`,
+//! `run_inspect` and `run_kill` admin requests.
+//! `session_start` / `session_message` / `session_end` give clients a server-managed thread
+//! (see `session`).
+//! With the `grpc` feature and `SERVE_GRPC_ADDR` set, the same run, tools_list and ping API is
+//! also served over gRPC (see `proto/loom.proto`).
+//! `POST /hooks/github` and `POST /hooks/gitlab` run the agent on issue and pull/merge request
+//! comments and post the reply back, when their webhook secrets are set (see `hooks`).
+//! With `SERVE_WORKERS` set, runs execute in worker processes ([`run_worker`]) so a crashing
+//! run cannot take down the server.
+//! With the `test-util` feature, [`testing`] runs the server in-process with a scripted LLM for
+//! end-to-end tests of apps built on loom.
 //!
-//! **Public API**: [`run_serve`], [`run_serve_on_listener`].
+//! **Public API**: [`run_serve`], [`run_serve_on_listener`], [`router_from_builder`],
+//! [`router_with_hooks`], [`run_worker`].
 
+mod access_log;
+mod admin;
 mod agents;
 mod app;
+mod approvals;
 mod connection;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+mod hooks;
+mod limits;
 mod models;
+mod reload;
 mod response;
 mod run;
+mod session;
+mod state_show;
+mod stores;
+#[cfg(feature = "test-util")]
+pub mod testing;
 mod tools;
 mod user_messages;
 mod workspace;
@@ -18,9 +50,9 @@ use std::sync::Arc;
 use std::time::Instant;
 use tokio::net::TcpListener;
 use tokio::sync::oneshot;
-use tracing::{error, info, warn};
+use tracing::{error, info};
 
-use app::{router, run_config_from_env, AppState, RunConfig};
+use app::{router, run_config_from_builder, run_config_from_env, AppState, SharedRunConfig};
 use loom::llm::{ModelRegistry, ProviderConfig};
 
 const DEFAULT_WS_ADDR: &str = "127.0.0.1:8080";
@@ -40,8 +72,15 @@ pub async fn run_serve_on_listener(
     // Setup basic components
     info!("📦 Setting up server components...");
     let (shutdown_tx, shutdown_rx) = oneshot::channel();
-    let workspace_store = setup_workspace_store();
-    let user_message_store = setup_user_message_store();
+    let store_mode = stores::DegradationMode::from_env();
+    let stores = stores::Stores::open(store_mode);
+    if store_mode == stores::DegradationMode::FailFast {
+        if let Some(failure) = stores.failure() {
+            error!("❌ {} (SERVE_STORE_DEGRADATION=fail_fast)", failure);
+            return Err(failure.into());
+        }
+    }
+    stores.spawn_reconnect();
     info!("✅ Server components setup complete");
 
     // Initialize ModelRegistry and load providers from config
@@ -110,16 +149,19 @@ pub async fn run_serve_on_listener(
         info!("  {}. {} ({})", i + 1, model.name, model.provider);
     }
 
-    let run_config = RunConfig::default();
+    let run_config = SharedRunConfig::new(run_config_from_env());
+    let worker_pool = run::WorkerPool::from_env();
+    #[cfg(unix)]
+    reload::spawn_sighup_reload(run_config.clone(), worker_pool.clone());
 
     info!("🚀 Starting server with configuration:");
     info!(
         "  Event queue capacity: {}",
-        run_config.event_queue_capacity
+        run_config.current().event_queue_capacity
     );
     info!(
         "  Append queue capacity: {}",
-        run_config.append_queue_capacity
+        run_config.current().append_queue_capacity
     );
 
     let state = Arc::new(AppState {
@@ -128,12 +170,30 @@ pub async fn run_serve_on_listener(
         } else {
             None
         })),
-        workspace_store,
-        user_message_store,
-        run_config: run_config_from_env(),
+        stores,
+        run_config,
         providers: Arc::new(providers),
+        model_catalog: models::spawn_model_catalog(),
+        access_log: Arc::new(access_log::AccessLog::from_env()),
+        detached_runs: run::DetachedRuns::from_env(),
+        worker_pool,
+        active_runs: run::ActiveRuns::default(),
+        sessions: session::Sessions::default(),
+        hooks: Arc::new(hooks::HookConfig::from_env()),
     });
 
+    #[cfg(feature = "grpc")]
+    if let Some(grpc_addr) = grpc::grpc_addr_from_env() {
+        let grpc_listener = TcpListener::bind(&grpc_addr).await?;
+        info!("🛰️  gRPC endpoint: {}", grpc_listener.local_addr()?);
+        let grpc_state = state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = grpc::serve_grpc(grpc_listener, grpc_state).await {
+                error!("❌ gRPC server stopped: {}", e);
+            }
+        });
+    }
+
     let app = router(state);
 
     info!("✅ Server initialization complete, ready to accept connections");
@@ -157,6 +217,54 @@ pub async fn run_serve_on_listener(
     Ok(())
 }
 
+/// WebSocket router (route `/`) for embedding the server in your own axum app, configured from
+/// a [`loom::LoomBuilder`].
+///
+/// Each run builds its own agent from the request and the environment, so only the builder's
+/// model (for runs that name none), system prompt, tool allowlist, read-only mode, working
+/// folder (for runs whose request and workspace set none) and provider, base URL and API key
+/// (where the run's model resolution leaves them unset) apply; see [`loom::LoomBuilder::build`] for a single
+/// in-process runner. A builder with a custom `llm()`, `mcp_server()` or `deny_tools()` is
+/// refused with an error, since runs cannot apply them. Stores and the access log come from the
+/// environment as in [`run_serve`] (`SERVE_STORE_DEGRADATION=fail_fast` only logs here); the
+/// provider list is empty, so model listing requests return no models. An `admin_reload`
+/// request reloads the run settings from the environment and applies the builder's again.
+pub fn router_from_builder(
+    builder: &loom::LoomBuilder,
+) -> Result<axum::Router, Box<dyn std::error::Error + Send + Sync>> {
+    router_with_hooks(builder, loom::RunHooks::default())
+}
+
+/// [`router_from_builder`] with run hooks: `hooks` fire when each run of the server starts and
+/// with its final [`loom::RunEndResponse`] or error (WebSocket, gRPC and webhook runs alike), so
+/// the app can send notifications, update billing or invalidate caches without polling run
+/// history. The hooks survive `admin_reload`.
+pub fn router_with_hooks(
+    builder: &loom::LoomBuilder,
+    hooks: loom::RunHooks<loom::RunEndResponse>,
+) -> Result<axum::Router, Box<dyn std::error::Error + Send + Sync>> {
+    let run_config = run_config_from_builder(builder)?;
+    let stores = stores::Stores::open(stores::DegradationMode::from_env());
+    stores.spawn_reconnect();
+    let state = Arc::new(AppState {
+        shutdown_tx: Arc::new(std::sync::Mutex::new(None)),
+        stores,
+        run_config: SharedRunConfig::new(app::RunConfig {
+            run_hooks: hooks,
+            ..run_config
+        }),
+        providers: Arc::new(Vec::new()),
+        model_catalog: None,
+        access_log: Arc::new(access_log::AccessLog::from_env()),
+        detached_runs: run::DetachedRuns::from_env(),
+        worker_pool: run::WorkerPool::from_env(),
+        active_runs: run::ActiveRuns::default(),
+        sessions: session::Sessions::default(),
+        hooks: Arc::new(hooks::HookConfig::from_env()),
+    });
+    Ok(router(state))
+}
+
 /// Runs the WebSocket server. Listens on `addr` (default 127.0.0.1:8080).
 /// When `once` is true, accepts one connection, handles it, then returns (process exits).
 pub async fn run_serve(
@@ -168,32 +276,16 @@ pub async fn run_serve(
     run_serve_on_listener(listener, once).await
 }
 
-/// Setup workspace store from environment
-fn setup_workspace_store() -> Option<Arc<loom_workspace::Store>> {
-    let db_path = std::env::var("WORKSPACE_DB")
-        .ok()
-        .unwrap_or_else(|| "workspace.db".to_string());
-
-    loom_workspace::Store::new(&db_path)
-        .ok()
-        .map(Arc::new)
-        .inspect(|_| info!("✓ Workspace store initialized"))
-}
-
-/// Setup user message store from environment
-fn setup_user_message_store() -> Option<std::sync::Arc<dyn loom::UserMessageStore>> {
-    let db_path = std::env::var("USER_MESSAGE_DB")
-        .ok()
-        .unwrap_or_else(|| "serve.db".to_string());
-
-    match loom::SqliteUserMessageStore::new(&db_path) {
-        Ok(store) => {
-            info!("✓ User message store initialized (db: {})", db_path);
-            Some(Arc::new(store) as Arc<dyn loom::UserMessageStore>)
-        }
-        Err(e) => {
-            warn!("⚠️  Failed to init user message store: {}", e);
-            None
-        }
-    }
+/// Runs a worker process of the `SERVE_WORKERS` pool (`loom worker`): reads runs from stdin and
+/// writes their responses to stdout, one JSON object per line, until stdin closes. Stores come
+/// from the environment, as for [`run_serve`]; run settings come with each run from the server.
+pub async fn run_worker() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let stores = stores::Stores::open(stores::DegradationMode::from_env());
+    run::serve_worker(
+        tokio::io::BufReader::new(tokio::io::stdin()),
+        tokio::io::stdout(),
+        stores.workspace.current(),
+        stores.user_messages.current(),
+    )
+    .await
 }
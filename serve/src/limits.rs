@@ -0,0 +1,197 @@
+//! Incoming request limits: frame size, attachment size, and JSON nesting depth.
+//!
+//! Checked by the connection loop before a frame is parsed (size, depth) and before a run starts
+//! (attachments), so oversized input is rejected with an `ErrorResponse` (`code:
+//! payload_too_large`) instead of reaching the run task.
+
+use loom::{ContentPart, ErrorResponse, ServerResponse, UserContent, ERROR_CODE_PAYLOAD_TOO_LARGE};
+
+/// Size and nesting limits for incoming WebSocket requests.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct RequestLimits {
+    /// Max bytes of one incoming message (text or binary frame payload).
+    pub(crate) max_message_bytes: usize,
+    /// Max bytes of one inline attachment (base64 image/audio/video/pdf/file data) in a run message.
+    pub(crate) max_attachment_bytes: usize,
+    /// Max nesting depth of arrays/objects in a request's JSON.
+    pub(crate) max_json_depth: usize,
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        Self {
+            max_message_bytes: 16 * 1024 * 1024,
+            max_attachment_bytes: 10 * 1024 * 1024,
+            max_json_depth: 64,
+        }
+    }
+}
+
+impl RequestLimits {
+    /// Hard cap passed to the WebSocket transport. Frames between `max_message_bytes` and this
+    /// cap still get a structured error; larger ones close the connection before being buffered.
+    pub(crate) fn transport_max_message_bytes(&self) -> usize {
+        self.max_message_bytes.saturating_mul(2)
+    }
+
+    /// Checks a raw request frame before it is parsed.
+    pub(crate) fn check_frame(&self, text: &str) -> Result<(), String> {
+        if text.len() > self.max_message_bytes {
+            return Err(format!(
+                "message is {} bytes, limit is {} bytes",
+                text.len(),
+                self.max_message_bytes
+            ));
+        }
+        if json_depth_exceeds(text, self.max_json_depth) {
+            return Err(format!(
+                "JSON nesting exceeds depth limit {}",
+                self.max_json_depth
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks inline attachments of a run message.
+    pub(crate) fn check_message(&self, message: &UserContent) -> Result<(), String> {
+        let UserContent::Multimodal(parts) = message else {
+            return Ok(());
+        };
+        for part in parts {
+            let (kind, len) = match part {
+                ContentPart::ImageBase64 { data, .. } => ("image", data.len()),
+                ContentPart::AudioBase64 { data, .. } => ("audio", data.len()),
+                ContentPart::VideoBase64 { data, .. } => ("video", data.len()),
+                ContentPart::PdfBase64 { data } => ("pdf", data.len()),
+                ContentPart::File {
+                    file_data: Some(data),
+                    ..
+                } => ("file", data.len()),
+                _ => continue,
+            };
+            if len > self.max_attachment_bytes {
+                return Err(format!(
+                    "{} attachment is {} bytes, limit is {} bytes",
+                    kind, len, self.max_attachment_bytes
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Builds RequestLimits from environment variables, falling back to [`Default`] for unset or invalid values.
+///
+/// - `SERVE_MAX_MESSAGE_BYTES` (default 16 MiB)
+/// - `SERVE_MAX_ATTACHMENT_BYTES` (default 10 MiB)
+/// - `SERVE_MAX_JSON_DEPTH` (default 64)
+pub(crate) fn request_limits_from_env() -> RequestLimits {
+    let default = RequestLimits::default();
+    let var = |name: &str, fallback: usize| {
+        std::env::var(name)
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+            .filter(|v: &usize| *v > 0)
+            .unwrap_or(fallback)
+    };
+    RequestLimits {
+        max_message_bytes: var("SERVE_MAX_MESSAGE_BYTES", default.max_message_bytes),
+        max_attachment_bytes: var("SERVE_MAX_ATTACHMENT_BYTES", default.max_attachment_bytes),
+        max_json_depth: var("SERVE_MAX_JSON_DEPTH", default.max_json_depth),
+    }
+}
+
+/// `payload_too_large` error response for a rejected request.
+pub(crate) fn payload_too_large(id: Option<String>, error: String) -> ServerResponse {
+    ServerResponse::Error(ErrorResponse {
+        id,
+        error,
+        code: Some(ERROR_CODE_PAYLOAD_TOO_LARGE.to_string()),
+    })
+}
+
+/// Returns true when `text` has arrays/objects nested deeper than `max_depth`.
+/// Brackets inside JSON strings are ignored; the text does not need to be valid JSON.
+fn json_depth_exceeds(text: &str, max_depth: usize) -> bool {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for b in text.bytes() {
+        if in_string {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match b {
+            b'"' => in_string = true,
+            b'{' | b'[' => {
+                depth += 1;
+                if depth > max_depth {
+                    return true;
+                }
+            }
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_depth_ignores_brackets_in_strings() {
+        assert!(!json_depth_exceeds(r#"{"a":[1,{"b":"[[[[{{{{"}]}"#, 3));
+        assert!(json_depth_exceeds(r#"{"a":[1,{"b":[2]}]}"#, 3));
+        assert!(!json_depth_exceeds(r#"{"s":"quote \" then [[["}"#, 1));
+    }
+
+    #[test]
+    fn check_frame_rejects_large_and_deep_payloads() {
+        let limits = RequestLimits {
+            max_message_bytes: 32,
+            max_attachment_bytes: 8,
+            max_json_depth: 2,
+        };
+        assert!(limits.check_frame(r#"{"type":"ping","id":"1"}"#).is_ok());
+        assert!(limits
+            .check_frame(&format!(r#"{{"x":"{}"}}"#, "a".repeat(40)))
+            .unwrap_err()
+            .contains("limit is 32 bytes"));
+        assert!(limits
+            .check_frame(r#"{"a":[[1]]}"#)
+            .unwrap_err()
+            .contains("depth"));
+    }
+
+    #[test]
+    fn check_message_rejects_large_attachments() {
+        let limits = RequestLimits {
+            max_attachment_bytes: 8,
+            ..RequestLimits::default()
+        };
+        let small = UserContent::Multimodal(vec![
+            ContentPart::Text {
+                text: "a long text part is not an attachment".to_string(),
+            },
+            ContentPart::ImageBase64 {
+                media_type: "image/png".to_string(),
+                data: "AAAA".to_string(),
+            },
+        ]);
+        assert!(limits.check_message(&small).is_ok());
+        let large = UserContent::Multimodal(vec![ContentPart::PdfBase64 {
+            data: "A".repeat(9),
+        }]);
+        assert!(limits
+            .check_message(&large)
+            .unwrap_err()
+            .starts_with("pdf attachment"));
+    }
+}
@@ -1,15 +1,70 @@
-//! Model request handlers.
+//! Model request handlers and the model catalog refresher.
+//!
+//! The catalog (context/output limits from models.dev) is kept fresh by a background
+//! [`ResolverRefresher`] and persisted under `~/.loom/cache`, so `list_models` answers with
+//! token limits without fetching on the request path.
+
+use std::sync::Arc;
+use std::time::Duration;
 
 use loom::{
     llm::{ModelRegistry, ProviderConfig},
+    model_spec::default_catalog_cache_path,
     protocol::responses::ModelInfo,
-    ServerResponse,
+    CachedResolver, ModelsDevResolver, ResolverRefresher, ServerResponse,
 };
 
-/// Handle list_models request
+/// Model limits catalog shared by all connections.
+pub(crate) type ModelCatalog = Arc<CachedResolver<ModelsDevResolver>>;
+
+const DEFAULT_CATALOG_REFRESH_SECS: u64 = 6 * 60 * 60;
+const DEFAULT_CATALOG_JITTER_SECS: u64 = 5 * 60;
+
+/// Starts the catalog refresher from the environment:
+///
+/// - `SERVE_MODEL_CATALOG_REFRESH_SECS`: refresh interval (default 6h); `0` disables the catalog
+/// - `SERVE_MODEL_CATALOG_JITTER_SECS`: random extra delay per refresh (default 5min)
+/// - `SERVE_MODEL_CATALOG_CACHE`: persisted catalog file (default `~/.loom/cache/models.json`)
+pub(crate) fn spawn_model_catalog() -> Option<ModelCatalog> {
+    let secs = |name: &str, default: u64| {
+        std::env::var(name)
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(default)
+    };
+    let interval = secs(
+        "SERVE_MODEL_CATALOG_REFRESH_SECS",
+        DEFAULT_CATALOG_REFRESH_SECS,
+    );
+    if interval == 0 {
+        return None;
+    }
+    let cache_file = std::env::var("SERVE_MODEL_CATALOG_CACHE")
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+        .map(Into::into)
+        .unwrap_or_else(default_catalog_cache_path);
+    let catalog: ModelCatalog = Arc::new(CachedResolver::new(ModelsDevResolver::new()));
+    tracing::info!(
+        "  Model catalog: refresh every {}s, cache {}",
+        interval,
+        cache_file.display()
+    );
+    ResolverRefresher::new(catalog.clone(), Duration::from_secs(interval))
+        .with_jitter(Duration::from_secs(secs(
+            "SERVE_MODEL_CATALOG_JITTER_SECS",
+            DEFAULT_CATALOG_JITTER_SECS,
+        )))
+        .with_cache_file(cache_file)
+        .spawn();
+    Some(catalog)
+}
+
+/// Handle list_models request. Token limits come from `catalog` when it knows the model.
 pub(crate) async fn handle_list_models(
     request: loom::ListModelsRequest,
     providers: &[ProviderConfig],
+    catalog: Option<&ModelCatalog>,
 ) -> ServerResponse {
     let registry = ModelRegistry::global();
     let model_entries = registry.list_all_models(providers).await;
@@ -19,24 +74,28 @@ pub(crate) async fn handle_list_models(
         model_entries.len()
     );
 
-    let models = model_entries
-        .into_iter()
-        .map(|entry| {
-            tracing::debug!(
-                "🤖 Model: id={}, name={}, provider={}",
-                entry.id,
-                entry.name,
-                entry.provider
-            );
-            ModelInfo {
-                id: entry.id.clone(),
-                name: entry.name.clone(),
-                provider: entry.provider.clone(),
-                family: None,       // ModelEntry doesn't provide family info
-                capabilities: None, // ModelEntry doesn't provide capabilities info
-            }
-        })
-        .collect();
+    let mut models = Vec::with_capacity(model_entries.len());
+    for entry in model_entries {
+        tracing::debug!(
+            "🤖 Model: id={}, name={}, provider={}",
+            entry.id,
+            entry.name,
+            entry.provider
+        );
+        let spec = match catalog {
+            Some(catalog) => catalog.get_cached(&entry.provider, &entry.name).await,
+            None => None,
+        };
+        models.push(ModelInfo {
+            id: entry.id.clone(),
+            name: entry.name.clone(),
+            provider: entry.provider.clone(),
+            family: None,       // ModelEntry doesn't provide family info
+            capabilities: None, // ModelEntry doesn't provide capabilities info
+            context_limit: spec.as_ref().map(|s| s.context_limit),
+            output_limit: spec.as_ref().map(|s| s.output_limit),
+        });
+    }
 
     ServerResponse::ListModels(loom::ListModelsResponse {
         id: request.id,
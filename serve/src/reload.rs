@@ -0,0 +1,157 @@
+//! Live configuration reload: re-applies `.env` / `config.toml` and rebuilds [`RunConfig`] on
+//! SIGHUP or an authenticated `admin_reload` request.
+//!
+//! Open connections are kept. Runs already in progress keep the settings they started with;
+//! requests handled after the reload see the new ones, and idle pool workers are retired so
+//! later runs start in workers with the reloaded environment. Agent profiles are read from disk per run
+//! and need no reload. The WebSocket frame size limit is fixed per connection at upgrade time.
+//!
+//! [`RunConfig`]: crate::app::RunConfig
+
+use loom::{AdminReloadRequest, AdminReloadResponse, ServerResponse};
+
+use crate::admin::authorize;
+use crate::app::{run_config_from_env, with_builder_defaults, RunConfig, SharedRunConfig};
+use crate::run::WorkerPool;
+
+/// What one reload changed.
+pub(crate) struct ReloadOutcome {
+    pub(crate) reloaded: Vec<String>,
+    pub(crate) warnings: Vec<String>,
+}
+
+/// Re-applies config files to the environment, then swaps in a [`RunConfig`](crate::app::RunConfig)
+/// built from it, with the server's builder settings (if any) applied again. A config file error is reported as a warning; the run config is still rebuilt
+/// from the (unchanged) environment so `SERVE_ROLE_FILE` edits are picked up. Idle workers of
+/// `worker_pool` are retired.
+pub(crate) fn reload(run_config: &SharedRunConfig, worker_pool: &WorkerPool) -> ReloadOutcome {
+    let mut reloaded = Vec::new();
+    let mut warnings = Vec::new();
+    match config::reload_and_apply_with_report("loom", None) {
+        Ok(report) => {
+            tracing::info!("{}", report.summary());
+            reloaded.push("config_files".to_string());
+        }
+        Err(e) => {
+            tracing::warn!("⚠️  Config files not reloaded: {}", e);
+            warnings.push(format!("config files not reloaded: {}", e));
+        }
+    }
+    let current = run_config.current();
+    run_config.replace(with_builder_defaults(
+        RunConfig {
+            llm_script: current.llm_script.clone(),
+            run_hooks: current.run_hooks.clone(),
+            ..run_config_from_env()
+        },
+        current.builder.clone(),
+    ));
+    reloaded.push("run_config".to_string());
+    if worker_pool.enabled() {
+        worker_pool.retire_idle();
+        reloaded.push("workers".to_string());
+    }
+    tracing::info!("🔄 Configuration reloaded: {}", reloaded.join(", "));
+    ReloadOutcome { reloaded, warnings }
+}
+
+/// Handles `admin_reload`: checks the token against `SERVE_ADMIN_TOKEN`, then reloads.
+pub(crate) fn handle_admin_reload(
+    r: AdminReloadRequest,
+    run_config: &SharedRunConfig,
+    worker_pool: &WorkerPool,
+) -> ServerResponse {
+    if let Err(resp) = authorize("admin_reload", &r.id, &r.token) {
+        return resp;
+    }
+    let outcome = reload(run_config, worker_pool);
+    ServerResponse::AdminReload(AdminReloadResponse {
+        id: r.id,
+        reloaded: outcome.reloaded,
+        warnings: outcome.warnings,
+    })
+}
+
+/// Reloads on every SIGHUP until the process exits.
+#[cfg(unix)]
+pub(crate) fn spawn_sighup_reload(run_config: SharedRunConfig, worker_pool: WorkerPool) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangups = match signal(SignalKind::hangup()) {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::warn!("⚠️  SIGHUP reload unavailable: {}", e);
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        while hangups.recv().await.is_some() {
+            tracing::info!("🔄 SIGHUP received, reloading configuration");
+            reload(&run_config, &worker_pool);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::admin::ADMIN_TOKEN_ENV;
+    use crate::app::RunConfig;
+    use loom::ERROR_CODE_UNAUTHORIZED;
+
+    #[test]
+    fn admin_reload_without_configured_token_is_unauthorized() {
+        if std::env::var(ADMIN_TOKEN_ENV).is_ok() {
+            return;
+        }
+        let shared = SharedRunConfig::new(RunConfig::default());
+        let resp = handle_admin_reload(
+            AdminReloadRequest {
+                id: "r1".to_string(),
+                token: String::new(),
+            },
+            &shared,
+            &WorkerPool::default(),
+        );
+        match resp {
+            ServerResponse::Error(e) => {
+                assert_eq!(e.id.as_deref(), Some("r1"));
+                assert_eq!(e.code.as_deref(), Some(ERROR_CODE_UNAUTHORIZED));
+            }
+            other => panic!("expected unauthorized error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reload_keeps_builder_read_only_and_allowlist() {
+        let builder = loom::LoomBuilder::new(loom::ReactBuildConfig::from_env())
+            .read_only(true)
+            .allowed_tools(["read"]);
+        let shared = SharedRunConfig::new(crate::app::run_config_from_builder(&builder).unwrap());
+        reload(&shared, &WorkerPool::default());
+        let after = shared.current();
+        assert!(after.read_only);
+        assert_eq!(
+            after.allowed_tools.as_deref(),
+            Some(&["read".to_string()][..])
+        );
+        assert!(after.builder.is_some());
+    }
+
+    #[test]
+    fn shared_run_config_snapshot_survives_replace() {
+        let shared = SharedRunConfig::new(RunConfig::default());
+        let before = shared.current();
+        shared.replace(RunConfig {
+            display_max_len: 10,
+            allowed_tools: Some(vec!["read".to_string()]),
+            ..RunConfig::default()
+        });
+        assert_eq!(before.display_max_len, 2000);
+        assert_eq!(shared.current().display_max_len, 10);
+        assert_eq!(
+            shared.current().allowed_tools.as_deref(),
+            Some(&["read".to_string()][..])
+        );
+    }
+}
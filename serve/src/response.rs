@@ -1,19 +1,44 @@
-//! Send a single `ServerResponse` as JSON over the WebSocket.
+//! Send a single `ServerResponse` over the WebSocket, as JSON text or (when the connection
+//! negotiated the `loom.msgpack` or `loom.json.deflate` subprotocol) a MessagePack or
+//! compressed JSON binary frame.
 
 use axum::extract::ws::{Message, WebSocket};
+use loom::protocol::encoding::{encode, EncodedFrame, WireEncoding};
 use loom::{ErrorResponse, ServerResponse};
 
+/// Encoding of server messages on `socket`, from the subprotocol accepted at upgrade.
+pub(crate) fn socket_encoding(socket: &WebSocket) -> WireEncoding {
+    WireEncoding::from_subprotocol(socket.protocol().and_then(|p| p.to_str().ok()))
+}
+
+/// Sends `response`; returns the encoded frame size in bytes.
 pub(crate) async fn send_response(
     socket: &mut WebSocket,
     response: &ServerResponse,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let json = serde_json::to_string(response).unwrap_or_else(|_| {
-        serde_json::to_string(&ServerResponse::Error(ErrorResponse {
-            id: None,
-            error: "serialization error".to_string(),
-        }))
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    let encoding = socket_encoding(socket);
+    let frame = encode(response, encoding).unwrap_or_else(|e| {
+        tracing::error!("❌ Failed to encode response: {}", e);
+        encode(
+            &ServerResponse::Error(ErrorResponse {
+                id: None,
+                error: "serialization error".to_string(),
+                code: None,
+            }),
+            encoding,
+        )
         .unwrap()
     });
-    socket.send(Message::Text(json)).await?;
-    Ok(())
+    let (message, len) = match frame {
+        EncodedFrame::Text(text) => {
+            let len = text.len();
+            (Message::Text(text), len)
+        }
+        EncodedFrame::Binary(bytes) => {
+            let len = bytes.len();
+            (Message::Binary(bytes), len)
+        }
+    };
+    socket.send(message).await?;
+    Ok(len)
 }
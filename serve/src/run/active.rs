@@ -0,0 +1,346 @@
+//! Server-wide registry of runs in progress, behind the admin requests `active_runs`,
+//! `run_inspect` and `run_kill`.
+//!
+//! [`dispatch_run`](super::dispatch_run) wraps each run's sender in an [`ActiveRunSender`]: it
+//! registers the run, follows its current node and token usage from the events it sends (so
+//! runs on worker processes are covered too) and delivers a kill as a `cancel_run` control,
+//! which the run acknowledges to its own client. A detached run can be killed too. The run is
+//! removed when its sender is dropped.
+
+use async_trait::async_trait;
+use loom::protocol::requests::CancelRunRequest;
+use loom::{
+    ActiveRunInfo, ActiveRunsRequest, ActiveRunsResponse, ErrorResponse, ProtocolEvent,
+    RunInspectRequest, RunInspectResponse, RunKillRequest, RunKillResponse, ServerResponse,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::Notify;
+
+use super::delivery::{RunControl, RunStreamSender};
+use crate::admin::authorize;
+
+/// Runs in progress on this server, by run id.
+#[derive(Clone, Default)]
+pub(crate) struct ActiveRuns {
+    runs: Arc<Mutex<HashMap<String, Arc<ActiveRun>>>>,
+}
+
+/// One registered run: what `run_inspect` reports, plus its kill signal.
+struct ActiveRun {
+    started: Instant,
+    /// Reported state; `current_node` and usage are updated as events are sent.
+    info: Mutex<ActiveRunInfo>,
+    /// Id of the first `run_kill` request for the run.
+    kill_request: Mutex<Option<String>>,
+    killed: Notify,
+}
+
+impl ActiveRun {
+    fn snapshot(&self) -> ActiveRunInfo {
+        let mut info = lock(&self.info).clone();
+        info.elapsed_ms = self.started.elapsed().as_millis() as u64;
+        info
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as i64)
+}
+
+impl ActiveRuns {
+    /// Registers run `run_id` of `r`; the returned sender wraps `sender` and deregisters the run
+    /// when dropped.
+    pub(crate) fn track<'a, S>(
+        &self,
+        run_id: &str,
+        r: &loom::RunRequest,
+        sender: &'a mut S,
+    ) -> ActiveRunSender<'a, S> {
+        let run = Arc::new(ActiveRun {
+            started: Instant::now(),
+            info: Mutex::new(ActiveRunInfo {
+                run_id: run_id.to_string(),
+                agent: r.agent.to_string(),
+                thread_id: r.thread_id.clone(),
+                workspace_id: r.workspace_id.clone(),
+                started_at_ms: now_ms(),
+                elapsed_ms: 0,
+                current_node: None,
+                prompt_tokens: 0,
+                completion_tokens: 0,
+                total_tokens: 0,
+            }),
+            kill_request: Mutex::new(None),
+            killed: Notify::new(),
+        });
+        lock(&self.runs).insert(run_id.to_string(), Arc::clone(&run));
+        ActiveRunSender {
+            inner: sender,
+            runs: self.clone(),
+            run_id: run_id.to_string(),
+            run,
+            inner_open: true,
+            kill_sent: false,
+        }
+    }
+
+    /// Runs in progress, oldest first.
+    pub(crate) fn list(&self) -> Vec<ActiveRunInfo> {
+        let mut runs: Vec<Arc<ActiveRun>> = lock(&self.runs).values().cloned().collect();
+        runs.sort_by_key(|run| run.started);
+        runs.iter().map(|run| run.snapshot()).collect()
+    }
+
+    /// Current state of run `run_id`; `None` when it is not running.
+    pub(crate) fn inspect(&self, run_id: &str) -> Option<ActiveRunInfo> {
+        let run = lock(&self.runs).get(run_id).cloned()?;
+        Some(run.snapshot())
+    }
+
+    /// Asks run `run_id` to cancel on behalf of request `request_id`. `false` when it is not
+    /// running.
+    pub(crate) fn kill(&self, run_id: &str, request_id: &str) -> bool {
+        let Some(run) = lock(&self.runs).get(run_id).cloned() else {
+            return false;
+        };
+        lock(&run.kill_request).get_or_insert_with(|| request_id.to_string());
+        run.killed.notify_one();
+        true
+    }
+}
+
+/// [`RunStreamSender`] of a registered run: records its node and usage and adds a kill to the
+/// controls of `inner`. Controls stay open until the run was killed, also after `inner` stops
+/// delivering them (client gone, run detached).
+pub(crate) struct ActiveRunSender<'a, S> {
+    inner: &'a mut S,
+    runs: ActiveRuns,
+    run_id: String,
+    run: Arc<ActiveRun>,
+    inner_open: bool,
+    kill_sent: bool,
+}
+
+impl<S> Drop for ActiveRunSender<'_, S> {
+    fn drop(&mut self) {
+        lock(&self.runs.runs).remove(&self.run_id);
+    }
+}
+
+#[async_trait]
+impl<S: RunStreamSender> RunStreamSender for ActiveRunSender<'_, S> {
+    async fn send_response(
+        &mut self,
+        response: &ServerResponse,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if let ServerResponse::RunStreamEvent(r) = response {
+            let mut info = lock(&self.run.info);
+            match &r.event.event {
+                ProtocolEvent::NodeEnter { id } => info.current_node = Some(id.clone()),
+                ProtocolEvent::Usage {
+                    prompt_tokens,
+                    completion_tokens,
+                    total_tokens,
+                } => {
+                    info.prompt_tokens += u64::from(*prompt_tokens);
+                    info.completion_tokens += u64::from(*completion_tokens);
+                    info.total_tokens += u64::from(*total_tokens);
+                }
+                _ => {}
+            }
+        }
+        self.inner.send_response(response).await
+    }
+
+    async fn recv_control(&mut self, run_id: &str) -> Option<RunControl> {
+        loop {
+            tokio::select! {
+                control = self.inner.recv_control(run_id), if self.inner_open => match control {
+                    Some(control) => return Some(control),
+                    None => self.inner_open = false,
+                },
+                _ = self.run.killed.notified(), if !self.kill_sent => {
+                    self.kill_sent = true;
+                    let id = lock(&self.run.kill_request).clone().unwrap_or_default();
+                    tracing::info!("🛑 Run {} killed by admin request {}", run_id, id);
+                    return Some(RunControl::Cancel(CancelRunRequest {
+                        id,
+                        run_id: run_id.to_string(),
+                    }));
+                },
+                else => return None,
+            }
+        }
+    }
+}
+
+fn run_not_found(id: String, run_id: &str) -> ServerResponse {
+    ServerResponse::Error(ErrorResponse {
+        id: Some(id),
+        error: format!("Run {} not found or already completed", run_id),
+        code: None,
+    })
+}
+
+/// Handles `active_runs`: lists the runs in progress on the server.
+pub(crate) fn handle_active_runs(r: ActiveRunsRequest, runs: &ActiveRuns) -> ServerResponse {
+    if let Err(resp) = authorize("active_runs", &r.id, &r.token) {
+        return resp;
+    }
+    ServerResponse::ActiveRuns(ActiveRunsResponse {
+        id: r.id,
+        runs: runs.list(),
+    })
+}
+
+/// Handles `run_inspect`: current node, elapsed time and usage so far of one run.
+pub(crate) fn handle_run_inspect(r: RunInspectRequest, runs: &ActiveRuns) -> ServerResponse {
+    if let Err(resp) = authorize("run_inspect", &r.id, &r.token) {
+        return resp;
+    }
+    match runs.inspect(&r.run_id) {
+        Some(run) => ServerResponse::RunInspect(RunInspectResponse { id: r.id, run }),
+        None => run_not_found(r.id, &r.run_id),
+    }
+}
+
+/// Handles `run_kill`: cancels a run started by any connection.
+pub(crate) fn handle_run_kill(r: RunKillRequest, runs: &ActiveRuns) -> ServerResponse {
+    if let Err(resp) = authorize("run_kill", &r.id, &r.token) {
+        return resp;
+    }
+    if !runs.kill(&r.run_id, &r.id) {
+        return run_not_found(r.id, &r.run_id);
+    }
+    tracing::info!("🛑 Kill requested for run {}", r.run_id);
+    ServerResponse::RunKill(RunKillResponse {
+        id: r.id,
+        run_id: r.run_id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::admin::ADMIN_TOKEN_ENV;
+    use loom::{ProtocolEventEnvelope, RunStreamEventResponse, ERROR_CODE_UNAUTHORIZED};
+
+    /// Records responses; delivers no controls (as a detached run's sender).
+    struct Collect(Vec<ServerResponse>);
+
+    #[async_trait]
+    impl RunStreamSender for Collect {
+        async fn send_response(
+            &mut self,
+            response: &ServerResponse,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.0.push(response.clone());
+            Ok(())
+        }
+
+        async fn recv_control(&mut self, _run_id: &str) -> Option<RunControl> {
+            None
+        }
+    }
+
+    fn request() -> loom::RunRequest {
+        serde_json::from_value(serde_json::json!({
+            "message": "hi",
+            "agent": "dev",
+            "thread_id": "t1",
+        }))
+        .unwrap()
+    }
+
+    fn event(event: ProtocolEvent) -> ServerResponse {
+        ServerResponse::RunStreamEvent(RunStreamEventResponse {
+            id: "run-1".to_string(),
+            event: ProtocolEventEnvelope {
+                session_id: None,
+                node_id: None,
+                event_id: None,
+                prev_event_id: None,
+                event,
+            },
+        })
+    }
+
+    #[tokio::test]
+    async fn tracks_node_and_usage_until_dropped() {
+        let runs = ActiveRuns::default();
+        let mut inner = Collect(Vec::new());
+        let mut sender = runs.track("run-1", &request(), &mut inner);
+        sender
+            .send_response(&event(ProtocolEvent::NodeEnter {
+                id: "think".to_string(),
+            }))
+            .await
+            .unwrap();
+        for _ in 0..2 {
+            sender
+                .send_response(&event(ProtocolEvent::Usage {
+                    prompt_tokens: 10,
+                    completion_tokens: 5,
+                    total_tokens: 15,
+                }))
+                .await
+                .unwrap();
+        }
+        let info = runs.inspect("run-1").unwrap();
+        assert_eq!(info.agent, "dev");
+        assert_eq!(info.thread_id.as_deref(), Some("t1"));
+        assert_eq!(info.current_node.as_deref(), Some("think"));
+        assert_eq!(info.total_tokens, 30);
+        assert_eq!(runs.list().len(), 1);
+        drop(sender);
+        assert_eq!(inner.0.len(), 3);
+        assert!(runs.list().is_empty());
+        assert!(runs.inspect("run-1").is_none());
+    }
+
+    #[tokio::test]
+    async fn kill_reaches_run_whose_client_is_gone() {
+        let runs = ActiveRuns::default();
+        let mut inner = Collect(Vec::new());
+        let mut sender = runs.track("run-1", &request(), &mut inner);
+        assert!(!runs.kill("run-2", "k0"));
+        assert!(runs.kill("run-1", "k1"));
+        match sender.recv_control("run-1").await {
+            Some(RunControl::Cancel(c)) => {
+                assert_eq!(c.id, "k1");
+                assert_eq!(c.run_id, "run-1");
+            }
+            _ => panic!("expected cancel control"),
+        }
+        assert!(sender.recv_control("run-1").await.is_none());
+    }
+
+    #[test]
+    fn admin_requests_without_configured_token_are_unauthorized() {
+        if std::env::var(ADMIN_TOKEN_ENV).is_ok() {
+            return;
+        }
+        let resp = handle_active_runs(
+            ActiveRunsRequest {
+                id: "a1".to_string(),
+                token: String::new(),
+            },
+            &ActiveRuns::default(),
+        );
+        match resp {
+            ServerResponse::Error(e) => {
+                assert_eq!(e.id.as_deref(), Some("a1"));
+                assert_eq!(e.code.as_deref(), Some(ERROR_CODE_UNAUTHORIZED));
+            }
+            other => panic!("expected unauthorized error, got {:?}", other),
+        }
+    }
+}
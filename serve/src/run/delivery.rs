@@ -1,17 +1,45 @@
 //! Delivering run stream to the client: RunStreamSender abstraction and handle_run_stream.
+//!
+//! While a run streams, the connection's incoming messages are still read: `stop_generation` and
+//! `cancel_run` for this run are applied immediately, everything else is deferred to the
+//! connection loop and handled after the run.
+//!
+//! Data events are delivered in `event_id` order: ids are assigned and queued under the envelope
+//! state lock (see `stream.rs`), and each lane is drained in order. Control events (tool
+//! approvals) skip ahead of queued data events (see `lanes`). When the data lane overflows,
+//! events are dropped after their id was taken; the client sees this as an event whose
+//! `prev_event_id` is not the last id it received, and delivery logs the gap.
 
 use async_trait::async_trait;
-use axum::extract::ws::WebSocket;
+use axum::extract::ws::{Message, WebSocket};
+use loom::cli_run::RunCancellation;
+use loom::protocol::requests::CancelRunRequest;
+use loom::protocol::responses::CancelRunResponse;
+use loom::protocol::stream::stream_event_to_protocol_envelope;
 use loom::{
-    EnvelopeState, ErrorResponse, ProtocolEventEnvelope, RunCompletion, RunEndResponse, RunError,
-    RunStreamEventResponse, ServerResponse,
+    ClientRequest, EnvelopeState, ErrorResponse, ReActState, RunCompletion, RunEndResponse,
+    RunError, RunStreamEventResponse, ServerResponse, StopGenerationRequest,
+    StopGenerationResponse, StreamEvent,
 };
+use std::collections::VecDeque;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
-use tokio::sync::mpsc;
 
+use super::lanes::{DropDetector, EventReceiver};
+use super::summary::ThreadSummaryJob;
+use super::usage::RunUsageJob;
+use crate::access_log::AccessRecord;
 use crate::response::send_response;
 
+/// Error sent when a run was cancelled.
+pub(crate) const RUN_CANCELLED: &str = "run cancelled";
+
+/// Client request that controls the run being streamed.
+pub(crate) enum RunControl {
+    StopGeneration(StopGenerationRequest),
+    Cancel(CancelRunRequest),
+}
+
 /// Abstraction for sending run-related server responses (RunStreamEvent, RunEnd, Error).
 #[async_trait]
 pub(crate) trait RunStreamSender: Send {
@@ -21,10 +49,22 @@ pub(crate) trait RunStreamSender: Send {
         &mut self,
         response: &ServerResponse,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// Waits for the next control request for run `run_id`; `None` when no more can arrive.
+    /// The default never yields one.
+    async fn recv_control(&mut self, _run_id: &str) -> Option<RunControl> {
+        std::future::pending().await
+    }
 }
 
 /// Wraps the WebSocket in [`RunStreamSender`] so stream handling can be tested with a mock.
-pub(super) struct WebSocketRunSender<'a>(pub(super) &'a mut WebSocket);
+/// Incoming messages that are not controls for the current run are pushed to `deferred`;
+/// every response sent is accounted in the request's `access` record.
+pub(super) struct WebSocketRunSender<'a> {
+    pub(super) socket: &'a mut WebSocket,
+    pub(super) deferred: &'a mut VecDeque<String>,
+    pub(super) access: &'a mut AccessRecord,
+}
 
 #[async_trait]
 impl RunStreamSender for WebSocketRunSender<'_> {
@@ -32,7 +72,48 @@ impl RunStreamSender for WebSocketRunSender<'_> {
         &mut self,
         response: &ServerResponse,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        send_response(self.0, response).await
+        let bytes = send_response(self.socket, response).await?;
+        self.access.observe(response, bytes);
+        Ok(())
+    }
+
+    async fn recv_control(&mut self, run_id: &str) -> Option<RunControl> {
+        loop {
+            let text = match self.socket.recv().await? {
+                Ok(Message::Text(t)) => t,
+                Ok(Message::Binary(b)) => String::from_utf8_lossy(&b).into_owned(),
+                Ok(Message::Close(_)) | Err(_) => return None,
+                Ok(_) => continue,
+            };
+            match serde_json::from_str::<ClientRequest>(&text) {
+                Ok(ClientRequest::StopGeneration(r)) if r.run_id == run_id => {
+                    return Some(RunControl::StopGeneration(r));
+                }
+                Ok(ClientRequest::CancelRun(r)) if r.run_id == run_id => {
+                    return Some(RunControl::Cancel(r));
+                }
+                _ => self.deferred.push_back(text),
+            }
+        }
+    }
+}
+
+/// Applies a control request to the run and returns the acknowledgment to send.
+fn apply_control(control: RunControl, cancellation: &RunCancellation) -> ServerResponse {
+    match control {
+        RunControl::StopGeneration(StopGenerationRequest { id, run_id }) => {
+            tracing::info!("✋ Stopping generation for run: {}", run_id);
+            cancellation.stop_generation();
+            ServerResponse::StopGeneration(StopGenerationResponse { id, run_id })
+        }
+        RunControl::Cancel(r) => {
+            tracing::info!("🛑 Cancelling run: {}", r.run_id);
+            cancellation.cancel();
+            ServerResponse::CancelRun(CancelRunResponse {
+                id: r.id,
+                run_id: r.run_id,
+            })
+        }
     }
 }
 
@@ -45,13 +126,19 @@ pub(super) type RunTaskResult = (
 );
 
 /// Consumes the event stream from the run task: for each event sends RunStreamEvent via
-/// `sender`, then awaits the run task. On success, sends RunEnd or Error. Logs when
-/// events or appends were dropped.
+/// `sender` (applying control requests from [`RunStreamSender::recv_control`] in between),
+/// then awaits the run task. On success, sends RunEnd or Error. Logs when
+/// events or appends were dropped. When `summary_job` is set and the run finished, a
+/// `thread_summary` RunStreamEvent follows the RunEnd. When `usage_job` is set, the run's
+/// token usage is recorded once the run task has ended (also when it failed or was aborted).
 pub(super) async fn handle_run_stream<S>(
     run_id: String,
-    mut rx: mpsc::Receiver<ProtocolEventEnvelope>,
+    mut rx: EventReceiver,
     run_handle: tokio::task::JoinHandle<RunTaskResult>,
     sender: &mut S,
+    summary_job: Option<ThreadSummaryJob>,
+    mut usage_job: Option<RunUsageJob>,
+    cancellation: &RunCancellation,
 ) -> Result<Option<ServerResponse>, Box<dyn std::error::Error + Send + Sync>>
 where
     S: RunStreamSender,
@@ -59,9 +146,42 @@ where
     tracing::info!("📡 Starting stream delivery for run: {}", run_id);
     let mut event_count = 0;
     let mut send_err: Option<Box<dyn std::error::Error + Send + Sync>> = None;
+    let mut controls_open = true;
+    let mut drops = DropDetector::default();
 
-    while let Some(event) = rx.recv().await {
+    loop {
+        let event = tokio::select! {
+            event = rx.recv() => match event {
+                Some(event) => event,
+                None => break,
+            },
+            control = sender.recv_control(&run_id), if controls_open => {
+                match control {
+                    Some(control) => {
+                        let ack = apply_control(control, cancellation);
+                        if let Err(e) = sender.send_response(&ack).await {
+                            send_err = Some(e);
+                            break;
+                        }
+                    }
+                    None => controls_open = false,
+                }
+                continue;
+            }
+        };
         event_count += 1;
+        let dropped = drops.observe(&event);
+        if dropped > 0 {
+            tracing::warn!(
+                "⚠️  Run {}: {} events dropped before event #{}",
+                run_id,
+                dropped,
+                event.event_id.unwrap_or_default()
+            );
+        }
+        if let Some(job) = usage_job.as_mut() {
+            job.observe(&event.event);
+        }
         tracing::debug!("📨 Sending event #{} for run: {}", event_count, run_id);
 
         if let Err(e) = sender
@@ -94,6 +214,9 @@ where
         tracing::warn!("⚠️  Stream delivery failed, aborting run: {}", run_id);
         run_handle.abort();
         let _ = run_handle.await;
+        if let Some(job) = usage_job {
+            job.record().await;
+        }
         return Err(e);
     }
 
@@ -102,6 +225,9 @@ where
         tracing::error!("❌ Run task failed for {}: {:?}", run_id, e);
         Box::new(e) as Box<dyn std::error::Error + Send + Sync>
     })?;
+    if let Some(job) = usage_job {
+        job.record().await;
+    }
 
     let de = dropped_events.load(Ordering::Relaxed);
     let da = dropped_appends.load(Ordering::Relaxed);
@@ -124,25 +250,55 @@ where
                 .unwrap_or((None, None, None));
 
             tracing::debug!("📤 Sending RunEnd response for: {}", run_id);
+            let reply = result.reply;
             sender
                 .send_response(&ServerResponse::RunEnd(RunEndResponse {
                     id: run_id.clone(),
-                    reply: result.reply,
+                    reply: reply.clone(),
                     reasoning_content: result.reasoning_content,
                     usage: None,
                     total_usage: None,
+                    finish_reason: result.finish_reason,
                     session_id,
                     node_id,
                     event_id,
+                    transcript: (!result.transcript.is_empty()).then_some(result.transcript),
+                    timing: result.timing,
+                    flags: result.flags.into_iter().collect(),
+                    confidence: result.confidence,
                 }))
                 .await?;
+
+            if let Some(job) = summary_job {
+                if let Some(summary) = job.run(&reply).await {
+                    let ev: StreamEvent<ReActState> = StreamEvent::ThreadSummary {
+                        title: summary.title,
+                        summary: summary.summary,
+                    };
+                    let envelope = state
+                        .lock()
+                        .ok()
+                        .and_then(|mut s| stream_event_to_protocol_envelope(&ev, &mut s).ok());
+                    if let Some(event) = envelope {
+                        sender
+                            .send_response(&ServerResponse::RunStreamEvent(
+                                RunStreamEventResponse {
+                                    id: run_id.clone(),
+                                    event,
+                                },
+                            ))
+                            .await?;
+                    }
+                }
+            }
         }
         Ok(RunCompletion::Cancelled) => {
             tracing::warn!("⚠️  Run cancelled: {}", run_id);
             sender
                 .send_response(&ServerResponse::Error(ErrorResponse {
                     id: Some(run_id.clone()),
-                    error: "run cancelled".to_string(),
+                    error: RUN_CANCELLED.to_string(),
+                    code: None,
                 }))
                 .await?;
         }
@@ -152,6 +308,7 @@ where
                 .send_response(&ServerResponse::Error(ErrorResponse {
                     id: Some(run_id.clone()),
                     error: e.to_string(),
+                    code: e.error_code().map(str::to_string),
                 }))
                 .await?;
         }
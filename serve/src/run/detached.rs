@@ -0,0 +1,427 @@
+//! Detached runs: a run whose client disconnected keeps running, and its output is kept so a
+//! new connection can follow it with `resume_run`.
+//!
+//! While the client is connected, [`DetachingSender`] remembers the run's last
+//! [`RECENT_RESPONSES`] responses (the client may not have received them when the connection
+//! dropped). When a send fails, the run is registered in [`DetachedRuns`] and every further
+//! response is buffered there instead of aborting the run. Runs are kept for
+//! [`ENV_DETACHED_RUN_TTL_SECS`] after they end (default 300; `0` disables detaching, so a
+//! disconnect aborts the run).
+
+use async_trait::async_trait;
+use loom::{ErrorResponse, ResumeRunRequest, ServerResponse};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+use super::delivery::{RunControl, RunStreamSender};
+
+/// Env var: seconds a detached run is kept after it ended (default 300; `0` disables).
+pub(crate) const ENV_DETACHED_RUN_TTL_SECS: &str = "SERVE_DETACHED_RUN_TTL_SECS";
+
+const DEFAULT_TTL_SECS: u64 = 300;
+/// Responses remembered while the client is connected.
+const RECENT_RESPONSES: usize = 256;
+/// Responses buffered per detached run; older events are dropped beyond this.
+const MAX_BUFFERED: usize = 10_000;
+
+#[derive(Default)]
+struct RunBuffer {
+    responses: VecDeque<ServerResponse>,
+    /// Responses dropped from the front (so positions stay stable for readers).
+    dropped: usize,
+    ended_at: Option<Instant>,
+}
+
+/// Buffered output of one detached run.
+pub(crate) struct DetachedRun {
+    buffer: Mutex<RunBuffer>,
+    /// Bumped on every change of `buffer`.
+    version: watch::Sender<u64>,
+}
+
+impl DetachedRun {
+    fn new(recent: VecDeque<ServerResponse>) -> Self {
+        Self {
+            buffer: Mutex::new(RunBuffer {
+                responses: recent,
+                ..Default::default()
+            }),
+            version: watch::channel(0).0,
+        }
+    }
+
+    fn push(&self, response: ServerResponse) {
+        if let Ok(mut b) = self.buffer.lock() {
+            if b.responses.len() >= MAX_BUFFERED {
+                b.responses.pop_front();
+                b.dropped += 1;
+            }
+            b.responses.push_back(response);
+        }
+        self.version.send_modify(|v| *v += 1);
+    }
+
+    fn end(&self) {
+        if let Ok(mut b) = self.buffer.lock() {
+            b.ended_at = Some(Instant::now());
+        }
+        self.version.send_modify(|v| *v += 1);
+    }
+
+    /// Responses from position `from` on, the next position, and whether the run ended.
+    fn read_from(&self, from: usize) -> (Vec<ServerResponse>, usize, bool) {
+        let Ok(b) = self.buffer.lock() else {
+            return (Vec::new(), from, true);
+        };
+        let skip = from.saturating_sub(b.dropped);
+        let batch = b.responses.iter().skip(skip).cloned().collect();
+        (batch, b.dropped + b.responses.len(), b.ended_at.is_some())
+    }
+
+    fn expired(&self, ttl: Duration) -> bool {
+        self.buffer
+            .lock()
+            .map(|b| b.ended_at.is_some_and(|t| t.elapsed() >= ttl))
+            .unwrap_or(true)
+    }
+}
+
+/// Detached runs of the server, by run id.
+#[derive(Clone)]
+pub(crate) struct DetachedRuns {
+    ttl: Duration,
+    runs: Arc<Mutex<HashMap<String, Arc<DetachedRun>>>>,
+}
+
+impl DetachedRuns {
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            runs: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Reads [`ENV_DETACHED_RUN_TTL_SECS`].
+    pub(crate) fn from_env() -> Self {
+        let secs = std::env::var(ENV_DETACHED_RUN_TTL_SECS)
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+            .unwrap_or(DEFAULT_TTL_SECS);
+        Self::new(Duration::from_secs(secs))
+    }
+
+    fn enabled(&self) -> bool {
+        !self.ttl.is_zero()
+    }
+
+    /// Registers `run_id` as detached, starting from the responses the client may have missed.
+    /// Ended runs past the TTL are dropped.
+    fn detach(&self, run_id: &str, recent: VecDeque<ServerResponse>) -> Arc<DetachedRun> {
+        let run = Arc::new(DetachedRun::new(recent));
+        if let Ok(mut runs) = self.runs.lock() {
+            runs.retain(|_, r| !r.expired(self.ttl));
+            runs.insert(run_id.to_string(), run.clone());
+        }
+        run
+    }
+
+    fn get(&self, run_id: &str) -> Option<Arc<DetachedRun>> {
+        let runs = self.runs.lock().ok()?;
+        runs.get(run_id).filter(|r| !r.expired(self.ttl)).cloned()
+    }
+}
+
+/// Run id a run response belongs to.
+fn run_id_of(response: &ServerResponse) -> Option<&str> {
+    match response {
+        ServerResponse::RunStreamEvent(r) => Some(&r.id),
+        ServerResponse::RunEnd(r) => Some(&r.id),
+        ServerResponse::Error(e) => e.id.as_deref(),
+        ServerResponse::StopGeneration(r) => Some(&r.run_id),
+        ServerResponse::CancelRun(r) => Some(&r.run_id),
+        _ => None,
+    }
+}
+
+/// Responses replayed on resume: the run's events and its end (not control acknowledgments).
+fn is_run_output(response: &ServerResponse) -> bool {
+    matches!(
+        response,
+        ServerResponse::RunStreamEvent(_) | ServerResponse::RunEnd(_) | ServerResponse::Error(_)
+    )
+}
+
+/// [`RunStreamSender`] that detaches the run instead of failing when the client is gone.
+pub(crate) struct DetachingSender<'a, S> {
+    inner: S,
+    runs: &'a DetachedRuns,
+    recent: VecDeque<ServerResponse>,
+    detached: Option<Arc<DetachedRun>>,
+}
+
+impl<'a, S> DetachingSender<'a, S> {
+    pub(crate) fn new(inner: S, runs: &'a DetachedRuns) -> Self {
+        Self {
+            inner,
+            runs,
+            recent: VecDeque::new(),
+            detached: None,
+        }
+    }
+}
+
+impl<S> Drop for DetachingSender<'_, S> {
+    fn drop(&mut self) {
+        if let Some(run) = &self.detached {
+            run.end();
+        }
+    }
+}
+
+#[async_trait]
+impl<S: RunStreamSender> RunStreamSender for DetachingSender<'_, S> {
+    async fn send_response(
+        &mut self,
+        response: &ServerResponse,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(run) = &self.detached {
+            if is_run_output(response) {
+                run.push(response.clone());
+            }
+            return Ok(());
+        }
+        match self.inner.send_response(response).await {
+            Ok(()) => {
+                if self.runs.enabled() && is_run_output(response) {
+                    if self.recent.len() >= RECENT_RESPONSES {
+                        self.recent.pop_front();
+                    }
+                    self.recent.push_back(response.clone());
+                }
+                Ok(())
+            }
+            Err(e) => {
+                let Some(run_id) = run_id_of(response).filter(|_| self.runs.enabled()) else {
+                    return Err(e);
+                };
+                tracing::warn!("🔌 Client gone ({}); run {} continues detached", e, run_id);
+                let run = self.runs.detach(run_id, std::mem::take(&mut self.recent));
+                if is_run_output(response) {
+                    run.push(response.clone());
+                }
+                self.detached = Some(run);
+                Ok(())
+            }
+        }
+    }
+
+    async fn recv_control(&mut self, run_id: &str) -> Option<RunControl> {
+        if self.detached.is_some() {
+            return None;
+        }
+        self.inner.recv_control(run_id).await
+    }
+}
+
+/// Whether `response` is an event the client already has (at or before `after_event_id`).
+fn already_received(response: &ServerResponse, after_event_id: Option<u64>) -> bool {
+    match (response, after_event_id) {
+        (ServerResponse::RunStreamEvent(r), Some(after)) => {
+            r.event.event_id.is_some_and(|id| id <= after)
+        }
+        _ => false,
+    }
+}
+
+/// Handles `resume_run`: replays the detached run's output after `after_event_id` through
+/// `sender`, then follows the run until its end has been sent. Returns the error response when
+/// the run is unknown or expired.
+pub(crate) async fn handle_resume_run<S>(
+    r: ResumeRunRequest,
+    runs: &DetachedRuns,
+    sender: &mut S,
+) -> Result<Option<ServerResponse>, Box<dyn std::error::Error + Send + Sync>>
+where
+    S: RunStreamSender,
+{
+    let Some(run) = runs.get(&r.run_id) else {
+        return Ok(Some(ServerResponse::Error(ErrorResponse {
+            id: Some(r.id),
+            error: format!("Run {} not found or expired", r.run_id),
+            code: None,
+        })));
+    };
+    tracing::debug!(
+        "🔁 Resuming run {} after event {:?}",
+        r.run_id,
+        r.after_event_id
+    );
+    let mut version = run.version.subscribe();
+    let mut next = 0;
+    loop {
+        let (batch, after, ended) = run.read_from(next);
+        next = after;
+        for response in batch {
+            if !already_received(&response, r.after_event_id) {
+                sender.send_response(&response).await?;
+            }
+        }
+        if ended || version.changed().await.is_err() {
+            return Ok(None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use loom::{ProtocolEvent, ProtocolEventEnvelope, RunEndResponse, RunStreamEventResponse};
+
+    fn event(id: u64) -> ServerResponse {
+        ServerResponse::RunStreamEvent(RunStreamEventResponse {
+            id: "run-1".to_string(),
+            event: ProtocolEventEnvelope {
+                session_id: None,
+                node_id: None,
+                event_id: Some(id),
+                prev_event_id: id.checked_sub(1),
+                event: ProtocolEvent::NodeEnter {
+                    id: "think".to_string(),
+                },
+            },
+        })
+    }
+
+    fn run_end() -> ServerResponse {
+        ServerResponse::RunEnd(RunEndResponse {
+            id: "run-1".to_string(),
+            reply: "done".to_string(),
+            reasoning_content: None,
+            usage: None,
+            total_usage: None,
+            finish_reason: None,
+            session_id: None,
+            node_id: None,
+            event_id: None,
+            transcript: None,
+            timing: None,
+            flags: Default::default(),
+            confidence: None,
+        })
+    }
+
+    /// Sends until `fail_at` responses were sent, then fails every send.
+    struct FlakySender {
+        sent: Vec<ServerResponse>,
+        fail_at: usize,
+    }
+
+    #[async_trait]
+    impl RunStreamSender for FlakySender {
+        async fn send_response(
+            &mut self,
+            response: &ServerResponse,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            if self.sent.len() >= self.fail_at {
+                return Err("connection closed".into());
+            }
+            self.sent.push(response.clone());
+            Ok(())
+        }
+    }
+
+    fn event_ids(sent: &[ServerResponse]) -> Vec<Option<u64>> {
+        sent.iter()
+            .map(|r| match r {
+                ServerResponse::RunStreamEvent(e) => e.event.event_id,
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn disconnected_run_is_buffered_and_resumed_after_last_event() {
+        let runs = DetachedRuns::new(Duration::from_secs(60));
+        {
+            let mut sender = DetachingSender::new(
+                FlakySender {
+                    sent: Vec::new(),
+                    fail_at: 2,
+                },
+                &runs,
+            );
+            for response in [event(1), event(2), event(3), run_end()] {
+                sender.send_response(&response).await.unwrap();
+            }
+            assert!(sender.recv_control("run-1").await.is_none());
+        }
+
+        let mut client = FlakySender {
+            sent: Vec::new(),
+            fail_at: usize::MAX,
+        };
+        let resume = ResumeRunRequest {
+            id: "r1".to_string(),
+            run_id: "run-1".to_string(),
+            after_event_id: Some(1),
+        };
+        let out = handle_resume_run(resume, &runs, &mut client).await.unwrap();
+        assert!(out.is_none());
+        assert_eq!(event_ids(&client.sent), vec![Some(2), Some(3), None]);
+        assert!(matches!(client.sent[2], ServerResponse::RunEnd(_)));
+    }
+
+    #[tokio::test]
+    async fn resume_follows_a_run_still_in_progress() {
+        let runs = DetachedRuns::new(Duration::from_secs(60));
+        let run = runs.detach("run-1", VecDeque::from([event(1)]));
+        let follower = {
+            let runs = runs.clone();
+            tokio::spawn(async move {
+                let mut client = FlakySender {
+                    sent: Vec::new(),
+                    fail_at: usize::MAX,
+                };
+                let resume = ResumeRunRequest {
+                    id: "r1".to_string(),
+                    run_id: "run-1".to_string(),
+                    after_event_id: None,
+                };
+                handle_resume_run(resume, &runs, &mut client).await.unwrap();
+                client.sent
+            })
+        };
+        run.push(event(2));
+        run.push(run_end());
+        run.end();
+        let sent = follower.await.unwrap();
+        assert_eq!(event_ids(&sent), vec![Some(1), Some(2), None]);
+    }
+
+    #[tokio::test]
+    async fn unknown_run_and_disabled_detaching_are_errors() {
+        let runs = DetachedRuns::new(Duration::ZERO);
+        let resume = ResumeRunRequest {
+            id: "r1".to_string(),
+            run_id: "run-9".to_string(),
+            after_event_id: None,
+        };
+        let mut client = FlakySender {
+            sent: Vec::new(),
+            fail_at: usize::MAX,
+        };
+        let out = handle_resume_run(resume, &runs, &mut client).await.unwrap();
+        assert!(matches!(out, Some(ServerResponse::Error(e)) if e.id.as_deref() == Some("r1")));
+
+        let mut sender = DetachingSender::new(
+            FlakySender {
+                sent: Vec::new(),
+                fail_at: 0,
+            },
+            &runs,
+        );
+        assert!(sender.send_response(&event(1)).await.is_err());
+    }
+}
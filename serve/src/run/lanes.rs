@@ -0,0 +1,218 @@
+//! Event queue between the run task and delivery, split in two lanes so a backlog of chunks
+//! never holds up what the client has to act on.
+//!
+//! - **Control lane** (unbounded): tool approval requests, GoT node failures, answer revisions
+//!   and model switches. Never dropped; delivery takes them before any queued data event.
+//! - **Data lane** (bounded by `SERVE_EVENT_QUEUE_CAPACITY`): everything else (message chunks,
+//!   values, tool progress). Dropped when full, as before.
+//!
+//! A control event can therefore reach the client before data events with lower `event_id`s.
+//! That is safe for `answer_revised`: message chunks carry their `revision`, so a client drops
+//! draft chunks that arrive after the revision instead of appending them to the new answer.
+//! RunEnd and Error are not queued: they are sent once both lanes are drained, since they end
+//! the run's stream.
+
+use loom::{ProtocolEvent, ProtocolEventEnvelope};
+use std::collections::BTreeSet;
+use tokio::sync::mpsc;
+
+/// Whether `event` goes on the control lane.
+pub(super) fn is_control(event: &ProtocolEvent) -> bool {
+    matches!(
+        event,
+        ProtocolEvent::ToolApproval { .. }
+            | ProtocolEvent::GotNodeFailed { .. }
+            | ProtocolEvent::AnswerRevised { .. }
+            | ProtocolEvent::ModelSwitched { .. }
+    )
+}
+
+/// Creates the two lanes; the data lane holds up to `data_capacity` events.
+pub(super) fn channel(data_capacity: usize) -> (EventSender, EventReceiver) {
+    let (control_tx, control_rx) = mpsc::unbounded_channel();
+    let (data_tx, data_rx) = mpsc::channel(data_capacity);
+    (
+        EventSender {
+            control: control_tx,
+            data: data_tx,
+        },
+        EventReceiver {
+            control: Some(control_rx),
+            data: Some(data_rx),
+        },
+    )
+}
+
+/// Run task side of the lanes.
+#[derive(Clone)]
+pub(super) struct EventSender {
+    control: mpsc::UnboundedSender<ProtocolEventEnvelope>,
+    data: mpsc::Sender<ProtocolEventEnvelope>,
+}
+
+impl EventSender {
+    /// Queues `envelope` on its lane without waiting. Returns false when it was dropped (data
+    /// lane full, or delivery gone).
+    pub(super) fn send(&self, envelope: ProtocolEventEnvelope) -> bool {
+        if is_control(&envelope.event) {
+            self.control.send(envelope).is_ok()
+        } else {
+            self.data.try_send(envelope).is_ok()
+        }
+    }
+}
+
+/// Delivery side of the lanes.
+pub(super) struct EventReceiver {
+    control: Option<mpsc::UnboundedReceiver<ProtocolEventEnvelope>>,
+    data: Option<mpsc::Receiver<ProtocolEventEnvelope>>,
+}
+
+impl EventReceiver {
+    /// Next event, control lane first; `None` once both lanes are closed and empty.
+    /// Cancel-safe: no event is lost when the future is dropped.
+    pub(super) async fn recv(&mut self) -> Option<ProtocolEventEnvelope> {
+        loop {
+            tokio::select! {
+                biased;
+                event = recv_control(&mut self.control), if self.control.is_some() => match event {
+                    Some(event) => return Some(event),
+                    None => self.control = None,
+                },
+                event = recv_data(&mut self.data), if self.data.is_some() => match event {
+                    Some(event) => return Some(event),
+                    None => self.data = None,
+                },
+                else => return None,
+            }
+        }
+    }
+}
+
+async fn recv_control(
+    rx: &mut Option<mpsc::UnboundedReceiver<ProtocolEventEnvelope>>,
+) -> Option<ProtocolEventEnvelope> {
+    rx.as_mut()?.recv().await
+}
+
+async fn recv_data(
+    rx: &mut Option<mpsc::Receiver<ProtocolEventEnvelope>>,
+) -> Option<ProtocolEventEnvelope> {
+    rx.as_mut()?.recv().await
+}
+
+/// Finds data events dropped from the data lane by comparing each event's `prev_event_id` with
+/// the last id delivered, not counting ids that came ahead on the control lane.
+#[derive(Default)]
+pub(super) struct DropDetector {
+    last_data_id: Option<u64>,
+    control_ids: BTreeSet<u64>,
+}
+
+impl DropDetector {
+    /// Records a delivered event. Returns how many events were dropped right before it.
+    pub(super) fn observe(&mut self, envelope: &ProtocolEventEnvelope) -> u64 {
+        let Some(id) = envelope.event_id else {
+            return 0;
+        };
+        if is_control(&envelope.event) {
+            self.control_ids.insert(id);
+            return 0;
+        }
+        let dropped = match (envelope.prev_event_id, self.last_data_id) {
+            (Some(prev), Some(last)) if prev > last => {
+                let ahead = self.control_ids.range(last + 1..=prev).count() as u64;
+                prev - last - ahead
+            }
+            _ => 0,
+        };
+        self.last_data_id = Some(id);
+        self.control_ids = self.control_ids.split_off(&(id + 1));
+        dropped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope(event: ProtocolEvent, id: u64) -> ProtocolEventEnvelope {
+        ProtocolEventEnvelope {
+            event,
+            session_id: None,
+            node_id: None,
+            event_id: Some(id),
+            prev_event_id: id.checked_sub(1),
+        }
+    }
+
+    fn chunk(id: u64) -> ProtocolEventEnvelope {
+        envelope(
+            ProtocolEvent::Custom {
+                value: serde_json::json!(id),
+            },
+            id,
+        )
+    }
+
+    fn approval(id: u64) -> ProtocolEventEnvelope {
+        envelope(
+            ProtocolEvent::ToolApproval {
+                call_id: None,
+                name: "bash".to_string(),
+                arguments: serde_json::json!({}),
+            },
+            id,
+        )
+    }
+
+    #[tokio::test]
+    async fn approval_overtakes_a_full_data_lane() {
+        let (tx, mut rx) = channel(2);
+        assert!(tx.send(chunk(1)));
+        assert!(tx.send(chunk(2)));
+        assert!(!tx.send(chunk(3)), "data lane is full");
+        assert!(tx.send(approval(4)), "control lane never drops");
+        drop(tx);
+
+        let mut detector = DropDetector::default();
+        let mut delivered = Vec::new();
+        while let Some(event) = rx.recv().await {
+            delivered.push((event.event_id.unwrap(), detector.observe(&event)));
+        }
+        assert_eq!(delivered, vec![(4, 0), (1, 0), (2, 0)]);
+
+        // Chunk 5 arriving after the approval: only chunk 3 is missing.
+        let mut detector = DropDetector::default();
+        for event in [chunk(1), chunk(2), approval(4)] {
+            detector.observe(&event);
+        }
+        assert_eq!(detector.observe(&chunk(5)), 1);
+    }
+
+    #[tokio::test]
+    async fn revision_and_model_switch_survive_a_full_data_lane() {
+        let (tx, mut rx) = channel(1);
+        assert!(tx.send(chunk(1)));
+        assert!(!tx.send(chunk(2)), "data lane is full");
+        let revised = ProtocolEvent::AnswerRevised {
+            reason: "reflection".to_string(),
+            revision: 1,
+        };
+        let switched = ProtocolEvent::ModelSwitched {
+            from: "openai/gpt-4o-mini".to_string(),
+            to: "openai/gpt-4.1".to_string(),
+            prompt_tokens: 150_000,
+            context_limit: 128_000,
+        };
+        assert!(tx.send(envelope(revised, 3)));
+        assert!(tx.send(envelope(switched, 4)));
+        drop(tx);
+
+        let mut delivered = Vec::new();
+        while let Some(event) = rx.recv().await {
+            delivered.push(event.event_id.unwrap());
+        }
+        assert_eq!(delivered, vec![3, 4, 1]);
+    }
+}
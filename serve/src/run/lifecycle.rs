@@ -0,0 +1,185 @@
+//! Run hooks in serve: [`HookedRunSender`] records a run's final RunEnd or Error on its way to
+//! the client, then [`HookedRunSender::report`] passes it to the `on_end` / `on_error` hook.
+
+use async_trait::async_trait;
+use loom::{RunEnd, RunEndResponse, RunFailure, RunHooks, ServerResponse};
+
+use super::delivery::{RunControl, RunStreamSender, RUN_CANCELLED};
+
+/// Final response of a run, as last sent through a [`HookedRunSender`].
+enum Outcome {
+    End(RunEndResponse),
+    Error { error: String, code: Option<String> },
+}
+
+/// [`RunStreamSender`] that remembers the final response of a run sent through `inner`, also
+/// when delivering it failed.
+pub(super) struct HookedRunSender<S> {
+    inner: S,
+    outcome: Option<Outcome>,
+}
+
+impl<S> HookedRunSender<S> {
+    pub(super) fn new(inner: S) -> Self {
+        Self {
+            inner,
+            outcome: None,
+        }
+    }
+
+    /// Fires `on_end` with the RunEnd sent for run `run_id`, else `on_error` with the Error sent
+    /// or with `error` (the run's failure before any final response).
+    pub(super) async fn report(
+        self,
+        hooks: &RunHooks<RunEndResponse>,
+        run_id: &str,
+        thread_id: Option<String>,
+        error: Option<String>,
+    ) {
+        let run_id = Some(run_id.to_string());
+        match (self.outcome, error) {
+            (Some(Outcome::End(end)), _) => {
+                let usage = end.total_usage.clone().or_else(|| end.usage.clone());
+                hooks
+                    .run_ended(RunEnd {
+                        run_id,
+                        thread_id,
+                        state: end,
+                        usage,
+                    })
+                    .await
+            }
+            (Some(Outcome::Error { error, code }), _) => {
+                hooks
+                    .run_failed(RunFailure {
+                        run_id,
+                        thread_id,
+                        cancelled: error == RUN_CANCELLED,
+                        error,
+                        code,
+                    })
+                    .await
+            }
+            (None, Some(error)) => {
+                hooks
+                    .run_failed(RunFailure {
+                        run_id,
+                        thread_id,
+                        error,
+                        code: None,
+                        cancelled: false,
+                    })
+                    .await
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+#[async_trait]
+impl<S: RunStreamSender> RunStreamSender for HookedRunSender<S> {
+    async fn send_response(
+        &mut self,
+        response: &ServerResponse,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        match response {
+            ServerResponse::RunEnd(end) => self.outcome = Some(Outcome::End(end.clone())),
+            ServerResponse::Error(e) => {
+                self.outcome = Some(Outcome::Error {
+                    error: e.error.clone(),
+                    code: e.code.clone(),
+                })
+            }
+            _ => {}
+        }
+        self.inner.send_response(response).await
+    }
+
+    async fn recv_control(&mut self, run_id: &str) -> Option<RunControl> {
+        self.inner.recv_control(run_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use loom::ErrorResponse;
+    use std::sync::{Arc, Mutex};
+
+    struct Discard;
+
+    #[async_trait]
+    impl RunStreamSender for Discard {
+        async fn send_response(
+            &mut self,
+            _response: &ServerResponse,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            Ok(())
+        }
+    }
+
+    fn recording_hooks(seen: &Arc<Mutex<Vec<String>>>) -> RunHooks<RunEndResponse> {
+        let (on_end, on_error) = (seen.clone(), seen.clone());
+        RunHooks::new()
+            .on_end(move |end: RunEnd<RunEndResponse>| {
+                let seen = on_end.clone();
+                async move {
+                    seen.lock().unwrap().push(format!(
+                        "end {} {}",
+                        end.run_id.unwrap_or_default(),
+                        end.state.reply
+                    ));
+                }
+            })
+            .on_error(move |failure: RunFailure| {
+                let seen = on_error.clone();
+                async move {
+                    seen.lock()
+                        .unwrap()
+                        .push(format!("error {} {}", failure.error, failure.cancelled));
+                }
+            })
+    }
+
+    #[tokio::test]
+    async fn reports_final_response_or_failure() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let hooks = recording_hooks(&seen);
+
+        let mut sender = HookedRunSender::new(Discard);
+        let end: RunEndResponse = serde_json::from_value(serde_json::json!({
+            "id": "run-1",
+            "reply": "done",
+        }))
+        .unwrap();
+        sender
+            .send_response(&ServerResponse::RunEnd(end))
+            .await
+            .unwrap();
+        sender.report(&hooks, "run-1", None, None).await;
+
+        let mut sender = HookedRunSender::new(Discard);
+        sender
+            .send_response(&ServerResponse::Error(ErrorResponse {
+                id: Some("run-2".to_string()),
+                error: RUN_CANCELLED.to_string(),
+                code: None,
+            }))
+            .await
+            .unwrap();
+        sender.report(&hooks, "run-2", None, None).await;
+
+        HookedRunSender::new(Discard)
+            .report(&hooks, "run-3", None, Some("worker gone".to_string()))
+            .await;
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                "end run-1 done".to_string(),
+                "error run cancelled true".to_string(),
+                "error worker gone false".to_string(),
+            ]
+        );
+    }
+}
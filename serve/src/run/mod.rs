@@ -1,32 +1,156 @@
 //! Handle `Run` request: execute agent (streaming or single reply).
 //!
 //! Flow: request preparation (register thread, append initial message, build opts/cmd) →
-//! spawn run task → consume event stream and send over WebSocket → send RunEnd or Error.
+//! spawn run task → consume event stream and send it through a [`RunStreamSender`] (the
+//! WebSocket, or the gRPC response stream) → send RunEnd or Error. With a [`WorkerPool`], the
+//! whole flow runs in a worker process and its responses are forwarded (see `worker`).
 
+mod active;
 mod delivery;
+mod detached;
+mod lanes;
+mod lifecycle;
 mod request;
 mod stream;
+mod summary;
+mod trim;
+mod usage;
+mod worker;
 
 use axum::extract::ws::WebSocket;
-use loom::{ProtocolEventEnvelope, ServerResponse};
+use loom::ServerResponse;
 use request::{PrepareRunInput, PrepareRunResult};
+use std::collections::VecDeque;
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use tracing::Instrument;
 use uuid::Uuid;
 
+use crate::access_log::AccessRecord;
 use crate::app::RunConfig;
 
+pub(crate) use active::{handle_active_runs, handle_run_inspect, handle_run_kill, ActiveRuns};
+pub(crate) use delivery::RunStreamSender;
+pub(crate) use detached::DetachedRuns;
+pub(crate) use worker::{serve_worker, WorkerPool};
+
+/// Id for a new run.
+fn new_run_id() -> String {
+    format!("run-{}", Uuid::new_v4())
+}
+
+/// Span around everything logged for run `run_id`; its `run_id` field routes the run's events
+/// to a per-run log file when the CLI logs with `--run-log-dir`.
+fn run_span(run_id: &str) -> tracing::Span {
+    tracing::info_span!("serve_run", run_id = %run_id)
+}
+
 /// Entry point for a Run request: prepares run (register thread, append initial user
 /// message, build options), spawns the agent task, and streams events + final RunEnd/Error
-/// over the WebSocket. Returns `Ok((run_id, cancellation, None))` in the normal streaming case (response already
-/// sent); returns `Err` if streaming or sending the final response fails.
+/// over the WebSocket. Messages the client sends meanwhile that are not controls for this run
+/// are pushed to `deferred`. When the client disconnects mid-run, the run continues detached
+/// (see [`DetachedRuns`]) and can be followed with `resume_run`. When `worker_pool` is enabled,
+/// the run executes in a worker process. The run is listed in `active_runs` while it lasts.
+/// Returns
+/// `Ok((run_id, cancellation, None))` in the normal streaming case (response already sent);
+/// returns `Err` if streaming or sending the final response fails and the run was not detached.
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn handle_run(
     r: loom::RunRequest,
     socket: &mut WebSocket,
+    deferred: &mut VecDeque<String>,
+    access: &mut AccessRecord,
     workspace_store: Option<Arc<loom_workspace::Store>>,
     user_message_store: Option<Arc<dyn loom::UserMessageStore>>,
     run_config: &RunConfig,
+    detached_runs: &DetachedRuns,
+    worker_pool: &WorkerPool,
+    active_runs: &ActiveRuns,
 ) -> Result<(String, loom::cli_run::RunCancellation, Option<ServerResponse>), Box<dyn std::error::Error + Send + Sync>> {
+    let ws_sender = delivery::WebSocketRunSender {
+        socket,
+        deferred,
+        access,
+    };
+    let mut sender = detached::DetachingSender::new(ws_sender, detached_runs);
+    dispatch_run(r, &mut sender, workspace_store, user_message_store, run_config, worker_pool, active_runs).await
+}
+
+/// Runs `r` on a worker when `worker_pool` is enabled, else in-process with [`stream_run`].
+/// A worker run returns a cancellation handle of its own; controls reach the worker through
+/// `sender`. Either way the run is registered in `active_runs` until it ends, and the run hooks
+/// of `run_config` fire when it starts and with its final RunEnd or Error. Everything runs in
+/// the run's `serve_run` span.
+pub(crate) async fn dispatch_run<S>(
+    r: loom::RunRequest,
+    sender: &mut S,
+    workspace_store: Option<Arc<loom_workspace::Store>>,
+    user_message_store: Option<Arc<dyn loom::UserMessageStore>>,
+    run_config: &RunConfig,
+    worker_pool: &WorkerPool,
+    active_runs: &ActiveRuns,
+) -> Result<(String, loom::cli_run::RunCancellation, Option<ServerResponse>), Box<dyn std::error::Error + Send + Sync>>
+where
+    S: RunStreamSender,
+{
+    let run_id = new_run_id();
+    let span = run_span(&run_id);
+    async move {
+        let hooks = &run_config.run_hooks;
+        let thread_id = r.thread_id.clone();
+        hooks
+            .run_started(loom::RunStart {
+                run_id: Some(run_id.clone()),
+                thread_id: thread_id.clone(),
+            })
+            .await;
+        let mut sender = lifecycle::HookedRunSender::new(active_runs.track(&run_id, &r, sender));
+        let result = if worker_pool.enabled() {
+            worker_pool
+                .run(r, &run_id, run_config, &mut sender)
+                .await
+                .map(|()| (run_id.clone(), loom::cli_run::RunCancellation::new(1), None))
+        } else {
+            stream_run(r, run_id.clone(), &mut sender, workspace_store, user_message_store, run_config).await
+        };
+        let error = result.as_ref().err().map(|e| e.to_string());
+        sender.report(hooks, &run_id, thread_id, error).await;
+        result
+    }
+    .instrument(span)
+    .await
+}
+
+/// Handles `resume_run` over the WebSocket: replays and follows a detached run. Returns the
+/// error response to send when the run is unknown.
+pub(crate) async fn handle_resume_run(
+    r: loom::ResumeRunRequest,
+    socket: &mut WebSocket,
+    deferred: &mut VecDeque<String>,
+    access: &mut AccessRecord,
+    detached_runs: &DetachedRuns,
+) -> Result<Option<ServerResponse>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut sender = delivery::WebSocketRunSender {
+        socket,
+        deferred,
+        access,
+    };
+    detached::handle_resume_run(r, detached_runs, &mut sender).await
+}
+
+/// Prepares and spawns the run `run_id`, then delivers its events and final RunEnd/Error
+/// through `sender`. Shared by the WebSocket and gRPC transports and the worker processes.
+pub(crate) async fn stream_run<S>(
+    r: loom::RunRequest,
+    run_id: String,
+    sender: &mut S,
+    workspace_store: Option<Arc<loom_workspace::Store>>,
+    user_message_store: Option<Arc<dyn loom::UserMessageStore>>,
+    run_config: &RunConfig,
+) -> Result<(String, loom::cli_run::RunCancellation, Option<ServerResponse>), Box<dyn std::error::Error + Send + Sync>>
+where
+    S: RunStreamSender,
+{
+    let workspace_id = r.workspace_id.clone();
     let PrepareRunResult {
         opts,
         cmd,
@@ -37,50 +161,80 @@ pub(crate) async fn handle_run(
         workspace_store.as_ref(),
         user_message_store.as_ref(),
         PrepareRunInput {
+            run_id: run_id.clone(),
             display_max_len: run_config.display_max_len,
+            role_setting: run_config.role_setting.clone(),
+            allowed_tools: run_config.allowed_tools.clone(),
+            read_only: run_config.read_only,
+            default_model: run_config.default_model.clone(),
+            input_policy: run_config.input_policy.clone(),
+            builder: run_config.builder.clone(),
         },
     )
     .await;
 
-    let run_id = format!("run-{}", Uuid::new_v4());
     let session_id = run_id.clone();
-    let (tx, rx) = mpsc::channel::<ProtocolEventEnvelope>(run_config.event_queue_capacity);
+    let (tx, rx) = lanes::channel(run_config.event_queue_capacity);
+    let summary_job =
+        summary::ThreadSummaryJob::from_run(&opts, run_config, workspace_store.clone());
+    let usage_job =
+        usage::RunUsageJob::from_run(&run_id, &opts, &cmd, workspace_id, workspace_store.clone());
     let opts = opts.clone();
     let cmd = cmd.clone();
     let thread_id_for_append = opts.thread_id.clone();
     let user_message_store_for_append = user_message_store.clone();
-    let run_handle = tokio::spawn(stream::run_agent_task(stream::AgentTaskParams {
-        session_id,
-        tx,
-        opts,
-        cmd,
-        initial_user_appended,
-        user_message_store: user_message_store_for_append,
-        thread_id: thread_id_for_append,
-        append_queue_capacity: run_config.append_queue_capacity,
-    }));
+    let run_handle = tokio::spawn(
+        stream::run_agent_task(stream::AgentTaskParams {
+            session_id,
+            tx,
+            opts,
+            cmd,
+            initial_user_appended,
+            user_message_store: user_message_store_for_append,
+            thread_id: thread_id_for_append,
+            append_queue_capacity: run_config.append_queue_capacity,
+            values_max_bytes: run_config.values_max_bytes,
+            llm_script: run_config.llm_script.clone(),
+        })
+        .in_current_span(),
+    );
 
-    let mut sender = delivery::WebSocketRunSender(socket);
-    let result = delivery::handle_run_stream(run_id.clone(), rx, run_handle, &mut sender).await?;
+    let result = delivery::handle_run_stream(
+        run_id.clone(),
+        rx,
+        run_handle,
+        sender,
+        summary_job,
+        usage_job,
+        &cancellation,
+    )
+    .await?;
     Ok((run_id, cancellation, result))
 }
 
 #[cfg(test)]
 mod tests {
     use async_trait::async_trait;
+    use loom::cli_run::RunCancellation;
     use loom::{
         AgentRunResult, EnvelopeState, ProtocolEvent, ProtocolEventEnvelope, RunCmd, RunCompletion,
         RunError, RunOptions, ServerResponse,
     };
     use std::sync::atomic::AtomicUsize;
     use std::sync::{Arc, Mutex};
-    use tokio::sync::mpsc;
 
-    use super::delivery::{handle_run_stream, RunStreamSender};
-    use super::request::{try_append_initial_user_message, try_register_thread_in_workspace};
+    use super::delivery::{handle_run_stream, RunControl, RunStreamSender};
+    use super::lanes;
+    use super::request::{
+        effective_allowed_tools, load_workspace_defaults, try_append_initial_user_message,
+        try_register_thread_in_workspace, workspace_history_search,
+    };
     use super::stream::{
-        run_agent_task, AgentTaskParams, APPEND_QUEUE_CAPACITY, EVENT_QUEUE_CAPACITY,
+        message_meta, run_agent_task, AgentTaskParams, APPEND_QUEUE_CAPACITY,
+        EVENT_QUEUE_CAPACITY,
     };
+    use super::summary::ThreadSummaryJob;
+    use super::usage::RunUsageJob;
 
     /// Mock sender that can fail on first send or record sent responses.
     struct MockRunStreamSender {
@@ -88,6 +242,7 @@ mod tests {
         fail_after: Option<usize>,
         last_run_end: Option<(String, String)>,
         last_error: Option<(Option<String>, String)>,
+        last_event: Option<ProtocolEvent>,
     }
 
     #[async_trait]
@@ -109,6 +264,9 @@ mod tests {
                 ServerResponse::Error(e) => {
                     self.last_error = Some((e.id.clone(), e.error.clone()));
                 }
+                ServerResponse::RunStreamEvent(e) => {
+                    self.last_event = Some(e.event.event.clone());
+                }
                 _ => {}
             }
             Ok(())
@@ -117,13 +275,18 @@ mod tests {
 
     #[tokio::test]
     async fn handle_run_stream_send_failure_aborts_and_returns_err() {
-        let (tx, rx) = mpsc::channel::<ProtocolEventEnvelope>(2);
+        let (tx, rx) = lanes::channel(2);
         let run_handle = tokio::spawn(async move {
             tokio::time::sleep(std::time::Duration::from_secs(30)).await;
             (
                 Ok(RunCompletion::Finished(AgentRunResult {
                     reply: "never".to_string(),
                     reasoning_content: None,
+                    finish_reason: None,
+                    transcript: Vec::new(),
+                    timing: None,
+                    flags: Default::default(),
+                    confidence: None,
                 })),
                 Arc::new(Mutex::new(EnvelopeState::new("s".into()))),
                 Arc::new(AtomicUsize::new(0)),
@@ -134,26 +297,37 @@ mod tests {
             session_id: Some("run-1".into()),
             node_id: Some("n".into()),
             event_id: Some(1),
+            prev_event_id: None,
             event: ProtocolEvent::NodeEnter {
                 id: "think".to_string(),
             },
         };
-        tx.send(env).await.unwrap();
+        assert!(tx.send(env));
         drop(tx);
         let mut sender = MockRunStreamSender {
             send_count: 0,
             fail_after: Some(1),
             last_run_end: None,
             last_error: None,
+            last_event: None,
         };
-        let out = handle_run_stream("run-1".to_string(), rx, run_handle, &mut sender).await;
+        let out = handle_run_stream(
+            "run-1".to_string(),
+            rx,
+            run_handle,
+            &mut sender,
+            None,
+            None,
+            &RunCancellation::new(1),
+        )
+        .await;
         assert!(out.is_err());
         assert_eq!(out.unwrap_err().to_string(), "mock send failure");
     }
 
     #[tokio::test]
     async fn handle_run_stream_agent_ok_sends_run_end() {
-        let (_tx, rx) = mpsc::channel::<ProtocolEventEnvelope>(1);
+        let (_tx, rx) = lanes::channel(1);
         drop(_tx);
         let state = Arc::new(Mutex::new(EnvelopeState::new("run-1".into())));
         let run_handle = tokio::spawn(async move {
@@ -161,6 +335,11 @@ mod tests {
                 Ok(RunCompletion::Finished(AgentRunResult {
                     reply: "reply text".to_string(),
                     reasoning_content: Some("thinking".to_string()),
+                    finish_reason: None,
+                    transcript: Vec::new(),
+                    timing: None,
+                    flags: Default::default(),
+                    confidence: None,
                 })),
                 state,
                 Arc::new(AtomicUsize::new(0)),
@@ -172,8 +351,18 @@ mod tests {
             fail_after: None,
             last_run_end: None,
             last_error: None,
+            last_event: None,
         };
-        let out = handle_run_stream("run-1".to_string(), rx, run_handle, &mut sender).await;
+        let out = handle_run_stream(
+            "run-1".to_string(),
+            rx,
+            run_handle,
+            &mut sender,
+            None,
+            None,
+            &RunCancellation::new(1),
+        )
+        .await;
         assert!(out.is_ok());
         assert!(out.unwrap().is_none());
         assert_eq!(sender.send_count, 1);
@@ -182,9 +371,209 @@ mod tests {
         assert_eq!(reply, "reply text");
     }
 
+    /// Sender that yields queued control requests, then none, and records acknowledgments.
+    struct ControlSender {
+        controls: Vec<RunControl>,
+        sent: Vec<ServerResponse>,
+    }
+
+    #[async_trait]
+    impl RunStreamSender for ControlSender {
+        async fn send_response(
+            &mut self,
+            response: &ServerResponse,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.sent.push(response.clone());
+            Ok(())
+        }
+
+        async fn recv_control(&mut self, _run_id: &str) -> Option<RunControl> {
+            self.controls.pop()
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_run_stream_applies_stop_generation_while_streaming() {
+        let (tx, rx) = lanes::channel(1);
+        let state = Arc::new(Mutex::new(EnvelopeState::new("run-1".into())));
+        let cancellation = RunCancellation::new(1);
+        let watched = cancellation.clone();
+        let run_handle = tokio::spawn(async move {
+            // The run only finishes once it has been asked to stop.
+            watched.stop_requested().await;
+            drop(tx);
+            (
+                Ok(RunCompletion::Finished(AgentRunResult {
+                    reply: "partial".to_string(),
+                    reasoning_content: None,
+                    finish_reason: Some(loom::FinishReason::UserStopped),
+                    transcript: Vec::new(),
+                    timing: None,
+                    flags: Default::default(),
+                    confidence: None,
+                })),
+                state,
+                Arc::new(AtomicUsize::new(0)),
+                Arc::new(AtomicUsize::new(0)),
+            )
+        });
+        let mut sender = ControlSender {
+            controls: vec![RunControl::StopGeneration(loom::StopGenerationRequest {
+                id: "s1".to_string(),
+                run_id: "run-1".to_string(),
+            })],
+            sent: Vec::new(),
+        };
+        let out = handle_run_stream(
+            "run-1".to_string(),
+            rx,
+            run_handle,
+            &mut sender,
+            None,
+            None,
+            &cancellation,
+        )
+        .await;
+        assert!(out.is_ok());
+        assert!(matches!(
+            &sender.sent[0],
+            ServerResponse::StopGeneration(r) if r.id == "s1" && r.run_id == "run-1"
+        ));
+        assert!(matches!(&sender.sent[1], ServerResponse::RunEnd(r) if r.reply == "partial"));
+        assert!(cancellation.take_stop_request());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn handle_run_stream_with_summary_job_sends_thread_summary_after_run_end() {
+        let (_tx, rx) = lanes::channel(1);
+        drop(_tx);
+        let state = Arc::new(Mutex::new(EnvelopeState::new("run-1".into())));
+        let run_handle = tokio::spawn(async move {
+            (
+                Ok(RunCompletion::Finished(AgentRunResult {
+                    reply: "Kyoto in spring is lovely.".to_string(),
+                    reasoning_content: None,
+                    finish_reason: None,
+                    transcript: Vec::new(),
+                    timing: None,
+                    flags: Default::default(),
+                    confidence: None,
+                })),
+                state,
+                Arc::new(AtomicUsize::new(0)),
+                Arc::new(AtomicUsize::new(0)),
+            )
+        });
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let store = Arc::new(loom_workspace::Store::new(file.path()).unwrap());
+        let job = ThreadSummaryJob {
+            thread_id: "thread-1".to_string(),
+            user_message: "Plan a trip to Kyoto".to_string(),
+            llm: Box::new(loom::MockLlm::with_no_tool_calls(
+                r#"{"title":"Kyoto trip","summary":"The user planned a spring trip."}"#,
+            )),
+            workspace_store: Some(store.clone()),
+        };
+        let mut sender = MockRunStreamSender {
+            send_count: 0,
+            fail_after: None,
+            last_run_end: None,
+            last_error: None,
+            last_event: None,
+        };
+        let out = handle_run_stream(
+            "run-1".to_string(),
+            rx,
+            run_handle,
+            &mut sender,
+            Some(job),
+            None,
+            &RunCancellation::new(1),
+        )
+        .await;
+        assert!(out.is_ok());
+        assert_eq!(sender.send_count, 2);
+        assert!(sender.last_run_end.is_some());
+        match sender.last_event.as_ref().unwrap() {
+            ProtocolEvent::ThreadSummary { title, summary } => {
+                assert_eq!(title, "Kyoto trip");
+                assert_eq!(summary, "The user planned a spring trip.");
+            }
+            other => panic!("expected thread_summary, got {:?}", other),
+        }
+        let stored = store.get_thread_summary("thread-1").await.unwrap().unwrap();
+        assert_eq!(stored.title, "Kyoto trip");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn handle_run_stream_with_usage_job_records_summed_usage() {
+        let (tx, rx) = lanes::channel(4);
+        for tokens in [10, 20] {
+            assert!(tx.send(ProtocolEventEnvelope {
+                session_id: Some("run-1".into()),
+                node_id: Some("think".into()),
+                event_id: None,
+                prev_event_id: None,
+                event: ProtocolEvent::Usage {
+                    prompt_tokens: tokens,
+                    completion_tokens: 1,
+                    total_tokens: tokens + 1,
+                },
+            }));
+        }
+        drop(tx);
+        let state = Arc::new(Mutex::new(EnvelopeState::new("run-1".into())));
+        let run_handle = tokio::spawn(async move {
+            (
+                Ok(RunCompletion::Finished(AgentRunResult::default())),
+                state,
+                Arc::new(AtomicUsize::new(0)),
+                Arc::new(AtomicUsize::new(0)),
+            )
+        });
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let store = Arc::new(loom_workspace::Store::new(file.path()).unwrap());
+        let job = RunUsageJob {
+            usage: loom_workspace::RunUsage {
+                run_id: "run-1".to_string(),
+                workspace_id: Some("ws-1".to_string()),
+                thread_id: None,
+                model: "openai/gpt-4o".to_string(),
+                agent: "react".to_string(),
+                prompt_tokens: 0,
+                completion_tokens: 0,
+                total_tokens: 0,
+            },
+            workspace_store: store.clone(),
+        };
+        let mut sender = MockRunStreamSender {
+            send_count: 0,
+            fail_after: None,
+            last_run_end: None,
+            last_error: None,
+            last_event: None,
+        };
+        handle_run_stream(
+            "run-1".to_string(),
+            rx,
+            run_handle,
+            &mut sender,
+            None,
+            Some(job),
+            &RunCancellation::new(1),
+        )
+        .await
+        .unwrap();
+        let report = store.usage_report(Some("ws-1"), None, None).await.unwrap();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].runs, 1);
+        assert_eq!(report[0].prompt_tokens, 30);
+        assert_eq!(report[0].total_tokens, 32);
+    }
+
     #[tokio::test]
     async fn handle_run_stream_agent_err_sends_error_response() {
-        let (_tx, rx) = mpsc::channel::<ProtocolEventEnvelope>(1);
+        let (_tx, rx) = lanes::channel(1);
         drop(_tx);
         let state = Arc::new(Mutex::new(EnvelopeState::new("run-1".into())));
         let run_handle = tokio::spawn(async move {
@@ -200,8 +589,18 @@ mod tests {
             fail_after: None,
             last_run_end: None,
             last_error: None,
+            last_event: None,
         };
-        let out = handle_run_stream("run-1".to_string(), rx, run_handle, &mut sender).await;
+        let out = handle_run_stream(
+            "run-1".to_string(),
+            rx,
+            run_handle,
+            &mut sender,
+            None,
+            None,
+            &RunCancellation::new(1),
+        )
+        .await;
         assert!(out.is_ok());
         assert_eq!(sender.send_count, 1);
         let (id, error) = sender.last_error.as_ref().unwrap();
@@ -211,7 +610,7 @@ mod tests {
 
     #[tokio::test]
     async fn handle_run_stream_join_error_returns_err() {
-        let (_tx, rx) = mpsc::channel::<ProtocolEventEnvelope>(1);
+        let (_tx, rx) = lanes::channel(1);
         drop(_tx);
         let run_handle = tokio::spawn(async move {
             panic!("task panicked");
@@ -221,8 +620,18 @@ mod tests {
             fail_after: None,
             last_run_end: None,
             last_error: None,
+            last_event: None,
         };
-        let out = handle_run_stream("run-1".to_string(), rx, run_handle, &mut sender).await;
+        let out = handle_run_stream(
+            "run-1".to_string(),
+            rx,
+            run_handle,
+            &mut sender,
+            None,
+            None,
+            &RunCancellation::new(1),
+        )
+        .await;
         assert!(out.is_err());
         assert_eq!(sender.send_count, 0);
     }
@@ -264,35 +673,83 @@ mod tests {
         assert_eq!(threads[0].thread_id, "thread-1");
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn load_workspace_defaults_reads_store_and_falls_back_to_empty() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let store = Arc::new(loom_workspace::Store::new(file.path()).unwrap());
+        let ws_id = store.create_workspace(None).await.unwrap();
+        let defaults = loom_workspace::WorkspaceDefaults {
+            model: Some("gpt-4o-mini".to_string()),
+            tools: Some(vec!["read".to_string()]),
+            ..Default::default()
+        };
+        store.set_workspace_defaults(&ws_id, &defaults).await.unwrap();
+
+        assert_eq!(
+            load_workspace_defaults(Some(&store), Some(&ws_id)).await,
+            defaults
+        );
+        assert!(load_workspace_defaults(Some(&store), None).await.is_empty());
+        assert!(load_workspace_defaults(None, Some(&ws_id)).await.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn workspace_history_search_scopes_to_workspace_threads() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let ws_store = Arc::new(loom_workspace::Store::new(file.path()).unwrap());
+        let ws_id = ws_store.create_workspace(None).await.unwrap();
+        ws_store
+            .add_thread_to_workspace(&ws_id, "t1")
+            .await
+            .unwrap();
+        let store: Arc<dyn loom::UserMessageStore> = Arc::new(loom::NoOpUserMessageStore);
+
+        let search = workspace_history_search(Some(&ws_store), Some(&store), Some(&ws_id))
+            .await
+            .expect("history search for workspace run");
+        assert_eq!(search.thread_ids, Some(vec!["t1".to_string()]));
+        assert!(workspace_history_search(Some(&ws_store), None, Some(&ws_id))
+            .await
+            .is_none());
+        assert!(workspace_history_search(Some(&ws_store), Some(&store), None)
+            .await
+            .is_none());
+    }
+
     #[tokio::test]
     async fn try_append_initial_user_message_store_none_returns_false() {
-        let got = try_append_initial_user_message(None, Some("t1"), "hi").await;
+        let got = try_append_initial_user_message(None, Some("t1"), "run-1", "hi").await;
         assert!(!got);
     }
 
     #[tokio::test]
     async fn try_append_initial_user_message_thread_id_none_returns_false() {
         let store: Arc<dyn loom::UserMessageStore> = Arc::new(loom::NoOpUserMessageStore);
-        let got = try_append_initial_user_message(Some(&store), None, "hi").await;
+        let got = try_append_initial_user_message(Some(&store), None, "run-1", "hi").await;
         assert!(!got);
     }
 
     #[tokio::test]
     async fn try_append_initial_user_message_both_some_returns_true() {
-        let store: Arc<dyn loom::UserMessageStore> = Arc::new(loom::NoOpUserMessageStore);
-        let got = try_append_initial_user_message(Some(&store), Some("t1"), "hello").await;
+        let store: Arc<dyn loom::UserMessageStore> =
+            Arc::new(loom::InMemoryUserMessageStore::new());
+        let got =
+            try_append_initial_user_message(Some(&store), Some("t1"), "run-1", "hello").await;
         assert!(got);
+        let records = store.list_records("t1", None, None).await.unwrap();
+        assert_eq!(records[0].meta.run_id.as_deref(), Some("run-1"));
     }
 
     #[tokio::test]
     async fn run_agent_task_completes_and_returns_result_and_state() {
-        let (tx, _rx) = mpsc::channel::<ProtocolEventEnvelope>(EVENT_QUEUE_CAPACITY);
+        let (tx, _rx) = lanes::channel(EVENT_QUEUE_CAPACITY);
         let opts = RunOptions {
             message: loom::UserContent::text("ping".to_string()),
             working_folder: None,
             session_id: None,
             thread_id: None,
             agent: None,
+            agent_file: None,
             verbose: false,
             got_adaptive: false,
             display_max_len: 2000,
@@ -306,6 +763,15 @@ mod tests {
             cancellation: None,
             output_timestamp: false,
             dry_run: false,
+            role_setting: None,
+            allowed_tools: None,
+            read_only: false,
+            reply_format: None,
+            reply_schema: None,
+            messages: None,
+            history_search: None,
+            locale: None,
+            flags: Default::default(),
         };
         let (result, state, _dropped_events, _dropped_appends) = run_agent_task(AgentTaskParams {
             session_id: "test-session".to_string(),
@@ -316,6 +782,8 @@ mod tests {
             user_message_store: None,
             thread_id: None,
             append_queue_capacity: APPEND_QUEUE_CAPACITY,
+            values_max_bytes: None,
+            llm_script: None,
         })
         .await;
         let _ = result;
@@ -325,7 +793,7 @@ mod tests {
 
     #[tokio::test]
     async fn run_agent_task_with_user_message_store_uses_append_channel() {
-        let (tx, _rx) = mpsc::channel::<ProtocolEventEnvelope>(EVENT_QUEUE_CAPACITY);
+        let (tx, _rx) = lanes::channel(EVENT_QUEUE_CAPACITY);
         let store: Arc<dyn loom::UserMessageStore> = Arc::new(loom::NoOpUserMessageStore);
         let opts = RunOptions {
             message: loom::UserContent::text("hi".to_string()),
@@ -333,6 +801,7 @@ mod tests {
             session_id: None,
             thread_id: Some("thread-append".to_string()),
             agent: None,
+            agent_file: None,
             verbose: false,
             got_adaptive: false,
             display_max_len: 2000,
@@ -346,6 +815,15 @@ mod tests {
             cancellation: None,
             output_timestamp: false,
             dry_run: false,
+            role_setting: None,
+            allowed_tools: None,
+            read_only: false,
+            reply_format: None,
+            reply_schema: None,
+            messages: None,
+            history_search: None,
+            locale: None,
+            flags: Default::default(),
         };
         let (result, state, _dropped_events, _dropped_appends) = run_agent_task(AgentTaskParams {
             session_id: "session-2".to_string(),
@@ -356,10 +834,63 @@ mod tests {
             user_message_store: Some(store),
             thread_id: Some("thread-append".to_string()),
             append_queue_capacity: APPEND_QUEUE_CAPACITY,
+            values_max_bytes: None,
+            llm_script: None,
         })
         .await;
         let _ = result;
         let guard = state.lock().unwrap();
         assert_eq!(guard.session_id, "session-2");
     }
+
+    #[test]
+    fn message_meta_names_run_tool_and_last_usage() {
+        let usage = loom::LlmUsage {
+            prompt_tokens: 20,
+            completion_tokens: 4,
+            total_tokens: 24,
+            ..Default::default()
+        };
+        let state = loom::ReActState {
+            messages: vec![
+                loom::Message::user("weather?"),
+                loom::Message::assistant_with_tool_calls(
+                    String::new(),
+                    vec![loom::AssistantToolCall {
+                        id: "c1".to_string(),
+                        name: "get_weather".to_string(),
+                        arguments: "{}".to_string(),
+                    }],
+                ),
+                loom::Message::Tool {
+                    tool_call_id: "c1".to_string(),
+                    content: "sunny".into(),
+                },
+                loom::Message::assistant("It is sunny."),
+            ],
+            usage: Some(usage.clone()),
+            ..Default::default()
+        };
+
+        assert_eq!(message_meta(&state, 0, "run-1"), loom::MessageMeta::for_run("run-1"));
+        assert_eq!(message_meta(&state, 1, "run-1").usage, None);
+        let tool = message_meta(&state, 2, "run-1");
+        assert_eq!(tool.tool_name.as_deref(), Some("get_weather"));
+        assert_eq!(tool.tool_call_id.as_deref(), Some("c1"));
+        let answer = message_meta(&state, 3, "run-1");
+        assert_eq!(answer.run_id.as_deref(), Some("run-1"));
+        assert_eq!(answer.usage, Some(usage));
+    }
+
+    #[test]
+    fn server_allowlist_narrows_workspace_allowlist() {
+        let v = |xs: &[&str]| Some(xs.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+        assert_eq!(
+            effective_allowed_tools(v(&["read", "bash"]), v(&["read", "ls"])),
+            v(&["read"])
+        );
+        assert_eq!(effective_allowed_tools(None, v(&["ls"])), v(&["ls"]));
+        assert_eq!(effective_allowed_tools(v(&["bash"]), None), v(&["bash"]));
+        assert_eq!(effective_allowed_tools(None, None), None);
+    }
 }
@@ -1,9 +1,13 @@
 //! Request preparation: register thread in workspace, append initial user message, build RunOptions and RunCmd.
 
-use loom::{cli_run::RunCancellation, protocol::AgentIdentifier, AgentType, Message, RunCmd, RunOptions};
+use loom::{
+    cli_run::RunCancellation, protocol::AgentIdentifier, AgentType, Message, RunCmd, RunOptions,
+};
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::app::BuilderDefaults;
+
 /// Registers the run's thread in the given workspace when all of workspace_id, thread_id,
 /// and workspace_store are present (run-time association for UI: "thread belongs to workspace").
 /// Missing any of the three is a no-op. On store error only logs a warning and does not
@@ -21,13 +25,57 @@ pub(super) async fn try_register_thread_in_workspace(
     }
 }
 
-/// Appends the initial user message to the per-thread message store when both thread_id
-/// and user_message_store are set. Returns `true` if append was performed (caller may use
-/// this to set initial message count for the run). Returns `false` if store or thread_id
-/// is missing, or append I/O failed (only a warning is logged; run is not failed).
+/// Loads the default run settings of the request's workspace. Returns empty defaults when the
+/// request has no workspace_id, no store is configured, or the lookup fails (logged as a warning).
+pub(super) async fn load_workspace_defaults(
+    workspace_store: Option<&Arc<loom_workspace::Store>>,
+    workspace_id: Option<&str>,
+) -> loom_workspace::WorkspaceDefaults {
+    let (Some(store), Some(ws_id)) = (workspace_store, workspace_id) else {
+        return Default::default();
+    };
+    store
+        .get_workspace_defaults(ws_id)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("workspace get_workspace_defaults: {}", e);
+            Default::default()
+        })
+}
+
+/// Scope of the `search_history` tool for a run: the workspace's threads in the user-message
+/// store. `None` (tool not registered) without a workspace or store.
+pub(super) async fn workspace_history_search(
+    workspace_store: Option<&Arc<loom_workspace::Store>>,
+    user_message_store: Option<&Arc<dyn loom::UserMessageStore>>,
+    workspace_id: Option<&str>,
+) -> Option<loom::tools::HistorySearch> {
+    let (Some(ws_store), Some(store), Some(ws_id)) =
+        (workspace_store, user_message_store, workspace_id)
+    else {
+        return None;
+    };
+    match ws_store.list_threads(ws_id).await {
+        Ok(threads) => Some(loom::tools::HistorySearch {
+            store: Arc::clone(store),
+            thread_ids: Some(threads.into_iter().map(|t| t.thread_id).collect()),
+        }),
+        Err(e) => {
+            tracing::warn!("workspace list_threads for history search: {}", e);
+            None
+        }
+    }
+}
+
+/// Appends the initial user message, as a message of run `run_id`, to the per-thread message
+/// store when both thread_id and user_message_store are set. Returns `true` if append was
+/// performed (caller may use this to set initial message count for the run). Returns `false`
+/// if store or thread_id is missing, or append I/O failed (only a warning is logged; run is
+/// not failed).
 pub(super) async fn try_append_initial_user_message(
     user_message_store: Option<&Arc<dyn loom::UserMessageStore>>,
     thread_id: Option<&str>,
+    run_id: &str,
     message: &str,
 ) -> bool {
     let Some(store) = user_message_store else {
@@ -37,7 +85,10 @@ pub(super) async fn try_append_initial_user_message(
         return false;
     };
     let msg = Message::user(message);
-    match store.append(thread_id, &msg).await {
+    match store
+        .append_with_meta(thread_id, &msg, &loom::MessageMeta::for_run(run_id))
+        .await
+    {
         Ok(()) => true,
         Err(e) => {
             tracing::warn!("user_message_store append initial user: {}", e);
@@ -48,7 +99,35 @@ pub(super) async fn try_append_initial_user_message(
 
 /// Input for building run options and command from a Run request.
 pub(super) struct PrepareRunInput {
+    /// Run the request starts; stored with the initial user message.
+    pub run_id: String,
     pub display_max_len: usize,
+    /// Server default role, used when the workspace sets none.
+    pub role_setting: Option<String>,
+    /// Server-wide tool allowlist; narrows the workspace allowlist.
+    pub allowed_tools: Option<Vec<String>>,
+    /// Server-wide read-only mode; forces read-only whatever the request says.
+    pub read_only: bool,
+    /// Server default model, used when neither the request nor the workspace names one.
+    pub default_model: Option<String>,
+    /// Normalization applied to the user message before it is stored or run.
+    pub input_policy: loom::InputPolicy,
+    /// Builder settings of the server: working folder when the request and workspace set
+    /// none, and provider, base URL and API key where the run's model resolution leaves them
+    /// unset.
+    pub builder: Option<Arc<BuilderDefaults>>,
+}
+
+/// Tool allowlist for a run: the workspace allowlist narrowed to the server allowlist when both
+/// are set, otherwise whichever is set.
+pub(super) fn effective_allowed_tools(
+    workspace_tools: Option<Vec<String>>,
+    server_tools: Option<Vec<String>>,
+) -> Option<Vec<String>> {
+    match (workspace_tools, server_tools) {
+        (Some(ws), Some(server)) => Some(ws.into_iter().filter(|t| server.contains(t)).collect()),
+        (ws, server) => ws.or(server),
+    }
 }
 
 /// Result of request preparation: options, command, whether the initial user message was appended, and cancellation handle.
@@ -60,13 +139,23 @@ pub(super) struct PrepareRunResult {
 }
 
 /// Registers thread in workspace, appends initial user message when configured, and builds
-/// RunOptions and RunCmd from the request. Used by [`crate::run::handle_run`].
+/// RunOptions and RunCmd from the request. Workspace defaults fill `model` and `working_folder`
+/// when the request omits them and supply the role and tool allowlist; the server's role and
+/// allowlist (see [`PrepareRunInput`]) apply on top, and the user message is normalized with
+/// the server's input policy and pinned when the request sets `pin`. Runs in a workspace get the
+/// `search_history` tool over the workspace's threads. Used by
+/// [`crate::run::handle_run`].
 pub(super) async fn prepare_run(
-    r: loom::RunRequest,
+    mut r: loom::RunRequest,
     workspace_store: Option<&Arc<loom_workspace::Store>>,
     user_message_store: Option<&Arc<dyn loom::UserMessageStore>>,
     input: PrepareRunInput,
 ) -> PrepareRunResult {
+    input.input_policy.apply_content(r.user_message_mut());
+    if r.pin == Some(true) {
+        let message = r.user_message_mut();
+        *message = std::mem::take(message).pinned();
+    }
     try_register_thread_in_workspace(
         workspace_store,
         r.workspace_id.as_deref(),
@@ -74,14 +163,42 @@ pub(super) async fn prepare_run(
     )
     .await;
 
+    let defaults = load_workspace_defaults(workspace_store, r.workspace_id.as_deref()).await;
+    if r.model.is_none() {
+        r.model = defaults.model.or(input.default_model.clone());
+    }
+    if r.working_folder.is_none() {
+        r.working_folder = defaults.working_folder.or_else(|| {
+            input
+                .builder
+                .as_ref()
+                .and_then(|b| b.working_folder.as_ref())
+                .map(|p| p.display().to_string())
+        });
+    }
+    let history_search = workspace_history_search(
+        workspace_store,
+        user_message_store,
+        r.workspace_id.as_deref(),
+    )
+    .await;
+
     let initial_user_appended = try_append_initial_user_message(
         user_message_store,
         r.thread_id.as_deref(),
-        r.message.as_text().as_ref(),
+        &input.run_id,
+        r.user_message().as_text().as_ref(),
     )
     .await;
 
-    let resolved = loom::resolve_model_config(r.model.as_deref()).await;
+    let mut resolved = loom::resolve_model_config(r.model.as_deref()).await;
+    if let Some(ref b) = input.builder {
+        resolved.provider = resolved.provider.or_else(|| b.provider.clone());
+        resolved.base_url = resolved.base_url.or_else(|| b.base_url.clone());
+        resolved.api_key = resolved
+            .api_key
+            .or_else(|| b.api_key.as_ref().map(|k| k.expose().to_string()));
+    }
 
     // Log the model resolution
     match &r.model {
@@ -110,12 +227,13 @@ pub(super) async fn prepare_run(
     let run_cancellation = RunCancellation::new(1);
 
     let opts = RunOptions {
-        message: r.message,
+        message: r.user_message().clone(),
         working_folder: r.working_folder.map(PathBuf::from),
         session_id: None,
         cancellation: Some(run_cancellation.clone()),
         thread_id: r.thread_id,
         agent: None,
+        agent_file: None,
         verbose: r.verbose.unwrap_or(false),
         got_adaptive: r.got_adaptive.unwrap_or(false),
         display_max_len: input.display_max_len,
@@ -124,10 +242,19 @@ pub(super) async fn prepare_run(
         mcp_config_path: None,
         output_timestamp: false,
         dry_run: false,
+        role_setting: defaults.role.or(input.role_setting),
+        allowed_tools: effective_allowed_tools(defaults.tools, input.allowed_tools),
+        read_only: input.read_only || r.read_only.unwrap_or(false),
+        reply_format: r.reply_format,
+        reply_schema: r.reply_schema,
+        messages: r.messages,
+        history_search,
+        locale: r.locale,
         provider: resolved.provider,
         base_url: resolved.base_url,
         api_key: resolved.api_key,
         provider_type: resolved.provider_type,
+        flags: r.flags,
     };
 
     // Handle both AgentType (react/dup/tot/got) and custom agent names
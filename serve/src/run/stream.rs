@@ -1,14 +1,16 @@
 //! Agent run task: stream events to protocol envelopes and optional message store append.
 
 use loom::{
-    run_agent_with_options, AnyStreamEvent, EnvelopeState, Message, ProtocolEventEnvelope, RunCmd,
-    RunCompletion, RunError, RunOptions, StreamEvent,
+    run_agent_with_llm_override, AnyStreamEvent, EnvelopeState, Message, MessageMeta, ReActState,
+    RunCmd, RunCompletion, RunError, RunOptions, StreamEvent,
 };
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
 
-/// Default event queue capacity (used by tests; production uses [`crate::app::RunConfig`]).
+use super::lanes::EventSender;
+
+/// Default data lane capacity (used by tests; production uses [`crate::app::RunConfig`]).
 #[allow(dead_code)]
 pub(super) const EVENT_QUEUE_CAPACITY: usize = 128;
 
@@ -16,34 +18,74 @@ pub(super) const EVENT_QUEUE_CAPACITY: usize = 128;
 #[allow(dead_code)]
 pub(super) const APPEND_QUEUE_CAPACITY: usize = 64;
 
-/// Extracts the message list from a React stream event, if the event carries one.
-fn react_event_messages(ev: &AnyStreamEvent) -> Option<&[Message]> {
+/// Message to store: thread id, message and its meta.
+type AppendItem = (String, Message, MessageMeta);
+
+/// Extracts the state from a React stream event, if the event carries one.
+fn react_event_state(ev: &AnyStreamEvent) -> Option<&ReActState> {
     let AnyStreamEvent::React(react_ev) = ev else {
         return None;
     };
-    let messages = match react_ev {
-        StreamEvent::Values(s) => &s.messages[..],
-        StreamEvent::Updates { state: s, .. } => &s.messages[..],
-        StreamEvent::Checkpoint(cp) => &cp.state.messages[..],
+    let state = match react_ev {
+        StreamEvent::Values(s) => s,
+        StreamEvent::Updates { state: s, .. } => s,
+        StreamEvent::Checkpoint(cp) => &cp.state,
         _ => return None,
     };
-    Some(messages)
+    Some(state)
 }
 
-/// Sends only the *new* messages (since last seen count) from a React event into the
-/// append channel; when append channel is full, increments `dropped_appends` if provided.
-fn forward_react_messages_to_append(
-    ev: &AnyStreamEvent,
-    append_tx: Option<&mpsc::Sender<(String, Message)>>,
-    thread_id: Option<&String>,
-    message_count: &Arc<Mutex<usize>>,
-    dropped_appends: Option<&Arc<AtomicUsize>>,
-) {
+/// Name of the tool called with `tool_call_id` by an assistant message in `messages`.
+fn tool_call_name(messages: &[Message], tool_call_id: &str) -> Option<String> {
+    messages.iter().rev().find_map(|m| match m {
+        Message::Assistant(payload) => payload
+            .tool_calls
+            .iter()
+            .find(|call| call.id == tool_call_id)
+            .map(|call| call.name.clone()),
+        _ => None,
+    })
+}
+
+/// Meta stored with `state.messages[index]`: the run, the tool a tool message answers and,
+/// for the last assistant message, the usage of the last LLM call.
+pub(super) fn message_meta(state: &ReActState, index: usize, run_id: &str) -> MessageMeta {
+    let messages = &state.messages;
+    let mut meta = MessageMeta::for_run(run_id);
+    match &messages[index] {
+        Message::Assistant(_)
+            if messages[index + 1..]
+                .iter()
+                .all(|m| !matches!(m, Message::Assistant(_))) =>
+        {
+            meta.usage = state.usage.clone();
+        }
+        Message::Tool { tool_call_id, .. } => {
+            meta.tool_name = tool_call_name(&messages[..index], tool_call_id);
+            meta.tool_call_id = Some(tool_call_id.clone());
+        }
+        _ => {}
+    }
+    meta
+}
+
+/// Sends only the *new* messages (since last seen count) from a React event, with their
+/// [`MessageMeta`], into the append channel; when append channel is full, increments
+/// `dropped_appends` if provided.
+fn forward_react_messages_to_append(ev: &AnyStreamEvent, append_ctx: &AppendContext<'_>) {
+    let AppendContext {
+        append_tx,
+        thread_id,
+        run_id,
+        message_count,
+        dropped_appends,
+    } = *append_ctx;
     let Some(atx) = append_tx else { return };
     let Some(tid) = thread_id else { return };
-    let Some(messages) = react_event_messages(ev) else {
+    let Some(state) = react_event_state(ev) else {
         return;
     };
+    let messages = &state.messages;
     let mut seen_count = match message_count.lock() {
         Ok(g) => g,
         Err(e) => {
@@ -55,8 +97,9 @@ fn forward_react_messages_to_append(
     if start >= messages.len() {
         return;
     }
-    for msg in &messages[start..] {
-        if atx.try_send((tid.clone(), msg.clone())).is_err() {
+    for (index, msg) in messages.iter().enumerate().skip(start) {
+        let meta = message_meta(state, index, run_id);
+        if atx.try_send((tid.clone(), msg.clone(), meta)).is_err() {
             if let Some(c) = dropped_appends {
                 c.fetch_add(1, Ordering::Relaxed);
             }
@@ -67,17 +110,21 @@ fn forward_react_messages_to_append(
 
 /// Handles a single stream event: forward new messages to append channel, convert to
 /// protocol envelope and send to `tx`. Increments drop counters when queues are full.
+#[derive(Clone, Copy)]
 struct AppendContext<'a> {
-    append_tx: Option<&'a mpsc::Sender<(String, Message)>>,
+    append_tx: Option<&'a mpsc::Sender<AppendItem>>,
     thread_id: Option<&'a String>,
+    run_id: &'a str,
     message_count: &'a Arc<Mutex<usize>>,
     dropped_appends: Option<&'a Arc<AtomicUsize>>,
 }
 
 struct EventContext<'a> {
     state: &'a Arc<Mutex<EnvelopeState>>,
-    tx: &'a mpsc::Sender<ProtocolEventEnvelope>,
+    tx: &'a EventSender,
     dropped_events: Option<&'a Arc<AtomicUsize>>,
+    /// See [`crate::app::RunConfig::values_max_bytes`].
+    values_max_bytes: Option<usize>,
 }
 
 fn process_run_stream_event(
@@ -85,13 +132,7 @@ fn process_run_stream_event(
     event_ctx: &EventContext<'_>,
     append_ctx: &AppendContext<'_>,
 ) {
-    forward_react_messages_to_append(
-        &ev,
-        append_ctx.append_tx,
-        append_ctx.thread_id,
-        append_ctx.message_count,
-        append_ctx.dropped_appends,
-    );
+    forward_react_messages_to_append(&ev, append_ctx);
 
     let mut guard = match event_ctx.state.lock() {
         Ok(g) => g,
@@ -100,10 +141,14 @@ fn process_run_stream_event(
             return;
         }
     };
-    let Ok(protocol_envelope) = ev.to_protocol_event(&mut guard) else {
+    // The guard is held until the event is queued so queue order matches event_id order.
+    let Ok(mut protocol_envelope) = ev.to_protocol_event(&mut guard) else {
         return;
     };
-    if event_ctx.tx.try_send(protocol_envelope).is_err() {
+    if let Some(max_bytes) = event_ctx.values_max_bytes {
+        super::trim::trim_values(&mut protocol_envelope.event, max_bytes);
+    }
+    if !event_ctx.tx.send(protocol_envelope) {
         if let Some(c) = event_ctx.dropped_events {
             c.fetch_add(1, Ordering::Relaxed);
         }
@@ -113,14 +158,18 @@ fn process_run_stream_event(
 
 /// Runs the agent in the current task. Returns result, envelope state, and drop counters.
 pub(super) struct AgentTaskParams {
+    /// The run id; also stored as the run of the messages the run appends.
     pub(super) session_id: String,
-    pub(super) tx: mpsc::Sender<ProtocolEventEnvelope>,
+    pub(super) tx: EventSender,
     pub(super) opts: RunOptions,
     pub(super) cmd: RunCmd,
     pub(super) initial_user_appended: bool,
     pub(super) user_message_store: Option<Arc<dyn loom::UserMessageStore>>,
     pub(super) thread_id: Option<String>,
     pub(super) append_queue_capacity: usize,
+    pub(super) values_max_bytes: Option<usize>,
+    /// See [`crate::app::RunConfig::llm_script`].
+    pub(super) llm_script: Option<loom::MockScript>,
 }
 
 pub(super) async fn run_agent_task(
@@ -140,21 +189,23 @@ pub(super) async fn run_agent_task(
         user_message_store,
         thread_id,
         append_queue_capacity,
+        values_max_bytes,
+        llm_script,
     } = params;
     let state = Arc::new(Mutex::new(EnvelopeState::new(session_id.clone())));
     let state_clone = state.clone();
     let dropped_events = Arc::new(AtomicUsize::new(0));
     let dropped_appends = Arc::new(AtomicUsize::new(0));
 
-    let (append_tx, mut append_rx) = mpsc::channel::<(String, Message)>(append_queue_capacity);
+    let (append_tx, mut append_rx) = mpsc::channel::<AppendItem>(append_queue_capacity);
     let message_count = Arc::new(Mutex::new(if initial_user_appended { 1 } else { 0 }));
     let append_handle = if let (Some(store), Some(_thread_id)) =
         (user_message_store.as_ref(), thread_id.as_ref())
     {
         let store = Arc::clone(store);
         Some(tokio::spawn(async move {
-            while let Some((tid, msg)) = append_rx.recv().await {
-                if let Err(e) = store.append(&tid, &msg).await {
+            while let Some((tid, msg, meta)) = append_rx.recv().await {
+                if let Err(e) = store.append_with_meta(&tid, &msg, &meta).await {
                     tracing::warn!("user_message_store append: {}", e);
                 }
             }
@@ -175,16 +226,20 @@ pub(super) async fn run_agent_task(
             state: &state_clone,
             tx: &tx,
             dropped_events: Some(&dropped_events_clone),
+            values_max_bytes,
         };
         let append_ctx = AppendContext {
             append_tx: append_tx_for_closure.as_ref(),
             thread_id: thread_id_closure.as_ref(),
+            run_id: &session_id,
             message_count: &message_count_clone,
             dropped_appends: Some(&dropped_appends_clone),
         };
         process_run_stream_event(ev, &event_ctx, &append_ctx);
     });
-    let result = run_agent_with_options(&opts, &cmd, Some(on_event)).await;
+    let llm_override = llm_script
+        .map(|script| Box::new(loom::MockLlm::scripted(script)) as Box<dyn loom::LlmClient>);
+    let result = run_agent_with_llm_override(&opts, &cmd, Some(on_event), llm_override).await;
     drop(append_tx_to_drop);
     if let Some(h) = append_handle {
         let _ = h.await;
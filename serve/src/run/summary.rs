@@ -0,0 +1,69 @@
+//! Auto-summarize: after RunEnd, one extra (cheap) LLM call produces a thread title and short
+//! summary, which is stored in the workspace store and sent as a `thread_summary` stream event.
+
+use loom::{LlmClient, RunOptions, ThreadSummary};
+use std::sync::Arc;
+
+use crate::app::RunConfig;
+
+/// Pending title/summary generation for one run's thread.
+pub(crate) struct ThreadSummaryJob {
+    pub(crate) thread_id: String,
+    pub(crate) user_message: String,
+    pub(crate) llm: Box<dyn LlmClient>,
+    pub(crate) workspace_store: Option<Arc<loom_workspace::Store>>,
+}
+
+impl ThreadSummaryJob {
+    /// Returns a job when `auto_summarize` is enabled and the run has a thread_id. The LLM uses
+    /// the run's provider and credentials, with `summary_model` replacing the model when set.
+    /// Build failures are logged and disable summarization for this run.
+    pub(crate) fn from_run(
+        opts: &RunOptions,
+        run_config: &RunConfig,
+        workspace_store: Option<Arc<loom_workspace::Store>>,
+    ) -> Option<Self> {
+        if !run_config.auto_summarize {
+            return None;
+        }
+        let thread_id = opts.thread_id.clone()?;
+        let (_helve, config, _agent) = loom::cli_run::build_helve_config(opts);
+        let llm = match loom::build_auxiliary_llm(&config, run_config.summary_model.as_deref()) {
+            Ok(llm) => llm,
+            Err(e) => {
+                tracing::warn!(thread_id = %thread_id, "auto-summarize disabled for run: {}", e);
+                return None;
+            }
+        };
+        Some(Self {
+            thread_id,
+            user_message: opts.message.as_text().into_owned(),
+            llm,
+            workspace_store,
+        })
+    }
+
+    /// Generates the title and summary for `reply` and stores it with the thread. Errors are
+    /// logged and yield `None`: the run itself has already succeeded.
+    pub(crate) async fn run(&self, reply: &str) -> Option<ThreadSummary> {
+        let summary =
+            match loom::llm::generate_thread_summary(self.llm.as_ref(), &self.user_message, reply)
+                .await
+            {
+                Ok(s) => s,
+                Err(e) => {
+                    tracing::warn!(thread_id = %self.thread_id, "thread summary generation: {}", e);
+                    return None;
+                }
+            };
+        if let Some(store) = &self.workspace_store {
+            if let Err(e) = store
+                .set_thread_summary(&self.thread_id, &summary.title, &summary.summary)
+                .await
+            {
+                tracing::warn!("workspace set_thread_summary: {}", e);
+            }
+        }
+        Some(summary)
+    }
+}
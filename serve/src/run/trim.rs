@@ -0,0 +1,71 @@
+//! Payload trimming for slow links: a `values` event whose state serializes to more than
+//! `SERVE_VALUES_MAX_BYTES` is sent with a digest in place of the state.
+//!
+//! The digest is `{"trimmed": true, "bytes": N, "sha256": "<hex>", "message_count": M}`
+//! (`message_count` when the state has a `messages` array). Clients that need the full state
+//! read it with `state_show`; an unchanged `sha256` means the state did not change.
+
+use loom::ProtocolEvent;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+/// Replaces the state of a `values` event larger than `max_bytes` with its digest. Returns
+/// whether the event was trimmed.
+pub(super) fn trim_values(event: &mut ProtocolEvent, max_bytes: usize) -> bool {
+    let ProtocolEvent::Values { state } = event else {
+        return false;
+    };
+    let Ok(bytes) = serde_json::to_vec(state) else {
+        return false;
+    };
+    if bytes.len() <= max_bytes {
+        return false;
+    }
+    *state = state_digest(state, &bytes);
+    true
+}
+
+/// Digest of `state`, whose JSON serialization is `bytes`.
+fn state_digest(state: &Value, bytes: &[u8]) -> Value {
+    let hash = hex::encode(Sha256::digest(bytes));
+    let mut digest = json!({
+        "trimmed": true,
+        "bytes": bytes.len(),
+        "sha256": hash,
+    });
+    if let Some(messages) = state.get("messages").and_then(Value::as_array) {
+        digest["message_count"] = json!(messages.len());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trims_only_values_events_over_the_limit() {
+        let state = json!({ "messages": [{ "role": "user", "content": "x".repeat(100) }] });
+        let mut small = ProtocolEvent::Values {
+            state: state.clone(),
+        };
+        assert!(!trim_values(&mut small, 4096));
+
+        let mut large = ProtocolEvent::Values {
+            state: state.clone(),
+        };
+        assert!(trim_values(&mut large, 64));
+        let ProtocolEvent::Values { state: digest } = large else {
+            panic!("expected values event");
+        };
+        assert_eq!(digest["trimmed"], json!(true));
+        assert_eq!(digest["message_count"], json!(1));
+        assert_eq!(digest["sha256"].as_str().unwrap().len(), 64);
+
+        let mut other = ProtocolEvent::Updates {
+            id: "think".to_string(),
+            state,
+        };
+        assert!(!trim_values(&mut other, 64));
+    }
+}
@@ -0,0 +1,70 @@
+//! Usage recording: token counts from a run's `usage` events are summed while the run streams
+//! and stored in the workspace store when it ends, for `usage_report`.
+
+use loom::{ProtocolEvent, RunCmd, RunOptions};
+use loom_workspace::RunUsage;
+use std::sync::Arc;
+
+/// Usage being collected for one run.
+pub(crate) struct RunUsageJob {
+    pub(crate) usage: RunUsage,
+    pub(crate) workspace_store: Arc<loom_workspace::Store>,
+}
+
+impl RunUsageJob {
+    /// Returns a job when a workspace store is configured. The model is the one the run's LLM
+    /// is built with; the agent is the run's agent type.
+    pub(crate) fn from_run(
+        run_id: &str,
+        opts: &RunOptions,
+        cmd: &RunCmd,
+        workspace_id: Option<String>,
+        workspace_store: Option<Arc<loom_workspace::Store>>,
+    ) -> Option<Self> {
+        let workspace_store = workspace_store?;
+        let (_helve, config, _agent) = loom::cli_run::build_helve_config(opts);
+        Some(Self {
+            usage: RunUsage {
+                run_id: run_id.to_string(),
+                workspace_id,
+                thread_id: opts.thread_id.clone(),
+                model: config.resolved_model().to_string(),
+                agent: agent_type_name(cmd).to_string(),
+                prompt_tokens: 0,
+                completion_tokens: 0,
+                total_tokens: 0,
+            },
+            workspace_store,
+        })
+    }
+
+    /// Adds the counts of a `usage` event; other events are ignored.
+    pub(crate) fn observe(&mut self, event: &ProtocolEvent) {
+        if let ProtocolEvent::Usage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens,
+        } = event
+        {
+            self.usage.prompt_tokens += u64::from(*prompt_tokens);
+            self.usage.completion_tokens += u64::from(*completion_tokens);
+            self.usage.total_tokens += u64::from(*total_tokens);
+        }
+    }
+
+    /// Stores the collected usage. Errors are only logged.
+    pub(crate) async fn record(self) {
+        if let Err(e) = self.workspace_store.record_run_usage(&self.usage).await {
+            tracing::warn!("workspace record_run_usage: {}", e);
+        }
+    }
+}
+
+fn agent_type_name(cmd: &RunCmd) -> &'static str {
+    match cmd {
+        RunCmd::React => "react",
+        RunCmd::Dup => "dup",
+        RunCmd::Tot => "tot",
+        RunCmd::Got { .. } => "got",
+    }
+}
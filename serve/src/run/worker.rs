@@ -0,0 +1,700 @@
+//! Worker pool: runs executed by `loom worker` processes instead of tasks of the server.
+//!
+//! With [`ENV_WORKERS`] set to N > 0, each run goes to one of up to N worker processes (the
+//! server binary started as `<binary> worker`, or [`ENV_WORKER_PROGRAM`]). A panic, abort or OOM
+//! kill then takes down that worker, not the server: the run ends with an ErrorResponse coded
+//! [`ERROR_CODE_WORKER_FAILED`] and the next run starts a fresh worker. A worker runs one run at a
+//! time, is replaced after [`ENV_WORKER_MAX_RUNS`] runs and, on Unix, can be given an address
+//! space limit ([`ENV_WORKER_MEMORY_MB`]).
+//!
+//! The server talks to a worker over its stdin and stdout, one JSON object per line: it writes a
+//! [`WorkerInput::Run`] and then the run's control requests, the worker answers with every
+//! response of the run and a final `done` ([`WorkerOutput`]). The worker prepares and streams
+//! the run like the server does in-process ([`super::stream_run`]), with the server's current
+//! [`RunConfig`] sent along with the run (builder settings, LLM script and reloads included) and
+//! stores read from its environment (inherited from the server when it was spawned). After a
+//! reload, idle workers are retired so new runs start in processes with the reloaded environment.
+
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use loom::{ClientRequest, ErrorResponse, RunRequest, ServerResponse};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::{mpsc, Semaphore};
+use tracing::Instrument;
+
+use super::delivery::{RunControl, RunStreamSender};
+use crate::app::RunConfig;
+
+/// Env var: number of worker processes runs are dispatched to (default 0: runs stay in-process).
+pub(crate) const ENV_WORKERS: &str = "SERVE_WORKERS";
+/// Env var: runs after which a worker is replaced by a fresh one (default 100; `0` never).
+pub(crate) const ENV_WORKER_MAX_RUNS: &str = "SERVE_WORKER_MAX_RUNS";
+/// Env var: address space limit per worker in MiB (Unix only; default 0 = unlimited).
+pub(crate) const ENV_WORKER_MEMORY_MB: &str = "SERVE_WORKER_MEMORY_MB";
+/// Env var: program started (with the argument `worker`) for each worker; default: this binary.
+pub(crate) const ENV_WORKER_PROGRAM: &str = "SERVE_WORKER_PROGRAM";
+
+/// [`ErrorResponse::code`] when the worker running a run exited or broke the protocol.
+pub(crate) const ERROR_CODE_WORKER_FAILED: &str = "worker_failed";
+
+const DEFAULT_MAX_RUNS: u32 = 100;
+
+/// Line from the server to a worker.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WorkerInput {
+    /// Start a run with this id and the server's run settings.
+    Run {
+        run_id: String,
+        request: RunRequest,
+        config: Box<RunConfig>,
+    },
+    /// `stop_generation` or `cancel_run` for the current run.
+    Control { request: ClientRequest },
+}
+
+/// Line from a worker to the server.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WorkerOutput {
+    /// Response to forward to the run's client.
+    Response { response: ServerResponse },
+    /// The run is over; the worker is ready for the next one.
+    Done,
+}
+
+impl RunControl {
+    fn into_request(self) -> ClientRequest {
+        match self {
+            RunControl::StopGeneration(r) => ClientRequest::StopGeneration(r),
+            RunControl::Cancel(r) => ClientRequest::CancelRun(r),
+        }
+    }
+
+    fn from_request(request: ClientRequest) -> Option<Self> {
+        match request {
+            ClientRequest::StopGeneration(r) => Some(RunControl::StopGeneration(r)),
+            ClientRequest::CancelRun(r) => Some(RunControl::Cancel(r)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct WorkerConfig {
+    size: usize,
+    max_runs: u32,
+    memory_mb: u64,
+    program: PathBuf,
+}
+
+/// One worker process and its pipes.
+struct Worker {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+    runs: u32,
+    /// [`PoolInner::generation`] the worker was started in.
+    generation: u64,
+}
+
+impl Worker {
+    fn spawn(config: &WorkerConfig, generation: u64) -> std::io::Result<Self> {
+        let mut child = worker_command(config)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(std::io::Error::other("worker pipes unavailable"));
+        };
+        tracing::info!(pid = child.id(), "👷 Worker started");
+        Ok(Self {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout).lines(),
+            runs: 0,
+            generation,
+        })
+    }
+
+    async fn send(&mut self, input: &WorkerInput) -> std::io::Result<()> {
+        let mut line = serde_json::to_string(input)?;
+        line.push('\n');
+        self.stdin.write_all(line.as_bytes()).await?;
+        self.stdin.flush().await
+    }
+
+    /// Next output line; `Err` when the worker exited or wrote something that is not one.
+    async fn recv(&mut self) -> Result<WorkerOutput, String> {
+        match self.stdout.next_line().await {
+            Ok(Some(line)) => serde_json::from_str(&line)
+                .map_err(|e| format!("invalid worker output ({}): {}", e, line)),
+            Ok(None) => Err(self.exit_status().await),
+            Err(e) => Err(format!("reading worker output: {}", e)),
+        }
+    }
+
+    async fn exit_status(&mut self) -> String {
+        let _ = self.child.start_kill();
+        match self.child.wait().await {
+            Ok(status) => format!("worker exited ({})", status),
+            Err(e) => format!("worker exited: {}", e),
+        }
+    }
+}
+
+/// `<program> worker`, wrapped in `ulimit -v` when a memory limit is set (Unix).
+fn worker_command(config: &WorkerConfig) -> Command {
+    #[cfg(unix)]
+    {
+        if config.memory_mb > 0 {
+            let mut command = Command::new("/bin/sh");
+            command
+                .arg("-c")
+                .arg("ulimit -v \"$0\" && exec \"$@\"")
+                .arg((config.memory_mb * 1024).to_string())
+                .arg(&config.program)
+                .arg("worker");
+            return command;
+        }
+    }
+    let mut command = Command::new(&config.program);
+    command.arg("worker");
+    command
+}
+
+struct PoolInner {
+    config: WorkerConfig,
+    idle: Mutex<Vec<Worker>>,
+    slots: Arc<Semaphore>,
+    /// Bumped by [`WorkerPool::retire_idle`]; workers of an older generation are not reused.
+    generation: AtomicU64,
+}
+
+/// Pool of worker processes shared by all connections; disabled (runs stay in-process) unless
+/// [`ENV_WORKERS`] is set. See the module docs.
+#[derive(Clone, Default)]
+pub(crate) struct WorkerPool {
+    inner: Option<Arc<PoolInner>>,
+}
+
+impl WorkerPool {
+    fn new(config: WorkerConfig) -> Self {
+        Self {
+            inner: Some(Arc::new(PoolInner {
+                slots: Arc::new(Semaphore::new(config.size)),
+                idle: Mutex::new(Vec::new()),
+                config,
+                generation: AtomicU64::new(0),
+            })),
+        }
+    }
+
+    /// Reads [`ENV_WORKERS`], [`ENV_WORKER_MAX_RUNS`], [`ENV_WORKER_MEMORY_MB`] and
+    /// [`ENV_WORKER_PROGRAM`].
+    pub(crate) fn from_env() -> Self {
+        let number = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|s| s.trim().parse::<u64>().ok())
+        };
+        let size = number(ENV_WORKERS).unwrap_or(0) as usize;
+        if size == 0 {
+            return Self::default();
+        }
+        let program = match std::env::var(ENV_WORKER_PROGRAM)
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+        {
+            Some(program) => PathBuf::from(program.trim()),
+            None => match std::env::current_exe() {
+                Ok(exe) => exe,
+                Err(e) => {
+                    tracing::error!("❌ Worker pool disabled, no worker program: {}", e);
+                    return Self::default();
+                }
+            },
+        };
+        let memory_mb = number(ENV_WORKER_MEMORY_MB).unwrap_or(0);
+        if memory_mb > 0 && !cfg!(unix) {
+            tracing::warn!("⚠️  {} is only applied on Unix", ENV_WORKER_MEMORY_MB);
+        }
+        tracing::info!(
+            "👷 Runs go to up to {} worker processes ({})",
+            size,
+            program.display()
+        );
+        Self::new(WorkerConfig {
+            size,
+            max_runs: number(ENV_WORKER_MAX_RUNS).map_or(DEFAULT_MAX_RUNS, |n| n as u32),
+            memory_mb,
+            program,
+        })
+    }
+
+    pub(crate) fn enabled(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    /// Stops the idle workers and keeps busy ones from being reused once their run ends, so
+    /// later runs start in fresh processes (after a config reload changed the environment).
+    pub(crate) fn retire_idle(&self) {
+        let Some(inner) = self.inner.as_ref() else {
+            return;
+        };
+        inner.generation.fetch_add(1, Ordering::SeqCst);
+        let retired = inner
+            .idle
+            .lock()
+            .map(|mut idle| std::mem::take(&mut *idle))
+            .unwrap_or_default();
+        if !retired.is_empty() {
+            tracing::info!(count = retired.len(), "👷 Idle workers retired");
+        }
+    }
+
+    /// Runs `request` as `run_id` with `run_config` on a worker and forwards its responses
+    /// through `sender`, as [`super::stream_run`] does for an in-process run. Control requests
+    /// from `sender` go to
+    /// the worker. When the worker dies before the run ended, an ErrorResponse with
+    /// [`ERROR_CODE_WORKER_FAILED`] ends the run. Returns `Err` when `sender` fails.
+    pub(crate) async fn run<S>(
+        &self,
+        request: RunRequest,
+        run_id: &str,
+        run_config: &RunConfig,
+        sender: &mut S,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+    where
+        S: RunStreamSender,
+    {
+        let Some(inner) = self.inner.as_ref() else {
+            return Err("worker pool is disabled".into());
+        };
+        let _slot = Arc::clone(&inner.slots).acquire_owned().await?;
+        let input = WorkerInput::Run {
+            run_id: run_id.to_string(),
+            request,
+            config: Box::new(run_config.clone()),
+        };
+        let mut worker = match inner.start(&input).await {
+            Ok(worker) => worker,
+            Err(e) => {
+                tracing::error!("❌ Run {}: no worker: {}", run_id, e);
+                return sender.send_response(&worker_failed(run_id, &e)).await;
+            }
+        };
+
+        let mut ended = false;
+        let mut controls_open = true;
+        let failure = loop {
+            tokio::select! {
+                output = worker.recv() => match output {
+                    Ok(WorkerOutput::Response { response }) => {
+                        ended |= matches!(
+                            &response,
+                            ServerResponse::RunEnd(_) | ServerResponse::Error(_)
+                        );
+                        // Dropping the worker kills it, which aborts the run as in-process.
+                        sender.send_response(&response).await?;
+                    }
+                    Ok(WorkerOutput::Done) => break None,
+                    Err(e) => break Some(e),
+                },
+                control = sender.recv_control(run_id), if controls_open => match control {
+                    Some(control) => {
+                        let input = WorkerInput::Control { request: control.into_request() };
+                        // A dead worker shows up as a failed read.
+                        let _ = worker.send(&input).await;
+                    }
+                    None => controls_open = false,
+                },
+            }
+        };
+
+        match failure {
+            None => {
+                inner.release(worker);
+                Ok(())
+            }
+            Some(e) => {
+                tracing::error!("❌ Run {}: {}", run_id, e);
+                if ended {
+                    return Ok(());
+                }
+                sender.send_response(&worker_failed(run_id, &e)).await
+            }
+        }
+    }
+}
+
+impl PoolInner {
+    /// Sends `input` to an idle worker, or to a new one when there is none or it has died.
+    async fn start(&self, input: &WorkerInput) -> Result<Worker, String> {
+        let generation = self.generation.load(Ordering::SeqCst);
+        let idle = self.idle.lock().ok().and_then(|mut idle| idle.pop());
+        if let Some(mut worker) = idle.filter(|w| w.generation == generation) {
+            if worker.send(input).await.is_ok() {
+                return Ok(worker);
+            }
+            tracing::warn!("⚠️  Idle worker is gone, starting a new one");
+        }
+        let mut worker =
+            Worker::spawn(&self.config, generation).map_err(|e| format!("spawn: {}", e))?;
+        worker
+            .send(input)
+            .await
+            .map_err(|e| format!("sending run: {}", e))?;
+        Ok(worker)
+    }
+
+    /// Keeps `worker` for the next run, unless it has done its [`ENV_WORKER_MAX_RUNS`] or was
+    /// retired meanwhile.
+    fn release(&self, mut worker: Worker) {
+        worker.runs += 1;
+        if worker.generation != self.generation.load(Ordering::SeqCst) {
+            return;
+        }
+        if self.config.max_runs > 0 && worker.runs >= self.config.max_runs {
+            tracing::info!(
+                pid = worker.child.id(),
+                runs = worker.runs,
+                "👷 Worker retired"
+            );
+            return;
+        }
+        if let Ok(mut idle) = self.idle.lock() {
+            idle.push(worker);
+        }
+    }
+}
+
+fn worker_failed(run_id: &str, error: &str) -> ServerResponse {
+    ServerResponse::Error(ErrorResponse {
+        id: Some(run_id.to_string()),
+        error: format!("run worker failed: {}", error),
+        code: Some(ERROR_CODE_WORKER_FAILED.to_string()),
+    })
+}
+
+/// [`RunStreamSender`] of a worker: writes responses as [`WorkerOutput`] lines and takes
+/// control requests from the input lines.
+struct WorkerSender<'a, W> {
+    output: &'a mut W,
+    inputs: &'a mut mpsc::Receiver<WorkerInput>,
+}
+
+#[async_trait]
+impl<W> RunStreamSender for WorkerSender<'_, W>
+where
+    W: AsyncWrite + Unpin + Send,
+{
+    async fn send_response(
+        &mut self,
+        response: &ServerResponse,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        write_output(
+            self.output,
+            &WorkerOutput::Response {
+                response: response.clone(),
+            },
+        )
+        .await
+    }
+
+    async fn recv_control(&mut self, _run_id: &str) -> Option<RunControl> {
+        loop {
+            match self.inputs.recv().await? {
+                WorkerInput::Control { request } => {
+                    if let Some(control) = RunControl::from_request(request) {
+                        return Some(control);
+                    }
+                }
+                WorkerInput::Run { run_id, .. } => {
+                    tracing::warn!("⚠️  Worker is busy, ignoring run {}", run_id);
+                }
+            }
+        }
+    }
+}
+
+async fn write_output<W>(
+    output: &mut W,
+    line: &WorkerOutput,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+where
+    W: AsyncWrite + Unpin + Send,
+{
+    let mut text = serde_json::to_string(line)?;
+    text.push('\n');
+    output.write_all(text.as_bytes()).await?;
+    output.flush().await?;
+    Ok(())
+}
+
+/// Worker main loop: runs each [`WorkerInput::Run`] read from `input` with the run config it
+/// carries and writes its responses and `done` to `output`, until `input` ends or `output` fails.
+pub(crate) async fn serve_worker<R, W>(
+    input: R,
+    mut output: W,
+    workspace_store: Option<Arc<loom_workspace::Store>>,
+    user_message_store: Option<Arc<dyn loom::UserMessageStore>>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+where
+    R: AsyncBufRead + Unpin + Send + 'static,
+    W: AsyncWrite + Unpin + Send,
+{
+    let (tx, mut inputs) = mpsc::channel::<WorkerInput>(16);
+    tokio::spawn(async move {
+        let mut lines = input.lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            match serde_json::from_str::<WorkerInput>(&line) {
+                Ok(input) => {
+                    if tx.send(input).await.is_err() {
+                        break;
+                    }
+                }
+                Err(e) => tracing::warn!("⚠️  Worker ignored invalid input: {}", e),
+            }
+        }
+    });
+
+    while let Some(next) = inputs.recv().await {
+        let WorkerInput::Run {
+            run_id,
+            request,
+            config,
+        } = next
+        else {
+            continue;
+        };
+        let span = super::run_span(&run_id);
+        tracing::info!(parent: &span, "👷 Worker starting run {}", run_id);
+        let mut sender = WorkerSender {
+            output: &mut output,
+            inputs: &mut inputs,
+        };
+        super::stream_run(
+            request,
+            run_id,
+            &mut sender,
+            workspace_store.clone(),
+            user_message_store.clone(),
+            &config,
+        )
+        .instrument(span)
+        .await?;
+        write_output(&mut output, &WorkerOutput::Done).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use loom::protocol::requests::CancelRunRequest;
+    use loom::StopGenerationRequest;
+
+    /// Records every response; never yields a control request.
+    #[derive(Default)]
+    struct Collect(Vec<ServerResponse>);
+
+    #[async_trait]
+    impl RunStreamSender for Collect {
+        async fn send_response(
+            &mut self,
+            response: &ServerResponse,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.0.push(response.clone());
+            Ok(())
+        }
+    }
+
+    fn request() -> RunRequest {
+        serde_json::from_value(serde_json::json!({
+            "message": "hi",
+            "agent": "react"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn worker_lines_round_trip() {
+        let input = WorkerInput::Control {
+            request: ClientRequest::StopGeneration(StopGenerationRequest {
+                id: "s1".to_string(),
+                run_id: "run-1".to_string(),
+            }),
+        };
+        let line = serde_json::to_string(&input).unwrap();
+        assert!(line.starts_with(r#"{"type":"control","request":{"type":"stop_generation""#));
+        let WorkerInput::Control { request } = serde_json::from_str(&line).unwrap() else {
+            panic!("expected control");
+        };
+        assert!(matches!(
+            RunControl::from_request(request),
+            Some(RunControl::StopGeneration(r)) if r.run_id == "run-1"
+        ));
+        let cancel = RunControl::Cancel(CancelRunRequest {
+            id: "c1".to_string(),
+            run_id: "run-1".to_string(),
+        });
+        assert!(matches!(
+            cancel.into_request(),
+            ClientRequest::CancelRun(r) if r.id == "c1"
+        ));
+
+        let done = serde_json::to_string(&WorkerOutput::Done).unwrap();
+        assert_eq!(done, r#"{"type":"done"}"#);
+    }
+
+    #[test]
+    fn run_line_carries_the_server_run_config() {
+        let config = RunConfig {
+            read_only: true,
+            allowed_tools: Some(vec!["read".to_string()]),
+            llm_script: Some(serde_json::from_str(r#"{"responses":[{"content":"hi"}]}"#).unwrap()),
+            builder: Some(Arc::new(crate::app::BuilderDefaults {
+                model: Some("gpt-4o".to_string()),
+                api_key: Some(config::Secret::new("sk-test")),
+                ..Default::default()
+            })),
+            ..RunConfig::default()
+        };
+        let input = WorkerInput::Run {
+            run_id: "run-1".to_string(),
+            request: request(),
+            config: Box::new(config),
+        };
+        let line = serde_json::to_string(&input).unwrap();
+        let WorkerInput::Run { run_id, config, .. } = serde_json::from_str(&line).unwrap() else {
+            panic!("expected run");
+        };
+        assert_eq!(run_id, "run-1");
+        assert!(config.read_only);
+        assert_eq!(config.allowed_tools, Some(vec!["read".to_string()]));
+        assert_eq!(config.llm_script.unwrap().responses[0].content, "hi");
+        let builder = config.builder.unwrap();
+        assert_eq!(builder.model.as_deref(), Some("gpt-4o"));
+        assert_eq!(
+            builder.api_key.as_ref().map(|k| k.expose()),
+            Some("sk-test")
+        );
+    }
+
+    /// Pool of one worker running `script` with `sh`.
+    #[cfg(unix)]
+    fn script_pool(dir: &std::path::Path, script: &str, max_runs: u32) -> WorkerPool {
+        use std::os::unix::fs::PermissionsExt;
+        let program = dir.join("worker.sh");
+        std::fs::write(&program, format!("#!/bin/sh\n{}", script)).unwrap();
+        std::fs::set_permissions(&program, std::fs::Permissions::from_mode(0o755)).unwrap();
+        WorkerPool::new(WorkerConfig {
+            size: 1,
+            max_runs,
+            memory_mb: 0,
+            program,
+        })
+    }
+
+    /// Worker that answers every run with an error naming its pid.
+    #[cfg(unix)]
+    const ECHO_PID: &str = r#"while read line; do
+echo "{\"type\":\"response\",\"response\":{\"type\":\"error\",\"id\":\"run\",\"error\":\"pid $$\"}}"
+echo '{"type":"done"}'
+done
+"#;
+
+    #[cfg(unix)]
+    fn error_text(response: &ServerResponse) -> &str {
+        match response {
+            ServerResponse::Error(e) => &e.error,
+            other => panic!("expected error, got {:?}", other),
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn pool_forwards_responses_and_reuses_workers() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = script_pool(dir.path(), ECHO_PID, 0);
+        let mut sender = Collect::default();
+        pool.run(request(), "run-1", &RunConfig::default(), &mut sender)
+            .await
+            .unwrap();
+        pool.run(request(), "run-2", &RunConfig::default(), &mut sender)
+            .await
+            .unwrap();
+        assert_eq!(sender.0.len(), 2);
+        assert!(error_text(&sender.0[0]).starts_with("pid "));
+        assert_eq!(error_text(&sender.0[0]), error_text(&sender.0[1]));
+
+        let retiring = script_pool(dir.path(), ECHO_PID, 1);
+        let mut sender = Collect::default();
+        retiring
+            .run(request(), "run-1", &RunConfig::default(), &mut sender)
+            .await
+            .unwrap();
+        retiring
+            .run(request(), "run-2", &RunConfig::default(), &mut sender)
+            .await
+            .unwrap();
+        assert_ne!(error_text(&sender.0[0]), error_text(&sender.0[1]));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn retired_idle_workers_are_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = script_pool(dir.path(), ECHO_PID, 0);
+        let mut sender = Collect::default();
+        pool.run(request(), "run-1", &RunConfig::default(), &mut sender)
+            .await
+            .unwrap();
+        pool.retire_idle();
+        pool.run(request(), "run-2", &RunConfig::default(), &mut sender)
+            .await
+            .unwrap();
+        assert_ne!(error_text(&sender.0[0]), error_text(&sender.0[1]));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn pool_reports_a_worker_that_dies_mid_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = script_pool(dir.path(), "read line\nexit 3\n", 0);
+        let mut sender = Collect::default();
+        pool.run(request(), "run-1", &RunConfig::default(), &mut sender)
+            .await
+            .unwrap();
+        let [ServerResponse::Error(e)] = sender.0.as_slice() else {
+            panic!("expected one error, got {:?}", sender.0);
+        };
+        assert_eq!(e.id.as_deref(), Some("run-1"));
+        assert_eq!(e.code.as_deref(), Some(ERROR_CODE_WORKER_FAILED));
+        assert!(e.error.contains("worker exited"), "{}", e.error);
+    }
+
+    #[tokio::test]
+    async fn serve_worker_skips_controls_without_a_run_and_stops_at_end_of_input() {
+        let control = serde_json::to_string(&WorkerInput::Control {
+            request: ClientRequest::CancelRun(CancelRunRequest {
+                id: "c1".to_string(),
+                run_id: "run-1".to_string(),
+            }),
+        })
+        .unwrap();
+        let input = BufReader::new(std::io::Cursor::new(format!("{}\nnot json\n", control)));
+        let mut output = Vec::new();
+        serve_worker(input, &mut output, None, None).await.unwrap();
+        assert!(output.is_empty());
+    }
+}
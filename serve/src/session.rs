@@ -0,0 +1,374 @@
+//! Server-managed conversations for thin clients that just want "a chat".
+//!
+//! `session_start` opens a session on a new thread, `session_message` runs a message on it (the
+//! connection turns it into a `run` with the session's thread and settings), and `session_end`
+//! closes it, optionally titling the thread and compacting its history.
+//!
+//! Sessions live in memory and are shared by all connections, so a client can reconnect and
+//! continue. They are lost on restart; the thread keeps its checkpoints and can still be
+//! continued with `run` and the `thread_id` from `session_start`.
+
+use loom::compress::compaction::compact;
+use loom::{
+    CompactionConfig, ErrorResponse, LlmClient, Message, RunRequest, ServerResponse,
+    SessionEndRequest, SessionEndResponse, SessionMessageRequest, SessionStartRequest,
+    SessionStartResponse, ThreadSummary,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use uuid::Uuid;
+
+use crate::app::RunConfig;
+use crate::state_show::memory_db_path;
+
+/// Open sessions, by session id.
+#[derive(Clone, Default)]
+pub(crate) struct Sessions {
+    sessions: Arc<Mutex<HashMap<String, Session>>>,
+}
+
+/// One open session: its thread and the settings every message runs with.
+#[derive(Clone)]
+struct Session {
+    thread_id: String,
+    settings: SessionStartRequest,
+}
+
+impl Sessions {
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Session>> {
+        self.sessions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Opens a session on a new thread.
+    pub(crate) fn start(&self, r: SessionStartRequest) -> ServerResponse {
+        let session_id = format!("session-{}", Uuid::new_v4());
+        let thread_id = format!("thread-{}", Uuid::new_v4());
+        tracing::info!("💬 Session {} started on thread {}", session_id, thread_id);
+        let id = r.id.clone();
+        self.lock().insert(
+            session_id.clone(),
+            Session {
+                thread_id: thread_id.clone(),
+                settings: r,
+            },
+        );
+        ServerResponse::SessionStart(SessionStartResponse {
+            id,
+            session_id,
+            thread_id,
+        })
+    }
+
+    /// The `run` request for a session message; `Err` holds the error to send when the session
+    /// is unknown.
+    pub(crate) fn run_request(
+        &self,
+        r: SessionMessageRequest,
+    ) -> Result<RunRequest, ServerResponse> {
+        let Some(session) = self.lock().get(&r.session_id).cloned() else {
+            return Err(session_not_found(r.id, &r.session_id));
+        };
+        let settings = session.settings;
+        Ok(RunRequest {
+            id: Some(r.id),
+            message: r.message,
+            agent: settings.agent,
+            thread_id: Some(session.thread_id),
+            workspace_id: settings.workspace_id,
+            working_folder: settings.working_folder,
+            got_adaptive: None,
+            verbose: None,
+            model: settings.model,
+            reply_format: None,
+            reply_schema: None,
+            messages: None,
+            read_only: settings.read_only,
+            locale: settings.locale,
+            pin: None,
+            flags: Default::default(),
+        })
+    }
+
+    fn end(&self, session_id: &str) -> Option<Session> {
+        self.lock().remove(session_id)
+    }
+}
+
+fn session_not_found(id: String, session_id: &str) -> ServerResponse {
+    ServerResponse::Error(ErrorResponse {
+        id: Some(id),
+        error: format!("Session {} not found or already ended", session_id),
+        code: None,
+    })
+}
+
+/// Handles `session_end`: closes the session, then titles and compacts its thread when asked.
+/// Title and compaction failures are logged and leave `title` unset / `compacted` false; the
+/// session is closed either way.
+pub(crate) async fn handle_session_end(
+    r: SessionEndRequest,
+    sessions: &Sessions,
+    run_config: &RunConfig,
+    workspace_store: Option<Arc<loom_workspace::Store>>,
+    user_message_store: Option<Arc<dyn loom::UserMessageStore>>,
+) -> ServerResponse {
+    let Some(session) = sessions.end(&r.session_id) else {
+        return session_not_found(r.id, &r.session_id);
+    };
+    tracing::info!(
+        "👋 Session {} ended (thread {})",
+        r.session_id,
+        session.thread_id
+    );
+    let mut resp = SessionEndResponse {
+        id: r.id,
+        session_id: r.session_id,
+        thread_id: session.thread_id.clone(),
+        title: None,
+        summary: None,
+        compacted: false,
+    };
+    if !r.title && !r.compact {
+        return ServerResponse::SessionEnd(resp);
+    }
+    let llm = match session_llm(&session, run_config, workspace_store.as_ref()).await {
+        Ok(llm) => llm,
+        Err(e) => {
+            tracing::warn!(thread_id = %session.thread_id, "session end: no LLM: {}", e);
+            return ServerResponse::SessionEnd(resp);
+        }
+    };
+    let messages = match thread_messages(&session.thread_id, user_message_store.as_deref()).await {
+        Ok(messages) => messages,
+        Err(e) => {
+            tracing::warn!(thread_id = %session.thread_id, "session end: {}", e);
+            return ServerResponse::SessionEnd(resp);
+        }
+    };
+    if r.title {
+        if let Some(summary) = title_thread(&session.thread_id, &messages, llm.as_ref()).await {
+            if let Some(store) = &workspace_store {
+                if let Err(e) = store
+                    .set_thread_summary(&session.thread_id, &summary.title, &summary.summary)
+                    .await
+                {
+                    tracing::warn!("workspace set_thread_summary: {}", e);
+                }
+            }
+            resp.title = Some(summary.title);
+            resp.summary = Some(summary.summary);
+        }
+    }
+    if r.compact {
+        match compact_thread(&session.thread_id, llm.as_ref()).await {
+            Ok(compacted) => resp.compacted = compacted,
+            Err(e) => tracing::warn!(thread_id = %session.thread_id, "session compaction: {}", e),
+        }
+    }
+    ServerResponse::SessionEnd(resp)
+}
+
+/// LLM for titling and compaction: the session's model (else the workspace's, else the
+/// server default) with `SERVE_SUMMARY_MODEL` replacing the model when set.
+async fn session_llm(
+    session: &Session,
+    run_config: &RunConfig,
+    workspace_store: Option<&Arc<loom_workspace::Store>>,
+) -> Result<Box<dyn LlmClient>, String> {
+    let mut model = session.settings.model.clone();
+    if model.is_none() {
+        if let (Some(store), Some(ws_id)) = (workspace_store, &session.settings.workspace_id) {
+            model = store
+                .get_workspace_defaults(ws_id)
+                .await
+                .ok()
+                .and_then(|d| d.model);
+        }
+    }
+    let model = model.or_else(|| run_config.default_model.clone());
+    let resolved = loom::resolve_model_config(model.as_deref()).await;
+    let mut config = loom::ReactBuildConfig::from_env();
+    if let Some(m) = resolved.model {
+        config.model = Some(m);
+    }
+    if let Some(url) = resolved.base_url {
+        config.openai_base_url = Some(url);
+    }
+    if let Some(key) = resolved.api_key {
+        config.openai_api_key = Some(key.into());
+    }
+    if let Some(t) = resolved.provider_type {
+        config.llm_provider = Some(t);
+    }
+    loom::build_auxiliary_llm(&config, run_config.summary_model.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// Messages of the thread from the user message store, or from its latest checkpoint when the
+/// store has none (no store, or a thread from before the store); empty when neither has any.
+async fn thread_messages(
+    thread_id: &str,
+    user_message_store: Option<&dyn loom::UserMessageStore>,
+) -> Result<Vec<Message>, String> {
+    if let Some(store) = user_message_store {
+        let messages = store
+            .list(thread_id, None, Some(1000))
+            .await
+            .map_err(|e| e.to_string())?;
+        if !messages.is_empty() {
+            return Ok(messages);
+        }
+    }
+    let checkpoint = loom::runner_common::load_checkpoint_json(memory_db_path(), thread_id, None)
+        .await
+        .map_err(|e| e.to_string())?;
+    match checkpoint.and_then(|c| c.channel_values.get("messages").cloned()) {
+        Some(messages) => serde_json::from_value(messages).map_err(|e| e.to_string()),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Title and summary from the thread's first user message and last assistant reply.
+async fn title_thread(
+    thread_id: &str,
+    messages: &[Message],
+    llm: &dyn LlmClient,
+) -> Option<ThreadSummary> {
+    let user = messages.iter().find(|m| matches!(m, Message::User(_)))?;
+    let reply = messages
+        .iter()
+        .rev()
+        .find(|m| matches!(m, Message::Assistant(_)))?;
+    match loom::llm::generate_thread_summary(llm, &user.content(), &reply.content()).await {
+        Ok(summary) => Some(summary),
+        Err(e) => {
+            tracing::warn!(thread_id = %thread_id, "thread summary generation: {}", e);
+            None
+        }
+    }
+}
+
+/// Replaces the older messages of the thread's latest checkpoint with a summary (see
+/// [`compact`]) and saves the result as a new checkpoint. `false` when there was nothing to
+/// compact.
+async fn compact_thread(thread_id: &str, llm: &dyn LlmClient) -> Result<bool, String> {
+    let db_path = memory_db_path();
+    let Some(checkpoint) = loom::runner_common::load_checkpoint_json(&db_path, thread_id, None)
+        .await
+        .map_err(|e| e.to_string())?
+    else {
+        return Ok(false);
+    };
+    let Some(messages) = checkpoint.channel_values.get("messages").cloned() else {
+        return Ok(false);
+    };
+    let messages: Vec<Message> = serde_json::from_value(messages).map_err(|e| e.to_string())?;
+    let compacted = compact(&messages, llm, &CompactionConfig::default())
+        .await
+        .map_err(|e| e.to_string())?;
+    if compacted.len() >= messages.len() {
+        return Ok(false);
+    }
+    let mut state = checkpoint.channel_values.clone();
+    state["messages"] = serde_json::to_value(&compacted).map_err(|e| e.to_string())?;
+    loom::runner_common::put_thread_state_json(&db_path, thread_id, &checkpoint, state)
+        .await
+        .map_err(|e| e.to_string())?;
+    tracing::info!(
+        thread_id = %thread_id,
+        before = messages.len(),
+        after = compacted.len(),
+        "session thread compacted"
+    );
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use loom::{AgentIdentifier, AgentType, UserContent, UserMessageStore};
+
+    fn start_request() -> SessionStartRequest {
+        serde_json::from_value(serde_json::json!({
+            "id": "s1",
+            "model": "gpt-4o",
+            "workspace_id": "ws1",
+        }))
+        .unwrap()
+    }
+
+    fn started(sessions: &Sessions) -> SessionStartResponse {
+        match sessions.start(start_request()) {
+            ServerResponse::SessionStart(r) => r,
+            other => panic!("expected session_start, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn session_messages_run_on_the_session_thread() {
+        let sessions = Sessions::default();
+        let start = started(&sessions);
+        assert_eq!(start.id, "s1");
+        for id in ["m1", "m2"] {
+            let run = sessions
+                .run_request(SessionMessageRequest {
+                    id: id.to_string(),
+                    session_id: start.session_id.clone(),
+                    message: UserContent::Text("hi".to_string()),
+                })
+                .unwrap();
+            assert_eq!(run.id.as_deref(), Some(id));
+            assert_eq!(run.thread_id.as_deref(), Some(start.thread_id.as_str()));
+            assert_eq!(run.agent, AgentIdentifier::Type(AgentType::React));
+            assert_eq!(run.model.as_deref(), Some("gpt-4o"));
+            assert_eq!(run.workspace_id.as_deref(), Some("ws1"));
+        }
+    }
+
+    #[tokio::test]
+    async fn ended_session_takes_no_more_messages() {
+        let sessions = Sessions::default();
+        let start = started(&sessions);
+        let end = SessionEndRequest {
+            id: "e1".to_string(),
+            session_id: start.session_id.clone(),
+            compact: false,
+            title: false,
+        };
+        match handle_session_end(end.clone(), &sessions, &RunConfig::default(), None, None).await {
+            ServerResponse::SessionEnd(r) => {
+                assert_eq!(r.thread_id, start.thread_id);
+                assert!(!r.compacted);
+            }
+            other => panic!("expected session_end, got {:?}", other),
+        }
+        let err = sessions
+            .run_request(SessionMessageRequest {
+                id: "m1".to_string(),
+                session_id: start.session_id,
+                message: UserContent::Text("hi".to_string()),
+            })
+            .unwrap_err();
+        assert!(matches!(err, ServerResponse::Error(ref e) if e.id.as_deref() == Some("m1")));
+        assert!(matches!(
+            handle_session_end(end, &sessions, &RunConfig::default(), None, None).await,
+            ServerResponse::Error(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn thread_messages_come_from_the_user_message_store() {
+        let store = loom::InMemoryUserMessageStore::new();
+        store
+            .append("t1", &Message::user("plan the trip"))
+            .await
+            .unwrap();
+        store
+            .append("t1", &Message::assistant("Here is a plan."))
+            .await
+            .unwrap();
+        let messages = thread_messages("t1", Some(&store)).await.unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].content(), "Here is a plan.");
+    }
+}
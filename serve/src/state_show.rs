@@ -0,0 +1,87 @@
+//! Handle `StateShow` and `CheckpointList` requests: a thread's checkpointed state (latest or
+//! by checkpoint id) and its checkpoint history.
+
+use std::path::PathBuf;
+
+use loom::memory::CheckpointFilter;
+use loom::{
+    CheckpointListRequest, CheckpointListResponse, CheckpointSummary, ErrorResponse,
+    ServerResponse, StateShowRequest, StateShowResponse,
+};
+
+/// Memory DB used by runs: `LOOM_DB_PATH` when set, else the XDG default (same as `ReactBuildConfig`).
+pub(crate) fn memory_db_path() -> PathBuf {
+    std::env::var("LOOM_DB_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| loom::memory::default_memory_db_path())
+}
+
+/// Handles state_show request: loads the thread's latest checkpoint (or `checkpoint_id`) as JSON.
+/// A thread without checkpoints yields a response with `state: None`, not an error.
+pub(crate) async fn handle_state_show(r: StateShowRequest) -> ServerResponse {
+    if r.thread_id.is_empty() {
+        return ServerResponse::Error(ErrorResponse {
+            id: Some(r.id),
+            error: "thread_id is required".to_string(),
+            code: None,
+        });
+    }
+    let found = loom::runner_common::load_checkpoint_json(
+        memory_db_path(),
+        &r.thread_id,
+        r.checkpoint_id.as_deref(),
+    )
+    .await;
+    match found {
+        Ok(found) => {
+            let (checkpoint_id, state) = match found {
+                Some(checkpoint) => (Some(checkpoint.id), Some(checkpoint.channel_values)),
+                None => (None, None),
+            };
+            ServerResponse::StateShow(StateShowResponse {
+                id: r.id,
+                thread_id: r.thread_id,
+                checkpoint_id,
+                state,
+            })
+        }
+        Err(e) => ServerResponse::Error(ErrorResponse {
+            id: Some(r.id),
+            error: e.to_string(),
+            code: None,
+        }),
+    }
+}
+
+/// Handles checkpoint_list request: checkpoint metadata of the thread matching the filters.
+/// An unknown thread yields an empty list.
+pub(crate) async fn handle_checkpoint_list(r: CheckpointListRequest) -> ServerResponse {
+    if r.thread_id.is_empty() {
+        return ServerResponse::Error(ErrorResponse {
+            id: Some(r.id),
+            error: "thread_id is required".to_string(),
+            code: None,
+        });
+    }
+    let filter = CheckpointFilter {
+        node: r.node,
+        min_step: r.min_step,
+        max_step: r.max_step,
+        limit: r.limit,
+        ..Default::default()
+    };
+    match loom::runner_common::list_thread_checkpoints(memory_db_path(), &r.thread_id, &filter)
+        .await
+    {
+        Ok(items) => ServerResponse::CheckpointList(CheckpointListResponse {
+            id: r.id,
+            thread_id: r.thread_id,
+            checkpoints: items.iter().map(CheckpointSummary::from).collect(),
+        }),
+        Err(e) => ServerResponse::Error(ErrorResponse {
+            id: Some(r.id),
+            error: e.to_string(),
+            code: None,
+        }),
+    }
+}
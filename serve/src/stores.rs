@@ -0,0 +1,450 @@
+//! Workspace and user-message stores, and what the server does when one cannot be opened.
+//!
+//! `SERVE_STORE_DEGRADATION` picks the mode for a store whose database fails to open:
+//!
+//! - `fail_fast`: `loom serve` does not start.
+//! - `read_only` (default): the database is opened read-only, so listing still works and writes
+//!   fail; when that fails too the store is unavailable (as if not configured).
+//! - `in_memory`: an in-memory store stands in until the database can be opened again; what it
+//!   holds is dropped then.
+//!
+//! With `WORKSPACE_DB_KEY` (or loom's `LOOM_DB_KEY`) set, the workspace database is encrypted
+//! with SQLCipher; the user-message database uses `LOOM_DB_KEY` like loom's other stores. Both
+//! need the `sqlcipher` feature.
+//!
+//! A degraded store is reopened every `SERVE_STORE_RECONNECT_SECS` (default 30, `0` disables);
+//! connections pick up the reopened store on their next request. `GET /healthz` reports the
+//! state of both stores.
+
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tracing::{error, info, warn};
+
+const DEFAULT_RECONNECT_SECS: u64 = 30;
+
+/// What to do when a store's database fails to open.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DegradationMode {
+    FailFast,
+    ReadOnly,
+    InMemory,
+}
+
+impl DegradationMode {
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "fail_fast" => Some(Self::FailFast),
+            "read_only" => Some(Self::ReadOnly),
+            "in_memory" => Some(Self::InMemory),
+            _ => None,
+        }
+    }
+
+    /// Mode from `SERVE_STORE_DEGRADATION`; unset or unknown values mean `read_only`.
+    pub(crate) fn from_env() -> Self {
+        match std::env::var("SERVE_STORE_DEGRADATION") {
+            Ok(s) if !s.trim().is_empty() => Self::parse(&s).unwrap_or_else(|| {
+                warn!(
+                    "⚠️  Unknown SERVE_STORE_DEGRADATION '{}', using read_only",
+                    s
+                );
+                Self::ReadOnly
+            }),
+            _ => Self::ReadOnly,
+        }
+    }
+}
+
+/// How a store is currently backed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum StoreState {
+    Ok,
+    ReadOnly,
+    InMemory,
+    Unavailable,
+}
+
+impl StoreState {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::ReadOnly => "read_only",
+            Self::InMemory => "in_memory",
+            Self::Unavailable => "unavailable",
+        }
+    }
+}
+
+/// SQLCipher key of the workspace database: `WORKSPACE_DB_KEY`, else loom's
+/// [`loom::DB_KEY_ENV`] (each also from `<NAME>_FILE` or the keychain).
+fn workspace_db_key() -> Option<config::Secret> {
+    config::read_secret("WORKSPACE_DB_KEY").or_else(|| config::read_secret(loom::DB_KEY_ENV))
+}
+
+type Opener<T> = fn(&str) -> Result<Arc<T>, String>;
+
+/// How to open one kind of store: normally, read-only, and in memory.
+struct Openers<T: ?Sized> {
+    open: Opener<T>,
+    open_read_only: Opener<T>,
+    in_memory: fn() -> Result<Arc<T>, String>,
+}
+
+struct SlotInner<T: ?Sized> {
+    store: Option<Arc<T>>,
+    state: StoreState,
+    /// Why the database could not be opened, while degraded.
+    error: Option<String>,
+}
+
+/// One store and its state; swapped in place when a degraded store is reopened.
+pub(crate) struct StoreSlot<T: ?Sized> {
+    name: &'static str,
+    path: String,
+    open: Opener<T>,
+    inner: Arc<RwLock<SlotInner<T>>>,
+}
+
+impl<T: ?Sized> Clone for StoreSlot<T> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name,
+            path: self.path.clone(),
+            open: self.open,
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: ?Sized> StoreSlot<T> {
+    fn open(name: &'static str, path: String, mode: DegradationMode, openers: Openers<T>) -> Self {
+        let (store, state, error) = match (openers.open)(&path) {
+            Ok(store) => {
+                info!("✓ {} store initialized (db: {})", name, path);
+                (Some(store), StoreState::Ok, None)
+            }
+            Err(e) => {
+                warn!("⚠️  Failed to open {} store (db: {}): {}", name, path, e);
+                let fallback = match mode {
+                    DegradationMode::FailFast => None,
+                    DegradationMode::ReadOnly => (openers.open_read_only)(&path)
+                        .inspect_err(|e| warn!("⚠️  Read-only {} store failed too: {}", name, e))
+                        .ok()
+                        .map(|s| (s, StoreState::ReadOnly)),
+                    DegradationMode::InMemory => (openers.in_memory)()
+                        .inspect_err(|e| warn!("⚠️  In-memory {} store failed: {}", name, e))
+                        .ok()
+                        .map(|s| (s, StoreState::InMemory)),
+                };
+                match fallback {
+                    Some((store, state)) => {
+                        warn!("⚠️  {} store degraded to {}", name, state.as_str());
+                        (Some(store), state, Some(e))
+                    }
+                    None => (None, StoreState::Unavailable, Some(e)),
+                }
+            }
+        };
+        Self {
+            name,
+            path,
+            open: openers.open,
+            inner: Arc::new(RwLock::new(SlotInner {
+                store,
+                state,
+                error,
+            })),
+        }
+    }
+
+    /// The store to use for the next request, if any.
+    pub(crate) fn current(&self) -> Option<Arc<T>> {
+        self.inner
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .store
+            .clone()
+    }
+
+    pub(crate) fn state(&self) -> StoreState {
+        self.inner.read().unwrap_or_else(|e| e.into_inner()).state
+    }
+
+    fn error(&self) -> Option<String> {
+        self.inner
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .error
+            .clone()
+    }
+
+    /// A healthy slot holding `store`, with no database behind it.
+    #[cfg(feature = "test-util")]
+    fn fixed(name: &'static str, store: Arc<T>) -> Self {
+        Self {
+            name,
+            path: ":memory:".to_string(),
+            open: |_| Err("in-memory store has no database".to_string()),
+            inner: Arc::new(RwLock::new(SlotInner {
+                store: Some(store),
+                state: StoreState::Ok,
+                error: None,
+            })),
+        }
+    }
+
+    /// Tries to open the database of a degraded store; on success the store is swapped in.
+    fn try_reconnect(&self) {
+        if self.state() == StoreState::Ok {
+            return;
+        }
+        match (self.open)(&self.path) {
+            Ok(store) => {
+                info!("✓ {} store reconnected (db: {})", self.name, self.path);
+                *self.inner.write().unwrap_or_else(|e| e.into_inner()) = SlotInner {
+                    store: Some(store),
+                    state: StoreState::Ok,
+                    error: None,
+                };
+            }
+            Err(e) => {
+                tracing::debug!("{} store still unavailable: {}", self.name, e);
+                self.inner.write().unwrap_or_else(|e| e.into_inner()).error = Some(e);
+            }
+        }
+    }
+
+    fn health(&self) -> Value {
+        let mut status = json!({
+            "state": self.state().as_str(),
+            "path": self.path,
+        });
+        if let Some(error) = self.error() {
+            status["error"] = json!(error);
+        }
+        status
+    }
+}
+
+/// The server's workspace and user-message stores.
+#[derive(Clone)]
+pub(crate) struct Stores {
+    /// Run requests with workspace_id + thread_id register the thread here; usage and thread
+    /// summaries are recorded here.
+    pub(crate) workspace: StoreSlot<loom_workspace::Store>,
+    /// User and assistant messages are appended here per thread.
+    pub(crate) user_messages: StoreSlot<dyn loom::UserMessageStore>,
+}
+
+impl Stores {
+    /// Opens both stores from `WORKSPACE_DB` (default `workspace.db`) and `USER_MESSAGE_DB`
+    /// (default `serve.db`), degrading a store that fails to open as `mode` says.
+    pub(crate) fn open(mode: DegradationMode) -> Self {
+        let path = |name: &str, default: &str| {
+            std::env::var(name)
+                .ok()
+                .filter(|s| !s.trim().is_empty())
+                .unwrap_or_else(|| default.to_string())
+        };
+        let workspace = StoreSlot::open(
+            "Workspace",
+            path("WORKSPACE_DB", "workspace.db"),
+            mode,
+            Openers {
+                open: |p| {
+                    match workspace_db_key() {
+                        Some(key) => loom_workspace::Store::open_encrypted(p, key.expose()),
+                        None => loom_workspace::Store::new(p),
+                    }
+                    .map(Arc::new)
+                    .map_err(|e| e.to_string())
+                },
+                open_read_only: |p| {
+                    match workspace_db_key() {
+                        Some(key) => {
+                            loom_workspace::Store::open_read_only_encrypted(p, key.expose())
+                        }
+                        None => loom_workspace::Store::open_read_only(p),
+                    }
+                    .map(Arc::new)
+                    .map_err(|e| e.to_string())
+                },
+                in_memory: || {
+                    loom_workspace::Store::in_memory()
+                        .map(Arc::new)
+                        .map_err(|e| e.to_string())
+                },
+            },
+        );
+        let user_messages = StoreSlot::open(
+            "User message",
+            path("USER_MESSAGE_DB", "serve.db"),
+            mode,
+            Openers {
+                open: |p| {
+                    loom::SqliteUserMessageStore::new(p)
+                        .map(|s| Arc::new(s) as Arc<dyn loom::UserMessageStore>)
+                        .map_err(|e| e.to_string())
+                },
+                open_read_only: |p| {
+                    loom::SqliteUserMessageStore::open_read_only(p)
+                        .map(|s| Arc::new(s) as Arc<dyn loom::UserMessageStore>)
+                        .map_err(|e| e.to_string())
+                },
+                in_memory: || {
+                    Ok(Arc::new(loom::InMemoryUserMessageStore::new())
+                        as Arc<dyn loom::UserMessageStore>)
+                },
+            },
+        );
+        Self {
+            workspace,
+            user_messages,
+        }
+    }
+
+    /// Both stores in memory, ignoring `WORKSPACE_DB` / `USER_MESSAGE_DB`; what they hold is
+    /// dropped with the server. Used by the `test-util` harness.
+    #[cfg(feature = "test-util")]
+    pub(crate) fn in_memory() -> Result<Self, String> {
+        let workspace = loom_workspace::Store::in_memory().map_err(|e| e.to_string())?;
+        Ok(Self {
+            workspace: StoreSlot::fixed("Workspace", Arc::new(workspace)),
+            user_messages: StoreSlot::fixed(
+                "User message",
+                Arc::new(loom::InMemoryUserMessageStore::new()) as Arc<dyn loom::UserMessageStore>,
+            ),
+        })
+    }
+
+    /// First store that failed to open, as `"<name> store: <error>"`.
+    pub(crate) fn failure(&self) -> Option<String> {
+        [
+            (
+                self.workspace.name,
+                self.workspace.state(),
+                self.workspace.error(),
+            ),
+            (
+                self.user_messages.name,
+                self.user_messages.state(),
+                self.user_messages.error(),
+            ),
+        ]
+        .into_iter()
+        .find(|(_, state, _)| *state != StoreState::Ok)
+        .map(|(name, _, error)| format!("{} store: {}", name, error.unwrap_or_default()))
+    }
+
+    fn degraded(&self) -> bool {
+        self.workspace.state() != StoreState::Ok || self.user_messages.state() != StoreState::Ok
+    }
+
+    /// Retries degraded stores every `SERVE_STORE_RECONNECT_SECS` in the background. Does
+    /// nothing when both stores are fine, the interval is `0`, or there is no Tokio runtime.
+    pub(crate) fn spawn_reconnect(&self) {
+        let secs = std::env::var("SERVE_STORE_RECONNECT_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .unwrap_or(DEFAULT_RECONNECT_SECS);
+        if secs == 0 || !self.degraded() {
+            return;
+        }
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            error!("❌ No Tokio runtime; degraded stores will not be reopened");
+            return;
+        };
+        info!("  Reopening degraded stores every {}s", secs);
+        let stores = self.clone();
+        handle.spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(secs));
+            interval.tick().await;
+            while stores.degraded() {
+                interval.tick().await;
+                let s = stores.clone();
+                let _ = tokio::task::spawn_blocking(move || {
+                    s.workspace.try_reconnect();
+                    s.user_messages.try_reconnect();
+                })
+                .await;
+            }
+        });
+    }
+
+    /// `GET /healthz` body: `status` is `ok` or `degraded`, plus each store's state.
+    pub(crate) fn health(&self) -> Value {
+        json!({
+            "status": if self.degraded() { "degraded" } else { "ok" },
+            "stores": {
+                "workspace": self.workspace.health(),
+                "user_messages": self.user_messages.health(),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_ok(path: &str) -> Result<Arc<str>, String> {
+        Ok(Arc::from(path))
+    }
+
+    fn open_failing(_: &str) -> Result<Arc<str>, String> {
+        Err("disk on fire".to_string())
+    }
+
+    fn openers(fail: bool) -> Openers<str> {
+        Openers {
+            open: if fail { open_failing } else { open_ok },
+            open_read_only: |_| Err("no file".to_string()),
+            in_memory: || Ok(Arc::from("memory")),
+        }
+    }
+
+    #[test]
+    fn mode_parses_both_spellings() {
+        assert_eq!(
+            DegradationMode::parse("fail-fast"),
+            Some(DegradationMode::FailFast)
+        );
+        assert_eq!(
+            DegradationMode::parse("In_Memory"),
+            Some(DegradationMode::InMemory)
+        );
+        assert_eq!(DegradationMode::parse("whatever"), None);
+    }
+
+    #[test]
+    fn failed_store_degrades_by_mode() {
+        let slot = StoreSlot::open("t", "db".into(), DegradationMode::InMemory, openers(true));
+        assert_eq!(slot.state(), StoreState::InMemory);
+        assert_eq!(slot.current().as_deref(), Some("memory"));
+        assert_eq!(slot.error().as_deref(), Some("disk on fire"));
+
+        let slot = StoreSlot::open("t", "db".into(), DegradationMode::ReadOnly, openers(true));
+        assert_eq!(slot.state(), StoreState::Unavailable);
+        assert!(slot.current().is_none());
+
+        let slot = StoreSlot::open("t", "db".into(), DegradationMode::FailFast, openers(false));
+        assert_eq!(slot.state(), StoreState::Ok);
+        assert_eq!(slot.current().as_deref(), Some("db"));
+    }
+
+    #[test]
+    fn reconnect_swaps_in_the_reopened_store() {
+        let mut slot = StoreSlot::open("t", "db".into(), DegradationMode::InMemory, openers(true));
+        slot.try_reconnect();
+        assert_eq!(slot.state(), StoreState::InMemory);
+
+        slot.open = open_ok;
+        let held = slot.clone();
+        slot.try_reconnect();
+        assert_eq!(held.state(), StoreState::Ok);
+        assert_eq!(held.current().as_deref(), Some("db"));
+        assert!(held.health().get("error").is_none());
+    }
+}
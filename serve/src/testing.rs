@@ -0,0 +1,243 @@
+//! End-to-end test harness for apps built on loom (feature `test-util`).
+//!
+//! [`TestServer`] runs this server in-process on an ephemeral port, with in-memory stores and
+//! a scripted [`MockLlm`](loom::MockLlm) answering every LLM call, so no API key or database is
+//! needed. [`TestServer::run`] sends one ReAct run over a real WebSocket connection and returns
+//! its [`RunTrace`]: the streamed events and the final `run_end`, with assertion helpers.
+//!
+//! ```ignore
+//! use loom::MockScript;
+//! use serve::testing::TestServer;
+//!
+//! let server = TestServer::start(
+//!     MockScript::default()
+//!         .with_tool_call("ls", serde_json::json!({ "path": "." }))
+//!         .with_reply("done"),
+//! )
+//! .await?;
+//! server
+//!     .run("list the files")
+//!     .await?
+//!     .assert_tool_called("ls")
+//!     .assert_reply_contains("done");
+//! ```
+//!
+//! Everything else (tools, prompts, limits) comes from the environment as for
+//! [`run_serve`](crate::run_serve). Runs always execute in the server process: `SERVE_WORKERS`
+//! is ignored, since worker processes would not see the script.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use loom::client::{ClientError, WsClient};
+use loom::protocol::AgentIdentifier;
+use loom::{
+    AgentType, MockScript, ProtocolEvent, ProtocolEventEnvelope, RunEndResponse, RunRequest,
+    UserContent,
+};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+use crate::app::{router, run_config_from_env, AppState, RunConfig, SharedRunConfig};
+use crate::{access_log, hooks, run, session, stores};
+
+/// In-process server on `127.0.0.1:<ephemeral port>`; stopped when dropped.
+pub struct TestServer {
+    addr: SocketAddr,
+    handle: JoinHandle<()>,
+}
+
+impl TestServer {
+    /// Starts a server whose runs get their LLM replies from `script`, in order (see
+    /// [`MockScript`]; once the script runs out the mock echoes the user message). Each run
+    /// starts from the top of the script.
+    pub async fn start(script: MockScript) -> std::io::Result<Self> {
+        Self::start_with_hooks(script, loom::RunHooks::default()).await
+    }
+
+    /// [`TestServer::start`] with run hooks, as registered with [`crate::router_with_hooks`].
+    pub async fn start_with_hooks(
+        script: MockScript,
+        hooks: loom::RunHooks<RunEndResponse>,
+    ) -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let stores = stores::Stores::in_memory().map_err(std::io::Error::other)?;
+        let state = Arc::new(AppState {
+            shutdown_tx: Arc::new(std::sync::Mutex::new(None)),
+            stores,
+            run_config: SharedRunConfig::new(RunConfig {
+                llm_script: Some(script),
+                run_hooks: hooks,
+                ..run_config_from_env()
+            }),
+            providers: Arc::new(Vec::new()),
+            model_catalog: None,
+            access_log: Arc::new(access_log::AccessLog::default()),
+            detached_runs: run::DetachedRuns::from_env(),
+            worker_pool: run::WorkerPool::default(),
+            active_runs: run::ActiveRuns::default(),
+            sessions: session::Sessions::default(),
+            hooks: Arc::new(hooks::HookConfig::from_env()),
+        });
+        let app = router(state);
+        let handle = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+        Ok(Self { addr, handle })
+    }
+
+    /// WebSocket URL of the server (`ws://127.0.0.1:<port>`).
+    pub fn url(&self) -> String {
+        format!("ws://{}", self.addr)
+    }
+
+    /// Opens a new client connection, for requests other than a plain run.
+    pub async fn client(&self) -> Result<WsClient, ClientError> {
+        WsClient::connect(&self.url()).await
+    }
+
+    /// Runs the ReAct agent on `message` over a new connection and collects the whole run.
+    pub async fn run(&self, message: impl Into<String>) -> Result<RunTrace, ClientError> {
+        self.run_request(RunRequest {
+            id: None,
+            message: UserContent::text(message.into()),
+            agent: AgentIdentifier::Type(AgentType::React),
+            thread_id: None,
+            workspace_id: None,
+            working_folder: None,
+            got_adaptive: None,
+            verbose: None,
+            model: None,
+            reply_format: None,
+            reply_schema: None,
+            messages: None,
+            read_only: None,
+            locale: None,
+            pin: None,
+            flags: Default::default(),
+        })
+        .await
+    }
+
+    /// Sends `request` over a new connection and collects the whole run.
+    pub async fn run_request(&self, request: RunRequest) -> Result<RunTrace, ClientError> {
+        let mut client = self.client().await?;
+        let mut stream = client.run(request).await?;
+        let mut events = Vec::new();
+        while let Some(event) = stream.next_event().await? {
+            events.push(event);
+        }
+        let end = stream.finish().await?;
+        client.close().await?;
+        Ok(RunTrace { events, end })
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// Events and final response of one run. The `assert_*` methods panic on a mismatch and
+/// return `self`, so they chain.
+#[derive(Clone, Debug)]
+pub struct RunTrace {
+    pub events: Vec<ProtocolEventEnvelope>,
+    pub end: RunEndResponse,
+}
+
+impl RunTrace {
+    /// Final reply of the run.
+    pub fn reply(&self) -> &str {
+        &self.end.reply
+    }
+
+    /// Names of the nodes entered, in order (e.g. `["think", "act", "observe", "think"]`).
+    pub fn nodes(&self) -> Vec<&str> {
+        self.events
+            .iter()
+            .filter_map(|e| match &e.event {
+                ProtocolEvent::NodeEnter { id } => Some(id.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Names of the tools the model called, in order.
+    pub fn tool_calls(&self) -> Vec<&str> {
+        self.events
+            .iter()
+            .filter_map(|e| match &e.event {
+                ProtocolEvent::ToolCall { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Wire `type` of each event, in order (e.g. `node_enter`, `message_chunk`).
+    pub fn event_types(&self) -> Vec<String> {
+        self.events
+            .iter()
+            .filter_map(|e| e.to_value().ok())
+            .filter_map(|v| v.get("type").and_then(|t| t.as_str()).map(str::to_string))
+            .collect()
+    }
+
+    /// Asserts the final reply contains `text`.
+    pub fn assert_reply_contains(&self, text: &str) -> &Self {
+        assert!(
+            self.reply().contains(text),
+            "reply {:?} does not contain {:?}",
+            self.reply(),
+            text
+        );
+        self
+    }
+
+    /// Asserts the nodes entered are exactly `expected`, in order.
+    pub fn assert_nodes(&self, expected: &[&str]) -> &Self {
+        assert_eq!(self.nodes(), expected, "nodes entered");
+        self
+    }
+
+    /// Asserts tool `name` was called at least once.
+    pub fn assert_tool_called(&self, name: &str) -> &Self {
+        assert!(
+            self.tool_calls().contains(&name),
+            "tool {:?} not called; calls: {:?}",
+            name,
+            self.tool_calls()
+        );
+        self
+    }
+
+    /// Asserts at least one event has wire type `event_type` (e.g. `usage`).
+    pub fn assert_event(&self, event_type: &str) -> &Self {
+        let types = self.event_types();
+        assert!(
+            types.iter().any(|t| t == event_type),
+            "no {:?} event; events: {:?}",
+            event_type,
+            types
+        );
+        self
+    }
+
+    /// Asserts event ids strictly increase and no event was dropped (`prev_event_id` matches
+    /// the previous event's id).
+    pub fn assert_events_in_order(&self) -> &Self {
+        let mut last: Option<u64> = None;
+        for (i, event) in self.events.iter().enumerate() {
+            if let (Some(prev), Some(id)) = (last, event.event_id) {
+                assert!(id > prev, "event {} has id {} after {}", i, id, prev);
+                if let Some(expected) = event.prev_event_id {
+                    assert_eq!(expected, prev, "event {} reports a dropped event", i);
+                }
+            }
+            last = event.event_id.or(last);
+        }
+        self
+    }
+}
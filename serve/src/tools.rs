@@ -20,6 +20,7 @@ pub(crate) async fn handle_tools_list(
         cancellation: None,
         thread_id: r.thread_id.clone(),
         agent: None,
+        agent_file: None,
         verbose: false,
         got_adaptive: false,
         display_max_len: run_config.display_max_len,
@@ -28,10 +29,19 @@ pub(crate) async fn handle_tools_list(
         mcp_config_path: None,
         output_timestamp: false,
         dry_run: false,
+        role_setting: None,
+        allowed_tools: run_config.allowed_tools.clone(),
+        read_only: run_config.read_only,
+        reply_format: None,
+        reply_schema: None,
+        messages: None,
+        history_search: None,
+        locale: None,
         provider: None,
         base_url: None,
         api_key: None,
         provider_type: None,
+        flags: Default::default(),
     };
     let (_helve, config, _resolved_agent) = build_helve_config(&opts);
     match build_react_run_context(&config).await {
@@ -40,11 +50,13 @@ pub(crate) async fn handle_tools_list(
             Err(e) => ServerResponse::Error(ErrorResponse {
                 id: Some(id),
                 error: e.to_string(),
+                code: None,
             }),
         },
         Err(e) => ServerResponse::Error(ErrorResponse {
             id: Some(id),
             error: e.to_string(),
+            code: None,
         }),
     }
 }
@@ -61,6 +73,7 @@ pub(crate) async fn handle_tool_show(
         cancellation: None,
         thread_id: r.thread_id.clone(),
         agent: None,
+        agent_file: None,
         verbose: false,
         got_adaptive: false,
         display_max_len: run_config.display_max_len,
@@ -69,10 +82,19 @@ pub(crate) async fn handle_tool_show(
         mcp_config_path: None,
         output_timestamp: false,
         dry_run: false,
+        role_setting: None,
+        allowed_tools: run_config.allowed_tools.clone(),
+        read_only: run_config.read_only,
+        reply_format: None,
+        reply_schema: None,
+        messages: None,
+        history_search: None,
+        locale: None,
         provider: None,
         base_url: None,
         api_key: None,
         provider_type: None,
+        flags: Default::default(),
     };
     let (_helve, config, _resolved_agent) = build_helve_config(&opts);
     match build_react_run_context(&config).await {
@@ -111,17 +133,20 @@ pub(crate) async fn handle_tool_show(
                     None => ServerResponse::Error(ErrorResponse {
                         id: Some(id),
                         error: format!("tool not found: {}", r.name),
+                        code: None,
                     }),
                 }
             }
             Err(e) => ServerResponse::Error(ErrorResponse {
                 id: Some(id),
                 error: e.to_string(),
+                code: None,
             }),
         },
         Err(e) => ServerResponse::Error(ErrorResponse {
             id: Some(id),
             error: e.to_string(),
+            code: None,
         }),
     }
 }
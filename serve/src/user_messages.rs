@@ -1,8 +1,13 @@
-//! Handle `UserMessages` request: list stored messages for a thread.
+//! Handle `UserMessages` and `UserMessagesDelete` requests: list, search and delete stored
+//! messages for a thread.
 
-use loom::{Message, UserMessageItem, UserMessagesResponse};
+use loom::{
+    StoredMessage, UserMessageItem, UserMessagesDeleteRequest, UserMessagesDeleteResponse,
+    UserMessagesResponse,
+};
 
-/// Handles user_messages request: lists messages from the store for the given thread.
+/// Handles user_messages request: lists messages from the store for the given thread, or
+/// searches them when `query` is set.
 /// When store is None or NoOp, returns empty messages and has_more: false (no error).
 /// When thread_id is missing (empty), returns an error response.
 pub(crate) async fn handle_user_messages(
@@ -10,10 +15,7 @@ pub(crate) async fn handle_user_messages(
     user_message_store: Option<std::sync::Arc<dyn loom::UserMessageStore>>,
 ) -> loom::ServerResponse {
     if r.thread_id.is_empty() {
-        return loom::ServerResponse::Error(loom::ErrorResponse {
-            id: Some(r.id.clone()),
-            error: "thread_id is required".to_string(),
-        });
+        return thread_id_required(r.id);
     }
     let Some(store) = user_message_store else {
         return loom::ServerResponse::UserMessages(UserMessagesResponse {
@@ -23,28 +25,83 @@ pub(crate) async fn handle_user_messages(
             has_more: Some(false),
         });
     };
-    match store.list(&r.thread_id, r.before, r.limit).await {
-        Ok(messages) => {
-            let items: Vec<UserMessageItem> =
-                messages.into_iter().map(|m| message_to_item(&m)).collect();
+    let limit = r.limit.unwrap_or(100).min(1000);
+    let records = match r.query.as_deref() {
+        Some(query) => {
+            store
+                .search(query, Some(std::slice::from_ref(&r.thread_id)), limit)
+                .await
+        }
+        None => {
+            store
+                .list_records(&r.thread_id, r.before, Some(limit))
+                .await
+        }
+    };
+    match records {
+        Ok(records) => {
+            let has_more = records.len() == limit as usize;
+            let items: Vec<UserMessageItem> = records.iter().map(record_to_item).collect();
             loom::ServerResponse::UserMessages(UserMessagesResponse {
                 id: r.id.clone(),
                 thread_id: r.thread_id.clone(),
                 messages: items,
-                has_more: Some(false),
+                has_more: Some(has_more),
             })
         }
         Err(e) => loom::ServerResponse::Error(loom::ErrorResponse {
             id: Some(r.id.clone()),
             error: e.to_string(),
+            code: None,
         }),
     }
 }
 
-fn message_to_item(m: &Message) -> UserMessageItem {
-    let (role, content) = m.to_role_content_pair();
+/// Handles user_messages_delete request: deletes all stored messages of the thread.
+/// When store is None, nothing is deleted (no error).
+pub(crate) async fn handle_user_messages_delete(
+    r: UserMessagesDeleteRequest,
+    user_message_store: Option<std::sync::Arc<dyn loom::UserMessageStore>>,
+) -> loom::ServerResponse {
+    if r.thread_id.is_empty() {
+        return thread_id_required(r.id);
+    }
+    let deleted = match user_message_store {
+        Some(store) => store.delete(&r.thread_id).await,
+        None => Ok(0),
+    };
+    match deleted {
+        Ok(deleted) => loom::ServerResponse::UserMessagesDelete(UserMessagesDeleteResponse {
+            id: r.id,
+            thread_id: r.thread_id,
+            deleted,
+        }),
+        Err(e) => loom::ServerResponse::Error(loom::ErrorResponse {
+            id: Some(r.id),
+            error: e.to_string(),
+            code: None,
+        }),
+    }
+}
+
+fn thread_id_required(id: String) -> loom::ServerResponse {
+    loom::ServerResponse::Error(loom::ErrorResponse {
+        id: Some(id),
+        error: "thread_id is required".to_string(),
+        code: None,
+    })
+}
+
+fn record_to_item(record: &StoredMessage) -> UserMessageItem {
+    let (role, content) = record.message.to_role_content_pair();
     UserMessageItem {
         role: role.to_string(),
         content,
+        id: Some(record.id),
+        run_id: record.meta.run_id.clone(),
+        usage: record.meta.usage.clone(),
+        tool_name: record.meta.tool_name.clone(),
+        tool_call_id: record.meta.tool_call_id.clone(),
+        created_at_ms: record.created_at_ms,
     }
 }
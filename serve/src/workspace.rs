@@ -2,17 +2,39 @@
 
 use std::sync::Arc;
 
+use loom::model_spec::{price_usage_rows, ModelsDevResolver};
 use loom::{
-    ErrorResponse, ServerResponse, ThreadInWorkspace, WorkspaceCreateRequest,
-    WorkspaceCreateResponse, WorkspaceListRequest, WorkspaceListResponse, WorkspaceMeta,
-    WorkspaceThreadAddRequest, WorkspaceThreadAddResponse, WorkspaceThreadListRequest,
-    WorkspaceThreadListResponse, WorkspaceThreadRemoveRequest, WorkspaceThreadRemoveResponse,
+    ErrorResponse, ServerResponse, ThreadInWorkspace, UsageReportRequest, UsageReportResponse,
+    UsageReportRow, WorkspaceCreateRequest, WorkspaceCreateResponse, WorkspaceDefaults,
+    WorkspaceListRequest, WorkspaceListResponse, WorkspaceMeta, WorkspaceThreadAddRequest,
+    WorkspaceThreadAddResponse, WorkspaceThreadListRequest, WorkspaceThreadListResponse,
+    WorkspaceThreadRemoveRequest, WorkspaceThreadRemoveResponse, WorkspaceUpdateRequest,
+    WorkspaceUpdateResponse,
 };
 
+pub(crate) fn defaults_to_protocol(d: loom_workspace::WorkspaceDefaults) -> WorkspaceDefaults {
+    WorkspaceDefaults {
+        model: d.model,
+        working_folder: d.working_folder,
+        role: d.role,
+        tools: d.tools,
+    }
+}
+
+fn defaults_from_protocol(d: WorkspaceDefaults) -> loom_workspace::WorkspaceDefaults {
+    loom_workspace::WorkspaceDefaults {
+        model: d.model,
+        working_folder: d.working_folder,
+        role: d.role,
+        tools: d.tools,
+    }
+}
+
 fn no_store_error(id: &str) -> ServerResponse {
     ServerResponse::Error(ErrorResponse {
         id: Some(id.to_string()),
         error: "workspace store not configured (set WORKSPACE_DB)".to_string(),
+        code: None,
     })
 }
 
@@ -32,6 +54,7 @@ pub(crate) async fn handle_workspace_list(
                     id: w.id,
                     name: w.name,
                     created_at_ms: w.created_at_ms,
+                    defaults: defaults_to_protocol(w.defaults),
                 })
                 .collect();
             ServerResponse::WorkspaceList(WorkspaceListResponse { id, workspaces })
@@ -39,6 +62,7 @@ pub(crate) async fn handle_workspace_list(
         Err(e) => ServerResponse::Error(ErrorResponse {
             id: Some(id),
             error: e.to_string(),
+            code: None,
         }),
     }
 }
@@ -58,6 +82,7 @@ pub(crate) async fn handle_workspace_create(
         Err(e) => ServerResponse::Error(ErrorResponse {
             id: Some(id),
             error: e.to_string(),
+            code: None,
         }),
     }
 }
@@ -78,6 +103,8 @@ pub(crate) async fn handle_workspace_thread_list(
                 .map(|t| ThreadInWorkspace {
                     thread_id: t.thread_id,
                     created_at_ms: t.created_at_ms,
+                    title: t.title,
+                    summary: t.summary,
                 })
                 .collect();
             ServerResponse::WorkspaceThreadList(WorkspaceThreadListResponse {
@@ -89,6 +116,7 @@ pub(crate) async fn handle_workspace_thread_list(
         Err(e) => ServerResponse::Error(ErrorResponse {
             id: Some(id),
             error: e.to_string(),
+            code: None,
         }),
     }
 }
@@ -115,6 +143,7 @@ pub(crate) async fn handle_workspace_thread_add(
         Err(e) => ServerResponse::Error(ErrorResponse {
             id: Some(id),
             error: e.to_string(),
+            code: None,
         }),
     }
 }
@@ -141,6 +170,75 @@ pub(crate) async fn handle_workspace_thread_remove(
         Err(e) => ServerResponse::Error(ErrorResponse {
             id: Some(id),
             error: e.to_string(),
+            code: None,
+        }),
+    }
+}
+
+pub(crate) async fn handle_workspace_update(
+    r: WorkspaceUpdateRequest,
+    store: Option<Arc<loom_workspace::Store>>,
+) -> ServerResponse {
+    let id = r.id.clone();
+    let workspace_id = r.workspace_id.clone();
+    let Some(store) = store else {
+        return no_store_error(&id);
+    };
+    let defaults = defaults_from_protocol(r.defaults);
+    match store
+        .set_workspace_defaults(&r.workspace_id, &defaults)
+        .await
+    {
+        Ok(()) => ServerResponse::WorkspaceUpdate(WorkspaceUpdateResponse {
+            id,
+            workspace_id,
+            defaults: defaults_to_protocol(defaults),
+        }),
+        Err(e) => ServerResponse::Error(ErrorResponse {
+            id: Some(id),
+            error: e.to_string(),
+            code: None,
+        }),
+    }
+}
+
+/// Sums recorded run usage by model and agent type and prices it from models.dev.
+pub(crate) async fn handle_usage_report(
+    r: UsageReportRequest,
+    store: Option<Arc<loom_workspace::Store>>,
+) -> ServerResponse {
+    let id = r.id.clone();
+    let Some(store) = store else {
+        return no_store_error(&id);
+    };
+    match store
+        .usage_report(r.workspace_id.as_deref(), r.since, r.until)
+        .await
+    {
+        Ok(summaries) => {
+            let mut rows: Vec<UsageReportRow> = summaries
+                .into_iter()
+                .map(|s| UsageReportRow {
+                    model: s.model,
+                    agent: s.agent,
+                    runs: s.runs,
+                    prompt_tokens: s.prompt_tokens,
+                    completion_tokens: s.completion_tokens,
+                    total_tokens: s.total_tokens,
+                    cost_usd: None,
+                })
+                .collect();
+            price_usage_rows(&ModelsDevResolver::new(), &mut rows).await;
+            ServerResponse::UsageReport(UsageReportResponse {
+                id,
+                workspace_id: r.workspace_id,
+                rows,
+            })
+        }
+        Err(e) => ServerResponse::Error(ErrorResponse {
+            id: Some(id),
+            error: e.to_string(),
+            code: None,
         }),
     }
 }
@@ -0,0 +1,36 @@
+use super::common;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+#[tokio::test]
+async fn e2e_healthz_reports_store_states() {
+    common::load_dotenv();
+    let (url, server_handle) = common::spawn_server_once().await;
+    let addr = url.trim_start_matches("ws://");
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(
+            format!(
+                "GET /healthz HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+                addr
+            )
+            .as_bytes(),
+        )
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    let body = response.split("\r\n\r\n").nth(1).unwrap_or_default();
+    let health: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert_eq!(health["stores"]["workspace"]["state"], "ok");
+    assert!(health["stores"]["user_messages"]["state"].is_string());
+    assert!(matches!(
+        health["status"].as_str(),
+        Some("ok") | Some("degraded")
+    ));
+
+    server_handle.abort();
+}
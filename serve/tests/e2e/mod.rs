@@ -1,10 +1,19 @@
 mod agent_list;
 mod common;
+mod healthz;
 mod invalid_json;
+mod msgpack_encoding;
+mod payload_limits;
 mod ping;
+mod router_from_builder;
 mod run_react;
+mod schema;
+mod state_show;
+#[cfg(feature = "test-util")]
+mod testing;
 mod tool_show_existing;
 mod tool_show_nonexistent;
 mod tools_list;
 mod user_messages;
 mod workspace;
+mod ws_client;
@@ -0,0 +1,57 @@
+use super::common;
+use futures_util::{SinkExt, StreamExt};
+use loom::protocol::encoding::{decode_msgpack, SUBPROTOCOL_MSGPACK};
+use loom::{ClientRequest, PingRequest, ServerResponse};
+use std::time::Duration;
+use tokio::time::timeout;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message;
+
+#[tokio::test]
+async fn e2e_msgpack_subprotocol_sends_binary_responses() {
+    common::load_dotenv();
+    let (url, server_handle) = common::spawn_server_once().await;
+
+    let mut request = url.as_str().into_client_request().unwrap();
+    request.headers_mut().insert(
+        "Sec-WebSocket-Protocol",
+        HeaderValue::from_static(SUBPROTOCOL_MSGPACK),
+    );
+    let (ws, response) = connect_async(request).await.unwrap();
+    assert_eq!(
+        response
+            .headers()
+            .get("Sec-WebSocket-Protocol")
+            .and_then(|v| v.to_str().ok()),
+        Some(SUBPROTOCOL_MSGPACK)
+    );
+    let (mut write, mut read) = ws.split();
+
+    // Requests stay JSON; the response comes back as MessagePack.
+    let req = ClientRequest::Ping(PingRequest {
+        id: "ping-mp".to_string(),
+    });
+    write
+        .send(Message::Text(serde_json::to_string(&req).unwrap()))
+        .await
+        .unwrap();
+    let msg = timeout(Duration::from_secs(10), read.next())
+        .await
+        .expect("timeout waiting for response")
+        .expect("no message")
+        .unwrap();
+    let bytes = match msg {
+        Message::Binary(b) => b,
+        other => panic!("expected binary frame, got {:?}", other),
+    };
+    match decode_msgpack::<ServerResponse>(&bytes).unwrap() {
+        ServerResponse::Pong(p) => assert_eq!(p.id, "ping-mp"),
+        other => panic!("expected Pong, got {:?}", other),
+    }
+
+    drop(write);
+    drop(read);
+    let _ = timeout(Duration::from_secs(5), server_handle).await;
+}
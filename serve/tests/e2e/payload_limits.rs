@@ -0,0 +1,36 @@
+use futures_util::{SinkExt, StreamExt};
+use loom::ServerResponse;
+use std::time::Duration;
+use tokio::time::timeout;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+use super::common;
+
+#[tokio::test]
+async fn e2e_deeply_nested_json_returns_payload_too_large() {
+    common::load_dotenv();
+    let (url, server_handle) = common::spawn_server_once().await;
+
+    let (ws, _) = connect_async(&url).await.unwrap();
+    let (mut write, mut read) = ws.split();
+
+    let nested = format!("{}{}", "[".repeat(200), "]".repeat(200));
+    write.send(Message::Text(nested)).await.unwrap();
+    let opt = timeout(Duration::from_secs(5), read.next()).await.unwrap();
+    let msg = opt.expect("expected one response").expect("ws message");
+    let text = msg.to_text().unwrap_or("");
+    eprintln!("[e2e] received: {}", text);
+
+    let resp: ServerResponse = serde_json::from_str(text).unwrap();
+    match &resp {
+        ServerResponse::Error(e) => {
+            assert_eq!(e.code.as_deref(), Some("payload_too_large"));
+            assert!(e.error.contains("depth"), "{}", e.error);
+        }
+        _ => panic!("expected payload_too_large Error, got {:?}", resp),
+    }
+
+    drop(write);
+    drop(read);
+    let _ = timeout(Duration::from_secs(5), server_handle).await;
+}
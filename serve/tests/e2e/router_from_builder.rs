@@ -0,0 +1,43 @@
+use super::common;
+use futures_util::StreamExt;
+use loom::{ClientRequest, LoomBuilder, PingRequest, ReactBuildConfig, ServerResponse};
+use tokio::net::TcpListener;
+use tokio_tungstenite::connect_async;
+
+#[tokio::test]
+async fn e2e_router_from_builder_serves_ping() {
+    common::load_dotenv();
+    let builder = LoomBuilder::new(ReactBuildConfig::from_env())
+        .model("gpt-4o-mini")
+        .read_only(true);
+    let app = serve::router_from_builder(&builder).unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let server_handle = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let (ws, _) = connect_async(&url).await.unwrap();
+    let (mut write, mut read) = ws.split();
+    let req = ClientRequest::Ping(PingRequest {
+        id: "ping-builder".to_string(),
+    });
+    let (resp, _) = common::send_and_recv(&mut write, &mut read, &req)
+        .await
+        .unwrap();
+    match &resp {
+        ServerResponse::Pong(p) => assert_eq!(p.id, "ping-builder"),
+        _ => panic!("expected Pong, got {:?}", resp),
+    }
+
+    server_handle.abort();
+}
+
+#[tokio::test]
+async fn e2e_router_from_builder_refuses_settings_runs_cannot_apply() {
+    common::load_dotenv();
+    let with_llm =
+        LoomBuilder::new(ReactBuildConfig::from_env()).llm(loom::MockLlm::with_no_tool_calls("hi"));
+    let err = serve::router_from_builder(&with_llm).unwrap_err();
+    assert!(err.to_string().contains("llm()"), "{}", err);
+    let with_deny = LoomBuilder::new(ReactBuildConfig::from_env()).deny_tools(["bash"]);
+    assert!(serve::router_from_builder(&with_deny).is_err());
+}
@@ -51,7 +51,7 @@ fn assert_event(event: &ProtocolEvent, saw_node_enter: &mut bool, saw_node_exit:
             assert_optional_non_empty("event.call_id", call_id);
             assert_optional_non_empty("event.name", name);
         }
-        ProtocolEvent::MessageChunk { content, id: _ } => {
+        ProtocolEvent::MessageChunk { content, .. } => {
             assert_non_empty("event.content", content);
         }
         ProtocolEvent::ThoughtChunk { content, id: _ } => {
@@ -138,6 +138,13 @@ async fn e2e_run_then_disconnect() {
         got_adaptive: None,
         verbose: Some(false),
         model: None,
+        reply_format: None,
+        reply_schema: None,
+        messages: None,
+        read_only: None,
+        locale: None,
+        pin: None,
+        flags: Default::default(),
     });
     let req_json = serde_json::to_string(&req).unwrap();
     write.send(Message::Text(req_json)).await.unwrap();
@@ -215,7 +222,14 @@ async fn e2e_run_react() {
         working_folder: None,
         got_adaptive: None,
         model: None,
+        reply_format: None,
+        reply_schema: None,
+        messages: None,
         verbose: Some(false),
+        read_only: None,
+        locale: None,
+        pin: None,
+        flags: Default::default(),
     });
     let read_timeout = Duration::from_secs(30);
     let req_json = serde_json::to_string(&req).unwrap();
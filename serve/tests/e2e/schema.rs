@@ -0,0 +1,31 @@
+use super::common;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+#[tokio::test]
+async fn e2e_schema_endpoint_returns_protocol_schema() {
+    common::load_dotenv();
+    let (url, server_handle) = common::spawn_server_once().await;
+    let addr = url.trim_start_matches("ws://");
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(
+            format!(
+                "GET /schema HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+                addr
+            )
+            .as_bytes(),
+        )
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    let body = response.split("\r\n\r\n").nth(1).unwrap_or_default();
+    let schema: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert_eq!(schema, loom::protocol::protocol_schema());
+
+    server_handle.abort();
+}
@@ -0,0 +1,163 @@
+//! E2E: state_show returns the latest checkpointed state for a thread, and no state for unknown threads;
+//! checkpoint_list returns the thread's checkpoint history, and state_show can show any entry of it.
+
+use super::common;
+use futures_util::StreamExt;
+use loom::memory::{
+    Checkpoint, CheckpointSource, Checkpointer, JsonSerializer, RunnableConfig, SqliteSaver,
+};
+use loom::{
+    CheckpointListRequest, ClientRequest, Message, ReActState, ServerResponse, StateShowRequest,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::timeout;
+use tokio_tungstenite::connect_async;
+
+#[tokio::test]
+async fn e2e_state_show_returns_checkpointed_state() {
+    let _lock = common::env_test_lock().lock().unwrap();
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    let db_path = tmp.path().to_string_lossy().to_string();
+    let prev_db = std::env::var("LOOM_DB_PATH").ok();
+    std::env::set_var("LOOM_DB_PATH", &db_path);
+
+    let saver = SqliteSaver::<ReActState>::new(&db_path, Arc::new(JsonSerializer)).unwrap();
+    let state = ReActState {
+        messages: vec![Message::user("hello")],
+        turn_count: 3,
+        ..Default::default()
+    };
+    let config = RunnableConfig {
+        thread_id: Some("state-show-thread".to_string()),
+        ..Default::default()
+    };
+    let checkpoint_id = saver
+        .put(
+            &config,
+            &Checkpoint::from_state(state, CheckpointSource::Loop, 1),
+        )
+        .await
+        .unwrap();
+
+    let (url, server_handle) = common::spawn_server_once().await;
+    let (ws, _) = connect_async(&url).await.unwrap();
+    let (mut write, mut read) = ws.split();
+
+    let req = ClientRequest::StateShow(StateShowRequest {
+        id: "ss-1".to_string(),
+        thread_id: "state-show-thread".to_string(),
+        checkpoint_id: None,
+    });
+    let (resp, _) = common::send_and_recv(&mut write, &mut read, &req)
+        .await
+        .unwrap();
+    match &resp {
+        ServerResponse::StateShow(r) => {
+            assert_eq!(r.id, "ss-1");
+            assert_eq!(r.checkpoint_id.as_deref(), Some(checkpoint_id.as_str()));
+            let state = r.state.as_ref().expect("state");
+            assert_eq!(state["turn_count"], 3);
+        }
+        _ => panic!("expected StateShow, got {:?}", resp),
+    }
+
+    let req = ClientRequest::StateShow(StateShowRequest {
+        id: "ss-2".to_string(),
+        thread_id: "unknown-thread".to_string(),
+        checkpoint_id: None,
+    });
+    let (resp, _) = common::send_and_recv(&mut write, &mut read, &req)
+        .await
+        .unwrap();
+    match &resp {
+        ServerResponse::StateShow(r) => {
+            assert!(r.state.is_none());
+            assert!(r.checkpoint_id.is_none());
+        }
+        _ => panic!("expected StateShow, got {:?}", resp),
+    }
+
+    drop(write);
+    drop(read);
+    let _ = timeout(Duration::from_secs(5), server_handle).await;
+    match prev_db {
+        Some(v) => std::env::set_var("LOOM_DB_PATH", v),
+        None => std::env::remove_var("LOOM_DB_PATH"),
+    }
+}
+
+#[tokio::test]
+async fn e2e_checkpoint_list_then_show_earlier_checkpoint() {
+    let _lock = common::env_test_lock().lock().unwrap();
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    let db_path = tmp.path().to_string_lossy().to_string();
+    let prev_db = std::env::var("LOOM_DB_PATH").ok();
+    std::env::set_var("LOOM_DB_PATH", &db_path);
+
+    let saver = SqliteSaver::<ReActState>::new(&db_path, Arc::new(JsonSerializer)).unwrap();
+    let config = RunnableConfig {
+        thread_id: Some("history-thread".to_string()),
+        ..Default::default()
+    };
+    let mut ids = Vec::new();
+    for (step, node) in [(1, "think"), (2, "act"), (3, "observe")] {
+        let state = ReActState {
+            turn_count: step as u32,
+            ..Default::default()
+        };
+        let mut checkpoint = Checkpoint::from_state(state, CheckpointSource::Loop, step);
+        checkpoint.metadata.node = Some(node.to_string());
+        ids.push(saver.put(&config, &checkpoint).await.unwrap());
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+
+    let (url, server_handle) = common::spawn_server_once().await;
+    let (ws, _) = connect_async(&url).await.unwrap();
+    let (mut write, mut read) = ws.split();
+
+    let req = ClientRequest::CheckpointList(CheckpointListRequest {
+        id: "cl-1".to_string(),
+        thread_id: "history-thread".to_string(),
+        node: None,
+        min_step: Some(2),
+        max_step: None,
+        limit: None,
+    });
+    let (resp, _) = common::send_and_recv(&mut write, &mut read, &req)
+        .await
+        .unwrap();
+    match &resp {
+        ServerResponse::CheckpointList(r) => {
+            let nodes: Vec<_> = r.checkpoints.iter().map(|c| c.node.as_deref()).collect();
+            assert_eq!(nodes, vec![Some("act"), Some("observe")]);
+            assert_eq!(r.checkpoints[0].parent_id.as_deref(), Some(ids[0].as_str()));
+            assert!(r.checkpoints[0].size_bytes.unwrap_or(0) > 0);
+        }
+        _ => panic!("expected CheckpointList, got {:?}", resp),
+    }
+
+    let req = ClientRequest::StateShow(StateShowRequest {
+        id: "ss-3".to_string(),
+        thread_id: "history-thread".to_string(),
+        checkpoint_id: Some(ids[1].clone()),
+    });
+    let (resp, _) = common::send_and_recv(&mut write, &mut read, &req)
+        .await
+        .unwrap();
+    match &resp {
+        ServerResponse::StateShow(r) => {
+            assert_eq!(r.checkpoint_id.as_deref(), Some(ids[1].as_str()));
+            assert_eq!(r.state.as_ref().expect("state")["turn_count"], 2);
+        }
+        _ => panic!("expected StateShow, got {:?}", resp),
+    }
+
+    drop(write);
+    drop(read);
+    let _ = timeout(Duration::from_secs(5), server_handle).await;
+    match prev_db {
+        Some(v) => std::env::set_var("LOOM_DB_PATH", v),
+        None => std::env::remove_var("LOOM_DB_PATH"),
+    }
+}
@@ -0,0 +1,63 @@
+use super::common;
+use loom::MockScript;
+use serde_json::json;
+use serve::testing::TestServer;
+
+#[tokio::test]
+async fn e2e_test_server_runs_scripted_tool_call_and_reply() {
+    common::load_dotenv();
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "hello").unwrap();
+    let server = TestServer::start(
+        MockScript::default()
+            .with_tool_call("ls", json!({ "path": dir.path() }))
+            .with_reply("found notes.txt"),
+    )
+    .await
+    .unwrap();
+
+    let trace = server.run("what is in the folder?").await.unwrap();
+    trace
+        .assert_tool_called("ls")
+        .assert_reply_contains("notes.txt")
+        .assert_event("node_enter")
+        .assert_events_in_order();
+    assert_eq!(trace.nodes().first(), Some(&"think"));
+
+    // Each run replays the script from the start.
+    let again = server.run("and now?").await.unwrap();
+    again.assert_tool_called("ls");
+}
+
+#[tokio::test]
+async fn e2e_test_server_fires_run_hooks() {
+    common::load_dotenv();
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let (start_tx, end_tx) = (tx.clone(), tx);
+    let hooks = loom::RunHooks::new()
+        .on_start(move |start: loom::RunStart| {
+            let tx = start_tx.clone();
+            async move {
+                let _ = tx.send(format!("start {}", start.run_id.is_some()));
+            }
+        })
+        .on_end(move |end: loom::RunEnd<loom::RunEndResponse>| {
+            let tx = end_tx.clone();
+            async move {
+                let _ = tx.send(format!("end {}", end.state.reply));
+            }
+        });
+    let server = TestServer::start_with_hooks(MockScript::default().with_reply("hooked"), hooks)
+        .await
+        .unwrap();
+
+    server.run("hello").await.unwrap();
+
+    // on_end fires after RunEnd was sent, so it may land after the client saw the reply.
+    let mut seen = Vec::new();
+    for _ in 0..2 {
+        let next = tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv()).await;
+        seen.push(next.unwrap().unwrap());
+    }
+    assert_eq!(seen, vec!["start true", "end hooked"]);
+}
@@ -4,7 +4,10 @@
 use super::common;
 use futures_util::StreamExt;
 use loom::protocol::AgentIdentifier;
-use loom::{AgentType, ClientRequest, RunRequest, ServerResponse, UserMessagesRequest};
+use loom::{
+    AgentType, ClientRequest, RunRequest, ServerResponse, UserMessagesDeleteRequest,
+    UserMessagesRequest,
+};
 use std::time::Duration;
 use tokio::io::AsyncReadExt;
 use tokio::io::AsyncWriteExt;
@@ -147,6 +150,13 @@ async fn e2e_user_messages_after_run() {
         got_adaptive: None,
         verbose: Some(false),
         model: None,
+        reply_format: None,
+        reply_schema: None,
+        messages: None,
+        read_only: None,
+        locale: None,
+        pin: None,
+        flags: Default::default(),
     });
 
     let read_timeout = Duration::from_secs(90);
@@ -168,6 +178,7 @@ async fn e2e_user_messages_after_run() {
         thread_id: thread_id.to_string(),
         before: None,
         limit: Some(50),
+        query: None,
     });
     let (um_resp, _) = common::send_and_recv(&mut write, &mut read, &um_req)
         .await
@@ -189,11 +200,36 @@ async fn e2e_user_messages_after_run() {
                 has_assistant,
                 "user_messages should contain at least one assistant message"
             );
+            assert!(
+                r.messages
+                    .iter()
+                    .all(|m| m.id.is_some() && m.run_id.is_some()),
+                "stored messages should carry their id and run"
+            );
         }
         ServerResponse::Error(e) => panic!("user_messages failed: {}", e.error),
         _ => panic!("expected UserMessages or Error, got {:?}", um_resp),
     }
 
+    let delete_req = ClientRequest::UserMessagesDelete(UserMessagesDeleteRequest {
+        id: "umd-1".to_string(),
+        thread_id: thread_id.to_string(),
+    });
+    let (delete_resp, _) = common::send_and_recv(&mut write, &mut read, &delete_req)
+        .await
+        .expect("user_messages_delete request should get response");
+    match &delete_resp {
+        ServerResponse::UserMessagesDelete(r) => assert!(r.deleted >= 2),
+        _ => panic!("expected UserMessagesDelete, got {:?}", delete_resp),
+    }
+    let (um_resp, _) = common::send_and_recv(&mut write, &mut read, &um_req)
+        .await
+        .expect("user_messages request should get response");
+    match &um_resp {
+        ServerResponse::UserMessages(r) => assert!(r.messages.is_empty()),
+        _ => panic!("expected UserMessages, got {:?}", um_resp),
+    }
+
     drop(write);
     let _ = timeout(Duration::from_secs(5), server_handle).await;
     drop(mock_handle);
@@ -1,8 +1,9 @@
 use super::common;
 use futures_util::StreamExt;
 use loom::{
-    ClientRequest, ServerResponse, WorkspaceCreateRequest, WorkspaceListRequest,
+    ClientRequest, ServerResponse, WorkspaceCreateRequest, WorkspaceDefaults, WorkspaceListRequest,
     WorkspaceThreadAddRequest, WorkspaceThreadListRequest, WorkspaceThreadRemoveRequest,
+    WorkspaceUpdateRequest,
 };
 use std::time::Duration;
 use tokio::time::timeout;
@@ -153,3 +154,69 @@ async fn e2e_workspace_thread_add_list_remove() {
     drop(read);
     let _ = timeout(Duration::from_secs(5), server_handle).await;
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn e2e_workspace_update_sets_defaults_shown_in_list() {
+    common::load_dotenv();
+    let (url, server_handle) = common::spawn_server_once().await;
+
+    let (ws, _) = connect_async(&url).await.unwrap();
+    let (mut write, mut read) = ws.split();
+
+    let create_req = ClientRequest::WorkspaceCreate(WorkspaceCreateRequest {
+        id: "wc-1".to_string(),
+        name: Some("defaults".to_string()),
+    });
+    let (resp, _) = common::send_and_recv(&mut write, &mut read, &create_req)
+        .await
+        .unwrap();
+    let workspace_id = match resp {
+        ServerResponse::WorkspaceCreate(r) => r.workspace_id,
+        other => panic!("expected WorkspaceCreate, got {:?}", other),
+    };
+
+    let defaults = WorkspaceDefaults {
+        model: Some("gpt-4o-mini".to_string()),
+        role: Some("You are a release manager.".to_string()),
+        tools: Some(vec!["read".to_string()]),
+        ..Default::default()
+    };
+    let update_req = ClientRequest::WorkspaceUpdate(WorkspaceUpdateRequest {
+        id: "wu-1".to_string(),
+        workspace_id: workspace_id.clone(),
+        defaults: defaults.clone(),
+    });
+    let (resp, received) = common::send_and_recv(&mut write, &mut read, &update_req)
+        .await
+        .unwrap();
+    assert!(
+        received.contains("\"type\":\"workspace_update\""),
+        "expected workspace_update response, received: {}",
+        received
+    );
+    match resp {
+        ServerResponse::WorkspaceUpdate(r) => {
+            assert_eq!(r.workspace_id, workspace_id);
+            assert_eq!(r.defaults, defaults);
+        }
+        other => panic!("expected WorkspaceUpdate, got {:?}", other),
+    }
+
+    let list_req = ClientRequest::WorkspaceList(WorkspaceListRequest {
+        id: "wl-1".to_string(),
+    });
+    let (resp, _) = common::send_and_recv(&mut write, &mut read, &list_req)
+        .await
+        .unwrap();
+    match resp {
+        ServerResponse::WorkspaceList(r) => {
+            let ws = r.workspaces.iter().find(|w| w.id == workspace_id).unwrap();
+            assert_eq!(ws.defaults, defaults);
+        }
+        other => panic!("expected WorkspaceList, got {:?}", other),
+    }
+
+    drop(write);
+    drop(read);
+    let _ = timeout(Duration::from_secs(5), server_handle).await;
+}
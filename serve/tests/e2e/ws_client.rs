@@ -0,0 +1,90 @@
+use super::common;
+use loom::client::{ClientError, WsClient};
+use loom::protocol::WireEncoding;
+use loom::{ClientRequest, RunRequest, ServerResponse, ToolsListRequest};
+use std::time::Duration;
+use tokio::time::timeout;
+
+#[tokio::test]
+async fn e2e_ws_client_ping_and_tools_list_over_msgpack() {
+    common::load_dotenv();
+    let (url, server_handle) = common::spawn_server_once().await;
+
+    let mut client = WsClient::connect_with_encoding(&url, WireEncoding::MessagePack)
+        .await
+        .unwrap();
+    assert_eq!(client.encoding(), WireEncoding::MessagePack);
+    client.ping().await.unwrap();
+
+    let resp = client
+        .request(&ClientRequest::ToolsList(ToolsListRequest {
+            id: "tools-list-1".to_string(),
+            working_folder: None,
+            thread_id: None,
+        }))
+        .await
+        .unwrap();
+    match resp {
+        ServerResponse::ToolsList(r) => assert!(!r.tools.is_empty()),
+        other => panic!("expected ToolsList, got {:?}", other),
+    }
+
+    let err = client
+        .request(&ClientRequest::Run(
+            serde_json::from_str::<RunRequest>(r#"{"message":"hi","agent":"react"}"#).unwrap(),
+        ))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ClientError::InvalidRequest(_)));
+
+    client.close().await.unwrap();
+    let _ = timeout(Duration::from_secs(5), server_handle).await;
+}
+
+#[tokio::test]
+async fn e2e_ws_client_tools_list_over_deflate() {
+    common::load_dotenv();
+    let (url, server_handle) = common::spawn_server_once().await;
+
+    let mut client = WsClient::connect_with_encoding(&url, WireEncoding::DeflateJson)
+        .await
+        .unwrap();
+    assert_eq!(client.encoding(), WireEncoding::DeflateJson);
+    client.ping().await.unwrap();
+
+    let resp = client
+        .request(&ClientRequest::ToolsList(ToolsListRequest {
+            id: "tools-list-deflate".to_string(),
+            working_folder: None,
+            thread_id: None,
+        }))
+        .await
+        .unwrap();
+    match resp {
+        ServerResponse::ToolsList(r) => assert!(!r.tools.is_empty()),
+        other => panic!("expected ToolsList, got {:?}", other),
+    }
+
+    client.close().await.unwrap();
+    let _ = timeout(Duration::from_secs(5), server_handle).await;
+}
+
+#[tokio::test]
+async fn e2e_ws_client_resume_unknown_run_is_server_error() {
+    common::load_dotenv();
+    let (url, server_handle) = common::spawn_server_once().await;
+
+    let mut client = WsClient::connect(&url).await.unwrap();
+    let mut run = client.resume_run("run-missing", Some(3)).await.unwrap();
+    let err = timeout(Duration::from_secs(10), run.next_event())
+        .await
+        .expect("response before timeout")
+        .unwrap_err();
+    match err {
+        ClientError::Server(e) => assert!(e.error.contains("run-missing"), "{}", e.error),
+        other => panic!("expected server error, got {:?}", other),
+    }
+
+    client.close().await.unwrap();
+    let _ = timeout(Duration::from_secs(5), server_handle).await;
+}
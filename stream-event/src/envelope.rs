@@ -1,5 +1,16 @@
 //! Envelope (session_id, node_id, event_id) per protocol_spec §2 / §7.1.
 //! EnvelopeState tracks current node and injects envelope into each event.
+//!
+//! # Ordering
+//!
+//! Event ids of a session are strictly increasing and contiguous (1, 2, 3, …): every event
+//! takes the next id from a sequence shared by all clones of the session's [`EnvelopeState`],
+//! so concurrent emitters never reuse or skip an id. Each event also carries `prev_event_id`
+//! (the id before it; absent on the first event). A receiver that gets an event whose
+//! `prev_event_id` is not the last id it saw knows events were dropped in between.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
 use crate::event::ProtocolEvent;
 use serde_json::Value;
@@ -13,6 +24,8 @@ pub struct Envelope {
     pub node_id: Option<String>,
     /// Per-message sequence number; monotonically increasing within a stream.
     pub event_id: Option<u64>,
+    /// Event id of the event emitted just before this one in the session (gap detection).
+    pub prev_event_id: Option<u64>,
 }
 
 impl Envelope {
@@ -42,6 +55,12 @@ impl Envelope {
         self
     }
 
+    #[must_use]
+    pub fn with_prev_event_id(mut self, id: u64) -> Self {
+        self.prev_event_id = Some(id);
+        self
+    }
+
     /// Merges envelope fields into the given JSON object (top-level only).
     /// Does not overwrite existing keys.
     pub fn inject_into(&self, obj: &mut Value) {
@@ -60,19 +79,25 @@ impl Envelope {
             obj.entry("event_id")
                 .or_insert_with(|| Value::Number(serde_json::Number::from(id)));
         }
+        if let Some(id) = self.prev_event_id {
+            obj.entry("prev_event_id")
+                .or_insert_with(|| Value::Number(serde_json::Number::from(id)));
+        }
     }
 }
 
 /// Envelope state for a single run stream.
 ///
 /// Create one instance per run/session and call [`EnvelopeState::inject_into`]
-/// for each outgoing event in stream order.
+/// for each outgoing event in stream order. Clones share the event id sequence (see the
+/// module docs), so events emitted through any clone stay strictly ordered by id.
 #[derive(Clone, Debug)]
 pub struct EnvelopeState {
     pub session_id: String,
     pub current_node_id: String,
     pub node_run_seq: u64,
-    pub next_event_id: u64,
+    /// Next event id to hand out; shared by clones.
+    next_event_id: Arc<AtomicU64>,
 }
 
 impl EnvelopeState {
@@ -83,7 +108,27 @@ impl EnvelopeState {
             session_id,
             current_node_id: String::new(),
             node_run_seq: 0,
-            next_event_id: 1,
+            next_event_id: Arc::new(AtomicU64::new(1)),
+        }
+    }
+
+    /// The id the next event will get.
+    #[must_use]
+    pub fn next_event_id(&self) -> u64 {
+        self.next_event_id.load(Ordering::SeqCst)
+    }
+
+    /// Envelope for the next event: takes a fresh id from the shared sequence.
+    fn next_envelope(&self) -> Envelope {
+        let id = self.next_event_id.fetch_add(1, Ordering::SeqCst);
+        let env = Envelope::new()
+            .with_session_id(&self.session_id)
+            .with_node_id(self.active_node_id())
+            .with_event_id(id);
+        if id > 1 {
+            env.with_prev_event_id(id - 1)
+        } else {
+            env
         }
     }
 
@@ -105,12 +150,7 @@ impl EnvelopeState {
                 self.node_run_seq += 1;
             }
         }
-        let env = Envelope::new()
-            .with_session_id(&self.session_id)
-            .with_node_id(self.active_node_id())
-            .with_event_id(self.next_event_id);
-        self.next_event_id += 1;
-        env.inject_into(value);
+        self.next_envelope().inject_into(value);
     }
 
     /// Builds the envelope for the reply line (protocol_spec §5).
@@ -122,7 +162,7 @@ impl EnvelopeState {
         Envelope::new()
             .with_session_id(&self.session_id)
             .with_node_id(self.active_node_id())
-            .with_event_id(self.next_event_id)
+            .with_event_id(self.next_event_id())
     }
 }
 
@@ -185,6 +225,7 @@ mod tests {
             &ProtocolEvent::MessageChunk {
                 content: "hello".to_string(),
                 id: "think".to_string(),
+                revision: 0,
             },
             &mut state,
         )
@@ -218,4 +259,49 @@ mod tests {
         assert_eq!(first_reply.event_id, Some(2));
         assert_eq!(second_reply.event_id, Some(2));
     }
+
+    #[test]
+    fn prev_event_id_links_events_and_clones_share_the_sequence() {
+        let mut state = EnvelopeState::new("sess-1".to_string());
+        let mut first = json!({"type":"node_enter","id":"think"});
+        state.inject_into(&mut first);
+        assert_eq!(first["event_id"], 1);
+        assert!(first.get("prev_event_id").is_none());
+
+        let mut other = state.clone();
+        let mut second = json!({"type":"usage"});
+        other.inject_into(&mut second);
+        let mut third = json!({"type":"usage"});
+        state.inject_into(&mut third);
+        assert_eq!(second["event_id"], 2);
+        assert_eq!(second["prev_event_id"], 1);
+        assert_eq!(third["event_id"], 3);
+        assert_eq!(third["prev_event_id"], 2);
+        assert_eq!(state.next_event_id(), 4);
+    }
+
+    #[test]
+    fn concurrent_emitters_get_unique_contiguous_ids() {
+        let state = EnvelopeState::new("sess-1".to_string());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let mut s = state.clone();
+                std::thread::spawn(move || {
+                    (0..100)
+                        .map(|_| {
+                            let mut v = json!({"type":"usage"});
+                            s.inject_into(&mut v);
+                            v["event_id"].as_u64().unwrap()
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        let mut ids: Vec<u64> = handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap())
+            .collect();
+        ids.sort_unstable();
+        assert_eq!(ids, (1..=400).collect::<Vec<_>>());
+    }
 }
@@ -29,6 +29,7 @@ use serde_json::Value;
 /// - `id` in payload = node name (e.g. "think", "act"); see [`EnvelopeState`](crate::EnvelopeState) for how envelope `node_id` is derived from `NodeEnter.id`.
 /// - `got_expand` keeps payload field name `node_id` by protocol definition.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ProtocolEvent {
     /// Node run started. Emitted when the agent begins executing a node (e.g. think, act).
@@ -52,6 +53,9 @@ pub enum ProtocolEvent {
         content: String,
         /// Producing node name (e.g. `"think"`, `"reply"`).
         id: String,
+        /// Answer revision (omitted for the first draft); see [`AnswerRevised`](Self::AnswerRevised).
+        #[serde(default, skip_serializing_if = "is_zero")]
+        revision: u32,
     },
     /// One chunk of **reasoning/thinking** text. Streamed during completion.
     /// Corresponds to ACP `agent_thought_chunk`. Use [`MessageChunk`](Self::MessageChunk) for final reply.
@@ -67,6 +71,21 @@ pub enum ProtocolEvent {
         completion_tokens: u32,
         total_tokens: u32,
     },
+    /// Graph structure with live progress, for painting the agent graph (DOT/Mermaid) in a UI.
+    /// Sent when each node starts and once more with `current: null` when the run ends.
+    GraphProgress {
+        /// Node names of the graph (without `__start__` / `__end__`).
+        nodes: Vec<String>,
+        /// Edges as `{"from", "to", "label"?}`; `label` is the routing key of a conditional edge.
+        edges: Vec<Value>,
+        /// Node running now; `None` after the run ends.
+        current: Option<String>,
+        /// Number of times each node has started in this run.
+        visits: std::collections::BTreeMap<String, u32>,
+    },
+    /// Why the last LLM completion stopped: `stop`, `length` (truncated by the output token
+    /// limit), `tool_calls`, `content_filter`, or a provider-specific value.
+    FinishReason { reason: String },
     /// Full state snapshot. Emitted to replace client state with the given graph state.
     /// Shape depends on agent type (ReAct, ToT, GoT, etc.). Contrast with [`Updates`](Self::Updates) (incremental).
     Values {
@@ -139,6 +158,9 @@ pub enum ProtocolEvent {
         node_id: String,
         nodes_added: usize,
         edges_added: usize,
+        /// Set when the expand was denied (e.g. `token budget exhausted ...`); no nodes added.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        denied: Option<String>,
     },
     /// Tool call arguments streamed incrementally (e.g. streaming JSON).
     /// First chunk usually has `call_id` and `name`; later chunks may have only `arguments_delta`.
@@ -167,6 +189,13 @@ pub enum ProtocolEvent {
         name: String,
         content: String,
     },
+    /// Partial result of a running tool (e.g. one line of a build's stdout), in order. Lets clients
+    /// show long-running tools live; the final result still comes in [`ToolEnd`](Self::ToolEnd).
+    ToolOutputChunk {
+        call_id: Option<String>,
+        name: String,
+        content: String,
+    },
     /// Tool execution finished. Contains the final result or error text. Ends the sequence started by [`ToolStart`](Self::ToolStart).
     ToolEnd {
         call_id: Option<String>,
@@ -187,6 +216,46 @@ pub enum ProtocolEvent {
         name: String,
         arguments: Value,
     },
+    /// Generated thread title and short summary. Sent after the run's reply when the server
+    /// has auto-summarize enabled; clients use it to label the thread in sidebars.
+    ThreadSummary { title: String, summary: String },
+    /// The tool list changed mid-run (e.g. an MCP server added tools); the model sees the new
+    /// definitions from the next think turn on. `tools` lists the names of all tools now available.
+    ToolsRefreshed { tools: Vec<String> },
+    /// The prompt (`prompt_tokens`, estimated) did not fit the context window of model `from`
+    /// (`context_limit` tokens), so this think call went to the larger-context model `to`.
+    ModelSwitched {
+        from: String,
+        to: String,
+        prompt_tokens: u32,
+        context_limit: u32,
+    },
+    /// The answer streamed so far was discarded (`reason`: `reflection` or `tool_call_repair`).
+    /// Message chunks that follow carry `revision`; clients drop the text shown for earlier
+    /// revisions instead of appending to it.
+    AnswerRevised { reason: String, revision: u32 },
+    /// How long one part of the run took, in `duration_ms`. `kind` is `node` (a whole node run),
+    /// `prompt` (think step work before the LLM call), `first_token` (LLM call start to first
+    /// streamed token), `llm` (all LLM calls of a think step; `name` is the endpoint when the
+    /// agent routes between several) or `tool` (one tool call, `name` is the tool). `node_id` is
+    /// the node the measurement belongs to.
+    Timing {
+        node_id: String,
+        kind: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        duration_ms: u64,
+        /// For `node`: size of the run state after the node, when the agent has state limits.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        state_bytes: Option<u64>,
+        /// For `node`: messages in the run state after the node, when the agent has state limits.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        message_count: Option<u64>,
+    },
+}
+
+fn is_zero(v: &u32) -> bool {
+    *v == 0
 }
 
 impl ProtocolEvent {
@@ -208,11 +277,35 @@ mod tests {
         let event = ProtocolEvent::MessageChunk {
             content: "final reply".to_string(),
             id: "think".to_string(),
+            revision: 0,
         };
         let v = event.to_value().unwrap();
         assert_eq!(v["type"], "message_chunk");
         assert_eq!(v["content"], "final reply");
         assert_eq!(v["id"], "think");
+        assert!(v.get("revision").is_none());
+    }
+
+    #[test]
+    fn answer_revised_and_revised_chunk_format() {
+        let v = ProtocolEvent::AnswerRevised {
+            reason: "reflection".to_string(),
+            revision: 1,
+        }
+        .to_value()
+        .unwrap();
+        assert_eq!(v["type"], "answer_revised");
+        assert_eq!(v["reason"], "reflection");
+        assert_eq!(v["revision"], 1);
+
+        let chunk = ProtocolEvent::MessageChunk {
+            content: "new draft".to_string(),
+            id: "think".to_string(),
+            revision: 1,
+        }
+        .to_value()
+        .unwrap();
+        assert_eq!(chunk["revision"], 1);
     }
 
     #[test]
@@ -246,12 +339,14 @@ mod tests {
             node_id: "n-3".to_string(),
             nodes_added: 2,
             edges_added: 1,
+            denied: None,
         };
         let value = event.to_value().unwrap();
 
         assert_eq!(value["type"], "got_expand");
         assert_eq!(value["node_id"], "n-3");
         assert!(value.get("id").is_none());
+        assert!(value.get("denied").is_none());
     }
 
     #[test]
@@ -334,6 +429,19 @@ mod tests {
         assert_eq!(v["content"], "Compiling loom v0.1.0\n");
     }
 
+    #[test]
+    fn tool_output_chunk_format() {
+        let event = ProtocolEvent::ToolOutputChunk {
+            call_id: Some("call_1".to_string()),
+            name: "bash".to_string(),
+            content: "   Compiling serde v1.0.0\n".to_string(),
+        };
+        let v = event.to_value().unwrap();
+        assert_eq!(v["type"], "tool_output_chunk");
+        assert_eq!(v["call_id"], "call_1");
+        assert_eq!(v["content"], "   Compiling serde v1.0.0\n");
+    }
+
     #[test]
     fn tool_end_success_format() {
         let event = ProtocolEvent::ToolEnd {
@@ -379,4 +487,57 @@ mod tests {
         assert_eq!(v["name"], "delete_file");
         assert_eq!(v["arguments"]["path"], "./important.txt");
     }
+
+    #[test]
+    fn thread_summary_format() {
+        let event = ProtocolEvent::ThreadSummary {
+            title: "Trip planning".to_string(),
+            summary: "The user planned a trip to Kyoto.".to_string(),
+        };
+        let v = event.to_value().unwrap();
+        assert_eq!(v["type"], "thread_summary");
+        assert_eq!(v["title"], "Trip planning");
+        assert_eq!(v["summary"], "The user planned a trip to Kyoto.");
+    }
+
+    #[test]
+    fn tools_refreshed_format() {
+        let event = ProtocolEvent::ToolsRefreshed {
+            tools: vec!["bash".to_string(), "install_pkg".to_string()],
+        };
+        let v = event.to_value().unwrap();
+        assert_eq!(v["type"], "tools_refreshed");
+        assert_eq!(v["tools"][1], "install_pkg");
+    }
+
+    #[test]
+    fn model_switched_format() {
+        let event = ProtocolEvent::ModelSwitched {
+            from: "openai/gpt-4o-mini".to_string(),
+            to: "openai/gpt-4.1".to_string(),
+            prompt_tokens: 150_000,
+            context_limit: 128_000,
+        };
+        let v = event.to_value().unwrap();
+        assert_eq!(v["type"], "model_switched");
+        assert_eq!(v["to"], "openai/gpt-4.1");
+        assert_eq!(v["prompt_tokens"], 150_000);
+    }
+
+    #[test]
+    fn timing_format() {
+        let event = ProtocolEvent::Timing {
+            node_id: "think".to_string(),
+            kind: "first_token".to_string(),
+            name: None,
+            duration_ms: 420,
+            state_bytes: None,
+            message_count: None,
+        };
+        let v = event.to_value().unwrap();
+        assert_eq!(v["type"], "timing");
+        assert_eq!(v["kind"], "first_token");
+        assert_eq!(v["duration_ms"], 420);
+        assert!(v.get("name").is_none());
+    }
 }
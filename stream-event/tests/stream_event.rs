@@ -117,6 +117,7 @@ fn node_id_remains_active_until_next_node_enter() {
         &ProtocolEvent::MessageChunk {
             content: "hi".to_string(),
             id: "think".to_string(),
+            revision: 0,
         },
         &mut state,
     )
@@ -148,7 +148,9 @@ pub async fn handle_common_message(ctx: &MessageContext<'_>) -> Result<(), BotEr
                     }
                     return Ok(());
                 }
-                loom_command::Command::Compact { .. } | loom_command::Command::Summarize => {
+                loom_command::Command::Compact { .. }
+                | loom_command::Command::Summarize
+                | loom_command::Command::Pin { .. } => {
                     ctx.deps
                         .sender
                         .send_text(ctx.chat_id(), "Command not yet supported in Telegram bot.")
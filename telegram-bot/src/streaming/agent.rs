@@ -48,6 +48,7 @@ pub async fn run_loom_agent_streaming(
         working_folder: Some(PathBuf::from(".")),
         session_id: None,
         agent: None,
+        agent_file: None,
         verbose: false,
         got_adaptive: false,
         display_max_len: 2000,
@@ -61,6 +62,15 @@ pub async fn run_loom_agent_streaming(
         cancellation: None,
         output_timestamp: false,
         dry_run: false,
+        role_setting: None,
+        allowed_tools: None,
+        read_only: false,
+        reply_format: None,
+        reply_schema: None,
+        messages: None,
+        history_search: None,
+        locale: None,
+        flags: Default::default(),
     };
 
     let mapper = StreamEventMapper::new(tx.clone(), settings.streaming.show_act_phase);
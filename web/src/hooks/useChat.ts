@@ -226,9 +226,9 @@ export function useChat(options?: {
 
       for (const msg of history) {
         uiMessages.push({
-          id: crypto.randomUUID(),
+          id: msg.id !== undefined ? String(msg.id) : crypto.randomUUID(),
           sender: msg.role === 'user' ? 'user' : 'assistant',
-          timestamp: new Date().toISOString(),
+          timestamp: new Date(msg.created_at_ms ?? Date.now()).toISOString(),
           content: [
             {
               type: 'text' as const,
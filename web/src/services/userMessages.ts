@@ -1,8 +1,20 @@
 import { getConnection } from './connection'
 
+export type TokenUsage = {
+  prompt_tokens: number
+  completion_tokens: number
+  total_tokens: number
+}
+
 export type UserMessageItem = {
   role: string
   content: string
+  id?: number
+  run_id?: string
+  usage?: TokenUsage
+  tool_name?: string
+  tool_call_id?: string
+  created_at_ms?: number
 }
 
 export type UserMessagesResponse = {
@@ -15,7 +27,7 @@ export type UserMessagesResponse = {
 
 export async function getUserMessages(
   sessionId: string,
-  options?: { before?: number; limit?: number }
+  options?: { before?: number; limit?: number; query?: string }
 ): Promise<UserMessageItem[]> {
   const resp = await getConnection().request({
     type: 'user_messages',
@@ -23,8 +35,26 @@ export async function getUserMessages(
     thread_id: sessionId,
     before: options?.before,
     limit: options?.limit,
+    query: options?.query,
   })
 
   const msg = resp as UserMessagesResponse
   return msg.messages ?? []
 }
+
+export type UserMessagesDeleteResponse = {
+  type: 'user_messages_delete'
+  id: string
+  thread_id: string
+  deleted: number
+}
+
+export async function deleteUserMessages(sessionId: string): Promise<number> {
+  const resp = await getConnection().request({
+    type: 'user_messages_delete',
+    id: crypto.randomUUID(),
+    thread_id: sessionId,
+  })
+
+  return (resp as UserMessagesDeleteResponse).deleted ?? 0
+}
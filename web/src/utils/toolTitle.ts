@@ -20,6 +20,7 @@ const TOOL_DISPLAY_NAMES: Record<string, string> = {
   recall: 'Recall',
   search_memories: 'Search Memory',
   list_memories: 'List Memory',
+  forget: 'Forget',
   todo_read: 'Todo Read',
   todo_write: 'Todo Write',
   skill: 'Skill',
@@ -60,7 +61,8 @@ const TITLE_PARAMS: Record<string, (args: Args) => string | null> = {
   remember: (a) => str(a, 'key'),
   recall: (a) => str(a, 'key'),
   search_memories: (a) => str(a, 'query'),
-  list_memories: () => null,
+  list_memories: (a) => str(a, 'prefix'),
+  forget: (a) => str(a, 'key'),
   todo_read: () => null,
   todo_write: () => null,
   skill: (a) => str(a, 'name'),